@@ -0,0 +1,58 @@
+use odoo_ls_server::features::ast_utils::AstUtils;
+use ruff_python_ast::Stmt;
+use ruff_text_size::{Ranged, TextRange};
+
+use iai_callgrind::{
+    library_benchmark, library_benchmark_group, main, LibraryBenchmarkConfig
+};
+use std::hint::black_box;
+
+/*
+Compare the retrieval of the statement of a symbol in a large file, by walking the AST with its range, or with its
+ast indexes. To run it, see iai_profiler.rs
+*/
+
+const CLASSES: usize = 2000;
+const FIELDS: usize = 20;
+
+fn setup() -> (Vec<Stmt>, TextRange, Vec<u16>) {
+    let mut source = String::new();
+    for class in 0..CLASSES {
+        source += &format!("class Model{}(models.Model):\n    _name = 'model.{}'\n", class, class);
+        for field in 0..FIELDS {
+            source += &format!("    field_{} = fields.Char()\n", field);
+        }
+    }
+    let ast = ruff_python_parser::parse_module(&source).unwrap().into_syntax().body;
+    //the last field of the last class
+    let indexes = vec![(CLASSES - 1) as u16, FIELDS as u16];
+    let range = AstUtils::find_stmt_from_ast(&ast, &indexes).as_assign_stmt().unwrap().targets[0].range();
+    (ast, range, indexes)
+}
+
+fn walk<'a>(stmts: &'a [Stmt], range: &TextRange) -> Option<&'a Stmt> {
+    let stmt = stmts.iter().find(|stmt| stmt.range().contains_range(*range))?;
+    match stmt {
+        Stmt::ClassDef(c) => walk(&c.body, range),
+        _ => Some(stmt)
+    }
+}
+
+#[library_benchmark]
+#[bench::large_file(setup())]
+fn iai_ast_walk((ast, range, _indexes): (Vec<Stmt>, TextRange, Vec<u16>)) -> bool {
+    black_box(walk(&ast, &range).is_some())
+}
+
+#[library_benchmark]
+#[bench::large_file(setup())]
+fn iai_ast_indexes((ast, _range, indexes): (Vec<Stmt>, TextRange, Vec<u16>)) -> bool {
+    black_box(AstUtils::get_stmt_from_ast(&ast, &indexes).is_some())
+}
+
+library_benchmark_group!(name = ast_lookup; benchmarks = iai_ast_walk, iai_ast_indexes);
+
+main!(
+    config = LibraryBenchmarkConfig::default();
+    library_benchmark_groups = ast_lookup
+);
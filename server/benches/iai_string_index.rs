@@ -0,0 +1,56 @@
+use odoo_ls_server::core::string_index::{StringIndex, StringLookup};
+use ruff_python_ast::Stmt;
+
+use iai_callgrind::{
+    library_benchmark, library_benchmark_group, main, LibraryBenchmarkConfig
+};
+use std::hint::black_box;
+
+/*
+Compare the search of the files referencing a model name, by collecting the string literals of the ast of all the
+files, or with the string index. To run it, see iai_profiler.rs
+*/
+
+const FILES: usize = 500;
+const CLASSES: usize = 10;
+const SEARCHED: &str = "res.partner";
+
+fn setup() -> (Vec<(String, Vec<Stmt>)>, StringIndex) {
+    let mut files = vec![];
+    let mut index = StringIndex::new();
+    for file in 0..FILES {
+        let mut source = String::new();
+        for class in 0..CLASSES {
+            source += &format!("class Model{}(models.Model):\n    _name = 'model.{}.{}'\n", class, file, class);
+            source += &format!("    partner_id = fields.Many2one('{}')\n", if file % 50 == 0 { SEARCHED } else { "res.users" });
+            source += "    name = fields.Char(related='partner_id.name')\n";
+        }
+        let ast = ruff_python_parser::parse_module(&source).unwrap().into_syntax().body;
+        let path = format!("/addons/module_{}/models/model.py", file);
+        index.update_file(&path, &ast);
+        files.push((path, ast));
+    }
+    (files, index)
+}
+
+#[library_benchmark]
+#[bench::workspace(setup())]
+fn iai_string_scan((files, _index): (Vec<(String, Vec<Stmt>)>, StringIndex)) -> usize {
+    black_box(files.iter().filter(|(_, ast)| StringIndex::collect_literals(ast).iter().any(|(value, _)| value == SEARCHED)).count())
+}
+
+#[library_benchmark]
+#[bench::workspace(setup())]
+fn iai_string_index((_files, index): (Vec<(String, Vec<Stmt>)>, StringIndex)) -> usize {
+    match index.lookup(SEARCHED) {
+        StringLookup::FOUND(files) => black_box(files.len()),
+        StringLookup::NOT_INDEXED => 0
+    }
+}
+
+library_benchmark_group!(name = string_index; benchmarks = iai_string_scan, iai_string_index);
+
+main!(
+    config = LibraryBenchmarkConfig::default();
+    library_benchmark_groups = string_index
+);
@@ -0,0 +1,76 @@
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer;
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/*
+Compare the wall time of the build of a python path of a few hundred files, with the files parsed by the main thread
+or by the workers of the ParsePool. iai-callgrind counts the instructions of all the threads, and can't show the gain
+of the workers, so this bench measures the time of each build. To run it: cargo bench --bench parse_pool
+*/
+
+const PACKAGES: usize = 10;
+const MODULES: usize = 30;
+const RUNS: usize = 5;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn write_fixture(root: &Path) {
+    for package in 0..PACKAGES {
+        let modules: Vec<String> = (0..MODULES).map(|module| format!("mod_{}", module)).collect();
+        write(root, &format!("pkg_{}/__init__.py", package), &format!("from . import {}\n", modules.join(", ")));
+        for module in 0..MODULES {
+            let other = (package + 1) % PACKAGES;
+            write(root, &format!("pkg_{}/mod_{}.py", package, module), &format!("\
+import os.path
+from pkg_{other}.mod_{module} import Base
+
+VALUE = {module}
+
+class Base:
+    label = 'base'
+
+    def run(self, value: int = VALUE) -> str:
+        return str(value) + self.label
+"));
+        }
+    }
+    let imports: Vec<String> = (0..PACKAGES).map(|package| format!("import pkg_{}", package)).collect();
+    write(root, "main.py", &(imports.join("\n") + "\n"));
+}
+
+fn build_fixture(root: &Path, workers: usize) -> Duration {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().parse_pool.set_workers(workers);
+    let root_symbol = session.sync_odoo.symbols.clone().unwrap();
+    root_symbol.borrow_mut().add_path(session.sync_odoo.stdlib_dir.clone());
+    root_symbol.borrow_mut().add_path(root.sanitize());
+    root_symbol.borrow_mut().as_root_mut().sys_path.push(root.sanitize());
+    SyncOdoo::load_builtins(&mut session);
+    let start = Instant::now();
+    let main = Symbol::create_from_path(&mut session, &root.join("main.py"), root_symbol.clone(), false).unwrap();
+    session.sync_odoo.add_to_rebuild_arch(main);
+    SyncOdoo::process_rebuilds(&mut session);
+    start.elapsed()
+}
+
+fn main() {
+    let root = std::env::temp_dir().join(format!("odoo_ls_bench_parse_pool_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write_fixture(&root);
+    for workers in [0, 4] {
+        let best = (0..RUNS).map(|_| build_fixture(&root, workers)).min().unwrap();
+        println!("parse_pool: {} worker(s), best of {} builds: {:?}", workers, RUNS, best);
+    }
+    let _ = fs::remove_dir_all(&root);
+}
@@ -53,6 +53,7 @@ impl CliBackend {
         config.no_typeshed = self.cli.no_typeshed;
         config.additional_stubs = self.cli.stubs.clone().unwrap_or(vec![]);
         config.stdlib = self.cli.stdlib.clone().unwrap_or(S!(""));
+        config.max_file_diagnostics = 0; //the output contains all the diagnostics
         SyncOdoo::init(&mut session, config);
 
         let output_path = self.cli.output.clone().unwrap_or(S!("output.json"));
@@ -7,6 +7,8 @@ pub const EXTENSION_VERSION: &str = "0.2.8";
 pub const DEBUG_ODOO_BUILDER: bool = false;
 pub const DEBUG_MEMORY: bool = false;
 
+pub const MAX_NOT_FOUND_PATHS: usize = 100; //maximum number of unresolved paths registered on a symbol
+
 pub type Tree = (Vec<String>, Vec<String>);
 
 pub fn tree(a: Vec<&str>, b: Vec<&str>) -> Tree {
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+use ruff_python_ast::visitor::{Visitor, walk_expr};
+use ruff_python_ast::{Decorator, Expr, ExprCall, ExprStringLiteral, Stmt};
+
+use crate::core::context_keys::ContextKeys;
+use crate::features::ast_utils::AstUtils;
+
+/* Context keys that are read by the framework and the standard modules, and that can be given to api.depends_context.
+The keys given to with_context by the model itself are accepted too. */
+pub const KNOWN_CONTEXT_KEYS: [&str; 36] = [
+    "active_id", "active_ids", "active_model", "active_test", "allowed_company_ids", "bin_size", "company", "company_id",
+    "date", "display_default_code", "edit_translations", "force_company", "formatted_display_name", "from_date",
+    "hierarchical_naming", "install_mode", "journal_id", "lang", "location", "lot_id", "owner_id", "package_id",
+    "partner_id", "pricelist", "quantity", "show_address", "show_email", "show_vat", "to_date", "tracking_disable",
+    "tz", "uid", "uom", "warehouse", "website_id", "with_expected",
+];
+
+/* Prefixes of the context keys that are generated from field names */
+pub const CONTEXT_KEY_PREFIXES: [&str; 2] = ["default_", "search_default_"];
+
+/* Helpers for the decorators of odoo.api that take their arguments as strings:
+    @api.returns('self', lambda value: value.id): the model of the returned records, or 'self'
+    @api.depends_context('company', 'lang'): context keys the computed fields depend on
+    @api.ondelete(at_uninstall=False): methods called before the records are deleted */
+pub struct ApiDecorators {}
+
+impl ApiDecorators {
+
+    /* Return the decorator api.<name> of the list, and its call if the decorator has arguments */
+    pub fn find<'a>(decorators: &'a [Decorator], name: &str) -> Option<(&'a Decorator, Option<&'a ExprCall>)> {
+        decorators.iter().find_map(|decorator| {
+            let (func, call) = match &decorator.expression {
+                Expr::Call(call) => (&*call.func, Some(call)),
+                expr => (expr, None)
+            };
+            let flattened = AstUtils::flatten_expr(func);
+            match flattened.strip_prefix("odoo.").unwrap_or(&flattened).strip_prefix("api.") {
+                Some(decorator_name) if decorator_name == name => Some((decorator, call)),
+                _ => None
+            }
+        })
+    }
+
+    /* Return the literal naming the model returned by a method decorated with api.returns: 'self' or a model name */
+    pub fn get_returns_model(call: &ExprCall) -> Option<&ExprStringLiteral> {
+        let model = match call.arguments.find_keyword("model") {
+            Some(keyword) => &keyword.value,
+            None => call.arguments.args.first()?
+        };
+        model.as_string_literal_expr()
+    }
+
+    /* Return the literals of the context keys given to api.depends_context */
+    pub fn get_context_keys(call: &ExprCall) -> Vec<&ExprStringLiteral> {
+        call.arguments.args.iter().filter_map(|arg| arg.as_string_literal_expr()).collect()
+    }
+
+    /* Return true if the key can be used by api.depends_context, given the keys used by the model */
+    pub fn is_known_context_key(key: &str, used_keys: &HashSet<String>) -> bool {
+        ContextKeys::is_builtin_key(key) || used_keys.contains(key)
+    }
+
+    /* Return the context keys used by the statements: given to with_context (as keywords or in a dict), or read from
+    the context with context.get('key') or context['key'] */
+    pub fn get_used_context_keys(stmts: &Vec<Stmt>) -> HashSet<String> {
+        let mut visitor = ContextKeysVisitor { keys: HashSet::new() };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.keys
+    }
+}
+
+struct ContextKeysVisitor {
+    keys: HashSet<String>,
+}
+
+impl ContextKeysVisitor {
+
+    /* Add the keys of a value given to with_context: a dict literal, or dict(...) with keywords */
+    fn add_dict_keys(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Dict(dict) => {
+                self.keys.extend(dict.items.iter().filter_map(|item| item.key.as_ref())
+                    .filter_map(|key| key.as_string_literal_expr()).map(|key| key.value.to_string()));
+            },
+            Expr::Call(call) if AstUtils::flatten_expr(&call.func) == "dict" => {
+                self.keys.extend(call.arguments.keywords.iter().filter_map(|k| k.arg.as_ref()).map(|arg| arg.to_string()));
+                for arg in call.arguments.args.iter() {
+                    self.add_dict_keys(arg);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ContextKeysVisitor {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Call(call) => {
+                if let Expr::Attribute(func) = &*call.func {
+                    if func.attr.as_str() == "with_context" {
+                        self.keys.extend(call.arguments.keywords.iter().filter_map(|k| k.arg.as_ref()).map(|arg| arg.to_string()));
+                        for arg in call.arguments.args.iter() {
+                            self.add_dict_keys(arg);
+                        }
+                    } else if func.attr.as_str() == "get" && ContextKeys::is_context(&func.value) {
+                        if let Some(key) = call.arguments.args.first().and_then(|arg| arg.as_string_literal_expr()) {
+                            self.keys.insert(key.value.to_string());
+                        }
+                    }
+                }
+            },
+            Expr::Subscript(subscript) => {
+                if ContextKeys::is_context(&subscript.value) {
+                    if let Some(key) = subscript.slice.as_string_literal_expr() {
+                        self.keys.insert(key.value.to_string());
+                    }
+                }
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
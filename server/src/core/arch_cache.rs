@@ -0,0 +1,1250 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+use std::time::UNIX_EPOCH;
+
+use ruff_python_ast::comparable::ComparableExpr;
+use ruff_python_ast::Expr;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::constants::{BuildStatus, BuildSteps, PackageType, SymType, EXTENSION_VERSION};
+use crate::core::argument_types::ParameterType;
+use crate::core::dataclasses::{DataclassField, DataclassInfo};
+use crate::core::evaluation::{ContextValue, Evaluation, EvaluationSymbol, EvaluationSymbolPtr, EvaluationSymbolWeak, EvaluationValue};
+use crate::core::file_mgr::FileInfo;
+use crate::core::import_resolver::get_or_create_module_symbol;
+use crate::core::odoo::SyncOdoo;
+use crate::core::static_conditions::StaticConditions;
+use crate::core::symbols::class_symbol::ClassSymbol;
+use crate::core::symbols::function_symbol::{Argument, ArgumentType, FunctionSymbol, TypeGuard};
+use crate::core::symbols::symbol::Symbol;
+use crate::core::symbols::symbol_mgr::{SectionIndex, SectionRange, SymbolMgr};
+use crate::core::symbols::variable_symbol::VariableSymbol;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+pub const CLEAR_ARCH_CACHE_COMMAND: &str = "odooLs.clearArchCache";
+
+/* Version of the format of the entries. The entries are stored in a directory per version, and the directories of the
+other versions are removed when the cache is configured */
+const CACHE_VERSION: u32 = 3;
+
+/* The sections, the symbols and the deleted names of a scope, that are the same fields on all the scopes. None for the
+symbols that are not scopes, and for the modules of Odoo, that are never cached */
+macro_rules! with_scope {
+    ($symbol:expr, |$scope:ident| $body:expr) => {
+        match $symbol {
+            Symbol::File($scope) => Some($body),
+            Symbol::Package(crate::core::symbols::package_symbol::PackageSymbol::PythonPackage($scope)) => Some($body),
+            Symbol::Class($scope) => Some($body),
+            Symbol::Function($scope) => Some($body),
+            _ => None
+        }
+    };
+}
+
+type CachedRange = (u32, u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    secs: u64,
+    nanos: u32,
+    size: u64,
+}
+
+/* A symbol referenced by the file: the tree of its file, and the name and the start of each symbol from the file to it,
+as several symbols of a scope can have the same name */
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedRef {
+    files: Vec<String>,
+    content: Vec<(String, u32)>,
+}
+
+/* The target of a weak: an index in the refs of the entry, or a symbol that was already dropped */
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedWeak {
+    DROPPED,
+    REF(usize),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedPtr {
+    WEAK { target: CachedWeak, instance: Option<bool>, is_super: bool },
+    SELF,
+    ARG(u32),
+    DOMAIN,
+    NONE,
+    ANY,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedContextValue {
+    BOOLEAN(bool),
+    STRING(String),
+    MODULE(CachedWeak),
+    SYMBOL(CachedWeak),
+    RANGE(CachedRange),
+}
+
+/* The expressions of the values are given by their range in the fragments of the entry */
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedValue {
+    ANY,
+    CONSTANT(CachedRange),
+    DICT(Vec<(CachedRange, CachedRange)>),
+    LIST(Vec<CachedRange>),
+    SET(Vec<CachedRange>),
+    TUPLE(Vec<CachedRange>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEvaluation {
+    ptr: CachedPtr,
+    context: Vec<(String, CachedContextValue)>,
+    factory: Option<CachedWeak>,
+    value: Option<CachedValue>,
+    range: Option<CachedRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedSectionIndex {
+    INDEX(u32),
+    OR(Vec<CachedSectionIndex>),
+    NONE,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedScope {
+    sections: Vec<(u32, u32, CachedSectionIndex)>,
+    symbols: Vec<(u32, CachedSymbol)>, //by section, in the order of the symbols of each name
+    deleted_names: Vec<(String, u32, Vec<u32>)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedVariable {
+    name: String,
+    range: CachedRange,
+    doc_string: Option<String>,
+    is_import_variable: bool,
+    is_type_checking_import: bool,
+    is_parameter: bool,
+    is_dynamic_field: bool,
+    is_narrowed: bool,
+    is_container_mutation: bool,
+    is_explicit_type_alias: bool,
+    type_params: Vec<String>,
+    evaluations: Vec<CachedEvaluation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedParameterType {
+    ANY,
+    NONE,
+    CLASS(CachedWeak),
+    MODEL(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedArgument {
+    param: (String, u32), //the parameter in the scope of the function
+    default_value: Option<CachedEvaluation>,
+    arg_type: u8,
+    annotation: Option<Vec<CachedParameterType>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFunction {
+    name: String,
+    range: CachedRange,
+    body_range: CachedRange,
+    doc_string: Option<String>,
+    is_static: bool,
+    is_property: bool,
+    setter: Option<Box<CachedFunction>>,
+    deleter: Option<Box<CachedFunction>>,
+    evaluations: Vec<CachedEvaluation>,
+    declared_evaluations: Vec<CachedEvaluation>,
+    built: [bool; 4], //the steps done on the body
+    args: Vec<CachedArgument>,
+    is_overloaded: bool,
+    overloads: Vec<CachedFunction>,
+    is_class_method: bool,
+    is_lambda: bool,
+    is_synthesized: bool,
+    is_async: bool,
+    decorators: Vec<CachedEvaluation>,
+    global_names: Vec<String>,
+    nonlocal_names: Vec<String>,
+    type_guard: Option<(Vec<CachedEvaluation>, bool)>,
+    narrowed_attributes: Vec<(String, CachedRange, Vec<CachedEvaluation>)>,
+    scope: CachedScope,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedClass {
+    name: String,
+    range: CachedRange,
+    body_range: CachedRange,
+    doc_string: Option<String>,
+    bases: Vec<CachedWeak>,
+    has_dynamic_fields: bool,
+    metaclass_attributes: Vec<String>,
+    dataclass: Option<CachedDataclass>,
+    scope: CachedScope,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDataclassField {
+    name: String,
+    range: CachedRange,
+    has_default: bool,
+    kw_only: bool,
+    init: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDataclass {
+    fields: Vec<CachedDataclassField>,
+    is_named_tuple: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum CachedSymbol {
+    VARIABLE(CachedVariable),
+    FUNCTION(CachedFunction),
+    CLASS(CachedClass),
+}
+
+impl CachedSymbol {
+    fn name(&self) -> &String {
+        match self {
+            CachedSymbol::VARIABLE(v) => &v.name,
+            CachedSymbol::FUNCTION(f) => &f.name,
+            CachedSymbol::CLASS(c) => &c.name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    version: u32,
+    environment: String,
+    path: String,
+    stamp: FileStamp,
+    sources: Vec<(String, FileStamp)>, //the source files of the referenced files and packages
+    refs: Vec<CachedRef>,
+    fragments: Vec<(u32, String)>, //the parts of the source holding the expressions of the values, by offset
+    scope: CachedScope,
+    not_found_paths: Vec<(i32, Vec<String>)>,
+    dependencies: Vec<(usize, usize, Vec<CachedWeak>)>, //step, level and dependencies
+}
+
+/* Cache of the architecture of the external files (the stdlib, the stubs and the packages of the python path) between
+the sessions. These files never change, but they are parsed and evaluated again at each start of the server, which takes
+most of the startup on a large environment. After the ARCH_EVAL of an external file that was parsed, its symbols, their
+evaluations and its dependencies are written in an entry keyed by the path, the modification time and the size of the
+file. The next sessions build the file from its entry instead of parsing it.
+The symbols of other files are referenced by their tree, and resolved like imports when the entry is restored: the entry
+is only valid while the referenced files keep their stamp. The expressions of the constant values are restored by parsing
+their source again, only the fragments of the source holding them are stored. A file that can't be restored exactly is
+not stored: a symbol of the workspace or of Odoo, an evaluation given by a hook, a model... The entries also depend on the
+environment: the version of the server, of python, the platform and the root paths, whose modification time changes when
+a package is installed. An entry that can't be read or restored falls back to the parse. */
+#[derive(Debug)]
+pub struct ArchCache {
+    dir: PathBuf,
+    enabled: bool,
+    environment: String,
+    loaded_stamps: HashMap<String, FileStamp>, //the external files parsed in this session, with their stamp before the parse
+    pub hits: u64,
+    pub misses: u64,
+    pub stored: u64,
+    pub rejected: u64, //files that could not be stored
+}
+
+impl Default for ArchCache {
+    fn default() -> Self {
+        ArchCache::new()
+    }
+}
+
+#[derive(Default)]
+struct Encoder {
+    targets: Vec<Weak<RefCell<Symbol>>>,
+    values: Vec<(TextRange, Expr)>,
+}
+
+struct Decoder {
+    targets: Vec<Rc<RefCell<Symbol>>>,
+    skeleton: String,
+}
+
+impl ArchCache {
+
+    pub fn new() -> Self {
+        ArchCache {
+            dir: ArchCache::get_default_dir(),
+            enabled: false,
+            environment: String::new(),
+            loaded_stamps: HashMap::new(),
+            hits: 0,
+            misses: 0,
+            stored: 0,
+            rejected: 0,
+        }
+    }
+
+    fn get_default_dir() -> PathBuf {
+        let base = env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(env::temp_dir);
+        base.join("odoo-ls").join("arch")
+    }
+
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.dir = dir;
+    }
+
+    /* Return the directory of the entries of the current version */
+    pub fn get_version_dir(&self) -> PathBuf {
+        self.dir.join(format!("v{}", CACHE_VERSION))
+    }
+
+    /* Enable the cache if the configuration allows it, for the environment of the session. Must be called once the paths
+    of the root are known */
+    pub fn configure(session: &mut SessionInfo) {
+        let mut environment = format!("{}|{:?}|{}", EXTENSION_VERSION, session.sync_odoo.config_report.python_version, StaticConditions::get_platform());
+        for path in session.sync_odoo.symbols.as_ref().unwrap().borrow().paths().iter() {
+            let stamp = ArchCache::read_stamp(path);
+            environment += &format!("|{}:{:?}", path, stamp.map(|stamp| (stamp.secs, stamp.nanos)));
+        }
+        let cache = &mut session.sync_odoo.arch_cache;
+        cache.enabled = session.sync_odoo.config.arch_cache;
+        cache.environment = environment;
+        cache.loaded_stamps.clear();
+        if cache.enabled {
+            cache.remove_other_versions();
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.loaded_stamps.clear();
+        }
+    }
+
+    fn remove_other_versions(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let current = format!("v{}", CACHE_VERSION);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('v') && name != current {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    /* Remove all the entries, and return their number */
+    pub fn clear(&mut self) -> usize {
+        let mut removed = 0;
+        if let Ok(entries) = fs::read_dir(self.get_version_dir()) {
+            for entry in entries.flatten() {
+                if fs::remove_file(entry.path()).is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+
+    /* The entries are named by a FNV-1a hash of the path, that is the same for all the builds of the server, unlike the
+    hashers of the standard library */
+    pub fn get_entry_path(&self, path: &str) -> PathBuf {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in path.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        self.get_version_dir().join(format!("{:016x}.json", hash))
+    }
+
+    fn read_stamp(path: &str) -> Option<FileStamp> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(FileStamp { secs: modified.as_secs(), nanos: modified.subsec_nanos(), size: metadata.len() })
+    }
+
+    /* Return the source file of a file or a package, whose stamp is checked */
+    fn get_source_path(module: &Symbol) -> Option<String> {
+        match module.typ() {
+            SymType::FILE => module.paths().first().cloned(),
+            SymType::PACKAGE(PackageType::PYTHON_PACKAGE) => {
+                Some(PathBuf::from(module.paths().first()?).join("__init__.py").sanitize() + module.as_package().i_ext().as_str())
+            },
+            _ => None
+        }
+    }
+
+    /* Build an external file from its entry, instead of parsing it. Return false if the file must be parsed: the cache is
+    disabled, the file is not external, or its entry is missing, outdated or can't be restored. The stamp of a parsed file
+    is kept, so it is stored after its evaluation */
+    pub fn restore(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, path: &str) -> bool {
+        if !session.sync_odoo.arch_cache.enabled {
+            return false;
+        }
+        {
+            let file = file.borrow();
+            if !file.is_external() || file.in_workspace() || file.typ() == SymType::PACKAGE(PackageType::MODULE) {
+                return false;
+            }
+        }
+        if session.sync_odoo.get_file_mgr().borrow().get_file_info(&path.to_string()).is_some_and(|file_info| file_info.borrow().opened) {
+            return false;
+        }
+        let Some(stamp) = ArchCache::read_stamp(path) else {
+            return false;
+        };
+        let restored = match session.sync_odoo.arch_cache.read_entry(path, &stamp) {
+            Some(entry) => ArchCache::restore_entry(session, file, &entry),
+            None => false
+        };
+        let cache = &mut session.sync_odoo.arch_cache;
+        if restored {
+            cache.hits += 1;
+        } else {
+            cache.misses += 1;
+            cache.loaded_stamps.insert(path.to_string(), stamp);
+        }
+        restored
+    }
+
+    fn read_entry(&self, path: &str, stamp: &FileStamp) -> Option<CacheEntry> {
+        let entry_path = self.get_entry_path(path);
+        let content = fs::read_to_string(&entry_path).ok()?;
+        let entry: CacheEntry = match serde_json::from_str(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Removing the corrupted entry {} of the arch cache: {}", entry_path.display(), e);
+                let _ = fs::remove_file(&entry_path);
+                return None;
+            }
+        };
+        if entry.version != CACHE_VERSION || entry.environment != self.environment || entry.path != path || entry.stamp != *stamp {
+            return None;
+        }
+        if entry.sources.iter().any(|(source, stamp)| ArchCache::read_stamp(source).as_ref() != Some(stamp)) {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /* Create the symbols of the entry, resolve the referenced symbols, then fill the symbols. The referenced files are
+    resolved after the creation of the symbols, as they can refer to the file. On failure, the symbols are unloaded and
+    the file is parsed */
+    fn restore_entry(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, entry: &CacheEntry) -> bool {
+        let mut restored = vec![];
+        let mut done = ArchCache::create_scope(file, &entry.scope, &mut restored).is_some();
+        if done {
+            let targets: Option<Vec<Rc<RefCell<Symbol>>>> = entry.refs.iter().map(|reference| ArchCache::resolve_ref(session, reference)).collect();
+            let skeleton = ArchCache::build_skeleton(&entry.fragments);
+            done = match (targets, skeleton) {
+                (Some(targets), Some(skeleton)) => {
+                    let decoder = Decoder { targets, skeleton };
+                    let filled = restored.iter().all(|(symbol, cached)| decoder.fill_symbol(symbol, cached).is_some());
+                    if filled {
+                        decoder.fill_file(session, file, entry);
+                    }
+                    filled
+                },
+                _ => false
+            };
+        }
+        if !done {
+            Symbol::unload_content(session, file);
+            with_scope!(&mut *file.borrow_mut(), |scope| {
+                scope.sections.clear();
+                scope._init_symbol_mgr();
+            });
+        }
+        done
+    }
+
+    /* Create the symbols of the scope, without their evaluations. The symbols are added to restored with their entry */
+    fn create_scope<'a>(scope: &Rc<RefCell<Symbol>>, cached: &'a CachedScope, restored: &mut Vec<(Rc<RefCell<Symbol>>, &'a CachedSymbol)>) -> Option<()> {
+        let weak_scope = Rc::downgrade(scope);
+        let mut children = vec![];
+        {
+            let mut scope_bw = scope.borrow_mut();
+            let (sections, symbols, deleted_names) = with_scope!(&mut *scope_bw, |s| (&mut s.sections, &mut s.symbols, &mut s.deleted_names))?;
+            if cached.sections.is_empty() {
+                return None;
+            }
+            *sections = cached.sections.iter().map(|(start, index, previous)| SectionRange {
+                start: *start,
+                index: *index,
+                previous_indexes: ArchCache::decode_section_index(previous),
+            }).collect();
+            deleted_names.clear();
+            for (name, section, positions) in cached.deleted_names.iter() {
+                deleted_names.entry(name.clone()).or_default().insert(*section, positions.clone());
+            }
+            for (section, cached_symbol) in cached.symbols.iter() {
+                if *section as usize >= sections.len() {
+                    return None;
+                }
+                let symbol = ArchCache::create_symbol(cached_symbol, &weak_scope);
+                symbols.entry(cached_symbol.name().clone()).or_default().entry(*section).or_default().push(symbol.clone());
+                children.push((symbol, cached_symbol));
+            }
+        }
+        for (symbol, cached_symbol) in children.into_iter() {
+            match cached_symbol {
+                CachedSymbol::FUNCTION(function) => ArchCache::create_function_content(&symbol, function, &weak_scope, restored)?,
+                CachedSymbol::CLASS(class) => ArchCache::create_scope(&symbol, &class.scope, restored)?,
+                CachedSymbol::VARIABLE(_) => {}
+            }
+            restored.push((symbol, cached_symbol));
+        }
+        Some(())
+    }
+
+    fn create_symbol(cached: &CachedSymbol, parent: &Weak<RefCell<Symbol>>) -> Rc<RefCell<Symbol>> {
+        let symbol = match cached {
+            CachedSymbol::VARIABLE(v) => Symbol::Variable(VariableSymbol::new(v.name.clone(), ArchCache::decode_range(&v.range), true)),
+            CachedSymbol::FUNCTION(f) => ArchCache::new_function(f),
+            CachedSymbol::CLASS(c) => Symbol::Class(ClassSymbol::new(c.name.clone(), ArchCache::decode_range(&c.range), ArchCache::decode_range(&c.body_range).start(), true)),
+        };
+        let symbol = Rc::new(RefCell::new(symbol));
+        let weak_self = Some(Rc::downgrade(&symbol));
+        match &mut *symbol.borrow_mut() {
+            Symbol::Variable(v) => { v.weak_self = weak_self; v.parent = Some(parent.clone()); },
+            Symbol::Function(f) => { f.weak_self = weak_self; f.parent = Some(parent.clone()); },
+            Symbol::Class(c) => { c.weak_self = weak_self; c.parent = Some(parent.clone()); },
+            _ => {}
+        }
+        symbol
+    }
+
+    fn new_function(cached: &CachedFunction) -> Symbol {
+        Symbol::Function(FunctionSymbol::new(cached.name.clone(), ArchCache::decode_range(&cached.range), ArchCache::decode_range(&cached.body_range).start(), true))
+    }
+
+    /* Create the body of a function, and the functions it owns (see FunctionSymbol::owned_functions), that have the same
+    parent */
+    fn create_function_content<'a>(function: &Rc<RefCell<Symbol>>, cached: &'a CachedFunction, parent: &Weak<RefCell<Symbol>>, restored: &mut Vec<(Rc<RefCell<Symbol>>, &'a CachedSymbol)>) -> Option<()> {
+        ArchCache::create_scope(function, &cached.scope, restored)?;
+        let mut overloads = vec![];
+        for overload in cached.overloads.iter() {
+            overloads.push(ArchCache::create_owned_function(overload, parent, restored)?);
+        }
+        let setter = match &cached.setter {
+            Some(setter) => Some(ArchCache::create_owned_function(setter, parent, restored)?),
+            None => None
+        };
+        let deleter = match &cached.deleter {
+            Some(deleter) => Some(ArchCache::create_owned_function(deleter, parent, restored)?),
+            None => None
+        };
+        let mut function = function.borrow_mut();
+        let function = function.as_func_mut();
+        function.overloads = overloads;
+        function.setter = setter;
+        function.deleter = deleter;
+        Some(())
+    }
+
+    fn create_owned_function<'a>(cached: &'a CachedFunction, parent: &Weak<RefCell<Symbol>>, restored: &mut Vec<(Rc<RefCell<Symbol>>, &'a CachedSymbol)>) -> Option<Rc<RefCell<Symbol>>> {
+        let function = Rc::new(RefCell::new(ArchCache::new_function(cached)));
+        {
+            let mut function_bw = function.borrow_mut();
+            let function_bw = function_bw.as_func_mut();
+            function_bw.weak_self = Some(Rc::downgrade(&function));
+            function_bw.parent = Some(parent.clone());
+        }
+        ArchCache::create_function_content(&function, cached, parent, restored)?;
+        Some(function)
+    }
+
+    /* Return the symbol referenced by the entry, loading its file like an import */
+    fn resolve_ref(session: &mut SessionInfo, reference: &CachedRef) -> Option<Rc<RefCell<Symbol>>> {
+        let module = get_or_create_module_symbol(session, &reference.files)?;
+        if reference.content.is_empty() {
+            return Some(module);
+        }
+        let pending = matches!(module.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) && module.borrow().build_status(BuildSteps::ARCH) == BuildStatus::PENDING;
+        if pending {
+            SyncOdoo::rebuild_arch_now(session, &module);
+        }
+        ArchCache::find_content(&module, &reference.content)
+    }
+
+    fn find_module(root: &Rc<RefCell<Symbol>>, files: &[String]) -> Option<Rc<RefCell<Symbol>>> {
+        let mut current = root.clone();
+        for name in files.iter() {
+            let next = current.borrow().get_symbol(&(vec![name.clone()], vec![]), u32::MAX).first().cloned()?;
+            current = next;
+        }
+        Some(current)
+    }
+
+    fn find_content(module: &Rc<RefCell<Symbol>>, content: &[(String, u32)]) -> Option<Rc<RefCell<Symbol>>> {
+        let mut current = module.clone();
+        for (name, start) in content.iter() {
+            let next = ArchCache::find_in_scope(&current, name, *start)?;
+            current = next;
+        }
+        Some(current)
+    }
+
+    /* Return the symbol of the scope with the name, declared at start. The functions owned by another one are found
+    through it */
+    fn find_in_scope(scope: &Rc<RefCell<Symbol>>, name: &str, start: u32) -> Option<Rc<RefCell<Symbol>>> {
+        let scope = scope.borrow();
+        if !matches!(scope.typ(), SymType::FILE | SymType::PACKAGE(_) | SymType::CLASS | SymType::FUNCTION) {
+            return None;
+        }
+        let (_, sections) = scope.iter_symbols().find(|(symbol_name, _)| symbol_name.as_str() == name)?;
+        let position = TextRange::empty(TextSize::new(start));
+        for symbol in sections.values().flatten() {
+            if symbol.borrow().range().start() == position.start() {
+                return Some(symbol.clone());
+            }
+            if symbol.borrow().typ() == SymType::FUNCTION {
+                if let Some(owned) = symbol.borrow().as_func().get_owned_function(&position) {
+                    return Some(owned);
+                }
+            }
+        }
+        None
+    }
+
+    /* Return the file, package or namespace containing the symbol, and the path of the symbol from it */
+    fn get_module_and_content(symbol: &Rc<RefCell<Symbol>>) -> Option<(Rc<RefCell<Symbol>>, Vec<(String, u32)>)> {
+        let mut content = vec![];
+        let mut current = symbol.clone();
+        while current.borrow().is_file_content() {
+            let parent = {
+                let current = current.borrow();
+                content.insert(0, (current.name().clone(), current.range().start().to_u32()));
+                current.parent()?.upgrade()?
+            };
+            current = parent;
+        }
+        Some((current, content))
+    }
+
+    /* Write the entry of an external file after its evaluation, if it was parsed in this session */
+    pub fn store(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, path: &str, file_info: &Rc<RefCell<FileInfo>>) {
+        let Some(stamp) = session.sync_odoo.arch_cache.loaded_stamps.remove(path) else {
+            return;
+        };
+        {
+            let file_info = file_info.borrow();
+            if !file_info.valid || file_info.ast.is_none() || file_info.opened {
+                return;
+            }
+        }
+        if !file.borrow().is_external() || file.borrow().in_workspace() {
+            return;
+        }
+        match ArchCache::encode_entry(session, file, path, stamp, file_info) {
+            Some(entry) => session.sync_odoo.arch_cache.write_entry(&entry),
+            None => session.sync_odoo.arch_cache.rejected += 1
+        }
+    }
+
+    fn encode_entry(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, path: &str, stamp: FileStamp, file_info: &Rc<RefCell<FileInfo>>) -> Option<CacheEntry> {
+        let mut encoder = Encoder::default();
+        let scope = encoder.encode_scope(&file.borrow())?;
+        let mut dependencies = vec![];
+        let not_found_paths;
+        {
+            let file = file.borrow();
+            if file.model_dependencies().is_some_and(|models| !models.is_empty()) {
+                return None;
+            }
+            for (step, levels) in file.dependencies().iter().enumerate() {
+                for (level, symbols) in levels.iter().enumerate() {
+                    if !symbols.is_empty() {
+                        dependencies.push((step, level, symbols.iter().map(|symbol| encoder.encode_weak(&Rc::downgrade(&symbol))).collect()));
+                    }
+                }
+            }
+            not_found_paths = file.not_found_paths().iter().map(|(step, tree)| (*step as i32, tree.clone())).collect();
+        }
+        //the symbols are only borrowed to find their tree once the whole file is encoded
+        let (refs, sources) = ArchCache::encode_refs(session, file, &encoder.targets)?;
+        let fragments = encoder.get_fragments(&file_info.borrow())?;
+        let skeleton = ArchCache::build_skeleton(&fragments)?;
+        for (range, expr) in encoder.values.iter() {
+            let parsed = ArchCache::parse_value(&skeleton, range)?;
+            if ComparableExpr::from(&parsed) != ComparableExpr::from(expr) {
+                return None;
+            }
+        }
+        Some(CacheEntry {
+            version: CACHE_VERSION,
+            environment: session.sync_odoo.arch_cache.environment.clone(),
+            path: path.to_string(),
+            stamp,
+            sources,
+            refs,
+            fragments,
+            scope,
+            not_found_paths,
+            dependencies,
+        })
+    }
+
+    /* Return the refs of the symbols referenced by the file, that must be found again from their tree, and the stamps of
+    their source files. None if a symbol is not in an external file */
+    fn encode_refs(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, targets: &[Weak<RefCell<Symbol>>]) -> Option<(Vec<CachedRef>, Vec<(String, FileStamp)>)> {
+        let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+        let mut refs = vec![];
+        let mut sources: Vec<(String, FileStamp)> = vec![];
+        for target in targets.iter() {
+            let symbol = target.upgrade()?;
+            let (module, content) = ArchCache::get_module_and_content(&symbol)?;
+            if !module.borrow().is_external() || module.borrow().typ() == SymType::PACKAGE(PackageType::MODULE) {
+                return None;
+            }
+            let files = module.borrow().get_tree().0;
+            let found = ArchCache::find_module(&root, &files)?;
+            if !Rc::ptr_eq(&found, &module) || !Rc::ptr_eq(&ArchCache::find_content(&module, &content)?, &symbol) {
+                return None;
+            }
+            if !Rc::ptr_eq(&module, file) {
+                let source = ArchCache::get_source_path(&module.borrow());
+                if let Some(source) = source {
+                    if !sources.iter().any(|(path, _)| *path == source) {
+                        let stamp = ArchCache::read_stamp(&source)?;
+                        sources.push((source, stamp));
+                    }
+                }
+            }
+            refs.push(CachedRef { files, content });
+        }
+        Some((refs, sources))
+    }
+
+    fn write_entry(&mut self, entry: &CacheEntry) {
+        let entry_path = self.get_entry_path(&entry.path);
+        let temp_path = entry_path.with_extension(format!("{}.tmp", std::process::id()));
+        //written then renamed, so a session never reads a partial entry
+        let written = fs::create_dir_all(self.get_version_dir())
+            .and_then(|_| fs::write(&temp_path, serde_json::to_string(entry).unwrap_or_default()))
+            .and_then(|_| fs::rename(&temp_path, &entry_path));
+        match written {
+            Ok(_) => self.stored += 1,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                warn!("Unable to write the entry of {} in the arch cache: {}", entry.path, e);
+            }
+        }
+    }
+
+    /* The source of the values: the fragments at their offset, and spaces elsewhere */
+    fn build_skeleton(fragments: &[(u32, String)]) -> Option<String> {
+        let len = fragments.iter().map(|(start, text)| *start as usize + text.len()).max().unwrap_or(0);
+        let mut bytes = vec![b' '; len];
+        for (start, text) in fragments.iter() {
+            bytes[*start as usize..*start as usize + text.len()].copy_from_slice(text.as_bytes());
+        }
+        String::from_utf8(bytes).ok()
+    }
+
+    fn parse_value(skeleton: &str, range: &TextRange) -> Option<Expr> {
+        let (start, end) = (range.start().to_usize(), range.end().to_usize());
+        if start >= end || end > skeleton.len() || !skeleton.is_char_boundary(start) || !skeleton.is_char_boundary(end) {
+            return None;
+        }
+        ruff_python_parser::parse_expression_range(skeleton, *range).ok().map(|parsed| parsed.expr().clone())
+    }
+
+    fn encode_range(range: &TextRange) -> CachedRange {
+        (range.start().to_u32(), range.end().to_u32())
+    }
+
+    fn decode_range(range: &CachedRange) -> TextRange {
+        TextRange::new(TextSize::new(range.0), TextSize::new(range.1.max(range.0)))
+    }
+
+    fn encode_section_index(index: &SectionIndex) -> CachedSectionIndex {
+        match index {
+            SectionIndex::INDEX(index) => CachedSectionIndex::INDEX(*index),
+            SectionIndex::OR(indexes) => CachedSectionIndex::OR(indexes.iter().map(ArchCache::encode_section_index).collect()),
+            SectionIndex::NONE => CachedSectionIndex::NONE,
+        }
+    }
+
+    fn decode_section_index(index: &CachedSectionIndex) -> SectionIndex {
+        match index {
+            CachedSectionIndex::INDEX(index) => SectionIndex::INDEX(*index),
+            CachedSectionIndex::OR(indexes) => SectionIndex::OR(indexes.iter().map(ArchCache::decode_section_index).collect()),
+            CachedSectionIndex::NONE => SectionIndex::NONE,
+        }
+    }
+
+    fn encode_arg_type(arg_type: ArgumentType) -> u8 {
+        match arg_type {
+            ArgumentType::POS_ONLY => 0,
+            ArgumentType::ARG => 1,
+            ArgumentType::KWARG => 2,
+            ArgumentType::VARARG => 3,
+            ArgumentType::KWORD_ONLY => 4,
+        }
+    }
+
+    fn decode_arg_type(arg_type: u8) -> Option<ArgumentType> {
+        match arg_type {
+            0 => Some(ArgumentType::POS_ONLY),
+            1 => Some(ArgumentType::ARG),
+            2 => Some(ArgumentType::KWARG),
+            3 => Some(ArgumentType::VARARG),
+            4 => Some(ArgumentType::KWORD_ONLY),
+            _ => None
+        }
+    }
+
+    fn decode_step(step: i64) -> Option<BuildSteps> {
+        match step {
+            0 => Some(BuildSteps::ARCH),
+            1 => Some(BuildSteps::ARCH_EVAL),
+            2 => Some(BuildSteps::ODOO),
+            3 => Some(BuildSteps::VALIDATION),
+            _ => None
+        }
+    }
+
+    fn decode_status(done: bool) -> BuildStatus {
+        match done {
+            true => BuildStatus::DONE,
+            false => BuildStatus::PENDING
+        }
+    }
+}
+
+impl Encoder {
+
+    /* Return the index of the target of the weak in the refs of the entry. The refs are built at the end, as finding the
+    tree of a symbol borrows its parents */
+    fn encode_weak(&mut self, weak: &Weak<RefCell<Symbol>>) -> CachedWeak {
+        if weak.upgrade().is_none() {
+            return CachedWeak::DROPPED;
+        }
+        match self.targets.iter().position(|target| target.ptr_eq(weak)) {
+            Some(index) => CachedWeak::REF(index),
+            None => {
+                self.targets.push(weak.clone());
+                CachedWeak::REF(self.targets.len() - 1)
+            }
+        }
+    }
+
+    fn encode_expr(&mut self, expr: &Expr) -> CachedRange {
+        self.values.push((expr.range(), expr.clone()));
+        ArchCache::encode_range(&expr.range())
+    }
+
+    fn encode_exprs(&mut self, exprs: &[Expr]) -> Vec<CachedRange> {
+        exprs.iter().map(|expr| self.encode_expr(expr)).collect()
+    }
+
+    /* Return the source of the values, merged in fragments */
+    fn get_fragments(&self, file_info: &FileInfo) -> Option<Vec<(u32, String)>> {
+        let mut ranges: Vec<TextRange> = self.values.iter().map(|(range, _)| *range).collect();
+        ranges.sort_by_key(|range| range.start());
+        let mut merged: Vec<TextRange> = vec![];
+        for range in ranges.into_iter() {
+            match merged.last_mut() {
+                Some(last) if range.start() <= last.end() => *last = last.cover(range),
+                _ => merged.push(range)
+            }
+        }
+        merged.iter().map(|range| Some((range.start().to_u32(), file_info.get_text(range)?))).collect()
+    }
+
+    fn encode_evaluation(&mut self, evaluation: &Evaluation) -> Option<CachedEvaluation> {
+        let symbol = &evaluation.symbol;
+        if symbol.get_symbol_hook.is_some() {
+            return None;
+        }
+        let ptr = match symbol.ptr() {
+            EvaluationSymbolPtr::WEAK(w) => CachedPtr::WEAK { target: self.encode_weak(&w.weak), instance: w.instance, is_super: w.is_super },
+            EvaluationSymbolPtr::SELF => CachedPtr::SELF,
+            EvaluationSymbolPtr::ARG(index) => CachedPtr::ARG(*index),
+            EvaluationSymbolPtr::DOMAIN => CachedPtr::DOMAIN,
+            EvaluationSymbolPtr::NONE => CachedPtr::NONE,
+            EvaluationSymbolPtr::ANY => CachedPtr::ANY,
+        };
+        let mut context = vec![];
+        for (key, value) in symbol.context.iter() {
+            let value = match value {
+                ContextValue::BOOLEAN(value) => CachedContextValue::BOOLEAN(*value),
+                ContextValue::STRING(value) => CachedContextValue::STRING(value.clone()),
+                ContextValue::MODULE(module) => CachedContextValue::MODULE(self.encode_weak(module)),
+                ContextValue::SYMBOL(symbol) => CachedContextValue::SYMBOL(self.encode_weak(symbol)),
+                ContextValue::RANGE(range) => CachedContextValue::RANGE(ArchCache::encode_range(range)),
+            };
+            context.push((key.clone(), value));
+        }
+        let factory = symbol.factory.as_ref().map(|factory| self.encode_weak(factory));
+        let value = evaluation.value.as_ref().map(|value| match value {
+            EvaluationValue::ANY() => CachedValue::ANY,
+            EvaluationValue::CONSTANT(expr) => CachedValue::CONSTANT(self.encode_expr(expr)),
+            EvaluationValue::DICT(items) => CachedValue::DICT(items.iter().map(|(key, value)| (self.encode_expr(key), self.encode_expr(value))).collect()),
+            EvaluationValue::LIST(items) => CachedValue::LIST(self.encode_exprs(items)),
+            EvaluationValue::SET(items) => CachedValue::SET(self.encode_exprs(items)),
+            EvaluationValue::TUPLE(items) => CachedValue::TUPLE(self.encode_exprs(items)),
+        });
+        Some(CachedEvaluation { ptr, context, factory, value, range: evaluation.range.as_ref().map(ArchCache::encode_range) })
+    }
+
+    fn encode_evaluations(&mut self, evaluations: &[Evaluation]) -> Option<Vec<CachedEvaluation>> {
+        evaluations.iter().map(|evaluation| self.encode_evaluation(evaluation)).collect()
+    }
+
+    fn encode_scope(&mut self, scope: &Symbol) -> Option<CachedScope> {
+        let (sections, symbols, deleted_names) = with_scope!(scope, |s| (&s.sections, &s.symbols, &s.deleted_names))?;
+        let mut cached_symbols = vec![];
+        for name_sections in symbols.values() {
+            for (section, content) in name_sections.iter() {
+                for symbol in content.iter() {
+                    cached_symbols.push((*section, self.encode_symbol(&symbol.borrow())?));
+                }
+            }
+        }
+        let mut cached_deleted_names = vec![];
+        for (name, name_sections) in deleted_names.iter() {
+            for (section, positions) in name_sections.iter() {
+                cached_deleted_names.push((name.clone(), *section, positions.clone()));
+            }
+        }
+        Some(CachedScope {
+            sections: sections.iter().map(|section| (section.start, section.index, ArchCache::encode_section_index(&section.previous_indexes))).collect(),
+            symbols: cached_symbols,
+            deleted_names: cached_deleted_names,
+        })
+    }
+
+    fn encode_symbol(&mut self, symbol: &Symbol) -> Option<CachedSymbol> {
+        match symbol {
+            Symbol::Variable(v) => Some(CachedSymbol::VARIABLE(CachedVariable {
+                name: v.name.clone(),
+                range: ArchCache::encode_range(&v.range),
+                doc_string: v.doc_string.clone(),
+                is_import_variable: v.is_import_variable,
+                is_type_checking_import: v.is_type_checking_import,
+                is_parameter: v.is_parameter,
+                is_dynamic_field: v.is_dynamic_field,
+                is_narrowed: v.is_narrowed,
+                is_container_mutation: v.is_container_mutation,
+                is_explicit_type_alias: v.is_explicit_type_alias,
+                type_params: v.type_params.clone(),
+                evaluations: self.encode_evaluations(&v.evaluations)?,
+            })),
+            Symbol::Function(_) => Some(CachedSymbol::FUNCTION(self.encode_function(symbol)?)),
+            Symbol::Class(c) => {
+                //the models are built from the classes of Odoo and of the addons, not from the external files
+                if c._model.is_some() {
+                    return None;
+                }
+                Some(CachedSymbol::CLASS(CachedClass {
+                    name: c.name.clone(),
+                    range: ArchCache::encode_range(&c.range),
+                    body_range: ArchCache::encode_range(&c.body_range),
+                    doc_string: c.doc_string.clone(),
+                    bases: c.bases.iter().map(|base| self.encode_weak(base)).collect(),
+                    has_dynamic_fields: c.has_dynamic_fields,
+                    metaclass_attributes: c.metaclass_attributes.clone(),
+                    dataclass: c.dataclass.as_ref().map(|dataclass| CachedDataclass {
+                        fields: dataclass.fields.iter().map(|field| CachedDataclassField {
+                            name: field.name.clone(),
+                            range: ArchCache::encode_range(&field.range),
+                            has_default: field.has_default,
+                            kw_only: field.kw_only,
+                            init: field.init,
+                        }).collect(),
+                        is_named_tuple: dataclass.is_named_tuple,
+                    }),
+                    scope: self.encode_scope(symbol)?,
+                }))
+            },
+            _ => None
+        }
+    }
+
+    fn encode_function(&mut self, symbol: &Symbol) -> Option<CachedFunction> {
+        let f = symbol.as_func();
+        let function = f.weak_self.as_ref()?.upgrade()?;
+        let mut args = vec![];
+        for arg in f.args.iter() {
+            let param = arg.symbol.upgrade()?;
+            let param = (param.borrow().name().clone(), param.borrow().range().start().to_u32());
+            //the parameter is found again in the scope of the function when the entry is restored
+            if !ArchCache::find_in_scope(&function, &param.0, param.1).is_some_and(|found| Rc::ptr_eq(&found, &arg.symbol.upgrade().unwrap())) {
+                return None;
+            }
+            let default_value = match &arg.default_value {
+                Some(default_value) => Some(self.encode_evaluation(default_value)?),
+                None => None
+            };
+            let annotation = arg.annotation.as_ref().map(|types| types.iter().map(|typ| match typ {
+                ParameterType::ANY => CachedParameterType::ANY,
+                ParameterType::NONE => CachedParameterType::NONE,
+                ParameterType::CLASS(class) => CachedParameterType::CLASS(self.encode_weak(class)),
+                ParameterType::MODEL(model) => CachedParameterType::MODEL(model.clone()),
+            }).collect());
+            args.push(CachedArgument { param, default_value, arg_type: ArchCache::encode_arg_type(arg.arg_type), annotation });
+        }
+        let setter = match &f.setter {
+            Some(setter) => Some(Box::new(self.encode_function(&setter.borrow())?)),
+            None => None
+        };
+        let deleter = match &f.deleter {
+            Some(deleter) => Some(Box::new(self.encode_function(&deleter.borrow())?)),
+            None => None
+        };
+        let type_guard = match &f.type_guard {
+            Some(type_guard) => Some((self.encode_evaluations(&type_guard.evaluations)?, type_guard.is_type_is)),
+            None => None
+        };
+        let mut narrowed_attributes = vec![];
+        for (chain, range, evaluations) in f.narrowed_attributes.iter() {
+            narrowed_attributes.push((chain.clone(), ArchCache::encode_range(range), self.encode_evaluations(evaluations)?));
+        }
+        Some(CachedFunction {
+            name: f.name.clone(),
+            range: ArchCache::encode_range(&f.range),
+            body_range: ArchCache::encode_range(&f.body_range),
+            doc_string: f.doc_string.clone(),
+            is_static: f.is_static,
+            is_property: f.is_property,
+            setter,
+            deleter,
+            evaluations: self.encode_evaluations(&f.evaluations)?,
+            declared_evaluations: self.encode_evaluations(&f.declared_evaluations)?,
+            built: [f.arch_status, f.arch_eval_status, f.odoo_status, f.validation_status].map(|status| status == BuildStatus::DONE),
+            args,
+            is_overloaded: f.is_overloaded,
+            overloads: f.overloads.iter().map(|overload| self.encode_function(&overload.borrow())).collect::<Option<Vec<_>>>()?,
+            is_class_method: f.is_class_method,
+            is_lambda: f.is_lambda,
+            is_synthesized: f.is_synthesized,
+            is_async: f.is_async,
+            decorators: self.encode_evaluations(&f.decorators)?,
+            global_names: f.global_names.clone(),
+            nonlocal_names: f.nonlocal_names.clone(),
+            type_guard,
+            narrowed_attributes,
+            scope: self.encode_scope(symbol)?,
+        })
+    }
+}
+
+impl Decoder {
+
+    fn weak(&self, cached: &CachedWeak) -> Option<Weak<RefCell<Symbol>>> {
+        match cached {
+            CachedWeak::DROPPED => Some(Weak::new()),
+            CachedWeak::REF(index) => self.targets.get(*index).map(Rc::downgrade)
+        }
+    }
+
+    fn expr(&self, range: &CachedRange) -> Option<Expr> {
+        ArchCache::parse_value(&self.skeleton, &ArchCache::decode_range(range))
+    }
+
+    fn exprs(&self, ranges: &[CachedRange]) -> Option<Vec<Expr>> {
+        ranges.iter().map(|range| self.expr(range)).collect()
+    }
+
+    fn evaluation(&self, cached: &CachedEvaluation) -> Option<Evaluation> {
+        let ptr = match &cached.ptr {
+            CachedPtr::WEAK { target, instance, is_super } => EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak::new(self.weak(target)?, *instance, *is_super)),
+            CachedPtr::SELF => EvaluationSymbolPtr::SELF,
+            CachedPtr::ARG(index) => EvaluationSymbolPtr::ARG(*index),
+            CachedPtr::DOMAIN => EvaluationSymbolPtr::DOMAIN,
+            CachedPtr::NONE => EvaluationSymbolPtr::NONE,
+            CachedPtr::ANY => EvaluationSymbolPtr::ANY,
+        };
+        let mut context = HashMap::new();
+        for (key, value) in cached.context.iter() {
+            let value = match value {
+                CachedContextValue::BOOLEAN(value) => ContextValue::BOOLEAN(*value),
+                CachedContextValue::STRING(value) => ContextValue::STRING(value.clone()),
+                CachedContextValue::MODULE(module) => ContextValue::MODULE(self.weak(module)?),
+                CachedContextValue::SYMBOL(symbol) => ContextValue::SYMBOL(self.weak(symbol)?),
+                CachedContextValue::RANGE(range) => ContextValue::RANGE(ArchCache::decode_range(range)),
+            };
+            context.insert(key.clone(), value);
+        }
+        let factory = match &cached.factory {
+            Some(factory) => Some(self.weak(factory)?),
+            None => None
+        };
+        let value = match &cached.value {
+            Some(value) => Some(match value {
+                CachedValue::ANY => EvaluationValue::ANY(),
+                CachedValue::CONSTANT(range) => EvaluationValue::CONSTANT(self.expr(range)?),
+                CachedValue::DICT(items) => EvaluationValue::DICT(items.iter().map(|(key, value)| Some((self.expr(key)?, self.expr(value)?))).collect::<Option<Vec<_>>>()?),
+                CachedValue::LIST(items) => EvaluationValue::LIST(self.exprs(items)?),
+                CachedValue::SET(items) => EvaluationValue::SET(self.exprs(items)?),
+                CachedValue::TUPLE(items) => EvaluationValue::TUPLE(self.exprs(items)?),
+            }),
+            None => None
+        };
+        Some(Evaluation {
+            symbol: EvaluationSymbol::new_from_ptr(ptr, context, factory),
+            value,
+            range: cached.range.as_ref().map(ArchCache::decode_range),
+        })
+    }
+
+    fn evaluations(&self, cached: &[CachedEvaluation]) -> Option<Vec<Evaluation>> {
+        cached.iter().map(|evaluation| self.evaluation(evaluation)).collect()
+    }
+
+    fn fill_symbol(&self, symbol: &Rc<RefCell<Symbol>>, cached: &CachedSymbol) -> Option<()> {
+        match cached {
+            CachedSymbol::VARIABLE(cached) => {
+                let evaluations = self.evaluations(&cached.evaluations)?;
+                let mut symbol = symbol.borrow_mut();
+                let variable = symbol.as_variable_mut();
+                variable.doc_string = cached.doc_string.clone();
+                variable.is_import_variable = cached.is_import_variable;
+                variable.is_type_checking_import = cached.is_type_checking_import;
+                variable.is_parameter = cached.is_parameter;
+                variable.is_dynamic_field = cached.is_dynamic_field;
+                variable.is_narrowed = cached.is_narrowed;
+                variable.is_container_mutation = cached.is_container_mutation;
+                variable.is_explicit_type_alias = cached.is_explicit_type_alias;
+                variable.type_params = cached.type_params.clone();
+                variable.evaluations = evaluations;
+            },
+            CachedSymbol::FUNCTION(cached) => self.fill_function(symbol, cached)?,
+            CachedSymbol::CLASS(cached) => {
+                let bases = cached.bases.iter().map(|base| self.weak(base)).collect::<Option<Vec<_>>>()?;
+                let mut symbol = symbol.borrow_mut();
+                let class = symbol.as_class_sym_mut();
+                class.doc_string = cached.doc_string.clone();
+                class.bases = bases;
+                class.has_dynamic_fields = cached.has_dynamic_fields;
+                class.metaclass_attributes = cached.metaclass_attributes.clone();
+                class.dataclass = cached.dataclass.as_ref().map(|dataclass| DataclassInfo {
+                    fields: dataclass.fields.iter().map(|field| DataclassField {
+                        name: field.name.clone(),
+                        range: ArchCache::decode_range(&field.range),
+                        has_default: field.has_default,
+                        kw_only: field.kw_only,
+                        init: field.init,
+                    }).collect(),
+                    is_named_tuple: dataclass.is_named_tuple,
+                });
+            }
+        }
+        Some(())
+    }
+
+    fn fill_function(&self, symbol: &Rc<RefCell<Symbol>>, cached: &CachedFunction) -> Option<()> {
+        let mut args = vec![];
+        for arg in cached.args.iter() {
+            let param = ArchCache::find_in_scope(symbol, &arg.param.0, arg.param.1)?;
+            let default_value = match &arg.default_value {
+                Some(default_value) => Some(self.evaluation(default_value)?),
+                None => None
+            };
+            let annotation = match &arg.annotation {
+                Some(types) => Some(types.iter().map(|typ| match typ {
+                    CachedParameterType::ANY => Some(ParameterType::ANY),
+                    CachedParameterType::NONE => Some(ParameterType::NONE),
+                    CachedParameterType::CLASS(class) => Some(ParameterType::CLASS(self.weak(class)?)),
+                    CachedParameterType::MODEL(model) => Some(ParameterType::MODEL(model.clone())),
+                }).collect::<Option<Vec<_>>>()?),
+                None => None
+            };
+            args.push(Argument { symbol: Rc::downgrade(&param), default_value, arg_type: ArchCache::decode_arg_type(arg.arg_type)?, annotation });
+        }
+        let evaluations = self.evaluations(&cached.evaluations)?;
+        let declared_evaluations = self.evaluations(&cached.declared_evaluations)?;
+        let decorators = self.evaluations(&cached.decorators)?;
+        let type_guard = match &cached.type_guard {
+            Some((evaluations, is_type_is)) => Some(TypeGuard { evaluations: self.evaluations(evaluations)?, is_type_is: *is_type_is }),
+            None => None
+        };
+        let mut narrowed_attributes = vec![];
+        for (chain, range, evaluations) in cached.narrowed_attributes.iter() {
+            narrowed_attributes.push((chain.clone(), ArchCache::decode_range(range), self.evaluations(evaluations)?));
+        }
+        let (overloads, setter, deleter) = {
+            let function = symbol.borrow();
+            let function = function.as_func();
+            (function.overloads.clone(), function.setter.clone(), function.deleter.clone())
+        };
+        for (overload, cached_overload) in overloads.iter().zip(cached.overloads.iter()) {
+            self.fill_function(overload, cached_overload)?;
+        }
+        if let (Some(setter), Some(cached_setter)) = (&setter, &cached.setter) {
+            self.fill_function(setter, cached_setter)?;
+        }
+        if let (Some(deleter), Some(cached_deleter)) = (&deleter, &cached.deleter) {
+            self.fill_function(deleter, cached_deleter)?;
+        }
+        let mut symbol = symbol.borrow_mut();
+        let function = symbol.as_func_mut();
+        function.doc_string = cached.doc_string.clone();
+        function.is_static = cached.is_static;
+        function.is_property = cached.is_property;
+        function.evaluations = evaluations;
+        function.declared_evaluations = declared_evaluations;
+        function.arch_status = ArchCache::decode_status(cached.built[0]);
+        function.arch_eval_status = ArchCache::decode_status(cached.built[1]);
+        function.odoo_status = ArchCache::decode_status(cached.built[2]);
+        function.validation_status = ArchCache::decode_status(cached.built[3]);
+        function.args = args;
+        function.is_overloaded = cached.is_overloaded;
+        function.is_class_method = cached.is_class_method;
+        function.is_lambda = cached.is_lambda;
+        function.is_synthesized = cached.is_synthesized;
+        function.is_async = cached.is_async;
+        function.decorators = decorators;
+        function.global_names = cached.global_names.clone();
+        function.nonlocal_names = cached.nonlocal_names.clone();
+        function.type_guard = type_guard;
+        function.narrowed_attributes = narrowed_attributes;
+        Some(())
+    }
+
+    /* Restore the not found paths and the dependencies of the file, once all its symbols are restored */
+    fn fill_file(&self, session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, entry: &CacheEntry) {
+        let mut not_found_paths = vec![];
+        for (step, tree) in entry.not_found_paths.iter() {
+            if let Some(step) = ArchCache::decode_step(*step as i64) {
+                not_found_paths.push((step, tree.clone()));
+            }
+        }
+        if !not_found_paths.is_empty() {
+            session.sync_odoo.not_found_symbols.insert(file.clone());
+        }
+        *file.borrow_mut().not_found_paths_mut() = not_found_paths;
+        for (step, level, dependencies) in entry.dependencies.iter() {
+            let (Some(step), Some(level)) = (ArchCache::decode_step(*step as i64), ArchCache::decode_step(*level as i64)) else {
+                continue;
+            };
+            for dependency in dependencies.iter() {
+                if let Some(dependency) = self.weak(dependency).and_then(|weak| weak.upgrade()) {
+                    Symbol::add_dependency_to(file, &dependency, step, level);
+                }
+            }
+        }
+    }
+}
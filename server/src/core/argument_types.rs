@@ -0,0 +1,237 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use ruff_python_ast::{Expr, ExprCall, Operator};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::constants::{SymType, Tree, EXTENSION_NAME};
+use crate::core::call_binding::CallBinding;
+use crate::core::evaluation::{Evaluation, EvaluationValue};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* A type accepted by a parameter, given by its annotation */
+#[derive(Debug, Clone)]
+pub enum ParameterType {
+    ANY,
+    NONE,
+    CLASS(Weak<RefCell<Symbol>>),
+    MODEL(String), //a recordset of the model, annotated by its name: partner: 'res.partner'
+}
+
+/* The type of a value given as argument to a call */
+enum ValueType {
+    NONE,
+    INSTANCE(Rc<RefCell<Symbol>>),
+}
+
+/* Check of the arguments of the calls to the workspace functions against the annotations of their parameters, enabled by
+the validateArgumentTypes setting. The check is permissive: the parameters without annotation and the arguments whose
+type is unknown are skipped, and an argument is only reported if none of its possible types matches the annotation. */
+pub struct ArgumentTypes {}
+
+impl ArgumentTypes {
+
+    /* Return the types accepted by an annotation. The special forms of typing (Optional, Union...) are recognized by
+    their name. An annotation that can't be resolved accepts any type */
+    pub fn from_annotation(session: &mut SessionInfo, annotation: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Vec<ParameterType> {
+        match annotation {
+            Expr::NoneLiteral(_) => vec![ParameterType::NONE],
+            Expr::StringLiteral(s) => match Evaluation::parse_forward_reference(annotation) {
+                Some(parsed) => ArgumentTypes::from_annotation(session, parsed.expr(), scope, &TextSize::new(u32::MAX)),
+                None => vec![ParameterType::MODEL(s.value.to_string())]
+            },
+            Expr::BinOp(bin_op) if bin_op.op == Operator::BitOr => {
+                let mut res = ArgumentTypes::from_annotation(session, &bin_op.left, scope.clone(), max_infer);
+                res.extend(ArgumentTypes::from_annotation(session, &bin_op.right, scope, max_infer));
+                res
+            },
+            Expr::Subscript(subscript) => {
+                let items = match &*subscript.slice {
+                    Expr::Tuple(tuple) => tuple.elts.iter().collect::<Vec<&Expr>>(),
+                    slice => vec![slice]
+                };
+                match ArgumentTypes::get_annotation_name(&subscript.value).as_deref() {
+                    Some("Optional") => {
+                        let mut res = ArgumentTypes::from_annotation(session, items[0], scope, max_infer);
+                        res.push(ParameterType::NONE);
+                        res
+                    },
+                    Some("Union") => items.into_iter().flat_map(|item| ArgumentTypes::from_annotation(session, item, scope.clone(), max_infer)).collect(),
+                    Some("Annotated") => ArgumentTypes::from_annotation(session, items[0], scope, max_infer),
+                    Some(name @ ("List" | "Dict" | "Set" | "FrozenSet" | "Tuple")) => {
+                        let builtin = session.sync_odoo.get_symbol(&(vec![S!("builtins")], vec![name.to_lowercase()]), u32::MAX);
+                        match builtin.last() {
+                            Some(builtin) => vec![ParameterType::CLASS(Rc::downgrade(builtin))],
+                            None => vec![ParameterType::ANY]
+                        }
+                    },
+                    _ => ArgumentTypes::from_annotation(session, &subscript.value, scope, max_infer) //list[int] accepts any list
+                }
+            },
+            Expr::Name(_) | Expr::Attribute(_) => {
+                if ArgumentTypes::get_annotation_name(annotation).as_deref() == Some("Any") {
+                    return vec![ParameterType::ANY];
+                }
+                let (evaluations, _) = Evaluation::eval_from_ast(session, annotation, scope, max_infer);
+                let mut res = vec![];
+                for evaluation in evaluations.iter() {
+                    let weak = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+                    for weak in Symbol::follow_ref(&weak, session, &mut None, false, false, None, &mut vec![]).iter() {
+                        match weak.weak.upgrade() {
+                            Some(class) if class.borrow().typ() == SymType::CLASS && !weak.instance.unwrap_or(false) => res.push(ParameterType::CLASS(weak.weak.clone())),
+                            _ => return vec![ParameterType::ANY]
+                        }
+                    }
+                }
+                if res.is_empty() {
+                    res.push(ParameterType::ANY);
+                }
+                res
+            },
+            _ => vec![ParameterType::ANY]
+        }
+    }
+
+    fn get_annotation_name(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Name(name) => Some(name.id.to_string()),
+            Expr::Attribute(attribute) => Some(attribute.attr.to_string()),
+            _ => None
+        }
+    }
+
+    /* Return the diagnostics of the arguments of the call whose type doesn't match the annotation of their parameter */
+    pub fn validate_call(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, call: &ExprCall, is_on_instance: bool, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Vec<Diagnostic> {
+        let (binding, parameters) = {
+            let function = function.borrow();
+            let function = function.as_func();
+            if function.is_external || function.args.iter().all(|arg| arg.annotation.is_none()) {
+                return vec![];
+            }
+            let parameters: Vec<(String, Option<Vec<ParameterType>>)> = function.args.iter()
+                .map(|arg| (arg.symbol.upgrade().map(|s| s.borrow().name().clone()).unwrap_or_default(), arg.annotation.clone()))
+                .collect();
+            (CallBinding::bind_function(function, &call.arguments, is_on_instance), parameters)
+        };
+        let mut diagnostics = vec![];
+        let bound_args = call.arguments.args.iter().zip(binding.args.iter())
+            .chain(call.arguments.keywords.iter().map(|keyword| &keyword.value).zip(binding.keywords.iter()));
+        for (arg, parameter) in bound_args {
+            let Some((name, Some(expected))) = parameter.and_then(|parameter| parameters.get(parameter)) else {
+                continue;
+            };
+            if expected.iter().any(|t| matches!(t, ParameterType::ANY)) {
+                continue;
+            }
+            let Some(values) = ArgumentTypes::get_value_types(session, arg, scope.clone(), max_infer) else {
+                continue;
+            };
+            if values.is_empty() || values.iter().any(|value| ArgumentTypes::is_compatible(session, expected, value)) {
+                continue;
+            }
+            let expected: Vec<String> = expected.iter().map(ArgumentTypes::parameter_type_name).collect();
+            diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(arg.range().start().to_u32(), 0), Position::new(arg.range().end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20014"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("Parameter '{}' expects {}, but {} is given", name, expected.join(" | "), ArgumentTypes::value_type_name(&values[0])),
+                None,
+                None,
+            ));
+        }
+        diagnostics
+    }
+
+    /* Return the possible types of the argument, or None if one of them is unknown */
+    fn get_value_types(session: &mut SessionInfo, arg: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<Vec<ValueType>> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, arg, scope, max_infer);
+        let mut res = vec![];
+        for evaluation in evaluations.iter() {
+            if matches!(evaluation.value, Some(EvaluationValue::CONSTANT(Expr::NoneLiteral(_)))) {
+                res.push(ValueType::NONE);
+                continue;
+            }
+            let weak = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for weak in Symbol::follow_ref(&weak, session, &mut None, false, false, None, &mut vec![]).iter() {
+                match weak.weak.upgrade() {
+                    Some(class) if class.borrow().typ() == SymType::CLASS && weak.instance == Some(true) => res.push(ValueType::INSTANCE(class)),
+                    _ => return None
+                }
+            }
+        }
+        Some(res)
+    }
+
+    fn is_compatible(session: &mut SessionInfo, expected: &Vec<ParameterType>, value: &ValueType) -> bool {
+        let ValueType::INSTANCE(value_class) = value else {
+            return expected.iter().any(|t| matches!(t, ParameterType::NONE));
+        };
+        let value_class = value_class.borrow();
+        let value_tree = value_class.get_tree();
+        let value_models: Vec<String> = value_class.as_class_sym()._model.iter().flat_map(|m| m.names().cloned()).collect();
+        for expected_type in expected.iter() {
+            match expected_type {
+                ParameterType::ANY => return true,
+                ParameterType::NONE => {},
+                //an unknown name can be a forward reference to a class
+                ParameterType::MODEL(name) => if value_models.contains(name) || !session.sync_odoo.models.contains_key(name) {
+                    return true;
+                },
+                ParameterType::CLASS(expected_class) => {
+                    let Some(expected_class) = expected_class.upgrade() else {
+                        return true;
+                    };
+                    if value_class.is_equal(&expected_class) || value_class.as_class_sym().inherits(&expected_class, &mut None) {
+                        return true;
+                    }
+                    let expected_class = expected_class.borrow();
+                    if expected_class.as_class_sym()._model.iter().flat_map(|m| m.names()).any(|name| value_models.contains(name)) {
+                        return true;
+                    }
+                    if ArgumentTypes::is_builtin_promotion(&value_tree, &expected_class.get_tree()) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /* Numeric tower: an int is accepted as a float or a complex, a float as a complex. Any value is an object */
+    fn is_builtin_promotion(value_tree: &Tree, expected_tree: &Tree) -> bool {
+        if value_tree.0 != [S!("builtins")] || expected_tree.0 != [S!("builtins")] {
+            return false;
+        }
+        let value_name = value_tree.1.join(".");
+        match expected_tree.1.join(".").as_str() {
+            "float" => value_name == "int",
+            "complex" => value_name == "int" || value_name == "float",
+            "object" => true,
+            _ => false
+        }
+    }
+
+    fn parameter_type_name(parameter_type: &ParameterType) -> String {
+        match parameter_type {
+            ParameterType::ANY => S!("Any"),
+            ParameterType::NONE => S!("None"),
+            ParameterType::CLASS(class) => class.upgrade().map(|c| c.borrow().name().clone()).unwrap_or(S!("Any")),
+            ParameterType::MODEL(name) => name.clone(),
+        }
+    }
+
+    /* Return the name of the type of the value. A recordset is named by its model */
+    fn value_type_name(value: &ValueType) -> String {
+        match value {
+            ValueType::NONE => S!("None"),
+            ValueType::INSTANCE(class) => {
+                let class = class.borrow();
+                class.as_class_sym()._model.as_ref().and_then(|m| m.names().next().cloned()).unwrap_or(class.name().clone())
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::config::Config;
+use crate::utils::PathSanitizer;
+use crate::S;
+
+pub const PYPROJECT_FILE: &str = "pyproject.toml";
+const PYPROJECT_TABLE: &str = "tool.odoo-ls";
+
+/* Where a path of the configuration comes from */
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSource {
+    EDITOR,
+    ODOO_CONF(String),
+    PYPROJECT(String),
+}
+
+impl fmt::Display for PathSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSource::EDITOR => write!(f, "editor settings"),
+            PathSource::ODOO_CONF(path) => write!(f, "addons_path of {}", path),
+            PathSource::PYPROJECT(path) => write!(f, "[{}] of {}", PYPROJECT_TABLE, path),
+        }
+    }
+}
+
+/* The paths that can be found in the files of the workspace, to avoid duplicating them in the editor settings:
+    - the addons_path option of the odoo.conf file (or any ini file with an [options] section)
+    - the [tool.odoo-ls] table of pyproject.toml, with odooPath, addons and pythonPath keys
+Relative paths are resolved against the directory of the file that declares them. */
+#[derive(Debug, Clone, Default)]
+pub struct AutoConfig {
+    pub odoo_path: Option<(String, PathSource)>,
+    pub addons: Vec<(String, PathSource)>,
+    pub python_path: Option<(String, PathSource)>,
+}
+
+/* A value of the [tool.odoo-ls] table */
+#[derive(Debug, Clone, PartialEq)]
+pub enum TomlValue {
+    STRING(String),
+    ARRAY(Vec<String>),
+}
+
+impl AutoConfig {
+
+    /* Read the odoo.conf-style file (named odoo_conf_file, empty to ignore it) and the pyproject.toml file at the root of
+    each workspace folder. Return the errors encountered while reading them */
+    pub fn load(workspace_folders: &Vec<String>, odoo_conf_file: &str) -> (AutoConfig, Vec<String>) {
+        let mut auto_config = AutoConfig::default();
+        let mut errors = vec![];
+        for folder in workspace_folders.iter() {
+            if !odoo_conf_file.is_empty() {
+                let path = PathBuf::from(folder).join(odoo_conf_file);
+                if let Some(content) = AutoConfig::read_file(&path, &mut errors) {
+                    auto_config.read_odoo_conf(&content, &path.sanitize(), &mut errors);
+                }
+            }
+            let path = PathBuf::from(folder).join(PYPROJECT_FILE);
+            if let Some(content) = AutoConfig::read_file(&path, &mut errors) {
+                auto_config.read_pyproject(&content, &path.sanitize(), &mut errors);
+            }
+        }
+        (auto_config, errors)
+    }
+
+    fn read_file(path: &Path, errors: &mut Vec<String>) -> Option<String> {
+        if !path.is_file() {
+            return None;
+        }
+        match fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                errors.push(format!("Unable to read {}: {}", path.sanitize(), e));
+                None
+            }
+        }
+    }
+
+    /* Add the entries of the addons_path option of an odoo.conf file */
+    pub fn read_odoo_conf(&mut self, content: &str, path: &String, errors: &mut Vec<String>) {
+        let sections = AutoConfig::parse_ini(content);
+        let Some(addons_path) = sections.get("options").and_then(|options| options.get("addons_path")) else {
+            return;
+        };
+        for entry in addons_path.split(',').map(|entry| entry.trim()).filter(|entry| !entry.is_empty()) {
+            let resolved = AutoConfig::resolve_path(path, entry);
+            if PathBuf::from(&resolved).is_dir() {
+                self.addons.push((resolved, PathSource::ODOO_CONF(path.clone())));
+            } else {
+                errors.push(format!("The addons_path entry '{}' of {} is not a directory", entry, path));
+            }
+        }
+    }
+
+    /* Read the paths of the [tool.odoo-ls] table of a pyproject.toml file */
+    pub fn read_pyproject(&mut self, content: &str, path: &String, errors: &mut Vec<String>) {
+        let table = match AutoConfig::parse_toml_table(content, PYPROJECT_TABLE) {
+            Ok(table) => table,
+            Err(e) => {
+                errors.push(format!("Unable to parse [{}] of {}: {}", PYPROJECT_TABLE, path, e));
+                return;
+            }
+        };
+        let mut keys: Vec<&String> = table.keys().collect();
+        keys.sort();
+        for key in keys {
+            let source = PathSource::PYPROJECT(path.clone());
+            match (key.as_str(), &table[key]) {
+                ("odooPath", TomlValue::STRING(value)) => {
+                    let resolved = AutoConfig::resolve_path(path, value);
+                    if !PathBuf::from(&resolved).is_dir() {
+                        errors.push(format!("The odooPath '{}' of {} is not a directory", value, path));
+                    } else if let Some((odoo_path, odoo_path_source)) = &self.odoo_path {
+                        if *odoo_path != resolved {
+                            errors.push(format!("The odooPath '{}' of {} is ignored, as {} gives '{}'", value, path, odoo_path_source, odoo_path));
+                        }
+                    } else {
+                        self.odoo_path = Some((resolved, source));
+                    }
+                },
+                ("addons", TomlValue::ARRAY(values)) => {
+                    for value in values.iter() {
+                        let resolved = AutoConfig::resolve_path(path, value);
+                        if PathBuf::from(&resolved).is_dir() {
+                            self.addons.push((resolved, source.clone()));
+                        } else {
+                            errors.push(format!("The addons entry '{}' of {} is not a directory", value, path));
+                        }
+                    }
+                },
+                ("pythonPath", TomlValue::STRING(value)) => {
+                    //a command like 'python3' is searched in the PATH
+                    let python_path = if value.contains('/') || value.contains('\\') { AutoConfig::resolve_path(path, value) } else { value.clone() };
+                    if self.python_path.is_none() {
+                        self.python_path = Some((python_path, source));
+                    }
+                },
+                ("odooPath" | "pythonPath", _) => errors.push(format!("{} of {} must be a string", key, path)),
+                ("addons", _) => errors.push(format!("addons of {} must be a list of strings", path)),
+                _ => errors.push(format!("Unknown key {} in [{}] of {}", key, PYPROJECT_TABLE, path)),
+            }
+        }
+    }
+
+    /* Complete the paths of the editor settings, which always win, with the paths found in the workspace. If the editor
+    gives no python path, python_path must be None. Return the conflicts between both, and fill the sources of the
+    paths of the configuration */
+    pub fn merge(&self, config: &mut Config, python_path: Option<String>) -> Vec<String> {
+        let mut conflicts = vec![];
+        let mut sources = vec![];
+        match &self.odoo_path {
+            Some((path, source)) if config.odoo_path.is_empty() => {
+                config.odoo_path = path.clone();
+                sources.push((path.clone(), source.to_string()));
+            },
+            Some((path, source)) => {
+                if PathBuf::from(&config.odoo_path).sanitize() != *path {
+                    conflicts.push(format!("The odooPath '{}' of {} is ignored, as the editor settings give '{}'", path, source, config.odoo_path));
+                }
+                sources.push((config.odoo_path.clone(), PathSource::EDITOR.to_string()));
+            },
+            None if !config.odoo_path.is_empty() => sources.push((config.odoo_path.clone(), PathSource::EDITOR.to_string())),
+            None => {}
+        }
+        for addon in config.addons.iter() {
+            sources.push((addon.clone(), PathSource::EDITOR.to_string()));
+        }
+        let odoo_addons = [PathBuf::from(&config.odoo_path).join("addons").sanitize(), PathBuf::from(&config.odoo_path).join("odoo").join("addons").sanitize()];
+        for (path, source) in self.addons.iter() {
+            //the addons of odoo itself are loaded with the odoo path
+            if config.addons.iter().any(|addon| PathBuf::from(addon).sanitize() == *path) || (!config.odoo_path.is_empty() && odoo_addons.contains(path)) {
+                continue;
+            }
+            config.addons.push(path.clone());
+            sources.push((path.clone(), source.to_string()));
+        }
+        match (python_path, &self.python_path) {
+            (Some(editor_path), Some((path, source))) => {
+                if editor_path != *path {
+                    conflicts.push(format!("The pythonPath '{}' of {} is ignored, as the editor settings give '{}'", path, source, editor_path));
+                }
+                config.python_path = editor_path;
+                sources.push((config.python_path.clone(), PathSource::EDITOR.to_string()));
+            },
+            (Some(editor_path), None) => {
+                config.python_path = editor_path;
+                sources.push((config.python_path.clone(), PathSource::EDITOR.to_string()));
+            },
+            (None, Some((path, source))) => {
+                config.python_path = path.clone();
+                sources.push((path.clone(), source.to_string()));
+            },
+            (None, None) => {}
+        }
+        config.path_sources = sources;
+        conflicts
+    }
+
+    fn resolve_path(config_file: &String, path: &str) -> String {
+        let path = PathBuf::from(path);
+        let path = match path.is_absolute() {
+            true => path,
+            false => PathBuf::from(config_file).parent().map(|dir| dir.join(&path)).unwrap_or(path)
+        };
+        //remove the '..' of paths like '../addons', as paths are compared as strings
+        let mut res = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::ParentDir => { res.pop(); },
+                Component::CurDir => {},
+                component => res.push(component)
+            }
+        }
+        res.sanitize()
+    }
+
+    /* Return the options of each section of an ini file, as read by the ConfigParser of python: 'key = value' or
+    'key: value' lines, with '#' and ';' comments, and indented lines that continue the previous value */
+    pub fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section = None;
+        let mut last_key: Option<String> = None;
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) {
+                if let (Some(section), Some(key)) = (&section, &last_key) {
+                    if let Some(value) = sections.get_mut(section).and_then(|options: &mut HashMap<String, String>| options.get_mut(key)) {
+                        value.push('\n');
+                        value.push_str(trimmed);
+                    }
+                }
+                continue;
+            }
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                let name = trimmed[1..trimmed.len() - 1].trim().to_string();
+                sections.entry(name.clone()).or_default();
+                section = Some(name);
+                last_key = None;
+                continue;
+            }
+            let Some(separator) = trimmed.find(|c| c == '=' || c == ':') else {
+                continue;
+            };
+            if let Some(section) = &section {
+                let key = trimmed[..separator].trim().to_lowercase();
+                let value = trimmed[separator + 1..].trim().to_string();
+                sections.get_mut(section).unwrap().insert(key.clone(), value);
+                last_key = Some(key);
+            }
+        }
+        sections
+    }
+
+    /* Return the values of a table of a toml file. Only string values and arrays of strings are supported */
+    pub fn parse_toml_table(content: &str, table: &str) -> Result<HashMap<String, TomlValue>, String> {
+        let mut res = HashMap::new();
+        let mut in_table = false;
+        let mut pending: Option<(String, String)> = None; //key and text of a multiline array
+        for line in content.lines() {
+            let line = AutoConfig::strip_toml_comment(line);
+            let line = line.trim();
+            if let Some((key, mut text)) = pending.take() {
+                text.push_str(line);
+                if AutoConfig::is_array_closed(&text) {
+                    res.insert(key, AutoConfig::parse_toml_value(&text)?);
+                } else {
+                    pending = Some((key, text));
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                let name: String = line.trim_start_matches('[').trim_end_matches(']').split('.')
+                    .map(|part| part.trim().trim_matches('"').trim_matches('\'')).collect::<Vec<&str>>().join(".");
+                in_table = name == table;
+                continue;
+            }
+            if !in_table {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("invalid line '{}'", line));
+            };
+            let key = key.trim().trim_matches('"').to_string();
+            let value = value.trim().to_string();
+            if value.starts_with('[') && !AutoConfig::is_array_closed(&value) {
+                pending = Some((key, value));
+            } else {
+                res.insert(key, AutoConfig::parse_toml_value(&value)?);
+            }
+        }
+        if let Some((key, _)) = pending {
+            return Err(format!("unclosed array for {}", key));
+        }
+        Ok(res)
+    }
+
+    fn strip_toml_comment(line: &str) -> String {
+        let mut res = String::new();
+        let mut quote = None;
+        for c in line.chars() {
+            match (quote, c) {
+                (None, '#') => break,
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                _ => {}
+            }
+            res.push(c);
+        }
+        res
+    }
+
+    fn is_array_closed(text: &str) -> bool {
+        AutoConfig::parse_toml_strings(text).is_some_and(|(_, rest)| rest.trim_end().ends_with(']'))
+    }
+
+    fn parse_toml_value(text: &str) -> Result<TomlValue, String> {
+        let invalid = || format!("unsupported value {}", text);
+        let (strings, rest) = AutoConfig::parse_toml_strings(text).ok_or_else(invalid)?;
+        if text.starts_with('[') {
+            //only separators between the strings
+            if !rest.chars().all(|c| c == ',' || c == '[' || c == ']' || c.is_whitespace()) {
+                return Err(invalid());
+            }
+            Ok(TomlValue::ARRAY(strings))
+        } else {
+            match strings.as_slice() {
+                [value] if rest.trim().is_empty() => Ok(TomlValue::STRING(value.clone())),
+                _ => Err(invalid())
+            }
+        }
+    }
+
+    /* Return the strings of the text, and the text outside of them, or None if a string is not closed */
+    fn parse_toml_strings(text: &str) -> Option<(Vec<String>, String)> {
+        let mut strings = vec![];
+        let mut rest = S!("");
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c != '"' && c != '\'' {
+                rest.push(c);
+                continue;
+            }
+            let mut value = S!("");
+            loop {
+                match chars.next()? {
+                    '\\' if c == '"' => value.push(chars.next()?),
+                    end if end == c => break,
+                    other => value.push(other)
+                }
+            }
+            strings.push(value);
+        }
+        Some((strings, rest))
+    }
+}
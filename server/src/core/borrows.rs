@@ -0,0 +1,134 @@
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+
+use tracing::warn;
+
+thread_local! {
+    /* Whether a try_borrow or a try_borrow_mut failed since the last call to Borrows::take_conflicts */
+    static CONFLICT: Cell<bool> = const { Cell::new(false) };
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /* The tracked mutable borrows that are alive: the address of the cell, and the place that borrowed it */
+    static OUTSTANDING: RefCell<Vec<(usize, &'static Location<'static>)>> = const { RefCell::new(vec![]) };
+}
+
+/* A mutable borrow taken with Borrows::borrow_mut. In debug builds, the place that took it is recorded as long as it is
+alive, so that the panic of a conflicting borrow can be traced back to it in the logs */
+pub struct TrackedRefMut<'a, T> {
+    inner: RefMut<'a, T>,
+    #[cfg(debug_assertions)]
+    address: usize,
+}
+
+impl<T> Deref for TrackedRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for TrackedRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<T> Drop for TrackedRefMut<'_, T> {
+    fn drop(&mut self) {
+        OUTSTANDING.with(|outstanding| {
+            let mut outstanding = outstanding.borrow_mut();
+            if let Some(index) = outstanding.iter().rposition(|(address, _)| *address == self.address) {
+                outstanding.remove(index);
+            }
+        });
+    }
+}
+
+/* Borrows of the symbols and the files that must not crash the server when they conflict.
+The builders and the hooks borrow the same Rc<RefCell<...>> in nested calls: a conflict between two of these borrows is a
+bug that panics. The request paths take their borrows with try_borrow and try_borrow_mut instead of crashing when a value is already
+borrowed. The failed borrows are recorded, and a request that met one is answered with an error the client retries (see
+Odoo::handle_request). The borrow_mut of the hot spots of the builders is tracked: in
+debug builds, the place holding the conflicting borrow is written in the logs. */
+pub struct Borrows {}
+
+impl Borrows {
+
+    /* Borrow the cell, or return None if it is mutably borrowed. The conflict is logged */
+    #[track_caller]
+    pub fn try_borrow<T>(cell: &RefCell<T>) -> Option<Ref<'_, T>> {
+        match cell.try_borrow() {
+            Ok(inner) => Some(inner),
+            Err(_) => {
+                Borrows::log_conflict(Location::caller(), Borrows::get_outstanding_location(cell));
+                None
+            }
+        }
+    }
+
+    /* Mutably borrow the cell, or return None if it is already borrowed. The conflict is logged */
+    #[track_caller]
+    pub fn try_borrow_mut<T>(cell: &RefCell<T>) -> Option<TrackedRefMut<'_, T>> {
+        match cell.try_borrow_mut() {
+            Ok(inner) => Some(Borrows::track(inner, cell.as_ptr() as usize, Location::caller())),
+            Err(_) => {
+                Borrows::log_conflict(Location::caller(), Borrows::get_outstanding_location(cell));
+                None
+            }
+        }
+    }
+
+    /* Return whether a try_borrow or a try_borrow_mut failed on this thread since the last call, and forget it */
+    pub fn take_conflicts() -> bool {
+        CONFLICT.with(|conflict| conflict.replace(false))
+    }
+
+    /* Mutably borrow the cell, recording the place of the borrow in debug builds. Panics like RefCell::borrow_mut if the
+    cell is already borrowed, with the place of the outstanding borrow if it is known */
+    #[track_caller]
+    pub fn borrow_mut<T>(cell: &RefCell<T>) -> TrackedRefMut<'_, T> {
+        let Ok(inner) = cell.try_borrow_mut() else {
+            match Borrows::get_outstanding_location(cell) {
+                Some(outstanding) => panic!("already borrowed: BorrowMutError (held at {})", outstanding),
+                None => panic!("already borrowed: BorrowMutError"),
+            }
+        };
+        Borrows::track(inner, cell.as_ptr() as usize, Location::caller())
+    }
+
+    /* Return the place holding the tracked mutable borrow of the cell. Always None in release builds */
+    #[cfg(debug_assertions)]
+    pub fn get_outstanding_location<T>(cell: &RefCell<T>) -> Option<&'static Location<'static>> {
+        let address = cell.as_ptr() as usize;
+        OUTSTANDING.with(|outstanding| outstanding.borrow().iter().rev().find(|(a, _)| *a == address).map(|(_, location)| *location))
+    }
+
+    #[cfg(not(debug_assertions))]
+    pub fn get_outstanding_location<T>(_cell: &RefCell<T>) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    #[cfg(debug_assertions)]
+    fn track<'a, T>(inner: RefMut<'a, T>, address: usize, location: &'static Location<'static>) -> TrackedRefMut<'a, T> {
+        OUTSTANDING.with(|outstanding| outstanding.borrow_mut().push((address, location)));
+        TrackedRefMut { inner, address }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn track<'a, T>(inner: RefMut<'a, T>, _address: usize, _location: &'static Location<'static>) -> TrackedRefMut<'a, T> {
+        TrackedRefMut { inner }
+    }
+
+    fn log_conflict(location: &Location, outstanding: Option<&Location>) {
+        CONFLICT.with(|conflict| conflict.set(true));
+        match outstanding {
+            Some(outstanding) => warn!("{} can't borrow a value borrowed at {}", location, outstanding),
+            None => warn!("{} can't borrow a value that is already borrowed", location),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use ruff_python_ast::Operator;
+
+pub const INT: &str = "builtins.int";
+pub const FLOAT: &str = "builtins.float";
+pub const STR: &str = "builtins.str";
+pub const BYTES: &str = "builtins.bytes";
+pub const LIST: &str = "builtins.list";
+pub const TUPLE: &str = "builtins.tuple";
+pub const SET: &str = "builtins.set";
+
+/* Result types of the arithmetic between the builtin numbers, strings and containers:
+    int + int -> int, int / int -> float, int + float -> float
+    str + str -> str, str * int -> str, str % value -> str (the same for bytes)
+    list + list -> list, list * int -> list (the same for tuple)
+    set | set -> set
+The operands are given by the paths of their classes, as for DateArithmetic. */
+pub struct BuiltinArithmetic {}
+
+impl BuiltinArithmetic {
+
+    /* Return the path of the class of the result of 'left op right', where left and right are the paths of the classes
+    of the operands, if it is an arithmetic between builtin values */
+    pub fn get_result_type(left: &str, op: Operator, right: &str) -> Option<&'static str> {
+        let is_number = |path: &str| path == INT || path == FLOAT;
+        match (left, right) {
+            (INT, INT) => match op {
+                Operator::Div => Some(FLOAT),
+                Operator::MatMult => None,
+                _ => Some(INT)
+            },
+            (left, right) if is_number(left) && is_number(right) => match op {
+                Operator::Add | Operator::Sub | Operator::Mult | Operator::Div | Operator::FloorDiv | Operator::Mod | Operator::Pow => Some(FLOAT),
+                _ => None
+            },
+            (STR, STR) | (BYTES, BYTES) | (LIST, LIST) | (TUPLE, TUPLE) if op == Operator::Add => Some(BuiltinArithmetic::as_static(left)),
+            (STR | BYTES | LIST | TUPLE, INT) if op == Operator::Mult => Some(BuiltinArithmetic::as_static(left)),
+            (INT, STR | BYTES | LIST | TUPLE) if op == Operator::Mult => Some(BuiltinArithmetic::as_static(right)),
+            (STR | BYTES, _) if op == Operator::Mod => Some(BuiltinArithmetic::as_static(left)),
+            (SET, SET) => match op {
+                Operator::BitOr | Operator::BitAnd | Operator::BitXor | Operator::Sub => Some(SET),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    fn as_static(path: &str) -> &'static str {
+        [INT, FLOAT, STR, BYTES, LIST, TUPLE, SET].into_iter().find(|builtin| *builtin == path).unwrap()
+    }
+}
@@ -0,0 +1,278 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ruff_python_ast::{Arguments, Expr, ExprCall, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::constants::SymType;
+use crate::core::evaluation::{Evaluation, EvaluationValue};
+use crate::core::file_mgr::FileMgr;
+use crate::core::python_utils;
+use crate::core::symbols::function_symbol::{ArgumentType, FunctionSymbol};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* Maximum depth of the bases followed to find the keys of a TypedDict */
+const MAX_TYPED_DICT_DEPTH: u32 = 10;
+
+/* The values given by an unpacked argument of a call, when they are known */
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum UnpackedValues {
+    POSITIONAL(usize), //*values: the number of items of a list or a tuple
+    KEYWORDS(Vec<String>), //**values: the keys of a dict
+}
+
+/* The parameters of a function that receive the arguments of a call, with the binding rules of Python:
+    def func(self, a, b=1, *args, c, **kwargs): ...
+    record.func(1, 2, 3, c=4, d=5)
+binds 1 to a, 2 to b, 3 to args, 4 to c and 5 to kwargs. The parameters are given by their index in the arguments of
+the function. The arguments that can't be bound (too many positional arguments, unknown keywords) have no parameter.
+An unpacked argument whose values are known, like *[a, b] or **{'c': 1}, binds each of its values. After an unpacked
+argument whose values are unknown, the positional arguments are not bound anymore, and the binding is incomplete. */
+#[derive(Debug, PartialEq)]
+pub struct CallBinding {
+    pub args: Vec<Option<usize>>, //parameter of each positional argument of the call, the first one for a known unpacked argument
+    pub keywords: Vec<Option<usize>>, //parameter of each keyword argument of the call, None for **values
+    pub unpacked_keywords: Vec<(String, Option<usize>)>, //parameter of each key of the known **values
+    pub complete: bool, //false if a value of unknown length or keys is unpacked: missing or unexpected arguments can't be reported
+    next_positional: Option<usize>, //parameter of a positional argument added after the others
+}
+
+impl CallBinding {
+
+    /* Bind the arguments of the call to the parameters of the function. If is_on_instance, the first parameter is bound to
+    the object the function is called on */
+    pub fn bind_function(function: &FunctionSymbol, arguments: &Arguments, is_on_instance: bool) -> CallBinding {
+        let parameters: Vec<(String, ArgumentType)> = function.args.iter()
+            .map(|arg| (arg.symbol.upgrade().map(|s| s.borrow().name().clone()).unwrap_or_default(), arg.arg_type))
+            .collect();
+        CallBinding::bind(&parameters, arguments, is_on_instance)
+    }
+
+    pub fn bind(parameters: &[(String, ArgumentType)], arguments: &Arguments, skip_first: bool) -> CallBinding {
+        CallBinding::bind_with(parameters, arguments, skip_first, &CallBinding::get_literal_values)
+    }
+
+    /* Bind the arguments, with the values of the unpacked arguments given by unpacked, called on the unpacked expression */
+    pub fn bind_with(parameters: &[(String, ArgumentType)], arguments: &Arguments, skip_first: bool, unpacked: &dyn Fn(&Expr) -> Option<UnpackedValues>) -> CallBinding {
+        let mut positional = parameters.iter().enumerate()
+            .filter(|(_, (_, arg_type))| matches!(arg_type, ArgumentType::POS_ONLY | ArgumentType::ARG))
+            .map(|(index, _)| index)
+            .skip(skip_first as usize);
+        let vararg = parameters.iter().position(|(_, arg_type)| *arg_type == ArgumentType::VARARG);
+        let kwarg = parameters.iter().position(|(_, arg_type)| *arg_type == ArgumentType::KWARG);
+        let mut bound = vec![];
+        let mut complete = true;
+        let mut args = vec![];
+        for arg in arguments.args.iter() {
+            let count = match arg {
+                Expr::Starred(starred) => match unpacked(&starred.value) {
+                    Some(UnpackedValues::POSITIONAL(count)) => count,
+                    _ => {
+                        complete = false;
+                        0
+                    }
+                },
+                _ => 1
+            };
+            let mut parameter = None;
+            for index in 0..count {
+                let value_parameter = match complete {
+                    true => positional.next().or(vararg),
+                    false => None
+                };
+                bound.extend(value_parameter);
+                if index == 0 {
+                    parameter = value_parameter;
+                }
+            }
+            args.push(parameter);
+        }
+        let next_positional = match complete {
+            true => positional.next().or(vararg),
+            false => None
+        };
+        let bind_keyword = |name: &str, bound: &Vec<usize>| {
+            let parameter = parameters.iter().position(|(param_name, arg_type)| {
+                param_name == name && matches!(arg_type, ArgumentType::ARG | ArgumentType::KWORD_ONLY)
+            });
+            match parameter {
+                Some(parameter) if bound.contains(&parameter) => None, //given twice
+                Some(parameter) => Some(parameter),
+                None => kwarg
+            }
+        };
+        let mut unpacked_keywords = vec![];
+        let mut keywords = vec![];
+        for keyword in arguments.keywords.iter() {
+            match &keyword.arg {
+                Some(name) => keywords.push(bind_keyword(name.as_str(), &bound)),
+                None => {
+                    match unpacked(&keyword.value) {
+                        Some(UnpackedValues::KEYWORDS(keys)) => {
+                            unpacked_keywords.extend(keys.into_iter().map(|key| {
+                                let parameter = bind_keyword(key.as_str(), &bound);
+                                (key, parameter)
+                            }));
+                        },
+                        _ => complete = false
+                    }
+                    keywords.push(None);
+                }
+            }
+        }
+        CallBinding { args, keywords, unpacked_keywords, complete, next_positional }
+    }
+
+    /* Return the values of an unpacked literal: the items of *[a, b] or *(a, b), and the keys of **{'a': 1} or
+    **dict(a=1). An item that is itself unpacked makes the values unknown */
+    pub fn get_literal_values(expr: &Expr) -> Option<UnpackedValues> {
+        match expr {
+            Expr::List(list) if !list.elts.iter().any(|elt| elt.is_starred_expr()) => Some(UnpackedValues::POSITIONAL(list.elts.len())),
+            Expr::Tuple(tuple) if !tuple.elts.iter().any(|elt| elt.is_starred_expr()) => Some(UnpackedValues::POSITIONAL(tuple.elts.len())),
+            Expr::Dict(dict) => dict.items.iter()
+                .map(|item| item.key.as_ref().and_then(|key| key.as_string_literal_expr()).map(|key| key.value.to_string()))
+                .collect::<Option<Vec<String>>>()
+                .map(UnpackedValues::KEYWORDS),
+            Expr::Call(call) if call.arguments.args.is_empty() && call.func.as_name_expr().is_some_and(|name| name.id.as_str() == "dict") => call.arguments.keywords.iter()
+                .map(|keyword| keyword.arg.as_ref().map(|arg| arg.to_string()))
+                .collect::<Option<Vec<String>>>()
+                .map(UnpackedValues::KEYWORDS),
+            _ => None
+        }
+    }
+
+    /* Return the values of the unpacked arguments of the call that are known, by range of the unpacked expression: the
+    literals, the constant lists and dicts, the variables annotated with a TypedDict, and the dict literals returned by a
+    helper, like in self.create(**self._prepare_vals()) */
+    pub fn get_unpacked_values(session: &mut SessionInfo, call: &ExprCall, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> HashMap<TextRange, UnpackedValues> {
+        let unpacked: Vec<(&Expr, bool)> = call.arguments.args.iter().filter_map(|arg| arg.as_starred_expr()).map(|starred| (&*starred.value, true))
+            .chain(call.arguments.keywords.iter().filter(|keyword| keyword.arg.is_none()).map(|keyword| (&keyword.value, false)))
+            .collect();
+        let mut res = HashMap::new();
+        for (value, positional) in unpacked.into_iter() {
+            let values = match CallBinding::get_literal_values(value) {
+                Some(values) => Some(values),
+                None => CallBinding::eval_unpacked_values(session, value, positional, scope.clone(), max_infer)
+            };
+            if let Some(values) = values {
+                res.insert(value.range(), values);
+            }
+        }
+        res
+    }
+
+    fn eval_unpacked_values(session: &mut SessionInfo, value: &Expr, positional: bool, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<UnpackedValues> {
+        if let (Expr::Call(call), false) = (value, positional) {
+            return CallBinding::get_returned_keys(session, call, scope, max_infer).map(UnpackedValues::KEYWORDS);
+        }
+        let (evaluations, _) = Evaluation::eval_from_ast(session, value, scope, max_infer);
+        let mut res = None;
+        for evaluation in evaluations.iter() {
+            //an empty collection is also the value of a collection whose items are not all literals
+            let values = match (&evaluation.value, positional) {
+                (Some(EvaluationValue::LIST(items) | EvaluationValue::TUPLE(items)), true) if !items.is_empty() => UnpackedValues::POSITIONAL(items.len()),
+                (Some(EvaluationValue::DICT(items)), false) if !items.is_empty() => UnpackedValues::KEYWORDS(items.iter()
+                    .map(|(key, _)| key.as_string_literal_expr().map(|key| key.value.to_string()))
+                    .collect::<Option<Vec<String>>>()?),
+                (_, false) => {
+                    let weak = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+                    let class = Symbol::follow_ref(&weak, session, &mut None, false, false, None, &mut vec![]).first()?.weak.upgrade()?;
+                    if class.borrow().typ() != SymType::CLASS {
+                        return None;
+                    }
+                    UnpackedValues::KEYWORDS(CallBinding::get_typed_dict_keys(session, &class, 0)?)
+                },
+                _ => return None
+            };
+            if res.as_ref().is_some_and(|res| *res != values) {
+                return None;
+            }
+            res = Some(values);
+        }
+        res
+    }
+
+    /* Return the keys of the dict literals returned by the called function, if they all have the same keys */
+    fn get_returned_keys(session: &mut SessionInfo, call: &ExprCall, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<Vec<String>> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, &call.func, scope, max_infer);
+        let mut res: Option<Vec<String>> = None;
+        for evaluation in evaluations.iter() {
+            let function = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade()?;
+            if function.borrow().typ() != SymType::FUNCTION {
+                return None;
+            }
+            let file_info = FileMgr::get_file_info_of_symbol(session, &function)?;
+            let file_info = file_info.borrow();
+            let function = function.borrow();
+            let Stmt::FunctionDef(function_def) = function.get_ast_node(&file_info)? else {
+                return None;
+            };
+            for returned in python_utils::get_returned_values(&function_def.body).into_iter() {
+                let Some(UnpackedValues::KEYWORDS(mut keys)) = returned.and_then(CallBinding::get_literal_values) else {
+                    return None;
+                };
+                keys.sort();
+                if res.as_ref().is_some_and(|res| *res != keys) {
+                    return None;
+                }
+                res = Some(keys);
+            }
+        }
+        res
+    }
+
+    /* Return the keys of a TypedDict class: the annotated names of its body and of the bodies of its TypedDict bases */
+    fn get_typed_dict_keys(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, depth: u32) -> Option<Vec<String>> {
+        if depth > MAX_TYPED_DICT_DEPTH {
+            return None;
+        }
+        let file_info = FileMgr::get_file_info_of_symbol(session, class)?;
+        let (mut is_typed_dict, mut keys) = {
+            let file_info = file_info.borrow();
+            let class = class.borrow();
+            let Stmt::ClassDef(class_def) = class.get_ast_node(&file_info)? else {
+                return None;
+            };
+            let is_typed_dict = class_def.bases().iter().any(|base| match base {
+                Expr::Name(name) => name.id.as_str() == "TypedDict",
+                Expr::Attribute(attr) => attr.attr.as_str() == "TypedDict",
+                _ => false
+            });
+            let keys: Vec<String> = class_def.body.iter().filter_map(|stmt| match stmt {
+                Stmt::AnnAssign(ann_assign) => ann_assign.target.as_name_expr().map(|name| name.id.to_string()),
+                _ => None
+            }).collect();
+            (is_typed_dict, keys)
+        };
+        let bases: Vec<Rc<RefCell<Symbol>>> = class.borrow().as_class_sym().bases.iter().filter_map(|base| base.upgrade()).collect();
+        for base in bases.iter() {
+            if let Some(base_keys) = CallBinding::get_typed_dict_keys(session, base, depth + 1) {
+                keys.extend(base_keys);
+                is_typed_dict = true;
+            }
+        }
+        is_typed_dict.then_some(keys)
+    }
+
+    /* Return the parameter of the argument at the given offset of the call, like the active parameter of a signature
+    help. After the last positional argument, it is the parameter of the next one */
+    pub fn active_parameter(&self, arguments: &Arguments, offset: TextSize) -> Option<usize> {
+        for (arg, parameter) in arguments.args.iter().zip(self.args.iter()) {
+            if offset <= arg.range().end() {
+                return *parameter;
+            }
+        }
+        for (keyword, parameter) in arguments.keywords.iter().zip(self.keywords.iter()) {
+            if offset <= keyword.range().end() {
+                return *parameter;
+            }
+        }
+        match arguments.keywords.is_empty() {
+            true => self.next_positional,
+            false => None
+        }
+    }
+}
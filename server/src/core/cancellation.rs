@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lsp_server::{ErrorCode, RequestId, ResponseError};
+
+use crate::S;
+
+/* A flag shared between a long operation, that checks it between its steps to stop early, and the threads that can
+cancel it */
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    /* The error answered to a request stopped by $/cancelRequest */
+    pub fn cancelled_error() -> ResponseError {
+        ResponseError { code: ErrorCode::RequestCanceled as i32, message: S!("Request cancelled"), data: None }
+    }
+}
+
+/* The tokens of the operations that can be cancelled: the rebuild, that is stale as soon as a file it has still to
+build is edited, and the requests being processed, cancelled by $/cancelRequest. It is shared by SyncOdoo and the
+dispatcher of the Server, that doesn't wait for SyncOdoo to be free to cancel them */
+#[derive(Debug, Clone, Default)]
+pub struct CancellationMgr {
+    rebuild: CancellationToken,
+    pending_paths: Arc<Mutex<HashSet<String>>>, //paths of the files and packages in the rebuild queues
+    requests: Arc<Mutex<HashMap<RequestId, CancellationToken>>>,
+}
+
+impl CancellationMgr {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_rebuild_token(&self) -> CancellationToken {
+        self.rebuild.clone()
+    }
+
+    pub fn add_pending_path(&self, path: &str) {
+        self.pending_paths.lock().unwrap().insert(path.to_string());
+    }
+
+    pub fn remove_pending_path(&self, path: &str) {
+        self.pending_paths.lock().unwrap().remove(path);
+    }
+
+    pub fn clear_pending_paths(&self) {
+        self.pending_paths.lock().unwrap().clear();
+    }
+
+    /* Cancel the rebuild if the edited file is still to be built by it. The path of a package is the one of its
+    folder. Return true if the rebuild is cancelled */
+    pub fn cancel_rebuild_for(&self, path: &str) -> bool {
+        let pending_paths = self.pending_paths.lock().unwrap();
+        let path = Path::new(path);
+        let is_init = path.file_name().is_some_and(|name| name == "__init__.py" || name == "__init__.pyi");
+        let pending = pending_paths.iter().any(|pending| Path::new(pending) == path || (is_init && path.parent() == Some(Path::new(pending))));
+        if pending {
+            self.rebuild.cancel();
+        }
+        pending
+    }
+
+    /* Register a request when it is dispatched, so that it can be cancelled before being processed */
+    pub fn start_request(&self, id: &RequestId) -> CancellationToken {
+        self.requests.lock().unwrap().entry(id.clone()).or_default().clone()
+    }
+
+    pub fn end_request(&self, id: &RequestId) {
+        self.requests.lock().unwrap().remove(id);
+    }
+
+    /* Cancel a request being processed. Return false if it is already answered */
+    pub fn cancel_request(&self, id: &RequestId) -> bool {
+        match self.requests.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            },
+            None => false
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use ruff_python_ast::comparable::ComparableExpr;
+use ruff_python_ast::{Expr, ExprCall, ExprNoneLiteral, ExprSlice, Operator};
+use ruff_text_size::Ranged;
+
+use crate::core::evaluation::EvaluationValue;
+use crate::core::static_conditions::{StaticConditions, StaticValue};
+
+/* Maximum number of elements of a folded collection. A bigger repetition is not evaluated */
+const MAX_FOLDED_LENGTH: usize = 10_000;
+
+/* The builtin calls that build a collection from another one */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    LIST,     //list(values)
+    TUPLE,    //tuple(values)
+    SET,      //set(values)
+    SORTED,   //sorted(values)
+    FROMKEYS, //dict.fromkeys(values)
+}
+
+/* Constant folding of the operations on constant collections, beyond the literals:
+    __all__ = SUBMODULES[:3] + ("extra",) * 2
+    NAMES = list(dict.fromkeys(BASE_NAMES + EXTRA_NAMES))
+Slices and repetitions need constant ints. The conversions are recognized by their name: a module that shadows list or
+sorted is not handled. */
+pub struct CollectionFolding {}
+
+impl CollectionFolding {
+
+    /* Return the value of a collection expression, if it can be computed statically. resolve gives the value of the
+    names and attributes */
+    pub fn fold(expr: &Expr, resolve: &mut dyn FnMut(&Expr) -> Option<EvaluationValue>) -> Option<EvaluationValue> {
+        match expr {
+            Expr::List(l) => Some(EvaluationValue::LIST(CollectionFolding::fold_elements(&l.elts, resolve)?)),
+            Expr::Tuple(t) => Some(EvaluationValue::TUPLE(CollectionFolding::fold_elements(&t.elts, resolve)?)),
+            Expr::Set(s) => Some(EvaluationValue::SET(CollectionFolding::dedup(CollectionFolding::fold_elements(&s.elts, resolve)?))),
+            Expr::Dict(d) => {
+                let mut items = vec![];
+                for item in d.items.iter() {
+                    match &item.key {
+                        Some(key) if key.is_literal_expr() && item.value.is_literal_expr() => items.push((key.clone(), item.value.clone())),
+                        _ => return None
+                    }
+                }
+                Some(EvaluationValue::DICT(items))
+            },
+            Expr::Name(_) | Expr::Attribute(_) => resolve(expr),
+            Expr::BinOp(op) if op.op == Operator::Add => {
+                let left = CollectionFolding::fold(&op.left, resolve)?;
+                if !matches!(left, EvaluationValue::LIST(_) | EvaluationValue::TUPLE(_)) {
+                    return None;
+                }
+                CollectionFolding::concat(&left, &CollectionFolding::fold(&op.right, resolve)?)
+            },
+            Expr::BinOp(op) if op.op == Operator::Mult => {
+                //the count can be on both sides: ("a",) * 2 or 2 * ("a",)
+                if let Some(count) = CollectionFolding::get_int(&op.right, resolve) {
+                    return CollectionFolding::repeat(&CollectionFolding::fold(&op.left, resolve)?, count);
+                }
+                let count = CollectionFolding::get_int(&op.left, resolve)?;
+                CollectionFolding::repeat(&CollectionFolding::fold(&op.right, resolve)?, count)
+            },
+            Expr::Subscript(sub) => {
+                let Expr::Slice(slice) = &*sub.slice else {
+                    return None;
+                };
+                let (lower, upper, step) = CollectionFolding::get_slice_bounds(slice, resolve)?;
+                CollectionFolding::slice(&CollectionFolding::fold(&sub.value, resolve)?, lower, upper, step)
+            },
+            Expr::Call(call) => {
+                let (conversion, arg) = CollectionFolding::get_conversion(call)?;
+                CollectionFolding::convert(&CollectionFolding::fold(arg, resolve)?, conversion)
+            },
+            _ => None
+        }
+    }
+
+    /* Return the elements of a list, tuple or set display. They must be literals, or unpack a foldable collection */
+    fn fold_elements(elts: &Vec<Expr>, resolve: &mut dyn FnMut(&Expr) -> Option<EvaluationValue>) -> Option<Vec<Expr>> {
+        let mut res = vec![];
+        for elt in elts.iter() {
+            match elt {
+                Expr::Starred(starred) => res.extend(CollectionFolding::get_elements(&CollectionFolding::fold(&starred.value, resolve)?)?),
+                elt if elt.is_literal_expr() => res.push(elt.clone()),
+                _ => return None
+            }
+        }
+        Some(res)
+    }
+
+    /* Return the elements given by an iteration on the value: the items of a list, tuple or set, or the keys of a dict */
+    pub fn get_elements(value: &EvaluationValue) -> Option<Vec<Expr>> {
+        match value {
+            EvaluationValue::LIST(items) | EvaluationValue::TUPLE(items) | EvaluationValue::SET(items) => Some(items.clone()),
+            EvaluationValue::DICT(items) => Some(items.iter().map(|(key, _)| key.clone()).collect()),
+            _ => None
+        }
+    }
+
+    /* left + right, for two lists or two tuples */
+    pub fn concat(left: &EvaluationValue, right: &EvaluationValue) -> Option<EvaluationValue> {
+        match (left, right) {
+            (EvaluationValue::LIST(left), EvaluationValue::LIST(right)) => Some(EvaluationValue::LIST([left.clone(), right.clone()].concat())),
+            (EvaluationValue::TUPLE(left), EvaluationValue::TUPLE(right)) => Some(EvaluationValue::TUPLE([left.clone(), right.clone()].concat())),
+            _ => None
+        }
+    }
+
+    /* value * count, for a list or a tuple */
+    pub fn repeat(value: &EvaluationValue, count: i64) -> Option<EvaluationValue> {
+        match value {
+            EvaluationValue::LIST(items) => Some(EvaluationValue::LIST(CollectionFolding::repeat_items(items, count)?)),
+            EvaluationValue::TUPLE(items) => Some(EvaluationValue::TUPLE(CollectionFolding::repeat_items(items, count)?)),
+            _ => None
+        }
+    }
+
+    /* value[lower:upper:step], for a list or a tuple */
+    pub fn slice(value: &EvaluationValue, lower: Option<i64>, upper: Option<i64>, step: Option<i64>) -> Option<EvaluationValue> {
+        match value {
+            EvaluationValue::LIST(items) => Some(EvaluationValue::LIST(CollectionFolding::slice_items(items, lower, upper, step)?)),
+            EvaluationValue::TUPLE(items) => Some(EvaluationValue::TUPLE(CollectionFolding::slice_items(items, lower, upper, step)?)),
+            _ => None
+        }
+    }
+
+    /* Return the result of a conversion call on the value. sorted() needs elements that are all strings or all ints */
+    pub fn convert(value: &EvaluationValue, conversion: Conversion) -> Option<EvaluationValue> {
+        let elements = CollectionFolding::get_elements(value)?;
+        match conversion {
+            Conversion::LIST => Some(EvaluationValue::LIST(elements)),
+            Conversion::TUPLE => Some(EvaluationValue::TUPLE(elements)),
+            Conversion::SET => Some(EvaluationValue::SET(CollectionFolding::dedup(elements))),
+            Conversion::SORTED => Some(EvaluationValue::LIST(CollectionFolding::sort(elements)?)),
+            Conversion::FROMKEYS => Some(EvaluationValue::DICT(CollectionFolding::dedup(elements).into_iter().map(|key| {
+                let none = Expr::NoneLiteral(ExprNoneLiteral { range: key.range() });
+                (key, none)
+            }).collect())),
+        }
+    }
+
+    /* Return the conversion done by a call, and the collection it converts */
+    pub fn get_conversion(call: &ExprCall) -> Option<(Conversion, &Expr)> {
+        let [arg] = &*call.arguments.args else {
+            return None;
+        };
+        if !call.arguments.keywords.is_empty() || arg.is_starred_expr() {
+            return None;
+        }
+        let conversion = match &*call.func {
+            Expr::Name(name) => match name.id.as_str() {
+                "list" => Conversion::LIST,
+                "tuple" => Conversion::TUPLE,
+                "set" => Conversion::SET,
+                "sorted" => Conversion::SORTED,
+                _ => return None
+            },
+            Expr::Attribute(attr) if attr.attr.as_str() == "fromkeys" && matches!(&*attr.value, Expr::Name(dict) if dict.id.as_str() == "dict") => Conversion::FROMKEYS,
+            _ => return None
+        };
+        Some((conversion, arg))
+    }
+
+    /* Return the constant bounds of a slice. A missing bound or None is given as None */
+    pub fn get_slice_bounds(slice: &ExprSlice, resolve: &mut dyn FnMut(&Expr) -> Option<EvaluationValue>) -> Option<(Option<i64>, Option<i64>, Option<i64>)> {
+        let mut bounds = [None, None, None];
+        for (bound, expr) in bounds.iter_mut().zip([&slice.lower, &slice.upper, &slice.step]) {
+            match expr.as_deref() {
+                None | Some(Expr::NoneLiteral(_)) => {},
+                Some(expr) => *bound = Some(CollectionFolding::get_int(expr, resolve)?),
+            }
+        }
+        Some((bounds[0], bounds[1], bounds[2]))
+    }
+
+    /* Return the value of a constant int, like 3, -1 or a name holding an int */
+    pub fn get_int(expr: &Expr, resolve: &mut dyn FnMut(&Expr) -> Option<EvaluationValue>) -> Option<i64> {
+        let value = match expr {
+            Expr::Name(_) | Expr::Attribute(_) => match resolve(expr)? {
+                EvaluationValue::CONSTANT(constant) => StaticConditions::get_value(&constant, &mut |_| None),
+                _ => None
+            },
+            _ => StaticConditions::get_value(expr, &mut |_| None)
+        };
+        match value? {
+            StaticValue::INT(i) => Some(i),
+            _ => None
+        }
+    }
+
+    /* items[lower:upper:step], with the same clamping of the bounds as Python */
+    pub fn slice_items<T: Clone>(items: &[T], lower: Option<i64>, upper: Option<i64>, step: Option<i64>) -> Option<Vec<T>> {
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return None; //ValueError
+        }
+        let len = items.len() as i64;
+        let adjust = |bound: i64| {
+            if bound < 0 {
+                (bound + len).max(if step < 0 { -1 } else { 0 })
+            } else {
+                bound.min(if step < 0 { len - 1 } else { len })
+            }
+        };
+        let (mut index, end) = match step > 0 {
+            true => (lower.map_or(0, adjust), upper.map_or(len, adjust)),
+            false => (lower.map_or(len - 1, adjust), upper.map_or(-1, adjust))
+        };
+        let mut res = vec![];
+        while (step > 0 && index < end) || (step < 0 && index > end) {
+            res.push(items[index as usize].clone());
+            index += step;
+        }
+        Some(res)
+    }
+
+    /* items * count. A negative count gives an empty collection */
+    pub fn repeat_items<T: Clone>(items: &[T], count: i64) -> Option<Vec<T>> {
+        let count = count.max(0) as usize;
+        if items.len().saturating_mul(count) > MAX_FOLDED_LENGTH {
+            return None;
+        }
+        Some(items.repeat(count))
+    }
+
+    /* Remove the elements equal to a previous one, keeping the first occurrence */
+    pub fn dedup(elements: Vec<Expr>) -> Vec<Expr> {
+        let mut res: Vec<Expr> = vec![];
+        for element in elements.into_iter() {
+            if !res.iter().any(|r| ComparableExpr::from(r) == ComparableExpr::from(&element)) {
+                res.push(element);
+            }
+        }
+        res
+    }
+
+    /* Sort elements that are all strings or all ints. Python can't compare the other mixes of constants */
+    fn sort(elements: Vec<Expr>) -> Option<Vec<Expr>> {
+        let mut keyed = vec![];
+        for element in elements.into_iter() {
+            keyed.push((StaticConditions::get_value(&element, &mut |_| None)?, element));
+        }
+        if !keyed.iter().all(|(key, _)| matches!(key, StaticValue::STR(_))) && !keyed.iter().all(|(key, _)| matches!(key, StaticValue::INT(_))) {
+            return None;
+        }
+        keyed.sort_by(|(left, _), (right, _)| match (left, right) {
+            (StaticValue::STR(left), StaticValue::STR(right)) => left.cmp(right),
+            (StaticValue::INT(left), StaticValue::INT(right)) => left.cmp(right),
+            _ => std::cmp::Ordering::Equal
+        });
+        Some(keyed.into_iter().map(|(_, element)| element).collect())
+    }
+}
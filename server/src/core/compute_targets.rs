@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, Stmt};
+
+use crate::constants::SymType;
+use crate::core::field_consistency::FieldDeclaration;
+use crate::core::file_mgr::FileMgr;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* The fields assigned by the body of a compute method */
+#[derive(Debug, Default)]
+pub struct AssignedFields {
+    pub names: HashSet<String>,
+    pub dynamic: bool, //other fields can be assigned: the method calls super(), another compute method, or update() or setattr() with names known at runtime
+}
+
+/* A compute method fills the fields whose compute argument names it: compute="_compute_totals" can be shared by several
+fields, that must all be assigned by the method, or Odoo raises an error when one of them is read. The join is done on
+the literal declarations of a class body, like FieldConsistency */
+pub struct ComputeTargets {}
+
+impl ComputeTargets {
+
+    /* Return the fields computed by the method */
+    pub fn get_targets<'a>(fields: &'a [FieldDeclaration], method: &str) -> Vec<&'a FieldDeclaration> {
+        fields.iter().filter(|field| ComputeTargets::is_computed_by(field, method)).collect()
+    }
+
+    pub fn is_computed_by(field: &FieldDeclaration, method: &str) -> bool {
+        field.compute.as_ref().is_some_and(|(compute, _)| compute.as_deref() == Some(method))
+    }
+
+    /* A computed or related field is readonly, unless it is declared with readonly=False or has an inverse method */
+    pub fn is_writable(field: &FieldDeclaration) -> bool {
+        if field.compute.is_none() && field.related.is_none() {
+            return true;
+        }
+        field.inverse.is_some() || matches!(field.readonly, Some((false, _)))
+    }
+
+    /* Return the fields assigned by the statements: record.field = value, record['field'] = value, record.update({'field': value})
+    or setattr(record, 'field', value), on any record */
+    pub fn get_assigned_fields(body: &[Stmt]) -> AssignedFields {
+        let mut visitor = AssignmentVisitor { assigned: AssignedFields::default() };
+        for stmt in body.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.assigned
+    }
+
+    /* A method whose body is only a docstring, pass, ... or a raise is a placeholder, overridden by the classes that implement it */
+    pub fn is_placeholder(body: &[Stmt]) -> bool {
+        body.iter().all(|stmt| match stmt {
+            Stmt::Pass(_) | Stmt::Raise(_) => true,
+            Stmt::Expr(expr) => matches!(&*expr.value, Expr::StringLiteral(_) | Expr::EllipsisLiteral(_)),
+            _ => false
+        })
+    }
+
+    /* Return the fields declared in the body of a class, read from the ast of its file */
+    pub fn get_class_fields(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>) -> Vec<FieldDeclaration> {
+        if class.borrow().typ() != SymType::CLASS {
+            return vec![];
+        }
+        let Some(file_info) = FileMgr::get_file_info_of_symbol(session, class) else {
+            return vec![];
+        };
+        let class_range = *class.borrow().range();
+        let file_info = file_info.borrow();
+        file_info.ast.iter().flatten().find_map(|stmt| match stmt {
+            Stmt::ClassDef(c) if c.range == class_range => Some(c.body.iter().filter_map(FieldDeclaration::from_stmt).collect()),
+            _ => None
+        }).unwrap_or_default()
+    }
+
+    fn add_target(assigned: &mut AssignedFields, target: &Expr) {
+        match target {
+            Expr::Attribute(attr) => {
+                assigned.names.insert(attr.attr.to_string());
+            },
+            Expr::Subscript(subscript) => if let Expr::StringLiteral(name) = &*subscript.slice {
+                assigned.names.insert(name.value.to_string());
+            },
+            Expr::Tuple(tuple) => tuple.elts.iter().for_each(|elt| ComputeTargets::add_target(assigned, elt)),
+            Expr::List(list) => list.elts.iter().for_each(|elt| ComputeTargets::add_target(assigned, elt)),
+            Expr::Starred(starred) => ComputeTargets::add_target(assigned, &starred.value),
+            _ => {}
+        }
+    }
+
+    fn add_call(assigned: &mut AssignedFields, expr: &Expr) {
+        let Expr::Call(call) = expr else {
+            return;
+        };
+        match &*call.func {
+            Expr::Name(name) if name.id.as_str() == "super" => assigned.dynamic = true,
+            Expr::Name(name) if name.id.as_str() == "setattr" => match call.arguments.args.get(1) {
+                Some(Expr::StringLiteral(field)) => {
+                    assigned.names.insert(field.value.to_string());
+                },
+                _ => assigned.dynamic = true
+            },
+            Expr::Attribute(attr) if attr.attr.as_str() == "update" || attr.attr.as_str() == "write" => match call.arguments.args.first() {
+                Some(Expr::Dict(dict)) => {
+                    for item in dict.items.iter() {
+                        match &item.key {
+                            Some(Expr::StringLiteral(field)) => {
+                                assigned.names.insert(field.value.to_string());
+                            },
+                            _ => assigned.dynamic = true //a key built at runtime, or **values
+                        }
+                    }
+                },
+                None => {
+                    for keyword in call.arguments.keywords.iter() {
+                        match &keyword.arg {
+                            Some(field) => {
+                                assigned.names.insert(field.to_string());
+                            },
+                            None => assigned.dynamic = true
+                        }
+                    }
+                },
+                Some(_) => assigned.dynamic = true
+            },
+            //the computation is delegated to another compute method
+            Expr::Attribute(attr) if attr.attr.as_str().starts_with("_compute") => assigned.dynamic = true,
+            _ => {}
+        }
+    }
+}
+
+struct AssignmentVisitor {
+    assigned: AssignedFields,
+}
+
+impl<'a> Visitor<'a> for AssignmentVisitor {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Assign(assign) => assign.targets.iter().for_each(|target| ComputeTargets::add_target(&mut self.assigned, target)),
+            Stmt::AugAssign(aug_assign) => ComputeTargets::add_target(&mut self.assigned, &aug_assign.target),
+            Stmt::AnnAssign(ann_assign) if ann_assign.value.is_some() => ComputeTargets::add_target(&mut self.assigned, &ann_assign.target),
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        ComputeTargets::add_call(&mut self.assigned, expr);
+        walk_expr(self, expr);
+    }
+}
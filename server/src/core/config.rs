@@ -1,5 +1,7 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use lsp_types::request::Request;
+use lsp_types::DiagnosticSeverity;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -74,6 +76,27 @@ pub struct Config {
     pub additional_stubs: Vec<String>,
     pub stdlib: String,
     pub ac_filter_model_names: bool, // AC: Only show model names from module dependencies 
+    pub deprecated_api_file: String, // Path to a json file extending the deprecated API table
+    pub plugin_rules_file: String, // Path to a json file of rules describing frameworks built over Odoo (see PluginRule)
+    pub evaluation_depth: u32, // Maximum number of variables followed to evaluate a symbol (a = b, b imported from another file...)
+    pub max_file_diagnostics: u32, // Maximum number of diagnostics published for a file. 0 for no limit
+    pub hover_doc_max_lines: u32, // Maximum number of lines of the documentation shown by a hover. 0 for no limit
+    pub exclude: Vec<String>, // Globs of files to ignore, in addition to the .gitignore/.ignore files of the workspace
+    pub file_logging: bool, // Save logs to file. Can be changed at runtime
+    pub disabled_snippets: Vec<String>, // Names of the snippets that should not be proposed in completion
+    pub strict_manifest_data: bool, // Report the data files of manifests that can't be computed statically
+    pub validate_argument_types: bool, // Check the arguments of the calls to workspace functions against the annotations of their parameters
+    pub report_possibly_missing_members: bool, // Report the members of a union that are declared by some of its types only
+    pub check_external_dependencies: bool, // Report the third-party imports missing from the external_dependencies of the manifest, and the declared ones that are not installed
+    pub unused_fields_exclude: Vec<String>, // Globs of field names never reported by the findUnusedFields command, like legacy_*
+    pub module_dependencies_allowlist: Vec<String>, // Modules that can be used without being in the depends, like 'website' or 'sale_custom:website' (see ModuleDependencyCheck)
+    pub field_consistency_severity: HashMap<String, Option<DiagnosticSeverity>>, // Severity of the field consistency rules (see FieldRule), by code. None disables the rule
+    pub validation_exclude: Vec<String>, // Globs of files that are indexed but not validated
+    pub validation_include: Vec<String>, // If not empty, globs of the only files that are validated
+    pub validation_exclude_generated: bool, // Don't validate generated files (_pb2.py, '# generated by' header)
+    pub odoo_conf_file: String, // Name of the odoo.conf file read at the root of the workspace folders (see AutoConfig). Empty to not read it
+    pub path_sources: Vec<(String, String)>, // The odoo, addons and python paths, with the description of where they come from
+    pub arch_cache: bool, // Keep the architecture of the external files on disk between the sessions (see ArchCache)
 }
 
 impl Config {
@@ -90,6 +113,27 @@ impl Config {
             additional_stubs: vec![],
             stdlib: "".to_string(),
             ac_filter_model_names: false,
+            deprecated_api_file: "".to_string(),
+            plugin_rules_file: "".to_string(),
+            evaluation_depth: 20,
+            max_file_diagnostics: 500,
+            hover_doc_max_lines: 30,
+            exclude: vec![],
+            file_logging: true,
+            disabled_snippets: vec![],
+            strict_manifest_data: false,
+            validate_argument_types: false,
+            report_possibly_missing_members: false,
+            check_external_dependencies: true,
+            unused_fields_exclude: vec![],
+            module_dependencies_allowlist: vec![],
+            field_consistency_severity: HashMap::new(),
+            validation_exclude: vec![],
+            validation_include: vec![],
+            validation_exclude_generated: true,
+            odoo_conf_file: "odoo.conf".to_string(),
+            path_sources: vec![],
+            arch_cache: true,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::core::config::Config;
+use crate::utils::PathSanitizer;
+use crate::S;
+
+/* The oldest Odoo version supported by the server */
+pub const MIN_ODOO_VERSION: u32 = 14;
+/* The oldest Python version supported by the stubs of the server */
+pub const MIN_PYTHON_VERSION: (u32, u32) = (3, 7);
+
+/* How the database is built, given the result of the check of the configuration */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[allow(non_camel_case_types)]
+pub enum IndexMode {
+    #[default]
+    FULL, //odoo and the modules of the addons paths are indexed
+    REDUCED, //the configuration is not usable: only the opened files are analyzed, without the features of Odoo
+}
+
+/* A problem of the configuration. A blocking issue prevents odoo from being indexed */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub setting: String, //the setting at the origin of the issue: odooPath, addons or pythonPath
+    pub message: String,
+    pub blocking: bool,
+}
+
+impl ConfigIssue {
+    fn new(setting: &str, message: String, blocking: bool) -> Self {
+        Self { setting: setting.to_string(), message, blocking }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    pub odoo_version: Option<(u32, u32, u32)>,
+    pub python_version: Option<(u32, u32, u32)>,
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    pub fn mode(&self) -> IndexMode {
+        match self.issues.iter().any(|issue| issue.blocking) {
+            true => IndexMode::REDUCED,
+            false => IndexMode::FULL,
+        }
+    }
+}
+
+/* A wrong odoo path (a folder without odoo/release.py, an archive that is not extracted, an unsupported version) makes
+every import of odoo unresolved, and used to end in thousands of unrelated diagnostics. The paths and the interpreter of
+the configuration are checked before indexing: the blocking issues are reported once, and the database is built in
+the REDUCED mode instead */
+pub struct ConfigCheck {}
+
+impl ConfigCheck {
+
+    /* Check the odoo path, the addons paths and the interpreter of the configuration */
+    pub fn check(config: &Config) -> ConfigReport {
+        let mut report = ConfigReport::default();
+        let (odoo_version, mut issues) = ConfigCheck::check_odoo_path(&config.odoo_path);
+        report.odoo_version = odoo_version;
+        report.issues.append(&mut issues);
+        for addons_path in config.addons.iter() {
+            if let Some(issue) = ConfigCheck::check_addons_path(addons_path) {
+                report.issues.push(issue);
+            }
+        }
+        let (python_version, mut issues) = ConfigCheck::check_python(&config.python_path);
+        report.python_version = python_version;
+        report.issues.append(&mut issues);
+        report
+    }
+
+    /* Check that the odoo path contains the odoo package and the addons folder, and return the version of odoo/release.py */
+    pub fn check_odoo_path(odoo_path: &str) -> (Option<(u32, u32, u32)>, Vec<ConfigIssue>) {
+        if odoo_path.is_empty() {
+            return (None, vec![ConfigIssue::new("odooPath", S!("No odoo path is configured"), true)]);
+        }
+        let path = PathBuf::from(odoo_path);
+        if path.is_file() {
+            let message = match ConfigCheck::is_archive(&path) {
+                true => format!("The odoo path {} is an archive: extract it and select the extracted folder", odoo_path),
+                false => format!("The odoo path {} is a file, not a folder", odoo_path),
+            };
+            return (None, vec![ConfigIssue::new("odooPath", message, true)]);
+        }
+        if !path.is_dir() {
+            return (None, vec![ConfigIssue::new("odooPath", format!("The odoo path {} does not exist", odoo_path), true)]);
+        }
+        let missing: Vec<&str> = ["odoo/__init__.py", "odoo/release.py", "addons"].into_iter()
+            .filter(|entry| !path.join(entry).exists()).collect();
+        if !missing.is_empty() {
+            let mut message = format!("The odoo path {} is not a folder of the Odoo sources: {} not found", odoo_path, missing.join(", "));
+            if let Some(suggestion) = ConfigCheck::suggest_odoo_path(&path) {
+                message += &format!(". Did you mean {}?", suggestion);
+            }
+            return (None, vec![ConfigIssue::new("odooPath", message, true)]);
+        }
+        let version = fs::read_to_string(path.join("odoo/release.py")).ok().and_then(|content| ConfigCheck::parse_release(&content));
+        let issues = match version {
+            None => vec![ConfigIssue::new("odooPath", format!("Unable to read the Odoo version of {}", path.join("odoo/release.py").sanitize()), true)],
+            Some((major, minor, micro)) if major < MIN_ODOO_VERSION => vec![ConfigIssue::new("odooPath",
+                format!("Odoo {}.{}.{} is not supported: the oldest supported version is {}.0", major, minor, micro, MIN_ODOO_VERSION), true)],
+            Some(_) => vec![]
+        };
+        (version, issues)
+    }
+
+    /* Return the version of the version_info tuple of odoo/release.py */
+    pub fn parse_release(content: &str) -> Option<(u32, u32, u32)> {
+        let re = Regex::new(r#"^version_info\s*=\s*\(\s*['"]?(?:saas~)?(\d+)['"]?\s*,\s*(\d+)\s*,\s*(\d+)"#).unwrap();
+        content.lines().find_map(|line| {
+            let captures = re.captures(line)?;
+            Some((captures[1].parse().ok()?, captures[2].parse().ok()?, captures[3].parse().ok()?))
+        })
+    }
+
+    /* Check that an addons path is a folder containing at least one module */
+    pub fn check_addons_path(addons_path: &str) -> Option<ConfigIssue> {
+        let path = PathBuf::from(addons_path);
+        if !path.is_dir() {
+            return Some(ConfigIssue::new("addons", format!("The addons path {} does not exist", addons_path), false));
+        }
+        if ConfigCheck::is_module(&path) {
+            let parent = path.parent().map(|parent| parent.sanitize()).unwrap_or_default();
+            return Some(ConfigIssue::new("addons", format!("The addons path {} is a module: the addons path is the folder containing it, {}", addons_path, parent), false));
+        }
+        let has_module = fs::read_dir(&path).map(|entries| entries.flatten().any(|entry| ConfigCheck::is_module(&entry.path()))).unwrap_or(false);
+        if !has_module {
+            return Some(ConfigIssue::new("addons", format!("The addons path {} does not contain any module", addons_path), false));
+        }
+        None
+    }
+
+    /* Run the interpreter to get its version */
+    pub fn check_python(python_path: &str) -> (Option<(u32, u32, u32)>, Vec<ConfigIssue>) {
+        let output = Command::new(python_path).args(["-c", "import sys; print('%d.%d.%d' % sys.version_info[:3])"]).output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                let message = format!("The python interpreter {} failed: {}", python_path, String::from_utf8_lossy(&output.stderr).trim());
+                return (None, vec![ConfigIssue::new("pythonPath", message, true)]);
+            },
+            Err(e) => return (None, vec![ConfigIssue::new("pythonPath", format!("Unable to run the python interpreter {}: {}", python_path, e), true)])
+        };
+        let Some(version) = ConfigCheck::parse_python_version(&String::from_utf8_lossy(&output.stdout)) else {
+            return (None, vec![ConfigIssue::new("pythonPath", format!("{} did not report a Python version", python_path), true)]);
+        };
+        let issues = match (version.0, version.1) < MIN_PYTHON_VERSION {
+            true => vec![ConfigIssue::new("pythonPath", format!("Python {}.{}.{} of {} is not supported: the oldest supported version is {}.{}",
+                version.0, version.1, version.2, python_path, MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1), false)],
+            false => vec![]
+        };
+        (Some(version), issues)
+    }
+
+    /* Parse the version printed by the interpreter: 3.10.12, or Python 3.10.12 */
+    pub fn parse_python_version(output: &str) -> Option<(u32, u32, u32)> {
+        let version = output.trim().trim_start_matches("Python").trim();
+        let mut parts = version.split('.').map(|part| part.chars().take_while(|c| c.is_ascii_digit()).collect::<String>());
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let micro = parts.next().and_then(|micro| micro.parse().ok()).unwrap_or(0);
+        Some((major, minor, micro))
+    }
+
+    fn is_archive(path: &Path) -> bool {
+        let name = path.to_string_lossy().to_lowercase();
+        [".zip", ".tar", ".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".7z"].iter().any(|extension| name.ends_with(extension))
+    }
+
+    fn is_module(path: &Path) -> bool {
+        path.join("__manifest__.py").is_file() || path.join("__openerp__.py").is_file()
+    }
+
+    /* The odoo path is often the odoo package itself, or the parent folder of the sources */
+    fn suggest_odoo_path(path: &Path) -> Option<String> {
+        if path.join("release.py").is_file() {
+            return path.parent().map(|parent| parent.sanitize());
+        }
+        let entries = fs::read_dir(path).ok()?;
+        entries.flatten().map(|entry| entry.path())
+            .find(|child| child.join("odoo").join("release.py").is_file())
+            .map(|child| child.sanitize())
+    }
+}
@@ -0,0 +1,214 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::comparable::ComparableExpr;
+use ruff_python_ast::{Expr, ExprName, ExprStringLiteral, Keyword, Operator, Stmt, StringLiteral, StringLiteralValue};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::evaluation::{Evaluation, EvaluationValue};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* How a statement changes the content of a container */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationKind {
+    SET_ITEM, //vals['key'] = value
+    UPDATE,   //vals.update({...}), vals.update(key=value) or vals |= {...}
+    APPEND,   //values.append(value)
+    EXTEND,   //values.extend([...]) or values += [...], also for a tuple
+    ADD,      //values.add(value)
+}
+
+/* A statement that changes the content of the container held by a variable:
+    vals = {'name': name}       -> DICT name
+    vals['partner_id'] = pid    -> DICT name, partner_id
+    vals.update(extra)          -> DICT name, partner_id and any other key
+The mutation is declared as a variable at the end of the statement, so the lookups after the statement see the new
+content and the ones before still see the previous one. Only the literal keys and items are
+known: a non-literal one is kept in the value, and makes the container open (its other keys or items are unknown). */
+#[derive(Debug)]
+pub struct ContainerMutation<'a> {
+    pub name: &'a ExprName,
+    pub kind: MutationKind,
+    pub key: Option<&'a Expr>, //the key of SET_ITEM
+    pub value: Option<&'a Expr>, //the assigned value or the positional argument
+    pub keywords: &'a [Keyword], //the keyword arguments of UPDATE
+    pub operator: Option<Operator>, //the operator of an augmented assignment (values += [...])
+    pub range: TextRange, //range of the statement
+}
+
+pub struct ContainerMutations {}
+
+impl ContainerMutations {
+
+    /* Return the mutation done by the statement, if it changes a container held by a name */
+    pub fn find(stmt: &Stmt) -> Option<ContainerMutation> {
+        match stmt {
+            Stmt::Assign(assign) if assign.targets.len() == 1 => {
+                let Expr::Subscript(subscript) = &assign.targets[0] else {
+                    return None;
+                };
+                let Expr::Name(name) = &*subscript.value else {
+                    return None;
+                };
+                Some(ContainerMutation { name, kind: MutationKind::SET_ITEM, key: Some(&subscript.slice), value: Some(&assign.value), keywords: &[], operator: None, range: assign.range })
+            },
+            Stmt::AugAssign(aug_assign) => {
+                let Expr::Name(name) = &*aug_assign.target else {
+                    return None;
+                };
+                let kind = match aug_assign.op {
+                    Operator::Add => MutationKind::EXTEND,
+                    Operator::BitOr => MutationKind::UPDATE,
+                    _ => return None
+                };
+                Some(ContainerMutation { name, kind, key: None, value: Some(&aug_assign.value), keywords: &[], operator: Some(aug_assign.op), range: aug_assign.range })
+            },
+            Stmt::Expr(expr_stmt) => {
+                let Expr::Call(call) = &*expr_stmt.value else {
+                    return None;
+                };
+                let Expr::Attribute(func) = &*call.func else {
+                    return None;
+                };
+                let Expr::Name(name) = &*func.value else {
+                    return None;
+                };
+                let kind = match func.attr.as_str() {
+                    "update" => MutationKind::UPDATE,
+                    "append" => MutationKind::APPEND,
+                    "extend" => MutationKind::EXTEND,
+                    "add" => MutationKind::ADD,
+                    _ => return None
+                };
+                if call.arguments.args.len() > 1 || call.arguments.args.iter().any(|arg| arg.is_starred_expr())
+                    || (kind != MutationKind::UPDATE && (call.arguments.args.len() != 1 || !call.arguments.keywords.is_empty())) {
+                    return None;
+                }
+                Some(ContainerMutation { name, kind, key: None, value: call.arguments.args.first(), keywords: &call.arguments.keywords, operator: None, range: expr_stmt.range })
+            },
+            _ => None
+        }
+    }
+
+    /* Return the evaluations of the mutated variable: the previous evaluations of the name, with their containers
+    changed by the mutation. A previous declaration that doesn't hold a container of the right kind is kept as is. */
+    pub fn evaluate(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, mutation: &ContainerMutation) -> Vec<Evaluation> {
+        let mut evaluations = vec![];
+        let name = mutation.name.id.to_string();
+        for previous in Symbol::infer_name(session.sync_odoo, scope, &name, Some(mutation.range.end().to_u32())).iter() {
+            let previous_evals = previous.borrow().evaluations().cloned().unwrap_or_default();
+            let mut mutated = vec![];
+            for eval in previous_evals.iter() {
+                let value = eval.follow_ref_and_get_value(session, &mut None, &mut vec![]);
+                if let Some(value) = value.and_then(|value| ContainerMutations::apply(mutation, value)) {
+                    mutated.push(value);
+                }
+            }
+            if mutated.len() < previous_evals.len() || mutated.is_empty() {
+                evaluations.push(Evaluation::eval_from_symbol(&Rc::downgrade(previous), None));
+            }
+            for value in mutated.into_iter() {
+                evaluations.push(match value {
+                    EvaluationValue::DICT(items) => Evaluation::new_dict(session.sync_odoo, items, mutation.range),
+                    EvaluationValue::SET(items) => Evaluation::new_set(session.sync_odoo, items, mutation.range),
+                    EvaluationValue::LIST(items) => Evaluation::new_list(session.sync_odoo, items, mutation.range),
+                    EvaluationValue::TUPLE(items) => Evaluation::new_tuple(session.sync_odoo, items, mutation.range),
+                    _ => continue
+                });
+            }
+        }
+        evaluations
+    }
+
+    /* Return the value of the container after the mutation, or None if the mutation doesn't apply to this value */
+    pub fn apply(mutation: &ContainerMutation, value: EvaluationValue) -> Option<EvaluationValue> {
+        match (mutation.kind, value) {
+            (MutationKind::SET_ITEM, EvaluationValue::DICT(mut items)) => {
+                ContainerMutations::set_item(&mut items, mutation.key?, mutation.value?);
+                Some(EvaluationValue::DICT(items))
+            },
+            (MutationKind::UPDATE, EvaluationValue::DICT(mut items)) => {
+                match mutation.value {
+                    Some(Expr::Dict(dict)) => {
+                        for item in dict.items.iter() {
+                            match &item.key {
+                                Some(key) => ContainerMutations::set_item(&mut items, key, &item.value),
+                                None => items.push((item.value.clone(), item.value.clone())) //**other: open
+                            }
+                        }
+                    },
+                    Some(other) => items.push((other.clone(), other.clone())),
+                    None => {}
+                }
+                for keyword in mutation.keywords.iter() {
+                    match &keyword.arg {
+                        Some(arg) => {
+                            let key = Expr::StringLiteral(ExprStringLiteral {
+                                range: arg.range(),
+                                value: StringLiteralValue::single(StringLiteral {
+                                    range: arg.range(),
+                                    value: arg.as_str().into(),
+                                    flags: Default::default(),
+                                }),
+                            });
+                            ContainerMutations::set_item(&mut items, &key, &keyword.value);
+                        },
+                        None => items.push((keyword.value.clone(), keyword.value.clone())) //**kwargs: open
+                    }
+                }
+                Some(EvaluationValue::DICT(items))
+            },
+            (MutationKind::APPEND, EvaluationValue::LIST(mut items)) => {
+                items.push(mutation.value?.clone());
+                Some(EvaluationValue::LIST(items))
+            },
+            (MutationKind::EXTEND, EvaluationValue::LIST(mut items)) => {
+                match mutation.value? {
+                    Expr::List(list) => items.extend(list.elts.iter().cloned()),
+                    Expr::Tuple(tuple) => items.extend(tuple.elts.iter().cloned()),
+                    other => items.push(other.clone()),
+                }
+                Some(EvaluationValue::LIST(items))
+            },
+            //a tuple is only extended by +=, which creates a new tuple
+            (MutationKind::EXTEND, EvaluationValue::TUPLE(mut items)) => {
+                match mutation.value? {
+                    Expr::Tuple(tuple) => items.extend(tuple.elts.iter().cloned()),
+                    _ => return None
+                }
+                Some(EvaluationValue::TUPLE(items))
+            },
+            (MutationKind::ADD, EvaluationValue::SET(mut items)) => {
+                let value = mutation.value?;
+                if !items.iter().any(|item| ComparableExpr::from(item) == ComparableExpr::from(value)) {
+                    items.push(value.clone());
+                }
+                Some(EvaluationValue::SET(items))
+            },
+            _ => None
+        }
+    }
+
+    /* Set the value of a literal key, or add a non-literal one */
+    fn set_item(items: &mut Vec<(Expr, Expr)>, key: &Expr, value: &Expr) {
+        if key.is_literal_expr() {
+            items.retain(|(k, _)| ComparableExpr::from(k) != ComparableExpr::from(key));
+        }
+        items.push((key.clone(), value.clone()));
+    }
+
+    /* Return true if the container can hold other keys or items than the known ones */
+    pub fn is_open(value: &EvaluationValue) -> bool {
+        match value {
+            EvaluationValue::DICT(items) => items.iter().any(|(key, _)| !key.is_literal_expr()),
+            EvaluationValue::LIST(items) | EvaluationValue::SET(items) | EvaluationValue::TUPLE(items) => items.iter().any(|item| !item.is_literal_expr()),
+            _ => false
+        }
+    }
+
+    /* Return the position where the mutation is declared */
+    pub fn get_position(mutation: &ContainerMutation) -> TextRange {
+        TextRange::empty(mutation.range.end())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Arguments, Expr, ExprCall, ExprStringLiteral, Stmt};
+use ruff_text_size::{TextRange, TextSize};
+
+use crate::core::api_decorators::{CONTEXT_KEY_PREFIXES, KNOWN_CONTEXT_KEYS};
+use crate::features::ast_utils::AstUtils;
+
+/* Helpers for the keys of the context, that are only known by their string:
+    self.with_context(default_partner_id=partner.id) or self.with_context({'active_test': False}): the keys are set
+    ctx = dict(self.env.context, skip_check=True), ctx['lang'] = 'fr_FR', context.update(...): the keys are set
+    {'res_model': 'sale.order', 'context': {'default_partner_id': ...}}: the keys of an action are set
+    self.env.context.get('skip_check') or self._context['lang']: the keys are read */
+pub struct ContextKeys {}
+
+impl ContextKeys {
+
+    /* Return true if the expression is a context: self.env.context, self._context, context, ctx, new_context... */
+    pub fn is_context(expr: &Expr) -> bool {
+        let flattened = AstUtils::flatten_expr(expr);
+        let name = flattened.rsplit('.').next().unwrap_or(&flattened);
+        name == "context" || name == "_context" || name == "ctx" || name.ends_with("_context") || name.ends_with("_ctx")
+    }
+
+    /* Return true if the key is read by the framework, or generated from a field name, like 'default_partner_id' */
+    pub fn is_builtin_key(key: &str) -> bool {
+        KNOWN_CONTEXT_KEYS.contains(&key)
+            || CONTEXT_KEY_PREFIXES.iter().any(|prefix| key.starts_with(prefix) && key.len() > prefix.len())
+    }
+
+    /* Return the field name of a 'default_<field>' key */
+    pub fn get_default_field(key: &str) -> Option<&str> {
+        key.strip_prefix("default_").filter(|field| !field.is_empty())
+    }
+
+    /* Return the keys set by the statements, with the range of the keyword or the literal setting them */
+    pub fn find_set_keys(stmts: &[Stmt]) -> Vec<(String, TextRange)> {
+        let mut visitor = SetKeysVisitor { keys: vec![] };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.keys
+    }
+
+    /* Return the keys given to a with_context call, with the range of the keyword or the literal setting them */
+    pub fn get_with_context_keys(call: &ExprCall) -> Vec<(String, TextRange)> {
+        match &*call.func {
+            Expr::Attribute(func) if func.attr.as_str() == "with_context" => ContextKeys::get_arguments_keys(&call.arguments),
+            _ => vec![]
+        }
+    }
+
+    /* Return the literal of the key read by context.get('key') */
+    pub fn get_read_key(call: &ExprCall) -> Option<&ExprStringLiteral> {
+        match &*call.func {
+            Expr::Attribute(func) if func.attr.as_str() == "get" && ContextKeys::is_context(&func.value) => {
+                call.arguments.args.first()?.as_string_literal_expr()
+            },
+            _ => None
+        }
+    }
+
+    /* Return the literal of the key read by context.get('key') or context['key'] whose range contains the offset */
+    pub fn get_read_key_at(stmts: &[Stmt], offset: TextSize) -> Option<&ExprStringLiteral> {
+        let mut visitor = ReadKeysVisitor { keys: vec![] };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.keys.into_iter().find(|key| key.range.start() < offset && offset < key.range.end())
+    }
+
+    /* Keys given as keywords, or in a dict given as argument: with_context(key=...), update({'key': ...}) */
+    fn get_arguments_keys(arguments: &Arguments) -> Vec<(String, TextRange)> {
+        let mut keys: Vec<(String, TextRange)> = arguments.keywords.iter()
+            .filter_map(|keyword| keyword.arg.as_ref()).map(|arg| (arg.to_string(), arg.range)).collect();
+        for arg in arguments.args.iter() {
+            keys.extend(ContextKeys::get_dict_keys(arg));
+        }
+        keys
+    }
+
+    /* Keys of a dict literal, or of dict(...) */
+    fn get_dict_keys(expr: &Expr) -> Vec<(String, TextRange)> {
+        match expr {
+            Expr::Dict(dict) => dict.items.iter().filter_map(|item| item.key.as_ref())
+                .filter_map(|key| key.as_string_literal_expr()).map(|key| (key.value.to_string(), key.range)).collect(),
+            Expr::Call(call) if AstUtils::flatten_expr(&call.func) == "dict" => ContextKeys::get_arguments_keys(&call.arguments),
+            _ => vec![]
+        }
+    }
+}
+
+struct SetKeysVisitor {
+    keys: Vec<(String, TextRange)>,
+}
+
+impl SetKeysVisitor {
+
+    /* dict(self.env.context, key=...): a copy of a context with other keys */
+    fn is_context_copy(expr: &Expr) -> bool {
+        match expr {
+            Expr::Call(call) => AstUtils::flatten_expr(&call.func) == "dict" && call.arguments.args.first().is_some_and(ContextKeys::is_context),
+            _ => false
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for SetKeysVisitor {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            for target in assign.targets.iter() {
+                match target {
+                    Expr::Subscript(subscript) if ContextKeys::is_context(&subscript.value) => {
+                        if let Some(key) = subscript.slice.as_string_literal_expr() {
+                            self.keys.push((key.value.to_string(), key.range));
+                        }
+                    },
+                    //the copies of a context, like dict(self.env.context, key=...), are read by visit_expr
+                    Expr::Name(_) | Expr::Attribute(_) if ContextKeys::is_context(target) && !SetKeysVisitor::is_context_copy(&assign.value) => {
+                        self.keys.extend(ContextKeys::get_dict_keys(&assign.value));
+                    },
+                    _ => {}
+                }
+            }
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Call(call) => {
+                match &*call.func {
+                    Expr::Attribute(func) if func.attr.as_str() == "with_context" => {
+                        self.keys.extend(ContextKeys::get_arguments_keys(&call.arguments));
+                    },
+                    Expr::Attribute(func) if func.attr.as_str() == "update" && ContextKeys::is_context(&func.value) => {
+                        self.keys.extend(ContextKeys::get_arguments_keys(&call.arguments));
+                    },
+                    _ if SetKeysVisitor::is_context_copy(expr) => {
+                        self.keys.extend(ContextKeys::get_arguments_keys(&call.arguments));
+                    },
+                    _ => {}
+                }
+                for keyword in call.arguments.keywords.iter() {
+                    if keyword.arg.as_ref().is_some_and(|arg| arg.as_str() == "context") {
+                        self.keys.extend(ContextKeys::get_dict_keys(&keyword.value));
+                    }
+                }
+            },
+            //the context of an action: {'type': 'ir.actions.act_window', 'context': {...}}
+            Expr::Dict(dict) => {
+                for item in dict.items.iter() {
+                    if item.key.as_ref().and_then(|key| key.as_string_literal_expr()).is_some_and(|key| key.value.to_str() == "context") {
+                        self.keys.extend(ContextKeys::get_dict_keys(&item.value));
+                    }
+                }
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct ReadKeysVisitor<'a> {
+    keys: Vec<&'a ExprStringLiteral>,
+}
+
+impl<'a> Visitor<'a> for ReadKeysVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Call(call) => {
+                if let Some(key) = ContextKeys::get_read_key(call) {
+                    self.keys.push(key);
+                }
+            },
+            Expr::Subscript(subscript) if ContextKeys::is_context(&subscript.value) => {
+                if let Some(key) = subscript.slice.as_string_literal_expr() {
+                    self.keys.push(key);
+                }
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/* Index of the context keys set by the python files, built during their ARCH like the StringIndex: a key gives the
+files and the ranges where it is set, to check the keys read from the context and to go to the places setting them */
+#[derive(Debug, Default)]
+pub struct ContextKeyIndex {
+    keys: HashMap<String, HashMap<String, Vec<TextRange>>>,
+    file_keys: HashMap<String, Vec<String>>, //the keys set by each file, to remove them when the file is rebuilt
+}
+
+impl ContextKeyIndex {
+
+    pub fn new() -> Self {
+        ContextKeyIndex::default()
+    }
+
+    /* Replace the keys set by the file by the ones of its new ast */
+    pub fn update_file(&mut self, path: &str, ast: &[Stmt]) {
+        self.remove_file(path);
+        let mut file_keys = vec![];
+        for (key, range) in ContextKeys::find_set_keys(ast).into_iter() {
+            if !file_keys.contains(&key) {
+                file_keys.push(key.clone());
+            }
+            self.keys.entry(key).or_default().entry(path.to_string()).or_default().push(range);
+        }
+        if !file_keys.is_empty() {
+            self.file_keys.insert(path.to_string(), file_keys);
+        }
+    }
+
+    pub fn remove_file(&mut self, path: &str) {
+        for key in self.file_keys.remove(path).unwrap_or_default().iter() {
+            let Some(files) = self.keys.get_mut(key) else {
+                continue;
+            };
+            files.remove(path);
+            if files.is_empty() {
+                self.keys.remove(key);
+            }
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    /* Return the places where the key is set, ordered by path and position */
+    pub fn get_locations(&self, key: &str) -> Vec<(String, TextRange)> {
+        let mut res: Vec<(String, TextRange)> = self.keys.get(key).map(|files| files.iter()
+            .flat_map(|(path, ranges)| ranges.iter().map(|range| (path.clone(), *range))).collect()).unwrap_or_default();
+        res.sort_by(|(path_1, range_1), (path_2, range_2)| path_1.cmp(path_2).then(range_1.start().cmp(&range_2.start())));
+        res
+    }
+
+    /* Return the keys set by the files */
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.keys.keys()
+    }
+}
@@ -0,0 +1,228 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use ruff_python_ast::{Expr, Stmt, StmtClassDef};
+use ruff_text_size::TextRange;
+use weak_table::PtrWeakHashSet;
+
+use crate::constants::SymType;
+use crate::core::evaluation::Evaluation;
+use crate::core::static_conditions::StaticConditions;
+use crate::core::symbols::function_symbol::{Argument, ArgumentType};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* A field of a dataclass or of a NamedTuple: an annotated assignment of its body, that is a parameter of the generated
+__init__ unless it is declared with field(init=False) */
+#[derive(Debug, Clone)]
+pub struct DataclassField {
+    pub name: String,
+    pub range: TextRange,
+    pub has_default: bool, //a value, or field(default=...) or field(default_factory=...)
+    pub kw_only: bool,
+    pub init: bool,
+}
+
+/* The fields declared by the body of a dataclass or a NamedTuple, in the order of the source */
+#[derive(Debug, Clone)]
+pub struct DataclassInfo {
+    pub fields: Vec<DataclassField>,
+    pub is_named_tuple: bool,
+}
+
+/* Classes whose __init__ is generated from their annotated class attributes:
+    @dataclass(frozen=True)
+    class Line:
+        product: str
+        qty: int = 1
+        taxes: list = field(default_factory=list)
+        _: KW_ONLY
+        note: str = ''
+
+    class Point(NamedTuple):
+        x: int
+        y: int = 0
+The generated __init__ of Line is __init__(self, product, qty=..., taxes=..., *, note=...). The fields inherited from a
+dataclass base come first, a field declared again keeping the position of the first declaration. The decorator and the
+NamedTuple base are found through the imports of the module, or by their bare name. */
+pub struct Dataclasses {}
+
+impl Dataclasses {
+
+    /* Return the fields of the class if it is a dataclass or a NamedTuple, with false if its __init__ is not generated,
+    for @dataclass(init=False) */
+    pub fn find(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, class_def: &StmtClassDef, globals_complete: bool) -> Option<(DataclassInfo, bool)> {
+        let is_named_tuple = class_def.bases().iter().any(|base| {
+            Dataclasses::is_named(session, scope, base, "NamedTuple", &["typing.NamedTuple", "typing_extensions.NamedTuple"], globals_complete)
+        });
+        let mut kw_only = false;
+        let mut init = true;
+        if !is_named_tuple {
+            let decorator = class_def.decorator_list.iter().find(|decorator| {
+                let expr = match &decorator.expression {
+                    Expr::Call(call) => &*call.func,
+                    expr => expr
+                };
+                Dataclasses::is_named(session, scope, expr, "dataclass", &["dataclasses.dataclass"], globals_complete)
+            })?;
+            if let Expr::Call(call) = &decorator.expression {
+                kw_only = Dataclasses::get_bool_keyword(&call.arguments.keywords, "kw_only").unwrap_or(false);
+                init = Dataclasses::get_bool_keyword(&call.arguments.keywords, "init").unwrap_or(true);
+            }
+        }
+        let mut fields: Vec<DataclassField> = vec![];
+        for stmt in class_def.body.iter() {
+            let Stmt::AnnAssign(ann_assign) = stmt else {
+                continue;
+            };
+            let Expr::Name(target) = &*ann_assign.target else {
+                continue;
+            };
+            if Dataclasses::is_annotation_named(&ann_assign.annotation, "ClassVar") {
+                continue;
+            }
+            //the fields after '_: KW_ONLY' are keyword-only
+            if Dataclasses::is_annotation_named(&ann_assign.annotation, "KW_ONLY") {
+                kw_only = true;
+                continue;
+            }
+            let mut field = DataclassField {
+                name: target.id.to_string(),
+                range: target.range,
+                has_default: ann_assign.value.is_some(),
+                kw_only,
+                init: true,
+            };
+            if let Some(Expr::Call(call)) = ann_assign.value.as_deref() {
+                let is_field = match &*call.func {
+                    Expr::Name(name) => name.id.as_str() == "field",
+                    Expr::Attribute(attribute) => attribute.attr.as_str() == "field",
+                    _ => false
+                };
+                if is_field && !is_named_tuple {
+                    let keywords = &call.arguments.keywords;
+                    field.has_default = keywords.iter().any(|k| k.arg.as_ref().is_some_and(|arg| ["default", "default_factory"].contains(&arg.as_str())));
+                    field.kw_only = Dataclasses::get_bool_keyword(keywords, "kw_only").unwrap_or(kw_only);
+                    field.init = Dataclasses::get_bool_keyword(keywords, "init").unwrap_or(true);
+                }
+            }
+            Dataclasses::add_field(&mut fields, field);
+        }
+        Some((DataclassInfo { fields, is_named_tuple }, init))
+    }
+
+    /* Return the fields of the class, with the class declaring them: the ones inherited from its bases first, in the
+    reversed order of the bases like the MRO gives them, then its own fields */
+    pub fn get_fields(class: &Rc<RefCell<Symbol>>) -> Vec<(DataclassField, Rc<RefCell<Symbol>>)> {
+        let mut res = vec![];
+        Dataclasses::collect_fields(class, &mut res, &mut PtrWeakHashSet::new());
+        res
+    }
+
+    fn collect_fields(class: &Rc<RefCell<Symbol>>, res: &mut Vec<(DataclassField, Rc<RefCell<Symbol>>)>, visited: &mut PtrWeakHashSet<Weak<RefCell<Symbol>>>) {
+        if visited.contains(class) {
+            return;
+        }
+        visited.insert(class.clone());
+        let bases: Vec<Rc<RefCell<Symbol>>> = class.borrow().as_class_sym().bases.iter().filter_map(|base| base.upgrade()).collect();
+        for base in bases.iter().rev() {
+            if base.borrow().typ() == SymType::CLASS {
+                Dataclasses::collect_fields(base, res, visited);
+            }
+        }
+        let Some(info) = class.borrow().as_class_sym().dataclass.clone() else {
+            return;
+        };
+        for field in info.fields.into_iter() {
+            match res.iter_mut().find(|(existing, _)| existing.name == field.name) {
+                Some(existing) => *existing = (field, class.clone()),
+                None => res.push((field, class.clone()))
+            }
+        }
+    }
+
+    /* Set the arguments of the __init__ generated for the class: self, then its fields, the keyword-only ones last. A
+    parameter is evaluated to the class attribute of its field, the default values are placeholders */
+    pub fn build_init(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, init: &Rc<RefCell<Symbol>>) {
+        let range = *init.borrow().range();
+        let fields = Dataclasses::get_fields(class);
+        let (positional, keywords): (Vec<_>, Vec<_>) = fields.into_iter().filter(|(field, _)| field.init).partition(|(field, _)| !field.kw_only);
+        let self_param = Dataclasses::get_or_add_parameter(session, init, &S!("self"), &range);
+        self_param.borrow_mut().set_evaluations(vec![Evaluation::eval_from_symbol(&Rc::downgrade(class), Some(true))]);
+        let mut args = vec![Argument {
+            symbol: Rc::downgrade(&self_param),
+            default_value: None,
+            arg_type: ArgumentType::ARG,
+            annotation: None
+        }];
+        for (arg_type, fields) in [(ArgumentType::ARG, positional), (ArgumentType::KWORD_ONLY, keywords)] {
+            for (field, owner) in fields.into_iter() {
+                let param = Dataclasses::get_or_add_parameter(session, init, &field.name, &range);
+                let attribute = owner.borrow().get_positioned_symbol(&field.name, &field.range);
+                let evaluations = attribute.map(|attribute| vec![Evaluation::eval_from_symbol(&Rc::downgrade(&attribute), None)]).unwrap_or_default();
+                param.borrow_mut().set_evaluations(evaluations);
+                args.push(Argument {
+                    symbol: Rc::downgrade(&param),
+                    default_value: field.has_default.then(Evaluation::new_none),
+                    arg_type,
+                    annotation: None
+                });
+            }
+        }
+        init.borrow_mut().as_func_mut().args = args;
+    }
+
+    /* Return the evaluations of the elements of an instance of the class, if it is a NamedTuple: its fields */
+    pub fn get_named_tuple_elements(class: &Rc<RefCell<Symbol>>) -> Option<Vec<Vec<Evaluation>>> {
+        let class_bw = class.borrow();
+        let info = class_bw.as_class_sym().dataclass.as_ref().filter(|info| info.is_named_tuple)?;
+        let mut res = vec![];
+        for field in info.fields.iter() {
+            let attribute = class_bw.get_positioned_symbol(&field.name, &field.range)?;
+            res.push(vec![Evaluation::eval_from_symbol(&Rc::downgrade(&attribute), None)]);
+        }
+        Some(res)
+    }
+
+    fn get_or_add_parameter(session: &mut SessionInfo, init: &Rc<RefCell<Symbol>>, name: &String, range: &TextRange) -> Rc<RefCell<Symbol>> {
+        if let Some(param) = init.borrow().get_positioned_symbol(name, range) {
+            return param;
+        }
+        let param = init.borrow_mut().add_new_variable(session, name, range);
+        param.borrow_mut().as_variable_mut().is_parameter = true;
+        param
+    }
+
+    /* Add a field, or replace the one declared before with the same name, that keeps its position */
+    fn add_field(fields: &mut Vec<DataclassField>, field: DataclassField) {
+        match fields.iter_mut().find(|existing| existing.name == field.name) {
+            Some(existing) => *existing = field,
+            None => fields.push(field)
+        }
+    }
+
+    fn is_named(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr, name: &str, qualified_names: &[&str], globals_complete: bool) -> bool {
+        //the imports of the scope of a rebuilt function are not known here
+        expr.as_name_expr().is_some_and(|n| n.id.as_str() == name) ||
+            StaticConditions::get_qualified_name(session, scope, expr, globals_complete).is_some_and(|qualified| qualified_names.contains(&qualified.as_str()))
+    }
+
+    /* Return true if the annotation is the given name, possibly subscripted: ClassVar, typing.ClassVar[int] */
+    fn is_annotation_named(annotation: &Expr, name: &str) -> bool {
+        let annotation = match annotation {
+            Expr::Subscript(subscript) => &*subscript.value,
+            annotation => annotation
+        };
+        match annotation {
+            Expr::Name(n) => n.id.as_str() == name,
+            Expr::Attribute(attribute) => attribute.attr.as_str() == name,
+            _ => false
+        }
+    }
+
+    fn get_bool_keyword(keywords: &[ruff_python_ast::Keyword], name: &str) -> Option<bool> {
+        let keyword = keywords.iter().find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == name))?;
+        keyword.value.as_boolean_literal_expr().map(|b| b.value)
+    }
+}
@@ -0,0 +1,77 @@
+use ruff_python_ast::Operator;
+
+pub const DATE: &str = "datetime.date";
+pub const DATETIME: &str = "datetime.datetime";
+pub const TIMEDELTA: &str = "datetime.timedelta";
+pub const RELATIVEDELTA: &str = "dateutil.relativedelta.relativedelta";
+
+/* Return types of the helpers of fields.Date and fields.Datetime, used when they can't be inferred from their source
+(the older versions of Odoo don't annotate them) */
+pub const DATE_FIELD_HELPERS: [(&str, &str, &str); 10] = [
+    ("Date", "today", DATE),
+    ("Date", "context_today", DATE),
+    ("Date", "to_date", DATE),
+    ("Date", "to_string", "builtins.str"),
+    ("Datetime", "now", DATETIME),
+    ("Datetime", "today", DATETIME),
+    ("Datetime", "to_datetime", DATETIME),
+    ("Datetime", "context_timestamp", DATETIME),
+    ("Datetime", "to_string", "builtins.str"),
+    ("Datetime", "from_string", DATETIME),
+];
+
+/* Result types of the arithmetic between dates and durations:
+    date + timedelta / relativedelta -> date (and the reverse)
+    date - date -> timedelta
+    timedelta + timedelta -> timedelta
+As datetime is a subclass of date, the most specific class of the operands is given by their path. */
+pub struct DateArithmetic {}
+
+impl DateArithmetic {
+
+    /* Return the path of the class of the result of 'left op right', where left and right are the paths of the classes of
+    the operands, if it is an arithmetic between dates and durations */
+    pub fn get_result_type(left: &str, op: Operator, right: &str) -> Option<&'static str> {
+        let is_date = |path: &str| path == DATE || path == DATETIME;
+        let is_delta = |path: &str| path == TIMEDELTA || path == RELATIVEDELTA;
+        let as_static = |path: &str| if path == DATE { DATE } else { DATETIME };
+        match op {
+            Operator::Add => {
+                if is_date(left) && is_delta(right) {
+                    Some(as_static(left))
+                } else if is_delta(left) && is_date(right) {
+                    Some(as_static(right))
+                } else if is_delta(left) && is_delta(right) {
+                    Some(if left == RELATIVEDELTA || right == RELATIVEDELTA { RELATIVEDELTA } else { TIMEDELTA })
+                } else {
+                    None
+                }
+            },
+            Operator::Sub => {
+                if is_date(left) && is_delta(right) {
+                    Some(as_static(left))
+                } else if is_date(left) && left == right {
+                    Some(TIMEDELTA)
+                } else if is_delta(left) && is_delta(right) {
+                    Some(if left == RELATIVEDELTA || right == RELATIVEDELTA { RELATIVEDELTA } else { TIMEDELTA })
+                } else {
+                    None
+                }
+            },
+            Operator::Mult => {
+                //a duration can be multiplied by a number
+                match (left, right) {
+                    (TIMEDELTA, "builtins.int" | "builtins.float") | ("builtins.int" | "builtins.float", TIMEDELTA) => Some(TIMEDELTA),
+                    (RELATIVEDELTA, "builtins.int" | "builtins.float") | ("builtins.int" | "builtins.float", RELATIVEDELTA) => Some(RELATIVEDELTA),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    /* Return true if the class is a date or a datetime */
+    pub fn is_date(path: &str) -> bool {
+        path == DATE || path == DATETIME
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Position, Range};
+use ruff_text_size::TextRange;
+use serde::Deserialize;
+
+use crate::constants::EXTENSION_NAME;
+use crate::S;
+
+static DEFAULT_DEPRECATED_API: &str = include_str!("deprecated_api.json");
+
+/* An entry of the deprecated API table. Exactly one way of matching should be set:
+ - model + method: a member (method or attribute) of a model. model can be "*" to match any model
+ - function: the full path of a function, like "odoo.fields.Date.from_string"
+ - decorator: the decorator name as written in the code, like "api.multi"
+The entry applies to versions in [since, until[. If until is not set, it applies to all versions after since.
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeprecatedApiEntry {
+    pub model: Option<String>,
+    pub method: Option<String>,
+    pub function: Option<String>,
+    pub decorator: Option<String>,
+    pub since: String,
+    pub until: Option<String>,
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+impl DeprecatedApiEntry {
+
+    fn is_valid(&self) -> bool {
+        let by_member = self.model.is_some() && self.method.is_some();
+        let by_function = self.function.is_some();
+        let by_decorator = self.decorator.is_some();
+        [by_member, by_function, by_decorator].iter().filter(|x| **x).count() == 1
+    }
+
+    fn applies_to(&self, version: (u32, u32)) -> bool {
+        if version < parse_version(&self.since) {
+            return false;
+        }
+        if let Some(until) = self.until.as_ref() {
+            return version < parse_version(until);
+        }
+        true
+    }
+
+    pub fn name(&self) -> String {
+        if let Some(method) = self.method.as_ref() {
+            return method.clone();
+        }
+        if let Some(function) = self.function.as_ref() {
+            return function.clone();
+        }
+        format!("@{}", self.decorator.as_ref().unwrap())
+    }
+
+    pub fn create_diagnostic(&self, range: &TextRange) -> Diagnostic {
+        let mut message = format!("Deprecation Warning: Since {}: {} is deprecated", self.since, self.name());
+        if let Some(replacement) = self.replacement.as_ref() {
+            message += &format!(", use {} instead", replacement);
+        }
+        if let Some(details) = self.message.as_ref() {
+            message += &format!(". {}", details);
+        }
+        Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::WARNING),
+            Some(NumberOrString::String(S!("OLS20007"))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            Some(vec![DiagnosticTag::DEPRECATED]),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeprecatedApiTable {
+    entries: Vec<DeprecatedApiEntry>,
+}
+
+impl DeprecatedApiTable {
+
+    pub fn new() -> Self {
+        Self {
+            entries: vec![]
+        }
+    }
+
+    /* Load the table embedded in the server, and extend it with the entries of the file at user_path, if provided.
+    Return the table and the errors encountered while reading the user file. */
+    pub fn load(user_path: &str) -> (Self, Vec<String>) {
+        let mut errors = vec![];
+        let mut entries: Vec<DeprecatedApiEntry> = serde_json::from_str(DEFAULT_DEPRECATED_API).expect("Embedded deprecated API table is invalid");
+        if !user_path.is_empty() {
+            match fs::read_to_string(user_path) {
+                Ok(content) => {
+                    match serde_json::from_str::<Vec<DeprecatedApiEntry>>(&content) {
+                        Ok(user_entries) => {
+                            for entry in user_entries.into_iter() {
+                                if entry.is_valid() {
+                                    entries.push(entry);
+                                } else {
+                                    errors.push(format!("Invalid deprecated API entry in {}: {:?}", user_path, entry));
+                                }
+                            }
+                        },
+                        Err(e) => errors.push(format!("Unable to parse deprecated API file {}: {}", user_path, e))
+                    }
+                },
+                Err(e) => errors.push(format!("Unable to read deprecated API file {}: {}", user_path, e))
+            }
+        }
+        (Self { entries }, errors)
+    }
+
+    /* Search for a deprecated member. model is None if the member is defined on a class common to all models (BaseModel) */
+    pub fn find_member(&self, version: (u32, u32), model: Option<&str>, member: &str) -> Option<&DeprecatedApiEntry> {
+        self.entries.iter().find(|e| {
+            e.method.as_deref() == Some(member) &&
+            e.model.as_ref().is_some_and(|m| m == "*" || Some(m.as_str()) == model) &&
+            e.applies_to(version)
+        })
+    }
+
+    pub fn find_function(&self, version: (u32, u32), path: &str) -> Option<&DeprecatedApiEntry> {
+        self.entries.iter().find(|e| e.function.as_deref() == Some(path) && e.applies_to(version))
+    }
+
+    /* name is the flattened decorator expression. "odoo.api.multi" will match an entry for "api.multi" */
+    pub fn find_decorator(&self, version: (u32, u32), name: &str) -> Option<&DeprecatedApiEntry> {
+        self.entries.iter().find(|e| {
+            e.decorator.as_ref().is_some_and(|d| name == d || name.ends_with(&format!(".{}", d))) &&
+            e.applies_to(version)
+        })
+    }
+}
+
+fn parse_version(version: &str) -> (u32, u32) {
+    let mut parts = version.split('.').map(|p| p.trim().parse::<u32>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
@@ -0,0 +1,174 @@
+use ruff_python_ast::visitor::{walk_expr, Visitor};
+use ruff_python_ast::{Expr, ExprCall, FStringElement, FStringPart, Operator, Stmt};
+
+/* Minimum length of the literal parts of a pattern. Shorter patterns, like "%s" or "_%s", match too many methods */
+const MIN_LITERAL_LENGTH: usize = 3;
+
+/* Name of a method built at runtime from literal parts around a value, like "_get_%s_values" % provider */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodPattern {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl MethodPattern {
+
+    /* Return the pattern of a name built with a %-format, an f-string or a concatenation, if its literal parts are long
+    enough to select some methods */
+    pub fn from_expr(expr: &Expr) -> Option<MethodPattern> {
+        let pattern = match expr {
+            Expr::BinOp(bin_op) if bin_op.op == Operator::Mod => match &*bin_op.left {
+                Expr::StringLiteral(format) => MethodPattern::from_percent_format(format.value.to_str()),
+                _ => None
+            },
+            Expr::BinOp(bin_op) if bin_op.op == Operator::Add => MethodPattern::from_concatenation(expr),
+            Expr::FString(f_string) => {
+                let mut parts: Vec<Option<String>> = vec![]; //None for an interpolation
+                for part in f_string.value.iter() {
+                    match part {
+                        FStringPart::Literal(literal) => parts.push(Some(literal.value.to_string())),
+                        FStringPart::FString(f) => for element in f.elements.iter() {
+                            match element {
+                                FStringElement::Literal(literal) => parts.push(Some(literal.value.to_string())),
+                                FStringElement::Expression(_) => parts.push(None),
+                            }
+                        }
+                    }
+                }
+                MethodPattern::from_parts(&parts)
+            },
+            _ => None
+        }?;
+        if pattern.prefix.len() + pattern.suffix.len() < MIN_LITERAL_LENGTH {
+            return None;
+        }
+        Some(pattern)
+    }
+
+    /* The name matches if it starts with the prefix and ends with the suffix, around at least one character */
+    pub fn matches(&self, name: &str) -> bool {
+        name.len() > self.prefix.len() + self.suffix.len() && name.starts_with(&self.prefix) && name.ends_with(&self.suffix)
+    }
+
+    /* Return the pattern as shown in the diagnostics: "_get_*_values" */
+    pub fn label(&self) -> String {
+        format!("{}*{}", self.prefix, self.suffix)
+    }
+
+    /* Return the literal text before the first conversion and after the last one of a %-format. %% is a literal % */
+    fn from_percent_format(format: &str) -> Option<MethodPattern> {
+        let mut parts: Vec<Option<String>> = vec![];
+        let mut literal = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                literal.push('%');
+                continue;
+            }
+            //%[(key)][flags][width][.precision][length]conversion
+            if chars.peek() == Some(&'(') {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            while chars.peek().is_some_and(|c| "#0- +.*hlL".contains(*c) || c.is_ascii_digit()) {
+                chars.next();
+            }
+            chars.next()?;
+            parts.push(Some(std::mem::take(&mut literal)));
+            parts.push(None);
+        }
+        parts.push(Some(literal));
+        MethodPattern::from_parts(&parts)
+    }
+
+    /* Return the pattern of a concatenation of literals and values: "_action_" + kind */
+    fn from_concatenation(expr: &Expr) -> Option<MethodPattern> {
+        let mut operands = vec![];
+        MethodPattern::flatten_concatenation(expr, &mut operands);
+        let parts: Vec<Option<String>> = operands.iter().map(|operand| match operand {
+            Expr::StringLiteral(literal) => Some(literal.value.to_string()),
+            _ => None
+        }).collect();
+        MethodPattern::from_parts(&parts)
+    }
+
+    fn flatten_concatenation<'a>(expr: &'a Expr, operands: &mut Vec<&'a Expr>) {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.op == Operator::Add => {
+                MethodPattern::flatten_concatenation(&bin_op.left, operands);
+                MethodPattern::flatten_concatenation(&bin_op.right, operands);
+            },
+            _ => operands.push(expr)
+        }
+    }
+
+    /* Return the pattern of a sequence of literals and values (None). There is no pattern without any value, as the name
+    is then a constant */
+    fn from_parts(parts: &[Option<String>]) -> Option<MethodPattern> {
+        let first_value = parts.iter().position(|part| part.is_none())?;
+        let last_value = parts.iter().rposition(|part| part.is_none())?;
+        Some(MethodPattern {
+            prefix: parts[..first_value].iter().flatten().cloned().collect(),
+            suffix: parts[last_value + 1..].iter().flatten().cloned().collect(),
+        })
+    }
+}
+
+/* Odoo dispatches to the methods of a model by building their name at runtime: getattr(self, "_get_%s_values" % provider).
+The provider can't be known statically, but the literal parts of the name select the methods that can be called: a
+method matching the pattern is referenced by the getattr, and a pattern that matches no method of the model is likely
+a mistake */
+pub struct DynamicDispatch {}
+
+impl DynamicDispatch {
+
+    /* Return the pattern of a getattr on self or cls whose name is built at runtime */
+    pub fn get_getattr_pattern(call: &ExprCall) -> Option<MethodPattern> {
+        let Expr::Name(func) = &*call.func else {
+            return None;
+        };
+        if func.id.as_str() != "getattr" || call.arguments.args.len() < 2 {
+            return None;
+        }
+        match &call.arguments.args[0] {
+            Expr::Name(receiver) if receiver.id.as_str() == "self" || receiver.id.as_str() == "cls" => {},
+            _ => return None
+        }
+        MethodPattern::from_expr(&call.arguments.args[1])
+    }
+
+    /* Return the patterns of the getattr calls of the statements */
+    pub fn collect_patterns(body: &[Stmt]) -> Vec<MethodPattern> {
+        let mut visitor = GetattrVisitor { patterns: vec![] };
+        for stmt in body.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.patterns
+    }
+}
+
+struct GetattrVisitor {
+    patterns: Vec<MethodPattern>,
+}
+
+impl<'a> Visitor<'a> for GetattrVisitor {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr {
+            if let Some(pattern) = DynamicDispatch::get_getattr_pattern(call) {
+                if !self.patterns.contains(&pattern) {
+                    self.patterns.push(pattern);
+                }
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
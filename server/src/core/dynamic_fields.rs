@@ -0,0 +1,195 @@
+use ruff_python_ast::{Expr, ExprCall, Stmt, StmtClassDef};
+use ruff_text_size::TextRange;
+
+/* Fields that a model class adds dynamically, instead of declaring them in its body. The supported idioms are:
+    class A(models.Model):
+        for name in NAMES:
+            locals()[name] = fields.Char()
+
+        def _setup_fields(self):
+            for name, field in FIELDS.items():
+                self._add_field(name, field)
+
+    for name in NAMES:
+        setattr(A, name, fields.Char())
+Names are extracted when the names iterated by the loop are literals (list, tuple, set or dict keys), directly or through
+a constant of the module or of the class. Otherwise, the model is only known to have dynamic fields. */
+#[derive(Debug, Default)]
+pub struct DynamicFields {
+    pub names: Vec<(String, TextRange)>,
+    pub has_unknown_names: bool,
+}
+
+/* Loops enclosing the current statement: the target of the loop, and the iterated expression */
+type Loops<'a> = Vec<(&'a Expr, &'a Expr)>;
+
+impl DynamicFields {
+
+    /* Find the dynamic fields of class_def, in its body and in the statements of its module */
+    pub fn find(module_stmts: &Vec<Stmt>, class_def: &StmtClassDef) -> DynamicFields {
+        let mut res = DynamicFields::default();
+        let class_name = class_def.name.as_str();
+        let mut constants: Vec<(&str, &Expr)> = vec![];
+        DynamicFields::collect_constants(module_stmts, &mut constants);
+        DynamicFields::collect_constants(&class_def.body, &mut constants);
+        res.visit(&class_def.body, &mut vec![], &["cls", "self", class_name], true, &constants);
+        res.visit(module_stmts, &mut vec![], &[class_name], false, &constants);
+        res
+    }
+
+    fn collect_constants<'a>(stmts: &'a Vec<Stmt>, constants: &mut Vec<(&'a str, &'a Expr)>) {
+        for stmt in stmts.iter() {
+            if let Stmt::Assign(assign) = stmt {
+                for target in assign.targets.iter() {
+                    if let Expr::Name(name) = target {
+                        constants.push((name.id.as_str(), &assign.value));
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit<'a>(&mut self, stmts: &'a Vec<Stmt>, loops: &mut Loops<'a>, owners: &[&str], class_level: bool, constants: &Vec<(&str, &'a Expr)>) {
+        for stmt in stmts.iter() {
+            match stmt {
+                Stmt::Expr(expr) => self.visit_call(&expr.value, loops, owners, constants),
+                Stmt::Assign(assign) => {
+                    for target in assign.targets.iter() {
+                        //locals()[name] = field, only meaningful in the body of the class
+                        if let (true, Expr::Subscript(subscript)) = (class_level, target) {
+                            let is_namespace = matches!(&*subscript.value, Expr::Call(call)
+                                if matches!(&*call.func, Expr::Name(n) if matches!(n.id.as_str(), "locals" | "vars")));
+                            if is_namespace {
+                                self.add_names(&subscript.slice, loops, constants);
+                            }
+                        }
+                    }
+                    self.visit_call(&assign.value, loops, owners, constants);
+                },
+                Stmt::For(for_stmt) => {
+                    loops.push((&*for_stmt.target, &*for_stmt.iter));
+                    self.visit(&for_stmt.body, loops, owners, class_level, constants);
+                    loops.pop();
+                },
+                Stmt::If(if_stmt) => {
+                    self.visit(&if_stmt.body, loops, owners, class_level, constants);
+                    for clause in if_stmt.elif_else_clauses.iter() {
+                        self.visit(&clause.body, loops, owners, class_level, constants);
+                    }
+                },
+                Stmt::With(with_stmt) => self.visit(&with_stmt.body, loops, owners, class_level, constants),
+                Stmt::Try(try_stmt) => self.visit(&try_stmt.body, loops, owners, class_level, constants),
+                //methods of the class can add fields to it, but other classes and functions of the module can't
+                Stmt::FunctionDef(func) if owners.len() > 1 => {
+                    let mut func_loops = vec![];
+                    self.visit(&func.body, &mut func_loops, owners, false, constants);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /* setattr(cls, name, field) and cls._add_field(name, field) */
+    fn visit_call<'a>(&mut self, expr: &'a Expr, loops: &Loops<'a>, owners: &[&str], constants: &Vec<(&str, &'a Expr)>) {
+        let Expr::Call(call) = expr else {
+            return;
+        };
+        let is_owner = |expr: &Expr| matches!(expr, Expr::Name(n) if owners.contains(&n.id.as_str()));
+        let name = match &*call.func {
+            Expr::Name(func) if func.id.as_str() == "setattr" && call.arguments.args.len() == 3 && is_owner(&call.arguments.args[0]) => {
+                &call.arguments.args[1]
+            },
+            Expr::Attribute(func) if func.attr.as_str() == "_add_field" && is_owner(&func.value) => {
+                match DynamicFields::first_arg(call) {
+                    Some(name) => name,
+                    None => return
+                }
+            },
+            _ => return
+        };
+        self.add_names(name, loops, constants);
+    }
+
+    fn first_arg(call: &ExprCall) -> Option<&Expr> {
+        call.arguments.args.first().or_else(|| call.arguments.keywords.iter()
+            .find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == "name"))
+            .map(|k| &k.value))
+    }
+
+    fn add_names<'a>(&mut self, name: &'a Expr, loops: &Loops<'a>, constants: &Vec<(&str, &'a Expr)>) {
+        let names = match name {
+            Expr::StringLiteral(s) => Some(vec![(s.value.to_string(), s.range)]),
+            Expr::Name(var) => {
+                //the innermost loop that defines the variable gives its values
+                loops.iter().rev().find_map(|(target, iter)| {
+                    let index = match target {
+                        Expr::Name(n) if n.id.as_str() == var.id.as_str() => None,
+                        Expr::Tuple(t) => Some(t.elts.iter().position(|e| matches!(e, Expr::Name(n) if n.id.as_str() == var.id.as_str()))?),
+                        _ => return None
+                    };
+                    Some(DynamicFields::get_iterated_names(iter, index, constants, 0))
+                }).flatten()
+            },
+            _ => None
+        };
+        match names {
+            Some(names) => {
+                for (name, range) in names.into_iter() {
+                    if !self.names.iter().any(|(n, _)| *n == name) {
+                        self.names.push((name, range));
+                    }
+                }
+            },
+            None => self.has_unknown_names = true
+        }
+    }
+
+    /* Return the string values of the index-th element of the items of iter, or of the items if index is None */
+    fn get_iterated_names(iter: &Expr, index: Option<usize>, constants: &Vec<(&str, &Expr)>, depth: u32) -> Option<Vec<(String, TextRange)>> {
+        if depth > 5 {
+            return None;
+        }
+        match iter {
+            Expr::Name(name) => {
+                let (_, value) = constants.iter().rev().find(|(n, _)| *n == name.id.as_str())?;
+                DynamicFields::get_iterated_names(value, index, constants, depth + 1)
+            },
+            Expr::Dict(dict) if index.is_none() => {
+                dict.items.iter().map(|item| match &item.key {
+                    Some(Expr::StringLiteral(s)) => Some((s.value.to_string(), s.range)),
+                    _ => None
+                }).collect()
+            },
+            Expr::Call(call) if call.arguments.args.is_empty() && call.arguments.keywords.is_empty() => {
+                let Expr::Attribute(attr) = &*call.func else {
+                    return None;
+                };
+                match (attr.attr.as_str(), index) {
+                    ("keys", None) | ("items", Some(0)) => DynamicFields::get_iterated_names(&attr.value, None, constants, depth + 1),
+                    _ => None
+                }
+            },
+            Expr::List(_) | Expr::Tuple(_) | Expr::Set(_) => {
+                let elts = match iter {
+                    Expr::List(l) => &l.elts,
+                    Expr::Tuple(t) => &t.elts,
+                    Expr::Set(s) => &s.elts,
+                    _ => return None
+                };
+                elts.iter().map(|elt| {
+                    let value = match (index, elt) {
+                        (None, elt) => elt,
+                        (Some(i), Expr::Tuple(t)) => t.elts.get(i)?,
+                        (Some(i), Expr::List(l)) => l.elts.get(i)?,
+                        _ => return None
+                    };
+                    match value {
+                        Expr::StringLiteral(s) => Some((s.value.to_string(), s.range)),
+                        _ => None
+                    }
+                }).collect()
+            },
+            _ => None
+        }
+    }
+}
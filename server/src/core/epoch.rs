@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, Sender};
+use lsp_server::{ErrorCode, Message, ResponseError};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::info;
+
+use crate::constants::{BuildSteps, SymType};
+use crate::core::config_check::IndexMode;
+use crate::core::file_mgr::FileMgr;
+use crate::core::odoo::SyncOdoo;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::{DelayedProcessingMessage, SessionInfo};
+use crate::S;
+
+pub const STATISTICS_COMMAND: &str = "odooLs.statistics";
+
+/* Maximum number of symbols waiting in the ARCH, ARCH_EVAL and ODOO queues for a request to finish the rebuild itself */
+const MAX_FINISHED_REBUILD: usize = 50;
+/* Maximum time spent by a request to finish the rebuild before answering, or refusing the request */
+const FINISH_DEADLINE: Duration = Duration::from_millis(300);
+
+/* Counters of the requests that read the index while a rebuild is in flight, returned by the statistics command */
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochStatistics {
+    pub epoch: u64,
+    pub mixed_epoch_reads: u64, //requests that would read files of two states of the index
+    pub finished_rebuilds: u64, //mixed reads answered after finishing the rebuild
+    pub refused_reads: u64, //mixed reads refused with ContentModified, as the rebuild was too long to be finished
+    pub evaluation_cache_hits: u64, //evaluations answered by the EvaluationCache
+    pub evaluation_cache_misses: u64,
+    pub evaluation_cache_entries: usize,
+    pub arch_cache_hits: u64, //external files built from the ArchCache instead of being parsed
+    pub arch_cache_misses: u64,
+    pub index_mode: IndexMode, //REDUCED if the configuration is not valid (see ConfigCheck)
+    pub config_issues: Vec<String>,
+}
+
+/* A rebuild is done in cascade: a file is rebuilt, then the files that depend on it. A request answered in the middle of
+the cascade reads some files built from the new version, and others that still wait to be rebuilt against it, like a
+model flattened from the new file and from the previous content of a dependent file. The answer is then wrong until the
+end of the cascade, and the diagnostics or completions flicker.
+Each cascade is an epoch of the index (see SyncOdoo::epoch), and each file records the epoch of its last ARCH_EVAL. A
+read mixes two states if some of the files it reads are built in the current epoch while others wait in the queues. The
+small rebuild is then finished before answering, by the rebuild thread for the requests of the read thread, within a
+deadline. If the read is still mixed after the deadline, the request is refused with ContentModified, so that the client
+retries it once the rebuild progressed, instead of getting an answer from the half-rebuilt index. */
+pub struct Epochs {}
+
+impl Epochs {
+
+    /* Return true if the files, given by the epoch of their last ARCH_EVAL and if they wait to be rebuilt, are not all
+    from the same state of the index */
+    pub fn is_mixed(epoch: u64, files: &Vec<(u64, bool)>) -> bool {
+        files.iter().any(|(_, pending)| *pending) && files.iter().any(|(file_epoch, pending)| !*pending && *file_epoch == epoch)
+    }
+
+    /* Return true if a request on the file would read files of two states of the index */
+    pub fn is_mixed_read(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> bool {
+        if session.sync_odoo.get_pending_build_count() == 0 {
+            return false;
+        }
+        let files: Vec<(u64, bool)> = Epochs::get_read_files(session, file).iter().map(|f| {
+            let pending = [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO].iter().any(|step| session.sync_odoo.is_in_rebuild(f, *step));
+            (f.borrow().arch_eval_epoch().unwrap_or(0), pending)
+        }).collect();
+        Epochs::is_mixed(session.sync_odoo.epoch, &files)
+    }
+
+    /* Make sure that a request of the main thread on the file reads a coherent index. The main thread can build the
+    symbols, so it finishes the small pending rebuild itself. Return the error to answer if it can't */
+    pub fn ensure_coherent_read(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> Result<(), ResponseError> {
+        if !Epochs::is_mixed_read(session, file) {
+            return Ok(());
+        }
+        session.sync_odoo.epoch_statistics.mixed_epoch_reads += 1;
+        if !Epochs::finish_pending_builds(session, Instant::now() + FINISH_DEADLINE) {
+            return Err(Epochs::refuse_mixed(session));
+        }
+        session.sync_odoo.epoch_statistics.finished_rebuilds += 1;
+        Ok(())
+    }
+
+    /* Make sure that a request of the read thread on a document reads a coherent index. The read thread never builds the
+    symbols: if the request would read a mixed state, the lock is released and the rebuild thread is asked to finish the
+    small pending rebuild, until the deadline. Return the error to answer if the state reached is still mixed */
+    pub fn wait_coherent_read(sync_odoo: &Arc<Mutex<SyncOdoo>>, params: &Value, sender: &Sender<Message>, receiver: &Receiver<Message>, delayed_process_sender: &Sender<DelayedProcessingMessage>) -> Result<(), ResponseError> {
+        let Some(uri) = params.get("textDocument").and_then(|document| document.get("uri")).and_then(|uri| uri.as_str()) else {
+            return Ok(());
+        };
+        {
+            let mut sync_odoo = sync_odoo.lock().unwrap();
+            let mut session = SessionInfo::new_from_custom_channel(sender.clone(), receiver.clone(), &mut sync_odoo);
+            if !Epochs::is_mixed_document(&mut session, uri) {
+                return Ok(());
+            }
+            session.sync_odoo.epoch_statistics.mixed_epoch_reads += 1;
+            if session.sync_odoo.get_pending_build_count() > MAX_FINISHED_REBUILD {
+                return Err(Epochs::refuse_mixed(&mut session));
+            }
+        }
+        let deadline = Instant::now() + FINISH_DEADLINE;
+        let (done_sender, done_receiver) = crossbeam_channel::bounded(1);
+        if delayed_process_sender.send(DelayedProcessingMessage::FINISH_BUILDS(deadline, done_sender)).is_ok() {
+            let _ = done_receiver.recv_deadline(deadline);
+        }
+        let mut sync_odoo = sync_odoo.lock().unwrap();
+        let mut session = SessionInfo::new_from_custom_channel(sender.clone(), receiver.clone(), &mut sync_odoo);
+        if Epochs::is_mixed_document(&mut session, uri) {
+            return Err(Epochs::refuse_mixed(&mut session));
+        }
+        session.sync_odoo.epoch_statistics.finished_rebuilds += 1;
+        Ok(())
+    }
+
+    /* Build the pending symbols before the deadline if they are few, from the main thread or the rebuild thread. The
+    validation of the rebuilt files is left to the next process_rebuilds. Return true if nothing waits to be built */
+    pub fn finish_pending_builds(session: &mut SessionInfo, deadline: Instant) -> bool {
+        if session.sync_odoo.get_pending_build_count() > MAX_FINISHED_REBUILD {
+            return false;
+        }
+        let finished = SyncOdoo::process_pending_builds(session, deadline);
+        session.request_delayed_rebuild();
+        finished
+    }
+
+    fn is_mixed_document(session: &mut SessionInfo, uri: &str) -> bool {
+        let path = FileMgr::uri2canonical(session, uri);
+        match session.sync_odoo.get_file_symbol(&PathBuf::from(path)) {
+            Some(file) => Epochs::is_mixed_read(session, &file),
+            None => false
+        }
+    }
+
+    fn refuse_mixed(session: &mut SessionInfo) -> ResponseError {
+        info!("Refusing a read of a mixed state: {} symbols wait to be rebuilt", session.sync_odoo.get_pending_build_count());
+        session.sync_odoo.epoch_statistics.refused_reads += 1;
+        ResponseError { code: ErrorCode::ContentModified as i32, message: S!("The index is being rebuilt, retry"), data: None }
+    }
+
+    /* Return the files read by a request on the file: the file, the files it imports, and the files of the classes of
+    the models it uses */
+    fn get_read_files(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res = vec![file.clone()];
+        let models: Vec<_> = {
+            let file = file.borrow();
+            for step in [BuildSteps::ARCH, BuildSteps::ARCH_EVAL] {
+                res.extend(file.get_dependencies(step, BuildSteps::ARCH).iter());
+            }
+            file.model_dependencies().map(|models| models.iter().collect()).unwrap_or_default()
+        };
+        for model in models.iter() {
+            let classes = model.borrow().all_symbols(session, None);
+            for (class, _) in classes.iter() {
+                if let Some(class_file) = class.borrow().get_file().and_then(|f| f.upgrade()) {
+                    res.push(class_file);
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        res.retain(|f| matches!(f.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) && seen.insert(Rc::as_ptr(f)));
+        res
+    }
+}
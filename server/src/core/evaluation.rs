@@ -1,4 +1,5 @@
-use ruff_python_ast::{Expr, ExprCall, Identifier, Operator, Parameter};
+use ruff_python_ast::{Expr, ExprCall, Identifier, ModExpression, Operator, Parameter};
+use ruff_python_parser::Parsed;
 use ruff_text_size::{Ranged, TextRange, TextSize};
 use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 use weak_table::traits::WeakElement;
@@ -6,18 +7,34 @@ use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::i32;
 use std::rc::{Rc, Weak};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use crate::constants::*;
+use crate::core::argument_types::ArgumentTypes;
+use crate::core::call_binding::{CallBinding, UnpackedValues};
+use crate::core::collection_folding::CollectionFolding;
 use crate::core::odoo::SyncOdoo;
+use crate::core::overloads::Overloads;
+use crate::core::union_members::UnionMembers;
 use crate::threads::SessionInfo;
+use crate::features::ast_utils::AstUtils;
 use crate::S;
 
+use super::builtin_arithmetic::BuiltinArithmetic;
+use super::date_arithmetic::DateArithmetic;
 use super::file_mgr::FileMgr;
+use super::guards::{Guard, GuardedName};
+use super::narrowing::Narrowings;
 use super::python_validator::PythonValidator;
 use super::symbols::function_symbol::{Argument, ArgumentType, FunctionSymbol};
 use super::symbols::symbol::Symbol;
 use super::symbols::symbol_mgr::SectionIndex;
 
+/* Maximum number of nested evaluations of callback parameters (see eval_callback_parameter) */
+const MAX_CALLBACK_DEPTH: u8 = 3;
+
+thread_local! {
+    static CALLBACK_DEPTH: Cell<u8> = const { Cell::new(0) };
+}
 
 #[derive(Debug, Clone)]
 pub enum EvaluationValue {
@@ -25,6 +42,7 @@ pub enum EvaluationValue {
     CONSTANT(ruff_python_ast::Expr), //expr is a literal
     DICT(Vec<(ruff_python_ast::Expr, ruff_python_ast::Expr)>), //expr is a literal
     LIST(Vec<ruff_python_ast::Expr>), //expr is a literal
+    SET(Vec<ruff_python_ast::Expr>), //expr is a literal
     TUPLE(Vec<ruff_python_ast::Expr>) //expr is a literal
 }
 
@@ -161,7 +179,7 @@ impl EvaluationSymbolWeak {
 }
 
 #[derive(Debug, Default, Clone)]
-enum EvaluationSymbolPtr {
+pub enum EvaluationSymbolPtr {
     WEAK(EvaluationSymbolWeak),
     SELF,
     ARG(u32),
@@ -229,6 +247,23 @@ impl Evaluation {
         }
     }
 
+    pub fn new_set(odoo: &mut SyncOdoo, values: Vec<Expr>, range: TextRange) -> Evaluation {
+        Evaluation {
+            symbol: EvaluationSymbol {
+                sym: EvaluationSymbolPtr::WEAK(EvaluationSymbolWeak{
+                    weak: Rc::downgrade(&odoo.get_symbol(&(vec![S!("builtins")], vec![S!("set")]), u32::MAX).last().expect("builtins set not found")),
+                    instance: Some(true),
+                    is_super: false,
+                }),
+                context: HashMap::new(),
+                factory: None,
+                get_symbol_hook: None
+            },
+            value: Some(EvaluationValue::SET(values)),
+            range: Some(range)
+        }
+    }
+
     pub fn new_dict(odoo: &mut SyncOdoo, values: Vec<(Expr, Expr)>, range: TextRange) -> Evaluation {
         Evaluation {
             symbol: EvaluationSymbol {
@@ -246,6 +281,17 @@ impl Evaluation {
         }
     }
 
+    /* Return the evaluation of a list, tuple, set or dict value */
+    pub fn new_collection(odoo: &mut SyncOdoo, value: EvaluationValue, range: TextRange) -> Option<Evaluation> {
+        match value {
+            EvaluationValue::LIST(items) => Some(Evaluation::new_list(odoo, items, range)),
+            EvaluationValue::TUPLE(items) => Some(Evaluation::new_tuple(odoo, items, range)),
+            EvaluationValue::SET(items) => Some(Evaluation::new_set(odoo, items, range)),
+            EvaluationValue::DICT(items) => Some(Evaluation::new_dict(odoo, items, range)),
+            _ => None
+        }
+    }
+
     pub fn new_domain(odoo: &mut SyncOdoo) -> Evaluation {
         Evaluation {
             symbol: EvaluationSymbol {
@@ -378,10 +424,10 @@ impl Evaluation {
     /// else:
     ///     i="test"
     /// It will return two evaluation for i, one with 5 and one for "test"
-    pub fn from_sections(parent: &Symbol, sections: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>) -> Vec<Evaluation> {
+    pub fn from_sections(parent: &Symbol, name: &str, sections: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>) -> Vec<Evaluation> {
         let mut res = vec![];
         let section = parent.as_symbol_mgr().get_section_for(u32::MAX);
-        let syms = parent.as_symbol_mgr()._get_loc_symbol(sections, u32::MAX, &SectionIndex::INDEX(section.index), &mut vec![]);
+        let syms = parent.as_symbol_mgr()._get_loc_symbol(name, sections, u32::MAX, &SectionIndex::INDEX(section.index), &mut vec![]);
         for sym in syms {
             let mut is_instance = None;
             if matches!(sym.borrow().typ(), SymType::VARIABLE | SymType::FUNCTION) {
@@ -445,6 +491,59 @@ impl Evaluation {
         return (analyze_result.evaluations, analyze_result.diagnostics)
     }
 
+    /* Build the evaluations of the types aliased by an expression, like list for list[float], or int and str for
+    int | str. The arguments of the generic types are not evaluated, as they can be type parameters of the alias. */
+    pub fn eval_type_alias(session: &mut SessionInfo, ast: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        match ast {
+            Expr::Subscript(sub) => Evaluation::eval_type_alias(session, &sub.value, parent, max_infer),
+            Expr::BinOp(operator) if operator.op == Operator::BitOr => {
+                let (mut evals, mut diagnostics) = Evaluation::eval_type_alias(session, &operator.left, parent.clone(), max_infer);
+                let (right_evals, right_diagnostics) = Evaluation::eval_type_alias(session, &operator.right, parent, max_infer);
+                evals.extend(right_evals);
+                diagnostics.extend(right_diagnostics);
+                (evals, diagnostics)
+            },
+            Expr::StringLiteral(_) => match Evaluation::parse_forward_reference(ast) {
+                //the ranges of the parsed expression are relative to the string: its diagnostics are not given
+                Some(parsed) => (Evaluation::eval_type_alias(session, parsed.expr(), parent, &TextSize::new(u32::MAX)).0, vec![]),
+                None => Evaluation::eval_from_ast(session, ast, parent, max_infer)
+            },
+            _ => Evaluation::eval_from_ast(session, ast, parent, max_infer)
+        }
+    }
+
+    /* Return the expression of a forward reference: an annotation given by a string naming a class, like -> "ResPartner"
+    or -> "models.ResPartner". It is evaluated with the whole scope, as the class can be declared later or imported under
+    TYPE_CHECKING only. A string without uppercase letter is a model name ('res.partner') */
+    pub fn parse_forward_reference(annotation: &Expr) -> Option<Parsed<ModExpression>> {
+        let Expr::StringLiteral(s) = annotation else {
+            return None;
+        };
+        if !s.value.to_str().chars().any(|c| c.is_ascii_uppercase()) {
+            return None;
+        }
+        ruff_python_parser::parse_expression(s.value.to_str()).ok()
+    }
+
+    /* Evaluate an annotation to the values it declares: the classes of the annotation are instances ('-> str' returns a
+    str), and the other evaluations are kept. Like for eval_type_alias, the type arguments are not evaluated */
+    pub fn eval_annotation(session: &mut SessionInfo, annotation: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        let (evaluations, mut diagnostics) = Evaluation::eval_type_alias(session, annotation, parent, max_infer);
+        let mut res = vec![];
+        for evaluation in evaluations.into_iter() {
+            let sym_ref = evaluation.symbol.get_symbol(session, &mut None, &mut diagnostics, None);
+            let followed = Symbol::follow_ref(&sym_ref, session, &mut None, false, false, None, &mut diagnostics);
+            let class = followed.iter().find(|value| {
+                value.instance != Some(true) && value.weak.upgrade().is_some_and(|sym| sym.borrow().typ() == SymType::CLASS)
+            });
+            match class {
+                Some(class) => res.push(Evaluation::eval_from_symbol(&class.weak, Some(true))),
+                None => res.push(evaluation)
+            }
+        }
+        (res, diagnostics)
+    }
+
     /* Given an Expr, try to return the represented String. None if it can't be achieved */
     fn expr_to_str(session: &mut SessionInfo, ast: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize, diagnostics: &mut Vec<Diagnostic>) -> (Option<String>, Vec<Diagnostic>) {
         let from_module;
@@ -513,6 +612,21 @@ impl Evaluation {
         Definition -> symbol
         Autocompletion -> effective_sym
      */
+    /* Return the evaluations of an attribute chain narrowed by a TypeGuard in the branch of the function that contains
+    the offset (see PythonArchEval::_eval_guards) */
+    fn get_narrowed_attribute(parent: &Rc<RefCell<Symbol>>, expr: &Expr, max_infer: &TextSize) -> Option<Vec<Evaluation>> {
+        let function = parent.borrow().get_in_parents(&vec![SymType::FUNCTION], true)?.upgrade()?;
+        let function = function.borrow();
+        let narrowed_attributes = &function.as_func().narrowed_attributes;
+        if narrowed_attributes.is_empty() {
+            return None;
+        }
+        let chain = Narrowings::get_attribute_chain(expr)?;
+        narrowed_attributes.iter().rev()
+            .find(|(c, range, _)| *c == chain && range.contains_inclusive(*max_infer))
+            .map(|(_, _, evaluations)| evaluations.clone())
+    }
+
     pub fn analyze_ast(session: &mut SessionInfo, ast: &ExprOrIdent, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize, context: &mut Option<Context>) -> AnalyzeAstResult {
         let odoo = &mut session.sync_odoo;
         let mut evals = vec![];
@@ -562,6 +676,17 @@ impl Evaluation {
                 }
                 evals.push(Evaluation::new_tuple(odoo, values, expr.range));
             },
+            ExprOrIdent::Expr(Expr::Set(expr)) => {
+                let mut values: Vec<ruff_python_ast::Expr> = Vec::new();
+                for e in expr.elts.iter() {
+                    if e.is_literal_expr() {
+                        values.push(e.clone());
+                    } else {
+                        values = Vec::new(); break;
+                    }
+                }
+                evals.push(Evaluation::new_set(odoo, values, expr.range));
+            },
             ExprOrIdent::Expr(Expr::Dict(expr)) => {
                 let mut values: Vec<(ruff_python_ast::Expr, ruff_python_ast::Expr)> = Vec::new();
                 for (index, e) in expr.iter_keys().enumerate() {
@@ -583,6 +708,15 @@ impl Evaluation {
                 evals.push(Evaluation::new_dict(odoo, values, expr.range));
             },
             ExprOrIdent::Expr(Expr::Call(expr)) => {
+                //set by the evaluation of 'await call()', that gives the value returned by an async function instead of its coroutine
+                let awaited = context.as_mut().and_then(|context| context.remove(&S!("awaited"))).is_some();
+                //list(), tuple(), set(), sorted() or dict.fromkeys() of a constant collection
+                if CollectionFolding::get_conversion(expr).is_some() {
+                    if let Some(eval) = Evaluation::eval_folded_collection(session, ast.expr(), parent.clone(), max_infer) {
+                        evals.push(eval);
+                        return AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics };
+                    }
+                }
                 let (base_eval, diags) = Evaluation::eval_from_ast(session, &expr.func, parent.clone(), max_infer);
                 diagnostics.extend(diags);
                 //TODO actually we only evaluate if there is only one function behind the evaluation.
@@ -619,7 +753,20 @@ impl Evaluation {
                 if let Some(base_sym) = base_sym {
                     if base_sym.borrow().typ() == SymType::CLASS {
                         if base_sym_weak_eval.instance.unwrap_or(false) {
-                            //TODO handle call on class instance
+                            //calling an instance calls the __call__ method of its class, like self.env(user=...)
+                            let (call_syms, _) = base_sym.borrow().get_member_symbol(session, &S!("__call__"), module.clone(), false, false, false, false);
+                            for call_sym in call_syms.iter() {
+                                if call_sym.borrow().typ() != SymType::FUNCTION {
+                                    continue;
+                                }
+                                let call_evals = call_sym.borrow().evaluations().cloned().unwrap_or_default();
+                                for mut eval in call_evals.into_iter() {
+                                    //the called instance gives its context (test mode...) to the result
+                                    eval.symbol.context.extend(base_eval[0].symbol.context.clone());
+                                    eval.range = Some(expr.range);
+                                    evals.push(eval);
+                                }
+                            }
                         } else {
                             if base_sym.borrow().get_tree() == (vec![S!("builtins")], vec![S!("super")]){
                                 //  - If 1st argument exists, we add that class with symbol_type Super
@@ -721,6 +868,23 @@ impl Evaluation {
                                 });
                             }
                         }
+                    } else if base_sym.borrow().typ() == SymType::FUNCTION && session.sync_odoo.plugin_rules.is_factory(&flatten_tree(&base_sym.borrow().get_tree()).join(".")) {
+                        //a factory of the plugin rules returns an instance of the class given as first argument
+                        if let Some(class_arg) = expr.arguments.args.first() {
+                            let (class_evals, diags) = Evaluation::eval_from_ast(session, class_arg, parent.clone(), max_infer);
+                            diagnostics.extend(diags);
+                            for class_eval in class_evals.iter() {
+                                let class_weak = class_eval.symbol.get_symbol(session, &mut None, &mut diagnostics, None);
+                                for class_weak in Symbol::follow_ref(&class_weak, session, &mut None, false, false, None, &mut diagnostics).iter() {
+                                    if class_weak.instance.unwrap_or(false) || !class_weak.weak.upgrade().is_some_and(|c| c.borrow().typ() == SymType::CLASS) {
+                                        continue;
+                                    }
+                                    let mut eval = Evaluation::eval_from_symbol(&class_weak.weak, Some(true));
+                                    eval.range = Some(expr.range);
+                                    evals.push(eval);
+                                }
+                            }
+                        }
                     } else if base_sym.borrow().typ() == SymType::FUNCTION {
                         //function return evaluation can come from:
                         //  - type annotation parsing (ARCH_EVAL step)
@@ -728,13 +892,7 @@ impl Evaluation {
                         //  - function body inference (VALIDATION step)
                         // Therefore, the actual version of the algorithm will trigger build from the different steps if this one has already been reached.
                         // We don't want to launch validation step while Arch evaluating the code.
-                        if base_sym.borrow().evaluations().is_some() && base_sym.borrow().evaluations().unwrap().len() == 0 {
-                            if base_sym.borrow().parent_file_or_function().as_ref().unwrap().upgrade().unwrap().borrow().build_status(BuildSteps::ODOO) == BuildStatus::DONE &&
-                            base_sym.borrow().build_status(BuildSteps::VALIDATION) == BuildStatus::PENDING { //TODO update with new step validation to lower it to localized level
-                                let mut v = PythonValidator::new(base_sym.clone());
-                                v.validate(session);
-                            }
-                        }
+                        Evaluation::infer_return_evaluations(session, &base_sym);
                         if base_sym.borrow().evaluations().is_some() {
                             let parent_file_or_func = parent.borrow().parent_file_or_function().as_ref().unwrap().upgrade().unwrap();
                             let is_in_validation = match parent_file_or_func.borrow().typ().clone() {
@@ -746,63 +904,139 @@ impl Evaluation {
                                 }
                                 _ => {false}
                             };
+                            let mut on_instance = !base_sym.borrow().as_func().is_static;
+                            //the class is bound to the first parameter of a classmethod, whether it is called on the class or on an instance
+                            if on_instance && !base_sym.borrow().as_func().is_class_method {
+                                //check that the call is indeed done on an instance
+                                on_instance = context.as_ref().unwrap().get(&S!("is_attr_of_instance"))
+                                .unwrap_or(&ContextValue::BOOLEAN(false)).as_bool();
+                            }
                             if is_in_validation {
-                                let mut on_instance = !base_sym.borrow().as_func().is_static;
-                                if on_instance {
-                                    //check that the call is indeed done on an instance
-                                    on_instance = context.as_ref().unwrap().get(&S!("is_attr_of_instance"))
-                                    .unwrap_or(&ContextValue::BOOLEAN(false)).as_bool();
+                                //the functions of the stubs and of the libraries, and the ones whose decorator can change the parameters, are not checked
+                                if !base_sym.borrow().as_func().is_external && FunctionSymbol::keeps_signature(session, &base_sym) {
+                                    let from_module = parent.borrow().find_module();
+                                    let unpacked = CallBinding::get_unpacked_values(session, expr, parent.clone(), max_infer);
+                                    diagnostics.extend(Evaluation::validate_call_arguments(session,
+                                        &base_sym.borrow().as_func(),
+                                        expr,
+                                        context.as_ref().unwrap().get_key_value(&S!("parent")).unwrap_or((&S!(""), &ContextValue::SYMBOL(Weak::new()))).1.as_symbol(),
+                                        from_module,
+                                        on_instance,
+                                        &unpacked));
+                                }
+                                if session.sync_odoo.config.validate_argument_types {
+                                    diagnostics.extend(ArgumentTypes::validate_call(session, &base_sym, expr, on_instance, parent.clone(), max_infer));
                                 }
-                                let from_module = parent.borrow().find_module();
-                                diagnostics.extend(Evaluation::validate_call_arguments(session,
-                                    &base_sym.borrow().as_func(),
-                                    expr,
-                                    context.as_ref().unwrap().get_key_value(&S!("parent")).unwrap_or((&S!(""), &ContextValue::SYMBOL(Weak::new()))).1.as_symbol(),
-                                    
-                                    from_module,
-                                    on_instance));
                             }
-                            for eval in base_sym.borrow().evaluations().unwrap().iter() {
-                                let mut e = eval.clone();
-                                e.symbol.context.extend(context.as_mut().unwrap().clone());
+                            //the call of an overloaded function returns the values of the overloads accepting its arguments
+                            let is_overloaded = base_sym.borrow().as_func().is_overloaded();
+                            let returns = match is_overloaded {
+                                true => Overloads::get_call_returns(session, &base_sym, expr, on_instance),
+                                false => base_sym.borrow().evaluations().unwrap().clone()
+                            };
+                            if base_sym.borrow().as_func().is_async && !awaited {
+                                let mut e = Evaluation::eval_coroutine(&base_sym, context.as_ref().unwrap().clone());
                                 e.range = Some(expr.range.clone());
                                 evals.push(e);
+                            } else {
+                                for eval in returns.iter() {
+                                    let mut e = eval.clone();
+                                    e.symbol.context.extend(context.as_mut().unwrap().clone());
+                                    e.range = Some(expr.range.clone());
+                                    evals.push(e);
+                                }
                             }
                         }
                     }
                 }
             },
             ExprOrIdent::Expr(Expr::Attribute(expr)) => {
+                if let Some(narrowed) = Evaluation::get_narrowed_attribute(&parent, ast.expr(), max_infer) {
+                    return AnalyzeAstResult { evaluations: narrowed, effective_sym, factory, diagnostics };
+                }
                 let (base_evals, diags) = Evaluation::eval_from_ast(session, &expr.value, parent.clone(), max_infer);
                 diagnostics.extend(diags);
-                // TODO handle multiple base_evals
-                if base_evals.len() == 0 || (base_evals.len() > 0 && base_evals[0].symbol.get_symbol(session, &mut None, &mut diagnostics, None).weak.is_expired()) {
+                //a receiver with several evaluations is a union: the member is looked up on all its arms
+                let file = parent.borrow().get_file().unwrap().upgrade().unwrap();
+                let mut bases = vec![];
+                for base_eval in base_evals.iter() {
+                    let base_ref = base_eval.symbol.get_symbol(session, &mut None, &mut diagnostics, Some(file.clone()));
+                    if base_ref.weak.is_expired() {
+                        continue;
+                    }
+                    let is_super = base_ref.is_super;
+                    for base in Symbol::follow_ref(&base_ref, session, &mut None, false, false, None, &mut diagnostics).into_iter() {
+                        match Evaluation::get_property_value(session, &base, &mut diagnostics, &file) {
+                            Some(values) => bases.extend(values.into_iter().map(|value| (value, false))),
+                            None => bases.push((base, is_super))
+                        }
+                    }
+                }
+                if bases.is_empty() {
                     return AnalyzeAstResult::from_only_diagnostics(diagnostics);
                 }
-                let base_ref = base_evals[0].symbol.get_symbol(session, &mut None, &mut diagnostics, Some(parent.borrow().get_file().unwrap().upgrade().unwrap().clone()));
-                let bases = Symbol::follow_ref(&base_ref, session, &mut None, false, false, None, &mut diagnostics);
-                for ibase in bases.iter() {
+                let attr = expr.attr.to_string();
+                let arms = UnionMembers::get_arms(&bases.iter().map(|(base, _)| base.clone()).collect());
+                let mut providers = vec![];
+                let mut attribute_evals: Vec<Evaluation> = vec![];
+                for (ibase, is_super) in bases.iter() {
                     let base_loc = ibase.weak.upgrade();
                     if let Some(base_loc) = base_loc {
-                        let (attributes, mut attributes_diagnostics) = base_loc.borrow().get_member_symbol(session, &expr.attr.to_string(), module.clone(), false, false, true, base_ref.is_super);
+                        let (attributes, mut attributes_diagnostics) = base_loc.borrow().get_member_symbol(session, &attr, module.clone(), false, false, true, *is_super);
                         for diagnostic in attributes_diagnostics.iter_mut(){
                             diagnostic.range = FileMgr::textRange_to_temporary_Range(&expr.range())
                         }
                         diagnostics.extend(attributes_diagnostics);
+                        //the attributes a module does not declare can be given by its __getattr__ function
+                        if attributes.is_empty() && !ibase.instance.unwrap_or(false) {
+                            if let Some(getattr) = base_loc.borrow().get_module_getattr() {
+                                let mut eval = Evaluation::eval_module_getattr(&getattr);
+                                eval.range = Some(expr.range());
+                                attribute_evals.push(eval);
+                            }
+                        }
                         if !attributes.is_empty() {
+                            if arms.iter().any(|(arm, _)| Rc::ptr_eq(arm, &base_loc)) {
+                                providers.push(UnionMembers::get_arm_name(&base_loc));
+                            }
                             if ibase.instance.unwrap_or(false) {
                                 context.as_mut().unwrap().insert(S!("is_attr_of_instance"), ContextValue::BOOLEAN(true));
                             }
                             attributes.iter().for_each(|attribute|{
+                                //an arm reached twice gives the same declarations
+                                if attribute_evals.iter().any(|e| e.symbol.get_weak().weak.ptr_eq(&Rc::downgrade(attribute))) {
+                                    return;
+                                }
                                 let mut eval = Evaluation::eval_from_symbol(&Rc::downgrade(attribute), None);
                                 eval.symbol.context = context.as_ref().unwrap().clone();
                                 eval.symbol.context.insert(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&base_loc)));
-                                evals.push(eval);
+                                attribute_evals.push(eval);
                             });
                             context.as_mut().unwrap().remove(&S!("is_attr_of_instance"));
                         }
                     }
                 }
+                if arms.len() > 1 {
+                    let arm_names: Vec<String> = arms.iter().map(|(arm, _)| UnionMembers::get_arm_name(arm)).collect();
+                    for eval in attribute_evals.iter_mut() {
+                        eval.symbol.context.insert(S!("union_arms"), ContextValue::STRING(arm_names.join(" | ")));
+                        eval.symbol.context.insert(S!("provided_by"), ContextValue::STRING(providers.join(" | ")));
+                    }
+                    if session.sync_odoo.config.report_possibly_missing_members {
+                        if let Some(provenance) = UnionMembers::get_provenance(&arm_names, &providers) {
+                            diagnostics.push(Diagnostic::new(
+                                FileMgr::textRange_to_temporary_Range(&expr.attr.range()),
+                                Some(DiagnosticSeverity::WARNING),
+                                Some(NumberOrString::String(S!("OLS30340"))),
+                                Some(EXTENSION_NAME.to_string()),
+                                format!("{} is possibly missing: it is a member of {} ({})", attr, arm_names.join(" | "), provenance),
+                                None,
+                                None,
+                            ));
+                        }
+                    }
+                }
+                evals.extend(attribute_evals);
             },
             ExprOrIdent::Expr(Expr::Name(_)) | ExprOrIdent::Ident(_) | ExprOrIdent::Parameter(_) => {
                 let infered_syms = match ast {
@@ -821,13 +1055,27 @@ impl Evaluation {
                 };
 
                 if infered_syms.is_empty() {
-                    return AnalyzeAstResult::from_only_diagnostics(diagnostics);
+                    if let ExprOrIdent::Expr(Expr::Name(expr)) = ast {
+                        evals.extend(Evaluation::eval_lambda_parameter(session, &parent, &expr.id.to_string(), expr.range));
+                    }
+                    return AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics };
                 }
                 for infered_sym in infered_syms.iter() {
+                    let callback_records = Evaluation::eval_callback_parameter(session, infered_sym);
+                    if !callback_records.is_empty() {
+                        evals.extend(callback_records);
+                        continue;
+                    }
                     evals.push(Evaluation::eval_from_symbol(&Rc::downgrade(infered_sym), None));
                 }
             },
             ExprOrIdent::Expr(Expr::Subscript(sub)) => {
+                if sub.slice.is_slice_expr() {
+                    if let Some(eval) = Evaluation::eval_folded_collection(session, ast.expr(), parent.clone(), max_infer) {
+                        evals.push(eval);
+                        return AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics };
+                    }
+                }
                 let (eval_left, diags) = Evaluation::eval_from_ast(session, &sub.value, parent.clone(), max_infer);
                 diagnostics.extend(diags);
                 // TODO handle multiple eval_left
@@ -835,6 +1083,16 @@ impl Evaluation {
                     return AnalyzeAstResult::from_only_diagnostics(diagnostics);
                 }
                 let base = &eval_left[0].symbol.get_symbol(session, &mut None, &mut diagnostics, None); //TODO set context?
+                //registry['name'] gives the function registered under this name by a decorator of the plugin rules
+                if let (Some(registry), Expr::StringLiteral(name)) = (base.weak.upgrade(), &*sub.slice) {
+                    let registry_path = flatten_tree(&registry.borrow().get_tree()).join(".");
+                    if let Some(function) = session.sync_odoo.plugin_rules.get_registered(&registry_path, name.value.to_str()) {
+                        let mut eval = Evaluation::eval_from_symbol(&Rc::downgrade(&function), Some(false));
+                        eval.range = Some(sub.range);
+                        evals.push(eval);
+                        return AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics };
+                    }
+                }
                 let bases = Symbol::follow_ref(&base, session, &mut None, false, false, None, &mut diagnostics);
                 if bases.len() != 1 {
                     return AnalyzeAstResult::from_only_diagnostics(diagnostics);
@@ -866,11 +1124,64 @@ impl Evaluation {
                 }
             },
             ExprOrIdent::Expr(Expr::BinOp(operator)) => {
-                match operator.op {
-                    Operator::Add => {
-
-                    },
-                    _ => {}
+                //concatenation and repetition of constant lists and tuples
+                if matches!(operator.op, Operator::Add | Operator::Mult) {
+                    if let Some(eval) = Evaluation::eval_folded_collection(session, ast.expr(), parent.clone(), max_infer) {
+                        evals.push(eval);
+                        return AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics };
+                    }
+                }
+                //the arithmetic between dates and durations gives a date or a duration (see DateArithmetic), and the one
+                //between builtin values gives a builtin value (see BuiltinArithmetic)
+                let left_classes = Evaluation::get_instance_class_paths(session, &operator.left, parent.clone(), max_infer);
+                let right_classes = match left_classes.is_empty() {
+                    true => vec![],
+                    false => Evaluation::get_instance_class_paths(session, &operator.right, parent.clone(), max_infer)
+                };
+                let mut results: Vec<&str> = vec![];
+                for left in left_classes.iter() {
+                    for right in right_classes.iter() {
+                        let result = DateArithmetic::get_result_type(left, operator.op, right)
+                            .or_else(|| BuiltinArithmetic::get_result_type(left, operator.op, right));
+                        if let Some(result) = result {
+                            if !results.contains(&result) {
+                                results.push(result);
+                            }
+                        }
+                    }
+                }
+                for result in results.iter() {
+                    let (module, class) = result.rsplit_once('.').unwrap();
+                    let tree = (module.split('.').map(|part| part.to_string()).collect(), vec![class.to_string()]);
+                    if let Some(class_sym) = session.sync_odoo.get_symbol(&tree, u32::MAX).last() {
+                        let mut eval = Evaluation::eval_from_symbol(&Rc::downgrade(class_sym), Some(true));
+                        eval.range = Some(operator.range);
+                        evals.push(eval);
+                    }
+                }
+            },
+            ExprOrIdent::Expr(Expr::Await(expr)) => {
+                if expr.value.is_call_expr() {
+                    let mut awaited_context = context.clone().or_else(|| Some(HashMap::new()));
+                    awaited_context.as_mut().unwrap().insert(S!("awaited"), ContextValue::BOOLEAN(true));
+                    let value = Evaluation::analyze_ast(session, &ExprOrIdent::Expr(&expr.value), parent.clone(), max_infer, &mut awaited_context);
+                    diagnostics.extend(value.diagnostics);
+                    evals.extend(value.evaluations);
+                } else {
+                    let (values, diags) = Evaluation::eval_from_ast(session, &expr.value, parent.clone(), max_infer);
+                    diagnostics.extend(diags);
+                    for value in values.iter() {
+                        evals.extend(Evaluation::get_awaited_evaluations(session, value));
+                    }
+                }
+            },
+            ExprOrIdent::Expr(Expr::Compare(expr)) => {
+                //a comparison gives a bool, even if it is chained (0 < qty <= 10). The operands are not evaluated: they keep their own evaluation
+                let bool_class = odoo.get_symbol(&(vec![S!("builtins")], vec![S!("bool")]), u32::MAX);
+                if let Some(bool_class) = bool_class.last() {
+                    let mut eval = Evaluation::eval_from_symbol(&Rc::downgrade(bool_class), Some(true));
+                    eval.range = Some(expr.range);
+                    evals.push(eval);
                 }
             }
             _ => {}
@@ -878,7 +1189,236 @@ impl Evaluation {
         AnalyzeAstResult { evaluations: evals, effective_sym, factory, diagnostics }
     }
 
-    fn validate_call_arguments(session: &mut SessionInfo, function: &FunctionSymbol, exprCall: &ExprCall, on_object: Weak<RefCell<Symbol>>, from_module: Option<Rc<RefCell<Symbol>>>, is_on_instance: bool) -> Vec<Diagnostic> {
+    /* Return the evaluation of an operation on constant collections, like BASE[:3] + ('extra',) (see CollectionFolding).
+    The names are resolved to the values of their evaluation */
+    fn eval_folded_collection(session: &mut SessionInfo, expr: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<Evaluation> {
+        let value = CollectionFolding::fold(expr, &mut |leaf| {
+            let (evals, _) = Evaluation::eval_from_ast(session, leaf, parent.clone(), max_infer);
+            let [eval] = evals.as_slice() else {
+                return None;
+            };
+            eval.follow_ref_and_get_value(session, &mut None, &mut vec![])
+        })?;
+        Evaluation::new_collection(session.sync_odoo, value, expr.range())
+    }
+
+    /* Return the paths of the classes of the instances that the expression evaluates to, like 'datetime.date' */
+    pub fn get_instance_class_paths(session: &mut SessionInfo, expr: &Expr, parent: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Vec<String> {
+        let (evals, _) = Evaluation::eval_from_ast(session, expr, parent, max_infer);
+        let mut res = vec![];
+        for eval in evals.iter() {
+            let eval_symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for eval_weak in Symbol::follow_ref(&eval_symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                let Some(symbol) = eval_weak.weak.upgrade() else {
+                    continue;
+                };
+                if symbol.borrow().typ() != SymType::CLASS || !eval_weak.instance.unwrap_or(false) {
+                    continue;
+                }
+                let path = flatten_tree(&symbol.borrow().get_tree()).join(".");
+                if !res.contains(&path) {
+                    res.push(path);
+                }
+            }
+        }
+        res
+    }
+
+    /* Evaluate the first parameter of a lambda given as callback to a recordset method, like 'line' in
+    order.order_line.filtered(lambda line: line.product_id), as a record of the model of the recordset */
+    fn eval_lambda_parameter(session: &mut SessionInfo, parent: &Rc<RefCell<Symbol>>, name: &String, range: TextRange) -> Vec<Evaluation> {
+        let Some(file_info) = FileMgr::get_file_info_of_symbol(session, parent) else {
+            return vec![];
+        };
+        let receiver = match file_info.try_borrow() {
+            Ok(file_info) => file_info.ast.as_ref().and_then(|ast| AstUtils::find_lambda_records(ast, name, range)),
+            Err(_) => None
+        };
+        match receiver {
+            Some(receiver) => Evaluation::eval_records(session, &receiver, parent.clone()),
+            None => vec![]
+        }
+    }
+
+    /* Evaluate the record parameter of a function that is given as callback to recordset methods in its file, like
+    'line' in 'def _is_service(self, line)' when order.order_line.filtered(self._is_service) is used */
+    fn eval_callback_parameter(session: &mut SessionInfo, parameter: &Rc<RefCell<Symbol>>) -> Vec<Evaluation> {
+        if parameter.borrow().typ() != SymType::VARIABLE || !parameter.borrow().evaluations().is_some_and(|e| e.is_empty()) {
+            return vec![];
+        }
+        let Some(function) = parameter.borrow().parent().and_then(|p| p.upgrade()) else {
+            return vec![];
+        };
+        if function.borrow().typ() != SymType::FUNCTION {
+            return vec![];
+        }
+        let (function_name, function_range) = {
+            let function = function.borrow();
+            let Some(class) = function.parent().and_then(|p| p.upgrade()) else {
+                return vec![];
+            };
+            //only methods of models are checked, as they are the ones that receive records
+            if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() || function.as_func().is_static {
+                return vec![];
+            }
+            let record_arg = function.as_func().args.get(1);
+            if !record_arg.is_some_and(|arg| arg.symbol.upgrade().is_some_and(|s| Rc::ptr_eq(&s, parameter))) {
+                return vec![];
+            }
+            (function.name().clone(), function.as_func().range)
+        };
+        let Some(file_info) = FileMgr::get_file_info_of_symbol(session, &function) else {
+            return vec![];
+        };
+        let receivers = match file_info.try_borrow() {
+            Ok(file_info) => file_info.ast.as_ref().map(|ast| AstUtils::find_function_records(ast, &function_name)).unwrap_or_default(),
+            Err(_) => vec![]
+        };
+        let Some(file_symbol) = function.borrow().get_file().and_then(|f| f.upgrade()) else {
+            return vec![];
+        };
+        //the receivers can be parameters of other callbacks, that can refer to this one
+        if CALLBACK_DEPTH.get() >= MAX_CALLBACK_DEPTH {
+            return vec![];
+        }
+        CALLBACK_DEPTH.set(CALLBACK_DEPTH.get() + 1);
+        let mut res = vec![];
+        for receiver in receivers.iter() {
+            //calls inside the function itself would evaluate the parameter from itself
+            if function_range.contains_range(receiver.range()) {
+                continue;
+            }
+            let scope = Symbol::get_scope_symbol(file_symbol.clone(), receiver.range().start().to_u32(), false);
+            for eval in Evaluation::eval_records(session, receiver, scope) {
+                if !res.iter().any(|e: &Evaluation| e.symbol.get_weak().weak.ptr_eq(&eval.symbol.get_weak().weak)) {
+                    res.push(eval);
+                }
+            }
+        }
+        CALLBACK_DEPTH.set(CALLBACK_DEPTH.get() - 1);
+        res
+    }
+
+    /* Evaluate the records of a recordset expression: an instance of the class of its model */
+    fn eval_records(session: &mut SessionInfo, recordset: &Expr, scope: Rc<RefCell<Symbol>>) -> Vec<Evaluation> {
+        let (evals, _) = Evaluation::eval_from_ast(session, recordset, scope, &recordset.range().start());
+        let mut res = vec![];
+        for eval in evals.iter() {
+            let symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for record in Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]).iter() {
+                let Some(class) = record.weak.upgrade() else {
+                    continue;
+                };
+                if class.borrow().typ() == SymType::CLASS && class.borrow().as_class_sym()._model.is_some() {
+                    res.push(Evaluation::eval_from_symbol(&record.weak, Some(true)));
+                }
+            }
+        }
+        res
+    }
+
+    /* Infer the return evaluations of a function that has none yet, by validating it if its file is built enough */
+    fn infer_return_evaluations(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) {
+        if function.borrow().evaluations().is_some() && function.borrow().evaluations().unwrap().len() == 0 {
+            if function.borrow().parent_file_or_function().as_ref().unwrap().upgrade().unwrap().borrow().build_status(BuildSteps::ODOO) == BuildStatus::DONE &&
+            function.borrow().build_status(BuildSteps::VALIDATION) == BuildStatus::PENDING { //TODO update with new step validation to lower it to localized level
+                let mut v = PythonValidator::new(function.clone());
+                v.validate(session);
+            }
+        }
+    }
+
+    /* Return the evaluation of an attribute given by the __getattr__ function of a module (PEP 562): the value returned
+    by the function, read when the evaluation is used, as it is only inferred at the validation of the function */
+    pub fn eval_module_getattr(getattr: &Rc<RefCell<Symbol>>) -> Evaluation {
+        Evaluation {
+            symbol: EvaluationSymbol::new_with_symbol(Rc::downgrade(getattr), false, HashMap::new(), None, Some(Evaluation::get_module_getattr_value)),
+            value: None,
+            range: None
+        }
+    }
+
+    /* Hook of eval_module_getattr: return the first value returned by the __getattr__ function */
+    fn get_module_getattr_value(session: &mut SessionInfo, evaluation_sym: &EvaluationSymbol, _context: &mut Option<Context>, diagnostics: &mut Vec<Diagnostic>, file_symbol: Option<Rc<RefCell<Symbol>>>) -> EvaluationSymbolWeak {
+        let unknown = EvaluationSymbolWeak::new(Weak::new(), Some(false), false);
+        let Some(getattr) = evaluation_sym.get_weak().weak.upgrade() else {
+            return unknown;
+        };
+        Evaluation::infer_return_evaluations(session, &getattr);
+        let returns = getattr.borrow().as_func().evaluations.clone();
+        for evaluation in returns.iter() {
+            let value = evaluation.symbol.get_symbol(session, &mut None, diagnostics, file_symbol.clone());
+            if !value.weak.is_expired() {
+                return value;
+            }
+        }
+        unknown
+    }
+
+    /* Return the evaluation of the call of an async function: an instance of typing.Coroutine, whose awaited value is the
+    value returned by the function (see get_awaited_evaluations). The context of the call is kept to evaluate it */
+    pub fn eval_coroutine(function: &Rc<RefCell<Symbol>>, context: Context) -> Evaluation {
+        let mut context = context;
+        context.insert(S!("coroutine"), ContextValue::SYMBOL(Rc::downgrade(function)));
+        Evaluation {
+            symbol: EvaluationSymbol::new_with_symbol(Weak::new(), true, context, None, Some(Evaluation::get_coroutine_class)),
+            value: None,
+            range: None
+        }
+    }
+
+    /* Hook of eval_coroutine: return an instance of typing.Coroutine */
+    fn get_coroutine_class(session: &mut SessionInfo, _evaluation_sym: &EvaluationSymbol, _context: &mut Option<Context>, _diagnostics: &mut Vec<Diagnostic>, _file_symbol: Option<Rc<RefCell<Symbol>>>) -> EvaluationSymbolWeak {
+        match session.sync_odoo.get_symbol(&(vec![S!("typing")], vec![S!("Coroutine")]), u32::MAX).last() {
+            Some(coroutine) => EvaluationSymbolWeak::new(Rc::downgrade(coroutine), Some(true), false),
+            None => EvaluationSymbolWeak::new(Weak::new(), Some(false), false)
+        }
+    }
+
+    /* Return the evaluations of the value given by awaiting a coroutine (see eval_coroutine): the values returned by the
+    async function, in the context of its call. The other awaitables are unknown, as their type arguments are not kept */
+    fn get_awaited_evaluations(session: &mut SessionInfo, coroutine: &Evaluation) -> Vec<Evaluation> {
+        let Some(ContextValue::SYMBOL(function)) = coroutine.symbol.context.get(&S!("coroutine")) else {
+            return vec![];
+        };
+        let Some(function) = function.upgrade() else {
+            return vec![];
+        };
+        Evaluation::infer_return_evaluations(session, &function);
+        let mut context = coroutine.symbol.context.clone();
+        context.remove(&S!("coroutine"));
+        let returns = function.borrow().as_func().evaluations.clone();
+        returns.into_iter().map(|mut eval| {
+            eval.symbol.context.extend(context.clone());
+            eval.range = coroutine.range;
+            eval
+        }).collect()
+    }
+
+    /* Return the symbols of the value of a property (@property or @functools.cached_property) used as the base of an
+    attribute: record.partner_display.name reads name on the value returned by partner_display. None if the symbol is
+    not a property */
+    fn get_property_value(session: &mut SessionInfo, symbol: &EvaluationSymbolWeak, diagnostics: &mut Vec<Diagnostic>, file: &Rc<RefCell<Symbol>>) -> Option<Vec<EvaluationSymbolWeak>> {
+        let function = symbol.weak.upgrade()?;
+        if function.borrow().typ() != SymType::FUNCTION || !function.borrow().as_func().is_property {
+            return None;
+        }
+        Evaluation::infer_return_evaluations(session, &function);
+        let returns = function.borrow().as_func().evaluations.clone();
+        let mut res = vec![];
+        for evaluation in returns.iter() {
+            let value = evaluation.symbol.get_symbol(session, &mut None, diagnostics, Some(file.clone()));
+            if !value.weak.is_expired() {
+                res.extend(Symbol::follow_ref(&value, session, &mut None, false, false, None, diagnostics));
+            }
+        }
+        Some(res)
+    }
+
+    /* Validate the arguments of the call against the parameters of the function. The unpacked arguments whose values are
+    known (see CallBinding::get_unpacked_values) are counted as their values, while the other ones prevent the missing and
+    unexpected arguments from being reported */
+    fn validate_call_arguments(session: &mut SessionInfo, function: &FunctionSymbol, exprCall: &ExprCall, on_object: Weak<RefCell<Symbol>>, from_module: Option<Rc<RefCell<Symbol>>>, is_on_instance: bool, unpacked: &HashMap<TextRange, UnpackedValues>) -> Vec<Diagnostic> {
         if function.is_overloaded() {
             return vec![];
         }
@@ -931,63 +1471,92 @@ impl Evaluation {
             arg_index += 1;
         }
         for arg in exprCall.arguments.args.iter() {
-            if arg.is_starred_expr() {
-                //TODO try to unpack the starred
-                return diagnostics;
+            //the values of an unpacked argument are not validated, only counted
+            let (value, count) = match arg {
+                Expr::Starred(starred) => match unpacked.get(&starred.value.range()) {
+                    Some(UnpackedValues::POSITIONAL(count)) => (None, *count),
+                    _ => return diagnostics
+                },
+                _ => (Some(arg), 1)
+            };
+            for _ in 0..count {
+                //match arg with argument from function
+                let function_arg = function.args.get(min(arg_index, vararg_index) as usize);
+                if function_arg.is_none() || function_arg.unwrap().arg_type == ArgumentType::KWORD_ONLY || function_arg.unwrap().arg_type == ArgumentType::KWARG {
+                    diagnostics.push(Diagnostic::new(
+                        Range::new(Position::new(exprCall.range().start().to_u32(), 0), Position::new(exprCall.range().end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::ERROR),
+                        Some(NumberOrString::String(S!("OLS30315"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("{} takes {} positional arguments, but at least {} is given", function.name, number_pos_arg, arg_index + 1),
+                        None,
+                        None,
+                    ));
+                    return diagnostics;
+                }
+                if let (Some(value), true) = (value, function_arg.unwrap().arg_type != ArgumentType::VARARG) {
+                    //positional or arg
+                    diagnostics.extend(Evaluation::validate_func_arg(session, function_arg.unwrap(), value, on_object.clone(), from_module.clone()));
+                }
+                arg_index += 1;
             }
-            //match arg with argument from function
-            let function_arg = function.args.get(min(arg_index, vararg_index) as usize);
-            if function_arg.is_none() || function_arg.unwrap().arg_type == ArgumentType::KWORD_ONLY || function_arg.unwrap().arg_type == ArgumentType::KWARG {
+        }
+        let min_arg_for_kword = arg_index;
+        let mut found_pos_arg_with_kw = arg_index;
+        let to_skip = min(min_arg_for_kword, vararg_index);
+        //the keywords of the call, with the keys of the known **values, whose values are not validated
+        let mut keywords: Vec<(String, Option<&Expr>, TextRange)> = vec![];
+        for arg in exprCall.arguments.keywords.iter() {
+            match &arg.arg {
+                Some(arg_identifier) => keywords.push((arg_identifier.id.to_string(), Some(&arg.value), arg.range())),
+                None => match unpacked.get(&arg.value.range()) { //a dictionnary of keywords, like in self.func(a, b, **any_kwargs)
+                    Some(UnpackedValues::KEYWORDS(keys)) => keywords.extend(keys.iter().map(|key| (key.clone(), None, arg.range()))),
+                    _ => found_pos_arg_with_kw = number_pos_arg
+                }
+            }
+        }
+        for (arg_identifier, value, range) in keywords.iter() {
+            //a parameter already given by a positional argument, or the bound instance, can't be given again by keyword
+            let given_by_position = function.args.iter().take(to_skip as usize).any(|func_arg| func_arg.arg_type == ArgumentType::ARG
+                && func_arg.symbol.upgrade().is_some_and(|symbol| symbol.borrow().name() == arg_identifier));
+            if given_by_position {
                 diagnostics.push(Diagnostic::new(
-                    Range::new(Position::new(exprCall.range().start().to_u32(), 0), Position::new(exprCall.range().end().to_u32(), 0)),
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
                     Some(DiagnosticSeverity::ERROR),
-                    Some(NumberOrString::String(S!("OLS30315"))),
+                    Some(NumberOrString::String(S!("OLS30343"))),
                     Some(EXTENSION_NAME.to_string()),
-                    format!("{} takes {} positional arguments, but at least {} is given", function.name, number_pos_arg, arg_index + 1),
+                    format!("{} got multiple values for argument '{}'", function.name, arg_identifier),
                     None,
                     None,
                 ));
-                return diagnostics;
+                continue;
             }
-            if function_arg.unwrap().arg_type != ArgumentType::VARARG {
-                //positional or arg
-                diagnostics.extend(Evaluation::validate_func_arg(session, function_arg.unwrap(), arg, on_object.clone(), from_module.clone()));
-            }
-            arg_index += 1;
-        }
-        let min_arg_for_kword = arg_index;
-        let mut found_pos_arg_with_kw = arg_index;
-        let to_skip = min(min_arg_for_kword, vararg_index);
-        for arg in exprCall.arguments.keywords.iter() {
-            if let Some(arg_identifier) = &arg.arg { //if None, arg is a dictionnary of keywords, like in self.func(a, b, **any_kwargs)
-                let mut found_one = false;
-                for func_arg in function.args.iter().skip(to_skip as usize) {
-                    if func_arg.symbol.upgrade().unwrap().borrow().name() == arg_identifier.id {
-                        diagnostics.extend(Evaluation::validate_func_arg(session, func_arg, &arg.value, on_object.clone(), from_module.clone()));
-                        if func_arg.arg_type == ArgumentType::ARG {
-                            found_pos_arg_with_kw += 1;
-                        }
-                        found_one = true;
-                        break;
+            let mut found_one = false;
+            for func_arg in function.args.iter().skip(to_skip as usize) {
+                if func_arg.symbol.upgrade().unwrap().borrow().name() == arg_identifier {
+                    if let Some(value) = value {
+                        diagnostics.extend(Evaluation::validate_func_arg(session, func_arg, value, on_object.clone(), from_module.clone()));
                     }
+                    if func_arg.arg_type == ArgumentType::ARG {
+                        found_pos_arg_with_kw += 1;
+                    }
+                    found_one = true;
+                    break;
                 }
-                if !found_one && kwarg_index == i32::MAX {
-                    diagnostics.push(Diagnostic::new(
-                        Range::new(Position::new(exprCall.range().start().to_u32(), 0), Position::new(exprCall.range().end().to_u32(), 0)),
-                        Some(DiagnosticSeverity::ERROR),
-                        Some(NumberOrString::String(S!("OLS30316"))),
-                        Some(EXTENSION_NAME.to_string()),
-                        format!("{} got an unexpected keyword argument '{}'", function.name, arg_identifier.id),
-                        None,
-                        None,
-                    ))
-                }
-            } else {
-                // if arg is None, it means that it is a **arg
-                found_pos_arg_with_kw = number_pos_arg;
+            }
+            if !found_one && kwarg_index == i32::MAX {
+                diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(exprCall.range().start().to_u32(), 0), Position::new(exprCall.range().end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::ERROR),
+                    Some(NumberOrString::String(S!("OLS30316"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("{} got an unexpected keyword argument '{}'", function.name, arg_identifier),
+                    None,
+                    None,
+                ))
             }
         }
-        if found_pos_arg_with_kw + 1 < number_pos_arg {
+        if found_pos_arg_with_kw < number_pos_arg {
             diagnostics.push(Diagnostic::new(
                 Range::new(Position::new(exprCall.range().start().to_u32(), 0), Position::new(exprCall.range().end().to_u32(), 0)),
                 Some(DiagnosticSeverity::ERROR),
@@ -1004,6 +1573,11 @@ impl Evaluation {
 
     fn validate_domain(session: &mut SessionInfo, on_object: Weak<RefCell<Symbol>>, from_module: Option<Rc<RefCell<Symbol>>>, value: &Expr) -> Vec<Diagnostic> {
         let mut diagnostics = vec![];
+        //domain=lambda self: [...] is evaluated when the domain is used
+        let value = match value {
+            Expr::Lambda(lambda) => &*lambda.body,
+            _ => value
+        };
         if !matches!(value, Expr::List(_)) {
             return diagnostics;
         }
@@ -1108,15 +1682,21 @@ impl Evaluation {
                                 false,
                                 false);
                             if symbols.is_empty() {
-                                diagnostics.push(Diagnostic::new(
+                                //the field can be one of the fields added dynamically to the model
+                                let severity = if object.borrow().has_dynamic_fields(session, from_module.clone()) {
+                                    DiagnosticSeverity::HINT
+                                } else {
+                                    DiagnosticSeverity::ERROR
+                                };
+                                diagnostics.push(Guard::set_guarded_name(Diagnostic::new(
                                     Range::new(Position::new(s.range().start().to_u32(), 0), Position::new(s.range().end().to_u32(), 0)),
-                                    Some(DiagnosticSeverity::ERROR),
+                                    Some(severity),
                                     Some(NumberOrString::String(S!("OLS30320"))),
                                     Some(EXTENSION_NAME.to_string()),
                                     format!("Invalid search domain field: {} is not a member of {}", name, object.borrow().name()),
                                     None,
                                     None,
-                                ));
+                                ), GuardedName::MEMBER(name.to_string())));
                                 break;
                             }
                             for s in symbols.iter() {
@@ -1184,6 +1764,15 @@ impl EvaluationSymbol {
         }
     }
 
+    /* Build an evaluation symbol from its parts, like the ones given by ptr(), to restore an evaluation (see ArchCache) */
+    pub fn new_from_ptr(sym: EvaluationSymbolPtr, context: Context, factory: Option<Weak<RefCell<Symbol>>>) -> Self {
+        Self { sym, context, factory, get_symbol_hook: None }
+    }
+
+    pub fn ptr(&self) -> &EvaluationSymbolPtr {
+        &self.sym
+    }
+
     pub fn is_instance(&self) -> Option<bool> {
         match &self.sym {
             EvaluationSymbolPtr::ANY => None,
@@ -1195,6 +1784,14 @@ impl EvaluationSymbol {
         }
     }
 
+    /* Return the symbol of the evaluation, if it is given directly (not self, an argument, a domain...) */
+    pub fn as_weak(&self) -> Option<&EvaluationSymbolWeak> {
+        match &self.sym {
+            EvaluationSymbolPtr::WEAK(w) => Some(w),
+            _ => None
+        }
+    }
+
     pub fn get_weak(&self) -> &EvaluationSymbolWeak {
         match &self.sym {
             EvaluationSymbolPtr::WEAK(w) => w,
@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::rc::Weak;
+
+use crate::core::evaluation::EvaluationSymbolWeak;
+use crate::core::symbols::symbol::Symbol;
+
+/* Maximum number of entries. Beyond, the outdated entries are removed, and the cache is cleared if they are still too many */
+const MAX_ENTRIES: usize = 50_000;
+
+/* Key of Symbol::follow_ref: the followed symbol, stop_on_type, stop_on_value and the max scope (0 if none) */
+pub type FollowRefKey = (usize, bool, bool, usize);
+/* The followed symbol, to detect a key reused by a new symbol, and the results. None if the symbol has nothing to follow */
+pub type FollowRefResult = (Weak<RefCell<Symbol>>, Option<Vec<EvaluationSymbolWeak>>);
+
+#[derive(Debug)]
+struct CacheEntry<V> {
+    value: V,
+    files: Vec<(usize, u64)>, //the files read by the evaluation, with their stamp when it was computed
+    models_stamp: Option<u64>, //the stamp of the registry when it was computed, if the evaluation read the models
+}
+
+/* Memo of the evaluations repeated by the requests, like the resolution of self.env to the Environment class, that is
+done again by every hover and every completion item. An entry records the files read by its evaluation, and is only
+valid while none of them is rebuilt: each build step done on a file gives it a new stamp, and the entries are checked
+lazily when they are read. The evaluations going through hooks (like env['sale.order']) also depend on the registry, that
+gets a new stamp when a model is created or gets a new class. Files are identified by the address of their symbol, and
+a new symbol reusing the address of a dropped file is always built, so it gets a new stamp before being read */
+#[derive(Debug)]
+pub struct EvaluationCache<K, V> {
+    stamp: u64,
+    file_stamps: HashMap<usize, u64>,
+    building: HashSet<usize>, //the files whose build step is in progress: the evaluations reading them are not stored
+    models_stamp: u64,
+    entries: HashMap<K, CacheEntry<V>>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Default for EvaluationCache<K, V> {
+    fn default() -> Self {
+        EvaluationCache::new()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> EvaluationCache<K, V> {
+
+    pub fn new() -> Self {
+        EvaluationCache {
+            stamp: 0,
+            file_stamps: HashMap::new(),
+            building: HashSet::new(),
+            models_stamp: 0,
+            entries: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /* Return the value of the key if none of the files it was computed from has been rebuilt since */
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let valid = match self.entries.get(key) {
+            Some(entry) => EvaluationCache::<K, V>::is_valid(&self.file_stamps, self.models_stamp, entry),
+            None => {
+                self.misses += 1;
+                return None;
+            }
+        };
+        if !valid {
+            self.entries.remove(key);
+            self.misses += 1;
+            return None;
+        }
+        self.hits += 1;
+        self.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    /* Store a value computed from the files, and from the registry if reads_models is true. The value must be inserted
+    after its computation, as the computation can build some of the files it reads. It is not stored if one of the files
+    is being built */
+    pub fn insert(&mut self, key: K, value: V, files: impl IntoIterator<Item = usize>, reads_models: bool) {
+        if self.entries.len() >= MAX_ENTRIES {
+            let (file_stamps, models_stamp) = (&self.file_stamps, self.models_stamp);
+            self.entries.retain(|_, entry| EvaluationCache::<K, V>::is_valid(file_stamps, models_stamp, entry));
+            if self.entries.len() >= MAX_ENTRIES {
+                self.entries.clear();
+            }
+        }
+        let mut entry_files: Vec<(usize, u64)> = vec![];
+        for file in files.into_iter() {
+            if self.building.contains(&file) {
+                return;
+            }
+            if !entry_files.iter().any(|(f, _)| *f == file) {
+                entry_files.push((file, self.file_stamps.get(&file).copied().unwrap_or(0)));
+            }
+        }
+        self.entries.insert(key, CacheEntry {
+            value,
+            files: entry_files,
+            models_stamp: if reads_models { Some(self.models_stamp) } else { None },
+        });
+    }
+
+    /* Invalidate the entries computed from the file, that is rebuilt or unloaded */
+    pub fn touch_file(&mut self, file: usize) {
+        self.stamp += 1;
+        self.file_stamps.insert(file, self.stamp);
+    }
+
+    /* A build step starts on the file: its symbols are changed until end_build */
+    pub fn start_build(&mut self, file: usize) {
+        self.touch_file(file);
+        self.building.insert(file);
+    }
+
+    pub fn end_build(&mut self, file: usize) {
+        self.touch_file(file);
+        self.building.remove(&file);
+    }
+
+    /* Invalidate the entries that read the registry, that has just been changed */
+    pub fn touch_models(&mut self) {
+        self.stamp += 1;
+        self.models_stamp = self.stamp;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.file_stamps.clear();
+        self.building.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn is_valid(file_stamps: &HashMap<usize, u64>, models_stamp: u64, entry: &CacheEntry<V>) -> bool {
+        entry.files.iter().all(|(file, stamp)| file_stamps.get(file).copied().unwrap_or(0) == *stamp) &&
+            entry.models_stamp.map_or(true, |stamp| stamp == models_stamp)
+    }
+}
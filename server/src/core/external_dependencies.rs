@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use crate::constants::BUILT_IN_LIBS;
+
+/* Distributions whose import name is not their name on PyPI */
+const IMPORT_NAMES: [(&str, &str); 15] = [
+    ("beautifulsoup4", "bs4"),
+    ("opencv-python", "cv2"),
+    ("pillow", "PIL"),
+    ("pycryptodome", "Crypto"),
+    ("pyjwt", "jwt"),
+    ("pyopenssl", "OpenSSL"),
+    ("pyserial", "serial"),
+    ("pyusb", "usb"),
+    ("python-dateutil", "dateutil"),
+    ("python-ldap", "ldap"),
+    ("python-magic", "magic"),
+    ("python-slugify", "slugify"),
+    ("python-stdnum", "stdnum"),
+    ("pyyaml", "yaml"),
+    ("scikit-learn", "sklearn"),
+];
+
+/* Packages required by Odoo itself (its requirements.txt), that the modules can import without declaring them */
+const ODOO_REQUIREMENTS: [&str; 44] = ["asn1crypto", "babel", "cbor2", "chardet", "cryptography", "dateutil", "decorator",
+    "docutils", "ebaysdk", "freezegun", "geoip2", "gevent", "greenlet", "idna", "jinja2", "ldap", "lxml",
+    "markupsafe", "num2words", "ofxparse", "openpyxl", "OpenSSL", "passlib", "PIL", "polib", "psutil", "psycopg2", "pydot",
+    "pyotp", "PyPDF2", "pypdf", "pytz", "qrcode", "reportlab", "requests", "rjsmin", "sass", "serial", "stdnum", "urllib3", "usb",
+    "vobject", "werkzeug", "xlsxwriter"];
+
+/* The python packages required by a module are declared in the external_dependencies of its manifest:
+{"python": ["stripe", "python-dateutil"]}. Odoo refuses to install the module if they can't be imported. The imports of
+the files of a module are checked against the manifest of this module: a declared package that is not installed is
+expected, and a third-party package that is not declared makes the installation succeed on a server that will then fail
+to load the module */
+pub struct ExternalDependencies {}
+
+impl ExternalDependencies {
+
+    /* Return the name a declared distribution is imported with: 'python-dateutil' is imported as 'dateutil' */
+    pub fn get_import_name(distribution: &str) -> String {
+        let distribution = distribution.split(['<', '>', '=', '!', '~', '[', ';', ' ']).next().unwrap_or_default().trim();
+        let lower = distribution.to_lowercase().replace('_', "-");
+        match IMPORT_NAMES.iter().find(|(name, _)| *name == lower) {
+            Some((_, import_name)) => import_name.to_string(),
+            None => distribution.replace('-', "_"),
+        }
+    }
+
+    /* Return the top-level package of an absolute import: 'stripe' for 'from stripe.api import Charge' */
+    pub fn get_imported_package(from_stmt: Option<&str>, name: &str, level: Option<u32>) -> Option<String> {
+        if level.unwrap_or(0) > 0 {
+            return None;
+        }
+        from_stmt.unwrap_or(name).split('.').next().filter(|package| !package.is_empty()).map(|package| package.to_string())
+    }
+
+    /* Return the distribution of the declared ones that provides the top-level package of an import */
+    pub fn find_declaration<'a>(package: &str, declared: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+        let package = package.to_lowercase();
+        declared.into_iter().find(|distribution| ExternalDependencies::get_import_name(distribution).to_lowercase() == package)
+    }
+
+    /* Return true if the package doesn't need to be declared: the standard library, odoo and its requirements */
+    pub fn is_provided(package: &str) -> bool {
+        package == "odoo" || package == "openerp" || BUILT_IN_LIBS.contains(&package) || ODOO_REQUIREMENTS.iter().any(|r| r.eq_ignore_ascii_case(package))
+    }
+
+    /* Return true if the file is installed in the interpreter by a third-party distribution, or is one of its stubs */
+    pub fn is_third_party_path(path: &str, stubs_dirs: &[String]) -> bool {
+        let path = Path::new(path);
+        path.components().any(|c| c.as_os_str() == "site-packages" || c.as_os_str() == "dist-packages") ||
+            stubs_dirs.iter().any(|stubs| path.starts_with(stubs))
+    }
+}
@@ -0,0 +1,239 @@
+use lsp_types::DiagnosticSeverity;
+use ruff_python_ast::{Expr, ExprCall, Keyword, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::api_decorators::ApiDecorators;
+use crate::core::compute_targets::ComputeTargets;
+
+/* The field classes whose first argument is the comodel */
+const RELATIONAL_FIELDS: [&str; 3] = ["Many2many", "Many2one", "One2many"];
+
+/* The rules checked on the fields of a model. Their severity can be changed with the fieldConsistencySeverity setting */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum FieldRule {
+    UNSTORED_COMPUTE_USED, //a computed field without store=True is used in _order or by a stored related field
+    READONLY_WITHOUT_INVERSE, //readonly=False on a computed field that is not stored and has no inverse
+    MISSING_DEPENDS, //the compute method of a stored field has no @api.depends
+    RELATED_AND_COMPUTE, //related= and compute= on the same field
+    UNASSIGNED_COMPUTE, //a compute method doesn't assign some of the fields it computes
+}
+
+impl FieldRule {
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            FieldRule::UNSTORED_COMPUTE_USED => "OLS20015",
+            FieldRule::READONLY_WITHOUT_INVERSE => "OLS20016",
+            FieldRule::MISSING_DEPENDS => "OLS20017",
+            FieldRule::RELATED_AND_COMPUTE => "OLS20018",
+            FieldRule::UNASSIGNED_COMPUTE => "OLS20026",
+        }
+    }
+
+    pub fn default_severity(&self) -> DiagnosticSeverity {
+        DiagnosticSeverity::WARNING
+    }
+}
+
+/* The keyword arguments of a field declaration that decide how its value is computed and stored, with their ranges */
+#[derive(Debug)]
+pub struct FieldDeclaration {
+    pub name: String,
+    pub range: TextRange,
+    pub field_type: String, //the class of the field: Char, Many2one...
+    pub comodel: Option<String>, //the model of the records of a relational field, if given by a string
+    pub compute: Option<(Option<String>, TextRange)>, //name of the method, if given by a string or a name
+    pub related: Option<(Option<String>, TextRange)>, //path of the related field, if given by a string
+    pub store: Option<(bool, TextRange)>,
+    pub readonly: Option<(bool, TextRange)>,
+    pub inverse: Option<TextRange>,
+    pub groups: Option<TextRange>,
+    pub recursive: bool, //recursive=True: the field depends on itself through a parent field, like parent_id.display_name
+}
+
+impl FieldDeclaration {
+
+    /* Return the field declared by the assignment, if its value is a call to a field class (fields.Char(...)) */
+    pub fn from_stmt(stmt: &Stmt) -> Option<FieldDeclaration> {
+        let (target, value) = match stmt {
+            Stmt::Assign(a) => (a.targets.first()?.as_name_expr()?, &*a.value),
+            Stmt::AnnAssign(a) => (a.target.as_name_expr()?, a.value.as_deref()?),
+            _ => return None
+        };
+        let call = FieldDeclaration::as_field_call(value)?;
+        let field_type = call.func.as_attribute_expr()?.attr.to_string();
+        let keyword = |name: &str| call.arguments.keywords.iter().find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == name));
+        let as_bool = |keyword: &Keyword| match &keyword.value {
+            Expr::BooleanLiteral(b) => Some((b.value, keyword.range())),
+            _ => None
+        };
+        let comodel = match keyword("comodel_name") {
+            Some(keyword) => keyword.value.as_string_literal_expr(),
+            None if RELATIONAL_FIELDS.contains(&field_type.as_str()) => call.arguments.args.first().and_then(|arg| arg.as_string_literal_expr()),
+            None => None
+        };
+        Some(FieldDeclaration {
+            name: target.id.to_string(),
+            range: target.range(),
+            comodel: comodel.map(|comodel| comodel.value.to_string()),
+            field_type,
+            compute: keyword("compute").map(|k| (match &k.value {
+                Expr::StringLiteral(s) => Some(s.value.to_string()),
+                Expr::Name(name) => Some(name.id.to_string()),
+                _ => None
+            }, k.range())),
+            related: keyword("related").map(|k| (k.value.as_string_literal_expr().map(|s| s.value.to_string()), k.range())),
+            store: keyword("store").and_then(as_bool),
+            readonly: keyword("readonly").and_then(as_bool),
+            inverse: keyword("inverse").map(|k| k.range()),
+            groups: keyword("groups").map(|k| k.range()),
+            recursive: keyword("recursive").and_then(as_bool).is_some_and(|(recursive, _)| recursive),
+        })
+    }
+
+    fn as_field_call(expr: &Expr) -> Option<&ExprCall> {
+        let Expr::Call(call) = expr else {
+            return None;
+        };
+        let Expr::Attribute(attr) = &*call.func else {
+            return None;
+        };
+        let is_field = attr.value.as_name_expr().is_some_and(|module| module.id.as_str() == "fields")
+            && attr.attr.as_str().starts_with(|c: char| c.is_ascii_uppercase());
+        if is_field { Some(call) } else { None }
+    }
+
+    pub fn is_relational(&self) -> bool {
+        RELATIONAL_FIELDS.contains(&self.field_type.as_str())
+    }
+
+    pub fn is_stored(&self) -> bool {
+        match self.store {
+            Some((store, _)) => store,
+            None => self.compute.is_none() && self.related.is_none()
+        }
+    }
+}
+
+/* A field declaration that doesn't behave as written */
+#[derive(Debug)]
+pub struct FieldIssue {
+    pub rule: FieldRule,
+    pub range: TextRange,
+    pub message: String,
+    pub related: Option<(TextRange, String)>, //the declaration involved, given as related information
+}
+
+/* Consistency of the store, compute, readonly, inverse and related arguments of the fields declared in the body of a
+model class, with the _order of the class and the compute methods it defines, that must assign all the fields they
+compute. Only the literal arguments are checked, and the compute methods defined in other classes are not followed. */
+pub struct FieldConsistency {}
+
+impl FieldConsistency {
+
+    pub fn check(body: &Vec<Stmt>) -> Vec<FieldIssue> {
+        let fields: Vec<FieldDeclaration> = body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+        let mut res = vec![];
+        let unstored_compute = |name: &str| fields.iter().find(|f| f.name == name && f.compute.is_some() && !f.is_stored());
+        //_order
+        if let Some((order, order_range)) = FieldConsistency::get_order(body) {
+            for name in order.split(',').filter_map(|part| part.split_whitespace().next()) {
+                if let Some(field) = unstored_compute(name) {
+                    res.push(FieldIssue {
+                        rule: FieldRule::UNSTORED_COMPUTE_USED,
+                        range: field.compute.as_ref().unwrap().1,
+                        message: format!("{} is computed and not stored, so it can't be used in _order", field.name),
+                        related: Some((order_range, "_order of the model".to_string())),
+                    });
+                }
+            }
+        }
+        for field in fields.iter() {
+            //stored related chains
+            if let (Some((Some(path), related_range)), true) = (&field.related, field.is_stored()) {
+                if let Some(used) = path.split('.').next().and_then(unstored_compute) {
+                    res.push(FieldIssue {
+                        rule: FieldRule::UNSTORED_COMPUTE_USED,
+                        range: used.compute.as_ref().unwrap().1,
+                        message: format!("{} is computed and not stored, but the stored related field {} depends on it", used.name, field.name),
+                        related: Some((*related_range, format!("related field {}", field.name))),
+                    });
+                }
+            }
+            if let (Some((_, compute_range)), Some((_, related_range))) = (&field.compute, &field.related) {
+                res.push(FieldIssue {
+                    rule: FieldRule::RELATED_AND_COMPUTE,
+                    range: *related_range,
+                    message: format!("{} is related and computed: the related value is computed by Odoo, so compute is ignored", field.name),
+                    related: Some((*compute_range, "compute argument".to_string())),
+                });
+            }
+            let Some((compute, compute_range)) = &field.compute else {
+                continue;
+            };
+            if let (Some((false, readonly_range)), None, false) = (field.readonly, field.inverse, field.is_stored()) {
+                res.push(FieldIssue {
+                    rule: FieldRule::READONLY_WITHOUT_INVERSE,
+                    range: readonly_range,
+                    message: format!("{} is editable, but it is computed and has no inverse: the edited values are lost", field.name),
+                    related: None,
+                });
+            }
+            if !field.is_stored() {
+                continue;
+            }
+            let method = compute.as_ref().and_then(|compute| body.iter().find_map(|stmt| match stmt {
+                Stmt::FunctionDef(f) if f.name.as_str() == compute.as_str() => Some(f),
+                _ => None
+            }));
+            if let Some(method) = method {
+                if ApiDecorators::find(&method.decorator_list, "depends").is_none() {
+                    res.push(FieldIssue {
+                        rule: FieldRule::MISSING_DEPENDS,
+                        range: *compute_range,
+                        message: format!("{} is stored, but its compute method {} has no @api.depends: the stored value is never recomputed", field.name, method.name),
+                        related: Some((method.name.range(), "compute method".to_string())),
+                    });
+                }
+            }
+        }
+        //compute methods
+        for stmt in body.iter() {
+            let Stmt::FunctionDef(method) = stmt else {
+                continue;
+            };
+            let targets = ComputeTargets::get_targets(&fields, method.name.as_str());
+            if targets.is_empty() || ComputeTargets::is_placeholder(&method.body) {
+                continue;
+            }
+            let assigned = ComputeTargets::get_assigned_fields(&method.body);
+            if assigned.dynamic {
+                continue;
+            }
+            let missing: Vec<&&FieldDeclaration> = targets.iter().filter(|field| !assigned.names.contains(&field.name)).collect();
+            let Some(first) = missing.first() else {
+                continue;
+            };
+            let names: Vec<&str> = missing.iter().map(|field| field.name.as_str()).collect();
+            res.push(FieldIssue {
+                rule: FieldRule::UNASSIGNED_COMPUTE,
+                range: method.name.range(),
+                message: format!("{} doesn't assign {}, that it computes", method.name, names.join(", ")),
+                related: Some((first.range, format!("field {}", first.name))),
+            });
+        }
+        res
+    }
+
+    /* Return the value of _order and the range of its declaration */
+    fn get_order(body: &Vec<Stmt>) -> Option<(String, TextRange)> {
+        body.iter().find_map(|stmt| match stmt {
+            Stmt::Assign(a) => match (a.targets.first().and_then(|t| t.as_name_expr()), &*a.value) {
+                (Some(target), Expr::StringLiteral(s)) if target.id.as_str() == "_order" => Some((s.value.to_string(), stmt.range())),
+                _ => None
+            },
+            _ => None
+        })
+    }
+}
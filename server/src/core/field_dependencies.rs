@@ -0,0 +1,262 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use ruff_python_ast::Stmt;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::api_decorators::ApiDecorators;
+use crate::core::compute_targets::ComputeTargets;
+use crate::core::field_consistency::FieldDeclaration;
+use crate::core::model_metadata::ModelMetadata;
+
+/* Depth of the traversals of the graph when the client doesn't give one */
+pub const DEFAULT_DEPENDENCY_DEPTH: u32 = 10;
+
+/* Maximum depth of the traversals of the graph, whatever the depth asked */
+pub const MAX_DEPENDENCY_DEPTH: u32 = 50;
+
+/* A field of a model, like amount_total of sale.order */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FieldRef {
+    pub model: String,
+    pub field: String,
+}
+
+impl FieldRef {
+
+    pub fn new(model: &str, field: &str) -> Self {
+        FieldRef { model: model.to_string(), field: field.to_string() }
+    }
+}
+
+/* A field declared in the body of a model class, with the paths of the fields its value depends on */
+#[derive(Debug, Clone)]
+pub struct DependentField {
+    pub field: FieldRef,
+    pub comodel: Option<String>,
+    pub related: Option<String>, //the path of a related field
+    pub depends: Vec<String>, //the paths given to the @api.depends of its compute method, if defined in the same class
+    pub decorator: Option<TextRange>, //the range of this @api.depends
+    pub recursive: bool,
+}
+
+/* Graph of the dependencies between the fields of the models, read from the literal declarations of the classes:
+    amount = fields.Float(compute='_compute_amount') with @api.depends('line_ids.price'): amount depends on line_ids, and
+    on the price of the comodel of line_ids
+    partner_name = fields.Char(related='partner_id.name'): partner_name depends on partner_id and the name of res.partner
+The fields are kept by file, so the graph of a model is updated when one of the files declaring its fields is rebuilt,
+like the StringIndex. The paths are resolved when the graph is traversed, with the comodels known at that time. */
+#[derive(Debug, Default)]
+pub struct FieldDependencyGraph {
+    files: HashMap<String, Vec<DependentField>>, //the fields declared by each file
+    models: HashMap<String, BTreeSet<String>>, //the files declaring fields of each model
+}
+
+impl FieldDependencyGraph {
+
+    pub fn new() -> Self {
+        FieldDependencyGraph::default()
+    }
+
+    /* Return the fields declared by the model classes of the statements, with their dependencies */
+    pub fn get_fields(stmts: &[Stmt]) -> Vec<DependentField> {
+        let mut res = vec![];
+        for stmt in stmts.iter() {
+            let Stmt::ClassDef(class) = stmt else {
+                continue;
+            };
+            let metadata = ModelMetadata::from_body(&class.body);
+            let model = match (metadata.name, metadata.inherit) {
+                (Some((name, _)), _) => name,
+                (None, Some((inherit, _))) if !inherit.is_empty() => inherit[0].clone(),
+                _ => continue
+            };
+            let fields: Vec<FieldDeclaration> = class.body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+            let mut depends: HashMap<&str, (Vec<String>, TextRange)> = HashMap::new();
+            for stmt in class.body.iter() {
+                let Stmt::FunctionDef(method) = stmt else {
+                    continue;
+                };
+                let Some((decorator, Some(call))) = ApiDecorators::find(&method.decorator_list, "depends") else {
+                    continue;
+                };
+                let paths: Vec<String> = call.arguments.args.iter().filter_map(|arg| arg.as_string_literal_expr())
+                    .map(|path| path.value.to_string()).collect();
+                for target in ComputeTargets::get_targets(&fields, method.name.as_str()).into_iter() {
+                    depends.insert(target.name.as_str(), (paths.clone(), decorator.range()));
+                }
+            }
+            for field in fields.iter() {
+                let (paths, decorator) = match depends.remove(field.name.as_str()) {
+                    Some((paths, decorator)) => (paths, Some(decorator)),
+                    None => (vec![], None)
+                };
+                res.push(DependentField {
+                    field: FieldRef::new(&model, &field.name),
+                    comodel: field.comodel.clone(),
+                    related: field.related.as_ref().and_then(|(path, _)| path.clone()),
+                    depends: paths,
+                    decorator,
+                    recursive: field.recursive,
+                });
+            }
+        }
+        res
+    }
+
+    /* Replace the fields declared by the file by the ones of its new ast */
+    pub fn update_file(&mut self, path: &str, ast: &[Stmt]) {
+        self.remove_file(path);
+        let fields = FieldDependencyGraph::get_fields(ast);
+        if fields.is_empty() {
+            return;
+        }
+        for field in fields.iter() {
+            self.models.entry(field.field.model.clone()).or_default().insert(path.to_string());
+        }
+        self.files.insert(path.to_string(), fields);
+    }
+
+    pub fn remove_file(&mut self, path: &str) {
+        for field in self.files.remove(path).unwrap_or_default().iter() {
+            let Some(files) = self.models.get_mut(&field.field.model) else {
+                continue;
+            };
+            files.remove(path);
+            if files.is_empty() {
+                self.models.remove(&field.field.model);
+            }
+        }
+    }
+
+    /* Return the declarations of the field, ordered by path: a field can be declared by several classes of the model */
+    pub fn get_declarations(&self, field: &FieldRef) -> Vec<&DependentField> {
+        let Some(files) = self.models.get(&field.model) else {
+            return vec![];
+        };
+        files.iter().filter_map(|path| self.files.get(path)).flatten().filter(|declaration| declaration.field == *field).collect()
+    }
+
+    /* Return the fields read by the dependencies of the field, without following them */
+    pub fn get_direct_dependencies(&self, field: &FieldRef) -> BTreeSet<FieldRef> {
+        let mut res = BTreeSet::new();
+        for declaration in self.get_declarations(field).into_iter() {
+            for path in declaration.depends.iter().chain(declaration.related.iter()) {
+                res.extend(self.resolve_path(&field.model, path));
+            }
+        }
+        res
+    }
+
+    /* Return the fields the field depends on, with their distance to it, ordered by distance then by model and field.
+    The boolean is true if the traversal was stopped by the depth */
+    pub fn get_dependencies(&self, field: &FieldRef, max_depth: u32) -> (Vec<(FieldRef, u32)>, bool) {
+        FieldDependencyGraph::traverse(field, max_depth, |current| self.get_direct_dependencies(current))
+    }
+
+    /* Return the fields that depend on the field, with their distance to it, ordered like get_dependencies */
+    pub fn get_dependents(&self, field: &FieldRef, max_depth: u32) -> (Vec<(FieldRef, u32)>, bool) {
+        let mut dependents: HashMap<FieldRef, BTreeSet<FieldRef>> = HashMap::new();
+        for declaration in self.files.values().flatten() {
+            for dependency in self.get_direct_dependencies(&declaration.field).into_iter() {
+                dependents.entry(dependency).or_default().insert(declaration.field.clone());
+            }
+        }
+        FieldDependencyGraph::traverse(field, max_depth, |current| dependents.get(current).cloned().unwrap_or_default())
+    }
+
+    /* Return the chain of fields leading from the field to itself, if it depends on itself and is not declared as
+    recursive. The chain starts and ends with the field */
+    pub fn find_cycle(&self, field: &FieldRef, max_depth: u32) -> Option<Vec<FieldRef>> {
+        if self.get_declarations(field).iter().any(|declaration| declaration.recursive) {
+            return None;
+        }
+        let mut parents: HashMap<FieldRef, FieldRef> = HashMap::new();
+        let mut level = vec![field.clone()];
+        for _ in 0..max_depth.min(MAX_DEPENDENCY_DEPTH) {
+            let mut next_level = vec![];
+            for current in level.iter() {
+                for dependency in self.get_direct_dependencies(current).into_iter() {
+                    if dependency == *field {
+                        let mut chain = vec![field.clone(), current.clone()];
+                        while let Some(parent) = parents.get(chain.last().unwrap()) {
+                            chain.push(parent.clone());
+                        }
+                        chain.reverse();
+                        return Some(chain);
+                    }
+                    if !parents.contains_key(&dependency) {
+                        parents.insert(dependency.clone(), current.clone());
+                        next_level.push(dependency);
+                    }
+                }
+            }
+            level = next_level;
+        }
+        None
+    }
+
+    /* Return the fields read by the path from the model: 'partner_id.country_id.code' reads partner_id on the model,
+    country_id on the comodel of partner_id and code on the comodel of country_id. The resolution stops at the first
+    field whose comodel is unknown */
+    fn resolve_path(&self, model: &str, path: &str) -> Vec<FieldRef> {
+        let mut res = vec![];
+        let mut model = model.to_string();
+        let mut parts = path.split('.').filter(|part| !part.is_empty()).peekable();
+        while let Some(part) = parts.next() {
+            let field = FieldRef::new(&model, part);
+            let comodel = match parts.peek() {
+                Some(_) => self.get_comodel(&field, 0),
+                None => None
+            };
+            res.push(field);
+            match comodel {
+                Some(comodel) => model = comodel,
+                None => break
+            }
+        }
+        res
+    }
+
+    /* Return the comodel of a relational field, given by its declaration or by the last field of its related path */
+    fn get_comodel(&self, field: &FieldRef, depth: u32) -> Option<String> {
+        if depth > MAX_DEPENDENCY_DEPTH {
+            return None;
+        }
+        let declarations = self.get_declarations(field);
+        if let Some(comodel) = declarations.iter().find_map(|declaration| declaration.comodel.clone()) {
+            return Some(comodel);
+        }
+        let related = declarations.iter().find_map(|declaration| declaration.related.as_ref())?;
+        let mut model = field.model.clone();
+        for part in related.split('.') {
+            model = self.get_comodel(&FieldRef::new(&model, part), depth + 1)?;
+        }
+        Some(model)
+    }
+
+    /* Breadth-first traversal of the graph from the field, each level being ordered */
+    fn traverse(field: &FieldRef, max_depth: u32, next: impl Fn(&FieldRef) -> BTreeSet<FieldRef>) -> (Vec<(FieldRef, u32)>, bool) {
+        let mut res = vec![];
+        let mut visited: HashSet<FieldRef> = HashSet::from([field.clone()]);
+        let mut level = vec![field.clone()];
+        let max_depth = max_depth.min(MAX_DEPENDENCY_DEPTH);
+        let mut depth = 0;
+        while !level.is_empty() {
+            let next_level: BTreeSet<FieldRef> = level.iter().flat_map(|current| next(current).into_iter())
+                .filter(|field| !visited.contains(field)).collect();
+            if next_level.is_empty() {
+                break;
+            }
+            if depth == max_depth {
+                return (res, true);
+            }
+            depth += 1;
+            for field in next_level.iter() {
+                visited.insert(field.clone());
+                res.push((field.clone(), depth));
+            }
+            level = next_level.into_iter().collect();
+        }
+        (res, false)
+    }
+}
@@ -0,0 +1,117 @@
+use std::ops::Range;
+use ruff_python_ast::{Expr, ExprCall, ExprStringLiteral};
+
+/* How field names are written in a string argument */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldNameFormat {
+    NAME,      // 'partner_id' or 'partner_id.name', only the first field belongs to the model of the receiver
+    PATH,      // 'partner_id/name', only the first field belongs to the model of the receiver
+    GROUPBY,   // 'date:month'
+    AGGREGATE, // 'amount:sum', 'total:sum(amount)' or '__count'
+    ORDER,     // 'name desc, id'
+}
+
+/* A parameter of an ORM method that takes field names of the model of the receiver, as a string or a list of strings */
+#[derive(Debug)]
+pub struct FieldNameArg {
+    pub method: &'static str,
+    pub parameter: &'static str,
+    pub position: usize, //position of the parameter, without self
+    pub format: FieldNameFormat,
+}
+
+/* Parameters that name fields. Adding a method here is enough to validate and complete its field names, and to find
+them in the references of the fields (see ReferencesFeature) */
+pub const FIELD_NAME_ARGS: [FieldNameArg; 20] = [
+    FieldNameArg { method: "read", parameter: "fields", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "fields_get", parameter: "allfields", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "export_data", parameter: "fields_to_export", position: 0, format: FieldNameFormat::PATH },
+    FieldNameArg { method: "flush_model", parameter: "fnames", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "invalidate_model", parameter: "fnames", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "search", parameter: "order", position: 3, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "search_fetch", parameter: "field_names", position: 1, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "search_fetch", parameter: "order", position: 4, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "search_read", parameter: "fields", position: 1, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "search_read", parameter: "order", position: 4, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "read_group", parameter: "fields", position: 1, format: FieldNameFormat::AGGREGATE },
+    FieldNameArg { method: "read_group", parameter: "groupby", position: 2, format: FieldNameFormat::GROUPBY },
+    FieldNameArg { method: "read_group", parameter: "orderby", position: 5, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "_read_group", parameter: "groupby", position: 1, format: FieldNameFormat::GROUPBY },
+    FieldNameArg { method: "_read_group", parameter: "aggregates", position: 2, format: FieldNameFormat::AGGREGATE },
+    FieldNameArg { method: "_read_group", parameter: "order", position: 6, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "mapped", parameter: "func", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "filtered", parameter: "func", position: 0, format: FieldNameFormat::NAME },
+    FieldNameArg { method: "sorted", parameter: "key", position: 0, format: FieldNameFormat::ORDER },
+    FieldNameArg { method: "grouped", parameter: "key", position: 0, format: FieldNameFormat::NAME },
+];
+
+pub struct FieldNameArgs {}
+
+impl FieldNameArgs {
+
+    /* Return the arguments of the call that name fields of the receiver, with their format */
+    pub fn get_arguments(call: &ExprCall) -> Vec<(&'static FieldNameArg, &Expr)> {
+        let Expr::Attribute(func) = &*call.func else {
+            return vec![];
+        };
+        let mut res = vec![];
+        for field_arg in FIELD_NAME_ARGS.iter().filter(|a| a.method == func.attr.as_str()) {
+            let keyword = call.arguments.keywords.iter()
+                .find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == field_arg.parameter))
+                .map(|k| &k.value);
+            if let Some(value) = keyword.or_else(|| call.arguments.args.get(field_arg.position)) {
+                if !value.is_starred_expr() {
+                    res.push((field_arg, value));
+                }
+            }
+        }
+        res
+    }
+
+    /* Return the string literals of the argument: the argument itself, or the items of a list or a tuple */
+    pub fn get_string_literals(expr: &Expr) -> Vec<&ExprStringLiteral> {
+        match expr {
+            Expr::StringLiteral(s) => vec![s],
+            Expr::List(l) => l.elts.iter().filter_map(|e| e.as_string_literal_expr()).collect(),
+            Expr::Tuple(t) => t.elts.iter().filter_map(|e| e.as_string_literal_expr()).collect(),
+            _ => vec![]
+        }
+    }
+
+    /* Return the field names written in the value of a string, with their byte range in the value. Only the first
+    field of a path is returned, as the others belong to other models. */
+    pub fn get_field_names(value: &str, format: FieldNameFormat) -> Vec<(String, Range<usize>)> {
+        let mut res = vec![];
+        let parts: Vec<(usize, &str)> = match format {
+            FieldNameFormat::ORDER => value.split(',').scan(0, |start, part| {
+                let res = (*start, part);
+                *start += part.len() + 1;
+                Some(res)
+            }).collect(),
+            _ => vec![(0, value)],
+        };
+        for (start, part) in parts.into_iter() {
+            let trimmed_start = part.len() - part.trim_start().len();
+            let (mut start, mut part) = (start + trimmed_start, part.trim());
+            match format {
+                FieldNameFormat::AGGREGATE => {
+                    //'total:sum(amount)' aggregates the field amount under the alias total
+                    if let (Some(open), Some(close)) = (part.find('('), part.rfind(')')) {
+                        if open < close {
+                            start += open + 1;
+                            part = &part[open + 1..close];
+                        }
+                    }
+                },
+                _ => {}
+            }
+            let end = part.find(|c: char| matches!(c, ':' | '.' | '/' | ' ' | '(')).unwrap_or(part.len());
+            let name = &part[..end];
+            if name.is_empty() || name.starts_with("__") || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            res.push((name.to_string(), start..start + end));
+        }
+        res
+    }
+}
@@ -1,21 +1,34 @@
 use lsp_types::notification::{Notification, PublishDiagnostics};
 use ropey::Rope;
-use ruff_python_ast::Mod;
-use ruff_python_parser::Mode;
+use ruff_python_ast::Expr;
 use lsp_types::{Diagnostic, DiagnosticSeverity, MessageType, NumberOrString, Position, PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent};
 use tracing::{error, warn};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::{collections::HashMap, fs};
+use std::time::{Duration, Instant, SystemTime};
+use std::{collections::{BTreeSet, HashMap, HashSet}, fs};
+use crate::core::context_keys::ContextKeyIndex;
+use crate::core::field_dependencies::FieldDependencyGraph;
+use crate::core::function_rebuild::FunctionSplice;
+use crate::core::guards::{Guard, GuardedName};
+use crate::core::ignore::{IgnoreMgr, ValidationScope};
+use crate::core::parse_pool::{ParsePool, ParsedSource};
 use crate::threads::SessionInfo;
-use crate::utils::PathSanitizer;
+use crate::utils::{path_to_uri, real_path, to_long_path, uri_to_path, PathSanitizer};
 use std::rc::Rc;
 use std::cell::RefCell;
 use crate::S;
 use crate::constants::*;
-use ruff_text_size::TextRange;
+use ruff_text_size::{Ranged, TextRange};
+use crate::core::python_string::StringSourceMap;
+use crate::core::string_index::StringIndex;
+use crate::core::symbols::symbol::Symbol;
+use crate::trace;
+
+/* Minimum delay between two flushes of the diagnostics during a rebuild */
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub struct FileInfo {
@@ -24,10 +37,16 @@ pub struct FileInfo {
     pub uri: String,
     pub valid: bool, // indicates if the file contains syntax error or not
     pub opened: bool,
+    pub errors_only: bool, //only the errors are published, for the files analyzed in single-file mode
     need_push: bool,
+    published: Option<(i32, Vec<Diagnostic>)>, //the version and the diagnostics last sent to the client
     text_rope: Option<ropey::Rope>,
     text_hash: u64,
+    indexed_hash: Option<u64>, //hash of the text the symbols of the file have been built from
+    last_validation: Option<SystemTime>, //end of the last validation of the file
     diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>,
+    guards: Vec<Guard>,
+    splice: Option<FunctionSplice>, //the method to parse again instead of the whole file at the next update
 }
 
 impl FileInfo {
@@ -38,10 +57,16 @@ impl FileInfo {
             uri,
             valid: true,
             opened: false,
+            errors_only: false,
             need_push: false,
+            published: None,
             text_rope: None,
             text_hash: 0,
+            indexed_hash: None,
+            last_validation: None,
             diagnostics: HashMap::new(),
+            guards: vec![],
+            splice: None,
         }
     }
     pub fn update(&mut self, session: &mut SessionInfo, uri: &str, content: Option<&Vec<TextDocumentContentChangeEvent>>, version: Option<i32>, force: bool) -> bool {
@@ -52,6 +77,7 @@ impl FileInfo {
         // -100 can be given as version number to indicates that the file has not been opened yet, and that we have to load it ourself
         // See https://github.com/Microsoft/language-server-protocol/issues/177
         // Return true if the update has been done and not discarded
+        let splice = self.splice.take().filter(|splice| Some(splice.version()) == version);
         if let Some(version) = version {
             if version == -100 {
                 self.version = 1;
@@ -71,7 +97,7 @@ impl FileInfo {
                 self.apply_change(change);
             }
         } else {
-            match fs::read_to_string(uri) {
+            match fs::read_to_string(to_long_path(uri)) {
                 Ok(content) => {
                     self.text_rope = Some(ropey::Rope::from(content.as_str()));
                 },
@@ -88,86 +114,278 @@ impl FileInfo {
         if old_hash == self.text_hash {
             return false;
         }
-        self._build_ast();
+        if !splice.is_some_and(|splice| self._splice_ast(&splice)) {
+            self._build_ast();
+        }
+        true
+    }
+
+    /* Set the method to parse again at the update to the version of the splice (see FunctionRebuild) */
+    pub fn set_splice(&mut self, splice: Option<FunctionSplice>) {
+        self.splice = splice;
+    }
+
+    fn _splice_ast(&mut self, splice: &FunctionSplice) -> bool {
+        if !self.valid {
+            return false;
+        }
+        let (Some(ast), Some(rope)) = (self.ast.as_mut(), self.text_rope.as_ref()) else {
+            return false;
+        };
+        if !splice.apply(rope, ast) {
+            return false;
+        }
+        self.guards = Guard::collect(ast);
+        self.replace_diagnostics(BuildSteps::SYNTAX, vec![]);
         true
     }
 
     pub fn _build_ast(&mut self) {
-        let mut diagnostics = vec![];
         let content = &self.text_rope.as_ref().unwrap().slice(..);
         let source = content.to_string(); //cast to string to get a version with all changes
-        let ast = ruff_python_parser::parse_unchecked(source.as_str(), Mode::Module);
-        self.valid = true;
-        for error in ast.errors().iter() {
-            self.valid = false;
-            diagnostics.push(Diagnostic::new(
-                Range{ start: Position::new(error.location.start().to_u32(), 0),
-                    end: Position::new(error.location.end().to_u32(), 0)},
-                Some(DiagnosticSeverity::ERROR),
-                Some(NumberOrString::String(S!("OLS30001"))),
-                Some(EXTENSION_NAME.to_string()),
-                error.error.to_string(),
-                None,
-                None));
-        }
-        match ast.into_syntax() {
-            Mod::Expression(_expr) => {
-                warn!("No support for expression-file only");
-                self.ast = None
-            },
-            Mod::Module(module) => {
-                self.ast = Some(module.body);
-            }
-        }
+        let (ast, valid, diagnostics, guards) = ParsedSource::parse(source.as_str());
+        self.valid = valid;
+        self.ast = ast;
+        self.guards = guards;
         self.replace_diagnostics(BuildSteps::SYNTAX, diagnostics);
     }
 
+    /* Update a file that is not opened with its source parsed by the ParsePool, like update would do from the disk */
+    pub fn update_from_source(&mut self, parsed: ParsedSource) -> bool {
+        self.diagnostics.clear();
+        self.text_rope = Some(parsed.rope);
+        let old_hash = self.text_hash;
+        self.text_hash = parsed.hash;
+        if old_hash == self.text_hash {
+            return false;
+        }
+        self.valid = parsed.valid;
+        self.ast = parsed.ast;
+        self.guards = parsed.guards;
+        self.replace_diagnostics(BuildSteps::SYNTAX, parsed.diagnostics);
+        true
+    }
+
+    /* Mark the current ast as the one the symbols of the file are built from */
+    pub fn mark_indexed(&mut self) {
+        self.indexed_hash = Some(self.text_hash);
+    }
+
+    /* Return true if the symbols of the file are built from the current ast */
+    pub fn is_indexed(&self) -> bool {
+        self.indexed_hash == Some(self.text_hash)
+    }
+
+    /* Mark the end of a validation of the file, the last step of its build */
+    pub fn mark_validated(&mut self) {
+        self.last_validation = Some(SystemTime::now());
+    }
+
+    pub fn last_validation(&self) -> Option<SystemTime> {
+        self.last_validation
+    }
+
+    /* Return the diagnostics of a build step, with their ranges still given as offsets */
+    pub fn get_step_diagnostics(&self, step: BuildSteps) -> Vec<Diagnostic> {
+        self.diagnostics.get(&step).cloned().unwrap_or_default()
+    }
+
     pub fn replace_diagnostics(&mut self, step: BuildSteps, diagnostics: Vec<Diagnostic>) {
         self.need_push = true;
-        self.diagnostics.insert(step, diagnostics);
+        self.diagnostics.insert(step, Guard::filter_diagnostics(&self.guards, diagnostics));
     }
 
     pub fn update_validation_diagnostics(&mut self, diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>) {
         self.need_push = true;
         for (key, value) in diagnostics.iter() {
-            self.diagnostics.entry(*key).or_insert_with(|| vec![]).extend(value.clone());
+            self.diagnostics.entry(*key).or_insert_with(|| vec![]).extend(Guard::filter_diagnostics(&self.guards, value.clone()));
         }
     }
 
+    /* Return true if the name is guarded (see Guard) at the given offset */
+    pub fn is_guarded(&self, name: &GuardedName, offset: u32) -> bool {
+        Guard::is_guarded(&self.guards, name, offset)
+    }
+
+    /* Convert the offsets of the diagnostic to positions, with the offsets of its related information in this file */
     fn update_range(&self, mut diagnostic: Diagnostic) -> Diagnostic {
         diagnostic.range.start = self.offset_to_position(diagnostic.range.start.line as usize);
         diagnostic.range.end = self.offset_to_position(diagnostic.range.end.line as usize);
+        if let Some(related_information) = diagnostic.related_information.as_mut() {
+            let uri = FileMgr::pathname2uri(&self.uri);
+            for information in related_information.iter_mut().filter(|information| information.location.uri == uri) {
+                information.location.range.start = self.offset_to_position(information.location.range.start.line as usize);
+                information.location.range.end = self.offset_to_position(information.location.range.end.line as usize);
+            }
+        }
         diagnostic
     }
 
+    /* Return all the diagnostics of the file, even the ones that are not published because of the maxFileDiagnostics setting */
+    pub fn get_diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.values().flat_map(|diagnostics| diagnostics.iter().map(|d| self.update_range(d.clone()))).collect()
+    }
+
+    /* Return the number of diagnostics that are not published because of the maxFileDiagnostics setting */
+    pub fn count_hidden_diagnostics(&self, max_diagnostics: u32) -> u32 {
+        let count = self.diagnostics.values().map(|diagnostics| diagnostics.len()).sum::<usize>() as u32;
+        match max_diagnostics {
+            0 => 0,
+            _ => count.saturating_sub(max_diagnostics)
+        }
+    }
+
+    fn severity_rank(severity: Option<DiagnosticSeverity>) -> u8 {
+        match severity {
+            Some(s) if s == DiagnosticSeverity::WARNING => 1,
+            Some(s) if s == DiagnosticSeverity::INFORMATION => 2,
+            Some(s) if s == DiagnosticSeverity::HINT => 3,
+            _ => 0
+        }
+    }
+
+    fn get_code(diagnostic: &Diagnostic) -> String {
+        match &diagnostic.code {
+            Some(NumberOrString::String(code)) => code.clone(),
+            Some(NumberOrString::Number(code)) => code.to_string(),
+            None => S!("")
+        }
+    }
+
+    /* The family of a code is the code without its last two digits: OLS200xx for the imports and the base classes,
+    OLS303xx for the evaluation of the expressions... */
+    fn get_code_family(code: &str) -> Option<&str> {
+        match code.starts_with("OLS") && code.len() == 8 {
+            true => Some(&code[..6]),
+            false => None
+        }
+    }
+
+    /* Order of the published diagnostics: by range, then by code */
+    fn compare_diagnostics(a: &Diagnostic, b: &Diagnostic) -> std::cmp::Ordering {
+        (a.range.start.line, a.range.start.character, a.range.end.line, a.range.end.character)
+            .cmp(&(b.range.start.line, b.range.start.character, b.range.end.line, b.range.end.character))
+            .then_with(|| FileInfo::get_code(a).cmp(&FileInfo::get_code(b)))
+    }
+
+    /* Merge the diagnostics of the build steps into the list that is published. The diagnostics with the same range and
+    code are reported once, with the highest severity. A diagnostic is superseded by a diagnostic of a later step on the
+    same range with a code of the same family, as the later step reports the same problem with more context (an import
+    not found, then failed to evaluate). The result is sorted by range then code, and doesn't depend on the order the
+    steps are given in, so the same diagnostics give the same payload at each publication */
+    pub fn merge_diagnostics(mut diagnostics: Vec<(BuildSteps, Diagnostic)>) -> Vec<(BuildSteps, Diagnostic)> {
+        diagnostics.sort_by(|(step_a, a), (step_b, b)| FileInfo::compare_diagnostics(a, b)
+            .then_with(|| step_a.cmp(step_b))
+            .then_with(|| a.message.cmp(&b.message)));
+        let mut unique: Vec<(BuildSteps, Diagnostic)> = vec![];
+        for (step, diagnostic) in diagnostics.into_iter() {
+            if let Some((last_step, last)) = unique.last_mut() {
+                if last.range == diagnostic.range && last.code == diagnostic.code {
+                    //the later step is kept on a tie
+                    if FileInfo::severity_rank(diagnostic.severity) <= FileInfo::severity_rank(last.severity) {
+                        *last_step = step;
+                        *last = diagnostic;
+                    }
+                    continue;
+                }
+            }
+            unique.push((step, diagnostic));
+        }
+        let mut res = vec![];
+        for (index, (step, diagnostic)) in unique.iter().enumerate() {
+            let code = FileInfo::get_code(diagnostic);
+            let family = FileInfo::get_code_family(&code);
+            //the diagnostics of the same range are next to each other
+            let same_range = unique[..index].iter().rev().take_while(|(_, d)| d.range == diagnostic.range)
+                .chain(unique[index + 1..].iter().take_while(|(_, d)| d.range == diagnostic.range));
+            let superseded = family.is_some() && same_range.into_iter().any(|(other_step, other)| *other_step > *step
+                && FileInfo::get_code_family(&FileInfo::get_code(other)) == family);
+            if !superseded {
+                res.push((*step, diagnostic.clone()));
+            }
+        }
+        res
+    }
+
+    /* Keep the max_diagnostics most important diagnostics: by severity, then by build step (syntax errors first). The
+    kept diagnostics are sorted by range then code, and the number of removed diagnostics is given by an information at
+    the top of the file. 0 means no limit */
+    pub fn limit_diagnostics(mut diagnostics: Vec<(BuildSteps, Diagnostic)>, max_diagnostics: u32) -> Vec<Diagnostic> {
+        if max_diagnostics == 0 || diagnostics.len() <= max_diagnostics as usize {
+            return diagnostics.into_iter().map(|(_, d)| d).collect();
+        }
+        diagnostics.sort_by_key(|(step, d)| (FileInfo::severity_rank(d.severity), *step));
+        let removed = diagnostics.len() - max_diagnostics as usize;
+        let mut res: Vec<Diagnostic> = diagnostics.into_iter().take(max_diagnostics as usize).map(|(_, d)| d).collect();
+        res.sort_by(FileInfo::compare_diagnostics);
+        res.push(Diagnostic::new(
+            Range::new(Position::new(0, 0), Position::new(0, 0)),
+            Some(DiagnosticSeverity::INFORMATION),
+            Some(NumberOrString::String(S!("OLS10002"))),
+            Some(EXTENSION_NAME.to_string()),
+            format!("{} diagnostics are not shown, as this file has more than {} diagnostics (see the maxFileDiagnostics setting)", removed, max_diagnostics),
+            None,
+            None,
+        ));
+        res
+    }
+
+    /* Publish the diagnostics again, even if they didn't change */
+    pub fn force_publish_diagnostics(&mut self, session: &mut SessionInfo) {
+        self.need_push = true;
+        self.published = None;
+        self.publish_diagnostics(session);
+    }
+
+    /* Mark the file to be published by the next flush of the diagnostics (see FileMgr::flush_diagnostics), if they
+    changed. The builders use this instead of publish_diagnostics, so a file built several times by a rebuild is
+    published once, with its final diagnostics */
+    pub fn schedule_publication(&self, session: &mut SessionInfo) {
+        if self.need_push {
+            session.sync_odoo.get_file_mgr().borrow_mut().dirty_files.insert(self.uri.clone());
+        }
+    }
+
+    /* Publish the merged diagnostics of the build steps (see merge_diagnostics). Nothing is sent if they are the same as
+    the last published ones, so the client doesn't redraw them */
     pub fn publish_diagnostics(&mut self, session: &mut SessionInfo) {
         if self.need_push {
             let mut all_diagnostics = Vec::new();
 
-            for diagnostics in self.diagnostics.values() {
-                for d in diagnostics.iter() {
-                    all_diagnostics.push(self.update_range(d.clone()));
+            for (step, diagnostics) in self.diagnostics.iter() {
+                for d in diagnostics.iter().filter(|d| !self.errors_only || d.severity == Some(DiagnosticSeverity::ERROR)) {
+                    all_diagnostics.push((*step, self.update_range(d.clone())));
                 }
             }
+            let all_diagnostics = FileInfo::merge_diagnostics(all_diagnostics);
+            let all_diagnostics = FileInfo::limit_diagnostics(all_diagnostics, session.sync_odoo.config.max_file_diagnostics);
+            self.need_push = false;
+            if self.published.as_ref().is_some_and(|(version, diagnostics)| *version == self.version && *diagnostics == all_diagnostics) {
+                return;
+            }
+            self.published = Some((self.version, all_diagnostics.clone()));
             let mut slash = "";
             if cfg!(windows) {
                 slash = "/";
             }
-            let uri = format!("file://{}{}", slash, self.uri);
+            let client_path = session.sync_odoo.get_file_mgr().borrow().get_client_path(&self.uri);
+            let uri = format!("file://{}{}", slash, client_path);
+            let has_diagnostics = !all_diagnostics.is_empty();
             session.send_notification::<PublishDiagnosticsParams>(PublishDiagnostics::METHOD, PublishDiagnosticsParams{
                 uri: lsp_types::Uri::from_str(&uri).expect("Unable to parse uri"),
                 diagnostics: all_diagnostics,
                 version: Some(self.version),
             });
-            self.need_push = false;
+            session.sync_odoo.get_file_mgr().borrow_mut().set_published(&self.uri, has_diagnostics);
         }
     }
 
+    /* The columns of the positions are given in UTF-16 code units, the default encoding of the LSP */
     pub fn offset_to_position_with_rope(rope: &Rope, offset: usize) -> Position {
         let char = rope.try_byte_to_char(offset).expect("unable to get char from bytes");
         let line = rope.try_char_to_line(char).ok().expect("unable to get line from char");
         let first_char_of_line = rope.try_line_to_char(line).expect("unable to get char from line");
-        let column = char - first_char_of_line;
+        let column = rope.char_to_utf16_cu(char) - rope.char_to_utf16_cu(first_char_of_line);
         Position::new(line as u32, column as u32)
     }
 
@@ -177,8 +395,66 @@ impl FileInfo {
     }
 
     pub fn position_to_offset_with_rope(rope: &Rope, line: u32, char: u32) -> usize {
+        let char_idx = FileInfo::position_to_char_with_rope(rope, line, char);
+        rope.try_char_to_byte(char_idx).expect("unable to get byte from char")
+    }
+
+    /* Return the index of the char at the given position, whose column is given in UTF-16 code units */
+    fn position_to_char_with_rope(rope: &Rope, line: u32, char: u32) -> usize {
         let line_char = rope.try_line_to_char(line as usize).expect("unable to get char from line");
-        rope.try_char_to_byte(line_char + char as usize).expect("unable to get byte from char")
+        let line_cu = rope.char_to_utf16_cu(line_char);
+        rope.try_utf16_cu_to_char(line_cu + char as usize).expect("unable to get char from utf-16 code units")
+    }
+
+    /* Return the string used for one level of indentation in the file, found from the first block opened with ':'.
+    Default to 4 spaces */
+    pub fn get_indent_unit(&self) -> String {
+        if let Some(rope) = self.text_rope.as_ref() {
+            let mut previous: Option<(String, bool)> = None; //indentation of the previous line, and if it opens a block
+            for line in rope.lines() {
+                let line = line.to_string();
+                let content = line.trim_end();
+                if content.trim_start().is_empty() || content.trim_start().starts_with('#') {
+                    continue;
+                }
+                let indent: String = content.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+                if let Some((previous_indent, true)) = previous.as_ref() {
+                    if indent.len() > previous_indent.len() && indent.starts_with(previous_indent.as_str()) {
+                        return indent[previous_indent.len()..].to_string();
+                    }
+                }
+                previous = Some((indent, content.ends_with(':')));
+            }
+        }
+        S!("    ")
+    }
+
+    /* Return the whole text of the file */
+    pub fn get_source(&self) -> Option<String> {
+        self.text_rope.as_ref().map(|rope| rope.to_string())
+    }
+
+    pub fn get_rope(&self) -> Option<&Rope> {
+        self.text_rope.as_ref()
+    }
+
+    /* Return the text of the file in the given range */
+    /* Return the first lines of the file, where the markers of generated files are written */
+    pub fn get_header(&self) -> Option<String> {
+        let rope = self.text_rope.as_ref()?;
+        let end = rope.len_lines().min(5);
+        Some(rope.slice(..rope.line_to_char(end)).to_string())
+    }
+
+    pub fn get_text(&self, range: &TextRange) -> Option<String> {
+        let rope = self.text_rope.as_ref()?;
+        rope.get_byte_slice(range.start().to_usize()..range.end().to_usize()).map(|s| s.to_string())
+    }
+
+    /* Return the map between the value of a string expression and its source code */
+    pub fn get_string_source_map(&self, expr: &Expr) -> Option<StringSourceMap> {
+        let source = self.get_text(&expr.range())?;
+        StringSourceMap::from_expr(&source, expr.range().start(), expr)
     }
 
     pub fn position_to_offset(&self, line: u32, char: u32) -> usize {
@@ -191,12 +467,16 @@ impl FileInfo {
             self.text_rope = Some(ropey::Rope::from_str(&change.text));
             return;
         }
-        let start_idx = self.text_rope.as_ref().unwrap().try_line_to_char(change.range.unwrap().start.line as usize).expect("Unable to get char position of line");
-        let start_idx = start_idx + change.range.unwrap().start.character as usize;
-        let end_idx = self.text_rope.as_ref().unwrap().try_line_to_char(change.range.unwrap().end.line as usize).expect("Unable to get char position of line");
-        let end_idx = end_idx + change.range.unwrap().end.character as usize;
-        self.text_rope.as_mut().unwrap().remove(start_idx .. end_idx);
-        self.text_rope.as_mut().unwrap().insert(start_idx, &change.text);
+        FileInfo::apply_change_with_rope(self.text_rope.as_mut().unwrap(), change);
+    }
+
+    /* Apply an incremental change, that must have a range, to the rope */
+    pub fn apply_change_with_rope(rope: &mut Rope, change: &TextDocumentContentChangeEvent) {
+        let range = change.range.unwrap();
+        let start_idx = FileInfo::position_to_char_with_rope(rope, range.start.line, range.start.character);
+        let end_idx = FileInfo::position_to_char_with_rope(rope, range.end.line, range.end.character);
+        rope.remove(start_idx .. end_idx);
+        rope.insert(start_idx, &change.text);
     }
 }
 
@@ -204,6 +484,19 @@ impl FileInfo {
 pub struct FileMgr {
     pub files: HashMap<String, Rc<RefCell<FileInfo>>>,
     workspace_folder: Vec<String>,
+    real_workspace_folders: HashMap<String, String>, // real paths of the workspace folders that are symlinks
+    tree_roots: Vec<(String, String)>, // (path in the symbol tree, real path) of the addons paths and of the symlinked modules
+    aliases: HashMap<String, String>, // canonical paths of the files reached through symlinks, by the path used to reach them
+    client_paths: HashMap<String, String>, // paths the client opened the files reached through symlinks with, by canonical path
+    published_uris: HashSet<String>, // paths of files for which the client currently displays diagnostics
+    dirty_files: BTreeSet<String>, // paths of files whose diagnostics are waiting for the next flush
+    last_flush: Option<Instant>,
+    ignore_mgr: IgnoreMgr,
+    validation_scope: ValidationScope,
+    pub string_index: StringIndex, //string literals of the python files, updated by their ARCH
+    pub context_keys: ContextKeyIndex, //context keys set by the python files, updated by their ARCH
+    pub field_dependencies: FieldDependencyGraph, //dependencies between the fields declared by the python files, updated by their ARCH
+    pub parse_pool: ParsePool, //external files parsed in advance by worker threads
 }
 
 impl FileMgr {
@@ -212,6 +505,19 @@ impl FileMgr {
         Self {
             files: HashMap::new(),
             workspace_folder: vec![],
+            real_workspace_folders: HashMap::new(),
+            tree_roots: vec![],
+            aliases: HashMap::new(),
+            client_paths: HashMap::new(),
+            published_uris: HashSet::new(),
+            dirty_files: BTreeSet::new(),
+            last_flush: None,
+            ignore_mgr: IgnoreMgr::new(),
+            validation_scope: ValidationScope::new(),
+            string_index: StringIndex::new(),
+            context_keys: ContextKeyIndex::new(),
+            field_dependencies: FieldDependencyGraph::new(),
+            parse_pool: ParsePool::new(),
         }
     }
 
@@ -229,6 +535,17 @@ impl FileMgr {
         }
     }
 
+    /* Return the FileInfo of the file that contains the symbol, if it is in cache */
+    pub fn get_file_info_of_symbol(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<Rc<RefCell<FileInfo>>> {
+        let file = symbol.borrow().get_file()?.upgrade()?;
+        let file = file.borrow();
+        let path = match file.typ() {
+            SymType::PACKAGE(_) => PathBuf::from(&file.paths()[0]).join(format!("__init__.py{}", file.as_package().i_ext())).sanitize(),
+            _ => file.paths()[0].clone(),
+        };
+        session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path)
+    }
+
     pub fn text_range_to_range(&mut self, session: &mut SessionInfo, path: &String, range: &TextRange) -> Range {
         let file = self.files.get(path);
         if let Some(file) = file {
@@ -238,7 +555,7 @@ impl FileMgr {
             }
         }
         //file not in cache, let's load rope on the fly
-        match fs::read_to_string(path) {
+        match fs::read_to_string(to_long_path(path)) {
             Ok(content) => {
                 let rope = ropey::Rope::from(content.as_str());
                 return Range {
@@ -252,75 +569,261 @@ impl FileMgr {
     }
 
     pub fn update_file_info(&mut self, session: &mut SessionInfo, uri: &str, content: Option<&Vec<TextDocumentContentChangeEvent>>, version: Option<i32>, force: bool) -> (bool, Rc<RefCell<FileInfo>>) {
+        //a file reached through a symlink is the file of its canonical path
+        let uri = self.get_canonical_path(uri);
+        let uri = uri.as_str();
         let file_info = self.files.entry(uri.to_string()).or_insert_with(|| Rc::new(RefCell::new(FileInfo::new(uri.to_string()))));
         let return_info = file_info.clone();
         //Do not modify the file if a version is not given but the file is opened
         let mut updated: bool = false;
         if (version.is_some() && version.unwrap() != -100) || !file_info.borrow().opened {
             let mut file_info_mut = (*return_info).borrow_mut();
-            updated = file_info_mut.update(session, uri, content, version, force);
+            //a file loaded from the disk for the first time may have been parsed in advance
+            let parsed = match content.is_none() && version.is_none() && file_info_mut.version == 0 {
+                true => self.parse_pool.take(uri),
+                false => None
+            };
+            updated = match parsed {
+                Some(parsed) => file_info_mut.update_from_source(parsed),
+                None => file_info_mut.update(session, uri, content, version, force)
+            };
             drop(file_info_mut);
         }
         (updated, return_info)
     }
 
-    pub fn delete_path(&mut self, session: &mut SessionInfo, uri: &String) {
-        let to_del = self.files.remove(uri);
-        if let Some(to_del) = to_del {
-            if self.is_in_workspace(uri) {
-                let mut to_del = (*to_del).borrow_mut();
-                to_del.replace_diagnostics(BuildSteps::SYNTAX, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ARCH, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ARCH_EVAL, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ODOO, vec![]);
-                to_del.replace_diagnostics(BuildSteps::VALIDATION, vec![]);
-                to_del.publish_diagnostics(session)
+    pub fn set_published(&mut self, path: &str, has_diagnostics: bool) {
+        if has_diagnostics {
+            self.published_uris.insert(path.to_string());
+        } else {
+            self.published_uris.remove(path);
+        }
+    }
+
+    pub fn is_published(&self, path: &str) -> bool {
+        self.published_uris.contains(path)
+    }
+
+    /* Return true if the diagnostics of the file are waiting for the next flush */
+    pub fn is_dirty(&self, path: &str) -> bool {
+        self.dirty_files.contains(path)
+    }
+
+    /* Publish the diagnostics of all the files scheduled since the last flush, in one batch ordered by path. Unless
+    forced, nothing is published if the last flush is too recent, so the files rebuilt several times in a row are
+    published once */
+    pub fn flush_diagnostics(session: &mut SessionInfo, force: bool) {
+        let files: Vec<Rc<RefCell<FileInfo>>> = {
+            let file_mgr = session.sync_odoo.get_file_mgr();
+            let mut file_mgr = file_mgr.borrow_mut();
+            if file_mgr.dirty_files.is_empty() || (!force && file_mgr.last_flush.is_some_and(|last_flush| last_flush.elapsed() < FLUSH_DELAY)) {
+                return;
             }
+            file_mgr.last_flush = Some(Instant::now());
+            let dirty_files = std::mem::take(&mut file_mgr.dirty_files);
+            dirty_files.iter().filter_map(|path| file_mgr.files.get(path).cloned()).collect()
+        };
+        for file in files.iter() {
+            file.borrow_mut().publish_diagnostics(session);
+        }
+    }
+
+    /* Send an empty set of diagnostics for the given path if the client still displays some for it */
+    fn publish_empty_diagnostics(&mut self, session: &mut SessionInfo, path: &String) {
+        if self.published_uris.remove(path) {
+            session.send_notification::<PublishDiagnosticsParams>(PublishDiagnostics::METHOD, PublishDiagnosticsParams{
+                uri: FileMgr::pathname2uri(&self.get_client_path(path)),
+                diagnostics: vec![],
+                version: None,
+            });
+        }
+    }
+
+    /* Drop the FileInfo of the given path (and of all files below it if it is a directory),
+    and clear the diagnostics the client is still displaying for them. */
+    pub fn delete_path(&mut self, session: &mut SessionInfo, uri: &String) {
+        let to_del: Vec<String> = self.files.keys().filter(|p| FileMgr::is_path_under(p, uri)).cloned().collect();
+        for path in to_del.iter() {
+            self.files.remove(path);
+            self.string_index.remove_file(path);
+            self.context_keys.remove_file(path);
+            self.field_dependencies.remove_file(path);
+        }
+        self.dirty_files.retain(|p| !FileMgr::is_path_under(p, uri));
+        let to_clean: Vec<String> = self.published_uris.iter().filter(|p| FileMgr::is_path_under(p, uri)).cloned().collect();
+        for path in to_clean.iter() {
+            self.publish_empty_diagnostics(session, path);
+        }
+        self.client_paths.retain(|p, _| !FileMgr::is_path_under(p, uri));
+        self.aliases.retain(|_, p| !FileMgr::is_path_under(p, uri));
+    }
+
+    /* Publish again the diagnostics of all the files that have some, like when the maxFileDiagnostics setting changes */
+    pub fn republish_diagnostics(session: &mut SessionInfo) {
+        let files: Vec<Rc<RefCell<FileInfo>>> = {
+            let file_mgr = session.sync_odoo.get_file_mgr();
+            let file_mgr = file_mgr.borrow();
+            file_mgr.files.iter().filter(|(path, _)| file_mgr.is_published(path)).map(|(_, f)| f.clone()).collect()
+        };
+        for file in files.iter() {
+            file.borrow_mut().force_publish_diagnostics(session);
         }
     }
 
     pub fn clear(&mut self, session: &mut SessionInfo) {
-        for file in self.files.values() {
-            if self.is_in_workspace(&file.borrow().uri) {
-                let mut to_del = (**file).borrow_mut();
-                to_del.replace_diagnostics(BuildSteps::SYNTAX, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ARCH, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ARCH_EVAL, vec![]);
-                to_del.replace_diagnostics(BuildSteps::ODOO, vec![]);
-                to_del.replace_diagnostics(BuildSteps::VALIDATION, vec![]);
-                to_del.publish_diagnostics(session)
-            }
+        let to_clean: Vec<String> = self.published_uris.iter().cloned().collect();
+        for path in to_clean.iter() {
+            self.publish_empty_diagnostics(session, path);
         }
         self.files.clear();
+        self.dirty_files.clear();
+        self.tree_roots.clear();
+        self.aliases.clear();
+        self.string_index = StringIndex::new();
+        self.context_keys = ContextKeyIndex::new();
+        self.field_dependencies = FieldDependencyGraph::new();
+        self.parse_pool.clear();
     }
 
     pub fn add_workspace_folder(&mut self, path: String) {
         let sanitized = PathBuf::from(path).sanitize();
         if !self.workspace_folder.contains(&sanitized) {
+            if let Some(real) = real_path(&sanitized).filter(|real| *real != sanitized) {
+                self.real_workspace_folders.insert(sanitized.clone(), real);
+            }
             self.workspace_folder.push(sanitized);
+            trace::set_workspace_roots(&self.workspace_folder);
         }
     }
 
-    pub fn remove_workspace_folder(&mut self, path: String) {
-        let index = self.workspace_folder.iter().position(|x| *x == path).unwrap();
+    /* Remove a workspace folder, and clean the files that are not part of the workspace anymore */
+    pub fn remove_workspace_folder(&mut self, session: &mut SessionInfo, path: String) {
+        let sanitized = PathBuf::from(path).sanitize();
+        let Some(index) = self.workspace_folder.iter().position(|x| *x == sanitized) else {
+            warn!("Trying to remove a workspace folder that is not registered: {}", sanitized);
+            return;
+        };
         self.workspace_folder.swap_remove(index);
+        self.real_workspace_folders.remove(&sanitized);
+        trace::set_workspace_roots(&self.workspace_folder);
+        let to_del: Vec<String> = self.files.keys().chain(self.published_uris.iter())
+            .filter(|p| FileMgr::is_path_under(p, &sanitized) && !self.is_in_workspace(p, false))
+            .cloned().collect();
+        for path in to_del.iter() {
+            self.delete_path(session, path);
+        }
+    }
+
+    pub fn is_path_under(path: &str, root: &str) -> bool {
+        path == root || (path.starts_with(root) && (root.ends_with('/') || path[root.len()..].starts_with('/')))
+    }
+
+    pub fn workspace_folders(&self) -> &Vec<String> {
+        &self.workspace_folder
+    }
+
+    pub fn set_ignore_mgr(&mut self, ignore_mgr: IgnoreMgr) {
+        self.ignore_mgr = ignore_mgr;
+    }
+
+    /* Return true if the path matches the ignore files of the workspace or the exclude setting */
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        self.ignore_mgr.is_ignored(path, is_dir)
     }
 
-    pub fn is_in_workspace(&self, path: &str) -> bool {
-        for p in self.workspace_folder.iter() {
-            if path.starts_with(p) {
+    pub fn set_validation_scope(&mut self, validation_scope: ValidationScope) -> ValidationScope {
+        std::mem::replace(&mut self.validation_scope, validation_scope)
+    }
+
+    /* Return true if the file is in the workspace but must not be validated, according to the validation settings */
+    pub fn is_validation_excluded(&self, file_info: &FileInfo) -> bool {
+        self.validation_scope.is_excluded(&file_info.uri, file_info.get_header().as_deref())
+    }
+
+    /* Return true if the file is in a workspace folder. A file reached through a symlink is in the workspace if its
+    real path is, and a symlinked workspace folder contains the files of its real path */
+    pub fn is_in_workspace(&self, path: &str, is_dir: bool) -> bool {
+        if self.is_ignored(path, is_dir) {
+            return false;
+        }
+        let real = self.get_real_path(path);
+        let folders = self.workspace_folder.iter().chain(self.real_workspace_folders.values());
+        for p in folders {
+            if path.starts_with(p) || real.as_ref().is_some_and(|real| real.starts_with(p)) {
                 return true;
             }
         }
         false
     }
 
-    pub fn pathname2uri(s: &String) -> lsp_types::Uri {
-        let mut slash = "";
-        if cfg!(windows) {
-            slash = "/";
+    /* Register a root of the symbol tree (an addons path, or a symlinked module directory), so the paths reaching its
+    files through other symlinks can be mapped to their path in the tree. A root whose symlinks loop is ignored */
+    pub fn add_tree_root(&mut self, path: &str) {
+        let Some(real) = real_path(path) else {
+            warn!("Unable to resolve the real path of {}", path);
+            return;
+        };
+        //the first root registered for a real path is kept, like an addons path and a symlink to it
+        if !self.tree_roots.iter().any(|(root, other)| root == path || *other == real) {
+            self.tree_roots.push((path.to_string(), real));
+            self.aliases.clear();
         }
-        let url = lsp_types::Uri::from_str(&format!("file://{}{}", slash, s));
+    }
+
+    /* Return the path of the file in the symbol tree. A path below a root of the tree is already canonical. Any other path
+    is resolved to its real path, and mapped back below the deepest root that contains it. As a deleted file can't be
+    resolved, its deepest existing parent is. The paths outside of all roots are kept as they are */
+    pub fn get_canonical_path(&mut self, path: &str) -> String {
+        if let Some(canonical) = self.aliases.get(path) {
+            return canonical.clone();
+        }
+        if self.tree_roots.is_empty() || self.tree_roots.iter().any(|(root, _)| FileMgr::is_path_under(path, root)) {
+            return path.to_string();
+        }
+        let Some(real) = Path::new(path).ancestors()
+            .find_map(|ancestor| real_path(ancestor.to_str()?).map(|real| format!("{}{}", real, &path[ancestor.as_os_str().len()..]))) else {
+            return path.to_string();
+        };
+        let root = self.tree_roots.iter()
+            .filter(|(_, real_root)| FileMgr::is_path_under(&real, real_root))
+            .max_by_key(|(_, real_root)| real_root.len());
+        let Some((root, real_root)) = root else {
+            return path.to_string();
+        };
+        let canonical = format!("{}{}", root, &real[real_root.len()..]);
+        if canonical != path {
+            self.aliases.insert(path.to_string(), canonical.clone());
+        }
+        canonical
+    }
+
+    /* Return the canonical path of a file opened by the client, and remember the path it has been opened with: its
+    diagnostics are published on it */
+    pub fn open_path(&mut self, path: &str) -> String {
+        let canonical = self.get_canonical_path(path);
+        if canonical == path {
+            self.client_paths.remove(path);
+        } else {
+            self.client_paths.insert(canonical.clone(), path.to_string());
+        }
+        canonical
+    }
+
+    /* Return the path the client knows the file by: the path it has been opened with, or its canonical path */
+    pub fn get_client_path(&self, path: &str) -> String {
+        self.client_paths.get(path).cloned().unwrap_or_else(|| path.to_string())
+    }
+
+    /* Return the real path of a file of the symbol tree, if it is below a symlinked root */
+    fn get_real_path(&self, path: &str) -> Option<String> {
+        let (root, real_root) = self.tree_roots.iter()
+            .filter(|(root, real_root)| root != real_root && FileMgr::is_path_under(path, root))
+            .max_by_key(|(root, _)| root.len())?;
+        Some(format!("{}{}", real_root, &path[root.len()..]))
+    }
+
+    pub fn pathname2uri(s: &String) -> lsp_types::Uri {
+        let url = lsp_types::Uri::from_str(&path_to_uri(s, cfg!(windows)));
         if let Ok(url) = url {
             return url;
         } else {
@@ -328,11 +831,15 @@ impl FileMgr {
         }
     }
 
+    /* Return the canonical path of the file of the uri (see get_canonical_path) */
+    pub fn uri2canonical(session: &mut SessionInfo, s: &str) -> String {
+        let path = FileMgr::uri2pathname(s);
+        session.sync_odoo.get_file_mgr().borrow_mut().get_canonical_path(&path)
+    }
+
     pub fn uri2pathname(s: &str) -> String {
-        if let Ok(url) = url::Url::parse(s) {
-            if let Ok(url) = url.to_file_path() {
-                return url.sanitize();
-            }
+        if let Some(path) = uri_to_path(s, cfg!(windows)) {
+            return path;
         }
         error!("Unable to extract path from uri: {s}");
         S!(s)
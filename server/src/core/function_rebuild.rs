@@ -0,0 +1,513 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{Diagnostic, TextDocumentContentChangeEvent};
+use ropey::Rope;
+use ruff_python_ast::{Mod, Stmt};
+use ruff_python_parser::Mode;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::core::dynamic_fields::DynamicFields;
+use crate::core::evaluation::Evaluation;
+use crate::core::file_mgr::FileInfo;
+use crate::core::odoo::SyncOdoo;
+use crate::core::symbols::symbol::Symbol;
+use crate::core::symbols::symbol_mgr::SectionRange;
+use crate::features::ast_utils::AstUtils;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer;
+
+/* Rebuild of a single method after an edit of its body.
+The body of a method is built on demand (see PythonArchBuilder::visit_func_def), and changing it doesn't change the
+public surface of the file. So when all the changes of a didChange are in the body of a method, only the symbols of
+this method are rebuilt: the other symbols of the file are kept, and its dependents are not invalidated.
+The method is found before the changes are applied (prepare), and the FileInfo parses again only the source of the
+method instead of the whole file (see FunctionSplice). The new ast is then checked (apply): if anything outside of the
+body of the method changed, the caller falls back to the rebuild of the whole file. The positions kept by the symbols
+after the method (ranges, sections, evaluations) and by the diagnostics are shifted by the change of its length. */
+pub struct FunctionRebuild {
+    file: Rc<RefCell<Symbol>>,
+    scopes: Vec<Rc<RefCell<Symbol>>>, //the classes enclosing the method, then the method
+    ast_indexes: Vec<u16>,
+    body_lengths: Vec<usize>, //number of statements of the module, then of each class enclosing the method
+    start: TextSize,
+    old_end: TextSize, //end of the method before the changes
+    new_end: TextSize, //end of the method once the changes are applied
+    dynamic_fields: DynamicFields, //dynamic fields of the top level class, that can be added by its methods
+    diagnostics: Vec<(BuildSteps, Vec<Diagnostic>)>, //diagnostics of the file, cleared when the changes are applied
+}
+
+impl FunctionRebuild {
+
+    /* Return the rebuild of the method whose body contains all the changes, or None if the file must be fully rebuilt.
+    Must be called before the changes of the given version are applied to the file */
+    pub fn prepare(session: &mut SessionInfo, path: &PathBuf, changes: &Vec<TextDocumentContentChangeEvent>, version: i32) -> Option<FunctionRebuild> {
+        if changes.is_empty() || changes.iter().any(|change| change.range.is_none()) {
+            return None;
+        }
+        if session.sync_odoo.tree_from_path(path).is_err() {
+            return None;
+        }
+        let file = session.sync_odoo.get_file_symbol(path)?;
+        {
+            let file_bw = file.borrow();
+            if file_bw.typ() != SymType::FILE
+                || file_bw.build_status(BuildSteps::ARCH) != BuildStatus::DONE
+                || file_bw.build_status(BuildSteps::ARCH_EVAL) != BuildStatus::DONE
+                || file_bw.build_status(BuildSteps::ODOO) == BuildStatus::IN_PROGRESS {
+                return None;
+            }
+        }
+        if [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO].iter().any(|step| session.sync_odoo.is_in_rebuild(&file, *step)) {
+            return None;
+        }
+        let file_info_rc = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path.sanitize())?;
+        let file_info = file_info_rc.borrow();
+        if !file_info.valid || !file_info.is_indexed() {
+            return None;
+        }
+        let ast = file_info.ast.as_ref()?;
+        let rope = file_info.get_rope()?;
+        //find the method containing the first change, directly in a class
+        let first_change = changes[0].range.unwrap().start;
+        let offset = TextSize::new(FileInfo::position_to_offset_with_rope(rope, first_change.line, first_change.character) as u32);
+        let mut ast_indexes = vec![];
+        let mut body_lengths = vec![];
+        let mut stmts = ast;
+        let func_stmt = loop {
+            let index = stmts.iter().position(|stmt| stmt.range().contains_inclusive(offset))?;
+            ast_indexes.push(index as u16);
+            body_lengths.push(stmts.len());
+            match &stmts[index] {
+                Stmt::ClassDef(class_stmt) => stmts = &class_stmt.body,
+                Stmt::FunctionDef(func_stmt) if ast_indexes.len() > 1 => break func_stmt,
+                _ => return None
+            }
+        };
+        //the body must start on a line after the signature, so changes of the signature or the decorators are excluded
+        let header_end = func_stmt.returns.as_ref().map(|returns| returns.range().end()).unwrap_or(func_stmt.parameters.range.end());
+        let body_line = rope.byte_to_line(header_end.to_usize()) + 1;
+        if body_line >= rope.len_lines() {
+            return None;
+        }
+        let body_line_start = rope.line_to_byte(body_line);
+        if func_stmt.body.first()?.range().start().to_usize() < body_line_start {
+            return None;
+        }
+        let mut new_rope = rope.clone();
+        let mut end = func_stmt.range.end().to_usize();
+        for change in changes.iter() {
+            let range = change.range.unwrap();
+            let change_start = FileInfo::position_to_offset_with_rope(&new_rope, range.start.line, range.start.character);
+            let change_end = FileInfo::position_to_offset_with_rope(&new_rope, range.end.line, range.end.character);
+            if change_start < body_line_start || change_end > end {
+                return None;
+            }
+            end = end - (change_end - change_start) + change.text.len();
+            FileInfo::apply_change_with_rope(&mut new_rope, change);
+        }
+        //the symbols of the classes enclosing the method, then of the method
+        let mut scopes = vec![];
+        let mut scope = file.clone();
+        for depth in 1..=ast_indexes.len() {
+            let (name, range) = match AstUtils::get_stmt_from_ast(ast, &ast_indexes[..depth])? {
+                Stmt::ClassDef(class_stmt) => (class_stmt.name.to_string(), class_stmt.range),
+                Stmt::FunctionDef(func_stmt) => (func_stmt.name.to_string(), func_stmt.range),
+                _ => return None
+            };
+            let symbol = scope.borrow().get_positioned_symbol(&name, &range)?;
+            scopes.push(symbol.clone());
+            scope = symbol;
+        }
+        if scope.borrow().typ() != SymType::FUNCTION || scope.borrow().ast_indexes() != Some(&ast_indexes) {
+            return None;
+        }
+        let dynamic_fields = DynamicFields::find(ast, ast[ast_indexes[0] as usize].as_class_def_stmt()?);
+        let diagnostics = [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO].into_iter()
+            .map(|step| (step, file_info.get_step_diagnostics(step)))
+            .collect();
+        let function_rebuild = FunctionRebuild {
+            file: file.clone(),
+            scopes,
+            ast_indexes,
+            body_lengths,
+            start: func_stmt.range.start(),
+            old_end: func_stmt.range.end(),
+            new_end: TextSize::new(end as u32),
+            dynamic_fields,
+            diagnostics,
+        };
+        let splice = FunctionSplice::new(rope, ast, &function_rebuild, version);
+        drop(file_info);
+        file_info_rc.borrow_mut().set_splice(splice);
+        Some(function_rebuild)
+    }
+
+    /* Rebuild the method from the new ast of the file. Return false if the new ast doesn't match the prepared rebuild,
+    in which case the whole file must be rebuilt */
+    pub fn apply(self, session: &mut SessionInfo, path: &PathBuf) -> bool {
+        let Some(file_info_rc) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path.sanitize()) else {
+            return false;
+        };
+        let file_info = file_info_rc.borrow();
+        if !file_info.valid {
+            return false;
+        }
+        let Some(ast) = file_info.ast.as_ref() else {
+            return false;
+        };
+        //the statements enclosing the method must be the same, so the ast indexes of the other symbols are still valid
+        for (depth, length) in self.body_lengths.iter().enumerate() {
+            let new_length = match depth {
+                0 => ast.len(),
+                _ => match AstUtils::get_stmt_from_ast(ast, &self.ast_indexes[..depth]) {
+                    Some(Stmt::ClassDef(class_stmt)) => class_stmt.body.len(),
+                    _ => return false
+                }
+            };
+            if new_length != *length {
+                return false;
+            }
+        }
+        let function = self.scopes.last().unwrap().clone();
+        let Some(Stmt::FunctionDef(func_stmt)) = AstUtils::get_stmt_from_ast(ast, &self.ast_indexes) else {
+            return false;
+        };
+        //a different end means that the changes moved statements in or out of the method
+        if func_stmt.name.as_str() != function.borrow().name() || func_stmt.range.start() != self.start || func_stmt.range.end() != self.new_end {
+            return false;
+        }
+        let Some(class_stmt) = ast[self.ast_indexes[0] as usize].as_class_def_stmt() else {
+            return false;
+        };
+        let dynamic_fields = DynamicFields::find(ast, class_stmt);
+        if dynamic_fields.names != self.dynamic_fields.names || dynamic_fields.has_unknown_names != self.dynamic_fields.has_unknown_names {
+            return false;
+        }
+        let doc_string = func_stmt.body.first()
+            .and_then(|stmt| stmt.as_expr_stmt())
+            .and_then(|expr| expr.value.as_string_literal_expr())
+            .map(|s| s.value.to_string());
+        //the ends of the method and of the enclosing classes follow the changes
+        let mut ranges = vec![];
+        for depth in 1..=self.ast_indexes.len() {
+            let (range, body) = match AstUtils::find_stmt_from_ast(ast, &self.ast_indexes[..depth]) {
+                Stmt::ClassDef(class_stmt) => (class_stmt.range, &class_stmt.body),
+                Stmt::FunctionDef(func_stmt) => (func_stmt.range, &func_stmt.body),
+                _ => return false
+            };
+            ranges.push((range, TextRange::new(body[0].range().start(), range.end())));
+        }
+        let shift = PositionShift { from: self.old_end.to_u32(), delta: self.new_end.to_u32() as i64 - self.old_end.to_u32() as i64 };
+        if shift.delta != 0 && !shift.can_shift(&self.file, &function) {
+            return false;
+        }
+        let path_str = path.sanitize();
+        if !path_str.ends_with(".pyi") {
+            let file_mgr = session.sync_odoo.get_file_mgr();
+            let mut file_mgr = file_mgr.borrow_mut();
+            file_mgr.string_index.update_file(&path_str, ast);
+            file_mgr.context_keys.update_file(&path_str, ast);
+            file_mgr.field_dependencies.update_file(&path_str, ast);
+        }
+        drop(file_info);
+        //the symbols of the file are changed: the evaluations read from them are outdated
+        let file_address = Rc::as_ptr(&self.file) as usize;
+        session.sync_odoo.evaluation_cache.start_build(file_address);
+        if shift.delta != 0 {
+            shift.shift_symbols(&self.file, &function);
+        }
+        for (step, mut diagnostics) in self.diagnostics.into_iter() {
+            if shift.delta != 0 {
+                for diagnostic in diagnostics.iter_mut() {
+                    shift.shift_diagnostic(diagnostic);
+                }
+            }
+            file_info_rc.borrow_mut().replace_diagnostics(step, diagnostics);
+        }
+        for (scope, (range, body_range)) in self.scopes.iter().zip(ranges.into_iter()) {
+            match *scope.borrow_mut() {
+                Symbol::Class(ref mut c) => {
+                    c.range = range;
+                    c.body_range = body_range;
+                },
+                Symbol::Function(ref mut f) => {
+                    f.range = range;
+                    f.body_range = body_range;
+                },
+                _ => {}
+            }
+        }
+        //unload the body of the method. The parameters are built with the file and kept
+        let content: Vec<Rc<RefCell<Symbol>>> = function.borrow().all_symbols()
+            .filter(|symbol| !(symbol.borrow().typ() == SymType::VARIABLE && symbol.borrow().as_variable().is_parameter))
+            .collect();
+        for symbol in content.into_iter() {
+            Symbol::unload(session, symbol);
+        }
+        {
+            let mut function_bw = function.borrow_mut();
+            let func = function_bw.as_func_mut();
+            func.sections.truncate(1);
+            func.deleted_names.clear();
+            func.evaluations = func.declared_evaluations.clone();
+            func.diagnostics.clear();
+            func.doc_string = doc_string;
+            func.arch_status = BuildStatus::PENDING;
+            func.arch_eval_status = BuildStatus::PENDING;
+            func.validation_status = BuildStatus::PENDING;
+        }
+        session.sync_odoo.evaluation_cache.end_build(file_address);
+        file_info_rc.borrow_mut().mark_indexed();
+        //the dependents of the file are not invalidated, as its public surface didn't change. The validation of the file builds the method again, as for the first build
+        session.sync_odoo.add_to_validations(self.file.clone());
+        SyncOdoo::process_rebuilds(session);
+        true
+    }
+}
+
+/* Parse of the source of a method prepared by a FunctionRebuild, replacing the method in the ast of the file.
+The source given to the parser starts at the line of the method, after a padding that keeps the offsets of the file:
+a comment, then a header for each class enclosing the method, at the indentation of the class. When the length of the
+method is unchanged, only the lines of the method are parsed. Otherwise the statements after the method are parsed
+too, from the method to the end of the file, as all their ranges are moved. The statements before the method are kept.
+If the parsed statements don't match the ast (a syntax error, or statements added or removed), the whole file is parsed */
+#[derive(Debug)]
+pub struct FunctionSplice {
+    version: i32, //version of the document the changes lead to
+    ast_indexes: Vec<u16>,
+    indents: Vec<String>, //indentation of the classes enclosing the method
+    line_start: usize, //start of the first line of the method
+    range: TextRange, //range of the method once the changes are applied
+    tail: bool, //the statements after the method are parsed too
+}
+
+impl FunctionSplice {
+
+    fn new(rope: &Rope, ast: &Vec<Stmt>, function_rebuild: &FunctionRebuild, version: i32) -> Option<FunctionSplice> {
+        let mut indents = vec![];
+        let mut line_start = 0;
+        for depth in 1..=function_rebuild.ast_indexes.len() {
+            let start = AstUtils::get_stmt_from_ast(ast, &function_rebuild.ast_indexes[..depth])?.range().start().to_usize();
+            line_start = rope.line_to_byte(rope.byte_to_line(start));
+            let indent = rope.get_byte_slice(line_start..start)?.to_string();
+            if !indent.chars().all(|c| c == ' ' || c == '\t') {
+                return None;
+            }
+            indents.push(indent);
+        }
+        indents.pop();
+        Some(FunctionSplice {
+            version,
+            ast_indexes: function_rebuild.ast_indexes.clone(),
+            indents,
+            line_start,
+            range: TextRange::new(function_rebuild.start, function_rebuild.new_end),
+            tail: function_rebuild.new_end != function_rebuild.old_end,
+        })
+    }
+
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /* Parse the method in the text of the file once the changes are applied, and replace it in the ast. Return false if
+    the ast must be built from the whole file. The ast may be partially changed in this case */
+    pub fn apply(&self, rope: &Rope, ast: &mut Vec<Stmt>) -> bool {
+        let headers: String = self.indents.iter().map(|indent| format!("{}class _:\n", indent)).collect();
+        let Some(padding) = self.line_start.checked_sub(headers.len() + 2) else {
+            return false;
+        };
+        let end = match self.tail {
+            true => rope.len_bytes(),
+            false => {
+                let line = rope.byte_to_line(self.range.end().to_usize()) + 1;
+                match line < rope.len_lines() {
+                    true => rope.line_to_byte(line),
+                    false => rope.len_bytes()
+                }
+            }
+        };
+        let Some(region) = rope.get_byte_slice(self.line_start..end) else {
+            return false;
+        };
+        let source = format!("#{}\n{}{}", " ".repeat(padding), headers, region);
+        let parsed = ruff_python_parser::parse_unchecked(&source, Mode::Module);
+        if !parsed.errors().is_empty() {
+            return false;
+        }
+        let Mod::Module(module) = parsed.into_syntax() else {
+            return false;
+        };
+        self.splice(ast, &self.ast_indexes, module.body)
+    }
+
+    /* Replace the statement at the indexes by the first parsed statement. The next parsed ones replace the statements
+    that follow it in the tail mode */
+    fn splice(&self, stmts: &mut Vec<Stmt>, ast_indexes: &[u16], parsed: Vec<Stmt>) -> bool {
+        let index = ast_indexes[0] as usize;
+        let mut parsed = parsed.into_iter();
+        let Some(new_stmt) = parsed.next() else {
+            return false;
+        };
+        let next_stmts: Vec<Stmt> = parsed.collect();
+        let same_length = match self.tail {
+            true => stmts.len() == index + 1 + next_stmts.len(),
+            false => index < stmts.len() && next_stmts.is_empty()
+        };
+        if !same_length {
+            return false;
+        }
+        if ast_indexes.len() == 1 {
+            match (&stmts[index], &new_stmt) {
+                (Stmt::FunctionDef(_), Stmt::FunctionDef(func_stmt)) if func_stmt.range == self.range => {},
+                _ => return false
+            }
+            stmts[index] = new_stmt;
+        } else {
+            let (Stmt::ClassDef(class_stmt), Stmt::ClassDef(new_class_stmt)) = (&mut stmts[index], new_stmt) else {
+                return false;
+            };
+            let class_end = new_class_stmt.range.end();
+            if !self.splice(&mut class_stmt.body, &ast_indexes[1..], new_class_stmt.body) {
+                return false;
+            }
+            if self.tail {
+                class_stmt.range = TextRange::new(class_stmt.range.start(), class_end);
+            }
+        }
+        if self.tail {
+            stmts.truncate(index + 1);
+            stmts.extend(next_stmts);
+        }
+        true
+    }
+}
+
+/* Shift of the positions after the end of a rebuilt method, when the changes modified its length */
+struct PositionShift {
+    from: u32,
+    delta: i64,
+}
+
+impl PositionShift {
+
+    fn offset(&self, offset: u32) -> u32 {
+        match offset >= self.from {
+            true => (offset as i64 + self.delta) as u32,
+            false => offset
+        }
+    }
+
+    fn range(&self, range: &TextRange) -> TextRange {
+        TextRange::new(TextSize::new(self.offset(range.start().to_u32())), TextSize::new(self.offset(range.end().to_u32())))
+    }
+
+    fn evaluations(&self, evaluations: &mut Vec<Evaluation>) {
+        for evaluation in evaluations.iter_mut() {
+            evaluation.range = evaluation.range.map(|range| self.range(&range));
+        }
+    }
+
+    fn sections(&self, sections: &mut Vec<SectionRange>, deleted_names: &mut HashMap<String, HashMap<u32, Vec<u32>>>) {
+        for section in sections.iter_mut() {
+            section.start = self.offset(section.start);
+        }
+        for offsets in deleted_names.values_mut().flat_map(|by_section| by_section.values_mut()) {
+            for offset in offsets.iter_mut() {
+                *offset = self.offset(*offset);
+            }
+        }
+    }
+
+    /* Return true if shift_symbols moves all the positions kept by the symbols after the method. The ones it doesn't know
+    would keep their old offsets: the sections of a package and the fields of the dataclasses. The whole file is rebuilt
+    instead */
+    fn can_shift(&self, file: &Rc<RefCell<Symbol>>, function: &Rc<RefCell<Symbol>>) -> bool {
+        let mut done: HashSet<*const RefCell<Symbol>> = HashSet::new();
+        let mut to_check = vec![file.clone()];
+        while let Some(symbol) = to_check.pop() {
+            if !done.insert(Rc::as_ptr(&symbol)) || Rc::ptr_eq(&symbol, function) {
+                continue;
+            }
+            let symbol_bw = symbol.borrow();
+            match &*symbol_bw {
+                Symbol::File(_) => to_check.extend(symbol_bw.all_symbols()),
+                Symbol::Package(_) => return false, //the sections of an __init__.py are not shifted
+                Symbol::Class(c) => {
+                    if c.range.end().to_u32() >= self.from && c.dataclass.is_some() {
+                        return false;
+                    }
+                    to_check.extend(symbol_bw.all_symbols());
+                },
+                Symbol::Function(f) => {
+                    to_check.extend(symbol_bw.all_symbols());
+                    to_check.extend(f.setter.iter().chain(f.deleter.iter()).chain(f.overloads.iter()).cloned());
+                },
+                _ => {}
+            }
+        }
+        true
+    }
+
+    /* Shift the positions of all the symbols of the file, except the content of the rebuilt method that is unloaded */
+    fn shift_symbols(&self, file: &Rc<RefCell<Symbol>>, function: &Rc<RefCell<Symbol>>) {
+        let mut done: HashSet<*const RefCell<Symbol>> = HashSet::new();
+        let mut to_shift = vec![file.clone()];
+        while let Some(symbol) = to_shift.pop() {
+            if !done.insert(Rc::as_ptr(&symbol)) {
+                continue;
+            }
+            if !Rc::ptr_eq(&symbol, function) {
+                match symbol.borrow().typ() {
+                    SymType::FILE | SymType::CLASS | SymType::FUNCTION => to_shift.extend(symbol.borrow().all_symbols()),
+                    _ => {}
+                }
+            }
+            match *symbol.borrow_mut() {
+                Symbol::File(ref mut f) => self.sections(&mut f.sections, &mut f.deleted_names),
+                Symbol::Class(ref mut c) => {
+                    c.range = self.range(&c.range);
+                    c.body_range = self.range(&c.body_range);
+                    self.sections(&mut c.sections, &mut c.deleted_names);
+                },
+                Symbol::Function(ref mut f) => {
+                    f.range = self.range(&f.range);
+                    f.body_range = self.range(&f.body_range);
+                    self.sections(&mut f.sections, &mut f.deleted_names);
+                    self.evaluations(&mut f.evaluations);
+                    self.evaluations(&mut f.declared_evaluations);
+                    self.evaluations(&mut f.decorators);
+                    for (_, range, _) in f.narrowed_attributes.iter_mut() {
+                        *range = self.range(range);
+                    }
+                    for default_value in f.args.iter_mut().filter_map(|arg| arg.default_value.as_mut()) {
+                        default_value.range = default_value.range.map(|range| self.range(&range));
+                    }
+                    if let Some(type_guard) = f.type_guard.as_mut() {
+                        self.evaluations(&mut type_guard.evaluations);
+                    }
+                    //the diagnostics of a validated method, collected with the ones of the file
+                    for diagnostic in f.diagnostics.values_mut().flat_map(|diagnostics| diagnostics.iter_mut()) {
+                        self.shift_diagnostic(diagnostic);
+                    }
+                    to_shift.extend(f.setter.iter().chain(f.deleter.iter()).chain(f.overloads.iter()).cloned());
+                },
+                Symbol::Variable(ref mut v) => {
+                    v.range = self.range(&v.range);
+                    self.evaluations(&mut v.evaluations);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /* The ranges of the diagnostics stored in the FileInfo are offsets, kept in their line field */
+    fn shift_diagnostic(&self, diagnostic: &mut Diagnostic) {
+        diagnostic.range.start.line = self.offset(diagnostic.range.start.line);
+        diagnostic.range.end.line = self.offset(diagnostic.range.end.line);
+    }
+}
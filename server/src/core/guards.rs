@@ -0,0 +1,196 @@
+use lsp_types::{Diagnostic, NumberOrString};
+use ruff_python_ast::{BoolOp, CmpOp, Expr, Stmt, UnaryOp};
+use ruff_text_size::{Ranged, TextRange};
+use serde_json::json;
+
+/* Diagnostics that can be suppressed by a guard on a model name */
+const GUARDED_MODEL_CODES: [&str; 3] = ["OLS30101", "OLS30102", "OLS30105"];
+/* Diagnostics that can be suppressed by a guard on a member name */
+const GUARDED_MEMBER_CODES: [&str; 2] = ["OLS30320", "OLS30326"];
+
+/* A name whose existence is checked by a guard */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardedName {
+    MEMBER(String), //hasattr(record, "name") or "name" in record._fields
+    MODEL(String), //self.env.get("model.name")
+}
+
+/* Optional dependencies are often used in branches that are only executed if the optional module is installed:
+    if hasattr(self.env["res.partner"], "vat"):
+        ...
+Inside such a branch, the guarded name can be used even if it is not declared in the dependencies of the module.
+A Guard is the range of code in which a name is guarded. */
+#[derive(Debug, Clone)]
+pub struct Guard {
+    pub range: TextRange,
+    pub name: GuardedName,
+}
+
+impl Guard {
+
+    /* Return the guards of the given statements, and of all the statements they contain */
+    pub fn collect(stmts: &Vec<Stmt>) -> Vec<Guard> {
+        let mut res = vec![];
+        for stmt in stmts.iter() {
+            Guard::collect_stmt(stmt, &mut res);
+        }
+        res
+    }
+
+    fn collect_stmt(stmt: &Stmt, res: &mut Vec<Guard>) {
+        match stmt {
+            Stmt::If(if_stmt) => {
+                let mut names = vec![];
+                Guard::guarded_names(&if_stmt.test, true, &mut names);
+                if let Some(range) = Guard::body_range(&if_stmt.body) {
+                    res.extend(names.into_iter().map(|name| Guard { range, name }));
+                }
+                //the elif and else clauses are executed if the test is false
+                let mut names = vec![];
+                Guard::guarded_names(&if_stmt.test, false, &mut names);
+                for clause in if_stmt.elif_else_clauses.iter() {
+                    res.extend(names.iter().map(|name| Guard { range: clause.range(), name: name.clone() }));
+                }
+                for stmt in if_stmt.body.iter() {
+                    Guard::collect_stmt(stmt, res);
+                }
+                for clause in if_stmt.elif_else_clauses.iter() {
+                    for stmt in clause.body.iter() {
+                        Guard::collect_stmt(stmt, res);
+                    }
+                }
+            },
+            Stmt::FunctionDef(f) => res.extend(Guard::collect(&f.body)),
+            Stmt::ClassDef(c) => res.extend(Guard::collect(&c.body)),
+            Stmt::For(f) => {
+                res.extend(Guard::collect(&f.body));
+                res.extend(Guard::collect(&f.orelse));
+            },
+            Stmt::While(w) => {
+                res.extend(Guard::collect(&w.body));
+                res.extend(Guard::collect(&w.orelse));
+            },
+            Stmt::With(w) => res.extend(Guard::collect(&w.body)),
+            Stmt::Try(t) => {
+                res.extend(Guard::collect(&t.body));
+                for handler in t.handlers.iter() {
+                    if let Some(handler) = handler.as_except_handler() {
+                        res.extend(Guard::collect(&handler.body));
+                    }
+                }
+                res.extend(Guard::collect(&t.orelse));
+                res.extend(Guard::collect(&t.finalbody));
+            },
+            Stmt::Match(m) => {
+                for case in m.cases.iter() {
+                    res.extend(Guard::collect(&case.body));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn body_range(body: &Vec<Stmt>) -> Option<TextRange> {
+        Some(TextRange::new(body.first()?.range().start(), body.last()?.range().end()))
+    }
+
+    /* Add to res the names that are guarded when the test evaluates to 'value' */
+    fn guarded_names(test: &Expr, value: bool, res: &mut Vec<GuardedName>) {
+        match test {
+            Expr::UnaryOp(op) if op.op == UnaryOp::Not => {
+                Guard::guarded_names(&op.operand, !value, res);
+            },
+            //all the operands of a 'and' are true if it is true, and all the operands of a 'or' are false if it is false
+            Expr::BoolOp(op) if (op.op == BoolOp::And) == value => {
+                for operand in op.values.iter() {
+                    Guard::guarded_names(operand, value, res);
+                }
+            },
+            Expr::Call(call) if value => {
+                let string_arg = |index: usize| call.arguments.args.get(index).and_then(|a| a.as_string_literal_expr()).map(|s| s.value.to_string());
+                match call.func.as_ref() {
+                    Expr::Name(name) if name.id.as_str() == "hasattr" && call.arguments.args.len() == 2 => {
+                        if let Some(member) = string_arg(1) {
+                            res.push(GuardedName::MEMBER(member));
+                        }
+                    },
+                    Expr::Attribute(attr) if attr.attr.as_str() == "get" && Guard::is_env(&attr.value) => {
+                        if let Some(model) = string_arg(0) {
+                            res.push(GuardedName::MODEL(model));
+                        }
+                    },
+                    _ => {}
+                }
+            },
+            Expr::Compare(compare) if compare.ops.len() == 1 && compare.comparators.len() == 1 => {
+                let is_in = match compare.ops[0] {
+                    CmpOp::In => true,
+                    CmpOp::NotIn => false,
+                    _ => return
+                };
+                if is_in != value {
+                    return;
+                }
+                let Some(member) = compare.left.as_string_literal_expr() else {
+                    return;
+                };
+                if let Expr::Attribute(attr) = &compare.comparators[0] {
+                    if attr.attr.as_str() == "_fields" {
+                        res.push(GuardedName::MEMBER(member.value.to_string()));
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /* Return true if the expression is 'env' or 'xxx.env' */
+    fn is_env(expr: &Expr) -> bool {
+        match expr {
+            Expr::Name(name) => name.id.as_str() == "env",
+            Expr::Attribute(attr) => attr.attr.as_str() == "env",
+            _ => false
+        }
+    }
+
+    /* Return true if the name is guarded at the given offset */
+    pub fn is_guarded(guards: &Vec<Guard>, name: &GuardedName, offset: u32) -> bool {
+        guards.iter().any(|g| g.name == *name && g.range.start().to_u32() <= offset && offset <= g.range.end().to_u32())
+    }
+
+    /* Store in the diagnostic the name it is about, so it can be suppressed if this name is guarded */
+    pub fn set_guarded_name(mut diagnostic: Diagnostic, name: GuardedName) -> Diagnostic {
+        diagnostic.data = Some(match name {
+            GuardedName::MEMBER(member) => json!({"member": member}),
+            GuardedName::MODEL(model) => json!({"model": model}),
+        });
+        diagnostic
+    }
+
+    /* Remove the diagnostics about a name that is guarded where they are raised.
+    Ranges of the diagnostics are expected to be temporary ranges, that hold offsets (see FileMgr::textRange_to_temporary_Range) */
+    pub fn filter_diagnostics(guards: &Vec<Guard>, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        if guards.is_empty() {
+            return diagnostics;
+        }
+        diagnostics.into_iter().filter(|diagnostic| {
+            let Some(NumberOrString::String(code)) = diagnostic.code.as_ref() else {
+                return true;
+            };
+            let Some(data) = diagnostic.data.as_ref() else {
+                return true;
+            };
+            let name = if GUARDED_MODEL_CODES.contains(&code.as_str()) {
+                data.get("model").and_then(|m| m.as_str()).map(|m| GuardedName::MODEL(m.to_string()))
+            } else if GUARDED_MEMBER_CODES.contains(&code.as_str()) {
+                data.get("member").and_then(|m| m.as_str()).map(|m| GuardedName::MEMBER(m.to_string()))
+            } else {
+                None
+            };
+            match name {
+                Some(name) => !Guard::is_guarded(guards, &name, diagnostic.range.start.line),
+                None => true
+            }
+        }).collect()
+    }
+}
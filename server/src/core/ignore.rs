@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use glob::{MatchOptions, Pattern};
+
+use crate::core::file_mgr::FileMgr;
+use crate::utils::PathSanitizer;
+
+pub const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/* A rule from a .gitignore/.ignore file or from the exclude setting.
+The supported syntax is the one of gitignore: '#' comments, '!' negations, trailing '/' for directories only,
+and patterns containing a '/' are relative to the directory of the file that declares them. */
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    base: String,
+    pattern: Pattern,
+    negate: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+
+    fn parse(base: &str, line: &str) -> Result<Option<IgnoreRule>, String> {
+        let mut line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let mut negate = false;
+        if let Some(l) = line.strip_prefix('!') {
+            negate = true;
+            line = l;
+        }
+        let mut dir_only = false;
+        if let Some(l) = line.strip_suffix('/') {
+            dir_only = true;
+            line = l;
+        }
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return Ok(None);
+        }
+        match Pattern::new(line) {
+            Ok(pattern) => Ok(Some(IgnoreRule {
+                base: base.to_string(),
+                pattern,
+                negate,
+                dir_only,
+                anchored,
+            })),
+            Err(e) => Err(format!("Invalid ignore pattern '{}': {}", line, e))
+        }
+    }
+
+    /* Return true if the rule matches the path. relative is the path relative to the base of the rule */
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        if self.anchored {
+            return self.pattern.matches_with(relative, options);
+        }
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        self.pattern.matches_with(name, options)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IgnoreMgr {
+    rules: Vec<IgnoreRule>,
+    roots: Vec<String>, //the workspace folders: the rules only apply to their content
+}
+
+impl IgnoreMgr {
+
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            roots: vec![],
+        }
+    }
+
+    /* Build the rules from the ignore files of each workspace folder and of its subdirectories, and from the exclude
+    globs. Exclude globs are relative to the workspace folders, and come after the ignore files so a negation in an
+    ignore file can't include an excluded path. Return the errors encountered while parsing the rules. */
+    pub fn load(workspace_folders: &Vec<String>, exclude: &Vec<String>) -> (Self, Vec<String>) {
+        let mut rules = vec![];
+        let mut globs = vec![];
+        let mut errors = vec![];
+        for folder in workspace_folders.iter() {
+            IgnoreMgr::parse_globs(folder, exclude, &mut globs, &mut errors);
+        }
+        for folder in workspace_folders.iter() {
+            IgnoreMgr::load_dir(&PathBuf::from(folder), &globs, &mut rules, &mut errors);
+        }
+        rules.extend(globs);
+        (Self { rules, roots: workspace_folders.clone() }, errors)
+    }
+
+    /* Build the rules from globs only, relative to the workspace folders */
+    pub fn from_globs(workspace_folders: &Vec<String>, globs: &Vec<String>) -> (Self, Vec<String>) {
+        let mut rules = vec![];
+        let mut errors = vec![];
+        for folder in workspace_folders.iter() {
+            IgnoreMgr::parse_globs(folder, globs, &mut rules, &mut errors);
+        }
+        (Self { rules, roots: workspace_folders.clone() }, errors)
+    }
+
+    fn parse_globs(folder: &str, globs: &Vec<String>, rules: &mut Vec<IgnoreRule>, errors: &mut Vec<String>) {
+        for glob in globs.iter() {
+            match IgnoreRule::parse(folder, glob) {
+                Ok(Some(rule)) => rules.push(rule),
+                Ok(None) => {},
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    /* Load the ignore files of the directory, then of its subdirectories that are not ignored. The rules of a nested
+    file come after the rules of its parents, so they take precedence like in git. Symlinks are not followed */
+    fn load_dir(dir: &Path, globs: &[IgnoreRule], rules: &mut Vec<IgnoreRule>, errors: &mut Vec<String>) {
+        let base = dir.sanitize();
+        for ignore_file in IGNORE_FILES.iter() {
+            let path = dir.join(ignore_file);
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            for line in content.lines() {
+                match IgnoreRule::parse(&base, line) {
+                    Ok(Some(rule)) => rules.push(rule),
+                    Ok(None) => {},
+                    Err(e) => errors.push(format!("{} in {}", e, path.sanitize())),
+                }
+            }
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) || entry.file_name() == ".git" {
+                continue;
+            }
+            let sub_dir = entry.path();
+            let sub_path = sub_dir.sanitize();
+            if IgnoreMgr::match_rules(rules, &sub_path, true) || IgnoreMgr::match_rules(globs, &sub_path, true) {
+                continue;
+            }
+            IgnoreMgr::load_dir(&sub_dir, globs, rules, errors);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /* Return true if the path, or one of its parent directories inside its workspace folder, is ignored. is_dir is given
+    by the caller, as the rules ending with '/' only match directories */
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let Some(root) = self.roots.iter().filter(|root| FileMgr::is_path_under(path, root)).max_by_key(|root| root.len()) else {
+            return false; //the rules are relative to the workspace folders
+        };
+        let start = root.trim_end_matches('/').len() + 1;
+        if start >= path.len() {
+            return false;
+        }
+        for (index, _) in path[start..].match_indices('/') {
+            if IgnoreMgr::match_rules(&self.rules, &path[..start + index], true) {
+                return true;
+            }
+        }
+        IgnoreMgr::match_rules(&self.rules, path, is_dir)
+    }
+
+    fn match_rules(rules: &[IgnoreRule], path: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in rules.iter() {
+            if rule.negate != ignored {
+                continue; //the rule can't change the result
+            }
+            let Some(relative) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            let Some(relative) = relative.strip_prefix('/') else {
+                continue; //same prefix, but not a subdirectory
+            };
+            if rule.matches(relative, is_dir) {
+                ignored = !rule.negate;
+            }
+        }
+        ignored
+    }
+}
+
+/* Suffixes and header comments of the files that are generated by tools, and that should not be validated */
+pub const GENERATED_SUFFIXES: [&str; 2] = ["_pb2.py", "_pb2.pyi"];
+pub const GENERATED_MARKERS: [&str; 2] = ["# generated by", "# @generated"];
+
+/* Files of the workspace that are indexed, but whose validation is skipped (vendored libraries, generated code...).
+Unlike the ignored files, they are still built up to the ARCH_EVAL step, so they can be navigated and used by the other
+files. When include globs are given, only the files that match them are validated. */
+#[derive(Debug, Clone)]
+pub struct ValidationScope {
+    exclude: IgnoreMgr,
+    include: IgnoreMgr,
+    exclude_generated: bool,
+}
+
+impl ValidationScope {
+
+    pub fn new() -> Self {
+        Self {
+            exclude: IgnoreMgr::new(),
+            include: IgnoreMgr::new(),
+            exclude_generated: false,
+        }
+    }
+
+    pub fn load(workspace_folders: &Vec<String>, exclude: &Vec<String>, include: &Vec<String>, exclude_generated: bool) -> (Self, Vec<String>) {
+        let (exclude, mut errors) = IgnoreMgr::from_globs(workspace_folders, exclude);
+        let (include, include_errors) = IgnoreMgr::from_globs(workspace_folders, include);
+        errors.extend(include_errors);
+        (Self { exclude, include, exclude_generated }, errors)
+    }
+
+    /* Return true if the validation of the file must be skipped. header is the beginning of the file, if it is known */
+    pub fn is_excluded(&self, path: &str, header: Option<&str>) -> bool {
+        if !self.include.is_empty() && !self.include.is_ignored(path, false) {
+            return true;
+        }
+        if self.exclude.is_ignored(path, false) {
+            return true;
+        }
+        self.exclude_generated && ValidationScope::is_generated(path, header)
+    }
+
+    pub fn is_generated(path: &str, header: Option<&str>) -> bool {
+        if GENERATED_SUFFIXES.iter().any(|suffix| path.ends_with(suffix)) {
+            return true;
+        }
+        let Some(header) = header else {
+            return false;
+        };
+        header.lines().take(5).any(|line| {
+            let line = line.trim().to_lowercase();
+            GENERATED_MARKERS.iter().any(|marker| line.starts_with(marker))
+        })
+    }
+}
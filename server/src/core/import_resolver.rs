@@ -4,7 +4,8 @@ use tracing::error;
 use std::collections::HashSet;
 use std::rc::Rc;
 use std::cell::RefCell;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use ruff_text_size::TextRange;
 use ruff_python_ast::{Alias, Identifier};
@@ -12,6 +13,7 @@ use crate::{constants::*, S};
 use crate::threads::SessionInfo;
 use crate::utils::{is_dir_cs, is_file_cs, PathSanitizer};
 
+use super::method_references::MethodReferences;
 use super::odoo::SyncOdoo;
 use super::symbols::symbol::Symbol;
 
@@ -21,6 +23,7 @@ pub struct ImportResult {
     pub symbol: Rc<RefCell<Symbol>>,
     pub file_tree: Tree,
     pub range: TextRange,
+    pub from_getattr: bool, //the name is not declared by the module, but given by its __getattr__ function (PEP 562)
 }
 
 fn resolve_import_stmt_hook(alias: &Alias, from_symbol: &Option<Rc<RefCell<Symbol>>>, session: &mut SessionInfo, source_file_symbol: &Rc<RefCell<Symbol>>, from_stmt: Option<&Identifier>, level: Option<u32>, diagnostics: &mut Option<&mut Vec<Diagnostic>>) -> Option<ImportResult>{
@@ -67,7 +70,8 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
             found: false,
             symbol: fallback_sym.clone(),
             file_tree: (file_tree.clone(), vec![]),
-            range: alias.range.clone()
+            range: alias.range.clone(),
+            from_getattr: false,
         })
     }
     if from_symbol.is_none() {
@@ -106,6 +110,9 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
                     name_symbol = name_symbol_vec.get(0).cloned();
                 }
                 if name_symbol.is_none() {
+                    if from_stmt.is_some() && _resolve_getattr(from_symbol.as_ref().unwrap(), &mut result[name_index as usize]) {
+                        continue;
+                    }
                     result[name_index as usize].symbol = fallback_sym.clone();
                     continue;
                 }
@@ -139,6 +146,9 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
             //TODO what if multiple values?
             name_symbol = next_symbol.as_ref().unwrap().borrow_mut().get_symbol(&(vec![], name_last_name), u32::MAX).get(0).cloned();
             if name_symbol.is_none() {
+                if from_stmt.is_some() && _resolve_getattr(next_symbol.as_ref().unwrap(), &mut result[name_index as usize]) {
+                    continue;
+                }
                 result[name_index as usize].symbol = fallback_sym.clone();
                 continue;
             }
@@ -153,6 +163,18 @@ pub fn resolve_import_stmt(session: &mut SessionInfo, source_file_symbol: &Rc<Re
     return result;
 }
 
+/* Resolve a name imported from a module that does not declare it to the __getattr__ function of the module (PEP 562),
+if it has one. Return true if the name is resolved */
+fn _resolve_getattr(module: &Rc<RefCell<Symbol>>, result: &mut ImportResult) -> bool {
+    let Some(getattr) = module.borrow().get_module_getattr() else {
+        return false;
+    };
+    result.found = true;
+    result.symbol = getattr;
+    result.from_getattr = true;
+    true
+}
+
 pub fn find_module(session: &mut SessionInfo, odoo_addons: Rc<RefCell<Symbol>>, name: &String) -> Option<Rc<RefCell<Symbol>>> {
     let paths = (*odoo_addons).borrow().paths().clone();
     for path in paths.iter() {
@@ -179,6 +201,18 @@ pub fn find_module(session: &mut SessionInfo, odoo_addons: Rc<RefCell<Symbol>>,
     None
 }
 
+/* Return true if the top-level package can be imported from the paths of the interpreter */
+pub fn is_importable(session: &mut SessionInfo, package: &str) -> bool {
+    let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+    _get_or_create_symbol(session, root, &vec![package.to_string()], None).0.is_some()
+}
+
+/* Return the file, package or namespace of the tree, creating it like an import if it is not loaded yet */
+pub fn get_or_create_module_symbol(session: &mut SessionInfo, tree: &Vec<String>) -> Option<Rc<RefCell<Symbol>>> {
+    let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+    _get_or_create_symbol(session, root, tree, None).0
+}
+
 fn _resolve_packages(file_path: &String, file_tree: &Tree, file_sym_type: &SymType, level: Option<u32>, from_stmt: Option<&Identifier>) -> Vec<String> {
     let mut first_part_tree: Vec<String> = vec![];
     if level.is_some() && level.unwrap() > 0 {
@@ -243,6 +277,7 @@ fn _resolve_new_symbol(session: &mut SessionInfo, parent: Rc<RefCell<Symbol>>, n
         return Ok((*parent).borrow_mut().add_new_compiled(session, &sym_name, &S!("")));
     }
     let paths = (*parent).borrow().paths().clone();
+    let mut namespace_paths: Vec<String> = vec![];
     for path in paths.iter() {
         let mut full_path = Path::new(path.as_str()).join(name);
         for stub in session.sync_odoo.stubs_dirs.iter() {
@@ -250,30 +285,33 @@ fn _resolve_new_symbol(session: &mut SessionInfo, parent: Rc<RefCell<Symbol>>, n
                 full_path = full_path.join(name);
             }
         }
-        if is_dir_cs(full_path.sanitize()) {
-            // if is_dir_cs(full_path.to_str().unwrap().to_string() + "-stubs") {
-            //     full_path.set_file_name(full_path.file_name().unwrap().to_str().unwrap().to_string() + "-stubs");
-            // }
+        //a package of stubs installed next to a package (PEP 561) shadows it
+        if (*parent).borrow().get_tree().0.is_empty() {
+            if let Some(stubs) = _create_stub_package(session, &full_path, &parent, name) {
+                SyncOdoo::rebuild_arch_now(session, &stubs);
+                return Ok(stubs);
+            }
+        }
+        let is_dir = is_dir_cs(full_path.sanitize());
+        if is_dir {
             let _rc_symbol = Symbol::create_from_path(session, &full_path, parent.clone(), false);
             if _rc_symbol.is_some() {
                 let _arc_symbol = _rc_symbol.unwrap();
                 SyncOdoo::rebuild_arch_now(session, &_arc_symbol);
                 return Ok(_arc_symbol);
             }
-        } else if is_file_cs(full_path.with_extension("py").sanitize()) {
-            let _arc_symbol = Symbol::create_from_path(session, &full_path.with_extension("py"), parent.clone(), false);
-            if _arc_symbol.is_some() {
-                let _arc_symbol = _arc_symbol.unwrap();
-                SyncOdoo::rebuild_arch_now(session, &_arc_symbol);
-                return Ok(_arc_symbol);
-            }
-        } else if is_file_cs(full_path.with_extension("pyi").sanitize()) {
-            let _arc_symbol = Symbol::create_from_path(session, &full_path.with_extension("pyi"), parent.clone(), false);
+        }
+        if let Some(file_path) = _get_module_file(session, &full_path) {
+            let _arc_symbol = Symbol::create_from_path(session, &file_path, parent.clone(), false);
             if _arc_symbol.is_some() {
                 let _arc_symbol = _arc_symbol.unwrap();
                 SyncOdoo::rebuild_arch_now(session, &_arc_symbol);
                 return Ok(_arc_symbol);
             }
+        } else if is_dir {
+            //a directory without __init__.py is a portion of a namespace package (PEP 420), unless a regular package or a
+            //module is found in the next paths
+            namespace_paths.push(full_path.sanitize());
         } else if !(*parent).borrow().get_tree().0.is_empty() {
             if cfg!(windows) {
                 for entry in glob((full_path.sanitize() + "*.pyd").as_str()).expect("Failed to read glob pattern") {
@@ -296,9 +334,46 @@ fn _resolve_new_symbol(session: &mut SessionInfo, parent: Rc<RefCell<Symbol>>, n
             }
         }
     }
+    if let Some((first, others)) = namespace_paths.split_first() {
+        let namespace = (*parent).borrow_mut().add_new_namespace(session, &sym_name, first);
+        for other in others.iter() {
+            namespace.borrow_mut().add_path(other.clone());
+        }
+        return Ok(namespace);
+    }
     return Err("Symbol not found".to_string())
 }
 
+/* Return the file of a module: its stub (.pyi) if there is one, as its annotations are more precise than the
+implementation, or else its implementation (.py). The implementations of the workspace are kept, as they are the files
+being edited. */
+fn _get_module_file(session: &mut SessionInfo, full_path: &PathBuf) -> Option<PathBuf> {
+    let implementation = full_path.with_extension("py");
+    let stub = full_path.with_extension("pyi");
+    let has_implementation = is_file_cs(implementation.sanitize());
+    if is_file_cs(stub.sanitize()) && !(has_implementation && session.sync_odoo.get_file_mgr().borrow().is_in_workspace(&implementation.sanitize(), false)) {
+        return Some(stub);
+    }
+    if has_implementation { Some(implementation) } else { None }
+}
+
+/* Create the symbol of the package of stubs of a top-level package (name-stubs, PEP 561), if it is installed. Partial
+stubs (py.typed containing 'partial') only type some modules of the package: the package itself is used, as its
+modules can't be merged with the stubs. */
+fn _create_stub_package(session: &mut SessionInfo, full_path: &PathBuf, parent: &Rc<RefCell<Symbol>>, name: &String) -> Option<Rc<RefCell<Symbol>>> {
+    let stubs_path = PathBuf::from(full_path.sanitize() + "-stubs");
+    if !is_dir_cs(stubs_path.sanitize()) || !stubs_path.join("__init__.pyi").exists() {
+        return None;
+    }
+    let partial = fs::read_to_string(stubs_path.join("py.typed")).is_ok_and(|content| content.lines().any(|line| line.trim() == "partial"));
+    if partial && (is_dir_cs(full_path.sanitize()) || is_file_cs(full_path.with_extension("py").sanitize())) {
+        return None;
+    }
+    let package = parent.borrow_mut().add_new_python_package(session, name, &stubs_path.sanitize());
+    package.borrow_mut().as_package_mut().set_i_ext("i".to_string());
+    Some(package)
+}
+
 pub fn get_all_valid_names(session: &mut SessionInfo, source_file_symbol: &Rc<RefCell<Symbol>>, from_stmt: Option<&Identifier>, base_name: String, level: Option<u32>) -> HashSet<String> {
     //A: search base of different imports
     let _source_file_symbol_lock = source_file_symbol.borrow_mut();
@@ -352,3 +427,75 @@ pub fn get_all_valid_names(session: &mut SessionInfo, source_file_symbol: &Rc<Re
 
     return result;
 }
+
+/* Why an import can't be resolved */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum UnresolvedImportKind {
+    MODULE_NOT_FOUND, //no file or folder of the paths of the closest found package gives the module
+    NAME_NOT_FOUND, //the module is found, but it does not declare the imported name
+    CASE_MISMATCH, //a file or a folder gives the module with another case. Python refuses it, even if the file system ignores the case (Windows)
+}
+
+/* The diagnosis of an import that can't be resolved: the closest package that is found, the first name of the import
+that is not found in it, and the paths where it was searched */
+pub struct UnresolvedImport {
+    pub kind: UnresolvedImportKind,
+    pub closest: Rc<RefCell<Symbol>>,
+    pub missing: String,
+    pub attempted_paths: Vec<String>,
+    pub case_mismatch: Option<String>, //the name of the file or the folder that only differs by its case
+    pub suggestion: Option<String>, //a name of the module close to the missing name, for NAME_NOT_FOUND
+}
+
+/* Explain why the import of the tree failed. closest is the deepest symbol of the tree that is found (the symbol of
+the ImportResult), and is_from is true if the last name is imported by a 'from ... import name' statement */
+pub fn diagnose_unresolved_import(closest: &Rc<RefCell<Symbol>>, tree: &[String], is_from: bool) -> UnresolvedImport {
+    let depth = closest.borrow().get_tree().0.len().min(tree.len().saturating_sub(1));
+    let missing = tree.get(depth).cloned().unwrap_or_default();
+    let typ = closest.borrow().typ();
+    let folders = match typ {
+        SymType::ROOT | SymType::NAMESPACE | SymType::PACKAGE(_) => closest.borrow().paths().clone(),
+        _ => vec![]
+    };
+    let mut attempted_paths = vec![];
+    let mut case_mismatch = None;
+    for folder in folders.iter() {
+        let full_path = Path::new(folder).join(&missing);
+        attempted_paths.push(full_path.sanitize());
+        attempted_paths.push(full_path.with_extension("py").sanitize());
+        attempted_paths.push(full_path.with_extension("pyi").sanitize());
+        if case_mismatch.is_none() {
+            case_mismatch = _find_case_mismatch(folder, &missing);
+        }
+    }
+    let is_module = matches!(typ, SymType::FILE | SymType::PACKAGE(_));
+    let kind = if case_mismatch.is_some() {
+        UnresolvedImportKind::CASE_MISMATCH
+    } else if is_from && is_module && depth == tree.len() - 1 {
+        UnresolvedImportKind::NAME_NOT_FOUND
+    } else {
+        UnresolvedImportKind::MODULE_NOT_FOUND
+    };
+    let suggestion = match kind {
+        UnresolvedImportKind::NAME_NOT_FOUND => {
+            let mut names: Vec<String> = closest.borrow().all_symbols().map(|symbol| symbol.borrow().name().clone()).collect();
+            names.sort();
+            names.dedup();
+            MethodReferences::find_closest(&missing, names.iter().map(|name| name.as_str())).map(|name| name.to_string())
+        },
+        _ => None
+    };
+    UnresolvedImport { kind, closest: closest.clone(), missing, attempted_paths, case_mismatch, suggestion }
+}
+
+/* Return the name of the entry of the folder that is the module of the name with another case: a folder, or a .py or
+.pyi file. is_dir_cs and is_file_cs reject them, even on the file systems that ignore the case */
+fn _find_case_mismatch(folder: &str, name: &str) -> Option<String> {
+    let lower_name = name.to_lowercase();
+    let candidates = [lower_name.clone(), format!("{}.py", lower_name), format!("{}.pyi", lower_name)];
+    let exact = [name.to_string(), format!("{}.py", name), format!("{}.pyi", name)];
+    fs::read_dir(folder).ok()?.flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .find(|entry| candidates.contains(&entry.to_lowercase()) && !exact.contains(entry))
+}
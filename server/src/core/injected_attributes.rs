@@ -0,0 +1,147 @@
+use ruff_python_ast::{Expr, Stmt, StmtClassDef, StmtFunctionDef};
+use ruff_text_size::TextRange;
+
+/* Methods of a metaclass that can set attributes on the classes it creates */
+pub const METACLASS_METHODS: [&str; 2] = ["__new__", "__init__"];
+
+/* An attribute that is set on a class when it is created, or by a method of the class, instead of being declared in its
+body. The supported hooks are:
+    class Base:
+        def __init_subclass__(cls, **kwargs):
+            cls.code = cls.__name__.lower()
+
+    class Meta(type):
+        def __init__(cls, name, bases, attrs):
+            cls._module = attrs.get('__module__')
+
+        def __new__(meta, name, bases, attrs):
+            cls = super().__new__(meta, name, bases, attrs)
+            setattr(cls, '_registered', True)
+            return cls
+
+    class TestPartner(TransactionCase):
+        @classmethod
+        def setUpClass(cls):
+            cls.partner = cls.env['res.partner'].create({...})
+
+        def setUp(self):
+            self.user = self.env.user
+Only the attributes whose name is a literal are found. */
+#[derive(Debug)]
+pub struct InjectedAttribute<'a> {
+    pub name: String,
+    pub range: TextRange,
+    pub value: Option<&'a Expr>,
+}
+
+pub struct InjectedAttributes {}
+
+impl InjectedAttributes {
+
+    /* Return true if the class looks like a metaclass: one of its bases is 'type' or is named like a metaclass (ABCMeta,
+    MetaModel, api.Meta...). The bases are not evaluated yet when this is called. */
+    pub fn is_metaclass(class_def: &StmtClassDef) -> bool {
+        class_def.bases().iter().any(|base| {
+            let name = match base {
+                Expr::Name(n) => n.id.as_str(),
+                Expr::Attribute(a) => a.attr.as_str(),
+                _ => return false
+            };
+            name == "type" || name.starts_with("Meta") || name.ends_with("Meta")
+        })
+    }
+
+    /* Return true if the attributes that the method sets on its first parameter are used by the other methods of the
+    class: the fixtures set by the classmethods (setUpClass) and by the setUp of test cases */
+    pub fn is_fixture_method(func: &StmtFunctionDef) -> bool {
+        func.name.as_str() == "setUp" ||
+            func.decorator_list.iter().any(|d| matches!(&d.expression, Expr::Name(n) if n.id.as_str() == "classmethod"))
+    }
+
+    /* Return the attributes set on the created class by a class creation hook (__init_subclass__, or __new__/__init__ of
+    a metaclass) */
+    pub fn find(func: &StmtFunctionDef) -> Vec<InjectedAttribute> {
+        let mut owners: Vec<&str> = vec![];
+        if func.name.as_str() != "__new__" {
+            //__init_subclass__(cls) and __init__(cls) receive the class as first parameter
+            if let Some(first) = func.parameters.posonlyargs.iter().chain(func.parameters.args.iter()).next() {
+                owners.push(first.parameter.name.as_str());
+            }
+        }
+        let mut res = vec![];
+        InjectedAttributes::visit(&func.body, &mut owners, &mut res);
+        res
+    }
+
+    fn visit<'a>(stmts: &'a Vec<Stmt>, owners: &mut Vec<&'a str>, res: &mut Vec<InjectedAttribute<'a>>) {
+        for stmt in stmts.iter() {
+            match stmt {
+                Stmt::Assign(assign) => {
+                    //cls = super().__new__(...) or cls = type.__new__(...) gives the created class
+                    if InjectedAttributes::is_new_call(&assign.value) {
+                        for target in assign.targets.iter() {
+                            if let Expr::Name(name) = target {
+                                owners.push(name.id.as_str());
+                            }
+                        }
+                        continue;
+                    }
+                    for target in assign.targets.iter() {
+                        InjectedAttributes::add_target(target, Some(&assign.value), owners, res);
+                    }
+                },
+                Stmt::AnnAssign(ann_assign) => {
+                    InjectedAttributes::add_target(&ann_assign.target, ann_assign.value.as_deref(), owners, res);
+                },
+                Stmt::Expr(expr) => {
+                    let Expr::Call(call) = &*expr.value else {
+                        continue;
+                    };
+                    let is_setattr = matches!(&*call.func, Expr::Name(n) if n.id.as_str() == "setattr");
+                    if !is_setattr || call.arguments.args.len() != 3 {
+                        continue;
+                    }
+                    let (owner, name, value) = (&call.arguments.args[0], &call.arguments.args[1], &call.arguments.args[2]);
+                    if let (Expr::Name(owner), Expr::StringLiteral(name)) = (owner, name) {
+                        if owners.contains(&owner.id.as_str()) {
+                            InjectedAttributes::push(res, name.value.to_string(), name.range, Some(value));
+                        }
+                    }
+                },
+                Stmt::If(if_stmt) => {
+                    InjectedAttributes::visit(&if_stmt.body, owners, res);
+                    for clause in if_stmt.elif_else_clauses.iter() {
+                        InjectedAttributes::visit(&clause.body, owners, res);
+                    }
+                },
+                Stmt::For(for_stmt) => InjectedAttributes::visit(&for_stmt.body, owners, res),
+                Stmt::With(with_stmt) => InjectedAttributes::visit(&with_stmt.body, owners, res),
+                Stmt::Try(try_stmt) => {
+                    InjectedAttributes::visit(&try_stmt.body, owners, res);
+                    InjectedAttributes::visit(&try_stmt.orelse, owners, res);
+                    InjectedAttributes::visit(&try_stmt.finalbody, owners, res);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn is_new_call(expr: &Expr) -> bool {
+        matches!(expr, Expr::Call(call) if matches!(&*call.func, Expr::Attribute(a) if a.attr.as_str() == "__new__"))
+    }
+
+    fn add_target<'a>(target: &'a Expr, value: Option<&'a Expr>, owners: &Vec<&'a str>, res: &mut Vec<InjectedAttribute<'a>>) {
+        if let Expr::Attribute(attr) = target {
+            if matches!(&*attr.value, Expr::Name(n) if owners.contains(&n.id.as_str())) {
+                InjectedAttributes::push(res, attr.attr.to_string(), attr.attr.range, value);
+            }
+        }
+    }
+
+    fn push<'a>(res: &mut Vec<InjectedAttribute<'a>>, name: String, range: TextRange, value: Option<&'a Expr>) {
+        //the first assignment gives the declaration
+        if !res.iter().any(|a| a.name == name) {
+            res.push(InjectedAttribute { name, range, value });
+        }
+    }
+}
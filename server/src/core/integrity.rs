@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::constants::SymType;
+use crate::core::symbols::package_symbol::PackageSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+pub const CHECK_INTEGRITY_COMMAND: &str = "odooLs.checkIntegrity";
+
+/* Checks of the consistency of the symbol tree and of the registries that refer to its symbols. A rebuild must detach
+the previous symbols completely, as a symbol that is still referenced after being unloaded comes back in completions
+or hovers. Each violation is described with the path of the symbol */
+pub struct IntegrityChecker {}
+
+impl IntegrityChecker {
+
+    /* Return the violations found in the whole tree:
+    - the weak_self and parent links of each symbol refer to the symbol and to the symbol that contains it
+    - the dependencies between files are symmetric, and don't refer to detached symbols
+    - the models and modules registries only refer to symbols of the tree */
+    pub fn check(session: &mut SessionInfo) -> Vec<String> {
+        let mut violations = vec![];
+        let Some(root) = session.sync_odoo.symbols.clone() else {
+            return violations;
+        };
+        let mut stack = vec![root];
+        while let Some(symbol) = stack.pop() {
+            let children: Vec<Rc<RefCell<Symbol>>> = symbol.borrow().all_symbols().collect();
+            for child in children.iter() {
+                IntegrityChecker::check_links(&symbol, child, &mut violations);
+            }
+            if matches!(symbol.borrow().typ(), SymType::FILE | SymType::PACKAGE(_) | SymType::NAMESPACE) {
+                IntegrityChecker::check_dependencies(&symbol, &mut violations);
+            }
+            stack.extend(children);
+        }
+        for (name, model) in session.sync_odoo.models.clone().iter() {
+            let symbols = model.borrow().all_symbols(session, None);
+            for (symbol, _) in symbols.iter() {
+                let (path, attached) = IntegrityChecker::get_path(symbol);
+                if !attached {
+                    violations.push(format!("{}: detached symbol in the model {}", path, name));
+                } else if !symbol.borrow().as_class_sym()._model.as_ref().is_some_and(|m| m.names().any(|n| n == name)) {
+                    violations.push(format!("{}: registered in the model {}, but doesn't declare it", path, name));
+                }
+            }
+            for dependent in model.borrow().dependents.iter() {
+                let (path, attached) = IntegrityChecker::get_path(&dependent);
+                if !attached {
+                    violations.push(format!("{}: detached symbol in the dependents of the model {}", path, name));
+                }
+            }
+        }
+        for (name, module) in session.sync_odoo.modules.iter() {
+            match module.upgrade() {
+                Some(module) => {
+                    let (path, attached) = IntegrityChecker::get_path(&module);
+                    if !attached {
+                        violations.push(format!("{}: detached symbol registered as the module {}", path, name));
+                    }
+                },
+                None => violations.push(format!("The module {} is registered, but its symbol has been dropped", name))
+            }
+        }
+        violations
+    }
+
+    fn check_links(parent: &Rc<RefCell<Symbol>>, child: &Rc<RefCell<Symbol>>, violations: &mut Vec<String>) {
+        let child_bw = child.borrow();
+        if !child_bw.weak_self().and_then(|w| w.upgrade()).is_some_and(|w| Rc::ptr_eq(&w, child)) {
+            violations.push(format!("{}: weak_self doesn't refer to the symbol", IntegrityChecker::get_path(child).0));
+        }
+        if !child_bw.parent().and_then(|p| p.upgrade()).is_some_and(|p| Rc::ptr_eq(&p, parent)) {
+            violations.push(format!("{}: the parent is not {}", IntegrityChecker::get_path(child).0, IntegrityChecker::get_path(parent).0));
+        }
+    }
+
+    /* Check that each dependency is registered in the dependents of the other symbol, and the opposite */
+    fn check_dependencies(symbol: &Rc<RefCell<Symbol>>, violations: &mut Vec<String>) {
+        let symbol_bw = symbol.borrow();
+        let path = IntegrityChecker::get_path(symbol).0;
+        for (step, levels) in symbol_bw.dependencies().iter().enumerate() {
+            for (level, dependencies) in levels.iter().enumerate() {
+                for dependency in dependencies.iter() {
+                    let (dependency_path, attached) = IntegrityChecker::get_path(&dependency);
+                    if !attached {
+                        violations.push(format!("{}: depends on the detached symbol {}", path, dependency_path));
+                    }
+                    if !dependency.borrow().dependents()[level].get(step).is_some_and(|dependents| dependents.contains(symbol)) {
+                        violations.push(format!("{}: depends on {} (step {}, level {}), which doesn't have it as dependent", path, dependency_path, step, level));
+                    }
+                }
+            }
+        }
+        for (level, steps) in symbol_bw.dependents().iter().enumerate() {
+            for (step, dependents) in steps.iter().enumerate() {
+                for dependent in dependents.iter() {
+                    let (dependent_path, attached) = IntegrityChecker::get_path(&dependent);
+                    if !attached {
+                        violations.push(format!("{}: the detached symbol {} depends on it", path, dependent_path));
+                    }
+                    if !dependent.borrow().dependencies()[step].get(level).is_some_and(|dependencies| dependencies.contains(symbol)) {
+                        violations.push(format!("{}: has {} as dependent (step {}, level {}), which doesn't depend on it", path, dependent_path, step, level));
+                    }
+                }
+            }
+        }
+        let model_dependencies = match &*symbol_bw {
+            Symbol::File(f) => Some(&f.model_dependencies),
+            Symbol::Package(PackageSymbol::Module(m)) => Some(&m.model_dependencies),
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => Some(&p.model_dependencies),
+            _ => None
+        };
+        for model in model_dependencies.into_iter().flat_map(|models| models.iter()) {
+            if !model.borrow().dependents.contains(symbol) {
+                violations.push(format!("{}: depends on a model that doesn't have it as dependent", path));
+            }
+        }
+    }
+
+    /* Return the path of the symbol from the root, and false if the symbol is not attached to the root */
+    fn get_path(symbol: &Rc<RefCell<Symbol>>) -> (String, bool) {
+        let mut names = vec![symbol.borrow().name().clone()];
+        let mut parent = symbol.borrow().parent().and_then(|p| p.upgrade());
+        let mut attached = symbol.borrow().typ() == SymType::ROOT;
+        while let Some(current) = parent {
+            if current.borrow().typ() == SymType::ROOT {
+                attached = true;
+                break;
+            }
+            names.push(current.borrow().name().clone());
+            parent = current.borrow().parent().and_then(|p| p.upgrade());
+        }
+        names.reverse();
+        (names.join("."), attached)
+    }
+}
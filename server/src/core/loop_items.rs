@@ -0,0 +1,296 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ruff_python_ast::{Expr, ExprCall, ExprList, ExprSet, ExprTuple, Stmt};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::constants::{flatten_tree, SymType};
+use crate::core::evaluation::{ContextValue, Evaluation, EvaluationSymbolWeak, EvaluationValue};
+use crate::core::file_mgr::FileMgr;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* The items of an iterable, as far as they are known */
+#[derive(Debug, Clone)]
+#[allow(non_camel_case_types)]
+pub enum LoopItem {
+    VALUE(Vec<Evaluation>), //evaluations of the items
+    TUPLE(Vec<LoopItem>), //items that are tuples of a fixed length, like the (key, value) pairs of dict.items()
+}
+
+impl LoopItem {
+
+    /* Return the evaluations of the item assigned to a single name: a tuple item is a tuple */
+    pub fn evaluations(&self, session: &mut SessionInfo) -> Vec<Evaluation> {
+        match self {
+            LoopItem::VALUE(evaluations) => evaluations.clone(),
+            LoopItem::TUPLE(_) => session.sync_odoo.get_symbol(&(vec![S!("builtins")], vec![S!("tuple")]), u32::MAX).last()
+                .map(|tuple| vec![Evaluation::eval_from_symbol(&Rc::downgrade(tuple), Some(true))])
+                .unwrap_or_default()
+        }
+    }
+
+    /* Return the item that is any of the given ones. Tuples of the same length are merged element by element */
+    fn union(session: &mut SessionInfo, items: Vec<LoopItem>) -> Option<LoopItem> {
+        let length = match items.first()? {
+            LoopItem::TUPLE(elements) => Some(elements.len()),
+            LoopItem::VALUE(_) => None
+        };
+        let same_tuples = length.is_some() && items.iter().all(|item| matches!(item, LoopItem::TUPLE(elements) if Some(elements.len()) == length));
+        if same_tuples {
+            let mut columns: Vec<Vec<LoopItem>> = vec![vec![]; length.unwrap()];
+            for item in items.into_iter() {
+                let LoopItem::TUPLE(elements) = item else {
+                    continue;
+                };
+                for (column, element) in columns.iter_mut().zip(elements.into_iter()) {
+                    column.push(element);
+                }
+            }
+            return columns.into_iter().map(|column| LoopItem::union(session, column)).collect::<Option<Vec<LoopItem>>>().map(LoopItem::TUPLE);
+        }
+        let mut evaluations = vec![];
+        for item in items.iter() {
+            evaluations.extend(item.evaluations(session));
+        }
+        Some(LoopItem::VALUE(evaluations))
+    }
+}
+
+/* The items given to the target of a for loop by the iterated expression:
+    for partner in self.partner_ids: the records of the recordset, given by __iter__ (then __next__ on the iterator)
+    for name in ['a', 'b']: the elements of a list, tuple, set or dict literal, or of a variable holding one
+    for line in lines: the type argument of the annotation of the variable or the parameter, like lines: list[Line]
+    for index in range(10): ints, and the pairs of enumerate() and zip()
+    for key, value in values.items(): the pairs of the keys and values of a dict literal or of a dict[K, V] annotation
+The tuple items are distributed on the names of a tuple target. The items that can't be found are not evaluated. */
+pub struct LoopItems {}
+
+impl LoopItems {
+
+    /* Return the items of the iterable. For an async for, they are given by __aiter__ then __anext__ */
+    pub fn get_item(session: &mut SessionInfo, iterable: &Expr, is_async: bool, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<LoopItem> {
+        if is_async {
+            return LoopItems::get_protocol_item(session, iterable, "__aiter__", "__anext__", scope, max_infer);
+        }
+        let item = match iterable {
+            Expr::List(ExprList { elts, .. }) | Expr::Tuple(ExprTuple { elts, .. }) | Expr::Set(ExprSet { elts, .. }) => LoopItems::get_elements_item(session, elts, scope.clone(), max_infer),
+            Expr::Dict(dict) => {
+                let keys: Vec<Expr> = dict.items.iter().filter_map(|item| item.key.clone()).collect();
+                LoopItems::get_elements_item(session, &keys, scope.clone(), max_infer)
+            },
+            Expr::Call(call) => LoopItems::get_call_item(session, call, scope.clone(), max_infer),
+            Expr::Name(_) => LoopItems::get_annotation_args(session, iterable, scope.clone(), max_infer)
+                .and_then(|(_, args)| args.first().map(|arg| LoopItems::get_annotation_item(session, arg, scope.clone(), max_infer)))
+                .or_else(|| LoopItems::get_literal_item(session, iterable, None, scope.clone(), max_infer)),
+            _ => None
+        };
+        item.or_else(|| LoopItems::get_protocol_item(session, iterable, "__iter__", "__next__", scope, max_infer))
+    }
+
+    /* Return the item that is any of the elements of a literal */
+    fn get_elements_item(session: &mut SessionInfo, elements: &[Expr], scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<LoopItem> {
+        if elements.iter().any(|element| element.is_starred_expr()) {
+            return None;
+        }
+        let mut items = vec![];
+        for element in elements.iter() {
+            let item = match element {
+                Expr::Tuple(ExprTuple { elts, .. }) if !elts.iter().any(|elt| elt.is_starred_expr()) => {
+                    LoopItem::TUPLE(elts.iter().map(|elt| LoopItem::VALUE(Evaluation::eval_from_ast(session, elt, scope.clone(), max_infer).0)).collect())
+                },
+                _ => LoopItem::VALUE(Evaluation::eval_from_ast(session, element, scope.clone(), max_infer).0)
+            };
+            items.push(item);
+        }
+        LoopItem::union(session, items)
+    }
+
+    /* Return the items of the elements of the literal value of an expression, like a variable assigned to a list literal.
+    The methods of a dict (keys, values or items) select what its items are */
+    fn get_literal_item(session: &mut SessionInfo, expr: &Expr, dict_method: Option<&str>, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<LoopItem> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, expr, scope.clone(), max_infer);
+        let [evaluation] = evaluations.as_slice() else {
+            return None;
+        };
+        match (&evaluation.value, dict_method) {
+            (Some(EvaluationValue::LIST(elements) | EvaluationValue::TUPLE(elements) | EvaluationValue::SET(elements)), None) => {
+                LoopItems::get_elements_item(session, elements, scope, max_infer)
+            },
+            (Some(EvaluationValue::DICT(items)), method) if !items.is_empty() => {
+                let keys: Vec<Expr> = items.iter().map(|(key, _)| key.clone()).collect();
+                let values: Vec<Expr> = items.iter().map(|(_, value)| value.clone()).collect();
+                match method {
+                    None | Some("keys") => LoopItems::get_elements_item(session, &keys, scope, max_infer),
+                    Some("values") => LoopItems::get_elements_item(session, &values, scope, max_infer),
+                    _ => Some(LoopItem::TUPLE(vec![
+                        LoopItems::get_elements_item(session, &keys, scope.clone(), max_infer)?,
+                        LoopItems::get_elements_item(session, &values, scope, max_infer)?,
+                    ]))
+                }
+            },
+            _ => None
+        }
+    }
+
+    /* Return the items of the calls of range(), enumerate() and zip(), and of the keys(), values() and items() methods
+    of a dict */
+    fn get_call_item(session: &mut SessionInfo, call: &ExprCall, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<LoopItem> {
+        if let Expr::Attribute(attribute) = &*call.func {
+            let method = attribute.attr.as_str();
+            if !["keys", "values", "items"].contains(&method) || !call.arguments.args.is_empty() || !call.arguments.keywords.is_empty() {
+                return None;
+            }
+            if let Some((true, args)) = LoopItems::get_annotation_args(session, &attribute.value, scope.clone(), max_infer) {
+                let [key, value] = args.as_slice() else {
+                    return None;
+                };
+                return match method {
+                    "keys" => Some(LoopItems::get_annotation_item(session, key, scope, max_infer)),
+                    "values" => Some(LoopItems::get_annotation_item(session, value, scope, max_infer)),
+                    _ => Some(LoopItem::TUPLE(vec![
+                        LoopItems::get_annotation_item(session, key, scope.clone(), max_infer),
+                        LoopItems::get_annotation_item(session, value, scope, max_infer),
+                    ]))
+                };
+            }
+            return LoopItems::get_literal_item(session, &attribute.value, Some(method), scope, max_infer);
+        }
+        let (functions, _) = Evaluation::eval_from_ast(session, &call.func, scope.clone(), max_infer);
+        let function = functions.first()?.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade()?;
+        let path = flatten_tree(&function.borrow().get_tree()).join(".");
+        let args = &call.arguments.args;
+        if args.iter().any(|arg| arg.is_starred_expr()) {
+            return None;
+        }
+        match path.as_str() {
+            "builtins.range" => LoopItems::get_builtin_item(session, "int"),
+            "builtins.enumerate" => {
+                let iterable = args.first()?;
+                Some(LoopItem::TUPLE(vec![
+                    LoopItems::get_builtin_item(session, "int")?,
+                    LoopItems::get_item(session, iterable, false, scope, max_infer).unwrap_or(LoopItem::VALUE(vec![])),
+                ]))
+            },
+            "builtins.zip" => Some(LoopItem::TUPLE(args.iter().map(|arg| {
+                LoopItems::get_item(session, arg, false, scope.clone(), max_infer).unwrap_or(LoopItem::VALUE(vec![]))
+            }).collect())),
+            _ => None
+        }
+    }
+
+    fn get_builtin_item(session: &mut SessionInfo, name: &str) -> Option<LoopItem> {
+        let class = session.sync_odoo.get_symbol(&(vec![S!("builtins")], vec![name.to_string()]), u32::MAX).last()?.clone();
+        Some(LoopItem::VALUE(vec![Evaluation::eval_from_symbol(&Rc::downgrade(&class), Some(true))]))
+    }
+
+    /* Return the item declared by a type argument of an annotation: tuple[str, int] gives tuple items */
+    fn get_annotation_item(session: &mut SessionInfo, annotation: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> LoopItem {
+        if let Expr::Subscript(subscript) = annotation {
+            if let (Some("tuple" | "Tuple"), Expr::Tuple(elements)) = (LoopItems::get_name(&subscript.value), &*subscript.slice) {
+                if !elements.elts.iter().any(|element| element.is_ellipsis_literal_expr()) {
+                    return LoopItem::TUPLE(elements.elts.iter().map(|element| LoopItems::get_annotation_item(session, element, scope.clone(), max_infer)).collect());
+                }
+            }
+        }
+        LoopItem::VALUE(Evaluation::eval_annotation(session, annotation, scope, max_infer).0)
+    }
+
+    /* Return the type arguments of the annotation of a variable or a parameter given by its name, if it is a collection,
+    with true if it is a mapping: lines: list[Line] gives [Line], values: dict[str, int] gives [str, int] */
+    fn get_annotation_args(session: &mut SessionInfo, expr: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<(bool, Vec<Expr>)> {
+        let Expr::Name(name) = expr else {
+            return None;
+        };
+        let declarations = Symbol::infer_name(session.sync_odoo, &scope, &name.id.to_string(), Some(max_infer.to_u32()));
+        let variable = declarations.last().filter(|variable| variable.borrow().typ() == SymType::VARIABLE)?.clone();
+        let annotation = LoopItems::get_declared_annotation(session, &variable)?;
+        let Expr::Subscript(subscript) = &annotation else {
+            return None;
+        };
+        let args = match &*subscript.slice {
+            Expr::Tuple(tuple) => tuple.elts.clone(),
+            arg => vec![arg.clone()]
+        };
+        match LoopItems::get_name(&subscript.value)? {
+            "list" | "List" | "set" | "Set" | "frozenset" | "FrozenSet" | "Sequence" | "MutableSequence" | "Iterable" | "Iterator"
+            | "Collection" | "AbstractSet" | "MutableSet" | "Generator" => Some((false, args.into_iter().take(1).collect())),
+            //tuple[int, ...] holds ints, while the elements of tuple[int, str] are not the same
+            "tuple" | "Tuple" if args.len() == 2 && args[1].is_ellipsis_literal_expr() => Some((false, args.into_iter().take(1).collect())),
+            "dict" | "Dict" | "Mapping" | "MutableMapping" | "OrderedDict" | "defaultdict" => Some((true, args)),
+            _ => None
+        }
+    }
+
+    /* Return the annotation of the declaration of a variable: 'lines: list[Line] = []', or the one of a parameter */
+    fn get_declared_annotation(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>) -> Option<Expr> {
+        let is_parameter = variable.borrow().as_variable().is_parameter;
+        let declaration = match is_parameter {
+            true => variable.borrow().parent()?.upgrade()?,
+            false => variable.clone()
+        };
+        let file_info = FileMgr::get_file_info_of_symbol(session, &declaration)?;
+        let file_info = file_info.borrow();
+        let declaration = declaration.borrow();
+        let range = *variable.borrow().range();
+        match declaration.get_ast_node(&file_info)? {
+            Stmt::AnnAssign(ann_assign) if ann_assign.target.range() == range => Some((*ann_assign.annotation).clone()),
+            Stmt::FunctionDef(function_def) if is_parameter => {
+                let parameters = &function_def.parameters;
+                parameters.posonlyargs.iter().chain(parameters.args.iter()).chain(parameters.kwonlyargs.iter())
+                    .find(|arg| arg.parameter.name.range == range)
+                    .and_then(|arg| arg.parameter.annotation.as_deref().cloned())
+            },
+            _ => None
+        }
+    }
+
+    fn get_name(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::Name(name) => Some(name.id.as_str()),
+            Expr::Attribute(attribute) => Some(attribute.attr.as_str()),
+            _ => None
+        }
+    }
+
+    /* Return the items given by the iteration protocol: the value returned by the iter method (__iter__) of the type of
+    the iterable, then by the next method (__next__) of the iterator. An iterator without a known next method is
+    its own item, like a recordset whose __iter__ returns records. Only an iterable with a single evaluation is handled */
+    fn get_protocol_item(session: &mut SessionInfo, iterable: &Expr, iter_method: &str, next_method: &str, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<LoopItem> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, iterable, scope, max_infer);
+        let [evaluation] = evaluations.as_slice() else {
+            return None;
+        };
+        let value = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+        let iterator = LoopItems::call_method(session, &value, iter_method)?;
+        let item = LoopItems::call_method(session, &iterator, next_method).unwrap_or(iterator);
+        Some(LoopItem::VALUE(vec![Evaluation::eval_from_symbol(&item.weak, item.instance)]))
+    }
+
+    /* Return the first value returned by a method of the class of the value, evaluated on the value */
+    fn call_method(session: &mut SessionInfo, value: &EvaluationSymbolWeak, method: &str) -> Option<EvaluationSymbolWeak> {
+        let types = Symbol::follow_ref(value, session, &mut None, false, false, None, &mut vec![]);
+        let [value_type] = types.as_slice() else {
+            return None;
+        };
+        let class = value_type.weak.upgrade().filter(|class| class.borrow().typ() == SymType::CLASS)?;
+        let (methods, _) = class.borrow().get_member_symbol(session, &method.to_string(), None, true, false, false, false);
+        let [method] = methods.as_slice() else {
+            return None;
+        };
+        if method.borrow().typ() != SymType::FUNCTION {
+            return None;
+        }
+        let mut context = Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&class)))]));
+        let returns = method.borrow().as_func().evaluations.clone();
+        for returned in returns.iter() {
+            let value = returned.symbol.get_symbol(session, &mut context, &mut vec![], None);
+            if !value.weak.is_expired() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use ruff_python_ast::visitor::{Visitor, walk_expr};
+use ruff_python_ast::{Expr, Stmt, StmtFunctionDef};
+
+use crate::core::dynamic_dispatch::{DynamicDispatch, MethodPattern};
+
+/* Prefixes of the methods that are called by the framework through the compute, inverse and search arguments of a field */
+pub const FIELD_METHOD_PREFIXES: [&str; 3] = ["_compute_", "_inverse_", "_search_"];
+
+/* Maximum edit distance between an orphan method and the argument of a field to suggest this field */
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/* The names used by the classes of a model, to find the field methods that are never referenced: a _compute_total
+renamed during a refactoring leaves the previous method orphaned, and the field uncomputed. A method is referenced if its
+name is used as a string (compute='_compute_total'), a name (compute=_compute_total) or an attribute (self._compute_total()),
+anywhere in the classes of the model, or if it matches the name built by a getattr (see DynamicDispatch). */
+pub struct MethodReferences {
+    names: HashSet<String>,
+    patterns: Vec<MethodPattern>,
+}
+
+impl MethodReferences {
+
+    pub fn new() -> Self {
+        MethodReferences { names: HashSet::new(), patterns: vec![] }
+    }
+
+    /* Add the names used by the statements. The names of the functions they define are not references */
+    pub fn add_body(&mut self, body: &Vec<Stmt>) {
+        for stmt in body.iter() {
+            self.visit_stmt(stmt);
+        }
+        for pattern in DynamicDispatch::collect_patterns(body) {
+            if !self.patterns.contains(&pattern) {
+                self.patterns.push(pattern);
+            }
+        }
+    }
+
+    pub fn is_referenced(&self, name: &str) -> bool {
+        self.names.contains(name) || self.patterns.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /* Return the methods of the class body that are named like the methods of a field */
+    pub fn get_field_methods(body: &Vec<Stmt>) -> Vec<&StmtFunctionDef> {
+        body.iter().filter_map(|stmt| match stmt {
+            Stmt::FunctionDef(f) if FIELD_METHOD_PREFIXES.iter().any(|prefix| f.name.as_str().starts_with(prefix) && f.name.len() > prefix.len()) => Some(f),
+            _ => None
+        }).collect()
+    }
+
+    /* Return the argument the closest to the name of the method among the given compute, inverse or search arguments,
+    if it almost matches */
+    pub fn find_closest<'a>(method: &str, arguments: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+        arguments.filter(|argument| *argument != method)
+            .map(|argument| (MethodReferences::edit_distance(method, argument), argument))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, argument)| argument)
+    }
+
+    /* Levenshtein distance between two names */
+    pub fn edit_distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        for (i, char_a) in a.chars().enumerate() {
+            let mut current = vec![i + 1];
+            for (j, char_b) in b.iter().enumerate() {
+                let substitution = previous[j] + if char_a == *char_b { 0 } else { 1 };
+                current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+            }
+            previous = current;
+        }
+        previous[b.len()]
+    }
+}
+
+impl<'a> Visitor<'a> for MethodReferences {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::StringLiteral(s) => {
+                self.names.insert(s.value.to_string());
+            },
+            Expr::Attribute(attr) => {
+                self.names.insert(attr.attr.to_string());
+            },
+            Expr::Name(name) => {
+                self.names.insert(name.id.to_string());
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
@@ -1,9 +1,50 @@
+pub mod api_decorators;
+pub mod arch_cache;
+pub mod argument_types;
+pub mod auto_config;
+pub mod borrows;
+pub mod builtin_arithmetic;
+pub mod call_binding;
+pub mod cancellation;
+pub mod collection_folding;
+pub mod compute_targets;
 pub mod config;
+pub mod config_check;
+pub mod container_mutations;
+pub mod context_keys;
+pub mod dataclasses;
+pub mod date_arithmetic;
+pub mod deprecated_api;
+pub mod dynamic_dispatch;
+pub mod dynamic_fields;
+pub mod epoch;
 pub mod evaluation;
+pub mod evaluation_cache;
+pub mod external_dependencies;
+pub mod field_consistency;
+pub mod field_dependencies;
+pub mod field_name_args;
 pub mod file_mgr;
+pub mod function_rebuild;
+pub mod guards;
+pub mod ignore;
 pub mod import_resolver;
+pub mod injected_attributes;
+pub mod integrity;
+pub mod loop_items;
+pub mod method_references;
 pub mod model;
+pub mod model_kind;
+pub mod model_metadata;
+pub mod module_dependencies;
+pub mod module_description;
+pub mod module_discovery;
+pub mod module_hooks;
+pub mod narrowing;
 pub mod odoo;
+pub mod overloads;
+pub mod parse_pool;
+pub mod plugin_rules;
 pub mod python_arch_builder;
 pub mod python_arch_builder_hooks;
 pub mod python_arch_eval;
@@ -11,4 +52,15 @@ pub mod python_arch_eval_hooks;
 pub mod python_odoo_builder;
 pub mod python_validator;
 pub mod python_utils;
-pub mod symbols;
\ No newline at end of file
+pub mod python_string;
+pub mod reindex;
+pub mod selection_field;
+pub mod sql_view;
+pub mod static_conditions;
+pub mod string_index;
+pub mod suppression;
+pub mod symbols;
+pub mod tuple_unpacking;
+pub mod union_members;
+pub mod unused_fields;
+pub mod xml_data;
\ No newline at end of file
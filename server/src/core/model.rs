@@ -7,12 +7,15 @@ use std::collections::HashSet;
 
 use crate::threads::SessionInfo;
 
+use super::model_kind::ModelKind;
 use super::symbols::module_symbol::ModuleSymbol;
 use super::symbols::symbol::Symbol;
 
 #[derive(Debug)]
 pub struct ModelData {
     pub name: String,
+    pub unknown_name: bool, //_name is declared, but its value can't be statically determined. The class is not in the registry
+    pub other_names: Vec<String>, //other models the class is added to, when _inherit lists several models without _name
     pub inherit: Vec<String>,
     pub inherits: Vec<(String, String)>,
 
@@ -38,6 +41,8 @@ impl ModelData {
     pub fn new() -> Self {
         Self {
             name: String::new(),
+            unknown_name: false,
+            other_names: Vec::new(),
             inherit: Vec::new(),
             inherits: Vec::new(),
             description: String::new(),
@@ -58,6 +63,12 @@ impl ModelData {
             fold_name: String::from("fold"),
         }
     }
+
+    /* Return the names of all the models the class contributes to, starting with its own name. There is none if the
+    name is unknown */
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.name).filter(|_| !self.unknown_name).chain(self.other_names.iter())
+    }
 }
 
 #[derive(Debug)]
@@ -80,11 +91,13 @@ impl Model {
 
     pub fn add_symbol(&mut self, session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>) {
         self.symbols.insert(symbol);
+        session.sync_odoo.evaluation_cache.touch_models();
         self.add_dependents_to_validation(session);
     }
 
     pub fn remove_symbol(&mut self, session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
         self.symbols.remove(symbol);
+        session.sync_odoo.evaluation_cache.touch_models();
         self.add_dependents_to_validation(session);
     }
 
@@ -121,6 +134,23 @@ impl Model {
         res
     }
 
+    /* Return the kind of the model given by the classes creating it that are visible from the module, and whether one of
+    them sets _auto to False. None if no class creating the model is visible */
+    pub fn get_kind(&self, session: &mut SessionInfo, from_module: Option<Rc<RefCell<Symbol>>>) -> Option<(ModelKind, bool)> {
+        let main_symbols = self.get_main_symbols(session, from_module, &mut None);
+        let mut kinds = vec![];
+        let mut auto = true;
+        for symbol in main_symbols.iter() {
+            let symbol = symbol.borrow();
+            let Some(model_data) = symbol.as_class_sym()._model.as_ref() else {
+                continue;
+            };
+            kinds.push(ModelKind::from_data(model_data.is_abstract, model_data.transient));
+            auto &= model_data.auto;
+        }
+        ModelKind::get_effective_kind(&kinds).map(|kind| (kind, auto))
+    }
+
     /* Return all symbols that build this model. 
         It returns the symbol and an optional string that represents the module name that should be added to dependencies to be used.
     */
@@ -145,6 +175,48 @@ impl Model {
         symbol
     }
 
+    /* Return the contributions of the modules to the member 'name' of the model, as the name of the module and the
+    symbols it declares for this member. The first contribution is the one of the module that defines the member,
+    the next ones are the overrides, in the loading order of the modules.
+    If from_module is given, only the modules in its dependencies are returned.
+    */
+    pub fn get_member_contributions(&self, session: &mut SessionInfo, name: &String, from_module: Option<Rc<RefCell<Symbol>>>) -> Vec<(String, Vec<Rc<RefCell<Symbol>>>)> {
+        let mut contributions: Vec<(Rc<RefCell<Symbol>>, String, Vec<Rc<RefCell<Symbol>>>)> = vec![];
+        for sym in self.symbols.iter() {
+            let Some(module) = sym.borrow().find_module() else {
+                continue;
+            };
+            let dir_name = module.borrow().as_module_package().dir_name.clone();
+            if let Some(from_module) = from_module.as_ref() {
+                if !ModuleSymbol::is_in_deps(session, from_module, &dir_name, &mut None) {
+                    continue;
+                }
+            }
+            let members = sym.borrow().get_content_symbol(name, u32::MAX);
+            if members.is_empty() {
+                continue;
+            }
+            if let Some(contribution) = contributions.iter_mut().find(|c| c.1 == dir_name) {
+                contribution.2.extend(members);
+            } else {
+                contributions.push((module, dir_name, members));
+            }
+        }
+        //a module is loaded after all the modules it depends on, so it depends on more contributing modules than them
+        let mut ordered = vec![];
+        for (module, dir_name, members) in contributions.iter() {
+            let mut nb_deps = 0;
+            for (_, other_dir_name, _) in contributions.iter() {
+                if other_dir_name != dir_name && ModuleSymbol::is_in_deps(session, module, other_dir_name, &mut None) {
+                    nb_deps += 1;
+                }
+            }
+            ordered.push((nb_deps, dir_name.clone(), members.clone()));
+        }
+        ordered.sort_by(|x, y| x.0.cmp(&y.0).then_with(|| x.1.cmp(&y.1)));
+        ordered.into_iter().map(|(_, dir_name, members)| (dir_name, members)).collect()
+    }
+
     pub fn add_dependent(&mut self, symbol: &Rc<RefCell<Symbol>>) {
         self.dependents.insert(symbol.clone());
     }
@@ -0,0 +1,76 @@
+use ruff_python_ast::{Expr, ExprStringLiteral};
+
+/* Methods that read or write the table of the model, and fail on an abstract model */
+pub const TABLE_METHODS: [&str; 4] = ["create", "search", "search_count", "search_read"];
+/* Methods returning the same recordset in another environment, that don't change its model */
+const ENV_METHODS: [&str; 6] = ["sudo", "with_context", "with_user", "with_company", "with_env", "with_prefetch"];
+
+/* Kind of a model, given by the Python base class of the class creating it */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum ModelKind {
+    MODEL,
+    TRANSIENT,
+    ABSTRACT,
+}
+
+/* A model is persistent (models.Model), transient (models.TransientModel, whose records are removed by the vacuum) or
+abstract (models.AbstractModel, that has no table). The kind is set by the classes creating the model: the classes
+extending it with _inherit keep its kind, as Odoo refuses an extension that would change it. It is computed from the
+registry when it is needed, so a module loaded later and creating the model again is taken into account */
+impl ModelKind {
+
+    pub fn from_data(is_abstract: bool, transient: bool) -> ModelKind {
+        if transient {
+            ModelKind::TRANSIENT
+        } else if is_abstract {
+            ModelKind::ABSTRACT
+        } else {
+            ModelKind::MODEL
+        }
+    }
+
+    /* Return the kind of a model from the kinds of the classes creating it. A model created as abstract and created
+    again by a module as persistent is persistent */
+    pub fn get_effective_kind(creators: &[ModelKind]) -> Option<ModelKind> {
+        if creators.is_empty() {
+            None
+        } else if creators.contains(&ModelKind::MODEL) {
+            Some(ModelKind::MODEL)
+        } else if creators.contains(&ModelKind::TRANSIENT) {
+            Some(ModelKind::TRANSIENT)
+        } else {
+            Some(ModelKind::ABSTRACT)
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModelKind::MODEL => "Model",
+            ModelKind::TRANSIENT => "TransientModel",
+            ModelKind::ABSTRACT => "AbstractModel",
+        }
+    }
+
+    /* Return the name of the model of a receiver written env['model'], like self.env['sale.order'].sudo() */
+    pub fn get_env_model_name(expr: &Expr) -> Option<&ExprStringLiteral> {
+        match expr {
+            Expr::Call(call) => match &*call.func {
+                Expr::Attribute(attr) if ENV_METHODS.contains(&attr.attr.as_str()) => ModelKind::get_env_model_name(&attr.value),
+                _ => None
+            },
+            Expr::Subscript(subscript) => {
+                let is_env = match &*subscript.value {
+                    Expr::Attribute(attr) => attr.attr.as_str() == "env",
+                    Expr::Name(name) => name.id.as_str() == "env",
+                    _ => false
+                };
+                match &*subscript.slice {
+                    Expr::StringLiteral(literal) if is_env => Some(literal),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+/* Attributes that are only used by the vacuum of transient models */
+pub const TRANSIENT_ATTRIBUTES: [&str; 2] = ["_transient_max_count", "_transient_max_hours"];
+
+/* Metadata attributes of a model, as they are written in the body of one of its classes.
+Unlike ModelData, that holds the evaluated metadata of the class (with inherited and default values), only the literal
+declarations of the class are kept here, with their ranges, so the checks can point at what is written. */
+#[derive(Debug, Default)]
+pub struct ModelMetadata {
+    pub name: Option<(String, TextRange)>,
+    pub inherit: Option<(Vec<String>, TextRange)>,
+    pub description: Option<(String, TextRange)>,
+    pub table: Option<(String, TextRange)>,
+    pub auto: Option<(bool, TextRange)>,
+    pub transient_attributes: Vec<(String, TextRange)>,
+    pub methods: Vec<String>,
+}
+
+impl ModelMetadata {
+
+    pub fn from_body(body: &Vec<Stmt>) -> ModelMetadata {
+        let mut res = ModelMetadata::default();
+        for stmt in body.iter() {
+            let (target, value) = match stmt {
+                Stmt::Assign(a) => match a.targets.first().and_then(|t| t.as_name_expr()) {
+                    Some(target) => (target, &*a.value),
+                    None => continue
+                },
+                Stmt::AnnAssign(a) => match (a.target.as_name_expr(), a.value.as_deref()) {
+                    (Some(target), Some(value)) => (target, value),
+                    _ => continue
+                },
+                Stmt::FunctionDef(f) => {
+                    res.methods.push(f.name.to_string());
+                    continue;
+                },
+                _ => continue
+            };
+            let range = target.range();
+            match target.id.as_str() {
+                "_name" => res.name = ModelMetadata::as_string(value).map(|s| (s, range)),
+                "_description" => res.description = ModelMetadata::as_string(value).map(|s| (s, range)),
+                "_table" => res.table = ModelMetadata::as_string(value).map(|s| (s, range)),
+                "_auto" => res.auto = match value {
+                    Expr::BooleanLiteral(b) => Some((b.value, range)),
+                    _ => None
+                },
+                "_inherit" => res.inherit = match value {
+                    Expr::List(l) => Some((l.elts.iter().filter_map(ModelMetadata::as_string).collect(), range)),
+                    Expr::Tuple(t) => Some((t.elts.iter().filter_map(ModelMetadata::as_string).collect(), range)),
+                    value => Some((ModelMetadata::as_string(value).into_iter().collect(), range)),
+                },
+                name if TRANSIENT_ATTRIBUTES.contains(&name) => res.transient_attributes.push((name.to_string(), range)),
+                _ => {}
+            }
+        }
+        res
+    }
+
+    /* Return true if the class extends a model, instead of creating one (_inherit without _name, or _name in _inherit) */
+    pub fn is_extension(&self) -> bool {
+        match (&self.name, &self.inherit) {
+            (None, Some(_)) => true,
+            (Some((name, _)), Some((inherit, _))) => inherit.contains(name),
+            _ => false
+        }
+    }
+
+    /* Return true if the class creates a model from scratch without describing it. Models created from other models by
+    _inherit get the description of their parents */
+    pub fn is_missing_description(&self) -> bool {
+        self.name.is_some() && self.inherit.is_none() && self.description.is_none()
+    }
+
+    /* Return the range of '_auto = False' if the class doesn't create its table without defining init() to create it */
+    pub fn get_auto_without_init(&self) -> Option<TextRange> {
+        match self.auto {
+            Some((false, range)) if !self.is_extension() && !self.methods.iter().any(|m| m == "init") => Some(range),
+            _ => None
+        }
+    }
+
+    fn as_string(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::StringLiteral(s) => Some(s.value.to_string()),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, Location, MessageType, NumberOrString, Position, Range};
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::{BuildSteps, EXTENSION_NAME};
+use crate::core::field_consistency::FieldDeclaration;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::symbols::module_symbol::ModuleSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+pub const CHECK_MODULE_DEPENDENCIES_COMMAND: &str = "odooLs.checkModuleDependencies";
+pub const MODULE_DEPENDENCIES_PROGRESS_METHOD: &str = "$Odoo/moduleDependenciesProgress";
+
+/* Options of the checkModuleDependencies command, given as its first argument: {"modules": ["sale_custom"], "publishDiagnostics": true} */
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModuleDependenciesOptions {
+    pub modules: Vec<String>, //the workspace modules that are checked. Empty for all of them
+    pub allow: Vec<String>, //dependencies that are never reported as missing, added to the moduleDependenciesAllowlist setting
+    pub publish_diagnostics: bool, //publish the missing and the unused dependencies as diagnostics, until the next validation of the files
+}
+
+impl ModuleDependenciesOptions {
+
+    pub fn from_arguments(arguments: &Vec<Value>) -> Result<ModuleDependenciesOptions, String> {
+        match arguments.first() {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| format!("Invalid options for {}: {}", CHECK_MODULE_DEPENDENCIES_COMMAND, e)),
+            None => Ok(ModuleDependenciesOptions::default()),
+        }
+    }
+}
+
+/* How a module uses another one */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    Import, //import odoo.addons.sale.models
+    Model, //self.env['sale.order'], _inherit = 'sale.order', fields.Many2one('sale.order')
+    XmlId, //self.env.ref('sale.view_order_form'), inherit_id="sale.view_order_form"
+}
+
+/* A module, a model or an xml id written in a python file, with the range of the literal or the name using it */
+#[derive(Debug, Clone, PartialEq)]
+pub struct PythonReference {
+    pub kind: ReferenceKind,
+    pub name: String,
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DependencyEvidence {
+    pub kind: ReferenceKind,
+    pub name: String, //the imported module, the model or the xml id
+    pub location: Location,
+}
+
+/* A module used without being in the depends of the manifest, even indirectly */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingDependency {
+    pub module: String,
+    pub evidence: Vec<DependencyEvidence>, //ordered by file and position
+}
+
+/* The dependencies of a module, returned by the command and sent in its progress notifications */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleDependencies {
+    pub module: String,
+    pub missing: Vec<MissingDependency>, //ordered by module
+    pub unused: Vec<String>, //the depends of the manifest whose modules, and their own dependencies, are never used
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleDependenciesProgress {
+    pub done: usize, //modules checked, including this one
+    pub total: usize,
+    pub result: ModuleDependencies,
+}
+
+struct ReferenceVisitor {
+    references: Vec<PythonReference>,
+    safe_imports: u32, //depth of the try blocks catching ImportError, whose imports are optional
+}
+
+impl ReferenceVisitor {
+
+    fn add_import(&mut self, name: &str, range: TextRange) {
+        if self.safe_imports == 0 && name.starts_with("odoo.addons.") {
+            self.references.push(PythonReference { kind: ReferenceKind::Import, name: name.to_string(), range });
+        }
+    }
+
+    fn add_model(&mut self, expr: &Expr) {
+        if let Expr::StringLiteral(model) = expr {
+            self.references.push(PythonReference { kind: ReferenceKind::Model, name: model.value.to_string(), range: model.range });
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ReferenceVisitor {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Import(import) => {
+                for alias in import.names.iter() {
+                    self.add_import(alias.name.as_str(), alias.range);
+                }
+            },
+            Stmt::ImportFrom(import) if import.level == 0 => {
+                let module = import.module.as_ref().map(|module| module.to_string()).unwrap_or_default();
+                if module == "odoo.addons" {
+                    for alias in import.names.iter() {
+                        self.add_import(&format!("odoo.addons.{}", alias.name), alias.range);
+                    }
+                } else if let Some(module_name) = import.module.as_ref() {
+                    self.add_import(&module, module_name.range);
+                }
+            },
+            Stmt::Try(try_stmt) => {
+                let safe = AstUtils::catches_import_error(try_stmt);
+                if safe {
+                    self.safe_imports += 1;
+                }
+                for stmt in try_stmt.body.iter() {
+                    self.visit_stmt(stmt);
+                }
+                if safe {
+                    self.safe_imports -= 1;
+                }
+                for handler in try_stmt.handlers.iter() {
+                    self.visit_except_handler(handler);
+                }
+                for stmt in try_stmt.orelse.iter().chain(try_stmt.finalbody.iter()) {
+                    self.visit_stmt(stmt);
+                }
+                return;
+            },
+            Stmt::Assign(assign) => {
+                match assign.targets.first().and_then(|target| target.as_name_expr()).map(|target| target.id.as_str()) {
+                    Some("_inherit") => match &*assign.value {
+                        Expr::List(list) => list.elts.iter().for_each(|elt| self.add_model(elt)),
+                        Expr::Tuple(tuple) => tuple.elts.iter().for_each(|elt| self.add_model(elt)),
+                        value => self.add_model(value),
+                    },
+                    Some("_inherits") => if let Expr::Dict(dict) = &*assign.value {
+                        dict.items.iter().filter_map(|item| item.key.as_ref()).for_each(|key| self.add_model(key));
+                    },
+                    _ => {}
+                }
+                if let Some(field) = FieldDeclaration::from_stmt(stmt) {
+                    if let Some(comodel) = field.comodel {
+                        self.references.push(PythonReference { kind: ReferenceKind::Model, name: comodel, range: field.range });
+                    }
+                }
+            },
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Subscript(subscript) if AstUtils::flatten_expr(&subscript.value).ends_with("env") => {
+                self.add_model(&subscript.slice);
+            },
+            Expr::Call(call) => {
+                let is_ref = match &*call.func {
+                    Expr::Attribute(func) => (func.attr.as_str() == "ref" && AstUtils::flatten_expr(&func.value).ends_with("env"))
+                        || func.attr.as_str() == "_for_xml_id",
+                    _ => false
+                };
+                if let Some(Expr::StringLiteral(xml_id)) = call.arguments.args.first().filter(|_| is_ref) {
+                    if xml_id.value.to_str().contains('.') {
+                        self.references.push(PythonReference { kind: ReferenceKind::XmlId, name: xml_id.value.to_string(), range: xml_id.range });
+                    }
+                }
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/* Workspace analysis of the modules used by a module without being in the depends of its manifest. Such a module works
+in a database where everything is installed, and breaks on a clean install. A module is used by the python imports of
+odoo.addons.X, by the models defined by X only (env['model'], _inherit, _inherits, comodels), and by the xml ids
+prefixed by X in the python files and the data files. The depends whose modules are never used, directly or through
+their own dependencies, are reported as possibly unused. */
+pub struct ModuleDependencyCheck {}
+
+impl ModuleDependencyCheck {
+
+    /* Return the modules, the models and the xml ids used by the statements. The imports in a try block catching
+    ImportError are optional, and not returned */
+    pub fn find_python_references(stmts: &[Stmt]) -> Vec<PythonReference> {
+        let mut visitor = ReferenceVisitor { references: vec![], safe_imports: 0 };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.references
+    }
+
+    /* Return the module used by a reference to an import or an xml id */
+    pub fn get_referenced_module(kind: ReferenceKind, name: &str) -> Option<&str> {
+        match kind {
+            ReferenceKind::Import => name.strip_prefix("odoo.addons.")?.split('.').next(),
+            ReferenceKind::XmlId => name.split_once('.').map(|(module, _)| module),
+            ReferenceKind::Model => None,
+        }
+    }
+
+    /* An entry of the allowlist is a module that can always be used, like 'website', or a module that can be used by a
+    given module, like 'sale_custom:website' */
+    pub fn is_allowed(allowlist: &[String], module: &str, dependency: &str) -> bool {
+        allowlist.iter().any(|entry| match entry.split_once(':') {
+            Some((allowed_module, allowed_dependency)) => allowed_module == module && allowed_dependency == dependency,
+            None => entry == dependency
+        })
+    }
+
+    pub fn check(session: &mut SessionInfo, options: ModuleDependenciesOptions) -> Vec<ModuleDependencies> {
+        let start = Instant::now();
+        let allowlist: Vec<String> = session.sync_odoo.config.module_dependencies_allowlist.iter().chain(options.allow.iter()).cloned().collect();
+        let model_modules = ModuleDependencyCheck::get_model_modules(session);
+        let mut modules: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.modules.values().filter_map(|module| module.upgrade())
+            .filter(|module| module.borrow().in_workspace()).collect();
+        modules.retain(|module| options.modules.is_empty() || options.modules.contains(&module.borrow().as_module_package().dir_name));
+        modules.sort_by_key(|module| module.borrow().as_module_package().dir_name.clone());
+        let total = modules.len();
+        let mut res = vec![];
+        let mut diagnostics: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        for (done, module) in modules.iter().enumerate() {
+            let result = ModuleDependencyCheck::check_module(session, module, &model_modules, &allowlist, options.publish_diagnostics.then_some(&mut diagnostics));
+            session.send_notification(MODULE_DEPENDENCIES_PROGRESS_METHOD, ModuleDependenciesProgress { done: done + 1, total, result: result.clone() });
+            res.push(result);
+        }
+        for (path, diagnostics) in diagnostics.into_iter() {
+            let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path) else {
+                continue;
+            };
+            let mut file_info = file_info.borrow_mut();
+            file_info.update_validation_diagnostics(HashMap::from([(BuildSteps::VALIDATION, diagnostics)]));
+            file_info.publish_diagnostics(session);
+        }
+        session.log_message(MessageType::INFO, format!("Module dependencies checked in {} ms: {} missing and {} unused dependencies in {} module(s)",
+            start.elapsed().as_millis(), res.iter().map(|m| m.missing.len()).sum::<usize>(), res.iter().map(|m| m.unused.len()).sum::<usize>(), res.len()));
+        res
+    }
+
+    fn check_module(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>, model_modules: &HashMap<String, BTreeSet<String>>,
+        allowlist: &[String], mut diagnostics: Option<&mut HashMap<String, Vec<Diagnostic>>>) -> ModuleDependencies {
+        let (module_name, root_path, depends, xml_references) = {
+            let module = module.borrow();
+            let module = module.as_module_package();
+            (module.dir_name.clone(), module.root_path.clone(), module.get_depends().clone(), module.xml_id_references.clone())
+        };
+        let mut missing: BTreeMap<String, Vec<DependencyEvidence>> = BTreeMap::new();
+        let mut used: BTreeSet<String> = BTreeSet::new();
+        let mut add_reference = |session: &mut SessionInfo, dependency: &str, evidence: DependencyEvidence| {
+            if dependency == module_name || !session.sync_odoo.modules.contains_key(dependency) {
+                return false;
+            }
+            used.insert(dependency.to_string());
+            if ModuleSymbol::is_in_deps(session, module, &dependency.to_string(), &mut None) || ModuleDependencyCheck::is_allowed(allowlist, &module_name, dependency) {
+                return false;
+            }
+            missing.entry(dependency.to_string()).or_default().push(evidence);
+            true
+        };
+        let files: Vec<Rc<RefCell<FileInfo>>> = {
+            let file_mgr = session.sync_odoo.get_file_mgr();
+            let file_mgr = file_mgr.borrow();
+            let mut files: Vec<(&String, &Rc<RefCell<FileInfo>>)> = file_mgr.files.iter()
+                .filter(|(path, _)| path.ends_with(".py") && FileMgr::is_path_under(path, &root_path)).collect();
+            files.sort_by_key(|(path, _)| *path);
+            files.into_iter().map(|(_, file_info)| file_info.clone()).collect()
+        };
+        for file_info in files.iter() {
+            let (path, references) = {
+                let file_info = file_info.borrow();
+                (file_info.uri.clone(), file_info.ast.as_ref().map(|ast| ModuleDependencyCheck::find_python_references(ast)).unwrap_or_default())
+            };
+            for reference in references.into_iter() {
+                let dependencies: Vec<String> = match reference.kind {
+                    ReferenceKind::Model => {
+                        let defining = model_modules.get(&reference.name).cloned().unwrap_or_default();
+                        let visible: Vec<String> = defining.iter().filter(|dependency| **dependency == module_name
+                            || ModuleSymbol::is_in_deps(session, module, dependency, &mut None)).cloned().collect();
+                        //a model defined by several modules that are not dependencies can't be attributed to one of them
+                        match (visible.is_empty(), defining.len()) {
+                            (false, _) => visible,
+                            (true, 1) => defining.into_iter().collect(),
+                            _ => vec![]
+                        }
+                    },
+                    kind => ModuleDependencyCheck::get_referenced_module(kind, &reference.name).map(|dependency| vec![dependency.to_string()]).unwrap_or_default()
+                };
+                for dependency in dependencies.iter() {
+                    let location = {
+                        let file_info = file_info.borrow();
+                        Location {
+                            uri: FileMgr::pathname2uri(&path),
+                            range: Range::new(file_info.offset_to_position(reference.range.start().to_usize()), file_info.offset_to_position(reference.range.end().to_usize())),
+                        }
+                    };
+                    let evidence = DependencyEvidence { kind: reference.kind, name: reference.name.clone(), location };
+                    //the imports are already reported by the validation (OLS30103)
+                    if add_reference(session, dependency, evidence) && reference.kind != ReferenceKind::Import {
+                        if let Some(diagnostics) = diagnostics.as_mut() {
+                            diagnostics.entry(path.clone()).or_default().push(Diagnostic::new(
+                                Range::new(Position::new(reference.range.start().to_u32(), 0), Position::new(reference.range.end().to_u32(), 0)),
+                                Some(DiagnosticSeverity::ERROR),
+                                Some(NumberOrString::String(S!("OLS30342"))),
+                                Some(EXTENSION_NAME.to_string()),
+                                format!("{} is used, but {} is not in the dependencies of the module", reference.name, dependency),
+                                None,
+                                None,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        for (path, xml_id, range) in xml_references.into_iter() {
+            if let Some(dependency) = ModuleDependencyCheck::get_referenced_module(ReferenceKind::XmlId, &xml_id).map(|dependency| dependency.to_string()) {
+                let evidence = DependencyEvidence { kind: ReferenceKind::XmlId, location: Location { uri: FileMgr::pathname2uri(&path), range }, name: xml_id };
+                add_reference(session, &dependency, evidence);
+            }
+        }
+        let mut unused = vec![];
+        for dependency in depends.iter().filter(|dependency| dependency.as_str() != "base") {
+            let Some(dependency_module) = session.sync_odoo.modules.get(dependency).and_then(|module| module.upgrade()) else {
+                continue; //reported on the manifest
+            };
+            let is_used = used.iter().any(|used| ModuleSymbol::is_in_deps(session, &dependency_module, used, &mut None));
+            if !is_used {
+                unused.push(dependency.clone());
+            }
+        }
+        if let (Some(diagnostics), false) = (diagnostics, unused.is_empty()) {
+            let manifest_path = PathBuf::from(&root_path).join("__manifest__.py").sanitize();
+            let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&manifest_path);
+            let ranges = file_info.map(|file_info| file_info.borrow().ast.as_ref().map(|ast| ModuleDependencyCheck::get_depends_ranges(ast)).unwrap_or_default()).unwrap_or_default();
+            for (dependency, range) in ranges.into_iter().filter(|(dependency, _)| unused.contains(dependency)) {
+                diagnostics.entry(manifest_path.clone()).or_default().push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::HINT),
+                    Some(NumberOrString::String(S!("OLS10009"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("Nothing of {} seems to be used by the module", dependency),
+                    None,
+                    Some(vec![DiagnosticTag::UNNECESSARY]),
+                ));
+            }
+        }
+        for evidence in missing.values_mut() {
+            evidence.sort_by(|evidence_1, evidence_2| evidence_1.location.uri.as_str().cmp(evidence_2.location.uri.as_str())
+                .then(evidence_1.location.range.start.cmp(&evidence_2.location.range.start)));
+        }
+        ModuleDependencies {
+            module: module_name,
+            missing: missing.into_iter().map(|(module, evidence)| MissingDependency { module, evidence }).collect(),
+            unused,
+        }
+    }
+
+    /* Return the modules whose classes create each model */
+    fn get_model_modules(session: &mut SessionInfo) -> HashMap<String, BTreeSet<String>> {
+        let mut res: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let models: Vec<_> = session.sync_odoo.models.iter().map(|(name, model)| (name.clone(), model.clone())).collect();
+        for (name, model) in models.into_iter() {
+            let classes = model.borrow().get_main_symbols(session, None, &mut None);
+            for class in classes.iter() {
+                if let Some(module) = class.borrow().find_module() {
+                    res.entry(name.clone()).or_default().insert(module.borrow().as_module_package().dir_name.clone());
+                }
+            }
+        }
+        res
+    }
+
+    /* Return the literals of the depends of a manifest, with their range */
+    fn get_depends_ranges(ast: &[Stmt]) -> Vec<(String, TextRange)> {
+        let Some(Expr::Dict(dict)) = ast.first().and_then(|stmt| stmt.as_expr_stmt()).map(|stmt| &*stmt.value) else {
+            return vec![];
+        };
+        let depends = dict.items.iter().find(|item| item.key.as_ref()
+            .and_then(|key| key.as_string_literal_expr()).is_some_and(|key| key.value.to_str() == "depends"));
+        match depends.map(|item| &item.value) {
+            Some(Expr::List(list)) => list.elts.iter().filter_map(|elt| elt.as_string_literal_expr())
+                .map(|elt| (elt.value.to_string(), elt.range())).collect(),
+            _ => vec![]
+        }
+    }
+}
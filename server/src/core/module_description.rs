@@ -0,0 +1,227 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use ruff_python_ast::{Expr, Mod, Stmt};
+use ruff_python_parser::Mode;
+
+/* Maximum number of bytes read from a manifest or a README */
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+/* Maximum length, in characters, of each part of the description */
+pub const MAX_FIELD_LENGTH: usize = 200;
+pub const MAX_PARAGRAPH_LENGTH: usize = 800;
+
+/* The files the first paragraph of the description is read from, by priority, relative to the module directory */
+pub const README_FILES: [&str; 5] = ["README.md", "README.rst", "README.txt", "README", "static/description/index.html"];
+
+const MANIFEST_FILES: [&str; 2] = ["__manifest__.py", "__openerp__.py"];
+
+/* Human description of a module, shown on the hover of its name in the depends of a manifest, or of an import of
+odoo.addons.<module>, and in the documentation of the completion of the depends */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleDescription {
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    pub paragraph: Option<String>, //first paragraph of the README or of static/description/index.html, as plain text
+}
+
+impl ModuleDescription {
+
+    /* Read the description of the module from its manifest and its README */
+    pub fn load(module_path: &Path) -> ModuleDescription {
+        let mut res = MANIFEST_FILES.iter().find_map(|file| ModuleDescription::read_file(&module_path.join(file)))
+            .map(|content| ModuleDescription::from_manifest(&content))
+            .unwrap_or_default();
+        res.paragraph = README_FILES.iter().find_map(|file| {
+            let content = ModuleDescription::read_file(&module_path.join(file))?;
+            match file.ends_with(".html") {
+                true => ModuleDescription::first_paragraph(&ModuleDescription::html_to_text(&content)),
+                false => ModuleDescription::first_paragraph(&content),
+            }
+        });
+        res
+    }
+
+    /* Read the name, summary, version and author of a manifest. The values that are not string literals are ignored */
+    pub fn from_manifest(content: &str) -> ModuleDescription {
+        let mut res = ModuleDescription::default();
+        let Mod::Module(module) = ruff_python_parser::parse_unchecked(content, Mode::Module).into_syntax() else {
+            return res;
+        };
+        let Some(dict) = module.body.iter().find_map(|stmt| match stmt {
+            Stmt::Expr(expr) => expr.value.as_dict_expr(),
+            _ => None
+        }) else {
+            return res;
+        };
+        for item in dict.items.iter() {
+            let (Some(Expr::StringLiteral(key)), Expr::StringLiteral(value)) = (&item.key, &item.value) else {
+                continue;
+            };
+            let value = ModuleDescription::truncate(&ModuleDescription::collapse_whitespace(value.value.to_str()), MAX_FIELD_LENGTH);
+            if value.is_empty() {
+                continue;
+            }
+            match key.value.to_str() {
+                "name" => res.name = Some(value),
+                "summary" => res.summary = Some(value),
+                "version" => res.version = Some(value),
+                "author" => res.author = Some(value),
+                _ => {}
+            }
+        }
+        res
+    }
+
+    /* Return the first paragraph of a README, skipping the titles, the badges, the images and the rst directives.
+    The lines of the paragraph are joined and the result is capped to MAX_PARAGRAPH_LENGTH */
+    pub fn first_paragraph(content: &str) -> Option<String> {
+        let mut paragraphs: Vec<Vec<&str>> = vec![vec![]];
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                paragraphs.push(vec![]);
+            } else {
+                paragraphs.last_mut().unwrap().push(line);
+            }
+        }
+        for paragraph in paragraphs.iter().filter(|p| !p.is_empty()) {
+            //a title, with its rst underline, or a directive with its indented options
+            if paragraph.iter().any(|line| ModuleDescription::is_underline(line)) || paragraph[0].trim_start().starts_with("..") {
+                continue;
+            }
+            let lines: Vec<&str> = paragraph.iter().map(|line| line.trim())
+                .filter(|line| !["#", "|", "![", "[![", "<", ":"].iter().any(|prefix| line.starts_with(prefix)))
+                .collect();
+            if lines.is_empty() {
+                continue;
+            }
+            return Some(ModuleDescription::truncate(&lines.join(" "), MAX_PARAGRAPH_LENGTH));
+        }
+        None
+    }
+
+    /* Convert the html of static/description/index.html to plain text, with the blocks separated by an empty line.
+    The scripts, the styles, the comments and the headings are dropped, and the entities are decoded */
+    pub fn html_to_text(html: &str) -> String {
+        let mut text = html.to_string();
+        for pattern in [r"(?s)<!--.*?-->", r"(?is)<script\b.*?</script\s*>", r"(?is)<style\b.*?</style\s*>", r"(?is)<h[1-6]\b.*?</h[1-6]\s*>"] {
+            text = Regex::new(pattern).unwrap().replace_all(&text, " ").to_string();
+        }
+        let blocks = Regex::new(r"(?i)</?(p|div|section|br|li|ul|ol|tr|td|table|blockquote|article|header|footer)\b[^>]*>").unwrap();
+        text = blocks.replace_all(&text, "\n\n").to_string();
+        text = Regex::new(r"<[^>]*>").unwrap().replace_all(&text, " ").to_string();
+        let text = ModuleDescription::decode_entities(&text);
+        text.split("\n\n").map(|block| ModuleDescription::collapse_whitespace(block))
+            .filter(|block| !block.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+
+    /* Return the directory of the module whose description depends on the given file: its manifest, or one of its
+    README_FILES */
+    pub fn get_module_path(path: &Path) -> Option<PathBuf> {
+        let file_name = path.file_name()?.to_str()?;
+        if MANIFEST_FILES.contains(&file_name) || README_FILES.iter().any(|readme| !readme.contains('/') && *readme == file_name) {
+            return path.parent().map(|parent| parent.to_path_buf());
+        }
+        let description = path.parent()?;
+        let is_index = file_name == "index.html" && description.file_name().is_some_and(|name| name == "description")
+            && description.parent().and_then(|p| p.file_name()).is_some_and(|name| name == "static");
+        match is_index {
+            true => description.parent()?.parent().map(|module| module.to_path_buf()),
+            false => None
+        }
+    }
+
+    /* Build the markdown of the description. The texts are escaped, so the content of the module can't inject links
+    or html in the hover */
+    pub fn to_markdown(&self) -> String {
+        let mut lines = vec![];
+        let mut title = self.name.as_ref().map(|name| format!("**{}**", ModuleDescription::escape_markdown(name))).unwrap_or_default();
+        if let Some(version) = self.version.as_ref() {
+            title += &format!(" {}", ModuleDescription::escape_markdown(version));
+        }
+        if !title.is_empty() {
+            lines.push(title.trim().to_string());
+        }
+        if let Some(summary) = self.summary.as_ref() {
+            lines.push(ModuleDescription::escape_markdown(summary));
+        }
+        if let Some(author) = self.author.as_ref() {
+            lines.push(format!("Author: {}", ModuleDescription::escape_markdown(author)));
+        }
+        let mut value = lines.join("  \n");
+        if let Some(paragraph) = self.paragraph.as_ref() {
+            if !value.is_empty() {
+                value += "  \n***  \n";
+            }
+            value += &ModuleDescription::escape_markdown(paragraph);
+        }
+        value
+    }
+
+    pub fn escape_markdown(text: &str) -> String {
+        let mut res = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '<' => res.push_str("&lt;"),
+                '>' => res.push_str("&gt;"),
+                '\\' | '`' | '*' | '_' | '[' | ']' | '#' | '|' | '!' => {
+                    res.push('\\');
+                    res.push(c);
+                },
+                _ => res.push(c)
+            }
+        }
+        res
+    }
+
+    fn read_file(path: &Path) -> Option<String> {
+        let file = fs::File::open(path).ok()?;
+        let mut content = vec![];
+        file.take(MAX_FILE_SIZE).read_to_end(&mut content).ok()?;
+        Some(String::from_utf8_lossy(&content).to_string())
+    }
+
+    fn decode_entities(text: &str) -> String {
+        let entity = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+        entity.replace_all(text, |captures: &regex::Captures| {
+            let name = &captures[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if name.starts_with("#x") || name.starts_with("#X") => u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32),
+                _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+                _ => None
+            };
+            decoded.map(|c| c.to_string()).unwrap_or_else(|| captures[0].to_string())
+        }).to_string()
+    }
+
+    /* A line of rst or markdown underlining a title: ====, ----, ~~~~... */
+    fn is_underline(line: &str) -> bool {
+        let line = line.trim();
+        let Some(first) = line.chars().next() else {
+            return false;
+        };
+        line.chars().count() >= 3 && "=-~^*#+`'.:_\"".contains(first) && line.chars().all(|c| c == first)
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    fn truncate(text: &str, max_length: usize) -> String {
+        match text.char_indices().nth(max_length) {
+            Some((index, _)) => format!("{}…", text[..index].trim_end()),
+            None => text.to_string()
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use crate::constants::{PackageType, SymType};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+pub const MODULES_DISCOVERED_METHOD: &str = "odooLs/modulesDiscovered";
+
+/* A module found in the addons paths, as read from its manifest */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredModule {
+    pub name: String,
+    pub path: String,
+    pub depends: Vec<String>,
+    pub indexed_first: bool, //the module, or a module depending on it, has files opened in the editor
+}
+
+/* Sent with odooLs/modulesDiscovered, in the order the modules are built */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModulesDiscovered {
+    pub modules: Vec<DiscoveredModule>,
+}
+
+/* The modules of the addons paths are discovered before their files are parsed: the symbol of a module is created from
+its manifest only, so the list of the modules and their dependencies is sent to the client as soon as the addons paths
+are walked, while the parsing of the files can take minutes. The files of the modules opened in the editor and of their
+dependencies are then built first, so the user gets the features on these files before the whole database is loaded */
+pub struct ModuleDiscovery {}
+
+impl ModuleDiscovery {
+
+    /* Return the modules the opened modules depend on, directly or not, including themselves */
+    pub fn get_priority_modules(graph: &BTreeMap<String, Vec<String>>, opened: &HashSet<String>) -> HashSet<String> {
+        let mut res = HashSet::new();
+        let mut to_visit: Vec<&String> = opened.iter().filter(|module| graph.contains_key(*module)).collect();
+        while let Some(module) = to_visit.pop() {
+            if !res.insert(module.clone()) {
+                continue;
+            }
+            for depend in graph.get(module).into_iter().flatten() {
+                if graph.contains_key(depend) && !res.contains(depend) {
+                    to_visit.push(depend);
+                }
+            }
+        }
+        res
+    }
+
+    /* Return the order in which the modules are built: the priority modules first, then the others. In each group, a
+    module comes after its dependencies, and the modules are sorted by name otherwise. The modules of a dependency cycle
+    are added by name when no other module can be added */
+    pub fn get_build_order(graph: &BTreeMap<String, Vec<String>>, priority: &HashSet<String>) -> Vec<String> {
+        let mut res: Vec<String> = vec![];
+        let mut done: HashSet<&String> = HashSet::new();
+        for in_priority in [true, false] {
+            let mut remaining: Vec<&String> = graph.keys().filter(|module| priority.contains(*module) == in_priority).collect();
+            while !remaining.is_empty() {
+                let ready = remaining.iter().position(|module| graph[*module].iter()
+                    .all(|depend| !graph.contains_key(depend) || done.contains(depend) || depend == *module)).unwrap_or(0);
+                let module = remaining.remove(ready);
+                done.insert(module);
+                res.push(module.clone());
+            }
+        }
+        res
+    }
+
+    /* Return the dependency graph of the loaded modules, by name */
+    pub fn get_graph(session: &mut SessionInfo) -> BTreeMap<String, Vec<String>> {
+        let mut res = BTreeMap::new();
+        for module in session.sync_odoo.modules.values().filter_map(|m| m.upgrade()) {
+            let module = module.borrow();
+            let module = module.as_module_package();
+            res.insert(module.dir_name.clone(), module.depends.clone());
+        }
+        res
+    }
+
+    /* Return the modules holding a file opened in the editor */
+    pub fn get_opened_modules(session: &mut SessionInfo) -> HashSet<String> {
+        let opened_paths: Vec<String> = session.sync_odoo.get_file_mgr().borrow().files.iter()
+            .filter(|(_, file_info)| file_info.borrow().opened)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut res = HashSet::new();
+        for module in session.sync_odoo.modules.values().filter_map(|m| m.upgrade()) {
+            let module = module.borrow();
+            let module = module.as_module_package();
+            if opened_paths.iter().any(|path| Path::new(path).starts_with(&module.root_path)) {
+                res.insert(module.dir_name.clone());
+            }
+        }
+        res
+    }
+
+    /* Return the module a symbol waiting for its build belongs to, or None if it is outside of the modules */
+    pub fn get_module_name(symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let symbol = symbol.borrow();
+        if matches!(symbol.typ(), SymType::PACKAGE(PackageType::MODULE)) {
+            return Some(symbol.as_module_package().dir_name.clone());
+        }
+        symbol.find_module().map(|module| module.borrow().as_module_package().dir_name.clone())
+    }
+
+    /* Send the discovered modules to the client, in their build order */
+    pub fn publish(session: &mut SessionInfo) {
+        let graph = ModuleDiscovery::get_graph(session);
+        let opened = ModuleDiscovery::get_opened_modules(session);
+        let priority = ModuleDiscovery::get_priority_modules(&graph, &opened);
+        let mut modules = vec![];
+        for name in ModuleDiscovery::get_build_order(&graph, &priority).into_iter() {
+            let Some(module) = session.sync_odoo.modules.get(&name).and_then(|m| m.upgrade()) else {
+                continue;
+            };
+            let path = module.borrow().as_module_package().root_path.clone();
+            let indexed_first = priority.contains(&name);
+            modules.push(DiscoveredModule { depends: graph[&name].clone(), name, path, indexed_first });
+        }
+        session.send_notification(MODULES_DISCOVERED_METHOD, ModulesDiscovered { modules });
+    }
+}
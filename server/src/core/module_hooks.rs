@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::{Expr, Parameters, Stmt};
+
+use crate::constants::SymType;
+use crate::core::evaluation::EvaluationSymbolWeak;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::threads::SessionInfo;
+
+/* Methods of the models called by the registry with self only */
+pub const MODEL_HOOKS: [&str; 2] = ["_register_hook", "_auto_init"];
+
+/* How a hook named in the manifest is found in the module */
+#[allow(non_camel_case_types)]
+pub enum HookResolution {
+    FUNCTION(Vec<Rc<RefCell<Symbol>>>), //the functions, followed through the imports of the __init__.py
+    NOT_CALLABLE,
+    NOT_FOUND,
+    UNKNOWN, //the name is declared, but what it holds is not known
+}
+
+/* The functions called by Odoo on a module, given as pre_init_hook, post_init_hook and uninstall_hook in the manifest,
+and the methods called by the registry on the models (_register_hook and _auto_init) */
+pub struct ModuleHooks {}
+
+impl ModuleHooks {
+
+    /* Return the parameters a hook of the manifest is called with. Since Odoo 17, hooks receive the environment. Before,
+    they receive the cursor, and the registry for post_init_hook and uninstall_hook */
+    pub fn get_hook_parameters(hook: &str, version_major: u32) -> Vec<&'static str> {
+        if version_major >= 17 {
+            vec!["env"]
+        } else if hook == "pre_init_hook" {
+            vec!["cr"]
+        } else {
+            vec!["cr", "registry"]
+        }
+    }
+
+    /* Return true if a function with these parameters can be called with count positional arguments */
+    pub fn accepts(parameters: &Parameters, count: usize) -> bool {
+        let positional: Vec<_> = parameters.posonlyargs.iter().chain(parameters.args.iter()).collect();
+        let required = positional.iter().filter(|p| p.default.is_none()).count();
+        required <= count && (positional.len() >= count || parameters.vararg.is_some()) &&
+            parameters.kwonlyargs.iter().all(|p| p.default.is_some())
+    }
+
+    /* Return true if the body calls the method of the parent class: super()._auto_init() or super(Class, self)._auto_init() */
+    pub fn calls_super(body: &[Stmt], method: &str) -> bool {
+        body.iter().flat_map(AstUtils::find_calls_in_stmt).any(|call| {
+            let Expr::Attribute(attr) = &*call.func else {
+                return false;
+            };
+            attr.attr.as_str() == method && matches!(&*attr.value, Expr::Call(parent) if matches!(&*parent.func, Expr::Name(name) if name.id.as_str() == "super"))
+        })
+    }
+
+    /* Find the function given as a hook in the top level symbols of the module */
+    pub fn resolve_hook(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>, name: &str) -> HookResolution {
+        let symbols = module.borrow().get_content_symbol(name, u32::MAX);
+        if symbols.is_empty() {
+            return HookResolution::NOT_FOUND;
+        }
+        let mut functions = vec![];
+        let mut unknown = false;
+        for symbol in symbols.iter() {
+            let evaluations = Symbol::follow_ref(&EvaluationSymbolWeak::new(Rc::downgrade(symbol), None, false), session, &mut None, false, false, None, &mut vec![]);
+            for evaluation in evaluations.iter() {
+                let Some(resolved) = evaluation.weak.upgrade() else {
+                    unknown = true;
+                    continue;
+                };
+                let typ = resolved.borrow().typ();
+                match typ {
+                    SymType::FUNCTION => functions.push(resolved),
+                    SymType::CLASS if !evaluation.instance.unwrap_or(false) || !resolved.borrow().get_content_symbol("__call__", u32::MAX).is_empty() => unknown = true,
+                    SymType::CLASS | SymType::FILE | SymType::PACKAGE(_) | SymType::NAMESPACE | SymType::COMPILED => {},
+                    _ => unknown = true,
+                }
+            }
+        }
+        if !functions.is_empty() {
+            HookResolution::FUNCTION(functions)
+        } else if unknown {
+            HookResolution::UNKNOWN
+        } else {
+            HookResolution::NOT_CALLABLE
+        }
+    }
+}
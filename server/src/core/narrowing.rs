@@ -0,0 +1,303 @@
+use ruff_python_ast::comparable::ComparableExpr;
+use ruff_python_ast::{BoolOp, CmpOp, Expr, ExprCall, ExprCompare, ExprName, StmtAssert, StmtIf, UnaryOp};
+use ruff_text_size::{Ranged, TextSize};
+
+use crate::core::python_utils;
+
+/* How a test restricts the values of a variable */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NarrowingKind {
+    INCLUDE, //the variable is one of the values
+    EXCLUDE, //the variable is none of the values
+}
+
+/* A variable whose values are restricted in a branch of an if by the tests that lead to it:
+    if state in ('sale', 'done'):   -> state INCLUDE ['sale', 'done']
+        ...
+    elif state != 'draft':          -> state EXCLUDE ['sale', 'done', 'draft']
+        ...
+    else:                           -> state INCLUDE ['draft']
+Only the comparisons of a name with literals (==, !=, in, not in, is None, is not None) and the truthiness of a name
+narrow. Chained comparisons are processed from left to right, and the other operators (0 < qty <= 10) don't narrow
+anything. */
+#[derive(Debug)]
+pub struct Narrowing<'a> {
+    pub name: &'a ExprName,
+    pub kind: NarrowingKind,
+    pub values: Vec<&'a Expr>,
+    pub truthy: bool, //the variable is also none of the falsy literals: None, False, 0 and ''
+}
+
+/* A variable or an attribute chain given to a function that may return TypeGuard[X] or TypeIs[X]:
+    if is_posted(move):             -> move narrowed to X if is_posted returns a TypeGuard or a TypeIs
+        ...
+    elif is_draft(self.move_id):    -> move narrowed to not X if is_posted returns a TypeIs, self.move_id to X if
+        ...                            is_draft returns a TypeGuard or a TypeIs
+The function is only known when the test is evaluated: the calls with a variable or an attribute chain as first
+argument are all candidates. */
+#[derive(Debug)]
+pub struct GuardedCall<'a> {
+    pub target: &'a Expr, //a name, or an attribute chain of names (self.move_id)
+    pub function: &'a Expr,
+    pub positive: bool, //false if the call returns False in the branch: only a TypeIs narrows it
+}
+
+/* A branch of an if, with the position where its narrowings start to apply. An assert and an if whose branches all
+exit (a guard clause) give a branch that applies to the rest of the block:
+    assert partner, "partner required"     -> partner truthy after the assert
+    if not self.partner_id:                 -> self.partner_id truthy after the if
+        raise UserError("partner required")
+*/
+#[derive(Debug)]
+pub struct NarrowedBranch<'a> {
+    pub start: TextSize,
+    pub narrowings: Vec<Narrowing<'a>>,
+    pub guards: Vec<GuardedCall<'a>>,
+}
+
+pub struct Narrowings {}
+
+impl Narrowings {
+
+    /* Return the branches of the if statement, in order. The narrowings of a branch start at the end of its test, or
+    at the else keyword */
+    pub fn get_branches(if_stmt: &StmtIf) -> Vec<NarrowedBranch> {
+        let mut res = vec![NarrowedBranch {
+            start: if_stmt.test.end(),
+            narrowings: Narrowings::find(&if_stmt.test, true),
+            guards: Narrowings::find_guards(&if_stmt.test, true),
+        }];
+        let mut previous_tests: Vec<&Expr> = vec![&if_stmt.test];
+        for clause in if_stmt.elif_else_clauses.iter() {
+            //the previous tests are false in this branch
+            let mut narrowings = vec![];
+            let mut guards = vec![];
+            for test in previous_tests.iter() {
+                Narrowings::visit(test, false, &mut narrowings);
+                Narrowings::visit_guards(test, false, &mut guards);
+            }
+            let start = match &clause.test {
+                Some(test) => {
+                    Narrowings::visit(test, true, &mut narrowings);
+                    Narrowings::visit_guards(test, true, &mut guards);
+                    previous_tests.push(test);
+                    test.end()
+                },
+                None => clause.range.start()
+            };
+            res.push(NarrowedBranch { start, narrowings, guards });
+        }
+        res
+    }
+
+    /* Return the narrowings of the rest of the block after an assert: its test is true */
+    pub fn get_assert_branch(assert_stmt: &StmtAssert) -> NarrowedBranch {
+        NarrowedBranch {
+            start: assert_stmt.end(),
+            narrowings: Narrowings::find(&assert_stmt.test, true),
+            guards: Narrowings::find_guards(&assert_stmt.test, true),
+        }
+    }
+
+    /* Return the narrowings of the rest of the block after an if without else whose branches all exit (see
+    python_utils::always_exits), like `if not x: raise UserError(...)`: all its tests are false after it */
+    pub fn get_guard_clause(if_stmt: &StmtIf) -> Option<NarrowedBranch> {
+        if !Narrowings::get_exiting_branches(if_stmt).iter().all(|exits| *exits)
+            || if_stmt.elif_else_clauses.last().is_some_and(|clause| clause.test.is_none()) {
+            return None;
+        }
+        let mut narrowings = vec![];
+        let mut guards = vec![];
+        for test in std::iter::once(&*if_stmt.test).chain(if_stmt.elif_else_clauses.iter().filter_map(|clause| clause.test.as_ref())) {
+            Narrowings::visit(test, false, &mut narrowings);
+            Narrowings::visit_guards(test, false, &mut guards);
+        }
+        Some(NarrowedBranch { start: if_stmt.end(), narrowings, guards })
+    }
+
+    /* Return, for each branch of the if, true if the end of its body can't be reached */
+    pub fn get_exiting_branches(if_stmt: &StmtIf) -> Vec<bool> {
+        std::iter::once(&if_stmt.body).chain(if_stmt.elif_else_clauses.iter().map(|clause| &clause.body))
+            .map(|body| python_utils::always_exits(body))
+            .collect()
+    }
+
+    /* Return the narrowings given by the test, when it is true (positive) or false */
+    pub fn find(test: &Expr, positive: bool) -> Vec<Narrowing> {
+        let mut res = vec![];
+        Narrowings::visit(test, positive, &mut res);
+        res
+    }
+
+    /* Return the calls of the test that can narrow their argument, when it is true (positive) or false */
+    pub fn find_guards(test: &Expr, positive: bool) -> Vec<GuardedCall> {
+        let mut res = vec![];
+        Narrowings::visit_guards(test, positive, &mut res);
+        res
+    }
+
+    /* Return the first argument of the call, if it is a variable or an attribute chain that can be narrowed */
+    pub fn get_guarded_argument(call: &ExprCall) -> Option<&Expr> {
+        let arg = call.arguments.args.first()?;
+        Narrowings::get_attribute_chain(arg).map(|_| arg)
+    }
+
+    /* Return the dotted name of a name or an attribute chain of names: 'self.move_id' */
+    pub fn get_attribute_chain(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Name(name) => Some(name.id.to_string()),
+            Expr::Attribute(attr) => Narrowings::get_attribute_chain(&attr.value).map(|base| format!("{}.{}", base, attr.attr)),
+            _ => None
+        }
+    }
+
+    /* Return the (left, operator, right) pairs of a comparison from left to right: a < b <= c gives (a, <, b) and (b, <=, c) */
+    pub fn get_pairs(compare: &ExprCompare) -> Vec<(&Expr, CmpOp, &Expr)> {
+        let mut res = vec![];
+        let mut left = &*compare.left;
+        for (op, right) in compare.ops.iter().zip(compare.comparators.iter()) {
+            res.push((left, *op, right));
+            left = right;
+        }
+        res
+    }
+
+    /* Return the literals that the right operand gives to the operator: the literal itself for == and !=, or the items
+    of a tuple, a list or a set of literals for in and not in */
+    pub fn get_literals(expr: &Expr, op: CmpOp) -> Option<Vec<&Expr>> {
+        match op {
+            CmpOp::Eq | CmpOp::NotEq => {
+                Narrowings::is_literal(expr).then(|| vec![expr])
+            },
+            CmpOp::Is | CmpOp::IsNot => {
+                matches!(expr, Expr::NoneLiteral(_) | Expr::BooleanLiteral(_)).then(|| vec![expr])
+            },
+            CmpOp::In | CmpOp::NotIn => {
+                let elts = match expr {
+                    Expr::Tuple(t) => &t.elts,
+                    Expr::List(l) => &l.elts,
+                    Expr::Set(s) => &s.elts,
+                    _ => return None
+                };
+                elts.iter().all(|e| Narrowings::is_literal(e)).then(|| elts.iter().collect())
+            },
+            _ => None
+        }
+    }
+
+    /* Return the operand that is compared to literals and the literals, whatever the side of the operand: 'x == 1' and '1 == x' */
+    pub fn get_compared_literals<'a>(left: &'a Expr, op: CmpOp, right: &'a Expr) -> Option<(&'a Expr, Vec<&'a Expr>)> {
+        if let Some(literals) = Narrowings::get_literals(right, op) {
+            if !Narrowings::is_literal(left) {
+                return Some((left, literals));
+            }
+        }
+        if matches!(op, CmpOp::Eq | CmpOp::NotEq | CmpOp::Is | CmpOp::IsNot) && Narrowings::is_literal(left) && !Narrowings::is_literal(right) {
+            return Some((right, vec![left]));
+        }
+        None
+    }
+
+    fn is_literal(expr: &Expr) -> bool {
+        matches!(expr, Expr::StringLiteral(_) | Expr::NumberLiteral(_) | Expr::BooleanLiteral(_) | Expr::NoneLiteral(_))
+    }
+
+    /* Return true if the literal is false in a test */
+    pub fn is_falsy(expr: &Expr) -> bool {
+        match expr {
+            Expr::NoneLiteral(_) => true,
+            Expr::BooleanLiteral(b) => !b.value,
+            Expr::StringLiteral(s) => s.value.is_empty(),
+            Expr::NumberLiteral(n) => match &n.value {
+                ruff_python_ast::Number::Int(i) => i.as_u64() == Some(0),
+                ruff_python_ast::Number::Float(f) => *f == 0.0,
+                ruff_python_ast::Number::Complex { real, imag } => *real == 0.0 && *imag == 0.0,
+            },
+            _ => false
+        }
+    }
+
+    fn visit<'a>(test: &'a Expr, positive: bool, res: &mut Vec<Narrowing<'a>>) {
+        match test {
+            Expr::UnaryOp(unary) if unary.op == UnaryOp::Not => Narrowings::visit(&unary.operand, !positive, res),
+            //'a and b' is true if a and b are true, 'a or b' is false if a and b are false
+            Expr::BoolOp(bool_op) if (bool_op.op == BoolOp::And) == positive => {
+                for value in bool_op.values.iter() {
+                    Narrowings::visit(value, positive, res);
+                }
+            },
+            Expr::Compare(compare) => {
+                let pairs = Narrowings::get_pairs(compare);
+                //a false chain only tells that one of its comparisons is false
+                if !positive && pairs.len() != 1 {
+                    return;
+                }
+                for (left, op, right) in pairs {
+                    let Some((Expr::Name(name), values)) = Narrowings::get_compared_literals(left, op, right) else {
+                        continue;
+                    };
+                    let kind = if matches!(op, CmpOp::Eq | CmpOp::In | CmpOp::Is) == positive {
+                        NarrowingKind::INCLUDE
+                    } else {
+                        NarrowingKind::EXCLUDE
+                    };
+                    Narrowings::add(res, name, kind, values);
+                }
+            },
+            //a false name can be any falsy value, or an empty recordset
+            Expr::Name(name) if positive => {
+                match res.iter_mut().find(|n| n.name.id == name.id) {
+                    Some(existing) => existing.truthy = true,
+                    None => res.push(Narrowing { name, kind: NarrowingKind::EXCLUDE, values: vec![], truthy: true })
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn visit_guards<'a>(test: &'a Expr, positive: bool, res: &mut Vec<GuardedCall<'a>>) {
+        match test {
+            Expr::UnaryOp(unary) if unary.op == UnaryOp::Not => Narrowings::visit_guards(&unary.operand, !positive, res),
+            Expr::BoolOp(bool_op) if (bool_op.op == BoolOp::And) == positive => {
+                for value in bool_op.values.iter() {
+                    Narrowings::visit_guards(value, positive, res);
+                }
+            },
+            Expr::Call(call) => {
+                if let Some(target) = Narrowings::get_guarded_argument(call) {
+                    res.push(GuardedCall { target, function: &call.func, positive });
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /* Add a narrowing that applies with the ones already found for the same name */
+    fn add<'a>(res: &mut Vec<Narrowing<'a>>, name: &'a ExprName, kind: NarrowingKind, values: Vec<&'a Expr>) {
+        let Some(existing) = res.iter_mut().find(|n| n.name.id == name.id) else {
+            res.push(Narrowing { name, kind, values, truthy: false });
+            return;
+        };
+        match (existing.kind, kind) {
+            (NarrowingKind::INCLUDE, NarrowingKind::INCLUDE) => existing.values.retain(|v| Narrowings::contains(&values, v)),
+            (NarrowingKind::INCLUDE, NarrowingKind::EXCLUDE) => existing.values.retain(|v| !Narrowings::contains(&values, v)),
+            (NarrowingKind::EXCLUDE, NarrowingKind::INCLUDE) => {
+                let excluded = std::mem::take(&mut existing.values);
+                existing.values = values.into_iter().filter(|v| !Narrowings::contains(&excluded, v)).collect();
+                existing.kind = NarrowingKind::INCLUDE;
+            },
+            (NarrowingKind::EXCLUDE, NarrowingKind::EXCLUDE) => {
+                for value in values.into_iter() {
+                    if !Narrowings::contains(&existing.values, value) {
+                        existing.values.push(value);
+                    }
+                }
+            },
+        }
+    }
+
+    /* Return true if the constant value is one of the values */
+    pub fn contains(values: &Vec<&Expr>, value: &Expr) -> bool {
+        values.iter().any(|v| ComparableExpr::from(*v) == ComparableExpr::from(value))
+    }
+}
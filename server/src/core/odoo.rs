@@ -1,8 +1,22 @@
 use crate::core::config::{Config, PythonPathRequest, PythonPathRequestResult};
+use crate::core::config_check::{ConfigCheck, ConfigReport, IndexMode};
+use crate::core::module_description::ModuleDescription;
 use crate::threads::SessionInfo;
 use crate::features::completion::CompletionFeature;
 use crate::features::definition::DefinitionFeature;
-use crate::features::hover::HoverFeature;
+use crate::features::document_symbols::DocumentSymbolFeature;
+use crate::features::file_status::{FileStatus, FileStatusFeature, FileStatuses};
+use crate::features::hover::{HoverFeature, MemberContribution};
+use crate::features::manifest::ManifestFeature;
+use crate::features::organize_imports::OrganizeImportsFeature;
+use crate::features::readonly_documents::{ReadonlyDocument, ReadonlyDocumentFeature, ReadonlyDocuments};
+use crate::features::references::ReferencesFeature;
+use crate::features::rename::RenameFeature;
+use crate::features::signature_help::SignatureHelpFeature;
+use crate::features::field_dependencies::{FieldDependencies, FieldDependenciesFeature, FieldDependenciesParams};
+use crate::features::module_graph::{ModuleGraph, ModuleGraphFeature, ModuleGraphParams};
+use crate::features::test_explorer::{TestExplorerFeature, TestList, TestListParams};
+use crate::features::workspace_symbols::WorkspaceSymbolFeature;
 use std::collections::HashMap;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
@@ -11,7 +25,8 @@ use std::sync::Arc;
 use std::time::Instant;
 use lsp_server::ResponseError;
 use lsp_types::*;
-use request::{RegisterCapability, Request, WorkspaceConfiguration};
+use request::{RegisterCapability, Request, ShowMessageRequest, WorkspaceConfiguration};
+use notification::{Notification, ShowMessage};
 use tracing::{debug, error, info, trace};
 
 use std::collections::HashSet;
@@ -22,17 +37,31 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::env;
 use std::cmp;
-use regex::Regex;
 use crate::constants::*;
 use super::config::{DiagMissingImportsMode, RefreshMode};
-use super::file_mgr::FileMgr;
+use super::auto_config::{AutoConfig, PYPROJECT_FILE};
+use super::deprecated_api::DeprecatedApiTable;
+use super::plugin_rules::PluginRules;
+use super::ignore::{IgnoreMgr, ValidationScope, IGNORE_FILES};
+use super::integrity::IntegrityChecker;
+use super::module_dependencies::{ModuleDependencies, ModuleDependenciesOptions, ModuleDependencyCheck};
+use super::module_discovery::ModuleDiscovery;
+use super::epoch::{EpochStatistics, Epochs};
+use super::evaluation_cache::{EvaluationCache, FollowRefKey, FollowRefResult};
+use super::reindex::{ReindexScope, ReindexSummary, Reindexer};
+use super::unused_fields::{ModuleUnusedFields, UnusedFields, UnusedFieldsOptions};
+use super::borrows::Borrows;
+use super::file_mgr::{FileInfo, FileMgr};
+use super::function_rebuild::FunctionRebuild;
 use super::symbols::symbol::Symbol;
 use crate::core::model::Model;
 use crate::core::python_arch_builder::PythonArchBuilder;
 use crate::core::python_arch_eval::PythonArchEval;
 use crate::core::python_odoo_builder::PythonOdooBuilder;
 use crate::core::python_validator::PythonValidator;
-use crate::utils::{PathSanitizer, ToFilePath as _};
+use crate::core::arch_cache::ArchCache;
+use crate::core::cancellation::CancellationMgr;
+use crate::utils::{clear_case_sensitive_paths, invalidate_case_sensitive_path, is_symlink_cs, PathSanitizer, ToFilePath as _};
 use crate::S;
 //use super::python_arch_builder::PythonArchBuilder;
 
@@ -57,6 +86,8 @@ pub struct SyncOdoo {
     file_mgr: Rc<RefCell<FileMgr>>,
     pub modules: HashMap<String, Weak<RefCell<Symbol>>>,
     pub models: HashMap<String, Rc<RefCell<Model>>>,
+    pub deprecated_api: DeprecatedApiTable,
+    pub plugin_rules: PluginRules,
     pub interrupt_rebuild: Arc<AtomicBool>,
     rebuild_arch: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     rebuild_arch_eval: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
@@ -66,7 +97,19 @@ pub struct SyncOdoo {
     pub not_found_symbols: PtrWeakHashSet<Weak<RefCell<Symbol>>>,
     pub must_reload_paths: Vec<(Weak<RefCell<Symbol>>, String)>,
     pub load_odoo_addons: bool, //indicate if we want to load odoo addons or not
-    pub need_rebuild: bool //if true, the next process_rebuilds will drop everything and rebuild everything
+    pub need_rebuild: bool, //if true, the next process_rebuilds will drop everything and rebuild everything
+    pub definition_link_support: bool, //the client accepts LocationLinks as result of goto definition
+    config_messages: HashSet<String>, //messages about the configuration already shown to the user, kept across resets
+    pub file_statuses: FileStatuses, //statuses of the opened files sent to the client (see FileStatusFeature)
+    pub single_files: HashSet<String>, //opened files outside of the roots, analyzed alone (see open_single_file)
+    suggested_addons: HashSet<String>, //addons paths already suggested to the user, kept across resets
+    pub epoch: u64, //incremented when a rebuild cascade starts on empty queues (see Epochs)
+    pub epoch_statistics: EpochStatistics,
+    pub evaluation_cache: EvaluationCache<FollowRefKey, FollowRefResult>, //results of Symbol::follow_ref (see EvaluationCache)
+    pub readonly_documents: ReadonlyDocuments, //documents generated for the definitions without a readable source (see ReadonlyDocumentFeature)
+    pub config_report: ConfigReport, //result of the check of the configuration done before indexing (see ConfigCheck)
+    pub arch_cache: ArchCache, //architecture of the external files kept on disk between the sessions (see ArchCache)
+    pub cancellations: CancellationMgr, //tokens of the rebuild and of the requests, shared with the dispatcher of the Server
 }
 
 unsafe impl Send for SyncOdoo {}
@@ -89,6 +132,8 @@ impl SyncOdoo {
             stdlib_dir: env::current_dir().unwrap().join("typeshed").join("stdlib").sanitize(),
             modules: HashMap::new(),
             models: HashMap::new(),
+            deprecated_api: DeprecatedApiTable::new(),
+            plugin_rules: PluginRules::new(),
             interrupt_rebuild: Arc::new(AtomicBool::new(false)),
             rebuild_arch: PtrWeakHashSet::new(),
             rebuild_arch_eval: PtrWeakHashSet::new(),
@@ -99,6 +144,18 @@ impl SyncOdoo {
             must_reload_paths: vec![],
             load_odoo_addons: true,
             need_rebuild: false,
+            definition_link_support: false,
+            config_messages: HashSet::new(),
+            file_statuses: FileStatuses::new(),
+            single_files: HashSet::new(),
+            suggested_addons: HashSet::new(),
+            epoch: 0,
+            epoch_statistics: EpochStatistics::default(),
+            evaluation_cache: EvaluationCache::new(),
+            readonly_documents: ReadonlyDocuments::default(),
+            config_report: ConfigReport::default(),
+            arch_cache: ArchCache::new(),
+            cancellations: CancellationMgr::new(),
         };
         sync_odoo
     }
@@ -106,6 +163,7 @@ impl SyncOdoo {
     pub fn reset(session: &mut SessionInfo, config: Config) {
         let symbols = Symbol::new_root();
         session.log_message(MessageType::INFO, S!("Resetting Database..."));
+        clear_case_sensitive_paths();
         info!("Resetting database...");
         session.sync_odoo.version_major = 0;
         session.sync_odoo.version_minor = 0;
@@ -119,12 +177,18 @@ impl SyncOdoo {
         session.sync_odoo.stdlib_dir = env::current_dir().unwrap().join("typeshed").join("stdlib").sanitize();
         session.sync_odoo.modules = HashMap::new();
         session.sync_odoo.models = HashMap::new();
+        session.sync_odoo.evaluation_cache.clear();
+        session.sync_odoo.readonly_documents = ReadonlyDocuments::default();
+        session.sync_odoo.deprecated_api = DeprecatedApiTable::new();
+        session.sync_odoo.plugin_rules = PluginRules::new();
         session.sync_odoo.rebuild_arch = PtrWeakHashSet::new();
         session.sync_odoo.rebuild_arch_eval = PtrWeakHashSet::new();
         session.sync_odoo.rebuild_odoo = PtrWeakHashSet::new();
         session.sync_odoo.rebuild_validation = PtrWeakHashSet::new();
+        session.sync_odoo.cancellations.clear_pending_paths();
         session.sync_odoo.state_init = InitState::NOT_READY;
         session.sync_odoo.not_found_symbols = PtrWeakHashSet::new();
+        session.sync_odoo.single_files = HashSet::new();
         session.sync_odoo.load_odoo_addons = true;
         session.sync_odoo.need_rebuild = false;
         SyncOdoo::init(session, config);
@@ -146,6 +210,13 @@ impl SyncOdoo {
             session.sync_odoo.stdlib_dir = PathBuf::from(session.sync_odoo.config.stdlib.clone()).sanitize();
         }
         info!("Using stdlib path: {}", session.sync_odoo.stdlib_dir);
+        SyncOdoo::load_deprecated_api(session);
+        SyncOdoo::load_plugin_rules(session);
+        SyncOdoo::load_ignore_rules(session);
+        SyncOdoo::load_validation_scope(session);
+        let config_report = ConfigCheck::check(&session.sync_odoo.config);
+        SyncOdoo::report_config_check(session, &config_report);
+        session.sync_odoo.config_report = config_report;
         for stub in session.sync_odoo.stubs_dirs.iter() {
             let path = Path::new(stub);
             let found = match path.exists() {
@@ -161,37 +232,198 @@ impl SyncOdoo {
                 root_symbol.add_path(stub_dir.clone());
             }
             let output = Command::new(session.sync_odoo.config.python_path.clone()).args(&["-c", "import sys; import json; print(json.dumps(sys.path))"]).output();
-            if let Err(_output) = &output {
-                error!("Wrong python command: {}", session.sync_odoo.config.python_path.clone());
-                session.send_notification("$Odoo/invalid_python_path", ());
-                session.send_notification("$Odoo/loadingStatusUpdate", "stop");
-                return;
-            }
-            let output = output.unwrap();
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                session.log_message(MessageType::INFO, format!("Detected sys.path: {}", stdout));
-                let paths: Vec<String> = serde_json::from_str(&stdout).expect("Unable to get paths with json of sys.path output");
-                for path in paths.iter() {
-                    let path = path.replace("\\\\", "\\");
-                    let pathbuf = PathBuf::from(path);
-                    if pathbuf.is_dir() {
-                        let final_path = pathbuf.sanitize();
-                        session.log_message(MessageType::INFO, format!("Adding sys.path: {}", final_path));
-                        root_symbol.add_path(final_path.clone());
-                        root_symbol.as_root_mut().sys_path.push(final_path.clone());
+            match output {
+                Err(_) => {
+                    //the database is built in the REDUCED mode, with the stubs and the stdlib only
+                    error!("Wrong python command: {}", session.sync_odoo.config.python_path.clone());
+                    session.send_notification("$Odoo/invalid_python_path", ());
+                },
+                Ok(output) if output.status.success() => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    session.log_message(MessageType::INFO, format!("Detected sys.path: {}", stdout));
+                    let paths: Vec<String> = serde_json::from_str(&stdout).expect("Unable to get paths with json of sys.path output");
+                    for path in paths.iter() {
+                        let path = path.replace("\\\\", "\\");
+                        let pathbuf = PathBuf::from(path);
+                        if pathbuf.is_dir() {
+                            let final_path = pathbuf.sanitize();
+                            session.log_message(MessageType::INFO, format!("Adding sys.path: {}", final_path));
+                            root_symbol.add_path(final_path.clone());
+                            root_symbol.as_root_mut().sys_path.push(final_path.clone());
+                        }
                     }
+                },
+                Ok(output) => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    error!("{}", stderr);
                 }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                error!("{}", stderr);
             }
         }
+        let root_paths = session.sync_odoo.symbols.as_ref().unwrap().borrow().paths().clone();
+        for path in root_paths.iter() {
+            session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(path);
+        }
+        ArchCache::configure(session);
         SyncOdoo::load_builtins(session);
         session.sync_odoo.state_init = InitState::PYTHON_READY;
         SyncOdoo::build_database(session);
         session.send_notification("$Odoo/loadingStatusUpdate", "stop");
-        info!("Time taken: {} ms", start_time.elapsed().as_millis());
+        info!("Time taken: {} ms ({} files built from the arch cache)", start_time.elapsed().as_millis(), session.sync_odoo.arch_cache.hits);
+    }
+
+    pub fn load_deprecated_api(session: &mut SessionInfo) {
+        let (table, errors) = DeprecatedApiTable::load(&session.sync_odoo.config.deprecated_api_file);
+        for error in errors.iter() {
+            session.log_message(MessageType::ERROR, error.clone());
+        }
+        session.sync_odoo.deprecated_api = table;
+    }
+
+    /* (Re)load the rules of the plugin rules file. The errors of the file are logged, and only its valid
+    rules are used */
+    pub fn load_plugin_rules(session: &mut SessionInfo) {
+        let (rules, errors) = PluginRules::read_rules(&session.sync_odoo.config.plugin_rules_file);
+        for error in errors.iter() {
+            session.log_message(MessageType::ERROR, error.clone());
+        }
+        session.sync_odoo.plugin_rules.set_rules(rules);
+    }
+
+    /* Show a message about the configuration (unreadable paths, conflicts between the settings and the files of the
+    workspace) to the user, if it has not been shown yet */
+    pub fn show_config_message(session: &mut SessionInfo, message: String) {
+        session.log_message(MessageType::WARNING, message.clone());
+        if session.sync_odoo.config_messages.insert(message.clone()) {
+            session.send_notification(ShowMessage::METHOD, ShowMessageParams{
+                typ: MessageType::WARNING,
+                message
+            });
+        }
+    }
+
+    /* Report the result of the check of the configuration. The blocking issues disable the features of Odoo: they are
+    shown in a single error message, instead of the unresolved imports they would cause. The other issues are shown like
+    the other messages about the configuration */
+    fn report_config_check(session: &mut SessionInfo, report: &ConfigReport) {
+        if let Some((major, minor, micro)) = report.odoo_version {
+            session.log_message(MessageType::INFO, format!("Detected Odoo version: {}.{}.{}", major, minor, micro));
+        }
+        if let Some((major, minor, micro)) = report.python_version {
+            session.log_message(MessageType::INFO, format!("Detected Python version: {}.{}.{}", major, minor, micro));
+        }
+        for issue in report.issues.iter().filter(|issue| !issue.blocking) {
+            SyncOdoo::show_config_message(session, issue.message.clone());
+        }
+        let blocking: Vec<String> = report.issues.iter().filter(|issue| issue.blocking).map(|issue| issue.message.clone()).collect();
+        if blocking.is_empty() {
+            return;
+        }
+        for message in blocking.iter() {
+            session.log_message(MessageType::ERROR, message.clone());
+        }
+        session.send_notification(ShowMessage::METHOD, ShowMessageParams{
+            typ: MessageType::ERROR,
+            message: format!("The features of Odoo are disabled, as the configuration is not valid. {}", blocking.join(". "))
+        });
+    }
+
+    /* (Re)build the ignore rules from the .gitignore/.ignore files of the workspace folders and the exclude setting */
+    pub fn load_ignore_rules(session: &mut SessionInfo) {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let (ignore_mgr, errors) = IgnoreMgr::load(file_mgr.borrow().workspace_folders(), &session.sync_odoo.config.exclude);
+        file_mgr.borrow_mut().set_ignore_mgr(ignore_mgr);
+        for error in errors.iter() {
+            session.log_message(MessageType::ERROR, error.clone());
+        }
+    }
+
+    /* (Re)build the validation scope from the validation settings. Return the previous scope */
+    pub fn load_validation_scope(session: &mut SessionInfo) -> ValidationScope {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let config = &session.sync_odoo.config;
+        let (validation_scope, errors) = ValidationScope::load(file_mgr.borrow().workspace_folders(),
+            &config.validation_exclude, &config.validation_include, config.validation_exclude_generated);
+        let old_scope = file_mgr.borrow_mut().set_validation_scope(validation_scope);
+        for error in errors.iter() {
+            session.log_message(MessageType::ERROR, error.clone());
+        }
+        old_scope
+    }
+
+    /* Reload the validation scope and validate again the files of the workspace whose scope changed: files that are
+    now excluded lose their validation diagnostics, while files that are now included are validated. The other steps
+    are not impacted, as excluded files are still built. */
+    pub fn apply_validation_scope(session: &mut SessionInfo) {
+        let old_scope = SyncOdoo::load_validation_scope(session);
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return;
+        }
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let mut excluded_count = 0;
+        let mut symbols = vec![session.sync_odoo.symbols.clone().unwrap()];
+        while let Some(s) = symbols.pop() {
+            symbols.extend(s.borrow().all_module_symbol().map(|x| {x.clone()}) );
+            if !s.borrow().in_workspace() || !matches!(&s.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+                continue;
+            }
+            let Some(file_info) = FileMgr::get_file_info_of_symbol(session, &s) else {
+                continue;
+            };
+            let file_info = file_info.borrow();
+            let was_excluded = old_scope.is_excluded(&file_info.uri, file_info.get_header().as_deref());
+            let is_excluded = file_mgr.borrow().is_validation_excluded(&file_info);
+            drop(file_info);
+            if is_excluded {
+                excluded_count += 1;
+            }
+            if was_excluded != is_excluded {
+                s.borrow_mut().invalidate_sub_functions(session);
+                session.sync_odoo.add_to_validations(s.clone());
+            }
+        }
+        session.log_message(MessageType::INFO, format!("{} files of the workspace are excluded from validation", excluded_count));
+        SyncOdoo::process_rebuilds(session);
+    }
+
+    /* Reload the ignore rules and update the loaded symbols accordingly: files that are now ignored leave the workspace
+    (their diagnostics are cleared) and ignored modules are unloaded, while files and modules that are not ignored anymore
+    are indexed and validated again. */
+    pub fn apply_ignore_rules(session: &mut SessionInfo) {
+        SyncOdoo::load_ignore_rules(session);
+        SyncOdoo::load_validation_scope(session);
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return;
+        }
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let mut to_unload = vec![];
+        let mut symbols = vec![session.sync_odoo.symbols.clone().unwrap()];
+        while let Some(s) = symbols.pop() {
+            let typ = s.borrow().typ();
+            if matches!(typ, SymType::FILE | SymType::PACKAGE(_)) && !s.borrow().paths().is_empty() {
+                let path = s.borrow().paths()[0].clone();
+                if typ == SymType::PACKAGE(PackageType::MODULE) && file_mgr.borrow().is_ignored(&path, true) {
+                    to_unload.push(path);
+                    continue;
+                }
+                let was_in_workspace = s.borrow().in_workspace();
+                let in_workspace = file_mgr.borrow().is_in_workspace(&path, matches!(typ, SymType::PACKAGE(_)));
+                if was_in_workspace && !in_workspace {
+                    s.borrow_mut().set_in_workspace(false);
+                    file_mgr.borrow_mut().delete_path(session, &path);
+                } else if !was_in_workspace && in_workspace {
+                    s.borrow_mut().set_in_workspace(true);
+                    s.borrow_mut().invalidate_sub_functions(session);
+                    session.sync_odoo.add_to_validations(s.clone());
+                }
+            }
+            symbols.extend(s.borrow().all_module_symbol().map(|x| {x.clone()}) );
+        }
+        for path in to_unload.iter() {
+            session.log_message(MessageType::INFO, format!("Unloading ignored module {}", path));
+            let _ = SyncOdoo::_unload_path(session, &PathBuf::from(path), true);
+        }
+        SyncOdoo::discover_modules(session);
+        SyncOdoo::process_rebuilds(session);
     }
 
     pub fn load_builtins(session: &mut SessionInfo) {
@@ -208,6 +440,10 @@ impl SyncOdoo {
     }
 
     pub fn build_database(session: &mut SessionInfo) {
+        if session.sync_odoo.config_report.mode() == IndexMode::REDUCED {
+            session.log_message(MessageType::WARNING, String::from("Odoo is not indexed, as the configuration is not valid: only the opened files are analyzed"));
+            return;
+        }
         session.log_message(MessageType::INFO, String::from("Building Database"));
         let result = SyncOdoo::build_base(session);
         if result {
@@ -216,9 +452,6 @@ impl SyncOdoo {
     }
 
     pub fn read_version(session: &mut SessionInfo, release_path: PathBuf) -> (u32, u32, u32) {
-        let mut _version_major: u32 = 14;
-        let mut _version_minor: u32 = 0;
-        let mut _version_micro: u32 = 0;
         // open release.py and get version
         let release_file = fs::read_to_string(release_path.sanitize());
         let release_file = match release_file {
@@ -228,28 +461,13 @@ impl SyncOdoo {
                 return (0, 0, 0);
             }
         };
-        for line in release_file.lines() {
-            if line.starts_with("version_info = (") {
-                let re = Regex::new(r#"version_info = \((['\"]?(\D+~)?\d+['\"]?, \d+, \d+, \w+, \d+, \D+)\)"#).unwrap();
-                let result = re.captures(line);
-                match result {
-                    Some(result) => {
-                        let version_info = result.get(1).unwrap().as_str();
-                        let version_info = version_info.split(", ").collect::<Vec<&str>>();
-                        let version_major = version_info[0].replace("saas~", "").replace("'", "").replace(r#"""#, "");
-                        _version_major = version_major.parse().unwrap();
-                        _version_minor = version_info[1].parse().unwrap();
-                        _version_micro = version_info[2].parse().unwrap();
-                        break;
-                    },
-                    None => {
-                        session.log_message(MessageType::ERROR, String::from("Unable to detect the Odoo version. Running the tool for the version 14"));
-                        break;
-                    }
-                }
+        match ConfigCheck::parse_release(&release_file) {
+            Some(version) => version,
+            None => {
+                session.log_message(MessageType::ERROR, String::from("Unable to detect the Odoo version. Running the tool for the version 14"));
+                (14, 0, 0)
             }
         }
-        (_version_major, _version_minor, _version_micro)
     }
 
     fn build_base(session: &mut SessionInfo) -> bool {
@@ -276,6 +494,7 @@ impl SyncOdoo {
         session.sync_odoo.full_version = _full_version;
         //build base
         session.sync_odoo.symbols.as_ref().unwrap().borrow_mut().add_path(session.sync_odoo.config.odoo_path.clone());
+        session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(&PathBuf::from(&odoo_path).sanitize());
         if session.sync_odoo.symbols.is_none() {
             panic!("Odoo root symbol not found")
         }
@@ -300,6 +519,7 @@ impl SyncOdoo {
                 addon_symbol.borrow_mut().add_path(
                     odoo_addon_path.sanitize()
                 );
+                session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(&odoo_addon_path.sanitize());
             }
         } else {
             let odoo_addon_path = PathBuf::from(odoo_path.clone()).join("addons");
@@ -312,35 +532,67 @@ impl SyncOdoo {
                 addon_symbol.borrow_mut().add_path(
                     addon_path.sanitize()
                 );
+                session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(&addon_path.sanitize());
             }
         }
         return true;
     }
 
-    fn build_modules(session: &mut SessionInfo) {
-        {
-            let addons_symbol = session.sync_odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX)[0].clone();
-            let addons_path = addons_symbol.borrow_mut().paths().clone();
-            for addon_path in addons_path.iter() {
-                info!("searching modules in {}", addon_path);
-                if PathBuf::from(addon_path).exists() {
-                    //browse all dir in path
-                    for item in PathBuf::from(addon_path).read_dir().expect("Unable to find odoo addons path") {
-                        match item {
-                            Ok(item) => {
-                                if item.file_type().unwrap().is_dir() && !session.sync_odoo.modules.contains_key(&item.file_name().to_str().unwrap().to_string()) {
-                                    let module_symbol = Symbol::create_from_path(session, &item.path(), addons_symbol.clone(), true);
-                                    if module_symbol.is_some() {
-                                        session.sync_odoo.add_to_rebuild_arch(module_symbol.unwrap());
+    /* Search for modules in the addons paths that are not loaded yet, and add them to the rebuild queue. Only their
+    manifest is read: the new list of the modules is sent to the client before their files are built */
+    pub fn discover_modules(session: &mut SessionInfo) {
+        let Some(addons_symbol) = session.sync_odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX).get(0).cloned() else {
+            return;
+        };
+        let addons_path = addons_symbol.borrow_mut().paths().clone();
+        for addon_path in addons_path.iter() {
+            info!("searching modules in {}", addon_path);
+            if PathBuf::from(addon_path).exists() {
+                //browse all dir in path
+                for item in PathBuf::from(addon_path).read_dir().expect("Unable to find odoo addons path") {
+                    match item {
+                        Ok(item) => {
+                            //a symlinked module is a directory too
+                            if item.path().is_dir() && !session.sync_odoo.modules.contains_key(&item.file_name().to_str().unwrap().to_string()) {
+                                if session.sync_odoo.get_file_mgr().borrow().is_ignored(&item.path().sanitize(), true) {
+                                    continue;
+                                }
+                                let module_symbol = Symbol::create_from_path(session, &item.path(), addons_symbol.clone(), true);
+                                if module_symbol.is_some() {
+                                    if is_symlink_cs(&item.path().sanitize()) {
+                                        session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(&item.path().sanitize());
                                     }
+                                    session.sync_odoo.add_to_rebuild_arch(module_symbol.unwrap());
                                 }
-                            },
-                            Err(_) => {}
-                        }
+                            }
+                        },
+                        Err(_) => {}
                     }
                 }
             }
         }
+        ModuleDiscovery::publish(session);
+    }
+
+    fn build_modules(session: &mut SessionInfo) {
+        SyncOdoo::discover_modules(session);
+        //the modules of the opened files and their dependencies are built first
+        let graph = ModuleDiscovery::get_graph(session);
+        let opened = ModuleDiscovery::get_opened_modules(session);
+        let priority = ModuleDiscovery::get_priority_modules(&graph, &opened);
+        if !priority.is_empty() && priority.len() < graph.len() {
+            let deferred: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.rebuild_arch.iter()
+                .filter(|symbol| ModuleDiscovery::get_module_name(symbol).is_some_and(|module| !priority.contains(&module)))
+                .collect();
+            for symbol in deferred.iter() {
+                session.sync_odoo.rebuild_arch.remove(symbol);
+            }
+            info!("Building {} module(s) with opened files first", priority.len());
+            SyncOdoo::process_rebuilds(session);
+            for symbol in deferred.into_iter() {
+                session.sync_odoo.add_to_rebuild_arch(symbol);
+            }
+        }
         SyncOdoo::process_rebuilds(session);
         //println!("{}", self.symbols.as_ref().unwrap().borrow_mut().debug_print_graph());
         //fs::write("out_architecture.json", self.get_symbol(&tree(vec!["odoo", "addons", "module_1"], vec![])).as_ref().unwrap().borrow().debug_to_json().to_string()).expect("Unable to write file");
@@ -413,9 +665,13 @@ impl SyncOdoo {
     }
 
     fn add_from_self_reload(session: &mut SessionInfo) {
-        for (weak_sym, path) in session.sync_odoo.must_reload_paths.clone().iter() {
+        //the paths are consumed, as a second rebuild would create the symbols again next to the ones created here
+        for (weak_sym, path) in std::mem::take(&mut session.sync_odoo.must_reload_paths).iter() {
             if let Some(parent) = weak_sym.upgrade() {
                 let in_addons = parent.borrow().get_tree() == tree(vec!["odoo", "addons"], vec![]);
+                if in_addons && session.sync_odoo.get_file_mgr().borrow().is_ignored(path, PathBuf::from(path).is_dir()) {
+                    continue;
+                }
                 let new_symbol = Symbol::create_from_path(session, &PathBuf::from(path), parent, in_addons);
                 if new_symbol.is_some() {
                     let new_symbol = new_symbol.as_ref().unwrap().clone();
@@ -430,13 +686,26 @@ impl SyncOdoo {
 
     pub fn process_rebuilds(session: &mut SessionInfo) {
         session.sync_odoo.interrupt_rebuild.store(false, Ordering::SeqCst);
+        session.sync_odoo.cancellations.get_rebuild_token().reset(); //a cancellation is aimed at the rebuild that was running
         SyncOdoo::add_from_self_reload(session);
+        FileStatusFeature::push_changes(session, true);
         let mut already_arch_rebuilt: HashSet<Tree> = HashSet::new();
         let mut already_arch_eval_rebuilt: HashSet<Tree> = HashSet::new();
         let mut already_odoo_rebuilt: HashSet<Tree> = HashSet::new();
         let mut already_validation_rebuilt: HashSet<Tree> = HashSet::new();
         while !session.sync_odoo.need_rebuild && (!session.sync_odoo.rebuild_arch.is_empty() || !session.sync_odoo.rebuild_arch_eval.is_empty() || !session.sync_odoo.rebuild_odoo.is_empty() || !session.sync_odoo.rebuild_validation.is_empty()) {
             trace!("remains: {:?} - {:?} - {:?} - {:?}", session.sync_odoo.rebuild_arch.len(), session.sync_odoo.rebuild_arch_eval.len(), session.sync_odoo.rebuild_odoo.len(), session.sync_odoo.rebuild_validation.len());
+            FileStatusFeature::push_changes(session, false);
+            FileMgr::flush_diagnostics(session, false);
+            if session.is_cancelled() {
+                //the symbols not built yet are still in the queues, the next rebuild goes on with them
+                session.sync_odoo.cancellations.get_rebuild_token().reset();
+                session.log_message(MessageType::INFO, S!("Rebuild cancelled"));
+                session.request_delayed_rebuild();
+                FileMgr::flush_diagnostics(session, true);
+                FileStatusFeature::push_changes(session, true);
+                return;
+            }
             let sym = session.sync_odoo.pop_item(BuildSteps::ARCH);
             if let Some(sym_rc) = sym {
                 let tree = sym_rc.borrow().get_tree();
@@ -445,9 +714,9 @@ impl SyncOdoo {
                     continue;
                 }
                 already_arch_rebuilt.insert(tree);
-                //TODO should delete previous first
-                let mut builder = PythonArchBuilder::new(sym_rc);
+                let mut builder = PythonArchBuilder::new(sym_rc.clone());
                 builder.load_arch(session);
+                session.sync_odoo.release_pending_path(&sym_rc);
                 continue;
             }
             let sym = session.sync_odoo.pop_item(BuildSteps::ARCH_EVAL);
@@ -459,8 +728,9 @@ impl SyncOdoo {
                 }
                 already_arch_eval_rebuilt.insert(tree);
                 //TODO should delete previous first
-                let mut builder = PythonArchEval::new(sym_rc);
+                let mut builder = PythonArchEval::new(sym_rc.clone());
                 builder.eval_arch(session);
+                session.sync_odoo.release_pending_path(&sym_rc);
                 continue;
             }
             let sym = session.sync_odoo.pop_item(BuildSteps::ODOO);
@@ -472,8 +742,9 @@ impl SyncOdoo {
                 }
                 already_odoo_rebuilt.insert(tree);
                 //TODO should delete previous first
-                let mut builder = PythonOdooBuilder::new(sym_rc);
+                let mut builder = PythonOdooBuilder::new(sym_rc.clone());
                 builder.load_odoo_content(session);
+                session.sync_odoo.release_pending_path(&sym_rc);
                 continue;
             }
             let sym = session.sync_odoo.pop_item(BuildSteps::VALIDATION);
@@ -485,17 +756,25 @@ impl SyncOdoo {
                 }
                 already_validation_rebuilt.insert(tree);
                 //TODO should delete previous first
-                let mut validator = PythonValidator::new(sym_rc);
+                let mut validator = PythonValidator::new(sym_rc.clone());
                 validator.validate(session);
+                session.sync_odoo.release_pending_path(&sym_rc);
                 if session.sync_odoo.state_init == InitState::ODOO_READY && session.sync_odoo.interrupt_rebuild.load(Ordering::SeqCst) {
                     session.sync_odoo.interrupt_rebuild.store(false, Ordering::SeqCst);
                     session.log_message(MessageType::INFO, S!("Rebuild interrupted"));
                     session.request_delayed_rebuild();
+                    FileMgr::flush_diagnostics(session, true);
+                    FileStatusFeature::push_changes(session, true);
                     return;
                 }
                 continue;
             }
         }
+        FileMgr::flush_diagnostics(session, true);
+        FileStatusFeature::push_changes(session, true);
+        session.sync_odoo.cancellations.clear_pending_paths();
+        //the files parsed in advance that were not built are likely wrong guesses
+        session.sync_odoo.get_file_mgr().borrow_mut().parse_pool.clear();
         if session.sync_odoo.need_rebuild {
             session.log_message(MessageType::INFO, S!("Rebuild required. Resetting database on breaktime..."));
             SessionInfo::request_reload(session);
@@ -517,6 +796,13 @@ impl SyncOdoo {
             sym_borrowed.set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::PENDING);
             sym_borrowed.set_build_status(BuildSteps::ODOO, BuildStatus::PENDING);
             sym_borrowed.set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+            self.start_epoch_if_idle();
+            if let Ok(mut file_mgr) = self.file_mgr.try_borrow_mut() {
+                file_mgr.parse_pool.prefetch_symbol(&sym_borrowed);
+            }
+            if let Some(path) = sym_borrowed.paths().first() {
+                self.cancellations.add_pending_path(path);
+            }
             self.rebuild_arch.insert(symbol);
         }
     }
@@ -529,6 +815,10 @@ impl SyncOdoo {
             sym_borrowed.set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::PENDING);
             sym_borrowed.set_build_status(BuildSteps::ODOO, BuildStatus::PENDING);
             sym_borrowed.set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+            self.start_epoch_if_idle();
+            if let Some(path) = sym_borrowed.paths().first() {
+                self.cancellations.add_pending_path(path);
+            }
             self.rebuild_arch_eval.insert(symbol);
         }
     }
@@ -540,14 +830,29 @@ impl SyncOdoo {
             let mut sym_borrowed = sym_clone.borrow_mut();
             sym_borrowed.set_build_status(BuildSteps::ODOO, BuildStatus::PENDING);
             sym_borrowed.set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+            self.start_epoch_if_idle();
+            if let Some(path) = sym_borrowed.paths().first() {
+                self.cancellations.add_pending_path(path);
+            }
             self.rebuild_odoo.insert(symbol);
         }
     }
 
+    /* A new epoch starts when a symbol is added to the build queues while they are empty. The validation doesn't
+    change the symbols, so its queue is not considered */
+    fn start_epoch_if_idle(&mut self) {
+        if self.get_pending_build_count() == 0 {
+            self.epoch += 1;
+        }
+    }
+
     pub fn add_to_validations(&mut self, symbol: Rc<RefCell<Symbol>>) {
         trace!("ADDED TO VALIDATION - {}", symbol.borrow().paths().first().unwrap_or(symbol.borrow().name()));
         if symbol.borrow().build_status(BuildSteps::VALIDATION) != BuildStatus::IN_PROGRESS {
             symbol.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+            if let Some(path) = symbol.borrow().paths().first() {
+                self.cancellations.add_pending_path(path);
+            }
             self.rebuild_validation.insert(symbol);
         }
     }
@@ -568,6 +873,17 @@ impl SyncOdoo {
         self.rebuild_validation.remove(symbol);
     }
 
+    /* Forget the path of a symbol built by process_rebuilds once it is in none of the queues: an edit of its file
+    doesn't make the rebuild stale anymore */
+    fn release_pending_path(&self, symbol: &Rc<RefCell<Symbol>>) {
+        if [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO, BuildSteps::VALIDATION].into_iter().any(|step| self.is_in_rebuild(symbol, step)) {
+            return;
+        }
+        if let Some(path) = symbol.borrow().paths().first() {
+            self.cancellations.remove_pending_path(path);
+        }
+    }
+
     pub fn is_in_rebuild(&self, symbol: &Rc<RefCell<Symbol>>, step: BuildSteps) -> bool {
         if step == BuildSteps::ARCH {
             return self.rebuild_arch.contains(symbol);
@@ -591,6 +907,10 @@ impl SyncOdoo {
     /* Path must be absolute. Return a valid tree according the root paths and odoo/addons path. The given
     tree may not be in the graph however */
     pub fn tree_from_path(&self, path: &PathBuf) -> Result<Tree, &str> {
+        //a single file is a child of the root, named by its path
+        if !self.single_files.is_empty() && self.single_files.contains(&path.sanitize()) {
+            return Ok((vec![path.sanitize()], vec![]));
+        }
         //First check in odoo, before anywhere else
         {
             let odoo_sym = self.symbols.as_ref().unwrap().borrow().get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX);
@@ -615,8 +935,16 @@ impl SyncOdoo {
                 let path = path.strip_prefix(root_path).unwrap().to_path_buf();
                 let mut tree: Tree = (vec![], vec![]);
                 path.components().for_each(|c| {
-                    tree.0.push(c.as_os_str().to_str().unwrap().replace(".py", "").to_string());
+                    let name = c.as_os_str().to_str().unwrap();
+                    let name = name.strip_suffix(".pyi").or_else(|| name.strip_suffix(".py")).unwrap_or(name);
+                    tree.0.push(name.to_string());
                 });
+                //a package of stubs (PEP 561) is the package it types
+                if let Some(first) = tree.0.first_mut() {
+                    if let Some(package) = first.strip_suffix("-stubs") {
+                        *first = package.to_string();
+                    }
+                }
                 if tree.0.len() > 0 && ["__init__", "__manifest__"].contains(&tree.0.last().unwrap().as_str()) {
                     tree.0.pop();
                 }
@@ -629,7 +957,10 @@ impl SyncOdoo {
     pub fn _unload_path(session: &mut SessionInfo, path: &PathBuf, clean_cache: bool) -> Result<Rc<RefCell<Symbol>>, String> {
         let ub_symbol = session.sync_odoo.symbols.as_ref().unwrap().clone();
         let symbol = ub_symbol.borrow();
-        let path_symbol = symbol.get_symbol(&session.sync_odoo.tree_from_path(&path).unwrap(), u32::MAX);
+        let Ok(tree) = session.sync_odoo.tree_from_path(&path) else {
+            return Err("Path not found in any module".to_string());
+        };
+        let path_symbol = symbol.get_symbol(&tree, u32::MAX);
         if path_symbol.is_empty() {
             return Err("Symbol not found".to_string());
         }
@@ -670,6 +1001,11 @@ impl SyncOdoo {
     /* Consider the given 'tree' path as updated (or new) and move all symbols that were searching for it
         from the not_found_symbols list to the rebuild list. Return True is something should be rebuilt */
     pub fn search_symbols_to_rebuild(session: &mut SessionInfo, tree: &Tree) -> bool {
+        //a single file is not imported by other files: it has to be created again after an unload
+        if tree.0.len() == 1 && tree.1.is_empty() && session.sync_odoo.single_files.contains(&tree.0[0]) {
+            SyncOdoo::load_single_file(session, &tree.0[0]);
+            return true;
+        }
         let flat_tree = [tree.0.clone(), tree.1.clone()].concat();
         let mut found_sym: PtrWeakHashSet<Weak<RefCell<Symbol>>> = PtrWeakHashSet::new();
         let mut need_rebuild = false;
@@ -723,6 +1059,68 @@ impl SyncOdoo {
         need_rebuild
     }
 
+    pub fn is_single_file(&self, path: &str) -> bool {
+        self.single_files.contains(path)
+    }
+
+    /* Single-file mode: a Python file opened outside of the roots (a script, a file of another repository) is added
+    to the root under its path, and built against the configured interpreter and odoo paths. It can't be imported by
+    the other files, its models are not added to the registry, and only its errors are published. It is unloaded
+    when it is closed. */
+    fn open_single_file(session: &mut SessionInfo, path: &PathBuf) {
+        session.log_message(MessageType::INFO, format!("{} is outside of the roots, it is analyzed alone", path.sanitize()));
+        session.sync_odoo.single_files.insert(path.sanitize());
+    }
+
+    fn load_single_file(session: &mut SessionInfo, path: &String) {
+        let root = session.sync_odoo.symbols.clone().unwrap();
+        if root.borrow().get_module_symbol(path).is_some() {
+            return;
+        }
+        let file = root.borrow_mut().add_new_file(session, path, path);
+        if let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(path) {
+            file_info.borrow_mut().errors_only = true;
+        }
+        session.sync_odoo.add_to_rebuild_arch(file);
+    }
+
+    fn close_single_file(session: &mut SessionInfo, path: &PathBuf) {
+        let _ = SyncOdoo::_unload_path(session, path, true);
+        session.sync_odoo.single_files.remove(&path.sanitize());
+    }
+
+    /* If the file is in a module of a directory that is not an addons path, suggest once to add this directory to the
+    addons paths of the session */
+    fn suggest_addons_path(session: &mut SessionInfo, path: &PathBuf) {
+        let Some(addons_path) = path.ancestors().skip(1)
+            .find(|dir| dir.join("__manifest__.py").exists())
+            .and_then(|module| module.parent())
+            .map(|addons_path| addons_path.to_path_buf().sanitize()) else {
+            return;
+        };
+        if session.sync_odoo.config.addons.contains(&addons_path) || !session.sync_odoo.suggested_addons.insert(addons_path.clone()) {
+            return;
+        }
+        let action = S!("Add to the addons paths");
+        let answer = session.send_request::<ShowMessageRequestParams, MessageActionItem>(ShowMessageRequest::METHOD, ShowMessageRequestParams {
+            typ: MessageType::INFO,
+            message: format!("{} is in a module of {}, that is not in the addons paths of the configuration", path.sanitize(), addons_path),
+            actions: Some(vec![MessageActionItem { title: action.clone(), properties: HashMap::new() }]),
+        });
+        if !matches!(answer, Ok(Some(item)) if item.title == action) {
+            return;
+        }
+        session.log_message(MessageType::INFO, format!("Adding {} to the addons paths", addons_path));
+        let Some(addons_symbol) = session.sync_odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX).get(0).cloned() else {
+            return;
+        };
+        session.sync_odoo.config.addons.push(addons_path.clone());
+        session.sync_odoo.get_file_mgr().borrow_mut().add_tree_root(&addons_path);
+        addons_symbol.borrow_mut().add_path(addons_path);
+        SyncOdoo::discover_modules(session);
+        SyncOdoo::process_rebuilds(session);
+    }
+
     pub fn get_file_symbol(&self, path: &PathBuf) -> Option<Rc<RefCell<Symbol>>> {
         let symbol = self.symbols.as_ref().unwrap().borrow();
         let tree = &self.tree_from_path(&path);
@@ -748,10 +1146,62 @@ impl SyncOdoo {
         SyncOdoo::process_rebuilds(session);
     }
 
+    /* Force the validation of all files of the workspace, without rebuilding their architecture */
+    pub fn revalidate_workspace(session: &mut SessionInfo) {
+        let mut symbols = vec![session.sync_odoo.symbols.clone().unwrap()];
+        while symbols.len() > 0 {
+            let s = symbols.pop();
+            if let Some(s) = s {
+                if s.borrow().in_workspace() && matches!(&s.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+                    s.borrow_mut().invalidate_sub_functions(session);
+                    session.sync_odoo.add_to_validations(s.clone());
+                }
+                symbols.extend(s.borrow().all_module_symbol().map(|x| {x.clone()}) );
+            }
+        }
+        SyncOdoo::process_rebuilds(session);
+    }
+
     pub fn get_rebuild_queue_size(&self) -> usize {
         return self.rebuild_arch.len() + self.rebuild_arch_eval.len() + self.rebuild_odoo.len() + self.rebuild_validation.len()
     }
 
+    /* Return the number of symbols waiting in the ARCH, ARCH_EVAL and ODOO queues */
+    pub fn get_pending_build_count(&self) -> usize {
+        self.rebuild_arch.len() + self.rebuild_arch_eval.len() + self.rebuild_odoo.len()
+    }
+
+    /* Build the symbols waiting in the ARCH, ARCH_EVAL and ODOO queues until the deadline, without validating them.
+    Return true if these queues are empty */
+    pub fn process_pending_builds(session: &mut SessionInfo, deadline: Instant) -> bool {
+        while Instant::now() < deadline {
+            if let Some(sym_rc) = session.sync_odoo.pop_item(BuildSteps::ARCH) {
+                PythonArchBuilder::new(sym_rc).load_arch(session);
+            } else if let Some(sym_rc) = session.sync_odoo.pop_item(BuildSteps::ARCH_EVAL) {
+                PythonArchEval::new(sym_rc).eval_arch(session);
+            } else if let Some(sym_rc) = session.sync_odoo.pop_item(BuildSteps::ODOO) {
+                PythonOdooBuilder::new(sym_rc).load_odoo_content(session);
+            } else {
+                return true;
+            }
+        }
+        session.sync_odoo.get_pending_build_count() == 0
+    }
+
+    /* Return the number of symbols built before the first pending step of the symbol, if it is in a rebuild queue. The
+    queues of the previous steps are always emptied first. The order inside a queue depends on the dependencies, so the
+    other symbols of its queue are not counted. */
+    pub fn get_rebuild_queue_position(&self, symbol: &Rc<RefCell<Symbol>>) -> Option<u32> {
+        let mut position = 0;
+        for queue in [&self.rebuild_arch, &self.rebuild_arch_eval, &self.rebuild_odoo, &self.rebuild_validation] {
+            if queue.contains(symbol) {
+                return Some(position as u32);
+            }
+            position += queue.len();
+        }
+        None
+    }
+
 }
 
 #[derive(Debug)]
@@ -773,14 +1223,7 @@ impl Odoo {
             session.log_message(MessageType::ERROR, S!("Unable to get PythonPath. Be sure that your editor support the route Odoo/getPythonPath"));
             return Err(format!("{:?}", _e));
         }
-        let python_path = python_path.unwrap();
-        let python_path = match python_path {
-            Some(p) => {p.python_path},
-            None => {
-                session.log_message(MessageType::WARNING, S!("No PythonPath provided. Be sure that your editor support the route Odoo/getPythonPath and that route always return a result. Using 'python3' instead"));
-                S!("python3")
-            }
-        };
+        let python_path = python_path.unwrap().map(|p| p.python_path);
         let config = config.get(0);
         if !config.is_some() {
             session.log_message(MessageType::ERROR, String::from("No config found for Odoo. Exiting..."));
@@ -792,6 +1235,26 @@ impl Odoo {
         let mut _auto_save_delay : u64 = 2000;
         let mut _ac_filter_model_names : bool = true;
         let mut _diag_missing_imports : DiagMissingImportsMode = DiagMissingImportsMode::All;
+        let mut _deprecated_api_file : String = S!("");
+        let mut _plugin_rules_file : String = S!("");
+        let mut _evaluation_depth : u32 = 20;
+        let mut _max_file_diagnostics : u32 = 500;
+        let mut _hover_doc_max_lines : u32 = 30;
+        let mut _exclude : Vec<String> = vec![];
+        let mut _file_logging : bool = true;
+        let mut _arch_cache : bool = true;
+        let mut _disabled_snippets : Vec<String> = vec![];
+        let mut _strict_manifest_data : bool = false;
+        let mut _validate_argument_types : bool = false;
+        let mut _report_possibly_missing_members : bool = false;
+        let mut _check_external_dependencies : bool = true;
+        let mut _unused_fields_exclude : Vec<String> = vec![];
+        let mut _module_dependencies_allowlist : Vec<String> = vec![];
+        let mut _field_consistency_severity : HashMap<String, Option<DiagnosticSeverity>> = HashMap::new();
+        let mut _validation_exclude : Vec<String> = vec![];
+        let mut _validation_include : Vec<String> = vec![];
+        let mut _validation_exclude_generated : bool = true;
+        let mut _odoo_conf_file : String = S!("odoo.conf");
         let mut selected_configuration: String = S!("");
         let mut configurations = serde_json::Map::new();
         if let Some(map) = config.as_object() {
@@ -847,6 +1310,48 @@ impl Odoo {
                             };
                         }
                     },
+                    "deprecatedApiFile" => {
+                        if let Some(path) = value.as_str() {
+                            _deprecated_api_file = path.to_string();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse deprecatedApiFile. Only the embedded table will be used"));
+                        }
+                    },
+                    "pluginRulesFile" => {
+                        if let Some(path) = value.as_str() {
+                            _plugin_rules_file = path.to_string();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse pluginRulesFile. No plugin rule will be used"));
+                        }
+                    },
+                    "evaluationDepth" => {
+                        if let Some(depth) = value.as_u64() {
+                            _evaluation_depth = depth.min(u32::MAX as u64) as u32;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse evaluationDepth. Setting it to 20"));
+                        }
+                    },
+                    "maxFileDiagnostics" => {
+                        if let Some(max) = value.as_u64() {
+                            _max_file_diagnostics = max.min(u32::MAX as u64) as u32;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse maxFileDiagnostics. Setting it to 500"));
+                        }
+                    },
+                    "hoverDocMaxLines" => {
+                        if let Some(max) = value.as_u64() {
+                            _hover_doc_max_lines = max.min(u32::MAX as u64) as u32;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse hoverDocMaxLines. Setting it to 30"));
+                        }
+                    },
+                    "exclude" => {
+                        if let Some(values) = value.as_array() {
+                            _exclude = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse exclude. It must be a list of globs"));
+                        }
+                    },
                     "configurations" => {
                         if let Some(values)= value.as_object() {
                             configurations = values.clone();
@@ -860,6 +1365,130 @@ impl Odoo {
                     "serverLogLevel" => {
                         //Too late, set it with command line
                     },
+                    "disabledSnippets" => {
+                        if let Some(values) = value.as_array() {
+                            _disabled_snippets = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse disabledSnippets. It must be a list of snippet names"));
+                        }
+                    },
+                    "strictManifestData" => {
+                        if let Some(strict_manifest_data) = value.as_bool() {
+                            _strict_manifest_data = strict_manifest_data;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse strictManifestData. Setting it to false"));
+                        }
+                    },
+                    "validateArgumentTypes" => {
+                        if let Some(validate_argument_types) = value.as_bool() {
+                            _validate_argument_types = validate_argument_types;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse validateArgumentTypes. Setting it to false"));
+                        }
+                    },
+                    "reportPossiblyMissingMembers" => {
+                        if let Some(report_possibly_missing_members) = value.as_bool() {
+                            _report_possibly_missing_members = report_possibly_missing_members;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse reportPossiblyMissingMembers. Setting it to false"));
+                        }
+                    },
+                    "checkExternalDependencies" => {
+                        if let Some(check_external_dependencies) = value.as_bool() {
+                            _check_external_dependencies = check_external_dependencies;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse checkExternalDependencies. Setting it to true"));
+                        }
+                    },
+                    "unusedFieldsExclude" => {
+                        if let Some(values) = value.as_array() {
+                            _unused_fields_exclude = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse unusedFieldsExclude. It must be a list of globs"));
+                        }
+                    },
+                    "moduleDependenciesAllowlist" => {
+                        if let Some(values) = value.as_array() {
+                            _module_dependencies_allowlist = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse moduleDependenciesAllowlist. It must be a list of module names"));
+                        }
+                    },
+                    "fieldConsistencySeverity" => {
+                        if let Some(values) = value.as_object() {
+                            for (code, severity) in values {
+                                let severity = match severity.as_str() {
+                                    Some("error") => Some(DiagnosticSeverity::ERROR),
+                                    Some("warning") => Some(DiagnosticSeverity::WARNING),
+                                    Some("information") => Some(DiagnosticSeverity::INFORMATION),
+                                    Some("hint") => Some(DiagnosticSeverity::HINT),
+                                    Some("none") => None,
+                                    _ => {
+                                        session.log_message(MessageType::ERROR, format!("Unable to parse the severity of {} in fieldConsistencySeverity. It must be error, warning, information, hint or none", code));
+                                        continue;
+                                    }
+                                };
+                                _field_consistency_severity.insert(code.clone(), severity);
+                            }
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse fieldConsistencySeverity. It must be an object giving the severity of each rule"));
+                        }
+                    },
+                    "validation" => {
+                        if let Some(validation_config) = value.as_object() {
+                            for (key, value) in validation_config {
+                                match key.as_str() {
+                                    "exclude" => {
+                                        if let Some(values) = value.as_array() {
+                                            _validation_exclude = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                                        } else {
+                                            session.log_message(MessageType::ERROR, String::from("Unable to parse validation.exclude. It must be a list of globs"));
+                                        }
+                                    },
+                                    "include" => {
+                                        if let Some(values) = value.as_array() {
+                                            _validation_include = values.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect();
+                                        } else {
+                                            session.log_message(MessageType::ERROR, String::from("Unable to parse validation.include. It must be a list of globs"));
+                                        }
+                                    },
+                                    "excludeGenerated" => {
+                                        if let Some(exclude_generated) = value.as_bool() {
+                                            _validation_exclude_generated = exclude_generated;
+                                        } else {
+                                            session.log_message(MessageType::ERROR, String::from("Unable to parse validation.excludeGenerated. Setting it to true"));
+                                        }
+                                    },
+                                    _ => {
+                                        session.log_message(MessageType::ERROR, format!("Unknown validation config key: validation.{}", key));
+                                    },
+                                }
+                            }
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse validation config"));
+                        }
+                    },
+                    "odooConfFile" => {
+                        if let Some(odoo_conf_file) = value.as_str() {
+                            _odoo_conf_file = odoo_conf_file.to_string();
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse odooConfFile. Setting it to odoo.conf"));
+                        }
+                    },
+                    "fileLogging" => {
+                        if let Some(file_logging) = value.as_bool() {
+                            _file_logging = file_logging;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse fileLogging. Setting it to true"));
+                        }
+                    },
+                    "archCache" => {
+                        if let Some(arch_cache) = value.as_bool() {
+                            _arch_cache = arch_cache;
+                        } else {
+                            session.log_message(MessageType::ERROR, String::from("Unable to parse archCache. Setting it to true"));
+                        }
+                    },
                     _ => {
                         session.log_message(MessageType::ERROR, format!("Unknown config key: {}", key));
                     },
@@ -879,13 +1508,48 @@ impl Odoo {
         } else {
             config.addons = vec![];
             config.odoo_path = S!("");
+        }
+        //the paths of the editor settings are completed by the ones found in the files of the workspace
+        let (auto_config, mut messages) = AutoConfig::load(session.sync_odoo.get_file_mgr().borrow().workspace_folders(), &_odoo_conf_file);
+        let has_python_path = python_path.is_some() || auto_config.python_path.is_some();
+        messages.extend(auto_config.merge(&mut config, python_path));
+        for message in messages.into_iter() {
+            SyncOdoo::show_config_message(session, message);
+        }
+        if config.odoo_path.is_empty() {
             session.log_message(MessageType::ERROR, S!("Unable to find selected configuration. No odoo path has been found."));
         }
-        config.python_path = python_path.clone();
+        if !has_python_path {
+            session.log_message(MessageType::WARNING, S!("No PythonPath provided. Be sure that your editor support the route Odoo/getPythonPath and that route always return a result. Using 'python3' instead"));
+        }
+        for (path, source) in config.path_sources.iter() {
+            session.log_message(MessageType::INFO, format!("Using {} (from {})", path, source));
+        }
         config.refresh_mode = _refresh_mode;
         config.auto_save_delay = _auto_save_delay;
         config.ac_filter_model_names = _ac_filter_model_names;
         config.diag_missing_imports = _diag_missing_imports;
+        config.deprecated_api_file = _deprecated_api_file;
+        config.plugin_rules_file = _plugin_rules_file;
+        config.evaluation_depth = _evaluation_depth;
+        config.max_file_diagnostics = _max_file_diagnostics;
+        config.hover_doc_max_lines = _hover_doc_max_lines;
+        config.exclude = _exclude;
+        config.file_logging = _file_logging;
+        config.disabled_snippets = _disabled_snippets;
+        config.strict_manifest_data = _strict_manifest_data;
+        config.validate_argument_types = _validate_argument_types;
+        config.report_possibly_missing_members = _report_possibly_missing_members;
+        config.check_external_dependencies = _check_external_dependencies;
+        config.unused_fields_exclude = _unused_fields_exclude;
+        config.module_dependencies_allowlist = _module_dependencies_allowlist;
+        config.field_consistency_severity = _field_consistency_severity;
+        config.validation_exclude = _validation_exclude;
+        config.validation_include = _validation_include;
+        config.validation_exclude_generated = _validation_exclude_generated;
+        config.odoo_conf_file = _odoo_conf_file;
+        config.arch_cache = _arch_cache;
+        crate::trace::set_file_logging(config.file_logging);
 
         debug!("Final config: {:?}", config);
         Ok(config)
@@ -959,6 +1623,15 @@ impl Odoo {
         info!("Registered Capabilities");
     }
 
+    /* Return the file info of the path if its ast is parsed. The requests take the file manager and the file with
+    try_borrow, and answer nothing instead of panicking if they are borrowed (see Borrows) */
+    fn get_parsed_file_info(session: &mut SessionInfo, path: &String) -> Option<Rc<RefCell<FileInfo>>> {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let file_info = Borrows::try_borrow(&file_mgr)?.get_file_info(path)?;
+        let parsed = Borrows::try_borrow(&file_info)?.ast.is_some();
+        parsed.then_some(file_info)
+    }
+
     pub fn handle_hover(session: &mut SessionInfo, params: HoverParams) -> Result<Option<Hover>, ResponseError> {
         if session.sync_odoo.state_init == InitState::NOT_READY {
             return Ok(None);
@@ -967,14 +1640,284 @@ impl Odoo {
             params.text_document_position_params.text_document.uri.to_string(),
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character));
-        let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        let path = FileMgr::uri2canonical(session, params.text_document_position_params.text_document.uri.as_str());
+        if ManifestFeature::is_manifest(&path) {
+            if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                return Ok(ManifestFeature::get_hover(session, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+            }
+            return Ok(None);
+        }
         if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
-                let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
-                if let Some(file_info) = file_info {
-                    if file_info.borrow().ast.is_some() {
-                        return Ok(HoverFeature::get_hover(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
-                    }
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(HoverFeature::get_hover(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_references(session: &mut SessionInfo, params: ReferenceParams) -> Result<Option<Vec<Location>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("References requested on {} at {} - {}",
+            params.text_document_position.text_document.uri.to_string(),
+            params.text_document_position.position.line,
+            params.text_document_position.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document_position.text_document.uri.as_str());
+        if params.text_document_position.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(ReferencesFeature::get_references(session, &file_symbol, &file_info,
+                        params.text_document_position.position.line,
+                        params.text_document_position.position.character,
+                        params.context.include_declaration));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_prepare_rename(session: &mut SessionInfo, params: TextDocumentPositionParams) -> Result<Option<PrepareRenameResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Prepare rename requested on {} at {} - {}",
+            params.text_document.uri.to_string(),
+            params.position.line,
+            params.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document.uri.as_str());
+        if params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return RenameFeature::prepare_rename(session, &file_symbol, &file_info, params.position.line, params.position.character)
+                        .map(Some)
+                        .map_err(Odoo::request_failed);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_rename(session: &mut SessionInfo, params: RenameParams) -> Result<Option<WorkspaceEdit>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Rename to '{}' requested on {} at {} - {}",
+            params.new_name,
+            params.text_document_position.text_document.uri.to_string(),
+            params.text_document_position.position.line,
+            params.text_document_position.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document_position.text_document.uri.as_str());
+        if params.text_document_position.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return RenameFeature::rename(session, &file_symbol, &file_info,
+                        params.text_document_position.position.line,
+                        params.text_document_position.position.character,
+                        &params.new_name)
+                        .map(Some)
+                        .map_err(Odoo::request_failed);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /* The error of a request that can't be answered, with a message the client shows to the user */
+    fn request_failed(message: String) -> ResponseError {
+        ResponseError { code: lsp_server::ErrorCode::RequestFailed as i32, message, data: None }
+    }
+
+    /* Answer a request with its handler. The features skip the values that are already borrowed (see Borrows): a request
+    that met such a value would get an incomplete answer, and is refused with ContentModified, so that the client
+    retries it */
+    pub fn handle_request<P, R>(session: &mut SessionInfo, params: P, handler: fn(&mut SessionInfo, P) -> Result<Option<R>, ResponseError>) -> Result<Option<R>, ResponseError> {
+        Borrows::take_conflicts();
+        let result = handler(session, params);
+        if Borrows::take_conflicts() {
+            return Err(ResponseError { code: lsp_server::ErrorCode::ContentModified as i32, message: S!("The server is busy, retry"), data: None });
+        }
+        result
+    }
+
+    pub fn handle_document_symbol(session: &mut SessionInfo, params: DocumentSymbolParams) -> Result<Option<DocumentSymbolResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Document symbols requested on {}", params.text_document.uri.to_string()));
+        let path = FileMgr::uri2canonical(session, params.text_document.uri.as_str());
+        if params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(DocumentSymbolFeature::get_document_symbols(&file_symbol, &file_info));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_workspace_symbol(session: &mut SessionInfo, params: WorkspaceSymbolParams) -> Result<Option<WorkspaceSymbolResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Workspace symbols requested for '{}'", params.query));
+        Ok(Some(WorkspaceSymbolResponse::Flat(WorkspaceSymbolFeature::get_workspace_symbols(session, &params.query))))
+    }
+
+    pub fn handle_signature_help(session: &mut SessionInfo, params: SignatureHelpParams) -> Result<Option<SignatureHelp>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Signature help requested on {} at {} - {}",
+            params.text_document_position_params.text_document.uri.to_string(),
+            params.text_document_position_params.position.line,
+            params.text_document_position_params.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document_position_params.text_document.uri.as_str());
+        if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(SignatureHelpFeature::get_signature_help(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn handle_member_contributions(session: &mut SessionInfo, params: TextDocumentPositionParams) -> Result<Option<Vec<MemberContribution>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Member contributions requested on {} at {} - {}",
+            params.text_document.uri.to_string(),
+            params.position.line,
+            params.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document.uri.as_str());
+        if params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(HoverFeature::get_member_contributions(session, &file_symbol, &file_info, params.position.line, params.position.character));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /* Return the status of any file (see FileStatusFeature). The opened files also receive it as a notification */
+    pub fn handle_file_status(session: &mut SessionInfo, params: TextDocumentIdentifier) -> Result<Option<FileStatus>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let path = FileMgr::uri2canonical(session, params.uri.as_str());
+        Ok(FileStatusFeature::get_file_status(session, &path))
+    }
+
+    pub fn handle_readonly_document(session: &mut SessionInfo, params: TextDocumentIdentifier) -> Result<Option<ReadonlyDocument>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(ReadonlyDocumentFeature::get_document(session, params.uri.as_str()))
+    }
+
+    /* Return the Odoo tests of a module, or of all the modules of the workspace, for the test explorer of the client */
+    pub fn handle_test_list(session: &mut SessionInfo, params: TestListParams) -> Result<Option<TestList>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(TestExplorerFeature::list(session, params.module.as_ref())))
+    }
+
+    /* Return the fields a field of a model depends on, and the fields that depend on it, for the impact graph of the client */
+    pub fn handle_field_dependencies(session: &mut SessionInfo, params: FieldDependenciesParams) -> Result<Option<FieldDependencies>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(FieldDependenciesFeature::get(session, &params)))
+    }
+
+    pub fn handle_module_graph(session: &mut SessionInfo, params: ModuleGraphParams) -> Result<Option<ModuleGraph>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        Ok(Some(ModuleGraphFeature::get(session, &params)))
+    }
+
+    /* Debug command checking the consistency of the symbol tree. The violations are logged and returned */
+    pub fn handle_check_integrity(session: &mut SessionInfo, _params: ExecuteCommandParams) -> Result<Option<Vec<String>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let start = Instant::now();
+        let violations = IntegrityChecker::check(session);
+        session.log_message(MessageType::INFO, format!("Integrity check done in {} ms: {} violation(s)", start.elapsed().as_millis(), violations.len()));
+        for violation in violations.iter() {
+            session.log_message(MessageType::WARNING, violation.clone());
+        }
+        Ok(Some(violations))
+    }
+
+    /* Rebuild the part of the index given by the arguments, and return a summary of the rebuild */
+    pub fn handle_reindex(session: &mut SessionInfo, params: ExecuteCommandParams) -> Result<Option<ReindexSummary>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let invalid_params = |message: String| ResponseError { code: lsp_server::ErrorCode::InvalidParams as i32, message, data: None };
+        let scope = ReindexScope::from_arguments(&params.arguments).map_err(invalid_params)?;
+        Reindexer::reindex(session, scope).map(Some).map_err(invalid_params)
+    }
+
+    /* Search the fields of the workspace modules that are never used (see UnusedFields) */
+    pub fn handle_find_unused_fields(session: &mut SessionInfo, params: ExecuteCommandParams) -> Result<Option<Vec<ModuleUnusedFields>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let invalid_params = |message: String| ResponseError { code: lsp_server::ErrorCode::InvalidParams as i32, message, data: None };
+        let options = UnusedFieldsOptions::from_arguments(&params.arguments).map_err(invalid_params)?;
+        UnusedFields::find(session, options).map(Some).map_err(invalid_params)
+    }
+
+    /* Check the depends of the manifests of the workspace modules against the modules they use (see ModuleDependencyCheck) */
+    pub fn handle_check_module_dependencies(session: &mut SessionInfo, params: ExecuteCommandParams) -> Result<Option<Vec<ModuleDependencies>>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        let invalid_params = |message: String| ResponseError { code: lsp_server::ErrorCode::InvalidParams as i32, message, data: None };
+        let options = ModuleDependenciesOptions::from_arguments(&params.arguments).map_err(invalid_params)?;
+        Ok(Some(ModuleDependencyCheck::check(session, options)))
+    }
+
+    /* Debug command returning the counters of the reads done while a rebuild is in flight (see Epochs), and of the
+    evaluation cache */
+    pub fn handle_statistics(session: &mut SessionInfo, _params: ExecuteCommandParams) -> Result<Option<EpochStatistics>, ResponseError> {
+        let mut statistics = session.sync_odoo.epoch_statistics.clone();
+        statistics.epoch = session.sync_odoo.epoch;
+        statistics.evaluation_cache_hits = session.sync_odoo.evaluation_cache.hits;
+        statistics.evaluation_cache_misses = session.sync_odoo.evaluation_cache.misses;
+        statistics.evaluation_cache_entries = session.sync_odoo.evaluation_cache.len();
+        statistics.arch_cache_hits = session.sync_odoo.arch_cache.hits;
+        statistics.arch_cache_misses = session.sync_odoo.arch_cache.misses;
+        statistics.index_mode = session.sync_odoo.config_report.mode();
+        statistics.config_issues = session.sync_odoo.config_report.issues.iter().map(|issue| issue.message.clone()).collect();
+        Ok(Some(statistics))
+    }
+
+/* Remove the entries of the ArchCache, and return their number. The files already built are kept, the next sessions
+    parse them again */
+    pub fn handle_clear_arch_cache(session: &mut SessionInfo, _params: ExecuteCommandParams) -> Result<Option<usize>, ResponseError> {
+        Ok(Some(session.sync_odoo.arch_cache.clear()))
+    }
+
+        pub fn handle_code_action(session: &mut SessionInfo, params: CodeActionParams) -> Result<Option<CodeActionResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("Code actions requested on {}", params.text_document.uri.to_string()));
+        let path = FileMgr::uri2canonical(session, params.text_document.uri.as_str());
+        if params.text_document.uri.to_string().ends_with(".py") {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(OrganizeImportsFeature::get_code_actions(session, &file_symbol, &file_info, &params.text_document.uri, params.context.only.as_ref()));
                 }
             }
         }
@@ -989,14 +1932,39 @@ impl Odoo {
             params.text_document_position_params.text_document.uri.to_string(),
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character));
-        let path = FileMgr::uri2pathname(params.text_document_position_params.text_document.uri.as_str());
+        let path = FileMgr::uri2canonical(session, params.text_document_position_params.text_document.uri.as_str());
+        if ManifestFeature::is_manifest(&path) {
+            if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                return Ok(ManifestFeature::get_location(session, &path, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+            }
+            return Ok(None);
+        }
         if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
-                let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
-                if let Some(file_info) = file_info {
-                    if file_info.borrow().ast.is_some() {
-                        return Ok(DefinitionFeature::get_location(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
-                    }
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(DefinitionFeature::get_location(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+                }
+            }
+        }
+        if params.text_document_position_params.text_document.uri.to_string().ends_with(".xml") {
+            return Ok(DefinitionFeature::get_xml_location(session, &path, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
+        }
+        Ok(None)
+    }
+
+    pub fn handle_goto_declaration(session: &mut SessionInfo, params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>, ResponseError> {
+        if session.sync_odoo.state_init == InitState::NOT_READY {
+            return Ok(None);
+        }
+        session.log_message(MessageType::INFO, format!("GoToDeclaration requested on {} at {} - {}",
+            params.text_document_position_params.text_document.uri.to_string(),
+            params.text_document_position_params.position.line,
+            params.text_document_position_params.position.character));
+        let path = FileMgr::uri2canonical(session, params.text_document_position_params.text_document.uri.as_str());
+        if params.text_document_position_params.text_document.uri.to_string().ends_with(".py") && !ManifestFeature::is_manifest(&path) {
+            if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(DefinitionFeature::get_declaration(session, &file_symbol, &file_info, params.text_document_position_params.position.line, params.text_document_position_params.position.character));
                 }
             }
         }
@@ -1012,14 +1980,18 @@ impl Odoo {
             params.text_document_position.position.line,
             params.text_document_position.position.character
             ));
-        let path = FileMgr::uri2pathname(params.text_document_position.text_document.uri.as_str());
+        let path = FileMgr::uri2canonical(session, params.text_document_position.text_document.uri.as_str());
+        if ManifestFeature::is_manifest(&path) {
+            if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                return Ok(ManifestFeature::autocomplete(session, &path, &file_info, params.text_document_position.position.line, params.text_document_position.position.character));
+            }
+            return Ok(None);
+        }
         if params.text_document_position.text_document.uri.to_string().ends_with(".py") {
             if let Some(file_symbol) = session.sync_odoo.get_file_symbol(&PathBuf::from(path.clone())) {
-                let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path);
-                if let Some(file_info) = file_info {
-                    if file_info.borrow().ast.is_some() {
-                        return Ok(CompletionFeature::autocomplete(session, &file_symbol, &file_info, params.text_document_position.position.line, params.text_document_position.position.character));
-                    }
+                Epochs::ensure_coherent_read(session, &file_symbol)?;
+                if let Some(file_info) = Odoo::get_parsed_file_info(session, &path) {
+                    return Ok(CompletionFeature::autocomplete(session, &file_symbol, &file_info, params.text_document_position.position.line, params.text_document_position.position.character));
                 }
             }
         }
@@ -1031,8 +2003,11 @@ impl Odoo {
         match Odoo::update_configuration(session) {
             Ok (config) => {
                 session.sync_odoo.config = config.clone();
+                //a configuration that was not valid is checked again on any change
                 if config.odoo_path != old_config.odoo_path ||
                     config.addons != old_config.addons ||
+                    config.python_path != old_config.python_path ||
+                    session.sync_odoo.config_report.mode() == IndexMode::REDUCED ||
                     config.additional_stubs != old_config.additional_stubs ||
                     config.stdlib != old_config.stdlib {
                         SyncOdoo::reset(session, config);
@@ -1043,6 +2018,33 @@ impl Odoo {
                     if old_config.auto_save_delay != session.sync_odoo.config.auto_save_delay {
                         session.update_auto_refresh_delay(session.sync_odoo.config.auto_save_delay);
                     }
+                    if old_config.exclude != session.sync_odoo.config.exclude {
+                        SyncOdoo::apply_ignore_rules(session);
+                    }
+                    if old_config.validation_exclude != session.sync_odoo.config.validation_exclude ||
+                        old_config.validation_include != session.sync_odoo.config.validation_include ||
+                        old_config.validation_exclude_generated != session.sync_odoo.config.validation_exclude_generated {
+                        SyncOdoo::apply_validation_scope(session);
+                    }
+                    if old_config.deprecated_api_file != session.sync_odoo.config.deprecated_api_file {
+                        SyncOdoo::load_deprecated_api(session);
+                        SyncOdoo::revalidate_workspace(session);
+                    }
+                    if old_config.max_file_diagnostics != session.sync_odoo.config.max_file_diagnostics {
+                        FileMgr::republish_diagnostics(session);
+                    }
+                    if old_config.evaluation_depth != session.sync_odoo.config.evaluation_depth ||
+                        old_config.check_external_dependencies != session.sync_odoo.config.check_external_dependencies {
+                        session.sync_odoo.evaluation_cache.clear();
+                        SyncOdoo::refresh_evaluations(session);
+                    }
+                    if old_config.plugin_rules_file != session.sync_odoo.config.plugin_rules_file {
+                        SyncOdoo::load_plugin_rules(session);
+                        SyncOdoo::refresh_evaluations(session);
+                    }
+                    if old_config.arch_cache != session.sync_odoo.config.arch_cache {
+                        ArchCache::configure(session);
+                    }
                 }
             },
             Err(e) => {
@@ -1056,21 +2058,70 @@ impl Odoo {
         let file_mgr = session.sync_odoo.get_file_mgr();
         let mut file_mgr = file_mgr.borrow_mut();
         for added in params.event.added {
-            file_mgr.add_workspace_folder(added.uri.to_string());
+            file_mgr.add_workspace_folder(FileMgr::uri2pathname(added.uri.as_str()));
         }
         for removed in params.event.removed {
-            file_mgr.remove_workspace_folder(removed.uri.to_string());
+            file_mgr.remove_workspace_folder(session, FileMgr::uri2pathname(removed.uri.as_str()));
         }
+        drop(file_mgr);
+        SyncOdoo::apply_ignore_rules(session);
     }
 
     pub fn handle_did_change_watched_files(session: &mut SessionInfo, params: DidChangeWatchedFilesParams) {
         let mut to_create = vec![];
         let mut to_delete = vec![];
         let mut to_change = vec![];
+        let mut reload_deprecated_api = false;
+        let mut reload_plugin_rules = false;
+        let mut reload_ignore_rules = false;
+        let mut reload_configuration = false;
+        let mut created_manifests = false;
+        let mut deleted_modules = vec![];
+        let config_files: Vec<String> = session.sync_odoo.get_file_mgr().borrow().workspace_folders().iter().flat_map(|folder| {
+            let mut files = vec![PathBuf::from(folder).join(PYPROJECT_FILE).sanitize()];
+            if !session.sync_odoo.config.odoo_conf_file.is_empty() {
+                files.push(PathBuf::from(folder).join(&session.sync_odoo.config.odoo_conf_file).sanitize());
+            }
+            files
+        }).collect();
         for event in params.changes {
+            let path = FileMgr::uri2pathname(event.uri.as_str());
+            let path_buf = PathBuf::from(&path);
+            if path_buf.file_name().is_some_and(|f| IGNORE_FILES.contains(&f.to_str().unwrap_or(""))) &&
+                path_buf.parent().is_some_and(|p| session.sync_odoo.get_file_mgr().borrow().workspace_folders().contains(&p.sanitize())) {
+                reload_ignore_rules = true;
+                continue;
+            }
+            if config_files.contains(&path) {
+                reload_configuration = true;
+                continue;
+            }
             if event.uri.to_string().contains(".git") {
                 continue;
             }
+            if session.sync_odoo.get_file_mgr().borrow().is_ignored(&path, PathBuf::from(&path).is_dir()) {
+                continue;
+            }
+            if !session.sync_odoo.config.deprecated_api_file.is_empty() &&
+                FileMgr::uri2pathname(event.uri.as_str()) == PathBuf::from(&session.sync_odoo.config.deprecated_api_file).sanitize() {
+                reload_deprecated_api = true;
+                continue;
+            }
+            if !session.sync_odoo.config.plugin_rules_file.is_empty() &&
+                FileMgr::uri2pathname(event.uri.as_str()) == PathBuf::from(&session.sync_odoo.config.plugin_rules_file).sanitize() {
+                reload_plugin_rules = true;
+                continue;
+            }
+            if let Some(module_path) = ModuleDescription::get_module_path(&path_buf) {
+                Odoo::reset_module_description(session, &module_path.sanitize());
+            }
+            if path_buf.file_name().is_some_and(|f| f == "__manifest__.py") {
+                match event.typ {
+                    FileChangeType::CREATED => created_manifests = true,
+                    FileChangeType::DELETED => deleted_modules.extend(path_buf.parent().map(|dir| dir.sanitize())),
+                    _ => {}
+                }
+            }
             match event.typ {
                 FileChangeType::CREATED  => { to_create.push(FileCreate{uri: event.uri.to_string()}); }
                 FileChangeType::DELETED => { to_delete.push(FileDelete{uri: event.uri.to_string()}); }
@@ -1093,6 +2144,52 @@ impl Odoo {
         if !to_change.is_empty() {
             Odoo::handle_file_update(session, &to_change);
         }
+        if (created_manifests || !deleted_modules.is_empty()) && session.sync_odoo.state_init != InitState::NOT_READY {
+            Odoo::update_discovered_modules(session, &deleted_modules);
+        }
+        if reload_ignore_rules {
+            SyncOdoo::apply_ignore_rules(session);
+        }
+        if reload_deprecated_api && session.sync_odoo.state_init != InitState::NOT_READY {
+            SyncOdoo::load_deprecated_api(session);
+            SyncOdoo::revalidate_workspace(session);
+        }
+        if reload_plugin_rules && session.sync_odoo.state_init != InitState::NOT_READY {
+            //the registries of decorator rules are filled when files are evaluated
+            SyncOdoo::load_plugin_rules(session);
+            SyncOdoo::refresh_evaluations(session);
+        }
+        if reload_configuration && session.sync_odoo.state_init != InitState::NOT_READY {
+            //the paths found in these files are merged with the editor settings
+            Odoo::handle_did_change_configuration(session, DidChangeConfigurationParams { settings: serde_json::Value::Null });
+        }
+    }
+
+    /* A manifest has been added or removed: the directory of a removed manifest is not a module anymore, and the
+    directory of a new one is a new module. The list of the modules is sent again by discover_modules */
+    fn update_discovered_modules(session: &mut SessionInfo, deleted_modules: &Vec<String>) {
+        for dir in deleted_modules.iter() {
+            let is_module = session.sync_odoo.modules.values().filter_map(|m| m.upgrade())
+                .any(|m| m.borrow().as_module_package().root_path == *dir);
+            if is_module {
+                session.log_message(MessageType::INFO, format!("Unloading module {}, as its manifest has been removed", dir));
+                let _ = SyncOdoo::_unload_path(session, &PathBuf::from(dir), false);
+            }
+        }
+        SyncOdoo::discover_modules(session);
+        SyncOdoo::process_rebuilds(session);
+    }
+
+    /* Forget the cached description of the module at the given path, to read it again on the next hover */
+    fn reset_module_description(session: &mut SessionInfo, module_path: &String) {
+        let Some(dir_name) = PathBuf::from(module_path).file_name().and_then(|name| name.to_str()).map(|name| name.to_string()) else {
+            return;
+        };
+        if let Some(module) = session.sync_odoo.modules.get(&dir_name).and_then(|module| module.upgrade()) {
+            if module.borrow().as_module_package().root_path == *module_path {
+                module.borrow_mut().as_module_package_mut().reset_description();
+            }
+        }
     }
 
     fn handle_file_update(session: &mut SessionInfo, file_uris: &Vec<Uri>) {
@@ -1100,7 +2197,7 @@ impl Odoo {
             return
         }
         for uri in file_uris.iter() {
-            let path = uri.to_file_path().unwrap();
+            let path = PathBuf::from(FileMgr::uri2canonical(session, uri.as_str()));
             session.log_message(MessageType::INFO, format!("File update: {}", path.sanitize()));
             if Odoo::update_file_cache(session, path.clone(), None, -100) {
                 Odoo::update_file_index(session, path, true, false);
@@ -1111,8 +2208,20 @@ impl Odoo {
     pub fn handle_did_open(session: &mut SessionInfo, params: DidOpenTextDocumentParams) {
         //to implement Incremental update of file caches, we have to handle DidOpen notification, to be sure
         // that we use the same base version of the file for future incrementation.
-        let path = params.text_document.uri.to_file_path().unwrap();
-        session.log_message(MessageType::INFO, format!("File opened: {}", path.sanitize()));
+        if ReadonlyDocumentFeature::is_readonly_uri(params.text_document.uri.as_str()) {
+            return;
+        }
+        //a file opened through a symlink is the file of its canonical path, but its diagnostics are published on its uri
+        let client_path = params.text_document.uri.to_file_path().unwrap().sanitize();
+        let path = PathBuf::from(session.sync_odoo.get_file_mgr().borrow_mut().open_path(&client_path));
+        session.log_message(MessageType::INFO, format!("File opened: {}", client_path));
+        let is_python = path.extension().is_some_and(|ext| ext == "py");
+        if is_python && session.sync_odoo.state_init != InitState::NOT_READY && session.sync_odoo.tree_from_path(&path).is_err() {
+            SyncOdoo::suggest_addons_path(session, &path);
+            if session.sync_odoo.tree_from_path(&path).is_err() {
+                SyncOdoo::open_single_file(session, &path);
+            }
+        }
         if Odoo::update_file_cache(session, path.clone(), Some(&vec![TextDocumentContentChangeEvent{
             range: None,
             range_length: None,
@@ -1125,8 +2234,15 @@ impl Odoo {
     }
 
     pub fn handle_did_close(session: &mut SessionInfo, params: DidCloseTextDocumentParams) {
-        let path = params.text_document.uri.to_file_path().unwrap();
+        if ReadonlyDocumentFeature::is_readonly_uri(params.text_document.uri.as_str()) {
+            return;
+        }
+        let path = PathBuf::from(FileMgr::uri2canonical(session, params.text_document.uri.as_str()));
         session.log_message(MessageType::INFO, format!("File closed: {}", path.sanitize()));
+        if session.sync_odoo.is_single_file(&path.sanitize()) {
+            SyncOdoo::close_single_file(session, &path);
+            return;
+        }
         let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path.to_str().unwrap().to_string());
         if let Some(file_info) = file_info {
             file_info.borrow_mut().opened = false;
@@ -1134,12 +2250,16 @@ impl Odoo {
     }
 
     pub fn handle_did_rename(session: &mut SessionInfo, params: RenameFilesParams) {
+        for f in params.files.iter() {
+            invalidate_case_sensitive_path(&PathBuf::from(FileMgr::uri2pathname(&f.old_uri)));
+            invalidate_case_sensitive_path(&PathBuf::from(FileMgr::uri2pathname(&f.new_uri)));
+        }
         if session.sync_odoo.config.refresh_mode == RefreshMode::Off || session.sync_odoo.state_init == InitState::NOT_READY {
             return
         }
         for f in params.files.iter() {
-            let old_path = FileMgr::uri2pathname(&f.old_uri);
-            let new_path = FileMgr::uri2pathname(&f.new_uri);
+            let old_path = FileMgr::uri2canonical(session, &f.old_uri);
+            let new_path = FileMgr::uri2canonical(session, &f.new_uri);
             session.log_message(MessageType::INFO, format!("Renaming {} to {}", old_path, new_path));
             //1 - delete old uri
             let _ = SyncOdoo::_unload_path(session, &PathBuf::from(&old_path), false);
@@ -1154,11 +2274,14 @@ impl Odoo {
     }
 
     pub fn handle_did_create(session: &mut SessionInfo, params: CreateFilesParams) {
+        for f in params.files.iter() {
+            invalidate_case_sensitive_path(&PathBuf::from(FileMgr::uri2pathname(&f.uri)));
+        }
         if session.sync_odoo.config.refresh_mode == RefreshMode::Off || session.sync_odoo.state_init == InitState::NOT_READY {
             return
         }
         for f in params.files.iter() {
-            let path = FileMgr::uri2pathname(&f.uri);
+            let path = FileMgr::uri2canonical(session, &f.uri);
             session.log_message(MessageType::INFO, format!("Creating {}", path));
             //1 - delete old uri
             let tree = session.sync_odoo.tree_from_path(&PathBuf::from(path));
@@ -1170,11 +2293,14 @@ impl Odoo {
     }
 
     pub fn handle_did_delete(session: &mut SessionInfo, params: DeleteFilesParams) {
+        for f in params.files.iter() {
+            invalidate_case_sensitive_path(&PathBuf::from(FileMgr::uri2pathname(&f.uri)));
+        }
         if session.sync_odoo.config.refresh_mode == RefreshMode::Off || session.sync_odoo.state_init == InitState::NOT_READY {
             return
         }
         for f in params.files.iter() {
-            let path = FileMgr::uri2pathname(&f.uri);
+            let path = FileMgr::uri2canonical(session, &f.uri);
             session.log_message(MessageType::INFO, format!("Deleting {}", path));
             //1 - delete old uri
             let _ = SyncOdoo::_unload_path(session, &PathBuf::from(&path), false);
@@ -1184,19 +2310,33 @@ impl Odoo {
     }
 
     pub fn handle_did_change(session: &mut SessionInfo, params: DidChangeTextDocumentParams) {
-        let path = params.text_document.uri.to_file_path().unwrap();
+        if ReadonlyDocumentFeature::is_readonly_uri(params.text_document.uri.as_str()) {
+            return;
+        }
+        let path = PathBuf::from(FileMgr::uri2canonical(session, params.text_document.uri.as_str()));
         session.log_message(MessageType::INFO, format!("File changed: {}", path.sanitize()));
         let version = params.text_document.version;
+        let refresh = (session.sync_odoo.config.refresh_mode == RefreshMode::AfterDelay || session.sync_odoo.config.refresh_mode == RefreshMode::Adaptive) && session.sync_odoo.state_init != InitState::NOT_READY;
+        //an edit of the body of a single method is rebuilt right away, without the rebuild of the whole file
+        let function_rebuild = match refresh {
+            true => FunctionRebuild::prepare(session, &path, &params.content_changes, version),
+            false => None
+        };
         if Odoo::update_file_cache(session, path.clone(), Some(&params.content_changes), version) {
-            if (session.sync_odoo.config.refresh_mode != RefreshMode::AfterDelay && session.sync_odoo.config.refresh_mode != RefreshMode::Adaptive) || session.sync_odoo.state_init == InitState::NOT_READY {
+            if !refresh {
                 return
             }
+            if let Some(function_rebuild) = function_rebuild {
+                if function_rebuild.apply(session, &path) {
+                    return
+                }
+            }
             Odoo::update_file_index(session, path, false, false);
         }
     }
 
     pub fn handle_did_save(session: &mut SessionInfo, params: DidSaveTextDocumentParams) {
-        let path = params.text_document.uri.to_file_path().unwrap();
+        let path = PathBuf::from(FileMgr::uri2canonical(session, params.text_document.uri.as_str()));
         session.log_message(MessageType::INFO, format!("File saved: {}", path.sanitize()));
         if session.sync_odoo.config.refresh_mode != RefreshMode::OnSave || session.sync_odoo.state_init == InitState::NOT_READY {
             return
@@ -1214,6 +2354,7 @@ impl Odoo {
             session.log_message(MessageType::INFO, format!("File Change Event: {}, version {}", path.to_str().unwrap(), version));
             let (file_updated, file_info) = session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path.sanitize(), content, Some(version), false);
             file_info.borrow_mut().publish_diagnostics(session); //To push potential syntax errors or refresh previous one
+            FileStatusFeature::push_changes(session, true); //the symbols become stale on a syntax error
             return file_info.borrow().valid && (!file_info.borrow().opened || version >= 0) && file_updated;
         }
         false
@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::comparable::ComparableExpr;
+use ruff_python_ast::{Expr, ExprCall, Parameters, Stmt};
+
+use crate::core::call_binding::CallBinding;
+use crate::core::evaluation::Evaluation;
+use crate::core::file_mgr::FileMgr;
+use crate::core::symbols::function_symbol::ArgumentType;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* The signatures of a function decorated by @overload. The overloads and the implementation are owned by the first
+overload (see FunctionSymbol::overloads), and the return evaluation of each overload is given by its return annotation.
+A call is evaluated to the values returned by the overloads that accept its arguments:
+    @overload
+    def read(mode: Literal['r']) -> str: ...
+    @overload
+    def read(mode: Literal['rb']) -> bytes: ...
+    def read(mode): ...
+    read('rb')
+returns bytes. The arguments are matched on their number, their keywords, and the literal values of the parameters
+annotated with Literal. The other types are not compared. */
+pub struct Overloads {}
+
+impl Overloads {
+
+    /* Return the signatures of the function: its overloads, without the implementation. A function that is not
+    overloaded is its own signature */
+    pub fn get_signatures(function: &Rc<RefCell<Symbol>>) -> Vec<Rc<RefCell<Symbol>>> {
+        let overloads = function.borrow().as_func().overloads.clone();
+        if overloads.is_empty() {
+            return vec![function.clone()];
+        }
+        std::iter::once(function.clone()).chain(overloads.into_iter())
+            .filter(|overload| overload.borrow().as_func().is_overloaded)
+            .collect()
+    }
+
+    /* Return the return evaluations of the overloads that accept the arguments of the call, or of all the overloads if
+    none accepts them */
+    pub fn get_call_returns(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, call: &ExprCall, is_on_instance: bool) -> Vec<Evaluation> {
+        let signatures = Overloads::get_signatures(function);
+        let accepting: Vec<Rc<RefCell<Symbol>>> = signatures.iter()
+            .filter(|overload| Overloads::accepts(session, overload, call, is_on_instance))
+            .cloned()
+            .collect();
+        let selected = if accepting.is_empty() { signatures } else { accepting };
+        let mut res = vec![];
+        for overload in selected.iter() {
+            res.extend(overload.borrow().as_func().evaluations.iter().cloned());
+        }
+        res
+    }
+
+    /* Return true if the overload accepts the arguments of the call. An incomplete binding (see CallBinding::complete)
+    only rejects the literal values that don't match */
+    pub fn accepts(session: &mut SessionInfo, overload: &Rc<RefCell<Symbol>>, call: &ExprCall, is_on_instance: bool) -> bool {
+        let binding = CallBinding::bind_function(overload.borrow().as_func(), &call.arguments, is_on_instance);
+        if binding.complete {
+            let unbound_arg = call.arguments.args.iter().zip(binding.args.iter())
+                .any(|(arg, parameter)| parameter.is_none() && !arg.is_starred_expr());
+            let unbound_keyword = call.arguments.keywords.iter().zip(binding.keywords.iter())
+                .any(|(keyword, parameter)| parameter.is_none() && keyword.arg.is_some())
+                || binding.unpacked_keywords.iter().any(|(_, parameter)| parameter.is_none());
+            if unbound_arg || unbound_keyword {
+                return false;
+            }
+            let bound: Vec<usize> = binding.args.iter().chain(binding.keywords.iter()).flatten().copied()
+                .chain(binding.unpacked_keywords.iter().filter_map(|(_, parameter)| *parameter))
+                .collect();
+            let overload_bw = overload.borrow();
+            let function = overload_bw.as_func();
+            let first_is_bound = is_on_instance as usize;
+            let missing = function.args.iter().enumerate().skip(first_is_bound).any(|(index, arg)| {
+                matches!(arg.arg_type, ArgumentType::POS_ONLY | ArgumentType::ARG | ArgumentType::KWORD_ONLY) &&
+                arg.default_value.is_none() && !bound.contains(&index)
+            });
+            //the positional arguments unpacked from known values bind the next parameters
+            let unpacked_args = call.arguments.args.iter().any(|arg| arg.is_starred_expr());
+            if missing && !unpacked_args {
+                return false;
+            }
+        }
+        let Some(literals) = Overloads::get_literal_parameters(session, overload) else {
+            return true;
+        };
+        let bound_values = call.arguments.args.iter().zip(binding.args.iter())
+            .filter(|(arg, _)| !arg.is_starred_expr())
+            .chain(call.arguments.keywords.iter().zip(binding.keywords.iter())
+                .filter(|(keyword, _)| keyword.arg.is_some())
+                .map(|(keyword, parameter)| (&keyword.value, parameter)));
+        for (value, parameter) in bound_values {
+            let Some(Some(accepted)) = parameter.and_then(|parameter| literals.get(parameter)) else {
+                continue;
+            };
+            if Overloads::is_literal(value) && !accepted.iter().any(|literal| ComparableExpr::from(literal) == ComparableExpr::from(value)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /* Return, for each parameter of the overload, the values accepted by its annotation if it is a Literal[...]. The
+    parameters are in the order of the arguments of the function. None if the AST of the overload is not available */
+    fn get_literal_parameters(session: &mut SessionInfo, overload: &Rc<RefCell<Symbol>>) -> Option<Vec<Option<Vec<Expr>>>> {
+        let file_info = FileMgr::get_file_info_of_symbol(session, overload)?;
+        let file_info = file_info.borrow();
+        let overload = overload.borrow();
+        let Stmt::FunctionDef(function_def) = overload.get_ast_node(&file_info)? else {
+            return None;
+        };
+        let parameters: &Parameters = &function_def.parameters;
+        let annotations: Vec<Option<&Expr>> = parameters.posonlyargs.iter().chain(parameters.args.iter()).map(|arg| arg.parameter.annotation.as_deref())
+            .chain(parameters.vararg.iter().map(|arg| arg.annotation.as_deref()))
+            .chain(parameters.kwonlyargs.iter().map(|arg| arg.parameter.annotation.as_deref()))
+            .chain(parameters.kwarg.iter().map(|arg| arg.annotation.as_deref()))
+            .collect();
+        Some(annotations.into_iter().map(|annotation| annotation.and_then(Overloads::get_literal_values)).collect())
+    }
+
+    /* Return the values of a Literal[...] annotation, recognized by its name like the special forms of ArgumentTypes */
+    fn get_literal_values(annotation: &Expr) -> Option<Vec<Expr>> {
+        let Expr::Subscript(subscript) = annotation else {
+            return None;
+        };
+        let name = match &*subscript.value {
+            Expr::Name(name) => name.id.as_str(),
+            Expr::Attribute(attribute) => attribute.attr.as_str(),
+            _ => return None
+        };
+        if name != "Literal" {
+            return None;
+        }
+        let values = match &*subscript.slice {
+            Expr::Tuple(tuple) => tuple.elts.clone(),
+            value => vec![value.clone()]
+        };
+        values.iter().all(Overloads::is_literal).then_some(values)
+    }
+
+    fn is_literal(expr: &Expr) -> bool {
+        matches!(expr, Expr::StringLiteral(_) | Expr::BytesLiteral(_) | Expr::NumberLiteral(_) | Expr::BooleanLiteral(_) | Expr::NoneLiteral(_))
+    }
+}
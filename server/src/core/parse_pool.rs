@@ -0,0 +1,398 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use ropey::Rope;
+use ruff_python_ast::{ExceptHandler, Mod, Stmt};
+use ruff_python_parser::Mode;
+use tracing::warn;
+
+use crate::constants::{PackageType, SymType, EXTENSION_NAME};
+use crate::core::guards::Guard;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::{to_long_path, PathSanitizer as _};
+use crate::S;
+
+/* Maximum number of workers, the parse of a file being short compared to the other steps */
+const MAX_WORKERS: usize = 8;
+/* Number of files parsed in advance by each worker, queued or waiting to be taken */
+const JOBS_PER_WORKER: usize = 4;
+/* Maximum number of parsed files waiting to be taken. The oldest ones are dropped, as they were likely wrong guesses */
+const MAX_READY: usize = 512;
+
+/* The text of a file and its ast. It is computed without any FileInfo, so it can be built out of the main thread (see
+ParsePool) and given to the FileInfo of the file later (see FileInfo::update_from_source) */
+#[derive(Debug)]
+pub struct ParsedSource {
+    pub rope: Rope,
+    pub hash: u64,
+    pub ast: Option<Vec<Stmt>>,
+    pub valid: bool,
+    pub diagnostics: Vec<Diagnostic>, //the syntax errors
+    pub guards: Vec<Guard>,
+}
+
+impl ParsedSource {
+
+    pub fn new(rope: Rope) -> Self {
+        //the hash only detects the changes of the text in this process (see FileInfo::update), as DefaultHasher is not
+        //stable between the builds of the server. It must not be used as a persisted key, like the names of the ArchCache
+        let mut hasher = DefaultHasher::new();
+        rope.hash(&mut hasher);
+        let hash = hasher.finish();
+        let source = rope.slice(..).to_string();
+        let (ast, valid, diagnostics, guards) = ParsedSource::parse(&source);
+        ParsedSource { rope, hash, ast, valid, diagnostics, guards }
+    }
+
+    /* Read and parse the file. None if it can't be read */
+    pub fn read(path: &str) -> Option<Self> {
+        let content = fs::read_to_string(to_long_path(path)).ok()?;
+        Some(ParsedSource::new(Rope::from(content.as_str())))
+    }
+
+    /* Return the ast of the source, if it is valid, the diagnostics of its syntax errors and its guards */
+    pub fn parse(source: &str) -> (Option<Vec<Stmt>>, bool, Vec<Diagnostic>, Vec<Guard>) {
+        let mut diagnostics = vec![];
+        let ast = ruff_python_parser::parse_unchecked(source, Mode::Module);
+        let mut valid = true;
+        for error in ast.errors().iter() {
+            valid = false;
+            diagnostics.push(Diagnostic::new(
+                Range{ start: Position::new(error.location.start().to_u32(), 0),
+                    end: Position::new(error.location.end().to_u32(), 0)},
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!("OLS30001"))),
+                Some(EXTENSION_NAME.to_string()),
+                error.error.to_string(),
+                None,
+                None));
+        }
+        match ast.into_syntax() {
+            Mod::Expression(_expr) => {
+                warn!("No support for expression-file only");
+                (None, valid, diagnostics, vec![])
+            },
+            Mod::Module(module) => {
+                let guards = Guard::collect(&module.body);
+                (Some(module.body), valid, diagnostics, guards)
+            }
+        }
+    }
+}
+
+/* Parse the external files on worker threads, before the main thread needs them. Building the symbols of a file needs the
+whole symbol tree and stays on the main thread, in the same order as before, but reading and parsing a file only needs its
+path: the files that will likely be built next are sent to the workers, and their FileInfo is filled with the parsed
+source when the main thread loads them (see FileMgr::update_file_info). The symbols and the diagnostics are the same as
+with a parse on the main thread.
+The files sent are the external files queued for their ARCH, and the files that the imports of a built file can resolve
+to in the external roots, as most of the external files are built while resolving an import. A file that is asked by the
+main thread while it is being parsed is waited for, and a file that is not sent yet is parsed by the main thread. The
+channel of the jobs is bounded, and the parsed files that are never asked are dropped at the end of the rebuild. */
+#[derive(Debug)]
+pub struct ParsePool {
+    workers: usize,
+    generation: u64, //incremented by clear, the results of the previous generations are dropped
+    job_sender: Option<Sender<(u64, String)>>,
+    result_receiver: Option<Receiver<(u64, String, Option<ParsedSource>)>>,
+    dispatched: usize, //jobs sent and not received yet, of any generation
+    in_flight: HashSet<String>,
+    waiting: VecDeque<String>,
+    ready: HashMap<String, ParsedSource>,
+    ready_order: VecDeque<String>,
+    seen: HashSet<String>, //paths already sent, or waiting, in this generation
+    pub hits: u64, //files taken from the pool instead of being parsed by the main thread
+}
+
+impl Default for ParsePool {
+    fn default() -> Self {
+        ParsePool::new()
+    }
+}
+
+impl ParsePool {
+
+    pub fn new() -> Self {
+        let workers = thread::available_parallelism().map(|count| count.get().saturating_sub(1)).unwrap_or(0).min(MAX_WORKERS);
+        ParsePool {
+            workers,
+            generation: 0,
+            job_sender: None,
+            result_receiver: None,
+            dispatched: 0,
+            in_flight: HashSet::new(),
+            waiting: VecDeque::new(),
+            ready: HashMap::new(),
+            ready_order: VecDeque::new(),
+            seen: HashSet::new(),
+            hits: 0,
+        }
+    }
+
+    /* Set the number of workers. 0 disables the pool, the files being parsed by the main thread */
+    pub fn set_workers(&mut self, workers: usize) {
+        self.clear();
+        self.workers = workers.min(MAX_WORKERS);
+        //the current workers stop when their channel is dropped
+        self.job_sender = None;
+        self.result_receiver = None;
+        self.dispatched = 0;
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    fn capacity(&self) -> usize {
+        self.workers * JOBS_PER_WORKER
+    }
+
+    fn start_workers(&mut self) {
+        let (job_sender, job_receiver) = crossbeam_channel::bounded::<(u64, String)>(self.capacity());
+        let (result_sender, result_receiver) = crossbeam_channel::unbounded();
+        for index in 0..self.workers {
+            let job_receiver = job_receiver.clone();
+            let result_sender = result_sender.clone();
+            let spawned = thread::Builder::new().name(format!("ParsePool-{}", index)).spawn(move || {
+                for (generation, path) in job_receiver.iter() {
+                    //a file that can't be parsed is parsed again by the main thread, that reports the error
+                    let parsed = panic::catch_unwind(AssertUnwindSafe(|| ParsedSource::read(&path))).unwrap_or(None);
+                    if result_sender.send((generation, path, parsed)).is_err() {
+                        break;
+                    }
+                }
+            });
+            if let Err(e) = spawned {
+                warn!("Unable to start a worker of the parse pool: {}", e);
+            }
+        }
+        self.job_sender = Some(job_sender);
+        self.result_receiver = Some(result_receiver);
+    }
+
+    /* Ask the workers to parse the file, if it exists and is not loaded yet */
+    pub fn prefetch(&mut self, path: String) {
+        if self.workers == 0 || self.seen.contains(&path) {
+            return;
+        }
+        self.seen.insert(path.clone());
+        self.waiting.push_back(path);
+        self.dispatch();
+    }
+
+    /* Send the waiting files to the workers, while the bounded channel has room */
+    fn dispatch(&mut self) {
+        if self.job_sender.is_none() {
+            self.start_workers();
+        }
+        while self.dispatched < self.capacity() && self.ready.len() < MAX_READY {
+            let Some(path) = self.waiting.pop_front() else {
+                break;
+            };
+            if self.job_sender.as_ref().unwrap().try_send((self.generation, path.clone())).is_err() {
+                self.waiting.push_front(path);
+                break;
+            }
+            self.dispatched += 1;
+            self.in_flight.insert(path);
+        }
+    }
+
+    fn receive(&mut self, result: (u64, String, Option<ParsedSource>)) {
+        let (generation, path, parsed) = result;
+        self.dispatched -= 1;
+        if generation != self.generation || !self.in_flight.remove(&path) {
+            return;
+        }
+        if let Some(parsed) = parsed {
+            if self.ready.len() >= MAX_READY {
+                if let Some(oldest) = self.ready_order.pop_front() {
+                    self.ready.remove(&oldest);
+                }
+            }
+            self.ready_order.push_back(path.clone());
+            self.ready.insert(path, parsed);
+        }
+    }
+
+    /* Return the parsed source of the file, waiting for it if it is being parsed. None if the file has not been sent to
+    the workers: it must be parsed by the caller */
+    pub fn take(&mut self, path: &str) -> Option<ParsedSource> {
+        if self.result_receiver.is_none() {
+            return None;
+        }
+        while let Ok(result) = self.result_receiver.as_ref().unwrap().try_recv() {
+            self.receive(result);
+        }
+        while self.in_flight.contains(path) {
+            let Ok(result) = self.result_receiver.as_ref().unwrap().recv() else {
+                self.in_flight.remove(path);
+                break;
+            };
+            self.receive(result);
+        }
+        let parsed = self.ready.remove(path);
+        if parsed.is_some() {
+            self.ready_order.retain(|p| p != path);
+            self.hits += 1;
+        } else if let Some(index) = self.waiting.iter().position(|p| p == path) {
+            self.waiting.remove(index);
+        }
+        self.dispatch();
+        parsed
+    }
+
+    /* Drop the parsed files and the waiting ones. The files being parsed are dropped when they are received */
+    pub fn clear(&mut self) {
+        self.generation += 1;
+        self.in_flight.clear();
+        self.waiting.clear();
+        self.ready.clear();
+        self.ready_order.clear();
+        self.seen.clear();
+        if let Some(receiver) = self.result_receiver.clone() {
+            while let Ok(result) = receiver.try_recv() {
+                self.receive(result);
+            }
+        }
+    }
+
+    /* Send the file of an external symbol queued for its ARCH */
+    pub fn prefetch_symbol(&mut self, symbol: &Symbol) {
+        if self.workers == 0 || !symbol.is_external() {
+            return;
+        }
+        let path = match symbol.typ() {
+            SymType::FILE => symbol.paths().first().cloned(),
+            SymType::PACKAGE(PackageType::PYTHON_PACKAGE) => {
+                symbol.paths().first().map(|path| PathBuf::from(path).join("__init__.py").sanitize() + symbol.as_package().i_ext().as_str())
+            },
+            _ => None
+        };
+        if let Some(path) = path {
+            self.prefetch(path);
+        }
+    }
+
+    /* Send the files that the imports of the statements can be resolved to in the external roots: the stdlib, the stubs
+    and the python path. The relative imports are only followed from the external files */
+    pub fn prefetch_imports(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, path: &str, stmts: &[Stmt]) {
+        if session.sync_odoo.get_file_mgr().borrow().parse_pool.workers == 0 {
+            return;
+        }
+        let mut roots: Vec<PathBuf> = vec![PathBuf::from(&session.sync_odoo.stdlib_dir)];
+        roots.extend(session.sync_odoo.symbols.as_ref().unwrap().borrow().as_root().sys_path.iter().map(PathBuf::from));
+        let stubs_dirs: Vec<PathBuf> = session.sync_odoo.stubs_dirs.iter().map(PathBuf::from).collect();
+        let is_external = file.borrow().is_external();
+        let mut candidates = vec![];
+        let mut imports = vec![];
+        ParsePool::collect_imports(stmts, &mut imports);
+        for stmt in imports.into_iter() {
+            match stmt {
+                Stmt::Import(import_stmt) => {
+                    for alias in import_stmt.names.iter() {
+                        let module: Vec<&str> = alias.name.as_str().split('.').collect();
+                        let folders = ParsePool::get_root_folders(&roots, &stubs_dirs, module[0]);
+                        candidates.extend(ParsePool::get_import_candidates(&folders, &module, &[]));
+                    }
+                },
+                Stmt::ImportFrom(import_from) => {
+                    let module: Vec<&str> = import_from.module.as_ref().map(|module| module.as_str().split('.').collect()).unwrap_or_default();
+                    let names: Vec<&str> = import_from.names.iter().map(|alias| alias.name.as_str()).collect();
+                    let folders = if import_from.level > 0 {
+                        if !is_external {
+                            continue;
+                        }
+                        let mut folder = Path::new(path).parent().map(Path::to_path_buf);
+                        for _ in 1..import_from.level {
+                            folder = folder.and_then(|folder| folder.parent().map(Path::to_path_buf));
+                        }
+                        folder.into_iter().collect()
+                    } else if let Some(first) = module.first() {
+                        ParsePool::get_root_folders(&roots, &stubs_dirs, first)
+                    } else {
+                        continue;
+                    };
+                    candidates.extend(ParsePool::get_import_candidates(&folders, &module, &names));
+                },
+                _ => {}
+            }
+        }
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let mut file_mgr = file_mgr.borrow_mut();
+        for candidate in candidates.into_iter() {
+            if file_mgr.get_file_info(&candidate).is_none() {
+                file_mgr.parse_pool.prefetch(candidate);
+            }
+        }
+    }
+
+    /* The import statements of the body, and of its if and try blocks, like the imports of an optional dependency */
+    fn collect_imports<'a>(stmts: &'a [Stmt], imports: &mut Vec<&'a Stmt>) {
+        for stmt in stmts.iter() {
+            match stmt {
+                Stmt::Import(_) | Stmt::ImportFrom(_) => imports.push(stmt),
+                Stmt::If(if_stmt) => {
+                    ParsePool::collect_imports(&if_stmt.body, imports);
+                    for clause in if_stmt.elif_else_clauses.iter() {
+                        ParsePool::collect_imports(&clause.body, imports);
+                    }
+                },
+                Stmt::Try(try_stmt) => {
+                    ParsePool::collect_imports(&try_stmt.body, imports);
+                    for handler in try_stmt.handlers.iter() {
+                        let ExceptHandler::ExceptHandler(handler) = handler;
+                        ParsePool::collect_imports(&handler.body, imports);
+                    }
+                    ParsePool::collect_imports(&try_stmt.orelse, imports);
+                    ParsePool::collect_imports(&try_stmt.finalbody, imports);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /* The folders an absolute import is searched in. The stubs of typeshed are in a folder named like their package */
+    fn get_root_folders(roots: &[PathBuf], stubs_dirs: &[PathBuf], first: &str) -> Vec<PathBuf> {
+        let mut folders = roots.to_vec();
+        folders.extend(stubs_dirs.iter().map(|stubs_dir| stubs_dir.join(first)));
+        folders
+    }
+
+    /* Return the files that an import can be resolved to, from the given folders: the packages and the modules of each
+    part of the name, and of each imported name, as it can be a submodule. The files are not checked, the workers skip
+    the ones that don't exist */
+    pub fn get_import_candidates(folders: &[PathBuf], module: &[&str], names: &[&str]) -> Vec<String> {
+        let mut res = vec![];
+        for folder in folders.iter() {
+            let mut current = folder.clone();
+            for part in module.iter() {
+                current = current.join(part);
+                ParsePool::add_candidates(&mut res, &current);
+            }
+            for name in names.iter().filter(|name| **name != "*") {
+                ParsePool::add_candidates(&mut res, &current.join(name));
+            }
+        }
+        res
+    }
+
+    fn add_candidates(res: &mut Vec<String>, path: &Path) {
+        let init = path.join("__init__.py").sanitize();
+        res.push(init.clone() + "i");
+        res.push(init);
+        let path = path.sanitize();
+        res.push(path.clone() + ".pyi");
+        res.push(path + ".py");
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fs;
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use serde::Deserialize;
+use weak_table::PtrWeakHashSet;
+
+use crate::constants::flatten_tree;
+use crate::core::symbols::symbol::Symbol;
+use crate::S;
+
+/* A rule of the plugin rules file, that teaches the server about a framework built over Odoo. Exactly one kind of rule
+should be set:
+ - field (+ comodel_keyword): the classes that inherit the class at the path 'field' are fields. If comodel_keyword is
+   set, the comodel is given by this keyword or by the first argument, and must be a known model.
+ - decorator + registry: the functions decorated by the function at the path 'decorator' are added to the dict at the
+   path 'registry', under the name given as first argument to the decorator, or the name of the function. registry['name']
+   is then evaluated as the registered function.
+ - factory: the calls to the function at the path 'factory' return an instance of the class given as first argument.
+Paths are the full paths of the symbols, like "my_framework.fields.Money".
+See plugin_rules_example.json for the rule that reproduces the comodel of fields.Many2one. */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginRule {
+    pub field: Option<String>,
+    pub comodel_keyword: Option<String>,
+    pub decorator: Option<String>,
+    pub registry: Option<String>,
+    pub factory: Option<String>,
+}
+
+impl PluginRule {
+
+    /* Return the reason why the rule is invalid, if any */
+    fn validate(&self) -> Option<String> {
+        let kinds = [self.field.is_some(), self.decorator.is_some(), self.factory.is_some()].iter().filter(|x| **x).count();
+        if kinds != 1 {
+            return Some(S!("exactly one of 'field', 'decorator' or 'factory' must be set"));
+        }
+        if self.comodel_keyword.is_some() && self.field.is_none() {
+            return Some(S!("'comodel_keyword' can only be used with 'field'"));
+        }
+        if self.decorator.is_some() != self.registry.is_some() {
+            return Some(S!("'decorator' and 'registry' must be set together"));
+        }
+        for path in [&self.field, &self.decorator, &self.registry, &self.factory].into_iter().flatten() {
+            if !PluginRule::is_path(path) {
+                return Some(format!("'{}' is not a path to a symbol, like 'my_framework.fields.Money'", path));
+            }
+        }
+        if let Some(keyword) = self.comodel_keyword.as_ref() {
+            if keyword.is_empty() || !keyword.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Some(format!("'{}' is not a keyword argument name", keyword));
+            }
+        }
+        None
+    }
+
+    fn is_path(path: &str) -> bool {
+        path.contains('.') && path.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    }
+}
+
+#[derive(Debug)]
+pub struct PluginRules {
+    rules: Vec<PluginRule>,
+    registries: HashMap<String, HashMap<String, Weak<RefCell<Symbol>>>>, //functions registered by decorator rules, by registry path
+}
+
+impl PluginRules {
+
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            registries: HashMap::new(),
+        }
+    }
+
+    /* Read the rules of the file at path. Return the valid rules, and the errors encountered in the file */
+    pub fn read_rules(path: &str) -> (Vec<PluginRule>, Vec<String>) {
+        let mut errors = vec![];
+        let mut rules = vec![];
+        if path.is_empty() {
+            return (rules, errors);
+        }
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                match serde_json::from_str::<Vec<PluginRule>>(&content) {
+                    Ok(file_rules) => {
+                        for (index, rule) in file_rules.into_iter().enumerate() {
+                            match rule.validate() {
+                                Some(error) => errors.push(format!("Invalid rule #{} in plugin rules file {}: {}", index + 1, path, error)),
+                                None => rules.push(rule),
+                            }
+                        }
+                    },
+                    Err(e) => errors.push(format!("Unable to parse plugin rules file {}: {}", path, e))
+                }
+            },
+            Err(e) => errors.push(format!("Unable to read plugin rules file {}: {}", path, e))
+        }
+        (rules, errors)
+    }
+
+    /* Replace the rules. The registered functions are kept, as they are registered again when their file is evaluated */
+    pub fn set_rules(&mut self, rules: Vec<PluginRule>) {
+        self.rules = rules;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /* Return the field rule that applies to the class, if the class or one of its bases is the class of a field rule */
+    pub fn get_field_rule(&self, class: &Rc<RefCell<Symbol>>) -> Option<&PluginRule> {
+        if !self.rules.iter().any(|r| r.field.is_some()) {
+            return None;
+        }
+        let mut checked: PtrWeakHashSet<Weak<RefCell<Symbol>>> = PtrWeakHashSet::new();
+        let mut to_check = vec![class.clone()];
+        while let Some(class) = to_check.pop() {
+            if checked.contains(&class) {
+                continue;
+            }
+            checked.insert(class.clone());
+            let path = flatten_tree(&class.borrow().get_tree()).join(".");
+            if let Some(rule) = self.rules.iter().find(|r| r.field.as_deref() == Some(path.as_str())) {
+                return Some(rule);
+            }
+            if let Symbol::Class(c) = &*class.borrow() {
+                to_check.extend(c.bases.iter().filter_map(|b| b.upgrade()));
+            }
+        }
+        None
+    }
+
+    /* Return the registry path of the decorator, if it is the function of a decorator rule */
+    pub fn get_registry(&self, decorator_path: &str) -> Option<&String> {
+        self.rules.iter().find(|r| r.decorator.as_deref() == Some(decorator_path)).and_then(|r| r.registry.as_ref())
+    }
+
+    pub fn is_factory(&self, function_path: &str) -> bool {
+        self.rules.iter().any(|r| r.factory.as_deref() == Some(function_path))
+    }
+
+    pub fn register(&mut self, registry: &String, name: String, function: &Rc<RefCell<Symbol>>) {
+        self.registries.entry(registry.clone()).or_default().insert(name, Rc::downgrade(function));
+    }
+
+    /* Return the function registered under the name in the registry, if the registry is the one of a decorator rule */
+    pub fn get_registered(&self, registry: &str, name: &str) -> Option<Rc<RefCell<Symbol>>> {
+        if !self.rules.iter().any(|r| r.registry.as_deref() == Some(registry)) {
+            return None;
+        }
+        self.registries.get(registry).and_then(|functions| functions.get(name)).and_then(|f| f.upgrade())
+    }
+}
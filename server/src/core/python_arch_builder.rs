@@ -1,20 +1,32 @@
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::vec;
 use anyhow::Error;
 use ruff_text_size::{Ranged, TextRange};
-use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtTry, StmtWith};
+use ruff_python_ast::{Alias, Expr, ExprLambda, ExprName, Identifier, Parameters, Pattern, Stmt, StmtAnnAssign, StmtAssign, StmtAugAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtMatch, StmtTry, StmtTypeAlias, StmtWhile, StmtWith, TypeParam};
 use lsp_types::Diagnostic;
 use tracing::{trace, warn};
 use weak_table::traits::WeakElement;
 use std::path::PathBuf;
 
 use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::core::arch_cache::ArchCache;
+use crate::core::parse_pool::ParsePool;
+use crate::core::borrows::Borrows;
 use crate::core::python_utils;
 use crate::core::import_resolver::resolve_import_stmt;
+use crate::core::collection_folding::CollectionFolding;
+use crate::core::container_mutations::{ContainerMutation, ContainerMutations};
+use crate::core::dataclasses::Dataclasses;
 use crate::core::symbols::symbol::Symbol;
+use crate::core::symbols::symbol_mgr::{SectionIndex, SectionRange};
 use crate::core::evaluation::{Evaluation, EvaluationValue};
 use crate::core::python_arch_builder_hooks::PythonArchBuilderHooks;
+use crate::core::dynamic_fields::DynamicFields;
+use crate::core::injected_attributes::{InjectedAttributes, METACLASS_METHODS};
+use crate::core::narrowing::{NarrowedBranch, Narrowings};
+use crate::core::static_conditions::StaticConditions;
 use crate::features::ast_utils::AstUtils;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
@@ -31,8 +43,12 @@ pub struct PythonArchBuilder {
     file_mode: bool,
     current_step: BuildSteps,
     sym_stack: Vec<Rc<RefCell<Symbol>>>,
+    ast_indexes: Vec<u16>,
     __all_symbols_to_add: Vec<(String, TextRange)>,
-    diagnostics: Vec<Diagnostic>
+    constant_values: HashMap<String, EvaluationValue>, //constants assigned at the top level of the file, to fold __all__
+    type_checking: bool, //in the body of an if TYPE_CHECKING, whose imports are only done by the type checkers
+    diagnostics: Vec<Diagnostic>,
+    cancelled: bool, //the rebuild has been cancelled between two top-level statements of the file
 }
 
 impl PythonArchBuilder {
@@ -42,8 +58,12 @@ impl PythonArchBuilder {
             file_mode: false, //dummy, evaluated in load_arch
             current_step: BuildSteps::ARCH, //dummy, evaluated in load_arch
             sym_stack: vec![symbol],
+            ast_indexes: vec![],
             __all_symbols_to_add: Vec::new(),
-            diagnostics: vec![]
+            constant_values: HashMap::new(),
+            type_checking: false,
+            diagnostics: vec![],
+            cancelled: false,
         }
     }
 
@@ -59,8 +79,15 @@ impl PythonArchBuilder {
             self.file = file.clone();
             self.file_mode = Rc::ptr_eq(&file, &symbol);
             self.current_step = if self.file_mode {BuildSteps::ARCH} else {BuildSteps::VALIDATION};
+            if !self.file_mode {
+                self.ast_indexes = symbol.borrow().ast_indexes().cloned().unwrap_or_default(); //the body of the function is below its own indexes
+            }
         }
         trace!("building {} - {}", self.file.borrow().paths().first().unwrap_or(&S!("No path found")), symbol.borrow().name());
+        session.sync_odoo.evaluation_cache.start_build(Rc::as_ptr(&self.file) as usize);
+        if self.file_mode {
+            Symbol::unload_content(session, symbol); //the file is rebuilt
+        }
         symbol.borrow_mut().set_build_status(BuildSteps::ARCH, BuildStatus::IN_PROGRESS);
         let path = match self.file.borrow().typ() {
             SymType::FILE => {
@@ -72,15 +99,26 @@ impl PythonArchBuilder {
             _ => panic!("invalid symbol type to extract path")
         };
         if self.file_mode {
-            let in_workspace = (self.file.borrow().parent().is_some() &&
+            let in_workspace = !session.sync_odoo.get_file_mgr().borrow().is_ignored(path.as_str(), false) && ((self.file.borrow().parent().is_some() &&
                 self.file.borrow().parent().as_ref().unwrap().upgrade().is_some() &&
                 self.file.borrow().parent().as_ref().unwrap().upgrade().unwrap().borrow().in_workspace()) ||
-                session.sync_odoo.get_file_mgr().borrow().is_in_workspace(path.as_str());
+                session.sync_odoo.get_file_mgr().borrow().is_in_workspace(path.as_str(), false));
             self.file.borrow_mut().set_in_workspace(in_workspace);
+            if ArchCache::restore(session, &self.file, &path) {
+                //the entry holds the state of the file after its ARCH_EVAL
+                PythonArchBuilderHooks::on_done(session, &self.sym_stack[0]);
+                session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.file) as usize);
+                let mut symbol = self.sym_stack[0].borrow_mut();
+                symbol.set_build_status(BuildSteps::ARCH, BuildStatus::DONE);
+                symbol.set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::DONE);
+                symbol.set_arch_eval_epoch(session.sync_odoo.epoch);
+                return;
+            }
         }
         let file_info_rc = match self.file_mode {
             true => {
                 let (_, file_info) = session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, path.as_str(), None, None, false); //create ast if not in cache
+                file_info.borrow_mut().mark_indexed();
                 file_info
                 },
             false => {session.sync_odoo.get_file_mgr().borrow().get_file_info(&path).unwrap()}
@@ -101,17 +139,38 @@ impl PythonArchBuilder {
                     &AstUtils::find_stmt_from_ast(file_info.ast.as_ref().unwrap(), self.sym_stack[0].borrow().ast_indexes().unwrap()).as_function_def_stmt().unwrap().body
                 }
             };
+            if self.file_mode {
+                //the imported files are parsed by the workers while this file is built
+                ParsePool::prefetch_imports(session, &self.file, &path, ast);
+            }
             self.visit_node(session, &ast);
+            if self.cancelled {
+                //the content is unloaded when the file is built again from the start by the next rebuild
+                session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.file) as usize);
+                self.sym_stack[0].borrow_mut().set_build_status(BuildSteps::ARCH, BuildStatus::PENDING);
+                session.sync_odoo.add_to_rebuild_arch(self.sym_stack[0].clone());
+                return;
+            }
             self._resolve_all_symbols(session);
             if self.file_mode {
+                //the stubs are not searched for string references
+                if !path.ends_with(".pyi") {
+                    let file_mgr = session.sync_odoo.get_file_mgr();
+                    let mut file_mgr = file_mgr.borrow_mut();
+                    file_mgr.string_index.update_file(&path, ast);
+                    file_mgr.context_keys.update_file(&path, ast);
+                    file_mgr.field_dependencies.update_file(&path, ast);
+                }
+                self._add_dynamic_fields(session, &ast);
                 session.sync_odoo.add_to_rebuild_arch_eval(self.sym_stack[0].clone());
             }
         } else if self.file_mode {
             drop(file_info);
             let mut file_info = file_info_rc.borrow_mut();
-            file_info.publish_diagnostics(session);
+            file_info.schedule_publication(session);
         }
         PythonArchBuilderHooks::on_done(session, &self.sym_stack[0]);
+        session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.file) as usize);
         let mut symbol = self.sym_stack[0].borrow_mut();
         symbol.set_build_status(BuildSteps::ARCH, BuildStatus::DONE);
     }
@@ -119,80 +178,68 @@ impl PythonArchBuilder {
     fn create_local_symbols_from_import_stmt(&mut self, session: &mut SessionInfo, from_stmt: Option<&Identifier>, name_aliases: &[Alias], level: Option<u32>, range: &TextRange) -> Result<(), Error> {
         for import_name in name_aliases {
             if import_name.name.as_str() == "*" {
-                if self.sym_stack.len() != 1 { //only at top level for now.
+                //the names are added to the file, in any block (if, try...). Python only allows a star import at the
+                //module level: the body of a function rebuilt alone is the first scope of the stack, but not the file
+                if !Rc::ptr_eq(self.sym_stack.last().unwrap(), &self.file) {
                     continue;
                 }
                 let import_result: ImportResult = resolve_import_stmt(
                     session,
-                    self.sym_stack.last().unwrap(),
+                    &self.file,
                     from_stmt,
                     name_aliases,
                     level,
                     &mut None).remove(0); //we don't need the vector with this call as there will be 1 result.
                 if !import_result.found {
-                    session.sync_odoo.not_found_symbols.insert(self.file.clone());
-                    let file_tree_flattened = [import_result.file_tree.0.clone(), import_result.file_tree.1.clone()].concat();
-                    self.file.borrow_mut().not_found_paths_mut().push((self.current_step, file_tree_flattened));
+                    //optional dependencies of external packages are often missing: they won't be resolved, don't try to
+                    if !self.file.borrow().is_external() {
+                        session.sync_odoo.not_found_symbols.insert(self.file.clone());
+                        let file_tree_flattened = [import_result.file_tree.0.clone(), import_result.file_tree.1.clone()].concat();
+                        self.file.borrow_mut().add_not_found_path(self.current_step, file_tree_flattened);
+                    }
                     continue;
                 }
                 let mut all_name_allowed = true;
                 let mut name_filter: Vec<String> = vec![];
-                if let Some(all) = import_result.symbol.borrow().get_content_symbol("__all__", u32::MAX).get(0) {
-                    let all = Symbol::follow_ref(&EvaluationSymbolWeak::new(
-                        Rc::downgrade(all), None, false
-                    ), session, &mut None, false, true, None, &mut self.diagnostics);
-                    if let Some(all) = all.get(0) {
-                        if !all.weak.is_expired() {
-                            let all = all.weak.upgrade();
-                            if let Some(all) = all {
-                                let all = (*all).borrow();
-                                if all.evaluations().is_some() && all.evaluations().unwrap().len() == 1 {
-                                    let value = &all.evaluations().unwrap()[0].value;
-                                    if value.is_some() {
-                                        let (nf, parse_error) = self.extract_all_symbol_eval_values(&value.as_ref());
-                                        if parse_error {
-                                            warn!("error during parsing __all__ import in file {}", (*import_result.symbol).borrow().paths()[0] )
-                                        }
-                                        name_filter = nf;
-                                        all_name_allowed = false;
-                                    } else {
-                                        warn!("invalid __all__ import in file {} - no value found", (*import_result.symbol).borrow().paths()[0])
-                                    }
-                                } else {
-                                    warn!("invalid __all__ import in file {} - multiple evaluation found", (*import_result.symbol).borrow().paths()[0])
-                                }
-                            } else {
-                                warn!("invalid __all__ import in file {} - localizedSymbol not found", (*import_result.symbol).borrow().paths()[0])
-                            }
-                        } else {
-                            warn!("invalid __all__ import in file {} - expired symbol", (*import_result.symbol).borrow().paths()[0])
-                        }
-                    } else {
-                        warn!("invalid __all__ import in file {} - no symbol found", (*import_result.symbol).borrow().paths()[0])
+                if !import_result.symbol.borrow().get_content_symbol("__all__", u32::MAX).is_empty() {
+                    match self._get_all_names(session, &import_result.symbol) {
+                        Some(names) => {
+                            name_filter = names;
+                            all_name_allowed = false;
+                        },
+                        None => warn!("__all__ of file {} can't be computed, all its names are imported", (*import_result.symbol).borrow().paths()[0])
                     }
                 }
+                //a file importing itself gets no new name, and would borrow the file it is adding the names to
+                if Rc::ptr_eq(&import_result.symbol, &self.file) {
+                    continue;
+                }
+                //the evaluations are read before adding the variables, as the imported symbol can be a parent of the scope
+                let imported: Vec<(String, Vec<Evaluation>)> = {
+                    let symbol = import_result.symbol.borrow();
+                    symbol.iter_symbols().filter(|(name, _)| all_name_allowed || name_filter.contains(*name))
+                        .map(|(name, loc_syms)| (name.clone(), Evaluation::from_sections(&symbol, name, loc_syms)))
+                        .filter(|(_, evaluations)| !evaluations.is_empty()) //deleted at the end of the file
+                        .collect()
+                };
                 let mut dep_to_add = vec![];
-                let symbol = import_result.symbol.borrow();
-                for (name, loc_syms) in symbol.iter_symbols() {
-                    if all_name_allowed || name_filter.contains(&name) {
-                        let mut variable = self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &name, &import_result.range);
-                        let mut loc = variable.borrow_mut();
-                        loc.as_variable_mut().is_import_variable = true;
-                        loc.as_variable_mut().evaluations = Evaluation::from_sections(&symbol, loc_syms);
-                        dep_to_add.push(variable.clone());
+                for (name, evaluations) in imported.into_iter() {
+                    let variable = self._add_new_variable(session, &name, &import_result.range);
+                    let mut loc = Borrows::borrow_mut(&variable);
+                    loc.as_variable_mut().is_import_variable = true;
+                    loc.as_variable_mut().is_type_checking_import = self.type_checking;
+                    if let Some(evaluation) = evaluations.first() {
+                        dep_to_add.push(evaluation.symbol.clone());
                     }
+                    loc.as_variable_mut().evaluations = evaluations;
                 }
-                drop(symbol);
-                for sym in dep_to_add {
-                    let mut sym_bw = sym.borrow_mut();
-                    let evaluation = &sym_bw.as_variable_mut().evaluations[0];
-                    let evaluated_type = &evaluation.symbol;
-                    let evaluated_type = evaluated_type.get_symbol(session, &mut None, &mut self.diagnostics, None).weak;
-                    if !evaluated_type.is_expired() {
-                        let evaluated_type = evaluated_type.upgrade().unwrap();
-                        let evaluated_type_file = evaluated_type.borrow_mut().get_file().unwrap().clone().upgrade().unwrap();
-                        if !Rc::ptr_eq(&self.file, &evaluated_type_file) {
-                            self.file.borrow_mut().add_dependency(&mut evaluated_type_file.borrow_mut(), self.current_step, BuildSteps::ARCH);
+                //the variables are not borrowed while their evaluations are followed, as they can lead to the variables themselves
+                for evaluation_symbol in dep_to_add {
+                    let evaluated_type = evaluation_symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).weak;
+                    if let Some(evaluated_type) = evaluated_type.upgrade() {
+                        let evaluated_type_file = evaluated_type.borrow().get_file().and_then(|file| file.upgrade());
+                        if let Some(evaluated_type_file) = evaluated_type_file {
+                            Symbol::add_dependency_to(&self.file, &evaluated_type_file, self.current_step, BuildSteps::ARCH);
                         }
                     }
                 }
@@ -203,15 +250,27 @@ impl PythonArchBuilder {
                 } else {
                     import_name.asname.as_ref().unwrap().clone().to_string()
                 };
-                let mut variable = self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &var_name, &import_name.range);
+                let mut variable = self._add_new_variable(session, &var_name, &import_name.range);
                 variable.borrow_mut().as_variable_mut().is_import_variable = true;
+                variable.borrow_mut().as_variable_mut().is_type_checking_import = self.type_checking;
             }
         }
         Ok(())
     }
 
     fn visit_node(&mut self, session: &mut SessionInfo, nodes: &Vec<Stmt>) -> Result<(), Error> {
-        for stmt in nodes.iter() {
+        for (index, stmt) in nodes.iter().enumerate() {
+            if self.file_mode && self.ast_indexes.is_empty() && session.is_cancelled() {
+                self.cancelled = true;
+                break;
+            }
+            self.ast_indexes.push(index as u16);
+            //walrus operators in the expressions of the statement (not in its body) bind names in the current scope
+            for named in AstUtils::find_named_exprs(stmt) {
+                if let Expr::Name(target) = &*named.target {
+                    self._add_new_variable(session, &target.id.to_string(), &target.range);
+                }
+            }
             match stmt {
                 Stmt::Import(import_stmt) => {
                     self.create_local_symbols_from_import_stmt(session, None, &import_stmt.names, None, &import_stmt.range)?
@@ -225,6 +284,12 @@ impl PythonArchBuilder {
                 Stmt::Assign(assign_stmt) => {
                     self._visit_assign(session, assign_stmt);
                 },
+                Stmt::AugAssign(aug_assign_stmt) if ContainerMutations::find(stmt).is_none() => {
+                    self._visit_aug_assign(session, aug_assign_stmt);
+                },
+                Stmt::TypeAlias(type_alias_stmt) => {
+                    self._visit_type_alias(session, type_alias_stmt);
+                },
                 Stmt::FunctionDef(function_def_stmt) => {
                     self.visit_func_def(session, function_def_stmt)?;
                 },
@@ -243,12 +308,137 @@ impl PythonArchBuilder {
                 Stmt::With(with_stmt) => {
                     self.visit_with(session, with_stmt)?;
                 },
+                Stmt::While(while_stmt) => {
+                    self.visit_while(session, while_stmt)?;
+                },
+                Stmt::Global(global_stmt) => {
+                    if let Symbol::Function(function) = &mut *self.sym_stack.last().unwrap().borrow_mut() {
+                        for name in global_stmt.names.iter().filter(|name| !function.global_names.contains(&name.id.to_string())) {
+                            function.global_names.push(name.id.to_string());
+                        }
+                    }
+                },
+                Stmt::Nonlocal(nonlocal_stmt) => {
+                    if let Symbol::Function(function) = &mut *self.sym_stack.last().unwrap().borrow_mut() {
+                        for name in nonlocal_stmt.names.iter().filter(|name| !function.nonlocal_names.contains(&name.id.to_string())) {
+                            function.nonlocal_names.push(name.id.to_string());
+                        }
+                    }
+                },
+                Stmt::Match(match_stmt) => {
+                    self.visit_match(session, match_stmt)?;
+                },
+                Stmt::Delete(delete_stmt) => {
+                    //only the names are deleted from the scope. Attributes and subscripts are not tracked, nor the names
+                    //of an outer scope (global or nonlocal), as the function may not be called
+                    let end = delete_stmt.range.end().to_u32();
+                    let mut scope = self.sym_stack.last().unwrap().borrow_mut();
+                    for target in delete_stmt.targets.iter() {
+                        let Expr::Name(name) = target else {
+                            continue;
+                        };
+                        if let Symbol::Function(function) = &*scope {
+                            if function.get_outer_scope(name.id.as_str()).is_some() {
+                                continue;
+                            }
+                        }
+                        scope.as_mut_symbol_mgr().add_deleted_name(name.id.as_str(), end);
+                    }
+                },
+                Stmt::Assert(assert_stmt) => {
+                    let scope = self.sym_stack.last().unwrap().clone();
+                    self._add_narrowed_variables(session, &scope, &Narrowings::get_assert_branch(assert_stmt));
+                },
                 _ => {}
             }
+            if let Some(mutation) = ContainerMutations::find(stmt) {
+                self._visit_container_mutation(session, &mutation);
+            }
+            self.ast_indexes.pop();
         }
         Ok(())
     }
 
+    /* Visit a body of a statement that has several ones. bloc is the position of the body in the statement (see
+    AstUtils::get_stmt_from_ast) */
+    fn visit_bloc(&mut self, session: &mut SessionInfo, bloc: &[u16], body: &Vec<Stmt>) -> Result<(), Error> {
+        self.ast_indexes.extend(bloc);
+        let res = self.visit_node(session, body);
+        self.ast_indexes.truncate(self.ast_indexes.len() - bloc.len());
+        res
+    }
+
+    /* Add a variable to the current scope, that can be found back from the statement that declares it */
+    fn _add_new_variable(&mut self, session: &mut SessionInfo, name: &String, range: &TextRange) -> Rc<RefCell<Symbol>> {
+        let outer_scope = match &*self.sym_stack.last().unwrap().borrow() {
+            Symbol::Function(function) => function.get_outer_scope(name),
+            _ => None
+        };
+        let variable = match outer_scope {
+            Some(outer_scope) => self._add_outer_variable(session, &outer_scope, name, range),
+            None => self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, name, range)
+        };
+        *variable.borrow_mut().ast_indexes_mut() = self.ast_indexes.clone();
+        variable
+    }
+
+    /* Add a variable assigned by a function to the scope given by its global or nonlocal statement. As the function may
+    not be called, the assignment is a section of this scope that is possibly skipped, if the scope is still being built
+    (the function is in its body). Otherwise, like for a function rebuilt alone, the variable is added to the section of
+    the outer scope containing the function */
+    fn _add_outer_variable(&mut self, session: &mut SessionInfo, outer_scope: &Rc<RefCell<Symbol>>, name: &String, range: &TextRange) -> Rc<RefCell<Symbol>> {
+        if let Some(variable) = outer_scope.borrow().get_positioned_symbol(name, range) {
+            return variable;
+        }
+        let mut outer = outer_scope.borrow_mut();
+        let last_section = outer.as_symbol_mgr().get_section_for(u32::MAX);
+        if range.start().to_u32() <= last_section.start {
+            return outer.add_new_variable(session, name, range);
+        }
+        let pre_write = SectionIndex::INDEX(last_section.index);
+        let mut write_section = outer.as_mut_symbol_mgr().add_section(*range);
+        outer.as_mut_symbol_mgr().change_parent(pre_write.clone(), &mut write_section);
+        let variable = outer.add_new_variable(session, name, range);
+        let mut next_section = outer.as_mut_symbol_mgr().add_section(TextRange::new(range.end(), range.end()));
+        let next_parent = PythonArchBuilder::_resolve_popped_section(SectionIndex::OR(vec![pre_write, SectionIndex::INDEX(write_section.index)]), &next_section);
+        outer.as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
+        variable
+    }
+
+    /* Return the names of the __all__ of a module: the union of the values of its declarations reachable at the end of
+    the module, each one including the previous assignments and mutations (__all__ += [...], __all__.append(...), see
+    ContainerMutations). None if one of these values can't be computed, like after __all__.extend(other.__all__): the
+    star import then imports every name rather than none. */
+    fn _get_all_names(&mut self, session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>) -> Option<Vec<String>> {
+        let declarations = module.borrow().get_content_symbol("__all__", u32::MAX);
+        let mut names: Vec<String> = vec![];
+        for declaration in declarations.iter() {
+            let followed = Symbol::follow_ref(&EvaluationSymbolWeak::new(
+                Rc::downgrade(declaration), None, false
+            ), session, &mut None, false, true, None, &mut self.diagnostics);
+            for all in followed.iter() {
+                let all = all.weak.upgrade()?;
+                let all = all.borrow();
+                let evaluations = all.evaluations()?;
+                if evaluations.is_empty() {
+                    return None;
+                }
+                for evaluation in evaluations.iter() {
+                    let (values, parse_error) = self.extract_all_symbol_eval_values(&evaluation.value.as_ref());
+                    if parse_error {
+                        return None;
+                    }
+                    for value in values.into_iter() {
+                        if !names.contains(&value) {
+                            names.push(value);
+                        }
+                    }
+                }
+            }
+        }
+        Some(names)
+    }
+
     fn extract_all_symbol_eval_values(&self, value: &Option<&EvaluationValue>) -> (Vec<String>, bool) {
         let mut parse_error = false;
         let vec: Vec<String> = match value {
@@ -266,7 +456,7 @@ impl PythonArchBuilder {
                             _ => {parse_error = true; vec![]}
                         }
                     },
-                    EvaluationValue::DICT(_d) => {
+                    EvaluationValue::DICT(_) | EvaluationValue::SET(_) => {
                         parse_error = true; vec![]
                     },
                     EvaluationValue::LIST(l) => {
@@ -305,23 +495,56 @@ impl PythonArchBuilder {
             Some(value) => python_utils::unpack_assign(&vec![*ann_assign_stmt.target.clone()], Some(&ann_assign_stmt.annotation), Some(value)),
             None => python_utils::unpack_assign(&vec![*ann_assign_stmt.target.clone()], Some(&ann_assign_stmt.annotation), None)
         };
+        let scope = self.sym_stack.last().unwrap().clone();
+        let is_type_alias = StaticConditions::get_qualified_name(session, &scope, &ann_assign_stmt.annotation, !self.file_mode)
+            .is_some_and(|name| ["typing.TypeAlias", "typing_extensions.TypeAlias"].contains(&name.as_str()));
         for assign in assigns.iter() { //should only be one
-            self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
+            let variable = self._add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
+            variable.borrow_mut().as_variable_mut().is_explicit_type_alias = is_type_alias && assign.value.is_some();
         }
     }
 
+    /* Declare the alias of a type statement, like Pair in type Pair[T] = tuple[T, T]. Its type parameters are only
+    recorded, as the type variables are not modeled. */
+    fn _visit_type_alias(&mut self, session: &mut SessionInfo, type_alias_stmt: &StmtTypeAlias) {
+        let Expr::Name(name) = &*type_alias_stmt.name else {
+            return;
+        };
+        let variable = self._add_new_variable(session, &name.id.to_string(), &name.range);
+        let mut variable = variable.borrow_mut();
+        let variable = variable.as_variable_mut();
+        variable.is_explicit_type_alias = true;
+        variable.type_params = type_alias_stmt.type_params.iter().flat_map(|type_params| type_params.type_params.iter()).map(|type_param| match type_param {
+            TypeParam::TypeVar(t) => t.name.to_string(),
+            TypeParam::ParamSpec(t) => t.name.to_string(),
+            TypeParam::TypeVarTuple(t) => t.name.to_string(),
+        }).collect();
+    }
+
     fn _visit_assign(&mut self, session: &mut SessionInfo, assign_stmt: &StmtAssign) {
         let assigns = python_utils::unpack_assign(&assign_stmt.targets, None, Some(&assign_stmt.value));
         for assign in assigns.iter() {
-            let variable = self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
+            let folded = self._fold_constant_value(assign);
+            if let (Some(Expr::Lambda(lambda)), None) = (&assign.value, assign.index) {
+                if self._add_lambda(session, &assign.target, lambda).is_some() {
+                    continue;
+                }
+            }
+            let variable = self._add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
             let mut variable = variable.borrow_mut();
             if self.file_mode && variable.name() == "__all__" && assign.value.is_some() && variable.parent().is_some() {
                 let parent = variable.parent().as_ref().unwrap().upgrade();
                 if parent.is_some() {
                     let parent = parent.unwrap();
-                    let eval = Evaluation::eval_from_ast(session, &assign.value.as_ref().unwrap(), parent, &assign_stmt.range.start());
-                    variable.as_variable_mut().evaluations = eval.0;
-                    self.diagnostics.extend(eval.1);
+                    //the other variables are not evaluated yet: __all__ = BASE_NAMES[:2] is folded from the constants of the file
+                    match folded.and_then(|value| Evaluation::new_collection(session.sync_odoo, value, assign.value.as_ref().unwrap().range())) {
+                        Some(eval) => variable.as_variable_mut().evaluations = vec![eval],
+                        None => {
+                            let eval = Evaluation::eval_from_ast(session, &assign.value.as_ref().unwrap(), parent, &assign_stmt.range.start());
+                            variable.as_variable_mut().evaluations = eval.0;
+                            self.diagnostics.extend(eval.1);
+                        }
+                    }
                     if !variable.as_variable().evaluations.is_empty() {
                         if (*self.sym_stack.last().unwrap()).borrow().is_external() {
                             // external packages often import symbols from compiled files
@@ -330,7 +553,7 @@ impl PythonArchBuilder {
                             // as symbols to not raise any error.
                             let evaluation = variable.as_variable_mut().evaluations.get(0).unwrap();
                             match &evaluation.value {
-                                Some(EvaluationValue::LIST(list)) => {
+                                Some(EvaluationValue::LIST(list)) | Some(EvaluationValue::TUPLE(list)) => {
                                     for item in list.iter() {
                                         match item {
                                             Expr::StringLiteral(s) => {
@@ -349,11 +572,83 @@ impl PythonArchBuilder {
         }
     }
 
+    /* Add the variable bound by an augmented assignment that doesn't mutate a container (see ContainerMutations), like
+    total -= discount. It is declared like an assignment, even if the name has no previous declaration */
+    fn _visit_aug_assign(&mut self, session: &mut SessionInfo, aug_assign_stmt: &StmtAugAssign) {
+        let Expr::Name(target) = &*aug_assign_stmt.target else {
+            return; //attributes and subscripts don't bind a name
+        };
+        self.constant_values.remove(target.id.as_str());
+        self._add_new_variable(session, &target.id.to_string(), &target.range);
+    }
+
+    /* Record the value of a constant assigned at the top level of the file, and return it. A variable assigned with a
+    value that can't be folded is forgotten */
+    fn _fold_constant_value(&mut self, assign: &python_utils::Assign) -> Option<EvaluationValue> {
+        if !self.file_mode || !Rc::ptr_eq(self.sym_stack.last().unwrap(), &self.file) {
+            return None;
+        }
+        let name = assign.target.id.to_string();
+        let folded = match (&assign.value, assign.index) {
+            (Some(value), None) if value.is_literal_expr() => Some(EvaluationValue::CONSTANT(value.clone())),
+            (Some(value), None) => CollectionFolding::fold(value, &mut |expr| match expr {
+                Expr::Name(name) => self.constant_values.get(name.id.as_str()).cloned(),
+                _ => None
+            }),
+            _ => None
+        };
+        match &folded {
+            Some(value) => self.constant_values.insert(name, value.clone()),
+            None => self.constant_values.remove(&name)
+        };
+        folded
+    }
+
+    /* Declare the variable changed by a mutation at the end of the statement (see ContainerMutations). It stays in the
+    current section: a section starting there would be replaced by the one following a body ending with the statement.
+    The mutations of __all__ are evaluated now, as the star imports of other files read it before the evaluation of
+    this one. */
+    fn _visit_container_mutation(&mut self, session: &mut SessionInfo, mutation: &ContainerMutation) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        if scope.borrow().typ() == SymType::CLASS {
+            return; //a mutated variable in a class body would be a member of the class
+        }
+        let position = ContainerMutations::get_position(mutation);
+        let name = mutation.name.id.to_string();
+        self.constant_values.remove(&name);
+        let variable = self._add_new_variable(session, &name, &position);
+        variable.borrow_mut().as_variable_mut().is_container_mutation = true;
+        if self.file_mode && name == "__all__" && Rc::ptr_eq(&scope, &self.file) {
+            let evaluations = ContainerMutations::evaluate(session, &scope, mutation);
+            if scope.borrow().is_external() {
+                //see _visit_assign
+                for evaluation in evaluations.iter() {
+                    if let Some(EvaluationValue::LIST(list) | EvaluationValue::TUPLE(list)) = &evaluation.value {
+                        for item in list.iter() {
+                            if let Expr::StringLiteral(s) = item {
+                                self.__all_symbols_to_add.push((s.value.to_string(), evaluation.range.unwrap()));
+                            }
+                        }
+                    }
+                }
+            }
+            variable.borrow_mut().set_evaluations(evaluations);
+        }
+    }
+
     fn visit_func_def(&mut self, session: &mut SessionInfo, func_def: &StmtFunctionDef) -> Result<(), Error> {
-        let sym = self.sym_stack.last().unwrap().borrow_mut().add_new_function(
-            session, &func_def.name.id.to_string(), &func_def.range, &func_def.body.get(0).unwrap().range().start());
+        let body_start = func_def.body.get(0).unwrap().range().start();
+        let is_overload = self._is_overload(session, func_def);
+        let sym = match (self._get_accessed_property(func_def), self._get_overloaded_function(func_def)) {
+            (Some((property, is_setter)), _) => property.borrow_mut().add_new_property_accessor(&func_def.name.id.to_string(), &func_def.range, &body_start, is_setter),
+            (None, Some(overloaded)) => overloaded.borrow_mut().add_new_overload(&func_def.name.id.to_string(), &func_def.range, &body_start),
+            (None, None) => self.sym_stack.last().unwrap().borrow_mut().add_new_function(session, &func_def.name.id.to_string(), &func_def.range, &body_start)
+        };
         let mut sym_bw = sym.borrow_mut();
+        *sym_bw.ast_indexes_mut() = self.ast_indexes.clone();
         let func_sym = sym_bw.as_func_mut();
+        func_sym.is_overloaded = is_overload;
+        func_sym.is_async = func_def.is_async;
         for decorator in func_def.decorator_list.iter() {
             if decorator.expression.is_name_expr() {
                 if decorator.expression.as_name_expr().unwrap().id.to_string() == "staticmethod" {
@@ -362,9 +657,6 @@ impl PythonArchBuilder {
                 else if decorator.expression.as_name_expr().unwrap().id.to_string() == "property" {
                     func_sym.is_property = true;
                 }
-                else if decorator.expression.as_name_expr().unwrap().id.to_string() == "overload" {
-                    func_sym.is_overloaded = true;
-                }
                 else if decorator.expression.as_name_expr().unwrap().id.to_string() == "classmethod" {
                     func_sym.is_class_method = true;
                 }
@@ -377,56 +669,7 @@ impl PythonArchBuilder {
             }
         }
         drop(sym_bw);
-        //add params
-        for arg in func_def.parameters.posonlyargs.iter() {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.parameter.name.id.to_string(), &arg.range);
-            param.borrow_mut().as_variable_mut().is_parameter = true;
-            sym.borrow_mut().as_func_mut().args.push(Argument {
-                symbol: Rc::downgrade(&param),
-                default_value: None,
-                arg_type: ArgumentType::POS_ONLY
-            });
-        }
-        for arg in func_def.parameters.args.iter() {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.parameter.name.id.to_string(), &arg.range);
-            param.borrow_mut().as_variable_mut().is_parameter = true;
-            let mut default = None;
-            if arg.default.is_some() {
-                default = Some(Evaluation::new_none()); //TODO evaluate default? actually only used to know if there is a default or not
-            }
-            sym.borrow_mut().as_func_mut().args.push(Argument {
-                symbol: Rc::downgrade(&param),
-                default_value: default,
-                arg_type: ArgumentType::ARG
-            });
-        }
-        if let Some(arg) = &func_def.parameters.vararg {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.name.id.to_string(), &arg.range);
-            param.borrow_mut().as_variable_mut().is_parameter = true;
-            sym.borrow_mut().as_func_mut().args.push(Argument {
-                symbol: Rc::downgrade(&param),
-                default_value: None,
-                arg_type: ArgumentType::VARARG
-            });
-        }
-        for arg in func_def.parameters.kwonlyargs.iter() {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.parameter.name.id.to_string(), &arg.range);
-            param.borrow_mut().as_variable_mut().is_parameter = true;
-            sym.borrow_mut().as_func_mut().args.push(Argument {
-                symbol: Rc::downgrade(&param),
-                default_value: None,
-                arg_type: ArgumentType::KWORD_ONLY
-            });
-        }
-        if let Some(arg) = &func_def.parameters.kwarg {
-            let param = sym.borrow_mut().add_new_variable(session, &arg.name.id.to_string(), &arg.range);
-            param.borrow_mut().as_variable_mut().is_parameter = true;
-            sym.borrow_mut().as_func_mut().args.push(Argument {
-                symbol: Rc::downgrade(&param),
-                default_value: None,
-                arg_type: ArgumentType::KWARG
-            });
-        }
+        self._add_parameters(session, &sym, &func_def.parameters);
         //visit body
         if !self.file_mode || sym.borrow().get_in_parents(&vec![SymType::CLASS], true).is_none() {
             sym.borrow_mut().as_func_mut().arch_status = BuildStatus::IN_PROGRESS;
@@ -438,10 +681,113 @@ impl PythonArchBuilder {
         Ok(())
     }
 
+    /* Return true if the function is decorated by typing.overload, whatever the name it is imported with */
+    fn _is_overload(&self, session: &mut SessionInfo, func_def: &StmtFunctionDef) -> bool {
+        let scope = self.sym_stack.last().unwrap().clone();
+        func_def.decorator_list.iter().any(|decorator| {
+            //the imports of the scope of a rebuilt function are not known here
+            decorator.expression.as_name_expr().is_some_and(|name| name.id.as_str() == "overload") ||
+            StaticConditions::get_qualified_name(session, &scope, &decorator.expression, !self.file_mode)
+                .is_some_and(|name| ["typing.overload", "typing_extensions.overload"].contains(&name.as_str()))
+        })
+    }
+
+    /* Return the function of the current scope that the function is an overload or the implementation of: the previous
+    definition with the same name, if it is decorated by @overload (see FunctionSymbol::accepts_overloads) */
+    fn _get_overloaded_function(&self, func_def: &StmtFunctionDef) -> Option<Rc<RefCell<Symbol>>> {
+        let previous = self.sym_stack.last().unwrap().borrow().get_content_symbol(func_def.name.id.as_str(), func_def.range.start().to_u32()).pop()?;
+        let accepts_overloads = previous.borrow().typ() == SymType::FUNCTION && previous.borrow().as_func().accepts_overloads();
+        accepts_overloads.then_some(previous)
+    }
+
+    /* Return the property of the current scope whose setter or deleter is defined by the function, with true for a
+    setter: the getter of x for a function x decorated by @x.setter */
+    fn _get_accessed_property(&self, func_def: &StmtFunctionDef) -> Option<(Rc<RefCell<Symbol>>, bool)> {
+        for decorator in func_def.decorator_list.iter() {
+            let Expr::Attribute(attribute) = &decorator.expression else {
+                continue;
+            };
+            let is_setter = match attribute.attr.as_str() {
+                "setter" => true,
+                "deleter" => false,
+                _ => continue
+            };
+            //@x.setter on a function with another name makes another property, x is unchanged
+            if !attribute.value.as_name_expr().is_some_and(|name| name.id.as_str() == func_def.name.id.as_str()) {
+                continue;
+            }
+            let property = self.sym_stack.last().unwrap().borrow().get_content_symbol(func_def.name.id.as_str(), func_def.range.start().to_u32()).pop()?;
+            if property.borrow().typ() == SymType::FUNCTION && property.borrow().as_func().is_property {
+                return Some((property, is_setter));
+            }
+        }
+        None
+    }
+
+    /* Declare a lambda assigned to a name, like compute = lambda self, x: x * self.factor, as a function of the current
+    scope, so its calls are resolved like the ones of a def. It has no body to build: its returned expression is
+    evaluated with the assignment by the arch eval step. Return None if the name is assigned in an outer scope by a
+    global or nonlocal statement, as the lambda is then declared as a variable. */
+    fn _add_lambda(&mut self, session: &mut SessionInfo, target: &ExprName, lambda: &ExprLambda) -> Option<Rc<RefCell<Symbol>>> {
+        let name = target.id.to_string();
+        if let Symbol::Function(function) = &*self.sym_stack.last().unwrap().borrow() {
+            if function.get_outer_scope(&name).is_some() {
+                return None;
+            }
+        }
+        let function = self.sym_stack.last().unwrap().borrow_mut().add_new_function(session, &name, &target.range, &lambda.body.range().start());
+        *function.borrow_mut().ast_indexes_mut() = self.ast_indexes.clone();
+        if let Some(parameters) = &lambda.parameters {
+            self._add_parameters(session, &function, parameters);
+        }
+        let mut function_bw = function.borrow_mut();
+        let func = function_bw.as_func_mut();
+        func.is_lambda = true;
+        func.arch_status = BuildStatus::DONE;
+        func.arch_eval_status = BuildStatus::DONE;
+        func.odoo_status = BuildStatus::DONE;
+        func.validation_status = BuildStatus::DONE;
+        drop(function_bw);
+        Some(function)
+    }
+
+    /* Add the parameters to the function, in the order of the source: a, b=1, /, c, *args, d, e=2, **kwargs */
+    fn _add_parameters(&mut self, session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, parameters: &Parameters) {
+        for arg in parameters.posonlyargs.iter() {
+            self._add_parameter(session, function, &arg.parameter.name, ArgumentType::POS_ONLY, arg.default.is_some());
+        }
+        for arg in parameters.args.iter() {
+            self._add_parameter(session, function, &arg.parameter.name, ArgumentType::ARG, arg.default.is_some());
+        }
+        if let Some(arg) = &parameters.vararg {
+            self._add_parameter(session, function, &arg.name, ArgumentType::VARARG, false);
+        }
+        for arg in parameters.kwonlyargs.iter() {
+            self._add_parameter(session, function, &arg.parameter.name, ArgumentType::KWORD_ONLY, arg.default.is_some());
+        }
+        if let Some(arg) = &parameters.kwarg {
+            self._add_parameter(session, function, &arg.name, ArgumentType::KWARG, false);
+        }
+    }
+
+    /* Declare a parameter of the function as a variable of its scope, and add it to the arguments of the function */
+    fn _add_parameter(&mut self, session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, name: &Identifier, arg_type: ArgumentType, has_default: bool) {
+        let param = function.borrow_mut().add_new_variable(session, &name.id.to_string(), &name.range);
+        param.borrow_mut().as_variable_mut().is_parameter = true;
+        function.borrow_mut().as_func_mut().args.push(Argument {
+            symbol: Rc::downgrade(&param),
+            //a placeholder until the default is evaluated by the arch eval step
+            default_value: has_default.then(Evaluation::new_none),
+            arg_type,
+            annotation: None
+        });
+    }
+
     fn visit_class_def(&mut self, session: &mut SessionInfo, class_def: &StmtClassDef) -> Result<(), Error> {
         let mut sym = self.sym_stack.last().unwrap().borrow_mut().add_new_class(
             session, &class_def.name.id.to_string(), &class_def.range, &class_def.body.get(0).unwrap().range().start());
         let mut sym_bw = sym.borrow_mut();
+        *sym_bw.ast_indexes_mut() = self.ast_indexes.clone();
         let class_sym = sym_bw.as_class_sym_mut();
         if class_def.body.len() > 0 && class_def.body[0].is_expr_stmt() {
             let expr = class_def.body[0].as_expr_stmt().unwrap();
@@ -456,10 +802,86 @@ impl PythonArchBuilder {
         self.sym_stack.push(sym.clone());
         self.visit_node(session, &class_def.body)?;
         self.sym_stack.pop();
+        self._add_injected_attributes(session, &sym, class_def);
+        self._add_dataclass_init(session, &sym, class_def);
         PythonArchBuilderHooks::on_class_def(session, sym);
         Ok(())
     }
 
+    /* Create the attributes that __init_subclass__ sets on the subclasses, and the fixtures set by the classmethods and
+    setUp (see InjectedAttributes). They are added to the class itself, so the subclasses get them by inheritance. If the class is a metaclass, the attributes that it sets on the classes it creates
+    are kept, to be added to these classes when their metaclass is evaluated. */
+    fn _add_injected_attributes(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_def: &StmtClassDef) {
+        let is_metaclass = InjectedAttributes::is_metaclass(class_def);
+        for stmt in class_def.body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            if func.name.as_str() == "__init_subclass__" || InjectedAttributes::is_fixture_method(func) {
+                for attribute in InjectedAttributes::find(func).iter() {
+                    if class.borrow().get_content_symbol(&attribute.name, u32::MAX).is_empty() {
+                        class.borrow_mut().add_new_variable(session, &attribute.name, &attribute.range);
+                    }
+                }
+            } else if is_metaclass && METACLASS_METHODS.contains(&func.name.as_str()) {
+                let mut class = class.borrow_mut();
+                let metaclass_attributes = &mut class.as_class_sym_mut().metaclass_attributes;
+                for attribute in InjectedAttributes::find(func).into_iter() {
+                    if !metaclass_attributes.contains(&attribute.name) {
+                        metaclass_attributes.push(attribute.name);
+                    }
+                }
+            }
+        }
+    }
+
+    /* Store the fields of a dataclass or a NamedTuple, and declare the __init__ generated from them, unless the class
+    defines its own. It has no statement: its arguments are set by the arch eval step, once the fields inherited from
+    the bases are known (see Dataclasses::build_init). */
+    fn _add_dataclass_init(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_def: &StmtClassDef) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let Some((dataclass, has_init)) = Dataclasses::find(session, &scope, class_def, !self.file_mode) else {
+            return;
+        };
+        class.borrow_mut().as_class_sym_mut().dataclass = Some(dataclass);
+        if !has_init || !class.borrow().get_content_symbol("__init__", u32::MAX).is_empty() {
+            return;
+        }
+        let init = class.borrow_mut().add_new_function(session, &S!("__init__"), &class_def.name.range, &class_def.name.range.end());
+        let mut init_bw = init.borrow_mut();
+        *init_bw.ast_indexes_mut() = self.ast_indexes.clone();
+        let func = init_bw.as_func_mut();
+        func.is_synthesized = true;
+        func.arch_status = BuildStatus::DONE;
+        func.arch_eval_status = BuildStatus::DONE;
+        func.odoo_status = BuildStatus::DONE;
+        func.validation_status = BuildStatus::DONE;
+    }
+
+    /* Create the fields added by code to the classes of the file, so they can be found as members of their model */
+    fn _add_dynamic_fields(&mut self, session: &mut SessionInfo, ast: &Vec<Stmt>) {
+        for stmt in ast.iter() {
+            let Stmt::ClassDef(class_def) = stmt else {
+                continue;
+            };
+            let dynamic_fields = DynamicFields::find(ast, class_def);
+            if dynamic_fields.names.is_empty() && !dynamic_fields.has_unknown_names {
+                continue;
+            }
+            let Some(class) = self.sym_stack[0].borrow().get_positioned_symbol(&class_def.name.to_string(), &class_def.range) else {
+                continue;
+            };
+            for (name, range) in dynamic_fields.names.iter() {
+                if !class.borrow().get_content_symbol(name, u32::MAX).is_empty() {
+                    continue;
+                }
+                let field = class.borrow_mut().add_new_variable(session, name, range);
+                field.borrow_mut().as_variable_mut().is_dynamic_field = true;
+            }
+            class.borrow_mut().as_class_sym_mut().has_dynamic_fields = dynamic_fields.has_unknown_names;
+        }
+    }
+
     fn _resolve_all_symbols(&mut self, session: &mut SessionInfo) {
         for (symbol_name, range) in self.__all_symbols_to_add.drain(..) {
             if self.sym_stack.last().unwrap().borrow().get_content_symbol(&symbol_name, u32::MAX).is_empty() {
@@ -468,49 +890,272 @@ impl PythonArchBuilder {
         }
     }
 
+    /* See symbol_mgr.rs for the sections declared by an if */
     fn visit_if(&mut self, session: &mut SessionInfo, if_stmt: &StmtIf) -> Result<(), Error> {
-        //TODO check platform condition (sys.version > 3.12, etc...)
-        self.visit_node(session, &if_stmt.body)?;
-        for else_clause in if_stmt.elif_else_clauses.iter() {
-            self.visit_node(session, &else_clause.body)?;
+        let scope = self.sym_stack.last().unwrap().clone();
+        let branches = Narrowings::get_branches(if_stmt);
+        let selected_branch = self._select_static_branch(session, &scope, if_stmt);
+        let type_checking = StaticConditions::get_qualified_name(session, &scope, &if_stmt.test, !self.file_mode)
+            .is_some_and(|name| ["typing.TYPE_CHECKING", "typing_extensions.TYPE_CHECKING"].contains(&name.as_str()));
+        let mut condition = scope.borrow().as_symbol_mgr().get_last_index(); //section of the last evaluated test
+        let mut branch_ends = vec![];
+        for (index, branch) in branches.iter().enumerate() {
+            let (test, body) = match index {
+                0 => (None, &if_stmt.body),
+                _ => {
+                    let clause = &if_stmt.elif_else_clauses[index - 1];
+                    (clause.test.as_ref(), &clause.body)
+                }
+            };
+            if let Some(test) = test {
+                //the test of an elif is only evaluated if the previous tests are false
+                let mut condition_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(test.range());
+                let condition_parent = PythonArchBuilder::_resolve_popped_section(condition.clone(), &condition_section);
+                scope.borrow_mut().as_mut_symbol_mgr().change_parent(condition_parent, &mut condition_section);
+                condition = SectionIndex::INDEX(condition_section.index);
+            }
+            let mut body_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(branch.start, branch.start));
+            let body_parent = PythonArchBuilder::_resolve_popped_section(condition.clone(), &body_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(body_parent, &mut body_section);
+            self._add_narrowed_variables(session, &scope, branch);
+            let was_type_checking = self.type_checking;
+            self.type_checking |= index == 0 && type_checking;
+            let result = self.visit_bloc(session, &[index as u16], body);
+            self.type_checking = was_type_checking;
+            result?;
+            branch_ends.push(scope.borrow().as_symbol_mgr().get_last_index());
+        }
+        //without else, no branch is executed if all the tests are false
+        if if_stmt.elif_else_clauses.last().map_or(true, |clause| clause.test.is_some()) {
+            branch_ends.push(condition);
+        }
+        let next_parent = match selected_branch {
+            //only the declarations of the executed branch are reachable after the if
+            Some(index) => branch_ends[index].clone(),
+            None => {
+                //nor the ones of the branches that exit, unless no branch goes on after the if
+                let exiting = Narrowings::get_exiting_branches(if_stmt);
+                let reachable: Vec<SectionIndex> = branch_ends.iter().enumerate()
+                    .filter(|(index, _)| !exiting.get(*index).copied().unwrap_or(false))
+                    .map(|(_, end)| end.clone())
+                    .collect();
+                SectionIndex::OR(if reachable.is_empty() { branch_ends } else { reachable })
+            }
+        };
+        let mut next_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(if_stmt.range.end(), if_stmt.range.end()));
+        let next_parent = PythonArchBuilder::_resolve_popped_section(next_parent, &next_section);
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
+        if let Some(guard_clause) = Narrowings::get_guard_clause(if_stmt) {
+            self._add_narrowed_variables(session, &scope, &guard_clause);
         }
         Ok(())
     }
 
+    /* Declare the variables narrowed by a branch at its start, evaluated at the arch eval step */
+    fn _add_narrowed_variables(&mut self, session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, branch: &NarrowedBranch) {
+        //a narrowed variable in a class body would be a member of the class
+        if scope.borrow().typ() == SymType::CLASS {
+            return;
+        }
+        //the variables given to a function are narrowed if it returns a TypeGuard, known at the arch eval step
+        let guarded = branch.guards.iter().filter_map(|guard| guard.target.as_name_expr());
+        let mut names: Vec<String> = vec![];
+        for name in branch.narrowings.iter().map(|narrowing| narrowing.name).chain(guarded) {
+            if names.contains(&name.id.to_string()) {
+                continue;
+            }
+            names.push(name.id.to_string());
+            let variable = scope.borrow_mut().add_new_variable(session, &name.id.to_string(), &TextRange::new(branch.start, branch.start));
+            variable.borrow_mut().as_variable_mut().is_narrowed = true;
+        }
+    }
+
+    /* Return the branch of the if that is executed, if its tests only use constants, module-level variables with a
+    static value and the known names of the standard library, like sys.version_info (see StaticConditions). The files of these variables become dependencies of the current file, as
+    changing them can select another branch. */
+    fn _select_static_branch(&mut self, session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, if_stmt: &StmtIf) -> Option<usize> {
+        let mut files = vec![];
+        let globals_complete = !self.file_mode;
+        let selected_branch = StaticConditions::select_branch(if_stmt,
+            &mut |expr| StaticConditions::get_expr_value(session, scope, expr, globals_complete, 0, &mut files));
+        for file in files.iter() {
+            Symbol::add_dependency_to(&self.file, file, self.current_step, BuildSteps::ARCH);
+        }
+        selected_branch
+    }
+
+    /* See symbol_mgr.rs for the sections declared by a for loop */
     fn visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) -> Result<(), Error> {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let pre_loop = scope.borrow().as_symbol_mgr().get_last_index();
+        let mut body_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(for_stmt.target.range());
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(pre_loop.clone(), &mut body_section);
         let unpacked = python_utils::unpack_assign(&vec![*for_stmt.target.clone()], None, None);
         for assign in unpacked {
-            self.sym_stack.last().unwrap().borrow_mut().add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
+            self._add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
         }
-        self.visit_node(session, &for_stmt.body)?;
-        //TODO should split evaluations as in if
-        self.visit_node(session, &for_stmt.orelse)?;
+        self.visit_bloc(session, &[0], &for_stmt.body)?;
+        let body_end = scope.borrow().as_symbol_mgr().get_last_index();
+        let end_range = TextRange::new(for_stmt.range.end(), for_stmt.range.end());
+        let next_parent = if !for_stmt.orelse.is_empty() {
+            let mut orelse_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(for_stmt.orelse[0].range());
+            let orelse_parent = PythonArchBuilder::_resolve_popped_section(SectionIndex::OR(vec![pre_loop.clone(), body_end.clone()]), &orelse_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(orelse_parent, &mut orelse_section);
+            self.visit_bloc(session, &[1], &for_stmt.orelse)?;
+            let orelse_end = scope.borrow().as_symbol_mgr().get_last_index();
+            if PythonArchBuilder::_contains_break(&for_stmt.body) {
+                SectionIndex::OR(vec![orelse_end, body_end])
+            } else {
+                orelse_end
+            }
+        } else {
+            SectionIndex::OR(vec![pre_loop, body_end])
+        };
+        let mut next_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(end_range);
+        let next_parent = PythonArchBuilder::_resolve_popped_section(next_parent, &next_section);
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
         Ok(())
     }
 
+    /* add_section replaces the last section if it has the same start. In this case, the new section takes its index,
+    so any reference to it has to be replaced by the parents of the removed section. */
+    fn _resolve_popped_section(index: SectionIndex, new_section: &SectionRange) -> SectionIndex {
+        match index {
+            SectionIndex::INDEX(i) if i == new_section.index => new_section.previous_indexes.clone(),
+            SectionIndex::OR(indexes) => SectionIndex::OR(indexes.into_iter().map(|i| PythonArchBuilder::_resolve_popped_section(i, new_section)).collect()),
+            _ => index
+        }
+    }
+
+    /* Return true if the body contains a break statement that exits the current loop */
+    fn _contains_break(body: &Vec<Stmt>) -> bool {
+        body.iter().any(|stmt| match stmt {
+            Stmt::Break(_) => true,
+            Stmt::If(if_stmt) => PythonArchBuilder::_contains_break(&if_stmt.body) ||
+                if_stmt.elif_else_clauses.iter().any(|clause| PythonArchBuilder::_contains_break(&clause.body)),
+            Stmt::With(with_stmt) => PythonArchBuilder::_contains_break(&with_stmt.body),
+            Stmt::Try(try_stmt) => PythonArchBuilder::_contains_break(&try_stmt.body) ||
+                PythonArchBuilder::_contains_break(&try_stmt.orelse) ||
+                PythonArchBuilder::_contains_break(&try_stmt.finalbody) ||
+                try_stmt.handlers.iter().any(|h| match h {
+                    ruff_python_ast::ExceptHandler::ExceptHandler(h) => PythonArchBuilder::_contains_break(&h.body)
+                }),
+            Stmt::Match(match_stmt) => match_stmt.cases.iter().any(|case| PythonArchBuilder::_contains_break(&case.body)),
+            _ => false //break in nested loops exit the nested loop only
+        })
+    }
+
+    /* See symbol_mgr.rs for the sections declared by a try */
     fn visit_try(&mut self, session: &mut SessionInfo, try_stmt: &StmtTry) -> Result<(), Error> {
-        self.visit_node(session, &try_stmt.body)?;
-        self.visit_node(session, &try_stmt.orelse)?;
-        self.visit_node(session, &try_stmt.finalbody)?;
-        for handler in try_stmt.handlers.iter() {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let pre_try = scope.borrow().as_symbol_mgr().get_last_index();
+        self.visit_bloc(session, &[0], &try_stmt.body)?;
+        let body_end = scope.borrow().as_symbol_mgr().get_last_index();
+        let mut path_ends = vec![];
+        for (handler_index, handler) in try_stmt.handlers.iter().enumerate() {
             match handler {
-                ruff_python_ast::ExceptHandler::ExceptHandler(h) => self.visit_node(session, &h.body)?
+                ruff_python_ast::ExceptHandler::ExceptHandler(h) => {
+                    //the exception can be raised anywhere in the body: its variables are possibly bound
+                    let mut handler_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(h.range);
+                    let handler_parent = PythonArchBuilder::_resolve_popped_section(SectionIndex::OR(vec![pre_try.clone(), body_end.clone()]), &handler_section);
+                    scope.borrow_mut().as_mut_symbol_mgr().change_parent(handler_parent, &mut handler_section);
+                    if let Some(name) = h.name.as_ref() {
+                        self._add_new_variable(session, &name.id.to_string(), &name.range);
+                    }
+                    self.visit_bloc(session, &[3, handler_index as u16], &h.body)?;
+                    path_ends.push(scope.borrow().as_symbol_mgr().get_last_index());
+                }
             }
         }
+        if let Some(first) = try_stmt.orelse.first() {
+            let mut orelse_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(first.range());
+            let orelse_parent = PythonArchBuilder::_resolve_popped_section(body_end.clone(), &orelse_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(orelse_parent, &mut orelse_section);
+            self.visit_bloc(session, &[1], &try_stmt.orelse)?;
+            path_ends.insert(0, scope.borrow().as_symbol_mgr().get_last_index());
+        } else {
+            path_ends.insert(0, body_end);
+        }
+        //the statement is only left normally by the end of the body (or the else) or by a handler
+        let join = SectionIndex::OR(path_ends);
+        let next_parent = if let Some(first) = try_stmt.finalbody.first() {
+            let mut finally_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(first.range());
+            let finally_parent = PythonArchBuilder::_resolve_popped_section(join, &finally_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(finally_parent, &mut finally_section);
+            self.visit_bloc(session, &[2], &try_stmt.finalbody)?;
+            scope.borrow().as_symbol_mgr().get_last_index()
+        } else {
+            join
+        };
+        let mut next_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(try_stmt.range.end(), try_stmt.range.end()));
+        let next_parent = PythonArchBuilder::_resolve_popped_section(next_parent, &next_section);
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
+        Ok(())
+    }
+
+    /* Like a for loop, the body can be executed zero or several times. The walruses of the test, declared before the
+    loop, are bound before the body and after the loop: while (chunk := f.read(1024)): */
+    fn visit_while(&mut self, session: &mut SessionInfo, while_stmt: &StmtWhile) -> Result<(), Error> {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let pre_loop = scope.borrow().as_symbol_mgr().get_last_index();
+        let mut body_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(while_stmt.test.range());
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(pre_loop.clone(), &mut body_section);
+        self.visit_bloc(session, &[0], &while_stmt.body)?;
+        let body_end = scope.borrow().as_symbol_mgr().get_last_index();
+        let next_parent = if !while_stmt.orelse.is_empty() {
+            let mut orelse_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(while_stmt.orelse[0].range());
+            let orelse_parent = PythonArchBuilder::_resolve_popped_section(SectionIndex::OR(vec![pre_loop.clone(), body_end.clone()]), &orelse_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(orelse_parent, &mut orelse_section);
+            self.visit_bloc(session, &[1], &while_stmt.orelse)?;
+            let orelse_end = scope.borrow().as_symbol_mgr().get_last_index();
+            if PythonArchBuilder::_contains_break(&while_stmt.body) {
+                SectionIndex::OR(vec![orelse_end, body_end])
+            } else {
+                orelse_end
+            }
+        } else {
+            SectionIndex::OR(vec![pre_loop, body_end])
+        };
+        let mut next_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(while_stmt.range.end(), while_stmt.range.end()));
+        let next_parent = PythonArchBuilder::_resolve_popped_section(next_parent, &next_section);
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
+        Ok(())
+    }
+
+    /* The cases are tried in order: each case is reached when the previous ones don't match, and binds the names captured
+    by its pattern. The guards are not evaluated */
+    fn visit_match(&mut self, session: &mut SessionInfo, match_stmt: &StmtMatch) -> Result<(), Error> {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let pre_match = scope.borrow().as_symbol_mgr().get_last_index();
+        let mut case_ends = vec![];
+        for (index, case) in match_stmt.cases.iter().enumerate() {
+            let mut case_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(case.range.start(), case.range.start()));
+            let case_parent = PythonArchBuilder::_resolve_popped_section(pre_match.clone(), &case_section);
+            scope.borrow_mut().as_mut_symbol_mgr().change_parent(case_parent, &mut case_section);
+            for name in AstUtils::find_pattern_captures(&case.pattern) {
+                self._add_new_variable(session, &name.id.to_string(), &name.range);
+            }
+            self.visit_bloc(session, &[index as u16], &case.body)?;
+            case_ends.push(scope.borrow().as_symbol_mgr().get_last_index());
+        }
+        //no case is executed if the subject matches none of them, unless the last one is a wildcard or a capture
+        let irrefutable = match_stmt.cases.last().is_some_and(|case| case.guard.is_none()
+            && matches!(&case.pattern, Pattern::MatchAs(pattern) if pattern.pattern.is_none()));
+        if !irrefutable {
+            case_ends.push(pre_match);
+        }
+        let mut next_section = scope.borrow_mut().as_mut_symbol_mgr().add_section(TextRange::new(match_stmt.range.end(), match_stmt.range.end()));
+        let next_parent = PythonArchBuilder::_resolve_popped_section(SectionIndex::OR(case_ends), &next_section);
+        scope.borrow_mut().as_mut_symbol_mgr().change_parent(next_parent, &mut next_section);
         Ok(())
     }
 
     fn visit_with(&mut self, session: &mut SessionInfo, with_stmt: &StmtWith) -> Result<(), Error> {
         for item in with_stmt.items.iter() {
             if let Some(var) = item.optional_vars.as_ref() {
-                match &**var {
-                    Expr::Name(expr_name) => {
-                        self.sym_stack.last().unwrap().borrow_mut().add_new_variable(
-                            session, &expr_name.id.to_string(), &var.range());
-                    },
-                    Expr::Tuple(_) => {continue;},
-                    Expr::List(_) => {continue;},
-                    _ => {continue;}
+                //with open(a) as f, lock() as (b, c): f, b and c are bound
+                for assign in python_utils::unpack_assign(&vec![*var.clone()], None, None) {
+                    self._add_new_variable(session, &assign.target.id.to_string(), &assign.target.range);
                 }
             }
         }
@@ -29,7 +29,13 @@ impl PythonArchBuilderHooks {
                         }
                         sym.add_new_variable(session, &S!("env"), &range);
                     }
-                    
+                    // ----------- attributes set by MetaModel and the registry ------------
+                    for name in ["_module", "_original_module", "pool"] {
+                        if sym.get_symbol(&(vec![], vec![S!(name)]), u32::MAX).is_empty() {
+                            let range = sym.range().clone();
+                            sym.add_new_variable(session, &S!(name), &range);
+                        }
+                    }
                 }
             },
             "Environment" => {
@@ -1,32 +1,45 @@
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::{u32, vec};
 
-use ruff_text_size::{Ranged, TextRange};
-use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtReturn, StmtTry, StmtWith};
-use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use ruff_python_ast::{Alias, Expr, ExprBinOp, ExprLambda, ExprList, ExprName, ExprNamed, ExprNoneLiteral, ExprTuple, Identifier, Operator, Parameters, Stmt, StmtAnnAssign, StmtAssign, StmtAugAssign, StmtClassDef, StmtFor, StmtFunctionDef, StmtIf, StmtReturn, StmtTry, StmtTypeAlias, StmtWith};
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Position, Range};
+use serde_json::json;
 use tracing::{debug, trace};
 use weak_table::traits::WeakElement;
+use weak_table::PtrWeakHashSet;
 use std::path::PathBuf;
 
 use crate::constants::*;
-use crate::core::import_resolver::resolve_import_stmt;
+use crate::core::arch_cache::ArchCache;
+use crate::core::argument_types::{ArgumentTypes, ParameterType};
+use crate::core::import_resolver::{diagnose_unresolved_import, resolve_import_stmt, UnresolvedImport, UnresolvedImportKind};
+use crate::core::container_mutations::{ContainerMutation, ContainerMutations};
+use crate::core::dataclasses::Dataclasses;
+use crate::core::external_dependencies::ExternalDependencies;
+use crate::core::field_consistency::FieldDeclaration;
 use crate::core::odoo::SyncOdoo;
 use crate::core::symbols::symbol::Symbol;
-use crate::core::evaluation::Evaluation;
+use crate::core::injected_attributes::InjectedAttributes;
+use crate::core::loop_items::{LoopItem, LoopItems};
+use crate::core::evaluation::{Evaluation, EvaluationValue};
+use crate::core::narrowing::{NarrowedBranch, NarrowingKind, Narrowings};
 use crate::core::python_utils;
+use crate::core::tuple_unpacking::TupleUnpacking;
 use crate::features::ast_utils::AstUtils;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use crate::S;
 
+use super::api_decorators::ApiDecorators;
 use super::config::DiagMissingImportsMode;
-use super::evaluation::{ContextValue, EvaluationSymbolWeak};
+use super::evaluation::{ContextValue, EvaluationSymbol, EvaluationSymbolWeak};
 use super::file_mgr::FileMgr;
 use super::import_resolver::ImportResult;
 use super::python_arch_eval_hooks::PythonArchEvalHooks;
-use super::symbols::function_symbol::FunctionSymbol;
+use super::symbols::function_symbol::{FunctionSymbol, TypeGuard};
 
 
 #[derive(Debug, Clone)]
@@ -38,6 +51,7 @@ pub struct PythonArchEval {
     diagnostics: Vec<Diagnostic>,
     safe_import: Vec<bool>,
     ast_indexes: Vec<u16>,
+    deferred_annotations: bool, //the file imports annotations from __future__: its annotations are evaluated with the whole scope
 }
 
 impl PythonArchEval {
@@ -50,6 +64,7 @@ impl PythonArchEval {
             diagnostics: Vec::new(),
             safe_import: vec![false],
             ast_indexes: vec![],
+            deferred_annotations: false, //dummy, evaluated in eval_arch
         }
     }
 
@@ -72,6 +87,7 @@ impl PythonArchEval {
         }
         trace!("evaluating {} - {}", self.file.borrow().paths().first().unwrap_or(&S!("No path found")), symbol.borrow().name());
         symbol.borrow_mut().set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::IN_PROGRESS);
+        session.sync_odoo.evaluation_cache.start_build(Rc::as_ptr(&self.file) as usize);
         if self.file.borrow().paths().len() != 1 {
             panic!("Trying to eval_arch a symbol without any path")
         }
@@ -86,7 +102,9 @@ impl PythonArchEval {
         };
         let file_info_rc = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("File not found in cache").clone();
         let file_info = (*file_info_rc).borrow();
+        let mut cancelled = false;
         if file_info.ast.is_some() {
+            self.deferred_annotations = python_utils::has_deferred_annotations(file_info.ast.as_ref().unwrap());
             let ast = match self.file_mode {
                 true => {file_info.ast.as_ref().unwrap()},
                 false => {
@@ -94,6 +112,10 @@ impl PythonArchEval {
                 }
             };
             for (index, stmt) in ast.iter().enumerate() {
+                if self.file_mode && session.is_cancelled() {
+                    cancelled = true;
+                    break;
+                }
                 self.ast_indexes.push(index as u16);
                 self.visit_stmt(session, stmt);
                 self.ast_indexes.pop();
@@ -105,16 +127,27 @@ impl PythonArchEval {
             }
         }
         drop(file_info);
+        if cancelled {
+            //the evaluations already done are dropped by building the file again from the start
+            session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.file) as usize);
+            symbol.borrow_mut().set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::PENDING);
+            session.sync_odoo.add_to_rebuild_arch(symbol);
+            return;
+        }
         if self.file_mode {
             file_info_rc.borrow_mut().replace_diagnostics(BuildSteps::ARCH_EVAL, self.diagnostics.clone());
-            PythonArchEvalHooks::on_file_eval(session.sync_odoo, self.sym_stack.first().unwrap().clone());
+            PythonArchEvalHooks::on_file_eval(session, self.sym_stack.first().unwrap().clone());
         } else {
             //then Symbol must be a function
             symbol.borrow_mut().as_func_mut().replace_diagnostics(BuildSteps::ARCH_EVAL, self.diagnostics.clone());
             PythonArchEvalHooks::on_function_eval(session.sync_odoo, self.sym_stack.first().unwrap().clone());
         }
+        session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.file) as usize);
         let mut symbol = self.sym_stack.first().unwrap().borrow_mut();
         symbol.set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::DONE);
+        if self.file_mode {
+            symbol.set_arch_eval_epoch(session.sync_odoo.epoch);
+        }
         if symbol.is_external() {
             for sym in symbol.all_symbols() {
                 if sym.borrow().has_ast_indexes() {
@@ -122,6 +155,8 @@ impl PythonArchEval {
                 }
             }
             if self.file_mode {
+                drop(symbol);
+                ArchCache::store(session, &self.file, &path, &file_info_rc);
                 session.sync_odoo.get_file_mgr().borrow_mut().delete_path(session, &path);
             }
         } else {
@@ -133,6 +168,9 @@ impl PythonArchEval {
     }
 
     fn visit_stmt(&mut self, session: &mut SessionInfo, stmt: &Stmt) {
+        for named in AstUtils::find_named_exprs(stmt) {
+            self._visit_named_expr(session, named);
+        }
         match stmt {
             Stmt::Import(import_stmt) => {
                 self.eval_symbols_from_import_stmt(session, None, &import_stmt.names, None, &import_stmt.range)
@@ -152,6 +190,12 @@ impl PythonArchEval {
             Stmt::Assign(assign_stmt) => {
                 self._visit_assign(session, assign_stmt);
             },
+            Stmt::AugAssign(aug_assign_stmt) if ContainerMutations::find(stmt).is_none() => {
+                self._visit_aug_assign(session, aug_assign_stmt);
+            },
+            Stmt::TypeAlias(type_alias_stmt) => {
+                self._visit_type_alias(session, type_alias_stmt);
+            },
             Stmt::If(if_stmt) => {
                 self._visit_if(session, if_stmt);
             },
@@ -164,11 +208,41 @@ impl PythonArchEval {
             Stmt::With(with_stmt) => {
                 self._visit_with(session, with_stmt);
             }
+            Stmt::While(while_stmt) => {
+                for (bloc, body) in [&while_stmt.body, &while_stmt.orelse].into_iter().enumerate() {
+                    self.ast_indexes.push(bloc as u16);
+                    for (index_stmt, stmt) in body.iter().enumerate() {
+                        self.ast_indexes.push(index_stmt as u16);
+                        self.visit_stmt(session, stmt);
+                        self.ast_indexes.pop();
+                    }
+                    self.ast_indexes.pop();
+                }
+            }
+            Stmt::Match(match_stmt) => {
+                for (index, case) in match_stmt.cases.iter().enumerate() {
+                    self.ast_indexes.push(index as u16);
+                    for (index_stmt, stmt) in case.body.iter().enumerate() {
+                        self.ast_indexes.push(index_stmt as u16);
+                        self.visit_stmt(session, stmt);
+                        self.ast_indexes.pop();
+                    }
+                    self.ast_indexes.pop();
+                }
+            }
             Stmt::Return(return_stmt) => {
                 self._visit_return(session, return_stmt);
             }
+            Stmt::Assert(assert_stmt) => {
+                let branch = Narrowings::get_assert_branch(assert_stmt);
+                let block_end = self._get_scope_end();
+                self._eval_narrowings(session, &branch, block_end);
+            }
             _ => {}
         }
+        if let Some(mutation) = ContainerMutations::find(stmt) {
+            self._visit_container_mutation(session, &mutation);
+        }
     }
 
     fn _match_diag_config(&self, odoo: &mut SyncOdoo, symbol: &Rc<RefCell<Symbol>>) -> bool {
@@ -230,27 +304,49 @@ impl PythonArchEval {
             level,
             &mut Some(&mut self.diagnostics));
 
-        for _import_result in import_results.iter() {
+        let external_dependencies = self._get_external_dependencies();
+        for (_import_result, alias) in import_results.iter().zip(name_aliases.iter()) {
             let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&_import_result.name, &_import_result.range);
             let Some(variable) = variable.clone() else {
                 continue;
             };
+            let package = ExternalDependencies::get_imported_package(from_stmt.map(|f| f.as_str()), alias.name.as_str(), level);
+            let declaration = match (package.as_ref(), external_dependencies.as_ref()) {
+                (Some(package), Some(dependencies)) => ExternalDependencies::find_declaration(package, dependencies.iter()).cloned(),
+                _ => None
+            };
             if _import_result.found {
+                if let (Some(package), Some(_)) = (package.as_ref(), external_dependencies.as_ref()) {
+                    if declaration.is_none() && !self.safe_import.last().unwrap() && session.sync_odoo.config.check_external_dependencies && self._is_undeclared_dependency(session, package) {
+                        self.diagnostics.push(Diagnostic::new(
+                            Range::new(Position::new(_import_result.range.start().to_u32(), 0), Position::new(_import_result.range.end().to_u32(), 0)),
+                            Some(DiagnosticSeverity::WARNING),
+                            Some(NumberOrString::String(S!("OLS20023"))),
+                            Some(EXTENSION_NAME.to_string()),
+                            format!("'{}' is a third-party package, but it is not declared in the external_dependencies of the manifest", package),
+                            None,
+                            None,
+                        ));
+                    }
+                }
                 let import_sym_ref = _import_result.symbol.clone();
                 let has_loop = self.check_for_loop_evaluation(session, import_sym_ref, &variable);
                 if !has_loop { //anti-loop. We want to be sure we are not evaluating to the same sym
-                    variable.borrow_mut().set_evaluations(vec![Evaluation::eval_from_symbol(&Rc::downgrade(&_import_result.symbol), None)]);
+                    let evaluation = match _import_result.from_getattr {
+                        true => Evaluation::eval_module_getattr(&_import_result.symbol),
+                        false => Evaluation::eval_from_symbol(&Rc::downgrade(&_import_result.symbol), None)
+                    };
+                    variable.borrow_mut().set_evaluations(vec![evaluation]);
                     let file_of_import_symbol = _import_result.symbol.borrow().get_file();
                     if let Some(import_file) = file_of_import_symbol {
                         let import_file = import_file.upgrade().unwrap();
-                        if !Rc::ptr_eq(&self.file, &import_file) {
-                            self.file.borrow_mut().add_dependency(&mut import_file.borrow_mut(), self.current_step, BuildSteps::ARCH);
-                        }
+                        Symbol::add_dependency_to(&self.file, &import_file, self.current_step, BuildSteps::ARCH);
                     }
-                } else {
+                    self._add_evaluation_chain_dependencies(session, &_import_result.symbol);
+                } else if !self.file.borrow().is_external() {
                     let mut file_tree = [_import_result.file_tree.0.clone(), _import_result.file_tree.1.clone()].concat();
                     file_tree.extend(_import_result.name.split(".").map(str::to_string));
-                    self.file.borrow_mut().not_found_paths_mut().push((self.current_step, file_tree.clone()));
+                    self.file.borrow_mut().add_not_found_path(self.current_step, file_tree.clone());
                     session.sync_odoo.not_found_symbols.insert(self.file.clone());
                     if self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
                         self.diagnostics.push(Diagnostic::new(
@@ -271,25 +367,62 @@ impl PythonArchEval {
                 if BUILT_IN_LIBS.contains(&file_tree[0].as_str()) {
                     continue;
                 }
+                //In external packages, failed imports are usually optional backends. The variable stays without evaluation
+                //(Any), and is not registered to be rebuilt, as it would never be resolved
+                if self.file.borrow().is_external() {
+                    continue;
+                }
                 if !self.safe_import.last().unwrap() {
-                    self.file.borrow_mut().not_found_paths_mut().push((self.current_step, file_tree.clone()));
+                    self.file.borrow_mut().add_not_found_path(self.current_step, file_tree.clone());
                     session.sync_odoo.not_found_symbols.insert(self.file.clone());
-                    if self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
-                        self.diagnostics.push(Diagnostic::new(
-                            Range::new(Position::new(_import_result.range.start().to_u32(), 0), Position::new(_import_result.range.end().to_u32(), 0)),
-                            Some(DiagnosticSeverity::WARNING),
-                            Some(NumberOrString::String(S!("OLS20001"))),
-                            Some(EXTENSION_NAME.to_string()),
-                            format!("{} not found", file_tree.clone().join(".")),
-                            None,
-                            None,
-                        ));
+                    //the manifest requires the package: the module can't be installed in this interpreter, but its code is right
+                    if let Some(distribution) = declaration.as_ref() {
+                        if session.sync_odoo.config.diag_missing_imports != DiagMissingImportsMode::None {
+                            self.diagnostics.push(Diagnostic::new(
+                                Range::new(Position::new(_import_result.range.start().to_u32(), 0), Position::new(_import_result.range.end().to_u32(), 0)),
+                                Some(DiagnosticSeverity::INFORMATION),
+                                Some(NumberOrString::String(S!("OLS10004"))),
+                                Some(EXTENSION_NAME.to_string()),
+                                format!("declared external dependency '{}' is not installed in the configured interpreter", distribution),
+                                None,
+                                None,
+                            ));
+                        }
+                    } else if self._match_diag_config(session.sync_odoo, &_import_result.symbol) {
+                        let mut imported_tree = [_import_result.file_tree.0.clone(), _import_result.file_tree.1.clone()].concat();
+                        imported_tree.extend(alias.name.split(".").map(str::to_string));
+                        let is_from = from_stmt.is_some() || level.is_some_and(|level| level > 0);
+                        let unresolved = diagnose_unresolved_import(&_import_result.symbol, &imported_tree, is_from);
+                        self.diagnostics.push(PythonArchEval::_create_unresolved_import_diagnostic(&unresolved, &imported_tree, &_import_result.range));
                     }
                 }
             }
         }
     }
 
+    /* Return the python external dependencies of the manifest of the module of the file, if it is a module of the workspace */
+    fn _get_external_dependencies(&self) -> Option<Vec<String>> {
+        if self.file.borrow().is_external() {
+            return None;
+        }
+        let module = self.file.borrow().find_module()?;
+        let module = module.borrow();
+        if !module.in_workspace() {
+            return None;
+        }
+        Some(module.as_module_package().external_dependencies.iter().map(|(distribution, _)| distribution.clone()).collect())
+    }
+
+    /* Return true if the package is installed by a third-party distribution, that the module must declare */
+    fn _is_undeclared_dependency(&self, session: &mut SessionInfo, package: &String) -> bool {
+        if ExternalDependencies::is_provided(package) || session.sync_odoo.modules.contains_key(package) {
+            return false;
+        }
+        let root = session.sync_odoo.symbols.as_ref().unwrap().clone();
+        let paths = root.borrow().get_symbol(&(vec![package.clone()], vec![]), u32::MAX).first().map(|symbol| symbol.borrow().paths()).unwrap_or_default();
+        paths.first().is_some_and(|path| ExternalDependencies::is_third_party_path(path, &session.sync_odoo.stubs_dirs))
+    }
+
     fn _visit_ann_assign(&mut self, session: &mut SessionInfo, ann_assign_stmt: &StmtAnnAssign) {
         let assigns = match ann_assign_stmt.value.as_ref() {
             Some(value) => python_utils::unpack_assign(&vec![*ann_assign_stmt.target.clone()], Some(&ann_assign_stmt.annotation), Some(value)),
@@ -299,8 +432,14 @@ impl PythonArchEval {
             let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&assign.target.id.to_string(), &assign.target.range);
             if let Some(variable_rc) = variable {
                 let parent = variable_rc.borrow().parent().unwrap().upgrade().unwrap().clone();
-                if assign.annotation.is_some() {
-                    let (eval, diags) = Evaluation::eval_from_ast(session, &assign.annotation.as_ref().unwrap(), parent, &ann_assign_stmt.range.start());
+                let is_type_alias = variable_rc.borrow().as_variable().is_explicit_type_alias;
+                if is_type_alias && assign.value.is_some() {
+                    //the variable annotated with TypeAlias is evaluated to its value, the aliased type
+                    let (eval, diags) = Evaluation::eval_type_alias(session, &assign.value.as_ref().unwrap(), parent, &ann_assign_stmt.range.start());
+                    variable_rc.borrow_mut().set_evaluations(eval);
+                    self.diagnostics.extend(diags);
+                } else if assign.annotation.is_some() {
+                    let (eval, diags) = self._eval_annotation(session, &assign.annotation.as_ref().unwrap(), parent, &ann_assign_stmt.range.start());
                     variable_rc.borrow_mut().set_evaluations(eval);
                     self.diagnostics.extend(diags);
                 } else if assign.value.is_some() {
@@ -310,67 +449,114 @@ impl PythonArchEval {
                 } else {
                     panic!("either value or annotation should exists");
                 }
-                let mut dep_to_add = vec![];
-                let v_mut = variable_rc.borrow_mut();
-                for evaluation in v_mut.evaluations().unwrap().iter() {
-                    if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).weak.upgrade() {
-                        if let Some(file) = sym.borrow().get_file().clone() {
-                            let sym_file = file.upgrade().unwrap().clone();
-                            if !Rc::ptr_eq(&self.file, &sym_file) {
-                                match Rc::ptr_eq(&variable_rc, &sym_file) {
-                                    true => {
-                                        dep_to_add.push(variable_rc.clone());
-                                    },
-                                    false => {
-                                        dep_to_add.push(sym_file);
-                                    }
-                                };
+                self._add_evaluation_dependencies(session, &variable_rc);
+            } else {
+                debug!("Symbol not found");
+            }
+        }
+    }
+
+    fn _visit_type_alias(&mut self, session: &mut SessionInfo, type_alias_stmt: &StmtTypeAlias) {
+        let Expr::Name(name) = &*type_alias_stmt.name else {
+            return;
+        };
+        let Some(variable_rc) = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&name.id.to_string(), &name.range) else {
+            debug!("Symbol not found");
+            return;
+        };
+        let parent = self.sym_stack.last().unwrap().clone();
+        let (eval, diags) = Evaluation::eval_type_alias(session, &type_alias_stmt.value, parent, &type_alias_stmt.range.start());
+        variable_rc.borrow_mut().set_evaluations(eval);
+        self.diagnostics.extend(diags);
+        self._add_evaluation_dependencies(session, &variable_rc);
+    }
+
+    /* Evaluate a lambda assigned to a name (see PythonArchBuilder::_add_lambda): its parameters, like the ones of a def, and
+    its returned expression, in the scope of the lambda. A lambda returning another lambda returns an unknown value. */
+    fn _eval_lambda(&mut self, session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, lambda: &ExprLambda) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        if let Some(parameters) = &lambda.parameters {
+            let in_class = scope.borrow().typ() == SymType::CLASS;
+            for (index, arg) in parameters.posonlyargs.iter().chain(parameters.args.iter()).enumerate() {
+                let Some(param) = function.borrow().get_positioned_symbol(&arg.parameter.name.to_string(), &arg.parameter.name.range) else {
+                    continue;
+                };
+                //like for a method, the first parameter of a lambda stored in a class body is the instance
+                if index == 0 && in_class {
+                    param.borrow_mut().set_evaluations(vec![Evaluation::eval_from_symbol(&Rc::downgrade(&scope), Some(true))]);
+                } else if let Some(default) = &arg.default {
+                    let (eval, diags) = Evaluation::eval_from_ast(session, default, scope.clone(), &lambda.range.start());
+                    param.borrow_mut().set_evaluations(eval);
+                    self.diagnostics.extend(diags);
+                }
+            }
+            self._store_parameter_defaults(session, parameters, &lambda.range.start(), function);
+        }
+        let (eval, diags) = Evaluation::eval_from_ast(session, &lambda.body, function.clone(), &lambda.body.range().start());
+        function.borrow_mut().set_evaluations(eval);
+        self.diagnostics.extend(diags);
+        self._add_evaluation_dependencies(session, function);
+    }
+
+    /* Add the dependencies of the file to the files of the symbols a variable, or a lambda, is evaluated to */
+    fn _add_evaluation_dependencies(&mut self, session: &mut SessionInfo, variable_rc: &Rc<RefCell<Symbol>>) {
+        let mut dep_to_add = vec![];
+        //the variable is not borrowed while its evaluations are followed, as they can lead to the variable itself
+        let evaluations = variable_rc.borrow().evaluations().cloned().unwrap_or_default();
+        for evaluation in evaluations.iter() {
+            if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).weak.upgrade() {
+                if let Some(file) = sym.borrow().get_file().clone() {
+                    let sym_file = file.upgrade().unwrap().clone();
+                    if !Rc::ptr_eq(&self.file, &sym_file) {
+                        match Rc::ptr_eq(variable_rc, &sym_file) {
+                            true => {
+                                dep_to_add.push(variable_rc.clone());
+                            },
+                            false => {
+                                dep_to_add.push(sym_file);
                             }
-                        }
+                        };
                     }
                 }
-                drop(v_mut);
-                for dep in dep_to_add {
-                    self.file.borrow_mut().add_dependency(&mut dep.borrow_mut(), self.current_step, BuildSteps::ARCH);
-                }
-            } else {
-                debug!("Symbol not found");
             }
         }
+        for dep in dep_to_add {
+            Symbol::add_dependency_to(&self.file, &dep, self.current_step, BuildSteps::ARCH);
+        }
     }
 
     fn _visit_assign(&mut self, session: &mut SessionInfo, assign_stmt: &StmtAssign) {
         let assigns = python_utils::unpack_assign(&assign_stmt.targets, None, Some(&assign_stmt.value));
+        //elements of the unpacked values, by range of the value
+        let mut unpacked: HashMap<TextRange, Option<Vec<Vec<Evaluation>>>> = HashMap::new();
+        self._check_unpacking_arity(session, assign_stmt, &mut unpacked);
         for assign in assigns.iter() {
             let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&assign.target.id.to_string(), &assign.target.range);
             if let Some(variable_rc) = variable {
-                let parent = variable_rc.borrow().parent().as_ref().unwrap().upgrade().unwrap().clone();
-                let (eval, diags) = Evaluation::eval_from_ast(session, &assign.value.as_ref().unwrap(), parent, &assign_stmt.range.start());
-                variable_rc.borrow_mut().set_evaluations(eval);
-                self.diagnostics.extend(diags);
-                let mut dep_to_add = vec![];
-                let v_mut = variable_rc.borrow_mut();
-                for evaluation in v_mut.evaluations().unwrap().iter() {
-                    if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).weak.upgrade() {
-                        if let Some(file) = sym.borrow().get_file().clone() {
-                            let sym_file = file.upgrade().unwrap().clone();
-                            if !Rc::ptr_eq(&self.file, &sym_file) {
-                                match Rc::ptr_eq(&variable_rc, &sym_file) {
-                                    true => {
-                                        dep_to_add.push(variable_rc.clone());
-                                    },
-                                    false => {
-                                        dep_to_add.push(sym_file);
-                                    }
-                                };
-                            }
-                        }
+                if variable_rc.borrow().typ() == SymType::FUNCTION {
+                    if let Some(Expr::Lambda(lambda)) = &assign.value {
+                        self._eval_lambda(session, &variable_rc, lambda);
                     }
+                    continue;
                 }
-                drop(v_mut);
-                for dep in dep_to_add {
-                    self.file.borrow_mut().add_dependency(&mut dep.borrow_mut(), self.current_step, BuildSteps::ARCH);
-                }
+                //the value is evaluated in the current scope, even for a variable of the file assigned after a global statement
+                let parent = self.sym_stack.last().unwrap().clone();
+                let value = assign.value.as_ref().unwrap();
+                let (eval, diags) = match assign.index {
+                    Some(index) => {
+                        let elements = unpacked.entry(value.range())
+                            .or_insert_with(|| TupleUnpacking::eval_elements(session, value, parent.clone(), &assign_stmt.range.start()));
+                        let targets = TupleUnpacking::get_unpacking_target(&assign_stmt.targets, &assign.target.range).unwrap_or_default();
+                        let eval = elements.as_ref()
+                            .and_then(|elements| elements.get(TupleUnpacking::get_element_index(targets, index, elements.len())?))
+                            .cloned().unwrap_or_default();
+                        (eval, vec![])
+                    },
+                    None => Evaluation::eval_from_ast(session, value, parent, &assign_stmt.range.start())
+                };
+                variable_rc.borrow_mut().set_evaluations(eval);
+                self.diagnostics.extend(diags);
+                self._add_evaluation_dependencies(session, &variable_rc);
 
             } else {
                 debug!("Symbol not found");
@@ -378,9 +564,85 @@ impl PythonArchEval {
         }
     }
 
+    /* Report the tuple and list targets that can't be unpacked from the value, when its length is known */
+    fn _check_unpacking_arity(&mut self, session: &mut SessionInfo, assign_stmt: &StmtAssign, unpacked: &mut HashMap<TextRange, Option<Vec<Vec<Evaluation>>>>) {
+        for target in assign_stmt.targets.iter() {
+            let (Expr::Tuple(ExprTuple { elts, .. }) | Expr::List(ExprList { elts, .. })) = target else {
+                continue;
+            };
+            let length = match TupleUnpacking::get_literal_length(&assign_stmt.value) {
+                Some(length) => Some(length),
+                None => {
+                    let scope = self.sym_stack.last().unwrap().clone();
+                    unpacked.entry(assign_stmt.value.range())
+                        .or_insert_with(|| TupleUnpacking::eval_elements(session, &assign_stmt.value, scope, &assign_stmt.range.start()))
+                        .as_ref().map(|elements| elements.len())
+                }
+            };
+            if let Some(message) = length.and_then(|length| TupleUnpacking::check_arity(elts, length)) {
+                self.diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(target.range().start().to_u32(), 0), Position::new(assign_stmt.value.range().end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::INFORMATION),
+                    Some(NumberOrString::String(S!("OLS10007"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    message,
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    /* Build the diagnostic of an import that can't be resolved, with a code per kind of failure. The related information
+    gives the closest package that is found, and the data the paths where the module was searched, and the suggested
+    name if any */
+    fn _create_unresolved_import_diagnostic(unresolved: &UnresolvedImport, tree: &[String], range: &TextRange) -> Diagnostic {
+        let closest_tree = unresolved.closest.borrow().get_tree().0;
+        let (code, mut message) = match unresolved.kind {
+            UnresolvedImportKind::MODULE_NOT_FOUND => ("OLS20001", format!("{} not found", tree.join("."))),
+            UnresolvedImportKind::NAME_NOT_FOUND => ("OLS20030", format!("{} not found in {}", unresolved.missing, closest_tree.join("."))),
+            UnresolvedImportKind::CASE_MISMATCH => ("OLS20031", format!("{} not found: {} differs by its case",
+                tree.join("."), unresolved.case_mismatch.as_ref().unwrap())),
+        };
+        if let Some(suggestion) = unresolved.suggestion.as_ref() {
+            message += &format!(". Did you mean {}?", suggestion);
+        }
+        let mut diagnostic = Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::WARNING),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        );
+        let closest = unresolved.closest.borrow();
+        let closest_path = match closest.typ() {
+            SymType::PACKAGE(_) => Some(PathBuf::from(&closest.paths()[0]).join(format!("__init__.py{}", closest.as_package().i_ext())).sanitize()),
+            SymType::FILE | SymType::NAMESPACE => closest.paths().first().cloned(),
+            _ => None
+        };
+        if let Some(closest_path) = closest_path {
+            diagnostic.related_information = Some(vec![DiagnosticRelatedInformation {
+                location: Location {
+                    uri: FileMgr::pathname2uri(&closest_path),
+                    range: Range::default(),
+                },
+                message: format!("{} is found here", closest_tree.join(".")),
+            }]);
+        }
+        diagnostic.data = Some(json!({
+            "missing": unresolved.missing,
+            "closest": closest_tree.join("."),
+            "attempted_paths": unresolved.attempted_paths,
+            "suggestion": unresolved.suggestion,
+        }));
+        diagnostic
+    }
+
     fn create_diagnostic_base_not_found(&mut self, session: &mut SessionInfo, file: &mut Symbol, tree_not_found: &Tree, range: &TextRange) {
         let tree = flatten_tree(tree_not_found);
-        file.not_found_paths_mut().push((BuildSteps::ARCH_EVAL, tree.clone()));
+        file.add_not_found_path(BuildSteps::ARCH_EVAL, tree.clone());
         session.sync_odoo.not_found_symbols.insert(file.get_rc().unwrap());
         self.diagnostics.push(Diagnostic::new(
             Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
@@ -449,9 +711,7 @@ impl PythonArchEval {
                     }
                 } else {
                     let file_symbol = symbol.borrow().get_file().unwrap().upgrade().unwrap();
-                    if !Rc::ptr_eq(&self.file, &file_symbol) {
-                        self.file.borrow_mut().add_dependency(&mut file_symbol.borrow_mut(), self.current_step, BuildSteps::ARCH);
-                    }
+                    Symbol::add_dependency_to(&self.file, &file_symbol, self.current_step, BuildSteps::ARCH);
                     loc_sym.borrow_mut().as_class_sym_mut().bases.push(Rc::downgrade(&symbol));
                 }
             }
@@ -466,13 +726,134 @@ impl PythonArchEval {
         variable.as_ref().unwrap().borrow_mut().ast_indexes_mut().clear();
         variable.as_ref().unwrap().borrow_mut().ast_indexes_mut().extend(self.ast_indexes.iter());
         self.load_base_classes(session, variable.as_ref().unwrap(), class_stmt);
-        self.sym_stack.push(variable.unwrap().clone());
+        let variable = variable.unwrap();
+        self.sym_stack.push(variable.clone());
         for (index, stmt) in class_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
             self.visit_stmt(session, stmt);
             self.ast_indexes.pop();
         }
         self.sym_stack.pop();
+        self._eval_injected_attributes(session, &variable, class_stmt);
+        self._eval_dataclass_init(session, &variable);
+        self._eval_model_fields(session, &variable, class_stmt);
+    }
+
+    /* Evaluate the fields declared by a model class (a class with _name or _inherit) to the value they give on a record
+    first: a str for a Char, an instance of the comodel for a Many2one. The field class stays as the second evaluation,
+    for the accesses through the class. The related fields and the comodels not given by a string keep only the field */
+    fn _eval_model_fields(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_stmt: &StmtClassDef) {
+        if ["_name", "_inherit"].iter().all(|name| class.borrow().get_content_symbol(name, u32::MAX).is_empty()) {
+            return;
+        }
+        let module = self.file.borrow().find_module();
+        for stmt in class_stmt.body.iter() {
+            let Some(field) = FieldDeclaration::from_stmt(stmt) else {
+                continue;
+            };
+            if field.related.is_some() {
+                continue;
+            }
+            let Some(variable) = class.borrow().get_positioned_symbol(&field.name, &field.range) else {
+                continue;
+            };
+            let mut evaluations = PythonArchEvalHooks::eval_field_value(session, &field, &variable, module.as_ref());
+            if evaluations.is_empty() {
+                continue;
+            }
+            //the comodel is only known here when the file is evaluated again, the validation adds the dependency otherwise
+            if let Some(model) = field.comodel.as_ref().and_then(|comodel| session.sync_odoo.models.get(comodel).cloned()) {
+                self.file.borrow_mut().add_model_dependencies(&model);
+            }
+            evaluations.extend(variable.borrow().evaluations().cloned().unwrap_or_default());
+            variable.borrow_mut().set_evaluations(evaluations);
+        }
+    }
+
+    /* Set the arguments of the __init__ generated for a dataclass or a NamedTuple (see PythonArchBuilder::_add_dataclass_init),
+    now that the bases of the class are loaded */
+    fn _eval_dataclass_init(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>) {
+        let init = class.borrow().get_content_symbol("__init__", u32::MAX).into_iter()
+            .find(|init| init.borrow().typ() == SymType::FUNCTION && init.borrow().as_func().is_synthesized);
+        if let Some(init) = init {
+            Dataclasses::build_init(session, class, &init);
+        }
+    }
+
+    /* Evaluate the attributes that __init_subclass__ sets on the subclasses and the fixtures of the class, and add the attributes that the metaclass
+    of the class sets on it. The subclasses get them by inheritance, so a metaclass given to a base class is handled too. */
+    fn _eval_injected_attributes(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, class_stmt: &StmtClassDef) {
+        for stmt in class_stmt.body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            if func.name.as_str() != "__init_subclass__" && !InjectedAttributes::is_fixture_method(func) {
+                continue;
+            }
+            let Some(func_sym) = class.borrow().get_positioned_symbol(&func.name.to_string(), &func.range) else {
+                continue;
+            };
+            for attribute in InjectedAttributes::find(func).iter() {
+                let (Some(value), Some(attr_sym)) = (attribute.value, class.borrow().get_positioned_symbol(&attribute.name, &attribute.range)) else {
+                    continue;
+                };
+                let (eval, diags) = Evaluation::eval_from_ast(session, value, func_sym.clone(), &value.range().start());
+                attr_sym.borrow_mut().set_evaluations(eval);
+                self.diagnostics.extend(diags);
+            }
+        }
+        let Some(metaclass) = class_stmt.keywords().iter().find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == "metaclass")) else {
+            return;
+        };
+        let (eval, diags) = Evaluation::eval_from_ast(session, &metaclass.value, self.sym_stack.last().unwrap().clone(), &class_stmt.range().start());
+        self.diagnostics.extend(diags);
+        if eval.len() != 1 {
+            return;
+        }
+        let eval_symbol = eval[0].symbol.get_symbol(session, &mut None, &mut vec![], None);
+        let ref_sym = Symbol::follow_ref(&eval_symbol, session, &mut None, false, false, None, &mut vec![]);
+        let Some(metaclass) = ref_sym.first().and_then(|s| s.weak.upgrade()) else {
+            return;
+        };
+        if metaclass.borrow().typ() != SymType::CLASS {
+            return;
+        }
+        let file_symbol = metaclass.borrow().get_file().unwrap().upgrade().unwrap();
+        Symbol::add_dependency_to(&self.file, &file_symbol, self.current_step, BuildSteps::ARCH);
+        let attributes = metaclass.borrow().as_class_sym().metaclass_attributes.clone();
+        for name in attributes.iter() {
+            if class.borrow().get_content_symbol(name, u32::MAX).is_empty() {
+                class.borrow_mut().add_new_variable(session, name, &class_stmt.name.range);
+            }
+        }
+    }
+
+    /* Add a dependency to the files of the variables that are followed to evaluate an imported variable (config,
+    imported in odoo.tools from odoo.tools.config), so that a change in the middle of the chain invalidates this file */
+    fn _add_evaluation_chain_dependencies(&mut self, session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
+        let max_depth = session.sync_odoo.config.evaluation_depth;
+        let mut followed: PtrWeakHashSet<Weak<RefCell<Symbol>>> = PtrWeakHashSet::new();
+        let mut to_follow = vec![(symbol.clone(), 0)];
+        while let Some((sym, depth)) = to_follow.pop() {
+            if depth >= max_depth || followed.contains(&sym) || sym.borrow().typ() != SymType::VARIABLE {
+                continue;
+            }
+            followed.insert(sym.clone());
+            if !sym.borrow().is_external() {
+                Symbol::eval_file_of_variable(session, &sym);
+            }
+            let next_refs = Symbol::next_refs(session, &sym.borrow(), &mut vec![]);
+            for next_ref in next_refs.iter() {
+                let Some(next_sym) = next_ref.weak.upgrade() else {
+                    continue;
+                };
+                let file = next_sym.borrow().get_file().and_then(|f| f.upgrade());
+                if let Some(file) = file {
+                    Symbol::add_dependency_to(&self.file, &file, self.current_step, BuildSteps::ARCH);
+                }
+                to_follow.push((next_sym, depth + 1));
+            }
+        }
     }
 
     fn visit_func_def(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef) {
@@ -483,6 +864,8 @@ impl PythonArchEval {
         let variable = variable.unwrap();
         variable.borrow_mut().ast_indexes_mut().clear();
         variable.borrow_mut().ast_indexes_mut().extend(self.ast_indexes.iter());
+        //the decorators can make the function static or a classmethod, which changes the evaluation of its first parameter
+        self._eval_decorators(session, func_stmt, &variable);
         {
             if variable.borrow_mut().as_func_mut().can_be_in_class() || !(self.sym_stack.last().unwrap().borrow().typ() == SymType::CLASS){
                 let mut is_first = true;
@@ -497,7 +880,7 @@ impl PythonArchEval {
                     }
                     is_first = false;
                     if arg.parameter.annotation.is_some() {
-                        let (eval, diags) = Evaluation::eval_from_ast(session,
+                        let (eval, diags) = self._eval_annotation(session,
                                                     &arg.parameter.annotation.as_ref().unwrap(),
                                                     self.sym_stack.last().unwrap().clone(),
                                                     &func_stmt.range.start());
@@ -528,6 +911,14 @@ impl PythonArchEval {
                 ))
             }
         }
+        self._store_parameter_annotations(session, func_stmt, &variable);
+        self._store_parameter_defaults(session, &func_stmt.parameters, &func_stmt.range.start(), &variable);
+        self._store_type_guard(session, func_stmt, &variable);
+        if !session.sync_odoo.plugin_rules.is_empty() {
+            self._register_plugin_decorators(session, func_stmt, &variable);
+        }
+        self._eval_api_returns(session, func_stmt, &variable);
+        self._eval_annotated_returns(session, func_stmt, &variable);
         if !self.file_mode || variable.borrow().get_in_parents(&vec![SymType::CLASS], true).is_none() {
             variable.borrow_mut().as_func_mut().arch_eval_status = BuildStatus::IN_PROGRESS;
             self.sym_stack.push(variable.clone());
@@ -541,8 +932,246 @@ impl PythonArchEval {
         }
     }
 
+    /* Evaluate the annotation of a parameter or of a variable. A forward reference, or any annotation of a file importing
+    annotations from __future__, is evaluated with the whole scope */
+    fn _eval_annotation(&mut self, session: &mut SessionInfo, annotation: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> (Vec<Evaluation>, Vec<Diagnostic>) {
+        if let Some(parsed) = Evaluation::parse_forward_reference(annotation) {
+            //the ranges of the parsed expression are relative to the string: its diagnostics are not given
+            return (Evaluation::eval_from_ast(session, parsed.expr(), scope, &TextSize::new(u32::MAX)).0, vec![]);
+        }
+        let max_infer = if self.deferred_annotations { TextSize::new(u32::MAX) } else { *max_infer };
+        Evaluation::eval_from_ast(session, annotation, scope, &max_infer)
+    }
+
+    /* Store the types accepted by the annotations of the parameters on the arguments of the function (see ArgumentTypes).
+    The arguments are in the order of the parameters in the signature */
+    fn _store_parameter_annotations(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        let parameters = &func_stmt.parameters;
+        let annotations: Vec<Option<&Expr>> = parameters.posonlyargs.iter().chain(parameters.args.iter()).map(|arg| arg.parameter.annotation.as_deref())
+            .chain(parameters.vararg.iter().map(|arg| arg.annotation.as_deref()))
+            .chain(parameters.kwonlyargs.iter().map(|arg| arg.parameter.annotation.as_deref()))
+            .chain(parameters.kwarg.iter().map(|arg| arg.annotation.as_deref()))
+            .collect();
+        for (index, annotation) in annotations.into_iter().enumerate() {
+            let annotation = annotation.map(|annotation| ArgumentTypes::from_annotation(session, annotation, self.sym_stack.last().unwrap().clone(), &func_stmt.range.start()));
+            if let Some(arg) = function.borrow_mut().as_func_mut().args.get_mut(index) {
+                arg.annotation = annotation;
+            }
+        }
+    }
+
+    /* Store the evaluations of the defaults of the parameters on the arguments of the function. The defaults are evaluated
+    in the scope defining the function, like Python does, and the files of the symbols they use become dependencies of
+    the file. A default that can't be evaluated keeps the placeholder set by the arch step */
+    fn _store_parameter_defaults(&mut self, session: &mut SessionInfo, parameters: &Parameters, max_infer: &TextSize, function: &Rc<RefCell<Symbol>>) {
+        let defaults: Vec<Option<&Expr>> = parameters.posonlyargs.iter().chain(parameters.args.iter()).map(|arg| arg.default.as_deref())
+            .chain(parameters.vararg.iter().map(|_| None))
+            .chain(parameters.kwonlyargs.iter().map(|arg| arg.default.as_deref()))
+            .chain(parameters.kwarg.iter().map(|_| None))
+            .collect();
+        for (index, default) in defaults.into_iter().enumerate() {
+            let Some(default) = default else {
+                continue;
+            };
+            //the diagnostics of the defaults are given by the evaluation of the parameters
+            let (evaluations, _) = Evaluation::eval_from_ast(session, default, self.sym_stack.last().unwrap().clone(), max_infer);
+            let Some(evaluation) = evaluations.into_iter().next() else {
+                continue;
+            };
+            if let Some(sym) = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None).weak.upgrade() {
+                let sym_file = sym.borrow().get_file().and_then(|file| file.upgrade());
+                if let Some(sym_file) = sym_file.filter(|sym_file| !Rc::ptr_eq(&self.file, sym_file)) {
+                    Symbol::add_dependency_to(&self.file, &sym_file, self.current_step, BuildSteps::ARCH);
+                }
+            }
+            if let Some(arg) = function.borrow_mut().as_func_mut().args.get_mut(index) {
+                arg.default_value = Some(evaluation);
+            }
+        }
+    }
+
+    /* Evaluate the decorators of the function, and set its flags from the builtin ones they resolve to, whatever the name
+    they are imported with: @cp with "from functools import cached_property as cp" makes a property. The decorators
+    called with arguments, like @api.depends('name'), are evaluated to the decorator they call */
+    fn _eval_decorators(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        let mut decorators = vec![];
+        for decorator in func_stmt.decorator_list.iter() {
+            let expr = match &decorator.expression {
+                Expr::Call(call) => &*call.func,
+                expr => expr
+            };
+            //the diagnostics of the decorators are given by the validation
+            let (evaluations, _) = Evaluation::eval_from_ast(session, expr, self.sym_stack.last().unwrap().clone(), &func_stmt.range.start());
+            for evaluation in evaluations.iter() {
+                let sym_ref = evaluation.symbol.get_symbol(session, &mut None, &mut self.diagnostics, None);
+                for followed in Symbol::follow_ref(&sym_ref, session, &mut None, false, false, None, &mut self.diagnostics).iter() {
+                    let Some(sym) = followed.weak.upgrade() else {
+                        continue;
+                    };
+                    let sym_file = sym.borrow().get_file().and_then(|file| file.upgrade());
+                    if let Some(sym_file) = sym_file.filter(|sym_file| !Rc::ptr_eq(&self.file, sym_file)) {
+                        Symbol::add_dependency_to(&self.file, &sym_file, self.current_step, BuildSteps::ARCH);
+                    }
+                    let path = flatten_tree(&sym.borrow().get_tree()).join(".");
+                    let mut function = function.borrow_mut();
+                    let function = function.as_func_mut();
+                    match path.as_str() {
+                        "builtins.staticmethod" => function.is_static = true,
+                        "builtins.classmethod" => function.is_class_method = true,
+                        "builtins.property" | "functools.cached_property" => function.is_property = true,
+                        _ => {}
+                    }
+                }
+            }
+            decorators.extend(evaluations);
+        }
+        function.borrow_mut().as_func_mut().decorators = decorators;
+    }
+
+    /* Store the type narrowed by a TypeGuard[X] or TypeIs[X] return annotation. X can be a forward reference given by
+    a string, or a model name ('account.move') */
+    fn _store_type_guard(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        let type_guard = func_stmt.returns.as_deref().and_then(|returns| self._get_type_guard(session, returns, &func_stmt.range.start()));
+        function.borrow_mut().as_func_mut().type_guard = type_guard;
+    }
+
+    fn _get_type_guard(&mut self, session: &mut SessionInfo, returns: &Expr, max_infer: &TextSize) -> Option<TypeGuard> {
+        let Expr::Subscript(subscript) = returns else {
+            return None;
+        };
+        let is_type_is = match &*subscript.value {
+            Expr::Name(name) => name.id.as_str(),
+            Expr::Attribute(attr) => attr.attr.as_str(),
+            _ => return None
+        };
+        let is_type_is = match is_type_is {
+            "TypeGuard" => false,
+            "TypeIs" => true,
+            _ => return None
+        };
+        let scope = self.sym_stack.last().unwrap().clone();
+        //'account.move' is a model name, 'PostedMove' or 'models.PostedMove' a forward reference
+        let parameter_types = ArgumentTypes::from_annotation(session, &subscript.slice, scope, max_infer);
+        let mut evaluations = vec![];
+        for parameter_type in parameter_types.into_iter() {
+            match parameter_type {
+                ParameterType::ANY => return None,
+                ParameterType::NONE => evaluations.push(Evaluation::new_constant(session.sync_odoo, Expr::NoneLiteral(ExprNoneLiteral { range: subscript.slice.range() }), subscript.slice.range())),
+                ParameterType::CLASS(class) => evaluations.push(Evaluation::eval_from_symbol(&class, Some(true))),
+                ParameterType::MODEL(model) => {
+                    //resolved when the guard is used, like the models of api.returns
+                    let mut context = HashMap::from([
+                        (S!("args"), ContextValue::STRING(model)),
+                        (S!("range"), ContextValue::RANGE(subscript.slice.range()))
+                    ]);
+                    if let Some(module) = self.file.borrow().find_module() {
+                        context.insert(S!("module"), ContextValue::MODULE(Rc::downgrade(&module)));
+                    }
+                    evaluations.push(Evaluation {
+                        symbol: EvaluationSymbol::new_with_symbol(Weak::new(), true, context, None, Some(PythonArchEvalHooks::eval_env_get_item)),
+                        value: None,
+                        range: None
+                    });
+                }
+            }
+        }
+        Some(TypeGuard { evaluations, is_type_is })
+    }
+
+    /* Register the function in the registries of the decorator rules of the plugin rules. The function is registered
+    under the name given as first argument to the decorator (@register('name')), or under its own name (@register) */
+    fn _register_plugin_decorators(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        for decorator in func_stmt.decorator_list.iter() {
+            let (decorator_func, name_arg) = match &decorator.expression {
+                Expr::Call(call) => (&*call.func, call.arguments.args.first()),
+                expr => (expr, None)
+            };
+            let (evals, _) = Evaluation::eval_from_ast(session, decorator_func, self.sym_stack.last().unwrap().clone(), &func_stmt.range.start());
+            for eval in evals.iter() {
+                let decorator_sym = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+                let Some(decorator_sym) = decorator_sym.weak.upgrade() else {
+                    continue;
+                };
+                let decorator_path = flatten_tree(&decorator_sym.borrow().get_tree()).join(".");
+                let Some(registry) = session.sync_odoo.plugin_rules.get_registry(&decorator_path).cloned() else {
+                    continue;
+                };
+                let name = match name_arg {
+                    Some(Expr::StringLiteral(s)) => s.value.to_string(),
+                    Some(_) => continue,
+                    None => func_stmt.name.id.to_string()
+                };
+                session.sync_odoo.plugin_rules.register(&registry, name, function);
+            }
+        }
+    }
+
+    /* Give the model of @api.returns as return evaluation of the function: the records of 'self', or of a model that is
+    resolved when the function is called, like env['model'] */
+    fn _eval_api_returns(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        let model = ApiDecorators::find(&func_stmt.decorator_list, "returns")
+            .and_then(|(_, call)| call)
+            .and_then(|call| ApiDecorators::get_returns_model(call));
+        let declared_evaluations = match model {
+            Some(model) if model.value.to_str() == "self" => vec![Evaluation {
+                symbol: EvaluationSymbol::new_self(HashMap::new(), None, None),
+                value: None,
+                range: None
+            }],
+            Some(model) => {
+                let mut context = HashMap::from([
+                    (S!("args"), ContextValue::STRING(model.value.to_string())),
+                    (S!("range"), ContextValue::RANGE(model.range))
+                ]);
+                if let Some(module) = self.file.borrow().find_module() {
+                    context.insert(S!("module"), ContextValue::MODULE(Rc::downgrade(&module)));
+                }
+                vec![Evaluation {
+                    symbol: EvaluationSymbol::new_with_symbol(Weak::new(), true, context, None, Some(PythonArchEvalHooks::eval_env_get_item)),
+                    value: None,
+                    range: None
+                }]
+            },
+            None => vec![]
+        };
+        let mut function = function.borrow_mut();
+        let function = function.as_func_mut();
+        if !declared_evaluations.is_empty() {
+            function.evaluations = declared_evaluations.clone();
+        }
+        function.declared_evaluations = declared_evaluations;
+    }
+
+    /* Give the return annotation as return evaluation of an overload (@overload), as its body is only '...', and of a
+    function whose return annotation is deferred: a forward reference (-> "ResPartner"), or any annotation of a file
+    importing annotations from __future__. The class of such an annotation is often imported under TYPE_CHECKING only,
+    to avoid a circular import, and can't be inferred from the body */
+    fn _eval_annotated_returns(&mut self, session: &mut SessionInfo, func_stmt: &StmtFunctionDef, function: &Rc<RefCell<Symbol>>) {
+        let Some(returns) = func_stmt.returns.as_deref() else {
+            return;
+        };
+        let is_overloaded = function.borrow().as_func().is_overloaded;
+        let is_deferred = self.deferred_annotations || Evaluation::parse_forward_reference(returns).is_some();
+        //the model of api.returns is kept
+        if !is_overloaded && (!is_deferred || !function.borrow().as_func().declared_evaluations.is_empty()) {
+            return;
+        }
+        let max_infer = if is_deferred { TextSize::new(u32::MAX) } else { func_stmt.range.start() };
+        //the diagnostics of the annotation are given by the validation
+        let (declared_evaluations, _) = Evaluation::eval_annotation(session, returns, self.sym_stack.last().unwrap().clone(), &max_infer);
+        if !is_overloaded && declared_evaluations.is_empty() {
+            return;
+        }
+        let mut function = function.borrow_mut();
+        let function = function.as_func_mut();
+        function.evaluations = declared_evaluations.clone();
+        function.declared_evaluations = declared_evaluations;
+    }
+
     fn _visit_if(&mut self, session: &mut SessionInfo, if_stmt: &StmtIf) {
         //TODO eval test (walrus op)
+        let branches = Narrowings::get_branches(if_stmt);
+        self._eval_narrowings(session, &branches[0], if_stmt.body.last().map_or(branches[0].start, |stmt| stmt.end()));
         self.ast_indexes.push(0 as u16);//0 for body
         for (index, stmt) in if_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -552,6 +1181,7 @@ impl PythonArchEval {
         self.ast_indexes.pop();
         for (index, elif_clause) in if_stmt.elif_else_clauses.iter().enumerate() {
             //TODO eval test of else clauses
+            self._eval_narrowings(session, &branches[index + 1], elif_clause.body.last().map_or(branches[index + 1].start, |stmt| stmt.end()));
             self.ast_indexes.push((index+1) as u16);//0 for body, so index + 1
             for (index_stmt, stmt) in elif_clause.body.iter().enumerate() {
                 self.ast_indexes.push(index_stmt as u16);
@@ -560,44 +1190,150 @@ impl PythonArchEval {
             }
             self.ast_indexes.pop();
         }
+        if let Some(guard_clause) = Narrowings::get_guard_clause(if_stmt) {
+            let block_end = self._get_scope_end();
+            self._eval_narrowings(session, &guard_clause, block_end);
+        }
     }
 
-    fn _visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) {
-        let (eval_iter_node, diags) = Evaluation::eval_from_ast(session,
-            &for_stmt.iter,
-            self.sym_stack.last().unwrap().clone(),
-            &for_stmt.target.range().start());
-        self.diagnostics.extend(diags);
-        if eval_iter_node.len() == 1 { //Only handle values that we are sure about
-            let eval = &eval_iter_node[0];
-            let eval_symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
-            if !eval_symbol.weak.is_expired() {
-                let symbol_eval = Symbol::follow_ref(&eval_symbol, session, &mut None, false, false, None, &mut vec![]);
-                if symbol_eval.len() == 1 && symbol_eval[0].weak.upgrade().is_some() {
-                    let symbol_type_rc = symbol_eval[0].weak.upgrade().unwrap();
-                    let symbol_type = symbol_type_rc.borrow();
-                    if symbol_type.typ() == SymType::CLASS {
-                        let (iter, _) = symbol_type.get_member_symbol(session, &S!("__iter__"), None, true, false, false, false);
-                        if iter.len() == 1 {
-                            if iter[0].borrow().evaluations().is_some() && iter[0].borrow().evaluations().unwrap().len() == 1 {
-                                let iter = iter[0].borrow();
-                                let eval_iter = &iter.evaluations().unwrap()[0];
-                                if for_stmt.target.is_name_expr() { //only handle simple variable for now
-                                    let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&for_stmt.target.as_name_expr().unwrap().id.to_string(), &for_stmt.target.range());
-                                    variable.as_ref().unwrap().borrow_mut().evaluations_mut().unwrap().clear();
-                                    let symbol = &eval_iter.symbol.get_symbol(session, &mut Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&symbol_type_rc)))])), &mut vec![], None);
-                                    variable.as_ref().unwrap().borrow_mut().evaluations_mut().unwrap().push(
-                                        Evaluation::eval_from_symbol(
-                                            &symbol.weak,
-                                            symbol.instance
-                                        )
-                                    );
-                                }
-                            }
+    /* Return the end of the current scope. The attribute chains narrowed by an assert or a guard clause stay narrowed
+    until there, as the end of the block that contains them is not known by the visit */
+    fn _get_scope_end(&self) -> TextSize {
+        self.sym_stack.last().unwrap().borrow().range().end()
+    }
+
+    /* Evaluate the variables narrowed in a branch of an if: the literals of an INCLUDE narrowing, or the previous
+    evaluations without the literals of an EXCLUDE one. A truthy variable also loses its falsy literals */
+    fn _eval_narrowings(&mut self, session: &mut SessionInfo, branch: &NarrowedBranch, branch_end: TextSize) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        self._eval_guards(session, branch, branch_end);
+        for narrowing in branch.narrowings.iter() {
+            let name = narrowing.name.id.to_string();
+            let Some(variable) = scope.borrow().get_positioned_symbol(&name, &TextRange::new(branch.start, branch.start)) else {
+                continue; //narrowings are not declared in class bodies
+            };
+            let mut evaluations = vec![];
+            match narrowing.kind {
+                NarrowingKind::INCLUDE => {
+                    for value in narrowing.values.iter().filter(|value| !narrowing.truthy || !Narrowings::is_falsy(value)) {
+                        evaluations.push(Evaluation::new_constant(session.sync_odoo, (*value).clone(), value.range()));
+                    }
+                },
+                NarrowingKind::EXCLUDE => {
+                    for previous in Symbol::infer_name(session.sync_odoo, &scope, &name, Some(branch.start.to_u32())).iter() {
+                        let previous_evals = previous.borrow().evaluations().cloned().unwrap_or_default();
+                        let remaining: Vec<Evaluation> = previous_evals.iter()
+                            .filter(|eval| !matches!(&eval.value, Some(EvaluationValue::CONSTANT(value))
+                                if Narrowings::contains(&narrowing.values, value) || (narrowing.truthy && Narrowings::is_falsy(value))))
+                            .cloned().collect();
+                        if remaining.len() < previous_evals.len() {
+                            evaluations.extend(remaining);
+                        } else {
+                            //nothing to remove: the variable keeps the evaluation of its declaration
+                            evaluations.push(Evaluation::eval_from_symbol(&Rc::downgrade(previous), None));
                         }
                     }
                 }
             }
+            variable.borrow_mut().set_evaluations(evaluations);
+        }
+    }
+
+    /* Evaluate the variables and the attribute chains narrowed by a call to a function returning TypeGuard[X] or
+    TypeIs[X] in a branch of an if. The narrowed attribute chains are stored on the function that contains the if, for
+    the range of the branch. The variables narrowed by a comparison are evaluated by _eval_narrowings */
+    fn _eval_guards(&mut self, session: &mut SessionInfo, branch: &NarrowedBranch, branch_end: TextSize) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        for guard in branch.guards.iter() {
+            let type_guard = self._get_guard_function(session, guard.function);
+            let evaluations = match type_guard {
+                Some(type_guard) if guard.positive => Some(type_guard.evaluations),
+                Some(type_guard) if type_guard.is_type_is => {
+                    let previous = self._get_guarded_evaluations(session, guard.target, branch.start);
+                    previous.map(|previous| self._exclude_guarded_types(session, previous, &type_guard.evaluations))
+                },
+                _ => None
+            };
+            match guard.target {
+                Expr::Name(name) => {
+                    if branch.narrowings.iter().any(|n| n.name.id == name.id) {
+                        continue;
+                    }
+                    let Some(variable) = scope.borrow().get_positioned_symbol(&name.id.to_string(), &TextRange::new(branch.start, branch.start)) else {
+                        continue; //narrowings are not declared in class bodies
+                    };
+                    let evaluations = evaluations.unwrap_or_else(|| Symbol::infer_name(session.sync_odoo, &scope, &name.id.to_string(), Some(branch.start.to_u32())).iter()
+                        .map(|previous| Evaluation::eval_from_symbol(&Rc::downgrade(previous), None))
+                        .collect());
+                    variable.borrow_mut().set_evaluations(evaluations);
+                },
+                target => {
+                    let (Some(evaluations), Some(chain)) = (evaluations, Narrowings::get_attribute_chain(target)) else {
+                        continue;
+                    };
+                    if scope.borrow().typ() != SymType::FUNCTION {
+                        continue;
+                    }
+                    let range = TextRange::new(branch.start, branch_end);
+                    let mut scope = scope.borrow_mut();
+                    let narrowed_attributes = &mut scope.as_func_mut().narrowed_attributes;
+                    narrowed_attributes.retain(|(c, r, _)| *c != chain || *r != range);
+                    narrowed_attributes.push((chain, range, evaluations));
+                }
+            }
+        }
+    }
+
+    /* Return the TypeGuard of the function called by a guard, if it has one */
+    fn _get_guard_function(&mut self, session: &mut SessionInfo, function: &Expr) -> Option<TypeGuard> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, function, self.sym_stack.last().unwrap().clone(), &function.range().start());
+        for evaluation in evaluations.iter() {
+            let weak = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for function in Symbol::follow_ref(&weak, session, &mut None, false, false, None, &mut vec![]).iter() {
+                let Some(function) = function.weak.upgrade() else {
+                    continue;
+                };
+                let function = function.borrow();
+                if function.typ() == SymType::FUNCTION && function.as_func().type_guard.is_some() {
+                    return function.as_func().type_guard.clone();
+                }
+            }
+        }
+        None
+    }
+
+    /* Return the evaluations of the target of a guard before the branch */
+    fn _get_guarded_evaluations(&mut self, session: &mut SessionInfo, target: &Expr, start: TextSize) -> Option<Vec<Evaluation>> {
+        let (evaluations, _) = Evaluation::eval_from_ast(session, target, self.sym_stack.last().unwrap().clone(), &start);
+        if evaluations.is_empty() { None } else { Some(evaluations) }
+    }
+
+    /* Remove the evaluations of the types of a TypeIs from the evaluations of its argument, when it returns False */
+    fn _exclude_guarded_types(&mut self, session: &mut SessionInfo, previous: Vec<Evaluation>, guarded: &Vec<Evaluation>) -> Vec<Evaluation> {
+        let guarded_classes: Vec<Rc<RefCell<Symbol>>> = guarded.iter()
+            .filter_map(|evaluation| evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade())
+            .collect();
+        let mut res = vec![];
+        for evaluation in previous.into_iter() {
+            let weak = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            let is_guarded = Symbol::follow_ref(&weak, session, &mut None, false, false, None, &mut vec![]).iter()
+                .filter_map(|class| class.weak.upgrade())
+                .any(|class| guarded_classes.iter().any(|guarded| Rc::ptr_eq(guarded, &class)));
+            if !is_guarded {
+                res.push(evaluation);
+            }
+        }
+        res
+    }
+
+    /* The targets of a for loop are evaluated to the items of the iterable (see LoopItems). A target whose item can't be
+    found keeps no evaluation */
+    fn _visit_for(&mut self, session: &mut SessionInfo, for_stmt: &StmtFor) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let (_, diags) = Evaluation::eval_from_ast(session, &for_stmt.iter, scope.clone(), &for_stmt.target.range().start());
+        self.diagnostics.extend(diags);
+        if let Some(item) = LoopItems::get_item(session, &for_stmt.iter, for_stmt.is_async, scope, &for_stmt.target.range().start()) {
+            self._assign_loop_item(session, &for_stmt.target, &item);
         }
         self.ast_indexes.push(0 as u16);
         for (index_stmt, stmt) in for_stmt.body.iter().enumerate() {
@@ -606,7 +1342,6 @@ impl PythonArchEval {
             self.ast_indexes.pop();
         }
         self.ast_indexes.pop();
-        //TODO split evaluation
         self.ast_indexes.push(1 as u16);
         for (index_stmt, stmt) in for_stmt.orelse.iter().enumerate() {
             self.ast_indexes.push(index_stmt as u16);
@@ -616,17 +1351,27 @@ impl PythonArchEval {
         self.ast_indexes.pop();
     }
 
-    fn _visit_try(&mut self, session: &mut SessionInfo, try_stmt: &StmtTry) {
-        let mut safe_import = false;
-        for handler in try_stmt.handlers.iter() {
-            let handler = handler.as_except_handler().unwrap();
-            if let Some(type_) = &handler.type_ {
-                if type_.is_name_expr() && type_.as_name_expr().unwrap().id.to_string() == "ImportError" {
-                    safe_import = true;
+    /* Evaluate the names of the target of a for loop to the item, distributed on the elements of a tuple target */
+    fn _assign_loop_item(&mut self, session: &mut SessionInfo, target: &Expr, item: &LoopItem) {
+        match (target, item) {
+            (Expr::Name(name), item) => {
+                let Some(variable) = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&name.id.to_string(), &name.range) else {
+                    return;
+                };
+                let evaluations = item.evaluations(session);
+                variable.borrow_mut().set_evaluations(evaluations);
+            },
+            (Expr::Tuple(ExprTuple { elts, .. }) | Expr::List(ExprList { elts, .. }), LoopItem::TUPLE(elements)) if elts.len() == elements.len() && !elts.iter().any(|elt| elt.is_starred_expr()) => {
+                for (elt, element) in elts.iter().zip(elements.iter()) {
+                    self._assign_loop_item(session, elt, element);
                 }
-            }
+            },
+            _ => {}
         }
-        self.safe_import.push(safe_import);
+    }
+
+    fn _visit_try(&mut self, session: &mut SessionInfo, try_stmt: &StmtTry) {
+        self.safe_import.push(AstUtils::catches_import_error(try_stmt));
         self.ast_indexes.push(0 as u16);
         for (index, stmt) in try_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -635,6 +1380,25 @@ impl PythonArchEval {
         }
         self.ast_indexes.pop();
         self.safe_import.pop();
+        //the handlers are evaluated in the order of the source, as the else and finally bodies can use their variables
+        self.ast_indexes.push(3 as u16);
+        for (handler_iter, handler) in try_stmt.handlers.iter().enumerate() {
+            self.ast_indexes.push(handler_iter as u16);
+            match handler {
+                ruff_python_ast::ExceptHandler::ExceptHandler(h) => {
+                    if let (Some(name), Some(type_)) = (h.name.as_ref(), h.type_.as_ref()) {
+                        self._visit_except_name(session, name, type_);
+                    }
+                    for (index, stmt) in h.body.iter().enumerate() {
+                        self.ast_indexes.push(index as u16);
+                        self.visit_stmt(session, stmt);
+                        self.ast_indexes.pop();
+                    }
+                },
+            }
+            self.ast_indexes.pop();
+        }
+        self.ast_indexes.pop();
         self.ast_indexes.push(1 as u16);
         for (index, stmt) in try_stmt.orelse.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -649,21 +1413,93 @@ impl PythonArchEval {
             self.ast_indexes.pop();
         }
         self.ast_indexes.pop();
-        self.ast_indexes.push(3 as u16);
-        for (handler_iter, handler) in try_stmt.handlers.iter().enumerate() {
-            self.ast_indexes.push(handler_iter as u16);
-            match handler {
-                ruff_python_ast::ExceptHandler::ExceptHandler(h) => {
-                    for (index, stmt) in h.body.iter().enumerate() {
-                        self.ast_indexes.push(index as u16);
-                        self.visit_stmt(session, stmt);
-                        self.ast_indexes.pop();
-                    }
-                },
+    }
+
+    /* 'except ValueError as e' binds e to an instance of the exception. A tuple of exceptions gives an instance of each of them */
+    fn _visit_except_name(&mut self, session: &mut SessionInfo, name: &Identifier, type_: &Expr) {
+        let Some(variable) = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&name.id.to_string(), &name.range) else {
+            return;
+        };
+        let types: Vec<&Expr> = match type_ {
+            Expr::Tuple(t) => t.elts.iter().collect(),
+            type_ => vec![type_]
+        };
+        let mut evals = vec![];
+        for type_ in types.into_iter() {
+            let (eval, diags) = Evaluation::eval_from_ast(session, type_, self.sym_stack.last().unwrap().clone(), &name.range.start());
+            self.diagnostics.extend(diags);
+            for eval in eval.iter() {
+                let symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+                if !symbol.weak.is_expired() {
+                    evals.push(Evaluation::eval_from_symbol(&symbol.weak, Some(true)));
+                }
             }
-            self.ast_indexes.pop();
         }
-        self.ast_indexes.pop();
+        variable.borrow_mut().set_evaluations(evals);
+    }
+
+    fn _visit_named_expr(&mut self, session: &mut SessionInfo, named: &ExprNamed) {
+        let Expr::Name(target) = &*named.target else {
+            return;
+        };
+        let Some(variable) = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&target.id.to_string(), &target.range) else {
+            return;
+        };
+        let (eval, diags) = Evaluation::eval_from_ast(session, &named.value, self.sym_stack.last().unwrap().clone(), &named.range.start());
+        self.diagnostics.extend(diags);
+        variable.borrow_mut().set_evaluations(eval);
+    }
+
+    /* Evaluate the variable changed by a mutation: the previous evaluations of the name, with the mutated containers */
+    fn _visit_container_mutation(&mut self, session: &mut SessionInfo, mutation: &ContainerMutation) {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let Some(variable) = scope.borrow().get_positioned_symbol(&mutation.name.id.to_string(), &ContainerMutations::get_position(mutation)) else {
+            return; //mutations are not declared in class bodies
+        };
+        let mut evaluations = ContainerMutations::evaluate(session, &scope, mutation);
+        if let (true, Some(operator), Some(value)) = (evaluations.is_empty(), mutation.operator, mutation.value) {
+            //values += [...] without previous declaration of values
+            evaluations = self._eval_aug_assign(session, mutation.name, operator, value, mutation.range);
+        }
+        variable.borrow_mut().set_evaluations(evaluations);
+    }
+
+    /* Evaluate the variable bound by an augmented assignment that doesn't mutate a container */
+    fn _visit_aug_assign(&mut self, session: &mut SessionInfo, aug_assign_stmt: &StmtAugAssign) {
+        let Expr::Name(target) = &*aug_assign_stmt.target else {
+            return;
+        };
+        let Some(variable) = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&target.id.to_string(), &target.range) else {
+            return;
+        };
+        let evaluations = self._eval_aug_assign(session, target, aug_assign_stmt.op, &aug_assign_stmt.value, aug_assign_stmt.range);
+        variable.borrow_mut().set_evaluations(evaluations);
+        self._add_evaluation_dependencies(session, &variable);
+    }
+
+    /* Return the evaluations of 'target op= value': the result of the binary operation with the previous declarations of
+    the target if it is known, or these declarations. Without previous declaration, the target is bound to the value like
+    by an assignment */
+    fn _eval_aug_assign(&mut self, session: &mut SessionInfo, target: &ExprName, op: Operator, value: &Expr, range: TextRange) -> Vec<Evaluation> {
+        let scope = self.sym_stack.last().unwrap().clone();
+        let previous = Symbol::infer_name(session.sync_odoo, &scope, &target.id.to_string(), Some(range.start().to_u32()));
+        if previous.is_empty() {
+            let (evaluations, diags) = Evaluation::eval_from_ast(session, value, scope, &range.start());
+            self.diagnostics.extend(diags);
+            return evaluations;
+        }
+        let operation = Expr::BinOp(ExprBinOp {
+            range,
+            left: Box::new(Expr::Name(target.clone())),
+            op,
+            right: Box::new(value.clone()),
+        });
+        //the diagnostics of the operands are the ones of the value, reported by the validation
+        let (evaluations, _) = Evaluation::eval_from_ast(session, &operation, scope, &range.start());
+        if !evaluations.is_empty() {
+            return evaluations;
+        }
+        previous.iter().map(|previous| Evaluation::eval_from_symbol(&Rc::downgrade(previous), None)).collect()
     }
 
     fn _visit_return(&mut self, session: &mut SessionInfo, return_stmt: &StmtReturn) {
@@ -679,40 +1515,55 @@ impl PythonArchEval {
         }
     }
 
+    /* 'with manager as x' binds x to the value returned by the __enter__ method of the manager (__aenter__ for an async
+    with), or to the manager itself when this method can't be resolved. The names of a tuple target are declared without
+    evaluation */
     fn _visit_with(&mut self, session: &mut SessionInfo, with_stmt: &StmtWith) {
+        let enter_method = if with_stmt.is_async { S!("__aenter__") } else { S!("__enter__") };
         for item in with_stmt.items.iter() {
-            if let Some(var) = item.optional_vars.as_ref() {
-                match &**var {
-                    Expr::Name(expr_name) => {
-                        let variable = self.sym_stack.last().unwrap().borrow_mut().get_positioned_symbol(&expr_name.id.to_string(), &expr_name.range());
-                        if let Some(variable_rc) = variable {
-                            let parent = variable_rc.borrow().parent().unwrap().upgrade().unwrap().clone();
-                            let (eval, diags) = Evaluation::eval_from_ast(session, &item.context_expr, parent, &with_stmt.range.start());
-                            let mut evals = vec![];
-                            for eval in eval.iter() {
-                                let symbol = eval.symbol.get_symbol(session, &mut None, &mut self.diagnostics, Some(self.file.clone()));
-                                if let Some(symbol) = symbol.weak.upgrade() {
-                                    let _enter_ = symbol.borrow().get_symbol(&(vec![], vec![S!("__enter__")]), u32::MAX);
-                                    if let Some(_enter_) = _enter_.last() {
-                                        match *_enter_.borrow() {
-                                            Symbol::Function(ref func) => {
-                                                evals.extend(func.evaluations.clone());
-                                            },
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            variable_rc.borrow_mut().set_evaluations(eval);
-                            self.diagnostics.extend(diags);
+            let Some(Expr::Name(expr_name)) = item.optional_vars.as_deref() else {
+                continue;
+            };
+            let Some(variable) = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&expr_name.id.to_string(), &expr_name.range()) else {
+                continue;
+            };
+            let (managers, diags) = Evaluation::eval_from_ast(session, &item.context_expr, self.sym_stack.last().unwrap().clone(), &with_stmt.range.start());
+            self.diagnostics.extend(diags);
+            let mut evals = vec![];
+            for manager in managers.iter() {
+                let manager_symbol = manager.symbol.get_symbol(session, &mut None, &mut self.diagnostics, Some(self.file.clone()));
+                if manager_symbol.weak.is_expired() {
+                    continue;
+                }
+                let mut entered = vec![];
+                for manager_type in Symbol::follow_ref(&manager_symbol, session, &mut None, false, false, None, &mut self.diagnostics).iter() {
+                    let Some(manager_type) = manager_type.weak.upgrade().filter(|t| t.borrow().typ() == SymType::CLASS) else {
+                        continue;
+                    };
+                    let (enter, _) = manager_type.borrow().get_member_symbol(session, &enter_method, None, true, false, false, false);
+                    let Some(enter) = enter.last().filter(|enter| enter.borrow().typ() == SymType::FUNCTION) else {
+                        continue;
+                    };
+                    let file = enter.borrow().get_file().and_then(|file| file.upgrade());
+                    if let Some(file) = file.filter(|file| !Rc::ptr_eq(&self.file, file)) {
+                        Symbol::add_dependency_to(&self.file, &file, self.current_step, BuildSteps::ARCH);
+                    }
+                    //def __enter__(self) -> Self: the value is the manager
+                    let mut context = Some(HashMap::from([(S!("parent"), ContextValue::SYMBOL(Rc::downgrade(&manager_type)))]));
+                    for enter_eval in enter.borrow().as_func().evaluations.iter() {
+                        let value = enter_eval.symbol.get_symbol(session, &mut context, &mut vec![], None);
+                        if !value.weak.is_expired() {
+                            entered.push(Evaluation::eval_from_symbol(&value.weak, value.instance));
                         }
-                    },
-                    Expr::Tuple(_) => {continue;},
-                    Expr::List(_) => {continue;},
-                    _ => {continue;}
+                    }
+                }
+                if entered.is_empty() {
+                    evals.push(manager.clone());
+                } else {
+                    evals.extend(entered);
                 }
             }
-
+            variable.borrow_mut().set_evaluations(evals);
         }
         for (index, stmt) in with_stmt.body.iter().enumerate() {
             self.ast_indexes.push(index as u16);
@@ -6,6 +6,7 @@ use lsp_types::Diagnostic;
 use lsp_types::DiagnosticSeverity;
 use lsp_types::NumberOrString;
 use once_cell::sync::Lazy;
+use ruff_text_size::TextRange;
 use crate::core::odoo::SyncOdoo;
 use crate::core::evaluation::Context;
 use crate::core::symbols::symbol::Symbol;
@@ -17,7 +18,10 @@ use super::evaluation::Evaluation;
 use super::evaluation::ContextValue;
 use super::evaluation::EvaluationSymbol;
 use super::evaluation::EvaluationSymbolWeak;
+use super::date_arithmetic::DATE_FIELD_HELPERS;
+use super::field_consistency::FieldDeclaration;
 use super::file_mgr::FileMgr;
+use super::guards::{Guard, GuardedName};
 use super::symbols::module_symbol::ModuleSymbol;
 
 type PythonArchEvalHookFile = fn (odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>);
@@ -52,10 +56,31 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                 value: None,
                 range: None,
             }]);
-            file_symbol.borrow_mut().add_dependency(&mut env_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+            Symbol::add_dependency_to(&file_symbol, env_file.last().unwrap(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
             env.set_doc_string(Some(S!("")));
         }
     }},
+    PythonArchEvalFileHook { file_tree: vec![S!("odoo"), S!("models")],
+                        content_tree: vec![S!("BaseModel"), S!("_module")],
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("builtins")], vec![S!("str")]));
+    }},
+    PythonArchEvalFileHook { file_tree: vec![S!("odoo"), S!("models")],
+                        content_tree: vec![S!("BaseModel"), S!("_original_module")],
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("builtins")], vec![S!("str")]));
+    }},
+    PythonArchEvalFileHook { file_tree: vec![S!("odoo"), S!("models")],
+                        content_tree: vec![S!("BaseModel"), S!("pool")],
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        let registry_file = odoo.get_symbol(&(vec![S!("odoo"), S!("modules"), S!("registry")], vec![]), u32::MAX);
+        if PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("odoo"), S!("modules"), S!("registry")], vec![S!("Registry")])) {
+            Symbol::add_dependency_to(&file_symbol, registry_file.last().unwrap(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+        }
+    }},
     PythonArchEvalFileHook { file_tree: vec![S!("odoo"), S!("models")],
                         content_tree: vec![S!("BaseModel"), S!("ids")],
                         if_exist_only: true,
@@ -92,32 +117,50 @@ static arch_eval_file_hooks: Lazy<Vec<PythonArchEvalFileHook>> = Lazy::new(|| {v
                 value: None,
                 range: None,
             }]);
-            file_symbol.borrow_mut().add_dependency(&mut cursor_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+            Symbol::add_dependency_to(&file_symbol, cursor_file.last().unwrap(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+        }
+    }},
+    PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("api")],
+                            content_tree: vec![S!("Environment"), S!("registry")],
+                            if_exist_only: false,
+                            func: |odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        let registry_file = odoo.get_symbol(&(vec![S!("odoo"), S!("modules"), S!("registry")], vec![]), u32::MAX);
+        if PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("odoo"), S!("modules"), S!("registry")], vec![S!("Registry")])) {
+            Symbol::add_dependency_to(&file_symbol, registry_file.last().unwrap(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
         }
     }},
+    PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("modules"), S!("registry")],
+                            content_tree: vec![S!("Registry"), S!("models")],
+                            if_exist_only: false,
+                            func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        //the model classes by name. The classes are given by the subscript of the registry itself, that evaluates its literal
+        let range = *symbol.borrow().range();
+        symbol.borrow_mut().set_evaluations(vec![Evaluation::new_dict(odoo, vec![], range)]);
+    }},
+    PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("modules"), S!("registry")],
+                            content_tree: vec![S!("Registry"), S!("ready")],
+                            if_exist_only: false,
+                            func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("builtins")], vec![S!("bool")]));
+    }},
+    PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("modules"), S!("registry")],
+                            content_tree: vec![S!("Registry"), S!("_init_modules")],
+                            if_exist_only: false,
+                            func: |odoo: &mut SyncOdoo, _file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        let range = *symbol.borrow().range();
+        symbol.borrow_mut().set_evaluations(vec![Evaluation::new_set(odoo, vec![], range)]);
+    }},
     PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("tests"), S!("common")],
                             content_tree: vec![S!("TransactionCase"), S!("env")],
                             if_exist_only: true,
                             func: |odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
-        let env_file = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![]), u32::MAX);
-        let env_model = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![S!("Environment")]), u32::MAX);
-        if !env_model.is_empty() {
-            let env_model = env_model.last().unwrap();
-            let mut context = HashMap::new();
-            context.insert(S!("test_mode"), ContextValue::BOOLEAN(true));
-            symbol.borrow_mut().set_evaluations(vec![Evaluation {
-                symbol: EvaluationSymbol::new_with_symbol(
-                    Rc::downgrade(env_model),
-                    true,
-                    context,
-                    None,
-                    None
-                ),
-                value: None,
-                range: None,
-            }]);
-            file_symbol.borrow_mut().add_dependency(&mut env_file.last().unwrap().borrow_mut(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
-        }
+        PythonArchEvalHooks::_set_test_env(odoo, file_symbol, symbol);
+    }},
+    PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("tests"), S!("common")],
+                            content_tree: vec![S!("SingleTransactionCase"), S!("env")],
+                            if_exist_only: true,
+                            func: |odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>| {
+        PythonArchEvalHooks::_set_test_env(odoo, file_symbol, symbol);
     }},
     PythonArchEvalFileHook {file_tree: vec![S!("odoo"), S!("fields")],
                             content_tree: vec![S!("Boolean")],
@@ -264,6 +307,12 @@ static arch_eval_function_hooks: Lazy<Vec<PythonArchEvalFunctionHook>> = Lazy::n
             range: None
         }]);
     }},
+    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("api")], vec![S!("Environment"), S!("__call__")]),
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
+        //env(user=..., context=..., su=...) gives a new environment, that keeps the context of the called one
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("odoo"), S!("api")], vec![S!("Environment")]));
+    }},
     PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("modules"), S!("registry")], vec![S!("Registry"), S!("__getitem__")]),
                         if_exist_only: true,
                         func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
@@ -278,6 +327,17 @@ static arch_eval_function_hooks: Lazy<Vec<PythonArchEvalFunctionHook>> = Lazy::n
             range: None
         }]);
     }},
+    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("api")], vec![S!("Environment"), S!("__iter__")]),
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
+        //the environment and the registry are mappings of the model names
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("builtins")], vec![S!("str")]));
+    }},
+    PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("modules"), S!("registry")], vec![S!("Registry"), S!("__iter__")]),
+                        if_exist_only: true,
+                        func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (vec![S!("builtins")], vec![S!("str")]));
+    }},
     PythonArchEvalFunctionHook { tree: (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("__iter__")]),
                         if_exist_only: true,
                         func: |odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>| {
@@ -380,18 +440,21 @@ pub struct PythonArchEvalHooks {
 
 impl PythonArchEvalHooks {
 
-    pub fn on_file_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
+    pub fn on_file_eval(session: &mut SessionInfo, symbol: Rc<RefCell<Symbol>>) {
         let tree = symbol.borrow().get_tree();
         let name = symbol.borrow().name().clone();
         for hook in arch_eval_file_hooks.iter() {
             if name.eq(hook.file_tree.last().unwrap()) {
                 if tree.0 == hook.file_tree {
                     if hook.content_tree.is_empty() {
-                        (hook.func)(odoo, symbol.clone(), symbol.clone());
+                        (hook.func)(session.sync_odoo, symbol.clone(), symbol.clone());
                     } else {
-                        let sub_symbol = symbol.borrow().get_symbol(&(vec![], hook.content_tree.clone()), u32::MAX);
+                        let mut sub_symbol = symbol.borrow().get_symbol(&(vec![], hook.content_tree.clone()), u32::MAX);
+                        if sub_symbol.is_empty() && !hook.if_exist_only {
+                            sub_symbol = PythonArchEvalHooks::_add_missing_member(session, &symbol, &hook.content_tree).into_iter().collect();
+                        }
                         if !sub_symbol.is_empty() {
-                            (hook.func)(odoo, symbol.clone(), sub_symbol.last().unwrap().clone());
+                            (hook.func)(session.sync_odoo, symbol.clone(), sub_symbol.last().unwrap().clone());
                         }
                     }
                 }
@@ -399,6 +462,42 @@ impl PythonArchEvalHooks {
         }
     }
 
+    /* Some members are only assigned on self by a method (self.models = {} in Registry.init): they are created as
+    variables of their class, at its start, so the hook can give them a type */
+    fn _add_missing_member(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, content_tree: &[String]) -> Option<Rc<RefCell<Symbol>>> {
+        let (name, parent_tree) = content_tree.split_last()?;
+        let parent = file_symbol.borrow().get_symbol(&(vec![], parent_tree.to_vec()), u32::MAX).last().cloned()?;
+        if parent.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let range = TextRange::empty(parent.borrow().range().start());
+        let member = parent.borrow_mut().add_new_variable(session, name, &range);
+        Some(member)
+    }
+
+    /* The env of the test cases is set by setUpClass or setUp: it is evaluated as an Environment in test mode */
+    fn _set_test_env(odoo: &mut SyncOdoo, file_symbol: Rc<RefCell<Symbol>>, symbol: Rc<RefCell<Symbol>>) {
+        let env_file = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![]), u32::MAX);
+        let env_model = odoo.get_symbol(&(vec![S!("odoo"), S!("api")], vec![S!("Environment")]), u32::MAX);
+        if !env_model.is_empty() {
+            let env_model = env_model.last().unwrap();
+            let mut context = HashMap::new();
+            context.insert(S!("test_mode"), ContextValue::BOOLEAN(true));
+            symbol.borrow_mut().set_evaluations(vec![Evaluation {
+                symbol: EvaluationSymbol::new_with_symbol(
+                    Rc::downgrade(env_model),
+                    true,
+                    context,
+                    None,
+                    None
+                ),
+                value: None,
+                range: None,
+            }]);
+            Symbol::add_dependency_to(&file_symbol, env_file.last().unwrap(), BuildSteps::ARCH_EVAL, BuildSteps::ARCH);
+        }
+    }
+
     pub fn on_function_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>) {
         let tree = symbol.borrow().get_tree();
         let name = symbol.borrow().name().clone();
@@ -409,6 +508,24 @@ impl PythonArchEvalHooks {
                 }
             }
         }
+        if tree.0 == [S!("odoo"), S!("fields")] && tree.1.len() == 2 {
+            PythonArchEvalHooks::_eval_date_field_helper(odoo, symbol, &tree.1[0], &name);
+        }
+    }
+
+    /* Give the return type of the helpers of fields.Date and fields.Datetime (Date.today()...), if it is not inferred
+    from their source */
+    fn _eval_date_field_helper(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>, class_name: &String, name: &String) {
+        let Some((_, _, result)) = DATE_FIELD_HELPERS.iter().find(|(c, f, _)| c == class_name && f == name) else {
+            return;
+        };
+        let is_inferred = symbol.borrow().evaluations().is_some_and(|evals| evals.iter().any(|eval| eval.symbol.as_weak().is_some_and(|w|
+            w.instance.unwrap_or(false) && w.weak.upgrade().is_some_and(|s| s.borrow().typ() == SymType::CLASS))));
+        if is_inferred {
+            return;
+        }
+        let (module, class) = result.rsplit_once('.').unwrap();
+        PythonArchEvalHooks::_set_instance_eval(odoo, symbol, (module.split('.').map(|part| part.to_string()).collect(), vec![class.to_string()]));
     }
 
     pub fn eval_env_get_item(session: &mut SessionInfo, evaluation_sym: &EvaluationSymbol, context: &mut Option<Context>, diagnostics: &mut Vec<Diagnostic>, file_symbol: Option<Rc<RefCell<Symbol>>>) -> EvaluationSymbolWeak
@@ -450,14 +567,14 @@ impl PythonArchEvalHooks {
                                     let symbols = model.get_main_symbols(session, None, &mut None);
                                     if symbols.is_empty() {
                                         let range = FileMgr::textRange_to_temporary_Range(&context.get(&S!("range")).unwrap().as_text_range());
-                                        diagnostics.push(Diagnostic::new(range,
+                                        diagnostics.push(Guard::set_guarded_name(Diagnostic::new(range,
                                             Some(DiagnosticSeverity::ERROR),
                                             Some(NumberOrString::String(S!("OLS30105"))),
                                             Some(EXTENSION_NAME.to_string()),
                                             S!("This model is inherited, but never declared."),
                                             None,
                                             None
-                                            )
+                                            ), GuardedName::MODEL(s.clone()))
                                         );
                                     } else {
                                         let range = FileMgr::textRange_to_temporary_Range(&context.get(&S!("range")).unwrap().as_text_range());
@@ -465,38 +582,38 @@ impl PythonArchEvalHooks {
                                             Some(sym) => sym.borrow().name().clone(),
                                             None => S!("Unknown").clone()
                                         }).collect();
-                                        diagnostics.push(Diagnostic::new(range,
+                                        diagnostics.push(Guard::set_guarded_name(Diagnostic::new(range,
                                             Some(DiagnosticSeverity::ERROR),
                                             Some(NumberOrString::String(S!("OLS30101"))),
                                             Some(EXTENSION_NAME.to_string()),
                                             format!("This model is not declared in the dependencies of your module. You should consider adding one of the following dependency: {:?}", valid_modules),
                                             None,
                                             None
-                                            )
+                                            ), GuardedName::MODEL(s.clone()))
                                         );
                                     }
                                 } else {
                                     let range = FileMgr::textRange_to_temporary_Range(&context.get(&S!("range")).unwrap().as_text_range());
-                                    diagnostics.push(Diagnostic::new(range,
+                                    diagnostics.push(Guard::set_guarded_name(Diagnostic::new(range,
                                         Some(DiagnosticSeverity::ERROR),
                                         Some(NumberOrString::String(S!("OLS30102"))),
                                         Some(EXTENSION_NAME.to_string()),
                                         S!("Unknown model. Check your addons path"),
                                         None,
                                         None
-                                    ));
+                                    ), GuardedName::MODEL(s.clone())));
                                 }
                             }
                         } else {
                             let range = FileMgr::textRange_to_temporary_Range(&context.get(&S!("range")).unwrap().as_text_range());
-                            diagnostics.push(Diagnostic::new(range,
+                            diagnostics.push(Guard::set_guarded_name(Diagnostic::new(range,
                                 Some(DiagnosticSeverity::ERROR),
                                 Some(NumberOrString::String(S!("OLS30102"))),
                                 None,
                                 S!("Unknown model. Check your addons path"),
                                 None,
                                 None
-                            ));
+                            ), GuardedName::MODEL(s.clone())));
                         }
                     }
                     _ => {
@@ -546,6 +663,16 @@ impl PythonArchEvalHooks {
         evaluation_sym.get_weak().clone()
     }
 
+    /* Evaluate the symbol as an instance of the class at tree, if it is found. Return true if the evaluation is set */
+    fn _set_instance_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>, tree: Tree) -> bool {
+        let class_sym = odoo.get_symbol(&tree, u32::MAX);
+        let Some(class_sym) = class_sym.last() else {
+            return false;
+        };
+        symbol.borrow_mut().set_evaluations(vec![Evaluation::eval_from_symbol(&Rc::downgrade(class_sym), Some(true))]);
+        true
+    }
+
     fn _update_get_eval(odoo: &mut SyncOdoo, symbol: Rc<RefCell<Symbol>>, tree: Tree) {
         let get_sym = symbol.borrow().get_symbol(&(vec![], vec![S!("__get__")]), u32::MAX);
         if get_sym.is_empty() {
@@ -554,7 +681,7 @@ impl PythonArchEvalHooks {
         let return_sym = odoo.get_symbol(&tree, u32::MAX);
         if return_sym.is_empty() {
             let file = symbol.borrow_mut().get_file().clone();
-            file.as_ref().unwrap().upgrade().unwrap().borrow_mut().not_found_paths_mut().push((BuildSteps::ARCH_EVAL, flatten_tree(&tree)));
+            file.as_ref().unwrap().upgrade().unwrap().borrow_mut().add_not_found_path(BuildSteps::ARCH_EVAL, flatten_tree(&tree));
             odoo.not_found_symbols.insert(symbol);
             return;
         }
@@ -571,18 +698,70 @@ impl PythonArchEvalHooks {
         }]);
     }
 
+    /* Evaluate the value of a relational field on a record to an instance of its comodel, given in the context by
+    eval_field_value. The class of the comodel is the one visible from the module of the field. Nothing is returned if the
+    comodel is not loaded (yet), so the field keeps only its own evaluation */
     fn eval_relational(session: &mut SessionInfo, evaluation_sym: &EvaluationSymbol, context: &mut Option<Context>, diagnostics: &mut Vec<Diagnostic>, file_symbol: Option<Rc<RefCell<Symbol>>>) -> EvaluationSymbolWeak
     {
-        if context.is_none() {
-            return evaluation_sym.get_symbol(session, &mut None, diagnostics, None);
+        let unknown = EvaluationSymbolWeak::new(Weak::new(), Some(true), false);
+        let Some(context) = context.as_ref() else {
+            return unknown;
+        };
+        let Some(ContextValue::STRING(comodel)) = context.get(&S!("comodel")) else {
+            return unknown;
+        };
+        let Some(model) = session.sync_odoo.models.get(comodel).cloned() else {
+            return unknown;
+        };
+        if let Some(file_symbol) = file_symbol {
+            file_symbol.borrow_mut().add_model_dependencies(&model);
+        }
+        let from_module = match context.get(&S!("module")) {
+            Some(ContextValue::MODULE(m)) => m.upgrade(),
+            _ => None
+        };
+        let symbols = model.borrow().get_main_symbols(session, from_module, &mut None);
+        match symbols.first() {
+            Some(symbol) => EvaluationSymbolWeak::new(Rc::downgrade(symbol), Some(true), false),
+            None => unknown
+        }
+    }
+
+    /* Return the evaluations of the value that the field gives on a record: the ones of the __get__ of its field class,
+    with the name of the comodel for a relational field. Empty if the variable is not a field of odoo.fields, or if it
+    is a relational field whose comodel is not given by a string */
+    pub fn eval_field_value(session: &mut SessionInfo, field: &FieldDeclaration, variable: &Rc<RefCell<Symbol>>, module: Option<&Rc<RefCell<Symbol>>>) -> Vec<Evaluation> {
+        if field.is_relational() && field.comodel.is_none() {
+            return vec![];
         }
-        let comodel = context.as_ref().unwrap().get(&S!("comodel"));
-        if comodel.is_none() {
-            return evaluation_sym.get_symbol(session, &mut None, diagnostics, None);
+        let evaluations = variable.borrow().evaluations().cloned().unwrap_or_default();
+        let mut res = vec![];
+        for evaluation in evaluations.iter() {
+            let symbol = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for field_class in Symbol::follow_ref(&symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                let Some(field_class) = field_class.weak.upgrade() else {
+                    continue;
+                };
+                if field_class.borrow().typ() != SymType::CLASS || field_class.borrow().get_tree().0 != vec![S!("odoo"), S!("fields")] {
+                    continue;
+                }
+                let get_sym = field_class.borrow().get_symbol(&(vec![], vec![S!("__get__")]), u32::MAX);
+                let Some(get_sym) = get_sym.last() else {
+                    continue;
+                };
+                Symbol::eval_file_of_variable(session, get_sym);
+                for mut value in get_sym.borrow().evaluations().cloned().unwrap_or_default().into_iter() {
+                    if let Some(comodel) = field.comodel.as_ref() {
+                        value.symbol.context.insert(S!("comodel"), ContextValue::STRING(comodel.clone()));
+                        if let Some(module) = module {
+                            value.symbol.context.insert(S!("module"), ContextValue::MODULE(Rc::downgrade(module)));
+                        }
+                    }
+                    res.push(value);
+                }
+            }
         }
-        let comodel = comodel.unwrap().as_string();
-        //TODO let comodel_sym = odoo.models.get(comodel);
-        EvaluationSymbolWeak{weak: Weak::new(), instance: Some(false), is_super: false}
+        res
     }
 
     fn _update_get_eval_relational(symbol: Rc<RefCell<Symbol>>) {
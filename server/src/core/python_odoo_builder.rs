@@ -3,12 +3,13 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use lsp_types::notification::ShowMessage;
 use lsp_types::MessageType;
-use ruff_python_ast::Expr;
-use lsp_types::{Diagnostic, ShowMessageParams, notification::Notification};
+use ruff_python_ast::{Expr, ExprName};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, ShowMessageParams, notification::Notification};
 use tracing::{error, info};
 
-use crate::constants::{BuildStatus, BuildSteps, SymType, DEBUG_ODOO_BUILDER};
+use crate::constants::{BuildStatus, BuildSteps, SymType, DEBUG_ODOO_BUILDER, EXTENSION_NAME};
 use crate::core::model::{Model, ModelData};
+use crate::core::static_conditions::{StaticConditions, StaticValue};
 use crate::core::symbols::symbol::Symbol;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
@@ -42,19 +43,25 @@ impl PythonOdooBuilder {
             path = PathBuf::from(path).join("__init__").with_extension(S!("py") + symbol.as_package().i_ext().as_str()).sanitize();
         }
         symbol.set_build_status(BuildSteps::ODOO, BuildStatus::IN_PROGRESS);
+        session.sync_odoo.evaluation_cache.start_build(Rc::as_ptr(&self.symbol) as usize);
         symbol.set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
         if DEBUG_ODOO_BUILDER {
             info!("Loading Odoo content for: {}", path);
         }
         let file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&path).expect("File not found in cache").clone();
         if file_info.borrow().ast.is_none() {
+            session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.symbol) as usize);
             symbol.set_build_status(BuildSteps::ODOO, BuildStatus::DONE);
             return;
         }
         drop(symbol);
-        self._load(session);
+        //the models of a single file are not added to the registry
+        if !session.sync_odoo.is_single_file(&path) {
+            self._load(session);
+        }
         file_info.borrow_mut().replace_diagnostics(BuildSteps::ODOO, self.diagnostics.clone());
         session.sync_odoo.add_to_validations(self.symbol.clone());
+        session.sync_odoo.evaluation_cache.end_build(Rc::as_ptr(&self.symbol) as usize);
         let mut symbol = self.symbol.borrow_mut();
         symbol.set_build_status(BuildSteps::ODOO, BuildStatus::DONE);
     }
@@ -71,29 +78,40 @@ impl PythonOdooBuilder {
             if !self.test_symbol_is_model(session, &sym, &mut s_to_build) {
                 continue;
             }
-            self._load_class_inherit(session, &mut s_to_build);
-            self._load_class_name(session, &mut s_to_build);
+            drop(s_to_build);
+            let folded_inherit = self._fold_class_attribute(session, &sym, "_inherit");
+            let folded_name = self._fold_class_attribute(session, &sym, "_name");
+            let mut s_to_build = sym.borrow_mut();
+            self._load_class_inherit(session, &mut s_to_build, folded_inherit);
+            self._load_class_name(session, &mut s_to_build, folded_name);
             if s_to_build.as_class_sym()._model.is_none() {
                 continue;
             }
             self._load_class_inherits(session, &mut s_to_build);
             self._load_class_attributes(session, &mut s_to_build);
             self._add_magic_fields(session, &mut s_to_build);
-            let model = session.sync_odoo.models.get_mut(&s_to_build.as_class_sym()._model.as_ref().unwrap().name).cloned();
-            if model.is_none() {
-                let model = Model::new(s_to_build.as_class_sym()._model.as_ref().unwrap().name.clone(), sym.clone());
-                session.sync_odoo.models.insert(s_to_build.as_class_sym()._model.as_ref().unwrap().name.clone(), Rc::new(RefCell::new(model)));
-            } else {
-                let model = model.unwrap();
-                drop(s_to_build);
-                model.borrow_mut().add_symbol(session, sym.clone());
+            let model_names: Vec<String> = s_to_build.as_class_sym()._model.as_ref().unwrap().names().cloned().collect();
+            drop(s_to_build);
+            for model_name in model_names.into_iter() {
+                let model = session.sync_odoo.models.get(&model_name).cloned();
+                if let Some(model) = model {
+                    model.borrow_mut().add_symbol(session, sym.clone());
+                } else {
+                    let model = Model::new(model_name.clone(), sym.clone());
+                    session.sync_odoo.models.insert(model_name, Rc::new(RefCell::new(model)));
+                    session.sync_odoo.evaluation_cache.touch_models();
+                }
             }
         }
     }
 
-    fn _load_class_inherit(&mut self, session: &mut SessionInfo, symbol: &mut Symbol) {
+    fn _load_class_inherit(&mut self, session: &mut SessionInfo, symbol: &mut Symbol, folded: Option<Vec<String>>) {
         let _inherit = symbol.get_symbol(&(vec![], vec![S!("_inherit")]), u32::MAX);
         if let Some(_inherit) = _inherit.last() {
+            if let Some(folded) = folded {
+                symbol.as_class_sym_mut()._model.as_mut().unwrap().inherit = folded;
+                return;
+            }
             if _inherit.borrow().evaluations().is_none() || _inherit.borrow().evaluations().unwrap().len() == 0 {
                 error!("wrong _inherit structure");
             }
@@ -122,16 +140,29 @@ impl PythonOdooBuilder {
         }
     }
 
-    fn _evaluate_name(&mut self, session: &mut SessionInfo, symbol: &mut Symbol) -> String {
+    fn _evaluate_name(&mut self, session: &mut SessionInfo, symbol: &mut Symbol, folded: Option<Vec<String>>) -> String {
         let _name = symbol.get_symbol(&(vec![], vec![S!("_name")]), u32::MAX);
         if let Some(_name) = _name.last() {
+            if let Some([name]) = folded.as_deref() {
+                return name.clone();
+            }
             for eval in _name.borrow().evaluations().unwrap().iter() {
                 let eval = eval.follow_ref_and_get_value(session, &mut None, &mut self.diagnostics);
                 if let Some(EvaluationValue::CONSTANT(Expr::StringLiteral(s))) = eval {
                     return S!(s.value.to_str());
                 }
             }
-            error!("unable to parse model name");
+            //the class is still a model, but it is not added to the registry
+            symbol.as_class_sym_mut()._model.as_mut().unwrap().unknown_name = true;
+            self.diagnostics.push(Diagnostic::new(
+                Range::new(Position::new(_name.borrow().range().start().to_u32(), 0), Position::new(_name.borrow().range().end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20013"))),
+                Some(EXTENSION_NAME.to_string()),
+                S!("The name of the model can't be statically determined"),
+                None,
+                None,
+            ));
             return "".to_string();
         }
         if let Some(inherit_name) = symbol.as_class_sym_mut()._model.as_ref().unwrap().inherit.first() {
@@ -140,17 +171,75 @@ impl PythonOdooBuilder {
         symbol.name().clone()
     }
 
-    fn _load_class_name(&mut self, session: &mut SessionInfo, symbol: &mut Symbol) {
-        symbol.as_class_sym_mut()._model.as_mut().unwrap().name = self._evaluate_name(session, symbol);
-        if symbol.as_class_sym()._model.as_ref().unwrap().name.is_empty() {
+    fn _load_class_name(&mut self, session: &mut SessionInfo, symbol: &mut Symbol, folded: Option<Vec<String>>) {
+        symbol.as_class_sym_mut()._model.as_mut().unwrap().name = self._evaluate_name(session, symbol, folded);
+        let model_data = symbol.as_class_sym()._model.as_ref().unwrap();
+        if model_data.name.is_empty() && !model_data.unknown_name {
             symbol.as_class_sym_mut()._model = None;
             return;
         }
+        //without _name, a class that inherits several models is added to each of them, like a mixin injected in these models
+        let has_name = !symbol.get_symbol(&(vec![], vec![S!("_name")]), u32::MAX).is_empty();
+        let model_data = symbol.as_class_sym_mut()._model.as_mut().unwrap();
+        model_data.other_names = match has_name {
+            true => vec![],
+            false => model_data.inherit.iter().skip(1).filter(|name| **name != model_data.name).cloned().collect()
+        };
         if symbol.as_class_sym()._model.as_ref().unwrap().name != S!("base") {
             symbol.as_class_sym_mut()._model.as_mut().unwrap().inherit.push(S!("base"));
         }
     }
 
+    /* Return the value of _name or _inherit when it is built from constants, like
+        class LibraryBaseModel(models.AbstractModel):
+            _prefix = "lib."
+        class Book(LibraryBaseModel):
+            _name = _prefix + "book"
+    The names are resolved to the class attributes reachable on the MRO, then to the module-level constants. A string
+    gives one value, a list or a tuple gives the values of its items. */
+    fn _fold_class_attribute(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, attr: &str) -> Option<Vec<String>> {
+        let variable = class.borrow().get_symbol(&(vec![], vec![S!(attr)]), u32::MAX).last().cloned()?;
+        let (value, _) = StaticConditions::get_variable_expr(session, &variable, 0, &mut vec![])?;
+        let items = match &value {
+            Expr::List(l) => l.elts.iter().collect::<Vec<&Expr>>(),
+            Expr::Tuple(t) => t.elts.iter().collect(),
+            value => vec![value]
+        };
+        let mut res = vec![];
+        for item in items.into_iter() {
+            match StaticConditions::get_value(item, &mut |expr| self._get_class_name_value(session, class, expr.as_name_expr()?, 1))? {
+                StaticValue::STR(s) => res.push(s),
+                _ => return None
+            }
+        }
+        Some(res)
+    }
+
+    /* Return the static value of a name used in the body of the class */
+    fn _get_class_name_value(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, name: &ExprName, depth: u32) -> Option<StaticValue> {
+        if depth >= session.sync_odoo.config.evaluation_depth {
+            return None;
+        }
+        let (members, _) = class.borrow().get_member_symbol(session, &name.id.to_string(), None, true, false, false, false);
+        let Some(member) = members.first() else {
+            return StaticConditions::get_name_value(session, class, name, true, depth, &mut vec![]);
+        };
+        let owner = member.borrow().parent()?.upgrade()?;
+        if member.borrow().typ() != SymType::VARIABLE || owner.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let evaluations = member.borrow().evaluations().cloned().unwrap_or_default();
+        for eval in evaluations.iter() {
+            if let Some(EvaluationValue::CONSTANT(value)) = eval.follow_ref_and_get_value(session, &mut None, &mut vec![]) {
+                if let Some(value) = StaticConditions::get_value(&value, &mut |_| None) {
+                    return Some(value);
+                }
+            }
+        }
+        let (value, _) = StaticConditions::get_variable_expr(session, member, depth, &mut vec![])?;
+        StaticConditions::get_value(&value, &mut |expr| self._get_class_name_value(session, &owner, expr.as_name_expr()?, depth + 1))
+    }
+
     fn _load_class_inherits(&mut self, session: &mut SessionInfo, symbol: &mut Symbol) {
         let _inherits = symbol.get_symbol(&(vec![], vec![S!("_inherits")]), u32::MAX);
         if let Some(_inherits) = _inherits.last() {
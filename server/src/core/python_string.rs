@@ -0,0 +1,232 @@
+use std::ops::Range;
+use ruff_python_ast::{Expr, ExprStringLiteral};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+/* Map the value of a string literal to its source code.
+The value of a string can be different from its source: a literal can be made of multiple parts (implicit concatenation),
+each part has its own prefix (r, b, f, u) and quotes, and can contain escape sequences. An offset in the value can then
+not be used directly to build a TextRange.
+StringSourceMap gives, for each character of the value, the range of the source code that produces it. Replacement
+fields of f-strings don't produce any character, as their value is unknown. Bytes of a bytes literal are mapped to
+the character with the same code point. */
+#[derive(Debug, Clone)]
+pub struct StringSourceMap {
+    value: String,
+    chars: Vec<(usize, TextRange)>, //offset of the character in value, range in source
+}
+
+impl StringSourceMap {
+
+    /* Build the map from the source of the parts of a string. 'source' is the source code starting at the offset
+    'start', and contains all the parts. */
+    pub fn new(source: &str, start: TextSize, parts: &[TextRange]) -> Self {
+        let mut res = Self {
+            value: String::new(),
+            chars: vec![],
+        };
+        for part in parts.iter() {
+            if part.start() < start || (part.end() - start).to_usize() > source.len() {
+                continue;
+            }
+            let text = &source[(part.start() - start).to_usize()..(part.end() - start).to_usize()];
+            res.add_part(text, part.start().to_usize());
+        }
+        res
+    }
+
+    /* Build the map of a string, bytes or f-string expression. 'source' is the source code starting at the offset
+    'start', and contains the expression. */
+    pub fn from_expr(source: &str, start: TextSize, expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::StringLiteral(s) => Some(StringSourceMap::from_string_literal(source, start, s)),
+            Expr::BytesLiteral(b) => Some(StringSourceMap::new(source, start, &b.value.iter().map(|p| p.range()).collect::<Vec<TextRange>>())),
+            Expr::FString(f) => Some(StringSourceMap::new(source, start, &f.value.iter().map(|p| p.range()).collect::<Vec<TextRange>>())),
+            _ => None,
+        }
+    }
+
+    pub fn from_string_literal(source: &str, start: TextSize, literal: &ExprStringLiteral) -> Self {
+        let parts: Vec<TextRange> = literal.value.iter().map(|p| p.range()).collect();
+        let mut res = StringSourceMap::new(source, start, &parts);
+        //named unicode characters (\N{...}) can't be decoded here, take them from the parsed value
+        let value = literal.value.to_str();
+        if value.chars().count() == res.chars.len() {
+            let mut offset = 0;
+            for (index, c) in value.chars().enumerate() {
+                res.chars[index].0 = offset;
+                offset += c.len_utf8();
+            }
+            res.value = value.to_string();
+        }
+        res
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /* Return the ranges of source code that produce the given range of the value (as byte offsets of value).
+    Contiguous characters are merged in a single range, so a range of the value that is split across multiple parts,
+    or that contains a line continuation, gives multiple ranges. */
+    pub fn source_ranges(&self, range: Range<usize>) -> Vec<TextRange> {
+        let mut res: Vec<TextRange> = vec![];
+        for (offset, source_range) in self.chars.iter() {
+            if *offset < range.start || *offset >= range.end {
+                continue;
+            }
+            if let Some(last) = res.last_mut() {
+                if last.end() == source_range.start() {
+                    *last = TextRange::new(last.start(), source_range.end());
+                    continue;
+                }
+            }
+            res.push(*source_range);
+        }
+        res
+    }
+
+    /* Return the range of source code that covers the given range of the value (as byte offsets of value) */
+    pub fn source_range(&self, range: Range<usize>) -> Option<TextRange> {
+        let ranges = self.source_ranges(range);
+        Some(TextRange::new(ranges.first()?.start(), ranges.last()?.end()))
+    }
+
+    /* Return the offset in value of the character produced by the source code at the given offset, or the length of
+    value if the offset is after the last character, like a cursor at the end of a string */
+    pub fn value_offset(&self, source_offset: TextSize) -> usize {
+        self.chars.iter()
+            .find(|(_, source_range)| source_range.end() > source_offset)
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.value.len())
+    }
+
+    fn push(&mut self, c: char, start: usize, end: usize) {
+        self.chars.push((self.value.len(), TextRange::new(TextSize::new(start as u32), TextSize::new(end as u32))));
+        self.value.push(c);
+    }
+
+    /* Decode a part of the string. 'text' is the source code of the part, starting at the offset 'start' */
+    fn add_part(&mut self, text: &str, start: usize) {
+        let prefix_len = text.find(|c| c == '\'' || c == '"').unwrap_or(text.len());
+        let prefix = text[..prefix_len].to_lowercase();
+        let is_raw = prefix.contains('r');
+        let is_bytes = prefix.contains('b');
+        let is_fstring = prefix.contains('f');
+        let body = &text[prefix_len..];
+        let quote_len = if body.len() >= 6 && (body.starts_with("'''") || body.starts_with("\"\"\"")) { 3 } else { 1 };
+        if body.len() < 2 * quote_len {
+            return;
+        }
+        let content = &body[quote_len..body.len() - quote_len];
+        let base = start + prefix_len + quote_len;
+        let mut i = 0;
+        while i < content.len() {
+            let c = content[i..].chars().next().unwrap();
+            let next = content[i + c.len_utf8()..].chars().next();
+            match c {
+                '\r' if next == Some('\n') => {
+                    self.push('\n', base + i, base + i + 2);
+                    i += 2;
+                },
+                '\r' => {
+                    self.push('\n', base + i, base + i + 1);
+                    i += 1;
+                },
+                '{' if is_fstring => {
+                    if next == Some('{') {
+                        self.push('{', base + i, base + i + 2);
+                        i += 2;
+                    } else {
+                        i = StringSourceMap::skip_replacement_field(content, i);
+                    }
+                },
+                '}' if is_fstring && next == Some('}') => {
+                    self.push('}', base + i, base + i + 2);
+                    i += 2;
+                },
+                '\\' if !is_raw && next.is_some() => {
+                    i = self.decode_escape(content, i, base, is_bytes);
+                },
+                _ => {
+                    self.push(c, base + i, base + i + c.len_utf8());
+                    i += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    /* Decode the escape sequence starting at the index i of content, and return the index following it */
+    fn decode_escape(&mut self, content: &str, i: usize, base: usize, is_bytes: bool) -> usize {
+        let escaped = content[i + 1..].chars().next().unwrap();
+        let simple = match escaped {
+            '\\' => Some('\\'),
+            '\'' => Some('\''),
+            '"' => Some('"'),
+            'a' => Some('\x07'),
+            'b' => Some('\x08'),
+            'f' => Some('\x0c'),
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            'v' => Some('\x0b'),
+            _ => None
+        };
+        if let Some(c) = simple {
+            self.push(c, base + i, base + i + 2);
+            return i + 2;
+        }
+        match escaped {
+            '\n' => return i + 2, //line continuation
+            '\r' => return if content[i + 2..].starts_with('\n') { i + 3 } else { i + 2 },
+            '0'..='7' => {
+                let digits = content[i + 1..].chars().take(3).take_while(|c| c.is_digit(8)).count();
+                let code = u32::from_str_radix(&content[i + 1..i + 1 + digits], 8).unwrap();
+                let code = if is_bytes { code & 0xff } else { code };
+                self.push(char::from_u32(code).unwrap_or('\u{FFFD}'), base + i, base + i + 1 + digits);
+                return i + 1 + digits;
+            },
+            'x' | 'u' | 'U' if escaped == 'x' || !is_bytes => {
+                let nb_digits = match escaped {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8
+                };
+                let digits = &content[i + 2..];
+                if digits.len() >= nb_digits && digits.is_char_boundary(nb_digits) && digits[..nb_digits].chars().all(|c| c.is_ascii_hexdigit()) {
+                    let code = u32::from_str_radix(&digits[..nb_digits], 16).unwrap();
+                    self.push(char::from_u32(code).unwrap_or('\u{FFFD}'), base + i, base + i + 2 + nb_digits);
+                    return i + 2 + nb_digits;
+                }
+            },
+            'N' if !is_bytes && content[i + 2..].starts_with('{') => {
+                if let Some(end) = content[i + 2..].find('}') {
+                    //the name can't be resolved here, from_expr will take the character from the parsed value
+                    self.push('\u{FFFD}', base + i, base + i + 3 + end);
+                    return i + 3 + end;
+                }
+            },
+            _ => {}
+        }
+        //not an escape sequence: the backslash is kept as is
+        self.push('\\', base + i, base + i + 1);
+        i + 1
+    }
+
+    /* Return the index following the replacement field of an f-string starting at the index i of content */
+    fn skip_replacement_field(content: &str, i: usize) -> usize {
+        let mut depth = 0;
+        for (index, c) in content[i..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + index + 1;
+                    }
+                },
+                _ => {}
+            }
+        }
+        content.len()
+    }
+}
@@ -1,6 +1,11 @@
-use ruff_python_ast::{Expr, ExprName};
+use std::collections::{HashMap, HashSet};
+
+use ruff_python_ast::visitor::{walk_stmt, Visitor};
+use ruff_python_ast::{Expr, ExprName, Operator, Stmt};
 use tracing::error;
 
+use crate::core::collection_folding::{CollectionFolding, Conversion};
+
 #[derive(Debug, Clone)]
 pub struct Assign {
     pub target: ExprName,
@@ -177,4 +182,177 @@ pub fn unpack_assign(targets: &Vec<Expr>, annotation: Option<&Expr>, value: Opti
     }
 
     res
-}
\ No newline at end of file
+}
+
+/* Return the values of the return statements of a function body, None for a bare return. The nested functions and
+classes are not visited */
+pub fn get_returned_values(body: &[Stmt]) -> Vec<Option<&Expr>> {
+    let mut visitor = ReturnVisitor { returns: vec![] };
+    for stmt in body.iter() {
+        visitor.visit_stmt(stmt);
+    }
+    visitor.returns
+}
+
+struct ReturnVisitor<'a> {
+    returns: Vec<Option<&'a Expr>>,
+}
+
+impl<'a> Visitor<'a> for ReturnVisitor<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Return(return_stmt) => self.returns.push(return_stmt.value.as_deref()),
+            Stmt::FunctionDef(_) | Stmt::ClassDef(_) => {},
+            _ => walk_stmt(self, stmt)
+        }
+    }
+}
+
+/* Return true if the module imports annotations from __future__: its annotations are not evaluated at runtime, and can
+name the classes declared later or imported under TYPE_CHECKING only */
+pub fn has_deferred_annotations(body: &[Stmt]) -> bool {
+    body.iter().any(|stmt| match stmt {
+        Stmt::ImportFrom(import) => import.module.as_ref().is_some_and(|module| module.as_str() == "__future__")
+            && import.names.iter().any(|alias| alias.name.as_str() == "annotations"),
+        _ => false
+    })
+}
+
+/* Return true if the end of the body can't be reached: its last statement is a return or a raise, or a statement whose
+bodies all end this way (an if with an else, a with, a try whose body or finally exits and whose handlers exit) */
+pub fn always_exits(body: &[Stmt]) -> bool {
+    match body.last() {
+        Some(Stmt::Return(_) | Stmt::Raise(_)) => true,
+        Some(Stmt::If(if_stmt)) => always_exits(&if_stmt.body)
+            && if_stmt.elif_else_clauses.last().is_some_and(|clause| clause.test.is_none())
+            && if_stmt.elif_else_clauses.iter().all(|clause| always_exits(&clause.body)),
+        Some(Stmt::With(with_stmt)) => always_exits(&with_stmt.body),
+        Some(Stmt::Try(try_stmt)) => always_exits(&try_stmt.finalbody) || (
+            (always_exits(&try_stmt.body) || (!try_stmt.orelse.is_empty() && always_exits(&try_stmt.orelse)))
+            && try_stmt.handlers.iter().all(|handler| match handler {
+                ruff_python_ast::ExceptHandler::ExceptHandler(h) => always_exits(&h.body)
+            })
+        ),
+        _ => false
+    }
+}
+
+/* Return the value of a string expression that can be computed statically: literals, concatenations with '+' and calls
+to os.path.join with foldable parts */
+pub fn fold_string(expr: &Expr) -> Option<String> {
+    _fold_string(expr, &HashMap::new())
+}
+
+/* Return the values of a list of strings that can be computed statically: lists and tuples of foldable strings (with
+unpacked foldable lists), concatenations of lists with '+', repetitions and slices with constant ints, conversions with
+list(), tuple(), set(), sorted() or dict.fromkeys(), and list comprehensions over foldable lists, like
+    [os.path.join("views", f) for f in ["sale_views.xml", "menus.xml"]] */
+pub fn fold_string_list(expr: &Expr) -> Option<Vec<String>> {
+    _fold_string_list(expr, &HashMap::new())
+}
+
+fn _fold_string(expr: &Expr, bindings: &HashMap<String, String>) -> Option<String> {
+    match expr {
+        Expr::StringLiteral(s) => Some(s.value.to_string()),
+        Expr::Name(name) => bindings.get(name.id.as_str()).cloned(),
+        Expr::BinOp(op) if op.op == Operator::Add => {
+            Some(_fold_string(&op.left, bindings)? + &_fold_string(&op.right, bindings)?)
+        },
+        Expr::Call(call) if _is_path_join(&call.func) && !call.arguments.args.is_empty() && call.arguments.keywords.is_empty() => {
+            let mut res = String::new();
+            for arg in call.arguments.args.iter() {
+                let part = _fold_string(arg, bindings)?;
+                //same behaviour as posixpath.join: an absolute part discards the previous ones
+                if part.starts_with('/') || res.is_empty() {
+                    res = part;
+                } else if res.ends_with('/') {
+                    res += &part;
+                } else {
+                    res = res + "/" + &part;
+                }
+            }
+            Some(res)
+        },
+        _ => None
+    }
+}
+
+fn _fold_string_list(expr: &Expr, bindings: &HashMap<String, String>) -> Option<Vec<String>> {
+    match expr {
+        Expr::List(_) | Expr::Tuple(_) => {
+            let elts = match expr {
+                Expr::List(l) => &l.elts,
+                Expr::Tuple(t) => &t.elts,
+                _ => return None
+            };
+            let mut res = vec![];
+            for elt in elts.iter() {
+                match elt {
+                    Expr::Starred(starred) => res.extend(_fold_string_list(&starred.value, bindings)?),
+                    elt => res.push(_fold_string(elt, bindings)?),
+                }
+            }
+            Some(res)
+        },
+        Expr::BinOp(op) if op.op == Operator::Add => {
+            let mut res = _fold_string_list(&op.left, bindings)?;
+            res.extend(_fold_string_list(&op.right, bindings)?);
+            Some(res)
+        },
+        Expr::BinOp(op) if op.op == Operator::Mult => {
+            let (items, count) = match CollectionFolding::get_int(&op.right, &mut |_| None) {
+                Some(count) => (&op.left, count),
+                None => (&op.right, CollectionFolding::get_int(&op.left, &mut |_| None)?)
+            };
+            CollectionFolding::repeat_items(&_fold_string_list(items, bindings)?, count)
+        },
+        Expr::Subscript(sub) => {
+            let Expr::Slice(slice) = &*sub.slice else {
+                return None;
+            };
+            let (lower, upper, step) = CollectionFolding::get_slice_bounds(slice, &mut |_| None)?;
+            CollectionFolding::slice_items(&_fold_string_list(&sub.value, bindings)?, lower, upper, step)
+        },
+        Expr::Call(call) => {
+            let (conversion, arg) = CollectionFolding::get_conversion(call)?;
+            let mut res = _fold_string_list(arg, bindings)?;
+            match conversion {
+                Conversion::LIST | Conversion::TUPLE => {},
+                Conversion::SET | Conversion::FROMKEYS => {
+                    let mut seen = HashSet::new();
+                    res.retain(|value| seen.insert(value.clone()));
+                },
+                Conversion::SORTED => res.sort(),
+            }
+            Some(res)
+        },
+        Expr::ListComp(comp) if comp.generators.len() == 1 => {
+            let generator = &comp.generators[0];
+            let Expr::Name(target) = &generator.target else {
+                return None;
+            };
+            if generator.is_async || !generator.ifs.is_empty() {
+                return None;
+            }
+            let mut res = vec![];
+            for value in _fold_string_list(&generator.iter, bindings)? {
+                let mut bindings = bindings.clone();
+                bindings.insert(target.id.to_string(), value);
+                res.push(_fold_string(&comp.elt, &bindings)?);
+            }
+            Some(res)
+        },
+        _ => None
+    }
+}
+
+fn _is_path_join(func: &Expr) -> bool {
+    let Expr::Attribute(join) = func else {
+        return false;
+    };
+    let Expr::Attribute(path) = &*join.value else {
+        return false;
+    };
+    join.attr.as_str() == "join" && path.attr.as_str() == "path" && matches!(&*path.value, Expr::Name(os) if os.id.as_str() == "os")
+}
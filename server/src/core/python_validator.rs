@@ -1,24 +1,48 @@
-use ruff_python_ast::{Alias, Expr, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtTry};
+use ruff_python_ast::{Alias, CmpOp, Decorator, Expr, ExprCall, ExprStringLiteral, Identifier, Stmt, StmtAnnAssign, StmtAssign, StmtClassDef, StmtFunctionDef, StmtTry};
 use ruff_text_size::{Ranged, TextRange, TextSize};
 use tracing::{trace, warn};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::path::PathBuf;
-use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Position, Range};
 use crate::constants::*;
 use crate::core::symbols::symbol::Symbol;
 use crate::core::odoo::SyncOdoo;
-use crate::core::import_resolver::resolve_import_stmt;
+use crate::core::import_resolver::{resolve_import_stmt, ImportResult};
 use crate::core::symbols::module_symbol::ModuleSymbol;
+use crate::core::module_hooks::{ModuleHooks, MODEL_HOOKS};
+use crate::core::union_members::{MemberPresence, UnionMembers};
 use crate::features::ast_utils::AstUtils;
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use crate::S;
 
+use super::api_decorators::{ApiDecorators, KNOWN_CONTEXT_KEYS};
+use super::compute_targets::ComputeTargets;
+use super::context_keys::ContextKeys;
+use super::date_arithmetic::DateArithmetic;
+use super::dynamic_dispatch::DynamicDispatch;
 use super::evaluation::{Evaluation, EvaluationValue};
-use super::file_mgr::FileInfo;
+use super::field_name_args::{FieldNameArgs, FieldNameFormat};
+use super::field_consistency::{FieldConsistency, FieldDeclaration};
+use super::field_dependencies::{FieldRef, DEFAULT_DEPENDENCY_DEPTH};
+use super::file_mgr::{FileInfo, FileMgr};
+use super::guards::{Guard, GuardedName};
+use super::method_references::MethodReferences;
+use super::python_string::StringSourceMap;
+use super::model::Model;
+use super::model_kind::{ModelKind, TABLE_METHODS};
+use super::model_metadata::ModelMetadata;
+use super::narrowing::Narrowings;
+use super::sql_view::SqlView;
+use super::selection_field::{ModelSelection, SelectionAddEntry, SelectionField, ONDELETE_POLICIES};
 use super::python_arch_builder::PythonArchBuilder;
 use super::python_arch_eval::PythonArchEval;
+use super::suppression::Suppression;
+
+/* Prefixes of the xml ids generated by Odoo for the models, fields, modules and selection values */
+const GENERATED_XML_ID_PREFIXES: [&str; 4] = ["model_", "field_", "module_", "selection__"];
 
 #[derive(Debug)]
 pub struct PythonValidator {
@@ -26,7 +50,8 @@ pub struct PythonValidator {
     sym_stack: Vec<Rc<RefCell<Symbol>>>,
     pub diagnostics: Vec<Diagnostic>, //collect diagnostic from arch and arch_eval too from inner functions, but put everything at Validation level
     safe_imports: Vec<bool>,
-    current_module: Option<Rc<RefCell<Symbol>>>
+    current_module: Option<Rc<RefCell<Symbol>>>,
+    cancelled: bool, //the rebuild has been cancelled between two top-level statements of the file
 }
 
 /* PythonValidator operate on a single Symbol. Unlike other steps, it can be done on symbol containing code (file and functions only. Not class, variable, namespace).
@@ -40,6 +65,7 @@ impl PythonValidator {
             diagnostics: vec![],
             safe_imports: vec![false],
             current_module: None,
+            cancelled: false,
         }
     }
 
@@ -70,12 +96,23 @@ impl PythonValidator {
                 let file_info_rc = self.get_file_info(session.sync_odoo).clone();
                 file_info_rc.borrow_mut().replace_diagnostics(BuildSteps::VALIDATION, vec![]);
                 let file_info = file_info_rc.borrow();
-                if file_info.ast.is_some() && file_info.valid {
+                let excluded = session.sync_odoo.get_file_mgr().borrow().is_validation_excluded(&file_info);
+                if file_info.ast.is_some() && file_info.valid && !excluded {
                     self.validate_body(session, file_info.ast.as_ref().unwrap());
                 }
                 drop(file_info);
+                if self.cancelled {
+                    self.sym_stack[0].borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+                    session.sync_odoo.add_to_validations(self.sym_stack[0].clone());
+                    return;
+                }
                 let mut file_info = file_info_rc.borrow_mut();
                 file_info.replace_diagnostics(BuildSteps::VALIDATION, self.diagnostics.clone());
+                file_info.mark_validated();
+                drop(file_info);
+                if sym_type == SymType::PACKAGE(PackageType::MODULE) && self.sym_stack[0].borrow().in_workspace() {
+                    self._validate_module_data(session);
+                }
             },
             SymType::FUNCTION => {
                 trace!("Validating function {}", self.sym_stack[0].borrow().name());
@@ -101,7 +138,10 @@ impl PythonValidator {
                         },
                         _ => {panic!("Wrong statement in validation ast extraction {} ", sym_type)}
                     };
-                    self.validate_body(session, body);
+                    //functions of excluded files are built for the other files, but their diagnostics are not wanted
+                    if !session.sync_odoo.get_file_mgr().borrow().is_validation_excluded(&file_info) {
+                        self.validate_body(session, body);
+                    }
                     match stmt {
                         Stmt::FunctionDef(_) => {
                             self.sym_stack[0].borrow_mut().as_func_mut().diagnostics.insert(BuildSteps::VALIDATION, self.diagnostics.clone());
@@ -117,7 +157,7 @@ impl PythonValidator {
         let mut symbol = self.sym_stack[0].borrow_mut();
         symbol.set_build_status(BuildSteps::VALIDATION, BuildStatus::DONE);
         if matches!(&symbol.typ(), SymType::FILE | SymType::PACKAGE(_)) {
-            if !symbol.in_workspace() {
+            if !symbol.in_workspace() && !session.sync_odoo.is_single_file(&symbol.paths()[0]) {
                 if !symbol.is_external() {
                     return
                 }
@@ -126,15 +166,22 @@ impl PythonValidator {
                 drop(symbol);
                 let file_info = self.get_file_info(session.sync_odoo);
                 let mut file_info = file_info.borrow_mut();
-                file_info.publish_diagnostics(session);
+                file_info.schedule_publication(session);
             }
         }
     }
 
     fn validate_body(&mut self, session: &mut SessionInfo, vec_ast: &Vec<Stmt>) {
         for stmt in vec_ast.iter() {
+            if self.file_mode && self.sym_stack.len() == 1 && session.is_cancelled() {
+                self.cancelled = true;
+                break;
+            }
             match stmt {
                 Stmt::FunctionDef(f) => {
+                    self._check_deprecated_decorators(session, &f.decorator_list);
+                    self._check_api_decorator_names(session, &f.decorator_list);
+                    self._check_manifest_hook(session, f);
                     let sym = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&f.name.to_string(), &f.range);
                     if let Some(sym) = sym {
                         let val_status = sym.borrow().build_status(BuildSteps::VALIDATION).clone();
@@ -192,12 +239,34 @@ impl PythonValidator {
                     self.validate_body(session, &f.body);
                     self.validate_body(session, &f.orelse);
                 },
+                Stmt::Match(m) => {
+                    self.validate_expr(session, &m.subject, &m.subject.start());
+                    for case in m.cases.iter() {
+                        self.validate_body(session, &case.body);
+                    }
+                },
+                Stmt::With(w) => {
+                    for item in w.items.iter() {
+                        self.validate_expr(session, &item.context_expr, &item.context_expr.start());
+                    }
+                    self.validate_body(session, &w.body);
+                },
                 Stmt::While(w) => {
                     self.validate_expr(session, &w.test, &w.test.start());
                     self.validate_body(session, &w.body);
                     self.validate_body(session, &w.orelse);
                 },
-                Stmt::Return(r) => {},
+                Stmt::Return(r) => {
+                    if let Some(value) = r.value.as_ref() {
+                        self._check_deprecated_calls(session, value);
+                        self._check_field_name_args(session, value);
+                        self._check_selection_comparisons(session, value);
+                        self._check_date_comparisons(session, value);
+                        self._check_abstract_calls(session, value);
+                        self._check_dynamic_getattrs(session, value);
+                        self._check_context_keys(session, value);
+                    }
+                },
                 _ => {
                     trace!("Stmt not handled");
                 }
@@ -209,6 +278,18 @@ impl PythonValidator {
         let sym = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&c.name.to_string(), &c.range);
         if let Some(sym) = sym {
             self._check_model(session, &sym);
+            self._check_deprecated_members(session, &sym, &c.body);
+            self._check_selection_fields(session, &sym, &c.body);
+            self._check_plugin_fields(session, &sym, &c.body);
+            self._check_api_decorators(session, &sym, &c.body);
+            self._check_injected_members(session, &sym, &c.body);
+            self._check_model_metadata(session, &sym, c);
+            self._check_model_kind(session, &sym, c);
+            self._check_field_consistency(session, &sym, &c.body);
+            self._check_field_dependency_cycles(session, &sym, &c.body);
+            self._check_view_columns(session, &sym, c);
+            self._check_unreferenced_methods(session, &sym, &c.body);
+            self._check_model_hooks(&sym, &c.body);
             self.sym_stack.push(sym);
             self.validate_body(session, &c.body);
             self.sym_stack.pop();
@@ -218,18 +299,19 @@ impl PythonValidator {
     }
 
     fn visit_try(&mut self, session: &mut SessionInfo, node: &StmtTry) {
-        let mut safe_import = false;
+        self.safe_imports.push(AstUtils::catches_import_error(node));
+        self.validate_body(session, &node.body);
+        self.safe_imports.pop();
+        //the fallback imports of the handlers are not optional
         for handler in node.handlers.iter() {
             let handler = handler.as_except_handler().unwrap();
             if let Some(type_) = &handler.type_ {
-                if type_.is_name_expr() && type_.as_name_expr().unwrap().id.to_string() == "ImportError" {
-                    safe_import = true;
-                }
+                self.validate_expr(session, type_, &type_.start());
             }
+            self.validate_body(session, &handler.body);
         }
-        self.safe_imports.push(safe_import);
-        self.validate_body(session, &node.body);
-        self.safe_imports.pop();
+        self.validate_body(session, &node.orelse);
+        self.validate_body(session, &node.finalbody);
     }
 
     fn _resolve_import(&mut self, session: &mut SessionInfo, from_stmt: Option<&Identifier>, name_aliases: &[Alias], level: Option<u32>, range: &TextRange) {
@@ -246,7 +328,8 @@ impl PythonValidator {
             if import_result.found && self.current_module.is_some() {
                 let module = import_result.symbol.borrow().find_module();
                 if let Some(module) = module {
-                    if !ModuleSymbol::is_in_deps(session, self.current_module.as_ref().unwrap(), &module.borrow().as_module_package().dir_name, &mut None) && !self.safe_imports.last().unwrap() {
+                    if !ModuleSymbol::is_in_deps(session, self.current_module.as_ref().unwrap(), &module.borrow().as_module_package().dir_name, &mut None) && !self.safe_imports.last().unwrap()
+                        && !self._is_type_checking_import(import_result) {
                         self.diagnostics.push(Diagnostic::new(
                             Range::new(Position::new(import_result.range.start().to_u32(), 0), Position::new(import_result.range.end().to_u32(), 0)),
                             Some(DiagnosticSeverity::ERROR),
@@ -262,12 +345,327 @@ impl PythonValidator {
         }
     }
 
-    fn visit_ann_assign(&mut self, session: &mut SessionInfo, assign: &StmtAnnAssign) {
+    /* Return true if the import is only done by the type checkers (see is_type_checking_import): the module is not
+    loaded at runtime, and does not need to be a dependency */
+    fn _is_type_checking_import(&self, import_result: &ImportResult) -> bool {
+        let name = import_result.name.split('.').next().unwrap_or(&import_result.name).to_string(); //import os.path declares os
+        let variable = self.sym_stack.last().unwrap().borrow().get_positioned_symbol(&name, &import_result.range);
+        variable.is_some_and(|variable| {
+            let variable = variable.borrow();
+            variable.typ() == SymType::VARIABLE && variable.as_variable().is_type_checking_import
+        })
+    }
 
+    fn visit_ann_assign(&mut self, session: &mut SessionInfo, assign: &StmtAnnAssign) {
+        if let Some(value) = assign.value.as_ref() {
+            self._check_deprecated_calls(session, value);
+            self._check_field_name_args(session, value);
+            self._check_selection_comparisons(session, value);
+            self._check_date_comparisons(session, value);
+            self._check_abstract_calls(session, value);
+            self._check_dynamic_getattrs(session, value);
+            self._check_context_keys(session, value);
+        }
     }
 
     fn visit_assign(&mut self, session: &mut SessionInfo, assign: &StmtAssign) {
+        self._check_deprecated_calls(session, &assign.value);
+        self._check_field_name_args(session, &assign.value);
+        self._check_selection_comparisons(session, &assign.value);
+        self._check_date_comparisons(session, &assign.value);
+        self._check_abstract_calls(session, &assign.value);
+        self._check_dynamic_getattrs(session, &assign.value);
+        self._check_context_keys(session, &assign.value);
+    }
+
+    fn _deprecated_api_version(&self, session: &mut SessionInfo) -> Option<(u32, u32)> {
+        if session.sync_odoo.version_major == 0 {
+            return None; //Odoo version is unknown
+        }
+        Some((session.sync_odoo.version_major, session.sync_odoo.version_minor))
+    }
+
+    /* Check that the members declared in the body of a model are not deprecated (overrides of deprecated methods, deprecated attributes) */
+    fn _check_deprecated_members(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let Some(version) = self._deprecated_api_version(session) else {
+            return;
+        };
+        let Some(model_names) = class.borrow().as_class_sym()._model.as_ref().map(|m| m.names().cloned().collect::<Vec<String>>()) else {
+            return;
+        };
+        for stmt in body.iter() {
+            let names: Vec<(String, TextRange)> = match stmt {
+                Stmt::FunctionDef(f) => vec![(f.name.to_string(), f.name.range())],
+                Stmt::Assign(a) => a.targets.iter().filter_map(|t| t.as_name_expr()).map(|n| (n.id.to_string(), n.range())).collect(),
+                Stmt::AnnAssign(a) => a.target.as_name_expr().map(|n| (n.id.to_string(), n.range())).into_iter().collect(),
+                _ => vec![]
+            };
+            for (name, range) in names.iter() {
+                let entry = model_names.iter().find_map(|model_name| session.sync_odoo.deprecated_api.find_member(version, Some(model_name.as_str()), name));
+                if let Some(entry) = entry {
+                    self.diagnostics.push(entry.create_diagnostic(range));
+                }
+            }
+        }
+    }
+
+    fn _check_deprecated_decorators(&mut self, session: &mut SessionInfo, decorators: &Vec<Decorator>) {
+        let Some(version) = self._deprecated_api_version(session) else {
+            return;
+        };
+        for decorator in decorators.iter() {
+            let expr = match &decorator.expression {
+                Expr::Call(call) => &*call.func,
+                expr => expr
+            };
+            if let Some(entry) = session.sync_odoo.deprecated_api.find_decorator(version, &AstUtils::flatten_expr(expr)) {
+                self.diagnostics.push(entry.create_diagnostic(&decorator.range()));
+            }
+        }
+    }
+
+    /* Check that the decorators written as api.<name> are members of odoo.api: with a typo like @api.depens, the method
+    is never called as expected */
+    fn _check_api_decorator_names(&mut self, session: &mut SessionInfo, decorators: &Vec<Decorator>) {
+        for decorator in decorators.iter() {
+            let expr = match &decorator.expression {
+                Expr::Call(call) => &*call.func,
+                expr => expr
+            };
+            let Expr::Attribute(attr) = expr else {
+                continue;
+            };
+            if !["api", "odoo.api"].contains(&AstUtils::flatten_expr(&attr.value).as_str()) {
+                continue;
+            }
+            let (evals, _) = Evaluation::eval_from_ast(session, &attr.value, self.sym_stack.last().unwrap().clone(), &decorator.range().start());
+            let Some(api) = evals.first().and_then(|eval| eval.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade()) else {
+                continue;
+            };
+            if api.borrow().get_tree() != tree(vec!["odoo", "api"], vec![]) || !api.borrow().get_content_symbol(attr.attr.as_str(), u32::MAX).is_empty() {
+                continue;
+            }
+            if self._is_suppressed(session, &attr.attr.range(), "OLS30336") {
+                continue;
+            }
+            self.diagnostics.push(PythonValidator::_create_diagnostic(&attr.attr.range(), DiagnosticSeverity::ERROR, "OLS30336",
+                format!("{} is not a member of odoo.api", attr.attr)));
+        }
+    }
+
+    /* Check all calls of the expression against the deprecated functions and model methods */
+    fn _check_deprecated_calls(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        let Some(version) = self._deprecated_api_version(session) else {
+            return;
+        };
+        for call in AstUtils::find_calls(expr) {
+            //diagnostics of the evaluation are already reported by previous steps
+            let (evals, _) = Evaluation::eval_from_ast(session, &call.func, self.sym_stack.last().unwrap().clone(), &call.range.start());
+            for eval in evals.iter() {
+                let Some(symbol) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade() else {
+                    continue;
+                };
+                if let Some(diagnostic) = self._get_deprecated_call_diagnostic(session, &symbol, version, &call.func.range()) {
+                    self.diagnostics.push(diagnostic);
+                    break;
+                }
+            }
+        }
+    }
+
+    /* Check the field names given as strings to the ORM methods of FIELD_NAME_ARGS, like read(['name']) or
+    search(domain, order='date desc'). They must be fields of the model of the receiver. */
+    fn _check_field_name_args(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for call in AstUtils::find_calls(expr) {
+            let arguments = FieldNameArgs::get_arguments(call);
+            if arguments.is_empty() {
+                continue;
+            }
+            let Expr::Attribute(func) = &*call.func else {
+                continue;
+            };
+            let Some(model) = self._get_model_of_expr(session, &func.value, &call.range.start()) else {
+                continue;
+            };
+            for (field_arg, value) in arguments.iter() {
+                for literal in FieldNameArgs::get_string_literals(value) {
+                    self._check_field_names(session, &model, literal, field_arg.format);
+                }
+            }
+        }
+    }
+
+    fn _check_field_names(&mut self, session: &mut SessionInfo, model: &Rc<RefCell<Symbol>>, literal: &ExprStringLiteral, format: FieldNameFormat) {
+        let file_info = self.get_file_info(session.sync_odoo);
+        let source = file_info.borrow().get_text(&literal.range);
+        let map = source.map(|source| StringSourceMap::from_string_literal(&source, literal.range.start(), literal));
+        for (name, range) in FieldNameArgs::get_field_names(literal.value.to_str(), format).into_iter() {
+            let (symbols, _) = model.borrow().get_member_symbol(session, &name, self.current_module.clone(), false, true, false, false);
+            if !symbols.is_empty() {
+                continue;
+            }
+            let severity = if model.borrow().has_dynamic_fields(session, self.current_module.clone()) {
+                DiagnosticSeverity::HINT
+            } else {
+                DiagnosticSeverity::ERROR
+            };
+            let source_range = map.as_ref()
+                .filter(|map| map.value() == literal.value.to_str())
+                .and_then(|map| map.source_range(range))
+                .unwrap_or(literal.range);
+            self.diagnostics.push(Guard::set_guarded_name(PythonValidator::_create_diagnostic(&source_range, severity, "OLS30326",
+                format!("{} is not a field of {}", name, model.borrow().name())), GuardedName::MEMBER(name)));
+        }
+    }
+
+    /* Check the parameters of a function of the module given as pre_init_hook, post_init_hook or uninstall_hook in the
+    manifest. Since Odoo 17, hooks receive the environment. Before, they receive the cursor, and the registry for
+    post_init_hook and uninstall_hook. */
+    fn _check_manifest_hook(&mut self, session: &mut SessionInfo, func: &StmtFunctionDef) {
+        if self.sym_stack.len() != 1 || !matches!(self.sym_stack[0].borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+            return;
+        }
+        let Some(module) = self.current_module.clone() else {
+            return;
+        };
+        if session.sync_odoo.version_major == 0 {
+            return; //unknown version
+        }
+        let hooks: Vec<String> = module.borrow().as_module_package().manifest_hooks.iter()
+            .filter(|(_, (function, _))| function.as_str() == func.name.as_str())
+            .map(|(hook, _)| hook.clone()).collect();
+        for hook in hooks.iter() {
+            let expected = ModuleHooks::get_hook_parameters(hook, session.sync_odoo.version_major);
+            let since = if session.sync_odoo.version_major >= 17 { "since" } else { "before" };
+            if !ModuleHooks::accepts(&func.parameters, expected.len()) {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&func.name.range, DiagnosticSeverity::ERROR, "OLS30328",
+                    format!("{} is called with ({}) {} Odoo 17", hook, expected.join(", "), since)));
+            }
+        }
+    }
+
+    /* Check the overrides of _register_hook and _auto_init in a model. The registry calls them with self only, and
+    _auto_init creates the table and the columns of the model in the parent implementation, that must be called */
+    fn _check_model_hooks(&mut self, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        if class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        for stmt in body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            if !MODEL_HOOKS.contains(&func.name.as_str()) {
+                continue;
+            }
+            if !ModuleHooks::accepts(&func.parameters, 1) {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&func.name.range, DiagnosticSeverity::ERROR, "OLS30339",
+                    format!("{} is called by the registry with self only", func.name)));
+            } else if func.name.as_str() == "_auto_init" && !ModuleHooks::calls_super(&func.body, "_auto_init") {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&func.name.range, DiagnosticSeverity::WARNING, "OLS20022",
+                    S!("_auto_init doesn't call super()._auto_init(): the table of the model is not created")));
+            }
+        }
+    }
+
+    /* Check the keys that a Selection field is compared to, like record.state == 'sale' or record.state in ('sale', 'done').
+    They must be keys of the selection of the field, merged from all the classes of the model (selection_add). Chained
+    comparisons are checked from left to right. */
+    fn _check_selection_comparisons(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for compare in AstUtils::find_compares(expr) {
+            for (left, op, right) in Narrowings::get_pairs(compare) {
+                let Some((Expr::Attribute(field), values)) = Narrowings::get_compared_literals(left, op, right) else {
+                    continue;
+                };
+                let literals: Vec<&ExprStringLiteral> = values.iter().filter_map(|v| v.as_string_literal_expr()).collect();
+                if literals.is_empty() {
+                    continue; //False is the value of an empty selection
+                }
+                let Some(model) = self._get_model_of_expr(session, &field.value, &compare.range.start()) else {
+                    continue;
+                };
+                let Some(model_name) = model.borrow().as_class_sym()._model.as_ref().map(|m| m.name.clone()) else {
+                    continue;
+                };
+                let field_name = field.attr.to_string();
+                let ModelSelection::KEYS(keys, _) = SelectionField::get_model_selection(session, &model_name, &field_name, self.current_module.clone(), None) else {
+                    continue;
+                };
+                for literal in literals.iter() {
+                    if !keys.iter().any(|k| k.as_str() == literal.value.to_str()) {
+                        self.diagnostics.push(PythonValidator::_create_diagnostic(&literal.range, DiagnosticSeverity::ERROR, "OLS30327",
+                            format!("{} is not a key of the selection of {}", literal.value.to_str(), field_name)));
+                    }
+                }
+            }
+        }
+    }
+
+    /* Warn about the comparisons of a date or a datetime with a string: they were both strings before Odoo 12, but the
+    comparison is now always false (==) or raises a TypeError (<, >...) */
+    fn _check_date_comparisons(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for compare in AstUtils::find_compares(expr) {
+            for (left, op, right) in Narrowings::get_pairs(compare) {
+                if matches!(op, CmpOp::In | CmpOp::NotIn | CmpOp::Is | CmpOp::IsNot) {
+                    continue;
+                }
+                let (operand, literal) = match (left, right) {
+                    (Expr::StringLiteral(_), Expr::StringLiteral(_)) => continue,
+                    (operand, Expr::StringLiteral(literal)) | (Expr::StringLiteral(literal), operand) => (operand, literal),
+                    _ => continue
+                };
+                let classes = Evaluation::get_instance_class_paths(session, operand, self.sym_stack.last().unwrap().clone(), &compare.range.start());
+                if !classes.is_empty() && classes.iter().all(|c| DateArithmetic::is_date(c)) {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&literal.range, DiagnosticSeverity::WARNING, "OLS30330",
+                        format!("{} is compared to a string. Use a date or datetime object instead", AstUtils::flatten_expr(operand))));
+                }
+            }
+        }
+    }
 
+    /* Return the class of the model that the expression evaluates to (self, self.env['model'], a recordset...) */
+    fn _get_model_of_expr(&mut self, session: &mut SessionInfo, expr: &Expr, max_infer: &TextSize) -> Option<Rc<RefCell<Symbol>>> {
+        let (evals, _) = Evaluation::eval_from_ast(session, expr, self.sym_stack.last().unwrap().clone(), max_infer);
+        let mut models = vec![];
+        for eval in evals.iter() {
+            let eval_symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for eval_weak in Symbol::follow_ref(&eval_symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                if let Some(symbol) = eval_weak.weak.upgrade() {
+                    if symbol.borrow().typ() == SymType::CLASS && symbol.borrow().as_class_sym()._model.is_some() &&
+                        !models.iter().any(|m| Rc::ptr_eq(m, &symbol)) {
+                        models.push(symbol);
+                    }
+                }
+            }
+        }
+        //a field name can only be checked if the model is known for sure
+        match models.len() {
+            1 => models.pop(),
+            _ => None
+        }
+    }
+
+    fn _get_deprecated_call_diagnostic(&self, session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, version: (u32, u32), range: &TextRange) -> Option<Diagnostic> {
+        let symbol = symbol.borrow();
+        let path = flatten_tree(&symbol.get_tree()).join(".");
+        if let Some(entry) = session.sync_odoo.deprecated_api.find_function(version, &path) {
+            return Some(entry.create_diagnostic(range));
+        }
+        let parent = symbol.parent()?.upgrade()?;
+        let parent = parent.borrow();
+        if parent.typ() != SymType::CLASS {
+            return None;
+        }
+        let model_name = match parent.as_class_sym()._model.as_ref() {
+            Some(model) => Some(model.name.clone()),
+            None => {
+                if parent.get_tree() != (vec![S!("odoo"), S!("models")], vec![S!("BaseModel")]) {
+                    return None;
+                }
+                None
+            }
+        };
+        session.sync_odoo.deprecated_api.find_member(version, model_name.as_deref(), symbol.name())
+            .map(|entry| entry.create_diagnostic(range))
     }
 
     fn _check_model(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>) {
@@ -288,19 +686,22 @@ impl PythonValidator {
                 if let Some(inherit_value) = inherit_value {
                     match inherit_value {
                         EvaluationValue::CONSTANT(Expr::StringLiteral(s)) => {
-                            self._check_module_dependency(session, &s.value.to_string(), &s.range());
+                            let range = self._get_string_value_range(session, &s);
+                            self._check_module_dependency(session, &s.value.to_string(), &range);
                         },
                         EvaluationValue::LIST(l) => {
                             for e in l {
                                 if let Expr::StringLiteral(s) = e {
-                                    self._check_module_dependency(session, &s.value.to_string(), &s.range());
+                                    let range = self._get_string_value_range(session, &s);
+                                    self._check_module_dependency(session, &s.value.to_string(), &range);
                                 }
                             }
                         },
                         EvaluationValue::TUPLE(l) => {
                             for e in l {
                                 if let Expr::StringLiteral(s) = e {
-                                    self._check_module_dependency(session, &s.value.to_string(), &s.range());
+                                    let range = self._get_string_value_range(session, &s);
+                                    self._check_module_dependency(session, &s.value.to_string(), &range);
                                 }
                             }
                         },
@@ -313,6 +714,778 @@ impl PythonValidator {
         }
     }
 
+    /* Check the consistency of the store, compute, readonly and related arguments of the fields of a model. The diagnostics
+    point at the compute method or the _order involved with their related information */
+    fn _check_field_consistency(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        if class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let issues = FieldConsistency::check(body);
+        if issues.is_empty() {
+            return;
+        }
+        let uri = FileMgr::pathname2uri(&self.get_file_info(session.sync_odoo).borrow().uri);
+        for issue in issues.into_iter() {
+            let severity = match session.sync_odoo.config.field_consistency_severity.get(issue.rule.code()) {
+                Some(severity) => *severity,
+                None => Some(issue.rule.default_severity())
+            };
+            let Some(severity) = severity else {
+                continue;
+            };
+            let mut diagnostic = PythonValidator::_create_diagnostic(&issue.range, severity, issue.rule.code(), issue.message);
+            diagnostic.related_information = issue.related.map(|(range, message)| vec![DiagnosticRelatedInformation {
+                location: Location::new(uri.clone(), Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0))),
+                message,
+            }]);
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /* Check that the fields computed by the methods of the class don't depend on themselves, through their @api.depends,
+    the related fields and the other computed fields. The diagnostic is on the @api.depends of the method */
+    fn _check_field_dependency_cycles(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|m| m.name.clone()) else {
+            return;
+        };
+        let fields: Vec<FieldDeclaration> = body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+        for stmt in body.iter() {
+            let Stmt::FunctionDef(method) = stmt else {
+                continue;
+            };
+            let Some((decorator, Some(_))) = ApiDecorators::find(&method.decorator_list, "depends") else {
+                continue;
+            };
+            for target in ComputeTargets::get_targets(&fields, method.name.as_str()).into_iter() {
+                let cycle = session.sync_odoo.get_file_mgr().borrow().field_dependencies
+                    .find_cycle(&FieldRef::new(&model_name, &target.name), DEFAULT_DEPENDENCY_DEPTH);
+                let Some(cycle) = cycle else {
+                    continue;
+                };
+                let chain: Vec<String> = cycle.iter().map(|field| format!("{} ({})", field.field, field.model)).collect();
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&decorator.range(), DiagnosticSeverity::WARNING, "OLS20029",
+                    format!("{} depends on itself: {}. Add recursive=True to the field if it depends on its parents", target.name, chain.join(" -> "))));
+            }
+        }
+    }
+
+    /* Check the fields of a model backed by a view (_auto = False) against the SELECT list of the literal query of the
+    class: the stored fields must be columns of the view. The columns that are not fields are hinted, unless the model
+    inherits fields from other models */
+    fn _check_view_columns(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, c: &StmtClassDef) {
+        if class.borrow().as_class_sym()._model.is_none() {
+            return;
+        }
+        let metadata = ModelMetadata::from_body(&c.body);
+        if metadata.is_extension() || !matches!(metadata.auto, Some((false, _))) {
+            return;
+        }
+        let Some(query) = SqlView::find_query(&c.body) else {
+            return;
+        };
+        let Some(columns) = SqlView::get_columns(&query.literal.value.to_str()[query.start..]) else {
+            return;
+        };
+        let fields: Vec<FieldDeclaration> = c.body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+        for field in SqlView::get_fields_without_column(&fields, &columns) {
+            self.diagnostics.push(PythonValidator::_create_diagnostic(&field.range, DiagnosticSeverity::WARNING, "OLS20027",
+                format!("{} is not a column of the query of the view: reading the field fails", field.name)));
+        }
+        if metadata.inherit.is_some() {
+            return;
+        }
+        let file_info = self.get_file_info(session.sync_odoo);
+        let map = file_info.borrow().get_text(&query.literal.range)
+            .map(|source| StringSourceMap::from_string_literal(&source, query.literal.range.start(), query.literal))
+            .filter(|map| map.value() == query.literal.value.to_str());
+        for column in SqlView::get_columns_without_field(&fields, &columns) {
+            let range = map.as_ref()
+                .and_then(|map| map.source_range(query.start + column.range.start..query.start + column.range.end))
+                .unwrap_or(query.literal.range);
+            self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::HINT, "OLS10006",
+                format!("The column {} of the view is not a field of the model", column.name)));
+        }
+    }
+
+    /* Check that the methods of a model named like compute, inverse or search methods are referenced by the classes of
+    the model. The closest compute argument of the fields is given as related information, as it is often the previous
+    name of the method */
+    fn _check_unreferenced_methods(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().filter(|m| !m.unknown_name).map(|m| m.name.clone()) else {
+            return;
+        };
+        let methods = MethodReferences::get_field_methods(body);
+        if methods.is_empty() {
+            return;
+        }
+        let Some(model) = session.sync_odoo.models.get(&model_name).cloned() else {
+            return;
+        };
+        let current_uri = self.get_file_info(session.sync_odoo).borrow().uri.clone();
+        let mut references = MethodReferences::new();
+        references.add_body(body);
+        let mut computes: Vec<(String, Location)> = vec![];
+        let class_symbols = model.borrow().all_symbols(session, None);
+        for (class_symbol, _) in class_symbols.iter() {
+            let Some(file_info) = FileMgr::get_file_info_of_symbol(session, class_symbol) else {
+                continue;
+            };
+            let file_info = file_info.borrow();
+            let class_range = *class_symbol.borrow().range();
+            let Some(class_def) = file_info.ast.iter().flatten().find_map(|stmt| match stmt {
+                Stmt::ClassDef(c) if c.range == class_range => Some(c),
+                _ => None
+            }) else {
+                continue;
+            };
+            references.add_body(&class_def.body);
+            for field in class_def.body.iter().filter_map(FieldDeclaration::from_stmt) {
+                let Some((Some(compute), range)) = field.compute else {
+                    continue;
+                };
+                //the offsets of the current file are converted when the diagnostics are published
+                let range = match file_info.uri == current_uri {
+                    true => Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    false => Range::new(file_info.offset_to_position(range.start().to_usize()), file_info.offset_to_position(range.end().to_usize())),
+                };
+                computes.push((compute, Location::new(FileMgr::pathname2uri(&file_info.uri), range)));
+            }
+        }
+        //the framework methods, like _compute_display_name, are referenced by the fields of BaseModel
+        let base_model = session.sync_odoo.get_symbol(&tree(vec!["odoo", "models"], vec!["BaseModel"]), u32::MAX).first().cloned();
+        for method in methods.into_iter() {
+            let name = method.name.as_str();
+            if references.is_referenced(name) || base_model.as_ref().is_some_and(|b| !b.borrow().get_content_symbol(name, u32::MAX).is_empty()) {
+                continue;
+            }
+            if self._is_suppressed(session, &method.name.range(), "OLS20021") {
+                continue;
+            }
+            let mut diagnostic = PythonValidator::_create_diagnostic(&method.name.range(), DiagnosticSeverity::WARNING, "OLS20021",
+                format!("{} is not referenced by any field of {}", name, model_name));
+            if let Some(closest) = MethodReferences::find_closest(name, computes.iter().map(|(compute, _)| compute.as_str())) {
+                let location = computes.iter().find(|(compute, _)| compute == closest).unwrap().1.clone();
+                diagnostic.related_information = Some(vec![DiagnosticRelatedInformation {
+                    location,
+                    message: format!("compute='{}' is the closest compute argument", closest),
+                }]);
+            }
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /* Return true if the line where the range starts has a noqa comment for the code (see Suppression) */
+    fn _is_suppressed(&mut self, session: &mut SessionInfo, range: &TextRange, code: &str) -> bool {
+        let file_info = self.get_file_info(session.sync_odoo);
+        let file_info = file_info.borrow();
+        let Some(rope) = file_info.get_rope() else {
+            return false;
+        };
+        let line = rope.byte_to_line(range.start().to_usize());
+        Suppression::is_suppressed(&rope.line(line).to_string(), code)
+    }
+
+    /* Check the metadata attributes written in the body of a model class against the rules of the Odoo registry */
+    fn _check_model_metadata(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, c: &StmtClassDef) {
+        let Some((model_name, model_description, transient)) = class.borrow().as_class_sym()._model.as_ref().filter(|m| !m.unknown_name)
+            .map(|m| (m.name.clone(), m.description.clone(), m.transient)) else {
+            return;
+        };
+        let metadata = ModelMetadata::from_body(&c.body);
+        //a python parent class can give the description, that is then different from the default one
+        if metadata.is_missing_description() && model_description == model_name {
+            self.diagnostics.push(PythonValidator::_create_diagnostic(&c.name.range(), DiagnosticSeverity::WARNING, "OLS20008",
+                format!("The model {} has no _description", model_name)));
+        }
+        if let (true, Some((description, range))) = (metadata.is_extension(), metadata.description.as_ref()) {
+            if let Some(original) = self._get_original_description(session, &model_name, class) {
+                if original != *description {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::WARNING, "OLS20009",
+                        format!("The description of {} is changed from '{}' to '{}' by an extension of the model", model_name, original, description)));
+                }
+            }
+        }
+        if let Some((table, range)) = metadata.table.as_ref() {
+            if let Some(other) = self._find_model_with_table(session, table, &model_name) {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::WARNING, "OLS20010",
+                    format!("The table {} is already used by the model {}", table, other)));
+            }
+        }
+        if let Some(range) = metadata.get_auto_without_init() {
+            let (inits, _) = class.borrow().get_member_symbol(session, &S!("init"), self.current_module.clone(), false, false, false, false);
+            //BaseModel.init does nothing
+            let has_init = inits.iter().any(|init| !init.borrow().parent().and_then(|p| p.upgrade())
+                .is_some_and(|p| p.borrow().get_tree().0 == vec![S!("odoo"), S!("models")]));
+            if !has_init {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::WARNING, "OLS20011",
+                    format!("_auto is False, but {} doesn't define init() to create its table or view", model_name)));
+            }
+        }
+        if !transient {
+            for (attribute, range) in metadata.transient_attributes.iter() {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::WARNING, "OLS20012",
+                    format!("{} is only used by transient models, but {} is not transient", attribute, model_name)));
+            }
+        }
+    }
+
+    /* Check the calls reading or writing the table of a model given by env['model'], that fail if the model is abstract.
+    The calls on self are not checked, as the methods of an abstract model are called on the models inheriting it */
+    fn _check_abstract_calls(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for call in AstUtils::find_calls(expr) {
+            let Expr::Attribute(func) = &*call.func else {
+                continue;
+            };
+            if !TABLE_METHODS.contains(&func.attr.as_str()) {
+                continue;
+            }
+            let Some(literal) = ModelKind::get_env_model_name(&func.value) else {
+                continue;
+            };
+            let model_name = literal.value.to_string();
+            let Some(model) = session.sync_odoo.models.get(&model_name).cloned() else {
+                continue;
+            };
+            let kind = model.borrow().get_kind(session, self.current_module.clone());
+            if let Some((ModelKind::ABSTRACT, _)) = kind {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&func.range, DiagnosticSeverity::WARNING, "OLS20024",
+                    format!("{} is an abstract model: it has no table, and {}() can't be called on it", model_name, func.attr)));
+            }
+        }
+    }
+
+    /* Check the names built at runtime and given to getattr on self, like "_get_%s_values" % provider: some methods of
+    the model, declared by any module, must match them. A getattr with a default value expects missing methods */
+    fn _check_dynamic_getattrs(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for call in AstUtils::find_calls(expr) {
+            let Some(pattern) = DynamicDispatch::get_getattr_pattern(call) else {
+                continue;
+            };
+            if call.arguments.args.len() > 2 {
+                continue;
+            }
+            let Some(class) = self._get_model_of_expr(session, &call.arguments.args[0], &call.range.start()) else {
+                continue;
+            };
+            let Some(model_name) = class.borrow().as_class_sym()._model.as_ref().map(|m| m.name.clone()) else {
+                continue;
+            };
+            let mut members: HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>> = HashMap::new();
+            Symbol::all_members(&class, session, &mut members, true, None, &mut None, false);
+            let matched = members.iter().any(|(name, symbols)| pattern.matches(name) &&
+                symbols.iter().any(|(symbol, _)| symbol.borrow().typ() == SymType::FUNCTION));
+            if !matched {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&call.arguments.args[1].range(), DiagnosticSeverity::HINT, "OLS10005",
+                    format!("No method of {} matches {}, the name given to getattr", model_name, pattern.label())));
+            }
+        }
+    }
+
+    /* Check the keys read with context.get('key'), that should be set somewhere (see ContextKeyIndex), and the fields of
+    the 'default_' keys given to with_context, when the records are created right after: env['model'].with_context(...).create() */
+    fn _check_context_keys(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for call in AstUtils::find_calls(expr) {
+            if let Some(literal) = ContextKeys::get_read_key(call) {
+                let key = literal.value.to_str();
+                if !self._is_set_context_key(session, key) {
+                    let mut candidates: Vec<String> = session.sync_odoo.get_file_mgr().borrow().context_keys.keys().cloned().collect();
+                    candidates.extend(KNOWN_CONTEXT_KEYS.iter().map(|known| known.to_string()));
+                    candidates.sort();
+                    let message = match MethodReferences::find_closest(key, candidates.iter().map(|candidate| candidate.as_str())) {
+                        Some(closest) => format!("{} is never set in the context. Did you mean {}?", key, closest),
+                        None => format!("{} is never set in the context", key)
+                    };
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&literal.range, DiagnosticSeverity::HINT, "OLS10008", message));
+                }
+            }
+            let Expr::Attribute(func) = &*call.func else {
+                continue;
+            };
+            let Expr::Call(with_context) = &*func.value else {
+                continue;
+            };
+            if !["create", "new", "default_get"].contains(&func.attr.as_str()) {
+                continue;
+            }
+            let keys = ContextKeys::get_with_context_keys(with_context);
+            if !keys.iter().any(|(key, _)| ContextKeys::get_default_field(key).is_some()) {
+                continue;
+            }
+            let Expr::Attribute(with_context_func) = &*with_context.func else {
+                continue;
+            };
+            let Some(model) = self._get_model_of_expr(session, &with_context_func.value, &with_context.range.start()) else {
+                continue;
+            };
+            let Some(model_name) = model.borrow().as_class_sym()._model.as_ref().map(|m| m.name.clone()) else {
+                continue;
+            };
+            if model.borrow().has_dynamic_fields(session, self.current_module.clone()) {
+                continue;
+            }
+            for (key, range) in keys.iter() {
+                let Some(field) = ContextKeys::get_default_field(key) else {
+                    continue;
+                };
+                let (symbols, _) = model.borrow().get_member_symbol(session, &field.to_string(), self.current_module.clone(), false, true, false, false);
+                if symbols.is_empty() {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::WARNING, "OLS20028",
+                        format!("{} is not a field of {}: the default value given by {} is not used", field, model_name, key)));
+                }
+            }
+        }
+    }
+
+    /* Return true if the key is set by the framework, by a python file (see ContextKeyIndex) or by the context of a view */
+    fn _is_set_context_key(&self, session: &mut SessionInfo, key: &str) -> bool {
+        if ContextKeys::is_builtin_key(key) || session.sync_odoo.get_file_mgr().borrow().context_keys.contains(key) {
+            return true;
+        }
+        session.sync_odoo.modules.values().filter_map(|module| module.upgrade())
+            .any(|module| module.borrow().as_module_package().xml_field_names.contains(key))
+    }
+
+    /* Check the class against the kind of its model: a persistent class can't extend a transient model, and the One2many
+    of a transient model can't point to a persistent model, as its inverse Many2one would point from a persistent model to
+    a transient one, which Odoo forbids */
+    fn _check_model_kind(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, c: &StmtClassDef) {
+        let Some((model_name, own_kind)) = class.borrow().as_class_sym()._model.as_ref().filter(|m| !m.unknown_name)
+            .map(|m| (m.name.clone(), ModelKind::from_data(m.is_abstract, m.transient))) else {
+            return;
+        };
+        let metadata = ModelMetadata::from_body(&c.body);
+        if let (ModelKind::MODEL, Some((inherit, range))) = (own_kind, metadata.inherit.as_ref()) {
+            for parent in inherit.iter() {
+                let Some(model) = session.sync_odoo.models.get(parent).cloned() else {
+                    continue;
+                };
+                if let Some((ModelKind::TRANSIENT, _)) = model.borrow().get_kind(session, self.current_module.clone()) {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::ERROR, "OLS30341",
+                        format!("{} is a persistent model, but inherits the transient model {}. Use models.TransientModel", c.name, parent)));
+                }
+            }
+        }
+        //an extension has the kind of the model it extends
+        let kind = if metadata.is_extension() {
+            let Some(model) = session.sync_odoo.models.get(&model_name).cloned() else {
+                return;
+            };
+            let kind = model.borrow().get_kind(session, self.current_module.clone());
+            match kind {
+                Some((kind, _)) => kind,
+                None => return
+            }
+        } else {
+            own_kind
+        };
+        if kind != ModelKind::TRANSIENT {
+            return;
+        }
+        for stmt in c.body.iter() {
+            let Stmt::Assign(assign) = stmt else {
+                continue;
+            };
+            let (Some(target), Expr::Call(call)) = (assign.targets.first().and_then(|t| t.as_name_expr()), &*assign.value) else {
+                continue;
+            };
+            let is_one2many = match &*call.func {
+                Expr::Attribute(attr) => attr.attr.as_str() == "One2many",
+                Expr::Name(name) => name.id.as_str() == "One2many",
+                _ => false
+            };
+            if !is_one2many {
+                continue;
+            }
+            let comodel = match call.arguments.find_keyword("comodel_name") {
+                Some(keyword) => &keyword.value,
+                None => match call.arguments.args.first() {
+                    Some(arg) => arg,
+                    None => continue
+                }
+            };
+            let Expr::StringLiteral(comodel) = comodel else {
+                continue;
+            };
+            let comodel_name = comodel.value.to_string();
+            let Some(comodel) = session.sync_odoo.models.get(&comodel_name).cloned() else {
+                continue;
+            };
+            if let Some((ModelKind::MODEL, _)) = comodel.borrow().get_kind(session, self.current_module.clone()) {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&target.range, DiagnosticSeverity::WARNING, "OLS20025",
+                    format!("{} is a One2many of the transient model {} to the persistent model {}: its inverse Many2one can't point to a transient model. Use a Many2many instead",
+                        target.id, model_name, comodel_name)));
+            }
+        }
+    }
+
+    /* Return the description given by the classes that create the model, if they are in the dependencies of the current module */
+    fn _get_original_description(&self, session: &mut SessionInfo, model_name: &String, class: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let model = session.sync_odoo.models.get(model_name).cloned()?;
+        let main_symbols = model.borrow().get_main_symbols(session, self.current_module.clone(), &mut None);
+        main_symbols.iter().filter(|s| !Rc::ptr_eq(s, class))
+            .filter_map(|s| s.borrow().as_class_sym()._model.as_ref().map(|m| m.description.clone()))
+            .next()
+    }
+
+    /* Return the name of another model, visible from the current module, whose table is table */
+    fn _find_model_with_table(&self, session: &mut SessionInfo, table: &String, model_name: &String) -> Option<String> {
+        let mut models: Vec<(String, Rc<RefCell<Model>>)> = session.sync_odoo.models.iter()
+            .filter(|(name, _)| *name != model_name)
+            .map(|(name, model)| (name.clone(), model.clone()))
+            .collect();
+        models.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, model) in models.iter() {
+            let main_symbols = model.borrow().get_main_symbols(session, self.current_module.clone(), &mut None);
+            let uses_table = main_symbols.iter().any(|s| s.borrow().as_class_sym()._model.as_ref()
+                .is_some_and(|m| !m.is_abstract && m.table == *table));
+            if uses_table {
+                return Some(name.clone());
+            }
+        }
+        None
+    }
+
+    /* Check the extensions of Selection fields (selection_add and ondelete) against the fields they extend */
+    /* Check the comodel of the fields whose class is a field rule of the plugin rules with a comodel_keyword. The
+    comodel is given by this keyword or by the first argument, like for fields.Many2one */
+    fn _check_plugin_fields(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        if session.sync_odoo.plugin_rules.is_empty() {
+            return;
+        }
+        for stmt in body.iter() {
+            let Stmt::Assign(assign) = stmt else {
+                continue;
+            };
+            let Expr::Call(call) = &*assign.value else {
+                continue;
+            };
+            let (evals, _) = Evaluation::eval_from_ast(session, &call.func, class.clone(), &call.range.start());
+            let mut comodel_keyword = None;
+            for eval in evals.iter() {
+                let Some(field_class) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade() else {
+                    continue;
+                };
+                if field_class.borrow().typ() != SymType::CLASS {
+                    continue;
+                }
+                if let Some(keyword) = session.sync_odoo.plugin_rules.get_field_rule(&field_class).and_then(|r| r.comodel_keyword.clone()) {
+                    comodel_keyword = Some(keyword);
+                    break;
+                }
+            }
+            let Some(comodel_keyword) = comodel_keyword else {
+                continue;
+            };
+            let comodel = match call.arguments.find_keyword(&comodel_keyword) {
+                Some(keyword) => &keyword.value,
+                None => match call.arguments.args.first() {
+                    Some(arg) => arg,
+                    None => continue
+                }
+            };
+            let Expr::StringLiteral(comodel) = comodel else {
+                continue;
+            };
+            let comodel_name = comodel.value.to_string();
+            if !session.sync_odoo.models.contains_key(&comodel_name) {
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&comodel.range, DiagnosticSeverity::ERROR, "OLS30329",
+                    format!("Unknown model {}. Check your addons path", comodel_name)));
+            }
+        }
+    }
+
+    /* Check the arguments of the decorators of odoo.api that are given as strings, and the methods they decorate:
+    the model of api.returns, the context keys of api.depends_context, and the signature of api.ondelete methods */
+    fn _check_api_decorators(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let is_model = class.borrow().as_class_sym()._model.is_some();
+        let mut used_context_keys = None;
+        for stmt in body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            if let Some((_, Some(call))) = ApiDecorators::find(&func.decorator_list, "returns") {
+                if let Some(model) = ApiDecorators::get_returns_model(call) {
+                    let model_name = model.value.to_string();
+                    if model_name != "self" && !session.sync_odoo.models.contains_key(&model_name) {
+                        let range = self._get_string_value_range(session, model);
+                        self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::ERROR, "OLS30329",
+                            format!("Unknown model {}. Check your addons path", model_name)));
+                    }
+                }
+            }
+            if let Some((_, Some(call))) = ApiDecorators::find(&func.decorator_list, "depends_context") {
+                let used_keys = used_context_keys.get_or_insert_with(|| ApiDecorators::get_used_context_keys(body));
+                let mut unknown_keys = vec![];
+                for key in ApiDecorators::get_context_keys(call).into_iter() {
+                    if !ApiDecorators::is_known_context_key(key.value.to_str(), used_keys) {
+                        unknown_keys.push(key);
+                    }
+                }
+                for key in unknown_keys.into_iter() {
+                    let range = self._get_string_value_range(session, key);
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::WARNING, "OLS30331",
+                        format!("{} is not a known context key", key.value.to_str())));
+                }
+            }
+            if let Some((decorator, call)) = ApiDecorators::find(&func.decorator_list, "ondelete") {
+                if !call.is_some_and(|call| call.arguments.find_keyword("at_uninstall").is_some()) {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&decorator.range(), DiagnosticSeverity::ERROR, "OLS30332",
+                        S!("api.ondelete requires the keyword argument at_uninstall")));
+                }
+                let parameters = &func.parameters;
+                let required_parameters = parameters.posonlyargs.iter().chain(parameters.args.iter()).skip(1)
+                    .chain(parameters.kwonlyargs.iter())
+                    .filter(|p| p.default.is_none()).count();
+                if !is_model {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&func.name.range(), DiagnosticSeverity::ERROR, "OLS30333",
+                        format!("{} is decorated with api.ondelete, but is not a method of a model", func.name.as_str())));
+                } else if required_parameters > 0 || parameters.posonlyargs.len() + parameters.args.len() == 0 {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&func.name.range(), DiagnosticSeverity::ERROR, "OLS30333",
+                        format!("{} is decorated with api.ondelete: it is called on the deleted records without arguments", func.name.as_str())));
+                }
+            }
+        }
+    }
+
+    /* Check the classes added to several models (_inherit lists several models, without _name): the attributes of self
+    used by the methods must be members of all these models, and the members of the class must keep the kind (field or
+    method) they have in each of them. The diagnostics name the model that doesn't match */
+    fn _check_injected_members(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let model_names: Vec<String> = match class.borrow().as_class_sym()._model.as_ref() {
+            Some(model_data) if !model_data.other_names.is_empty() => model_data.names().cloned().collect(),
+            _ => return
+        };
+        let Some(from_module) = self.current_module.clone() else {
+            return;
+        };
+        for stmt in body.iter() {
+            let (name, range) = match stmt {
+                Stmt::FunctionDef(f) => (f.name.to_string(), f.name.range()),
+                Stmt::Assign(a) => match a.targets.first().and_then(|t| t.as_name_expr()) {
+                    Some(target) => (target.id.to_string(), target.range()),
+                    None => continue
+                },
+                Stmt::AnnAssign(a) => match a.target.as_name_expr() {
+                    Some(target) => (target.id.to_string(), target.range()),
+                    None => continue
+                },
+                _ => continue
+            };
+            let Some(member) = class.borrow().get_sub_symbol(&name, u32::MAX).last().cloned() else {
+                continue;
+            };
+            let is_field = member.borrow().is_field(session);
+            for model_name in model_names.iter() {
+                let Some(other) = PythonValidator::_find_model_member(session, class, model_name, &name, &from_module) else {
+                    continue;
+                };
+                let other_is_field = other.borrow().is_field(session);
+                let other_is_method = other.borrow().typ() == SymType::FUNCTION;
+                if is_field && other_is_method {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::WARNING, "OLS30335",
+                        format!("{} is a method of {}, but is redefined as a field", name, model_name)));
+                } else if !is_field && member.borrow().typ() == SymType::FUNCTION && other_is_field {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&range, DiagnosticSeverity::WARNING, "OLS30335",
+                        format!("{} is a field of {}, but is redefined as a method", name, model_name)));
+                }
+            }
+        }
+        for stmt in body.iter() {
+            let Stmt::FunctionDef(func) = stmt else {
+                continue;
+            };
+            let Some(self_name) = func.parameters.posonlyargs.iter().chain(func.parameters.args.iter()).next().map(|p| p.parameter.name.id.to_string()) else {
+                continue;
+            };
+            let mut checked = HashSet::new();
+            for attribute in AstUtils::find_attributes_of(&func.body, &self_name) {
+                let attr = attribute.attr.to_string();
+                if !checked.insert(attr.clone()) {
+                    continue;
+                }
+                //members of the class itself and of its bases are added to all the models
+                if !class.borrow().get_member_symbol(session, &attr, None, true, false, false, false).0.is_empty() {
+                    continue;
+                }
+                //the recordsets of the class are unions of the models
+                let declared: Vec<bool> = model_names.iter()
+                    .map(|model_name| PythonValidator::_find_model_member(session, class, model_name, &attr, &from_module).is_some())
+                    .collect();
+                //a member that is unknown on all the models is not specific to one of them
+                let MemberPresence::SOME(missing) = UnionMembers::get_presence(&declared) else {
+                    continue;
+                };
+                for model_name in missing.iter().map(|index| &model_names[*index]) {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&attribute.attr.range, DiagnosticSeverity::WARNING, "OLS30334",
+                        format!("{} is not a member of {}. This class is added to the models {}", attr, model_name, model_names.join(", "))));
+                }
+            }
+        }
+    }
+
+    /* Return the member 'name' of the model, declared by another class of the model than 'class', visible from the module */
+    fn _find_model_member(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, model_name: &String, name: &String, from_module: &Rc<RefCell<Symbol>>) -> Option<Rc<RefCell<Symbol>>> {
+        let model = session.sync_odoo.models.get(model_name).cloned()?;
+        let symbols: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_symbols(session, from_module.clone()).collect();
+        for symbol in symbols.iter().filter(|s| !Rc::ptr_eq(s, class)) {
+            let members = symbol.borrow().get_member_symbol(session, name, None, true, false, false, false).0;
+            if let Some(member) = members.first() {
+                return Some(member.clone());
+            }
+        }
+        None
+    }
+
+    fn _check_selection_fields(&mut self, session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, body: &Vec<Stmt>) {
+        let Some(model_names) = class.borrow().as_class_sym()._model.as_ref().map(|m| m.names().cloned().collect::<Vec<String>>()) else {
+            return;
+        };
+        let Some(model_name) = model_names.first() else {
+            return; //unknown model name
+        };
+        for stmt in body.iter() {
+            let Stmt::Assign(assign) = stmt else {
+                continue;
+            };
+            let (Some(target), Some(call)) = (assign.targets.first().and_then(|t| t.as_name_expr()), SelectionField::as_selection_call(&assign.value)) else {
+                continue;
+            };
+            if SelectionField::get_keyword(call, "selection_add").is_none() {
+                continue;
+            }
+            let field_name = target.id.to_string();
+            let entries = SelectionField::get_selection_add(call);
+            //a class added to several models extends the field of each of them
+            for other_name in model_names.iter().skip(1) {
+                if SelectionField::get_model_selection(session, other_name, &field_name, self.current_module.clone(), Some(class)) == ModelSelection::NOT_FOUND
+                    && class.borrow().get_member_symbol(session, &field_name, None, true, true, false, false).0.iter()
+                        .all(|m| m.borrow().parent().and_then(|p| p.upgrade()).is_some_and(|p| Rc::ptr_eq(&p, class))) {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&target.range, DiagnosticSeverity::ERROR, "OLS30321",
+                        format!("selection_add is used on {}, but this field is not defined on {}", field_name, other_name)));
+                }
+            }
+            //a field that is defined and extended in the same declaration doesn't depend on other classes
+            let base = match SelectionField::get_selection_expr(call) {
+                Some(selection) => match SelectionField::get_keys(session, class, selection) {
+                    Some(keys) => ModelSelection::KEYS(keys, SelectionField::is_required(call)),
+                    None => ModelSelection::UNKNOWN
+                },
+                None => SelectionField::get_model_selection(session, model_name, &field_name, self.current_module.clone(), Some(class))
+            };
+            let (base_keys, required) = match base {
+                ModelSelection::NOT_FOUND => {
+                    //the field can come from a parent model (_inherits, mixins), that is not checked
+                    let members = class.borrow().get_member_symbol(session, &field_name, None, false, true, false, false).0;
+                    if members.iter().any(|m| !m.borrow().parent().and_then(|p| p.upgrade()).is_some_and(|p| Rc::ptr_eq(&p, class))) {
+                        continue;
+                    }
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&target.range, DiagnosticSeverity::ERROR, "OLS30321",
+                        format!("selection_add is used on {}, but this field is not defined on {}", field_name, model_name)));
+                    continue;
+                },
+                ModelSelection::NOT_SELECTION => {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&target.range, DiagnosticSeverity::ERROR, "OLS30322",
+                        format!("selection_add is used on {}, but this field is not a Selection field", field_name)));
+                    continue;
+                },
+                ModelSelection::UNKNOWN => (None, false),
+                ModelSelection::KEYS(keys, required) => (Some(keys), required || SelectionField::is_required(call)),
+            };
+            let mut merged_keys = base_keys.clone().unwrap_or_default();
+            let mut added_keys = vec![];
+            for entry in entries.iter() {
+                match entry {
+                    SelectionAddEntry::ANCHOR(key, range) => {
+                        if base_keys.is_some() && !merged_keys.contains(key) {
+                            self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::ERROR, "OLS30323",
+                                format!("{} is not a key of the selection of {}", key, field_name)));
+                        }
+                    },
+                    SelectionAddEntry::ADD(key, range) => {
+                        if !base_keys.as_ref().is_some_and(|keys| keys.contains(key)) {
+                            added_keys.push((key.clone(), *range));
+                        }
+                    }
+                }
+                SelectionField::merge(&mut merged_keys, &vec![entry.clone()]);
+            }
+            self._check_ondelete(session, call, &field_name, &added_keys, &merged_keys, base_keys.is_some(), required);
+        }
+    }
+
+    /* Check that the ondelete policies of the added keys are valid. Since Odoo 14, required fields must give a policy for
+    each added key, as the records using it can't be reset when the module is uninstalled */
+    fn _check_ondelete(&mut self, session: &mut SessionInfo, call: &ExprCall, field_name: &String, added_keys: &Vec<(String, TextRange)>, merged_keys: &Vec<String>, keys_known: bool, required: bool) {
+        let ondelete = SelectionField::get_ondelete(call);
+        if required && session.sync_odoo.version_major >= 14 {
+            for (key, range) in added_keys.iter() {
+                let has_policy = ondelete.is_some_and(|d| d.items.iter().any(|item| matches!(&item.key, Some(Expr::StringLiteral(k)) if k.value.to_str() == key.as_str())));
+                if !has_policy {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(range, DiagnosticSeverity::ERROR, "OLS30324",
+                        format!("{} is required: an ondelete policy must be given for the added key {}", field_name, key)));
+                }
+            }
+        }
+        let Some(ondelete) = ondelete else {
+            return;
+        };
+        for item in ondelete.items.iter() {
+            let Some(Expr::StringLiteral(key)) = &item.key else {
+                continue;
+            };
+            if !added_keys.iter().any(|(k, _)| k.as_str() == key.value.to_str()) {
+                if keys_known {
+                    self.diagnostics.push(PythonValidator::_create_diagnostic(&key.range, DiagnosticSeverity::ERROR, "OLS30325",
+                        format!("{} is not a key added by selection_add", key.value.to_str())));
+                }
+                continue;
+            }
+            //other values are expected to be callables
+            let Expr::StringLiteral(policy) = &item.value else {
+                continue;
+            };
+            let policy_value = policy.value.to_str();
+            let is_valid = if policy_value == "set null" {
+                !required
+            } else {
+                ONDELETE_POLICIES.contains(&policy_value) || policy_value.strip_prefix("set ").is_some_and(|k| !keys_known || merged_keys.iter().any(|m| m.as_str() == k))
+            };
+            if !is_valid {
+                let message = if policy_value == "set null" {
+                    format!("'set null' can't be used on the required field {}", field_name)
+                } else {
+                    format!("Invalid ondelete policy '{}'. Expected 'set null', 'cascade', 'set default', 'set <key>' or a callable", policy_value)
+                };
+                self.diagnostics.push(PythonValidator::_create_diagnostic(&policy.range, DiagnosticSeverity::ERROR, "OLS30325", message));
+            }
+        }
+    }
+
+    fn _create_diagnostic(range: &TextRange, severity: DiagnosticSeverity, code: &str, message: String) -> Diagnostic {
+        Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(severity),
+            Some(NumberOrString::String(S!(code))),
+            Some(EXTENSION_NAME.to_string()),
+            message,
+            None,
+            None,
+        )
+    }
+
+    /* Return the range of the value of a string literal, without its prefix and quotes */
+    fn _get_string_value_range(&mut self, session: &mut SessionInfo, literal: &ExprStringLiteral) -> TextRange {
+        let file_info = self.get_file_info(session.sync_odoo);
+        let Some(source) = file_info.borrow().get_text(&literal.range) else {
+            return literal.range;
+        };
+        let map = StringSourceMap::from_string_literal(&source, literal.range.start(), literal);
+        if map.value() != literal.value.to_str() {
+            return literal.range; //the literal doesn't come from this file
+        }
+        map.source_range(0..map.value().len()).unwrap_or(literal.range)
+    }
+
     fn _check_module_dependency(&mut self, session: &mut SessionInfo, model: &String, range: &TextRange) {
         if let Some(from) = self.current_module.as_ref() {
             let model = session.sync_odoo.models.get(model);
@@ -371,8 +1544,70 @@ impl PythonValidator {
         }
     }
 
+    /* Report the methods called by the data files of the module that don't exist on the manifest, as the data files
+    are not validated themselves, the hooks of the manifest that are not functions of the module, and its external
+    dependencies that are not installed */
+    fn _validate_module_data(&mut self, session: &mut SessionInfo) {
+        let module = self.sym_stack[0].clone();
+        let mut diagnostics = ModuleSymbol::check_xml_method_references(session, &module);
+        diagnostics.extend(ModuleSymbol::check_manifest_hooks(session, &module));
+        diagnostics.extend(ModuleSymbol::check_external_dependencies(session, &module));
+        let manifest_path = PathBuf::from(module.borrow().as_module_package().path.clone()).join("__manifest__.py").sanitize();
+        let Some(manifest_file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&manifest_path) else {
+            return;
+        };
+        let mut manifest_file_info = manifest_file_info.borrow_mut();
+        manifest_file_info.replace_diagnostics(BuildSteps::VALIDATION, diagnostics);
+        manifest_file_info.schedule_publication(session);
+    }
+
+    /* Check the xml ids given to env.ref against the data files of their module. Only the modules of the workspace are
+    checked, and the ids generated by Odoo (model_*, field_*...) are skipped */
+    fn _check_xml_refs(&mut self, session: &mut SessionInfo, expr: &Expr) {
+        for call in AstUtils::find_calls(expr) {
+            let Expr::Attribute(func) = &*call.func else {
+                continue;
+            };
+            let on_env = match &*func.value {
+                Expr::Name(name) => name.id.as_str() == "env",
+                Expr::Attribute(attr) => attr.attr.as_str() == "env",
+                _ => false
+            };
+            if func.attr.as_str() != "ref" || !on_env {
+                continue;
+            }
+            let Some(Expr::StringLiteral(literal)) = call.arguments.args.first() else {
+                continue;
+            };
+            let xml_id = literal.value.to_string();
+            let Some((module_name, local_id)) = xml_id.split_once('.') else {
+                continue;
+            };
+            if GENERATED_XML_ID_PREFIXES.iter().any(|prefix| local_id.starts_with(prefix)) {
+                continue;
+            }
+            let Some(module) = session.sync_odoo.modules.get(module_name).and_then(|module| module.upgrade()) else {
+                continue;
+            };
+            let module = module.borrow();
+            if !module.in_workspace() || module.as_module_package().has_xml_id(&xml_id) != Some(false) {
+                continue;
+            }
+            self.diagnostics.push(PythonValidator::_create_diagnostic(&literal.range(), DiagnosticSeverity::WARNING, "OLS20019",
+                format!("{} is not declared by the data files of the module {}", xml_id, module_name)));
+        }
+    }
+
     fn validate_expr(&mut self, session: &mut SessionInfo, expr: &Expr, max_infer: &TextSize) {
         let (eval, diags) = Evaluation::eval_from_ast(session, expr, self.sym_stack.last().unwrap().clone(), max_infer);
         self.diagnostics.extend(diags);
+        self._check_deprecated_calls(session, expr);
+        self._check_field_name_args(session, expr);
+        self._check_selection_comparisons(session, expr);
+        self._check_date_comparisons(session, expr);
+        self._check_abstract_calls(session, expr);
+        self._check_dynamic_getattrs(session, expr);
+        self._check_context_keys(session, expr);
+        self._check_xml_refs(session, expr);
     }
 }
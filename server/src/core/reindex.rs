@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
+
+use lsp_types::{MessageType, TextDocumentContentChangeEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::file_mgr::FileMgr;
+use crate::core::odoo::SyncOdoo;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer;
+
+pub const REINDEX_COMMAND: &str = "odooLs.reindex";
+
+/* Part of the index rebuilt by the reindex command, given as its first argument: {"scope": "module", "module": "sale"} */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "scope")]
+#[allow(non_camel_case_types)]
+pub enum ReindexScope {
+    #[serde(rename = "file")]
+    FILE { uri: String },
+    #[serde(rename = "module")]
+    MODULE { module: String },
+    #[serde(rename = "addonsPath")]
+    ADDONS_PATH { path: String },
+    #[serde(rename = "externals")]
+    EXTERNALS, //the packages of the python path, except odoo and the builtins
+    #[serde(rename = "all")]
+    ALL,
+}
+
+impl ReindexScope {
+
+    /* Read the scope from the arguments of the command. Without argument, everything is rebuilt */
+    pub fn from_arguments(arguments: &Vec<Value>) -> Result<ReindexScope, String> {
+        match arguments.first() {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| format!("Invalid scope for {}: {}", REINDEX_COMMAND, e)),
+            None => Ok(ReindexScope::ALL),
+        }
+    }
+}
+
+/* Result of the reindex command */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReindexSummary {
+    pub scope: ReindexScope,
+    pub files_rebuilt: usize, //files of the scope in the cache after the rebuild
+    pub duration: u64, //in milliseconds
+}
+
+/* Unload the symbols of a scope, and build them again from the disk. The diagnostics and the registries are cleaned
+by the unload, and the opened files keep the content of the editor. The command is handled by the main thread like
+the file events, so it waits for the end of the running rebuild instead of running along it */
+pub struct Reindexer {}
+
+impl Reindexer {
+
+    pub fn reindex(session: &mut SessionInfo, scope: ReindexScope) -> Result<ReindexSummary, String> {
+        let start = Instant::now();
+        session.log_message(MessageType::INFO, format!("Reindexing {:?}", scope));
+        let roots = match &scope {
+            ReindexScope::ALL => {
+                SyncOdoo::reset(session, session.sync_odoo.config.clone());
+                None
+            },
+            _ => {
+                session.send_notification("$Odoo/loadingStatusUpdate", "start");
+                let roots = Reindexer::reindex_scope(session, &scope);
+                session.send_notification("$Odoo/loadingStatusUpdate", "stop");
+                Some(roots?)
+            }
+        };
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let files_rebuilt = file_mgr.borrow().files.keys().filter(|path| {
+            roots.as_ref().map_or(true, |roots| roots.iter().any(|root| FileMgr::is_path_under(path, root)))
+        }).count();
+        let summary = ReindexSummary {
+            scope,
+            files_rebuilt,
+            duration: start.elapsed().as_millis() as u64,
+        };
+        session.log_message(MessageType::INFO, format!("Reindex done in {} ms: {} file(s) rebuilt", summary.duration, summary.files_rebuilt));
+        Ok(summary)
+    }
+
+    /* Rebuild a scope that is not the whole index, and return the paths it covers */
+    fn reindex_scope(session: &mut SessionInfo, scope: &ReindexScope) -> Result<Vec<String>, String> {
+        let roots = match scope {
+            ReindexScope::FILE { uri } => {
+                vec![FileMgr::uri2pathname(uri)]
+            },
+            ReindexScope::MODULE { module } => {
+                let Some(module_symbol) = session.sync_odoo.modules.get(module).and_then(|m| m.upgrade()) else {
+                    return Err(format!("Module {} is not loaded", module));
+                };
+                module_symbol.borrow().paths()
+            },
+            ReindexScope::ADDONS_PATH { path } => {
+                let path = PathBuf::from(path).sanitize();
+                if !session.sync_odoo.config.addons.contains(&path) {
+                    return Err(format!("{} is not an addons path", path));
+                }
+                session.sync_odoo.modules.values().filter_map(|m| m.upgrade())
+                    .flat_map(|m| m.borrow().paths())
+                    .filter(|module_path| FileMgr::is_path_under(module_path, &path))
+                    .collect()
+            },
+            ReindexScope::EXTERNALS => {
+                Reindexer::get_externals(session).iter().flat_map(|s| s.borrow().paths()).collect()
+            },
+            ReindexScope::ALL => unreachable!(),
+        };
+        let opened_files = Reindexer::get_opened_files(session, &roots);
+        if let ReindexScope::EXTERNALS = scope {
+            //the root packages are not unloaded by path, as a namespace can be spread over several directories
+            for external in Reindexer::get_externals(session).iter() {
+                let tree = external.borrow().get_tree();
+                for path in external.borrow().paths().iter() {
+                    session.sync_odoo.get_file_mgr().borrow_mut().delete_path(session, path);
+                }
+                Symbol::unload(session, external.clone());
+                SyncOdoo::search_symbols_to_rebuild(session, &tree);
+            }
+        } else {
+            for root in roots.iter() {
+                let tree = session.sync_odoo.tree_from_path(&PathBuf::from(root)).map_err(|e| format!("{}: {}", root, e))?;
+                SyncOdoo::_unload_path(session, &PathBuf::from(root), true)?;
+                SyncOdoo::search_symbols_to_rebuild(session, &tree);
+            }
+        }
+        for (path, text, version) in opened_files.into_iter() {
+            session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path, Some(&vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text,
+            }]), Some(version), true);
+        }
+        SyncOdoo::process_rebuilds(session);
+        if let ReindexScope::ADDONS_PATH { .. } = scope {
+            //the modules created in the directory since the last build. The unloaded ones are created again by the rebuild
+            SyncOdoo::discover_modules(session);
+            SyncOdoo::process_rebuilds(session);
+        }
+        Ok(roots)
+    }
+
+    /* The packages at the root of the tree that are not odoo, the builtins, or a file opened in single-file mode */
+    fn get_externals(session: &mut SessionInfo) -> Vec<Rc<RefCell<Symbol>>> {
+        let root = session.sync_odoo.symbols.clone().unwrap();
+        let root = root.borrow();
+        root.all_module_symbol().filter(|s| {
+            let s = s.borrow();
+            !["odoo", "builtins"].contains(&s.name().as_str()) && !s.in_workspace() && !session.sync_odoo.is_single_file(s.name())
+        }).cloned().collect()
+    }
+
+    /* The content and the version of the opened files under the given paths, that must not be read from the disk */
+    fn get_opened_files(session: &mut SessionInfo, roots: &Vec<String>) -> Vec<(String, String, i32)> {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let file_mgr = file_mgr.borrow();
+        file_mgr.files.iter()
+            .filter(|(path, _)| roots.iter().any(|root| FileMgr::is_path_under(path, root)))
+            .filter_map(|(path, file_info)| {
+                let file_info = file_info.borrow();
+                match (file_info.opened, file_info.get_source()) {
+                    (true, Some(source)) => Some((path.clone(), source, file_info.version)),
+                    _ => None
+                }
+            }).collect()
+    }
+}
@@ -0,0 +1,410 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::{Expr, ExprCall, ExprDict, ExprListComp, ExprStringLiteral, Operator, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::constants::SymType;
+use crate::core::collection_folding::{CollectionFolding, Conversion};
+use crate::core::file_mgr::FileMgr;
+use crate::core::static_conditions::{StaticConditions, StaticValue};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* Policies accepted by the 'ondelete' parameter of a Selection field, in addition to callables and 'set <key>' */
+pub const ONDELETE_POLICIES: [&str; 3] = ["set null", "cascade", "set default"];
+
+/* Entry of the 'selection_add' parameter of a Selection field */
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum SelectionAddEntry {
+    ADD(String, TextRange), //("key", "Label"): the key is added at the current position
+    ANCHOR(String, TextRange), //("key",): the next added keys are inserted after this existing key
+}
+
+impl SelectionAddEntry {
+    pub fn key(&self) -> &String {
+        match self {
+            SelectionAddEntry::ADD(key, _) => key,
+            SelectionAddEntry::ANCHOR(key, _) => key,
+        }
+    }
+}
+
+/* Selection of a field, as defined by all the classes of the model that declare it */
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum ModelSelection {
+    NOT_FOUND, //no class of the model declares the field
+    NOT_SELECTION, //the field is not a Selection
+    UNKNOWN, //the keys can't be statically known (selection given by a method, values that are not constants, ...)
+    KEYS(Vec<String>, bool), //merged keys, and true if the field is required
+}
+
+/* Selection fields can be extended by the classes that inherit their model:
+    state = fields.Selection(selection_add=[("new", "New"), ("done",)], ondelete={"new": "cascade"})
+The keys are merged in the loading order of the modules. SelectionField reads these declarations from the AST,
+as keys are only useful if they are literals or constants. */
+pub struct SelectionField;
+
+impl SelectionField {
+
+    /* Return the call if the expression is a call to fields.Selection */
+    pub fn as_selection_call(expr: &Expr) -> Option<&ExprCall> {
+        let Expr::Call(call) = expr else {
+            return None;
+        };
+        let is_selection = match &*call.func {
+            Expr::Attribute(attr) => attr.attr.as_str() == "Selection",
+            Expr::Name(name) => name.id.as_str() == "Selection",
+            _ => false
+        };
+        if is_selection { Some(call) } else { None }
+    }
+
+    pub fn get_keyword<'a>(call: &'a ExprCall, name: &str) -> Option<&'a Expr> {
+        call.arguments.keywords.iter()
+            .find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == name))
+            .map(|k| &k.value)
+    }
+
+    /* Return the expression giving the base selection of the field, if any */
+    pub fn get_selection_expr(call: &ExprCall) -> Option<&Expr> {
+        call.arguments.args.first().or_else(|| SelectionField::get_keyword(call, "selection"))
+    }
+
+    pub fn is_required(call: &ExprCall) -> bool {
+        matches!(SelectionField::get_keyword(call, "required"), Some(Expr::BooleanLiteral(b)) if b.value)
+    }
+
+    /* Return the keys of a literal list of (key, label) tuples, or None if the selection is not a literal */
+    pub fn get_literal_keys(expr: &Expr) -> Option<Vec<String>> {
+        let elts = match expr {
+            Expr::List(l) => &l.elts,
+            Expr::Tuple(t) => &t.elts,
+            _ => return None
+        };
+        let mut keys = vec![];
+        for elt in elts.iter() {
+            match SelectionField::get_tuple_key(elt) {
+                Some((key, _)) => keys.push(key.value.to_string()),
+                None => return None
+            }
+        }
+        Some(keys)
+    }
+
+    /* Return the keys of the selection given by expr, evaluated in scope. In addition to literals, the selection can be
+    a module-level constant, in the same file or imported, or a comprehension over a constant:
+        selection=[(s, s.title()) for s in STATES]
+    Return None if a key can't be computed statically */
+    pub fn get_keys(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr) -> Option<Vec<String>> {
+        SelectionField::get_keys_at_depth(session, scope, expr, 0)
+    }
+
+    fn get_keys_at_depth(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr, depth: u32) -> Option<Vec<String>> {
+        match expr {
+            Expr::Name(name) => {
+                let (value, scope) = StaticConditions::get_name_expr(session, scope, name, depth, &mut vec![])?;
+                SelectionField::get_keys_at_depth(session, &scope, &value, depth + 1)
+            },
+            Expr::ListComp(comp) => SelectionField::get_comprehension_keys(session, scope, comp, depth),
+            Expr::List(_) | Expr::Tuple(_) | Expr::BinOp(_) | Expr::Subscript(_) | Expr::Call(_) => {
+                let (items, items_scope) = SelectionField::get_items(session, scope, expr, depth)?;
+                let mut keys = vec![];
+                for item in items.iter() {
+                    let key = SelectionField::get_entry_key(item)?;
+                    keys.push(SelectionField::get_str_value(session, &items_scope, key, &[], scope, depth)?);
+                }
+                Some(keys)
+            },
+            _ => None
+        }
+    }
+
+    /* Return the keys of a comprehension with a single generator over a constant iterable. The element must be a
+    (key, label) tuple, or a name bound to the (key, label) tuples of the iterable */
+    fn get_comprehension_keys(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, comp: &ExprListComp, depth: u32) -> Option<Vec<String>> {
+        let [generator] = comp.generators.as_slice() else {
+            return None;
+        };
+        if !generator.ifs.is_empty() || generator.is_async {
+            return None;
+        }
+        let (items, items_scope) = SelectionField::get_items(session, scope, &generator.iter, depth)?;
+        let mut keys = vec![];
+        for item in items.iter() {
+            let mut bindings = vec![];
+            SelectionField::bind(&generator.target, item, &mut bindings)?;
+            let key = match &*comp.elt {
+                Expr::Name(name) => {
+                    let (_, bound) = bindings.iter().find(|(id, _)| id == name.id.as_str())?;
+                    let key = SelectionField::get_entry_key(bound)?;
+                    SelectionField::get_str_value(session, &items_scope, key, &[], scope, depth)?
+                },
+                elt => {
+                    let key = SelectionField::get_entry_key(elt)?;
+                    SelectionField::get_str_value(session, scope, key, &bindings, &items_scope, depth)?
+                }
+            };
+            keys.push(key);
+        }
+        Some(keys)
+    }
+
+    /* Return the elements of a constant list, tuple or set, or the keys of a constant dict, with the scope of the
+    constant. The constant can be built from others by concatenations, repetitions, slices and conversions (see
+    CollectionFolding), if they are all in the same scope */
+    fn get_items(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr, depth: u32) -> Option<(Vec<Expr>, Rc<RefCell<Symbol>>)> {
+        match expr {
+            Expr::BinOp(op) if op.op == Operator::Add => {
+                let (mut items, items_scope) = SelectionField::get_items(session, scope, &op.left, depth)?;
+                let (right, right_scope) = SelectionField::get_items(session, scope, &op.right, depth)?;
+                if !Rc::ptr_eq(&items_scope, &right_scope) {
+                    return None;
+                }
+                items.extend(right);
+                Some((items, items_scope))
+            },
+            Expr::BinOp(op) if op.op == Operator::Mult => {
+                let (items, count) = match CollectionFolding::get_int(&op.right, &mut |_| None) {
+                    Some(count) => (&op.left, count),
+                    None => (&op.right, CollectionFolding::get_int(&op.left, &mut |_| None)?)
+                };
+                let (items, items_scope) = SelectionField::get_items(session, scope, items, depth)?;
+                Some((CollectionFolding::repeat_items(&items, count)?, items_scope))
+            },
+            Expr::Subscript(sub) => {
+                let Expr::Slice(slice) = &*sub.slice else {
+                    return None;
+                };
+                let (lower, upper, step) = CollectionFolding::get_slice_bounds(slice, &mut |_| None)?;
+                let (items, items_scope) = SelectionField::get_items(session, scope, &sub.value, depth)?;
+                Some((CollectionFolding::slice_items(&items, lower, upper, step)?, items_scope))
+            },
+            Expr::Call(call) => {
+                //the (key, label) tuples can't be sorted statically
+                let (conversion, arg) = CollectionFolding::get_conversion(call)?;
+                let (items, items_scope) = SelectionField::get_items(session, scope, arg, depth)?;
+                match conversion {
+                    Conversion::LIST | Conversion::TUPLE => Some((items, items_scope)),
+                    Conversion::SET | Conversion::FROMKEYS => Some((CollectionFolding::dedup(items), items_scope)),
+                    Conversion::SORTED => None
+                }
+            },
+            Expr::List(l) => Some((l.elts.clone(), scope.clone())),
+            Expr::Tuple(t) => Some((t.elts.clone(), scope.clone())),
+            Expr::Set(s) => Some((s.elts.clone(), scope.clone())),
+            Expr::Dict(d) => Some((d.items.iter().map(|item| item.key.clone()).collect::<Option<Vec<Expr>>>()?, scope.clone())),
+            Expr::Name(name) => {
+                let (value, scope) = StaticConditions::get_name_expr(session, scope, name, depth, &mut vec![])?;
+                SelectionField::get_items(session, &scope, &value, depth + 1)
+            },
+            _ => None
+        }
+    }
+
+    /* Bind the names of the target of a comprehension to the parts of an item of the iterable */
+    fn bind<'a>(target: &Expr, item: &'a Expr, bindings: &mut Vec<(String, &'a Expr)>) -> Option<()> {
+        match target {
+            Expr::Name(name) => bindings.push((name.id.to_string(), item)),
+            Expr::Tuple(targets) => {
+                let elts = match item {
+                    Expr::Tuple(t) => &t.elts,
+                    Expr::List(l) => &l.elts,
+                    _ => return None
+                };
+                if targets.elts.len() != elts.len() {
+                    return None;
+                }
+                for (target, elt) in targets.elts.iter().zip(elts.iter()) {
+                    SelectionField::bind(target, elt, bindings)?;
+                }
+            },
+            _ => return None
+        }
+        Some(())
+    }
+
+    fn get_entry_key(entry: &Expr) -> Option<&Expr> {
+        match entry {
+            Expr::Tuple(t) => t.elts.first(),
+            Expr::List(l) => l.elts.first(),
+            _ => None
+        }
+    }
+
+    /* Return the string value of a key. Its names are the bindings of the comprehension, evaluated in bindings_scope,
+    or the constants of scope */
+    fn get_str_value(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, key: &Expr, bindings: &[(String, &Expr)], bindings_scope: &Rc<RefCell<Symbol>>, depth: u32) -> Option<String> {
+        let value = StaticConditions::get_value(key, &mut |expr| {
+            let name = expr.as_name_expr()?;
+            match bindings.iter().find(|(id, _)| id == name.id.as_str()) {
+                Some((_, bound)) => StaticConditions::get_value(bound, &mut |expr| StaticConditions::get_name_value(session, bindings_scope, expr.as_name_expr()?, true, depth + 1, &mut vec![])),
+                None => StaticConditions::get_name_value(session, scope, name, true, depth + 1, &mut vec![])
+            }
+        });
+        match value {
+            Some(StaticValue::STR(key)) => Some(key),
+            _ => None
+        }
+    }
+
+    /* Return the entries of the 'selection_add' parameter. Entries that are not literal tuples are ignored */
+    pub fn get_selection_add(call: &ExprCall) -> Vec<SelectionAddEntry> {
+        let elts = match SelectionField::get_keyword(call, "selection_add") {
+            Some(Expr::List(l)) => &l.elts,
+            Some(Expr::Tuple(t)) => &t.elts,
+            _ => return vec![]
+        };
+        elts.iter().filter_map(|elt| SelectionField::get_tuple_key(elt)).map(|(key, len)| {
+            if len == 1 {
+                SelectionAddEntry::ANCHOR(key.value.to_string(), key.range)
+            } else {
+                SelectionAddEntry::ADD(key.value.to_string(), key.range)
+            }
+        }).collect()
+    }
+
+    pub fn get_ondelete(call: &ExprCall) -> Option<&ExprDict> {
+        match SelectionField::get_keyword(call, "ondelete") {
+            Some(Expr::Dict(d)) => Some(d),
+            _ => None
+        }
+    }
+
+    /* Merge the entries of a selection_add into keys. Added keys are inserted after the last anchor, or at the end.
+    An added key that already exists keeps its position and acts as an anchor */
+    pub fn merge(keys: &mut Vec<String>, entries: &Vec<SelectionAddEntry>) {
+        let mut position = keys.len();
+        for entry in entries.iter() {
+            match keys.iter().position(|k| k == entry.key()) {
+                Some(index) => position = index + 1,
+                None => {
+                    if let SelectionAddEntry::ADD(key, _) = entry {
+                        keys.insert(position, key.clone());
+                        position += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /* Return the string literal of an anchor of selection_add or of a key of ondelete that contains the offset */
+    pub fn get_key_literal_at(call: &ExprCall, offset: u32) -> Option<&ExprStringLiteral> {
+        let contains = |literal: &&ExprStringLiteral| literal.range.start().to_u32() < offset && offset <= literal.range.end().to_u32();
+        if let Some(Expr::List(l)) = SelectionField::get_keyword(call, "selection_add") {
+            for elt in l.elts.iter() {
+                if let Some((key, 1)) = SelectionField::get_tuple_key(elt) {
+                    if contains(&key) {
+                        return Some(key);
+                    }
+                }
+            }
+        }
+        if let Some(ondelete) = SelectionField::get_ondelete(call) {
+            for item in ondelete.items.iter() {
+                if let Some(Expr::StringLiteral(key)) = item.key.as_ref() {
+                    if contains(&key) {
+                        return Some(key);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /* Return the Selection of the field on the model, merged from all the classes of the model that are in the
+    dependencies of from_module. The class exclude is ignored, so the declaration of a class can be checked against
+    the fields it extends */
+    pub fn get_model_selection(session: &mut SessionInfo, model_name: &String, field_name: &String, from_module: Option<Rc<RefCell<Symbol>>>, exclude: Option<&Rc<RefCell<Symbol>>>) -> ModelSelection {
+        let Some(model) = session.sync_odoo.models.get(model_name).cloned() else {
+            return ModelSelection::NOT_FOUND;
+        };
+        let contributions = model.borrow().get_member_contributions(session, field_name, from_module);
+        let mut found = false;
+        let mut keys: Option<Vec<String>> = None;
+        let mut unknown = false;
+        let mut required = false;
+        for (_, members) in contributions.iter() {
+            for member in members.iter() {
+                let parent = member.borrow().parent().and_then(|p| p.upgrade());
+                if exclude.is_some_and(|exclude| parent.as_ref().is_some_and(|p| Rc::ptr_eq(p, exclude))) {
+                    continue;
+                }
+                if member.borrow().typ() != SymType::VARIABLE {
+                    return ModelSelection::NOT_SELECTION;
+                }
+                found = true;
+                let Some(value) = SelectionField::get_assigned_value(session, member) else {
+                    unknown = true;
+                    continue;
+                };
+                let Some(call) = SelectionField::as_selection_call(&value) else {
+                    return ModelSelection::NOT_SELECTION;
+                };
+                required |= SelectionField::is_required(call);
+                if let Some(selection) = SelectionField::get_selection_expr(call) {
+                    keys = match parent.as_ref() {
+                        Some(parent) => SelectionField::get_keys(session, parent, selection),
+                        None => SelectionField::get_literal_keys(selection)
+                    };
+                    unknown |= keys.is_none();
+                }
+                if let Some(keys) = keys.as_mut() {
+                    SelectionField::merge(keys, &SelectionField::get_selection_add(call));
+                }
+            }
+        }
+        if !found {
+            return ModelSelection::NOT_FOUND;
+        }
+        match keys {
+            Some(keys) if !unknown => ModelSelection::KEYS(keys, required),
+            _ => ModelSelection::UNKNOWN
+        }
+    }
+
+    /* Return the value assigned to a class variable, read from the AST of its file */
+    fn get_assigned_value(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>) -> Option<Expr> {
+        let file_info = FileMgr::get_file_info_of_symbol(session, variable)?;
+        let file_info = file_info.borrow();
+        let variable = variable.borrow();
+        let stmts = match variable.get_ast_node(&file_info) {
+            Some(stmt) => std::slice::from_ref(stmt),
+            None => file_info.ast.as_ref()?.as_slice() //the indexes are not up to date, search the whole file
+        };
+        SelectionField::find_assigned_value(stmts, variable.name(), variable.range()).cloned()
+    }
+
+    fn find_assigned_value<'a>(stmts: &'a [Stmt], name: &String, range: &TextRange) -> Option<&'a Expr> {
+        for stmt in stmts.iter() {
+            if !stmt.range().contains_range(*range) {
+                continue;
+            }
+            return match stmt {
+                Stmt::ClassDef(c) => SelectionField::find_assigned_value(&c.body, name, range),
+                Stmt::Assign(a) => a.targets.iter()
+                    .any(|t| t.as_name_expr().is_some_and(|n| n.id.as_str() == name.as_str() && n.range == *range))
+                    .then_some(&*a.value),
+                Stmt::AnnAssign(a) => a.value.as_deref()
+                    .filter(|_| a.target.as_name_expr().is_some_and(|n| n.id.as_str() == name.as_str() && n.range == *range)),
+                _ => None
+            };
+        }
+        None
+    }
+
+    /* Return the key of a ("key", ...) tuple, and the length of the tuple */
+    fn get_tuple_key(expr: &Expr) -> Option<(&ExprStringLiteral, usize)> {
+        let elts = match expr {
+            Expr::Tuple(t) => &t.elts,
+            Expr::List(l) => &l.elts,
+            _ => return None
+        };
+        match elts.first() {
+            Some(Expr::StringLiteral(key)) => Some((key, elts.len())),
+            _ => None
+        }
+    }
+}
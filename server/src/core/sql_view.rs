@@ -0,0 +1,282 @@
+use std::ops::Range;
+
+use ruff_python_ast::visitor::{walk_expr, Visitor};
+use ruff_python_ast::{Expr, ExprStringLiteral, Operator, Stmt};
+
+use crate::core::field_consistency::FieldDeclaration;
+
+/* The fields that are not a column of the table of their model */
+const NON_COLUMN_FIELDS: [&str; 2] = ["One2many", "Many2many"];
+
+/* A column of the SELECT list of a query, named by its alias or by the column it selects */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlColumn {
+    pub name: String,
+    pub range: Range<usize>, //range of the name in the query, as byte offsets
+}
+
+/* The query of a view model, as written in its class */
+#[derive(Debug)]
+pub struct ViewQuery<'a> {
+    pub literal: &'a ExprStringLiteral,
+    pub start: usize, //offset of the SELECT in the value of the literal
+}
+
+/* The models with _auto = False are backed by a view, given by the _table_query of the class or created by its init()
+method. Their stored fields are read from the columns of the view, so a field without a column fails at runtime. The
+SELECT list of the query is read from the literal of the class: the extraction is conservative, and gives up on the
+queries built at runtime, the common table expressions, and the columns that are not named by an alias or a column */
+pub struct SqlView {}
+
+impl SqlView {
+
+    /* Return the literal query of the view: the value of _table_query, as a class attribute or returned by a property,
+    or the CREATE VIEW executed by init(). The query can be formatted (%) or given to SQL() with its parameters */
+    pub fn find_query(body: &[Stmt]) -> Option<ViewQuery<'_>> {
+        for stmt in body.iter() {
+            match stmt {
+                Stmt::Assign(a) if a.targets.first().and_then(|t| t.as_name_expr()).is_some_and(|t| t.id.as_str() == "_table_query") => {
+                    return SqlView::get_literal(&a.value).map(|literal| ViewQuery { literal, start: 0 });
+                },
+                Stmt::FunctionDef(f) if f.name.as_str() == "_table_query" => {
+                    let statements: Vec<&Stmt> = f.body.iter().filter(|stmt| !SqlView::is_docstring(stmt)).collect();
+                    return match statements.as_slice() {
+                        [Stmt::Return(r)] => r.value.as_deref().and_then(SqlView::get_literal).map(|literal| ViewQuery { literal, start: 0 }),
+                        _ => None
+                    };
+                },
+                Stmt::FunctionDef(f) if f.name.as_str() == "init" => {
+                    let mut visitor = ExecuteVisitor { literals: vec![] };
+                    for stmt in f.body.iter() {
+                        visitor.visit_stmt(stmt);
+                    }
+                    return visitor.literals.into_iter().find_map(|literal| SqlView::get_view_select(literal.value.to_str())
+                        .map(|start| ViewQuery { literal, start }));
+                },
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /* Return the offset of the SELECT of a CREATE VIEW statement. The statement must be CREATE [OR REPLACE] VIEW name AS
+    followed by the SELECT, that can be in parentheses */
+    pub fn get_view_select(statement: &str) -> Option<usize> {
+        let statement = &SqlView::blank_comments(statement);
+        let (depths, _) = SqlView::get_depths(statement);
+        let create = SqlView::find_keyword(statement, &depths, 0, "create")?;
+        let view = SqlView::find_keyword(statement, &depths, create, "view")?;
+        let as_keyword = SqlView::find_keyword(statement, &depths, view, "as")?;
+        let start = as_keyword + 2 + statement[as_keyword + 2..].find(|c: char| !c.is_whitespace() && c != '(')?;
+        match SqlView::is_keyword_at(statement, start, "select") {
+            true => Some(start),
+            false => None
+        }
+    }
+
+    /* Return the columns of the SELECT list of the query, or None if they can't be known statically */
+    pub fn get_columns(query: &str) -> Option<Vec<SqlColumn>> {
+        let query = &SqlView::blank_comments(query);
+        let (depths, query_end) = SqlView::get_depths(query);
+        let select = query.find(|c: char| !c.is_whitespace())?;
+        if !SqlView::is_keyword_at(query, select, "select") {
+            return None; //WITH, VALUES...
+        }
+        let mut start = select + "select".len();
+        for modifier in ["distinct", "all"] {
+            if let Some(offset) = SqlView::skip_keyword(query, start, modifier) {
+                start = offset;
+            }
+        }
+        //DISTINCT ON (expressions)
+        if let Some(offset) = SqlView::skip_keyword(query, start, "on") {
+            let open = offset + query[offset..].find(|c: char| !c.is_whitespace())?;
+            if query.as_bytes()[open] != b'(' {
+                return None;
+            }
+            start = (open..query_end).find(|i| query.as_bytes()[*i] == b')' && depths[*i] == Some(0))? + 1;
+        }
+        let end = SqlView::find_keyword(query, &depths, start, "from").unwrap_or(query_end);
+        let mut columns = vec![];
+        let mut item_start = start;
+        for i in start..=end {
+            if i < end && !(depths[i] == Some(0) && query.as_bytes()[i] == b',') {
+                continue;
+            }
+            columns.push(SqlView::get_column(query, &depths, item_start, i)?);
+            item_start = i + 1;
+        }
+        Some(columns)
+    }
+
+    /* Return the stored fields declared in the class that are not a column of the view */
+    pub fn get_fields_without_column<'a>(fields: &'a [FieldDeclaration], columns: &[SqlColumn]) -> Vec<&'a FieldDeclaration> {
+        fields.iter()
+            .filter(|field| field.is_stored() && !NON_COLUMN_FIELDS.contains(&field.field_type.as_str()))
+            .filter(|field| !columns.iter().any(|column| column.name == field.name))
+            .collect()
+    }
+
+    /* Return the columns of the view that are not a field declared in the class. The id of the records is always selected */
+    pub fn get_columns_without_field<'a>(fields: &[FieldDeclaration], columns: &'a [SqlColumn]) -> Vec<&'a SqlColumn> {
+        columns.iter()
+            .filter(|column| column.name != "id" && !fields.iter().any(|field| field.name == column.name))
+            .collect()
+    }
+
+    /* Return the column of an item of the SELECT list: expression AS alias, or a column (table.column) */
+    fn get_column(query: &str, depths: &[Option<u32>], start: usize, end: usize) -> Option<SqlColumn> {
+        let item = &query[start..end];
+        let item_start = start + item.find(|c: char| !c.is_whitespace())?;
+        let item_end = start + item.trim_end_matches(|c: char| c.is_whitespace() || c == ';').len();
+        let item = &query[item_start..item_end];
+        if item.contains('%') || item.contains('{') || item == "*" || item.ends_with(".*") {
+            return None; //built at runtime, or all the columns of a table
+        }
+        let mut alias_start = None;
+        let mut offset = item_start;
+        while let Some(as_keyword) = SqlView::find_keyword(query, depths, offset, "as").filter(|k| *k < item_end) {
+            alias_start = Some(as_keyword + 2);
+            offset = as_keyword + 2;
+        }
+        let (name_start, name) = match alias_start {
+            Some(alias_start) => {
+                let name_start = alias_start + query[alias_start..item_end].find(|c: char| !c.is_whitespace())?;
+                (name_start, &query[name_start..item_end])
+            },
+            None => {
+                let name_start = item.rfind('.').map_or(item_start, |dot| item_start + dot + 1);
+                if !item.split('.').all(SqlView::is_identifier) {
+                    return None; //an expression without alias
+                }
+                (name_start, &query[name_start..item_end])
+            }
+        };
+        if !SqlView::is_identifier(name) {
+            return None;
+        }
+        let name = match name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+            Some(quoted) => quoted.to_string(),
+            None => name.to_lowercase()
+        };
+        Some(SqlColumn { name, range: name_start..item_end })
+    }
+
+    fn is_identifier(text: &str) -> bool {
+        if text.len() > 2 && text.starts_with('"') && text.ends_with('"') {
+            return !text[1..text.len() - 1].contains('"');
+        }
+        text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /* Replace the line comments (-- comment) by spaces, so the offsets of the query are kept */
+    fn blank_comments(query: &str) -> String {
+        let bytes = query.as_bytes();
+        let mut res = bytes.to_vec();
+        let mut quote: Option<u8> = None;
+        let mut i = 0;
+        while i < bytes.len() {
+            match (quote, bytes[i]) {
+                (Some(q), b) if b == q => quote = None,
+                (None, b @ (b'\'' | b'"')) => quote = Some(b),
+                (None, b'-') if bytes.get(i + 1) == Some(&b'-') => {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        res[i] = b' ';
+                        i += 1;
+                    }
+                    continue;
+                },
+                _ => {}
+            }
+            i += 1;
+        }
+        String::from_utf8(res).unwrap_or_else(|_| query.to_string())
+    }
+
+    /* Return the depth of parentheses of each byte of the query, or None for the bytes of the strings and quoted
+    identifiers, and the end of the query: a closing parenthesis without opening one, like the end of CREATE VIEW name
+    AS (SELECT ...), ends it */
+    fn get_depths(query: &str) -> (Vec<Option<u32>>, usize) {
+        let bytes = query.as_bytes();
+        let mut res = vec![None; bytes.len() + 1];
+        let mut depth: u32 = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                quote @ (b'\'' | b'"') => {
+                    let end = bytes[i + 1..].iter().position(|b| *b == quote).map_or(bytes.len(), |p| i + 1 + p);
+                    i = end + 1;
+                    continue;
+                },
+                b'(' => depth += 1,
+                b')' => match depth.checked_sub(1) {
+                    Some(d) => depth = d,
+                    None => return (res, i)
+                },
+                _ => {}
+            }
+            res[i] = Some(depth);
+            i += 1;
+        }
+        res[bytes.len()] = Some(depth);
+        (res, bytes.len())
+    }
+
+    /* Return the offset of the first keyword at depth 0 after start */
+    fn find_keyword(query: &str, depths: &[Option<u32>], start: usize, keyword: &str) -> Option<usize> {
+        (start..query.len()).find(|i| depths[*i] == Some(0) && SqlView::is_keyword_at(query, *i, keyword))
+    }
+
+    fn is_keyword_at(query: &str, offset: usize, keyword: &str) -> bool {
+        let bytes = query.as_bytes();
+        let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        offset + keyword.len() <= bytes.len()
+            && bytes[offset..offset + keyword.len()].eq_ignore_ascii_case(keyword.as_bytes())
+            && (offset == 0 || !is_word(bytes[offset - 1]))
+            && !bytes.get(offset + keyword.len()).is_some_and(|b| is_word(*b))
+    }
+
+    /* Return the offset after the keyword, if it is the next word after start */
+    fn skip_keyword(query: &str, start: usize, keyword: &str) -> Option<usize> {
+        let offset = start + query[start..].find(|c: char| !c.is_whitespace())?;
+        match SqlView::is_keyword_at(query, offset, keyword) {
+            true => Some(offset + keyword.len()),
+            false => None
+        }
+    }
+
+    /* Return the literal of the query: "...", "..." % values or SQL("...", values) */
+    fn get_literal(expr: &Expr) -> Option<&ExprStringLiteral> {
+        match expr {
+            Expr::StringLiteral(literal) => Some(literal),
+            Expr::BinOp(bin_op) if bin_op.op == Operator::Mod => bin_op.left.as_string_literal_expr(),
+            Expr::Call(call) if call.func.as_name_expr().is_some_and(|name| name.id.as_str() == "SQL") => {
+                call.arguments.args.first().and_then(|arg| arg.as_string_literal_expr())
+            },
+            _ => None
+        }
+    }
+
+    fn is_docstring(stmt: &Stmt) -> bool {
+        matches!(stmt, Stmt::Expr(expr) if expr.value.is_string_literal_expr())
+    }
+}
+
+/* Collect the literals given to the execute() calls: self.env.cr.execute("CREATE VIEW ...") */
+struct ExecuteVisitor<'a> {
+    literals: Vec<&'a ExprStringLiteral>,
+}
+
+impl<'a> Visitor<'a> for ExecuteVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr {
+            if call.func.as_attribute_expr().is_some_and(|attr| attr.attr.as_str() == "execute") {
+                if let Some(literal) = call.arguments.args.first().and_then(SqlView::get_literal) {
+                    self.literals.push(literal);
+                }
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
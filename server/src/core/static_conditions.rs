@@ -0,0 +1,446 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use ruff_python_ast::{Alias, BoolOp, CmpOp, ConversionFlag, Expr, ExprFString, ExprName, ExprSubscript, FStringElement, FStringPart, Identifier, Number, Operator, Stmt, StmtIf, UnaryOp};
+use ruff_text_size::Ranged;
+
+use crate::constants::SymType;
+use crate::core::file_mgr::FileMgr;
+use crate::core::import_resolver::resolve_import_stmt;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* A value that can be computed without executing the code */
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaticValue {
+    NONE,
+    BOOL(bool),
+    INT(i64),
+    STR(String),
+    TUPLE(Vec<StaticValue>),
+}
+
+impl StaticValue {
+
+    pub fn is_true(&self) -> bool {
+        match self {
+            StaticValue::NONE => false,
+            StaticValue::BOOL(b) => *b,
+            StaticValue::INT(i) => *i != 0,
+            StaticValue::STR(s) => !s.is_empty(),
+            StaticValue::TUPLE(t) => !t.is_empty(),
+        }
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            StaticValue::BOOL(b) => Some(*b as i64),
+            StaticValue::INT(i) => Some(*i),
+            _ => None
+        }
+    }
+
+    /* Return the text of the value, as given by str() */
+    fn to_str(&self) -> String {
+        match self {
+            StaticValue::NONE => "None".to_string(),
+            StaticValue::BOOL(true) => "True".to_string(),
+            StaticValue::BOOL(false) => "False".to_string(),
+            StaticValue::INT(i) => i.to_string(),
+            StaticValue::STR(s) => s.clone(),
+            StaticValue::TUPLE(t) if t.len() == 1 => format!("({},)", t[0].to_repr()),
+            StaticValue::TUPLE(t) => format!("({})", t.iter().map(|v| v.to_repr()).collect::<Vec<String>>().join(", ")),
+        }
+    }
+
+    /* Return the text of the value, as given by repr() */
+    fn to_repr(&self) -> String {
+        match self {
+            StaticValue::STR(s) => format!("'{}'", s),
+            _ => self.to_str()
+        }
+    }
+}
+
+/* The declaration of a module-level variable that can give it a static value */
+enum Declaration {
+    VALUE(Expr),
+    IMPORT(Option<Identifier>, Alias, u32),
+}
+
+/* Tests of if statements that can be decided statically, like configuration flags:
+    HAS_PDF = False
+    if HAS_PDF:
+        ...
+The names of the tests are resolved to the module-level variables they refer to, in the same file or through imports,
+if they are declared once with a constant value (literals, comparisons, conditional expressions, boolean operations,
+additions and f-strings between constants). Some names of the standard library also have a known value, like in the
+stubs of typeshed (see get_known_value):
+    if sys.version_info >= (3, 8):
+        from typing import Protocol
+    else:
+        from typing_extensions import Protocol */
+pub struct StaticConditions {}
+
+impl StaticConditions {
+
+    /* Return the value of the expression, if it can be computed statically. resolve gives the value of a name or of an
+    attribute */
+    pub fn get_value(expr: &Expr, resolve: &mut dyn FnMut(&Expr) -> Option<StaticValue>) -> Option<StaticValue> {
+        match expr {
+            Expr::NoneLiteral(_) => Some(StaticValue::NONE),
+            Expr::BooleanLiteral(b) => Some(StaticValue::BOOL(b.value)),
+            Expr::NumberLiteral(n) => match &n.value {
+                Number::Int(i) => i.as_i64().map(StaticValue::INT),
+                _ => None
+            },
+            Expr::StringLiteral(s) => Some(StaticValue::STR(s.value.to_str().to_string())),
+            Expr::FString(f_string) => StaticConditions::get_f_string_value(f_string, resolve),
+            Expr::Name(_) | Expr::Attribute(_) => resolve(expr),
+            Expr::Tuple(tuple) => tuple.elts.iter().map(|elt| StaticConditions::get_value(elt, resolve)).collect::<Option<Vec<StaticValue>>>().map(StaticValue::TUPLE),
+            Expr::Subscript(subscript) => StaticConditions::get_subscript_value(subscript, resolve),
+            Expr::BinOp(bin_op) if bin_op.op == Operator::Add => {
+                match (StaticConditions::get_value(&bin_op.left, resolve)?, StaticConditions::get_value(&bin_op.right, resolve)?) {
+                    (StaticValue::STR(left), StaticValue::STR(right)) => Some(StaticValue::STR(left + &right)),
+                    (left, right) => left.as_int()?.checked_add(right.as_int()?).map(StaticValue::INT)
+                }
+            },
+            Expr::If(if_expr) => match StaticConditions::get_value(&if_expr.test, resolve)?.is_true() {
+                true => StaticConditions::get_value(&if_expr.body, resolve),
+                false => StaticConditions::get_value(&if_expr.orelse, resolve)
+            },
+            Expr::UnaryOp(unary) => {
+                let operand = StaticConditions::get_value(&unary.operand, resolve)?;
+                match unary.op {
+                    UnaryOp::Not => Some(StaticValue::BOOL(!operand.is_true())),
+                    UnaryOp::USub => operand.as_int().and_then(|i| i.checked_neg()).map(StaticValue::INT),
+                    _ => None
+                }
+            },
+            Expr::BoolOp(bool_op) => {
+                //the operands are evaluated until one decides the result, which is this operand
+                let mut value = None;
+                for operand in bool_op.values.iter() {
+                    let operand = StaticConditions::get_value(operand, resolve)?;
+                    let decides = match bool_op.op {
+                        BoolOp::And => !operand.is_true(),
+                        BoolOp::Or => operand.is_true()
+                    };
+                    value = Some(operand);
+                    if decides {
+                        break;
+                    }
+                }
+                value
+            },
+            Expr::Compare(compare) => {
+                let mut left = StaticConditions::get_value(&compare.left, resolve)?;
+                for (op, comparator) in compare.ops.iter().zip(compare.comparators.iter()) {
+                    let result = match op {
+                        CmpOp::In | CmpOp::NotIn => {
+                            let elts = match comparator {
+                                Expr::Tuple(t) => &t.elts,
+                                Expr::List(l) => &l.elts,
+                                Expr::Set(s) => &s.elts,
+                                _ => return None
+                            };
+                            let mut found = false;
+                            for elt in elts.iter() {
+                                found |= StaticConditions::compare(&left, CmpOp::Eq, &StaticConditions::get_value(elt, resolve)?)?;
+                            }
+                            found == (*op == CmpOp::In)
+                        },
+                        _ => {
+                            let right = StaticConditions::get_value(comparator, resolve)?;
+                            let result = StaticConditions::compare(&left, *op, &right)?;
+                            left = right;
+                            result
+                        }
+                    };
+                    if !result {
+                        return Some(StaticValue::BOOL(false));
+                    }
+                }
+                Some(StaticValue::BOOL(true))
+            },
+            _ => None
+        }
+    }
+
+    /* Return the value of an f-string whose interpolations are constants, without conversion nor format spec */
+    fn get_f_string_value(f_string: &ExprFString, resolve: &mut dyn FnMut(&Expr) -> Option<StaticValue>) -> Option<StaticValue> {
+        let mut res = String::new();
+        for part in f_string.value.iter() {
+            match part {
+                FStringPart::Literal(literal) => res.push_str(&literal.value),
+                FStringPart::FString(f) => {
+                    for element in f.elements.iter() {
+                        match element {
+                            FStringElement::Literal(literal) => res.push_str(&literal.value),
+                            FStringElement::Expression(expression) => {
+                                if expression.debug_text.is_some() || expression.conversion != ConversionFlag::None || expression.format_spec.is_some() {
+                                    return None;
+                                }
+                                res.push_str(&StaticConditions::get_value(&expression.expression, resolve)?.to_str());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(StaticValue::STR(res))
+    }
+
+    /* Return the value of an item or of a slice of a tuple, like sys.version_info[:2] */
+    fn get_subscript_value(subscript: &ExprSubscript, resolve: &mut dyn FnMut(&Expr) -> Option<StaticValue>) -> Option<StaticValue> {
+        let StaticValue::TUPLE(elts) = StaticConditions::get_value(&subscript.value, resolve)? else {
+            return None;
+        };
+        let len = elts.len() as i64;
+        match &*subscript.slice {
+            Expr::Slice(slice) => {
+                if slice.step.is_some() {
+                    return None;
+                }
+                let lower = StaticConditions::get_slice_bound(slice.lower.as_deref(), 0, len, resolve)?;
+                let upper = StaticConditions::get_slice_bound(slice.upper.as_deref(), len, len, resolve)?;
+                Some(StaticValue::TUPLE(elts.get(lower as usize..upper.max(lower) as usize)?.to_vec()))
+            },
+            index => {
+                let index = StaticConditions::get_value(index, resolve)?.as_int()?;
+                let index = if index < 0 { index + len } else { index };
+                elts.get(usize::try_from(index).ok()?).cloned()
+            }
+        }
+    }
+
+    /* Return a bound of a slice of a sequence of length len. Negative bounds start from the end, and the bounds are
+    clamped to the sequence */
+    fn get_slice_bound(bound: Option<&Expr>, default: i64, len: i64, resolve: &mut dyn FnMut(&Expr) -> Option<StaticValue>) -> Option<i64> {
+        let Some(bound) = bound else {
+            return Some(default);
+        };
+        let bound = StaticConditions::get_value(bound, resolve)?.as_int()?;
+        Some(if bound < 0 { (bound + len).max(0) } else { bound.min(len) })
+    }
+
+    /* Compare two values like Python, tuples being compared item by item */
+    fn get_ordering(left: &StaticValue, right: &StaticValue) -> Option<Ordering> {
+        match (left, right) {
+            (StaticValue::STR(l), StaticValue::STR(r)) => Some(l.cmp(r)),
+            (StaticValue::TUPLE(l), StaticValue::TUPLE(r)) => {
+                for (l, r) in l.iter().zip(r.iter()) {
+                    let ordering = StaticConditions::get_ordering(l, r)?;
+                    if ordering.is_ne() {
+                        return Some(ordering);
+                    }
+                }
+                Some(l.len().cmp(&r.len()))
+            },
+            _ => Some(left.as_int()?.cmp(&right.as_int()?))
+        }
+    }
+
+    fn compare(left: &StaticValue, op: CmpOp, right: &StaticValue) -> Option<bool> {
+        let equal = match (left.as_int(), right.as_int()) {
+            (Some(l), Some(r)) => l == r, //True == 1
+            _ => StaticConditions::get_ordering(left, right).map_or(left == right, |ordering| ordering.is_eq())
+        };
+        match op {
+            CmpOp::Eq => Some(equal),
+            CmpOp::NotEq => Some(!equal),
+            //None, True and False are singletons, the identity of the other values is unknown
+            CmpOp::Is | CmpOp::IsNot => match (left, right) {
+                (StaticValue::NONE | StaticValue::BOOL(_), _) | (_, StaticValue::NONE | StaticValue::BOOL(_)) => Some((left == right) == (op == CmpOp::Is)),
+                _ => None
+            },
+            CmpOp::Lt | CmpOp::LtE | CmpOp::Gt | CmpOp::GtE => {
+                let ordering = StaticConditions::get_ordering(left, right)?;
+                Some(match op {
+                    CmpOp::Lt => ordering.is_lt(),
+                    CmpOp::LtE => ordering.is_le(),
+                    CmpOp::Gt => ordering.is_gt(),
+                    _ => ordering.is_ge()
+                })
+            },
+            _ => None
+        }
+    }
+
+    /* Return the index of the branch of the if statement that is executed, if the tests can be decided statically. The
+    index of the body is 0, then come the elif and else clauses. If all the tests are false and there is no else
+    clause, the index is the number of branches. */
+    pub fn select_branch(if_stmt: &StmtIf, resolve: &mut dyn FnMut(&Expr) -> Option<StaticValue>) -> Option<usize> {
+        let tests = std::iter::once(Some(&*if_stmt.test)).chain(if_stmt.elif_else_clauses.iter().map(|clause| clause.test.as_ref()));
+        for (index, test) in tests.enumerate() {
+            let Some(test) = test else {
+                return Some(index); //else clause
+            };
+            if StaticConditions::get_value(test, resolve)?.is_true() {
+                return Some(index);
+            }
+        }
+        Some(if_stmt.elif_else_clauses.len() + 1)
+    }
+
+    /* Return the value of a name or of an attribute used in the given scope: the known value of the name it imports (see
+    get_known_value), or the value of the module-level variable it refers to (see get_name_value) */
+    pub fn get_expr_value(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr, globals_complete: bool, depth: u32, files: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<StaticValue> {
+        if let Some(value) = StaticConditions::get_qualified_name(session, scope, expr, globals_complete).and_then(|name| StaticConditions::get_known_value(session, &name)) {
+            return Some(value);
+        }
+        match expr {
+            Expr::Name(name) => StaticConditions::get_name_value(session, scope, name, globals_complete, depth, files),
+            _ => None
+        }
+    }
+
+    /* Return the full name of what a name or an attribute refers to through the imports of its module, like
+    typing.TYPE_CHECKING for TYPE_CHECKING after 'from typing import TYPE_CHECKING', or sys.version_info for
+    sys.version_info after 'import sys'. Relative imports are not followed. */
+    pub fn get_qualified_name(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, expr: &Expr, globals_complete: bool) -> Option<String> {
+        let name = match expr {
+            Expr::Attribute(attribute) => {
+                let base = StaticConditions::get_qualified_name(session, scope, &attribute.value, globals_complete)?;
+                return Some(format!("{}.{}", base, attribute.attr));
+            },
+            Expr::Name(name) => name,
+            _ => return None
+        };
+        let variable = StaticConditions::get_global_variable(scope, name, globals_complete)?;
+        let file_info = FileMgr::get_file_info_of_symbol(session, &variable)?;
+        let file_info = file_info.borrow();
+        let variable = variable.borrow();
+        let range = *variable.range();
+        match variable.get_ast_node(&file_info)? {
+            Stmt::Import(import) => {
+                let alias = import.names.iter().find(|alias| alias.range == range)?;
+                match alias.asname {
+                    Some(_) => Some(alias.name.to_string()),
+                    None => alias.name.split('.').next().map(|name| name.to_string()) //import os.path declares os
+                }
+            },
+            Stmt::ImportFrom(import_from) if import_from.level == 0 => {
+                let alias = import_from.names.iter().find(|alias| alias.range == range)?;
+                Some(format!("{}.{}", import_from.module.as_ref()?, alias.name))
+            },
+            _ => None
+        }
+    }
+
+    /* Return the value of the names of the standard library that depend on the environment: TYPE_CHECKING is true, as
+    the code is analyzed like by a type checker, and sys.version_info and sys.platform are the ones of the configured
+    Python interpreter. The version is unknown until the configuration has been checked (see ConfigCheck). */
+    pub fn get_known_value(session: &SessionInfo, name: &str) -> Option<StaticValue> {
+        let version = session.sync_odoo.config_report.python_version;
+        match name {
+            "typing.TYPE_CHECKING" | "typing_extensions.TYPE_CHECKING" => Some(StaticValue::BOOL(true)),
+            "sys.version_info" => version.map(|(major, minor, micro)| StaticValue::TUPLE(vec![
+                StaticValue::INT(major as i64), StaticValue::INT(minor as i64), StaticValue::INT(micro as i64)
+            ])),
+            "sys.version_info.major" => version.map(|(major, _, _)| StaticValue::INT(major as i64)),
+            "sys.version_info.minor" => version.map(|(_, minor, _)| StaticValue::INT(minor as i64)),
+            "sys.version_info.micro" => version.map(|(_, _, micro)| StaticValue::INT(micro as i64)),
+            "sys.platform" => Some(StaticValue::STR(StaticConditions::get_platform().to_string())),
+            _ => None
+        }
+    }
+
+    /* Return sys.platform for the system of the server, which runs the configured interpreter */
+    pub fn get_platform() -> &'static str {
+        match std::env::consts::OS {
+            "windows" => "win32",
+            "macos" => "darwin",
+            os => os
+        }
+    }
+
+    /* Return the value of a name used in the given scope, if it refers to a single module-level variable with a static
+    value. If globals_complete is false, the module is still being built and the names used in functions are not
+    resolved, as they could be declared later. The files of the variables that have been consulted are added to files. */
+    pub fn get_name_value(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, name: &ExprName, globals_complete: bool, depth: u32, files: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<StaticValue> {
+        let variable = StaticConditions::get_global_variable(scope, name, globals_complete)?;
+        StaticConditions::get_variable_value(session, &variable, depth, files)
+    }
+
+    /* Return the expression assigned to the module-level variable a name refers to, and the scope in which this
+    expression must be evaluated. Imported variables are followed to their declaration */
+    pub fn get_name_expr(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, name: &ExprName, depth: u32, files: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<(Expr, Rc<RefCell<Symbol>>)> {
+        let variable = StaticConditions::get_global_variable(scope, name, true)?;
+        StaticConditions::get_variable_expr(session, &variable, depth, files)
+    }
+
+    fn get_global_variable(scope: &Rc<RefCell<Symbol>>, name: &ExprName, globals_complete: bool) -> Option<Rc<RefCell<Symbol>>> {
+        let mut scope = scope.clone();
+        let mut position = name.range.start().to_u32();
+        let symbols = loop {
+            let symbols = scope.borrow().get_content_symbol(name.id.as_str(), position);
+            if !symbols.is_empty() {
+                break symbols;
+            }
+            let typ = scope.borrow().typ();
+            match typ {
+                SymType::CLASS => {},
+                SymType::FUNCTION if globals_complete => position = u32::MAX,
+                _ => return None
+            }
+            let parent = scope.borrow().parent()?.upgrade()?;
+            scope = parent;
+        };
+        let [variable] = symbols.as_slice() else {
+            return None; //the name can have several values
+        };
+        let is_global = variable.borrow().typ() == SymType::VARIABLE &&
+            variable.borrow().parent().and_then(|p| p.upgrade()).is_some_and(|p| matches!(p.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)));
+        if is_global { Some(variable.clone()) } else { None }
+    }
+
+    /* Return the static value of a module-level variable, from its assignment or from the variable it imports */
+    fn get_variable_value(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>, depth: u32, files: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<StaticValue> {
+        let (value, scope) = StaticConditions::get_variable_expr(session, variable, depth, files)?;
+        StaticConditions::get_value(&value, &mut |expr| StaticConditions::get_expr_value(session, &scope, expr, true, depth + 1, files))
+    }
+
+    /* Return the expression assigned to a variable, and the scope in which this expression must be evaluated. Imported
+    variables are followed to their declaration */
+    pub fn get_variable_expr(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>, depth: u32, files: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<(Expr, Rc<RefCell<Symbol>>)> {
+        if depth >= session.sync_odoo.config.evaluation_depth {
+            return None;
+        }
+        let file = variable.borrow().get_file()?.upgrade()?;
+        if !files.iter().any(|f| Rc::ptr_eq(f, &file)) {
+            files.push(file.clone());
+        }
+        let scope = variable.borrow().parent()?.upgrade()?;
+        let declaration = {
+            let file_info = FileMgr::get_file_info_of_symbol(session, variable)?;
+            let file_info = file_info.borrow();
+            let variable = variable.borrow();
+            let range = *variable.range();
+            match variable.get_ast_node(&file_info)? {
+                Stmt::Assign(assign) if assign.targets.iter().any(|target| target.is_name_expr() && target.range() == range) => {
+                    Declaration::VALUE((*assign.value).clone())
+                },
+                Stmt::AnnAssign(ann_assign) if ann_assign.target.is_name_expr() && ann_assign.target.range() == range => {
+                    Declaration::VALUE((**ann_assign.value.as_ref()?).clone())
+                },
+                Stmt::ImportFrom(import_from) => {
+                    let alias = import_from.names.iter().find(|alias| alias.range == range)?;
+                    Declaration::IMPORT(import_from.module.clone(), alias.clone(), import_from.level)
+                },
+                _ => return None
+            }
+        };
+        match declaration {
+            Declaration::VALUE(value) => Some((value, scope)),
+            Declaration::IMPORT(module, alias, level) => {
+                let import_result = resolve_import_stmt(session, &file, module.as_ref(), &[alias], Some(level), &mut None).pop()?;
+                if !import_result.found || import_result.symbol.borrow().typ() != SymType::VARIABLE {
+                    return None;
+                }
+                StaticConditions::get_variable_expr(session, &import_result.symbol, depth + 1, files)
+            }
+        }
+    }
+}
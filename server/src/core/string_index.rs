@@ -0,0 +1,209 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ruff_python_ast::visitor::{walk_expr, Visitor};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::TextRange;
+
+use crate::core::file_mgr::FileInfo;
+use crate::threads::SessionInfo;
+
+/* Strings shorter or longer than these lengths are not indexed: they are too frequent or too unlikely to be searched */
+const MIN_LENGTH: usize = 3;
+const MAX_LENGTH: usize = 200;
+/* Maximum number of ranges kept for a string. Beyond, only the files containing it are kept */
+const MAX_RANGES: usize = 256;
+
+#[derive(Debug, Default)]
+struct StringEntry {
+    files: HashMap<u32, Option<Vec<TextRange>>>, //None if the ranges in this file were not kept
+    ranges: usize,
+}
+
+/* Answer of the index to "which files contain this literal string" */
+#[derive(Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum StringLookup {
+    FOUND(Vec<(String, Option<Vec<TextRange>>)>), //the files holding the string, with the ranges of the literals if they were kept
+    NOT_INDEXED, //the string is too short or too long to be indexed: all the files must be searched
+}
+
+/* Index of the string literals of the python files, built during their ARCH: a literal value gives the files and the
+ranges of the literals holding it. The literals are also indexed by their dotted and comma separated parts, so that a
+field name finds the depends and related paths it is part of: 'partner_id.name' is found by 'partner_id'.
+The features looking for string references (model names, xml ids, field paths...) ask the index for the candidate
+files, then confirm each occurrence on the text or the ast of these files only */
+#[derive(Debug, Default)]
+pub struct StringIndex {
+    paths: Vec<String>,
+    path_ids: HashMap<String, u32>,
+    entries: HashMap<String, StringEntry>,
+    file_keys: HashMap<u32, Vec<String>>, //the keys added by each file, to remove them when the file is rebuilt
+}
+
+impl StringIndex {
+
+    pub fn new() -> Self {
+        StringIndex::default()
+    }
+
+    pub fn is_indexable(value: &str) -> bool {
+        value.len() >= MIN_LENGTH && value.len() <= MAX_LENGTH
+    }
+
+    /* Return the keys of a literal: its value, and its parts if it is a path or a list: 'partner_id.name' */
+    pub fn get_keys(value: &str) -> Vec<&str> {
+        let mut res = vec![];
+        if StringIndex::is_indexable(value) {
+            res.push(value);
+        }
+        if value.len() <= MAX_LENGTH && value.contains(['.', ',', ' ', '/']) {
+            for part in value.split(['.', ',', ' ', '/']) {
+                if StringIndex::is_indexable(part) && part != value && !res.contains(&part) {
+                    res.push(part);
+                }
+            }
+        }
+        res
+    }
+
+    /* Return the value and the range of the string literals of the ast */
+    pub fn collect_literals(ast: &[Stmt]) -> Vec<(String, TextRange)> {
+        let mut visitor = StringLiteralVisitor { literals: vec![] };
+        for stmt in ast.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.literals
+    }
+
+    /* Replace the literals of the file by the ones of its new ast */
+    pub fn update_file(&mut self, path: &str, ast: &[Stmt]) {
+        self.remove_file(path);
+        let file_id = match self.path_ids.get(path) {
+            Some(id) => *id,
+            None => {
+                self.paths.push(path.to_string());
+                let id = (self.paths.len() - 1) as u32;
+                self.path_ids.insert(path.to_string(), id);
+                id
+            }
+        };
+        let mut keys = vec![];
+        for (value, range) in StringIndex::collect_literals(ast).iter() {
+            for key in StringIndex::get_keys(value) {
+                let entry = self.entries.entry(key.to_string()).or_default();
+                let ranges = entry.files.entry(file_id).or_insert_with(|| {
+                    keys.push(key.to_string());
+                    Some(vec![])
+                });
+                match ranges {
+                    Some(file_ranges) if entry.ranges < MAX_RANGES => {
+                        file_ranges.push(*range);
+                        entry.ranges += 1;
+                    },
+                    Some(file_ranges) => { //the ranges of the file would be incomplete
+                        entry.ranges -= file_ranges.len();
+                        *ranges = None;
+                    },
+                    None => {}
+                }
+            }
+        }
+        if !keys.is_empty() {
+            self.file_keys.insert(file_id, keys);
+        }
+    }
+
+    pub fn remove_file(&mut self, path: &str) {
+        let Some(file_id) = self.path_ids.get(path) else {
+            return;
+        };
+        for key in self.file_keys.remove(file_id).unwrap_or_default().iter() {
+            let Some(entry) = self.entries.get_mut(key) else {
+                continue;
+            };
+            if let Some(Some(ranges)) = entry.files.remove(file_id) {
+                entry.ranges -= ranges.len();
+            }
+            if entry.files.is_empty() {
+                self.entries.remove(key);
+            }
+        }
+    }
+
+    pub fn lookup(&self, value: &str) -> StringLookup {
+        if !StringIndex::is_indexable(value) {
+            return StringLookup::NOT_INDEXED;
+        }
+        let Some(entry) = self.entries.get(value) else {
+            return StringLookup::FOUND(vec![]);
+        };
+        let mut files: Vec<(String, Option<Vec<TextRange>>)> = entry.files.iter().map(|(id, ranges)| (self.paths[*id as usize].clone(), ranges.clone())).collect();
+        files.sort_by(|(path_1, _), (path_2, _)| path_1.cmp(path_2));
+        StringLookup::FOUND(files)
+    }
+
+    /* Number of strings in the index */
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /* Return the string literals of the loaded files whose value is the string, or one of its parts (see get_keys).
+    The ranges given by the index are confirmed on the text of the file, and the files whose ranges are unknown or
+    outdated are searched again */
+    pub fn find_references(session: &mut SessionInfo, value: &str) -> Vec<(String, TextRange)> {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let candidates: Vec<(String, Option<Vec<TextRange>>)> = match file_mgr.borrow().string_index.lookup(value) {
+            StringLookup::FOUND(files) => files,
+            StringLookup::NOT_INDEXED => file_mgr.borrow().files.keys().map(|path| (path.clone(), None)).collect(),
+        };
+        let mut res = vec![];
+        for (path, ranges) in candidates.into_iter() {
+            let Some(file_info) = file_mgr.borrow().get_file_info(&path) else {
+                continue;
+            };
+            let confirmed = ranges.and_then(|ranges| StringIndex::confirm_ranges(&file_info, value, ranges));
+            match confirmed {
+                Some(confirmed) => res.extend(confirmed.into_iter().map(|range| (path.clone(), range))),
+                None => {
+                    let file_info = file_info.borrow();
+                    let Some(ast) = file_info.ast.as_ref() else {
+                        continue;
+                    };
+                    for (literal, range) in StringIndex::collect_literals(ast).iter() {
+                        if literal == value || StringIndex::get_keys(literal).contains(&value) {
+                            res.push((path.clone(), *range));
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /* Return the ranges if they still hold the value in the text of the file, or None if the file must be searched */
+    fn confirm_ranges(file_info: &Rc<RefCell<FileInfo>>, value: &str, ranges: Vec<TextRange>) -> Option<Vec<TextRange>> {
+        let file_info = file_info.borrow();
+        for range in ranges.iter() {
+            if !file_info.get_text(range).is_some_and(|text| text.contains(value)) {
+                return None;
+            }
+        }
+        Some(ranges)
+    }
+}
+
+struct StringLiteralVisitor {
+    literals: Vec<(String, TextRange)>,
+}
+
+impl<'a> Visitor<'a> for StringLiteralVisitor {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::StringLiteral(string) = expr {
+            self.literals.push((string.value.to_str().to_string(), string.range));
+        }
+        walk_expr(self, expr);
+    }
+}
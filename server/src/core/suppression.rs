@@ -0,0 +1,25 @@
+/* Some diagnostics can be suppressed by a comment on the line they start on:
+    def _compute_legacy_total(self):  # noqa: OLS20021
+A '# noqa' comment without codes suppresses all of them. */
+pub struct Suppression {}
+
+impl Suppression {
+
+    /* Return true if the line has a noqa comment for the code */
+    pub fn is_suppressed(line: &str, code: &str) -> bool {
+        let mut rest = line;
+        while let Some(index) = rest.find('#') {
+            rest = &rest[index + 1..];
+            let Some(after) = rest.trim_start().strip_prefix("noqa") else {
+                continue;
+            };
+            let Some(codes) = after.trim_start().strip_prefix(':') else {
+                return !after.starts_with(|c: char| c.is_alphanumeric());
+            };
+            return codes.split(|c: char| c == ',' || c.is_whitespace())
+                .take_while(|part| !part.starts_with('#'))
+                .any(|part| part == code);
+        }
+        false
+    }
+}
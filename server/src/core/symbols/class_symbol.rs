@@ -4,6 +4,7 @@ use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use weak_table::PtrWeakHashSet;
 
+use crate::core::dataclasses::DataclassInfo;
 use crate::core::model::ModelData;
 
 use super::symbol::Symbol;
@@ -22,11 +23,15 @@ pub struct ClassSymbol {
     pub range: TextRange,
     pub body_range: TextRange,
     pub _model: Option<ModelData>,
+    pub has_dynamic_fields: bool, //the class adds fields to its model, but their names can't be statically known
+    pub metaclass_attributes: Vec<String>, //if the class is a metaclass, attributes that it sets on the classes it creates
+    pub dataclass: Option<DataclassInfo>, //the fields of a dataclass or a NamedTuple, that its generated __init__ takes
 
     //Trait SymbolMgr
     //--- Body symbols
     pub sections: Vec<SectionRange>,
     pub symbols: HashMap<String, HashMap<u32, Vec<Rc<RefCell<Symbol>>>>>,
+    pub deleted_names: HashMap<String, HashMap<u32, Vec<u32>>>, //the end offsets of the del statements of a name, by section
     //--- dynamics variables
     pub ext_symbols: HashMap<String, Vec<Rc<RefCell<Symbol>>>>,
 }
@@ -45,9 +50,13 @@ impl ClassSymbol {
             doc_string: None,
             sections: vec![],
             symbols: HashMap::new(),
+            deleted_names: HashMap::new(),
             ext_symbols: HashMap::new(),
             bases: vec![],
             _model: None,
+            has_dynamic_fields: false,
+            metaclass_attributes: vec![],
+            dataclass: None,
         };
         res._init_symbol_mgr();
         res
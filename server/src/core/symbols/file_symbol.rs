@@ -16,6 +16,7 @@ pub struct FileSymbol {
     pub arch_eval_status: BuildStatus,
     pub odoo_status: BuildStatus,
     pub validation_status: BuildStatus,
+    pub arch_eval_epoch: u64, //epoch of the last completed ARCH_EVAL, see SyncOdoo::epoch
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
     pub in_workspace: bool,
     pub model_dependencies: PtrWeakHashSet<Weak<RefCell<Model>>>, //always on validation level, as odoo step is always required
@@ -25,6 +26,7 @@ pub struct FileSymbol {
     //Trait SymbolMgr
     pub sections: Vec<SectionRange>,
     pub symbols: HashMap<String, HashMap<u32, Vec<Rc<RefCell<Symbol>>>>>,
+    pub deleted_names: HashMap<String, HashMap<u32, Vec<u32>>>, //the end offsets of the del statements of a name, by section
     //--- dynamics variables
     pub ext_symbols: HashMap<String, Vec<Rc<RefCell<Symbol>>>>,
 }
@@ -42,10 +44,12 @@ impl FileSymbol {
             arch_eval_status: BuildStatus::PENDING,
             odoo_status: BuildStatus::PENDING,
             validation_status: BuildStatus::PENDING,
+            arch_eval_epoch: 0,
             not_found_paths: vec![],
             in_workspace: false,
             sections: vec![],
             symbols: HashMap::new(),
+            deleted_names: HashMap::new(),
             ext_symbols: HashMap::new(),
             model_dependencies: PtrWeakHashSet::new(),
             dependencies: [
@@ -3,11 +3,23 @@ use std::{cell::RefCell, collections::HashMap, rc::{Rc, Weak}};
 use lsp_types::Diagnostic;
 use ruff_text_size::{TextRange, TextSize};
 
-use crate::{constants::{BuildStatus, BuildSteps, SymType}, core::evaluation::{Context, Evaluation}, threads::SessionInfo};
+use crate::{constants::{flatten_tree, BuildStatus, BuildSteps, SymType}, core::{argument_types::ParameterType, evaluation::{Context, Evaluation}}, threads::SessionInfo};
 
 use super::{symbol::Symbol, symbol_mgr::{SectionRange, SymbolMgr}};
 
-#[derive(Debug, PartialEq)]
+/* The decorators that return the function with the same parameters, or a descriptor calling it with them */
+const SIGNATURE_KEEPING_DECORATORS: [&str; 16] = [
+    "builtins.staticmethod", "builtins.classmethod", "builtins.property",
+    "functools.cached_property", "functools.cache", "functools.lru_cache",
+    "abc.abstractmethod", "typing.overload", "typing.final", "typing.override",
+    "typing_extensions.overload", "typing_extensions.final", "typing_extensions.override",
+    "contextlib.contextmanager", "contextlib.asynccontextmanager", "typing_extensions.deprecated",
+];
+
+/* The modules whose decorators all keep the parameters: api.depends, api.model, tools.ormcache... */
+const SIGNATURE_KEEPING_MODULES: [&str; 2] = ["odoo.api.", "odoo.tools.cache."];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ArgumentType {
     POS_ONLY,
     ARG,
@@ -22,6 +34,14 @@ pub struct Argument {
     //other informations about arg
     pub default_value: Option<Evaluation>,
     pub arg_type: ArgumentType,
+    pub annotation: Option<Vec<ParameterType>>, //types accepted by the annotation of the parameter, set at the arch eval step
+}
+
+/* The type given to the first argument of the function by its return annotation TypeGuard[X] or TypeIs[X] */
+#[derive(Debug, Clone)]
+pub struct TypeGuard {
+    pub evaluations: Vec<Evaluation>, //instances of X
+    pub is_type_is: bool, //a TypeIs also narrows the argument when the function returns False
 }
 
 #[derive(Debug)]
@@ -30,10 +50,13 @@ pub struct FunctionSymbol {
     pub is_external: bool,
     pub is_static: bool,
     pub is_property: bool,
+    pub setter: Option<Rc<RefCell<Symbol>>>, //function decorated by @name.setter, owned by the getter of the property instead of shadowing it
+    pub deleter: Option<Rc<RefCell<Symbol>>>, //function decorated by @name.deleter, like the setter
     pub doc_string: Option<String>,
     pub ast_indexes: Vec<u16>, //list of index to reach the corresponding ast node from file ast
     pub diagnostics: HashMap<BuildSteps, Vec<Diagnostic>>, //only temporary used for CLASS and FUNCTION to be collected like others are stored on FileInfo
     pub evaluations: Vec<Evaluation>, //Vec, because sometimes a single allocation can be ambiguous, like ''' a = "5" if X else 5 '''
+    pub declared_evaluations: Vec<Evaluation>, //return evaluations given by a decorator (api.returns), kept when the inferred ones are invalidated
     pub weak_self: Option<Weak<RefCell<Symbol>>>,
     pub parent: Option<Weak<RefCell<Symbol>>>,
     pub arch_status: BuildStatus,
@@ -44,12 +67,22 @@ pub struct FunctionSymbol {
     pub body_range: TextRange,
     pub args: Vec<Argument>,
     pub is_overloaded: bool, //used for @overload decorator. Only indicates if the decorator is present. Use is_overloaded() to know if this function is overloaded
+    pub overloads: Vec<Rc<RefCell<Symbol>>>, //next definitions of a function decorated by @overload: its other overloads, then its implementation
     pub is_class_method: bool, //used for @classmethod decorator
+    pub is_lambda: bool, //declared by the assignment of a lambda to a name. Its body is its returned expression, evaluated with the assignment
+    pub is_synthesized: bool, //the __init__ generated for a dataclass or a NamedTuple (see Dataclasses). Like a lambda, it has no body to build
+    pub is_async: bool, //declared by 'async def': a call gives a coroutine, whose awaited value is the returned value
+    pub decorators: Vec<Evaluation>, //evaluations of the decorators, in the order of the source, set at the arch eval step
+    pub global_names: Vec<String>, //names given to a global statement of the body, assigned in the scope of the file
+    pub nonlocal_names: Vec<String>, //names given to a nonlocal statement of the body, assigned in the enclosing function
+    pub type_guard: Option<TypeGuard>, //set at the arch eval step
+    pub narrowed_attributes: Vec<(String, TextRange, Vec<Evaluation>)>, //attribute chains narrowed by a TypeGuard in a branch of the body (self.move_id), with the range of the branch
 
     //Trait SymbolMgr
     //--- Body content
     pub sections: Vec<SectionRange>,
     pub symbols: HashMap<String, HashMap<u32, Vec<Rc<RefCell<Symbol>>>>>,
+    pub deleted_names: HashMap<String, HashMap<u32, Vec<u32>>>, //the end offsets of the del statements of a name, by section
     //--- dynamics variables
     pub ext_symbols: HashMap<String, Vec<Rc<RefCell<Symbol>>>>,
 
@@ -67,25 +100,49 @@ impl FunctionSymbol {
             body_range: TextRange::new(body_start, range.end()),
             is_static: false,
             is_property: false,
+            setter: None,
+            deleter: None,
             diagnostics: HashMap::new(),
             ast_indexes: vec![],
             doc_string: None,
             evaluations: vec![],
+            declared_evaluations: vec![],
             arch_status: BuildStatus::PENDING,
             arch_eval_status: BuildStatus::PENDING,
             odoo_status: BuildStatus::PENDING,
             validation_status: BuildStatus::PENDING,
             sections: vec![],
             symbols: HashMap::new(),
+            deleted_names: HashMap::new(),
             ext_symbols: HashMap::new(),
             args: vec![],
             is_overloaded: false,
+            overloads: vec![],
             is_class_method: false,
+            is_lambda: false,
+            is_synthesized: false,
+            is_async: false,
+            decorators: vec![],
+            global_names: vec![],
+            nonlocal_names: vec![],
+            type_guard: None,
+            narrowed_attributes: vec![],
         };
         res._init_symbol_mgr();
         res
     }
 
+    /* Return the functions declared with the same name that are owned by this one instead of shadowing it in its scope:
+    the setter and the deleter of a property, and the next definitions of an overloaded function */
+    pub fn owned_functions(&self) -> Vec<Rc<RefCell<Symbol>>> {
+        [&self.setter, &self.deleter].into_iter().flatten().chain(self.overloads.iter()).cloned().collect()
+    }
+
+    /* Return the owned function (see owned_functions) that is declared at the given range */
+    pub fn get_owned_function(&self, range: &TextRange) -> Option<Rc<RefCell<Symbol>>> {
+        self.owned_functions().into_iter().find(|function| function.borrow().range().start() == range.start())
+    }
+
     pub fn replace_diagnostics(&mut self, step: BuildSteps, diagnostics: Vec<Diagnostic>) {
         self.diagnostics.insert(step, diagnostics);
     }
@@ -117,29 +174,64 @@ impl FunctionSymbol {
         }
     }
 
+    /* Return true if the function can be a method: it receives the instance, or the class for a classmethod, as its first
+    positional argument, so it needs a positional parameter or *args. def f(self, *args) and def f(*args) can be methods,
+    def f(*, key) and def f(**kwargs) can't */
     pub fn can_be_in_class(&self) -> bool {
-        for arg in self.args.iter() {
-            if arg.arg_type != ArgumentType::KWARG && arg.arg_type != ArgumentType::KWORD_ONLY {
-                return true;
+        self.args.iter().any(|arg| !matches!(arg.arg_type, ArgumentType::KWARG | ArgumentType::KWORD_ONLY))
+    }
+
+    /* Return the scope where the body of the function assigns the name: the file for a name declared global, the enclosing
+    function for a name declared nonlocal. None for a local name */
+    pub fn get_outer_scope(&self, name: &str) -> Option<Rc<RefCell<Symbol>>> {
+        let is_global = self.global_names.iter().any(|global| global == name);
+        if !is_global && !self.nonlocal_names.iter().any(|nonlocal| nonlocal == name) {
+            return None;
+        }
+        let mut scope = self.parent.as_ref().and_then(|parent| parent.upgrade());
+        while let Some(current) = scope {
+            let found = match current.borrow().typ() {
+                SymType::FILE | SymType::PACKAGE(_) => true,
+                SymType::FUNCTION => !is_global,
+                _ => false
+            };
+            if found {
+                return Some(current);
             }
+            scope = current.borrow().parent().and_then(|parent| parent.upgrade());
         }
-        false
+        None
     }
 
-    /* Return true if a previous implementation has the @overload decorator or has it itself */
-    pub fn is_overloaded(&self) -> bool {
-        if self.is_overloaded {
-            return true;
-        }
-        if let Some(parent) = &self.parent {
-            if let Some(parent) = parent.upgrade() {
-                let previous_defs = parent.borrow().get_content_symbol(&self.name, self.range.start().to_u32());
-                if previous_defs.len() > 1 && previous_defs.last().unwrap().borrow().typ() == SymType::FUNCTION {
-                    return previous_defs.last().unwrap().borrow().as_func().is_overloaded;
-                }
+    /* Return true if the decorators of the function are all known to keep its parameters, like @classmethod or the
+    decorators of odoo.api. The calls of a function with another decorator, or with a decorator that can't be resolved,
+    can't be checked against its parameters, as the decorator can return a function with other parameters */
+    pub fn keeps_signature(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> bool {
+        let decorators = function.borrow().as_func().decorators.clone();
+        for decorator in decorators.iter() {
+            let symbol = decorator.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            let followed = Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]);
+            let is_known = !followed.is_empty() && followed.iter().all(|followed| followed.weak.upgrade().is_some_and(|decorator| {
+                let path = flatten_tree(&decorator.borrow().get_tree()).join(".");
+                SIGNATURE_KEEPING_DECORATORS.contains(&path.as_str())
+                    || SIGNATURE_KEEPING_MODULES.iter().any(|module| path.starts_with(module))
+            }));
+            if !is_known {
+                return false;
             }
         }
-        false
+        true
+    }
+
+    /* Return true if the function has the @overload decorator, or owns the overloads declared after it */
+    pub fn is_overloaded(&self) -> bool {
+        self.is_overloaded || !self.overloads.is_empty()
+    }
+
+    /* Return true if the next definitions with the same name are added to the overloads of the function: it has the
+    @overload decorator, and its implementation is not declared yet */
+    pub fn accepts_overloads(&self) -> bool {
+        self.is_overloaded && self.overloads.iter().all(|overload| overload.borrow().as_func().is_overloaded)
     }
 
     /**
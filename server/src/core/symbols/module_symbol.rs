@@ -1,18 +1,24 @@
-use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Position, Range};
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location, NumberOrString, Position, Range};
 use ruff_python_ast::{Expr, Stmt};
 use ruff_text_size::{Ranged, TextRange};
 use tracing::info;
 use weak_table::PtrWeakHashSet;
 use std::collections::{HashMap, HashSet};
+use std::fs;
 
 use crate::constants::*;
-use crate::core::file_mgr::FileInfo;
-use crate::core::import_resolver::find_module;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::external_dependencies::ExternalDependencies;
+use crate::core::import_resolver::{find_module, is_importable};
 use crate::core::model::Model;
+use crate::core::module_description::ModuleDescription;
+use crate::core::module_hooks::{HookResolution, ModuleHooks};
 use crate::core::odoo::SyncOdoo;
+use crate::core::python_utils;
 use crate::core::symbols::symbol::Symbol;
 use crate::constants::EXTENSION_NAME;
 use crate::core::symbols::symbol_mgr::SymbolMgr;
+use crate::core::xml_data::{XmlDataFile, XmlMethodReference};
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
 use crate::S;
@@ -23,23 +29,36 @@ use std::cell::RefCell;
 use super::symbol_mgr::SectionRange;
 
 
+/* Manifest keys that name a function of the module, called with the environment since Odoo 17 */
+pub const MANIFEST_HOOKS: [&str; 3] = ["pre_init_hook", "post_init_hook", "uninstall_hook"];
+
 #[derive(Debug)]
 pub struct ModuleSymbol {
     pub name: String,
     pub path: String,
     pub i_ext: String,
     pub is_external: bool,
-    root_path: String,
+    pub root_path: String,
     loaded: bool,
     module_name: String,
     pub dir_name: String,
     depends: Vec<String>,
-    data: Vec<String>, // TODO
+    depends_ranges: HashMap<String, TextRange>, //range of each entry of the depends of the manifest
+    pub installable: bool, //the installable key of the manifest, True by default
+    data: Vec<String>,
+    pub xml_ids: HashMap<String, Vec<(String, Range)>>, //xml ids declared by the data files, with their file and the range of the id
+    pub xml_method_references: Vec<(String, XmlMethodReference)>, //methods called by the crons and server actions of the data files
+    pub xml_field_names: HashSet<String>, //names that can be fields in the views and the data files (see XmlDataFile::field_names)
+    pub xml_id_references: Vec<(String, String, Range)>, //xml ids of other modules used by the data files, with their file and range
+    pub external_dependencies: Vec<(String, TextRange)>, //python packages of the external_dependencies of the manifest, with their range
+    pub manifest_hooks: HashMap<String, (String, TextRange)>, //pre_init_hook, post_init_hook and uninstall_hook of the manifest, with the name of their function and its range
+    description: Option<ModuleDescription>, //read from the manifest and the README on the first hover, see get_description
     pub module_symbols: HashMap<String, Rc<RefCell<Symbol>>>,
     pub arch_status: BuildStatus,
     pub arch_eval_status: BuildStatus,
     pub odoo_status: BuildStatus,
     pub validation_status: BuildStatus,
+    pub arch_eval_epoch: u64, //epoch of the last completed ARCH_EVAL, see SyncOdoo::epoch
     pub weak_self: Option<Weak<RefCell<Symbol>>>,
     pub parent: Option<Weak<RefCell<Symbol>>>,
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
@@ -51,6 +70,7 @@ pub struct ModuleSymbol {
     //Trait SymbolMgr
     pub sections: Vec<SectionRange>,
     pub symbols: HashMap<String, HashMap<u32, Vec<Rc<RefCell<Symbol>>>>>,
+    pub deleted_names: HashMap<String, HashMap<u32, Vec<u32>>>, //the end offsets of the del statements of a name, by section
     //--- dynamics variables
     pub ext_symbols: HashMap<String, Vec<Rc<RefCell<Symbol>>>>,
 }
@@ -70,7 +90,16 @@ impl ModuleSymbol {
             module_name: String::new(),
             dir_name: String::new(),
             depends: vec!("base".to_string()),
+            depends_ranges: HashMap::new(),
+            installable: true,
             data: Vec::new(),
+            xml_ids: HashMap::new(),
+            xml_method_references: vec![],
+            xml_field_names: HashSet::new(),
+            xml_id_references: vec![],
+            external_dependencies: vec![],
+            manifest_hooks: HashMap::new(),
+            description: None,
             weak_self: None,
             parent: None,
             module_symbols: HashMap::new(),
@@ -78,8 +107,10 @@ impl ModuleSymbol {
             arch_eval_status: BuildStatus::PENDING,
             odoo_status: BuildStatus::PENDING,
             validation_status: BuildStatus::PENDING,
+            arch_eval_epoch: 0,
             sections: vec![],
             symbols: HashMap::new(),
+            deleted_names: HashMap::new(),
             ext_symbols: HashMap::new(),
             model_dependencies: PtrWeakHashSet::new(),
             dependencies: [
@@ -130,12 +161,12 @@ impl ModuleSymbol {
         if manifest_file_info.ast.is_none() {
             return None;
         }
-        let diags = module._load_manifest(&manifest_file_info);
+        let diags = module._load_manifest(&manifest_file_info, session.sync_odoo.config.strict_manifest_data);
         if session.sync_odoo.modules.contains_key(&module.dir_name) {
             //TODO: handle multiple modules with the same name
         }
         manifest_file_info.replace_diagnostics(crate::constants::BuildSteps::SYNTAX, diags);
-        manifest_file_info.publish_diagnostics(session);
+        manifest_file_info.schedule_publication(session);
         drop(manifest_file_info);
         info!("End building new module: {:?}", dir_path.sanitize());
         Some(module)
@@ -147,6 +178,17 @@ impl ModuleSymbol {
         section_vec.push(content.clone());
     }
 
+    /* Return the description of the module, read from the files of the module on the first call */
+    pub fn get_description(&mut self) -> &ModuleDescription {
+        let root_path = PathBuf::from(&self.root_path);
+        self.description.get_or_insert_with(|| ModuleDescription::load(&root_path))
+    }
+
+    /* Forget the description, when the manifest or the README of the module changed */
+    pub fn reset_description(&mut self) {
+        self.description = None;
+    }
+
     pub fn load_module_info(symbol: Rc<RefCell<Symbol>>, session: &mut SessionInfo, odoo_addons: Rc<RefCell<Symbol>>) -> Vec<String> {
         {
             let _symbol = symbol.borrow();
@@ -166,16 +208,28 @@ impl ModuleSymbol {
             let manifest_file_info = session.sync_odoo.get_file_mgr().borrow_mut().get_file_info(&manifest_path.sanitize()).expect("file not found in cache").clone();
             let mut manifest_file_info = (*manifest_file_info).borrow_mut();
             manifest_file_info.replace_diagnostics(crate::constants::BuildSteps::ARCH, diagnostics);
-            manifest_file_info.publish_diagnostics(session);
+            manifest_file_info.schedule_publication(session);
         }
         loaded
     }
 
     /* Load manifest to identify the module characteristics.
     Returns list of od diagnostics to publish in manifest file. */
-    fn _load_manifest(&mut self, file_info: &FileInfo) -> Vec<Diagnostic> {
+    fn _load_manifest(&mut self, file_info: &FileInfo, strict_data: bool) -> Vec<Diagnostic> {
         let mut res = vec![];
         let ast = file_info.ast.as_ref().unwrap();
+        //the recovered ast is still read, but the keys after the syntax error can be missing
+        if !file_info.valid {
+            res.push(Diagnostic::new(
+                Range::new(Position::new(0, 0), Position::new(0, 1)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20203"))),
+                Some(EXTENSION_NAME.to_string()),
+                "The manifest has syntax errors: the dependencies of the module may be incomplete".to_string(),
+                None,
+                None,
+            ));
+        }
         let mut is_manifest_valid = true;
         if ast.len() != 1 {is_manifest_valid = false;}
         match &ast[0] {
@@ -224,25 +278,75 @@ impl ModuleSymbol {
                                         } else {
                                             let depend_value = depend.as_string_literal_expr().unwrap().value.to_string();
                                             if depend_value == self.dir_name {
-                                                res.push(self._create_diagnostic_for_manifest_key("A module cannot depends on itself", S!("OLS30206"), &depend.range()));
+                                                let range = file_info.get_string_source_map(depend)
+                                                    .and_then(|map| map.source_range(0..map.value().len()))
+                                                    .unwrap_or(depend.range());
+                                                res.push(self._create_diagnostic_for_manifest_key("A module cannot depends on itself", S!("OLS30206"), &range));
                                             } else {
+                                                self.depends_ranges.insert(depend_value.clone(), depend.range());
                                                 self.depends.push(depend_value);
                                             }
                                         }
                                     }
                                 }
                             } else if key_str == "data" {
-                                if !value.is_list_expr() {
+                                if let Some(list) = value.as_list_expr() {
+                                    for data in list.elts.iter() {
+                                        let folded = match data {
+                                            Expr::Starred(starred) => python_utils::fold_string_list(&starred.value),
+                                            data => python_utils::fold_string(data).map(|s| vec![s]),
+                                        };
+                                        match folded {
+                                            Some(files) => self.data.extend(files),
+                                            None if data.is_literal_expr() => {
+                                                res.push(self._create_diagnostic_for_manifest_key("The data key should be a list of strings", S!("OLS30208"), &data.range()));
+                                            },
+                                            None if strict_data => {
+                                                res.push(self._create_dynamic_data_diagnostic(&data.range()));
+                                            },
+                                            None => {}
+                                        }
+                                    }
+                                } else if let Some(files) = python_utils::fold_string_list(value) {
+                                    self.data.extend(files);
+                                } else if value.is_literal_expr() || value.is_dict_expr() {
                                     res.push(self._create_diagnostic_for_manifest_key("The data value should be a list", S!("OLS30207"), &key_literal.range));
-                                } else {
-                                    for data in value.as_list_expr().unwrap().elts.iter() {
-                                        if !data.is_literal_expr() {
-                                            res.push(self._create_diagnostic_for_manifest_key("The data key should be a list of strings", S!("OLS30208"), &data.range()));
-                                        } else {
-                                            self.data.push(data.as_string_literal_expr().unwrap().value.to_string());
+                                } else if strict_data {
+                                    res.push(self._create_dynamic_data_diagnostic(&value.range()));
+                                }
+                            } else if key_str == "external_dependencies" {
+                                if let Expr::Dict(dependencies) = value {
+                                    for item in dependencies.items.iter() {
+                                        if !matches!(&item.key, Some(Expr::StringLiteral(kind)) if kind.value.to_str() == "python") {
+                                            continue;
+                                        }
+                                        let Expr::List(packages) = &item.value else {
+                                            continue;
+                                        };
+                                        for package in packages.elts.iter() {
+                                            if let Expr::StringLiteral(package) = package {
+                                                self.external_dependencies.push((package.value.to_string(), package.range));
+                                            }
                                         }
                                     }
                                 }
+                            } else if key_str == "installable" {
+                                match value {
+                                    Expr::BooleanLiteral(installable) => self.installable = installable.value,
+                                    _ => res.push(Diagnostic::new(
+                                        Range::new(Position::new(value.range().start().to_u32(), 0), Position::new(value.range().end().to_u32(), 0)),
+                                        Some(DiagnosticSeverity::WARNING),
+                                        Some(NumberOrString::String(S!("OLS20204"))),
+                                        Some(EXTENSION_NAME.to_string()),
+                                        "The installable value should be True or False: the module is considered installable".to_string(),
+                                        None,
+                                        None,
+                                    ))
+                                }
+                            } else if MANIFEST_HOOKS.contains(&key_str.as_str()) {
+                                if let Expr::StringLiteral(function) = value {
+                                    self.manifest_hooks.insert(key_str, (function.value.to_string(), function.range));
+                                }
                             } else if key_str == "active" {
                                 res.push(Diagnostic::new(
                                     Range::new(Position::new(key_literal.range.start().to_u32(), 0), Position::new(key_literal.range.end().to_u32(), 0)),
@@ -279,6 +383,18 @@ impl ModuleSymbol {
         res
     }
 
+    fn _create_dynamic_data_diagnostic(&self, range: &TextRange) -> Diagnostic {
+        Diagnostic::new(
+            Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+            Some(DiagnosticSeverity::INFORMATION),
+            Some(NumberOrString::String(S!("OLS10001"))),
+            Some(EXTENSION_NAME.to_string()),
+            "This data file is not statically verifiable".to_string(),
+            None,
+            None,
+        )
+    }
+
     fn _create_diagnostic_for_manifest_key(&self, text: &str, code: String, range: &TextRange) -> Diagnostic {
         Diagnostic::new(
             Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
@@ -303,7 +419,7 @@ impl ModuleSymbol {
                 let module = find_module(session, odoo_addons.clone(), depend);
                 if module.is_none() {
                     session.sync_odoo.not_found_symbols.insert(symbol.weak_self().as_ref().unwrap().upgrade().expect("The symbol must be in the tree"));
-                    symbol.not_found_paths_mut().push((BuildSteps::ARCH, vec![S!("odoo"), S!("addons"), depend.clone()]));
+                    symbol.add_not_found_path(BuildSteps::ARCH, vec![S!("odoo"), S!("addons"), depend.clone()]);
                     diagnostics.push(Diagnostic::new(
                         Range::new(Position::new(0, 0), Position::new(0, 1)),
                         Some(DiagnosticSeverity::ERROR),
@@ -324,14 +440,160 @@ impl ModuleSymbol {
                 let mut module = (*module).borrow_mut();
                 symbol.add_dependency(&mut module, BuildSteps::ARCH, BuildSteps::ARCH)
             }
+            let depend_module = session.sync_odoo.modules.get(depend).and_then(|module| module.upgrade());
+            if depend_module.is_some_and(|module| !module.borrow().as_module_package().installable) {
+                let range = symbol.as_module_package().depends_ranges.get(depend).cloned().unwrap_or_default();
+                diagnostics.push(Diagnostic::new(
+                    Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                    Some(DiagnosticSeverity::WARNING),
+                    Some(NumberOrString::String(S!("OLS20205"))),
+                    Some(EXTENSION_NAME.to_string()),
+                    format!("{} is not installable, so this module can't be installed either", depend),
+                    None,
+                    None,
+                ));
+            }
         }
         (diagnostics, loaded)
     }
 
-    fn _load_data(_symbol: Rc<RefCell<Symbol>>, _odoo: &mut SyncOdoo) -> Vec<Diagnostic> {
+    /* Index the xml ids, the references to the xml ids of other modules, the method references and the field names of the
+    xml and csv data files of the manifest */
+    fn _load_data(symbol: Rc<RefCell<Symbol>>, _odoo: &mut SyncOdoo) -> Vec<Diagnostic> {
+        let mut symbol = symbol.borrow_mut();
+        let module = symbol.as_module_package_mut();
+        module.xml_ids.clear();
+        module.xml_method_references.clear();
+        module.xml_field_names.clear();
+        module.xml_id_references.clear();
+        for data in module.data.clone().iter() {
+            let path = PathBuf::from(&module.root_path).join(data);
+            let parse = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("xml") => XmlDataFile::parse_xml,
+                Some("csv") => XmlDataFile::parse_csv,
+                _ => continue
+            };
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue; //missing data files are not reported here
+            };
+            let path = path.sanitize();
+            let data_file = parse(&text);
+            for xml_id in data_file.ids.iter() {
+                module.xml_ids.entry(xml_id.full_id(&module.dir_name)).or_default().push((path.clone(), xml_id.range));
+            }
+            module.xml_method_references.extend(data_file.method_references.into_iter().map(|reference| (path.clone(), reference)));
+            module.xml_field_names.extend(data_file.field_names);
+            module.xml_id_references.extend(data_file.id_references.into_iter().map(|(id, range)| (path.clone(), id, range)));
+        }
         vec![]
     }
 
+    /* Return the modules of the depends of the manifest */
+    pub fn get_depends(&self) -> &Vec<String> {
+        &self.depends
+    }
+
+    /* Return true if the xml id is declared by the data files of the module, or None if they are not indexed yet */
+    pub fn has_xml_id(&self, xml_id: &str) -> Option<bool> {
+        if !self.loaded {
+            return None;
+        }
+        Some(self.xml_ids.contains_key(xml_id))
+    }
+
+    /* Return the methods of the model of the reference that have its name, or None if the model is unknown */
+    pub fn find_xml_method(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>, reference: &XmlMethodReference) -> Option<Vec<Rc<RefCell<Symbol>>>> {
+        let model = session.sync_odoo.models.iter().find(|(name, _)| reference.is_on_model(name)).map(|(_, model)| model.clone())?;
+        let mut res = vec![];
+        for class in model.borrow().get_main_symbols(session, Some(module.clone()), &mut None).iter() {
+            let (members, _) = class.borrow().get_member_symbol(session, &reference.method, Some(module.clone()), false, false, false, false);
+            res.extend(members.into_iter().filter(|member| member.borrow().typ() == SymType::FUNCTION));
+        }
+        Some(res)
+    }
+
+    /* Return the diagnostics of the methods called by the data files that don't exist on their model. They are reported
+    on the manifest, with the location of the call as related information */
+    pub fn check_xml_method_references(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>) -> Vec<Diagnostic> {
+        let references = module.borrow().as_module_package().xml_method_references.clone();
+        let mut res = vec![];
+        for (path, reference) in references.iter() {
+            let Some(methods) = ModuleSymbol::find_xml_method(session, module, reference) else {
+                continue;
+            };
+            if !methods.is_empty() {
+                continue;
+            }
+            let file_name = PathBuf::from(path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            res.push(Diagnostic {
+                range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(S!("OLS20020"))),
+                source: Some(EXTENSION_NAME.to_string()),
+                message: format!("{} is not a method of the model of {}, but it is called by {}", reference.method, reference.model_ref, file_name),
+                related_information: Some(vec![DiagnosticRelatedInformation {
+                    location: Location { uri: FileMgr::pathname2uri(path), range: reference.range },
+                    message: S!("call in the data file"),
+                }]),
+                ..Diagnostic::default()
+            });
+        }
+        res
+    }
+
+    /* Return the diagnostics of the hooks of the manifest that are not functions of the module. Odoo looks for them in
+    the __init__.py of the module, when the module is installed or uninstalled */
+    pub fn check_manifest_hooks(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>) -> Vec<Diagnostic> {
+        let mut hooks: Vec<(String, (String, TextRange))> = module.borrow().as_module_package().manifest_hooks.clone().into_iter().collect();
+        hooks.sort_by_key(|(_, (_, range))| range.start());
+        //a name imported from a file that is not found could be the hook
+        let unresolved_imports = !module.borrow().not_found_paths().is_empty();
+        let mut res = vec![];
+        for (hook, (function, range)) in hooks.iter() {
+            let (code, message) = match ModuleHooks::resolve_hook(session, module, function) {
+                HookResolution::NOT_FOUND if !unresolved_imports => ("OLS30337", format!("{} is not declared in the __init__.py of the module, but it is the {}", function, hook)),
+                HookResolution::NOT_CALLABLE => ("OLS30338", format!("{} is not a function, but it is the {}", function, hook)),
+                _ => continue
+            };
+            res.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::ERROR),
+                Some(NumberOrString::String(S!(code))),
+                Some(EXTENSION_NAME.to_string()),
+                message,
+                None,
+                None,
+            ));
+        }
+        res
+    }
+
+    /* Return the diagnostics of the python packages of the external_dependencies of the manifest that can't be imported
+    in the configured interpreter: Odoo refuses to install the module */
+    pub fn check_external_dependencies(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>) -> Vec<Diagnostic> {
+        if !session.sync_odoo.config.check_external_dependencies {
+            return vec![];
+        }
+        let dependencies = module.borrow().as_module_package().external_dependencies.clone();
+        let mut res = vec![];
+        for (distribution, range) in dependencies.iter() {
+            let package = ExternalDependencies::get_import_name(distribution);
+            if package.is_empty() || is_importable(session, &package) {
+                continue;
+            }
+            res.push(Diagnostic::new(
+                Range::new(Position::new(range.start().to_u32(), 0), Position::new(range.end().to_u32(), 0)),
+                Some(DiagnosticSeverity::WARNING),
+                Some(NumberOrString::String(S!("OLS20202"))),
+                Some(EXTENSION_NAME.to_string()),
+                format!("The external dependency '{}' can't be imported in the configured interpreter", distribution),
+                None,
+                None,
+            ));
+        }
+        res
+    }
+
     fn _load_arch(symbol: Rc<RefCell<Symbol>>, session: &mut SessionInfo) -> Vec<Diagnostic> {
         let root_path = (*symbol).borrow().as_module_package().root_path.clone();
         let tests_path = PathBuf::from(root_path).join("tests");
@@ -108,6 +108,7 @@ pub struct PythonPackageSymbol {
     pub arch_eval_status: BuildStatus,
     pub odoo_status: BuildStatus,
     pub validation_status: BuildStatus,
+    pub arch_eval_epoch: u64, //epoch of the last completed ARCH_EVAL, see SyncOdoo::epoch
     pub not_found_paths: Vec<(BuildSteps, Vec<String>)>,
     pub in_workspace: bool,
     pub self_import: bool,
@@ -119,6 +120,7 @@ pub struct PythonPackageSymbol {
     //Trait SymbolMgr
     pub sections: Vec<SectionRange>,
     pub symbols: HashMap<String, HashMap<u32, Vec<Rc<RefCell<Symbol>>>>>,
+    pub deleted_names: HashMap<String, HashMap<u32, Vec<u32>>>, //the end offsets of the del statements of a name, by section
     //--- dynamics variables
     pub ext_symbols: HashMap<String, Vec<Rc<RefCell<Symbol>>>>,
 }
@@ -137,12 +139,14 @@ impl PythonPackageSymbol {
             arch_eval_status: BuildStatus::PENDING,
             odoo_status: BuildStatus::PENDING,
             validation_status: BuildStatus::PENDING,
+            arch_eval_epoch: 0,
             not_found_paths: vec![],
             in_workspace: false,
             self_import: false, //indicates that if unloaded, the symbol should be added in the rebuild automatically as nothing depends on it (used for root packages)
             module_symbols: HashMap::new(),
             sections: vec![],
             symbols: HashMap::new(),
+            deleted_names: HashMap::new(),
             ext_symbols: HashMap::new(),
             model_dependencies: PtrWeakHashSet::new(),
             dependencies: [
@@ -1,12 +1,16 @@
-use ruff_text_size::{TextSize, TextRange};
+use ruff_python_ast::Stmt;
+use ruff_text_size::{Ranged, TextSize, TextRange};
 use tracing::{info, trace};
 use weak_table::traits::WeakElement;
 
 use crate::constants::*;
+use crate::core::borrows::Borrows;
 use crate::core::evaluation::{Context, Evaluation, EvaluationSymbolWeak};
+use crate::core::file_mgr::FileInfo;
 use crate::core::model::Model;
 use crate::core::odoo::SyncOdoo;
 use crate::core::python_arch_eval::PythonArchEval;
+use crate::features::ast_utils::AstUtils;
 use crate::threads::SessionInfo;
 use crate::utils::{PathSanitizer as _};
 use crate::S;
@@ -228,6 +232,34 @@ impl Symbol {
         function
     }
 
+    /* Add a setter (@name.setter) or a deleter (@name.deleter) to the getter of a property. The accessor is owned by the
+    getter, so the reads of the property still give the value returned by the getter */
+    pub fn add_new_property_accessor(&mut self, name: &String, range: &TextRange, body_start: &TextSize, is_setter: bool) -> Rc<RefCell<Self>> {
+        let accessor = self._new_owned_function(name, range, body_start);
+        let getter = self.as_func_mut();
+        match is_setter {
+            true => getter.setter = Some(accessor.clone()),
+            false => getter.deleter = Some(accessor.clone())
+        }
+        accessor
+    }
+
+    /* Add an overload (@overload) or the implementation to a function decorated by @overload. They are owned by the first
+    overload, so all the signatures are kept instead of the last one only */
+    pub fn add_new_overload(&mut self, name: &String, range: &TextRange, body_start: &TextSize) -> Rc<RefCell<Self>> {
+        let overload = self._new_owned_function(name, range, body_start);
+        self.as_func_mut().overloads.push(overload.clone());
+        overload
+    }
+
+    /* Create a function of the scope of this function, that is owned by it instead of being added to the scope */
+    fn _new_owned_function(&self, name: &String, range: &TextRange, body_start: &TextSize) -> Rc<RefCell<Self>> {
+        let function = Rc::new(RefCell::new(Symbol::Function(FunctionSymbol::new(name.clone(), range.clone(), body_start.clone(), self.is_external()))));
+        function.borrow_mut().set_weak_self(Rc::downgrade(&function));
+        function.borrow_mut().set_parent(self.parent());
+        function
+    }
+
     pub fn add_new_class(&mut self, _session: &mut SessionInfo, name: &String, range: &TextRange, body_start: &TextSize) -> Rc<RefCell<Self>> {
         let class = Rc::new(RefCell::new(Symbol::Class(ClassSymbol::new(name.clone(), range.clone(), body_start.clone(), self.is_external()))));
         class.borrow_mut().set_weak_self(Rc::downgrade(&class));
@@ -270,6 +302,12 @@ impl Symbol {
             _ => {panic!("Not a Root")}
         }
     }
+    pub fn as_compiled(&self) -> &CompiledSymbol {
+        match self {
+            Symbol::Compiled(c) => c,
+            _ => {panic!("Not a Compiled")}
+        }
+    }
     pub fn as_file(&self) -> &FileSymbol {
         match self {
             Symbol::File(f) => f,
@@ -373,6 +411,17 @@ impl Symbol {
         }
     }
 
+    pub fn as_mut_symbol_mgr(&mut self) -> &mut dyn SymbolMgr {
+        match self {
+            Symbol::File(f) => f,
+            Symbol::Class(c) => c,
+            Symbol::Function(f) => f,
+            Symbol::Package(PackageSymbol::Module(m)) => m,
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => p,
+            _ => {panic!("Not a symbol Mgr");}
+        }
+    }
+
     pub fn typ(&self) -> SymType {
         match self {
             Symbol::Root(_) => SymType::ROOT,
@@ -530,6 +579,18 @@ impl Symbol {
         }
     }
 
+    /* Return the statement that declares the symbol in the AST of its file, without walking the file. The AST is
+    rebuilt at each change of the file, while the indexes are only rebuilt with the arch of the file: until then, they
+    may point to another statement, and None is returned */
+    pub fn get_ast_node<'a>(&self, file_info: &'a FileInfo) -> Option<&'a Stmt> {
+        let indexes = self.ast_indexes()?;
+        let stmt = AstUtils::get_stmt_from_ast(file_info.ast.as_ref()?, indexes)?;
+        let matches = stmt.range().contains_range(*self.range());
+        //a file that is not opened can't be edited without being rebuilt
+        debug_assert!(matches || file_info.opened, "ast indexes of {} don't match the ast of {}", self.name(), file_info.uri);
+        matches.then_some(stmt)
+    }
+
     pub fn weak_self(&self) -> Option<Weak<RefCell<Symbol>>> {
         match self {
             Symbol::Root(r) => r.weak_self.clone(),
@@ -858,6 +919,23 @@ impl Symbol {
         }
     }
 
+    /* Register a path that can't be resolved during the given step, so the symbol can be rebuilt when this path is created
+    (see SyncOdoo::search_symbols_to_rebuild). The path is ignored if a registered path of the same step is one of its
+    prefixes, as any update matching the path would match this prefix too, and longer paths are replaced by a new prefix.
+    The number of paths is capped: above MAX_NOT_FOUND_PATHS, the symbol stays registered by its other paths, and the missing
+    ones will be registered again at its next rebuild. */
+    pub fn add_not_found_path(&mut self, step: BuildSteps, tree: Vec<String>) {
+        let paths = self.not_found_paths_mut();
+        if paths.iter().any(|(s, t)| *s == step && tree.starts_with(t)) {
+            return;
+        }
+        paths.retain(|(s, t)| *s != step || !t.starts_with(&tree));
+        if paths.len() >= MAX_NOT_FOUND_PATHS {
+            return;
+        }
+        paths.push((step, tree));
+    }
+
     pub fn not_found_paths_mut(&mut self) -> &mut Vec<(BuildSteps, Vec<String>)> {
         match self {
             Symbol::File(f) => { &mut f.not_found_paths },
@@ -1162,6 +1240,43 @@ impl Symbol {
         symbol.dependents_as_mut()[level_i][step_i].insert(self.get_rc().unwrap());
     }
 
+    /* Add the dependency between two symbols given by their Rc, borrowing them only for the insertion. A symbol never
+    depends on itself: the hooks and the imports of a file can lead to the file itself, that is then ignored */
+    pub fn add_dependency_to(symbol: &Rc<RefCell<Symbol>>, dependency: &Rc<RefCell<Symbol>>, step: BuildSteps, dep_level: BuildSteps) {
+        if Rc::ptr_eq(symbol, dependency) {
+            return;
+        }
+        Borrows::borrow_mut(symbol).add_dependency(&mut Borrows::borrow_mut(dependency), step, dep_level);
+    }
+
+    /* Return the epoch of the last completed ARCH_EVAL of a file or a package */
+    pub fn arch_eval_epoch(&self) -> Option<u64> {
+        match self {
+            Symbol::Package(PackageSymbol::Module(m)) => Some(m.arch_eval_epoch),
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => Some(p.arch_eval_epoch),
+            Symbol::File(f) => Some(f.arch_eval_epoch),
+            _ => None
+        }
+    }
+
+    pub fn set_arch_eval_epoch(&mut self, epoch: u64) {
+        match self {
+            Symbol::Package(PackageSymbol::Module(m)) => m.arch_eval_epoch = epoch,
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => p.arch_eval_epoch = epoch,
+            Symbol::File(f) => f.arch_eval_epoch = epoch,
+            _ => {}
+        }
+    }
+
+    pub fn model_dependencies(&self) -> Option<&PtrWeakHashSet<Weak<RefCell<Model>>>> {
+        match self {
+            Symbol::Package(PackageSymbol::Module(m)) => Some(&m.model_dependencies),
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => Some(&p.model_dependencies),
+            Symbol::File(f) => Some(&f.model_dependencies),
+            _ => None
+        }
+    }
+
     pub fn add_model_dependencies(&mut self, model: &Rc<RefCell<Model>>) {
         match self {
             Symbol::Package(PackageSymbol::Module(m)) => {
@@ -1236,9 +1351,11 @@ impl Symbol {
                     }
                     for class in sym_to_inv.iter_classes() {
                         if let Some(model_data) = &class.borrow().as_class_sym()._model {
-                            let model = session.sync_odoo.models.get(&model_data.name).cloned();
-                            if let Some(model) = model {
-                                model.borrow().add_dependents_to_validation(session);
+                            for model_name in model_data.names() {
+                                let model = session.sync_odoo.models.get(model_name).cloned();
+                                if let Some(model) = model {
+                                    model.borrow().add_dependents_to_validation(session);
+                                }
                             }
                         }
                     }
@@ -1255,7 +1372,13 @@ impl Symbol {
     pub fn invalidate_sub_functions(&mut self, _session: &mut SessionInfo) {
         if matches!(&self.typ(), SymType::FILE | SymType::PACKAGE(_)) {
             for func in self.iter_inner_functions() {
-                func.borrow_mut().evaluations_mut().unwrap().clear();
+                //the lambdas have no body: their returned expression is evaluated with the file
+                if func.borrow().as_func().is_lambda || func.borrow().as_func().is_synthesized {
+                    continue;
+                }
+                if let Symbol::Function(f) = &mut *func.borrow_mut() {
+                    f.evaluations = f.declared_evaluations.clone();
+                }
                 func.borrow_mut().set_build_status(BuildSteps::ARCH_EVAL, BuildStatus::PENDING);
                 func.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
             }
@@ -1288,7 +1411,10 @@ impl Symbol {
             parent_bw.remove_symbol(ref_to_unload.clone());
             drop(parent_bw);
             if matches!(&ref_to_unload.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+                session.sync_odoo.evaluation_cache.touch_file(Rc::as_ptr(&ref_to_unload) as usize);
                 Symbol::invalidate(session, ref_to_unload.clone(), &BuildSteps::ARCH);
+                Symbol::remove_dependencies(&ref_to_unload);
+                Symbol::remove_dependents(&ref_to_unload);
             }
             //check if we should not reimport automatically
             match ref_to_unload.borrow().typ() {
@@ -1308,9 +1434,11 @@ impl Symbol {
                 },
                 Symbol::Class(ref mut c) => {
                     if let Some(model_data) = c._model.as_ref() {
-                        let model = session.sync_odoo.models.get(&model_data.name).cloned();
-                        if let Some(model) = model {
-                            model.borrow_mut().remove_symbol(session, &ref_to_unload);
+                        for model_name in model_data.names() {
+                            let model = session.sync_odoo.models.get(model_name).cloned();
+                            if let Some(model) = model {
+                                model.borrow_mut().remove_symbol(session, &ref_to_unload);
+                            }
                         }
                     }
                 },
@@ -1320,6 +1448,92 @@ impl Symbol {
         }
     }
 
+    /* Unload the content of a file or a package (its classes, functions and variables) before it is rebuilt, so the new
+    build doesn't add its symbols next to the previous ones. The dependencies of the file are removed, as the build adds
+    them again, and its dependents are invalidated, as they refer to the removed symbols */
+    pub fn unload_content(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) {
+        let content: Vec<Rc<RefCell<Symbol>>> = symbol.borrow().iter_symbols()
+            .flat_map(|(_, sections)| sections.values().flatten().cloned())
+            .collect();
+        if content.is_empty() {
+            return;
+        }
+        if DEBUG_MEMORY {
+            info!("Unloading content of {:?} at {:?}", symbol.borrow().name(), symbol.borrow().paths());
+        }
+        for sym in content.into_iter() {
+            Symbol::unload(session, sym);
+        }
+        match *symbol.borrow_mut() {
+            Symbol::File(ref mut f) => {
+                f.sections.clear();
+                f._init_symbol_mgr();
+            },
+            Symbol::Package(PackageSymbol::Module(ref mut m)) => {
+                m.sections.clear();
+                m._init_symbol_mgr();
+            },
+            Symbol::Package(PackageSymbol::PythonPackage(ref mut p)) => {
+                p.sections.clear();
+                p._init_symbol_mgr();
+            },
+            _ => {}
+        }
+        Symbol::invalidate(session, symbol.clone(), &BuildSteps::ARCH);
+        Symbol::remove_dependencies(symbol);
+    }
+
+    /* Remove the dependencies of a file, a package or a namespace, in both directions: the symbol is removed from the
+    dependents of the symbols and models it depends on */
+    pub fn remove_dependencies(symbol: &Rc<RefCell<Symbol>>) {
+        let mut symbol_bw = symbol.borrow_mut();
+        for (step, levels) in symbol_bw.dependencies_mut().iter_mut().enumerate() {
+            for (level, dependencies) in levels.iter_mut().enumerate() {
+                for dependency in dependencies.iter() {
+                    if Rc::ptr_eq(&dependency, symbol) {
+                        continue; //the symbol is already borrowed, its dependents are cleared with them
+                    }
+                    if let Some(dependents) = dependency.borrow_mut().dependents_as_mut()[level].get_mut(step) {
+                        dependents.remove(symbol);
+                    }
+                }
+                dependencies.clear();
+            }
+        }
+        let model_dependencies = match &mut *symbol_bw {
+            Symbol::File(f) => Some(&mut f.model_dependencies),
+            Symbol::Package(PackageSymbol::Module(m)) => Some(&mut m.model_dependencies),
+            Symbol::Package(PackageSymbol::PythonPackage(p)) => Some(&mut p.model_dependencies),
+            _ => None
+        };
+        if let Some(model_dependencies) = model_dependencies {
+            for model in model_dependencies.iter() {
+                model.borrow_mut().dependents.remove(symbol);
+            }
+            model_dependencies.clear();
+        }
+    }
+
+    /* Remove the dependents of a file, a package or a namespace, in both directions: the symbol is removed from the
+    dependencies of the symbols that depend on it. Used when the symbol is unloaded, after its dependents have been
+    invalidated */
+    pub fn remove_dependents(symbol: &Rc<RefCell<Symbol>>) {
+        let mut symbol_bw = symbol.borrow_mut();
+        for (level, steps) in symbol_bw.dependents_as_mut().iter_mut().enumerate() {
+            for (step, dependents) in steps.iter_mut().enumerate() {
+                for dependent in dependents.iter() {
+                    if Rc::ptr_eq(&dependent, symbol) {
+                        continue;
+                    }
+                    if let Some(dependencies) = dependent.borrow_mut().dependencies_mut()[step].get_mut(level) {
+                        dependencies.remove(symbol);
+                    }
+                }
+                dependents.clear();
+            }
+        }
+    }
+
     pub fn get_rc(&self) -> Option<Rc<RefCell<Symbol>>> {
         if self.weak_self().is_none() {
             return None;
@@ -1404,9 +1618,21 @@ impl Symbol {
                     if sym.borrow().range().start() == range.start() {
                         return Some(sym.clone());
                     }
+                    //the setter and the deleter of a property are owned by its getter, and the overloads by the first one
+                    if let Symbol::Function(function) = &*sym.borrow() {
+                        if let Some(owned) = function.get_owned_function(range) {
+                            return Some(owned);
+                        }
+                    }
                 }
             }
         }
+        //the variables assigned after a global or nonlocal statement are declared in the outer scope
+        if let Symbol::Function(f) = self {
+            if let Some(outer) = f.get_outer_scope(name) {
+                return outer.borrow().get_positioned_symbol(name, range);
+            }
+        }
         None
     }
 
@@ -1507,67 +1733,123 @@ impl Symbol {
         let Some(symbol) = evaluation.weak.upgrade() else {
             return vec![evaluation.clone()];
         };
-        //return a list of all possible evaluation: a weak ptr to the final symbol, and a bool indicating if this is an instance or not
-        let mut results = Symbol::next_refs(session, &symbol.borrow(), &mut vec![]);
-        if results.is_empty() {
+        let key = (Rc::as_ptr(&symbol) as usize, stop_on_type, stop_on_value, max_scope.as_ref().map(|s| Rc::as_ptr(s) as usize).unwrap_or(0));
+        let results = match session.sync_odoo.evaluation_cache.get(&key) {
+            Some((cached_symbol, results)) if cached_symbol.upgrade().is_some_and(|s| Rc::ptr_eq(&s, &symbol)) => results,
+            _ => {
+                let mut read = vec![];
+                let results = Symbol::_follow_ref(&symbol, session, stop_on_type, stop_on_value, max_scope, &mut read);
+                //the files of the followed variables and of the results
+                read.extend(results.iter().flatten().filter_map(|r| r.weak.upgrade()));
+                let mut files = vec![];
+                let mut reads_models = false;
+                for sym in read.iter() {
+                    //a symbol being built is not stored, as its file is being built too
+                    let Some(file) = Symbol::_get_file_address(sym) else {
+                        return Symbol::_apply_follow_ref(evaluation, context, results);
+                    };
+                    files.push(file);
+                    reads_models |= sym.borrow().evaluations().is_some_and(|evals| evals.iter().any(|e| e.symbol.get_symbol_hook.is_some()));
+                }
+                session.sync_odoo.evaluation_cache.insert(key, (Rc::downgrade(&symbol), results.clone()), files, reads_models);
+                results
+            }
+        };
+        Symbol::_apply_follow_ref(evaluation, context, results)
+    }
+
+    fn _apply_follow_ref(evaluation: &EvaluationSymbolWeak, context: &mut Option<Context>, results: Option<Vec<EvaluationSymbolWeak>>) -> Vec<EvaluationSymbolWeak> {
+        let Some(results) = results else {
             return vec![evaluation.clone()];
-        }
+        };
         //there is a 'next_ref'. Remove "parent" from context if any
         if context.is_some() {
             context.as_mut().unwrap().remove(&S!("parent"));
         }
+        results
+    }
+
+    /* Return the address of the file of the symbol, identifying it in the EvaluationCache. None if the symbol or one
+    of its parents is borrowed mutably */
+    fn _get_file_address(symbol: &Rc<RefCell<Symbol>>) -> Option<usize> {
+        let mut current = symbol.clone();
+        loop {
+            let parent = {
+                let current_bw = current.try_borrow().ok()?;
+                if matches!(current_bw.typ(), SymType::FILE | SymType::PACKAGE(_)) {
+                    return Some(Rc::as_ptr(&current) as usize);
+                }
+                current_bw.parent().as_ref().and_then(|p| p.upgrade())?
+            };
+            current = parent;
+        }
+    }
+
+    /* Follow the evaluations of the symbol. Return None if it has nothing to follow. The followed symbols are added to read */
+    fn _follow_ref(symbol: &Rc<RefCell<Symbol>>, session: &mut SessionInfo, stop_on_type: bool, stop_on_value: bool, max_scope: Option<Rc<RefCell<Symbol>>>, read: &mut Vec<Rc<RefCell<Symbol>>>) -> Option<Vec<EvaluationSymbolWeak>> {
+        read.push(symbol.clone());
+        //return a list of all possible evaluation: a weak ptr to the final symbol, and a bool indicating if this is an instance or not
+        let mut to_follow: VecDeque<(EvaluationSymbolWeak, u32)> = Symbol::next_refs(session, &symbol.borrow(), &mut vec![]).into_iter().map(|r| (r, 1)).collect();
+        if to_follow.is_empty() {
+            return None;
+        }
         let can_eval_external = !symbol.borrow().is_external();
-        let mut index = 0;
-        while index < results.len() {
-            let next_ref = &results[index];
-            let sym = next_ref.weak.upgrade();
-            if sym.is_none() {
-                index += 1;
+        let max_depth = session.sync_odoo.config.evaluation_depth;
+        //the variables already followed, to stop on evaluation cycles (a = b; b = a), that can go through other files
+        let mut followed: PtrWeakHashSet<Weak<RefCell<Symbol>>> = PtrWeakHashSet::new();
+        followed.insert(symbol.clone());
+        let mut results = vec![];
+        while let Some((next_ref, depth)) = to_follow.pop_front() {
+            let Some(sym) = next_ref.weak.upgrade() else {
+                results.push(next_ref);
                 continue;
-            }
-            let sym = sym.unwrap();
-            let sym = sym.borrow();
-            match *sym {
-                Symbol::Variable(ref v) => {
-                    if stop_on_type && !next_ref.is_instance().unwrap_or(false) && !v.is_import_variable {
-                        index += 1;
-                        continue;
-                    }
-                    if stop_on_value && v.evaluations.len() == 1 && v.evaluations[0].value.is_some() {
-                        index += 1;
-                        continue;
-                    }
-                    if max_scope.is_some() && !sym.has_rc_in_parents(max_scope.as_ref().unwrap().clone(), true) {
-                        index += 1;
-                        continue;
-                    }
-                    if v.evaluations.is_empty() && can_eval_external {
-                        //no evaluation? let's check that the file has been evaluated
-                        let file_symbol = sym.get_file();
-                        if let Some(file_symbol) = file_symbol {
-                            if file_symbol.upgrade().expect("invalid weak value").borrow().build_status(BuildSteps::ARCH) == BuildStatus::PENDING &&
-                            session.sync_odoo.is_in_rebuild(&file_symbol.upgrade().unwrap(), BuildSteps::ARCH_EVAL) { //TODO check ARCH ?
-                                let mut builder = PythonArchEval::new(file_symbol.upgrade().unwrap());
-                                builder.eval_arch(session);
-                            }
-                        }
-                    }
-                    let next_sym_refs = Symbol::next_refs(session, &sym, &mut vec![]);
-                    index += 1;
-                    if !next_sym_refs.is_empty() {
-                        results.pop_front();
-                        index -= 1;
-                        for next_results in next_sym_refs {
-                            results.push_back(next_results);
-                        }
-                    }
+            };
+            let follow = match &*sym.borrow() {
+                Symbol::Variable(v) => {
+                    !(stop_on_type && !next_ref.is_instance().unwrap_or(false) && !v.is_import_variable) &&
+                    !(stop_on_value && v.evaluations.len() == 1 && v.evaluations[0].value.is_some()) &&
+                    !(max_scope.is_some() && !sym.borrow().has_rc_in_parents(max_scope.as_ref().unwrap().clone(), true))
                 },
-                _ => {
-                    index += 1;
-                }
+                _ => false
+            };
+            if !follow || depth > max_depth || followed.contains(&sym) {
+                results.push(next_ref);
+                continue;
+            }
+            followed.insert(sym.clone());
+            read.push(sym.clone());
+            if can_eval_external {
+                Symbol::eval_file_of_variable(session, &sym);
+            }
+            let next_sym_refs = Symbol::next_refs(session, &sym.borrow(), &mut vec![]);
+            if next_sym_refs.is_empty() {
+                results.push(next_ref);
+            } else {
+                to_follow.extend(next_sym_refs.into_iter().map(|r| (r, depth + 1)));
+            }
+        }
+        Some(results)
+    }
+
+    /* Evaluate the file of a variable that has no evaluation yet, if this file is waiting for its evaluation. This
+    gives a type to the variables imported from files that are not evaluated yet, like 'config' in odoo.tools */
+    pub fn eval_file_of_variable(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>) {
+        let file_symbol = {
+            let variable = variable.borrow();
+            if !variable.evaluations().is_some_and(|evals| evals.is_empty()) {
+                return;
             }
+            variable.get_file().and_then(|f| f.upgrade())
+        };
+        let Some(file_symbol) = file_symbol else {
+            return;
+        };
+        if file_symbol.borrow().build_status(BuildSteps::ARCH) == BuildStatus::DONE &&
+            file_symbol.borrow().build_status(BuildSteps::ARCH_EVAL) == BuildStatus::PENDING &&
+            session.sync_odoo.is_in_rebuild(&file_symbol, BuildSteps::ARCH_EVAL) {
+            let mut builder = PythonArchEval::new(file_symbol);
+            builder.eval_arch(session);
         }
-        Vec::from(results) // :'( a whole copy?
     }
 
     pub fn all_symbols(&self) -> impl Iterator<Item= Rc<RefCell<Symbol>>> {
@@ -1621,6 +1903,20 @@ impl Symbol {
         iter.into_iter()
     }
 
+    /* If the symbol is a member of a model class, return the contributions of the modules to this member (see
+    Model::get_member_contributions), seen from from_module */
+    pub fn get_member_contributions(symbol: &Rc<RefCell<Symbol>>, session: &mut SessionInfo, from_module: Option<Rc<RefCell<Symbol>>>) -> Option<Vec<(String, Vec<Rc<RefCell<Symbol>>>)>> {
+        let parent = symbol.borrow().parent()?.upgrade()?;
+        if parent.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        let model_name = parent.borrow().as_class_sym()._model.as_ref()?.name.clone();
+        let model = session.sync_odoo.models.get(&model_name).cloned()?;
+        let name = symbol.borrow().name().clone();
+        let contributions = model.borrow().get_member_contributions(session, &name, from_module);
+        Some(contributions)
+    }
+
     //store in result all available members for self: sub symbols, base class elements and models symbols
     pub fn all_members(symbol: &Rc<RefCell<Symbol>>, session: &mut SessionInfo, result: &mut HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>>, with_co_models: bool, from_module: Option<Rc<RefCell<Symbol>>>, acc: &mut Option<HashSet<Tree>>, is_super: bool) {
         if acc.is_none() {
@@ -1647,9 +1943,10 @@ impl Symbol {
                 }
                 if with_co_models {
                     let sym = symbol.borrow();
-                    let model_data =  sym.as_class_sym()._model.as_ref();
-                    if let Some(model_data) = model_data {
-                        if let Some(model) = session.sync_odoo.models.get(&model_data.name).cloned() {
+                    let model_names: Vec<String> = sym.as_class_sym()._model.iter().flat_map(|m| m.names().cloned()).collect();
+                    //a class added to several models gets the members of all of them
+                    for model_name in model_names.iter() {
+                        if let Some(model) = session.sync_odoo.models.get(model_name).cloned() {
                             for model_sym in model.borrow().all_symbols(session, from_module.clone()) {
                                 if !Rc::ptr_eq(symbol, &model_sym.0) {
                                     for s in model_sym.0.borrow().all_symbols() {
@@ -1686,36 +1983,36 @@ impl Symbol {
     /* return the Symbol (class, function or file) the closest to the given offset */
     pub fn get_scope_symbol(file_symbol: Rc<RefCell<Symbol>>, offset: u32, is_param: bool) -> Rc<RefCell<Symbol>> {
         let mut result = file_symbol.clone();
-        let section_id = file_symbol.borrow().as_symbol_mgr().get_section_for(offset);
         for (sym_name, sym_map) in file_symbol.borrow().iter_symbols() {
-            match sym_map.get(&section_id.index) {
-                Some(symbols) => {
-                    for symbol in symbols.iter() {
-                        let typ = symbol.borrow().typ();
-                        match typ {
-                            SymType::CLASS => {
-                                let range = match is_param {
-                                    true => symbol.borrow().range().start().to_u32(),
-                                    false => symbol.borrow().body_range().start().to_u32(),
-                                };
-                                if range < offset && symbol.borrow().body_range().end().to_u32() > offset {
-                                    result = Symbol::get_scope_symbol(symbol.clone(), offset, is_param);
-                                }
-                            },
-                            SymType::FUNCTION => {
+            for symbols in sym_map.values() { //classes and functions can be declared in any section (if, for, ...)
+                for symbol in symbols.iter() {
+                    let typ = symbol.borrow().typ();
+                    match typ {
+                        SymType::CLASS => {
+                            let range = match is_param {
+                                true => symbol.borrow().range().start().to_u32(),
+                                false => symbol.borrow().body_range().start().to_u32(),
+                            };
+                            if range < offset && symbol.borrow().body_range().end().to_u32() > offset {
+                                result = Symbol::get_scope_symbol(symbol.clone(), offset, is_param);
+                            }
+                        },
+                        SymType::FUNCTION => {
+                            //the setter and the deleter of a property are owned by its getter, and the overloads by the first one
+                            let owned = symbol.borrow().as_func().owned_functions();
+                            for function in std::iter::once(symbol.clone()).chain(owned.into_iter()) {
                                 let range = match is_param {
-                                    true => symbol.borrow().range().start().to_u32(),
-                                    false => symbol.borrow().body_range().start().to_u32(),
+                                    true => function.borrow().range().start().to_u32(),
+                                    false => function.borrow().body_range().start().to_u32(),
                                 };
-                                if range < offset && symbol.borrow().body_range().end().to_u32() > offset {
-                                    result = Symbol::get_scope_symbol(symbol.clone(), offset, is_param);
+                                if range < offset && function.borrow().body_range().end().to_u32() > offset {
+                                    result = Symbol::get_scope_symbol(function.clone(), offset, is_param);
                                 }
                             }
-                            _ => {}
                         }
+                        _ => {}
                     }
-                },
-                None => {}
+                }
             }
         }
         result
@@ -1814,6 +2111,9 @@ impl Symbol {
     pub fn is_field(&self, session: &mut SessionInfo) -> bool {
         match self.typ() {
             SymType::VARIABLE => {
+                if self.as_variable().is_dynamic_field {
+                    return true;
+                }
                 if let Some(evals) = self.evaluations().as_ref() {
                     for eval in evals.iter() {
                         let symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
@@ -1827,6 +2127,9 @@ impl Symbol {
                                         return true;
                                     }
                                 }
+                                if symbol.borrow().typ() == SymType::CLASS && session.sync_odoo.plugin_rules.get_field_rule(&symbol).is_some() {
+                                    return true;
+                                }
                             }
                         }
                     }
@@ -1842,6 +2145,34 @@ impl Symbol {
     if not all, it will return the first found. If all, the all found symbols are returned, but the first one
     is the one that is overriding others.
     :param: from_module: optional, can change the from_module of the given class */
+    /* Return true if the class, or a class of its model visible from from_module, adds fields whose names are unknown, or
+    if the name of its model is unknown */
+    pub fn has_dynamic_fields(&self, session: &mut SessionInfo, from_module: Option<Rc<RefCell<Symbol>>>) -> bool {
+        if self.typ() != SymType::CLASS {
+            return false;
+        }
+        //the members of a model whose name is unknown can't be checked against the other classes of the model
+        if self.as_class_sym().has_dynamic_fields || self.as_class_sym()._model.as_ref().is_some_and(|m| m.unknown_name) {
+            return true;
+        }
+        let Some(model) = self.as_class_sym()._model.as_ref().and_then(|m| session.sync_odoo.models.get(&m.name).cloned()) else {
+            return false;
+        };
+        let Some(from_module) = from_module.or_else(|| self.find_module()) else {
+            return false;
+        };
+        let model_symbols: Vec<Rc<RefCell<Symbol>>> = model.borrow().get_symbols(session, from_module).collect();
+        model_symbols.iter().any(|s| !self.is_equal(s) && s.borrow().as_class_sym().has_dynamic_fields)
+    }
+
+    /* Return the __getattr__ function of a module (PEP 562), that gives the attributes the module does not declare */
+    pub fn get_module_getattr(&self) -> Option<Rc<RefCell<Symbol>>> {
+        if !matches!(self.typ(), SymType::FILE | SymType::PACKAGE(_)) {
+            return None;
+        }
+        self.get_sub_symbol("__getattr__", u32::MAX).into_iter().find(|symbol| symbol.borrow().typ() == SymType::FUNCTION)
+    }
+
     pub fn get_member_symbol(&self, session: &mut SessionInfo, name: &String, from_module: Option<Rc<RefCell<Symbol>>>, prevent_comodel: bool, only_fields: bool, all: bool, is_super: bool) -> (Vec<Rc<RefCell<Symbol>>>, Vec<Diagnostic>) {
         let mut result: Vec<Rc<RefCell<Symbol>>> = vec![];
         let mut visited_symbols: PtrWeakHashSet<Weak<RefCell<Symbol>>> = PtrWeakHashSet::new();
@@ -1879,8 +2210,11 @@ impl Symbol {
             }
         }
         if self.typ() == SymType::CLASS && self.as_class_sym()._model.is_some() && !prevent_comodel {
-            let model = session.sync_odoo.models.get(&self.as_class_sym()._model.as_ref().unwrap().name);
-            if let Some(model) = model {
+            let model_names: Vec<String> = self.as_class_sym()._model.as_ref().unwrap().names().cloned().collect();
+            for model_name in model_names.iter() {
+                let Some(model) = session.sync_odoo.models.get(model_name).cloned() else {
+                    continue;
+                };
                 let mut from_module = from_module.clone();
                 if from_module.is_none() {
                     from_module = self.find_module();
@@ -1941,8 +2275,9 @@ impl Symbol {
                     for (_name, section) in c.symbols.iter() {
                         for (_position, symbol_list) in section.iter() {
                             for symbol in symbol_list.iter() {
-                                if let Symbol::Function(_) = *symbol.borrow() {
-                                    res.push(symbol.clone())
+                                if let Symbol::Function(f) = &*symbol.borrow() {
+                                    res.push(symbol.clone());
+                                    res.extend(f.owned_functions());
                                 }
                             }
                         }
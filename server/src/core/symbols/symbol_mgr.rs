@@ -23,10 +23,12 @@ pub trait SymbolMgr {
     fn get_section_for(&self, position: u32) -> SectionRange;
     fn add_section(&mut self, range: TextRange) -> SectionRange;
     fn change_parent(&mut self, new_parent: SectionIndex, section: &mut SectionRange);
+    fn get_last_index(&self) -> SectionIndex;
     fn get_symbol(&self, name: String, position: u32) -> Vec<Rc<RefCell<Symbol>>>;
     fn get_ext_symbol(&self, name: String) -> Option<&Vec<Rc<RefCell<Symbol>>>>;
+    fn add_deleted_name(&mut self, name: &str, position: u32);
     fn _init_symbol_mgr(&mut self);
-    fn _get_loc_symbol(&self, map: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>, position: u32, index: &SectionIndex, acc: &mut Vec<u32>) -> Vec<Rc<RefCell<Symbol>>>;
+    fn _get_loc_symbol(&self, name: &str, map: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>, position: u32, index: &SectionIndex, acc: &mut Vec<u32>) -> Vec<Rc<RefCell<Symbol>>>;
 }
 
 
@@ -35,27 +37,86 @@ pub trait SymbolMgr {
 given:
 i = IfStmt
 ei = ElifStmt
+e = ElseStmt
 
-old_last_section = last_section
-i_body = i.body)
+pre_if = last_section
+i_body = add_section(end of i.test) //the narrowed variables of the branch (see Narrowings) are declared at its start
     visit_body
-ei_condition = add_section(ei.condition)
-    visit_condition
-ei_body = add_section(ei.body)
+i_body_end = last_section
+ei_condition = add_section(ei.test)
+ei_body = add_section(end of ei.test)
     visit_body
-else_body = add_section(Range_none) //needed to have the possibility  to have ei_condition evaluated but not body
-next_sections = last_section
+ei_body_end = last_section
+e_body = add_section(e) //starts at the else keyword
+    visit_body
+e_body_end = last_section
+next_sections = add_section(end of i)
 
-change_parent(old_last_section, ei_condition)
+change_parent(pre_if, i_body)
+change_parent(pre_if, ei_condition) //the condition of an elif is only evaluated if the previous ones are false
 change_parent(ei_condition, ei_body)
-change_parent(ei_condition, else_body)
-change_parent(SectionIndex::Or(old_last_section | ei_body | else_body), next_sections)
+change_parent(ei_condition, e_body)
+change_parent(SectionIndex::Or(i_body_end | ei_body_end | e_body_end), next_sections) //ei_condition instead of e_body_end if there is no else
+If the executed branch is known statically (see StaticConditions), next_sections only has the end of this branch as
+parent. Otherwise the ends of the branches that always exit (return or raise, see python_utils::always_exits) are
+removed from its parents, unless all of them exit. After an if without else whose branches all exit, the variables
+narrowed by the negation of the tests are declared at the start of next_sections.
+
+Note on how to declare sections for a for loop:
+
+given:
+f = ForStmt
+
+pre_loop = last_section
+f_body = add_section(f.target) //the target is only assigned in the body
+    visit_body
+body_end = last_section
+f_orelse = add_section(f.orelse)
+    visit_orelse
+orelse_end = last_section
+next_sections = add_section(end of f)
+
+change_parent(pre_loop, f_body)
+change_parent(SectionIndex::Or(pre_loop | body_end), f_orelse) //the loop may not be executed at all
+change_parent(SectionIndex::Or(orelse_end | body_end), next_sections) //body_end only if the body contains a break
+
+Note on how to declare sections for a try:
+
+given:
+t = TryStmt
+h = ExceptHandler
+
+pre_try = last_section
+    visit_body //the body is executed in the current section
+body_end = last_section
+h_body = add_section(h) //starts at the except keyword, one per handler
+    visit_handler
+h_body_end = last_section
+t_orelse = add_section(t.orelse)
+    visit_orelse
+orelse_end = last_section
+t_finally = add_section(t.finalbody)
+    visit_finalbody
+finally_end = last_section
+next_sections = add_section(end of t)
+
+change_parent(SectionIndex::Or(pre_try | body_end), h_body) //the variables of the body are possibly bound in a handler
+change_parent(body_end, t_orelse)
+change_parent(SectionIndex::Or(orelse_end | h_body_end), t_finally) //body_end instead of orelse_end if there is no else
+change_parent(finally_end, next_sections) //or the parent of t_finally if there is no finally
+
+Note on del statements:
+
+A `del name` ends the declarations of the name in the section of the statement: the lookups positioned after it don't
+search the previous symbols of this section nor the parent sections, until the name is declared again. The lookups
+coming from another branch (OR parents) still see the declarations of this branch.
     */
 
 macro_rules! impl_section_mgr_for {
     ($($t:ty),+ $(,)?) => ($(
     impl SymbolMgr for $t {
         fn _init_symbol_mgr(&mut self) {
+            self.deleted_names.clear();
             self.sections.push(SectionRange{
                 start: 0,
                 index: 0,
@@ -64,20 +125,18 @@ macro_rules! impl_section_mgr_for {
         }
 
         fn get_section_for(&self, position: u32) -> SectionRange {
-            let mut last_section = self.sections.last().unwrap();
-            for section in self.sections.iter().rev().skip(1) { //reverse to fasten most calls as they will be with TextSize::MAX
-                if section.start < position {
-                    break;
+            for section in self.sections.iter().rev() { //reverse to fasten most calls as they will be with TextSize::MAX
+                if section.start <= position {
+                    return section.clone();
                 }
-                last_section = section;
             }
-            last_section.clone()
+            self.sections[0].clone()
         }
 
         /* Add a section at the END of the sections */
         fn add_section(&mut self, range: TextRange) -> SectionRange {
             if cfg!(debug_assertions) {
-                assert!(range.start().to_u32() > self.sections.last().unwrap().start);
+                assert!(range.start().to_u32() >= self.sections.last().unwrap().start);
             }
 
             let last_index = (self.sections.len() -1) as u32;
@@ -96,15 +155,20 @@ macro_rules! impl_section_mgr_for {
         }
 
         fn change_parent(&mut self, new_parent: SectionIndex, section: &mut SectionRange) {
+            self.sections[section.index as usize].previous_indexes = new_parent.clone();
             section.previous_indexes = new_parent;
         }
 
+        fn get_last_index(&self) -> SectionIndex {
+            SectionIndex::INDEX(self.sections.last().unwrap().index)
+        }
+
         ///Return all the symbols that are valid as last declaration for the given position
         fn get_symbol(&self, name: String, position: u32) -> Vec<Rc<RefCell<Symbol>>> {
             let sections: Option<&HashMap<u32, Vec<Rc<RefCell<Symbol>>>>> = self.symbols.get(&name);
             if let Some(sections) = sections {
                 let section: SectionRange = self.get_section_for(position);
-                return self._get_loc_symbol(sections, position, &SectionIndex::INDEX(section.index), &mut vec![]);
+                return self._get_loc_symbol(&name, sections, position, &SectionIndex::INDEX(section.index), &mut vec![]);
             }
             vec![]
         }
@@ -113,29 +177,48 @@ macro_rules! impl_section_mgr_for {
             self.ext_symbols.get(&name)
         }
 
+        /* Declare a del of the name ending at position, in the section containing it */
+        fn add_deleted_name(&mut self, name: &str, position: u32) {
+            let section = self.get_section_for(position);
+            self.deleted_names.entry(name.to_string()).or_default().entry(section.index).or_default().push(position);
+        }
+
         ///given all the sections of a symbol and a position, return all the Symbols that can represent the symbol
-        fn _get_loc_symbol(&self, map: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>, position: u32, index: &SectionIndex, acc: &mut Vec<u32>) -> Vec<Rc<RefCell<Symbol>>> {
+        fn _get_loc_symbol(&self, name: &str, map: &HashMap<u32, Vec<Rc<RefCell<Symbol>>>>, position: u32, index: &SectionIndex, acc: &mut Vec<u32>) -> Vec<Rc<RefCell<Symbol>>> {
             let mut res = vec![];
             match index {
                 SectionIndex::NONE => { return res; },
                 SectionIndex::INDEX(index) => {
+                    if acc.contains(index) { //already visited through another path
+                        return res;
+                    }
+                    acc.push(*index);
                     let section = self.sections.get(*index as usize).unwrap();
                     //take index and try to find an evaluation. if no evaluation is found, search in previous index, and mix evaluation if there is multiple precedences
-                    for loc_sym in map.get(index).unwrap().iter().rev() {
-                        if loc_sym.borrow().range().start().to_u32() < position {
-                            res.push(loc_sym.clone());
-                            break;
+                    if let Some(loc_syms) = map.get(index) {
+                        for loc_sym in loc_syms.iter().rev() {
+                            if loc_sym.borrow().range().start().to_u32() < position {
+                                res.push(loc_sym.clone());
+                                break;
+                            }
+                        }
+                    }
+                    //a del after the last declaration hides the name, in this section and the previous ones
+                    let last_deletion = self.deleted_names.get(name).and_then(|deletions| deletions.get(index))
+                        .and_then(|deletions| deletions.iter().filter(|deletion| **deletion <= position).max());
+                    if let Some(last_deletion) = last_deletion {
+                        if res.first().map_or(true, |loc_sym| loc_sym.borrow().range().start().to_u32() < *last_deletion) {
+                            return vec![];
                         }
                     }
                     if !res.is_empty() {
                         return res;
                     }
-                    acc.push(*index);
-                    res = self._get_loc_symbol(map, position, &section.previous_indexes, acc);
+                    res = self._get_loc_symbol(name, map, position, &section.previous_indexes, acc);
                 },
                 SectionIndex::OR(indexes) => {
                     for index in indexes.iter() {
-                        res.extend(self._get_loc_symbol(map, position, index, acc));
+                        res.extend(self._get_loc_symbol(name, map, position, index, acc));
                     }
                 }
             }
@@ -14,7 +14,13 @@ pub struct VariableSymbol {
     pub weak_self: Option<Weak<RefCell<Symbol>>>,
     pub parent: Option<Weak<RefCell<Symbol>>>,
     pub is_import_variable: bool,
+    pub is_type_checking_import: bool, //imported in the body of an if TYPE_CHECKING: only known by the type checkers, not at runtime
     pub is_parameter: bool,
+    pub is_dynamic_field: bool, //field added to its model by code (see DynamicFields), without declaration in the class body
+    pub is_narrowed: bool, //variable restricted in a branch of an if, or after an assert or a guard clause (see Narrowings). Its declarations are the previous ones
+    pub is_container_mutation: bool, //container changed by a statement, like vals['key'] = value (see ContainerMutations). Its declarations are the previous ones
+    pub is_explicit_type_alias: bool, //declared by a type statement, or annotated with TypeAlias. Its evaluations are the aliased types
+    pub type_params: Vec<String>, //type parameters of a generic type statement, like T in type Pair[T] = tuple[T, T]
     pub evaluations: Vec<Evaluation>, //Vec, because sometimes a single allocation can be ambiguous, like ''' a = "5" if X else 5 '''
     pub range: TextRange,
 }
@@ -31,12 +37,21 @@ impl VariableSymbol {
             parent: None,
             range,
             is_import_variable: false,
+            is_type_checking_import: false,
             is_parameter: false,
+            is_dynamic_field: false,
+            is_narrowed: false,
+            is_container_mutation: false,
+            is_explicit_type_alias: false,
+            type_params: vec![],
             evaluations: vec![],
         }
     }
 
     pub fn is_type_alias(&self) -> bool {
+        if self.is_explicit_type_alias {
+            return true;
+        }
         //TODO it does not use get_symbol call, and only evaluate "sym" from EvaluationSymbol
         return self.evaluations.len() >= 1 && self.evaluations.iter().all(|x| !x.symbol.is_instance().unwrap_or(true)) && !self.is_import_variable;
     }
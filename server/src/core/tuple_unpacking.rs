@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::{Expr, ExprList, ExprTuple, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::core::dataclasses::Dataclasses;
+use crate::core::evaluation::{Evaluation, EvaluationValue};
+use crate::core::file_mgr::FileMgr;
+use crate::core::python_utils;
+use crate::core::python_validator::PythonValidator;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+
+/* Maximum depth of the calls followed to find a returned tuple, for a helper returning the result of another helper */
+const MAX_RETURN_DEPTH: u32 = 5;
+
+/* The targets of an unpacking assignment, like `qty, price = self._get_qty_price()`, are evaluated to the elements of the
+value when its length is fixed: a tuple or a list literal, a variable holding a literal, an instance of a NamedTuple, or a
+call to a function whose return statements all return tuples of the same length, directly or through another call. Otherwise the targets are not
+evaluated, as the value itself is not the type of any of them */
+pub struct TupleUnpacking {}
+
+impl TupleUnpacking {
+
+    /* Return the elements of the tuple or list target that contains the name at the given range */
+    pub fn get_unpacking_target<'a>(targets: &'a [Expr], name_range: &TextRange) -> Option<&'a [Expr]> {
+        for target in targets.iter() {
+            let (Expr::Tuple(ExprTuple { elts, .. }) | Expr::List(ExprList { elts, .. })) = target else {
+                continue;
+            };
+            if elts.iter().any(|elt| matches!(elt, Expr::Name(name) if name.range == *name_range)) {
+                return Some(elts);
+            }
+            if let Some(res) = TupleUnpacking::get_unpacking_target(elts, name_range) {
+                return Some(res);
+            }
+        }
+        None
+    }
+
+    /* Return the index in the value of the target at the given index, the targets after a starred one being counted
+    from the end of the value */
+    pub fn get_element_index(targets: &[Expr], index: usize, length: usize) -> Option<usize> {
+        let res = match targets.iter().position(|target| target.is_starred_expr()) {
+            Some(starred) if index > starred => (length + index).checked_sub(targets.len())?,
+            _ => index
+        };
+        (res < length).then_some(res)
+    }
+
+    /* Return the number of elements of a tuple or list literal, if it has no starred element */
+    pub fn get_literal_length(value: &Expr) -> Option<usize> {
+        match value {
+            Expr::Tuple(ExprTuple { elts, .. }) | Expr::List(ExprList { elts, .. }) if !elts.iter().any(|elt| elt.is_starred_expr()) => Some(elts.len()),
+            _ => None
+        }
+    }
+
+    /* Return the message of the diagnostic if the targets can't be unpacked from a value of the given length */
+    pub fn check_arity(targets: &[Expr], length: usize) -> Option<String> {
+        if targets.iter().any(|target| target.is_starred_expr()) {
+            let expected = targets.len() - 1;
+            return (length < expected).then(|| format!("Not enough values to unpack (expected at least {}, got {})", expected, length));
+        }
+        match length.cmp(&targets.len()) {
+            std::cmp::Ordering::Less => Some(format!("Not enough values to unpack (expected {}, got {})", targets.len(), length)),
+            std::cmp::Ordering::Greater => Some(format!("Too many values to unpack (expected {})", targets.len())),
+            std::cmp::Ordering::Equal => None
+        }
+    }
+
+    /* Return the evaluations of each element of the value, if its length is fixed */
+    pub fn eval_elements(session: &mut SessionInfo, value: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize) -> Option<Vec<Vec<Evaluation>>> {
+        TupleUnpacking::_eval_elements(session, value, scope, max_infer, 0)
+    }
+
+    fn _eval_elements(session: &mut SessionInfo, value: &Expr, scope: Rc<RefCell<Symbol>>, max_infer: &TextSize, depth: u32) -> Option<Vec<Vec<Evaluation>>> {
+        match value {
+            Expr::Tuple(ExprTuple { elts, .. }) | Expr::List(ExprList { elts, .. }) => {
+                if elts.iter().any(|elt| elt.is_starred_expr()) {
+                    return None;
+                }
+                Some(elts.iter().map(|elt| Evaluation::eval_from_ast(session, elt, scope.clone(), max_infer).0).collect())
+            },
+            Expr::Call(call) => {
+                let (evaluations, _) = Evaluation::eval_from_ast(session, &call.func, scope, max_infer);
+                let mut res: Option<Vec<Vec<Evaluation>>> = None;
+                for evaluation in evaluations.iter() {
+                    let function = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade()?;
+                    let typ = function.borrow().typ();
+                    let elements = match typ {
+                        SymType::FUNCTION => TupleUnpacking::get_returned_elements(session, &function, depth)?,
+                        //the instance created by a NamedTuple class has its fields as elements
+                        SymType::CLASS => Dataclasses::get_named_tuple_elements(&function)?,
+                        _ => return None
+                    };
+                    res = Some(TupleUnpacking::merge(res, elements)?);
+                }
+                res
+            },
+            _ => {
+                //a variable holding a tuple or a list of literals
+                let (evaluations, _) = Evaluation::eval_from_ast(session, value, scope, max_infer);
+                let [evaluation] = evaluations.as_slice() else {
+                    return None;
+                };
+                if let Some(elements) = TupleUnpacking::get_named_tuple_elements(session, evaluation) {
+                    return Some(elements);
+                }
+                match &evaluation.value {
+                    Some(EvaluationValue::TUPLE(items) | EvaluationValue::LIST(items)) if !items.is_empty() => {
+                        Some(items.iter().map(|item| vec![Evaluation::new_constant(&mut session.sync_odoo, item.clone(), item.range())]).collect())
+                    },
+                    _ => None
+                }
+            }
+        }
+    }
+
+    /* Return the elements of the evaluation if it is an instance of a NamedTuple class */
+    fn get_named_tuple_elements(session: &mut SessionInfo, evaluation: &Evaluation) -> Option<Vec<Vec<Evaluation>>> {
+        let symbol = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+        let followed = Symbol::follow_ref(&symbol, session, &mut None, false, false, None, &mut vec![]);
+        let [instance] = followed.as_slice() else {
+            return None;
+        };
+        let class = instance.weak.upgrade()?;
+        if !instance.instance.unwrap_or(false) || class.borrow().typ() != SymType::CLASS {
+            return None;
+        }
+        Dataclasses::get_named_tuple_elements(&class)
+    }
+
+    /* Return the elements of the values of the return statements of the function, evaluated out of its scope */
+    fn get_returned_elements(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, depth: u32) -> Option<Vec<Vec<Evaluation>>> {
+        if depth >= MAX_RETURN_DEPTH {
+            return None;
+        }
+        //the local variables of the function are evaluated by its validation, once its file is built
+        let file_done = function.borrow().get_file().and_then(|file| file.upgrade())
+            .is_some_and(|file| file.borrow().build_status(BuildSteps::ODOO) == BuildStatus::DONE);
+        if file_done && function.borrow().build_status(BuildSteps::VALIDATION) == BuildStatus::PENDING {
+            PythonValidator::new(function.clone()).validate(session);
+        }
+        let returns: Vec<Expr> = {
+            let file_info = FileMgr::get_file_info_of_symbol(session, function)?;
+            let file_info = file_info.borrow();
+            let function = function.borrow();
+            let Stmt::FunctionDef(function_def) = function.get_ast_node(&file_info)? else {
+                return None;
+            };
+            //a bare return gives None, that can't be unpacked
+            python_utils::get_returned_values(&function_def.body).into_iter().map(|returned| returned.cloned()).collect::<Option<Vec<Expr>>>()?
+        };
+        let mut res = None;
+        for returned in returns.iter() {
+            let elements = TupleUnpacking::_eval_elements(session, returned, function.clone(), &returned.range().start(), depth + 1)?;
+            let elements = elements.into_iter().map(|evaluations| evaluations.iter()
+                .flat_map(|evaluation| evaluation.get_eval_out_of_function_scope(session, function))
+                .collect()).collect();
+            res = Some(TupleUnpacking::merge(res, elements)?);
+        }
+        res
+    }
+
+    /* Add the evaluations of the elements of another possible value, that must have the same length */
+    fn merge(res: Option<Vec<Vec<Evaluation>>>, elements: Vec<Vec<Evaluation>>) -> Option<Vec<Vec<Evaluation>>> {
+        let Some(mut res) = res else {
+            return Some(elements);
+        };
+        if res.len() != elements.len() {
+            return None;
+        }
+        for (evaluations, new_evaluations) in res.iter_mut().zip(elements.into_iter()) {
+            for new_evaluation in new_evaluations.into_iter() {
+                if !evaluations.iter().any(|evaluation| evaluation.eq_type(&new_evaluation)) {
+                    evaluations.push(new_evaluation);
+                }
+            }
+        }
+        Some(res)
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::constants::SymType;
+use crate::core::evaluation::EvaluationSymbolWeak;
+use crate::core::symbols::symbol::Symbol;
+
+/* How the arms of a union declare a member */
+#[derive(Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum MemberPresence {
+    ALL,
+    SOME(Vec<usize>), //the indexes of the arms missing the member
+    NONE,
+}
+
+/* A receiver evaluated to several types, like a variable assigned an int in a branch and a str in the other, is a
+union. Its members are the union of the members of its arms: a member declared by some arms only is valid, but only when
+the receiver holds one of these arms. The recordsets of a class added to several models with _inherit are unions of
+these models. */
+pub struct UnionMembers {}
+
+impl UnionMembers {
+
+    /* Return the presence of a member among the arms of a union, given by whether each arm declares it */
+    pub fn get_presence(declared: &[bool]) -> MemberPresence {
+        let missing: Vec<usize> = declared.iter().enumerate().filter(|(_, d)| !**d).map(|(index, _)| index).collect();
+        if missing.is_empty() {
+            MemberPresence::ALL
+        } else if missing.len() == declared.len() {
+            MemberPresence::NONE
+        } else {
+            MemberPresence::SOME(missing)
+        }
+    }
+
+    /* Return the text marking a member declared by some arms only: "only when str". None if all arms declare it */
+    pub fn get_provenance(arms: &[String], providers: &[String]) -> Option<String> {
+        let declared: Vec<bool> = arms.iter().map(|arm| providers.contains(arm)).collect();
+        match UnionMembers::get_presence(&declared) {
+            MemberPresence::SOME(missing) => {
+                let providing: Vec<&str> = arms.iter().enumerate().filter(|(index, _)| !missing.contains(index)).map(|(_, arm)| arm.as_str()).collect();
+                Some(format!("only when {}", providing.join(" | ")))
+            },
+            _ => None
+        }
+    }
+
+    /* Return the arms of a union: the distinct classes the evaluations are followed to. Other symbols, like modules or
+    unknown variables, are not arms, as their members are not known */
+    pub fn get_arms(evaluations: &Vec<EvaluationSymbolWeak>) -> Vec<(Rc<RefCell<Symbol>>, bool)> {
+        let mut res: Vec<(Rc<RefCell<Symbol>>, bool)> = vec![];
+        for evaluation in evaluations.iter() {
+            let Some(symbol) = evaluation.weak.upgrade() else {
+                continue;
+            };
+            if symbol.borrow().typ() != SymType::CLASS || res.iter().any(|(arm, _)| Rc::ptr_eq(arm, &symbol)) {
+                continue;
+            }
+            res.push((symbol, evaluation.instance.unwrap_or(false)));
+        }
+        res
+    }
+
+    /* Return the name of an arm, as shown in the completion and the hover: the model of a model class */
+    pub fn get_arm_name(arm: &Rc<RefCell<Symbol>>) -> String {
+        let arm = arm.borrow();
+        if arm.typ() != SymType::CLASS {
+            return arm.name().clone();
+        }
+        match arm.as_class_sym()._model.as_ref() {
+            Some(model) if !model.unknown_name => model.name.clone(),
+            _ => arm.name().clone()
+        }
+    }
+}
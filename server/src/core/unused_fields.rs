@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Instant;
+
+use glob::Pattern;
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, Location, MessageType, NumberOrString, Position, Range};
+use ruff_python_ast::visitor::{walk_expr, walk_keyword, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, Keyword, Stmt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::{BuildSteps, EXTENSION_NAME};
+use crate::core::field_consistency::FieldDeclaration;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+pub const FIND_UNUSED_FIELDS_COMMAND: &str = "odooLs.findUnusedFields";
+pub const UNUSED_FIELDS_PROGRESS_METHOD: &str = "$Odoo/unusedFieldsProgress";
+
+/* Options of the findUnusedFields command, given as its first argument: {"modules": ["sale_custom"], "publishHints": true} */
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UnusedFieldsOptions {
+    pub modules: Vec<String>, //the workspace modules whose fields are searched. Empty for all of them
+    pub exclude: Vec<String>, //globs of field names that are never reported, added to the unusedFieldsExclude setting
+    pub include_groups: bool, //report the fields restricted to groups, that are often read by the framework only
+    pub include_abstract: bool, //report the fields of the abstract models, that are used by the models inheriting them
+    pub publish_hints: bool, //publish the unused fields as hints on their declaration, until the next validation of the file
+}
+
+impl UnusedFieldsOptions {
+
+    pub fn from_arguments(arguments: &Vec<Value>) -> Result<UnusedFieldsOptions, String> {
+        match arguments.first() {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| format!("Invalid options for {}: {}", FIND_UNUSED_FIELDS_COMMAND, e)),
+            None => Ok(UnusedFieldsOptions::default()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnusedField {
+    pub model: String,
+    pub name: String,
+    pub location: Location,
+}
+
+/* The unused fields declared by a module, returned by the command and sent in its progress notifications */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleUnusedFields {
+    pub module: String,
+    pub fields: Vec<UnusedField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnusedFieldsProgress {
+    pub done: usize, //modules searched, including this one
+    pub total: usize,
+    pub result: ModuleUnusedFields,
+}
+
+/* The names that can designate a field in the python code: the attributes, the keyword arguments, and the identifiers
+of the strings, that hold the depends, related, domains, orders and field lists. A field is used if its name is in the
+set, whatever the model of the receiver: the search is conservative, and a field is only reported if its name is never
+written anywhere. The strings of the declaration of a field don't use it: name = fields.Char(related='partner_id.name') */
+#[derive(Debug, Default)]
+pub struct FieldUsages {
+    names: HashSet<String>,
+}
+
+impl FieldUsages {
+
+    pub fn add_python(&mut self, body: &[Stmt]) {
+        let mut visitor = UsageVisitor { names: &mut self.names };
+        for stmt in body.iter() {
+            visitor.visit_stmt(stmt);
+        }
+    }
+
+    /* Add the names found by the xml indexer in the views and the data files */
+    pub fn add_names<'a>(&mut self, names: impl Iterator<Item = &'a String>) {
+        self.names.extend(names.cloned());
+    }
+
+    pub fn is_used(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+}
+
+struct UsageVisitor<'b> {
+    names: &'b mut HashSet<String>,
+}
+
+impl<'a, 'b> Visitor<'a> for UsageVisitor<'b> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Some(field) = FieldDeclaration::from_stmt(stmt) {
+            let mut own_names = HashSet::new();
+            walk_stmt(&mut UsageVisitor { names: &mut own_names }, stmt);
+            own_names.remove(&field.name);
+            self.names.extend(own_names);
+            return;
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Attribute(attr) => {
+                self.names.insert(attr.attr.to_string());
+            },
+            Expr::StringLiteral(string) => {
+                let value = string.value.to_str();
+                self.names.extend(value.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|name| !name.is_empty()).map(|name| name.to_string()));
+            },
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_keyword(&mut self, keyword: &'a Keyword) {
+        if let Some(arg) = keyword.arg.as_ref() {
+            self.names.insert(arg.to_string());
+        }
+        walk_keyword(self, keyword);
+    }
+}
+
+/* Workspace analysis of the fields that are declared but never read, written, or shown in a view. Legacy modules
+accumulate such fields, that still cost a column and a place in the forms. The fields declared by the classes of the
+workspace modules are searched in the python files of the workspace and in the data files of its modules. The search is
+long on a big workspace: the results of each module are sent in a notification as soon as they are known */
+pub struct UnusedFields {}
+
+impl UnusedFields {
+
+    pub fn find(session: &mut SessionInfo, options: UnusedFieldsOptions) -> Result<Vec<ModuleUnusedFields>, String> {
+        let start = Instant::now();
+        let mut patterns = vec![];
+        for glob in session.sync_odoo.config.unused_fields_exclude.iter().chain(options.exclude.iter()) {
+            patterns.push(Pattern::new(glob).map_err(|e| format!("Invalid pattern '{}': {}", glob, e))?);
+        }
+        let usages = UnusedFields::collect_usages(session);
+        let declarations = UnusedFields::get_declarations(session, &options, &patterns);
+        let total = declarations.len();
+        let mut res = vec![];
+        let mut hints: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        for (done, (module, fields)) in declarations.into_iter().enumerate() {
+            let mut unused = vec![];
+            for (model, field, file_info) in fields.iter() {
+                if usages.is_used(&field.name) {
+                    continue;
+                }
+                let file_info = file_info.borrow();
+                unused.push(UnusedField {
+                    model: model.clone(),
+                    name: field.name.clone(),
+                    location: Location {
+                        uri: FileMgr::pathname2uri(&file_info.uri),
+                        range: Range::new(file_info.offset_to_position(field.range.start().to_usize()), file_info.offset_to_position(field.range.end().to_usize())),
+                    },
+                });
+                if options.publish_hints {
+                    hints.entry(file_info.uri.clone()).or_default().push(Diagnostic::new(
+                        Range::new(Position::new(field.range.start().to_u32(), 0), Position::new(field.range.end().to_u32(), 0)),
+                        Some(DiagnosticSeverity::HINT),
+                        Some(NumberOrString::String(S!("OLS10003"))),
+                        Some(EXTENSION_NAME.to_string()),
+                        format!("{} is never read, written or shown in a view", field.name),
+                        None,
+                        Some(vec![DiagnosticTag::UNNECESSARY]),
+                    ));
+                }
+            }
+            let result = ModuleUnusedFields { module, fields: unused };
+            session.send_notification(UNUSED_FIELDS_PROGRESS_METHOD, UnusedFieldsProgress { done: done + 1, total, result: result.clone() });
+            res.push(result);
+        }
+        for (path, diagnostics) in hints.into_iter() {
+            let Some(file_info) = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path) else {
+                continue;
+            };
+            let mut file_info = file_info.borrow_mut();
+            file_info.update_validation_diagnostics(HashMap::from([(BuildSteps::VALIDATION, diagnostics)]));
+            file_info.publish_diagnostics(session);
+        }
+        session.log_message(MessageType::INFO, format!("Unused fields found in {} ms: {} field(s) in {} module(s)", start.elapsed().as_millis(),
+            res.iter().map(|m| m.fields.len()).sum::<usize>(), res.len()));
+        Ok(res)
+    }
+
+    /* Collect the names used by the python files of the workspace and by the data files of the workspace modules */
+    fn collect_usages(session: &mut SessionInfo) -> FieldUsages {
+        let mut usages = FieldUsages::default();
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let files: Vec<Rc<RefCell<FileInfo>>> = file_mgr.borrow().files.iter()
+            .filter(|(path, _)| path.ends_with(".py") && file_mgr.borrow().is_in_workspace(path, false))
+            .map(|(_, file_info)| file_info.clone()).collect();
+        for file_info in files.iter() {
+            if let Some(ast) = file_info.borrow().ast.as_ref() {
+                usages.add_python(ast);
+            }
+        }
+        for module in session.sync_odoo.modules.values().filter_map(|m| m.upgrade()) {
+            let module = module.borrow();
+            if module.in_workspace() {
+                usages.add_names(module.as_module_package().xml_field_names.iter());
+            }
+        }
+        usages
+    }
+
+    /* Return the fields declared by the model classes of the selected workspace modules, by module, with their model
+    and the file of their class */
+    fn get_declarations(session: &mut SessionInfo, options: &UnusedFieldsOptions, patterns: &Vec<Pattern>) -> BTreeMap<String, Vec<(String, FieldDeclaration, Rc<RefCell<FileInfo>>)>> {
+        let mut res: BTreeMap<String, Vec<(String, FieldDeclaration, Rc<RefCell<FileInfo>>)>> = BTreeMap::new();
+        let mut seen_classes: HashSet<*const RefCell<Symbol>> = HashSet::new();
+        let models: Vec<_> = session.sync_odoo.models.values().cloned().collect();
+        for model in models.iter() {
+            let classes = model.borrow().all_symbols(session, None);
+            for (class, _) in classes.iter() {
+                if !seen_classes.insert(Rc::as_ptr(class)) {
+                    continue;
+                }
+                let Some(module) = class.borrow().find_module() else {
+                    continue;
+                };
+                let module_name = module.borrow().as_module_package().dir_name.clone();
+                if !module.borrow().in_workspace() || (!options.modules.is_empty() && !options.modules.contains(&module_name)) {
+                    continue;
+                }
+                let Some((model_name, is_abstract)) = class.borrow().as_class_sym()._model.as_ref().map(|m| (m.name.clone(), m.is_abstract)) else {
+                    continue;
+                };
+                if is_abstract && !options.include_abstract {
+                    continue;
+                }
+                let Some(file_info) = FileMgr::get_file_info_of_symbol(session, class) else {
+                    continue;
+                };
+                let class_range = *class.borrow().range();
+                let fields: Vec<FieldDeclaration> = file_info.borrow().ast.iter().flatten().find_map(|stmt| match stmt {
+                    Stmt::ClassDef(c) if c.range == class_range => Some(c.body.iter().filter_map(FieldDeclaration::from_stmt).collect()),
+                    _ => None
+                }).unwrap_or_default();
+                let module_fields = res.entry(module_name).or_default();
+                for field in fields.into_iter() {
+                    if (field.groups.is_some() && !options.include_groups) || patterns.iter().any(|p| p.matches(&field.name)) {
+                        continue;
+                    }
+                    module_fields.push((model_name.clone(), field, file_info.clone()));
+                }
+            }
+        }
+        for fields in res.values_mut() {
+            fields.sort_by(|(model_1, field_1, _), (model_2, field_2, _)| model_1.cmp(model_2).then(field_1.range.start().cmp(&field_2.range.start())));
+        }
+        res
+    }
+}
@@ -0,0 +1,177 @@
+use lsp_types::{Position, Range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/* Elements of the data files that declare an xml id, with their id attribute */
+static ID_DECLARATION: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<(record|template|menuitem|report)\b[^>]*?\bid\s*=\s*["']([^"']+)["']"#).unwrap());
+static RECORD: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<record\b([^>]*)>(.*?)</record>"#).unwrap());
+static MODEL_ATTRIBUTE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bmodel\s*=\s*["']([^"']+)["']"#).unwrap());
+static MODEL_ID_FIELD: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\s+name\s*=\s*["']model_id["']\s+ref\s*=\s*["']([^"']+)["']"#).unwrap());
+static CODE_FIELD: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<field\s+name\s*=\s*["']code["']\s*>(.*?)</field>"#).unwrap());
+static MODEL_METHOD_CALL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bmodel\s*\.\s*(\w+)\s*\("#).unwrap());
+static FIELD_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<field\b[^>]*?\bname\s*=\s*["']([^"']+)["']"#).unwrap());
+/* Attributes of the views and the templates whose value is an expression that can read fields */
+static EXPRESSION_ATTRIBUTE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(?:invisible|readonly|required|column_invisible|domain|filter_domain|context|attrs|options|eval|t-field|t-esc|t-out|t-if|t-elif|t-foreach|t-value|t-att-[\w-]+|t-attf-[\w-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+/* Attributes whose value is an xml id, or a list of xml ids for groups */
+static ID_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(?:ref|inherit_id|parent|action|groups|t-call)\s*=\s*["']([^"']+)["']"#).unwrap());
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[A-Za-z_]\w*"#).unwrap());
+
+/* Models of the records whose code field is run on the model given by their model_id */
+const CODE_MODELS: [&str; 2] = ["ir.cron", "ir.actions.server"];
+
+/* An xml id declared by a data file, with the range of its value */
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlId {
+    pub id: String, //as written, with or without the module prefix
+    pub tag: String, //record, template, menuitem or report. 'csv' for the lines of a csv file
+    pub range: Range,
+}
+
+impl XmlId {
+
+    /* Return the id prefixed by the module that declares it, if it has no prefix */
+    pub fn full_id(&self, module: &str) -> String {
+        match self.id.contains('.') {
+            true => self.id.clone(),
+            false => format!("{}.{}", module, self.id)
+        }
+    }
+}
+
+/* A method called on 'model' by the code of a cron or a server action: model.method_name() */
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlMethodReference {
+    pub model_ref: String, //xml id of the ir.model record given to model_id, like 'base.model_res_partner'
+    pub method: String,
+    pub range: Range, //range of the method name
+}
+
+impl XmlMethodReference {
+
+    /* Return true if model_ref is the xml id generated for the ir.model record of the model */
+    pub fn is_on_model(&self, model_name: &str) -> bool {
+        let local_id = self.model_ref.rsplit('.').next().unwrap_or_default();
+        local_id.strip_prefix("model_").is_some_and(|id| id == model_name.replace('.', "_"))
+    }
+}
+
+/* The xml ids, the method references and the names that can be fields of a data file of a module. The file is scanned
+with regular expressions, not parsed: the commented elements are read as the others, and the entities are not decoded. */
+#[derive(Debug, Default)]
+pub struct XmlDataFile {
+    pub ids: Vec<XmlId>,
+    pub method_references: Vec<XmlMethodReference>,
+    pub field_names: Vec<String>, //names of the field elements, and identifiers of the expressions of the views and templates
+    pub id_references: Vec<(String, Range)>, //xml ids of other modules used by the file, like inherit_id="sale.view_order_form"
+}
+
+impl XmlDataFile {
+
+    pub fn parse_xml(text: &str) -> XmlDataFile {
+        let lines = LineIndex::new(text);
+        let mut res = XmlDataFile::default();
+        for capture in ID_DECLARATION.captures_iter(text) {
+            let id = capture.get(2).unwrap();
+            res.ids.push(XmlId {
+                id: id.as_str().to_string(),
+                tag: capture[1].to_string(),
+                range: lines.range(id.start(), id.end()),
+            });
+        }
+        for record in RECORD.captures_iter(text) {
+            let is_code_model = MODEL_ATTRIBUTE.captures(&record[1]).is_some_and(|model| CODE_MODELS.contains(&&model[1]));
+            if !is_code_model {
+                continue;
+            }
+            let body = record.get(2).unwrap();
+            let Some(model_ref) = MODEL_ID_FIELD.captures(body.as_str()) else {
+                continue;
+            };
+            for code in CODE_FIELD.captures_iter(body.as_str()) {
+                let code = code.get(1).unwrap();
+                for call in MODEL_METHOD_CALL.captures_iter(code.as_str()) {
+                    let method = call.get(1).unwrap();
+                    let start = body.start() + code.start() + method.start();
+                    res.method_references.push(XmlMethodReference {
+                        model_ref: model_ref[1].to_string(),
+                        method: method.as_str().to_string(),
+                        range: lines.range(start, start + method.len()),
+                    });
+                }
+            }
+        }
+        for field in FIELD_NAME.captures_iter(text) {
+            res.field_names.push(field[1].to_string());
+        }
+        for attribute in EXPRESSION_ATTRIBUTE.captures_iter(text) {
+            let value = attribute.get(1).or(attribute.get(2)).unwrap();
+            res.field_names.extend(IDENTIFIER.find_iter(value.as_str()).map(|name| name.as_str().to_string()));
+        }
+        res.field_names.sort();
+        res.field_names.dedup();
+        for reference in ID_REFERENCE.captures_iter(text) {
+            let value = reference.get(1).unwrap();
+            let mut offset = value.start();
+            for id in value.as_str().split(',') {
+                let trimmed = id.trim().trim_start_matches('!');
+                //the ids without a module prefix, and the evaluated values like ref="%(action)d", are in the module itself
+                if trimmed.contains('.') && !trimmed.contains(['%', '(', ' ']) {
+                    let start = offset + id.find(trimmed).unwrap_or(0);
+                    res.id_references.push((trimmed.to_string(), lines.range(start, start + trimmed.len())));
+                }
+                offset += id.len() + 1;
+            }
+        }
+        res
+    }
+
+    /* The ids of a csv data file are in its 'id' column */
+    pub fn parse_csv(text: &str) -> XmlDataFile {
+        let lines = LineIndex::new(text);
+        let mut res = XmlDataFile::default();
+        let mut rows = text.split_inclusive('\n');
+        let Some(column) = rows.next().and_then(|header| header.trim_end().split(',').position(|c| c.trim_matches('"') == "id")) else {
+            return res;
+        };
+        let mut offset = text.find('\n').map_or(text.len(), |i| i + 1);
+        for row in rows {
+            if let Some(cell) = row.split(',').nth(column) {
+                let start = offset + row.split(',').take(column).map(|c| c.len() + 1).sum::<usize>();
+                let id = cell.trim_end_matches(['\r', '\n']).trim_matches('"');
+                if !id.is_empty() {
+                    let start = start + cell.find(id).unwrap_or(0);
+                    res.ids.push(XmlId {
+                        id: id.to_string(),
+                        tag: "csv".to_string(),
+                        range: lines.range(start, start + id.len()),
+                    });
+                }
+            }
+            offset += row.len();
+        }
+        res
+    }
+}
+
+/* Conversion of the byte offsets of a data file to lsp positions */
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+
+    fn new(text: &str) -> LineIndex {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { starts }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        Position::new(line as u32, (offset - self.starts[line]) as u32)
+    }
+
+    fn range(&self, start: usize, end: usize) -> Range {
+        Range::new(self.position(start), self.position(end))
+    }
+}
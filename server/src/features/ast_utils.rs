@@ -1,23 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::cell::RefCell;
+use crate::core::borrows::Borrows;
 use crate::core::evaluation::{AnalyzeAstResult, Context, ContextValue, Evaluation, ExprOrIdent};
 use crate::core::symbols::symbol::Symbol;
 use crate::core::file_mgr::FileInfo;
 use crate::threads::SessionInfo;
 use crate::S;
 use ruff_python_ast::visitor::{Visitor, walk_expr, walk_stmt, walk_alias, walk_except_handler, walk_parameter, walk_keyword, walk_pattern_keyword, walk_type_param, walk_pattern};
-use ruff_python_ast::{Expr, Stmt, Alias, ExceptHandler, Parameter, Keyword, PatternKeyword, TypeParam, Pattern};
+use ruff_python_ast::{Expr, ExprAttribute, ExprCall, ExprCompare, ExprNamed, Identifier, Stmt, StmtTry, Alias, ExceptHandler, Parameter, Keyword, PatternKeyword, TypeParam, Pattern};
 use ruff_text_size::{Ranged, TextRange, TextSize};
 use tracing::warn;
 
+/* Methods of recordsets that call a function on each of their records */
+pub const RECORD_CALLBACK_METHODS: [&str; 4] = ["filtered", "sorted", "mapped", "grouped"];
+
 pub struct AstUtils {}
 
 impl AstUtils {
 
     pub fn get_symbols(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> (AnalyzeAstResult, Option<TextRange>) {
         let mut expr: Option<ExprOrIdent> = None;
-        let file_info_borrowed = file_info.borrow();
+        let Some(file_info_borrowed) = Borrows::try_borrow(file_info) else {
+            return (AnalyzeAstResult::default(), None);
+        };
         for stmt in file_info_borrowed.ast.as_ref().unwrap().iter() {
             expr = ExprFinderVisitor::find_expr_at(stmt, offset);
             if expr.is_some() {
@@ -31,7 +37,7 @@ impl AstUtils {
         let expr = expr.unwrap();
         let parent_symbol = Symbol::get_scope_symbol(file_symbol.clone(), offset, matches!(expr, ExprOrIdent::Parameter(_)));
         let from_module;
-        if let Some(module) = file_symbol.borrow().find_module() {
+        if let Some(module) = Borrows::try_borrow(file_symbol).and_then(|file_symbol| file_symbol.find_module()) {
             from_module = ContextValue::MODULE(Rc::downgrade(&module));
         } else {
             from_module = ContextValue::BOOLEAN(false);
@@ -51,72 +57,233 @@ impl AstUtils {
                 n.id.to_string()
             },
             Expr::Attribute(a) => {
-                AstUtils::flatten_expr(&a.value) + &a.attr
+                AstUtils::flatten_expr(&a.value) + "." + &a.attr
             },
             _ => {S!("//Unhandled//")}
         }
     }
 
+    /* Return true if a handler of the try statement catches the ImportError of its body: a bare except, or an except of
+    ImportError, ModuleNotFoundError, Exception or BaseException, alone or in a tuple. The imports of the body are optional */
+    pub fn catches_import_error(try_stmt: &StmtTry) -> bool {
+        try_stmt.handlers.iter().filter_map(|handler| handler.as_except_handler()).any(|handler| {
+            let Some(type_) = handler.type_.as_ref() else {
+                return true;
+            };
+            let types: Vec<&Expr> = match &**type_ {
+                Expr::Tuple(tuple) => tuple.elts.iter().collect(),
+                type_ => vec![type_]
+            };
+            types.into_iter().any(|type_| {
+                let name = AstUtils::flatten_expr(type_);
+                matches!(name.rsplit('.').next(), Some("ImportError" | "ModuleNotFoundError" | "Exception" | "BaseException"))
+            })
+        })
+    }
+
+    /* Return all the calls contained in the expression, including the expression itself */
+    pub fn find_calls(expr: &Expr) -> Vec<&ExprCall> {
+        let mut visitor = CallFinderVisitor {
+            calls: vec![]
+        };
+        visitor.visit_expr(expr);
+        visitor.calls
+    }
+
+    /* Return all the comparisons contained in the expression */
+    pub fn find_compares(expr: &Expr) -> Vec<&ExprCompare> {
+        let mut visitor = CompareFinderVisitor {
+            compares: vec![]
+        };
+        visitor.visit_expr(expr);
+        visitor.compares
+    }
+
+    /* Return all the calls contained in the statement and in its body */
+    pub fn find_calls_in_stmt(stmt: &Stmt) -> Vec<&ExprCall> {
+        let mut visitor = CallFinderVisitor {
+            calls: vec![]
+        };
+        visitor.visit_stmt(stmt);
+        visitor.calls
+    }
+
+    /* Return the attributes of the name in the statements, like self.partner_id for the name self */
+    pub fn find_attributes_of<'a>(stmts: &'a [Stmt], name: &str) -> Vec<&'a ExprAttribute> {
+        let mut visitor = AttributeFinderVisitor {
+            name,
+            attributes: vec![]
+        };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.attributes
+    }
+
+    /* Return the names loaded by the statements. The strings of __all__ are counted as names, as they export them */
+    pub fn find_used_names(stmts: &[Stmt]) -> HashSet<String> {
+        let mut visitor = NameFinderVisitor {
+            names: HashSet::new()
+        };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        visitor.names
+    }
+
+    /* Return the receiver of the recordset method (see RECORD_CALLBACK_METHODS) whose callback is a lambda that has
+    'name' as first parameter and contains range. It gives its records to this parameter */
+    pub fn find_lambda_records(stmts: &[Stmt], name: &str, range: TextRange) -> Option<Expr> {
+        let mut res = None;
+        for (receiver, callback) in AstUtils::find_record_callbacks(stmts) {
+            let Expr::Lambda(lambda) = callback else {
+                continue;
+            };
+            let first_param = lambda.parameters.as_ref().and_then(|p| p.posonlyargs.iter().chain(p.args.iter()).next());
+            if first_param.is_some_and(|p| p.parameter.name.as_str() == name) && lambda.body.range().contains_range(range) {
+                res = Some(receiver.clone()); //calls are visited from the outer to the inner ones
+            }
+        }
+        res
+    }
+
+    /* Return the receivers of the recordset methods that are given the function 'name' as callback, like in
+    records.filtered(self.name) */
+    pub fn find_function_records(stmts: &[Stmt], name: &str) -> Vec<Expr> {
+        AstUtils::find_record_callbacks(stmts).into_iter().filter(|(_, callback)| match callback {
+            Expr::Name(n) => n.id.as_str() == name,
+            Expr::Attribute(a) => a.attr.as_str() == name && a.value.as_name_expr().is_some_and(|n| n.id.as_str() == "self"),
+            _ => false
+        }).map(|(receiver, _)| receiver.clone()).collect()
+    }
+
+    /* Return the walrus operators of the expressions of the statement. The statements of its body and the lambdas are not
+    visited, as they are other scopes or are visited separately */
+    pub fn find_named_exprs(stmt: &Stmt) -> Vec<&ExprNamed> {
+        let mut visitor = NamedExprFinderVisitor {
+            named_exprs: vec![],
+            depth: 0,
+        };
+        visitor.visit_stmt(stmt);
+        visitor.named_exprs
+    }
+
+    fn find_record_callbacks(stmts: &[Stmt]) -> Vec<(&Expr, &Expr)> {
+        let mut visitor = CallFinderVisitor {
+            calls: vec![]
+        };
+        for stmt in stmts.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        let mut res = vec![];
+        for call in visitor.calls.into_iter() {
+            let Expr::Attribute(method) = &*call.func else {
+                continue;
+            };
+            if !RECORD_CALLBACK_METHODS.contains(&method.attr.as_str()) {
+                continue;
+            }
+            let callback = call.arguments.args.first()
+                .or_else(|| call.arguments.keywords.iter().find(|k| k.arg.as_ref().is_some_and(|a| a.as_str() == "func" || a.as_str() == "key")).map(|k| &k.value));
+            if let Some(callback) = callback {
+                res.push((&*method.value, callback));
+            }
+        }
+        res
+    }
+
+    /* Return the names captured by a pattern of a case: case Point(x=px), case [first, *rest], case {"key": v, **others}
+    and case _ as whole capture px, first, rest, v, others and whole. The alternatives of a | pattern capture the same
+    names, returned once */
+    pub fn find_pattern_captures(pattern: &Pattern) -> Vec<&Identifier> {
+        let mut res: Vec<&Identifier> = vec![];
+        match pattern {
+            Pattern::MatchAs(pattern) => {
+                if let Some(sub_pattern) = pattern.pattern.as_ref() {
+                    res.extend(AstUtils::find_pattern_captures(sub_pattern));
+                }
+                res.extend(pattern.name.iter());
+            },
+            Pattern::MatchStar(pattern) => res.extend(pattern.name.iter()),
+            Pattern::MatchSequence(pattern) => {
+                res.extend(pattern.patterns.iter().flat_map(AstUtils::find_pattern_captures));
+            },
+            Pattern::MatchMapping(pattern) => {
+                res.extend(pattern.patterns.iter().flat_map(AstUtils::find_pattern_captures));
+                res.extend(pattern.rest.iter());
+            },
+            Pattern::MatchClass(pattern) => {
+                res.extend(pattern.arguments.patterns.iter().flat_map(AstUtils::find_pattern_captures));
+                res.extend(pattern.arguments.keywords.iter().flat_map(|keyword| AstUtils::find_pattern_captures(&keyword.pattern)));
+            },
+            Pattern::MatchOr(pattern) => {
+                for alternative in pattern.patterns.iter() {
+                    for name in AstUtils::find_pattern_captures(alternative) {
+                        if !res.iter().any(|captured| captured.id == name.id) {
+                            res.push(name);
+                        }
+                    }
+                }
+            },
+            Pattern::MatchValue(_) | Pattern::MatchSingleton(_) => {}
+        }
+        res
+    }
+
     pub fn find_stmt_from_ast<'a>(ast: &'a [Stmt], indexes: &[u16]) -> &'a Stmt {
-        let mut stmt = ast.get(indexes[0] as usize).expect("index not found in ast");
-        let mut i_index = 1;
-        while i_index < indexes.len() {
-            match stmt {
-                Stmt::ClassDef(c) => {
-                    stmt = c.body.get(*indexes.get(i_index).unwrap() as usize).expect("index not found in ast");
+        AstUtils::get_stmt_from_ast(ast, indexes).expect("index not found in ast")
+    }
+
+    /* Return the statement reached by the ast indexes of a symbol, or None if they don't match the ast anymore. Each
+    index is the position of the statement in its body, preceded by the bloc for the statements that have several
+    bodies: the clause for if (0 for the body, then the elif/else clauses), 0 for body and 1 for orelse for for and while, and
+    0, 1, 2 for the body, orelse and finalbody of try, or 3 followed by the handler, and the case for match */
+    pub fn get_stmt_from_ast<'a>(ast: &'a [Stmt], indexes: &[u16]) -> Option<&'a Stmt> {
+        let mut indexes = indexes.iter().map(|index| *index as usize);
+        let mut stmt = ast.get(indexes.next()?)?;
+        while let Some(index) = indexes.next() {
+            stmt = match stmt {
+                Stmt::ClassDef(class_stmt) => class_stmt.body.get(index)?,
+                Stmt::FunctionDef(func_stmt) => func_stmt.body.get(index)?,
+                Stmt::With(with_stmt) => with_stmt.body.get(index)?,
+                Stmt::Match(match_stmt) => match_stmt.cases.get(index)?.body.get(indexes.next()?)?,
+                Stmt::If(if_stmt) => {
+                    let body = match index {
+                        0 => &if_stmt.body,
+                        _ => &if_stmt.elif_else_clauses.get(index - 1)?.body
+                    };
+                    body.get(indexes.next()?)?
                 },
-                Stmt::FunctionDef(f) => {
-                    stmt = f.body.get(*indexes.get(i_index).unwrap() as usize).expect("index not found in ast");
+                Stmt::For(for_stmt) => {
+                    let body = match index {
+                        0 => &for_stmt.body,
+                        1 => &for_stmt.orelse,
+                        _ => return None
+                    };
+                    body.get(indexes.next()?)?
                 },
-                Stmt::If(if_stmt) => {
-                    let bloc = indexes.get(i_index).unwrap();
-                    i_index += 1;
-                    let stmt_index = indexes.get(i_index).unwrap();
-                    if *bloc == 0 {
-                        stmt = if_stmt.body.get(*stmt_index as usize).expect("index not found in ast");
-                    } else {
-                        stmt = if_stmt.elif_else_clauses.get((bloc-1) as usize).expect("Bloc not found in if stmt").body.get(*stmt_index as usize).expect("index not found in ast");
-                    }
+                Stmt::While(while_stmt) => {
+                    let body = match index {
+                        0 => &while_stmt.body,
+                        1 => &while_stmt.orelse,
+                        _ => return None
+                    };
+                    body.get(indexes.next()?)?
                 },
                 Stmt::Try(try_stmt) => {
-                    let first_index = indexes.get(i_index).unwrap();
-                    i_index += 1;
-                    let second_index = indexes.get(i_index).unwrap();
-                    if *first_index == 0 {
-                        stmt = try_stmt.body.get(*second_index as usize).expect("index not found in ast");
-                    } else if *first_index == 1 {
-                        stmt = try_stmt.orelse.get(*second_index as usize).expect("index not found in ast");
-                    } else if *first_index == 2 {
-                        stmt = try_stmt.finalbody.get(*second_index as usize).expect("index not found in ast");
-                    } else if *first_index == 3 {
-                        i_index += 1;
-                        let third_index = indexes.get(i_index).unwrap();
-                        let handler = try_stmt.handlers.get(*second_index as usize).expect("Handler not found in ast");
-                        stmt = handler.as_except_handler().unwrap().body.get(*third_index as usize).expect("index not found in handler ast");
-                    } else {
-                        panic!("Wrong try bloc");
-                    }
+                    let body = match index {
+                        0 => &try_stmt.body,
+                        1 => &try_stmt.orelse,
+                        2 => &try_stmt.finalbody,
+                        3 => &try_stmt.handlers.get(indexes.next()?)?.as_except_handler()?.body,
+                        _ => return None
+                    };
+                    body.get(indexes.next()?)?
                 },
-                Stmt::For(for_stmt) => {
-                    let bloc = indexes.get(i_index).unwrap();
-                    i_index += 1;
-                    let stmt_index = indexes.get(i_index).unwrap();
-                    if *bloc == 0 {
-                        stmt = for_stmt.body.get(*stmt_index as usize).expect("index not found in ast");
-                    } else if *bloc == 1 {
-                        stmt = for_stmt.orelse.get(*stmt_index as usize).expect("index not found in ast");
-                    } else {
-                        panic!("Wrong for bloc");
-                    }
-                },
-                Stmt::With(with_stmt) => {
-                    stmt = with_stmt.body.get(*indexes.get(i_index).unwrap() as usize).expect("index not found in with stmt");
-                }
-                _ => {}
-            }
-            i_index += 1;
+                _ => return None
+            };
         }
-        stmt
+        Some(stmt)
     }
 
 }
@@ -273,3 +440,104 @@ impl<'a> Visitor<'a> for ExprFinderVisitor<'a> {
         }
     }
 }
+
+struct CallFinderVisitor<'a> {
+    calls: Vec<&'a ExprCall>,
+}
+
+impl<'a> Visitor<'a> for CallFinderVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Call(call) = expr {
+            self.calls.push(call);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct AttributeFinderVisitor<'a, 'b> {
+    name: &'b str,
+    attributes: Vec<&'a ExprAttribute>,
+}
+
+impl<'a, 'b> Visitor<'a> for AttributeFinderVisitor<'a, 'b> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Attribute(attribute) = expr {
+            if attribute.value.as_name_expr().is_some_and(|n| n.id.as_str() == self.name) {
+                self.attributes.push(attribute);
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct CompareFinderVisitor<'a> {
+    compares: Vec<&'a ExprCompare>,
+}
+
+impl<'a> Visitor<'a> for CompareFinderVisitor<'a> {
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Compare(compare) = expr {
+            self.compares.push(compare);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct NameFinderVisitor {
+    names: HashSet<String>,
+}
+
+impl<'a> Visitor<'a> for NameFinderVisitor {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            if assign.targets.iter().any(|t| t.as_name_expr().is_some_and(|n| n.id.as_str() == "__all__")) {
+                let elts = match &*assign.value {
+                    Expr::List(list) => &list.elts,
+                    Expr::Tuple(tuple) => &tuple.elts,
+                    _ => return walk_stmt(self, stmt)
+                };
+                self.names.extend(elts.iter().filter_map(|e| e.as_string_literal_expr()).map(|s| s.value.to_string()));
+            }
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let Expr::Name(name) = expr {
+            self.names.insert(name.id.to_string());
+        }
+        walk_expr(self, expr);
+    }
+}
+
+struct NamedExprFinderVisitor<'a> {
+    named_exprs: Vec<&'a ExprNamed>,
+    depth: u32,
+}
+
+impl<'a> Visitor<'a> for NamedExprFinderVisitor<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if self.depth > 0 {
+            return;
+        }
+        self.depth += 1;
+        walk_stmt(self, stmt);
+        self.depth -= 1;
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Lambda(_) => {},
+            Expr::Named(named) => {
+                self.named_exprs.push(named);
+                walk_expr(self, expr);
+            },
+            _ => walk_expr(self, expr)
+        }
+    }
+}
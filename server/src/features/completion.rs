@@ -1,23 +1,47 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::{cell::RefCell, rc::Rc};
 use lsp_types::{CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionList, CompletionResponse, MarkupContent};
-use ruff_python_ast::{ExceptHandler, Expr, ExprAttribute, ExprIf, ExprName, ExprSubscript, ExprYield, Stmt, StmtGlobal, StmtImport, StmtImportFrom, StmtNonlocal};
-use ruff_text_size::Ranged;
+use ruff_python_ast::{Arguments, ExceptHandler, Expr, ExprAttribute, ExprIf, ExprName, ExprSubscript, ExprYield, Stmt, StmtGlobal, StmtImport, StmtImportFrom, StmtNonlocal};
+use ruff_text_size::{Ranged, TextRange, TextSize};
 use weak_table::traits::WeakElement;
 
+use crate::core::borrows::Borrows;
 use crate::constants::SymType;
+use crate::core::api_decorators::{ApiDecorators, KNOWN_CONTEXT_KEYS};
+use crate::core::call_binding::CallBinding;
+use crate::core::context_keys::ContextKeys;
+use crate::core::compute_targets::ComputeTargets;
+use crate::core::field_consistency::FieldDeclaration;
 use crate::core::evaluation::{Evaluation, EvaluationSymbolWeak};
+use crate::core::field_name_args::FieldNameArgs;
+use crate::core::python_string::StringSourceMap;
+use crate::core::symbols::function_symbol::ArgumentType;
 use crate::core::import_resolver;
 use crate::core::python_arch_eval_hooks::PythonArchEvalHooks;
 use crate::core::symbols::module_symbol::ModuleSymbol;
 use crate::threads::SessionInfo;
 use crate::S;
 use crate::core::symbols::symbol::Symbol;
-use crate::core::file_mgr::FileInfo;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::guards::GuardedName;
+use crate::core::overloads::Overloads;
+use crate::core::selection_field::{ModelSelection, SelectionAddEntry, SelectionField};
+use crate::core::union_members::UnionMembers;
+use crate::utils::PathSanitizer as _;
 
+use super::ast_utils::AstUtils;
+use super::completion_context::{AssignmentTarget, CompletionContext, CompletionContexts};
 use super::hover::HoverFeature;
+use super::snippets::SnippetFeature;
 
 
+/* Maximum number of **kwargs forwards followed to find keyword arguments */
+const MAX_KWARGS_FORWARD_DEPTH: u8 = 3;
+/* Class attributes of odoo fields that are not parameters */
+const FIELD_INTERNAL_ATTRIBUTES: [&str; 13] = ["type", "relational", "column_type", "column_format", "column_cast_from", "write_sequence",
+    "args", "automatic", "inherited", "inherited_field", "name", "model_name", "manual"];
+
 #[allow(non_camel_case_types)]
 pub enum ExpectedType {
     MODEL_NAME,
@@ -34,13 +58,62 @@ impl CompletionFeature {
         line: u32,
         character: u32
     ) -> Option<CompletionResponse> {
-        let offset = file_info.borrow().position_to_offset(line, character);
-        let file_info =  file_info.borrow();
+        let mut response = CompletionFeature::complete_file(session, file_symbol, file_info, line, character);
+        match &mut response {
+            Some(CompletionResponse::List(list)) => CompletionFeature::sort_items(&mut list.items),
+            Some(CompletionResponse::Array(items)) => CompletionFeature::sort_items(items),
+            None => {}
+        }
+        response
+    }
+
+    /* The members are collected from maps whose order changes from a build to another. The items are sorted, so that
+    two builds of the same file give the same response, and the clients caching it don't flicker */
+    pub fn sort_items(items: &mut [CompletionItem]) {
+        items.sort_by(|a, b| {
+            let key_a = (a.sort_text.as_ref().unwrap_or(&a.label), &a.label, &a.detail, a.label_details.as_ref().map(|d| &d.description));
+            let key_b = (b.sort_text.as_ref().unwrap_or(&b.label), &b.label, &b.detail, b.label_details.as_ref().map(|d| &d.description));
+            key_a.cmp(&key_b)
+        });
+    }
+
+    fn complete_file(session: &mut SessionInfo,
+        file_symbol: &Rc<RefCell<Symbol>>,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<CompletionResponse> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let file_info =  Borrows::try_borrow(file_info)?;
+        //the target of an assignment, like 'self.' at the beginning of a line of a compute method, is completed with the fields
+        if let Some(source) = file_info.get_source() {
+            if let Some(target) = CompletionContexts::get_assignment_target(&source, offset) {
+                if let CompletionContext::ATTRIBUTE(receiver, prefix) = CompletionContexts::extract(&source, offset) {
+                    return complete_receiver(session, file_symbol, &receiver, &prefix, offset, Some(target));
+                }
+            }
+        }
+        if !file_info.valid {
+            //the line being typed is incomplete: the receiver of an attribute is read from its text (see CompletionContexts)
+            if let Some(CompletionContext::ATTRIBUTE(receiver, prefix)) = file_info.get_source().map(|source| CompletionContexts::extract(&source, offset)) {
+                return complete_receiver(session, file_symbol, &receiver, &prefix, offset, None);
+            }
+        }
         let ast = file_info.ast.as_ref().unwrap();
         complete_vec_stmt(ast, session, file_symbol, offset)
     }
 }
 
+/* Complete the attributes of a receiver parsed from the text of the line, evaluated at the cursor */
+fn complete_receiver(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, receiver: &Expr, prefix: &str, offset: usize, target: Option<AssignmentTarget>) -> Option<CompletionResponse> {
+    let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, false);
+    let parent = Evaluation::eval_from_ast(session, receiver, scope, &TextSize::new(offset as u32)).0;
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items: get_member_items(session, file, &parent, prefix, offset, target)
+    }))
+}
+
 /* **********************************************************************
 ***************************** Statements ********************************
 *********************************************************************** */
@@ -67,7 +140,7 @@ fn complete_stmt(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, stmt: &S
         Stmt::ImportFrom(stmt_import_from) => complete_import_from_stmt(session, file, stmt_import_from, offset),
         Stmt::Global(stmt_global) => complete_global_stmt(session, file, stmt_global, offset),
         Stmt::Nonlocal(stmt_nonlocal) => complete_nonlocal_stmt(session, file, stmt_nonlocal, offset),
-        Stmt::Expr(stmt_expr) => complete_expr(&stmt_expr.value, session, file, offset, false, &vec![]),
+        Stmt::Expr(stmt_expr) => complete_expr_stmt(session, file, stmt_expr, offset),
         Stmt::Pass(_) => None,
         Stmt::Break(_) => None,
         Stmt::Continue(_) => None,
@@ -75,6 +148,32 @@ fn complete_stmt(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, stmt: &S
     }
 }
 
+fn complete_expr_stmt(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, stmt_expr: &ruff_python_ast::StmtExpr, offset: usize) -> Option<CompletionResponse> {
+    let res = complete_expr(&stmt_expr.value, session, file, offset, false, &vec![]);
+    //a name alone on its line can be the beginning of a snippet
+    let Expr::Name(name) = stmt_expr.value.as_ref() else {
+        return res;
+    };
+    if name.range.end().to_usize() != offset {
+        return res;
+    }
+    let indent = FileMgr::get_file_info_of_symbol(session, file).and_then(|file_info| Borrows::try_borrow(&file_info).map(|file_info| file_info.get_indent_unit())).unwrap_or(S!("    "));
+    let snippets = SnippetFeature::get_snippets(session, file, name.id.as_str(), offset, &indent);
+    if snippets.is_empty() {
+        return res;
+    }
+    let mut items = match res {
+        Some(CompletionResponse::List(list)) => list.items,
+        Some(CompletionResponse::Array(items)) => items,
+        None => vec![],
+    };
+    items.extend(snippets);
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
 fn complete_vec_stmt(stmts: &Vec<Stmt>, session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, offset: usize) -> Option<CompletionResponse> {
     let mut previous = None;
     for stmt in stmts.iter() {
@@ -98,6 +197,11 @@ fn complete_vec_stmt(stmts: &Vec<Stmt>, session: &mut SessionInfo, file_symbol:
 }
 
 fn complete_function_def_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_function_def: &ruff_python_ast::StmtFunctionDef, offset: usize) -> Option<CompletionResponse> {
+    for decorator in stmt_function_def.decorator_list.iter() {
+        if offset > decorator.range().start().to_usize() && offset <= decorator.range().end().to_usize() {
+            return complete_decorator(session, file, decorator, offset);
+        }
+    }
     if !stmt_function_def.body.is_empty() {
         if offset > stmt_function_def.body.first().unwrap().range().start().to_usize() && stmt_function_def.body.last().unwrap().range().end().to_usize() >= offset {
             return complete_vec_stmt(&stmt_function_def.body, session, file, offset);
@@ -106,6 +210,66 @@ fn complete_function_def_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<S
     None
 }
 
+/* Complete the model of @api.returns and the context keys of @api.depends_context, or the expression of the decorator */
+fn complete_decorator(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, decorator: &ruff_python_ast::Decorator, offset: usize) -> Option<CompletionResponse> {
+    let in_literal = |literal: &&ruff_python_ast::ExprStringLiteral| offset > literal.range.start().to_usize() && offset < literal.range.end().to_usize();
+    if let Some((_, Some(call))) = ApiDecorators::find(std::slice::from_ref(decorator), "returns") {
+        if let Some(model) = ApiDecorators::get_returns_model(call).filter(in_literal) {
+            return complete_string_literal(session, file, model, offset, false, &vec![ExpectedType::MODEL_NAME]);
+        }
+    }
+    if let Some((_, Some(call))) = ApiDecorators::find(std::slice::from_ref(decorator), "depends_context") {
+        if let Some(key) = ApiDecorators::get_context_keys(call).into_iter().find(in_literal) {
+            return complete_context_keys(session, file, key, offset);
+        }
+    }
+    complete_expr(&decorator.expression, session, file, offset, false, &vec![])
+}
+
+/* Complete the keys of api.depends_context: the known context keys, and the ones used by the class of the method */
+fn complete_context_keys(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, literal: &ruff_python_ast::ExprStringLiteral, offset: usize) -> Option<CompletionResponse> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file)?;
+    let used_keys = Borrows::try_borrow(&file_info)?.ast.as_ref()?.iter()
+        .filter_map(|stmt| stmt.as_class_def_stmt())
+        .find(|class| class.range.contains(TextSize::new(offset as u32)))
+        .map(|class| ApiDecorators::get_used_context_keys(&class.body))
+        .unwrap_or_default();
+    let prefix = literal.value.to_str();
+    let mut keys: Vec<(String, &str)> = KNOWN_CONTEXT_KEYS.iter().map(|key| (key.to_string(), "context key")).collect();
+    keys.extend(used_keys.into_iter().filter(|key| !KNOWN_CONTEXT_KEYS.contains(&key.as_str())).map(|key| (key, "used by the model")));
+    let items = keys.into_iter().filter(|(key, _)| key.starts_with(prefix)).map(|(key, detail)| CompletionItem {
+        label: key,
+        kind: Some(CompletionItemKind::CONSTANT),
+        detail: Some(S!(detail)),
+        ..Default::default()
+    }).collect();
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
+/* Complete the keys read from the context, like context.get('key') or ctx['key']: the known context keys, and the ones
+set by the python files (see ContextKeyIndex) */
+fn complete_read_context_keys(session: &mut SessionInfo<'_>, literal: &ruff_python_ast::ExprStringLiteral) -> Option<CompletionResponse> {
+    let prefix = literal.value.to_str();
+    let mut keys: Vec<(String, &str)> = KNOWN_CONTEXT_KEYS.iter().map(|key| (key.to_string(), "context key")).collect();
+    let mut set_keys: Vec<String> = Borrows::try_borrow(&session.sync_odoo.get_file_mgr())?.context_keys.keys()
+        .filter(|key| !KNOWN_CONTEXT_KEYS.contains(&key.as_str())).cloned().collect();
+    set_keys.sort();
+    keys.extend(set_keys.into_iter().map(|key| (key, "set in the context")));
+    let items = keys.into_iter().filter(|(key, _)| key.starts_with(prefix)).map(|(key, detail)| CompletionItem {
+        label: key,
+        kind: Some(CompletionItemKind::CONSTANT),
+        detail: Some(S!(detail)),
+        ..Default::default()
+    }).collect();
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
 fn complete_class_def_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_class_def: &ruff_python_ast::StmtClassDef, offset: usize) -> Option<CompletionResponse> {
     for base in stmt_class_def.bases().iter() {
         if offset > base.range().start().to_usize() && offset <= base.range().end().to_usize() {
@@ -138,11 +302,54 @@ fn complete_delete_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>
 
 fn complete_assign_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_assign: &ruff_python_ast::StmtAssign, offset: usize) -> Option<CompletionResponse> {
     if offset > stmt_assign.value.range().start().to_usize() && offset <= stmt_assign.value.range().end().to_usize() {
+        if let Some(response) = complete_selection_keys(session, file, stmt_assign, offset) {
+            return Some(response);
+        }
         return complete_expr( &stmt_assign.value, session, file, offset, false, &vec![]);
     }
     None
 }
 
+/* Complete the anchors of selection_add and the keys of ondelete in the extension of a Selection field with the keys
+of the merged selection */
+fn complete_selection_keys(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_assign: &ruff_python_ast::StmtAssign, offset: usize) -> Option<CompletionResponse> {
+    let call = SelectionField::as_selection_call(&stmt_assign.value)?;
+    let literal = SelectionField::get_key_literal_at(call, offset as u32)?;
+    let field_name = stmt_assign.targets.first()?.as_name_expr()?.id.to_string();
+    let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, false);
+    if scope.borrow().typ() != SymType::CLASS {
+        return None;
+    }
+    let model_name = scope.borrow().as_class_sym()._model.as_ref()?.name.clone();
+    let mut keys = match SelectionField::get_selection_expr(call) {
+        Some(selection) => SelectionField::get_keys(session, &scope, selection)?,
+        None => match SelectionField::get_model_selection(session, &model_name, &field_name, file.borrow().find_module(), Some(&scope)) {
+            ModelSelection::KEYS(keys, _) => keys,
+            _ => return None
+        }
+    };
+    let entries = SelectionField::get_selection_add(call);
+    let added: Vec<String> = entries.iter().filter(|e| matches!(e, SelectionAddEntry::ADD(..)) && !keys.contains(e.key())).map(|e| e.key().clone()).collect();
+    SelectionField::merge(&mut keys, &entries);
+    let prefix = literal.value.to_str();
+    let is_ondelete = SelectionField::get_ondelete(call).is_some_and(|d| d.range.contains(literal.range.start()));
+    let items = keys.iter().filter(|k| k.starts_with(prefix)).map(|key| {
+        let is_added = added.contains(key);
+        CompletionItem {
+            label: key.clone(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(if is_added { S!("added by selection_add") } else { format!("key of {}.{}", model_name, field_name) }),
+            //the keys that need a policy come first in ondelete
+            sort_text: Some(format!("{}{}", if is_ondelete && !is_added { "~" } else { "!" }, key)),
+            ..Default::default()
+        }
+    }).collect();
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
 fn complete_aug_assign_stmt(session: &mut SessionInfo<'_>, file: &Rc<RefCell<Symbol>>, stmt_aug_assign: &ruff_python_ast::StmtAugAssign, offset: usize) -> Option<CompletionResponse> {
     if offset > stmt_aug_assign.value.range().start().to_usize() && offset <= stmt_aug_assign.value.range().end().to_usize() {
         return complete_expr( &stmt_aug_assign.value, session, file, offset, false, &vec![]);
@@ -465,14 +672,270 @@ fn complete_call(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_cal
     if offset > expr_call.func.range().start().to_usize() && offset <= expr_call.func.range().end().to_usize() {
         return complete_expr( &expr_call.func, session, file, offset, is_param, expected_type);
     }
+    if let Some(response) = complete_field_name_args(session, file, expr_call, offset, is_param) {
+        return Some(response);
+    }
+    if let Some(literal) = ContextKeys::get_read_key(expr_call).filter(|literal| offset > literal.range.start().to_usize() && offset < literal.range.end().to_usize()) {
+        return complete_read_context_keys(session, literal);
+    }
     for arg in expr_call.arguments.args.iter() {
         if offset > arg.range().start().to_usize() && offset <= arg.range().end().to_usize() {
+            if let Expr::Name(name) = arg {
+                //a name being typed can be the beginning of a keyword argument
+                if name.range.end().to_usize() == offset {
+                    let mut items = complete_call_keywords(session, file, expr_call, offset, is_param, name.id.as_str());
+                    if let Some(CompletionResponse::List(names)) = complete_name(session, file, name, offset, is_param, expected_type) {
+                        items.extend(names.items);
+                    }
+                    return Some(CompletionResponse::List(CompletionList {
+                        is_incomplete: false,
+                        items
+                    }));
+                }
+            }
             return complete_expr(arg, session, file, offset, is_param, expected_type);
         }
     }
+    for keyword in expr_call.arguments.keywords.iter() {
+        if offset > keyword.value.range().start().to_usize() && offset <= keyword.value.range().end().to_usize() {
+            return complete_expr(&keyword.value, session, file, offset, is_param, expected_type);
+        }
+    }
+    if is_in_arguments(session, file, expr_call, offset) {
+        return Some(CompletionResponse::List(CompletionList {
+            is_incomplete: false,
+            items: complete_call_keywords(session, file, expr_call, offset, is_param, "")
+        }));
+    }
     None
 }
 
+/* Complete the field names given as strings to the ORM methods of FIELD_NAME_ARGS, like read(['partner_id', 'na']) */
+fn complete_field_name_args(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_call: &ruff_python_ast::ExprCall, offset: usize, is_param: bool) -> Option<CompletionResponse> {
+    let (field_arg, literal) = FieldNameArgs::get_arguments(expr_call).into_iter().find_map(|(field_arg, value)|
+        FieldNameArgs::get_string_literals(value).into_iter()
+            .find(|s| offset > s.range.start().to_usize() && offset < s.range.end().to_usize())
+            .map(|s| (field_arg, s)))?;
+    let Expr::Attribute(func) = &*expr_call.func else {
+        return None;
+    };
+    let file_info = FileMgr::get_file_info_of_symbol(session, file)?;
+    let source = Borrows::try_borrow(&file_info)?.get_text(&literal.range)?;
+    let map = StringSourceMap::from_string_literal(&source, literal.range.start(), literal);
+    let before_cursor = &map.value()[..map.value_offset(TextSize::new(offset as u32))];
+    let prefix_start = before_cursor.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map(|i| i + 1).unwrap_or(0);
+    let prefix = &before_cursor[prefix_start..];
+    //the cursor must be on a field name, not on a group by interval, an order direction or a related field
+    if !prefix.is_empty() && !FieldNameArgs::get_field_names(before_cursor, field_arg.format).last()
+        .is_some_and(|(_, range)| range.start == prefix_start && range.end == before_cursor.len()) {
+        return Some(CompletionResponse::List(CompletionList { is_incomplete: false, items: vec![] }));
+    }
+    let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
+    let receiver = Evaluation::eval_from_ast(session, &func.value, scope, &expr_call.range().start()).0;
+    let file_module = file.borrow().find_module();
+    let mut items = vec![];
+    for eval in receiver.iter() {
+        let eval_symbol = eval.symbol.get_symbol(session, &mut None, &mut vec![], Some(file.clone()));
+        for symbol_type in Symbol::follow_ref(&eval_symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+            let Some(model) = symbol_type.weak.upgrade() else {
+                continue;
+            };
+            if model.borrow().typ() != SymType::CLASS || model.borrow().as_class_sym()._model.is_none() {
+                continue;
+            }
+            let mut all_symbols: HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>> = HashMap::new();
+            Symbol::all_members(&model, session, &mut all_symbols, true, file_module.clone(), &mut None, false);
+            for (symbol_name, symbols) in all_symbols {
+                if !symbol_name.starts_with(prefix) || items.iter().any(|i: &CompletionItem| i.label == symbol_name) {
+                    continue;
+                }
+                if let Some((final_sym, dep)) = symbols.first() {
+                    if final_sym.borrow().is_field(session) {
+                        items.push(build_completion_item_from_symbol(session, final_sym, dep.clone(), file_module.clone()));
+                    }
+                }
+            }
+        }
+    }
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items
+    }))
+}
+
+/* Return true if the offset is between the parenthesis of the call. If the call is not closed yet, the arguments
+range ends with the last argument, that can be followed by the cursor */
+fn is_in_arguments(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_call: &ruff_python_ast::ExprCall, offset: usize) -> bool {
+    let range = expr_call.arguments.range();
+    if offset <= range.start().to_usize() || offset > range.end().to_usize() {
+        return false;
+    }
+    if offset < range.end().to_usize() {
+        return true;
+    }
+    let closing = FileMgr::get_file_info_of_symbol(session, file).and_then(|file_info|
+        Borrows::try_borrow(&file_info).and_then(|file_info| file_info.get_text(&TextRange::new(range.end() - TextSize::new(1), range.end()))));
+    closing.is_some_and(|closing| closing != ")")
+}
+
+/* Build the completion items of the keyword arguments of the called function that start with prefix, and that are not
+already given in the call, by keyword or by position */
+fn complete_call_keywords(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_call: &ruff_python_ast::ExprCall, offset: usize, is_param: bool, prefix: &str) -> Vec<CompletionItem> {
+    let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
+    let callees = Evaluation::eval_from_ast(session, &expr_call.func, scope, &expr_call.func.range().start()).0;
+    //the name being typed is not a positional argument yet
+    let given = Arguments {
+        range: expr_call.arguments.range,
+        args: expr_call.arguments.args.iter()
+            .filter(|arg| !(offset > arg.range().start().to_usize() && offset <= arg.range().end().to_usize()))
+            .cloned().collect(),
+        keywords: expr_call.arguments.keywords.clone(),
+    };
+    let mut keywords = vec![];
+    let mut visited = vec![];
+    add_callees_keywords(session, &callees, Some(file.clone()), Some(&given), &mut keywords, &mut visited, 0);
+    let used: Vec<String> = expr_call.arguments.keywords.iter().filter_map(|k| k.arg.as_ref().map(|a| a.id.to_string())).collect();
+    keywords.into_iter()
+        .filter(|(name, _)| name.starts_with(prefix) && !used.contains(name))
+        .map(|(name, owner)| CompletionItem {
+            label: format!("{}=", name),
+            filter_text: Some(name.clone()),
+            insert_text: Some(format!("{}=", name)),
+            kind: Some(CompletionItemKind::FIELD),
+            detail: Some(format!("parameter of {}", owner)),
+            //keywords are shown before any other identifier (see get_sort_text_for_symbol)
+            sort_text: Some(format!("!{}", name)),
+            ..Default::default()
+        }).collect()
+}
+
+/* Add to keywords the keyword arguments (name, name of the function) of the evaluated callees.
+For functions, all the overloads are merged, and the callees that receive the **kwargs of the function are followed.
+For classes, the keywords of __init__ are used, and the attributes of the class for odoo fields. The parameters bound to
+the positional arguments of the call, if given, are skipped */
+fn add_callees_keywords(session: &mut SessionInfo, callees: &Vec<Evaluation>, file: Option<Rc<RefCell<Symbol>>>, arguments: Option<&Arguments>, keywords: &mut Vec<(String, String)>, visited: &mut Vec<Rc<RefCell<Symbol>>>, depth: u8) {
+    if depth > MAX_KWARGS_FORWARD_DEPTH {
+        return;
+    }
+    for callee in callees.iter() {
+        let callee_weak = callee.symbol.get_symbol(session, &mut None, &mut vec![], file.clone());
+        if callee_weak.weak.is_expired() {
+            continue;
+        }
+        for callee_type in Symbol::follow_ref(&callee_weak, session, &mut None, true, false, None, &mut vec![]).iter() {
+            let Some(callee_sym) = callee_type.weak.upgrade() else {
+                continue;
+            };
+            let typ = callee_sym.borrow().typ();
+            match typ {
+                SymType::FUNCTION => add_function_keywords(session, &callee_sym, arguments, keywords, visited, depth),
+                SymType::CLASS => {
+                    let inits = callee_sym.borrow().get_member_symbol(session, &S!("__init__"), None, true, false, false, false).0;
+                    for init in inits.iter() {
+                        if init.borrow().typ() == SymType::FUNCTION {
+                            add_function_keywords(session, init, arguments, keywords, visited, depth);
+                        }
+                    }
+                    add_field_attributes_keywords(session, &callee_sym, keywords);
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+fn add_function_keywords(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, arguments: Option<&Arguments>, keywords: &mut Vec<(String, String)>, visited: &mut Vec<Rc<RefCell<Symbol>>>, depth: u8) {
+    if visited.iter().any(|v| Rc::ptr_eq(v, function)) {
+        return;
+    }
+    visited.push(function.clone());
+    let overloads = Overloads::get_signatures(function);
+    for overload in overloads.iter() {
+        let function_name = overload.borrow().name().clone();
+        let mut kwargs_name = None;
+        let positional = arguments.map(|arguments| get_positional_parameters(&overload.borrow(), arguments)).unwrap_or_default();
+        for (index, arg) in overload.borrow().as_func().args.iter().enumerate() {
+            let Some(arg_sym) = arg.symbol.upgrade() else {
+                continue;
+            };
+            let arg_name = arg_sym.borrow().name().clone();
+            match arg.arg_type {
+                ArgumentType::ARG | ArgumentType::KWORD_ONLY => {
+                    if arg_name != "self" && arg_name != "cls" && !positional.contains(&index) && !keywords.iter().any(|(name, _)| *name == arg_name) {
+                        keywords.push((arg_name, function_name.clone()));
+                    }
+                },
+                ArgumentType::KWARG => kwargs_name = Some(arg_name),
+                _ => {}
+            }
+        }
+        if let Some(kwargs_name) = kwargs_name {
+            for forward in get_kwargs_forwards(session, overload, &kwargs_name).iter() {
+                let callees = Evaluation::eval_from_ast(session, forward, overload.clone(), &forward.range().start()).0;
+                add_callees_keywords(session, &callees, None, None, keywords, visited, depth + 1);
+            }
+        }
+    }
+}
+
+/* Return the indexes of the parameters of the function that receive the positional arguments. The self or cls parameter
+of a method is bound to its receiver */
+fn get_positional_parameters(function: &Symbol, arguments: &Arguments) -> Vec<usize> {
+    let function = function.as_func();
+    let is_bound = function.args.first().is_some_and(|arg| arg.arg_type == ArgumentType::ARG
+        && arg.symbol.upgrade().is_some_and(|arg_sym| matches!(arg_sym.borrow().name().as_str(), "self" | "cls")));
+    CallBinding::bind_function(function, arguments, is_bound).args.into_iter().flatten().collect()
+}
+
+/* Return the called expressions of the calls that receive the **kwargs of the function */
+fn get_kwargs_forwards(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>, kwargs_name: &str) -> Vec<Expr> {
+    let mut res = vec![];
+    let Some(file_info) = FileMgr::get_file_info_of_symbol(session, function) else {
+        return res;
+    };
+    let Some(file_info) = Borrows::try_borrow(&file_info) else {
+        return res;
+    };
+    let function = function.borrow();
+    let Some(Stmt::FunctionDef(function_def)) = function.get_ast_node(&file_info) else {
+        return res;
+    };
+    for stmt in function_def.body.iter() {
+        for call in AstUtils::find_calls_in_stmt(stmt) {
+            let forwards_kwargs = call.arguments.keywords.iter().any(|k|
+                k.arg.is_none() && matches!(&k.value, Expr::Name(name) if name.id.as_str() == kwargs_name));
+            if forwards_kwargs {
+                res.push(call.func.as_ref().clone());
+            }
+        }
+    }
+    res
+}
+
+/* Odoo fields take their attributes as keyword arguments (through **kwargs) */
+fn add_field_attributes_keywords(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, keywords: &mut Vec<(String, String)>) {
+    let field_classes = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("fields")], vec![S!("Field")]), u32::MAX);
+    let Some(field_class) = field_classes.last() else {
+        return;
+    };
+    if !Rc::ptr_eq(class, field_class) && !class.borrow().as_class_sym().inherits(field_class, &mut None) {
+        return;
+    }
+    let mut members: HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>> = HashMap::new();
+    Symbol::all_members(class, session, &mut members, false, None, &mut None, false);
+    let class_name = class.borrow().name().clone();
+    let mut names: Vec<String> = members.into_iter()
+        .filter(|(name, symbols)| !name.starts_with('_') && !FIELD_INTERNAL_ATTRIBUTES.contains(&name.as_str()) &&
+            symbols.first().is_some_and(|(s, _)| s.borrow().typ() == SymType::VARIABLE))
+        .map(|(name, _)| name).collect();
+    names.sort();
+    for name in names {
+        if !keywords.iter().any(|(n, _)| *n == name) {
+            keywords.push((name, class_name.clone()));
+        }
+    }
+}
+
 fn complete_string_literal(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_string_literal: &ruff_python_ast::ExprStringLiteral, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
     let mut items = vec![];
     let current_module = file.borrow().find_module();
@@ -501,7 +964,7 @@ fn complete_string_literal(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>
                                 !ModuleSymbol::is_in_deps(session, &current_module, &module.borrow().as_module_package().dir_name, &mut None));
                             let dep_names: Vec<String> = required_modules.map(|module| module.borrow().as_module_package().dir_name.clone()).collect();
                             if !dep_names.is_empty() {
-                                if !session.sync_odoo.config.ac_filter_model_names{
+                                if !session.sync_odoo.config.ac_filter_model_names && !is_guarded_at(session, file, &GuardedName::MODEL(model_name.clone()), offset) {
                                     continue
                                 }
                                 label_details = Some(CompletionItemLabelDetails {
@@ -536,42 +999,143 @@ fn complete_string_literal(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>
 }
 
 fn complete_attribut(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, attr: &ExprAttribute, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
-    let mut items = vec![];
     let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
     if offset > attr.value.range().start().to_usize() && offset <= attr.value.range().end().to_usize() {
         return complete_expr( &attr.value, session, file, offset, is_param, expected_type);
-    } else {
-        let parent = Evaluation::eval_from_ast(session, &attr.value, scope, &attr.range().start()).0;
-
-        for parent_eval in parent.iter() {
-            let parent_sym_eval_weak = parent_eval.symbol.get_symbol(session, &mut None, &mut vec![], Some(file.clone()));
-            if !parent_sym_eval_weak.weak.is_expired() {
-                let parent_sym_types = Symbol::follow_ref(&parent_sym_eval_weak, session, &mut None, true, false, None, &mut vec![]);
-                for parent_sym_type in parent_sym_types.iter() {
-                    if let Some(parent_sym) = parent_sym_type.weak.upgrade() {
-                        let mut all_symbols: HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>> = HashMap::new();
-                        let from_module = parent_sym.borrow().find_module().clone();
-                        Symbol::all_members(&parent_sym, session, &mut all_symbols, true, from_module, &mut None, parent_sym_eval_weak.is_super);
-                        for (_symbol_name, symbols) in all_symbols {
-                            //we could use symbol_name to remove duplicated names, but it would hide functions vs variables
-                            if _symbol_name.starts_with(attr.attr.id.as_str()) {
-                                if let Some((final_sym, dep)) = symbols.first() {
-                                    items.push(build_completion_item_from_symbol(session, final_sym, dep.clone()));
-                                }
-                            }
+    }
+    let parent = Evaluation::eval_from_ast(session, &attr.value, scope, &attr.range().start()).0;
+    Some(CompletionResponse::List(CompletionList {
+        is_incomplete: false,
+        items: get_member_items(session, file, &parent, attr.attr.id.as_str(), offset, None)
+    }))
+}
+
+/* A member proposed by the completion: its name, its symbol, its dependency and the types declaring it */
+type MemberItem = (String, Rc<RefCell<Symbol>>, Option<String>, Vec<String>);
+
+/* Return the completion items of the members of the evaluations that start with the prefix. The target of an assignment
+is completed with the fields computed by the enclosing method first, and only with the writable fields once = is typed */
+fn get_member_items(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, parent: &Vec<Evaluation>, prefix: &str, offset: usize, target: Option<AssignmentTarget>) -> Vec<CompletionItem> {
+    let file_module = file.borrow().find_module();
+    //the receiver can be a union: the members of all its types are proposed, with the types declaring each of them
+    let mut bases = vec![];
+    for parent_eval in parent.iter() {
+        let parent_sym_eval_weak = parent_eval.symbol.get_symbol(session, &mut None, &mut vec![], Some(file.clone()));
+        if !parent_sym_eval_weak.weak.is_expired() {
+            let parent_sym_types = Symbol::follow_ref(&parent_sym_eval_weak, session, &mut None, true, false, None, &mut vec![]);
+            bases.extend(parent_sym_types.into_iter().map(|parent_sym_type| (parent_sym_type, parent_sym_eval_weak.is_super)));
+        }
+    }
+    let arms = UnionMembers::get_arms(&bases.iter().map(|(base, _)| base.clone()).collect());
+    let arm_names: Vec<String> = arms.iter().map(|(arm, _)| UnionMembers::get_arm_name(arm)).collect();
+    let mut members: Vec<MemberItem> = vec![];
+    let mut seen_bases: Vec<Rc<RefCell<Symbol>>> = vec![];
+    for (parent_sym_type, is_super) in bases.iter() {
+        if let Some(parent_sym) = parent_sym_type.weak.upgrade() {
+            if seen_bases.iter().any(|b| Rc::ptr_eq(b, &parent_sym)) {
+                continue;
+            }
+            seen_bases.push(parent_sym.clone());
+            let arm_name = UnionMembers::get_arm_name(&parent_sym);
+            let mut all_symbols: HashMap<String, Vec<(Rc<RefCell<Symbol>>, Option<String>)>> = HashMap::new();
+            let from_module = parent_sym.borrow().find_module().clone();
+            Symbol::all_members(&parent_sym, session, &mut all_symbols, true, from_module, &mut None, *is_super);
+            for (_symbol_name, symbols) in all_symbols {
+                //we could use symbol_name to remove duplicated names, but it would hide functions vs variables
+                if _symbol_name.starts_with(prefix) {
+                    if let Some((final_sym, dep)) = symbols.first() {
+                        match members.iter_mut().find(|(name, _, _, _)| *name == _symbol_name) {
+                            Some((_, _, _, providers)) => providers.push(arm_name.clone()),
+                            None => members.push((_symbol_name, final_sym.clone(), dep.clone(), vec![arm_name.clone()])),
                         }
                     }
                 }
             }
         }
     }
-    Some(CompletionResponse::List(CompletionList {
-        is_incomplete: false,
-        items
-    }))
+    let computed = match target {
+        Some(target) => filter_assigned_members(session, file, offset, target, &mut members),
+        None => vec![],
+    };
+    let mut items = vec![];
+    for (name, symbol, dep, providers) in members.iter() {
+        let mut item = build_completion_item_from_symbol(session, symbol, dep.clone(), file_module.clone());
+        if computed.contains(name) {
+            item.sort_text = Some(format!("!{}", name));
+            item.preselect = Some(true);
+        }
+        if let Some(provenance) = UnionMembers::get_provenance(&arm_names, providers) {
+            item.detail = Some(match item.detail {
+                Some(detail) => format!("{}, {}", provenance, detail),
+                None => provenance
+            });
+        }
+        items.push(item);
+    }
+    items
+}
+
+/* Keep the members that can be assigned, and return the names of the fields computed by the enclosing method. The
+fields computed by the method are kept, as it must assign them even if they are readonly. The members of the classes that
+are not models are all kept */
+fn filter_assigned_members(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, offset: usize, target: AssignmentTarget,
+    members: &mut Vec<MemberItem>) -> Vec<String> {
+    let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, false);
+    let method = match scope.borrow().typ() {
+        SymType::FUNCTION => Some(scope.borrow().name().clone()),
+        _ => None
+    };
+    let mut class_fields: Vec<(Rc<RefCell<Symbol>>, Vec<FieldDeclaration>)> = vec![];
+    let mut computed = vec![];
+    let mut kept = vec![];
+    for member in members.drain(..) {
+        let (is_computed, writable) = get_field_assignability(session, &member.1, method.as_deref(), &mut class_fields)
+            .unwrap_or((false, true));
+        if is_computed {
+            computed.push(member.0.clone());
+        }
+        if target == AssignmentTarget::STATEMENT || is_computed || writable {
+            kept.push(member);
+        }
+    }
+    *members = kept;
+    computed
+}
+
+/* Return if the member of a model is a field computed by the method, and if it is writable, from its declaration in the
+body of its class. The fields of the classes already read are kept in class_fields. None if it is not a member of a model */
+fn get_field_assignability(session: &mut SessionInfo, member: &Rc<RefCell<Symbol>>, method: Option<&str>,
+    class_fields: &mut Vec<(Rc<RefCell<Symbol>>, Vec<FieldDeclaration>)>) -> Option<(bool, bool)> {
+    let class = member.borrow().parent().and_then(|parent| parent.upgrade())?;
+    if class.borrow().typ() != SymType::CLASS || class.borrow().as_class_sym()._model.is_none() {
+        return None;
+    }
+    if member.borrow().typ() != SymType::VARIABLE {
+        return Some((false, false));
+    }
+    let index = match class_fields.iter().position(|(c, _)| Rc::ptr_eq(c, &class)) {
+        Some(index) => index,
+        None => {
+            let fields = ComputeTargets::get_class_fields(session, &class);
+            class_fields.push((class, fields));
+            class_fields.len() - 1
+        }
+    };
+    let name = member.borrow().name().clone();
+    let Some(declaration) = class_fields[index].1.iter().find(|declaration| declaration.name == name) else {
+        //a field without a literal declaration, or an attribute of the model
+        let is_field = member.borrow().is_field(session);
+        return Some((false, is_field));
+    };
+    let is_computed = method.is_some_and(|method| ComputeTargets::is_computed_by(declaration, method));
+    Some((is_computed, ComputeTargets::is_writable(declaration)))
 }
 
 fn complete_subscript(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_subscript: &ExprSubscript, offset: usize, is_param: bool, expected_type: &Vec<ExpectedType>) -> Option<CompletionResponse> {
+    if let Some(literal) = expr_subscript.slice.as_string_literal_expr().filter(|literal| ContextKeys::is_context(&expr_subscript.value)
+        && offset > literal.range.start().to_usize() && offset < literal.range.end().to_usize()) {
+        return complete_read_context_keys(session, literal);
+    }
     let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, is_param);
     let subscripted = Evaluation::eval_from_ast(session, &expr_subscript.value, scope, &expr_subscript.value.range().start()).0;
     for eval in subscripted.iter() {
@@ -628,7 +1192,13 @@ fn complete_list(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, expr_lis
     None
 }
 
-fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, dependency: Option<String>) -> CompletionItem {
+/* Return true if the name is guarded at the given offset of the file (see Guard) */
+fn is_guarded_at(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, name: &GuardedName, offset: usize) -> bool {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file);
+    file_info.and_then(|file_info| Borrows::try_borrow(&file_info).map(|file_info| file_info.is_guarded(name, offset as u32))).unwrap_or(false)
+}
+
+fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>, dependency: Option<String>, from_module: Option<Rc<RefCell<Symbol>>>) -> CompletionItem {
     //TODO use dependency to show it? or to filter depending of configuration
     let typ = Symbol::follow_ref(&EvaluationSymbolWeak::new(
         Rc::downgrade(symbol),
@@ -690,6 +1260,7 @@ fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefC
                             },
                             crate::core::evaluation::EvaluationValue::DICT(_) => None,
                             crate::core::evaluation::EvaluationValue::LIST(_) => None,
+                            crate::core::evaluation::EvaluationValue::SET(_) => None,
                             crate::core::evaluation::EvaluationValue::TUPLE(_) => None,
                         }
                     } else {
@@ -739,6 +1310,7 @@ fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefC
                             },
                             crate::core::evaluation::EvaluationValue::DICT(_) => None,
                             crate::core::evaluation::EvaluationValue::LIST(_) => None,
+                            crate::core::evaluation::EvaluationValue::SET(_) => None,
                             crate::core::evaluation::EvaluationValue::TUPLE(_) => None,
                         }
                     } else {
@@ -768,10 +1340,13 @@ fn build_completion_item_from_symbol(session: &mut SessionInfo, symbol: &Rc<RefC
             })}
         };
     }
+    //show the module that defines the member of a model
+    let detail = Symbol::get_member_contributions(symbol, session, from_module)
+        .and_then(|contributions| contributions.first().map(|(module, _)| format!("defined in {}", module)));
     CompletionItem {
         label: symbol.borrow().name().clone(),
         label_details: label_details,
-        detail: None,
+        detail: detail,
         kind: Some(get_completion_item_kind(symbol)),
         sort_text: Some(get_sort_text_for_symbol(symbol)),
         documentation: Some(
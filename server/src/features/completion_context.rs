@@ -0,0 +1,230 @@
+use ruff_python_ast::Expr;
+use ruff_text_size::{TextRange, TextSize};
+
+/* Keywords that can precede an expression. They end the scan of a receiver */
+const KEYWORDS: [&str; 33] = ["and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+    "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass",
+    "raise", "return", "try", "while", "with", "yield"];
+
+/* What the cursor is on, in the line being typed */
+#[derive(Debug)]
+pub enum CompletionContext {
+    ATTRIBUTE(Expr, String), //the receiver before the dot, parsed from the line, and the beginning of the attribute: self.partner_id.na
+    STRING, //in a string that is not terminated: the string completions apply
+    COMMENT,
+    OTHER, //the completion of the ast applies
+}
+
+/* The attribute being typed is the target of an assignment: self.amount_total = ... */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum AssignmentTarget {
+    ASSIGNED, //followed by = or an augmented assignment
+    STATEMENT, //alone at the beginning of a statement, that can become an assignment or a call
+}
+
+/* Operators of the augmented assignments */
+const AUGMENTED_ASSIGNMENTS: [&str; 13] = ["+=", "-=", "*=", "/=", "//=", "%=", "**=", "@=", "&=", "|=", "^=", ">>=", "<<="];
+
+/* The line being typed is often a syntax error, like 'self.partner_id.' followed by nothing, and the ast has no node at
+the cursor. The receiver of an attribute is then found back from the text of the line: the names, calls, subscripts and
+strings before the dot are scanned backwards until the beginning of the expression, then parsed on their own. Only the
+line of the cursor is read: a string opened on a previous line is not seen. */
+pub struct CompletionContexts {}
+
+impl CompletionContexts {
+
+    /* Return the context of a completion at the offset of the source */
+    pub fn extract(source: &str, offset: usize) -> CompletionContext {
+        if offset > source.len() || !source.is_char_boundary(offset) {
+            return CompletionContext::OTHER;
+        }
+        let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line = &source[line_start..offset];
+        match CompletionContexts::get_line_end(line) {
+            LineEnd::STRING => return CompletionContext::STRING,
+            LineEnd::COMMENT => return CompletionContext::COMMENT,
+            LineEnd::CODE => {}
+        }
+        let prefix_start = line.trim_end_matches(CompletionContexts::is_identifier_char).len();
+        let Some(before_dot) = line[..prefix_start].strip_suffix('.') else {
+            return CompletionContext::OTHER;
+        };
+        let receiver_start = CompletionContexts::find_receiver_start(before_dot);
+        let receiver = &before_dot[receiver_start..];
+        //'1.' is the beginning of a float
+        if receiver.is_empty() || receiver.starts_with(|c: char| c.is_ascii_digit()) {
+            return CompletionContext::OTHER;
+        }
+        let range = TextRange::new(TextSize::new((line_start + receiver_start) as u32), TextSize::new((line_start + before_dot.len()) as u32));
+        match ruff_python_parser::parse_expression_range(source, range) {
+            Ok(parsed) => CompletionContext::ATTRIBUTE(parsed.expr().clone(), line[prefix_start..].to_string()),
+            Err(_) => CompletionContext::OTHER
+        }
+    }
+
+    /* Return if the attribute at the offset is the target of an assignment: its receiver begins the line, and the rest of
+    the line is empty or an assignment */
+    pub fn get_assignment_target(source: &str, offset: usize) -> Option<AssignmentTarget> {
+        if offset > source.len() || !source.is_char_boundary(offset) {
+            return None;
+        }
+        let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line = &source[line_start..offset];
+        if CompletionContexts::get_line_end(line) != LineEnd::CODE {
+            return None;
+        }
+        let prefix_start = line.trim_end_matches(CompletionContexts::is_identifier_char).len();
+        let before_dot = line[..prefix_start].strip_suffix('.')?;
+        let receiver_start = CompletionContexts::find_receiver_start(before_dot);
+        if receiver_start == before_dot.len() || !before_dot[..receiver_start].trim().is_empty() {
+            return None;
+        }
+        let line_end = source[offset..].find('\n').map_or(source.len(), |index| offset + index);
+        let rest = source[offset..line_end].trim_start_matches(CompletionContexts::is_identifier_char).trim();
+        if rest.is_empty() || rest.starts_with('#') {
+            return Some(AssignmentTarget::STATEMENT);
+        }
+        if (rest.starts_with('=') && !rest.starts_with("==")) || AUGMENTED_ASSIGNMENTS.iter().any(|op| rest.starts_with(op)) {
+            return Some(AssignmentTarget::ASSIGNED);
+        }
+        None
+    }
+
+    /* Return where the end of the line is: in the code, in a string or in a comment */
+    fn get_line_end(line: &str) -> LineEnd {
+        let chars: Vec<char> = line.chars().collect();
+        let mut quote: Option<(char, bool)> = None; //the quote of the current string, and if it is tripled
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            match quote {
+                Some((_, _)) if c == '\\' => index += 1, //the escaped character can't end the string
+                Some((q, false)) if c == q => quote = None,
+                Some((q, true)) if chars[index..].starts_with(&[q, q, q]) => {
+                    quote = None;
+                    index += 2;
+                },
+                Some(_) => {},
+                None if c == '#' => return LineEnd::COMMENT,
+                None if c == '"' || c == '\'' => {
+                    let triple = chars[index..].starts_with(&[c, c, c]);
+                    quote = Some((c, triple));
+                    if triple {
+                        index += 2;
+                    }
+                },
+                None => {}
+            }
+            index += 1;
+        }
+        match quote {
+            Some(_) => LineEnd::STRING,
+            None => LineEnd::CODE
+        }
+    }
+
+    /* Return the byte index where the expression that ends the text begins. The expression is made of names joined by
+    dots, calls, subscripts and strings: self.env['res.partner'].search([]) */
+    fn find_receiver_start(text: &str) -> usize {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let mut index = chars.len(); //the receiver starts at chars[index]
+        let mut start = index; //start of the last complete part of the receiver
+        while index > 0 {
+            let c = chars[index - 1].1;
+            if CompletionContexts::is_identifier_char(c) {
+                let end = index;
+                while index > 0 && CompletionContexts::is_identifier_char(chars[index - 1].1) {
+                    index -= 1;
+                }
+                //'await', 'not' or 'return' before the receiver are not a part of it
+                let name: String = chars[index..end].iter().map(|(_, c)| c).collect();
+                if KEYWORDS.contains(&name.as_str()) {
+                    break;
+                }
+                start = index;
+                //a name continues the receiver after a dot
+                if index > 1 && chars[index - 1].1 == '.' {
+                    index -= 1;
+                    continue;
+                }
+                break;
+            } else if c == ')' || c == ']' || c == '}' {
+                let Some(opening) = CompletionContexts::find_opening(&chars, index - 1) else {
+                    break;
+                };
+                index = opening;
+                start = index;
+                //a call or a subscript continues the receiver: the callee or the subscripted value comes before
+                match chars[..index].last() {
+                    Some((_, c)) if CompletionContexts::is_identifier_char(*c) || matches!(c, ')' | ']' | '}' | '"' | '\'') => continue,
+                    _ => break
+                }
+            } else if c == '"' || c == '\'' {
+                let Some(opening) = CompletionContexts::find_string_start(&chars, index - 1) else {
+                    break;
+                };
+                index = opening;
+                start = index;
+                //the prefix of the string, like f'' or rb''
+                let prefix_len = chars[..index].iter().rev().take_while(|(_, c)| CompletionContexts::is_identifier_char(*c)).count();
+                let prefix: String = chars[index - prefix_len..index].iter().map(|(_, c)| c.to_ascii_lowercase()).collect();
+                if prefix_len <= 2 && prefix.chars().all(|c| matches!(c, 'r' | 'b' | 'f' | 'u')) {
+                    index -= prefix_len;
+                    start = index;
+                }
+                match chars[..index].last() {
+                    Some((_, c)) if matches!(c, '"' | '\'') => continue, //adjacent strings are concatenated: 'a''b'
+                    _ => break
+                }
+            } else {
+                break;
+            }
+        }
+        chars.get(start).map_or(text.len(), |(byte, _)| *byte)
+    }
+
+    /* Return the index of the bracket opened by the closing one at the given index, skipping the strings */
+    fn find_opening(chars: &[(usize, char)], closing: usize) -> Option<usize> {
+        let mut expected = vec![];
+        let mut index = closing + 1;
+        while index > 0 {
+            index -= 1;
+            match chars[index].1 {
+                ')' => expected.push('('),
+                ']' => expected.push('['),
+                '}' => expected.push('{'),
+                c @ ('(' | '[' | '{') => {
+                    if expected.pop() != Some(c) {
+                        return None;
+                    }
+                    if expected.is_empty() {
+                        return Some(index);
+                    }
+                },
+                '"' | '\'' => index = CompletionContexts::find_string_start(chars, index)?,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /* Return the index of the quote that opens the string ended by the quote at the given index */
+    fn find_string_start(chars: &[(usize, char)], closing: usize) -> Option<usize> {
+        let quote = chars[closing].1;
+        (0..closing).rev().find(|index| {
+            chars[*index].1 == quote && chars[..*index].iter().rev().take_while(|(_, c)| *c == '\\').count() % 2 == 0
+        })
+    }
+
+    fn is_identifier_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LineEnd {
+    CODE,
+    STRING,
+    COMMENT,
+}
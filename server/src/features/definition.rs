@@ -1,38 +1,112 @@
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::{cell::RefCell, rc::Rc};
+use ruff_python_ast::Expr;
 use ruff_text_size::{TextRange, TextSize};
-use lsp_types::{GotoDefinitionResponse, Location, Range};
+use lsp_types::{GotoDefinitionResponse, Location, LocationLink, Position, Range};
 
+use crate::core::borrows::Borrows;
 use crate::constants::SymType;
-use crate::core::evaluation::AnalyzeAstResult;
+use crate::core::context_keys::ContextKeys;
+use crate::core::evaluation::{AnalyzeAstResult, Evaluation, EvaluationValue, ExprOrIdent};
 use crate::core::file_mgr::{FileMgr, FileInfo};
+use crate::core::symbols::module_symbol::ModuleSymbol;
 use crate::core::symbols::symbol::Symbol;
 use crate::threads::SessionInfo;
-use crate::features::ast_utils::AstUtils;
+use crate::features::ast_utils::{AstUtils, ExprFinderVisitor};
+use crate::features::readonly_documents::ReadonlyDocumentFeature;
 use crate::utils::PathSanitizer as _;
 
 
 
 pub struct DefinitionFeature {}
 
+/* A definition found for the expression: the uri of its file, the range of the whole definition, and the range of its name */
+struct DefinitionTarget {
+    uri: lsp_types::Uri,
+    range: Range,
+    selection_range: Range,
+}
+
 impl DefinitionFeature {
 
+    /* Return the definitions of the symbol at the position. An imported name leads to the symbol defined by the module it
+    is imported from, through the re-exports and the star imports */
     pub fn get_location(session: &mut SessionInfo,
         file_symbol: &Rc<RefCell<Symbol>>,
         file_info: &Rc<RefCell<FileInfo>>,
         line: u32,
         character: u32
     ) -> Option<GotoDefinitionResponse> {
-        let offset = file_info.borrow().position_to_offset(line, character);
-        let (analyse_ast_result, _range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
+        DefinitionFeature::get_targets(session, file_symbol, file_info, line, character, true)
+    }
+
+    /* Return the declarations of the symbol at the position: like the definitions, but an imported name leads to its
+    nearest binding. The import statement of the file for a usage of the name, and the name in the module it is imported
+    from for the import statement itself, that can be a re-export */
+    pub fn get_declaration(session: &mut SessionInfo,
+        file_symbol: &Rc<RefCell<Symbol>>,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32
+    ) -> Option<GotoDefinitionResponse> {
+        DefinitionFeature::get_targets(session, file_symbol, file_info, line, character, false)
+    }
+
+    fn get_targets(session: &mut SessionInfo,
+        file_symbol: &Rc<RefCell<Symbol>>,
+        file_info: &Rc<RefCell<FileInfo>>,
+        line: u32,
+        character: u32,
+        follow_imports: bool
+    ) -> Option<GotoDefinitionResponse> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let (analyse_ast_result, range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
         if analyse_ast_result.evaluations.is_empty() {
             return None;
         }
-        let mut links = vec![];
+        let mut targets: Vec<DefinitionTarget> = vec![];
         let mut evaluations = analyse_ast_result.evaluations.clone();
+        //the import variables already followed, that are not followed again: the circular imports, and the last bindings of
+        //the imports leading to nothing
+        let mut followed_imports: Vec<Rc<RefCell<Symbol>>> = vec![];
         let mut index = 0;
         while index < evaluations.len() {
             let eval = evaluations[index].clone();
+            if let Some(EvaluationValue::CONSTANT(Expr::StringLiteral(literal))) = eval.value.as_ref() {
+                //an xml id, like in env.ref('module.record_id'), leads to the elements of the data files declaring it
+                let xml_targets = DefinitionFeature::get_xml_id_targets(session, &literal.value.to_string());
+                if !xml_targets.is_empty() {
+                    evaluations.remove(index);
+                    for target in xml_targets {
+                        if !targets.iter().any(|t| t.uri == target.uri && t.range == target.range) {
+                            targets.push(target);
+                        }
+                    }
+                    continue;
+                }
+                //a key read from the context, like in context.get('skip_check'), leads to the places setting it
+                let key_targets = DefinitionFeature::get_context_key_targets(session, file_info, offset as u32, &literal.value.to_string());
+                if !key_targets.is_empty() {
+                    evaluations.remove(index);
+                    for target in key_targets {
+                        if !targets.iter().any(|t| t.uri == target.uri && t.range == target.range) {
+                            targets.push(target);
+                        }
+                    }
+                    continue;
+                }
+                //a model name, like in env['res.partner'] or @api.returns('res.partner'), leads to the classes declaring the model
+                let model = session.sync_odoo.models.get(&literal.value.to_string()).cloned();
+                if let Some(model) = model {
+                    evaluations.remove(index);
+                    let from_module = Borrows::try_borrow(file_symbol)?.find_module();
+                    for main_class in model.borrow().get_main_symbols(session, from_module, &mut None).iter() {
+                        evaluations.push(Evaluation::eval_from_symbol(&Rc::downgrade(main_class), None));
+                    }
+                    continue;
+                }
+            }
             let sym_ref = eval.symbol.get_symbol(session, &mut None, &mut vec![], None);
             let loc_sym = sym_ref.weak.upgrade();
             if loc_sym.is_none() {
@@ -40,10 +114,40 @@ impl DefinitionFeature {
                 continue;
             }
             let symbol =loc_sym.unwrap();
+            if symbol.borrow().typ() == SymType::VARIABLE && (symbol.borrow().as_variable().is_narrowed || symbol.borrow().as_variable().is_container_mutation) {
+                //a narrowed or mutated variable is not a declaration: go to the ones it changes
+                evaluations.remove(index);
+                let name = symbol.borrow().name().clone();
+                let start = symbol.borrow().range().start().to_u32();
+                if let Some(parent) = symbol.borrow().parent().and_then(|p| p.upgrade()) {
+                    for previous in Symbol::infer_name(session.sync_odoo, &parent, &name, Some(start)).iter() {
+                        evaluations.push(Evaluation::eval_from_symbol(&Rc::downgrade(previous), None));
+                    }
+                }
+                continue;
+            }
+            let is_followed = followed_imports.iter().any(|followed| Rc::ptr_eq(followed, &symbol));
+            if follow_imports && !is_followed && symbol.borrow().typ() == SymType::VARIABLE && symbol.borrow().as_variable().is_import_variable {
+                evaluations.remove(index);
+                for imported in DefinitionFeature::follow_imports(session, &symbol, &mut followed_imports).iter() {
+                    evaluations.push(Evaluation::eval_from_symbol(&Rc::downgrade(imported), None));
+                }
+                continue;
+            }
+            if symbol.borrow().typ() == SymType::COMPILED {
+                //a compiled module has no source: go to the stub generated by the server
+                if let Some((uri, range)) = ReadonlyDocumentFeature::get_compiled_location(session, &symbol) {
+                    if !targets.iter().any(|t| t.uri == uri && t.range == range) {
+                        targets.push(DefinitionTarget{ uri, range, selection_range: range });
+                    }
+                }
+                index += 1;
+                continue;
+            }
             let file = symbol.borrow().get_file();
             if let Some(file) = file {
                 //if the symbol is at the given offset, let's take the next evaluation instead
-                if Rc::ptr_eq(&file.upgrade().unwrap(), file_symbol) && symbol.borrow().has_range() && symbol.borrow().range().contains(TextSize::new(offset as u32)) {
+                if !is_followed && Rc::ptr_eq(&file.upgrade().unwrap(), file_symbol) && symbol.borrow().has_range() && symbol.borrow().range().contains(TextSize::new(offset as u32)) {
                     evaluations.remove(index);
                     let symbol = symbol.borrow();
                     let sym_eval = symbol.evaluations();
@@ -53,29 +157,213 @@ impl DefinitionFeature {
                     continue;
                 }
                 for path in file.upgrade().unwrap().borrow().paths().iter() {
-                    links.push(
-                        match symbol.borrow().typ() {
-                            SymType::PACKAGE(_) => Location{
-                                uri: FileMgr::pathname2uri(&PathBuf::from(path).join(format!("__init__.py{}", symbol.borrow().as_package().i_ext())).sanitize()),
-                                range: Range::default()
-                            },
-                            SymType::FILE => Location{
-                                uri: FileMgr::pathname2uri(path),
-                                range: Range::default()
-                            },
-                            _ => {
-                                let range = *symbol.borrow().range();
-                                Location{
+                    let target = match symbol.borrow().typ() {
+                        SymType::PACKAGE(_) => {
+                            let init_path = PathBuf::from(path).join(format!("__init__.py{}", symbol.borrow().as_package().i_ext())).sanitize();
+                            DefinitionTarget{
+                                uri: DefinitionFeature::get_file_uri(session, &init_path),
+                                range: Range::default(),
+                                selection_range: Range::default(),
+                            }
+                        },
+                        SymType::FILE => DefinitionTarget{
+                            uri: DefinitionFeature::get_file_uri(session, path),
+                            range: Range::default(),
+                            selection_range: Range::default(),
+                        },
+                        _ => {
+                            let range = *symbol.borrow().range();
+                            let name_range = DefinitionFeature::get_name_range(session, &symbol).unwrap_or(range);
+                            if let Some((uri, target_range)) = ReadonlyDocumentFeature::get_source_location(session, path, &range) {
+                                //the file has been moved or deleted since it was loaded: its symbols are in the content read by the server
+                                let selection_range = ReadonlyDocumentFeature::get_source_location(session, path, &name_range).map_or(target_range, |(_, r)| r);
+                                DefinitionTarget{ uri, range: target_range, selection_range }
+                            } else {
+                                let file_mgr = session.sync_odoo.get_file_mgr();
+                                DefinitionTarget{
                                     uri: FileMgr::pathname2uri(path),
-                                    range: session.sync_odoo.get_file_mgr().borrow_mut().text_range_to_range(session, path, &range)
+                                    range: file_mgr.borrow_mut().text_range_to_range(session, path, &range),
+                                    selection_range: file_mgr.borrow_mut().text_range_to_range(session, path, &name_range),
                                 }
                             }
                         }
-                    );
+                    };
+                    //different evaluations can lead to the same definition
+                    if !targets.iter().any(|t| t.uri == target.uri && t.range == target.range) {
+                        targets.push(target);
+                    }
                 }
             }
             index += 1;
         }
-        Some(GotoDefinitionResponse::Array(links))
+        if !session.sync_odoo.definition_link_support {
+            return Some(GotoDefinitionResponse::Array(targets.into_iter().map(|t| Location{
+                uri: t.uri,
+                range: t.selection_range
+            }).collect()));
+        }
+        let origin_range = DefinitionFeature::get_origin_range(file_info, offset as u32).or(range).and_then(|range| {
+            let file_info = Borrows::try_borrow(file_info)?;
+            Some(Range {
+                start: file_info.offset_to_position(range.start().to_usize()),
+                end: file_info.offset_to_position(range.end().to_usize())
+            })
+        });
+        Some(GotoDefinitionResponse::Link(targets.into_iter().map(|t| LocationLink{
+            origin_selection_range: origin_range,
+            target_uri: t.uri,
+            target_range: t.range,
+            target_selection_range: t.selection_range
+        }).collect()))
+    }
+
+    /* Return the symbols an import variable leads to, following the variables of the modules it imports from. A chain
+    leading to nothing, or to a compiled module without source, gives its last variable instead. The variables followed
+    are added to followed, so a circular chain of imports ends */
+    fn follow_imports(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>, followed: &mut Vec<Rc<RefCell<Symbol>>>) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res: Vec<Rc<RefCell<Symbol>>> = vec![];
+        //the symbols to follow, with the last import variable leading to them
+        let mut to_follow: VecDeque<(Rc<RefCell<Symbol>>, Rc<RefCell<Symbol>>)> = VecDeque::from([(variable.clone(), variable.clone())]);
+        while let Some((symbol, binding)) = to_follow.pop_front() {
+            let is_import = symbol.borrow().typ() == SymType::VARIABLE && symbol.borrow().as_variable().is_import_variable;
+            let target = if is_import {
+                if followed.iter().any(|s| Rc::ptr_eq(s, &symbol)) {
+                    continue;
+                }
+                followed.push(symbol.clone());
+                let imported = symbol.borrow().evaluations().cloned().unwrap_or_default();
+                let mut found = false;
+                for evaluation in imported.iter() {
+                    if let Some(next) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade() {
+                        to_follow.push_back((next, symbol.clone()));
+                        found = true;
+                    }
+                }
+                if found {
+                    continue;
+                }
+                symbol
+            } else if symbol.borrow().typ() == SymType::COMPILED && ReadonlyDocumentFeature::get_compiled_location(session, &symbol).is_none() {
+                binding
+            } else {
+                symbol
+            };
+            if !res.iter().any(|s| Rc::ptr_eq(s, &target)) {
+                res.push(target);
+            }
+        }
+        //a chain of imports going back to the variable
+        if res.is_empty() {
+            res.push(variable.clone());
+        }
+        res
+    }
+
+    /* Return the uri of a file, or the one of its content read by the server if it is not on the disk anymore */
+    fn get_file_uri(session: &mut SessionInfo, path: &String) -> lsp_types::Uri {
+        ReadonlyDocumentFeature::get_source_location(session, path, &TextRange::default())
+            .map_or_else(|| FileMgr::pathname2uri(path), |(uri, _)| uri)
+    }
+
+    /* Return the elements of the data files that declare the xml id */
+    fn get_xml_id_targets(session: &mut SessionInfo, xml_id: &String) -> Vec<DefinitionTarget> {
+        let Some(module) = xml_id.split_once('.').and_then(|(module, _)| session.sync_odoo.modules.get(module)).and_then(|module| module.upgrade()) else {
+            return vec![];
+        };
+        let module = module.borrow();
+        module.as_module_package().xml_ids.get(xml_id).map(|declarations| declarations.iter().map(|(path, range)| DefinitionTarget {
+            uri: FileMgr::pathname2uri(path),
+            range: *range,
+            selection_range: *range,
+        }).collect()).unwrap_or_default()
+    }
+
+    /* Return the places setting the context key, if the offset is on a key read from the context */
+    fn get_context_key_targets(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, offset: u32, key: &String) -> Vec<DefinitionTarget> {
+        let is_read_key = Borrows::try_borrow(file_info).and_then(|file_info| file_info.ast.as_ref()
+            .and_then(|ast| ContextKeys::get_read_key_at(ast, TextSize::new(offset)).map(|literal| literal.value.to_str() == key)))
+            .unwrap_or(false);
+        if !is_read_key {
+            return vec![];
+        }
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let Some(locations) = Borrows::try_borrow(&file_mgr).map(|file_mgr| file_mgr.context_keys.get_locations(key)) else {
+            return vec![];
+        };
+        locations.into_iter().filter_map(|(path, range)| {
+            let range = Borrows::try_borrow_mut(&file_mgr)?.text_range_to_range(session, &path, &range);
+            Some(DefinitionTarget {
+                uri: FileMgr::pathname2uri(&path),
+                range,
+                selection_range: range,
+            })
+        }).collect()
+    }
+
+    /* Return the methods called by the code of a cron or a server action at the given position of a data file */
+    pub fn get_xml_location(session: &mut SessionInfo, path: &String, line: u32, character: u32) -> Option<GotoDefinitionResponse> {
+        let position = Position::new(line, character);
+        let modules: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.modules.values().filter_map(|module| module.upgrade()).collect();
+        for module in modules.iter() {
+            let reference = module.borrow().as_module_package().xml_method_references.iter()
+                .find(|(ref_path, reference)| ref_path == path && reference.range.start <= position && position <= reference.range.end)
+                .map(|(_, reference)| reference.clone());
+            let Some(reference) = reference else {
+                continue;
+            };
+            let methods = ModuleSymbol::find_xml_method(session, module, &reference).unwrap_or_default();
+            let mut locations = vec![];
+            for method in methods.iter() {
+                let Some(file) = method.borrow().get_file().and_then(|file| file.upgrade()) else {
+                    continue;
+                };
+                let name_range = DefinitionFeature::get_name_range(session, method).unwrap_or(*method.borrow().range());
+                for method_path in file.borrow().paths().iter() {
+                    let range = Borrows::try_borrow_mut(&session.sync_odoo.get_file_mgr())?.text_range_to_range(session, method_path, &name_range);
+                    locations.push(Location { uri: FileMgr::pathname2uri(method_path), range });
+                }
+            }
+            return Some(GotoDefinitionResponse::Array(locations));
+        }
+        None
+    }
+
+    /* Return the range of the name under the cursor: the attribute of 'a.b.c' instead of the whole expression */
+    pub fn get_origin_range(file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Option<TextRange> {
+        let file_info = Borrows::try_borrow(file_info)?;
+        let expr = file_info.ast.as_ref()?.iter().find_map(|stmt| ExprFinderVisitor::find_expr_at(stmt, offset))?;
+        match expr {
+            ExprOrIdent::Expr(Expr::Attribute(attr)) => Some(attr.attr.range),
+            ExprOrIdent::Parameter(p) => Some(p.name.range),
+            _ => Some(expr.range())
+        }
+    }
+
+    /* Return the range of the name of a class or a function, as their range covers their decorators and body */
+    pub fn get_name_range(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<TextRange> {
+        let keyword = match symbol.borrow().typ() {
+            SymType::CLASS => "class",
+            SymType::FUNCTION => "def",
+            _ => return None
+        };
+        let file_info = FileMgr::get_file_info_of_symbol(session, symbol)?;
+        let symbol = symbol.borrow();
+        let range = *symbol.range();
+        let text = Borrows::try_borrow(&file_info)?.get_text(&range)?;
+        let name = symbol.name();
+        let start = DefinitionFeature::find_definition_name(&text, keyword, name)?;
+        let start = range.start() + TextSize::new(start as u32);
+        Some(TextRange::at(start, TextSize::new(name.len() as u32)))
+    }
+
+    /* Return the offset of the name in the source of a definition, skipping decorators: 'def name' or 'class name' */
+    pub fn find_definition_name(text: &str, keyword: &str, name: &str) -> Option<usize> {
+        text.match_indices(name).map(|(i, _)| i).find(|i| {
+            let before = text[..*i].trim_end();
+            let is_keyword = before.strip_suffix(keyword)
+                .is_some_and(|b| b.is_empty() || b.ends_with(|c: char| c.is_whitespace()));
+            let after_name = text[*i + name.len()..].chars().next();
+            is_keyword && before.len() < *i && !after_name.is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
     }
-}
\ No newline at end of file
+}
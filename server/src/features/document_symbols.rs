@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{DocumentSymbol, DocumentSymbolResponse, Range, SymbolKind};
+use ruff_python_ast::Stmt;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::file_mgr::FileInfo;
+use crate::core::symbols::symbol::Symbol;
+
+pub struct DocumentSymbolFeature {}
+
+impl DocumentSymbolFeature {
+
+    /* Return the outline of the file: its classes, functions and variables, with the members of the classes and the
+    functions declared in the functions. The bodies of the methods are only known once they are built */
+    pub fn get_document_symbols(file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>) -> Option<DocumentSymbolResponse> {
+        let file_info = Borrows::try_borrow(file_info)?;
+        if file_info.ast.is_none() {
+            return None;
+        }
+        Some(DocumentSymbolResponse::Nested(DocumentSymbolFeature::get_children(file_symbol, &file_info)))
+    }
+
+    /* Return the document symbols of the content of the scope, in the order of the file, so that two builds of the file
+    give the same outline. A name declared several times is given by its first declaration */
+    fn get_children(scope: &Rc<RefCell<Symbol>>, file_info: &FileInfo) -> Vec<DocumentSymbol> {
+        let in_function = scope.borrow().typ() == SymType::FUNCTION;
+        let mut declarations: Vec<Rc<RefCell<Symbol>>> = vec![];
+        for (_, sections) in scope.borrow().iter_symbols() {
+            let first = sections.values().flatten()
+                .filter(|symbol| DocumentSymbolFeature::is_outlined(symbol, in_function))
+                .min_by_key(|symbol| symbol.borrow().range().start());
+            declarations.extend(first.cloned());
+        }
+        declarations.sort_by(|a, b| (a.borrow().range().start(), a.borrow().name()).cmp(&(b.borrow().range().start(), b.borrow().name())));
+        declarations.iter().filter_map(|symbol| DocumentSymbolFeature::build_document_symbol(symbol, file_info)).collect()
+    }
+
+    /* Return true if the symbol is part of the outline. The imports and the parameters are not, nor the variables without
+    statement, like the names backfilled from the __all__ of an external file, nor the generated __init__ of the
+    dataclasses. The functions only show their classes and their functions */
+    fn is_outlined(symbol: &Rc<RefCell<Symbol>>, in_function: bool) -> bool {
+        let symbol = symbol.borrow();
+        match symbol.typ() {
+            SymType::CLASS => true,
+            SymType::FUNCTION => !symbol.as_func().is_synthesized,
+            SymType::VARIABLE if in_function => false,
+            SymType::VARIABLE => {
+                let variable = symbol.as_variable();
+                !variable.is_import_variable && !variable.is_parameter && !variable.is_narrowed && !variable.is_container_mutation
+                    && !variable.is_dynamic_field
+            },
+            _ => false
+        }
+    }
+
+    fn build_document_symbol(symbol: &Rc<RefCell<Symbol>>, file_info: &FileInfo) -> Option<DocumentSymbol> {
+        let symbol_bw = symbol.borrow();
+        let name = symbol_bw.name().clone();
+        //the range spans the statement declaring the symbol, and the selection range its name. A variable without
+        //statement is not in the outline
+        let (range, selection_range) = match symbol_bw.get_ast_node(file_info) {
+            Some(Stmt::ClassDef(class_def)) if class_def.name.as_str() == name => (class_def.range(), class_def.name.range()),
+            Some(Stmt::FunctionDef(function_def)) if function_def.name.as_str() == name => (function_def.range(), function_def.name.range()),
+            Some(stmt) => (stmt.range(), *symbol_bw.range()),
+            None if symbol_bw.typ() == SymType::VARIABLE => return None,
+            None => (*symbol_bw.range(), *symbol_bw.range())
+        };
+        let kind = match symbol_bw.typ() {
+            SymType::CLASS => SymbolKind::CLASS,
+            SymType::FUNCTION if symbol_bw.as_func().is_property => SymbolKind::PROPERTY,
+            SymType::FUNCTION if symbol_bw.parent().and_then(|parent| parent.upgrade()).is_some_and(|parent| parent.borrow().typ() == SymType::CLASS) => SymbolKind::METHOD,
+            SymType::FUNCTION => SymbolKind::FUNCTION,
+            _ if DocumentSymbolFeature::is_constant_name(&name) => SymbolKind::CONSTANT,
+            _ => SymbolKind::VARIABLE
+        };
+        let children = match symbol_bw.typ() {
+            SymType::CLASS | SymType::FUNCTION => Some(DocumentSymbolFeature::get_children(symbol, file_info)),
+            _ => None
+        };
+        #[allow(deprecated)]
+        let document_symbol = DocumentSymbol {
+            name,
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: DocumentSymbolFeature::to_range(file_info, &range),
+            selection_range: DocumentSymbolFeature::to_range(file_info, &selection_range),
+            children,
+        };
+        Some(document_symbol)
+    }
+
+    /* Return true if the name is written in capitals, like MAX_SIZE */
+    pub fn is_constant_name(name: &str) -> bool {
+        name.chars().any(|c| c.is_ascii_uppercase()) && !name.chars().any(|c| c.is_lowercase())
+    }
+
+    fn to_range(file_info: &FileInfo, range: &TextRange) -> Range {
+        Range {
+            start: file_info.offset_to_position(range.start().to_usize()),
+            end: file_info.offset_to_position(range.end().to_usize()),
+        }
+    }
+}
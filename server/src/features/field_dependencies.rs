@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::field_dependencies::{FieldRef, DEFAULT_DEPENDENCY_DEPTH};
+use crate::threads::SessionInfo;
+
+/* Request returning the fields a field depends on, and the fields that depend on it, to show the impact of a change */
+pub const FIELD_DEPENDENCIES_METHOD: &str = "odooLs/fieldDependencies";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDependenciesParams {
+    pub model: String,
+    pub field: String,
+    pub max_depth: Option<u32>, //the number of levels followed, capped by MAX_DEPENDENCY_DEPTH
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDependency {
+    pub model: String,
+    pub field: String,
+    pub depth: u32, //1 for a direct dependency
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDependencies {
+    pub model: String,
+    pub field: String,
+    pub dependencies: Vec<FieldDependency>, //the fields read to compute the field, ordered by depth, model and field
+    pub dependents: Vec<FieldDependency>, //the fields recomputed when the field changes, in the same order
+    pub truncated: bool, //some fields are deeper than the depth asked
+}
+
+pub struct FieldDependenciesFeature {}
+
+impl FieldDependenciesFeature {
+
+    pub fn get(session: &mut SessionInfo, params: &FieldDependenciesParams) -> FieldDependencies {
+        let field = FieldRef::new(&params.model, &params.field);
+        let max_depth = params.max_depth.unwrap_or(DEFAULT_DEPENDENCY_DEPTH);
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let file_mgr = file_mgr.borrow();
+        let (dependencies, dependencies_truncated) = file_mgr.field_dependencies.get_dependencies(&field, max_depth);
+        let (dependents, dependents_truncated) = file_mgr.field_dependencies.get_dependents(&field, max_depth);
+        let to_response = |fields: Vec<(FieldRef, u32)>| fields.into_iter()
+            .map(|(field, depth)| FieldDependency { model: field.model, field: field.field, depth }).collect();
+        FieldDependencies {
+            model: params.model.clone(),
+            field: params.field.clone(),
+            dependencies: to_response(dependencies),
+            dependents: to_response(dependents),
+            truncated: dependencies_truncated || dependents_truncated,
+        }
+    }
+}
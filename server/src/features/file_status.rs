@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use lsp_types::Uri;
+use serde::Serialize;
+
+use crate::core::borrows::Borrows;
+use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::core::file_mgr::FileMgr;
+use crate::threads::SessionInfo;
+
+/* Method of the notification pushed when the status of an opened file changes, and of the request asking the status
+of any file */
+pub const FILE_STATUS_METHOD: &str = "odooLs/fileStatus";
+
+/* Version of the payload. It only changes if a field is removed or changes meaning: the new fields are optional, and
+ignored by the older extensions */
+pub const FILE_STATUS_VERSION: u32 = 1;
+
+/* Minimum delay between two pushes during a rebuild. The statuses at the end of the rebuild are always pushed */
+const PUSH_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[allow(non_camel_case_types)]
+pub enum StepStatus {
+    #[serde(rename = "pending")]
+    PENDING,
+    #[serde(rename = "inProgress")]
+    IN_PROGRESS,
+    #[serde(rename = "done")]
+    DONE,
+}
+
+impl From<BuildStatus> for StepStatus {
+    fn from(status: BuildStatus) -> Self {
+        match status {
+            BuildStatus::PENDING => StepStatus::PENDING,
+            BuildStatus::IN_PROGRESS => StepStatus::IN_PROGRESS,
+            BuildStatus::DONE => StepStatus::DONE,
+        }
+    }
+}
+
+/* Where a file is in the build pipeline, sent with odooLs/fileStatus */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatus {
+    pub version: u32,
+    pub uri: Uri,
+    pub arch: StepStatus,
+    pub arch_eval: StepStatus,
+    pub odoo: StepStatus,
+    pub validation: StepStatus,
+    pub queue_position: Option<u32>, //see SyncOdoo::get_rebuild_queue_position
+    pub last_build: Option<u64>, //end of the last validation, in milliseconds since the epoch
+    pub stale: bool, //the symbols are built from a previous version of the file, as the current one has syntax errors
+    pub hidden_diagnostics: u32, //diagnostics not published because of the maxFileDiagnostics setting
+}
+
+/* The statuses sent to the client for the opened files */
+#[derive(Debug, Default)]
+pub struct FileStatuses {
+    sent: HashMap<String, FileStatus>,
+    last_push: Option<Instant>,
+}
+
+impl FileStatuses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub struct FileStatusFeature {}
+
+impl FileStatusFeature {
+
+    /* Return the status of the file at the given path, if it is part of the symbol tree */
+    pub fn get_file_status(session: &mut SessionInfo, path: &String) -> Option<FileStatus> {
+        session.sync_odoo.tree_from_path(&PathBuf::from(path)).ok()?;
+        let symbol_rc = session.sync_odoo.get_file_symbol(&PathBuf::from(path))?;
+        let symbol = Borrows::try_borrow(&symbol_rc)?;
+        if !matches!(symbol.typ(), SymType::FILE | SymType::PACKAGE(_)) {
+            return None;
+        }
+        let file_info = Borrows::try_borrow(&session.sync_odoo.get_file_mgr())?.get_file_info(path);
+        let file_info = file_info.as_ref().and_then(|file_info| Borrows::try_borrow(file_info));
+        Some(FileStatus {
+            version: FILE_STATUS_VERSION,
+            uri: FileMgr::pathname2uri(path),
+            arch: symbol.build_status(BuildSteps::ARCH).into(),
+            arch_eval: symbol.build_status(BuildSteps::ARCH_EVAL).into(),
+            odoo: symbol.build_status(BuildSteps::ODOO).into(),
+            validation: symbol.build_status(BuildSteps::VALIDATION).into(),
+            queue_position: session.sync_odoo.get_rebuild_queue_position(&symbol_rc),
+            last_build: file_info.as_ref().and_then(|file_info| file_info.last_validation())
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis() as u64),
+            stale: file_info.as_ref().is_some_and(|file_info| !file_info.valid),
+            hidden_diagnostics: file_info.as_ref().map_or(0, |file_info| file_info.count_hidden_diagnostics(session.sync_odoo.config.max_file_diagnostics)),
+        })
+    }
+
+    /* Push the statuses of the opened files that changed since the last push. Unless forced, nothing is pushed if the
+    last push is too recent, so a rebuild only sends a few notifications. */
+    pub fn push_changes(session: &mut SessionInfo, force: bool) {
+        let statuses = &mut session.sync_odoo.file_statuses;
+        if !force && statuses.last_push.is_some_and(|last_push| last_push.elapsed() < PUSH_DELAY) {
+            return;
+        }
+        statuses.last_push = Some(Instant::now());
+        let opened: Vec<String> = session.sync_odoo.get_file_mgr().borrow().files.iter()
+            .filter(|(path, file_info)| path.ends_with(".py") && file_info.borrow().opened)
+            .map(|(path, _)| path.clone())
+            .collect();
+        session.sync_odoo.file_statuses.sent.retain(|path, _| opened.contains(path));
+        for path in opened.iter() {
+            let Some(status) = FileStatusFeature::get_file_status(session, path) else {
+                continue;
+            };
+            if session.sync_odoo.file_statuses.sent.get(path) != Some(&status) {
+                session.send_notification(FILE_STATUS_METHOD, &status);
+                session.sync_odoo.file_statuses.sent.insert(path.clone(), status);
+            }
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use ruff_python_ast::Expr;
 use ruff_text_size::TextRange;
-use lsp_types::{Hover, HoverContents, MarkupContent, Range};
+use lsp_types::{Hover, HoverContents, Location, MarkupContent, Range};
+use serde::Serialize;
 use weak_table::traits::WeakElement;
-use crate::core::evaluation::{AnalyzeAstResult, Context, Evaluation, EvaluationSymbolWeak, EvaluationValue};
+use crate::core::borrows::Borrows;
+use crate::core::evaluation::{AnalyzeAstResult, Context, ContextValue, Evaluation, EvaluationSymbolWeak, EvaluationValue};
+use crate::core::model_kind::ModelKind;
+use crate::core::union_members::{MemberPresence, UnionMembers};
 use crate::core::file_mgr::{FileInfo, FileMgr};
 use crate::threads::SessionInfo;
 use crate::utils::PathSanitizer as _;
@@ -12,23 +16,32 @@ use std::rc::{Rc, Weak};
 use crate::core::symbols::symbol::Symbol;
 use crate::constants::*;
 use crate::features::ast_utils::AstUtils;
+use crate::features::hover_formatter::HoverFormatter;
+use crate::core::symbols::function_symbol::ArgumentType;
 use crate::S;
 use std::cell::RefCell;
 
 pub struct HoverFeature {}
 
+/* Contribution of a module to a member of a model, returned by the $Odoo/memberContributions request */
+#[derive(Debug, Serialize)]
+pub struct MemberContribution {
+    pub module: String,
+    pub locations: Vec<Location>,
+}
+
 impl HoverFeature {
 
     pub fn get_hover(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<Hover> {
-        let offset = file_info.borrow().position_to_offset(line, character);
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
         let (analyse_ast_result, range): (AnalyzeAstResult, Option<TextRange>) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
         let evals = analyse_ast_result.evaluations;
         if evals.is_empty() {
             return None;
         };
         let range = Some(Range {
-            start: file_info.borrow().offset_to_position(range.unwrap().start().to_usize()),
-            end: file_info.borrow().offset_to_position(range.unwrap().end().to_usize())
+            start: Borrows::try_borrow(file_info)?.offset_to_position(range.unwrap().start().to_usize()),
+            end: Borrows::try_borrow(file_info)?.offset_to_position(range.unwrap().end().to_usize())
         });
         Some(Hover { contents:
             HoverContents::Markup(MarkupContent {
@@ -39,6 +52,46 @@ impl HoverFeature {
         })
     }
 
+    /* Return the contributions of the modules to the member under the cursor, from the module defining it to the
+    last one overriding it. Only the modules in the dependencies of the current module are returned. */
+    pub fn get_member_contributions(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<Vec<MemberContribution>> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let (analyse_ast_result, _range) = AstUtils::get_symbols(session, file_symbol, file_info, offset as u32);
+        let from_module = Borrows::try_borrow(file_symbol)?.find_module();
+        for eval in analyse_ast_result.evaluations.iter() {
+            let Some(symbol) = eval.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade() else {
+                continue;
+            };
+            let Some(contributions) = Symbol::get_member_contributions(&symbol, session, from_module.clone()) else {
+                continue;
+            };
+            let mut res = vec![];
+            for (module, symbols) in contributions.iter() {
+                let mut locations = vec![];
+                for sym in symbols.iter() {
+                    let Some(file) = sym.borrow().get_file().and_then(|f| f.upgrade()) else {
+                        continue;
+                    };
+                    let path = match file.borrow().typ() {
+                        SymType::PACKAGE(_) => PathBuf::from(&file.borrow().paths()[0]).join(format!("__init__.py{}", file.borrow().as_package().i_ext())).sanitize(),
+                        _ => file.borrow().paths()[0].clone(),
+                    };
+                    let range = *sym.borrow().range();
+                    locations.push(Location {
+                        uri: FileMgr::pathname2uri(&path),
+                        range: Borrows::try_borrow_mut(&session.sync_odoo.get_file_mgr())?.text_range_to_range(session, &path, &range),
+                    });
+                }
+                res.push(MemberContribution {
+                    module: module.clone(),
+                    locations,
+                });
+            }
+            return Some(res);
+        }
+        None
+    }
+
     /*
     Build the first block of the hover. It contains the name of the variable as well as the type.
     parameters:   (type_sym)  symbol: infered_types
@@ -53,6 +106,10 @@ impl HoverFeature {
         if symbol.typ() == SymType::VARIABLE && symbol.as_variable().is_import_variable {
             type_sym = S!("import");
         }
+        let is_explicit_type_alias = symbol.typ() == SymType::VARIABLE && symbol.as_variable().is_explicit_type_alias;
+        if is_explicit_type_alias {
+            type_sym = S!("type alias");
+        }
         if symbol.typ() == SymType::VARIABLE && symbol.as_variable().is_parameter {
             type_sym = S!("parameter");
         }
@@ -70,6 +127,9 @@ impl HoverFeature {
         if infered_types.len() == 1 && infered_types[0].weak.upgrade().unwrap().borrow().typ() == SymType::FUNCTION && !infered_types[0].weak.upgrade().unwrap().borrow().as_func().is_property {
             //display 'def' only if there is only a single evaluation to a function
             single_func_eval = true;
+            if infered_types[0].weak.upgrade().unwrap().borrow().as_func().is_async {
+                value += "async ";
+            }
             value += "def ";
             value += symbol.name();
             //display args
@@ -79,8 +139,17 @@ impl HoverFeature {
             value += "(";
             let max_index = function.args.len() as i32 - 1;
             for (index, arg) in function.args.iter().enumerate() {
+                match arg.arg_type {
+                    ArgumentType::VARARG => value += "*",
+                    ArgumentType::KWARG => value += "**",
+                    _ => {}
+                }
                 value += arg.symbol.upgrade().unwrap().borrow().name();
                 //TODO add parameter type
+                if let Some(default_value) = &arg.default_value {
+                    value += "=";
+                    value += &HoverFeature::build_default_value(default_value);
+                }
                 if index != max_index as usize {
                     value += ", ";
                 }
@@ -88,7 +157,23 @@ impl HoverFeature {
             value += ") -> "
         } else {
             value += symbol.name();
-            if symbol.typ() != SymType::CLASS {
+            if is_explicit_type_alias {
+                //type Pair[T] = tuple[T, T] is displayed as Pair[T] = tuple
+                let type_params = &symbol.as_variable().type_params;
+                if !type_params.is_empty() {
+                    value += &format!("[{}]", type_params.join(", "));
+                }
+                value += " = ";
+            } else if symbol.typ() == SymType::CLASS {
+                //class InvoiceReport(models.AbstractModel) is displayed as InvoiceReport(AbstractModel)
+                let bases: Vec<String> = symbol.as_class_sym().bases.iter()
+                    .filter_map(|base| base.upgrade())
+                    .map(|base| base.borrow().name().clone())
+                    .collect();
+                if !bases.is_empty() {
+                    value += &format!("({})", bases.join(", "));
+                }
+            } else {
                 value += ": ";
             }
         }
@@ -171,6 +256,72 @@ impl HoverFeature {
         value
     }
 
+    /* Build the line describing the module that defines a member and the ones overriding it.
+    For example: "defined in `sale`, overridden in `delivery`, `sale_stock`" */
+    /* Return the default of a parameter as written in a signature: the literal of a constant, or an ellipsis for the
+    containers and the values that are not literals */
+    pub fn build_default_value(evaluation: &Evaluation) -> String {
+        match &evaluation.value {
+            Some(EvaluationValue::CONSTANT(Expr::StringLiteral(s))) => format!("'{}'", s.value.to_str()),
+            Some(EvaluationValue::CONSTANT(Expr::NumberLiteral(n))) => match &n.value {
+                ruff_python_ast::Number::Int(i) => i.to_string(),
+                ruff_python_ast::Number::Float(f) => format!("{:?}", f),
+                ruff_python_ast::Number::Complex { .. } => S!("..."),
+            },
+            Some(EvaluationValue::CONSTANT(Expr::BooleanLiteral(b))) => S!(if b.value { "True" } else { "False" }),
+            Some(EvaluationValue::CONSTANT(Expr::NoneLiteral(_))) => S!("None"),
+            //the elements of a container are not kept when one of them is not a literal
+            Some(EvaluationValue::LIST(_)) => S!("[...]"),
+            Some(EvaluationValue::TUPLE(_)) => S!("(...)"),
+            Some(EvaluationValue::DICT(_)) | Some(EvaluationValue::SET(_)) => S!("{...}"),
+            _ => S!("...")
+        }
+    }
+
+    pub fn build_provenance(contributions: &Vec<(String, Vec<Rc<RefCell<Symbol>>>)>) -> Option<String> {
+        let (defining_module, _) = contributions.first()?;
+        let mut value = format!("defined in `{}`", defining_module);
+        if contributions.len() > 1 {
+            let overrides: Vec<String> = contributions[1..].iter().map(|(module, _)| format!("`{}`", module)).collect();
+            value += format!(", overridden in {}", overrides.join(", ")).as_str();
+        }
+        Some(value)
+    }
+
+    /* Build the line giving the kind of a model. For example: "Kind: Model, with `_auto = False`" */
+    pub fn build_model_kind(kind: ModelKind, auto: bool) -> String {
+        if kind == ModelKind::MODEL && !auto {
+            format!("Kind: {}, with `_auto = False`", kind.label())
+        } else {
+            format!("Kind: {}", kind.label())
+        }
+    }
+
+    /* Build the line describing the types of a union receiver that declare a member, given joined by ' | '.
+    For example: "member of `str` only, in `int | str`" */
+    pub fn build_union_provenance(arms: &str, providers: &str) -> Option<String> {
+        let arm_names: Vec<String> = arms.split(" | ").map(|arm| arm.to_string()).collect();
+        let provider_names: Vec<String> = providers.split(" | ").filter(|p| !p.is_empty()).map(|p| p.to_string()).collect();
+        match UnionMembers::get_presence(&arm_names.iter().map(|arm| provider_names.contains(arm)).collect::<Vec<bool>>()) {
+            MemberPresence::ALL => Some(format!("member of all the types of `{}`", arms)),
+            MemberPresence::SOME(_) => {
+                let names: Vec<String> = arm_names.iter().filter(|arm| provider_names.contains(arm)).map(|arm| format!("`{}`", arm)).collect();
+                Some(format!("member of {} only, in `{}`", names.join(", "), arms))
+            },
+            MemberPresence::NONE => None
+        }
+    }
+
+    /* Return the dotted path of the module declaring the symbol, or of the symbol itself if it is a module */
+    fn get_module_path(symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+        let module = match symbol.borrow().typ() {
+            SymType::FILE | SymType::PACKAGE(_) | SymType::NAMESPACE => symbol.clone(),
+            _ => symbol.borrow().get_file()?.upgrade()?,
+        };
+        let tree = module.borrow().get_tree().0;
+        (!tree.is_empty()).then(|| tree.join("."))
+    }
+
     pub fn build_markdown_description(session: &mut SessionInfo, file_symbol: Option<Rc<RefCell<Symbol>>>, evals: &Vec<Evaluation>) -> String {
         //let eval = &evals[0]; //TODO handle more evaluations
         let mut value = S!("");
@@ -191,15 +342,20 @@ impl HoverFeature {
                     let model = session.sync_odoo.models.get(&str).cloned();
                     if let Some(model) = model {
                         if let Some(file_symbol) = file_symbol.as_ref() {
-                            let from_module = file_symbol.borrow().find_module();
+                            let from_module = Borrows::try_borrow(file_symbol).and_then(|file_symbol| file_symbol.find_module());
                             let main_class = model.borrow().get_main_symbols(session, from_module.clone(), &mut None);
+                            let kind = model.borrow().get_kind(session, from_module.clone());
+                            if let Some((kind, auto)) = kind {
+                                value += HoverFeature::build_model_kind(kind, auto).as_str();
+                                value += "  \n";
+                            }
                             for main_class in main_class.iter() {
                                 let main_class = main_class.borrow();
                                 let main_class_module = main_class.find_module();
                                 if let Some(main_class_module) = main_class_module {
                                     value += format!("Model in {}: {}  \n", main_class_module.borrow().name(), main_class.name()).as_str();
                                     if main_class.doc_string().is_some() {
-                                        value = value + "  \n***  \n" + main_class.doc_string().as_ref().unwrap();
+                                        value = value + "  \n***  \n" + &HoverFormatter::format_doc_string(main_class.doc_string().as_ref().unwrap(), session.sync_odoo.config.hover_doc_max_lines);
                                     }
                                     let mut other_imps = model.borrow().all_symbols(session, from_module.clone());
                                     other_imps.sort_by(|x, y| {
@@ -230,6 +386,40 @@ impl HoverFeature {
             }
             // BLOCK 1: (type) **name** -> infered_type
             value += HoverFeature::build_block_1(session, &symbol, &type_refs, &mut context).as_str();
+            // BLOCK 1.5: modules contributing to the member of a model
+            let from_module = file_symbol.as_ref().and_then(|f| f.borrow().find_module());
+            if let Some(contributions) = Symbol::get_member_contributions(&symbol, session, from_module) {
+                if let Some(provenance) = HoverFeature::build_provenance(&contributions) {
+                    value += "  \n";
+                    value += provenance.as_str();
+                }
+            }
+            // BLOCK 1.6: models a class is added to, when it inherits several models without _name
+            if symbol.borrow().typ() == SymType::CLASS {
+                if let Some(model_data) = symbol.borrow().as_class_sym()._model.as_ref().filter(|m| !m.other_names.is_empty()) {
+                    let names: Vec<String> = model_data.names().map(|name| format!("`{}`", name)).collect();
+                    value += format!("  \nAdded to the models {}", names.join(", ")).as_str();
+                }
+            }
+            // BLOCK 1.7: types of a union receiver declaring the member
+            if let (Some(ContextValue::STRING(arms)), Some(ContextValue::STRING(providers))) = (eval.symbol.context.get("union_arms"), eval.symbol.context.get("provided_by")) {
+                if let Some(provenance) = HoverFeature::build_union_provenance(arms, providers) {
+                    value += "  \n";
+                    value += provenance.as_str();
+                }
+            }
+            // BLOCK 1.8: module an imported name comes from
+            let is_import = symbol.borrow().typ() == SymType::VARIABLE && symbol.borrow().as_variable().is_import_variable;
+            if is_import {
+                let imported = symbol.borrow().evaluations().cloned().unwrap_or_default();
+                let module_path = imported.iter().find_map(|evaluation| {
+                    let imported_symbol = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade()?;
+                    HoverFeature::get_module_path(&imported_symbol)
+                });
+                if let Some(module_path) = module_path {
+                    value += format!("  \nImported from `{}`", module_path).as_str();
+                }
+            }
             // BLOCK 2: useful links
             for typ in type_refs.iter() {
                 let typ = typ.weak.upgrade();
@@ -255,18 +445,16 @@ impl HoverFeature {
                 let typ = typ.weak.upgrade();
                 if let Some(typ) = typ {
                     if typ.borrow().doc_string().is_some() {
-                        // Replace leading spaces with nbsps to avoid it being parsed as a Markdown Codeblock
-                        let ds = typ.borrow().doc_string().as_ref().unwrap()
-                        .lines()
-                        .map(|line| {
-                            let leading_spaces = line.chars().take_while(|&ch| ch == ' ').count();
-                            let nbsp_replacement = "&nbsp;".repeat(leading_spaces);
-                            format!("{}{}", nbsp_replacement, &line[leading_spaces..])
-                        })
-                        .collect::<Vec<String>>()
-                        .join("\n\n");
+                        let ds = HoverFormatter::format_doc_string(typ.borrow().doc_string().as_ref().unwrap(), session.sync_odoo.config.hover_doc_max_lines);
                         value = value + "  \n***  \n" + &ds;
                     }
+                    // the manifest and the README of an Odoo module, like on an import of odoo.addons.<module>
+                    if typ.borrow().typ() == SymType::PACKAGE(PackageType::MODULE) {
+                        let description = typ.borrow_mut().as_module_package_mut().get_description().to_markdown();
+                        if !description.is_empty() {
+                            value = value + "  \n***  \n" + &description;
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,401 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/* A field of a Sphinx docstring: ':param int amount: the amount', ':returns: the total', ':raises ValueError: if ...' */
+static SPHINX_FIELD: Lazy<Regex> = Lazy::new(|| Regex::new(r"^:(\w+)((?:\s+[^:\s]+)*)\s*:\s*(.*)$").unwrap());
+/* The header of a section of a Google docstring, like 'Args:' */
+static GOOGLE_HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([A-Z][a-z]+(?: [A-Za-z]+)?):\s*$").unwrap());
+/* An entry of a section of a Google docstring: 'amount (int): the amount', 'ValueError: if ...' */
+static GOOGLE_ENTRY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\*{0,2}[\w.\[\]|]+)\s*(?:\(([^)]*)\))?\s*:\s*(.*)$").unwrap());
+/* An entry of a section of a NumPy docstring: 'amount : int' */
+static NUMPY_ENTRY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\*{0,2}[\w.]+)\s*:\s*(.*)$").unwrap());
+/* The underline of the header of a section of a NumPy docstring */
+static NUMPY_UNDERLINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-{3,}\s*$").unwrap());
+/* A reST role, like :class:`~odoo.models.Model` or :py:meth:`write` */
+static REST_ROLE: Lazy<Regex> = Lazy::new(|| Regex::new(r":(?:\w+:)?\w+:`(~?)([^`]+)`").unwrap());
+/* A reST inline literal: ``value`` */
+static REST_LITERAL: Lazy<Regex> = Lazy::new(|| Regex::new(r"``([^`]+)``").unwrap());
+
+/* A section of a docstring, with its entries (the parameters, the raised exceptions...) or its text (the notes, the
+examples...) */
+#[derive(Debug)]
+struct Section {
+    title: &'static str,
+    entries: Vec<Entry>,
+    text: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    name: Option<String>,
+    typ: Option<String>,
+    description: String,
+}
+
+#[derive(Debug)]
+enum Block {
+    Text(String),
+    Code(Vec<String>),
+    Section(Section),
+}
+
+pub struct HoverFormatter {}
+
+impl HoverFormatter {
+
+    /* Return the docstring in markdown. The sections of the Sphinx (':param x:'), Google ('Args:') and NumPy
+    ('Parameters' underlined by dashes) styles are converted to lists, and the reST roles are stripped. A docstring
+    longer than max_lines lines (0 for no limit) is cut and ends with an ellipsis */
+    pub fn format_doc_string(doc_string: &str, max_lines: u32) -> String {
+        let mut lines = HoverFormatter::clean_lines(doc_string);
+        let truncated = max_lines != 0 && lines.len() > max_lines as usize;
+        if truncated {
+            lines.truncate(max_lines as usize);
+        }
+        let blocks = HoverFormatter::parse(&lines);
+        let mut res = HoverFormatter::render(&blocks);
+        if truncated {
+            res += "  \n…";
+        }
+        res
+    }
+
+    /* Return the lines of the docstring without their common indentation, as inspect.cleandoc does. The first line is
+    not part of the common indentation, as it follows the quotes */
+    fn clean_lines(doc_string: &str) -> Vec<String> {
+        let lines: Vec<&str> = doc_string.lines().collect();
+        let indent = lines.iter().skip(1)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let mut res: Vec<String> = lines.iter().enumerate().map(|(index, line)| {
+            if index == 0 {
+                line.trim().to_string()
+            } else if line.trim().is_empty() {
+                String::new()
+            } else {
+                line[indent..].trim_end().to_string()
+            }
+        }).collect();
+        while res.last().is_some_and(|line| line.is_empty()) {
+            res.pop();
+        }
+        let first = res.iter().position(|line| !line.is_empty()).unwrap_or(res.len());
+        res.drain(..first);
+        res
+    }
+
+    fn indent_of(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+
+    /* Return the title of a section given by its name in a Google or a NumPy docstring, and if it lists entries */
+    fn get_section(name: &str) -> Option<(&'static str, bool)> {
+        match name {
+            "Args" | "Arguments" | "Parameters" | "Params" | "Other Parameters" => Some(("Parameters", true)),
+            "Keyword Args" | "Keyword Arguments" => Some(("Keyword arguments", true)),
+            "Returns" | "Return" => Some(("Returns", true)),
+            "Yields" | "Yield" => Some(("Yields", true)),
+            "Raises" => Some(("Raises", true)),
+            "Attributes" => Some(("Attributes", true)),
+            "Example" | "Examples" => Some(("Examples", false)),
+            "Note" | "Notes" => Some(("Notes", false)),
+            "See Also" => Some(("See also", false)),
+            "Warning" | "Warnings" => Some(("Warning", false)),
+            "Todo" => Some(("Todo", false)),
+            _ => None
+        }
+    }
+
+    /* Return the title of the section of a Sphinx field, like Parameters for ':param x:' */
+    fn get_sphinx_section(field: &str) -> Option<&'static str> {
+        match field {
+            "param" | "parameter" | "arg" | "argument" | "key" | "keyword" | "type" => Some("Parameters"),
+            "returns" | "return" | "rtype" => Some("Returns"),
+            "yields" | "yield" | "ytype" => Some("Yields"),
+            "raises" | "raise" | "except" | "exception" => Some("Raises"),
+            _ => None
+        }
+    }
+
+    fn parse(lines: &[String]) -> Vec<Block> {
+        let mut blocks: Vec<Block> = vec![];
+        let mut sphinx_sections: Vec<Section> = vec![];
+        let mut index = 0;
+        while index < lines.len() {
+            let line = &lines[index];
+            let trimmed = line.trim_start();
+            let indent = HoverFormatter::indent_of(line);
+            let next = lines.get(index + 1).map(|line| line.as_str());
+            //NumPy: the name of the section underlined by dashes
+            if let Some((title, is_list)) = HoverFormatter::get_section(trimmed).filter(|_| next.is_some_and(|next| NUMPY_UNDERLINE.is_match(next.trim()))) {
+                let (section, end) = HoverFormatter::parse_numpy_section(lines, index + 2, indent, title, is_list);
+                blocks.push(Block::Section(section));
+                index = end;
+                continue;
+            }
+            //Google: the name of the section followed by a colon, and its indented content
+            let google_section = GOOGLE_HEADER.captures(trimmed).and_then(|captures| HoverFormatter::get_section(captures.get(1).unwrap().as_str()));
+            let next_content = lines[index + 1..].iter().find(|line| !line.is_empty());
+            if let Some((title, is_list)) = google_section.filter(|_| next_content.is_some_and(|next| HoverFormatter::indent_of(next) > indent)) {
+                let (section, end) = HoverFormatter::parse_google_section(lines, index + 1, indent, title, is_list);
+                blocks.push(Block::Section(section));
+                index = end;
+                continue;
+            }
+            //Sphinx: a field, gathered with the other fields of its section at the end of the documentation
+            if let Some(captures) = SPHINX_FIELD.captures(trimmed) {
+                let field = captures.get(1).unwrap().as_str();
+                if let Some(title) = HoverFormatter::get_sphinx_section(field) {
+                    let mut description = captures.get(3).unwrap().as_str().to_string();
+                    index += 1;
+                    while index < lines.len() && !lines[index].is_empty() && HoverFormatter::indent_of(&lines[index]) > indent {
+                        description = format!("{} {}", description, lines[index].trim()).trim().to_string();
+                        index += 1;
+                    }
+                    let words: Vec<&str> = captures.get(2).unwrap().as_str().split_whitespace().collect();
+                    HoverFormatter::add_sphinx_field(&mut sphinx_sections, title, field, &words, description);
+                    continue;
+                }
+            }
+            //an example of the interactive interpreter, until the next empty line
+            if trimmed.starts_with(">>>") {
+                let mut code = vec![];
+                while index < lines.len() && !lines[index].is_empty() {
+                    code.push(lines[index].trim().to_string());
+                    index += 1;
+                }
+                blocks.push(Block::Code(code));
+                continue;
+            }
+            blocks.push(Block::Text(line.clone()));
+            index += 1;
+        }
+        blocks.extend(sphinx_sections.into_iter().map(Block::Section));
+        blocks
+    }
+
+    fn add_sphinx_field(sections: &mut Vec<Section>, title: &'static str, field: &str, words: &[&str], description: String) {
+        let section = match sections.iter().position(|section| section.title == title) {
+            Some(position) => &mut sections[position],
+            None => {
+                sections.push(Section { title, entries: vec![], text: vec![] });
+                sections.last_mut().unwrap()
+            }
+        };
+        match field {
+            //':type amount: int' and ':rtype: int' give the type of an entry declared before or after them
+            "type" => {
+                let Some(name) = words.last() else {
+                    return;
+                };
+                match section.entries.iter_mut().find(|entry| entry.name.as_deref() == Some(*name)) {
+                    Some(entry) => entry.typ = Some(description),
+                    None => section.entries.push(Entry { name: Some(name.to_string()), typ: Some(description), description: String::new() }),
+                }
+            },
+            "rtype" | "ytype" => {
+                match section.entries.first_mut() {
+                    Some(entry) => entry.typ = Some(description),
+                    None => section.entries.push(Entry { typ: Some(description), ..Default::default() }),
+                }
+            },
+            "returns" | "return" | "yields" | "yield" => {
+                match section.entries.first_mut() {
+                    Some(entry) if entry.description.is_empty() => entry.description = description,
+                    _ => section.entries.push(Entry { description, ..Default::default() }),
+                }
+            },
+            _ => {
+                //':param int amount:' gives the type before the name
+                let name = words.last().map(|name| name.to_string());
+                let typ = (words.len() > 1).then(|| words[..words.len() - 1].join(" "));
+                match section.entries.iter_mut().find(|entry| name.is_some() && entry.name == name) {
+                    Some(entry) => {
+                        entry.description = description;
+                        entry.typ = entry.typ.take().or(typ);
+                    },
+                    None => section.entries.push(Entry { name, typ, description }),
+                }
+            }
+        }
+    }
+
+    /* Parse the content of a Google section, indented under its header. Return the section and the index of the line
+    following it */
+    fn parse_google_section(lines: &[String], start: usize, header_indent: usize, title: &'static str, is_list: bool) -> (Section, usize) {
+        let mut section = Section { title, entries: vec![], text: vec![] };
+        let mut entry_indent: Option<usize> = None;
+        let mut index = start;
+        while index < lines.len() {
+            let line = &lines[index];
+            if line.is_empty() {
+                //an empty line ends the section, unless its content continues after it
+                let continues = lines[index + 1..].iter().find(|line| !line.is_empty()).is_some_and(|next| HoverFormatter::indent_of(next) > header_indent);
+                if !continues {
+                    break;
+                }
+                if !is_list {
+                    section.text.push(String::new());
+                }
+                index += 1;
+                continue;
+            }
+            let indent = HoverFormatter::indent_of(line);
+            if indent <= header_indent {
+                break;
+            }
+            let entry_indent = *entry_indent.get_or_insert(indent);
+            if !is_list {
+                section.text.push(line.chars().skip(entry_indent.min(indent)).collect());
+            } else if indent > entry_indent && !section.entries.is_empty() {
+                HoverFormatter::continue_entry(&mut section, line.trim());
+            } else {
+                section.entries.push(HoverFormatter::parse_google_entry(title, line.trim()));
+            }
+            index += 1;
+        }
+        (section, index)
+    }
+
+    fn parse_google_entry(title: &str, line: &str) -> Entry {
+        let Some(captures) = GOOGLE_ENTRY.captures(line) else {
+            return Entry { description: line.to_string(), ..Default::default() };
+        };
+        let name = captures.get(1).unwrap().as_str().to_string();
+        let description = captures.get(3).unwrap().as_str().to_string();
+        //'int: the total' gives the type of what is returned
+        if matches!(title, "Returns" | "Yields") {
+            return Entry { typ: Some(name), description, ..Default::default() };
+        }
+        Entry { name: Some(name), typ: captures.get(2).map(|typ| typ.as_str().to_string()), description }
+    }
+
+    /* Parse the content of a NumPy section, after the underline of its header. The entries are at the indentation of
+    the header, and their descriptions under them. Return the section and the index of the line following it */
+    fn parse_numpy_section(lines: &[String], start: usize, header_indent: usize, title: &'static str, is_list: bool) -> (Section, usize) {
+        let mut section = Section { title, entries: vec![], text: vec![] };
+        let mut index = start;
+        while index < lines.len() {
+            let line = &lines[index];
+            let is_next_header = HoverFormatter::get_section(line.trim()).is_some()
+                && lines.get(index + 1).is_some_and(|next| NUMPY_UNDERLINE.is_match(next.trim()));
+            if is_next_header {
+                break;
+            }
+            if line.is_empty() {
+                if !is_list {
+                    section.text.push(String::new());
+                }
+                index += 1;
+                continue;
+            }
+            let indent = HoverFormatter::indent_of(line);
+            if !is_list {
+                section.text.push(line.chars().skip(header_indent.min(indent)).collect());
+            } else if indent > header_indent && !section.entries.is_empty() {
+                HoverFormatter::continue_entry(&mut section, line.trim());
+            } else {
+                let trimmed = line.trim();
+                let entry = match NUMPY_ENTRY.captures(trimmed) {
+                    Some(captures) => Entry {
+                        name: Some(captures.get(1).unwrap().as_str().to_string()),
+                        typ: Some(captures.get(2).unwrap().as_str().to_string()).filter(|typ| !typ.is_empty()),
+                        description: String::new(),
+                    },
+                    //a line without colon is the type of what is returned, or the name of a raised exception
+                    None if matches!(title, "Returns" | "Yields") => Entry { typ: Some(trimmed.to_string()), ..Default::default() },
+                    None => Entry { name: Some(trimmed.to_string()), ..Default::default() },
+                };
+                section.entries.push(entry);
+            }
+            index += 1;
+        }
+        while section.text.last().is_some_and(|line| line.is_empty()) {
+            section.text.pop();
+        }
+        (section, index)
+    }
+
+    fn continue_entry(section: &mut Section, text: &str) {
+        let entry = section.entries.last_mut().unwrap();
+        entry.description = format!("{} {}", entry.description, text).trim().to_string();
+    }
+
+    fn render(blocks: &[Block]) -> String {
+        let mut res = String::new();
+        for block in blocks.iter() {
+            match block {
+                Block::Text(line) if line.is_empty() => res += "\n",
+                Block::Text(line) => {
+                    res += &HoverFormatter::render_text_line(line);
+                    res += "  \n";
+                },
+                Block::Code(code) => {
+                    res += &format!("\n```python\n{}\n```\n", code.join("\n"));
+                },
+                Block::Section(section) => {
+                    res += &format!("\n**{}**  \n", section.title);
+                    for entry in section.entries.iter() {
+                        res += &HoverFormatter::render_entry(entry);
+                        res += "\n";
+                    }
+                    for line in section.text.iter() {
+                        res += &HoverFormatter::render_text_line(line);
+                        res += "  \n";
+                    }
+                    res += "\n";
+                }
+            }
+        }
+        //a single empty line between the paragraphs
+        while res.contains("\n\n\n") {
+            res = res.replace("\n\n\n", "\n\n");
+        }
+        res.trim().to_string()
+    }
+
+    /* Return the line of text in markdown. The leading spaces are replaced by nbsps to avoid it being parsed as a
+    Markdown code block */
+    fn render_text_line(line: &str) -> String {
+        let leading_spaces = line.chars().take_while(|&ch| ch == ' ').count();
+        format!("{}{}", "&nbsp;".repeat(leading_spaces), HoverFormatter::strip_roles(&line[leading_spaces..]))
+    }
+
+    fn render_entry(entry: &Entry) -> String {
+        let mut res = String::from("-");
+        if let Some(name) = entry.name.as_ref() {
+            res += &format!(" `{}`", name);
+        }
+        if let Some(typ) = entry.typ.as_ref() {
+            res += &match entry.name {
+                Some(_) => format!(" (`{}`)", HoverFormatter::strip_roles(typ).replace('`', "")),
+                None => format!(" `{}`", HoverFormatter::strip_roles(typ).replace('`', "")),
+            };
+        }
+        if !entry.description.is_empty() {
+            if entry.name.is_some() || entry.typ.is_some() {
+                res += ":";
+            }
+            res += " ";
+            res += &HoverFormatter::strip_roles(&entry.description);
+        }
+        res
+    }
+
+    /* Replace the reST roles and literals by markdown code: :class:`~odoo.models.Model` gives `Model`, and ``value``
+    gives `value` */
+    pub fn strip_roles(text: &str) -> String {
+        let text = REST_ROLE.replace_all(text, |captures: &regex::Captures| {
+            let target = captures.get(2).unwrap().as_str();
+            //:meth:`write <odoo.models.Model.write>` shows its title
+            let target = target.split_once(" <").map_or(target, |(title, _)| title);
+            match captures.get(1).unwrap().as_str() {
+                "~" => format!("`{}`", target.rsplit('.').next().unwrap()),
+                _ => format!("`{}`", target),
+            }
+        });
+        REST_LITERAL.replace_all(&text, "`$1`").to_string()
+    }
+}
@@ -0,0 +1,317 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use lsp_types::{CompletionItem, CompletionItemKind, CompletionList, CompletionResponse, Documentation, GotoDefinitionResponse, Hover, HoverContents, Location, MarkupContent, MarkupKind, Range};
+use ruff_python_ast::{Expr, ExprDict, ExprStringLiteral, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::borrows::Borrows;
+use crate::constants::{tree, SymType};
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::module_description::ModuleDescription;
+use crate::core::module_hooks::{HookResolution, ModuleHooks};
+use crate::threads::SessionInfo;
+use crate::utils::{real_path, PathSanitizer as _};
+use crate::S;
+
+pub const MANIFEST_FILES: [&str; 2] = ["__manifest__.py", "__openerp__.py"];
+
+/* Known keys of a manifest: (key, expected type, description) */
+const MANIFEST_KEYS: [(&str, &str, &str); 30] = [
+    ("name", "str", "Human-readable name of the module"),
+    ("version", "str", "Version of the module, following semantic versioning rules, usually prefixed by the Odoo series (`17.0.1.0.0`)"),
+    ("summary", "str", "Short description of the module, shown in the Apps menu"),
+    ("description", "str", "Extended description of the module, in reStructuredText"),
+    ("author", "str", "Name of the module author(s)"),
+    ("maintainer", "str", "Person or organization in charge of the maintenance of the module"),
+    ("contributors", "list[str]", "People that contributed to the module"),
+    ("website", "str", "Website URL of the module author"),
+    ("category", "str", "Classification category of the module in Odoo. Use `/` to declare a hierarchy (`Sales/Point of Sale`)"),
+    ("license", "str", "Distribution license of the module. Defaults to `LGPL-3`"),
+    ("depends", "list[str]", "Odoo modules that must be loaded before this one, either because this module uses features they create or because it alters resources they define"),
+    ("data", "list[str]", "Data files (xml, csv) which must always be installed or updated with the module, as paths relative to the module directory"),
+    ("demo", "list[str]", "Data files (xml, csv) which are only installed or updated in demonstration mode"),
+    ("assets", "dict[str, list[str]]", "Static files (js, css, scss, xml templates) of the module, grouped by assets bundle"),
+    ("external_dependencies", "dict[str, list[str]]", "Python packages (`python` key) and binaries (`bin` key) required by the module. The module can't be installed if they are missing"),
+    ("installable", "bool", "Whether a user should be able to install the module from the Web UI. Defaults to `True`"),
+    ("application", "bool", "Whether the module should be considered as a fully-fledged application (`True`) or is just a technical module (`False`). Defaults to `False`"),
+    ("auto_install", "bool | list[str]", "If `True`, the module is automatically installed when all its dependencies are installed. If it is a list, the module is installed as soon as all the listed dependencies are installed"),
+    ("pre_init_hook", "str", "Name of a function of the module `__init__.py`, executed before the installation of the module. It receives the environment as parameter"),
+    ("post_init_hook", "str", "Name of a function of the module `__init__.py`, executed right after the installation of the module. It receives the environment as parameter"),
+    ("uninstall_hook", "str", "Name of a function of the module `__init__.py`, executed after the uninstallation of the module. It receives the environment as parameter"),
+    ("post_load", "str", "Name of a function of the module `__init__.py`, executed when the module is loaded, even before the registry. Used for server wide monkey patching"),
+    ("sequence", "int", "Position of the module in the Apps menu. Defaults to `100`"),
+    ("images", "list[str]", "Paths of the images of the module shown in the Apps store"),
+    ("countries", "list[str]", "Codes of the countries the module is meant for. Used by localization modules"),
+    ("web", "bool", "Whether the module adds features to the web client"),
+    ("bootstrap", "bool", "Whether the translations of the module should be loaded on the login page"),
+    ("price", "float", "Price of the module on the Odoo Apps store"),
+    ("currency", "str", "Currency of the price of the module (`EUR` or `USD`)"),
+    ("live_test_url", "str", "URL of a live demo of the module on the Odoo Apps store"),
+];
+
+const MANIFEST_LICENSES: [&str; 10] = [
+    "GPL-2",
+    "GPL-2 or any later version",
+    "GPL-3",
+    "GPL-3 or any later version",
+    "AGPL-3",
+    "LGPL-3",
+    "Other OSI approved licence",
+    "OEEL-1",
+    "OPL-1",
+    "Other proprietary",
+];
+
+const HOOK_KEYS: [&str; 4] = ["pre_init_hook", "post_init_hook", "uninstall_hook", "post_load"];
+
+/* The element of the manifest under the cursor */
+enum ManifestElement<'a> {
+    Key(&'a ExprStringLiteral),
+    Value(String, &'a ExprStringLiteral), //key, string literal of the value
+}
+
+pub struct ManifestFeature {}
+
+impl ManifestFeature {
+
+    pub fn is_manifest(path: &str) -> bool {
+        MANIFEST_FILES.iter().any(|f| path.ends_with(&format!("/{}", f)))
+    }
+
+    pub fn get_hover(session: &mut SessionInfo, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<Hover> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let file_info = Borrows::try_borrow(file_info)?;
+        let dict = ManifestFeature::get_manifest_dict(file_info.ast.as_ref()?)?;
+        let (value, range) = match ManifestFeature::find_element(dict, offset)? {
+            ManifestElement::Key(key) => {
+                let key_str = key.value.to_string();
+                let (_, typ, description) = MANIFEST_KEYS.iter().find(|(k, _, _)| *k == key_str)?;
+                (ManifestFeature::key_documentation(&key_str, typ, description), key.range)
+            },
+            ManifestElement::Value(key, value) if key == "depends" => {
+                let module_name = value.value.to_string();
+                let description = ManifestFeature::get_module_description(session, &module_name)?;
+                (ManifestFeature::module_documentation(&module_name, &description), value.range)
+            },
+            ManifestElement::Value(_, _) => return None
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(Range {
+                start: file_info.offset_to_position(range.start().to_usize()),
+                end: file_info.offset_to_position(range.end().to_usize()),
+            }),
+        })
+    }
+
+    pub fn autocomplete(session: &mut SessionInfo, path: &str, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<CompletionResponse> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let file_info = Borrows::try_borrow(file_info)?;
+        let dict = ManifestFeature::get_manifest_dict(file_info.ast.as_ref()?)?;
+        let module_path = PathBuf::from(path).parent()?.to_path_buf();
+        let items = match ManifestFeature::find_element(dict, offset)? {
+            ManifestElement::Key(_) => {
+                let used_keys: HashSet<String> = dict.iter_keys().flatten()
+                    .filter_map(|k| k.as_string_literal_expr())
+                    .filter(|k| !(k.range.start().to_usize() <= offset && offset <= k.range.end().to_usize()))
+                    .map(|k| k.value.to_string()).collect();
+                MANIFEST_KEYS.iter().filter(|(k, _, _)| !used_keys.contains(*k)).map(|(key, typ, description)| CompletionItem {
+                    label: key.to_string(),
+                    detail: Some(typ.to_string()),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    documentation: Some(Documentation::MarkupContent(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: description.to_string(),
+                    })),
+                    ..Default::default()
+                }).collect()
+            },
+            ManifestElement::Value(key, _) => {
+                match key.as_str() {
+                    "depends" => {
+                        let dir_name = module_path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+                        ManifestFeature::get_known_modules(session).into_iter().filter(|m| *m != dir_name).map(|m| {
+                            let description = ManifestFeature::get_module_description(session, &m);
+                            CompletionItem {
+                                label: m,
+                                kind: Some(CompletionItemKind::MODULE),
+                                detail: description.as_ref().and_then(|d| d.name.clone()),
+                                documentation: description.filter(|d| *d != ModuleDescription::default()).map(|d| Documentation::MarkupContent(MarkupContent {
+                                    kind: MarkupKind::Markdown,
+                                    value: d.to_markdown(),
+                                })),
+                                ..Default::default()
+                            }
+                        }).collect()
+                    },
+                    "data" | "demo" => {
+                        let mut files = vec![];
+                        ManifestFeature::get_data_files(&module_path, &module_path, &mut files, &mut HashSet::new());
+                        files.sort();
+                        files.into_iter().map(|f| CompletionItem {
+                            label: f,
+                            kind: Some(CompletionItemKind::FILE),
+                            ..Default::default()
+                        }).collect()
+                    },
+                    "license" => {
+                        MANIFEST_LICENSES.iter().map(|l| CompletionItem {
+                            label: l.to_string(),
+                            kind: Some(CompletionItemKind::VALUE),
+                            ..Default::default()
+                        }).collect()
+                    },
+                    _ => vec![]
+                }
+            }
+        };
+        Some(CompletionResponse::List(CompletionList {
+            is_incomplete: false,
+            items,
+        }))
+    }
+
+    /* Go to the hook functions declared in the manifest, through the imports of the __init__.py of the module */
+    pub fn get_location(session: &mut SessionInfo, path: &str, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<GotoDefinitionResponse> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character);
+        let hook_name = {
+            let file_info = Borrows::try_borrow(file_info)?;
+            let dict = ManifestFeature::get_manifest_dict(file_info.ast.as_ref()?)?;
+            let Some(ManifestElement::Value(key, value)) = ManifestFeature::find_element(dict, offset) else {
+                return None;
+            };
+            if !HOOK_KEYS.contains(&key.as_str()) {
+                return None;
+            }
+            value.value.to_string()
+        };
+        let dir_name = PathBuf::from(path).parent()?.file_name()?.to_str()?.to_string();
+        let module = session.sync_odoo.modules.get(&dir_name)?.upgrade()?;
+        //the hook can be imported in the __init__.py from another file of the module
+        let HookResolution::FUNCTION(functions) = ModuleHooks::resolve_hook(session, &module, &hook_name) else {
+            return None;
+        };
+        let mut links = vec![];
+        for function in functions.iter() {
+            let Some(file) = function.borrow().get_file().and_then(|f| f.upgrade()) else {
+                continue;
+            };
+            let file_path = match file.borrow().typ() {
+                SymType::PACKAGE(_) => PathBuf::from(&file.borrow().paths()[0]).join(format!("__init__.py{}", file.borrow().as_package().i_ext())).sanitize(),
+                _ => file.borrow().paths()[0].clone(),
+            };
+            let range = *function.borrow().range();
+            links.push(Location {
+                uri: FileMgr::pathname2uri(&file_path),
+                range: Borrows::try_borrow_mut(&session.sync_odoo.get_file_mgr())?.text_range_to_range(session, &file_path, &range),
+            });
+        }
+        Some(GotoDefinitionResponse::Array(links))
+    }
+
+    fn key_documentation(key: &str, typ: &str, description: &str) -> String {
+        format!("```python  \n(manifest key) {}: {}  \n```  \n---  \n{}", key, typ, description)
+    }
+
+    fn module_documentation(module_name: &str, description: &ModuleDescription) -> String {
+        let mut value = format!("```python  \n(module) {}  \n```", module_name);
+        let markdown = description.to_markdown();
+        if !markdown.is_empty() {
+            value += "  \n---  \n";
+            value += &markdown;
+        }
+        value
+    }
+
+    /* Return the description of a loaded module, cached on its symbol */
+    fn get_module_description(session: &mut SessionInfo, module_name: &str) -> Option<ModuleDescription> {
+        let module = session.sync_odoo.modules.get(module_name)?.upgrade()?;
+        let mut module = module.borrow_mut();
+        Some(module.as_module_package_mut().get_description().clone())
+    }
+
+    fn get_manifest_dict(ast: &Vec<Stmt>) -> Option<&ExprDict> {
+        ast.iter().find_map(|stmt| match stmt {
+            Stmt::Expr(expr) => expr.value.as_dict_expr(),
+            _ => None
+        })
+    }
+
+    fn find_element(dict: &ExprDict, offset: usize) -> Option<ManifestElement> {
+        let contains = |range: TextRange| range.start().to_usize() <= offset && offset <= range.end().to_usize();
+        for item in dict.items.iter() {
+            if let Some(key) = item.key.as_ref() {
+                if contains(key.range()) {
+                    return key.as_string_literal_expr().map(|k| ManifestElement::Key(k));
+                }
+            }
+            if !contains(item.value.range()) {
+                continue;
+            }
+            let Some(key) = item.key.as_ref().and_then(|k| k.as_string_literal_expr()) else {
+                return None;
+            };
+            let key = key.value.to_string();
+            let value = match &item.value {
+                Expr::StringLiteral(s) => Some(s),
+                Expr::List(l) => l.elts.iter().find(|e| contains(e.range())).and_then(|e| e.as_string_literal_expr()),
+                Expr::Tuple(t) => t.elts.iter().find(|e| contains(e.range())).and_then(|e| e.as_string_literal_expr()),
+                _ => None
+            };
+            return value.map(|v| ManifestElement::Value(key, v));
+        }
+        None
+    }
+
+    /* Return the names of the loaded modules, and of the modules found in the addons paths */
+    fn get_known_modules(session: &mut SessionInfo) -> Vec<String> {
+        let mut modules: HashSet<String> = session.sync_odoo.modules.keys().cloned().collect();
+        if let Some(addons_symbol) = session.sync_odoo.get_symbol(&tree(vec!["odoo", "addons"], vec![]), u32::MAX).get(0) {
+            for addon_path in addons_symbol.borrow().paths().iter() {
+                let Ok(entries) = fs::read_dir(addon_path) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    if MANIFEST_FILES.iter().any(|f| entry.path().join(f).exists()) {
+                        if let Some(name) = entry.file_name().to_str() {
+                            modules.insert(S!(name));
+                        }
+                    }
+                }
+            }
+        }
+        let mut modules: Vec<String> = modules.into_iter().collect();
+        modules.sort();
+        modules
+    }
+
+    /* Collect the data files (xml, csv) of the module, as paths relative to the module directory. The directories are
+    visited once by real path, so symlinks looping to a parent directory are not followed forever */
+    fn get_data_files(module_path: &Path, dir: &Path, files: &mut Vec<String>, visited: &mut HashSet<String>) {
+        if !real_path(&dir.sanitize()).is_some_and(|real| visited.insert(real)) {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if !entry.file_name().to_str().unwrap_or("").starts_with('.') && entry.file_name() != "static" {
+                    ManifestFeature::get_data_files(module_path, &path, files, visited);
+                }
+            } else if path.extension().is_some_and(|e| e == "xml" || e == "csv") {
+                if let Ok(relative) = path.strip_prefix(module_path) {
+                    files.push(relative.to_path_buf().sanitize());
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,19 @@
 pub mod completion;
+pub mod completion_context;
 pub mod definition;
+pub mod document_symbols;
+pub mod field_dependencies;
+pub mod file_status;
 pub mod hover;
-pub mod ast_utils;
\ No newline at end of file
+pub mod hover_formatter;
+pub mod ast_utils;
+pub mod manifest;
+pub mod module_graph;
+pub mod organize_imports;
+pub mod readonly_documents;
+pub mod references;
+pub mod rename;
+pub mod signature_help;
+pub mod snippets;
+pub mod test_explorer;
+pub mod workspace_symbols;
\ No newline at end of file
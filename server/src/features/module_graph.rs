@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::threads::SessionInfo;
+
+/* Request returning the modules with their depends, for the client to render the dependency graph of the modules */
+pub const MODULE_GRAPH_METHOD: &str = "odooLs/moduleGraph";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModuleGraphParams {
+    pub modules: Vec<String>, //the modules returned with their transitive dependencies. Empty for all the loaded modules
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleNode {
+    pub name: String,
+    pub path: String,
+    pub depends: Vec<String>, //the depends of the manifest, in their order
+    pub missing: Vec<String>, //the depends that are not found in the addons paths
+    pub installable: bool,
+    pub in_workspace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraph {
+    pub modules: Vec<ModuleNode>, //ordered by name
+}
+
+pub struct ModuleGraphFeature {}
+
+impl ModuleGraphFeature {
+
+    pub fn get(session: &mut SessionInfo, params: &ModuleGraphParams) -> ModuleGraph {
+        let mut nodes: BTreeMap<String, ModuleNode> = BTreeMap::new();
+        let mut to_visit: VecDeque<String> = match params.modules.is_empty() {
+            true => session.sync_odoo.modules.keys().cloned().collect(),
+            false => params.modules.iter().cloned().collect(),
+        };
+        while let Some(name) = to_visit.pop_front() {
+            if nodes.contains_key(&name) {
+                continue;
+            }
+            let Some(module) = session.sync_odoo.modules.get(&name).and_then(|module| module.upgrade()) else {
+                continue;
+            };
+            let module = module.borrow();
+            let module = module.as_module_package();
+            let depends = module.get_depends().clone();
+            let missing = depends.iter().filter(|depend| !session.sync_odoo.modules.contains_key(*depend)).cloned().collect();
+            to_visit.extend(depends.iter().cloned());
+            nodes.insert(name.clone(), ModuleNode {
+                name,
+                path: module.root_path.clone(),
+                depends,
+                missing,
+                installable: module.installable,
+                in_workspace: module.in_workspace,
+            });
+        }
+        ModuleGraph {
+            modules: nodes.into_values().collect(),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionResponse, Range, TextEdit, Uri, WorkspaceEdit};
+use ruff_python_ast::{Alias, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::file_mgr::FileInfo;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+use crate::S;
+
+use super::ast_utils::AstUtils;
+
+/* from imports longer than this are split on several lines */
+const MAX_LINE_LENGTH: usize = 100;
+
+/* Groups of imports, in the order they are written */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(non_camel_case_types)]
+pub enum ImportGroup {
+    FUTURE,
+    STDLIB,
+    THIRD_PARTY,
+    ODOO,
+    ODOO_ADDONS,
+    LOCAL,
+}
+
+/* An import statement of the organized block */
+struct ImportLine {
+    group: ImportGroup,
+    module: String, //imported module, prefixed by dots for relative imports
+    is_from: bool,
+    names: Vec<String>, //imported names, with their alias ("a as b"). For 'import x' statements, the only name is the module
+    verbatim: Option<String>, //source of a statement that must be kept as it is
+}
+
+impl ImportLine {
+    fn can_merge(&self) -> bool {
+        self.is_from && self.verbatim.is_none() && !self.names.iter().any(|n| n == "*")
+    }
+
+    fn render(&self, newline: &str) -> String {
+        if let Some(verbatim) = self.verbatim.as_ref() {
+            return verbatim.clone();
+        }
+        if !self.is_from {
+            return format!("import {}", self.names[0]);
+        }
+        let line = format!("from {} import {}", self.module, self.names.join(", "));
+        if line.len() <= MAX_LINE_LENGTH || self.names.len() == 1 {
+            return line;
+        }
+        let mut res = format!("from {} import ({}", self.module, newline);
+        for name in self.names.iter() {
+            res += &format!("    {},{}", name, newline);
+        }
+        res + ")"
+    }
+}
+
+/* Organize the first block of imports of a file:
+ - imports whose names are not used in the file are removed,
+ - 'from x import a' and 'from x import b' are merged,
+ - imports are sorted, and grouped by origin (__future__, standard library, third-party libraries, odoo, odoo addons and
+ relative imports), with a blank line between groups.
+Statements that hold comments (like '# noqa') and statements that are only imported for their side effects are kept as
+they are. Conditional imports (in try or if blocks) end the block, and are never modified.
+Organizing an organized block doesn't change anything. */
+pub struct OrganizeImportsFeature;
+
+impl OrganizeImportsFeature {
+
+    pub fn get_code_actions(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, uri: &Uri, only: Option<&Vec<CodeActionKind>>) -> Option<CodeActionResponse> {
+        if only.is_some_and(|kinds| !kinds.iter().any(|k| *k == CodeActionKind::SOURCE || *k == CodeActionKind::SOURCE_ORGANIZE_IMPORTS)) {
+            return None;
+        }
+        let file_info_ref = Borrows::try_borrow(file_info)?;
+        let ast = file_info_ref.ast.as_ref()?;
+        let source = file_info_ref.get_source()?;
+        //the imports of __init__ files are exported, they are never removed
+        let keep_unused = matches!(Borrows::try_borrow(file_symbol)?.typ(), SymType::PACKAGE(_));
+        let (range, text) = OrganizeImportsFeature::organize(&source, ast, keep_unused, &mut |module| OrganizeImportsFeature::classify(session, module))?;
+        let edit = TextEdit {
+            range: Range::new(file_info_ref.offset_to_position(range.start().to_usize()), file_info_ref.offset_to_position(range.end().to_usize())),
+            new_text: text,
+        };
+        Some(vec![CodeActionOrCommand::CodeAction(CodeAction {
+            title: S!("Organize imports"),
+            kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })])
+    }
+
+    /* Return the group of an absolute import, from the location of the module found by the import resolver */
+    fn classify(session: &mut SessionInfo, module: &str) -> ImportGroup {
+        if module == "odoo.addons" || module.starts_with("odoo.addons.") {
+            return ImportGroup::ODOO_ADDONS;
+        }
+        if module == "odoo" || module.starts_with("odoo.") {
+            return ImportGroup::ODOO;
+        }
+        let top_level = module.split('.').next().unwrap_or(module);
+        let symbols = session.sync_odoo.get_symbol(&(vec![S!(top_level)], vec![]), u32::MAX);
+        let Some(path) = symbols.first().and_then(|s| s.borrow().paths().first().cloned()) else {
+            return ImportGroup::THIRD_PARTY;
+        };
+        let is_under = |root: &String| !root.is_empty() && PathBuf::from(&path).starts_with(PathBuf::from(root).sanitize());
+        if is_under(&session.sync_odoo.stdlib_dir) {
+            return ImportGroup::STDLIB;
+        }
+        if session.sync_odoo.config.addons.iter().any(|addon| is_under(addon)) {
+            return ImportGroup::ODOO_ADDONS;
+        }
+        ImportGroup::THIRD_PARTY
+    }
+
+    /* Return the range of the block of imports in source, and its organized content, or None if it is already organized */
+    pub fn organize(source: &str, stmts: &Vec<Stmt>, keep_unused: bool, classify: &mut dyn FnMut(&str) -> ImportGroup) -> Option<(TextRange, String)> {
+        let first = stmts.iter().position(|s| matches!(s, Stmt::Import(_) | Stmt::ImportFrom(_)))?;
+        let mut last = first;
+        while last + 1 < stmts.len() && matches!(stmts[last + 1], Stmt::Import(_) | Stmt::ImportFrom(_)) {
+            let between = &source[stmts[last].end().to_usize()..stmts[last + 1].start().to_usize()];
+            let Some(line_break) = between.find('\n') else {
+                return None; //several statements on the same line
+            };
+            if !between[line_break..].trim().is_empty() {
+                break; //a comment ends the block
+            }
+            last += 1;
+        }
+        let block = &stmts[first..=last];
+        let block_start = OrganizeImportsFeature::line_start(source, stmts[first].start().to_usize());
+        let block_end = OrganizeImportsFeature::line_end(source, stmts[last].end().to_usize());
+        let used_names = AstUtils::find_used_names(&stmts[..first]).into_iter()
+            .chain(AstUtils::find_used_names(&stmts[last + 1..]))
+            .collect::<std::collections::HashSet<String>>();
+        let is_used = |name: &str| keep_unused || used_names.contains(name);
+        let mut lines: Vec<ImportLine> = vec![];
+        for stmt in block.iter() {
+            let start = OrganizeImportsFeature::line_start(source, stmt.start().to_usize());
+            let end = OrganizeImportsFeature::line_end(source, stmt.end().to_usize());
+            let lines_text = source[start..end].trim_end();
+            let has_comment = lines_text.contains('#') || lines_text.trim() != source[stmt.range()].trim();
+            match stmt {
+                Stmt::Import(import) => {
+                    if has_comment {
+                        let module = import.names[0].name.to_string();
+                        lines.push(ImportLine { group: classify(&module), module, is_from: false, names: vec![], verbatim: Some(lines_text.to_string()) });
+                        continue;
+                    }
+                    for alias in import.names.iter() {
+                        let binding = match alias.asname.as_ref() {
+                            Some(asname) => asname.to_string(),
+                            None => alias.name.as_str().split('.').next().unwrap_or("").to_string(),
+                        };
+                        if !is_used(&binding) {
+                            continue;
+                        }
+                        let module = alias.name.to_string();
+                        lines.push(ImportLine { group: classify(&module), module, is_from: false, names: vec![OrganizeImportsFeature::alias_text(alias)], verbatim: None });
+                    }
+                },
+                Stmt::ImportFrom(import) => {
+                    let module = ".".repeat(import.level as usize) + import.module.as_ref().map(|m| m.as_str()).unwrap_or("");
+                    let group = if import.level > 0 {
+                        ImportGroup::LOCAL
+                    } else if module == "__future__" {
+                        ImportGroup::FUTURE
+                    } else {
+                        classify(&module)
+                    };
+                    if has_comment {
+                        lines.push(ImportLine { group, module, is_from: true, names: vec![], verbatim: Some(lines_text.to_string()) });
+                        continue;
+                    }
+                    //'from . import x' loads the submodule x, and __future__ imports change the behaviour of the file
+                    let side_effect = (import.level > 0 && import.module.is_none()) || group == ImportGroup::FUTURE;
+                    let names: Vec<String> = import.names.iter()
+                        .filter(|alias| side_effect || alias.name.as_str() == "*" || is_used(alias.asname.as_ref().unwrap_or(&alias.name).as_str()))
+                        .map(|alias| OrganizeImportsFeature::alias_text(alias))
+                        .collect();
+                    if !names.is_empty() {
+                        lines.push(ImportLine { group, module, is_from: true, names, verbatim: None });
+                    }
+                },
+                _ => {}
+            }
+        }
+        let lines = OrganizeImportsFeature::merge(lines);
+        let newline = if source.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut text = String::new();
+        let mut previous_group = None;
+        for line in lines.iter() {
+            if previous_group.is_some_and(|g| g != line.group) {
+                text += newline;
+            }
+            text += &line.render(newline);
+            text += newline;
+            previous_group = Some(line.group);
+        }
+        let range = TextRange::new(TextSize::new(block_start as u32), TextSize::new(block_end as u32));
+        let mut current = source[block_start..block_end].to_string();
+        if !current.ends_with('\n') {
+            current += newline; //the file ends with the block
+        }
+        if current == text {
+            return None;
+        }
+        Some((range, text))
+    }
+
+    /* Merge the from imports of the same module, remove duplicates and sort the lines */
+    fn merge(lines: Vec<ImportLine>) -> Vec<ImportLine> {
+        let mut res: Vec<ImportLine> = vec![];
+        for line in lines.into_iter() {
+            let existing = res.iter_mut().find(|l| l.module == line.module && l.is_from == line.is_from && l.verbatim.is_none()
+                && ((l.can_merge() && line.can_merge()) || (!l.is_from && l.names == line.names)));
+            match existing {
+                Some(existing) => {
+                    for name in line.names.into_iter() {
+                        if !existing.names.contains(&name) {
+                            existing.names.push(name);
+                        }
+                    }
+                },
+                None => res.push(line),
+            }
+        }
+        for line in res.iter_mut() {
+            if line.can_merge() {
+                line.names.sort_by_key(|n| (n.to_lowercase(), n.clone()));
+            }
+        }
+        res.sort_by_key(|l| (l.group, l.module.to_lowercase(), l.is_from, l.names.first().cloned().unwrap_or_default(), l.verbatim.clone()));
+        res
+    }
+
+    fn alias_text(alias: &Alias) -> String {
+        match alias.asname.as_ref() {
+            Some(asname) => format!("{} as {}", alias.name, asname),
+            None => alias.name.to_string(),
+        }
+    }
+
+    fn line_start(source: &str, offset: usize) -> usize {
+        source[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0)
+    }
+
+    /* Return the offset after the end of line of offset, including the line break */
+    fn line_end(source: &str, offset: usize) -> usize {
+        source[offset..].find('\n').map(|i| offset + i + 1).unwrap_or(source.len())
+    }
+}
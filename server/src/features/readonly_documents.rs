@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use lsp_types::{Position, Range, Uri};
+use ruff_text_size::TextRange;
+use serde::Serialize;
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::utils::{path_to_uri, uri_to_path};
+
+/* Scheme of the documents generated by the server, that the extension opens in a read-only editor */
+pub const READONLY_SCHEME: &str = "odools-readonly";
+/* Request returning the content of a document of the READONLY_SCHEME */
+pub const READONLY_DOCUMENT_METHOD: &str = "odooLs/readonlyDocument";
+
+/* A document generated by the server */
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum ReadonlyTarget {
+    COMPILED(Vec<String>), //the tree of a compiled module, whose members are listed like a stub
+    SOURCE(String), //the path of a file that is not on the disk anymore, served from the cache of the server
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadonlyDocument {
+    pub uri: Uri,
+    pub language_id: String,
+    pub content: String,
+}
+
+/* A member of a compiled module: only the names used by the analyzed code are known */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledMember {
+    pub name: String,
+    pub members: Vec<CompiledMember>,
+}
+
+/* The generated documents, by uri. They are dropped when a new epoch starts, as the symbols they are built from may
+have changed */
+#[derive(Debug, Default)]
+pub struct ReadonlyDocuments {
+    epoch: u64,
+    documents: HashMap<String, (String, Vec<(Vec<String>, Range)>)>, //the content and the ranges of the members of the document
+}
+
+/* Goto definition returns a file uri for the definitions on the disk. The definitions without a readable source get
+a uri of the READONLY_SCHEME, whose content is asked by the extension with READONLY_DOCUMENT_METHOD: the compiled modules
+(.so, .pyd) are shown as a stub listing their known members, and the files that have been moved or deleted since they were
+loaded are shown as the server read them, so the ranges of their symbols are still valid */
+pub struct ReadonlyDocumentFeature {}
+
+impl ReadonlyDocumentFeature {
+
+    /* The documents of the READONLY_SCHEME can't be edited and are not analyzed: their events are ignored */
+    pub fn is_readonly_uri(uri: &str) -> bool {
+        uri.starts_with(&format!("{}:", READONLY_SCHEME))
+    }
+
+    pub fn get_uri(target: &ReadonlyTarget) -> Option<Uri> {
+        let uri = match target {
+            ReadonlyTarget::COMPILED(tree) => format!("{}:/compiled/{}.pyi", READONLY_SCHEME, tree.join("/")),
+            ReadonlyTarget::SOURCE(path) => {
+                let file_uri = path_to_uri(path, cfg!(windows));
+                let encoded_path = file_uri.trim_start_matches("file:").trim_start_matches('/');
+                format!("{}:/source/{}", READONLY_SCHEME, encoded_path)
+            }
+        };
+        Uri::from_str(&uri).ok()
+    }
+
+    pub fn parse_uri(uri: &str) -> Option<ReadonlyTarget> {
+        let path = uri.strip_prefix(READONLY_SCHEME)?.strip_prefix(":/")?;
+        if let Some(compiled) = path.strip_prefix("compiled/") {
+            let tree: Vec<String> = compiled.strip_suffix(".pyi")?.split('/').map(|name| name.to_string()).collect();
+            if tree.iter().any(|name| name.is_empty()) {
+                return None;
+            }
+            return Some(ReadonlyTarget::COMPILED(tree));
+        }
+        let source = path.strip_prefix("source/")?;
+        uri_to_path(&format!("file:///{}", source), cfg!(windows)).map(ReadonlyTarget::SOURCE)
+    }
+
+    /* Return the stub of a compiled module, and the range of the name of each member, given by its path from the module */
+    pub fn render_compiled(module_name: &str, binary_path: &str, members: &Vec<CompiledMember>) -> (String, Vec<(Vec<String>, Range)>) {
+        let mut lines = vec![
+            format!("# {} is a compiled module: its source is not available.", module_name),
+        ];
+        if !binary_path.is_empty() {
+            lines.push(format!("# Binary: {}", binary_path));
+        }
+        lines.push(String::from("# Only the names used by the analyzed code are listed, with unknown types."));
+        lines.push(String::from("from typing import Any"));
+        let mut ranges = vec![];
+        ReadonlyDocumentFeature::render_members(members, &mut vec![], &mut lines, &mut ranges);
+        (lines.join("\n") + "\n", ranges)
+    }
+
+    fn render_members(members: &Vec<CompiledMember>, path: &mut Vec<String>, lines: &mut Vec<String>, ranges: &mut Vec<(Vec<String>, Range)>) {
+        let mut sorted: Vec<&CompiledMember> = members.iter().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        let indent = 4 * path.len() as u32;
+        for member in sorted.into_iter() {
+            if path.is_empty() || !member.members.is_empty() {
+                lines.push(String::new());
+            }
+            let (prefix, suffix) = match member.members.is_empty() {
+                true => ("", ": Any"),
+                false => ("class ", ":"),
+            };
+            lines.push(format!("{}{}{}{}", " ".repeat(indent as usize), prefix, member.name, suffix));
+            let line = lines.len() as u32 - 1;
+            let start = indent + prefix.len() as u32;
+            path.push(member.name.clone());
+            ranges.push((path.clone(), Range::new(Position::new(line, start), Position::new(line, start + member.name.chars().count() as u32))));
+            ReadonlyDocumentFeature::render_members(&member.members, path, lines, ranges);
+            path.pop();
+        }
+    }
+
+    /* Return the uri and the range of a compiled symbol in the stub of its module */
+    pub fn get_compiled_location(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<(Uri, Range)> {
+        //the module is the first compiled symbol of the tree, its members are the next ones
+        let mut module = symbol.clone();
+        let mut member_path = vec![];
+        loop {
+            let parent = module.borrow().parent().and_then(|p| p.upgrade())?;
+            if parent.borrow().typ() != SymType::COMPILED {
+                break;
+            }
+            member_path.insert(0, module.borrow().name().clone());
+            module = parent;
+        }
+        let target = ReadonlyTarget::COMPILED(module.borrow().get_tree().0);
+        let uri = ReadonlyDocumentFeature::get_uri(&target)?;
+        ReadonlyDocumentFeature::get_document(session, uri.as_str())?;
+        let (_, ranges) = session.sync_odoo.readonly_documents.documents.get(uri.as_str())?;
+        let range = ranges.iter().find(|(path, _)| *path == member_path).map(|(_, range)| *range).unwrap_or_default();
+        Some((uri, range))
+    }
+
+    /* Return the uri and the range of a definition in a file that is not on the disk anymore, if the server still has
+    its content */
+    pub fn get_source_location(session: &mut SessionInfo, path: &String, range: &TextRange) -> Option<(Uri, Range)> {
+        if Path::new(path).exists() {
+            return None;
+        }
+        let file_info = Borrows::try_borrow(&session.sync_odoo.get_file_mgr())?.get_file_info(path)?;
+        let file_info = Borrows::try_borrow(&file_info)?;
+        file_info.get_rope()?;
+        let uri = ReadonlyDocumentFeature::get_uri(&ReadonlyTarget::SOURCE(path.clone()))?;
+        let range = Range::new(file_info.offset_to_position(range.start().to_usize()), file_info.offset_to_position(range.end().to_usize()));
+        Some((uri, range))
+    }
+
+    /* Return the content of a document of the READONLY_SCHEME, generated again if a new epoch started since it was */
+    pub fn get_document(session: &mut SessionInfo, uri: &str) -> Option<ReadonlyDocument> {
+        let epoch = session.sync_odoo.epoch;
+        let documents = &mut session.sync_odoo.readonly_documents;
+        if documents.epoch != epoch {
+            documents.documents.clear();
+            documents.epoch = epoch;
+        }
+        if let Some((content, _)) = documents.documents.get(uri) {
+            return Some(ReadonlyDocument { uri: Uri::from_str(uri).ok()?, language_id: String::from("python"), content: content.clone() });
+        }
+        let document = match ReadonlyDocumentFeature::parse_uri(uri)? {
+            ReadonlyTarget::COMPILED(tree) => {
+                let module = session.sync_odoo.get_symbol(&(tree.clone(), vec![]), u32::MAX).first().cloned()?;
+                if module.borrow().typ() != SymType::COMPILED {
+                    return None;
+                }
+                let members = ReadonlyDocumentFeature::get_compiled_members(&module);
+                let binary_path = module.borrow().as_compiled().path.clone();
+                ReadonlyDocumentFeature::render_compiled(&tree.join("."), &binary_path, &members)
+            },
+            ReadonlyTarget::SOURCE(path) => {
+                let file_info = Borrows::try_borrow(&session.sync_odoo.get_file_mgr())?.get_file_info(&path)?;
+                let content = Borrows::try_borrow(&file_info)?.get_rope()?.to_string();
+                (content, vec![])
+            }
+        };
+        let content = document.0.clone();
+        session.sync_odoo.readonly_documents.documents.insert(uri.to_string(), document);
+        Some(ReadonlyDocument { uri: Uri::from_str(uri).ok()?, language_id: String::from("python"), content })
+    }
+
+    fn get_compiled_members(compiled: &Rc<RefCell<Symbol>>) -> Vec<CompiledMember> {
+        compiled.borrow().as_compiled().module_symbols.values().map(|member| CompiledMember {
+            name: member.borrow().name().clone(),
+            members: match member.borrow().typ() {
+                SymType::COMPILED => ReadonlyDocumentFeature::get_compiled_members(member),
+                _ => vec![],
+            },
+        }).collect()
+    }
+}
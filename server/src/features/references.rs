@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use lsp_types::{Location, Range};
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, ExprCall, ExprStringLiteral, Identifier, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::core::borrows::Borrows;
+use crate::constants::{BuildStatus, BuildSteps, SymType};
+use crate::core::evaluation::{Context, ContextValue, Evaluation, ExprOrIdent};
+use crate::core::field_name_args::FieldNameArgs;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::python_string::StringSourceMap;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::features::definition::DefinitionFeature;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* A node of the ast that can refer to the searched symbol: a name, the attribute of an expression, a name imported
+by a 'from ... import' statement, or a field name given as a string to an ORM method (see FieldNameArgs), with the
+receiver of the method and the range of the name in the value of the string. The range is the one of the name in the
+source */
+enum Candidate<'a> {
+    Name(&'a Expr),
+    Attribute(&'a Expr),
+    Alias(&'a Identifier, TextRange),
+    FieldName(&'a Expr, &'a ExprStringLiteral, std::ops::Range<usize>),
+}
+
+pub struct ReferencesFeature {}
+
+impl ReferencesFeature {
+
+    /* Return the places where the symbol under the cursor is used. Only the file of the symbol and the files depending
+    on it, directly or not, can use it: they are the only ones evaluated. A name imported from another file is a
+    reference to the symbol it imports */
+    pub fn get_references(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32, include_declaration: bool) -> Option<Vec<Location>> {
+        let offset = Borrows::try_borrow(file_info)?.position_to_offset(line, character) as u32;
+        let target = ReferencesFeature::get_target(session, file_symbol, file_info, offset)?;
+        let declaration = ReferencesFeature::get_declaration(session, &target).and_then(|(file_info, range)| Borrows::try_borrow(&file_info).map(|file_info| ReferencesFeature::to_location(&file_info, &range)));
+        let mut locations: Vec<Location> = vec![];
+        for (file_info, ranges) in ReferencesFeature::get_reference_ranges(session, &target).iter() {
+            let Some(file_info) = Borrows::try_borrow(file_info) else {
+                continue;
+            };
+            for range in ranges.iter() {
+                let location = ReferencesFeature::to_location(&file_info, range);
+                if !locations.contains(&location) {
+                    locations.push(location);
+                }
+            }
+        }
+        //the name of the declaration is found when it is assigned, but not when it is defined by 'def' or 'class'
+        match declaration {
+            Some(declaration) if include_declaration => {
+                if !locations.contains(&declaration) {
+                    locations.insert(0, declaration);
+                }
+            },
+            Some(declaration) => locations.retain(|location| *location != declaration),
+            None => {}
+        }
+        Some(locations)
+    }
+
+    /* Return the symbol whose references are searched from the offset: the class, the function or the variable under
+    the cursor, the imported names being followed to the symbols they import */
+    pub fn get_target(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Option<Rc<RefCell<Symbol>>> {
+        let evaluations = AstUtils::get_symbols(session, file_symbol, file_info, offset).0.evaluations;
+        ReferencesFeature::resolve(session, &evaluations).into_iter()
+            .find(|symbol| matches!(symbol.borrow().typ(), SymType::CLASS | SymType::FUNCTION | SymType::VARIABLE))
+    }
+
+    /* Return the ranges of the names referring to the target, grouped by file. The search stops when the request is
+    cancelled */
+    pub fn get_reference_ranges(session: &mut SessionInfo, target: &Rc<RefCell<Symbol>>) -> Vec<(Rc<RefCell<FileInfo>>, Vec<TextRange>)> {
+        let Some(target_file) = target.borrow().get_file().and_then(|file| file.upgrade()) else {
+            return vec![];
+        };
+        let mut res = vec![];
+        for file in ReferencesFeature::get_dependent_files(&target_file).iter() {
+            //the request is answered with an error, the ranges found so far are not used
+            if session.is_cancelled() {
+                break;
+            }
+            let Some(file_info) = FileMgr::get_file_info_of_symbol(session, file) else {
+                continue;
+            };
+            let ranges = {
+                let Some(file_info_bw) = Borrows::try_borrow(&file_info) else {
+                    continue;
+                };
+                let Some(ast) = file_info_bw.ast.as_ref() else {
+                    continue;
+                };
+                ReferencesFeature::find_references(session, file, &file_info_bw, ast, target)
+            };
+            if !ranges.is_empty() {
+                res.push((file_info, ranges));
+            }
+        }
+        res
+    }
+
+    fn to_location(file_info: &FileInfo, range: &TextRange) -> Location {
+        Location {
+            uri: FileMgr::pathname2uri(&file_info.uri),
+            range: Range {
+                start: file_info.offset_to_position(range.start().to_usize()),
+                end: file_info.offset_to_position(range.end().to_usize()),
+            }
+        }
+    }
+
+    /* Return the symbols given by the evaluations. The imported names are followed to the symbols they import */
+    fn resolve(session: &mut SessionInfo, evaluations: &[Evaluation]) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res: Vec<Rc<RefCell<Symbol>>> = vec![];
+        let mut to_resolve: VecDeque<Rc<RefCell<Symbol>>> = VecDeque::new();
+        for evaluation in evaluations.iter() {
+            to_resolve.extend(evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade());
+        }
+        let mut visited: Vec<Rc<RefCell<Symbol>>> = vec![];
+        while let Some(symbol) = to_resolve.pop_front() {
+            if visited.iter().any(|s| Rc::ptr_eq(s, &symbol)) {
+                continue;
+            }
+            visited.push(symbol.clone());
+            let is_import = symbol.borrow().typ() == SymType::VARIABLE && symbol.borrow().as_variable().is_import_variable;
+            if !is_import {
+                res.push(symbol);
+                continue;
+            }
+            let imported = symbol.borrow().evaluations().cloned().unwrap_or_default();
+            for evaluation in imported.iter() {
+                to_resolve.extend(evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade());
+            }
+        }
+        res
+    }
+
+    /* Return the files of the workspace that can use the symbols of the file: the file itself, and the files that depend
+    on it through the imports, directly or not. The files that are being built are skipped. The dependents are sets of
+    pointers, so the files are sorted by path to give the same order after a rebuild */
+    fn get_dependent_files(file: &Rc<RefCell<Symbol>>) -> Vec<Rc<RefCell<Symbol>>> {
+        let mut res: Vec<Rc<RefCell<Symbol>>> = vec![];
+        let mut visited: HashSet<*const RefCell<Symbol>> = HashSet::new();
+        let mut to_visit: VecDeque<Rc<RefCell<Symbol>>> = VecDeque::from([file.clone()]);
+        while let Some(current) = to_visit.pop_front() {
+            if !visited.insert(Rc::as_ptr(&current)) || !matches!(current.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+                continue;
+            }
+            for steps in current.borrow().dependents().iter() {
+                for dependents in steps.iter() {
+                    to_visit.extend(dependents.iter());
+                }
+            }
+            let searched = current.borrow().in_workspace() && current.borrow().build_status(BuildSteps::ARCH_EVAL) == BuildStatus::DONE;
+            if searched {
+                res.push(current);
+            }
+        }
+        res.sort_by_key(|file| file.borrow().paths().into_iter().next().unwrap_or_default());
+        res
+    }
+
+    /* Return the ranges of the names of the file that refer to the target. Only the nodes with the name of the target,
+    or with a name it is imported as, are evaluated */
+    fn find_references(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, file_info: &FileInfo, ast: &[Stmt], target: &Rc<RefCell<Symbol>>) -> Vec<TextRange> {
+        let name = target.borrow().name().clone();
+        let mut visitor = CandidateFinderVisitor {
+            names: HashSet::from([name.clone()]),
+            attribute: name,
+            candidates: vec![],
+        };
+        for stmt in ast.iter() {
+            visitor.visit_stmt(stmt);
+        }
+        let from_module = match file.borrow().find_module() {
+            Some(module) => ContextValue::MODULE(Rc::downgrade(&module)),
+            None => ContextValue::BOOLEAN(false)
+        };
+        let mut res = vec![];
+        for candidate in visitor.candidates.iter() {
+            let (expr, range) = match candidate {
+                Candidate::Name(expr) => (ExprOrIdent::Expr(*expr), expr.range()),
+                Candidate::Attribute(expr) => (ExprOrIdent::Expr(*expr), expr.as_attribute_expr().unwrap().attr.range()),
+                Candidate::Alias(ident, range) => (ExprOrIdent::Ident(*ident), *range),
+                Candidate::FieldName(receiver, literal, value_range) => {
+                    res.extend(ReferencesFeature::find_field_name(session, file, file_info, receiver, literal, value_range.clone(), target));
+                    continue;
+                },
+            };
+            let scope = Symbol::get_scope_symbol(file.clone(), expr.range().start().to_u32(), false);
+            let mut context: Option<Context> = Some(HashMap::from([
+                (S!("module"), from_module.clone()),
+                (S!("range"), ContextValue::RANGE(expr.range()))
+            ]));
+            let evaluations = Evaluation::analyze_ast(session, &expr, scope, &expr.range().end(), &mut context).evaluations;
+            if ReferencesFeature::resolve(session, &evaluations).iter().any(|symbol| Rc::ptr_eq(symbol, target)) {
+                res.push(range);
+            }
+        }
+        res
+    }
+
+    /* Return the source range of a field name given as a string to an ORM method, if it names the target in the model
+    of the receiver */
+    fn find_field_name(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, file_info: &FileInfo, receiver: &Expr, literal: &ExprStringLiteral, value_range: std::ops::Range<usize>, target: &Rc<RefCell<Symbol>>) -> Option<TextRange> {
+        //a name whose source differs from its value, like an escape sequence, can't be renamed
+        let range = file_info.get_text(&literal.range)
+            .map(|source| StringSourceMap::from_string_literal(&source, literal.range.start(), literal))
+            .filter(|map| map.value() == literal.value.to_str())
+            .and_then(|map| map.source_range(value_range))?;
+        let name = target.borrow().name().clone();
+        let from_module = file.borrow().find_module();
+        let scope = Symbol::get_scope_symbol(file.clone(), receiver.range().start().to_u32(), false);
+        let (evaluations, _) = Evaluation::eval_from_ast(session, receiver, scope, &receiver.range().end());
+        for evaluation in evaluations.iter() {
+            let eval_symbol = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+            for eval_weak in Symbol::follow_ref(&eval_symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                let Some(model) = eval_weak.weak.upgrade() else {
+                    continue;
+                };
+                if model.borrow().typ() != SymType::CLASS || model.borrow().as_class_sym()._model.is_none() {
+                    continue;
+                }
+                let (fields, _) = model.borrow().get_member_symbol(session, &name, from_module.clone(), false, true, true, false);
+                if fields.iter().any(|field| Rc::ptr_eq(field, target)) {
+                    return Some(range);
+                }
+            }
+        }
+        None
+    }
+
+    /* Return the file of the declaration of the target, and the range of its name */
+    pub fn get_declaration(session: &mut SessionInfo, target: &Rc<RefCell<Symbol>>) -> Option<(Rc<RefCell<FileInfo>>, TextRange)> {
+        let file_info = FileMgr::get_file_info_of_symbol(session, target)?;
+        let name_range = DefinitionFeature::get_name_range(session, target).unwrap_or(*target.borrow().range());
+        Some((file_info, name_range))
+    }
+}
+
+/* Collect the nodes of the ast that can refer to a symbol of the given name. The names it is imported as by
+'from ... import name as other' are added to the searched names */
+struct CandidateFinderVisitor<'a> {
+    names: HashSet<String>,
+    attribute: String,
+    candidates: Vec<Candidate<'a>>,
+}
+
+impl<'a> Visitor<'a> for CandidateFinderVisitor<'a> {
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let Stmt::ImportFrom(import_from) = stmt {
+            for alias in import_from.names.iter() {
+                if alias.name.as_str() != self.attribute {
+                    continue;
+                }
+                //the name is evaluated where it is declared: the alias if any
+                let ident = alias.asname.as_ref().unwrap_or(&alias.name);
+                self.names.insert(ident.id.to_string());
+                self.candidates.push(Candidate::Alias(ident, alias.name.range()));
+            }
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(name) if self.names.contains(name.id.as_str()) => self.candidates.push(Candidate::Name(expr)),
+            Expr::Attribute(attribute) if attribute.attr.as_str() == self.attribute => self.candidates.push(Candidate::Attribute(expr)),
+            Expr::Call(call) => self.add_field_names(call),
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+impl<'a> CandidateFinderVisitor<'a> {
+
+    fn add_field_names(&mut self, call: &'a ExprCall) {
+        let Expr::Attribute(func) = &*call.func else {
+            return;
+        };
+        for (field_arg, value) in FieldNameArgs::get_arguments(call) {
+            for literal in FieldNameArgs::get_string_literals(value) {
+                for (name, range) in FieldNameArgs::get_field_names(literal.value.to_str(), field_arg.format) {
+                    if name == self.attribute {
+                        self.candidates.push(Candidate::FieldName(&func.value, literal, range));
+                    }
+                }
+            }
+        }
+    }
+}
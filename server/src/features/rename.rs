@@ -0,0 +1,114 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use lsp_types::{PrepareRenameResponse, Range, TextEdit, WorkspaceEdit};
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::file_mgr::{FileInfo, FileMgr};
+use crate::core::symbols::symbol::Symbol;
+use crate::features::definition::DefinitionFeature;
+use crate::features::references::ReferencesFeature;
+use crate::threads::SessionInfo;
+
+/* The keywords of Python, that can't be used as names */
+const KEYWORDS: [&str; 35] = ["False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield"];
+
+/* Answer of the renames meeting a file that is already borrowed by the server */
+const BUSY_MESSAGE: &str = "The server is busy, retry the rename";
+
+pub struct RenameFeature {}
+
+impl RenameFeature {
+
+    /* Return the range of the name under the cursor if its symbol can be renamed, or the reason why it can't be */
+    pub fn prepare_rename(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Result<PrepareRenameResponse, String> {
+        let offset = Borrows::try_borrow(file_info).ok_or_else(|| String::from(BUSY_MESSAGE))?.position_to_offset(line, character) as u32;
+        RenameFeature::get_renamed_symbol(session, file_symbol, file_info, offset)?;
+        let range = DefinitionFeature::get_origin_range(file_info, offset).ok_or_else(|| String::from("No symbol to rename at this position"))?;
+        let file_info = Borrows::try_borrow(file_info).ok_or_else(|| String::from(BUSY_MESSAGE))?;
+        Ok(PrepareRenameResponse::Range(Range {
+            start: file_info.offset_to_position(range.start().to_usize()),
+            end: file_info.offset_to_position(range.end().to_usize()),
+        }))
+    }
+
+    /* Return the edits renaming the symbol under the cursor: its declaration, and the references written with its name.
+    The names it is imported as by 'from ... import name as other' are kept, only the imported name is renamed */
+    pub fn rename(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32, new_name: &str) -> Result<WorkspaceEdit, String> {
+        let offset = Borrows::try_borrow(file_info).ok_or_else(|| String::from(BUSY_MESSAGE))?.position_to_offset(line, character) as u32;
+        let target = RenameFeature::get_renamed_symbol(session, file_symbol, file_info, offset)?;
+        if !RenameFeature::is_identifier(new_name) {
+            return Err(format!("'{}' is not a valid name", new_name));
+        }
+        let old_name = target.borrow().name().clone();
+        if let Some(scope) = RenameFeature::get_collision(&target, new_name) {
+            return Err(format!("'{}' is already declared in {}", new_name, scope));
+        }
+        let mut ranges = ReferencesFeature::get_reference_ranges(session, &target);
+        if let Some((file_info, range)) = ReferencesFeature::get_declaration(session, &target) {
+            match ranges.iter_mut().find(|(other, _)| Rc::ptr_eq(other, &file_info)) {
+                Some((_, file_ranges)) => file_ranges.push(range),
+                None => ranges.push((file_info, vec![range])),
+            }
+        }
+        let mut changes: HashMap<lsp_types::Uri, Vec<TextEdit>> = HashMap::new();
+        //a file skipped because it is borrowed would leave the old name in it: the whole rename is refused
+        for (file_info, file_ranges) in ranges.iter() {
+            let file_info = Borrows::try_borrow(file_info).ok_or_else(|| String::from(BUSY_MESSAGE))?;
+            let edits = changes.entry(FileMgr::pathname2uri(&file_info.uri)).or_default();
+            for range in file_ranges.iter() {
+                if file_info.get_text(range).as_deref() != Some(old_name.as_str()) {
+                    continue;
+                }
+                let edit = TextEdit {
+                    range: Range {
+                        start: file_info.offset_to_position(range.start().to_usize()),
+                        end: file_info.offset_to_position(range.end().to_usize()),
+                    },
+                    new_text: new_name.to_string(),
+                };
+                if !edits.contains(&edit) {
+                    edits.push(edit);
+                }
+            }
+        }
+        changes.retain(|_, edits| !edits.is_empty());
+        Ok(WorkspaceEdit::new(changes))
+    }
+
+    /* Return the symbol under the cursor if it can be renamed. The symbols declared outside of the workspace can't be,
+    as their files are not edited, nor the self and cls parameters */
+    fn get_renamed_symbol(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, offset: u32) -> Result<Rc<RefCell<Symbol>>, String> {
+        let target = ReferencesFeature::get_target(session, file_symbol, file_info, offset)
+            .ok_or_else(|| String::from("No symbol to rename at this position"))?;
+        let target_bw = target.borrow();
+        let in_workspace = target_bw.get_file().and_then(|file| file.upgrade()).is_some_and(|file| file.borrow().in_workspace());
+        if !in_workspace {
+            return Err(format!("'{}' is declared outside of the workspace and can't be renamed", target_bw.name()));
+        }
+        if target_bw.typ() == SymType::VARIABLE && target_bw.as_variable().is_parameter && matches!(target_bw.name().as_str(), "self" | "cls") {
+            return Err(format!("The '{}' parameter can't be renamed", target_bw.name()));
+        }
+        drop(target_bw);
+        Ok(target)
+    }
+
+    /* Return the name of the scope of the target if the new name is already declared in the section of the target */
+    fn get_collision(target: &Rc<RefCell<Symbol>>, new_name: &str) -> Option<String> {
+        let scope = target.borrow().parent()?.upgrade()?;
+        let scope = scope.borrow();
+        let section = scope.as_symbol_mgr().get_section_for(target.borrow().range().start().to_u32()).index;
+        let declared = scope.iter_symbols().any(|(name, sections)| name == new_name && sections.get(&section).is_some_and(|symbols| !symbols.is_empty()));
+        declared.then(|| scope.name().clone())
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
+            && !KEYWORDS.contains(&name)
+    }
+}
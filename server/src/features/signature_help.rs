@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use lsp_types::{Documentation, ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation};
+use ruff_python_ast::ExprCall;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::call_binding::CallBinding;
+use crate::core::evaluation::{Context, ContextValue, Evaluation, ExprOrIdent};
+use crate::core::file_mgr::FileInfo;
+use crate::core::overloads::Overloads;
+use crate::core::symbols::function_symbol::{ArgumentType, FunctionSymbol};
+use crate::core::symbols::symbol::Symbol;
+use crate::features::ast_utils::AstUtils;
+use crate::features::hover::HoverFeature;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Characters that open the signature help of a call, or move to its next parameter */
+pub const SIGNATURE_HELP_TRIGGER_CHARACTERS: [&str; 2] = ["(", ","];
+
+/* A function called by the call under the cursor */
+struct Callee {
+    function: Rc<RefCell<Symbol>>,
+    name: String, //the name of the class for its constructor
+    is_on_instance: bool, //the first parameter is bound to the object the function is called on, and is not displayed
+}
+
+pub struct SignatureHelpFeature {}
+
+impl SignatureHelpFeature {
+
+    /* Return the signatures of the functions called by the innermost call whose arguments contain the position. Each
+    overload of a function is a signature, and the active one is the first overload accepting the arguments */
+    pub fn get_signature_help(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, file_info: &Rc<RefCell<FileInfo>>, line: u32, character: u32) -> Option<SignatureHelp> {
+        let offset = TextSize::new(Borrows::try_borrow(file_info)?.position_to_offset(line, character) as u32);
+        let call = SignatureHelpFeature::find_call(&Borrows::try_borrow(file_info)?, offset)?;
+        let mut signatures = vec![];
+        let mut active_signature = None;
+        for callee in SignatureHelpFeature::get_callees(session, file_symbol, &call, offset).iter() {
+            for overload in Overloads::get_signatures(&callee.function).iter() {
+                if active_signature.is_none() && Overloads::accepts(session, overload, &call, callee.is_on_instance) {
+                    active_signature = Some(signatures.len() as u32);
+                }
+                let overload = overload.borrow();
+                signatures.push(SignatureHelpFeature::build_signature(&callee.name, overload.as_func(), callee.is_on_instance, &call, offset));
+            }
+        }
+        if signatures.is_empty() {
+            return None;
+        }
+        Some(SignatureHelp {
+            signatures,
+            active_signature: Some(active_signature.unwrap_or(0)),
+            active_parameter: None, //given by each signature
+        })
+    }
+
+    /* Return the innermost call whose arguments contain the offset, from the opening parenthesis to the closing one. The
+    arguments of a call that is not closed yet, like while it is typed, contain the offset at their end */
+    fn find_call(file_info: &FileInfo, offset: TextSize) -> Option<ExprCall> {
+        let mut res: Option<&ExprCall> = None;
+        for stmt in file_info.ast.as_ref()?.iter() {
+            if !stmt.range().contains_inclusive(offset) {
+                continue;
+            }
+            for call in AstUtils::find_calls_in_stmt(stmt) {
+                let arguments = call.arguments.range();
+                let contains = arguments.start() < offset && (offset < arguments.end() ||
+                    (offset == arguments.end() && file_info.get_text(&TextRange::new(arguments.end() - TextSize::new(1), arguments.end())).as_deref() != Some(")")));
+                if contains && res.map_or(true, |innermost| arguments.len() < innermost.arguments.range().len()) {
+                    res = Some(call);
+                }
+            }
+        }
+        res.cloned()
+    }
+
+    /* Return the functions called by the call: the evaluated functions, the __init__ of the classes, and the __call__ of
+    the instances */
+    fn get_callees(session: &mut SessionInfo, file_symbol: &Rc<RefCell<Symbol>>, call: &ExprCall, offset: TextSize) -> Vec<Callee> {
+        let scope = Symbol::get_scope_symbol(file_symbol.clone(), offset.to_u32(), false);
+        let from_module = match Borrows::try_borrow(file_symbol).and_then(|file_symbol| file_symbol.find_module()) {
+            Some(module) => ContextValue::MODULE(Rc::downgrade(&module)),
+            None => ContextValue::BOOLEAN(false)
+        };
+        let mut context: Option<Context> = Some(HashMap::from([
+            (S!("module"), from_module),
+            (S!("range"), ContextValue::RANGE(call.func.range()))
+        ]));
+        let evaluations = Evaluation::analyze_ast(session, &ExprOrIdent::Expr(&call.func), scope, &call.func.range().end(), &mut context).evaluations;
+        let mut res: Vec<Callee> = vec![];
+        for evaluation in evaluations.iter() {
+            let is_attr_of_instance = evaluation.symbol.context.get(&S!("is_attr_of_instance")).is_some_and(|value| value.as_bool());
+            let mut evaluation_context = Some(evaluation.symbol.context.clone());
+            let evaluation_symbol = evaluation.symbol.get_symbol(session, &mut evaluation_context, &mut vec![], None);
+            for callee in Symbol::follow_ref(&evaluation_symbol, session, &mut None, true, false, None, &mut vec![]).iter() {
+                let Some(callee_sym) = callee.weak.upgrade() else {
+                    continue;
+                };
+                let typ = callee_sym.borrow().typ();
+                let (functions, name) = match typ {
+                    SymType::FUNCTION => {
+                        let function = callee_sym.borrow();
+                        let function = function.as_func();
+                        let is_on_instance = !function.is_static && (function.is_class_method || is_attr_of_instance);
+                        (vec![(callee_sym.clone(), is_on_instance)], callee_sym.borrow().name().clone())
+                    },
+                    SymType::CLASS => {
+                        let method = if callee.instance.unwrap_or(false) { "__call__" } else { "__init__" };
+                        let methods = callee_sym.borrow().get_member_symbol(session, &S!(method), None, true, false, false, false).0;
+                        let methods = methods.into_iter().filter(|method| method.borrow().typ() == SymType::FUNCTION).map(|method| (method, true)).collect();
+                        (methods, callee_sym.borrow().name().clone())
+                    },
+                    _ => continue
+                };
+                for (function, is_on_instance) in functions.into_iter() {
+                    if !res.iter().any(|existing| Rc::ptr_eq(&existing.function, &function)) {
+                        res.push(Callee { function, name: name.clone(), is_on_instance });
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /* Build the signature of the function, like 'compute(amount, rate=0.5, *values, rounding=None, **options)', with
+    the active parameter given by the argument under the cursor */
+    fn build_signature(name: &str, function: &FunctionSymbol, is_on_instance: bool, call: &ExprCall, offset: TextSize) -> SignatureInformation {
+        let mut label = format!("{}(", name);
+        let mut parameters = vec![];
+        let mut displayed_args = vec![];
+        let mut keywords_started = false; //after *args or the first keyword-only parameter
+        for (index, arg) in function.args.iter().enumerate() {
+            if index == 0 && is_on_instance && matches!(arg.arg_type, ArgumentType::POS_ONLY | ArgumentType::ARG) {
+                continue;
+            }
+            let Some(arg_sym) = arg.symbol.upgrade() else {
+                continue;
+            };
+            let after_pos_only = displayed_args.last().is_some_and(|previous: &usize| function.args[*previous].arg_type == ArgumentType::POS_ONLY);
+            if after_pos_only && arg.arg_type != ArgumentType::POS_ONLY {
+                label += ", /";
+            }
+            if arg.arg_type == ArgumentType::KWORD_ONLY && !keywords_started {
+                label += if label.ends_with('(') { "*" } else { ", *" };
+            }
+            keywords_started |= matches!(arg.arg_type, ArgumentType::VARARG | ArgumentType::KWORD_ONLY);
+            if !label.ends_with('(') {
+                label += ", ";
+            }
+            let parameter = match arg.arg_type {
+                ArgumentType::VARARG => format!("*{}", arg_sym.borrow().name()),
+                ArgumentType::KWARG => format!("**{}", arg_sym.borrow().name()),
+                _ => match &arg.default_value {
+                    Some(default_value) => format!("{}={}", arg_sym.borrow().name(), HoverFeature::build_default_value(default_value)),
+                    None => arg_sym.borrow().name().clone()
+                }
+            };
+            //the offsets of the parameter in the label are in UTF-16 code units
+            let start = label.encode_utf16().count() as u32;
+            label += &parameter;
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, start + parameter.encode_utf16().count() as u32]),
+                documentation: None,
+            });
+            displayed_args.push(index);
+        }
+        if displayed_args.last().is_some_and(|last| function.args[*last].arg_type == ArgumentType::POS_ONLY) {
+            label += ", /";
+        }
+        label += ")";
+        //an argument that can't be bound has no active parameter: the index is out of the parameters
+        let binding = CallBinding::bind_function(function, &call.arguments, is_on_instance);
+        let active_parameter = binding.active_parameter(&call.arguments, offset)
+            .and_then(|parameter| displayed_args.iter().position(|index| *index == parameter))
+            .unwrap_or(displayed_args.len());
+        SignatureInformation {
+            label,
+            documentation: function.doc_string.clone().map(Documentation::String),
+            parameters: Some(parameters),
+            active_parameter: Some(active_parameter as u32),
+        }
+    }
+}
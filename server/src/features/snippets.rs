@@ -0,0 +1,201 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat, InsertTextMode, MarkupContent, MarkupKind};
+
+use crate::constants::SymType;
+use crate::core::symbols::module_symbol::ModuleSymbol;
+use crate::core::symbols::symbol::Symbol;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Places where snippets can be inserted */
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+enum SnippetContext {
+    MODEL_BODY, //directly in the body of a class that is an odoo model
+    MODULE_LEVEL, //at the top level of a file of an odoo module
+}
+
+/* Snippets: (setting name, label, description, context) */
+const SNIPPETS: [(&str, &str, &str, SnippetContext); 3] = [
+    ("many2one", "many2one", "Many2one field", SnippetContext::MODEL_BODY),
+    ("computeField", "compute field", "Computed field and its compute method", SnippetContext::MODEL_BODY),
+    ("newModel", "new model", "New model class", SnippetContext::MODULE_LEVEL),
+];
+
+/* Field types proposed for computed fields */
+const COMPUTED_FIELD_TYPES: [&str; 9] = ["Char", "Integer", "Float", "Boolean", "Monetary", "Date", "Datetime", "Many2one", "Text"];
+
+/* Snippets are completion items that are built from the index: they are only proposed where they are valid, and their
+placeholders are filled with data of the workspace (model names, name of the current module).
+The indentation of their body is the one of the file, and the client adapts it to the indentation of the current line. */
+pub struct SnippetFeature;
+
+impl SnippetFeature {
+
+    /* Return the snippets whose name starts with prefix, that can be inserted at offset.
+    indent is the string used for one level of indentation in the file */
+    pub fn get_snippets(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, prefix: &str, offset: usize, indent: &str) -> Vec<CompletionItem> {
+        let Some(context) = SnippetFeature::get_context(file, offset) else {
+            return vec![];
+        };
+        let prefix = prefix.to_lowercase();
+        let mut items = vec![];
+        for (name, label, description, snippet_context) in SNIPPETS.iter() {
+            let filter_text = label.replace(' ', "_");
+            if *snippet_context != context || !filter_text.starts_with(&prefix) || session.sync_odoo.config.disabled_snippets.iter().any(|d| d == name) {
+                continue;
+            }
+            let text = match *name {
+                "many2one" => SnippetFeature::many2one(session, file),
+                "computeField" => SnippetFeature::compute_field(indent),
+                "newModel" => SnippetFeature::new_model(file, indent),
+                _ => continue
+            };
+            items.push(CompletionItem {
+                label: S!(*label),
+                kind: Some(CompletionItemKind::SNIPPET),
+                detail: Some(S!(*description)),
+                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!("```python\n{}\n```", SnippetFeature::preview(&text)),
+                })),
+                filter_text: Some(filter_text),
+                insert_text: Some(text),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
+                ..Default::default()
+            });
+        }
+        items
+    }
+
+    fn get_context(file: &Rc<RefCell<Symbol>>, offset: usize) -> Option<SnippetContext> {
+        let scope = Symbol::get_scope_symbol(file.clone(), offset as u32, false);
+        let scope = scope.borrow();
+        match scope.typ() {
+            SymType::CLASS if scope.as_class_sym()._model.is_some() => Some(SnippetContext::MODEL_BODY),
+            SymType::FILE | SymType::PACKAGE(_) if file.borrow().find_module().is_some() => Some(SnippetContext::MODULE_LEVEL),
+            _ => None
+        }
+    }
+
+    fn many2one(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> String {
+        let model_names = SnippetFeature::get_available_model_names(session, file);
+        let comodel = if model_names.is_empty() {
+            S!("${2:comodel}")
+        } else {
+            format!("${{2|{}|}}", model_names.iter().map(|m| SnippetFeature::escape_choice(m)).collect::<Vec<String>>().join(","))
+        };
+        format!("${{1:field}}_id = fields.Many2one(\"{}\", string=\"${{3}}\")$0", comodel)
+    }
+
+    fn compute_field(indent: &str) -> String {
+        format!("${{1:field}} = fields.${{2|{}|}}(string=\"${{3}}\", compute=\"_compute_${{1:field}}\")\n\n\
+            @api.depends(${{4}})\n\
+            def _compute_${{1:field}}(self):\n\
+            {indent}for record in self:\n\
+            {indent}{indent}record.${{1:field}} = ${{0:False}}", COMPUTED_FIELD_TYPES.join(","), indent = indent)
+    }
+
+    fn new_model(file: &Rc<RefCell<Symbol>>, indent: &str) -> String {
+        let module_name = file.borrow().find_module().map(|m| m.borrow().as_module_package().dir_name.clone()).unwrap_or(S!("module"));
+        format!("class ${{1:ModelName}}(models.Model):\n\
+            {indent}_name = \"${{2:{module}.model}}\"\n\
+            {indent}_description = \"${{3:Description}}\"\n\
+            {indent}$0", module = SnippetFeature::escape_placeholder(&module_name), indent = indent)
+    }
+
+    /* Return the sorted names of the models that are declared in the dependencies of the module of the file.
+    If the file is not in a module, all models are returned */
+    fn get_available_model_names(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>) -> Vec<String> {
+        let current_module = file.borrow().find_module();
+        let models = session.sync_odoo.models.clone();
+        let mut res = vec![];
+        for (model_name, model) in models.iter() {
+            if model_name == "_unknown" {
+                continue;
+            }
+            if let Some(current_module) = current_module.as_ref() {
+                let main_symbols = model.borrow().get_main_symbols(session, None, &mut None);
+                let in_deps = main_symbols.iter().filter_map(|s| s.borrow().find_module()).any(|module|
+                    ModuleSymbol::is_in_deps(session, current_module, &module.borrow().as_module_package().dir_name, &mut None));
+                if !in_deps {
+                    continue;
+                }
+            }
+            res.push(model_name.clone());
+        }
+        res.sort();
+        res
+    }
+
+    fn escape_choice(value: &str) -> String {
+        value.replace('\\', "\\\\").replace(',', "\\,").replace('|', "\\|").replace('$', "\\$").replace('}', "\\}")
+    }
+
+    fn escape_placeholder(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('$', "\\$").replace('}', "\\}")
+    }
+
+    /* Return the text of a snippet with the default values of its placeholders, to be shown as documentation */
+    fn preview(snippet: &str) -> String {
+        let mut res = String::new();
+        let mut chars = snippet.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        res.push(escaped);
+                    }
+                },
+                '$' => {
+                    if chars.peek() == Some(&'{') {
+                        chars.next();
+                        let content = SnippetFeature::read_placeholder(&mut chars);
+                        let default = match content.find(|c| c == ':' || c == '|') {
+                            Some(index) if content[index..].starts_with('|') => content[index + 1..].split(',').next().unwrap_or("").to_string(),
+                            Some(index) => SnippetFeature::preview(&content[index + 1..]),
+                            None => String::new(),
+                        };
+                        res += &default;
+                    } else {
+                        while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                            chars.next();
+                        }
+                    }
+                },
+                _ => res.push(c),
+            }
+        }
+        res
+    }
+
+    /* Read the content of a placeholder until its closing brace, handling nested placeholders */
+    fn read_placeholder(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+        let mut content = String::new();
+        let mut depth = 1;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    content.push(c);
+                    if let Some(escaped) = chars.next() {
+                        content.push(escaped);
+                    }
+                    continue;
+                },
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                _ => {}
+            }
+            content.push(c);
+        }
+        content
+    }
+}
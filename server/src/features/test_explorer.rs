@@ -0,0 +1,236 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use lsp_types::{Range, Uri};
+use ruff_python_ast::{Decorator, Expr, Stmt};
+use serde::{Deserialize, Serialize};
+
+use crate::core::borrows::Borrows;
+use crate::constants::SymType;
+use crate::core::file_mgr::FileMgr;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::definition::DefinitionFeature;
+use crate::threads::SessionInfo;
+use crate::S;
+
+/* Request listing the Odoo tests of a module, or of all the modules */
+pub const TEST_LIST_METHOD: &str = "odooLs/tests/list";
+
+/* The tags of the tests without any tagged decorator, given by odoo.tests.common.BaseCase */
+pub const DEFAULT_TEST_TAGS: [&str; 2] = ["at_install", "standard"];
+
+/* The test cases of odoo.tests.common, the most specific first */
+const TEST_CASES: [&str; 4] = ["HttpCase", "SavepointCase", "SingleTransactionCase", "TransactionCase"];
+
+/* Maximum depth of the bases followed to find the inherited tags */
+const MAX_TAG_DEPTH: u32 = 20;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestListParams {
+    pub module: Option<String>, //the name of the module, or None for all the modules
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestMethod {
+    pub name: String,
+    pub uri: Uri,
+    pub range: Range, //range of the name of the method
+    pub inherited: bool, //the method is defined by a base class of the test class
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestClass {
+    pub name: String,
+    pub uri: Uri,
+    pub range: Range, //range of the name of the class
+    pub case: String, //the test case of odoo.tests.common it inherits: TransactionCase, HttpCase...
+    pub tags: Vec<String>, //the tags of the tagged decorator of the class, with the removed tags starting with '-'
+    pub effective_tags: Vec<String>, //the tags of the class, with the tags inherited from its bases
+    pub methods: Vec<TestMethod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestModule {
+    pub name: String,
+    pub path: String,
+    pub classes: Vec<TestClass>,
+}
+
+/* Response of odooLs/tests/list */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestList {
+    pub modules: Vec<TestModule>,
+}
+
+/* The tests of a module are the classes of its tests package that inherit a test case of odoo.tests.common, directly or
+through the bases defined by the modules, and their methods starting with test_, inherited ones included, like the test
+loader of Odoo. The list is built from the symbols on each request, so it follows the rebuilds of the test files */
+pub struct TestExplorerFeature {}
+
+impl TestExplorerFeature {
+
+    /* Return the tags given to the tagged decorator: @tagged("post_install", "-at_install"). The tags that are not literal
+    are ignored */
+    pub fn get_tags(decorators: &[Decorator]) -> Option<Vec<String>> {
+        decorators.iter().find_map(|decorator| {
+            let Expr::Call(call) = &decorator.expression else {
+                return None;
+            };
+            let is_tagged = match &*call.func {
+                Expr::Name(name) => name.id.as_str() == "tagged",
+                Expr::Attribute(attr) => attr.attr.as_str() == "tagged",
+                _ => false
+            };
+            if !is_tagged {
+                return None;
+            }
+            Some(call.arguments.args.iter().filter_map(|arg| arg.as_string_literal_expr()).map(|s| s.value.to_string()).collect())
+        })
+    }
+
+    /* Apply the tags of a tagged decorator to the tags inherited by the class: the tags are added, and the tags starting
+    with '-' are removed */
+    pub fn apply_tags(inherited: &BTreeSet<String>, tags: &[String]) -> BTreeSet<String> {
+        let mut res = inherited.clone();
+        for tag in tags.iter().filter(|tag| !tag.starts_with('-')) {
+            res.insert(tag.clone());
+        }
+        for tag in tags.iter().filter_map(|tag| tag.strip_prefix('-')) {
+            res.remove(tag);
+        }
+        res
+    }
+
+    /* Return the tests of the given module, or of all the modules of the workspace */
+    pub fn list(session: &mut SessionInfo, module_name: Option<&String>) -> TestList {
+        let mut modules: Vec<Rc<RefCell<Symbol>>> = session.sync_odoo.modules.iter()
+            .filter(|(name, _)| match module_name {
+                Some(module_name) => *name == module_name,
+                None => true
+            })
+            .filter_map(|(_, module)| module.upgrade())
+            .filter(|module| module_name.is_some() || module.borrow().in_workspace())
+            .collect();
+        modules.sort_by_key(|module| module.borrow().as_module_package().dir_name.clone());
+        let cases: Vec<(String, Rc<RefCell<Symbol>>)> = TEST_CASES.iter().filter_map(|case| session.sync_odoo
+            .get_symbol(&(vec![S!("odoo"), S!("tests"), S!("common")], vec![case.to_string()]), u32::MAX).last()
+            .map(|symbol| (case.to_string(), symbol.clone()))).collect();
+        let mut res = vec![];
+        for module in modules.iter() {
+            let classes = TestExplorerFeature::get_module_tests(session, module, &cases);
+            if classes.is_empty() {
+                continue;
+            }
+            let module = module.borrow();
+            let module = module.as_module_package();
+            res.push(TestModule { name: module.dir_name.clone(), path: module.root_path.clone(), classes });
+        }
+        TestList { modules: res }
+    }
+
+    fn get_module_tests(session: &mut SessionInfo, module: &Rc<RefCell<Symbol>>, cases: &[(String, Rc<RefCell<Symbol>>)]) -> Vec<TestClass> {
+        let Some(tests) = module.borrow().as_module_package().module_symbols.get("tests").cloned() else {
+            return vec![];
+        };
+        let mut files = vec![];
+        let mut stack = vec![tests];
+        while let Some(symbol) = stack.pop() {
+            match symbol.borrow().typ() {
+                SymType::FILE => files.push(symbol.clone()),
+                SymType::PACKAGE(_) => stack.extend(symbol.borrow().all_module_symbol().cloned()),
+                _ => {}
+            }
+        }
+        let mut res = vec![];
+        for file in files.iter() {
+            let classes = file.borrow().iter_classes();
+            for class in classes.iter() {
+                if let Some(test_class) = TestExplorerFeature::get_test_class(session, class, cases) {
+                    res.push(test_class);
+                }
+            }
+        }
+        res.sort_by(|a, b| (a.uri.as_str(), a.range.start.line).cmp(&(b.uri.as_str(), b.range.start.line)));
+        res
+    }
+
+    fn get_test_class(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, cases: &[(String, Rc<RefCell<Symbol>>)]) -> Option<TestClass> {
+        let case = cases.iter().find(|(_, case)| class.borrow().as_class_sym().inherits(case, &mut None))?.0.clone();
+        let mut methods: Vec<TestMethod> = vec![];
+        let mut classes = vec![(class.clone(), false)];
+        let mut visited = vec![];
+        while let Some((current, inherited)) = classes.pop() {
+            if visited.iter().any(|v| Rc::ptr_eq(v, &current)) || cases.iter().any(|(_, case)| Rc::ptr_eq(case, &current)) {
+                continue;
+            }
+            visited.push(current.clone());
+            let mut own_methods = vec![];
+            for (name, sections) in current.borrow().as_class_sym().symbols.iter() {
+                if !name.starts_with("test_") || methods.iter().any(|m| m.name == *name) {
+                    continue;
+                }
+                //the last definition of the name is the one of the class
+                let last_section = sections.iter().max_by_key(|(section, _)| **section);
+                if let Some(method) = last_section.and_then(|(_, symbols)| symbols.iter().rfind(|s| s.borrow().typ() == SymType::FUNCTION)) {
+                    own_methods.push(method.clone());
+                }
+            }
+            for method in own_methods.iter() {
+                let (uri, range) = TestExplorerFeature::get_location(session, method)?;
+                methods.push(TestMethod { name: method.borrow().name().clone(), uri, range, inherited });
+            }
+            //the first base is the first one of the MRO, it is visited first
+            let bases: Vec<Rc<RefCell<Symbol>>> = current.borrow().as_class_sym().bases.iter().filter_map(|b| b.upgrade()).collect();
+            classes.extend(bases.into_iter().rev().map(|base| (base, true)));
+        }
+        if methods.is_empty() {
+            return None; //a common base of the tests of the module
+        }
+        methods.sort_by(|a, b| (a.inherited, a.uri.as_str(), a.range.start.line).cmp(&(b.inherited, b.uri.as_str(), b.range.start.line)));
+        let tags = TestExplorerFeature::get_class_tags(session, class).unwrap_or_default();
+        let effective_tags = TestExplorerFeature::get_effective_tags(session, class, cases, 0).into_iter().collect();
+        let (uri, range) = TestExplorerFeature::get_location(session, class)?;
+        Some(TestClass { name: class.borrow().name().clone(), uri, range, case, tags, effective_tags, methods })
+    }
+
+    /* Return the tags of the class, from the tags of its first base that is a test */
+    fn get_effective_tags(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>, cases: &[(String, Rc<RefCell<Symbol>>)], depth: u32) -> BTreeSet<String> {
+        let base = class.borrow().as_class_sym().bases.iter().filter_map(|b| b.upgrade())
+            .find(|base| cases.iter().any(|(_, case)| Rc::ptr_eq(case, base) || base.borrow().as_class_sym().inherits(case, &mut None)));
+        let inherited = match base {
+            Some(base) if depth < MAX_TAG_DEPTH && !cases.iter().any(|(_, case)| Rc::ptr_eq(case, &base)) => {
+                TestExplorerFeature::get_effective_tags(session, &base, cases, depth + 1)
+            },
+            _ => DEFAULT_TEST_TAGS.iter().map(|tag| tag.to_string()).collect()
+        };
+        match TestExplorerFeature::get_class_tags(session, class) {
+            Some(tags) => TestExplorerFeature::apply_tags(&inherited, &tags),
+            None => inherited
+        }
+    }
+
+    /* Return the tags of the tagged decorator of the class, read from the ast of its file */
+    fn get_class_tags(session: &mut SessionInfo, class: &Rc<RefCell<Symbol>>) -> Option<Vec<String>> {
+        let file_info = FileMgr::get_file_info_of_symbol(session, class)?;
+        let class_range = *class.borrow().range();
+        let file_info = Borrows::try_borrow(&file_info)?;
+        file_info.ast.iter().flatten().find_map(|stmt| match stmt {
+            Stmt::ClassDef(c) if c.range == class_range => Some(TestExplorerFeature::get_tags(&c.decorator_list)),
+            _ => None
+        }).flatten()
+    }
+
+    /* Return the uri of the file of the class or the method, and the range of its name */
+    fn get_location(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<(Uri, Range)> {
+        let file = symbol.borrow().get_file()?.upgrade()?;
+        let path = file.borrow().paths().first()?.clone();
+        let name_range = DefinitionFeature::get_name_range(session, symbol).unwrap_or(*symbol.borrow().range());
+        let range = Borrows::try_borrow_mut(&session.sync_odoo.get_file_mgr())?.text_range_to_range(session, &path, &name_range);
+        Some((FileMgr::pathname2uri(&path), range))
+    }
+}
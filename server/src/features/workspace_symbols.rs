@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{Location, SymbolInformation, SymbolKind};
+
+use crate::core::borrows::Borrows;
+use crate::constants::{flatten_tree, BuildStatus, BuildSteps, SymType};
+use crate::core::file_mgr::FileMgr;
+use crate::core::symbols::symbol::Symbol;
+use crate::features::definition::DefinitionFeature;
+use crate::features::document_symbols::DocumentSymbolFeature;
+use crate::threads::SessionInfo;
+use crate::utils::PathSanitizer as _;
+
+/* Maximum number of symbols returned by a workspace/symbol request */
+pub const MAX_WORKSPACE_SYMBOLS: usize = 200;
+
+/* How well a name matches the query of a workspace/symbol request, from the best to the worst. The case is ignored */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchQuality {
+    EXACT,
+    PREFIX,
+    SUBSTRING,
+    FUZZY, //the characters of the query are in the name, in the same order
+}
+
+pub struct WorkspaceSymbolFeature {}
+
+impl WorkspaceSymbolFeature {
+
+    /* Return the classes, functions, methods and module-level variables of the files of the workspace whose name matches
+    the query, the best matches first. The files whose architecture is being built are skipped: their symbols are
+    found again once the build is done */
+    pub fn get_workspace_symbols(session: &mut SessionInfo, query: &str) -> Vec<SymbolInformation> {
+        let mut matches: Vec<(MatchQuality, Rc<RefCell<Symbol>>)> = vec![];
+        let mut stack = vec![session.sync_odoo.symbols.clone().unwrap()];
+        while let Some(symbol) = stack.pop() {
+            let typ = symbol.borrow().typ();
+            if matches!(typ, SymType::FILE | SymType::PACKAGE(_)) && symbol.borrow().in_workspace()
+                && symbol.borrow().build_status(BuildSteps::ARCH) == BuildStatus::DONE {
+                WorkspaceSymbolFeature::add_matches(&symbol, query, &mut matches);
+            }
+            if matches!(typ, SymType::ROOT | SymType::NAMESPACE | SymType::PACKAGE(_)) {
+                stack.extend(symbol.borrow().all_module_symbol().cloned());
+            }
+        }
+        matches.sort_by(|(quality_a, symbol_a), (quality_b, symbol_b)| {
+            let (symbol_a, symbol_b) = (symbol_a.borrow(), symbol_b.borrow());
+            (quality_a, symbol_a.name().len(), symbol_a.name(), symbol_a.get_tree()).cmp(&(quality_b, symbol_b.name().len(), symbol_b.name(), symbol_b.get_tree()))
+        });
+        matches.truncate(MAX_WORKSPACE_SYMBOLS);
+        matches.iter().filter_map(|(_, symbol)| WorkspaceSymbolFeature::build_symbol_information(session, symbol)).collect()
+    }
+
+    /* Add the matching symbols declared in the file, and the members of its classes */
+    fn add_matches(file: &Rc<RefCell<Symbol>>, query: &str, matches: &mut Vec<(MatchQuality, Rc<RefCell<Symbol>>)>) {
+        let mut scopes = vec![file.clone()];
+        while let Some(scope) = scopes.pop() {
+            let in_file = Rc::ptr_eq(&scope, file);
+            let mut found: Vec<Rc<RefCell<Symbol>>> = vec![];
+            for (name, sections) in scope.borrow().iter_symbols() {
+                //a name declared several times is given by its first declaration
+                let first = sections.values().flatten()
+                    .filter(|symbol| WorkspaceSymbolFeature::is_searched(symbol, in_file))
+                    .min_by_key(|symbol| symbol.borrow().range().start());
+                let Some(first) = first else {
+                    continue;
+                };
+                if first.borrow().typ() == SymType::CLASS {
+                    found.push(first.clone());
+                }
+                if let Some(quality) = WorkspaceSymbolFeature::get_match_quality(query, name) {
+                    matches.push((quality, first.clone()));
+                }
+            }
+            scopes.extend(found);
+        }
+    }
+
+    /* Return true if the symbol can be searched: the classes, the functions, and the variables of the file that are
+    not imported */
+    fn is_searched(symbol: &Rc<RefCell<Symbol>>, in_file: bool) -> bool {
+        let symbol = symbol.borrow();
+        match symbol.typ() {
+            SymType::CLASS | SymType::FUNCTION => true,
+            SymType::VARIABLE => in_file && !symbol.as_variable().is_import_variable && !symbol.as_variable().is_narrowed
+                && !symbol.as_variable().is_container_mutation,
+            _ => false
+        }
+    }
+
+    pub fn get_match_quality(query: &str, name: &str) -> Option<MatchQuality> {
+        let query = query.to_lowercase();
+        let name = name.to_lowercase();
+        if name == query {
+            return Some(MatchQuality::EXACT);
+        }
+        if name.starts_with(&query) {
+            return Some(MatchQuality::PREFIX);
+        }
+        if name.contains(&query) {
+            return Some(MatchQuality::SUBSTRING);
+        }
+        let mut name_chars = name.chars();
+        query.chars().all(|c| name_chars.any(|n| n == c)).then_some(MatchQuality::FUZZY)
+    }
+
+    /* Build the result of a symbol: the location of its name, and the dotted path of its module or class */
+    fn build_symbol_information(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<SymbolInformation> {
+        let file = symbol.borrow().get_file()?.upgrade()?;
+        let path = match file.borrow().typ() {
+            SymType::PACKAGE(_) => PathBuf::from(&file.borrow().paths()[0]).join(format!("__init__.py{}", file.borrow().as_package().i_ext())).sanitize(),
+            _ => file.borrow().paths()[0].clone(),
+        };
+        let container = symbol.borrow().parent()?.upgrade()?;
+        let container_name = flatten_tree(&container.borrow().get_tree()).join(".");
+        let name_range = DefinitionFeature::get_name_range(session, symbol).unwrap_or(*symbol.borrow().range());
+        let range = Borrows::try_borrow_mut(&session.sync_odoo.get_file_mgr())?.text_range_to_range(session, &path, &name_range);
+        let symbol = symbol.borrow();
+        let kind = match symbol.typ() {
+            SymType::CLASS => SymbolKind::CLASS,
+            SymType::FUNCTION if symbol.as_func().is_property => SymbolKind::PROPERTY,
+            SymType::FUNCTION if container.borrow().typ() == SymType::CLASS => SymbolKind::METHOD,
+            SymType::FUNCTION => SymbolKind::FUNCTION,
+            _ if DocumentSymbolFeature::is_constant_name(symbol.name()) => SymbolKind::CONSTANT,
+            _ => SymbolKind::VARIABLE
+        };
+        #[allow(deprecated)]
+        let information = SymbolInformation {
+            name: symbol.name().clone(),
+            kind,
+            tags: None,
+            deprecated: None,
+            location: Location { uri: FileMgr::pathname2uri(&path), range },
+            container_name: Some(container_name),
+        };
+        Some(information)
+    }
+}
@@ -1,11 +1,11 @@
 use lsp_server::Notification;
 use serde_json::json;
-use odoo_ls_server::{args::{Cli, LogLevel}, cli_backend::CliBackend, constants::*, server::Server, utils::PathSanitizer};
+use odoo_ls_server::{args::{Cli, LogLevel}, cli_backend::CliBackend, constants::*, server::Server, trace::{self, CaptureLayer, ClientTraceLayer, RelativizeMakeWriter}, utils::PathSanitizer};
 use clap::Parser;
 use tracing::{info, Level};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_panic::panic_hook;
-use tracing_subscriber::{fmt, FmtSubscriber, layer::SubscriberExt};
+use tracing_subscriber::{filter::LevelFilter, fmt, layer::SubscriberExt, Layer};
 
 use std::{env, path::PathBuf};
 
@@ -37,6 +37,7 @@ fn main() {
         }
     }
 
+    trace::set_log_directory(PathBuf::from(&log_dir));
     let file_appender = RollingFileAppender::builder()
         .max_log_files(5) // only the most recent 5 log files will be kept
         .rotation(Rotation::HOURLY)
@@ -45,19 +46,23 @@ fn main() {
         .build(log_dir)
         .expect("failed to initialize rolling file appender");
     let (file_writer, _guard) = tracing_appender::non_blocking(file_appender);
-    let subscriber = FmtSubscriber::builder()
+    let file_layer = fmt::layer()
         .with_thread_ids(true)
         .with_file(false)
-        .with_max_level(log_level)
         .with_ansi(false)
-        .with_writer(file_writer)
-        .finish();
-    if cli.parse || use_debug {
-        let stdout_subscriber = fmt::layer().with_writer(std::io::stdout).with_ansi(true);
-        tracing::subscriber::set_global_default(subscriber.with(stdout_subscriber)).expect("Unable to set default tracing subscriber");
+        .with_writer(RelativizeMakeWriter::new(file_writer))
+        .with_filter(trace::file_filter(log_level));
+    let stdout_layer = if cli.parse || use_debug {
+        Some(fmt::layer().with_writer(std::io::stdout).with_ansi(true).with_filter(LevelFilter::from_level(log_level)))
     } else {
-        tracing::subscriber::set_global_default(subscriber).expect("Unable to set default tracing subscriber");
-    }
+        None
+    };
+    let subscriber = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(stdout_layer)
+        .with(ClientTraceLayer)
+        .with(CaptureLayer);
+    tracing::subscriber::set_global_default(subscriber).expect("Unable to set default tracing subscriber");
     ctrlc::set_handler(move || {
         info!("Received ctrl-c signal");
         std::process::exit(0);
@@ -1,19 +1,19 @@
-use std::{collections::HashMap, io::Error, panic, sync::{atomic::AtomicBool, Arc, Mutex}, thread::JoinHandle};
+use std::{collections::HashMap, io::Error, panic, path::PathBuf, sync::{atomic::AtomicBool, Arc, Mutex}, thread::JoinHandle};
 
 use crossbeam_channel::{Receiver, Select, Sender};
-use lsp_server::{Connection, IoThreads, Message, ProtocolError, RequestId, ResponseError};
-use lsp_types::{notification::{DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders, DidCloseTextDocument,
-    DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, Notification},
-    request::{Completion, GotoDefinition, HoverRequest, Request, ResolveCompletionItem, Shutdown}, CompletionOptions, DefinitionOptions,
-    FileOperationFilter, FileOperationPattern, FileOperationRegistrationOptions, HoverProviderCapability, InitializeParams, InitializeResult,
-    OneOf, SaveOptions, ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+use lsp_server::{Connection, ErrorCode, IoThreads, Message, ProtocolError, RequestId, Response, ResponseError};
+use lsp_types::{notification::{Cancel, DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders, DidCloseTextDocument,
+    DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, Notification, SetTrace},
+    request::{CodeActionRequest, Completion, DocumentSymbolRequest, ExecuteCommand, GotoDeclaration, GotoDefinition, HoverRequest, PrepareRenameRequest, References, Rename, Request, ResolveCompletionItem, Shutdown, SignatureHelpRequest, WorkspaceSymbolRequest}, CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CompletionOptions, DefinitionOptions,
+    CancelParams, DeclarationCapability, ExecuteCommandOptions, ExecuteCommandParams, FileOperationFilter, FileOperationPattern, FileOperationRegistrationOptions, HoverProviderCapability, InitializeParams, InitializeResult,
+    NumberOrString, OneOf, RenameOptions, SaveOptions, ServerCapabilities, ServerInfo, SetTraceParams, SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
     WorkDoneProgressOptions, WorkspaceFileOperationsServerCapabilities, WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities};
 use serde_json::json;
 #[cfg(target_os = "linux")]
 use nix;
 use tracing::{error, info, warn};
 
-use crate::{constants::EXTENSION_VERSION, core::{file_mgr::FileMgr, odoo::SyncOdoo}, threads::{delayed_changes_process_thread, message_processor_thread_main, message_processor_thread_read, DelayedProcessingMessage}, S};
+use crate::{constants::EXTENSION_VERSION, core::{arch_cache::CLEAR_ARCH_CACHE_COMMAND, cancellation::CancellationMgr, epoch::STATISTICS_COMMAND, file_mgr::FileMgr, integrity::CHECK_INTEGRITY_COMMAND, module_dependencies::CHECK_MODULE_DEPENDENCIES_COMMAND, odoo::SyncOdoo, reindex::REINDEX_COMMAND, unused_fields::FIND_UNUSED_FIELDS_COMMAND}, features::{field_dependencies::FIELD_DEPENDENCIES_METHOD, file_status::FILE_STATUS_METHOD, module_graph::MODULE_GRAPH_METHOD, readonly_documents::READONLY_DOCUMENT_METHOD, signature_help::SIGNATURE_HELP_TRIGGER_CHARACTERS, test_explorer::TEST_LIST_METHOD}, threads::{delayed_changes_process_thread, message_processor_thread_main, message_processor_thread_read, DelayedProcessingMessage}, trace, utils::PathSanitizer, S};
 
 const THREAD_MAIN_COUNT: u16 = 1;
 const THREAD_READ_COUNT: u16 = 1;
@@ -39,6 +39,7 @@ pub struct Server {
     sender_to_delayed_process: Sender<DelayedProcessingMessage>, //unique channel to delayed process thread
     sync_odoo: Arc<Mutex<SyncOdoo>>,
     interrupt_rebuild_boolean: Arc<AtomicBool>,
+    cancellations: CancellationMgr, //shared with SyncOdoo, to cancel the rebuild and the requests without waiting for it
 }
 
 #[derive(Debug)]
@@ -82,6 +83,7 @@ impl Server {
         let mut threads = vec![];
         let sync_odoo = Arc::new(Mutex::new(SyncOdoo::new()));
         let interrupt_rebuild_boolean = sync_odoo.lock().unwrap().interrupt_rebuild.clone();
+        let cancellations = sync_odoo.lock().unwrap().cancellations.clone();
         let mut receivers_w_to_s = vec![];
         let mut senders_s_to_main = vec![];
         let (sender_to_delayed_process, receiver_delayed_process) = crossbeam_channel::unbounded();
@@ -152,6 +154,7 @@ impl Server {
             delayed_process_thread,
             sync_odoo: sync_odoo,
             interrupt_rebuild_boolean: interrupt_rebuild_boolean,
+            cancellations,
         }
     }
 
@@ -165,6 +168,15 @@ impl Server {
         if let Some(initialize_params) = initialize_params.process_id {
             self.client_process_id = initialize_params;
         }
+        if let Some(trace_value) = initialize_params.trace {
+            trace::set_trace(trace_value);
+        }
+        trace::set_client_sender(Some(self.connection.as_ref().unwrap().sender.clone()));
+        let definition_link_support = initialize_params.capabilities.text_document.as_ref()
+            .and_then(|text_document| text_document.definition.as_ref())
+            .and_then(|definition| definition.link_support)
+            .unwrap_or(false);
+        self.sync_odoo.lock().unwrap().definition_link_support = definition_link_support;
         if let Some(workspace_folders) = initialize_params.workspace_folders {
             let mut sync_odoo = self.sync_odoo.lock().unwrap();
             let file_mgr = sync_odoo.get_file_mgr();
@@ -199,11 +211,33 @@ impl Server {
                         work_done_progress: Some(false)
                     }
                 })),
+                declaration_provider: Some(DeclarationCapability::Simple(true)),
+                references_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(SIGNATURE_HELP_TRIGGER_CHARACTERS.iter().map(|c| c.to_string()).collect()),
+                    retrigger_characters: None,
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
                     trigger_characters: Some(vec![S!("."), S!(","), S!("'"), S!("\"")]),
                     ..CompletionOptions::default()
                 }),
+                code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                    code_action_kinds: Some(vec![CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                    resolve_provider: Some(false),
+                })),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![S!(trace::CAPTURE_TRACE_COMMAND), S!(CHECK_INTEGRITY_COMMAND), S!(REINDEX_COMMAND), S!(STATISTICS_COMMAND), S!(FIND_UNUSED_FIELDS_COMMAND), S!(CHECK_MODULE_DEPENDENCIES_COMMAND), S!(CLEAR_ARCH_CACHE_COMMAND)],
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
                 workspace: Some(WorkspaceServerCapabilities {
                     workspace_folders: Some(WorkspaceFoldersServerCapabilities {
                         supported: Some(true),
@@ -342,6 +376,7 @@ impl Server {
         drop(hook);
         let _ = self.sender_to_delayed_process.send(DelayedProcessingMessage::EXIT);
         let _ = stop_sender.send(());
+        trace::set_client_sender(None);
         self.connection = None; //drop connection before joining threads
         if let Some(pid_join_handle) = pid_thread {
             pid_join_handle.join().unwrap();
@@ -353,14 +388,20 @@ impl Server {
         self.delayed_process_thread.join().unwrap();
     }
 
+    /* send a request to the read threads, registered to be cancellable until it is answered */
+    fn send_to_read(&mut self, r: lsp_server::Request) {
+        self.cancellations.start_request(&r.id);
+        self.sender_s_to_read.send(Message::Request(r)).unwrap();
+    }
+
     /* address a message to the right thread. */
     fn dispatch(&mut self, msg: Message) {
         match msg {
             Message::Request(r) => {
                 match r.method.as_str() {
-                    HoverRequest::METHOD | GotoDefinition::METHOD => {
+                    HoverRequest::METHOD | GotoDefinition::METHOD | GotoDeclaration::METHOD | References::METHOD | PrepareRenameRequest::METHOD | Rename::METHOD | SignatureHelpRequest::METHOD | DocumentSymbolRequest::METHOD | WorkspaceSymbolRequest::METHOD | "$Odoo/memberContributions" | CodeActionRequest::METHOD | READONLY_DOCUMENT_METHOD => {
                         self.interrupt_rebuild_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
-                        self.sender_s_to_read.send(Message::Request(r)).unwrap();
+                        self.send_to_read(r);
                     },
                     Completion::METHOD => {
                         self.interrupt_rebuild_boolean.store(true, std::sync::atomic::Ordering::SeqCst);
@@ -369,6 +410,19 @@ impl Server {
                     ResolveCompletionItem::METHOD => {
                         info!("Got ignored CompletionItem/resolve")
                     }
+                    ExecuteCommand::METHOD if r.params.get("command").and_then(|c| c.as_str()).is_some_and(|c| [CHECK_INTEGRITY_COMMAND, STATISTICS_COMMAND].contains(&c)) => {
+                        self.send_to_read(r);
+                    },
+                    ExecuteCommand::METHOD if r.params.get("command").and_then(|c| c.as_str()).is_some_and(|c| [REINDEX_COMMAND, FIND_UNUSED_FIELDS_COMMAND, CHECK_MODULE_DEPENDENCIES_COMMAND, CLEAR_ARCH_CACHE_COMMAND].contains(&c)) => { //queued with the file events
+                        self.sender_s_to_main.send(Message::Request(r)).unwrap();
+                    },
+                    FILE_STATUS_METHOD | TEST_LIST_METHOD | FIELD_DEPENDENCIES_METHOD | MODULE_GRAPH_METHOD => { //a status request must not interrupt the rebuild it reports, nor the listings of the tests, of the field dependencies and of the modules
+                        self.send_to_read(r);
+                    },
+                    ExecuteCommand::METHOD => {
+                        let response = self.handle_execute_command(r);
+                        self.connection.as_ref().unwrap().sender.send(Message::Response(response)).unwrap();
+                    }
                     _ => {panic!("Not handled Request Id: {}", r.method)}
                 }
             },
@@ -401,11 +455,38 @@ impl Server {
             },
             Message::Notification(n) => {
                 match n.method.as_str() {
+                    DidChangeTextDocument::METHOD => {
+                        //the running rebuild is stale if the edited file is one it has still to build
+                        if let Some(uri) = n.params.get("textDocument").and_then(|d| d.get("uri")).and_then(|uri| uri.as_str()) {
+                            if self.cancellations.cancel_rebuild_for(&PathBuf::from(FileMgr::uri2pathname(uri)).sanitize()) {
+                                info!("Rebuild cancelled by an edit of {}", uri);
+                            }
+                        }
+                        self.sender_s_to_main.send(Message::Notification(n)).unwrap();
+                    }
                     DidOpenTextDocument::METHOD | DidChangeConfiguration::METHOD | DidChangeWorkspaceFolders::METHOD |
-                    DidChangeTextDocument::METHOD | DidCloseTextDocument::METHOD | DidSaveTextDocument::METHOD |
+                    DidCloseTextDocument::METHOD | DidSaveTextDocument::METHOD |
                     DidRenameFiles::METHOD | DidCreateFiles::METHOD | DidChangeWatchedFiles::METHOD | DidDeleteFiles::METHOD => {
                         self.sender_s_to_main.send(Message::Notification(n)).unwrap();
                     }
+                    Cancel::METHOD => {
+                        match serde_json::from_value::<CancelParams>(n.params) {
+                            Ok(params) => {
+                                let id = match params.id {
+                                    NumberOrString::Number(id) => RequestId::from(id),
+                                    NumberOrString::String(id) => RequestId::from(id),
+                                };
+                                self.cancellations.cancel_request(&id);
+                            },
+                            Err(e) => warn!("Unable to parse $/cancelRequest parameters: {}", e),
+                        }
+                    }
+                    SetTrace::METHOD => {
+                        match serde_json::from_value::<SetTraceParams>(n.params) {
+                            Ok(params) => trace::set_trace(params.value),
+                            Err(e) => warn!("Unable to parse $/setTrace parameters: {}", e),
+                        }
+                    }
                     _ => {
                         if n.method.starts_with("$/") {
                             warn!("Not handled message id: {}", n.method);
@@ -418,6 +499,27 @@ impl Server {
         }
     }
 
+    /* Commands are handled directly by the server, as they don't need to access SyncOdoo. The integrity check and the
+    statistics are sent to the read threads, and the reindex and the unused fields to the main thread */
+    fn handle_execute_command(&self, r: lsp_server::Request) -> Response {
+        let params: ExecuteCommandParams = match serde_json::from_value(r.params) {
+            Ok(params) => params,
+            Err(e) => return Response::new_err(r.id, ErrorCode::InvalidParams as i32, format!("Invalid parameters: {}", e)),
+        };
+        match params.command.as_str() {
+            trace::CAPTURE_TRACE_COMMAND => {
+                match trace::capture_trace_command(&params.arguments) {
+                    Ok(path) => {
+                        info!("Capturing trace in {}", path.sanitize());
+                        Response::new_ok(r.id, path.sanitize())
+                    },
+                    Err(e) => Response::new_err(r.id, ErrorCode::InvalidParams as i32, e),
+                }
+            },
+            _ => Response::new_err(r.id, ErrorCode::MethodNotFound as i32, format!("Unknown command: {}", params.command)),
+        }
+    }
+
     #[cfg(any(target_os = "linux", target_os = "macos"))]
     fn spawn_pid_thread(&self, pid: u32, stop_channel: Receiver<()>) -> JoinHandle<()> {
         use std::process::exit;
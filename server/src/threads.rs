@@ -1,25 +1,33 @@
 use std::{path::PathBuf, sync::{atomic::Ordering, Arc, Mutex}, time::Instant};
 
-use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use lsp_server::{Message, RequestId, Response, ResponseError};
 use lsp_types::{notification::{DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidChangeWorkspaceFolders,
     DidCloseTextDocument, DidCreateFiles, DidDeleteFiles, DidOpenTextDocument, DidRenameFiles, DidSaveTextDocument, LogMessage,
-    Notification}, request::{Completion, GotoDefinition, GotoTypeDefinitionResponse, HoverRequest, Request, Shutdown},
-    CompletionResponse, Hover, LogMessageParams, MessageType};
+    Notification}, request::{CodeActionRequest, Completion, DocumentSymbolRequest, ExecuteCommand, GotoDeclaration, GotoDeclarationResponse, GotoDefinition, GotoTypeDefinitionResponse, HoverRequest, PrepareRenameRequest, References, Rename, Request, Shutdown, SignatureHelpRequest, WorkspaceSymbolRequest},
+    CodeActionResponse, CompletionResponse, DocumentSymbolResponse, ExecuteCommandParams, Hover, Location, LogMessageParams, MessageType, PrepareRenameResponse, SignatureHelp, WorkspaceEdit,
+    WorkspaceSymbolResponse};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use tracing::{error, warn};
+use tracing::{error, info_span, warn};
 
-use crate::{core::{config::RefreshMode, odoo::{Odoo, SyncOdoo}}, server::ServerError, S};
+use crate::{core::{arch_cache::CLEAR_ARCH_CACHE_COMMAND, cancellation::CancellationToken, config::RefreshMode, epoch::{EpochStatistics, Epochs, STATISTICS_COMMAND}, module_dependencies::{ModuleDependencies, CHECK_MODULE_DEPENDENCIES_COMMAND}, odoo::{InitState, Odoo, SyncOdoo}, reindex::ReindexSummary, unused_fields::{ModuleUnusedFields, FIND_UNUSED_FIELDS_COMMAND}}, features::{field_dependencies::{FieldDependencies, FIELD_DEPENDENCIES_METHOD}, file_status::{FileStatus, FILE_STATUS_METHOD}, hover::MemberContribution, module_graph::{ModuleGraph, MODULE_GRAPH_METHOD}, readonly_documents::{ReadonlyDocument, READONLY_DOCUMENT_METHOD}, test_explorer::{TestList, TEST_LIST_METHOD}}, server::ServerError, trace, S};
 
 pub struct SessionInfo<'a> {
     sender: Sender<Message>,
     receiver: Receiver<Message>,
     pub sync_odoo: &'a mut SyncOdoo,
+    pub cancellation: CancellationToken, //the rebuild token for the main threads, the token of the request for the read threads
     delayed_process_sender: Option<Sender<DelayedProcessingMessage>>
 }
 
 impl <'a> SessionInfo<'a> {
+    /* True if the rebuild or the request of the session has to stop, as its token has been cancelled. The rebuild of
+    the first indexing is never cancelled */
+    pub fn is_cancelled(&self) -> bool {
+        self.sync_odoo.state_init == InitState::ODOO_READY && self.cancellation.is_cancelled()
+    }
+
     pub fn log_message(&self, msg_type: MessageType, msg: String) {
         self.sender.send(
             Message::Notification(lsp_server::Notification{
@@ -110,10 +118,12 @@ impl <'a> SessionInfo<'a> {
 
     /* use it for test or tools, that do not need to connect to the server, and only want a fake session to use SyncOdoo */
     pub fn new_from_custom_channel(sender: Sender<Message>, receiver: Receiver<Message>, sync_odoo: &'a mut SyncOdoo) -> Self {
+        let cancellation = sync_odoo.cancellations.get_rebuild_token();
         Self {
             sender,
             receiver,
             sync_odoo,
+            cancellation,
             delayed_process_sender: None
         }
     }
@@ -143,9 +153,24 @@ pub enum DelayedProcessingMessage {
     PROCESS(Instant), //Process rebuilds after delay
     UPDATE_FILE_INDEX(UpdateFileIndexData), //update the file after delay
     REBUILD(Instant), //reset the database after the delay
+    FINISH_BUILDS(Instant, Sender<()>), //finish the small pending rebuild for a request of the read thread before the deadline (see Epochs)
     EXIT, //exit the thread
 }
 
+fn finish_builds(sync_odoo: &Arc<Mutex<SyncOdoo>>, sender_session: &Sender<Message>, receiver_session: &Receiver<Message>, delayed_process_sender: &Sender<DelayedProcessingMessage>, deadline: Instant, done: Sender<()>) {
+    let mut sync_odoo = sync_odoo.lock().unwrap();
+    let cancellation = sync_odoo.cancellations.get_rebuild_token();
+    let mut session = SessionInfo{
+        sender: sender_session.clone(),
+        receiver: receiver_session.clone(),
+        sync_odoo: &mut sync_odoo,
+        cancellation,
+        delayed_process_sender: Some(delayed_process_sender.clone())
+    };
+    Epochs::finish_pending_builds(&mut session, deadline);
+    let _ = done.send(()); //the request may have stopped waiting
+}
+
 pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_session: Receiver<Message>, receiver: Receiver<DelayedProcessingMessage>, sync_odoo: Arc<Mutex<SyncOdoo>>, delayed_process_sender: Sender<DelayedProcessingMessage>) {
     const MAX_DELAY: u64 = 15000;
     let mut normal_delay = std::time::Duration::from_millis(std::cmp::min(sync_odoo.lock().unwrap().config.auto_save_delay, MAX_DELAY));
@@ -161,55 +186,56 @@ pub fn delayed_changes_process_thread(sender_session: Sender<Message>, receiver_
             Ok(DelayedProcessingMessage::UPDATE_DELAY(duration)) => {
                 normal_delay = std::time::Duration::from_millis(std::cmp::min(duration, MAX_DELAY));
             }
+            Ok(DelayedProcessingMessage::FINISH_BUILDS(deadline, done)) => {
+                finish_builds(&sync_odoo, &sender_session, &receiver_session, &delayed_process_sender, deadline, done);
+            }
             Ok(DelayedProcessingMessage::REBUILD(time) | DelayedProcessingMessage::PROCESS(time) | DelayedProcessingMessage::UPDATE_FILE_INDEX(UpdateFileIndexData{path: _, time})) => {
                 if matches!(msg, Ok(DelayedProcessingMessage::REBUILD(_))) {
                     rebuild = true;
                 }
                 let mut last_time = time;
-                let mut to_wait = (time + delay) - std::time::Instant::now();
-                while to_wait.as_millis() > 0 {
-                    std::thread::sleep(to_wait);
-                    to_wait = std::time::Duration::ZERO;
-                    loop {
-                        let new_msg = receiver.try_recv();
-                        match new_msg {
-                            Ok(DelayedProcessingMessage::EXIT) => {return;},
-                            Ok(DelayedProcessingMessage::UPDATE_DELAY(duration)) => {
-                                delay = std::time::Duration::from_millis(std::cmp::min(duration, MAX_DELAY));
-                            }
-                            Ok(DelayedProcessingMessage::PROCESS(t)) => {
-                                if t > last_time {
-                                    to_wait = (t + delay) - std::time::Instant::now();
-                                    last_time = t;
-                                }
-                            },
-                            Ok(DelayedProcessingMessage::REBUILD(t)) => {
-                                rebuild = true;
-                                delay = std::time::Duration::from_millis(std::cmp::max(normal_delay.as_millis() as u64, 4000));
-                                if t > last_time {
-                                    to_wait = (t + delay) - std::time::Instant::now();
-                                    last_time = t;
-                                }
-                            },
-                            Ok(DelayedProcessingMessage::UPDATE_FILE_INDEX(UpdateFileIndexData { path, time: t })) => {
-                                update_file_index = Some(path);
-                                if t > last_time {
-                                    to_wait = (t + delay) - std::time::Instant::now();
-                                    last_time = t;
-                                }
-                            },
-                            Err(TryRecvError::Empty) => {
-                                break;
-                            },
-                            Err(_) => {return;}
+                loop {
+                    let new_msg = receiver.recv_deadline(last_time + delay);
+                    match new_msg {
+                        Ok(DelayedProcessingMessage::EXIT) => {return;},
+                        Ok(DelayedProcessingMessage::UPDATE_DELAY(duration)) => {
+                            delay = std::time::Duration::from_millis(std::cmp::min(duration, MAX_DELAY));
                         }
+                        Ok(DelayedProcessingMessage::PROCESS(t)) => {
+                            if t > last_time {
+                                last_time = t;
+                            }
+                        },
+                        Ok(DelayedProcessingMessage::REBUILD(t)) => {
+                            rebuild = true;
+                            delay = std::time::Duration::from_millis(std::cmp::max(normal_delay.as_millis() as u64, 4000));
+                            if t > last_time {
+                                last_time = t;
+                            }
+                        },
+                        Ok(DelayedProcessingMessage::UPDATE_FILE_INDEX(UpdateFileIndexData { path, time: t })) => {
+                            update_file_index = Some(path);
+                            if t > last_time {
+                                last_time = t;
+                            }
+                        },
+                        Ok(DelayedProcessingMessage::FINISH_BUILDS(deadline, done)) => {
+                            finish_builds(&sync_odoo, &sender_session, &receiver_session, &delayed_process_sender, deadline, done);
+                        },
+                        Err(RecvTimeoutError::Timeout) => {
+                            break;
+                        },
+                        Err(_) => {return;}
                     }
                 }
                 {
+                    let mut sync_odoo = sync_odoo.lock().unwrap();
+                    let cancellation = sync_odoo.cancellations.get_rebuild_token();
                     let mut session = SessionInfo{
                         sender: sender_session.clone(),
                         receiver: receiver_session.clone(),
-                        sync_odoo: &mut sync_odoo.lock().unwrap(),
+                        sync_odoo: &mut sync_odoo,
+                        cancellation,
                         delayed_process_sender: Some(delayed_process_sender.clone())
                     };
                     if rebuild {
@@ -243,19 +269,35 @@ pub fn message_processor_thread_main(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
             break;
         }
         let msg = msg.unwrap();
+        let mut sync_odoo = sync_odoo.lock().unwrap();
+        let cancellation = sync_odoo.cancellations.get_rebuild_token();
         let mut session = SessionInfo{
             sender: sender.clone(),
             receiver: receiver.clone(),
-            sync_odoo: &mut sync_odoo.lock().unwrap(),
+            sync_odoo: &mut sync_odoo,
+            cancellation,
             delayed_process_sender: Some(delayed_process_sender.clone())
         };
         match msg {
             Message::Request(r) => {
+                let start = Instant::now();
+                let span = info_span!("request", method = r.method.as_str(), id = %r.id);
+                let _enter = span.enter();
+                trace::request_received(&r.method, &r.id);
                 let (value, error) = match r.method.as_str() {
                     Completion::METHOD => {
                         //Handle completion in main because updates has to be done before the autocompletion
                         //Moreover, autocompletion will trigger a process_rebuild if needed.
-                        to_value::<CompletionResponse>(Odoo::handle_autocomplete(&mut session, serde_json::from_value(r.params).unwrap()))
+                        to_value::<CompletionResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_autocomplete))
+                    },
+                    ExecuteCommand::METHOD => { //the reindex, that changes the symbols like the file events, and the analyses that publish diagnostics
+                        let params: ExecuteCommandParams = serde_json::from_value(r.params).unwrap();
+                        match params.command.as_str() {
+                            FIND_UNUSED_FIELDS_COMMAND => to_value::<Vec<ModuleUnusedFields>>(Odoo::handle_request(&mut session, params, Odoo::handle_find_unused_fields)),
+                            CHECK_MODULE_DEPENDENCIES_COMMAND => to_value::<Vec<ModuleDependencies>>(Odoo::handle_request(&mut session, params, Odoo::handle_check_module_dependencies)),
+                            CLEAR_ARCH_CACHE_COMMAND => to_value::<usize>(Odoo::handle_request(&mut session, params, Odoo::handle_clear_arch_cache)),
+                            _ => to_value::<ReindexSummary>(Odoo::handle_request(&mut session, params, Odoo::handle_reindex)),
+                        }
                     },
                     _ => {error!("Request not handled by main thread: {}", r.method); (None, Some(ResponseError{
                         code: 1,
@@ -263,6 +305,7 @@ pub fn message_processor_thread_main(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
                         data: None
                     }))}
                 };
+                trace::request_handled(&r.method, &r.id, start.elapsed(), error.is_some());
                 sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
             },
             Message::Notification(n) => {
@@ -299,20 +342,82 @@ pub fn message_processor_thread_read(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
             break;
         }
         let msg = msg.unwrap();
+        if let Message::Request(r) = &msg {
+            if let Err(error) = Epochs::wait_coherent_read(&sync_odoo, &r.params, &sender, &receiver, &delayed_process_sender) {
+                sender.send(Message::Response(Response { id: r.id.clone(), result: None, error: Some(error) })).unwrap();
+                continue;
+            }
+        }
+        let mut sync_odoo = sync_odoo.lock().unwrap(); //TODO work on read access
         let mut session = SessionInfo{
             sender: sender.clone(),
             receiver: receiver.clone(),
-            sync_odoo: &mut sync_odoo.lock().unwrap(), //TODO work on read access
+            sync_odoo: &mut sync_odoo,
+            cancellation: CancellationToken::new(),
             delayed_process_sender: Some(delayed_process_sender.clone()),
         };
         match msg {
             Message::Request(r) => {
+                let start = Instant::now();
+                let span = info_span!("request", method = r.method.as_str(), id = %r.id);
+                let _enter = span.enter();
+                trace::request_received(&r.method, &r.id);
+                session.cancellation = session.sync_odoo.cancellations.start_request(&r.id);
                 let (value, error) = match r.method.as_str() {
                     HoverRequest::METHOD => {
-                        to_value::<Hover>(Odoo::handle_hover(&mut session, serde_json::from_value(r.params).unwrap()))
+                        to_value::<Hover>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_hover))
                     },
                     GotoDefinition::METHOD => {
-                        to_value::<GotoTypeDefinitionResponse>(Odoo::handle_goto_definition(&mut session, serde_json::from_value(r.params).unwrap()))
+                        to_value::<GotoTypeDefinitionResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_goto_definition))
+                    },
+                    GotoDeclaration::METHOD => {
+                        to_value::<GotoDeclarationResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_goto_declaration))
+                    },
+                    References::METHOD => {
+                        to_value::<Vec<Location>>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_references))
+                    },
+                    PrepareRenameRequest::METHOD => {
+                        to_value::<PrepareRenameResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_prepare_rename))
+                    },
+                    Rename::METHOD => {
+                        to_value::<WorkspaceEdit>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_rename))
+                    },
+                    DocumentSymbolRequest::METHOD => {
+                        to_value::<DocumentSymbolResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_document_symbol))
+                    },
+                    SignatureHelpRequest::METHOD => {
+                        to_value::<SignatureHelp>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_signature_help))
+                    },
+                    WorkspaceSymbolRequest::METHOD => {
+                        to_value::<WorkspaceSymbolResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_workspace_symbol))
+                    },
+                    "$Odoo/memberContributions" => {
+                        to_value::<Vec<MemberContribution>>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_member_contributions))
+                    },
+                    CodeActionRequest::METHOD => {
+                        to_value::<CodeActionResponse>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_code_action))
+                    },
+                    FILE_STATUS_METHOD => {
+                        to_value::<FileStatus>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_file_status))
+                    },
+                    READONLY_DOCUMENT_METHOD => {
+                        to_value::<ReadonlyDocument>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_readonly_document))
+                    },
+                    TEST_LIST_METHOD => {
+                        to_value::<TestList>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_test_list))
+                    },
+                    FIELD_DEPENDENCIES_METHOD => {
+                        to_value::<FieldDependencies>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_field_dependencies))
+                    },
+                    MODULE_GRAPH_METHOD => {
+                        to_value::<ModuleGraph>(Odoo::handle_request(&mut session, serde_json::from_value(r.params).unwrap(), Odoo::handle_module_graph))
+                    },
+                    ExecuteCommand::METHOD => { //only the commands that need SyncOdoo are handled here
+                        let params: ExecuteCommandParams = serde_json::from_value(r.params).unwrap();
+                        match params.command.as_str() {
+                            STATISTICS_COMMAND => to_value::<EpochStatistics>(Odoo::handle_request(&mut session, params, Odoo::handle_statistics)),
+                            _ => to_value::<Vec<String>>(Odoo::handle_request(&mut session, params, Odoo::handle_check_integrity)),
+                        }
                     },
                     _ => {error!("Request not handled by read thread: {}", r.method); (None, Some(ResponseError{
                         code: 1,
@@ -320,6 +425,13 @@ pub fn message_processor_thread_read(sync_odoo: Arc<Mutex<SyncOdoo>>, generic_re
                         data: None
                     }))}
                 };
+                //the result of a request cancelled by the client can be partial
+                let (value, error) = match session.cancellation.is_cancelled() {
+                    true => (None, Some(CancellationToken::cancelled_error())),
+                    false => (value, error)
+                };
+                session.sync_odoo.cancellations.end_request(&r.id);
+                trace::request_handled(&r.method, &r.id, start.elapsed(), error.is_some());
                 sender.send(Message::Response(Response { id: r.id, result: value, error: error })).unwrap();
             },
             Message::Notification(r) => {
@@ -0,0 +1,289 @@
+use std::{fmt, fs::File, io::{self, BufWriter, Write}, path::PathBuf, sync::{atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering}, Mutex, RwLock}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+use crossbeam_channel::Sender;
+use lsp_server::{Message, RequestId};
+use lsp_types::{notification::{LogTrace, Notification}, LogTraceParams, TraceValue};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tracing::{field::{Field, Visit}, info, span, Event, Level, Metadata, Subscriber};
+use tracing_subscriber::{filter::{filter_fn, FilterFn}, fmt::MakeWriter, layer::Context, registry::LookupSpan, Layer};
+
+/* Logging of the server is done through a layered subscriber (see main.rs), made of independent outputs that can be
+toggled at runtime:
+ - the log file, that can be disabled with the 'fileLogging' setting,
+ - the client, that receives $/logTrace notifications about requests, according to the value given by $/setTrace,
+ - a capture file, that records everything during a few seconds when the client executes the command 'odooLs.captureTrace'.
+Paths in the logs are relativized to the workspace folders. */
+
+pub const CAPTURE_TRACE_COMMAND: &str = "odooLs.captureTrace";
+/* Target of the events about the lifecycle of requests, that are forwarded to the client */
+pub const TRACE_TARGET: &str = "lsp_trace";
+
+const DEFAULT_CAPTURE_SECONDS: u64 = 10;
+const MAX_CAPTURE_SECONDS: u64 = 300;
+
+const TRACE_OFF: u8 = 0;
+const TRACE_MESSAGES: u8 = 1;
+const TRACE_VERBOSE: u8 = 2;
+
+static FILE_LOGGING: AtomicBool = AtomicBool::new(true);
+static TRACE_LEVEL: AtomicU8 = AtomicU8::new(TRACE_OFF);
+static CAPTURE_ID: AtomicU64 = AtomicU64::new(0);
+static CLIENT_SENDER: Lazy<Mutex<Option<Sender<Message>>>> = Lazy::new(|| Mutex::new(None));
+static CAPTURE: Lazy<Mutex<Option<TraceCapture>>> = Lazy::new(|| Mutex::new(None));
+static WORKSPACE_ROOTS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(vec![]));
+static LOG_DIRECTORY: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(None));
+
+struct TraceCapture {
+    id: u64,
+    start: Instant,
+    writer: BufWriter<File>,
+}
+
+pub fn set_trace(value: TraceValue) {
+    let level = match value {
+        TraceValue::Off => TRACE_OFF,
+        TraceValue::Messages => TRACE_MESSAGES,
+        TraceValue::Verbose => TRACE_VERBOSE,
+    };
+    TRACE_LEVEL.store(level, Ordering::Relaxed);
+}
+
+pub fn set_file_logging(enabled: bool) {
+    FILE_LOGGING.store(enabled, Ordering::Relaxed);
+}
+
+/* Set the channel used to send $/logTrace notifications. It must be removed before closing the connection, as the
+connection waits for all its senders to be dropped */
+pub fn set_client_sender(sender: Option<Sender<Message>>) {
+    *CLIENT_SENDER.lock().unwrap() = sender;
+}
+
+pub fn set_log_directory(path: PathBuf) {
+    *LOG_DIRECTORY.write().unwrap() = Some(path);
+}
+
+pub fn set_workspace_roots(roots: &Vec<String>) {
+    let mut roots = roots.clone();
+    //longest first, so nested workspace folders are relativized to the nearest one
+    roots.sort_by(|a, b| b.len().cmp(&a.len()));
+    *WORKSPACE_ROOTS.write().unwrap() = roots;
+}
+
+/* Replace the paths under a workspace folder by paths relative to this folder */
+pub fn relativize(text: &str) -> String {
+    let roots = WORKSPACE_ROOTS.read().unwrap();
+    let mut res = text.to_string();
+    for root in roots.iter() {
+        let root = root.trim_end_matches('/');
+        if root.is_empty() || !res.contains(root) {
+            continue;
+        }
+        res = res.replace(&(root.to_string() + "/"), "./");
+        res = res.replace(root, ".");
+    }
+    res
+}
+
+/* Filter of the file layer: the level is given by the command line, and the output can be disabled at runtime */
+pub fn file_filter(max_level: Level) -> FilterFn<impl Fn(&Metadata<'_>) -> bool> {
+    filter_fn(move |metadata| FILE_LOGGING.load(Ordering::Relaxed) && *metadata.level() <= max_level)
+}
+
+pub fn request_received(method: &str, id: &RequestId) {
+    info!(target: TRACE_TARGET, verbose_only = true, "Received request '{} - ({})'", method, id);
+}
+
+pub fn request_handled(method: &str, id: &RequestId, duration: Duration, failed: bool) {
+    let verbose = format!("Request handled in {}ms{}", duration.as_millis(), if failed { " with an error" } else { "" });
+    info!(target: TRACE_TARGET, verbose = verbose.as_str(), "Sending response '{} - ({})'", method, id);
+}
+
+/* Handle the command 'odooLs.captureTrace'. The optional argument is the duration of the capture in seconds.
+Return the path of the file that will receive the trace */
+pub fn capture_trace_command(arguments: &Vec<Value>) -> Result<PathBuf, String> {
+    let seconds = match arguments.first() {
+        Some(value) => value.as_u64().ok_or(format!("Invalid duration for {}: {}", CAPTURE_TRACE_COMMAND, value))?,
+        None => DEFAULT_CAPTURE_SECONDS,
+    };
+    start_capture(Duration::from_secs(seconds.clamp(1, MAX_CAPTURE_SECONDS)))
+}
+
+/* Record all events and spans in a new file for the given duration, and return the path of this file */
+pub fn start_capture(duration: Duration) -> Result<PathBuf, String> {
+    let directory = LOG_DIRECTORY.read().unwrap().clone().unwrap_or(std::env::temp_dir());
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = directory.join(format!("odoo_trace_{}_{}.log", std::process::id(), timestamp));
+    let file = File::create(&path).map_err(|e| format!("Unable to create trace file {}: {}", path.display(), e))?;
+    let id = CAPTURE_ID.fetch_add(1, Ordering::Relaxed) + 1;
+    let previous = CAPTURE.lock().unwrap().replace(TraceCapture {
+        id,
+        start: Instant::now(),
+        writer: BufWriter::new(file),
+    });
+    if let Some(mut previous) = previous {
+        let _ = previous.writer.flush();
+    }
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        stop_capture(id);
+    });
+    Ok(path)
+}
+
+fn stop_capture(id: u64) {
+    let mut capture = CAPTURE.lock().unwrap();
+    if capture.as_ref().is_some_and(|c| c.id == id) {
+        if let Some(mut capture) = capture.take() {
+            let _ = capture.writer.flush();
+        }
+    }
+}
+
+fn is_capturing() -> bool {
+    CAPTURE.lock().unwrap().is_some()
+}
+
+fn capture_line(line: &str) {
+    if let Some(capture) = CAPTURE.lock().unwrap().as_mut() {
+        let elapsed = capture.start.elapsed().as_secs_f64() * 1000.0;
+        let _ = writeln!(capture.writer, "[{:>10.3}ms] {}", elapsed, relativize(line));
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl EventVisitor {
+    fn get_field(&self, name: &str) -> Option<&String> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    fn fields_to_string(&self) -> String {
+        self.fields.iter().map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<String>>().join(" ")
+    }
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
+
+/* Layer that forwards the lifecycle of requests to the client as $/logTrace notifications */
+pub struct ClientTraceLayer;
+
+impl<S: Subscriber> Layer<S> for ClientTraceLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != TRACE_TARGET {
+            return;
+        }
+        let level = TRACE_LEVEL.load(Ordering::Relaxed);
+        if level == TRACE_OFF {
+            return;
+        }
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        if level != TRACE_VERBOSE && visitor.get_field("verbose_only").is_some() {
+            return;
+        }
+        let params = LogTraceParams {
+            message: relativize(&visitor.message),
+            verbose: if level == TRACE_VERBOSE { visitor.get_field("verbose").map(|v| relativize(v)) } else { None },
+        };
+        if let Some(sender) = CLIENT_SENDER.lock().unwrap().as_ref() {
+            let _ = sender.send(Message::Notification(lsp_server::Notification {
+                method: LogTrace::METHOD.to_string(),
+                params: serde_json::to_value(params).unwrap(),
+            }));
+        }
+    }
+}
+
+/* Layer that writes all events, and the duration of all spans, to the capture file while a capture is running */
+pub struct CaptureLayer;
+
+struct SpanStart(Instant);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !is_capturing() {
+            return;
+        }
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let scope = ctx.event_scope(event)
+            .map(|scope| scope.from_root().map(|span| span.name().to_string()).collect::<Vec<String>>().join(":"))
+            .unwrap_or_default();
+        let metadata = event.metadata();
+        capture_line(&format!("{:>5} {} {}: {} {}", metadata.level(), metadata.target(), scope, visitor.message, visitor.fields_to_string()));
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if !is_capturing() {
+            return;
+        }
+        if let Some(span) = ctx.span(&id) {
+            let duration = span.extensions().get::<SpanStart>().map(|start| start.0.elapsed());
+            if let Some(duration) = duration {
+                capture_line(&format!(" SPAN {}: closed after {:.3}ms", span.name(), duration.as_secs_f64() * 1000.0));
+            }
+        }
+    }
+}
+
+/* Writer of the file layer, that relativizes the paths of the written logs */
+pub struct RelativizeMakeWriter<M> {
+    inner: M,
+}
+
+impl<M> RelativizeMakeWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RelativizeMakeWriter<M> {
+    type Writer = RelativizeWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RelativizeWriter { inner: self.inner.make_writer() }
+    }
+}
+
+pub struct RelativizeWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for RelativizeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.inner.write_all(relativize(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
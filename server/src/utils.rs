@@ -1,5 +1,11 @@
-use std::{fs, path::{Path, PathBuf}, str::FromStr};
-use path_slash::{PathBufExt, PathExt};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::{fs, path::{Path, PathBuf}};
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+#[cfg(target_os = "windows")]
+use once_cell::sync::Lazy;
+use path_slash::PathExt;
 use ruff_text_size::TextSize;
 
 #[macro_export]
@@ -9,30 +15,96 @@ macro_rules! S {
     };
 }
 
-#[cfg(target_os = "windows")]
-pub fn is_file_cs(path: String) -> bool {
-    let mut p = Path::new(&path);
-    if p.exists() && p.is_file() {
-        while p.parent().is_some() {
-            let mut found = false;
-            if let Ok(entries) = fs::read_dir(p.parent().unwrap()) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if entry.file_name() == p.components().last().unwrap().as_os_str() {
-                            found = true;
-                            break;
-                        }
-                    }
-                }
-            }
-            if !found {
+/* Maximum number of folders whose entries are kept by a CaseSensitivePathCache. The cache is emptied when it is reached */
+pub const MAX_CACHED_FOLDERS: usize = 10000;
+
+/* The names of the entries of the folders, with their case, read once per folder. On Windows, the file system ignores
+the case, but Python does not: is_file_cs and is_dir_cs check the case of each component of the path, and the import
+resolution asks them for the same few folders again and again. The entries of a folder are forgotten when a file is
+created, deleted or renamed in it (see invalidate) */
+#[derive(Debug, Default)]
+pub struct CaseSensitivePathCache {
+    folders: HashMap<PathBuf, Option<HashSet<OsString>>>, //None if the folder can't be read
+    read_count: usize,
+}
+
+impl CaseSensitivePathCache {
+
+    pub fn new() -> CaseSensitivePathCache {
+        CaseSensitivePathCache::default()
+    }
+
+    /* Return true if each component of the path is named with this case in its parent folder */
+    pub fn has_exact_case(&mut self, path: &Path) -> bool {
+        let mut current = path;
+        while let (Some(parent), Some(name)) = (current.parent(), current.file_name()) {
+            if !self.contains(parent, name) {
                 return false;
             }
-            p = p.parent().unwrap();
+            current = parent;
         }
-        return true;
+        true
+    }
+
+    /* Return true if the folder has an entry of this name, with this case */
+    pub fn contains(&mut self, folder: &Path, name: &OsStr) -> bool {
+        if !self.folders.contains_key(folder) {
+            if self.folders.len() >= MAX_CACHED_FOLDERS {
+                self.folders.clear();
+            }
+            let names = fs::read_dir(folder).ok().map(|entries| entries.flatten().map(|entry| entry.file_name()).collect());
+            self.read_count += 1;
+            self.folders.insert(folder.to_path_buf(), names);
+        }
+        self.folders.get(folder).is_some_and(|names| names.as_ref().is_some_and(|names| names.contains(name)))
+    }
+
+    /* Forget the entries of the folder of the path, that has been created, deleted or renamed, and the ones of the path
+    itself and of its subfolders if it is a folder */
+    pub fn invalidate(&mut self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            self.folders.remove(parent);
+        }
+        self.folders.retain(|folder, _| !folder.starts_with(path));
+    }
+
+    pub fn clear(&mut self) {
+        self.folders.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.folders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folders.is_empty()
     }
-    false
+
+    /* Number of folders read since the creation of the cache */
+    pub fn read_count(&self) -> usize {
+        self.read_count
+    }
+}
+
+#[cfg(target_os = "windows")]
+static CASE_SENSITIVE_PATHS: Lazy<Mutex<CaseSensitivePathCache>> = Lazy::new(|| Mutex::new(CaseSensitivePathCache::new()));
+
+/* Forget the cached entries of the folders of a path that has been created, deleted or renamed. Nothing is cached on
+Linux and macOS */
+pub fn invalidate_case_sensitive_path(_path: &Path) {
+    #[cfg(target_os = "windows")]
+    CASE_SENSITIVE_PATHS.lock().unwrap().invalidate(_path);
+}
+
+pub fn clear_case_sensitive_paths() {
+    #[cfg(target_os = "windows")]
+    CASE_SENSITIVE_PATHS.lock().unwrap().clear();
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_file_cs(path: String) -> bool {
+    let p = Path::new(&path);
+    p.exists() && p.is_file() && CASE_SENSITIVE_PATHS.lock().unwrap().has_exact_case(p)
 }
 
 #[cfg(any(target_os = "linux", target_os = "macos"))]
@@ -43,28 +115,8 @@ pub fn is_file_cs(path: String) -> bool {
 
 #[cfg(target_os = "windows")]
 pub fn is_dir_cs(path: String) -> bool {
-    let mut p = Path::new(&path);
-    if p.exists() && p.is_dir() {
-        while p.parent().is_some() {
-            let mut found = false;
-            if let Ok(entries) = fs::read_dir(p.parent().unwrap()) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if entry.file_name() == p.components().last().unwrap().as_os_str() {
-                            found = true;
-                            break;
-                        }
-                    }
-                }
-            }
-            if !found {
-                return false;
-            }
-            p = p.parent().unwrap();
-        }
-        return true;
-    }
-    false
+    let p = Path::new(&path);
+    p.exists() && p.is_dir() && CASE_SENSITIVE_PATHS.lock().unwrap().has_exact_case(p)
 }
 
 #[cfg(any(target_os = "linux", target_os = "macos"))]
@@ -73,16 +125,15 @@ pub fn is_dir_cs(path: String) -> bool {
     p.exists() && p.is_dir()
 }
 
-//TODO use it?
-pub fn is_symlink_cs(path: String) -> bool {
-    match fs::canonicalize(path) {
-        Ok(canonical_path) => {
-            fs::metadata(canonical_path).unwrap().is_symlink()
-        }
-        Err(_err) => {
-            false
-        }
-    }
+/* Return true if the path is a symlink. The link itself is checked, not the file it points to */
+pub fn is_symlink_cs(path: &str) -> bool {
+    fs::symlink_metadata(to_long_path(path)).is_ok_and(|metadata| metadata.is_symlink())
+}
+
+/* Return the path of the file with all its symlinks resolved, in the form given by sanitize_path. None is returned if the
+file doesn't exist, or if its symlinks loop */
+pub fn real_path(path: &str) -> Option<String> {
+    fs::canonicalize(to_long_path(path)).ok().map(|real| real.sanitize())
 }
 
 pub trait ToFilePath {
@@ -92,8 +143,7 @@ pub trait ToFilePath {
 impl ToFilePath for lsp_types::Uri {
 
     fn to_file_path(&self) -> Result<PathBuf, ()> {
-        let url = url::Url::from_str(self.as_str()).map_err(|_| ())?;
-        url.to_file_path()
+        uri_to_path(self.as_str(), cfg!(windows)).map(PathBuf::from).ok_or(())
     }
 
 }
@@ -105,37 +155,141 @@ pub trait PathSanitizer {
 impl PathSanitizer for PathBuf {
 
     fn sanitize(&self) -> String {
-        let mut path = self.to_slash_lossy().to_string();
-
-        #[cfg(windows)]
-        {
-            // Check if path begin with a letter + ':'
-            if path.len() > 2 && path.chars().nth(1) == Some(':') {
-                let disk_letter = path.chars().next().unwrap().to_ascii_lowercase();
-                path.replace_range(0..1, &disk_letter.to_string());
-            }
-        }
-
-        path
+        self.as_path().sanitize()
     }
 }
 
 impl PathSanitizer for Path {
 
     fn sanitize(&self) -> String {
-        let mut path = self.to_slash_lossy().to_string();
-
-        #[cfg(windows)]
-        {
-            // Check if path begin with a letter + ':'
-            if path.len() > 2 && path.chars().nth(1) == Some(':') {
-                let disk_letter = path.chars().next().unwrap().to_ascii_lowercase();
-                path.replace_range(0..1, &disk_letter.to_string());
+        sanitize_path(&self.to_slash_lossy(), cfg!(windows))
+    }
+}
+
+/* Return the canonical form of a path, that is used as key for files and symbols:
+ - on Windows, separators are '/', drive letters are lowercase, long path prefixes ('\\?\', '\\?\UNC\') are removed
+ and WSL shares ('\\wsl$\Distro', '\\wsl.localhost\Distro') are written '//wsl.localhost/Distro',
+ - on other platforms, the path is kept as it is.
+The canonical form can be given to pathname2uri to get back the uri of the file. */
+pub fn sanitize_path(path: &str, windows: bool) -> String {
+    if !windows {
+        return path.to_string();
+    }
+    let mut path = path.replace('\\', "/");
+    for prefix in ["//?/UNC/", "//./UNC/"] {
+        if starts_with_ignore_case(&path, prefix) {
+            path = S!("//") + &path[prefix.len()..];
+        }
+    }
+    for prefix in ["//?/", "//./"] {
+        if starts_with_ignore_case(&path, prefix) {
+            path = path[prefix.len()..].to_string();
+        }
+    }
+    if starts_with_ignore_case(&path, "//wsl$/") {
+        path = S!("//wsl.localhost/") + &path["//wsl$/".len()..];
+    } else if starts_with_ignore_case(&path, "//wsl.localhost/") {
+        path = S!("//wsl.localhost/") + &path["//wsl.localhost/".len()..];
+    }
+    // Check if path begin with a letter + ':'
+    if path.len() > 2 && path.as_bytes()[1] == b':' && path.as_bytes()[0].is_ascii_alphabetic() {
+        path.replace_range(0..1, &path[0..1].to_ascii_lowercase());
+    }
+    path
+}
+
+/* Return the canonical path of a file uri, or None if the uri doesn't refer to a local file. Supported uris are:
+ - file:///home/user/file.py and file:///c%3A/Users/file.py (percent-encoded or not, with any case for the drive letter),
+ - file://server/share/file.py for UNC paths,
+ - vscode-remote://wsl+Distro/home/user/file.py for files in WSL. The path is the one of the WSL share on Windows, and
+ the path in the distribution on other platforms (the server then runs in WSL). */
+pub fn uri_to_path(uri: &str, windows: bool) -> Option<String> {
+    let url = url::Url::parse(uri).ok()?;
+    let path = percent_decode(url.path())?;
+    let host = match url.host_str() {
+        Some(host) => percent_decode(host)?,
+        None => S!(""),
+    };
+    match url.scheme() {
+        "file" => {},
+        "vscode-remote" => {
+            let distribution = host.strip_prefix("wsl+")?;
+            if !windows {
+                return Some(path);
             }
+            return Some(sanitize_path(&format!("//wsl.localhost/{}{}", distribution, path), true));
+        },
+        _ => return None
+    }
+    if !host.is_empty() && host != "localhost" {
+        if !windows {
+            return None; //UNC paths can't be opened directly on other platforms
+        }
+        return Some(sanitize_path(&format!("//{}{}", host, path), true));
+    }
+    if !windows {
+        return Some(path);
+    }
+    let path = path.strip_prefix('/').unwrap_or(&path);
+    Some(sanitize_path(path, true))
+}
+
+/* Return the uri of a canonical path, as returned by sanitize_path */
+pub fn path_to_uri(path: &str, windows: bool) -> String {
+    if windows && path.starts_with("//") {
+        //UNC path: the server is the host of the uri
+        return format!("file:{}", percent_encode(path));
+    }
+    if windows {
+        return format!("file:///{}", percent_encode(path));
+    }
+    format!("file://{}", percent_encode(path))
+}
+
+/* Return the path to use for file operations. On Windows, paths longer than MAX_PATH must be given with the '\\?\' prefix */
+pub fn to_long_path(path: &str) -> PathBuf {
+    const MAX_PATH: usize = 260;
+    if !cfg!(windows) || path.len() < MAX_PATH || path.starts_with("\\\\?\\") {
+        return PathBuf::from(path);
+    }
+    let path = path.replace('/', "\\");
+    match path.strip_prefix("\\\\") {
+        Some(unc) => PathBuf::from(format!("\\\\?\\UNC\\{}", unc)),
+        None => PathBuf::from(format!("\\\\?\\{}", path)),
+    }
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len() && s.is_char_boundary(prefix.len()) && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            res.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            res.push(bytes[i]);
+            i += 1;
         }
+    }
+    String::from_utf8(res).ok()
+}
 
-        path
+fn percent_encode(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/".contains(&byte) {
+            res.push(byte as char);
+        } else {
+            res += &format!("%{:02X}", byte);
+        }
     }
+    res
 }
 
 pub trait MaxTextSize {
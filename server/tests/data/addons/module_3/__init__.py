@@ -0,0 +1,2 @@
+from . import exports
+from . import models
@@ -0,0 +1,14 @@
+# -*- coding: utf-8 -*-
+{
+    'name' : 'Module 3',
+    'version' : '1.0',
+    'summary': 'Test Module 3',
+    'description': """
+Module 3
+====================
+Module with its constants in a separate file, like the OCA modules
+    """,
+    'depends' : ["module_1"],
+    'installable': True,
+    'license': 'LGPL-3',
+}
@@ -0,0 +1,7 @@
+DRAFT = "draft"
+
+STATES = [DRAFT, "confirmed", "done"]
+
+STATE_SELECTION = [(DRAFT, "Draft"), ("done", "Done")]
+
+PRIORITIES = {"0": "Normal", "1": "Urgent"}
@@ -0,0 +1,5 @@
+from .names import *
+from .tools import *
+from .helpers import *
+from .reexports import *
+from .temporaries import *
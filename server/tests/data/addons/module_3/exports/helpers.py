@@ -0,0 +1,6 @@
+__all__ = ("HELPER_1",)
+__all__ += ("HELPER_2",)
+
+HELPER_1 = 1
+HELPER_2 = 2
+HELPER_3 = 3
@@ -0,0 +1,8 @@
+ALL_NAMES = ["NAME_1", "NAME_2", "NAME_3", "NAME_4"]
+
+__all__ = ALL_NAMES[:2] + ALL_NAMES[-1:]
+
+NAME_1 = 1
+NAME_2 = 2
+NAME_3 = 3
+NAME_4 = 4
@@ -0,0 +1,7 @@
+from . import names
+
+__all__ = ["REEXPORT_1"]
+__all__.extend(names.__all__)
+
+REEXPORT_1 = 1
+REEXPORT_2 = 2
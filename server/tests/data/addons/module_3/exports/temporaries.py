@@ -0,0 +1,3 @@
+TEMPORARY_1 = 1
+KEPT_1 = TEMPORARY_1 + 1
+del TEMPORARY_1
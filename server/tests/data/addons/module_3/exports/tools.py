@@ -0,0 +1,10 @@
+__all__ = ["TOOL_1"]
+__all__ += ["TOOL_2"]
+__all__.append("TOOL_3")
+__all__.extend(("TOOL_4",))
+
+TOOL_1 = 1
+TOOL_2 = 2
+TOOL_3 = 3
+TOOL_4 = 4
+TOOL_5 = 5
@@ -0,0 +1,50 @@
+from . import states
+from . import injected
+from . import scope_rebuild
+from . import scope_shift
+from . import registry_usage
+from . import unpacked_calls
+from . import tuple_unpacking
+from . import guard_clauses
+from . import star_imports
+from . import parameters
+from . import decorators
+from . import import_fallbacks
+from . import with_statements
+from . import walrus
+from . import global_names
+from . import optional_imports
+from . import lazy_module
+from . import lazy_usage
+from . import type_aliases
+from . import lambdas
+from . import properties
+from . import overloads
+from . import async_functions
+from . import loop_targets
+from . import aug_assignments
+from . import signatures
+from . import outline
+from . import workspace_symbols
+from . import amounts
+from . import amount_exports
+from . import amount_usage
+from . import amount_alias
+from . import keyword_arguments
+from . import unresolved_imports
+from . import symlink_target
+from . import symlink_dependent
+from . import records
+from . import relations
+from . import branches
+from . import call_arity
+from . import reexport_origin
+from . import reexport_star
+from . import reexport_level1
+from . import reexport_usage
+from . import reexport_cycle
+from . import reexport_cycle_back
+from . import deferred_partner
+from . import deferred_invoice
+from . import deprecated_decorators
+from . import field_name_refs
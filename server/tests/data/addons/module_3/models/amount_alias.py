@@ -0,0 +1,5 @@
+from .amounts import round_amount as rounded
+
+
+def print_rounded(amount):
+    return rounded(amount)
@@ -0,0 +1,3 @@
+from .amounts import format_amount
+
+__all__ = ["format_amount"]
@@ -0,0 +1,5 @@
+from .amount_exports import format_amount
+
+
+def print_total(total):
+    return format_amount(total)
@@ -0,0 +1,6 @@
+def format_amount(amount):
+    return "%.2f" % amount
+
+
+def round_amount(amount):
+    return round(amount, 2)
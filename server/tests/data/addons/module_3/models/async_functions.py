@@ -0,0 +1,42 @@
+class Response:
+
+    def text(self):
+        return "text"
+
+
+class Stream:
+
+    def __aiter__(self):
+        return self
+
+    async def __anext__(self):
+        return Response()
+
+
+class Session:
+
+    async def __aenter__(self):
+        return self
+
+    async def __aexit__(self, *args):
+        return None
+
+    async def fetch(self):
+        return Response()
+
+    async def read(self):
+        response = await self.fetch()
+        pending = self.fetch()
+        awaited = await pending
+        async with Session() as session:
+            opened = session
+        async for chunk in Stream():
+            received = chunk
+        return response
+
+
+async def get_response():
+    return Response()
+
+
+coroutine = get_response()
@@ -0,0 +1,14 @@
+import os
+
+if os.environ.get("ODOO_TOTAL"):
+    total = 10
+total -= 2
+
+ratio = 4
+ratio /= 2
+
+label = "item"
+label *= 2
+
+delta -= 1
+paths += ["static"]
@@ -0,0 +1,41 @@
+class Foo:
+    def foo(self):
+        pass
+
+    def common(self):
+        pass
+
+
+class Bar:
+    def bar(self):
+        pass
+
+    def common(self):
+        pass
+
+
+def pick(flag):
+    x = None
+    if flag:
+        x = Foo()
+    else:
+        x = Bar()
+        in_else = x
+    after = x
+    after.common()
+    return after
+
+
+def load(data):
+    try:
+        y = Foo()
+    except ValueError:
+        y = Bar()
+    loaded = y
+    for item in data:
+        z = Foo()
+        break
+    else:
+        z = Bar()
+    looped = z
+    return loaded, looped
@@ -0,0 +1,64 @@
+from odoo import api
+
+
+def log_calls(func):
+    def wrapper(*args, **kwargs):
+        return func(*args, **kwargs)
+    return wrapper
+
+
+def add(a, b):
+    return a + b
+
+
+def add_all(*args, **kwargs):
+    return add(*args, **kwargs)
+
+
+class Amount:
+
+    def total(self, amount, rate=1.0):
+        return amount * rate
+
+    @classmethod
+    def make(cls, name):
+        return cls()
+
+    @staticmethod
+    def convert(amount, currency):
+        return amount
+
+    @api.model
+    def default_rate(self, currency):
+        return 1.0
+
+    @log_calls
+    def logged(self, value):
+        return value
+
+    def forward(self, amount, **kwargs):
+        return self.total(amount, **kwargs)
+
+
+class TaxedAmount(Amount):
+
+    def total(self, amount, rate=1.0):
+        return super().total(amount, rate=rate) * 1.2
+
+
+def check_arity():
+    amount = Amount()
+    add(1)
+    add(1, 2, 3)
+    add(1, c=2)
+    add(1, a=2)
+    amount.total(1, amount=2)
+    amount.total()
+    Amount.make("x")
+    amount.make("x")
+    Amount.convert(1, "EUR")
+    amount.default_rate("EUR")
+    amount.logged(1, 2, 3)
+    add_all(1, 2, 3)
+    amount.forward(1, rate=2)
+    TaxedAmount().total(1, rate=2)
@@ -0,0 +1,36 @@
+import functools
+from functools import cached_property as cp
+
+from odoo import api, fields, models
+
+static = staticmethod
+
+
+class Display:
+    label = "display"
+
+
+class Decorated(models.Model):
+    _name = "module_3.decorated"
+    _description = "Decorated"
+
+    name = fields.Char(compute="_compute_name")
+
+    @cp
+    def display(self):
+        return Display()
+
+    @functools.cached_property
+    def other_display(self):
+        return Display()
+
+    @static
+    def build():
+        return Display()
+
+    @api.depends("display")
+    def _compute_name(self):
+        label = self.display.label
+        other_label = self.other_display.label
+        for record in self:
+            record.name = label or other_label
@@ -0,0 +1,23 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from .deferred_partner import Partner
+
+
+class Invoice:
+
+    def get_partner(self) -> Partner:
+        raise NotImplementedError()
+
+    def get_line(self) -> InvoiceLine:
+        raise NotImplementedError()
+
+
+class InvoiceLine:
+    pass
+
+
+partner = Invoice().get_partner()
+line = Invoice().get_line()
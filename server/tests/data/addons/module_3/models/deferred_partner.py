@@ -0,0 +1,22 @@
+# the classes of the return annotations are imported for the type checkers only, as the modules import each other
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from .deferred_invoice import Invoice
+
+
+class Partner:
+
+    def get_invoice(self) -> "Invoice":
+        raise NotImplementedError()
+
+    def get_address(self) -> "Address":
+        raise NotImplementedError()
+
+
+class Address:
+    pass
+
+
+invoice = Partner().get_invoice()
+address = Partner().get_address()
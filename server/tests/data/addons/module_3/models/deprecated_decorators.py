@@ -0,0 +1,12 @@
+from odoo import api, models
+
+
+class DeprecatedDecorators(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    @api.multi
+    def action_multi(self):
+        return True
+
+    def name_get(self):
+        return []
@@ -0,0 +1,10 @@
+from odoo import models
+
+
+class FieldNameRefs(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    def _read_names(self):
+        self.read(["f1"])
+        self.mapped("f1")
+        return self._read_group([], ["f1"], ["__count"], order="f1 desc")
@@ -0,0 +1,20 @@
+import logging
+
+_logger = None
+
+
+def setup_logging():
+    global _logger, _registry
+    _logger = logging.getLogger(__name__)
+    _registry = {}
+
+
+def counter():
+    count = 0
+
+    def increment():
+        nonlocal count
+        count = str(count)
+        return count
+
+    return increment
@@ -0,0 +1,31 @@
+from odoo import fields, models
+from odoo.exceptions import UserError
+
+
+class GuardClauses(models.Model):
+    _name = "module_3.guard_clauses"
+    _description = "Guard clauses"
+
+    partner_id = fields.Many2one("res.partner")
+
+    def check_guards(self, flag):
+        state = None
+        if flag:
+            state = "draft"
+        if state is None:
+            raise UserError("state required")
+        checked_state = state
+        qty = 0
+        if flag:
+            qty = 5
+        assert qty, "qty required"
+        checked_qty = qty
+        label = "default"
+        if not flag:
+            label = None
+            raise UserError("flag required")
+        checked_label = label
+        if not self.partner_id:
+            raise UserError("partner required")
+        email = self.partner_id.email
+        return checked_state, checked_qty, checked_label, email
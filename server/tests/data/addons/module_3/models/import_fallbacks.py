@@ -0,0 +1,16 @@
+try:
+    import simplejson as json
+except ImportError:
+    import json
+
+try:
+    import ujson as fast_json
+except:
+    fast_json = None
+
+
+def dumps(value):
+    try:
+        return json.dumps(value)
+    except (TypeError, ValueError) as error:
+        return str(error)
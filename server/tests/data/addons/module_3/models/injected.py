@@ -0,0 +1,10 @@
+from odoo import fields, models
+
+
+class InjectedNote(models.Model):
+    _inherit = ["pygls.tests.states", "pygls.tests.m_name"]
+
+    note = fields.Char()
+
+    def _get_note(self):
+        return self.note or self.f1 or self.state
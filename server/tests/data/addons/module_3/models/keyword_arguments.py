@@ -0,0 +1,22 @@
+from odoo import fields
+from .parameters import Finder
+
+finder = Finder()
+finder.search()
+finder.search([], order="name", )
+finder.search([], 10, )
+
+
+class Wrapper:
+
+    def __init__(self, name, size=0):
+        self.name = name
+
+    def forward(self, **kwargs):
+        return Finder().search([], **kwargs)
+
+
+wrapper = Wrapper()
+sized = Wrapper(si)
+wrapper.forward()
+code = fields.Char()
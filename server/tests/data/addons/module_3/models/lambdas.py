@@ -0,0 +1,14 @@
+class Scaler:
+    factor = 2.0
+    scale = lambda self, value: self
+
+    def rescale(self):
+        return self.scale(self.factor)
+
+
+get_name = lambda: "name"
+double = lambda value, times=2: value * times
+nested = lambda value: lambda other: value
+
+name = get_name()
+rescaled = Scaler().rescale()
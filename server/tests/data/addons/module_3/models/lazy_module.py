@@ -0,0 +1,11 @@
+# a module giving its attributes with a __getattr__ function (PEP 562)
+
+
+class LazyValue:
+
+    def get(self):
+        return 1
+
+
+def __getattr__(name) -> LazyValue:
+    return LazyValue()
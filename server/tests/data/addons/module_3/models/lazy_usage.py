@@ -0,0 +1,6 @@
+from . import lazy_module
+from .lazy_module import anything_imported
+
+value = lazy_module.anything
+imported = anything_imported
+declared = lazy_module.LazyValue
@@ -0,0 +1,27 @@
+class Line:
+
+    def total(self):
+        return 0
+
+
+class Order:
+
+    def read_lines(self, lines: list[Line], prices: dict[str, float]):
+        for line in lines:
+            current = line
+        for name, price in prices.items():
+            label = name
+            amount = price
+        for index, line in enumerate(lines):
+            position = index
+            enumerated = line
+        for number in range(3):
+            counted = number
+        for key, value in {"a": 1, "b": 2}.items():
+            literal_key = key
+            literal_value = value
+        for pair in [(1, "a"), (2, "b")]:
+            paired = pair
+        for first, second in [(1, "a"), (2, "b")]:
+            unpacked = second
+        return lines
@@ -0,0 +1,12 @@
+# star imports of optional dependencies, in blocks at the module level
+USE_LXML = True
+
+if USE_LXML:
+    try:
+        from lxml.etree import *
+    except ImportError:
+        pass
+
+
+def parse_document(content):
+    return fromstring(content)
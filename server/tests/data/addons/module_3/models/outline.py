@@ -0,0 +1,33 @@
+import os
+from typing import Optional
+
+MAX_SIZE = 10
+default_name = "outline"
+
+
+class Outline:
+    limit = 5
+
+    class Meta:
+        ordering = "name"
+
+        def describe(self):
+            return self.ordering
+
+    @property
+    def size(self):
+        return self.limit
+
+    @size.setter
+    def size(self, value):
+        self.limit = value
+
+    def resize(self, factor):
+        def scale(value):
+            return value * factor
+        return scale(self.limit)
+
+
+def build(name: Optional[str] = None):
+    path = os.path.join("outlines", name or default_name)
+    return Outline()
@@ -0,0 +1,38 @@
+from typing import Literal, overload
+import typing
+
+
+@overload
+def read(mode: Literal["r"]) -> str: ...
+@overload
+def read(mode: Literal["rb"]) -> bytes: ...
+def read(mode):
+    return open("data", mode).read()
+
+
+@typing.overload
+def convert(value: int) -> int: ...
+@typing.overload
+def convert(value: int, scale: float) -> float: ...
+def convert(value, scale=None):
+    return value if scale is None else value * scale
+
+
+class Reader:
+
+    @overload
+    def get(self, key: Literal["name"]) -> str: ...
+    @overload
+    def get(self, key: Literal["size"]) -> int: ...
+    def get(self, key):
+        return getattr(self, key)
+
+    def name(self):
+        return self.get("name")
+
+
+text = read("r")
+data = read("rb")
+unknown = read("w")
+whole = convert(1)
+scaled = convert(1, 2.0)
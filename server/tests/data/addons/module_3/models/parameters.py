@@ -0,0 +1,39 @@
+from odoo import models
+
+DEFAULT_ORDER = "name"
+
+
+def mixed_parameters(a, b=1, /, c=2, *args, d, e=3, **kwargs):
+    return kwargs.get('f', args)
+
+
+def keyword_only(*, key):
+    return key
+
+
+class Parameters(models.Model):
+    _name = "module_3.parameters"
+    _description = "Parameters"
+
+    def _star_only(self, *args):
+        return args
+
+    def write(self, vals, **kwargs):
+        mixed_parameters(0, d=4)
+        mixed_parameters(0, 1, 2, 3, d=4, f=5)
+        self._star_only(1, 2)
+        return super().write(vals)
+
+
+class Finder:
+
+    def search(self, domain, offset=0, limit=None, order=DEFAULT_ORDER, fields=[]):
+        return domain[offset:limit], order, fields
+
+    @classmethod
+    def create_multi(cls, vals):
+        return cls()
+
+
+def create_finder():
+    return Finder.create_multi([])
@@ -0,0 +1,19 @@
+class Temperature:
+
+    def __init__(self):
+        self._celsius = 0.0
+
+    @property
+    def celsius(self):
+        return 0.0
+
+    @celsius.setter
+    def celsius(self, value):
+        self._celsius = value
+
+    @celsius.deleter
+    def celsius(self):
+        del self._celsius
+
+    def read(self):
+        return self.celsius
@@ -0,0 +1,38 @@
+from dataclasses import KW_ONLY, dataclass, field
+from typing import ClassVar, NamedTuple
+
+
+@dataclass(frozen=True)
+class Line:
+    product: str
+    qty: int = 1
+    taxes: list = field(default_factory=list)
+    count: ClassVar[int] = 0
+    _: KW_ONLY
+    note: str = ""
+
+
+@dataclass
+class DiscountedLine(Line):
+    discount: float = field(default=0.0, kw_only=True)
+    qty: int = 2
+    total: float = field(init=False, default=0.0)
+
+
+class Point(NamedTuple):
+    x: int
+    y: str = "origin"
+
+
+class Record:
+
+    def unpack(self):
+        point = Point(1)
+        a, b = point
+        c, d = Point(2, "end")
+        return a, b, c, d
+
+
+line = Line("desk", 2, note="urgent")
+DiscountedLine("desk", discount=0.5)
+Point(1, y="up")
@@ -0,0 +1,5 @@
+# a name importing itself through a circular import
+from .reexport_cycle_back import Loop
+
+
+looped = Loop
@@ -0,0 +1 @@
+from .reexport_cycle import Loop
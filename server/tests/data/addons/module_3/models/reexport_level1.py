@@ -0,0 +1 @@
+from .reexport_star import Origin
@@ -0,0 +1,2 @@
+# re-exports the names of reexport_origin
+from .reexport_origin import *
@@ -0,0 +1,4 @@
+from .reexport_level1 import Origin
+
+
+value = Origin()
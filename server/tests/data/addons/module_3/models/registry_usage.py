@@ -0,0 +1,31 @@
+from odoo import api, models
+
+
+class RegistryUsage(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    # The models of the registry are walked like in odoo/models.py
+
+    @api.model
+    def _get_model_names(self):
+        names = []
+        for model_name in self.env:
+            names.append(model_name.lower())
+        for model_name in self.env.registry:
+            names.append(model_name.strip())
+        return names
+
+    @api.model
+    def _get_inherits_children(self):
+        models = self.env.registry.models
+        return [name for name in models.keys() if models.get(name) is not None]
+
+    @api.model
+    def _check_registry(self):
+        registry = self.env.registry
+        if not registry.ready:
+            return False
+        if "module_3" not in registry._init_modules:
+            registry._init_modules.add("module_3")
+        model = self.pool["pygls.tests.m_name"]
+        return model._name == registry["pygls.tests.m_name"]._name
@@ -0,0 +1,26 @@
+from odoo import fields, models
+
+
+class Customer(models.Model):
+    _name = "pygls.tests.customer"
+    _description = "Customer"
+
+    name = fields.Char()
+    credit = fields.Integer()
+    since = fields.Date()
+
+
+class Order(models.Model):
+    _name = "pygls.tests.order"
+    _description = "Order"
+
+    customer_id = fields.Many2one("pygls.tests.customer")
+    customer_name = fields.Char(related="customer_id.name")
+    carrier_id = fields.Many2one(comodel_name="pygls.tests.not_loaded")
+
+    def get_customer(self):
+        name = self.customer_id.name
+        credit = self.customer_id.credit
+        since = self.customer_id.since
+        carrier = self.carrier_id
+        return name
@@ -0,0 +1,16 @@
+from odoo import models
+
+
+class ScopeRebuild(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    def _get_label(self, prefix):
+        label = prefix + self.f1
+        return label
+
+    def _get_code(self):
+        code = self._get_label("x")
+        return code
+
+
+LABEL = "label"
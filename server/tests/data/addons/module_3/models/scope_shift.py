@@ -0,0 +1,13 @@
+from odoo import models
+
+
+class ScopeShift(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    def _get_name(self):
+        name = self.f1
+        return name
+
+    def _get_module(self):
+        import not_a_real_module
+        return not_a_real_module
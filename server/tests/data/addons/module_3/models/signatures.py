@@ -0,0 +1,28 @@
+from typing import Literal, overload
+
+
+def compute(amount, rate=0.5, *values, rounding=None, **options):
+    """Compute an amount"""
+    return amount
+
+
+class Reader:
+
+    def __init__(self, path, encoding="utf-8"):
+        self.path = path
+
+    def apply(self, factor, label="x"):
+        return factor
+
+    @overload
+    def read_as(self, mode: Literal['r']) -> str: ...
+    @overload
+    def read_as(self, mode: Literal['rb']) -> bytes: ...
+    def read_as(self, mode):
+        return None
+
+
+reader = Reader("data.csv")
+compute(1, 2, rounding=3)
+reader.apply(2, label="y")
+reader.read_as('rb')
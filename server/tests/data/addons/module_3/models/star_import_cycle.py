@@ -0,0 +1,3 @@
+from .star_imports import *
+
+CYCLE_VALUE = 2
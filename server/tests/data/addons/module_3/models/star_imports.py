@@ -0,0 +1,5 @@
+# the names of a file imported in the file itself, and by a file it imports
+from .star_imports import *
+from .star_import_cycle import *
+
+STAR_VALUE = 1
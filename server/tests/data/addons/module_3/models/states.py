@@ -0,0 +1,17 @@
+from odoo import api, fields, models
+from ..constants import STATES, STATE_SELECTION, PRIORITIES
+
+
+class StatesModel(models.Model):
+    _name = "pygls.tests.states"
+    _description = "States Model"
+
+    state = fields.Selection([(s, s.title()) for s in STATES])
+    simple_state = fields.Selection(STATE_SELECTION)
+    priority = fields.Selection([(key, label) for key, label in PRIORITIES.items()])
+    priority_key = fields.Selection([(key, key) for key in PRIORITIES])
+    dynamic_state = fields.Selection("_get_states")
+    partner_id = fields.Many2one("res.partner", domain=lambda self: [("id", "!=", self.env.uid)])
+
+    def _get_states(self):
+        return []
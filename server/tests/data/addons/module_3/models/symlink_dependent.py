@@ -0,0 +1,5 @@
+from .symlink_target import compute_total
+
+
+def print_total(lines):
+    return compute_total(lines)
@@ -0,0 +1,2 @@
+def compute_total(lines):
+    return sum(lines)
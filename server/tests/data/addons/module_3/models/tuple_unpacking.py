@@ -0,0 +1,25 @@
+from odoo import models
+
+
+class TupleUnpacking(models.Model):
+    _name = "module_3.tuple_unpacking"
+    _description = "Tuple unpacking"
+
+    def _get_qty_price(self):
+        if self.id:
+            return 1, "price"
+        return (2, "other")
+
+    def _get_values(self):
+        return self._get_qty_price()
+
+    def _get_partner_qty(self):
+        qty, price = self._get_values()
+        return self.env['res.partner'], qty
+
+    def compute_values(self):
+        partner, qty = self._get_partner_qty()
+        first, *others = self._get_values()
+        a, b, c = self._get_qty_price()
+        name, count = self.read()
+        return partner, qty, first, name
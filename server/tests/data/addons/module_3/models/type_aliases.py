@@ -0,0 +1,7 @@
+from typing import TypeAlias
+
+type Vector = list[float]
+type Pair[T] = tuple[T, T]
+Number: TypeAlias = int | float
+Size = int
+count: int = 0
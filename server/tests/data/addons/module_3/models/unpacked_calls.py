@@ -0,0 +1,35 @@
+from typing import TypedDict
+
+from odoo import models
+
+
+class LabelVals(TypedDict):
+    name: str
+    color: int
+
+
+class UnpackedCalls(models.Model):
+    _name = "module_3.unpacked_calls"
+    _description = "Unpacked calls"
+
+    def _make_label(self, name, code=False):
+        return name, code
+
+    def _prepare_label_vals(self):
+        return {'name': str(self.id), 'code': 'A'}
+
+    def _prepare_colored_vals(self):
+        if self.id:
+            return {'name': str(self.id), 'color': 1}
+        return {'color': 2, 'name': ''}
+
+    def make_labels(self, vals: LabelVals, options):
+        self._make_label(*['a', 'b'])
+        self._make_label(*('a', 'b', 'c'))
+        self._make_label(**{'name': 'a'})
+        self._make_label(**{'label': 'a'})
+        self._make_label(**self._prepare_label_vals())
+        self._make_label(**self._prepare_colored_vals())
+        self._make_label(**vals)
+        self._make_label(*self.ids)
+        self._make_label(**options)
@@ -0,0 +1,3 @@
+from .missing_package import missing_name
+from .amounts import format_amout
+from .Amounts import round_amount
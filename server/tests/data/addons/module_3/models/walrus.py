@@ -0,0 +1,8 @@
+def read_values(values, text):
+    if (size := len(values)) > 2:
+        return size
+    while (remaining := int(text)) > 0:
+        text = str(remaining - 1)
+        last = remaining
+    labels = [label for value in values if (label := str(value))]
+    return labels, last
@@ -0,0 +1,43 @@
+class Cursor:
+
+    def execute(self, query):
+        return query
+
+
+class Connection:
+
+    def __enter__(self):
+        return Cursor()
+
+    def __exit__(self, *args):
+        return False
+
+
+class AsyncConnection:
+
+    async def __aenter__(self):
+        return Cursor()
+
+    async def __aexit__(self, *args):
+        return False
+
+
+class Plain:
+    pass
+
+
+with Plain() as plain, Connection() as (first, second):
+    class Job:
+
+        def run(self):
+            return plain
+
+
+def query():
+    with Connection() as cursor:
+        return cursor.execute("select 1")
+
+
+async def fetch():
+    async with AsyncConnection() as cursor:
+        return cursor
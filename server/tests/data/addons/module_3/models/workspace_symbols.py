@@ -0,0 +1,15 @@
+REPORT_FORMATS = ["pdf", "html"]
+
+
+class InvoiceReport:
+
+    def render_invoice(self):
+        return REPORT_FORMATS[0]
+
+
+class Invoice:
+    pass
+
+
+def print_invoice_report(invoice):
+    return invoice
@@ -0,0 +1,14 @@
+# -*- coding: utf-8 -*-
+{
+    'name' : 'Module 4',
+    'version' : '1.0',
+    'summary': 'Test Module 4',
+    'description': """
+Module 4
+====================
+Module depending on a module that is not found and on a module that is not installable
+    """,
+    'depends' : ["module_1", "module_5", "module_not_found"],
+    'installable': True,
+    'license': 'LGPL-3',
+}
@@ -0,0 +1 @@
+from . import graph_imports
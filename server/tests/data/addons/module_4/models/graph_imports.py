@@ -0,0 +1,2 @@
+from odoo.addons.module_1 import models as module_1_models
+from odoo.addons.module_2 import models as module_2_models
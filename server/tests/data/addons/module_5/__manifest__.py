@@ -0,0 +1,14 @@
+# -*- coding: utf-8 -*-
+{
+    'name' : 'Module 5',
+    'version' : '1.0',
+    'summary': 'Test Module 5',
+    'description': """
+Module 5
+====================
+Module that is not installable
+    """,
+    'depends' : ["module_1"],
+    'installable': False,
+    'license': 'LGPL-3',
+}
@@ -0,0 +1,18 @@
+# -*- coding: utf-8 -*-
+{
+    'name' : 'Module 6',
+    'version' : '1.0',
+    'summary': 'Test Module 6',
+    'description': """
+Module 6
+====================
+Module with data files and an init hook declared in its manifest
+    """,
+    'depends' : ["module_1"],
+    'data': [
+        "views/views.xml",
+    ],
+    'post_init_hook': "post_init",
+    'installable': True,
+    'license': 'LGPL-3',
+}
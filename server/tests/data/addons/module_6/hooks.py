@@ -0,0 +1,2 @@
+def post_init(env):
+    pass
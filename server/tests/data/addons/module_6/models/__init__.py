@@ -0,0 +1,8 @@
+from . import contributions
+from . import snippets
+from . import environments
+from . import chain_c
+from . import chain_b
+from . import chain_a
+from . import cycle_a
+from . import cycle_b
@@ -0,0 +1,5 @@
+class Target:
+    pass
+
+
+target = Target()
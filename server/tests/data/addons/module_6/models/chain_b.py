@@ -0,0 +1,3 @@
+from .chain_a import target
+
+alias = target
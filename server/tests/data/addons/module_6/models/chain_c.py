@@ -0,0 +1,3 @@
+from .chain_b import alias
+
+final = alias
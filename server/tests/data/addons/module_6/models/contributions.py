@@ -0,0 +1,11 @@
+from odoo import models
+
+
+class ContributedModel(models.Model):
+    _inherit = "pygls.tests.m_name"
+
+    def func_1(self):
+        return super().func_1()
+
+    def action_check(self):
+        self.func_1()
@@ -0,0 +1,3 @@
+from .cycle_b import second
+
+first = second
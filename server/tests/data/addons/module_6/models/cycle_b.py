@@ -0,0 +1,3 @@
+from .cycle_a import first
+
+second = first
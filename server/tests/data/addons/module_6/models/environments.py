@@ -0,0 +1,5 @@
+from odoo.api import Environment
+
+env = Environment(None, 1, {})
+sudo_env = env(su=True)
+user_env = env(user=2, context={})
@@ -0,0 +1,12 @@
+from odoo import api, fields, models
+
+
+class SnippetModel(models.Model):
+  _inherit = "pygls.tests.m_name"
+
+  many
+
+  comp
+
+
+new
@@ -0,0 +1,26 @@
+use ruff_python_ast::{Mod, ModModule, Stmt};
+use ruff_python_parser::Mode;
+
+/* Parse the source as a module, recovering from the syntax errors like the server does for the files */
+pub fn parse_module(source: &str) -> ModModule {
+    let ast = ruff_python_parser::parse_unchecked(source, Mode::Module);
+    let Mod::Module(module) = ast.into_syntax() else {
+        panic!("Not a module");
+    };
+    module
+}
+
+/* Return the statements of the source */
+pub fn parse(source: &str) -> Vec<Stmt> {
+    parse_module(source).body
+}
+
+/* Return the statements of the source, that must not have syntax errors */
+pub fn parse_valid(source: &str) -> Vec<Stmt> {
+    let ast = ruff_python_parser::parse_unchecked(source, Mode::Module);
+    assert!(ast.errors().is_empty(), "Invalid source: {}", source);
+    let Mod::Module(module) = ast.into_syntax() else {
+        panic!("Not a module");
+    };
+    module.body
+}
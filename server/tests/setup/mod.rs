@@ -1 +1,7 @@
-pub mod setup;
\ No newline at end of file
+/* The test binaries each use a part of the shared helpers */
+#[allow(dead_code)]
+pub mod setup;
+#[allow(dead_code)]
+pub mod ast;
+#[allow(dead_code)]
+pub mod symbols;
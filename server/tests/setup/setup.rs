@@ -1,34 +1,45 @@
 use std::env;
 
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 
-use odoo_ls_server::{core::{config::{Config, DiagMissingImportsMode}, odoo::SyncOdoo}, threads::SessionInfo, utils::PathSanitizer as _};
+use odoo_ls_server::{core::{config::{Config, DiagMissingImportsMode}, integrity::IntegrityChecker, odoo::SyncOdoo}, threads::SessionInfo, utils::PathSanitizer as _};
 
 use odoo_ls_server::S;
 use tracing::{info, level_filters::LevelFilter};
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::RollingFileAppender;
 use tracing_subscriber::{fmt, layer::SubscriberExt, FmtSubscriber};
 
-pub fn setup_server() -> SyncOdoo {
+static TRACING_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
 
-    let file_appender = RollingFileAppender::builder()
-        .max_log_files(20) // only the most recent 5 log files will be kept
-        .filename_prefix(format!("odoo_tests_logs_{}", std::process::id()))
-        .filename_suffix("log")
-        .build("./logs")
-        .expect("failed to initialize rolling file appender");
-    let (file_writer, _guard) = tracing_appender::non_blocking(file_appender);
-    let subscriber = FmtSubscriber::builder()
-        .with_thread_ids(true)
-        .with_file(false)
-        .with_max_level(LevelFilter::INFO)
-        .with_ansi(false)
-        .with_writer(file_writer)
-        .finish();
-    let stdout_subscriber = fmt::layer().with_writer(std::io::stdout).with_ansi(true);
-    tracing::subscriber::set_global_default(subscriber.with(stdout_subscriber)).expect("Unable to set default tracing subscriber");
+/* The subscriber is global to the test binary, so it is only installed by the first server of the binary. The guard of the
+file writer is kept for the whole run, to not lose the logs of the next servers. */
+fn setup_tracing() {
+    TRACING_GUARD.get_or_init(|| {
+        let file_appender = RollingFileAppender::builder()
+            .max_log_files(20) // only the most recent 5 log files will be kept
+            .filename_prefix(format!("odoo_tests_logs_{}", std::process::id()))
+            .filename_suffix("log")
+            .build("./logs")
+            .expect("failed to initialize rolling file appender");
+        let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+        let subscriber = FmtSubscriber::builder()
+            .with_thread_ids(true)
+            .with_file(false)
+            .with_max_level(LevelFilter::INFO)
+            .with_ansi(false)
+            .with_writer(file_writer)
+            .finish();
+        let stdout_subscriber = fmt::layer().with_writer(std::io::stdout).with_ansi(true);
+        tracing::subscriber::set_global_default(subscriber.with(stdout_subscriber)).expect("Unable to set default tracing subscriber");
+        guard
+    });
+}
 
+pub fn setup_server() -> SyncOdoo {
+    setup_tracing();
 
     let community_path = env::var("COMMUNITY_PATH").expect("Please provide COMMUNITY_PATH environment variable with a valid path to your Odoo Community folder");
     info!("Community path: {:?}", community_path);
@@ -46,10 +57,13 @@ pub fn setup_server() -> SyncOdoo {
     config.refresh_mode = odoo_ls_server::core::config::RefreshMode::Off;
     config.diag_missing_imports = DiagMissingImportsMode::All;
     config.no_typeshed = false;
+    config.arch_cache = false; //the tests don't depend on the entries written by the previous runs
 
     let (s, r) = crossbeam_channel::unbounded();
     let mut session = SessionInfo::new_from_custom_channel(s, r, &mut server);
     SyncOdoo::init(&mut session, config);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "The symbol tree is inconsistent after the setup:\n{}", violations.join("\n"));
 
     server
 }
\ No newline at end of file
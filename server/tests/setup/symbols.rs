@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+
+/* Return the last declaration of the name in the scope */
+pub fn get_variable(scope: &Rc<RefCell<Symbol>>, name: &str) -> Rc<RefCell<Symbol>> {
+    let variable = scope.borrow().get_content_symbol(name, u32::MAX).pop();
+    variable.expect("variable not found")
+}
+
+/* Return the declarations of the name in all the sections of the scope */
+pub fn get_declarations(scope: &Rc<RefCell<Symbol>>, name: &str) -> Vec<Rc<RefCell<Symbol>>> {
+    scope.borrow().all_symbols().filter(|symbol| symbol.borrow().name() == name).collect()
+}
+
+/* Return the names of the types a symbol is evaluated to and whether they are instances, following the variables, in
+the order of its evaluations */
+pub fn get_types(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Vec<(String, bool)> {
+    let evaluations = symbol.borrow().evaluations().cloned().unwrap_or_default();
+    let mut res = vec![];
+    for evaluation in evaluations.iter() {
+        let value = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+        for value in Symbol::follow_ref(&value, session, &mut None, false, false, None, &mut vec![]).iter() {
+            if let Some(symbol) = value.weak.upgrade() {
+                res.push((symbol.borrow().name().clone(), value.instance.unwrap_or(false)));
+            }
+        }
+    }
+    res
+}
+
+/* Return the names of the types a symbol is evaluated to, following the variables, sorted */
+pub fn get_type_names(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Vec<String> {
+    let mut res: Vec<String> = get_types(session, symbol).into_iter().map(|(name, _)| name).collect();
+    res.sort();
+    res
+}
+
+/* Return the names of the types of all the declarations of the name in the scope, following the variables, in the
+order of their evaluations */
+pub fn get_declared_type_names(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, name: &str) -> Vec<String> {
+    let mut res = vec![];
+    for variable in get_declarations(scope, name).iter() {
+        res.extend(get_types(session, variable).into_iter().map(|(name, _)| name));
+    }
+    res
+}
+
+/* Return the names of the symbols given by the evaluations of a symbol, without following the variables */
+pub fn get_evaluated_names(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Vec<String> {
+    let evaluations = symbol.borrow().evaluations().cloned().unwrap_or_default();
+    evaluations.iter()
+        .filter_map(|evaluation| evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade())
+        .map(|symbol| symbol.borrow().name().clone())
+        .collect()
+}
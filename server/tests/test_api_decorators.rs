@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use ruff_python_ast::Stmt;
+use odoo_ls_server::core::api_decorators::ApiDecorators;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_class_body(code: &str) -> Vec<Stmt> {
+    let Some(Stmt::ClassDef(class)) = parse(code).into_iter().next() else {
+        panic!("Not a class");
+    };
+    class.body
+}
+
+#[test]
+fn test_find_api_decorators() {
+    let body = parse_class_body("\
+class A(models.Model):
+    @api.returns('res.partner', lambda value: value.id)
+    @odoo.api.depends_context('company', 'lang')
+    @api.ondelete
+    def f(self):
+        pass
+");
+    let Stmt::FunctionDef(func) = &body[0] else {
+        panic!("Not a function");
+    };
+    let (_, call) = ApiDecorators::find(&func.decorator_list, "returns").unwrap();
+    assert_eq!(ApiDecorators::get_returns_model(call.unwrap()).unwrap().value.to_str(), "res.partner");
+    let (_, call) = ApiDecorators::find(&func.decorator_list, "depends_context").unwrap();
+    let keys: Vec<&str> = ApiDecorators::get_context_keys(call.unwrap()).iter().map(|k| k.value.to_str()).collect();
+    assert_eq!(keys, vec!["company", "lang"]);
+    //without arguments, the decorator is found but has no call
+    let (_, call) = ApiDecorators::find(&func.decorator_list, "ondelete").unwrap();
+    assert!(call.is_none());
+    assert!(ApiDecorators::find(&func.decorator_list, "depends").is_none());
+}
+
+#[test]
+fn test_used_context_keys() {
+    let body = parse_class_body("\
+class A(models.Model):
+    def f(self):
+        self.with_context(skip_check=True, **kwargs)
+        self.with_context({'from_wizard': True})
+        self.with_context(dict(self.env.context, mail_create_nolog=True))
+        if self.env.context.get('my_flag') or self._context['other_flag']:
+            pass
+        values.get('not_a_context_key')
+");
+    let keys = ApiDecorators::get_used_context_keys(&body);
+    let expected: HashSet<String> = ["skip_check", "from_wizard", "mail_create_nolog", "my_flag", "other_flag"].iter().map(|k| k.to_string()).collect();
+    assert_eq!(keys, expected);
+    assert!(ApiDecorators::is_known_context_key("company", &keys));
+    assert!(ApiDecorators::is_known_context_key("default_partner_id", &keys));
+    assert!(ApiDecorators::is_known_context_key("my_flag", &keys));
+    assert!(!ApiDecorators::is_known_context_key("compagny", &keys));
+    assert!(!ApiDecorators::is_known_context_key("default_", &keys));
+}
@@ -0,0 +1,93 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::config::{Config, DiagMissingImportsMode, RefreshMode};
+use odoo_ls_server::core::evaluation::EvaluationSymbolPtr;
+use odoo_ls_server::core::integrity::IntegrityChecker;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+/* Build a server whose arch cache is in the given directory */
+fn build_server(cache_dir: &PathBuf, arch_cache: bool) -> SyncOdoo {
+    let community_path = env::var("COMMUNITY_PATH").expect("Please provide COMMUNITY_PATH environment variable with a valid path to your Odoo Community folder");
+    let mut server = SyncOdoo::new();
+    server.load_odoo_addons = false;
+    server.arch_cache.set_dir(cache_dir.clone());
+    let mut config = Config::new();
+    config.addons = vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").sanitize()];
+    config.odoo_path = community_path;
+    config.python_path = S!("python3");
+    config.refresh_mode = RefreshMode::Off;
+    config.diag_missing_imports = DiagMissingImportsMode::All;
+    config.arch_cache = arch_cache;
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut server);
+    SyncOdoo::init(&mut session, config);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "The symbol tree is inconsistent:\n{}", violations.join("\n"));
+    server
+}
+
+/* The symbols of a file, with their range and the names of the symbols they evaluate to, sorted */
+fn describe_file(server: &mut SyncOdoo, tree: &[&str]) -> Vec<String> {
+    let file = server.get_symbol(&(tree.iter().map(|name| S!(*name)).collect(), vec![]), u32::MAX).pop().expect("the file should be loaded");
+    let mut res = vec![];
+    for symbol in file.borrow().all_symbols() {
+        let symbol = symbol.borrow();
+        let evaluations = symbol.evaluations().map(|evaluations| evaluations.iter().map(|evaluation| match evaluation.symbol.ptr() {
+            EvaluationSymbolPtr::WEAK(w) => w.weak.upgrade().map(|target| target.borrow().name().clone()).unwrap_or_default(),
+            ptr => format!("{:?}", ptr),
+        }).collect::<Vec<_>>()).unwrap_or_default();
+        res.push(format!("{} {:?} {:?} {:?}", symbol.name(), symbol.typ(), symbol.range(), evaluations));
+    }
+    res.sort();
+    res
+}
+
+fn get_cached_files(server: &SyncOdoo) -> Vec<String> {
+    let mut res = vec![];
+    for entry in fs::read_dir(server.arch_cache.get_version_dir()).unwrap().flatten() {
+        let content = fs::read_to_string(entry.path()).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(&content).unwrap();
+        res.push(entry["path"].as_str().unwrap().to_string());
+    }
+    res
+}
+
+/* The external files parsed by a first session are built from their entries by the next one, with the same symbols. The
+files of Odoo and of the addons are never cached */
+#[test]
+fn test_arch_cache() {
+    let cache_dir = env::temp_dir().join(format!("odoo_ls_tests_arch_cache_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    fs::create_dir_all(cache_dir.join("v0")).unwrap();
+
+    let mut cold = build_server(&cache_dir, true);
+    assert!(cold.arch_cache.stored > 0);
+    assert_eq!(cold.arch_cache.hits, 0);
+    assert!(!cache_dir.join("v0").exists(), "The entries of the other versions should be removed");
+    let cached_files = get_cached_files(&cold);
+    let builtins_path = cached_files.iter().find(|path| path.ends_with("builtins.pyi")).expect("builtins should be cached").clone();
+    assert!(!cached_files.iter().any(|path| path.contains("module_3") || path.contains("odoo/models.py")), "{:?}", cached_files);
+
+    let mut warm = build_server(&cache_dir, true);
+    assert!(warm.arch_cache.hits > 0);
+    for tree in [vec!["builtins"], vec!["typing"]] {
+        assert_eq!(describe_file(&mut cold, &tree), describe_file(&mut warm, &tree), "{:?} should be restored", tree);
+    }
+
+    //a corrupted entry is parsed again, and rewritten
+    let entry_path = warm.arch_cache.get_entry_path(&builtins_path);
+    fs::write(&entry_path, "{ not json").unwrap();
+    let corrupted = build_server(&cache_dir, true);
+    assert!(corrupted.arch_cache.misses > 0);
+    assert!(serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&entry_path).unwrap()).is_ok());
+
+    let disabled = build_server(&cache_dir, false);
+    assert_eq!(disabled.arch_cache.hits, 0);
+    assert_eq!(disabled.arch_cache.stored, 0);
+    let _ = fs::remove_dir_all(&cache_dir);
+}
@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_builder::PythonArchBuilder;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::ast_utils::AstUtils;
+use odoo_ls_server::threads::SessionInfo;
+use ruff_text_size::Ranged;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+const CODE: &str = "\
+import os
+class A:
+    if os.name:
+        x = 1
+    else:
+        y = 2
+def f():
+    try:
+        a = 1
+    except ValueError as e:
+        b = 2
+    finally:
+        c = 3
+    for i in []:
+        pass
+    else:
+        d = 4
+    with open('p') as g:
+        class B:
+            z = 5
+";
+
+/* Return the first line of the statement found with the ast indexes of the symbol */
+fn get_node_line(session: &mut SessionInfo, symbol: &Rc<RefCell<Symbol>>) -> Option<String> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, symbol).unwrap();
+    let file_info = file_info.borrow();
+    let symbol = symbol.borrow();
+    let stmt = symbol.get_ast_node(&file_info)?;
+    CODE[stmt.range()].lines().next().map(|line| line.to_string())
+}
+
+fn get_symbol(scope: &Rc<RefCell<Symbol>>, name: &str) -> Rc<RefCell<Symbol>> {
+    scope.borrow().get_content_symbol(name, u32::MAX)[0].clone()
+}
+
+#[test]
+fn test_ast_indexes_of_symbols() {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_ast_indexes_{}.py", std::process::id()));
+    fs::write(&path, CODE).unwrap();
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    let file = Symbol::create_from_path(&mut session, &path, root, false).unwrap();
+    let mut builder = PythonArchBuilder::new(file.clone());
+    builder.load_arch(&mut session);
+
+    let class_a = get_symbol(&file, "A");
+    let function_f = get_symbol(&file, "f");
+    let expected = [
+        (&file, "os", "import os"),
+        (&file, "A", "class A:"),
+        (&class_a, "x", "x = 1"),
+        (&class_a, "y", "y = 2"),
+        (&file, "f", "def f():"),
+        (&function_f, "a", "a = 1"),
+        (&function_f, "e", "try:"),
+        (&function_f, "b", "b = 2"),
+        (&function_f, "c", "c = 3"),
+        (&function_f, "i", "for i in []:"),
+        (&function_f, "d", "d = 4"),
+        (&function_f, "g", "with open('p') as g:"),
+        (&function_f, "B", "class B:"),
+    ];
+    for (scope, name, line) in expected.iter() {
+        let symbol = get_symbol(scope, name);
+        assert_eq!(get_node_line(&mut session, &symbol).as_deref(), Some(*line), "wrong node for {}", name);
+    }
+    let class_b = get_symbol(&function_f, "B");
+    let z = get_symbol(&class_b, "z");
+    assert_eq!(get_node_line(&mut session, &z).as_deref(), Some("z = 5"));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_invalid_ast_indexes() {
+    let ast = parse_valid(CODE);
+    assert!(AstUtils::get_stmt_from_ast(&ast, &[]).is_none());
+    assert!(AstUtils::get_stmt_from_ast(&ast, &[1, 5]).is_none()); //class A has a single statement
+    assert!(AstUtils::get_stmt_from_ast(&ast, &[1, 0, 2, 0]).is_none()); //the if has a single else clause
+    assert!(AstUtils::get_stmt_from_ast(&ast, &[2, 0, 3, 1, 0]).is_none()); //the try has a single handler
+    assert!(AstUtils::get_stmt_from_ast(&ast, &[2, 0, 3, 0, 0]).is_some_and(|stmt| stmt.is_assign_stmt()));
+}
@@ -0,0 +1,40 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_declared_type_names;
+
+/* The call of an async function gives a coroutine, and awaiting it gives the returned value. The targets of async with
+and async for use __aenter__, and __aiter__ then __anext__ */
+#[test]
+fn test_async_functions() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("async_functions")];
+    let file = odoo.get_symbol(&(file_tree.clone(), vec![]), u32::MAX).pop().expect("async_functions should be loaded");
+    let methods: Vec<Rc<RefCell<Symbol>>> = [("Stream", "__aiter__"), ("Stream", "__anext__"), ("Session", "__aenter__"), ("Session", "read")].iter().map(|(class, method)| {
+        odoo.get_symbol(&(file_tree.clone(), vec![S!(*class), S!(*method)]), u32::MAX).pop().expect("method should be loaded")
+    }).collect();
+    let function = methods.last().unwrap().clone();
+    assert!(function.borrow().as_func().is_async);
+    assert!(!methods[0].borrow().as_func().is_async);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    assert_eq!(get_declared_type_names(&mut session, &file, "coroutine"), vec![S!("Coroutine")]);
+    //the values returned by the methods used by async for and async with are inferred by their validation
+    for method in methods.iter() {
+        method.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+        PythonValidator::new(method.clone()).validate(&mut session);
+    }
+    assert_eq!(get_declared_type_names(&mut session, &function, "response"), vec![S!("Response")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "pending"), vec![S!("Coroutine")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "awaited"), vec![S!("Response")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "opened"), vec![S!("Session")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "received"), vec![S!("Response")]);
+}
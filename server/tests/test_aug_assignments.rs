@@ -0,0 +1,36 @@
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* The augmented assignments bind their target like an assignment: the previous declarations are combined with the
+operand, and a target without previous declaration is bound to the operand */
+#[test]
+fn test_aug_assignments() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("aug_assignments")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("aug_assignments should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let mut get_type_names = |name: &str| {
+        let mut res = vec![];
+        let variables = file.borrow().get_content_symbol(name, u32::MAX);
+        for variable in variables.iter() {
+            let evaluations = variable.borrow().evaluations().cloned().unwrap_or_default();
+            for evaluation in evaluations.iter() {
+                let symbol = evaluation.symbol.get_symbol(&mut session, &mut None, &mut vec![], None);
+                let types = Symbol::follow_ref(&symbol, &mut session, &mut None, false, false, None, &mut vec![]);
+                res.extend(types.iter().filter_map(|t| t.weak.upgrade()).map(|t| t.borrow().name().clone()));
+            }
+        }
+        res
+    };
+    //the name is only declared in a branch before the augmented assignment
+    assert_eq!(get_type_names("total"), vec![S!("int")]);
+    assert_eq!(get_type_names("ratio"), vec![S!("float")]);
+    assert_eq!(get_type_names("label"), vec![S!("str")]);
+    //first bindings
+    assert_eq!(get_type_names("delta"), vec![S!("int")]);
+    assert_eq!(get_type_names("paths"), vec![S!("list")]);
+}
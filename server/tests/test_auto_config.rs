@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::auto_config::{AutoConfig, PathSource, TomlValue};
+use odoo_ls_server::core::config::Config;
+use odoo_ls_server::utils::PathSanitizer;
+
+fn create_workspace(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_auto_config_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(path.join("addons")).unwrap();
+    fs::create_dir_all(path.join("enterprise")).unwrap();
+    fs::create_dir_all(path.join("odoo").join("addons")).unwrap();
+    path
+}
+
+#[test]
+fn test_parse_ini() {
+    let sections = AutoConfig::parse_ini("\
+; comment
+[options]
+addons_path = addons,
+    ../enterprise
+db_name: test # not a comment
+[other]
+addons_path = other
+");
+    assert_eq!(sections["options"]["addons_path"], "addons,\n../enterprise");
+    assert_eq!(sections["options"]["db_name"], "test # not a comment");
+    assert_eq!(sections["other"]["addons_path"], "other");
+}
+
+#[test]
+fn test_parse_toml_table() {
+    let table = AutoConfig::parse_toml_table(r#"
+[project]
+name = "my_project"
+
+[tool.odoo-ls]
+odooPath = "../odoo" # the community sources
+addons = [
+    "addons",
+    'enterprise', # with a comment
+]
+
+[tool.other]
+addons = ["other"]
+"#, "tool.odoo-ls").unwrap();
+    assert_eq!(table.len(), 2);
+    assert_eq!(table["odooPath"], TomlValue::STRING("../odoo".to_string()));
+    assert_eq!(table["addons"], TomlValue::ARRAY(vec!["addons".to_string(), "enterprise".to_string()]));
+    assert!(AutoConfig::parse_toml_table("[tool.odoo-ls]\naddons = [\"a\"", "tool.odoo-ls").is_err());
+    assert!(AutoConfig::parse_toml_table("[tool.odoo-ls]\nodooPath = 3", "tool.odoo-ls").is_err());
+}
+
+#[test]
+fn test_auto_config_merge() {
+    let workspace = create_workspace("merge");
+    fs::write(workspace.join("odoo.conf"), "[options]\naddons_path = addons, missing, odoo/addons\n").unwrap();
+    fs::write(workspace.join("pyproject.toml"), "[tool.odoo-ls]\nodooPath = \"odoo\"\naddons = [\"enterprise\"]\n").unwrap();
+    let (auto_config, errors) = AutoConfig::load(&vec![workspace.sanitize()], "odoo.conf");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("'missing'"), "{:?}", errors);
+
+    //the paths of the files complete the empty settings
+    let mut config = Config::new();
+    let conflicts = auto_config.merge(&mut config, None);
+    assert!(conflicts.is_empty());
+    assert_eq!(config.odoo_path, workspace.join("odoo").sanitize());
+    assert_eq!(config.addons, vec![workspace.join("addons").sanitize(), workspace.join("enterprise").sanitize()]);
+    assert_eq!(config.path_sources[1].1, PathSource::ODOO_CONF(workspace.join("odoo.conf").sanitize()).to_string());
+
+    //the editor settings win
+    let mut config = Config::new();
+    config.odoo_path = "/opt/odoo".to_string();
+    config.addons = vec![workspace.join("addons").sanitize()];
+    let conflicts = auto_config.merge(&mut config, Some("/usr/bin/python3".to_string()));
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(config.odoo_path, "/opt/odoo");
+    assert_eq!(config.addons.len(), 3); //odoo/addons is not in the odoo path of the editor
+    assert_eq!(config.python_path, "/usr/bin/python3");
+    let _ = fs::remove_dir_all(&workspace);
+}
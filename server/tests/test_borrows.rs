@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+
+use lsp_types::{HoverParams, Position, TextDocumentIdentifier, TextDocumentPositionParams};
+use odoo_ls_server::core::borrows::Borrows;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_try_borrow() {
+    let cell = RefCell::new(1);
+    assert_eq!(*Borrows::try_borrow(&cell).unwrap(), 1);
+    let held = Borrows::borrow_mut(&cell);
+    assert!(Borrows::try_borrow(&cell).is_none());
+    assert!(Borrows::try_borrow_mut(&cell).is_none());
+    //the place of the outstanding borrow is only recorded in debug builds
+    if cfg!(debug_assertions) {
+        assert!(Borrows::get_outstanding_location(&cell).is_some_and(|location| location.file().ends_with("test_borrows.rs")));
+    }
+    drop(held);
+    assert!(Borrows::get_outstanding_location(&cell).is_none());
+    let reader = Borrows::try_borrow(&cell).unwrap();
+    assert!(Borrows::try_borrow_mut(&cell).is_none());
+    drop(reader);
+    *Borrows::try_borrow_mut(&cell).unwrap() += 1;
+    assert_eq!(*cell.borrow(), 2);
+}
+
+#[test]
+fn test_borrows_server() {
+    let mut odoo = setup::setup::setup_server();
+    check_star_imports_cycle(&mut odoo);
+    check_request_on_borrowed_file(&mut odoo);
+}
+
+fn check_star_imports_cycle(odoo: &mut odoo_ls_server::core::odoo::SyncOdoo) {
+    //a file importing its own names, and importing the names of a file that imports it, used to panic on the nested
+    //borrows of the ARCH
+    let models = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models")];
+    let star_imports = [models.clone(), vec![S!("star_imports")]].concat();
+    let cycle = [models.clone(), vec![S!("star_import_cycle")]].concat();
+    assert!(!odoo.get_symbol(&(star_imports.clone(), vec![S!("STAR_VALUE")]), u32::MAX).is_empty());
+    assert!(!odoo.get_symbol(&(cycle.clone(), vec![S!("CYCLE_VALUE")]), u32::MAX).is_empty());
+    //the names of one of the files are imported by the other, whatever the order of their builds
+    assert!(!odoo.get_symbol(&(star_imports, vec![S!("CYCLE_VALUE")]), u32::MAX).is_empty()
+        || !odoo.get_symbol(&(cycle, vec![S!("STAR_VALUE")]), u32::MAX).is_empty());
+}
+
+fn check_request_on_borrowed_file(odoo: &mut odoo_ls_server::core::odoo::SyncOdoo) {
+    //a request reading a file that is mutably borrowed is refused with an error the client retries, instead of panicking
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("star_imports")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("star_imports should be loaded");
+    let path = file.borrow().paths()[0].clone();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &file).expect("star_imports should have a file info");
+    let params = || HoverParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: FileMgr::pathname2uri(&path) },
+            position: Position::new(4, 2),
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let held = Borrows::borrow_mut(&file_info);
+    let error = Odoo::handle_request(&mut session, params(), Odoo::handle_hover).expect_err("the request should be refused");
+    assert_eq!(error.code, lsp_server::ErrorCode::ContentModified as i32);
+    assert_eq!(error.message, "The server is busy, retry");
+    drop(held);
+    //once released, the same request is answered
+    assert!(Odoo::handle_request(&mut session, params(), Odoo::handle_hover).unwrap().is_some());
+}
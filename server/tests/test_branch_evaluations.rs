@@ -0,0 +1,51 @@
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::{get_type_names, get_variable};
+
+/* These tests check the evaluations of the variables assigned in several branches: each branch sees its own
+assignment, and the code after the branches sees the assignments of all of them. See test_sections.rs for the
+declarations reachable at each position. */
+
+fn branches_tree() -> Vec<String> {
+    vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("branches")]
+}
+
+/* Validate the function of the branches file and return the types of its variables */
+fn get_variable_types(odoo: &mut SyncOdoo, function_name: &str, names: &[&str]) -> Vec<Vec<String>> {
+    let function = odoo.get_symbol(&(branches_tree(), vec![S!(function_name)]), u32::MAX).pop();
+    let function = function.expect("the function should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    let mut res = vec![];
+    for name in names.iter() {
+        let mut types = get_type_names(&mut session, &get_variable(&function, name));
+        types.dedup();
+        res.push(types);
+    }
+    res
+}
+
+#[test]
+fn test_branch_evaluations() {
+    let mut odoo = setup::setup::setup_server();
+    check_if_else_evaluations(&mut odoo);
+    check_try_and_for_else_evaluations(&mut odoo);
+}
+
+fn check_if_else_evaluations(odoo: &mut SyncOdoo) {
+    let res = get_variable_types(odoo, "pick", &["in_else", "after"]);
+    assert_eq!(res, vec![vec![S!("Bar")], vec![S!("Bar"), S!("Foo")]]);
+}
+
+fn check_try_and_for_else_evaluations(odoo: &mut SyncOdoo) {
+    let res = get_variable_types(odoo, "load", &["loaded", "looped"]);
+    assert_eq!(res, vec![vec![S!("Bar"), S!("Foo")], vec![S!("Bar"), S!("Foo")]]);
+}
@@ -0,0 +1,263 @@
+use lsp_types::NumberOrString;
+use ruff_python_ast::{Expr, ExprCall, Stmt};
+use ruff_text_size::TextSize;
+use odoo_ls_server::core::call_binding::{CallBinding, UnpackedValues};
+use odoo_ls_server::core::evaluation::EvaluationValue;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::function_symbol::ArgumentType;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_call(code: &str) -> ExprCall {
+    match parse(code).into_iter().next() {
+        Some(Stmt::Expr(expr)) => match *expr.value {
+            Expr::Call(call) => call,
+            _ => panic!("Not a call")
+        },
+        _ => panic!("Not an expression")
+    }
+}
+
+/* def func(self, a, b=1, *args, c, **kwargs) */
+fn parameters() -> Vec<(String, ArgumentType)> {
+    vec![
+        ("self".to_string(), ArgumentType::ARG),
+        ("a".to_string(), ArgumentType::ARG),
+        ("b".to_string(), ArgumentType::ARG),
+        ("args".to_string(), ArgumentType::VARARG),
+        ("c".to_string(), ArgumentType::KWORD_ONLY),
+        ("kwargs".to_string(), ArgumentType::KWARG),
+    ]
+}
+
+#[test]
+fn test_bind_arguments() {
+    let call = parse_call("record.func(1, 2, 3, 4, c=5, d=6)");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.args, vec![Some(1), Some(2), Some(3), Some(3)]);
+    assert_eq!(binding.keywords, vec![Some(4), Some(5)]);
+
+    //the default-filled parameters are not bound, and a parameter can't be given twice
+    let call = parse_call("record.func(1, a=2, c=3)");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.args, vec![Some(1)]);
+    assert_eq!(binding.keywords, vec![None, Some(4)]);
+
+    //nothing is known after an unpacked argument
+    let call = parse_call("Model.func(self, 1, *values, 2, **options)");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, false);
+    assert_eq!(binding.args, vec![Some(0), Some(1), None, None]);
+    assert_eq!(binding.keywords, vec![None]);
+}
+
+#[test]
+fn test_bind_unpacked_literals() {
+    //the items of an unpacked literal take the next positional parameters
+    let call = parse_call("record.func(*[1, 2], 3, c=4)");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.args, vec![Some(1), Some(3)]);
+    assert!(binding.complete);
+
+    //the keys of an unpacked dict literal are bound like keywords
+    let call = parse_call("record.func(1, **{'b': 2, 'c': 3, 'd': 4})");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.keywords, vec![None]);
+    assert_eq!(binding.unpacked_keywords, vec![(String::from("b"), Some(2)), (String::from("c"), Some(4)), (String::from("d"), Some(5))]);
+    assert!(binding.complete);
+
+    //mixed literal and unknown expansions: the binding stops at the unknown one
+    let call = parse_call("record.func(*(1,), *values, 2, **dict(c=3), **options)");
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.args, vec![Some(1), None, None]);
+    assert_eq!(binding.unpacked_keywords, vec![(String::from("c"), Some(4))]);
+    assert!(!binding.complete);
+}
+
+#[test]
+fn test_bind_unpacked_values() {
+    //the values of the unpacked expressions can be given, like the keys of a TypedDict
+    let call = parse_call("record.func(*args, **vals)");
+    let unpacked = |expr: &Expr| match expr {
+        Expr::Name(name) if name.id.as_str() == "args" => Some(UnpackedValues::POSITIONAL(3)),
+        Expr::Name(name) if name.id.as_str() == "vals" => Some(UnpackedValues::KEYWORDS(vec![String::from("c")])),
+        _ => None
+    };
+    let binding = CallBinding::bind_with(&parameters(), &call.arguments, true, &unpacked);
+    assert_eq!(binding.args, vec![Some(1)]);
+    assert_eq!(binding.unpacked_keywords, vec![(String::from("c"), Some(4))]);
+    assert!(binding.complete);
+    assert_eq!(CallBinding::get_literal_values(&parse_call("f({'a': 1, **other})").arguments.args[0]), None);
+    assert_eq!(CallBinding::get_literal_values(&parse_call("f([1, *other])").arguments.args[0]), None);
+}
+
+#[test]
+fn test_active_parameter() {
+    let code = "record.func(1, 2, c=3)";
+    let call = parse_call(code);
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    let offset = |text: &str| TextSize::new(code.find(text).unwrap() as u32);
+    assert_eq!(binding.active_parameter(&call.arguments, offset("1")), Some(1));
+    assert_eq!(binding.active_parameter(&call.arguments, offset("2")), Some(2));
+    assert_eq!(binding.active_parameter(&call.arguments, offset("c=")), Some(4));
+
+    let code = "record.func(1, 2, ";
+    let call = parse_call(code);
+    let binding = CallBinding::bind(&parameters(), &call.arguments, true);
+    assert_eq!(binding.active_parameter(&call.arguments, TextSize::new(code.len() as u32)), Some(3));
+}
+
+#[test]
+fn test_call_binding_validation() {
+    let mut odoo = setup::setup::setup_server();
+    check_unpacked_calls_validation(&mut odoo);
+    check_function_parameters(&mut odoo);
+    check_parameter_defaults(&mut odoo);
+    check_class_method(&mut odoo);
+    check_call_arity_validation(&mut odoo);
+}
+
+/* The unpacked literals, constant dicts, TypedDicts and dicts returned by a helper are bound when validating the calls */
+fn check_unpacked_calls_validation(odoo: &mut SyncOdoo) {
+    let file = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("unpacked_calls")], vec![]), u32::MAX).pop();
+    let file = file.expect("unpacked_calls should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    let messages = |code: &str| -> Vec<String> {
+        validator.diagnostics.iter()
+            .filter(|d| d.code == Some(NumberOrString::String(code.to_string())))
+            .map(|d| d.message.clone()).collect()
+    };
+    assert_eq!(messages("OLS30315"), vec![
+        S!("_make_label takes 2 positional arguments, but at least 4 is given"),
+        S!("_make_label takes 2 positional arguments, but only 1 is given"),
+    ]);
+    assert_eq!(messages("OLS30316"), vec![
+        S!("_make_label got an unexpected keyword argument 'label'"),
+        S!("_make_label got an unexpected keyword argument 'color'"),
+        S!("_make_label got an unexpected keyword argument 'color'"),
+    ]);
+}
+
+/* The five kinds of parameters are declared in the scope of the function, and given as arguments in the order of the source */
+fn check_function_parameters(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("parameters")];
+    let function = odoo.get_symbol(&(file_tree.clone(), vec![S!("mixed_parameters")]), u32::MAX).pop();
+    let function = function.expect("mixed_parameters should be loaded");
+    let arguments: Vec<(String, ArgumentType, bool)> = function.borrow().as_func().args.iter().map(|arg| {
+        let symbol = arg.symbol.upgrade().unwrap();
+        assert!(symbol.borrow().as_variable().is_parameter);
+        let name = symbol.borrow().name().clone();
+        (name, arg.arg_type, arg.default_value.is_some())
+    }).collect();
+    assert_eq!(arguments, vec![
+        (S!("a"), ArgumentType::POS_ONLY, false),
+        (S!("b"), ArgumentType::POS_ONLY, true),
+        (S!("c"), ArgumentType::ARG, true),
+        (S!("args"), ArgumentType::VARARG, false),
+        (S!("d"), ArgumentType::KWORD_ONLY, false),
+        (S!("e"), ArgumentType::KWORD_ONLY, true),
+        (S!("kwargs"), ArgumentType::KWARG, false),
+    ]);
+    assert!(function.borrow().as_func().can_be_in_class());
+    let keyword_only = odoo.get_symbol(&(file_tree.clone(), vec![S!("keyword_only")]), u32::MAX).pop().unwrap();
+    assert!(!keyword_only.borrow().as_func().can_be_in_class());
+    let star_only = odoo.get_symbol(&(file_tree.clone(), vec![S!("Parameters"), S!("_star_only")]), u32::MAX).pop().unwrap();
+    assert!(star_only.borrow().as_func().can_be_in_class());
+    //the calls omitting the parameters with a default, or giving extra arguments to *args and **kwargs, are valid
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().unwrap();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(!validator.diagnostics.iter().any(|d| d.code == Some(NumberOrString::String(S!("OLS30315")))
+        || d.code == Some(NumberOrString::String(S!("OLS30316")))));
+}
+
+/* The defaults of the parameters are evaluated in the scope of the function definition */
+fn check_parameter_defaults(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("parameters")];
+    let search = odoo.get_symbol(&(file_tree, vec![S!("Finder"), S!("search")]), u32::MAX).pop();
+    let search = search.expect("Finder.search should be loaded");
+    let search = search.borrow();
+    let args = &search.as_func().args;
+    assert!(args[0].default_value.is_none() && args[1].default_value.is_none());
+    let offset = args[2].default_value.as_ref().expect("offset has a default");
+    assert!(matches!(offset.value, Some(EvaluationValue::CONSTANT(Expr::NumberLiteral(_)))));
+    let limit = args[3].default_value.as_ref().expect("limit has a default");
+    assert!(matches!(limit.value, Some(EvaluationValue::CONSTANT(Expr::NoneLiteral(_)))));
+    //a mutable default is evaluated to its container
+    let fields = args[5].default_value.as_ref().expect("fields has a default");
+    assert!(matches!(fields.value, Some(EvaluationValue::LIST(_))));
+    let defaults: Vec<String> = args.iter().filter_map(|arg| arg.default_value.as_ref()).map(HoverFeature::build_default_value).collect();
+    assert_eq!(defaults, vec![S!("0"), S!("None"), S!("..."), S!("[...]")]);
+}
+
+/* The first parameter of a classmethod is the class itself, and is bound by the calls made on the class */
+fn check_class_method(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("parameters")];
+    let create_multi = odoo.get_symbol(&(file_tree.clone(), vec![S!("Finder"), S!("create_multi")]), u32::MAX).pop();
+    let create_multi = create_multi.expect("Finder.create_multi should be loaded");
+    assert!(create_multi.borrow().as_func().is_class_method);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    let cls = create_multi.borrow().as_func().args[0].symbol.upgrade().unwrap();
+    let cls_evaluation = cls.borrow().evaluations().unwrap()[0].clone();
+    let cls_symbol = cls_evaluation.symbol.get_symbol(&mut session, &mut None, &mut vec![], None);
+    assert_eq!(cls_symbol.weak.upgrade().unwrap().borrow().name(), "Finder");
+    assert_eq!(cls_symbol.instance, Some(false));
+    //cls() creates an instance of the class
+    create_multi.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(create_multi.clone()).validate(&mut session);
+    let returns = create_multi.borrow().as_func().evaluations.clone();
+    assert_eq!(returns.len(), 1);
+    let instance = returns[0].symbol.get_symbol(&mut session, &mut None, &mut vec![], None);
+    assert_eq!(instance.weak.upgrade().unwrap().borrow().name(), "Finder");
+    assert_eq!(instance.instance, Some(true));
+    //Finder.create_multi([]) gives its only argument to vals
+    let file = session.sync_odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().unwrap();
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(!validator.diagnostics.iter().any(|d| d.code == Some(NumberOrString::String(S!("OLS30315")))
+        || d.code == Some(NumberOrString::String(S!("OLS30316")))));
+}
+
+/* The calls are checked against the parameters of the functions, with the bound instance or class of the methods. The
+calls forwarding their *args and **kwargs, and the calls of a function with an unknown decorator are not checked */
+fn check_call_arity_validation(odoo: &mut SyncOdoo) {
+    let file = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("call_arity")], vec![]), u32::MAX).pop();
+    let file = file.expect("call_arity should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    let messages = |code: &str| -> Vec<String> {
+        validator.diagnostics.iter()
+            .filter(|d| d.code == Some(NumberOrString::String(code.to_string())))
+            .map(|d| d.message.clone()).collect()
+    };
+    assert_eq!(messages("OLS30315"), vec![
+        S!("add takes 2 positional arguments, but only 1 is given"),
+        S!("add takes 2 positional arguments, but at least 3 is given"),
+        S!("add takes 2 positional arguments, but only 1 is given"),
+        S!("add takes 2 positional arguments, but only 1 is given"),
+        S!("total takes 2 positional arguments, but only 1 is given"),
+    ]);
+    assert_eq!(messages("OLS30316"), vec![S!("add got an unexpected keyword argument 'c'")]);
+    assert_eq!(messages("OLS30343"), vec![
+        S!("add got multiple values for argument 'a'"),
+        S!("total got multiple values for argument 'amount'"),
+    ]);
+}
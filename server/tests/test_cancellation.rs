@@ -0,0 +1,164 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use lsp_server::RequestId;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps, SymType};
+use odoo_ls_server::core::cancellation::CancellationMgr;
+use odoo_ls_server::core::evaluation::EvaluationSymbolPtr;
+use odoo_ls_server::core::odoo::{InitState, SyncOdoo};
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer;
+
+const PACKAGES: usize = 10;
+const MODULES: usize = 30;
+
+fn write(root: &PathBuf, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/* A workspace of a few hundred files importing each other */
+fn write_fixture(root: &PathBuf) {
+    for package in 0..PACKAGES {
+        let modules: Vec<String> = (0..MODULES).map(|module| format!("mod_{}", module)).collect();
+        write(root, &format!("pkg_{}/__init__.py", package), &format!("from . import {}\n", modules.join(", ")));
+        for module in 0..MODULES {
+            let other = (package + 1) % PACKAGES;
+            write(root, &format!("pkg_{}/mod_{}.py", package, module), &format!("\
+from pkg_{other}.mod_{module} import Base
+from .mod_{next} import VALUE as NEXT_VALUE
+
+VALUE = {module}
+
+class Base:
+    label = 'base'
+
+    def run(self, value: int = VALUE) -> str:
+        return str(value) + self.label
+
+class Child(Base):
+    item = Base()
+
+def build(count=3):
+    return [Child() for _ in range(count)]
+
+RESULT = build()
+", next = (module + 1) % MODULES));
+        }
+    }
+    let imports: Vec<String> = (0..PACKAGES).map(|package| format!("import pkg_{}", package)).collect();
+    write(root, "main.py", &(imports.join("\n") + "\n"));
+}
+
+/* The symbols under the symbol, with their range and the names of the symbols they evaluate to */
+fn describe(symbol: &Rc<RefCell<Symbol>>, prefix: &str, res: &mut Vec<String>) {
+    for child in symbol.borrow().all_symbols() {
+        let child_bw = child.borrow();
+        let evaluations: Vec<String> = child_bw.evaluations().map(|evaluations| evaluations.iter().map(|evaluation| match evaluation.symbol.ptr() {
+            EvaluationSymbolPtr::WEAK(w) => w.weak.upgrade().map(|target| target.borrow().name().clone()).unwrap_or_default(),
+            ptr => format!("{:?}", ptr),
+        }).collect()).unwrap_or_default();
+        let name = format!("{}/{}", prefix, child_bw.name());
+        res.push(format!("{} {:?} {:?} {:?}", name, child_bw.typ(), child_bw.range(), evaluations));
+        drop(child_bw);
+        describe(&child, &name, res);
+    }
+}
+
+/* The files and packages under the symbol with a step in progress */
+fn get_in_progress(symbol: &Rc<RefCell<Symbol>>, res: &mut Vec<String>) {
+    for child in symbol.borrow().all_symbols() {
+        if !matches!(child.borrow().typ(), SymType::FILE | SymType::PACKAGE(_)) {
+            continue;
+        }
+        for step in [BuildSteps::ARCH, BuildSteps::ARCH_EVAL, BuildSteps::ODOO, BuildSteps::VALIDATION] {
+            if child.borrow().build_status(step) == BuildStatus::IN_PROGRESS {
+                res.push(format!("{} {:?}", child.borrow().paths().first().cloned().unwrap_or_default(), step));
+            }
+        }
+        get_in_progress(&child, res);
+    }
+}
+
+/* Build the fixture, cancelling the first rebuild after the given delay, and return the description of its symbols */
+fn build_fixture(root: &PathBuf, cancel_after: Option<Duration>) -> Vec<String> {
+    let mut odoo = SyncOdoo::new();
+    let token = odoo.cancellations.get_rebuild_token();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root_symbol = session.sync_odoo.symbols.clone().unwrap();
+    root_symbol.borrow_mut().add_path(session.sync_odoo.stdlib_dir.clone());
+    root_symbol.borrow_mut().add_path(root.sanitize());
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    SyncOdoo::load_builtins(&mut session);
+    session.sync_odoo.state_init = InitState::ODOO_READY; //the first indexing is never cancelled
+    let main = Symbol::create_from_path(&mut session, &root.join("main.py"), root_symbol.clone(), false).unwrap();
+    session.sync_odoo.add_to_rebuild_arch(main);
+    if let Some(delay) = cancel_after {
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            token.cancel();
+        });
+        SyncOdoo::process_rebuilds(&mut session);
+        canceller.join().unwrap();
+        assert!(session.sync_odoo.get_rebuild_queue_size() > 0, "The rebuild should be cancelled before its end");
+        let mut in_progress = vec![];
+        get_in_progress(&root_symbol, &mut in_progress);
+        assert!(in_progress.is_empty(), "No step should be left in progress: {:?}", in_progress);
+    }
+    SyncOdoo::process_rebuilds(&mut session);
+    assert_eq!(session.sync_odoo.get_rebuild_queue_size(), 0);
+    let mut res = vec![];
+    for package in 0..PACKAGES {
+        let symbol = session.sync_odoo.get_symbol(&(vec![format!("pkg_{}", package)], vec![]), u32::MAX).pop().expect("the package should be built");
+        describe(&symbol, &format!("pkg_{}", package), &mut res);
+    }
+    res.sort();
+    res
+}
+
+/* A rebuild cancelled midway leaves its symbols pending, and the next rebuild gives the same symbols as an uncancelled one */
+#[test]
+fn test_cancelled_rebuild() {
+    let root = std::env::temp_dir().join(format!("odoo_ls_tests_cancellation_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write_fixture(&root);
+    let expected = build_fixture(&root, None);
+    let resumed = build_fixture(&root, Some(Duration::from_millis(30)));
+    assert!(expected.len() > PACKAGES * MODULES * 5, "{} symbols", expected.len());
+    assert_eq!(expected, resumed);
+    let _ = fs::remove_dir_all(&root);
+}
+
+/* An edit cancels the rebuild only if the file is still to be built, and a request can be cancelled until it is answered */
+#[test]
+fn test_cancellation_mgr() {
+    let cancellations = CancellationMgr::new();
+    let token = cancellations.get_rebuild_token();
+    cancellations.add_pending_path("/addons/module/models.py");
+    cancellations.add_pending_path("/addons/module");
+    assert!(!cancellations.cancel_rebuild_for("/addons/module/views.py"));
+    assert!(!token.is_cancelled());
+    assert!(cancellations.cancel_rebuild_for("/addons/module/__init__.py"));
+    assert!(token.is_cancelled());
+    token.reset();
+    cancellations.remove_pending_path("/addons/module/models.py");
+    assert!(!cancellations.cancel_rebuild_for("/addons/module/models.py"));
+    cancellations.clear_pending_paths();
+    assert!(!cancellations.cancel_rebuild_for("/addons/module/__init__.py"));
+    assert!(!token.is_cancelled());
+
+    let id = RequestId::from(12);
+    assert!(!cancellations.cancel_request(&id));
+    let request_token = cancellations.start_request(&id);
+    assert!(cancellations.cancel_request(&id));
+    assert!(request_token.is_cancelled());
+    assert!(!token.is_cancelled(), "The rebuild is not cancelled with the request");
+    cancellations.end_request(&id);
+    assert!(!cancellations.cancel_request(&id));
+}
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::utils::CaseSensitivePathCache;
+
+fn create_folder(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_case_sensitive_paths_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(path.join("addons").join("Sale")).unwrap();
+    fs::write(path.join("addons").join("Sale").join("models.py"), "").unwrap();
+    fs::write(path.join("addons").join("Sale").join("views.py"), "").unwrap();
+    path
+}
+
+/* Each folder is read once, whatever the number of queries on its entries */
+#[test]
+fn test_single_read_per_folder() {
+    let path = create_folder("reads");
+    let mut cache = CaseSensitivePathCache::new();
+    assert!(cache.has_exact_case(&path.join("addons").join("Sale").join("models.py")));
+    let reads = cache.read_count();
+    assert_eq!(reads, path.join("addons").join("Sale").ancestors().count());
+    for _ in 0..1000 {
+        assert!(cache.has_exact_case(&path.join("addons").join("Sale").join("models.py")));
+        assert!(cache.has_exact_case(&path.join("addons").join("Sale").join("views.py")));
+        assert!(cache.has_exact_case(&path.join("addons").join("Sale")));
+        assert!(!cache.has_exact_case(&path.join("addons").join("Sale").join("Models.py")));
+    }
+    assert_eq!(cache.read_count(), reads);
+    let _ = fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_case_mismatch() {
+    let path = create_folder("mismatch");
+    let mut cache = CaseSensitivePathCache::new();
+    assert!(!cache.has_exact_case(&path.join("addons").join("sale")));
+    assert!(!cache.has_exact_case(&path.join("Addons").join("Sale").join("models.py")));
+    assert!(!cache.has_exact_case(&path.join("addons").join("Sale").join("missing.py")));
+    let _ = fs::remove_dir_all(&path);
+}
+
+/* A file created in a folder is only found once the folder is invalidated, and only this folder is read again */
+#[test]
+fn test_invalidation() {
+    let path = create_folder("invalidation");
+    let sale = path.join("addons").join("Sale");
+    let mut cache = CaseSensitivePathCache::new();
+    assert!(!cache.has_exact_case(&sale.join("report.py")));
+    fs::write(sale.join("report.py"), "").unwrap();
+    assert!(!cache.has_exact_case(&sale.join("report.py")));
+    let reads = cache.read_count();
+    cache.invalidate(&sale.join("report.py"));
+    assert!(cache.has_exact_case(&sale.join("report.py")));
+    assert_eq!(cache.read_count(), reads + 1);
+    //a renamed folder forgets its own entries too
+    cache.invalidate(&sale);
+    assert!(cache.has_exact_case(&sale.join("models.py")));
+    assert_eq!(cache.read_count(), reads + 3);
+    cache.clear();
+    assert!(cache.is_empty());
+    let _ = fs::remove_dir_all(&path);
+}
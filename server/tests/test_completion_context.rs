@@ -0,0 +1,119 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ruff_text_size::Ranged;
+use odoo_ls_server::features::completion_context::{AssignmentTarget, CompletionContext, CompletionContexts};
+
+/* Return the receiver and the prefix of an attribute completion at the end of the line */
+fn receiver(line: &str) -> Option<(String, String)> {
+    match CompletionContexts::extract(line, line.len()) {
+        CompletionContext::ATTRIBUTE(receiver, prefix) => Some((line[receiver.range()].to_string(), prefix)),
+        _ => None
+    }
+}
+
+fn collect_python_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_python_files(&path, files);
+        } else if path.extension().is_some_and(|e| e == "py") {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn test_attribute_receivers() {
+    let check = |line: &str, expected: &str, prefix: &str| {
+        assert_eq!(receiver(line), Some((expected.to_string(), prefix.to_string())), "{}", line);
+    };
+    check("        self.partner_id.", "self.partner_id", "");
+    check("        partners = self.env['res.partner'].search([('name', '=', 'x')]).na", "self.env['res.partner'].search([('name', '=', 'x')])", "na");
+    check("        self.env[\"sale.order)\"].sudo().", "self.env[\"sale.order)\"].sudo()", "");
+    check("        x = await self.env.", "self.env", "");
+    check("        if not record.partner_id.na", "record.partner_id", "na");
+    check("        self.write({'partner_id': order.partner_id.", "order.partner_id", "");
+    check("        self.write(vals=(order.", "order", "");
+    //the range of a parenthesized expression doesn't include the parentheses
+    check("        total = (a + b).", "a + b", "");
+    check("        return f'{name}'.up", "f'{name}'", "up");
+    check("        lines = self.order_line.filtered(lambda l: l.product_id).mapped('price')[0].", "self.order_line.filtered(lambda l: l.product_id).mapped('price')[0]", "");
+    check("        ids = [1, 2].", "[1, 2]", "");
+    check("    é_name.", "é_name", "");
+}
+
+#[test]
+fn test_other_contexts() {
+    let context = |line: &str| CompletionContexts::extract(line, line.len());
+    assert!(matches!(context("        name = 'self.partner"), CompletionContext::STRING));
+    assert!(matches!(context("        name = \"\"\"self.partner"), CompletionContext::STRING));
+    assert!(matches!(context("        # self.partner_id."), CompletionContext::COMMENT));
+    assert!(matches!(context("        name = 'a' # self.partner_id."), CompletionContext::COMMENT));
+    assert!(matches!(context("        name = '#' + self.partner_id."), CompletionContext::ATTRIBUTE(..)));
+    assert_eq!(receiver("        value = 1."), None);
+    assert_eq!(receiver("        value = self.env['res.partner'"), None);
+    assert_eq!(receiver("        value = ."), None);
+    assert_eq!(receiver("from . "), None);
+    assert_eq!(receiver("        return."), None);
+    assert_eq!(receiver("        x = foo)."), None);
+    assert_eq!(receiver("        x = 'a' + 'b'.up"), Some(("'b'".to_string(), "up".to_string())));
+    //the escaped quote doesn't end the string
+    assert!(matches!(context("        name = 'it\\'s self."), CompletionContext::STRING));
+    //only the lines before the cursor are read
+    let source = "class A:\n    def f(self):\n        self.partner_id.\n        return 1\n";
+    let offset = source.find("id.").unwrap() + 3;
+    match CompletionContexts::extract(source, offset) {
+        CompletionContext::ATTRIBUTE(receiver, prefix) => {
+            assert_eq!(&source[receiver.range()], "self.partner_id");
+            assert!(prefix.is_empty());
+        },
+        other => panic!("Not an attribute: {:?}", other)
+    }
+}
+
+#[test]
+fn test_assignment_targets() {
+    //the cursor is at the |
+    let target = |source: &str| {
+        let offset = source.find('|').unwrap();
+        CompletionContexts::get_assignment_target(&source.replace('|', ""), offset)
+    };
+    assert_eq!(target("        self.|"), Some(AssignmentTarget::STATEMENT));
+    assert_eq!(target("        record.amount_|\n        return"), Some(AssignmentTarget::STATEMENT));
+    assert_eq!(target("        record.amount_|tot = 0.0"), Some(AssignmentTarget::ASSIGNED));
+    assert_eq!(target("        self.amount_total| += line.price"), Some(AssignmentTarget::ASSIGNED));
+    assert_eq!(target("        self.partner_id.|name = 'x'"), Some(AssignmentTarget::ASSIGNED));
+    assert_eq!(target("        self.amount_total| == 0"), None);
+    assert_eq!(target("        self.write|({})"), None);
+    assert_eq!(target("        total = self.|"), None);
+    assert_eq!(target("        return self.|"), None);
+    assert_eq!(target("        # self.|"), None);
+    assert_eq!(target("        self|"), None);
+}
+
+/* Feed every prefix of the python files of the test addons, as if they were typed, and check the receivers found */
+#[test]
+fn test_truncated_files() {
+    let mut files = vec![];
+    collect_python_files(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons"), &mut files);
+    assert!(!files.is_empty());
+    for file in files.iter() {
+        let content = fs::read_to_string(file).unwrap();
+        for offset in (0..=content.len()).filter(|offset| content.is_char_boundary(*offset)) {
+            for source in [&content[..offset], content.as_str()] {
+                let CompletionContext::ATTRIBUTE(receiver, prefix) = CompletionContexts::extract(source, offset) else {
+                    continue;
+                };
+                let text = &source[receiver.range()];
+                assert!(!text.is_empty() && !text.contains('\n'), "{:?} in {}:{}", text, file.display(), offset);
+                assert!(!text.starts_with(char::is_whitespace) && !text.ends_with(char::is_whitespace), "{:?} in {}:{}", text, file.display(), offset);
+                //only the closing parentheses of the receiver are between the receiver and the dot
+                let after = &source[receiver.range().end().to_usize()..offset];
+                assert!(after.ends_with(&format!(".{}", prefix)) && after[..after.len() - prefix.len() - 1].chars().all(|c| c == ')' || c.is_whitespace()),
+                    "{:?} after {:?} in {}:{}", after, text, file.display(), offset);
+                assert!(prefix.chars().all(|c| c.is_alphanumeric() || c == '_'));
+            }
+        }
+    }
+}
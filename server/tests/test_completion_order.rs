@@ -0,0 +1,36 @@
+use lsp_types::{CompletionItem, CompletionItemLabelDetails};
+use odoo_ls_server::features::completion::CompletionFeature;
+
+fn item(label: &str, sort_text: Option<&str>, description: Option<&str>) -> CompletionItem {
+    CompletionItem {
+        label: label.to_string(),
+        sort_text: sort_text.map(|text| text.to_string()),
+        label_details: description.map(|description| CompletionItemLabelDetails { detail: None, description: Some(description.to_string()) }),
+        ..CompletionItem::default()
+    }
+}
+
+fn labels(items: &[CompletionItem]) -> Vec<String> {
+    items.iter().map(|item| format!("{}:{:?}", item.label, item.label_details.as_ref().and_then(|d| d.description.clone()))).collect()
+}
+
+#[test]
+fn test_sort_items() {
+    let items = vec![
+        item("name", Some("name"), Some("sale.order")),
+        item("_compute_name", Some("~_compute_name"), None),
+        item("date", None, None),
+        item("name", Some("name"), Some("res.partner")),
+        item("amount", Some("!amount"), None),
+    ];
+    let mut sorted = items.clone();
+    CompletionFeature::sort_items(&mut sorted);
+    assert_eq!(labels(&sorted), vec!["amount:None", "date:None", "name:Some(\"res.partner\")", "name:Some(\"sale.order\")", "_compute_name:None"]);
+    //the order does not depend on the order in which the members were collected
+    for shift in 1..items.len() {
+        let mut shifted = items.clone();
+        shifted.rotate_left(shift);
+        CompletionFeature::sort_items(&mut shifted);
+        assert_eq!(shifted, sorted);
+    }
+}
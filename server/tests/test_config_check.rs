@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::config_check::{ConfigCheck, ConfigIssue, ConfigReport, IndexMode};
+use odoo_ls_server::utils::PathSanitizer;
+
+fn create_folder(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_config_check_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+fn create_odoo(path: &PathBuf, version_info: &str) {
+    fs::create_dir_all(path.join("odoo")).unwrap();
+    fs::create_dir_all(path.join("addons")).unwrap();
+    fs::write(path.join("odoo").join("__init__.py"), "").unwrap();
+    fs::write(path.join("odoo").join("release.py"), format!("RELEASE_LEVELS = [ALPHA, BETA, RELEASE_CANDIDATE, FINAL]\n{}\n", version_info)).unwrap();
+}
+
+#[test]
+fn test_parse_release() {
+    assert_eq!(ConfigCheck::parse_release("version_info = (17, 0, 0, FINAL, 0, '')"), Some((17, 0, 0)));
+    assert_eq!(ConfigCheck::parse_release("version_info = ('saas~17', 2, 0, FINAL, 0, '')"), Some((17, 2, 0)));
+    assert_eq!(ConfigCheck::parse_release("serie = major_version = '.'.join(str(s) for s in version_info[:2])"), None);
+}
+
+#[test]
+fn test_parse_python_version() {
+    assert_eq!(ConfigCheck::parse_python_version("3.10.12\n"), Some((3, 10, 12)));
+    assert_eq!(ConfigCheck::parse_python_version("Python 3.12.0rc1"), Some((3, 12, 0)));
+    assert_eq!(ConfigCheck::parse_python_version("Python 3.8"), Some((3, 8, 0)));
+    assert_eq!(ConfigCheck::parse_python_version("command not found"), None);
+}
+
+#[test]
+fn test_check_odoo_path() {
+    let (_, issues) = ConfigCheck::check_odoo_path("");
+    assert!(issues[0].blocking);
+    let path = create_folder("odoo");
+    let (version, issues) = ConfigCheck::check_odoo_path(&path.sanitize());
+    assert_eq!(version, None);
+    assert!(issues[0].blocking && issues[0].message.contains("odoo/release.py"));
+    create_odoo(&path, "version_info = (17, 0, 0, FINAL, 0, '')");
+    assert_eq!(ConfigCheck::check_odoo_path(&path.sanitize()), (Some((17, 0, 0)), vec![]));
+    //the odoo package is selected instead of the folder of the sources
+    let (_, issues) = ConfigCheck::check_odoo_path(&path.join("odoo").sanitize());
+    assert!(issues[0].message.ends_with(&format!("Did you mean {}?", path.sanitize())));
+    create_odoo(&path, "version_info = (12, 0, 0, FINAL, 0, '')");
+    let (version, issues) = ConfigCheck::check_odoo_path(&path.sanitize());
+    assert_eq!(version, Some((12, 0, 0)));
+    assert!(issues[0].blocking);
+    let archive = path.join("odoo.zip");
+    fs::write(&archive, "").unwrap();
+    let (_, issues) = ConfigCheck::check_odoo_path(&archive.sanitize());
+    assert!(issues[0].message.contains("archive"));
+    let _ = fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_check_addons_path() {
+    let path = create_folder("addons");
+    assert!(ConfigCheck::check_addons_path(&path.join("missing").sanitize()).is_some());
+    let issue = ConfigCheck::check_addons_path(&path.sanitize()).unwrap();
+    assert!(!issue.blocking && issue.message.contains("does not contain any module"));
+    fs::create_dir_all(path.join("my_module")).unwrap();
+    fs::write(path.join("my_module").join("__manifest__.py"), "{'name': 'My Module'}").unwrap();
+    assert_eq!(ConfigCheck::check_addons_path(&path.sanitize()), None);
+    let issue = ConfigCheck::check_addons_path(&path.join("my_module").sanitize()).unwrap();
+    assert!(issue.message.contains("is a module"));
+    let _ = fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_index_mode() {
+    let mut report = ConfigReport::default();
+    assert_eq!(report.mode(), IndexMode::FULL);
+    report.issues.push(ConfigIssue { setting: String::from("addons"), message: String::from("The addons path does not exist"), blocking: false });
+    assert_eq!(report.mode(), IndexMode::FULL);
+    report.issues.push(ConfigIssue { setting: String::from("odooPath"), message: String::from("No odoo path is configured"), blocking: true });
+    assert_eq!(report.mode(), IndexMode::REDUCED);
+}
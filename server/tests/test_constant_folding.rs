@@ -0,0 +1,137 @@
+use ruff_python_ast::{Expr, Stmt};
+use odoo_ls_server::core::collection_folding::{CollectionFolding, Conversion};
+use odoo_ls_server::core::evaluation::EvaluationValue;
+use odoo_ls_server::core::python_utils::{fold_string, fold_string_list};
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn get_value(code: &str) -> Expr {
+    let module = parse_module(code);
+    match module.body.into_iter().next() {
+        Some(Stmt::Expr(expr)) => *expr.value,
+        _ => panic!("Not an expression")
+    }
+}
+
+#[test]
+fn test_fold_string() {
+    assert_eq!(fold_string(&get_value("'views/' 'sale.xml'")).as_deref(), Some("views/sale.xml"));
+    assert_eq!(fold_string(&get_value("'views/' + 'sale' + '.xml'")).as_deref(), Some("views/sale.xml"));
+    assert_eq!(fold_string(&get_value("os.path.join('views', 'sale.xml')")).as_deref(), Some("views/sale.xml"));
+    assert_eq!(fold_string(&get_value("os.path.join('views/', 'report', 'sale.xml')")).as_deref(), Some("views/report/sale.xml"));
+    assert_eq!(fold_string(&get_value("os.path.join('views', '/data/sale.xml')")).as_deref(), Some("/data/sale.xml"));
+    assert!(fold_string(&get_value("os.path.join('views', name)")).is_none());
+    assert!(fold_string(&get_value("'views/' + 1")).is_none());
+    assert!(fold_string(&get_value("join('views', 'sale.xml')")).is_none());
+}
+
+#[test]
+fn test_fold_string_list() {
+    assert_eq!(fold_string_list(&get_value("['security/ir.model.access.csv', *['views/a.xml', 'views/b.xml']]")).unwrap(),
+        vec!["security/ir.model.access.csv", "views/a.xml", "views/b.xml"]);
+    assert_eq!(fold_string_list(&get_value("['a.xml'] + [os.path.join('views', f) for f in ('b.xml', 'c.xml')]")).unwrap(),
+        vec!["a.xml", "views/b.xml", "views/c.xml"]);
+    assert_eq!(fold_string_list(&get_value("['views/' + f + '.xml' for f in ['sale', 'menus']]")).unwrap(),
+        vec!["views/sale.xml", "views/menus.xml"]);
+    assert!(fold_string_list(&get_value("[f for f in FILES]")).is_none());
+    assert!(fold_string_list(&get_value("[f for f in ['a.xml'] if f]")).is_none());
+    assert!(fold_string_list(&get_value("get_files()")).is_none());
+}
+
+/* Return the value of a collection expression, with the names resolved to the given values */
+fn fold(code: &str, names: &[(&str, EvaluationValue)]) -> Option<EvaluationValue> {
+    CollectionFolding::fold(&get_value(code), &mut |expr| match expr {
+        Expr::Name(name) => names.iter().find(|(id, _)| *id == name.id.as_str()).map(|(_, value)| value.clone()),
+        _ => None
+    })
+}
+
+/* Return the kind of a collection value, and its elements or keys as text */
+fn describe(value: &EvaluationValue) -> (&str, Vec<String>) {
+    let elements = CollectionFolding::get_elements(value).unwrap_or_default().iter().map(|e| match e {
+        Expr::StringLiteral(s) => s.value.to_string(),
+        Expr::NumberLiteral(_) => CollectionFolding::get_int(e, &mut |_| None).unwrap().to_string(),
+        _ => "<other>".to_string(),
+    }).collect();
+    let kind = match value {
+        EvaluationValue::LIST(_) => "list",
+        EvaluationValue::TUPLE(_) => "tuple",
+        EvaluationValue::SET(_) => "set",
+        EvaluationValue::DICT(_) => "dict",
+        _ => "other",
+    };
+    (kind, elements)
+}
+
+fn list(code: &str) -> EvaluationValue {
+    fold(code, &[]).expect("Not a constant collection")
+}
+
+#[test]
+fn test_slice_collection() {
+    let names = list("['a', 'b', 'c', 'd', 'e']");
+    let slice = |lower, upper, step| CollectionFolding::slice(&names, lower, upper, step).map(|v| describe(&v).1.join(""));
+    assert_eq!(slice(None, Some(3), None).as_deref(), Some("abc"));
+    assert_eq!(slice(Some(-2), None, None).as_deref(), Some("de"));
+    assert_eq!(slice(Some(1), Some(-1), Some(2)).as_deref(), Some("bd"));
+    assert_eq!(slice(None, None, Some(-1)).as_deref(), Some("edcba"));
+    assert_eq!(slice(Some(3), Some(0), Some(-2)).as_deref(), Some("db"));
+    assert_eq!(slice(Some(10), Some(20), None).as_deref(), Some(""));
+    assert_eq!(slice(Some(-10), Some(2), None).as_deref(), Some("ab"));
+    assert!(slice(None, None, Some(0)).is_none());
+    assert_eq!(describe(&CollectionFolding::slice(&list("('a', 'b')"), Some(1), None, None).unwrap()).0, "tuple");
+    assert!(CollectionFolding::slice(&list("{'a', 'b'}"), Some(1), None, None).is_none());
+}
+
+#[test]
+fn test_repeat_and_concat_collection() {
+    let names = list("['a', 'b']");
+    assert_eq!(describe(&CollectionFolding::repeat(&names, 2).unwrap()), ("list", vec![S!("a"), S!("b"), S!("a"), S!("b")]));
+    assert_eq!(describe(&CollectionFolding::repeat(&names, 1).unwrap()).1, vec!["a", "b"]);
+    assert!(describe(&CollectionFolding::repeat(&names, -1).unwrap()).1.is_empty());
+    assert!(CollectionFolding::repeat(&names, 1_000_000).is_none());
+    assert!(CollectionFolding::repeat(&list("{'a'}"), 2).is_none());
+    assert_eq!(describe(&CollectionFolding::concat(&list("('a',)"), &list("('b',)")).unwrap()), ("tuple", vec![S!("a"), S!("b")]));
+    assert!(CollectionFolding::concat(&names, &list("('b',)")).is_none());
+}
+
+#[test]
+fn test_convert_collection() {
+    let names = list("['b', 'a', 'b']");
+    assert_eq!(describe(&CollectionFolding::convert(&names, Conversion::LIST).unwrap()), ("list", vec![S!("b"), S!("a"), S!("b")]));
+    assert_eq!(describe(&CollectionFolding::convert(&names, Conversion::TUPLE).unwrap()), ("tuple", vec![S!("b"), S!("a"), S!("b")]));
+    assert_eq!(describe(&CollectionFolding::convert(&names, Conversion::SET).unwrap()), ("set", vec![S!("b"), S!("a")]));
+    assert_eq!(describe(&CollectionFolding::convert(&names, Conversion::SORTED).unwrap()), ("list", vec![S!("a"), S!("b"), S!("b")]));
+    assert_eq!(describe(&CollectionFolding::convert(&names, Conversion::FROMKEYS).unwrap()), ("dict", vec![S!("b"), S!("a")]));
+    assert_eq!(describe(&CollectionFolding::convert(&list("[3, 1, 2]"), Conversion::SORTED).unwrap()).1, vec!["1", "2", "3"]);
+    assert!(CollectionFolding::convert(&list("['a', 1]"), Conversion::SORTED).is_none());
+    //iterating on a dict gives its keys
+    assert_eq!(describe(&CollectionFolding::convert(&list("{'a': 1, 'b': 2}"), Conversion::LIST).unwrap()), ("list", vec![S!("a"), S!("b")]));
+}
+
+#[test]
+fn test_fold_collection() {
+    let base = list("['sale', 'purchase', 'stock', 'mrp']");
+    let names = [("BASE", base), ("COUNT", EvaluationValue::CONSTANT(get_value("2")))];
+    assert_eq!(describe(&fold("BASE[:2] + BASE[-1:]", &names).unwrap()), ("list", vec![S!("sale"), S!("purchase"), S!("mrp")]));
+    assert_eq!(describe(&fold("tuple(BASE[1:3]) + ('extra',)", &names).unwrap()), ("tuple", vec![S!("purchase"), S!("stock"), S!("extra")]));
+    assert_eq!(describe(&fold("list(dict.fromkeys(BASE * COUNT))", &names).unwrap()).1, vec!["sale", "purchase", "stock", "mrp"]);
+    assert_eq!(describe(&fold("sorted(set(BASE))", &names).unwrap()).1, vec!["mrp", "purchase", "sale", "stock"]);
+    assert_eq!(describe(&fold("2 * [*BASE[::3], 'x']", &names).unwrap()).1, vec!["sale", "mrp", "x", "sale", "mrp", "x"]);
+    assert!(fold("BASE[:n]", &names).is_none());
+    assert!(fold("OTHER[:2]", &names).is_none());
+    assert!(fold("sorted(BASE, key=len)", &names).is_none());
+    assert!(fold("BASE[0]", &names).is_none());
+}
+
+#[test]
+fn test_fold_string_list_operations() {
+    assert_eq!(fold_string_list(&get_value("['a.xml', 'b.xml', 'c.xml'][1:]")).unwrap(), vec!["b.xml", "c.xml"]);
+    assert_eq!(fold_string_list(&get_value("['a.xml'] * 2")).unwrap(), vec!["a.xml", "a.xml"]);
+    assert_eq!(fold_string_list(&get_value("sorted(['b.xml', 'a.xml'])")).unwrap(), vec!["a.xml", "b.xml"]);
+    assert_eq!(fold_string_list(&get_value("list(dict.fromkeys(['a.xml', 'b.xml', 'a.xml']))")).unwrap(), vec!["a.xml", "b.xml"]);
+    assert!(fold_string_list(&get_value("['a.xml'][:n]")).is_none());
+}
@@ -0,0 +1,68 @@
+use ruff_python_ast::Expr;
+use odoo_ls_server::core::container_mutations::{ContainerMutations, MutationKind};
+use odoo_ls_server::core::evaluation::EvaluationValue;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn keys(value: &EvaluationValue) -> Vec<String> {
+    let EvaluationValue::DICT(items) = value else {
+        panic!("Not a dict");
+    };
+    items.iter().map(|(key, _)| match key {
+        Expr::StringLiteral(s) => s.value.to_string(),
+        _ => "<other>".to_string(),
+    }).collect()
+}
+
+/* Apply the mutations of the statements, in order, to the given value */
+fn mutate(source: &str, mut value: EvaluationValue) -> EvaluationValue {
+    let module = parse_module(source);
+    for stmt in module.body.iter() {
+        let mutation = ContainerMutations::find(stmt).expect("Not a mutation");
+        value = ContainerMutations::apply(&mutation, value).expect("Mutation not applied");
+    }
+    value
+}
+
+#[test]
+fn test_find_mutations() {
+    let module = parse_module("vals['a'] = 1\nvals.update(b=2)\nvals |= other\nl.append(1)\nl += [2]\ns.add(3)\nvals.get('a')\nself.vals['a'] = 1\nl.append(1, 2)\n");
+    let kinds: Vec<Option<MutationKind>> = module.body.iter().map(|stmt| ContainerMutations::find(stmt).map(|m| m.kind)).collect();
+    assert_eq!(kinds, vec![
+        Some(MutationKind::SET_ITEM), Some(MutationKind::UPDATE), Some(MutationKind::UPDATE),
+        Some(MutationKind::APPEND), Some(MutationKind::EXTEND), Some(MutationKind::ADD),
+        None, None, None,
+    ]);
+}
+
+#[test]
+fn test_dict_mutations() {
+    let value = mutate("vals['partner_id'] = pid\nvals.update({'name': 'x', 'partner_id': 2}, ref=3)\n", EvaluationValue::DICT(vec![]));
+    assert_eq!(keys(&value), vec!["name", "partner_id", "ref"]);
+    assert!(!ContainerMutations::is_open(&value));
+
+    //a non-literal mutation keeps the known keys, but opens the dict
+    let value = mutate("vals.update(extra)\n", value);
+    assert_eq!(keys(&value), vec!["name", "partner_id", "ref", "<other>"]);
+    assert!(ContainerMutations::is_open(&value));
+}
+
+#[test]
+fn test_list_and_set_mutations() {
+    let EvaluationValue::LIST(items) = mutate("l.append('a')\nl.extend(['b', 'c'])\nl += ('d',)\n", EvaluationValue::LIST(vec![])) else {
+        panic!("Not a list");
+    };
+    assert_eq!(items.len(), 4);
+
+    let EvaluationValue::SET(items) = mutate("s.add('a')\ns.add('a')\n", EvaluationValue::SET(vec![])) else {
+        panic!("Not a set");
+    };
+    assert_eq!(items.len(), 1);
+
+    //a mutation of another kind of container is not applied
+    let module = parse_module("l.add('a')\n");
+    let mutation = ContainerMutations::find(&module.body[0]).unwrap();
+    assert!(ContainerMutations::apply(&mutation, EvaluationValue::LIST(vec![])).is_none());
+}
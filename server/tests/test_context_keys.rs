@@ -0,0 +1,65 @@
+use odoo_ls_server::core::context_keys::{ContextKeyIndex, ContextKeys};
+use ruff_text_size::TextSize;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+fn set_keys(code: &str) -> Vec<String> {
+    ContextKeys::find_set_keys(&parse_valid(code)).into_iter().map(|(key, _)| key).collect()
+}
+
+#[test]
+fn test_find_set_keys() {
+    assert_eq!(set_keys("self.with_context(skip_check=True, lang='fr_FR').write({})\n"), vec!["skip_check", "lang"]);
+    assert_eq!(set_keys("self.with_context({'active_test': False}).search([])\n"), vec!["active_test"]);
+    assert_eq!(set_keys("ctx = dict(self.env.context, default_partner_id=1)\n"), vec!["default_partner_id"]);
+    assert_eq!(set_keys("new_context = {'import_file': True}\nnew_context['tracking_disable'] = True\n"), vec!["import_file", "tracking_disable"]);
+    assert_eq!(set_keys("context.update({'from_wizard': True}, mail_notrack=True)\n"), vec!["mail_notrack", "from_wizard"]);
+    assert_eq!(set_keys("action = {'res_model': 'sale.order', 'context': {'default_origin': self.name}}\n"), vec!["default_origin"]);
+    assert_eq!(set_keys("self.env['ir.actions.act_window']._for_xml_id('x', context={'search_default_draft': 1})\n"), vec!["search_default_draft"]);
+    //a read, or a dict that is not a context, doesn't set a key
+    assert_eq!(set_keys("vals = {'name': 'x'}\nif self.env.context.get('skip_check'):\n    vals['state'] = ctx['lang']\n"), Vec::<String>::new());
+}
+
+#[test]
+fn test_read_keys() {
+    let code = "if self.env.context.get('skip_check') and self._context['lang']:\n    vals.get('name')\n";
+    let ast = parse_valid(code);
+    let key_at = |needle: &str| ContextKeys::get_read_key_at(&ast, TextSize::new(code.find(needle).unwrap() as u32 + 1))
+        .map(|literal| literal.value.to_string());
+    assert_eq!(key_at("skip_check"), Some("skip_check".to_string()));
+    assert_eq!(key_at("lang"), Some("lang".to_string()));
+    assert_eq!(key_at("name"), None);
+    assert!(ContextKeys::is_context(&parse_valid("self.env.context").remove(0).as_expr_stmt().unwrap().value));
+    assert!(ContextKeys::is_context(&parse_valid("local_ctx").remove(0).as_expr_stmt().unwrap().value));
+    assert!(!ContextKeys::is_context(&parse_valid("self.contexts").remove(0).as_expr_stmt().unwrap().value));
+}
+
+#[test]
+fn test_builtin_keys() {
+    assert!(ContextKeys::is_builtin_key("active_test"));
+    assert!(ContextKeys::is_builtin_key("default_partner_id"));
+    assert!(ContextKeys::is_builtin_key("search_default_draft"));
+    assert!(!ContextKeys::is_builtin_key("default_"));
+    assert!(!ContextKeys::is_builtin_key("defautl_partner_id"));
+    assert_eq!(ContextKeys::get_default_field("default_partner_id"), Some("partner_id"));
+    assert_eq!(ContextKeys::get_default_field("search_default_draft"), None);
+}
+
+#[test]
+fn test_index() {
+    let mut index = ContextKeyIndex::new();
+    index.update_file("/b.py", &parse_valid("self.with_context(skip_check=True)\n"));
+    index.update_file("/a.py", &parse_valid("x = 1\nself.with_context(skip_check=True, lang='fr')\n"));
+    assert!(index.contains("skip_check"));
+    let locations: Vec<(String, u32)> = index.get_locations("skip_check").into_iter().map(|(path, range)| (path, range.start().to_u32())).collect();
+    assert_eq!(locations, vec![("/a.py".to_string(), 24), ("/b.py".to_string(), 18)]);
+    //the keys of a file are replaced when it is rebuilt, and removed with it
+    index.update_file("/a.py", &parse_valid("x = 1\n"));
+    assert!(!index.contains("lang"));
+    assert_eq!(index.get_locations("skip_check").len(), 1);
+    index.remove_file("/b.py");
+    assert!(!index.contains("skip_check"));
+    assert_eq!(index.keys().count(), 0);
+}
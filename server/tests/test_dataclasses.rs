@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::SignatureHelp;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::function_symbol::ArgumentType;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::signature_help::SignatureHelpFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_type_names;
+
+fn records_tree() -> Vec<String> {
+    vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("records")]
+}
+
+/* Return the signature help at the end of the first occurrence of marker in the file */
+fn get_signature_help(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str) -> Option<SignatureHelp> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(marker).unwrap() + marker.len();
+    let position = file_info.borrow().offset_to_position(offset);
+    SignatureHelpFeature::get_signature_help(session, file, &file_info, position.line, position.character)
+}
+
+#[test]
+fn test_dataclasses() {
+    let mut odoo = setup::setup::setup_server();
+    check_dataclass_init(&mut odoo);
+    check_named_tuple(&mut odoo);
+}
+
+/* The __init__ of a dataclass is generated from its fields: the ClassVar are not fields, the fields after KW_ONLY are
+keyword-only, and the ones of a base dataclass come first, a redeclared field keeping its position */
+fn check_dataclass_init(odoo: &mut SyncOdoo) {
+    let line_init = odoo.get_symbol(&(records_tree(), vec![S!("Line"), S!("__init__")]), u32::MAX).pop();
+    let line_init = line_init.expect("the __init__ of Line should be generated");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    let args: Vec<(String, ArgumentType, bool)> = line_init.borrow().as_func().args.iter()
+        .map(|arg| (arg.symbol.upgrade().unwrap().borrow().name().clone(), arg.arg_type, arg.default_value.is_some()))
+        .collect();
+    assert_eq!(args, vec![
+        (S!("self"), ArgumentType::ARG, false),
+        (S!("product"), ArgumentType::ARG, false),
+        (S!("qty"), ArgumentType::ARG, true),
+        (S!("taxes"), ArgumentType::ARG, true),
+        (S!("note"), ArgumentType::KWORD_ONLY, true),
+    ]);
+    assert!(line_init.borrow().as_func().is_synthesized);
+    //the parameters are evaluated to their fields
+    let qty = line_init.borrow().get_content_symbol("qty", u32::MAX).pop().unwrap();
+    assert_eq!(get_type_names(&mut session, &qty), vec![S!("int")]);
+
+    let file = session.sync_odoo.get_symbol(&(records_tree(), vec![]), u32::MAX).pop().unwrap();
+    let help = get_signature_help(&mut session, &file, "line = Line(").unwrap();
+    assert_eq!(help.signatures[0].label, S!("Line(product, qty=..., taxes=..., *, note=...)"));
+    let help = get_signature_help(&mut session, &file, "\nDiscountedLine(").unwrap();
+    assert_eq!(help.signatures[0].label, S!("DiscountedLine(product, qty=..., taxes=..., *, note=..., discount=...)"));
+}
+
+/* A NamedTuple gets the same __init__, and its instances are unpacked to its fields */
+fn check_named_tuple(odoo: &mut SyncOdoo) {
+    let function = odoo.get_symbol(&(records_tree(), vec![S!("Record"), S!("unpack")]), u32::MAX).pop();
+    let function = function.expect("unpack should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    let file = session.sync_odoo.get_symbol(&(records_tree(), vec![]), u32::MAX).pop().unwrap();
+    let help = get_signature_help(&mut session, &file, "\nPoint(").unwrap();
+    assert_eq!(help.signatures[0].label, S!("Point(x, y=...)"));
+
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    for (name, expected) in [("a", "int"), ("b", "str"), ("c", "int"), ("d", "str")] {
+        let variable = function.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+        assert_eq!(get_type_names(&mut session, &variable), vec![S!(expected)], "type of {}", name);
+    }
+}
@@ -0,0 +1,17 @@
+use ruff_python_ast::Operator;
+use odoo_ls_server::core::date_arithmetic::{DateArithmetic, DATE, DATETIME, RELATIVEDELTA, TIMEDELTA};
+
+#[test]
+fn test_date_arithmetic() {
+    assert_eq!(DateArithmetic::get_result_type(DATE, Operator::Add, RELATIVEDELTA), Some(DATE));
+    assert_eq!(DateArithmetic::get_result_type(TIMEDELTA, Operator::Add, DATETIME), Some(DATETIME));
+    assert_eq!(DateArithmetic::get_result_type(DATETIME, Operator::Sub, TIMEDELTA), Some(DATETIME));
+    assert_eq!(DateArithmetic::get_result_type(DATETIME, Operator::Sub, DATETIME), Some(TIMEDELTA));
+    assert_eq!(DateArithmetic::get_result_type(TIMEDELTA, Operator::Add, TIMEDELTA), Some(TIMEDELTA));
+    assert_eq!(DateArithmetic::get_result_type(TIMEDELTA, Operator::Mult, "builtins.int"), Some(TIMEDELTA));
+    //a date can't be subtracted from a datetime, nor added to another date
+    assert_eq!(DateArithmetic::get_result_type(DATETIME, Operator::Sub, DATE), None);
+    assert_eq!(DateArithmetic::get_result_type(DATE, Operator::Add, DATE), None);
+    assert_eq!(DateArithmetic::get_result_type(TIMEDELTA, Operator::Sub, DATE), None);
+    assert_eq!(DateArithmetic::get_result_type("builtins.int", Operator::Add, "builtins.int"), None);
+}
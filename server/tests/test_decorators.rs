@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::{flatten_tree, BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::{get_declarations, get_evaluated_names};
+
+fn get_method(odoo: &SyncOdoo, name: &str) -> Rc<RefCell<Symbol>> {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("decorators")];
+    odoo.get_symbol(&(file_tree, vec![S!("Decorated"), S!(name)]), u32::MAX).pop().expect("method should be loaded")
+}
+
+/* The decorators are resolved to their symbols, whatever the name they are imported with */
+#[test]
+fn test_decorators() {
+    let mut odoo = setup::setup::setup_server();
+    assert!(get_method(&odoo, "display").borrow().as_func().is_property);
+    assert!(get_method(&odoo, "other_display").borrow().as_func().is_property);
+    assert!(get_method(&odoo, "build").borrow().as_func().is_static);
+    let compute = get_method(&odoo, "_compute_name");
+    assert!(!compute.borrow().as_func().is_property);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    //the decorators called with arguments are stored as the decorator they call
+    let decorators = compute.borrow().as_func().decorators.clone();
+    assert_eq!(decorators.len(), 1);
+    let depends = decorators[0].symbol.get_symbol(&mut session, &mut None, &mut vec![], None).weak.upgrade().unwrap();
+    assert_eq!(flatten_tree(&depends.borrow().get_tree()).join("."), "odoo.api.depends");
+    //the attributes read on a cached property are looked up on its value
+    compute.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(compute.clone()).validate(&mut session);
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&compute, "label")[0]), vec![S!("str")]);
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&compute, "other_label")[0]), vec![S!("str")]);
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::HoverContents;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_type_names;
+
+fn models_file(session: &mut SessionInfo, name: &str) -> Rc<RefCell<Symbol>> {
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!(name)];
+    session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().unwrap()
+}
+
+/* Return the hover of the start of the first line beginning with marker in the file */
+fn get_hover(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str) -> String {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(&format!("\n{}", marker)).unwrap() + 1;
+    let position = file_info.borrow().offset_to_position(offset);
+    let hover = HoverFeature::get_hover(session, file, &file_info, position.line, position.character).unwrap();
+    let HoverContents::Markup(content) = hover.contents else {
+        panic!("the hover should be markdown");
+    };
+    content.value
+}
+
+/* The return annotations naming the classes imported under TYPE_CHECKING, quoted or deferred by the import of
+annotations from __future__, are evaluated on both sides of the circular import, like the forward references to the
+classes declared later in the file */
+#[test]
+fn test_type_checking_annotations() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    for (file_name, expected) in [
+        ("deferred_partner", vec![("invoice", "Invoice"), ("address", "Address")]),
+        ("deferred_invoice", vec![("partner", "Partner"), ("line", "InvoiceLine")]),
+    ] {
+        let file = models_file(&mut session, file_name);
+        for (name, class) in expected.into_iter() {
+            let variable = file.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+            assert_eq!(get_type_names(&mut session, &variable), vec![S!(class)], "type of {} in {}", name, file_name);
+            let hover = get_hover(&mut session, &file, &format!("{} = ", name));
+            assert!(hover.contains(class), "hover of {} in {}: {}", name, file_name, hover);
+        }
+        file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+        let mut validator = PythonValidator::new(file.clone());
+        validator.validate(&mut session);
+        assert!(validator.diagnostics.is_empty(), "diagnostics of {}: {:?}", file_name, validator.diagnostics);
+    }
+}
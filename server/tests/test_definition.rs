@@ -0,0 +1,32 @@
+use odoo_ls_server::features::ast_utils::AstUtils;
+use odoo_ls_server::features::definition::DefinitionFeature;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+#[test]
+fn test_find_named_exprs() {
+    let module = parse_module("\
+if (n := len(a)) > 10 and (m := n * 2):
+    b = (c := 3)
+f = lambda x: (y := x)
+labels = [label for value in values if (label := str(value))]
+");
+    let names: Vec<String> = AstUtils::find_named_exprs(&module.body[0]).iter().map(|n| n.target.as_name_expr().unwrap().id.to_string()).collect();
+    //the body of the if is another statement
+    assert_eq!(names, vec!["n", "m"]);
+    //the walrus of a lambda belongs to the scope of the lambda
+    assert!(AstUtils::find_named_exprs(&module.body[1]).is_empty());
+    //the walrus of a comprehension binds the name in the enclosing scope
+    let names: Vec<String> = AstUtils::find_named_exprs(&module.body[2]).iter().map(|n| n.target.as_name_expr().unwrap().id.to_string()).collect();
+    assert_eq!(names, vec!["label"]);
+}
+
+#[test]
+fn test_find_definition_name() {
+    let text = "@api.depends('name')\n@api.model\ndef name(self):\n    pass";
+    assert_eq!(DefinitionFeature::find_definition_name(text, "def", "name"), Some(36));
+    assert_eq!(DefinitionFeature::find_definition_name("class  Partner(models.Model):", "class", "Partner"), Some(7));
+    assert_eq!(DefinitionFeature::find_definition_name("def names(self):", "def", "name"), None);
+}
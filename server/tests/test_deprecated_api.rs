@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{NumberOrString, Position, Range};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::deprecated_api::DeprecatedApiTable;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_deprecated_api_table() {
+    let (table, errors) = DeprecatedApiTable::load("");
+    assert!(errors.is_empty());
+    //the entries apply from their version
+    assert!(table.find_decorator((13, 0), "api.multi").is_none());
+    assert!(table.find_decorator((14, 0), "api.multi").is_some());
+    assert!(table.find_decorator((17, 0), "odoo.api.multi").is_some());
+    assert!(table.find_decorator((17, 0), "multi").is_none());
+    assert!(table.find_member((16, 0), Some("res.partner"), "name_get").is_none());
+    assert!(table.find_member((17, 0), Some("res.partner"), "name_get").is_some());
+    assert!(table.find_function((12, 0), "odoo.fields.Date.from_string").is_some());
+
+    //the user entries extend the embedded table, and the invalid ones are reported
+    let user_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("deprecated_api").join("user_table.json").sanitize();
+    let (table, errors) = DeprecatedApiTable::load(&user_path);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(table.find_decorator((14, 0), "api.multi").is_some());
+    assert!(table.find_function((14, 0), "odoo.tools.misc.old_helper").is_none());
+    assert!(table.find_function((16, 0), "odoo.tools.misc.old_helper").is_some());
+    assert!(table.find_function((17, 0), "odoo.tools.misc.old_helper").is_none());
+    assert!(table.find_decorator((16, 0), "api.returns").is_none());
+
+    let (table, errors) = DeprecatedApiTable::load("/nonexistent/deprecated_api.json");
+    assert_eq!(errors.len(), 1);
+    assert!(table.find_decorator((14, 0), "api.multi").is_some());
+}
+
+/* Return the ranges of the deprecation diagnostics of the file, validated against the given version of Odoo. The
+ranges of the diagnostics of the validator are offsets, converted to positions when they are published */
+fn get_deprecations(session: &mut SessionInfo, path: &PathBuf, version: (u32, u32)) -> Vec<Range> {
+    (session.sync_odoo.version_major, session.sync_odoo.version_minor) = version;
+    let file = session.sync_odoo.get_file_symbol(path).expect("deprecated_decorators should be loaded");
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(session);
+    let mut ranges: Vec<Range> = validator.diagnostics.iter()
+        .filter(|d| d.code == Some(NumberOrString::String(S!("OLS20007"))))
+        .map(|d| d.range)
+        .collect();
+    ranges.sort_by_key(|range| range.start.line);
+    ranges
+}
+
+#[test]
+fn test_deprecated_api_diagnostics() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join("deprecated_decorators.py");
+    let source = fs::read_to_string(&path).unwrap();
+    let range_of = |text: &str| {
+        let start = source.find(text).unwrap() as u32;
+        Range::new(Position::new(start, 0), Position::new(start + text.len() as u32, 0))
+    };
+    let version = (session.sync_odoo.version_major, session.sync_odoo.version_minor);
+    assert!(get_deprecations(&mut session, &path, (13, 0)).is_empty());
+    //the range of a deprecated decorator is the decorator, and the one of an override is the name of the method
+    assert_eq!(get_deprecations(&mut session, &path, (14, 0)), vec![range_of("@api.multi")]);
+    assert_eq!(get_deprecations(&mut session, &path, (17, 0)), vec![range_of("@api.multi"), range_of("name_get")]);
+    //an unknown version disables the check
+    assert!(get_deprecations(&mut session, &path, (0, 0)).is_empty());
+    (session.sync_odoo.version_major, session.sync_odoo.version_minor) = version;
+}
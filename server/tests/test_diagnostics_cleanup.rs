@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crossbeam_channel::Receiver;
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{DeleteFilesParams, DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams, FileChangeType, FileDelete, FileEvent, PublishDiagnosticsParams, Uri, WorkspaceFolder, WorkspaceFoldersChangeEvent};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::{InitState, Odoo, SyncOdoo};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+/* These tests check that the diagnostics of a file are cleared on the client side when the file leaves the
+analysis scope, whatever the way it leaves it. */
+
+fn create_workspace(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("odoo_ls_tests_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("module")).unwrap();
+    root
+}
+
+fn path_to_uri(path: &String) -> Uri {
+    FileMgr::pathname2uri(path)
+}
+
+/* Create a file with a syntax error in the workspace and publish its diagnostics */
+fn publish_file_with_error(session: &mut SessionInfo, receiver: &Receiver<Message>, path: &PathBuf) -> String {
+    fs::write(path, "def broken(:\n    pass\n").unwrap();
+    let path = path.sanitize();
+    let (_, file_info) = session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &path, None, None, false);
+    file_info.borrow_mut().publish_diagnostics(session);
+    let published = get_published_diagnostics(receiver);
+    assert!(published.iter().any(|p| p.uri == path_to_uri(&path) && !p.diagnostics.is_empty()));
+    assert!(session.sync_odoo.get_file_mgr().borrow().is_published(&path));
+    path
+}
+
+fn get_published_diagnostics(receiver: &Receiver<Message>) -> Vec<PublishDiagnosticsParams> {
+    let mut res = vec![];
+    while let Ok(msg) = receiver.try_recv() {
+        if let Message::Notification(n) = msg {
+            if n.method == PublishDiagnostics::METHOD {
+                res.push(serde_json::from_value(n.params).unwrap());
+            }
+        }
+    }
+    res
+}
+
+fn assert_cleared(session: &mut SessionInfo, receiver: &Receiver<Message>, path: &String) {
+    let published = get_published_diagnostics(receiver);
+    assert!(published.iter().any(|p| p.uri == path_to_uri(path) && p.diagnostics.is_empty()), "No empty diagnostics published for {}", path);
+    assert!(!session.sync_odoo.get_file_mgr().borrow().is_published(path));
+    assert!(session.sync_odoo.get_file_mgr().borrow().get_file_info(path).is_none());
+}
+
+#[test]
+fn test_cleanup_on_unload() {
+    let root = create_workspace("unload");
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    file_mgr.borrow_mut().delete_path(&mut session, &path);
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_cleanup_on_delete_event() {
+    let root = create_workspace("delete");
+    let mut odoo = SyncOdoo::new();
+    odoo.state_init = InitState::ODOO_READY;
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    fs::remove_file(&path).unwrap();
+    Odoo::handle_did_delete(&mut session, DeleteFilesParams {
+        files: vec![FileDelete { uri: path_to_uri(&path).to_string() }]
+    });
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_cleanup_on_directory_delete_event() {
+    let root = create_workspace("delete_dir");
+    let mut odoo = SyncOdoo::new();
+    odoo.state_init = InitState::ODOO_READY;
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    let module_path = root.join("module").sanitize();
+    fs::remove_dir_all(&module_path).unwrap();
+    Odoo::handle_did_delete(&mut session, DeleteFilesParams {
+        files: vec![FileDelete { uri: path_to_uri(&module_path).to_string() }]
+    });
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_cleanup_on_watched_file_deletion() {
+    let root = create_workspace("watched");
+    let mut odoo = SyncOdoo::new();
+    odoo.state_init = InitState::ODOO_READY;
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    fs::remove_file(&path).unwrap();
+    Odoo::handle_did_change_watched_files(&mut session, DidChangeWatchedFilesParams {
+        changes: vec![FileEvent { uri: path_to_uri(&path), typ: FileChangeType::DELETED }]
+    });
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_cleanup_on_workspace_folder_removal() {
+    let root = create_workspace("workspace");
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    Odoo::handle_did_change_workspace_folders(&mut session, DidChangeWorkspaceFoldersParams {
+        event: WorkspaceFoldersChangeEvent {
+            added: vec![],
+            removed: vec![WorkspaceFolder { uri: path_to_uri(&root.sanitize()), name: "workspace".to_string() }],
+        }
+    });
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_cleanup_on_cache_clear() {
+    /* Changing the addons paths resets the database, that clears the whole file cache */
+    let root = create_workspace("clear");
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(root.sanitize());
+    let path = publish_file_with_error(&mut session, &r, &root.join("module").join("file.py"));
+
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    file_mgr.borrow_mut().clear(&mut session);
+    assert_cleared(&mut session, &r, &path);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,33 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileInfo;
+
+fn diagnostic(code: &str, severity: DiagnosticSeverity) -> Diagnostic {
+    Diagnostic::new(Range::new(Position::new(1, 0), Position::new(1, 5)), Some(severity),
+        Some(NumberOrString::String(code.to_string())), None, code.to_string(), None, None)
+}
+
+fn codes(diagnostics: &Vec<Diagnostic>) -> Vec<String> {
+    diagnostics.iter().map(|d| match &d.code {
+        Some(NumberOrString::String(code)) => code.clone(),
+        _ => String::new()
+    }).collect()
+}
+
+#[test]
+fn test_diagnostics_limit() {
+    let diagnostics = vec![
+        (BuildSteps::VALIDATION, diagnostic("hint", DiagnosticSeverity::HINT)),
+        (BuildSteps::VALIDATION, diagnostic("validation_error", DiagnosticSeverity::ERROR)),
+        (BuildSteps::ARCH_EVAL, diagnostic("warning", DiagnosticSeverity::WARNING)),
+        (BuildSteps::SYNTAX, diagnostic("syntax_error", DiagnosticSeverity::ERROR)),
+    ];
+    //under the limit, or without limit, everything is kept
+    assert_eq!(FileInfo::limit_diagnostics(diagnostics.clone(), 4).len(), 4);
+    assert_eq!(FileInfo::limit_diagnostics(diagnostics.clone(), 0).len(), 4);
+
+    let limited = FileInfo::limit_diagnostics(diagnostics, 2);
+    assert_eq!(codes(&limited), vec!["syntax_error", "validation_error", "OLS10002"]);
+    assert!(limited[2].message.starts_with("2 diagnostics are not shown"));
+    assert_eq!(limited[2].range.start, Position::new(0, 0));
+}
@@ -0,0 +1,79 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileInfo;
+
+fn diagnostic(line: u32, code: &str, severity: DiagnosticSeverity) -> Diagnostic {
+    Diagnostic::new(Range::new(Position::new(line, 0), Position::new(line, 5)), Some(severity),
+        Some(NumberOrString::String(code.to_string())), None, format!("{} on line {}", code, line), None, None)
+}
+
+fn codes(diagnostics: &[(BuildSteps, Diagnostic)]) -> Vec<String> {
+    diagnostics.iter().map(|(_, d)| match &d.code {
+        Some(NumberOrString::String(code)) => format!("{}:{}", d.range.start.line, code),
+        _ => String::new()
+    }).collect()
+}
+
+#[test]
+fn test_duplicates() {
+    let merged = FileInfo::merge_diagnostics(vec![
+        (BuildSteps::ARCH, diagnostic(1, "OLS20001", DiagnosticSeverity::WARNING)),
+        (BuildSteps::VALIDATION, diagnostic(1, "OLS20001", DiagnosticSeverity::ERROR)),
+        (BuildSteps::ARCH_EVAL, diagnostic(1, "OLS20001", DiagnosticSeverity::HINT)),
+    ]);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].0, BuildSteps::VALIDATION);
+    assert_eq!(merged[0].1.severity, Some(DiagnosticSeverity::ERROR));
+    //on a tie, the diagnostic of the later step is kept
+    let merged = FileInfo::merge_diagnostics(vec![
+        (BuildSteps::ARCH_EVAL, diagnostic(1, "OLS20001", DiagnosticSeverity::WARNING)),
+        (BuildSteps::ARCH, diagnostic(1, "OLS20001", DiagnosticSeverity::WARNING)),
+    ]);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].0, BuildSteps::ARCH_EVAL);
+}
+
+#[test]
+fn test_superseded() {
+    let merged = FileInfo::merge_diagnostics(vec![
+        (BuildSteps::ARCH, diagnostic(1, "OLS20001", DiagnosticSeverity::WARNING)),
+        (BuildSteps::ARCH_EVAL, diagnostic(1, "OLS20004", DiagnosticSeverity::WARNING)),
+        //another family on the same range is kept
+        (BuildSteps::ARCH, diagnostic(1, "OLS20101", DiagnosticSeverity::WARNING)),
+        //the same family on another range is kept
+        (BuildSteps::ARCH, diagnostic(2, "OLS20001", DiagnosticSeverity::WARNING)),
+        //an earlier step doesn't supersede a later one
+        (BuildSteps::ARCH_EVAL, diagnostic(3, "OLS30301", DiagnosticSeverity::ERROR)),
+        (BuildSteps::ARCH, diagnostic(3, "OLS30302", DiagnosticSeverity::ERROR)),
+        //the codes that are not of the server have no family
+        (BuildSteps::ARCH, diagnostic(4, "E501", DiagnosticSeverity::WARNING)),
+        (BuildSteps::VALIDATION, diagnostic(4, "E502", DiagnosticSeverity::WARNING)),
+    ]);
+    assert_eq!(codes(&merged), vec!["1:OLS20004", "1:OLS20101", "2:OLS20001", "3:OLS30301", "4:E501", "4:E502"]);
+}
+
+#[test]
+fn test_stable_payload() {
+    let diagnostics = vec![
+        (BuildSteps::VALIDATION, diagnostic(5, "OLS30311", DiagnosticSeverity::ERROR)),
+        (BuildSteps::ARCH_EVAL, diagnostic(2, "OLS20004", DiagnosticSeverity::WARNING)),
+        (BuildSteps::SYNTAX, diagnostic(7, "OLS30001", DiagnosticSeverity::ERROR)),
+        (BuildSteps::VALIDATION, diagnostic(2, "OLS30311", DiagnosticSeverity::ERROR)),
+        (BuildSteps::ARCH, diagnostic(2, "OLS20001", DiagnosticSeverity::WARNING)),
+    ];
+    let merged = FileInfo::merge_diagnostics(diagnostics.clone());
+    assert_eq!(codes(&merged), vec!["2:OLS20004", "2:OLS30311", "5:OLS30311", "7:OLS30001"]);
+    //the steps are stored in a HashMap: the order they are given in doesn't change the payload
+    let payload = serde_json::to_string(&FileInfo::limit_diagnostics(merged, 0)).unwrap();
+    let mut reversed = diagnostics.clone();
+    reversed.reverse();
+    let rotated: Vec<(BuildSteps, Diagnostic)> = diagnostics[2..].iter().chain(diagnostics[..2].iter()).cloned().collect();
+    for other in [reversed, rotated] {
+        let other_payload = serde_json::to_string(&FileInfo::limit_diagnostics(FileInfo::merge_diagnostics(other), 0)).unwrap();
+        assert_eq!(payload, other_payload);
+    }
+    //the diagnostics kept by the limit are still sorted by range
+    let limited = FileInfo::limit_diagnostics(FileInfo::merge_diagnostics(diagnostics), 3);
+    let lines: Vec<u32> = limited.iter().map(|d| d.range.start.line).collect();
+    assert_eq!(lines, vec![2, 5, 7, 0]);
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crossbeam_channel::Receiver;
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::threads::SessionInfo;
+
+/* These tests check that the diagnostics scheduled by the builders are published by the flush, once per file and
+with their final state, whatever the number of times the file was rebuilt before. */
+
+const FILE_COUNT: usize = 500;
+
+fn file_path(index: usize) -> String {
+    format!("/odoo_ls_tests_publication/module/file_{}.py", index)
+}
+
+fn diagnostic(message: &str) -> Diagnostic {
+    Diagnostic::new(
+        Range::new(Position::new(0, 0), Position::new(5, 0)),
+        Some(DiagnosticSeverity::WARNING),
+        None,
+        None,
+        message.to_string(),
+        None,
+        None,
+    )
+}
+
+fn load_files(session: &mut SessionInfo) {
+    for index in 0..FILE_COUNT {
+        let content = vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: format!("value = {}\n", index) }];
+        session.sync_odoo.get_file_mgr().borrow_mut().update_file_info(session, &file_path(index), Some(&content), Some(1), false);
+    }
+}
+
+/* Replace the diagnostics of the file and schedule its publication, like a build step does */
+fn build_file(session: &mut SessionInfo, index: usize, message: &str) {
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&file_path(index)).unwrap();
+    let mut file_info = file_info.borrow_mut();
+    file_info.replace_diagnostics(BuildSteps::VALIDATION, vec![diagnostic(message)]);
+    file_info.schedule_publication(session);
+}
+
+fn get_published_diagnostics(receiver: &Receiver<Message>) -> Vec<PublishDiagnosticsParams> {
+    let mut res = vec![];
+    while let Ok(msg) = receiver.try_recv() {
+        if let Message::Notification(n) = msg {
+            if n.method == PublishDiagnostics::METHOD {
+                res.push(serde_json::from_value(n.params).unwrap());
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn test_flush_publishes_each_file_once() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    load_files(&mut session);
+    //a cascade: each file is rebuilt three times before the end of the rebuild
+    for step in ["arch", "arch eval", "validation"] {
+        for index in 0..FILE_COUNT {
+            build_file(&mut session, index, step);
+        }
+    }
+    assert!(get_published_diagnostics(&r).is_empty(), "the builders must not publish");
+    assert!(session.sync_odoo.get_file_mgr().borrow().is_dirty(&file_path(0)));
+    FileMgr::flush_diagnostics(&mut session, true);
+    let published = get_published_diagnostics(&r);
+    assert_eq!(published.len(), FILE_COUNT);
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for params in published.iter() {
+        *counts.entry(params.uri.as_str().to_string()).or_default() += 1;
+        assert_eq!(params.diagnostics.len(), 1);
+        assert_eq!(params.diagnostics[0].message, "validation");
+    }
+    assert_eq!(counts.len(), FILE_COUNT);
+    assert!(counts.values().all(|count| *count == 1));
+    //the batch is ordered by path, so two flushes of the same state give the same sequence
+    let uris: Vec<String> = published.iter().map(|params| params.uri.as_str().to_string()).collect();
+    let mut sorted = uris.clone();
+    sorted.sort();
+    assert_eq!(uris, sorted);
+    //nothing is left to flush
+    assert!(!session.sync_odoo.get_file_mgr().borrow().is_dirty(&file_path(0)));
+    FileMgr::flush_diagnostics(&mut session, true);
+    assert!(get_published_diagnostics(&r).is_empty());
+}
+
+#[test]
+fn test_flush_skips_unchanged_and_deleted_files() {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    load_files(&mut session);
+    for index in 0..3 {
+        build_file(&mut session, index, "first");
+    }
+    FileMgr::flush_diagnostics(&mut session, true);
+    assert_eq!(get_published_diagnostics(&r).len(), 3);
+    //an unforced flush right after a flush waits for the next one
+    build_file(&mut session, 0, "second");
+    FileMgr::flush_diagnostics(&mut session, false);
+    assert!(get_published_diagnostics(&r).is_empty());
+    //the same diagnostics are not sent again, and a deleted file is not published
+    build_file(&mut session, 1, "first");
+    build_file(&mut session, 2, "second");
+    let file_mgr = session.sync_odoo.get_file_mgr();
+    file_mgr.borrow_mut().delete_path(&mut session, &file_path(2));
+    get_published_diagnostics(&r);
+    FileMgr::flush_diagnostics(&mut session, true);
+    let published = get_published_diagnostics(&r);
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].uri, FileMgr::pathname2uri(&file_path(0)));
+    assert_eq!(published[0].diagnostics[0].message, "second");
+}
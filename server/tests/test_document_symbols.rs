@@ -0,0 +1,67 @@
+use lsp_types::{DocumentSymbol, DocumentSymbolResponse, SymbolKind};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::features::document_symbols::DocumentSymbolFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* A document symbol reduced to its name, its kind and its children */
+#[derive(Debug, PartialEq)]
+struct Node(&'static str, SymbolKind, Vec<Node>);
+
+fn same_tree(symbols: &[DocumentSymbol], nodes: &[Node]) -> bool {
+    symbols.len() == nodes.len() && symbols.iter().zip(nodes.iter()).all(|(symbol, Node(name, kind, children))| {
+        symbol.name == *name && symbol.kind == *kind && same_tree(symbol.children.as_deref().unwrap_or_default(), children)
+    })
+}
+
+/* The outline of module_3/models/outline.py: the imports and the parameters are hidden, the property is given once,
+and the functions only show the functions declared in them */
+#[test]
+fn test_document_symbols() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("outline")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("outline should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    //the bodies of the methods are built by the validation
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(file.clone()).validate(&mut session);
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &file).unwrap();
+    let Some(DocumentSymbolResponse::Nested(symbols)) = DocumentSymbolFeature::get_document_symbols(&file, &file_info) else {
+        panic!("the document symbols should be nested");
+    };
+    let expected = vec![
+        Node("MAX_SIZE", SymbolKind::CONSTANT, vec![]),
+        Node("default_name", SymbolKind::VARIABLE, vec![]),
+        Node("Outline", SymbolKind::CLASS, vec![
+            Node("limit", SymbolKind::VARIABLE, vec![]),
+            Node("Meta", SymbolKind::CLASS, vec![
+                Node("ordering", SymbolKind::VARIABLE, vec![]),
+                Node("describe", SymbolKind::METHOD, vec![]),
+            ]),
+            Node("size", SymbolKind::PROPERTY, vec![]),
+            Node("resize", SymbolKind::METHOD, vec![
+                Node("scale", SymbolKind::FUNCTION, vec![]),
+            ]),
+        ]),
+        Node("build", SymbolKind::FUNCTION, vec![]),
+    ];
+    assert!(same_tree(&symbols, &expected), "unexpected outline: {:#?}", symbols);
+    //the selection range is the name of the class, in its definition
+    let outline = &symbols[2];
+    assert_eq!(outline.selection_range.start.line, outline.range.start.line);
+    assert_eq!(outline.selection_range.start.character, 6);
+    assert!(outline.range.end.line > outline.range.start.line);
+}
+
+#[test]
+fn test_constant_names() {
+    assert!(DocumentSymbolFeature::is_constant_name("MAX_SIZE"));
+    assert!(DocumentSymbolFeature::is_constant_name("V2"));
+    assert!(!DocumentSymbolFeature::is_constant_name("default_name"));
+    assert!(!DocumentSymbolFeature::is_constant_name("_"));
+}
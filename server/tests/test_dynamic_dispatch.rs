@@ -0,0 +1,74 @@
+use odoo_ls_server::core::dynamic_dispatch::{DynamicDispatch, MethodPattern};
+use odoo_ls_server::core::method_references::MethodReferences;
+use ruff_python_ast::Expr;
+use ruff_python_parser::parse_expression;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+fn pattern(source: &str) -> Option<(String, String)> {
+    let parsed = parse_expression(source).unwrap();
+    MethodPattern::from_expr(parsed.expr()).map(|p| (p.prefix, p.suffix))
+}
+
+fn getattr_pattern(source: &str) -> Option<String> {
+    let parsed = parse_expression(source).unwrap();
+    match parsed.expr() {
+        Expr::Call(call) => DynamicDispatch::get_getattr_pattern(call).map(|p| p.label()),
+        _ => None
+    }
+}
+
+#[test]
+fn test_percent_format() {
+    assert_eq!(pattern("'_get_%s_values' % provider"), Some((String::from("_get_"), String::from("_values"))));
+    assert_eq!(pattern("'_onchange_%s' % fname"), Some((String::from("_onchange_"), String::new())));
+    assert_eq!(pattern("'_%s_%s_hook' % (a, b)"), Some((String::from("_"), String::from("_hook"))));
+    assert_eq!(pattern("'_compute_%(name)s' % values"), Some((String::from("_compute_"), String::new())));
+    //%% is a literal %, and a format without conversion is a constant
+    assert_eq!(pattern("'_get_%%_%s' % name"), Some((String::from("_get_%_"), String::new())));
+    assert_eq!(pattern("'_get_values' % ()"), None);
+    //the literal parts are too short to select methods
+    assert_eq!(pattern("'_%s' % name"), None);
+}
+
+#[test]
+fn test_f_string_and_concatenation() {
+    assert_eq!(pattern("f'_action_{kind}'"), Some((String::from("_action_"), String::new())));
+    assert_eq!(pattern("f'_get_{provider}_values'"), Some((String::from("_get_"), String::from("_values"))));
+    assert_eq!(pattern("f'_get_{a}_{b}_values'"), Some((String::from("_get_"), String::from("_values"))));
+    assert_eq!(pattern("'_prepare_' + kind + '_vals'"), Some((String::from("_prepare_"), String::from("_vals"))));
+    assert_eq!(pattern("'_prepare_' + '_vals'"), None);
+    assert_eq!(pattern("name"), None);
+}
+
+#[test]
+fn test_pattern_matches() {
+    let pattern = MethodPattern { prefix: String::from("_get_"), suffix: String::from("_values") };
+    assert!(pattern.matches("_get_stripe_values"));
+    assert!(!pattern.matches("_get_values"));
+    assert!(!pattern.matches("_get_stripe_vals"));
+    assert_eq!(pattern.label(), "_get_*_values");
+}
+
+#[test]
+fn test_getattr_pattern() {
+    assert_eq!(getattr_pattern("getattr(self, '_get_%s_values' % provider)"), Some(String::from("_get_*_values")));
+    assert_eq!(getattr_pattern("getattr(cls, f'_action_{kind}')"), Some(String::from("_action_*")));
+    assert_eq!(getattr_pattern("getattr(self, '_get_%s_values' % provider, None)"), Some(String::from("_get_*_values")));
+    //only the methods of the current model are resolved
+    assert_eq!(getattr_pattern("getattr(record, '_get_%s_values' % provider)"), None);
+    assert_eq!(getattr_pattern("getattr(self, name)"), None);
+    assert_eq!(getattr_pattern("hasattr(self, '_get_%s_values' % provider)"), None);
+}
+
+#[test]
+fn test_dynamic_references() {
+    let code = "class PaymentProvider:\n    def _get_rendering_values(self):\n        return getattr(self, '_get_%s_values' % self.code)()\n    def _compute_stripe_values(self):\n        pass\n";
+    let body = parse_valid(code);
+    let mut references = MethodReferences::new();
+    references.add_body(&body);
+    assert!(references.is_referenced("_get_stripe_values"));
+    assert!(!references.is_referenced("_compute_stripe_values"));
+}
@@ -0,0 +1,75 @@
+use ruff_python_ast::Stmt;
+use odoo_ls_server::core::dynamic_fields::DynamicFields;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn find(code: &str, class_name: &str) -> DynamicFields {
+    let module = parse_module(code);
+    let class_def = module.body.iter().find_map(|stmt| match stmt {
+        Stmt::ClassDef(c) if c.name.as_str() == class_name => Some(c.clone()),
+        _ => None
+    }).expect("class not found");
+    DynamicFields::find(&module.body, &class_def)
+}
+
+fn names(fields: &DynamicFields) -> Vec<&str> {
+    fields.names.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn test_literal_dynamic_fields() {
+    let code = "\
+TYPE2FIELD = {
+    'char': 'value_text',
+    'float': 'value_float',
+}
+
+class Property(models.Model):
+    _name = 'ir.property'
+    LEVELS = ['low', 'high']
+
+    for level in LEVELS:
+        locals()['level_' + level] = fields.Boolean()
+    for name in ('value_reference', 'value_binary'):
+        locals()[name] = fields.Char()
+
+    @api.model
+    def _setup_fields(self):
+        for name, column in TYPE2FIELD.items():
+            self._add_field(column, fields.Char())
+        for name, label in [('value_integer', 'Integer'), ('value_datetime', 'Date')]:
+            self._add_field(name, fields.Char(label))
+
+for name in ['company_dependent']:
+    setattr(Property, name, fields.Boolean())
+";
+    let fields = find(code, "Property");
+    assert_eq!(names(&fields), vec!["value_reference", "value_binary", "value_integer", "value_datetime", "company_dependent"]);
+    //'level_' + level can't be extracted, nor the values of the dict
+    assert!(fields.has_unknown_names);
+}
+
+#[test]
+fn test_no_dynamic_fields() {
+    let code = "\
+class A(models.Model):
+    _name = 'a'
+    name = fields.Char()
+
+    def write(self, vals):
+        for name in vals:
+            setattr(other, name, vals[name])
+        return super().write(vals)
+
+class B(models.Model):
+    _name = 'b'
+
+setattr(B, 'x', fields.Char())
+";
+    let fields = find(code, "A");
+    assert!(fields.names.is_empty());
+    assert!(!fields.has_unknown_names);
+    assert_eq!(names(&find(code, "B")), vec!["x"]);
+}
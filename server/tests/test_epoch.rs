@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender};
+use lsp_server::{ErrorCode, Message};
+use odoo_ls_server::core::epoch::Epochs;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_eval::PythonArchEval;
+use odoo_ls_server::core::python_odoo_builder::PythonOdooBuilder;
+use odoo_ls_server::threads::{delayed_changes_process_thread, DelayedProcessingMessage, SessionInfo};
+use odoo_ls_server::utils::PathSanitizer as _;
+use serde_json::json;
+
+mod setup;
+
+#[test]
+fn test_mixed_epochs() {
+    //nothing waits: the index is coherent, even if the files are from several cascades
+    assert!(!Epochs::is_mixed(5, &vec![(5, false), (3, false), (0, false)]));
+    //nothing read is from the current cascade yet: every file is still in its state before the cascade
+    assert!(!Epochs::is_mixed(5, &vec![(4, true), (3, true)]));
+    //files waiting with files of previous cascades are not mixed either
+    assert!(!Epochs::is_mixed(5, &vec![(4, true), (3, false)]));
+    //a file rebuilt by the current cascade and a dependent file still waiting to be rebuilt against it
+    assert!(Epochs::is_mixed(5, &vec![(5, false), (4, true)]));
+    //a file rebuilt in the current cascade and queued again is waiting
+    assert!(!Epochs::is_mixed(5, &vec![(5, true), (4, false)]));
+    assert!(!Epochs::is_mixed(5, &vec![]));
+}
+
+fn get_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join(name)
+}
+
+/* Leave the index in the middle of a cascade: reexport_level1 is rebuilt, while reexport_usage, that imports Origin from
+it, still waits */
+fn start_cascade(sync_odoo: &Arc<Mutex<SyncOdoo>>, s: &Sender<Message>, r: &Receiver<Message>) {
+    let mut odoo = sync_odoo.lock().unwrap();
+    let mut session = SessionInfo::new_from_custom_channel(s.clone(), r.clone(), &mut odoo);
+    let level1 = session.sync_odoo.get_file_symbol(&get_path("reexport_level1.py")).expect("reexport_level1 should be loaded");
+    let usage = session.sync_odoo.get_file_symbol(&get_path("reexport_usage.py")).expect("reexport_usage should be loaded");
+    session.sync_odoo.add_to_rebuild_arch_eval(level1.clone());
+    session.sync_odoo.add_to_rebuild_arch_eval(usage.clone());
+    session.sync_odoo.remove_from_rebuild_arch_eval(&level1);
+    PythonArchEval::new(level1.clone()).eval_arch(&mut session);
+    session.sync_odoo.remove_from_rebuild_odoo(&level1);
+    PythonOdooBuilder::new(level1.clone()).load_odoo_content(&mut session);
+    assert!(Epochs::is_mixed_read(&mut session, &usage));
+}
+
+fn get_params() -> serde_json::Value {
+    json!({"textDocument": {"uri": FileMgr::pathname2uri(&get_path("reexport_usage.py").sanitize()).to_string()}, "position": {"line": 2, "character": 8}})
+}
+
+/* A request of the read thread in the middle of a cascade waits for the rebuild thread to finish it */
+#[test]
+fn test_finish_cascade() {
+    let sync_odoo = Arc::new(Mutex::new(setup::setup::setup_server()));
+    let (s, r) = crossbeam_channel::unbounded();
+    let (delayed_sender, delayed_receiver) = crossbeam_channel::unbounded();
+    let rebuild_thread = {
+        let (s, r, sync_odoo, delayed_sender) = (s.clone(), r.clone(), sync_odoo.clone(), delayed_sender.clone());
+        thread::spawn(move || delayed_changes_process_thread(s, r, delayed_receiver, sync_odoo, delayed_sender))
+    };
+    start_cascade(&sync_odoo, &s, &r);
+    let params = get_params();
+    assert!(Epochs::wait_coherent_read(&sync_odoo, &params, &s, &r, &delayed_sender).is_ok());
+    {
+        let mut odoo = sync_odoo.lock().unwrap();
+        let statistics = odoo.epoch_statistics.clone();
+        assert_eq!((statistics.mixed_epoch_reads, statistics.finished_rebuilds, statistics.refused_reads), (1, 1, 0));
+        let mut session = SessionInfo::new_from_custom_channel(s.clone(), r.clone(), &mut odoo);
+        assert_eq!(session.sync_odoo.get_pending_build_count(), 0);
+        let usage = session.sync_odoo.get_file_symbol(&get_path("reexport_usage.py")).unwrap();
+        assert!(!Epochs::is_mixed_read(&mut session, &usage));
+    }
+    //a coherent read doesn't wait
+    assert!(Epochs::wait_coherent_read(&sync_odoo, &params, &s, &r, &delayed_sender).is_ok());
+    assert_eq!(sync_odoo.lock().unwrap().epoch_statistics.mixed_epoch_reads, 1);
+    delayed_sender.send(DelayedProcessingMessage::EXIT).unwrap();
+    rebuild_thread.join().unwrap();
+}
+
+/* A request still reading a mixed state after the deadline is refused with ContentModified, instead of being answered
+from the half-rebuilt index. There is no rebuild thread to finish the cascade here */
+#[test]
+fn test_refuse_mixed_read() {
+    let sync_odoo = Arc::new(Mutex::new(setup::setup::setup_server()));
+    let (s, r) = crossbeam_channel::unbounded();
+    let (delayed_sender, _delayed_receiver) = crossbeam_channel::unbounded();
+    start_cascade(&sync_odoo, &s, &r);
+    let error = Epochs::wait_coherent_read(&sync_odoo, &get_params(), &s, &r, &delayed_sender).expect_err("the mixed read should be refused");
+    assert_eq!(error.code, ErrorCode::ContentModified as i32);
+    let statistics = sync_odoo.lock().unwrap().epoch_statistics.clone();
+    assert_eq!((statistics.mixed_epoch_reads, statistics.finished_rebuilds, statistics.refused_reads), (1, 0, 1));
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{DidChangeTextDocumentParams, DidOpenTextDocumentParams, Position, Range, TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier};
+use odoo_ls_server::core::config::RefreshMode;
+use odoo_ls_server::core::evaluation::EvaluationSymbolWeak;
+use odoo_ls_server::core::evaluation_cache::EvaluationCache;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_transitive_invalidation() {
+    let mut cache: EvaluationCache<&str, i32> = EvaluationCache::new();
+    //self.env read in a model file (1), through the Environment of odoo/api.py (2)
+    cache.insert("env", 1, vec![1, 2], false);
+    //a variable of another file (3) imported from odoo/api.py
+    cache.insert("imported", 2, vec![3, 2], false);
+    cache.insert("local", 3, vec![4], false);
+    assert_eq!(cache.get(&"env"), Some(1));
+    assert_eq!(cache.get(&"imported"), Some(2));
+    //odoo/api.py is edited: the entries that read it are invalidated, and only them
+    cache.start_build(2);
+    cache.end_build(2);
+    assert_eq!(cache.get(&"env"), None);
+    assert_eq!(cache.get(&"imported"), None);
+    assert_eq!(cache.get(&"local"), Some(3));
+    assert_eq!(cache.len(), 1);
+    assert_eq!((cache.hits, cache.misses), (3, 2));
+    //the entries computed after the build are valid
+    cache.insert("env", 4, vec![1, 2], false);
+    assert_eq!(cache.get(&"env"), Some(4));
+    cache.touch_file(4);
+    assert_eq!(cache.get(&"local"), None);
+    assert_eq!(cache.get(&"env"), Some(4));
+}
+
+#[test]
+fn test_build_in_progress() {
+    let mut cache: EvaluationCache<&str, i32> = EvaluationCache::new();
+    //an evaluation reading a file being built is not stored, as the file is still changing
+    cache.start_build(1);
+    cache.insert("partial", 1, vec![1, 2], false);
+    assert_eq!(cache.get(&"partial"), None);
+    cache.insert("other", 2, vec![2], false);
+    cache.end_build(1);
+    cache.insert("partial", 1, vec![1, 2], false);
+    assert_eq!(cache.get(&"partial"), Some(1));
+    assert_eq!(cache.get(&"other"), Some(2));
+}
+
+#[test]
+fn test_models_invalidation() {
+    let mut cache: EvaluationCache<&str, i32> = EvaluationCache::new();
+    //env['sale.order'] depends on the classes of the model, that can be added by any file
+    cache.insert("model", 1, vec![1], true);
+    cache.insert("class", 2, vec![1], false);
+    cache.touch_models();
+    assert_eq!(cache.get(&"model"), None);
+    assert_eq!(cache.get(&"class"), Some(2));
+    cache.clear();
+    assert_eq!(cache.len(), 0);
+}
+
+fn get_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join("scope_rebuild.py")
+}
+
+/* The rebuild of a single method changes the symbols of its file: the follow_ref results read from the file are
+computed again */
+#[test]
+fn test_function_rebuild_invalidation() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.config.refresh_mode = RefreshMode::Adaptive;
+    let uri = FileMgr::pathname2uri(&get_path().sanitize());
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem { uri: uri.clone(), language_id: S!("python"), version: 1, text: fs::read_to_string(get_path()).unwrap() }
+    });
+    let file = session.sync_odoo.get_file_symbol(&get_path()).expect("scope_rebuild should be loaded");
+    let class = file.borrow().get_content_symbol("ScopeRebuild", u32::MAX).pop().expect("ScopeRebuild should be loaded");
+    let label = file.borrow().get_content_symbol("LABEL", u32::MAX).pop().expect("LABEL should be loaded");
+    //the max scope makes a key that the validation doesn't use, so the counters only move with these calls
+    let follow = |session: &mut SessionInfo| {
+        Symbol::follow_ref(&EvaluationSymbolWeak::new(Rc::downgrade(&label), None, false), session, &mut None, true, true, Some(file.clone()), &mut vec![])
+    };
+    follow(&mut session);
+    let misses = session.sync_odoo.evaluation_cache.misses;
+    follow(&mut session);
+    assert_eq!(session.sync_odoo.evaluation_cache.misses, misses);
+
+    //a line added in the body of _get_label: only the method is rebuilt, and LABEL is shifted
+    Odoo::handle_did_change(&mut session, DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri, version: 2 },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(8, 0), Position::new(8, 0))),
+            range_length: None,
+            text: S!("        other = label.strip()\n"),
+        }],
+    });
+    assert!(Rc::ptr_eq(&class, &file.borrow().get_content_symbol("ScopeRebuild", u32::MAX).pop().unwrap()), "The class has been rebuilt");
+    let misses = session.sync_odoo.evaluation_cache.misses;
+    follow(&mut session);
+    assert_eq!(session.sync_odoo.evaluation_cache.misses, misses + 1);
+}
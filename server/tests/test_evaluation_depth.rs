@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::SymType;
+use odoo_ls_server::core::evaluation::EvaluationSymbolWeak;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_variable(session: &mut SessionInfo, file_name: &str, name: &str) -> Rc<RefCell<Symbol>> {
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_6"), S!("models"), S!(file_name)];
+    let file = session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().unwrap();
+    let variable = file.borrow().get_content_symbol(name, u32::MAX).pop();
+    variable.unwrap()
+}
+
+/* Return the name and the type of the symbols the variable is followed to */
+fn follow(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>) -> Vec<(String, SymType)> {
+    let evaluation = EvaluationSymbolWeak::new(Rc::downgrade(variable), None, false);
+    let mut res: Vec<(String, SymType)> = Symbol::follow_ref(&evaluation, session, &mut None, false, false, None, &mut vec![]).iter()
+        .filter_map(|followed| followed.weak.upgrade())
+        .map(|followed| (followed.borrow().name().clone(), followed.borrow().typ()))
+        .collect();
+    res.sort_by(|a, b| a.0.cmp(&b.0));
+    res
+}
+
+/* The variables are followed through the imports of the other files (final = alias, alias imported from chain_b,
+where alias = target imported from chain_a), up to the evaluation depth of the configuration, and the evaluation
+stops on the cycles going through several files */
+#[test]
+fn test_evaluation_depth() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let final_var = get_variable(&mut session, "chain_c", "final");
+    assert_eq!(follow(&mut session, &final_var), vec![(S!("Target"), SymType::CLASS)]);
+
+    //the configuration is changed: the cache is cleared, as on a change of evaluationDepth
+    let depth = session.sync_odoo.config.evaluation_depth;
+    session.sync_odoo.config.evaluation_depth = 1;
+    session.sync_odoo.evaluation_cache.clear();
+    let followed = follow(&mut session, &final_var);
+    assert_eq!(followed.len(), 1, "{:?}", followed);
+    assert_eq!(followed[0].1, SymType::VARIABLE, "the chain should be cut: {:?}", followed);
+    session.sync_odoo.config.evaluation_depth = depth;
+    session.sync_odoo.evaluation_cache.clear();
+    assert_eq!(follow(&mut session, &final_var), vec![(S!("Target"), SymType::CLASS)]);
+
+    //first = second, second = first: the evaluation ends on a variable of the cycle
+    for (file_name, name) in [("cycle_a", "first"), ("cycle_b", "second")] {
+        let variable = get_variable(&mut session, file_name, name);
+        let followed = follow(&mut session, &variable);
+        assert!(followed.iter().all(|(_, typ)| *typ == SymType::VARIABLE), "{} in {}: {:?}", name, file_name, followed);
+    }
+}
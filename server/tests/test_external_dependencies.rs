@@ -0,0 +1,52 @@
+use odoo_ls_server::core::external_dependencies::ExternalDependencies;
+
+#[test]
+fn test_get_import_name() {
+    assert_eq!(ExternalDependencies::get_import_name("stripe"), "stripe");
+    assert_eq!(ExternalDependencies::get_import_name("python-dateutil"), "dateutil");
+    assert_eq!(ExternalDependencies::get_import_name("PyYAML"), "yaml");
+    assert_eq!(ExternalDependencies::get_import_name("python_stdnum"), "stdnum");
+    assert_eq!(ExternalDependencies::get_import_name("pdf-tools"), "pdf_tools");
+    //the version specifiers are not part of the name
+    assert_eq!(ExternalDependencies::get_import_name("pandas>=2.0"), "pandas");
+    assert_eq!(ExternalDependencies::get_import_name("requests[socks]"), "requests");
+}
+
+#[test]
+fn test_get_imported_package() {
+    assert_eq!(ExternalDependencies::get_imported_package(None, "stripe", None), Some(String::from("stripe")));
+    assert_eq!(ExternalDependencies::get_imported_package(None, "google.cloud.storage", None), Some(String::from("google")));
+    assert_eq!(ExternalDependencies::get_imported_package(Some("stripe.api_resources"), "Charge", Some(0)), Some(String::from("stripe")));
+    //relative imports are in the module
+    assert_eq!(ExternalDependencies::get_imported_package(Some("models"), "sale_order", Some(1)), None);
+    assert_eq!(ExternalDependencies::get_imported_package(None, "models", Some(1)), None);
+}
+
+#[test]
+fn test_find_declaration() {
+    let declared = vec![String::from("stripe"), String::from("python-dateutil"), String::from("Pillow")];
+    assert_eq!(ExternalDependencies::find_declaration("stripe", declared.iter()), Some(&declared[0]));
+    assert_eq!(ExternalDependencies::find_declaration("dateutil", declared.iter()), Some(&declared[1]));
+    assert_eq!(ExternalDependencies::find_declaration("PIL", declared.iter()), Some(&declared[2]));
+    assert_eq!(ExternalDependencies::find_declaration("pandas", declared.iter()), None);
+}
+
+#[test]
+fn test_is_provided() {
+    assert!(ExternalDependencies::is_provided("os"));
+    assert!(ExternalDependencies::is_provided("odoo"));
+    assert!(ExternalDependencies::is_provided("lxml"));
+    assert!(ExternalDependencies::is_provided("werkzeug"));
+    assert!(!ExternalDependencies::is_provided("stripe"));
+    assert!(!ExternalDependencies::is_provided("pandas"));
+}
+
+#[test]
+fn test_is_third_party_path() {
+    let stubs = vec![String::from("/opt/odoo_ls/typeshed/stubs")];
+    assert!(ExternalDependencies::is_third_party_path("/usr/lib/python3/dist-packages/stripe", &stubs));
+    assert!(ExternalDependencies::is_third_party_path("/home/odoo/.venv/lib/python3.12/site-packages/pandas", &stubs));
+    assert!(ExternalDependencies::is_third_party_path("/opt/odoo_ls/typeshed/stubs/requests/requests", &stubs));
+    assert!(!ExternalDependencies::is_third_party_path("/usr/lib/python3.12/json", &stubs));
+    assert!(!ExternalDependencies::is_third_party_path("/home/odoo/addons/site_packages_report", &stubs));
+}
@@ -0,0 +1,124 @@
+use ruff_python_ast::Stmt;
+use odoo_ls_server::core::compute_targets::ComputeTargets;
+use odoo_ls_server::core::field_consistency::{FieldConsistency, FieldDeclaration, FieldRule};
+
+mod setup;
+
+use setup::ast::parse_module;
+
+/* Return the rules broken by the class, with the text at the range of each issue */
+fn check(code: &str) -> Vec<(FieldRule, String)> {
+    let module = parse_module(code);
+    let Some(Stmt::ClassDef(class)) = module.body.into_iter().next() else {
+        panic!("Not a class");
+    };
+    FieldConsistency::check(&class.body).into_iter()
+        .map(|issue| (issue.rule, code[issue.range.start().to_usize()..issue.range.end().to_usize()].to_string()))
+        .collect()
+}
+
+#[test]
+fn test_unstored_compute_used() {
+    let issues = check("\
+class Book(models.Model):
+    _name = 'library.book'
+    _order = 'rating desc, name'
+    rating = fields.Float(compute='_compute_rating')
+    author_id = fields.Many2one('res.partner', compute='_compute_author')
+    author_name = fields.Char(related='author_id.name', store=True)
+    name = fields.Char()
+");
+    assert_eq!(issues, vec![
+        (FieldRule::UNSTORED_COMPUTE_USED, "compute='_compute_rating'".to_string()),
+        (FieldRule::UNSTORED_COMPUTE_USED, "compute='_compute_author'".to_string()),
+    ]);
+}
+
+#[test]
+fn test_compute_arguments() {
+    let issues = check("\
+class Book(models.Model):
+    _name = 'library.book'
+    rating = fields.Float(compute='_compute_rating', store=True)
+    score = fields.Float(compute='_compute_score', store=True)
+    editable = fields.Float(compute='_compute_score', readonly=False)
+    stored_editable = fields.Float(compute='_compute_score', readonly=False, store=True)
+    inversed = fields.Float(compute='_compute_score', inverse='_inverse_score', readonly=False)
+    title = fields.Char(related='name', compute='_compute_title')
+
+    def _compute_rating(self):
+        pass
+
+    @api.depends('name')
+    def _compute_score(self):
+        pass
+");
+    assert_eq!(issues, vec![
+        (FieldRule::MISSING_DEPENDS, "compute='_compute_rating'".to_string()),
+        (FieldRule::READONLY_WITHOUT_INVERSE, "readonly=False".to_string()),
+        (FieldRule::RELATED_AND_COMPUTE, "related='name'".to_string()),
+    ]);
+}
+
+#[test]
+fn test_unassigned_compute() {
+    let issues = check("\
+class Order(models.Model):
+    _name = 'sale.order'
+    amount_untaxed = fields.Float(compute='_compute_totals')
+    amount_tax = fields.Float(compute='_compute_totals')
+    amount_total = fields.Float(compute='_compute_totals')
+    margin = fields.Float(compute='_compute_margin')
+    weight = fields.Float(compute='_compute_weight')
+    volume = fields.Float(compute='_compute_volume')
+
+    def _compute_totals(self):
+        for order in self:
+            order.amount_untaxed = sum(order.line_ids.mapped('price_subtotal'))
+            order.amount_total = order.amount_untaxed
+
+    def _compute_margin(self):
+        for order in self:
+            order.update({'margin': 0.0})
+
+    def _compute_weight(self):
+        super()._compute_weight()
+
+    def _compute_volume(self):
+        pass
+");
+    assert_eq!(issues, vec![(FieldRule::UNASSIGNED_COMPUTE, "_compute_totals".to_string())]);
+}
+
+#[test]
+fn test_compute_targets() {
+    let module = parse_module("\
+class Order(models.Model):
+    amount_untaxed = fields.Float(compute='_compute_totals')
+    amount_tax = fields.Float(compute='_compute_totals', readonly=False)
+    amount_total = fields.Float(compute='_compute_totals', inverse='_inverse_total')
+    partner_name = fields.Char(related='partner_id.name')
+    note = fields.Text()
+
+    def _compute_totals(self):
+        for order in self:
+            order.amount_untaxed, order['amount_tax'] = 1.0, 2.0
+            setattr(order, 'amount_total', 3.0)
+");
+    let Some(Stmt::ClassDef(class)) = module.body.into_iter().next() else {
+        panic!("Not a class");
+    };
+    let fields: Vec<FieldDeclaration> = class.body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+    let targets: Vec<&str> = ComputeTargets::get_targets(&fields, "_compute_totals").iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(targets, vec!["amount_untaxed", "amount_tax", "amount_total"]);
+    let writable: Vec<&str> = fields.iter().filter(|f| ComputeTargets::is_writable(f)).map(|f| f.name.as_str()).collect();
+    assert_eq!(writable, vec!["amount_tax", "amount_total", "note"]);
+    let Some(Stmt::FunctionDef(method)) = class.body.last() else {
+        panic!("Not a method");
+    };
+    let assigned = ComputeTargets::get_assigned_fields(&method.body);
+    assert!(!assigned.dynamic);
+    for name in targets.iter() {
+        assert!(assigned.names.contains(*name), "{}", name);
+    }
+}
@@ -0,0 +1,106 @@
+use odoo_ls_server::core::field_dependencies::{FieldDependencyGraph, FieldRef};
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+fn names(fields: &[(FieldRef, u32)]) -> Vec<String> {
+    fields.iter().map(|(field, depth)| format!("{}.{}:{}", field.model, field.field, depth)).collect()
+}
+
+const ORDER: &str = "
+class SaleOrder(models.Model):
+    _name = 'sale.order'
+
+    partner_id = fields.Many2one('res.partner')
+    line_ids = fields.One2many(comodel_name='sale.order.line', inverse_name='order_id')
+    amount = fields.Float(compute='_compute_amount', store=True)
+    country_id = fields.Many2one(related='partner_id.country_id')
+
+    @api.depends('line_ids.subtotal', 'country_id.code')
+    def _compute_amount(self):
+        pass
+";
+
+const LINE: &str = "
+class SaleOrderLine(models.Model):
+    _name = 'sale.order.line'
+
+    order_id = fields.Many2one('sale.order')
+    price = fields.Float()
+    subtotal = fields.Float(compute='_compute_subtotal')
+
+    @api.depends('price')
+    def _compute_subtotal(self):
+        pass
+";
+
+const PARTNER: &str = "
+class Partner(models.Model):
+    _inherit = 'res.partner'
+
+    country_id = fields.Many2one('res.country')
+";
+
+#[test]
+fn test_dependencies() {
+    let mut graph = FieldDependencyGraph::new();
+    graph.update_file("/line.py", &parse_valid(LINE));
+    graph.update_file("/partner.py", &parse_valid(PARTNER));
+    graph.update_file("/order.py", &parse_valid(ORDER));
+    let amount = FieldRef::new("sale.order", "amount");
+    //the related field gives its comodel to the path that follows it
+    assert_eq!(names(&graph.get_dependencies(&amount, 10).0), vec![
+        "res.country.code:1", "sale.order.country_id:1", "sale.order.line_ids:1", "sale.order.line.subtotal:1",
+        "res.partner.country_id:2", "sale.order.partner_id:2", "sale.order.line.price:2",
+    ].into_iter().map(|field| field.to_string()).collect::<Vec<String>>());
+    let (dependencies, truncated) = graph.get_dependencies(&amount, 1);
+    assert_eq!(dependencies.len(), 4);
+    assert!(truncated);
+    let (dependents, truncated) = graph.get_dependents(&FieldRef::new("sale.order.line", "price"), 10);
+    assert_eq!(names(&dependents), vec!["sale.order.line.subtotal:1", "sale.order.amount:2"]);
+    assert!(!truncated);
+    assert!(graph.find_cycle(&amount, 10).is_none());
+}
+
+#[test]
+fn test_cycles() {
+    let mut graph = FieldDependencyGraph::new();
+    graph.update_file("/order.py", &parse_valid("
+class SaleOrder(models.Model):
+    _name = 'sale.order'
+
+    total = fields.Float(compute='_compute_total')
+    total_copy = fields.Float(related='total')
+    parent_id = fields.Many2one('sale.order')
+    complete_name = fields.Char(compute='_compute_complete_name', recursive=True)
+
+    @api.depends('total_copy')
+    def _compute_total(self):
+        pass
+
+    @api.depends('parent_id.complete_name')
+    def _compute_complete_name(self):
+        pass
+"));
+    let cycle = graph.find_cycle(&FieldRef::new("sale.order", "total"), 10).unwrap();
+    assert_eq!(cycle.iter().map(|field| field.field.as_str()).collect::<Vec<&str>>(), vec!["total", "total_copy", "total"]);
+    assert!(graph.find_cycle(&FieldRef::new("sale.order", "complete_name"), 10).is_none());
+    //the decorator of the compute method is kept to report the cycle
+    assert!(graph.get_declarations(&FieldRef::new("sale.order", "total"))[0].decorator.is_some());
+}
+
+#[test]
+fn test_incremental_update() {
+    let mut graph = FieldDependencyGraph::new();
+    graph.update_file("/order.py", &parse_valid(ORDER));
+    graph.update_file("/line.py", &parse_valid(LINE));
+    let subtotal = FieldRef::new("sale.order.line", "subtotal");
+    assert_eq!(names(&graph.get_dependents(&subtotal, 10).0), vec!["sale.order.amount:1"]);
+    //the fields of a file are replaced when it is rebuilt, and removed with it
+    graph.update_file("/order.py", &parse_valid("class SaleOrder(models.Model):\n    _name = 'sale.order'\n"));
+    assert!(graph.get_dependents(&subtotal, 10).0.is_empty());
+    assert_eq!(graph.get_direct_dependencies(&subtotal).len(), 1);
+    graph.remove_file("/line.py");
+    assert!(graph.get_declarations(&subtotal).is_empty());
+}
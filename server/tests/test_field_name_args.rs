@@ -0,0 +1,74 @@
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::TextSize;
+use odoo_ls_server::core::field_name_args::{FieldNameArgs, FieldNameFormat};
+use odoo_ls_server::core::python_string::StringSourceMap;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_expr(source: &str) -> Expr {
+    match parse(source).into_iter().next() {
+        Some(Stmt::Expr(expr)) => *expr.value,
+        _ => panic!("Not an expression")
+    }
+}
+
+fn names(value: &str, format: FieldNameFormat) -> Vec<String> {
+    FieldNameArgs::get_field_names(value, format).into_iter().map(|(name, range)| {
+        assert_eq!(&value[range], name);
+        name
+    }).collect()
+}
+
+#[test]
+fn test_field_names_formats() {
+    assert_eq!(names("partner_id", FieldNameFormat::NAME), vec!["partner_id"]);
+    assert_eq!(names("partner_id/name", FieldNameFormat::PATH), vec!["partner_id"]);
+    assert_eq!(names("date:month", FieldNameFormat::GROUPBY), vec!["date"]);
+    assert_eq!(names("amount:sum", FieldNameFormat::AGGREGATE), vec!["amount"]);
+    assert_eq!(names("total:sum(amount_total)", FieldNameFormat::AGGREGATE), vec!["amount_total"]);
+    assert!(names("__count", FieldNameFormat::AGGREGATE).is_empty());
+    assert_eq!(names("date desc, partner_id.name,  id", FieldNameFormat::ORDER), vec!["date", "partner_id", "id"]);
+}
+
+#[test]
+fn test_field_name_arguments() {
+    let expr = parse_expr("records._read_group([], ['partner_id', 'date:month'], aggregates=['amount:sum'], order='date desc')");
+    let Expr::Call(call) = &expr else {
+        panic!("Not a call");
+    };
+    let arguments = FieldNameArgs::get_arguments(call);
+    let parameters: Vec<&str> = arguments.iter().map(|(arg, _)| arg.parameter).collect();
+    assert_eq!(parameters, vec!["groupby", "aggregates", "order"]);
+    assert_eq!(FieldNameArgs::get_string_literals(arguments[0].1).len(), 2);
+
+    //the string callbacks of the recordsets name fields too
+    let expr = parse_expr("records.mapped('partner_id.name')");
+    let Expr::Call(call) = &expr else {
+        panic!("Not a call");
+    };
+    let arguments = FieldNameArgs::get_arguments(call);
+    assert_eq!(arguments.len(), 1);
+    assert_eq!(names("partner_id.name", arguments[0].0.format), vec!["partner_id"]);
+
+    //other methods are not checked
+    let expr = parse_expr("records.browse('partner_id')");
+    let Expr::Call(call) = &expr else {
+        panic!("Not a call");
+    };
+    assert!(FieldNameArgs::get_arguments(call).is_empty());
+}
+
+#[test]
+fn test_value_offset() {
+    let source = "'a\\tb' 'cd'";
+    let Expr::StringLiteral(literal) = parse_expr(source) else {
+        panic!("Not a string");
+    };
+    let map = StringSourceMap::from_string_literal(source, TextSize::new(0), &literal);
+    assert_eq!(map.value_offset(TextSize::new(1)), 0);
+    assert_eq!(map.value_offset(TextSize::new(4)), 2);
+    assert_eq!(map.value_offset(TextSize::new(9)), 4);
+    assert_eq!(map.value_offset(TextSize::new(11)), 5);
+}
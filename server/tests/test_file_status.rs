@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::Receiver;
+use lsp_server::Message;
+use lsp_types::{DidOpenTextDocumentParams, TextDocumentIdentifier, TextDocumentItem, Uri};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::{Odoo, SyncOdoo};
+use odoo_ls_server::features::file_status::{FileStatus, FileStatusFeature, StepStatus, FILE_STATUS_METHOD, FILE_STATUS_VERSION};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_file_status_payload() {
+    let status = FileStatus {
+        version: FILE_STATUS_VERSION,
+        uri: FileMgr::pathname2uri(&"/addons/module_1/models/models.py".to_string()),
+        arch: BuildStatus::DONE.into(),
+        arch_eval: BuildStatus::DONE.into(),
+        odoo: BuildStatus::IN_PROGRESS.into(),
+        validation: StepStatus::from(BuildStatus::PENDING),
+        queue_position: Some(3),
+        last_build: None,
+        stale: false,
+        hidden_diagnostics: 0,
+    };
+    let payload = serde_json::to_value(&status).unwrap();
+    assert_eq!(payload["version"], FILE_STATUS_VERSION);
+    assert_eq!(payload["archEval"], "done");
+    assert_eq!(payload["odoo"], "inProgress");
+    assert_eq!(payload["validation"], "pending");
+    assert_eq!(payload["queuePosition"], 3);
+    assert!(payload["lastBuild"].is_null());
+    assert_eq!(payload["hiddenDiagnostics"], 0);
+}
+
+fn get_models_path(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join(name).sanitize()
+}
+
+/* Return the statuses of the file pushed to the client since the last call */
+fn get_pushed_statuses(receiver: &Receiver<Message>, uri: &Uri) -> Vec<serde_json::Value> {
+    let mut res = vec![];
+    while let Ok(msg) = receiver.try_recv() {
+        if let Message::Notification(n) = msg {
+            if n.method == FILE_STATUS_METHOD && n.params["uri"] == uri.as_str() {
+                res.push(n.params);
+            }
+        }
+    }
+    res
+}
+
+/* The status of an opened file is pushed when it changes, at most once every 500 ms during a rebuild, and always at the
+end of the rebuild. It can also be asked for any file */
+#[test]
+fn test_file_status_notifications() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    let path = get_models_path("amounts.py");
+    let uri = FileMgr::pathname2uri(&path);
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem { uri: uri.clone(), language_id: S!("python"), version: 1, text: fs::read_to_string(&path).unwrap() }
+    });
+    let pushed = get_pushed_statuses(&r, &uri);
+    assert_eq!(pushed.len(), 1, "the status should be pushed on open: {:?}", pushed);
+    assert_eq!(pushed[0]["validation"], "done");
+
+    //the pull path gives the status of any file, opened or not
+    let status = Odoo::handle_file_status(&mut session, TextDocumentIdentifier { uri: uri.clone() }).unwrap().expect("amounts should have a status");
+    assert_eq!((status.arch, status.arch_eval, status.odoo, status.validation), (StepStatus::DONE, StepStatus::DONE, StepStatus::DONE, StepStatus::DONE));
+    assert_eq!(status.queue_position, None);
+    let other_uri = FileMgr::pathname2uri(&get_models_path("amount_usage.py"));
+    assert!(Odoo::handle_file_status(&mut session, TextDocumentIdentifier { uri: other_uri }).unwrap().is_some());
+
+    //the position counts the files waiting in the queues of the previous steps
+    let file = session.sync_odoo.get_file_symbol(&PathBuf::from(&path)).unwrap();
+    for name in ["amount_usage.py", "amount_exports.py"] {
+        let other = session.sync_odoo.get_file_symbol(&PathBuf::from(get_models_path(name))).unwrap();
+        session.sync_odoo.add_to_rebuild_arch_eval(other);
+    }
+    session.sync_odoo.add_to_validations(file.clone());
+    let status = Odoo::handle_file_status(&mut session, TextDocumentIdentifier { uri: uri.clone() }).unwrap().unwrap();
+    assert_eq!(status.validation, StepStatus::PENDING);
+    assert_eq!(status.queue_position, Some(2));
+
+    //a change is pushed at once when forced, and at most once every 500 ms otherwise
+    FileStatusFeature::push_changes(&mut session, true);
+    assert_eq!(get_pushed_statuses(&r, &uri).len(), 1);
+    FileStatusFeature::push_changes(&mut session, true);
+    assert!(get_pushed_statuses(&r, &uri).is_empty(), "an unchanged status should not be pushed again");
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::IN_PROGRESS);
+    FileStatusFeature::push_changes(&mut session, false);
+    assert!(get_pushed_statuses(&r, &uri).is_empty(), "the push should be delayed");
+    thread::sleep(Duration::from_millis(500));
+    FileStatusFeature::push_changes(&mut session, false);
+    let pushed = get_pushed_statuses(&r, &uri);
+    assert_eq!(pushed.len(), 1);
+    assert_eq!(pushed[0]["validation"], "inProgress");
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+
+    //a rebuild pushes the final status of the file, out of the queues
+    SyncOdoo::process_rebuilds(&mut session);
+    let pushed = get_pushed_statuses(&r, &uri);
+    let last = pushed.last().expect("the end of the rebuild should be pushed");
+    assert_eq!(last["validation"], "done");
+    assert!(last["queuePosition"].is_null());
+    assert!(pushed.len() <= 3, "{:?}", pushed);
+}
@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{DidChangeTextDocumentParams, DidOpenTextDocumentParams, Position, Range, TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier};
+use odoo_ls_server::core::config::RefreshMode;
+use odoo_ls_server::core::file_mgr::{FileInfo, FileMgr};
+use odoo_ls_server::core::integrity::IntegrityChecker;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::core::parse_pool::ParsedSource;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* An edit of the body of a single method only rebuilds this method. The symbols must be the same as after a full
+build of the file, and the ast where the method is parsed again the same as the ast of the whole file */
+
+fn get_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join("scope_rebuild.py")
+}
+
+fn get_class(session: &mut SessionInfo) -> Rc<RefCell<Symbol>> {
+    session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("scope_rebuild")], vec![S!("ScopeRebuild")]), u32::MAX)
+        .pop().expect("ScopeRebuild should be loaded")
+}
+
+fn change(session: &mut SessionInfo, version: i32, changes: Vec<(Range, &str)>) {
+    Odoo::handle_did_change(session, DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri: FileMgr::pathname2uri(&get_path().sanitize()), version },
+        content_changes: changes.into_iter().map(|(range, text)| TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: None,
+            text: S!(text),
+        }).collect(),
+    });
+}
+
+fn assert_ast(session: &mut SessionInfo) {
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&get_path().sanitize()).unwrap();
+    let file_info = file_info.borrow();
+    let (ast, valid, _, _) = ParsedSource::parse(&file_info.get_source().unwrap());
+    assert!(valid && file_info.valid);
+    assert_eq!(file_info.ast, ast);
+}
+
+/* The symbols of the file, with their ranges and their number of evaluations, sorted */
+fn dump(symbol: &Rc<RefCell<Symbol>>, prefix: &str, res: &mut Vec<String>) {
+    for child in symbol.borrow().all_symbols() {
+        let child_bw = child.borrow();
+        let name = format!("{}/{}", prefix, child_bw.name());
+        res.push(format!("{} {:?} {:?} {:?}", name, child_bw.typ(), child_bw.range(), child_bw.evaluations().map(|e| e.len())));
+        drop(child_bw);
+        dump(&child, &name, res);
+    }
+}
+
+fn dump_file(session: &mut SessionInfo) -> Vec<String> {
+    let file = session.sync_odoo.get_file_symbol(&get_path()).expect("scope_rebuild should be loaded");
+    let mut res = vec![];
+    dump(&file, "", &mut res);
+    res.sort();
+    res
+}
+
+#[test]
+fn test_function_rebuild() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.config.refresh_mode = RefreshMode::Adaptive;
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: FileMgr::pathname2uri(&get_path().sanitize()),
+            language_id: S!("python"),
+            version: 1,
+            text: fs::read_to_string(get_path()).unwrap(),
+        }
+    });
+    let class = get_class(&mut session);
+
+    //two changes in the body of _get_label: the method is rebuilt, but not its class
+    change(&mut session, 2, vec![
+        (Range::new(Position::new(8, 0), Position::new(8, 0)), "        other = label.strip()\n"),
+        (Range::new(Position::new(9, 15), Position::new(9, 20)), "other"),
+    ]);
+    assert!(Rc::ptr_eq(&class, &get_class(&mut session)), "The class has been rebuilt");
+    let function = class.borrow().get_content_symbol("_get_label", u32::MAX).pop().expect("_get_label should be in ScopeRebuild");
+    assert!(!function.borrow().get_content_symbol("other", u32::MAX).is_empty());
+    assert_ast(&mut session);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+    let fast_dump = dump_file(&mut session);
+
+    //a change that keeps the length of the method only parses the method
+    change(&mut session, 3, vec![
+        (Range::new(Position::new(8, 22), Position::new(8, 27)), "lower"),
+    ]);
+    assert!(Rc::ptr_eq(&class, &get_class(&mut session)), "The class has been rebuilt");
+    assert_ast(&mut session);
+    assert_eq!(fast_dump, dump_file(&mut session));
+
+    //a cold build of the same content gives the same symbols
+    Odoo::update_file_index(&mut session, get_path(), false, true);
+    let class = get_class(&mut session);
+    assert_eq!(fast_dump, dump_file(&mut session));
+
+    //a change of the signature rebuilds the whole file
+    change(&mut session, 4, vec![
+        (Range::new(Position::new(6, 25), Position::new(6, 31)), "value"),
+    ]);
+    assert!(!Rc::ptr_eq(&class, &get_class(&mut session)), "The class should have been rebuilt");
+    let class = get_class(&mut session);
+
+    //a batch of changes in the body of _get_code, that is followed by other statements. The columns are given in UTF-16
+    //code units, and the emoji takes two of them
+    change(&mut session, 5, vec![
+        (Range::new(Position::new(13, 0), Position::new(13, 0)), "        emoji = \"\u{1F600}\" + code\n"),
+        (Range::new(Position::new(14, 15), Position::new(14, 19)), "emoji"),
+        (Range::new(Position::new(13, 23), Position::new(13, 27)), "code.strip()"),
+    ]);
+    assert!(Rc::ptr_eq(&class, &get_class(&mut session)), "The class has been rebuilt");
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&get_path().sanitize()).unwrap();
+    let source = file_info.borrow().get_source().unwrap();
+    assert!(source.contains("        emoji = \"\u{1F600}\" + code.strip()\n        return emoji\n"), "{}", source);
+    let function = class.borrow().get_content_symbol("_get_code", u32::MAX).pop().expect("_get_code should be in ScopeRebuild");
+    assert!(!function.borrow().get_content_symbol("emoji", u32::MAX).is_empty());
+    assert_ast(&mut session);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+    //the symbols after the method are shifted to the same positions as a cold build
+    let fast_dump = dump_file(&mut session);
+    Odoo::update_file_index(&mut session, get_path(), false, true);
+    assert_eq!(fast_dump, dump_file(&mut session));
+    let class = get_class(&mut session);
+
+    //a change at the end of the document is out of any method
+    let end = FileInfo::offset_to_position_with_rope(file_info.borrow().get_rope().unwrap(), source.len());
+    change(&mut session, 6, vec![
+        (Range::new(end, end), "OTHER = LABEL\n"),
+    ]);
+    assert!(!Rc::ptr_eq(&class, &get_class(&mut session)), "The class should have been rebuilt");
+    let file = session.sync_odoo.get_file_symbol(&get_path()).unwrap();
+    assert!(!file.borrow().get_content_symbol("OTHER", u32::MAX).is_empty());
+    let class = get_class(&mut session);
+
+    //so is a change at the start of the document
+    change(&mut session, 7, vec![
+        (Range::new(Position::new(0, 0), Position::new(0, 0)), "# header\n"),
+    ]);
+    assert!(!Rc::ptr_eq(&class, &get_class(&mut session)), "The class should have been rebuilt");
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+}
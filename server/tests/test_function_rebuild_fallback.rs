@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{DidChangeTextDocumentParams, DidOpenTextDocumentParams, Position, Range, TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier};
+use odoo_ls_server::core::config::RefreshMode;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::integrity::IntegrityChecker;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3").join("models").join("scope_shift.py")
+}
+
+fn get_class(session: &mut SessionInfo) -> Rc<RefCell<Symbol>> {
+    session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("scope_shift")], vec![S!("ScopeShift")]), u32::MAX)
+        .pop().expect("ScopeShift should be loaded")
+}
+
+fn insert_line(session: &mut SessionInfo, version: i32, line: u32, text: &str) {
+    Odoo::handle_did_change(session, DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri: FileMgr::pathname2uri(&get_path().sanitize()), version },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(line, 0), Position::new(line, 0))),
+            range_length: None,
+            text: S!(text),
+        }],
+    });
+}
+
+/* The ranges of the diagnostics stored on a method, as offsets, sorted */
+fn function_diagnostics(session: &mut SessionInfo, name: &str) -> Vec<(u32, u32, String)> {
+    let function = get_class(session).borrow().get_content_symbol(name, u32::MAX).pop().expect("the method should be in ScopeShift");
+    let function_bw = function.borrow();
+    let mut res: Vec<(u32, u32, String)> = function_bw.as_func().diagnostics.values().flatten()
+        .map(|diagnostic| (diagnostic.range.start.line, diagnostic.range.end.line, diagnostic.message.clone()))
+        .collect();
+    res.sort();
+    res
+}
+
+/* The diagnostics of the unresolved import are stored on _get_module, that follows _get_name. A change of the length of
+_get_name only rebuilds it, and shifts these diagnostics to the positions of a cold build */
+#[test]
+fn test_function_rebuild_fallback() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.config.refresh_mode = RefreshMode::Adaptive;
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: FileMgr::pathname2uri(&get_path().sanitize()),
+            language_id: S!("python"),
+            version: 1,
+            text: fs::read_to_string(get_path()).unwrap(),
+        }
+    });
+    let class = get_class(&mut session);
+    let diagnostics = function_diagnostics(&mut session, "_get_module");
+    assert!(!diagnostics.is_empty(), "the unresolved import should be diagnosed on _get_module");
+
+    let text = "        other = name\n";
+    insert_line(&mut session, 2, 8, text);
+    assert!(Rc::ptr_eq(&class, &get_class(&mut session)), "The class has been rebuilt");
+    let shifted = function_diagnostics(&mut session, "_get_module");
+    let expected: Vec<(u32, u32, String)> = diagnostics.into_iter()
+        .map(|(start, end, message)| (start + text.len() as u32, end + text.len() as u32, message))
+        .collect();
+    assert_eq!(shifted, expected);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+    Odoo::update_file_index(&mut session, get_path(), false, true);
+    assert_eq!(shifted, function_diagnostics(&mut session, "_get_module"));
+    let class = get_class(&mut session);
+
+    insert_line(&mut session, 3, 13, "        other = not_a_real_module\n");
+    assert!(Rc::ptr_eq(&class, &get_class(&mut session)), "The class has been rebuilt");
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+}
@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_type_names;
+
+/* Return the names of the types of the declarations of the name reachable at the end of the scope */
+fn get_content_type_names(session: &mut SessionInfo, scope: &Rc<RefCell<Symbol>>, name: &str) -> Vec<String> {
+    let declarations = scope.borrow().get_content_symbol(name, u32::MAX);
+    let mut res = vec![];
+    for declaration in declarations.iter() {
+        res.extend(get_type_names(session, declaration));
+    }
+    res.sort();
+    res
+}
+
+/* The assignments following a global or nonlocal statement declare the name in the outer scope, where it is possibly
+assigned after the definition of the function */
+#[test]
+fn test_global_names() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("global_names")];
+    let file = odoo.get_symbol(&(file_tree.clone(), vec![]), u32::MAX).pop().expect("global_names should be loaded");
+    let setup_logging = odoo.get_symbol(&(file_tree.clone(), vec![S!("setup_logging")]), u32::MAX).pop().unwrap();
+    let counter = odoo.get_symbol(&(file_tree.clone(), vec![S!("counter")]), u32::MAX).pop().unwrap();
+    let increment = odoo.get_symbol(&(file_tree, vec![S!("counter"), S!("increment")]), u32::MAX).pop().unwrap();
+    assert_eq!(setup_logging.borrow().as_func().global_names, vec![S!("_logger"), S!("_registry")]);
+    assert!(setup_logging.borrow().get_content_symbol("_logger", u32::MAX).is_empty());
+    assert!(increment.borrow().get_content_symbol("count", u32::MAX).is_empty());
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    //the function may not be called: the first declaration is still reachable
+    assert_eq!(file.borrow().get_content_symbol("_logger", u32::MAX).len(), 2);
+    assert_eq!(get_content_type_names(&mut session, &file, "_logger"), vec![S!("Logger")]);
+    //a name only assigned by a function is known in the file
+    assert_eq!(get_content_type_names(&mut session, &file, "_registry"), vec![S!("dict")]);
+    assert_eq!(get_content_type_names(&mut session, &counter, "count"), vec![S!("int"), S!("str")]);
+}
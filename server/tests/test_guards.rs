@@ -0,0 +1,106 @@
+use lsp_types::{Diagnostic, NumberOrString, Position, Range};
+use odoo_ls_server::core::guards::{Guard, GuardedName};
+
+mod setup;
+
+use setup::ast::parse_module;
+
+/* These tests check that the names guarded by hasattr, _fields or env.get checks are only guarded in the right branches */
+
+fn get_guards(code: &str) -> Vec<Guard> {
+    let module = parse_module(code);
+    Guard::collect(&module.body)
+}
+
+fn offset_of(code: &str, marker: &str) -> u32 {
+    code.find(marker).expect("marker not found") as u32
+}
+
+fn member(name: &str) -> GuardedName {
+    GuardedName::MEMBER(name.to_string())
+}
+
+fn model(name: &str) -> GuardedName {
+    GuardedName::MODEL(name.to_string())
+}
+
+#[test]
+fn test_hasattr_guard() {
+    let code = "\
+def f(self):
+    if hasattr(self.env['res.partner'], 'vat'):
+        a = 1 #in
+    else:
+        b = 2 #else
+    c = 3 #after
+";
+    let guards = get_guards(code);
+    assert!(Guard::is_guarded(&guards, &member("vat"), offset_of(code, "a = 1")));
+    assert!(!Guard::is_guarded(&guards, &member("vat"), offset_of(code, "b = 2")));
+    assert!(!Guard::is_guarded(&guards, &member("vat"), offset_of(code, "c = 3")));
+    assert!(!Guard::is_guarded(&guards, &member("other"), offset_of(code, "a = 1")));
+}
+
+#[test]
+fn test_negative_guards() {
+    let code = "\
+if 'vat' not in record._fields:
+    a = 1
+else:
+    b = 2
+if not self.env.get('sale.order'):
+    c = 3
+elif x:
+    d = 4
+";
+    let guards = get_guards(code);
+    assert!(!Guard::is_guarded(&guards, &member("vat"), offset_of(code, "a = 1")));
+    assert!(Guard::is_guarded(&guards, &member("vat"), offset_of(code, "b = 2")));
+    assert!(!Guard::is_guarded(&guards, &model("sale.order"), offset_of(code, "c = 3")));
+    assert!(Guard::is_guarded(&guards, &model("sale.order"), offset_of(code, "d = 4")));
+}
+
+#[test]
+fn test_nested_and_combined_guards() {
+    let code = "\
+if hasattr(record, 'vat') and self.env.get('sale.order'):
+    if 'carrier_id' in record._fields:
+        a = 1
+    b = 2
+if hasattr(record, 'x') or hasattr(record, 'y'):
+    c = 3
+";
+    let guards = get_guards(code);
+    let a = offset_of(code, "a = 1");
+    assert!(Guard::is_guarded(&guards, &member("vat"), a));
+    assert!(Guard::is_guarded(&guards, &model("sale.order"), a));
+    assert!(Guard::is_guarded(&guards, &member("carrier_id"), a));
+    let b = offset_of(code, "b = 2");
+    assert!(Guard::is_guarded(&guards, &member("vat"), b));
+    assert!(!Guard::is_guarded(&guards, &member("carrier_id"), b));
+    //a 'or' doesn't guarantee any of its operands
+    assert!(!Guard::is_guarded(&guards, &member("x"), offset_of(code, "c = 3")));
+}
+
+#[test]
+fn test_filter_diagnostics() {
+    let code = "\
+if self.env.get('sale.order'):
+    a = 1
+b = 2
+";
+    let guards = get_guards(code);
+    let diagnostic = |offset: u32, code: &str, name: GuardedName| Guard::set_guarded_name(Diagnostic {
+        range: Range::new(Position::new(offset, 0), Position::new(offset + 1, 0)),
+        code: Some(NumberOrString::String(code.to_string())),
+        ..Default::default()
+    }, name);
+    let diagnostics = vec![
+        diagnostic(offset_of(code, "a = 1"), "OLS30101", model("sale.order")),
+        diagnostic(offset_of(code, "a = 1"), "OLS30101", model("stock.picking")),
+        diagnostic(offset_of(code, "b = 2"), "OLS30101", model("sale.order")),
+    ];
+    let filtered = Guard::filter_diagnostics(&guards, diagnostics);
+    assert_eq!(filtered.len(), 2);
+    assert!(filtered.iter().all(|d| d.range.start.line != offset_of(code, "a = 1") || d.data.as_ref().unwrap()["model"] == "stock.picking"));
+}
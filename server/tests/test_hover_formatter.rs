@@ -0,0 +1,125 @@
+use odoo_ls_server::features::hover_formatter::HoverFormatter;
+
+/* The sections of a Sphinx docstring are gathered at its end, the types given by ':type' and ':rtype' fields going to
+their parameter and to the returned value */
+#[test]
+fn test_sphinx_doc_string() {
+    let doc_string = "Compute the total of the invoice.
+
+    Rounded with :func:`~odoo.tools.float_round`.
+
+    :param invoice: the invoice to total
+    :type invoice: :class:`~odoo.models.Model`
+    :param bool with_taxes: include the taxes,
+        or not
+    :returns: the total
+    :rtype: float
+    :raises ValueError: if the invoice is empty
+    ";
+    assert_eq!(HoverFormatter::format_doc_string(doc_string, 0), "Compute the total of the invoice.  \n\
+        \n\
+        Rounded with `float_round`.  \n\
+        \n\
+        **Parameters**  \n\
+        - `invoice` (`Model`): the invoice to total\n\
+        - `with_taxes` (`bool`): include the taxes, or not\n\
+        \n\
+        **Returns**  \n\
+        - `float`: the total\n\
+        \n\
+        **Raises**  \n\
+        - `ValueError`: if the invoice is empty");
+}
+
+#[test]
+fn test_google_doc_string() {
+    let doc_string = "Compute the total of the invoice.
+
+    Args:
+        invoice (Model): the invoice to total
+        with_taxes (bool): include the taxes,
+            or not
+
+    Returns:
+        float: the total
+
+    Raises:
+        ValueError: if the invoice is empty
+
+    Note:
+        Uses ``float_round``.
+    ";
+    assert_eq!(HoverFormatter::format_doc_string(doc_string, 0), "Compute the total of the invoice.  \n\
+        \n\
+        **Parameters**  \n\
+        - `invoice` (`Model`): the invoice to total\n\
+        - `with_taxes` (`bool`): include the taxes, or not\n\
+        \n\
+        **Returns**  \n\
+        - `float`: the total\n\
+        \n\
+        **Raises**  \n\
+        - `ValueError`: if the invoice is empty\n\
+        \n\
+        **Notes**  \n\
+        Uses `float_round`.");
+}
+
+#[test]
+fn test_numpy_doc_string() {
+    let doc_string = "Compute the total of the invoice.
+
+    Parameters
+    ----------
+    invoice : Model
+        the invoice to total
+    with_taxes : bool
+        include the taxes,
+        or not
+
+    Returns
+    -------
+    float
+        the total
+
+    Raises
+    ------
+    ValueError
+        if the invoice is empty
+    ";
+    assert_eq!(HoverFormatter::format_doc_string(doc_string, 0), "Compute the total of the invoice.  \n\
+        \n\
+        **Parameters**  \n\
+        - `invoice` (`Model`): the invoice to total\n\
+        - `with_taxes` (`bool`): include the taxes, or not\n\
+        \n\
+        **Returns**  \n\
+        - `float`: the total\n\
+        \n\
+        **Raises**  \n\
+        - `ValueError`: if the invoice is empty");
+}
+
+/* The text keeps its indentation, the examples are shown as code, and a docstring longer than the maximum number of
+lines is cut */
+#[test]
+fn test_doc_string_text() {
+    let doc_string = "Print the invoice.
+    The layout is:
+      - the header
+      - the lines
+
+    >>> print_invoice(invoice)
+    True
+    ";
+    assert_eq!(HoverFormatter::format_doc_string(doc_string, 0), "Print the invoice.  \n\
+        The layout is:  \n\
+        &nbsp;&nbsp;- the header  \n\
+        &nbsp;&nbsp;- the lines  \n\
+        \n\
+        ```python\n\
+        >>> print_invoice(invoice)\n\
+        True\n\
+        ```");
+    assert_eq!(HoverFormatter::format_doc_string(doc_string, 2), "Print the invoice.  \nThe layout is:  \n…");
+}
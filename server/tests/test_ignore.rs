@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use odoo_ls_server::core::ignore::IgnoreMgr;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+fn from_globs(globs: &[&str]) -> IgnoreMgr {
+    let (ignore_mgr, errors) = IgnoreMgr::from_globs(&vec![S!("/workspace")], &globs.iter().map(|g| g.to_string()).collect());
+    assert!(errors.is_empty(), "{:?}", errors);
+    ignore_mgr
+}
+
+#[test]
+fn test_ignore_rules() {
+    //a pattern without '/' matches the name at any depth, and the last matching rule wins
+    let ignore_mgr = from_globs(&["# comment", "", "*.tmp", "!keep.tmp"]);
+    assert!(ignore_mgr.is_ignored("/workspace/a/b/cache.tmp", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/a/b/keep.tmp", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/a/b/cache.py", false));
+
+    //a pattern containing a '/' is anchored to the workspace folder, and '*' doesn't match a '/'
+    let ignore_mgr = from_globs(&["/setup.py", "doc/*.py"]);
+    assert!(ignore_mgr.is_ignored("/workspace/setup.py", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/module/setup.py", false));
+    assert!(ignore_mgr.is_ignored("/workspace/doc/conf.py", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/module/doc/conf.py", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/doc/sub/conf.py", false));
+
+    //'**' matches any number of directories
+    let ignore_mgr = from_globs(&["doc/**/*.py"]);
+    assert!(ignore_mgr.is_ignored("/workspace/doc/conf.py", false));
+    assert!(ignore_mgr.is_ignored("/workspace/doc/a/b/conf.py", false));
+    assert!(!ignore_mgr.is_ignored("/workspace/module/doc/conf.py", false));
+
+    //a trailing '/' only matches the directories, and so the files they contain
+    let ignore_mgr = from_globs(&["build/"]);
+    assert!(ignore_mgr.is_ignored("/workspace/module/build", true));
+    assert!(!ignore_mgr.is_ignored("/workspace/module/build", false));
+    assert!(ignore_mgr.is_ignored("/workspace/module/build/lib.py", false));
+
+    //the content of an ignored directory can't be included again
+    let ignore_mgr = from_globs(&["build/", "!build/lib.py"]);
+    assert!(ignore_mgr.is_ignored("/workspace/build/lib.py", false));
+    //only the paths inside the workspace folders can be ignored
+    assert!(!ignore_mgr.is_ignored("/other/build/lib.py", false));
+    assert!(!ignore_mgr.is_ignored("/workspace", true));
+    assert!(!ignore_mgr.is_ignored("/workspace_build/build/lib.py", false));
+
+    let (_, errors) = IgnoreMgr::from_globs(&vec![S!("/workspace")], &vec![S!("module[")]);
+    assert_eq!(errors.len(), 1);
+}
+
+/* The ignore files of the subdirectories apply to their content, after the rules of their parents. The exclude setting
+applies after all the ignore files */
+#[test]
+fn test_nested_ignore_files() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("ignore_workspace").sanitize();
+    let (ignore_mgr, errors) = IgnoreMgr::load(&vec![root.clone()], &vec![S!("excluded.py")]);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(ignore_mgr.is_ignored(&format!("{}/other/cache.tmp", root), false));
+    assert!(ignore_mgr.is_ignored(&format!("{}/module/generated.py", root), false));
+    assert!(!ignore_mgr.is_ignored(&format!("{}/other/generated.py", root), false));
+    assert!(!ignore_mgr.is_ignored(&format!("{}/module/kept.tmp", root), false));
+    assert!(ignore_mgr.is_ignored(&format!("{}/module/excluded.py", root), false));
+}
@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::GotoDefinitionResponse;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::definition::DefinitionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn models_file(session: &mut SessionInfo, name: &str) -> Rc<RefCell<Symbol>> {
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!(name)];
+    session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().unwrap()
+}
+
+/* Return the file names and the lines of the definitions, or of the declarations, of the end of the first occurrence of
+marker in the file */
+fn get_targets(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str, declaration: bool) -> Vec<(String, u32)> {
+    session.sync_odoo.definition_link_support = false;
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(marker).unwrap() + marker.len();
+    let position = file_info.borrow().offset_to_position(offset);
+    let response = if declaration {
+        DefinitionFeature::get_declaration(session, file, &file_info, position.line, position.character)
+    } else {
+        DefinitionFeature::get_location(session, file, &file_info, position.line, position.character)
+    };
+    let Some(GotoDefinitionResponse::Array(locations)) = response else {
+        panic!("no locations found for {}", marker);
+    };
+    locations.iter().map(|location| {
+        (S!(location.uri.path().as_str().rsplit('/').next().unwrap()), location.range.start.line)
+    }).collect()
+}
+
+#[test]
+fn test_import_definitions() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    check_definition_through_reexports(&mut session);
+    check_definition_through_circular_imports(&mut session);
+}
+
+/* The definition of an imported name goes through the re-exports and the star imports to the class, when its declaration
+stops at the nearest binding */
+fn check_definition_through_reexports(session: &mut SessionInfo) {
+    let usage = models_file(session, "reexport_usage");
+    assert_eq!(get_targets(session, &usage, "value = Origi", false), vec![(S!("reexport_origin.py"), 0)]);
+    assert_eq!(get_targets(session, &usage, "import Origi", false), vec![(S!("reexport_origin.py"), 0)]);
+    assert_eq!(get_targets(session, &usage, "value = Origi", true), vec![(S!("reexport_usage.py"), 0)]);
+    assert_eq!(get_targets(session, &usage, "import Origi", true), vec![(S!("reexport_level1.py"), 0)]);
+}
+
+/* A circular import ends on the binding of the name */
+fn check_definition_through_circular_imports(session: &mut SessionInfo) {
+    let cycle = models_file(session, "reexport_cycle");
+    let targets = get_targets(session, &cycle, "looped = Lo", false);
+    assert!(!targets.is_empty());
+    for (file, _) in targets.iter() {
+        assert!(file == "reexport_cycle.py" || file == "reexport_cycle_back.py", "unexpected definition in {}", file);
+    }
+}
@@ -0,0 +1,71 @@
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_import_fallbacks() {
+    let mut odoo = setup::setup::setup_server();
+    check_import_fallbacks(&mut odoo);
+    check_except_name(&mut odoo);
+    check_optional_star_import(&mut odoo);
+}
+
+/* The imports of a try block catching ImportError are optional, and the handlers declare the fallbacks */
+fn check_import_fallbacks(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("import_fallbacks")];
+    let file = odoo.get_symbol(&(file_tree.clone(), vec![]), u32::MAX).pop().expect("import_fallbacks should be loaded");
+    //json is declared by the import of the try block and by the one of the handler
+    let json = file.borrow().get_content_symbol("json", u32::MAX);
+    assert_eq!(json.len(), 2);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    //simplejson is not installed: only the fallback is resolved, to the json of the standard library
+    let resolved: Vec<String> = json.iter().flat_map(|declaration| declaration.borrow().evaluations().cloned().unwrap_or_default())
+        .filter_map(|evaluation| evaluation.symbol.get_symbol(&mut session, &mut None, &mut vec![], None).weak.upgrade())
+        .map(|symbol| symbol.borrow().name().clone()).collect();
+    assert_eq!(resolved, vec![S!("json")]);
+    //a bare except catches the ImportError too
+    assert_eq!(file.borrow().get_content_symbol("fast_json", u32::MAX).len(), 2);
+    assert!(file.borrow().not_found_paths().is_empty());
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(!validator.diagnostics.iter().any(|d| d.code == Some(NumberOrString::String(S!("OLS20001")))));
+}
+
+/* The name bound by a handler is an instance of each exception it catches */
+fn check_except_name(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("import_fallbacks")];
+    let dumps = odoo.get_symbol(&(file_tree, vec![S!("dumps")]), u32::MAX).pop().expect("dumps should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    dumps.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(dumps.clone()).validate(&mut session);
+    let error = dumps.borrow().all_symbols().find(|symbol| symbol.borrow().name() == "error").expect("error should be declared");
+    let evaluations = error.borrow().evaluations().cloned().unwrap_or_default();
+    let types: Vec<(String, Option<bool>)> = evaluations.iter().map(|evaluation| {
+        let symbol = evaluation.symbol.get_symbol(&mut session, &mut None, &mut vec![], None);
+        (symbol.weak.upgrade().unwrap().borrow().name().clone(), symbol.instance)
+    }).collect();
+    assert_eq!(types, vec![(S!("TypeError"), Some(true)), (S!("ValueError"), Some(true))]);
+}
+
+/* A star import of an optional dependency, in a try block under an if at the module level, declares its names */
+fn check_optional_star_import(odoo: &mut SyncOdoo) {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("optional_imports")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("optional_imports should be loaded");
+    for name in ["fromstring", "Element", "XMLParser"] {
+        assert!(!file.borrow().get_content_symbol(name, u32::MAX).is_empty(), "{} should be imported", name);
+    }
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(!validator.diagnostics.iter().any(|d| d.code == Some(NumberOrString::String(S!("OLS20001")))));
+}
@@ -0,0 +1,83 @@
+use ruff_python_ast::{Stmt, StmtClassDef};
+use odoo_ls_server::core::injected_attributes::InjectedAttributes;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn get_class(code: &str) -> StmtClassDef {
+    let module = parse_module(code);
+    match module.body.into_iter().next() {
+        Some(Stmt::ClassDef(class)) => class,
+        _ => panic!("Not a class")
+    }
+}
+
+fn get_injected_names(class: &StmtClassDef, method: &str) -> Vec<String> {
+    class.body.iter().find_map(|stmt| match stmt {
+        Stmt::FunctionDef(f) if f.name.as_str() == method => Some(InjectedAttributes::find(f).into_iter().map(|a| a.name).collect()),
+        _ => None
+    }).expect("method not found")
+}
+
+#[test]
+fn test_init_subclass_attributes() {
+    let class = get_class("\
+class Base:
+    def __init_subclass__(cls, code=None, **kwargs):
+        super().__init_subclass__(**kwargs)
+        cls.code = code or cls.__name__.lower()
+        if code:
+            setattr(cls, '_registered', True)
+        setattr(cls, code, True)
+        other.value = 1
+");
+    assert!(!InjectedAttributes::is_metaclass(&class));
+    assert_eq!(get_injected_names(&class, "__init_subclass__"), vec!["code", "_registered"]);
+}
+
+#[test]
+fn test_metaclass_attributes() {
+    let class = get_class("\
+class MetaModel(api.Meta):
+    def __new__(meta, name, bases, attrs):
+        cls = super().__new__(meta, name, bases, attrs)
+        cls._created = True
+        return cls
+
+    def __init__(self, name, bases, attrs):
+        super().__init__(name, bases, attrs)
+        if not hasattr(self, '_module'):
+            self._module = self._get_addon_name(self.__module__)
+");
+    assert!(InjectedAttributes::is_metaclass(&class));
+    assert_eq!(get_injected_names(&class, "__new__"), vec!["_created"]);
+    assert_eq!(get_injected_names(&class, "__init__"), vec!["_module"]);
+    assert!(InjectedAttributes::is_metaclass(&get_class("class M(type):\n    pass\n")));
+}
+
+#[test]
+fn test_fixture_attributes() {
+    let class = get_class("\
+class TestPartner(TransactionCase):
+    @classmethod
+    def setUpClass(cls):
+        super().setUpClass()
+        cls.partner = cls.env['res.partner'].create({'name': 'Test'})
+        cls.partner.name = 'Renamed'
+
+    def setUp(self):
+        super().setUp()
+        self.user = self.env.user
+
+    def test_partner(self):
+        self.other = 1
+");
+    let is_fixture: Vec<bool> = class.body.iter().filter_map(|stmt| match stmt {
+        Stmt::FunctionDef(f) => Some(InjectedAttributes::is_fixture_method(f)),
+        _ => None
+    }).collect();
+    assert_eq!(is_fixture, vec![true, true, false]);
+    assert_eq!(get_injected_names(&class, "setUpClass"), vec!["partner"]);
+    assert_eq!(get_injected_names(&class, "setUp"), vec!["user"]);
+}
@@ -0,0 +1,30 @@
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* A class whose _inherit lists several models without _name is added to each of them */
+#[test]
+fn test_class_added_to_several_models() {
+    let mut odoo = setup::setup::setup_server();
+    let module = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3")], vec![]), u32::MAX).pop();
+    assert!(module.is_some());
+    let injected = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("injected")], vec![S!("InjectedNote")]), u32::MAX).pop();
+    let injected = injected.expect("InjectedNote should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let model_names: Vec<String> = injected.borrow().as_class_sym()._model.as_ref().unwrap().names().cloned().collect();
+    assert_eq!(model_names, vec![S!("pygls.tests.states"), S!("pygls.tests.m_name")]);
+    for model_name in model_names.iter() {
+        let model = session.sync_odoo.models.get(model_name).cloned().unwrap();
+        let symbols = model.borrow().all_symbols(&mut session, module.clone());
+        assert!(symbols.iter().any(|(s, _)| std::rc::Rc::ptr_eq(s, &injected)), "InjectedNote is not a class of {}", model_name);
+        //the class only extends the models
+        assert!(!model.borrow().get_main_symbols(&mut session, module.clone(), &mut None).iter().any(|s| std::rc::Rc::ptr_eq(s, &injected)));
+    }
+    //self gets the members of all the models
+    for member in ["note", "f1", "state"] {
+        let (members, _) = injected.borrow().get_member_symbol(&mut session, &S!(member), module.clone(), false, false, false, false);
+        assert!(!members.is_empty(), "{} is not a member of InjectedNote", member);
+    }
+}
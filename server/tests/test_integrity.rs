@@ -0,0 +1,34 @@
+use std::fs;
+
+use odoo_ls_server::core::integrity::IntegrityChecker;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_builder::PythonArchBuilder;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+
+/* A rebuilt file must replace its previous content, and detach it from the tree */
+#[test]
+fn test_rebuild_replaces_content() {
+    let code = "class A:\n    x = 1\n\ndef f():\n    pass\n\nif f():\n    y = 1\nelse:\n    y = 2\n";
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_integrity_{}.py", std::process::id()));
+    fs::write(&path, code).unwrap();
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    let file = Symbol::create_from_path(&mut session, &path, root, false).unwrap();
+    PythonArchBuilder::new(file.clone()).load_arch(&mut session);
+    let previous_class = file.borrow().get_content_symbol("A", u32::MAX);
+    assert_eq!(previous_class.len(), 1);
+    assert!(IntegrityChecker::check(&mut session).is_empty());
+
+    PythonArchBuilder::new(file.clone()).load_arch(&mut session);
+    let class = file.borrow().get_content_symbol("A", u32::MAX);
+    assert_eq!(class.len(), 1);
+    assert!(!std::rc::Rc::ptr_eq(&class[0], &previous_class[0]));
+    assert!(previous_class[0].borrow().parent().is_none());
+    assert_eq!(file.borrow().get_content_symbol("y", u32::MAX).len(), 2);
+    let violations = IntegrityChecker::check(&mut session);
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{CompletionItemKind, CompletionResponse};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Return the labels of the keyword arguments completed at the end of the first occurrence of marker in the file */
+fn get_keywords(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str) -> Vec<String> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(marker).unwrap() + marker.len();
+    let position = file_info.borrow().offset_to_position(offset);
+    let Some(CompletionResponse::List(list)) = CompletionFeature::autocomplete(session, file, &file_info, position.line, position.character) else {
+        panic!("a completion list should be given after {}", marker);
+    };
+    list.items.into_iter().filter(|item| item.kind == Some(CompletionItemKind::FIELD)).map(|item| item.label).collect()
+}
+
+/* The keyword arguments of a call are the parameters of the callee that are not given yet, by position or by keyword.
+The self parameter of a method is bound to its receiver */
+#[test]
+fn test_keyword_completion() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("keyword_arguments")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("keyword_arguments should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let keywords = get_keywords(&mut session, &file, "finder.search(");
+    assert_eq!(keywords, vec!["domain=", "fields=", "limit=", "offset=", "order="]);
+    assert!(!keywords.contains(&S!("self=")));
+    //the domain is given by position, and the order by keyword
+    let keywords = get_keywords(&mut session, &file, "order=\"name\", ");
+    assert_eq!(keywords, vec!["fields=", "limit=", "offset="]);
+    let keywords = get_keywords(&mut session, &file, "finder.search([], 10, ");
+    assert_eq!(keywords, vec!["fields=", "limit=", "order="]);
+
+    //the keywords of a class are the ones of its __init__, and a name being typed filters them
+    assert_eq!(get_keywords(&mut session, &file, "wrapper = Wrapper("), vec!["name=", "size="]);
+    assert_eq!(get_keywords(&mut session, &file, "Wrapper(si"), vec!["size="]);
+    //the **kwargs of a method are forwarded to the callee that receives them
+    let keywords = get_keywords(&mut session, &file, "wrapper.forward(");
+    assert!(keywords.contains(&S!("order=")) && keywords.contains(&S!("limit=")), "{:?}", keywords);
+    //the attributes of a field class are its parameters, except the internal ones
+    let keywords = get_keywords(&mut session, &file, "fields.Char(");
+    assert!(keywords.contains(&S!("string=")) && keywords.contains(&S!("required=")), "{:?}", keywords);
+    assert!(!keywords.contains(&S!("type=")) && !keywords.contains(&S!("column_type=")), "{:?}", keywords);
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps, SymType};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::function_symbol::ArgumentType;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_types;
+
+/* Return the names of the parameters of a function, with their kinds and whether they have a default */
+fn get_parameters(function: &Rc<RefCell<Symbol>>) -> Vec<(String, ArgumentType, bool)> {
+    function.borrow().as_func().args.iter().map(|arg| {
+        (arg.symbol.upgrade().unwrap().borrow().name().clone(), arg.arg_type, arg.default_value.is_some())
+    }).collect()
+}
+
+/* The lambdas assigned to names are functions, whose returned value is the expression of the lambda */
+#[test]
+fn test_lambdas() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("lambdas")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("lambdas should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let get_name = file.borrow().get_content_symbol("get_name", u32::MAX).pop().unwrap();
+    assert_eq!(get_name.borrow().typ(), SymType::FUNCTION);
+    assert!(get_parameters(&get_name).is_empty());
+    assert_eq!(get_types(&mut session, &get_name), vec![(S!("str"), true)]);
+    let name = file.borrow().get_content_symbol("name", u32::MAX).pop().unwrap();
+    assert_eq!(get_types(&mut session, &name), vec![(S!("str"), true)]);
+    let double = file.borrow().get_content_symbol("double", u32::MAX).pop().unwrap();
+    assert_eq!(get_parameters(&double), vec![(S!("value"), ArgumentType::ARG, false), (S!("times"), ArgumentType::ARG, true)]);
+    //the lambda returned by a lambda is not evaluated
+    let nested = file.borrow().get_content_symbol("nested", u32::MAX).pop().unwrap();
+    assert_eq!(nested.borrow().typ(), SymType::FUNCTION);
+    //the first parameter of a lambda of a class body is the instance, like for a method
+    let scaler = file.borrow().get_content_symbol("Scaler", u32::MAX).pop().unwrap();
+    let scale = scaler.borrow().get_content_symbol("scale", u32::MAX).pop().unwrap();
+    assert_eq!(get_parameters(&scale), vec![(S!("self"), ArgumentType::ARG, false), (S!("value"), ArgumentType::ARG, false)]);
+    assert_eq!(get_types(&mut session, &scale), vec![(S!("Scaler"), true)]);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(validator.diagnostics.is_empty(), "{:?}", validator.diagnostics.iter().map(|d| d.code.clone()).collect::<Vec<Option<NumberOrString>>>());
+    //the call of the lambda by a method is resolved like the one of a def
+    let rescale = scaler.borrow().get_content_symbol("rescale", u32::MAX).pop().unwrap();
+    assert_eq!(get_types(&mut session, &rescale), vec![(S!("Scaler"), true)]);
+}
@@ -0,0 +1,32 @@
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_declared_type_names;
+
+/* The targets of the for loops are evaluated to the items of the iterable: the type arguments of the annotations, the
+elements of the literals, and the pairs of dict.items(), enumerate() and zip(), distributed on the tuple targets */
+#[test]
+fn test_loop_targets() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("loop_targets")];
+    let function = odoo.get_symbol(&(file_tree, vec![S!("Order"), S!("read_lines")]), u32::MAX).pop().expect("read_lines should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    assert_eq!(get_declared_type_names(&mut session, &function, "current"), vec![S!("Line")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "label"), vec![S!("str")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "amount"), vec![S!("float")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "position"), vec![S!("int")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "enumerated"), vec![S!("Line")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "counted"), vec![S!("int")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "literal_key"), vec![S!("str"), S!("str")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "literal_value"), vec![S!("int"), S!("int")]);
+    //a tuple item assigned to a single name is a tuple
+    assert_eq!(get_declared_type_names(&mut session, &function, "paired"), vec![S!("tuple")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "unpacked"), vec![S!("str"), S!("str")]);
+}
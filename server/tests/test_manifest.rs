@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{CompletionResponse, DidOpenTextDocumentParams, GotoDefinitionResponse, HoverContents, Position, TextDocumentItem};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::features::manifest::ManifestFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_module_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_6")
+}
+
+/* Position of the offset-th character after the first occurrence of text in the source */
+fn position_of(source: &str, text: &str, offset: usize) -> Position {
+    let index = source.find(text).unwrap() + offset;
+    let line_start = source[..index].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Position::new(source[..index].matches('\n').count() as u32, (index - line_start) as u32)
+}
+
+fn get_labels(response: Option<CompletionResponse>) -> Vec<String> {
+    match response {
+        Some(CompletionResponse::List(list)) => list.items.into_iter().map(|item| item.label).collect(),
+        Some(CompletionResponse::Array(items)) => items.into_iter().map(|item| item.label).collect(),
+        None => vec![],
+    }
+}
+
+#[test]
+fn test_manifest_features() {
+    assert!(ManifestFeature::is_manifest("/addons/sale/__manifest__.py"));
+    assert!(ManifestFeature::is_manifest("/addons/sale/__openerp__.py"));
+    assert!(!ManifestFeature::is_manifest("/addons/sale/my__manifest__.py"));
+
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let path = get_module_path().join("__manifest__.py").sanitize();
+    let source = fs::read_to_string(&path).unwrap();
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem { uri: FileMgr::pathname2uri(&path), language_id: S!("python"), version: 1, text: source.clone() }
+    });
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path).expect("the manifest should be parsed");
+
+    //hover of a key: its type and description
+    let position = position_of(&source, "'depends'", 2);
+    let hover = ManifestFeature::get_hover(&mut session, &file_info, position.line, position.character).expect("no hover on depends");
+    let HoverContents::Markup(markup) = hover.contents else { panic!("hover should be markdown") };
+    assert!(markup.value.contains("(manifest key) depends: list[str]"), "{}", markup.value);
+    assert_eq!(hover.range.map(|range| (range.start, range.end)), Some((position_of(&source, "'depends'", 0), position_of(&source, "'depends'", 9))));
+    //hover of a dependency: the description of the module
+    let position = position_of(&source, "\"module_1\"", 3);
+    let hover = ManifestFeature::get_hover(&mut session, &file_info, position.line, position.character).expect("no hover on module_1");
+    let HoverContents::Markup(markup) = hover.contents else { panic!("hover should be markdown") };
+    assert!(markup.value.starts_with("```python  \n(module) module_1"), "{}", markup.value);
+    //nothing on the other values
+    let position = position_of(&source, "'LGPL-3'", 2);
+    assert!(ManifestFeature::get_hover(&mut session, &file_info, position.line, position.character).is_none());
+
+    //the keys are completed, except the ones already in the manifest
+    let position = position_of(&source, "'name'", 2);
+    let labels = get_labels(ManifestFeature::autocomplete(&mut session, &path, &file_info, position.line, position.character));
+    assert!(labels.contains(&S!("name")) && labels.contains(&S!("category")) && labels.contains(&S!("assets")));
+    assert!(!labels.contains(&S!("version")) && !labels.contains(&S!("depends")));
+    //the modules of the addons paths, except the module itself
+    let position = position_of(&source, "\"module_1\"", 3);
+    let labels = get_labels(ManifestFeature::autocomplete(&mut session, &path, &file_info, position.line, position.character));
+    assert!(labels.contains(&S!("module_2")) && labels.contains(&S!("module_5")));
+    assert!(!labels.contains(&S!("module_6")));
+    //the data files of the module, out of the static directory
+    let position = position_of(&source, "\"views/views.xml\"", 3);
+    let labels = get_labels(ManifestFeature::autocomplete(&mut session, &path, &file_info, position.line, position.character));
+    assert_eq!(labels, vec![S!("data/records.csv"), S!("views/views.xml")]);
+    let position = position_of(&source, "'LGPL-3'", 2);
+    let labels = get_labels(ManifestFeature::autocomplete(&mut session, &path, &file_info, position.line, position.character));
+    assert_eq!(labels.len(), 10);
+    assert!(labels.contains(&S!("LGPL-3")));
+
+    //the hooks lead to their function, imported in the __init__.py of the module
+    let position = position_of(&source, "\"post_init\"", 3);
+    let Some(GotoDefinitionResponse::Array(locations)) = ManifestFeature::get_location(&mut session, &path, &file_info, position.line, position.character) else {
+        panic!("no definition for post_init");
+    };
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].uri, FileMgr::pathname2uri(&get_module_path().join("hooks.py").sanitize()));
+    assert_eq!(locations[0].range.start, Position::new(0, 0));
+    let position = position_of(&source, "\"module_1\"", 3);
+    assert!(ManifestFeature::get_location(&mut session, &path, &file_info, position.line, position.character).is_none());
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_types::{Diagnostic, NumberOrString, Position, Range};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_type_names;
+
+fn get_module_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_6")
+}
+
+/* Return the OLS30328 diagnostics of the hooks file, validated against the given major version of Odoo */
+fn get_hook_diagnostics(session: &mut SessionInfo, path: &PathBuf, version_major: u32) -> Vec<Diagnostic> {
+    session.sync_odoo.version_major = version_major;
+    let file = session.sync_odoo.get_file_symbol(path).expect("hooks should be loaded");
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(session);
+    validator.diagnostics.into_iter().filter(|d| d.code == Some(NumberOrString::String(S!("OLS30328")))).collect()
+}
+
+/* Calling an environment gives a new environment, and the post_init_hook of the manifest is called with env since
+Odoo 17, and with (cr, registry) before */
+#[test]
+fn test_manifest_hooks_and_environments() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_6"), S!("models"), S!("environments")];
+    let file = session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().expect("environments should be loaded");
+    for name in ["env", "sudo_env", "user_env"] {
+        let variable = file.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+        assert_eq!(get_type_names(&mut session, &variable), vec![S!("Environment")], "type of {}", name);
+        let evaluations = variable.borrow().evaluations().cloned().unwrap_or_default();
+        assert!(evaluations.iter().all(|e| e.symbol.get_symbol(&mut session, &mut None, &mut vec![], None).is_instance().unwrap_or(false)),
+            "{} should be an instance", name);
+    }
+
+    let path = get_module_path().join("hooks.py");
+    let source = fs::read_to_string(&path).unwrap();
+    let start = source.find("post_init").unwrap() as u32;
+    let version = session.sync_odoo.version_major;
+    assert!(get_hook_diagnostics(&mut session, &path, 17).is_empty());
+    let diagnostics = get_hook_diagnostics(&mut session, &path, 16);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].range, Range::new(Position::new(start, 0), Position::new(start + "post_init".len() as u32, 0)));
+    assert_eq!(diagnostics[0].message, "post_init_hook is called with (cr, registry) before Odoo 17");
+    //an unknown version disables the check
+    assert!(get_hook_diagnostics(&mut session, &path, 0).is_empty());
+    session.sync_odoo.version_major = version;
+}
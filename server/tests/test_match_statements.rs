@@ -0,0 +1,44 @@
+use std::fs;
+
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_builder::PythonArchBuilder;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+
+const CODE: &str = "\
+match event:
+    case Point(x=px, y=py):
+        pass
+    case [first, *rest]:
+        pass
+    case {'key': value, **others}:
+        pass
+    case str() | bytes() as text:
+        pass
+    case _ as whole:
+        class Handler:
+            pass
+";
+
+/* The names captured by the patterns of the cases are declared in the enclosing scope, with the range of the name */
+#[test]
+fn test_match_captures() {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_match_{}.py", std::process::id()));
+    fs::write(&path, CODE).unwrap();
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    let file = Symbol::create_from_path(&mut session, &path, root, false).unwrap();
+    let mut builder = PythonArchBuilder::new(file.clone());
+    builder.load_arch(&mut session);
+    let _ = fs::remove_file(&path);
+    for name in ["px", "py", "first", "rest", "value", "others", "text", "whole"] {
+        let declarations = file.borrow().get_content_symbol(name, u32::MAX);
+        assert_eq!(declarations.len(), 1, "{} should be declared once", name);
+        let range = declarations[0].borrow().range().clone();
+        assert_eq!(&CODE[range.start().to_usize()..range.end().to_usize()], name);
+    }
+    //the definitions of the case bodies are visited
+    assert_eq!(file.borrow().get_content_symbol("Handler", u32::MAX).len(), 1);
+}
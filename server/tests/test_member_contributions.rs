@@ -0,0 +1,49 @@
+use lsp_types::{CompletionResponse, HoverContents};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* func_1 is defined on pygls.tests.m_name by module_1 and overridden by module_6, that depends on it */
+#[test]
+fn test_member_contributions() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_6"), S!("models"), S!("contributions")];
+    let file = session.sync_odoo.get_symbol(&(file_tree.clone(), vec![]), u32::MAX).pop().expect("contributions should be loaded");
+    let func_1 = session.sync_odoo.get_symbol(&(file_tree, vec![S!("ContributedModel"), S!("func_1")]), u32::MAX).pop().expect("func_1 should be loaded");
+    let module_6 = file.borrow().find_module();
+    let contributions = Symbol::get_member_contributions(&func_1, &mut session, module_6).expect("func_1 is a member of a model");
+    let modules: Vec<String> = contributions.iter().map(|(module, _)| module.clone()).collect();
+    assert_eq!(modules, vec![S!("module_1"), S!("module_6")]);
+    assert_eq!(HoverFeature::build_provenance(&contributions), Some(S!("defined in `module_1`, overridden in `module_6`")));
+    //module_1 doesn't see the override of module_6, that it doesn't depend on
+    let module_1 = contributions[0].1[0].borrow().find_module();
+    let contributions = Symbol::get_member_contributions(&func_1, &mut session, module_1).unwrap();
+    assert_eq!(HoverFeature::build_provenance(&contributions), Some(S!("defined in `module_1`")));
+
+    //the call in action_check
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let position = file_info.borrow().offset_to_position(source.find("self.func_1()").unwrap() + "self.func_1".len());
+    let contributions = HoverFeature::get_member_contributions(&mut session, &file, &file_info, position.line, position.character - 1).expect("no contributions on func_1");
+    assert_eq!(contributions.iter().map(|c| c.module.clone()).collect::<Vec<String>>(), vec![S!("module_1"), S!("module_6")]);
+    assert!(contributions.iter().all(|c| c.locations.len() == 1));
+    assert!(contributions[1].locations[0].uri.as_str().ends_with("module_6/models/contributions.py"));
+    let hover = HoverFeature::get_hover(&mut session, &file, &file_info, position.line, position.character - 1).expect("no hover on func_1");
+    let HoverContents::Markup(content) = hover.contents else {
+        panic!("the hover should be markdown");
+    };
+    assert!(content.value.contains("defined in `module_1`, overridden in `module_6`"), "{}", content.value);
+    //the completion items show the module defining the member
+    let Some(CompletionResponse::List(list)) = CompletionFeature::autocomplete(&mut session, &file, &file_info, position.line, position.character) else {
+        panic!("no completion on self.func_1");
+    };
+    let item = list.items.iter().find(|item| item.label == "func_1").expect("func_1 should be completed");
+    assert_eq!(item.detail, Some(S!("defined in module_1")));
+}
@@ -0,0 +1,82 @@
+use ruff_python_ast::Stmt;
+use odoo_ls_server::core::method_references::MethodReferences;
+use odoo_ls_server::core::suppression::Suppression;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+/* Return the field methods of the class that are not referenced by its body */
+fn unreferenced(code: &str) -> Vec<String> {
+    let module = parse_module(code);
+    let Some(Stmt::ClassDef(class)) = module.body.into_iter().next() else {
+        panic!("Not a class");
+    };
+    let mut references = MethodReferences::new();
+    references.add_body(&class.body);
+    MethodReferences::get_field_methods(&class.body).into_iter()
+        .filter(|method| !references.is_referenced(method.name.as_str()))
+        .map(|method| method.name.to_string())
+        .collect()
+}
+
+#[test]
+fn test_unreferenced_field_methods() {
+    let methods = unreferenced("\
+class Order(models.Model):
+    _name = 'sale.order'
+    total = fields.Float(compute='_compute_amount_total', inverse=_inverse_total, search='_search_total')
+    tax = fields.Float(compute='_compute_tax')
+
+    def _compute_total(self):
+        pass
+
+    def _compute_amount_total(self):
+        self._compute_tax()
+
+    def _compute_tax(self):
+        pass
+
+    def _inverse_total(self):
+        pass
+
+    def _search_total(self, operator, value):
+        pass
+
+    def _search_old(self, operator, value):
+        return self._search_old(operator, value)
+
+    def _inverse_discount(self):
+        pass
+
+    def _compute_(self):
+        pass
+
+    def compute_total(self):
+        pass
+");
+    assert_eq!(methods, vec!["_compute_total", "_inverse_discount"]);
+}
+
+#[test]
+fn test_closest_compute() {
+    let arguments = vec!["_compute_amount_total", "_compute_totl", "_compute_tax"];
+    assert_eq!(MethodReferences::find_closest("_compute_total", arguments.iter().copied()), Some("_compute_totl"));
+    assert_eq!(MethodReferences::find_closest("_compute_taxes", arguments.iter().copied()), Some("_compute_tax"));
+    assert_eq!(MethodReferences::find_closest("_compute_weight", arguments.iter().copied()), None);
+    assert_eq!(MethodReferences::find_closest("_compute_tax", vec!["_compute_tax"].into_iter()), None);
+    assert_eq!(MethodReferences::edit_distance("kitten", "sitting"), 3);
+    assert_eq!(MethodReferences::edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn test_noqa_suppression() {
+    assert!(Suppression::is_suppressed("    def _compute_old(self):  # noqa: OLS20021", "OLS20021"));
+    assert!(Suppression::is_suppressed("    def _compute_old(self):  # noqa:OLS30336, OLS20021", "OLS20021"));
+    assert!(Suppression::is_suppressed("    @api.depens('total')  # noqa", "OLS30336"));
+    assert!(Suppression::is_suppressed("    @api.depens('total')  #noqa: OLS30336 typo kept on purpose", "OLS30336"));
+    assert!(!Suppression::is_suppressed("    def _compute_old(self):  # noqa: OLS30336", "OLS20021"));
+    assert!(!Suppression::is_suppressed("    def _compute_old(self):  # noqaa", "OLS20021"));
+    assert!(!Suppression::is_suppressed("    def _compute_old(self):  # legacy", "OLS20021"));
+    assert!(!Suppression::is_suppressed("    def _compute_old(self):", "OLS20021"));
+}
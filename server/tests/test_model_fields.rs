@@ -0,0 +1,39 @@
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_type_names;
+
+fn relations_tree() -> Vec<String> {
+    vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("relations")]
+}
+
+/* The fields of a model are evaluated to the value they give on a record, then to their field class: a Many2one to
+its comodel, so the attributes of the comodel can be chained */
+#[test]
+fn test_relational_fields() {
+    let mut odoo = setup::setup::setup_server();
+    let order = odoo.get_symbol(&(relations_tree(), vec![S!("Order")]), u32::MAX).pop().expect("Order should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let customer_id = order.borrow().get_content_symbol("customer_id", u32::MAX).pop().unwrap();
+    assert_eq!(get_type_names(&mut session, &customer_id), vec![S!("Customer"), S!("Many2one")]);
+    //a related field and a comodel that is not loaded keep their field class
+    let customer_name = order.borrow().get_content_symbol("customer_name", u32::MAX).pop().unwrap();
+    assert_eq!(get_type_names(&mut session, &customer_name), vec![S!("Char")]);
+    let carrier_id = order.borrow().get_content_symbol("carrier_id", u32::MAX).pop().unwrap();
+    assert_eq!(get_type_names(&mut session, &carrier_id), vec![S!("Many2one")]);
+    assert!(customer_id.borrow().is_field(&mut session));
+
+    let function = order.borrow().get_content_symbol("get_customer", u32::MAX).pop().unwrap();
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    for (name, expected) in [("name", vec![S!("Char"), S!("str")]), ("credit", vec![S!("Integer"), S!("int")]),
+                             ("since", vec![S!("Date"), S!("date")]), ("carrier", vec![S!("Many2one")])] {
+        let variable = function.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+        assert_eq!(get_type_names(&mut session, &variable), expected, "type of {}", name);
+    }
+}
@@ -0,0 +1,48 @@
+use odoo_ls_server::core::model_kind::ModelKind;
+use odoo_ls_server::features::hover::HoverFeature;
+use ruff_python_ast::Expr;
+use ruff_python_parser::parse_expression;
+
+fn get_env_model_name(source: &str) -> Option<String> {
+    let parsed = parse_expression(source).unwrap();
+    let expr: &Expr = parsed.expr();
+    ModelKind::get_env_model_name(expr).map(|literal| literal.value.to_string())
+}
+
+#[test]
+fn test_kind_from_data() {
+    assert_eq!(ModelKind::from_data(false, false), ModelKind::MODEL);
+    assert_eq!(ModelKind::from_data(true, false), ModelKind::ABSTRACT);
+    //TransientModel sets _transient, and inherits _abstract = False
+    assert_eq!(ModelKind::from_data(false, true), ModelKind::TRANSIENT);
+    assert_eq!(ModelKind::from_data(true, true), ModelKind::TRANSIENT);
+}
+
+#[test]
+fn test_effective_kind() {
+    assert_eq!(ModelKind::get_effective_kind(&[]), None);
+    assert_eq!(ModelKind::get_effective_kind(&[ModelKind::ABSTRACT]), Some(ModelKind::ABSTRACT));
+    //a module creating the abstract model again as a persistent one makes it persistent
+    assert_eq!(ModelKind::get_effective_kind(&[ModelKind::ABSTRACT, ModelKind::MODEL]), Some(ModelKind::MODEL));
+    assert_eq!(ModelKind::get_effective_kind(&[ModelKind::TRANSIENT, ModelKind::ABSTRACT]), Some(ModelKind::TRANSIENT));
+    assert_eq!(ModelKind::get_effective_kind(&[ModelKind::TRANSIENT, ModelKind::MODEL]), Some(ModelKind::MODEL));
+}
+
+#[test]
+fn test_env_model_name() {
+    assert_eq!(get_env_model_name("self.env['mail.thread']"), Some(String::from("mail.thread")));
+    assert_eq!(get_env_model_name("env[\"sale.order\"]"), Some(String::from("sale.order")));
+    assert_eq!(get_env_model_name("self.env['mail.thread'].sudo().with_context(active_test=False)"), Some(String::from("mail.thread")));
+    assert_eq!(get_env_model_name("self"), None);
+    assert_eq!(get_env_model_name("self.env[model_name]"), None);
+    assert_eq!(get_env_model_name("self.env['sale.order'].browse(1)"), None);
+    assert_eq!(get_env_model_name("values['sale.order']"), None);
+}
+
+#[test]
+fn test_model_kind_hover() {
+    assert_eq!(HoverFeature::build_model_kind(ModelKind::TRANSIENT, true), String::from("Kind: TransientModel"));
+    assert_eq!(HoverFeature::build_model_kind(ModelKind::MODEL, false), String::from("Kind: Model, with `_auto = False`"));
+    //an abstract model never has a table
+    assert_eq!(HoverFeature::build_model_kind(ModelKind::ABSTRACT, false), String::from("Kind: AbstractModel"));
+}
@@ -0,0 +1,56 @@
+use ruff_python_ast::Stmt;
+use odoo_ls_server::core::model_metadata::ModelMetadata;
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn get_metadata(code: &str) -> ModelMetadata {
+    let module = parse_module(code);
+    match module.body.into_iter().next() {
+        Some(Stmt::ClassDef(class)) => ModelMetadata::from_body(&class.body),
+        _ => panic!("Not a class")
+    }
+}
+
+#[test]
+fn test_new_model_metadata() {
+    let metadata = get_metadata("\
+class Report(models.Model):
+    _name = 'sale.report'
+    _auto = False
+    _transient_max_hours = 2
+");
+    assert!(!metadata.is_extension());
+    assert!(metadata.is_missing_description());
+    assert!(metadata.get_auto_without_init().is_some());
+    assert_eq!(metadata.transient_attributes[0].0, "_transient_max_hours");
+
+    let metadata = get_metadata("\
+class Report(models.Model):
+    _name = 'sale.report'
+    _description = 'Sales Analysis'
+    _auto = False
+
+    def init(self):
+        pass
+");
+    assert!(!metadata.is_missing_description());
+    assert!(metadata.get_auto_without_init().is_none());
+}
+
+#[test]
+fn test_extension_metadata() {
+    //extensions don't need a description
+    for code in ["class A(models.Model):\n    _inherit = 'res.partner'\n",
+            "class A(models.Model):\n    _name = 'res.partner'\n    _inherit = ['res.partner', 'mail.thread']\n"] {
+        let metadata = get_metadata(code);
+        assert!(metadata.is_extension());
+        assert!(!metadata.is_missing_description());
+    }
+    //a model created from another one gets its description
+    let metadata = get_metadata("class A(models.Model):\n    _name = 'res.partner.copy'\n    _inherit = 'res.partner'\n    _auto = False\n");
+    assert!(!metadata.is_extension());
+    assert!(!metadata.is_missing_description());
+    assert!(metadata.get_auto_without_init().is_some());
+}
@@ -0,0 +1,85 @@
+use odoo_ls_server::core::module_dependencies::{ModuleDependenciesOptions, ModuleDependencyCheck, ReferenceKind};
+use serde_json::json;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+const CODE: &str = "
+from odoo.addons.sale.models.sale_order import SaleOrder
+from odoo.addons import stock
+import odoo.addons.account
+from . import models
+
+try:
+    from odoo.addons.website.controllers import main
+except ImportError:
+    main = None
+
+class SaleOrderCustom(models.Model):
+    _inherit = ['sale.order', 'mail.thread']
+    _inherits = {'product.product': 'product_id'}
+
+    warehouse_id = fields.Many2one('stock.warehouse')
+    line_ids = fields.One2many(comodel_name='sale.order.line', inverse_name='order_id')
+
+    def action_open(self):
+        picking = self.env['stock.picking']
+        action = self.env.ref('stock.action_picking_tree_all')
+        view = self.env.ref('local_view')
+        return self.env['ir.actions.act_window']._for_xml_id('account.action_move_out_invoice_type')
+";
+
+#[test]
+fn test_python_references() {
+    let references = ModuleDependencyCheck::find_python_references(&parse_valid(CODE));
+    let found: Vec<(ReferenceKind, &str)> = references.iter().map(|reference| (reference.kind, reference.name.as_str())).collect();
+    //the import of website is optional, and the xml ids without a module prefix are in the module itself
+    assert_eq!(found, vec![
+        (ReferenceKind::Import, "odoo.addons.sale.models.sale_order"),
+        (ReferenceKind::Import, "odoo.addons.stock"),
+        (ReferenceKind::Import, "odoo.addons.account"),
+        (ReferenceKind::Model, "sale.order"),
+        (ReferenceKind::Model, "mail.thread"),
+        (ReferenceKind::Model, "product.product"),
+        (ReferenceKind::Model, "stock.warehouse"),
+        (ReferenceKind::Model, "sale.order.line"),
+        (ReferenceKind::Model, "stock.picking"),
+        (ReferenceKind::XmlId, "stock.action_picking_tree_all"),
+        (ReferenceKind::XmlId, "account.action_move_out_invoice_type"),
+        (ReferenceKind::Model, "ir.actions.act_window"),
+    ]);
+    let start = CODE.find("'stock.picking'").unwrap();
+    assert_eq!(references[8].range.start().to_usize(), start);
+    assert_eq!(references[8].range.end().to_usize(), start + "'stock.picking'".len());
+}
+
+#[test]
+fn test_referenced_module() {
+    assert_eq!(ModuleDependencyCheck::get_referenced_module(ReferenceKind::Import, "odoo.addons.sale.models.sale_order"), Some("sale"));
+    assert_eq!(ModuleDependencyCheck::get_referenced_module(ReferenceKind::Import, "odoo.addons.stock"), Some("stock"));
+    assert_eq!(ModuleDependencyCheck::get_referenced_module(ReferenceKind::Import, "odoo.tools"), None);
+    assert_eq!(ModuleDependencyCheck::get_referenced_module(ReferenceKind::XmlId, "stock.action_picking_tree_all"), Some("stock"));
+    //the modules defining a model are only known by the symbols
+    assert_eq!(ModuleDependencyCheck::get_referenced_module(ReferenceKind::Model, "sale.order"), None);
+}
+
+#[test]
+fn test_allowlist() {
+    let allowlist = vec!["website".to_string(), "sale_custom:stock".to_string()];
+    assert!(ModuleDependencyCheck::is_allowed(&allowlist, "sale_custom", "website"));
+    assert!(ModuleDependencyCheck::is_allowed(&allowlist, "purchase_custom", "website"));
+    assert!(ModuleDependencyCheck::is_allowed(&allowlist, "sale_custom", "stock"));
+    assert!(!ModuleDependencyCheck::is_allowed(&allowlist, "purchase_custom", "stock"));
+    assert!(!ModuleDependencyCheck::is_allowed(&allowlist, "sale_custom", "account"));
+}
+
+#[test]
+fn test_options() {
+    let options = ModuleDependenciesOptions::from_arguments(&vec![json!({"modules": ["sale_custom"], "publishDiagnostics": true})]).unwrap();
+    assert_eq!(options.modules, vec!["sale_custom"]);
+    assert!(options.allow.is_empty());
+    assert!(options.publish_diagnostics);
+    assert_eq!(ModuleDependenciesOptions::from_arguments(&vec![]).unwrap(), ModuleDependenciesOptions::default());
+    assert!(ModuleDependenciesOptions::from_arguments(&vec![json!({"modules": "sale_custom"})]).is_err());
+}
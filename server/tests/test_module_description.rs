@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::module_description::{ModuleDescription, MAX_PARAGRAPH_LENGTH};
+
+#[test]
+fn test_from_manifest() {
+    let description = ModuleDescription::from_manifest("{
+    'name': 'Sales',
+    'version': '1.2',
+    'summary': 'From quotations\\n    to invoices',
+    'author': AUTHOR,
+    'depends': ['sale'],
+}");
+    assert_eq!(description, ModuleDescription {
+        name: Some(String::from("Sales")),
+        summary: Some(String::from("From quotations to invoices")),
+        version: Some(String::from("1.2")),
+        author: None,
+        paragraph: None,
+    });
+}
+
+#[test]
+fn test_first_paragraph() {
+    let rst = "
+.. image:: https://img.shields.io/badge/maturity-Beta-yellow.png
+    :target: https://odoo-community.org/page/development-status
+
+==========
+Sale Stock
+==========
+
+|badge1| |badge2|
+
+Link the sales
+and the deliveries.
+
+Second paragraph.
+";
+    assert_eq!(ModuleDescription::first_paragraph(rst), Some(String::from("Link the sales and the deliveries.")));
+    let md = "# Sale Stock\n\n[![build](https://ci/badge.svg)](https://ci)\n\nLink the sales and the deliveries.\n";
+    assert_eq!(ModuleDescription::first_paragraph(md), Some(String::from("Link the sales and the deliveries.")));
+    assert_eq!(ModuleDescription::first_paragraph("Title\n=====\n"), None);
+    let long = "word ".repeat(MAX_PARAGRAPH_LENGTH);
+    assert_eq!(ModuleDescription::first_paragraph(&long).unwrap().chars().count(), MAX_PARAGRAPH_LENGTH);
+}
+
+#[test]
+fn test_html_to_text() {
+    let html = "<section class=\"oe_container\"><h2 class=\"oe_slogan\">Sale Stock</h2>
+<script>alert('x')</script><!-- comment -->
+<p class=\"oe_mt32\">Link the <b>sales</b> &amp; the&nbsp;deliveries &#x2014; <a href=\"https://odoo.com\">more</a></p>
+<div>Second block</div></section>";
+    assert_eq!(ModuleDescription::html_to_text(html), "Link the sales & the deliveries — more\n\nSecond block");
+}
+
+#[test]
+fn test_to_markdown() {
+    let description = ModuleDescription {
+        name: Some(String::from("Sales")),
+        summary: None,
+        version: Some(String::from("1.0")),
+        author: Some(String::from("Odoo")),
+        paragraph: Some(String::from("<img src=x> [link](https://example.com)")),
+    };
+    assert_eq!(description.to_markdown(), "**Sales** 1.0  \nAuthor: Odoo  \n***  \n&lt;img src=x&gt; \\[link\\](https://example.com)");
+    assert_eq!(ModuleDescription::default().to_markdown(), "");
+}
+
+#[test]
+fn test_get_module_path() {
+    let module = PathBuf::from("/addons/sale_stock");
+    assert_eq!(ModuleDescription::get_module_path(&module.join("__manifest__.py")), Some(module.clone()));
+    assert_eq!(ModuleDescription::get_module_path(&module.join("README.rst")), Some(module.clone()));
+    assert_eq!(ModuleDescription::get_module_path(&module.join("static").join("description").join("index.html")), Some(module.clone()));
+    assert_eq!(ModuleDescription::get_module_path(&module.join("models").join("sale.py")), None);
+    assert_eq!(ModuleDescription::get_module_path(&module.join("static").join("src").join("index.html")), None);
+}
+
+#[test]
+fn test_load() {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_module_description_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(path.join("static").join("description")).unwrap();
+    fs::write(path.join("__manifest__.py"), "{'name': 'My Module', 'summary': 'Does things'}").unwrap();
+    fs::write(path.join("static").join("description").join("index.html"), "<h1>My Module</h1><p>From the index.</p>").unwrap();
+    let description = ModuleDescription::load(&path);
+    assert_eq!(description.name, Some(String::from("My Module")));
+    assert_eq!(description.paragraph, Some(String::from("From the index.")));
+    //the README comes first
+    fs::write(path.join("README.md"), "# My Module\n\nFrom the readme.\n").unwrap();
+    assert_eq!(ModuleDescription::load(&path).paragraph, Some(String::from("From the readme.")));
+    let _ = fs::remove_dir_all(&path);
+}
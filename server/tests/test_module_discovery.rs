@@ -0,0 +1,44 @@
+use std::collections::{BTreeMap, HashSet};
+
+use odoo_ls_server::core::module_discovery::ModuleDiscovery;
+
+fn graph(modules: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+    modules.iter().map(|(name, depends)| (name.to_string(), depends.iter().map(|d| d.to_string()).collect())).collect()
+}
+
+fn names(modules: &[&str]) -> HashSet<String> {
+    modules.iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn test_priority_modules() {
+    let graph = graph(&[("base", &[]), ("mail", &["base"]), ("sale", &["mail", "product"]), ("product", &["base"]), ("stock", &["product"]), ("website", &["base"])]);
+    assert_eq!(ModuleDiscovery::get_priority_modules(&graph, &names(&["sale"])), names(&["sale", "mail", "product", "base"]));
+    assert_eq!(ModuleDiscovery::get_priority_modules(&graph, &names(&["stock", "website"])), names(&["stock", "product", "website", "base"]));
+    assert_eq!(ModuleDiscovery::get_priority_modules(&graph, &names(&[])), names(&[]));
+    //the opened files outside of the discovered modules don't give any priority
+    assert_eq!(ModuleDiscovery::get_priority_modules(&graph, &names(&["unknown"])), names(&[]));
+}
+
+#[test]
+fn test_build_order() {
+    let graph = graph(&[("base", &[]), ("mail", &["base"]), ("sale", &["mail", "product"]), ("product", &["base"]), ("stock", &["product"]), ("website", &["base"])]);
+    assert_eq!(ModuleDiscovery::get_build_order(&graph, &names(&[])), vec!["base", "mail", "product", "sale", "stock", "website"]);
+    let priority = ModuleDiscovery::get_priority_modules(&graph, &names(&["stock"]));
+    assert_eq!(ModuleDiscovery::get_build_order(&graph, &priority), vec!["base", "product", "stock", "mail", "sale", "website"]);
+    let priority = ModuleDiscovery::get_priority_modules(&graph, &names(&["sale"]));
+    assert_eq!(ModuleDiscovery::get_build_order(&graph, &priority), vec!["base", "mail", "product", "sale", "stock", "website"]);
+}
+
+#[test]
+fn test_build_order_unknown_and_cycles() {
+    //a dependency that is not found doesn't block the module
+    let graph_missing = graph(&[("base", &[]), ("sale_custom", &["sale", "base"])]);
+    assert_eq!(ModuleDiscovery::get_build_order(&graph_missing, &names(&[])), vec!["base", "sale_custom"]);
+    //the modules of a cycle are all added, by name
+    let graph_cycle = graph(&[("a", &["b"]), ("b", &["a"]), ("base", &[]), ("c", &["a"])]);
+    assert_eq!(ModuleDiscovery::get_build_order(&graph_cycle, &names(&[])), vec!["base", "a", "b", "c"]);
+    //a module depending on itself is not blocked
+    let graph_self = graph(&[("a", &["a"])]);
+    assert_eq!(ModuleDiscovery::get_build_order(&graph_self, &names(&[])), vec!["a"]);
+}
@@ -0,0 +1,28 @@
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::{get_types, get_variable};
+
+/* The attributes that a module does not declare, read or imported, are the values returned by its __getattr__ */
+#[test]
+fn test_module_getattr() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("lazy_usage")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("lazy_usage should be loaded");
+    assert!(file.borrow().not_found_paths().is_empty());
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    assert_eq!(get_types(&mut session, &get_variable(&file, "value")), vec![(S!("LazyValue"), true)]);
+    assert_eq!(get_types(&mut session, &get_variable(&file, "imported")), vec![(S!("LazyValue"), true)]);
+    //the declared attributes don't use __getattr__
+    assert_eq!(get_types(&mut session, &get_variable(&file, "declared")), vec![(S!("LazyValue"), false)]);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(validator.diagnostics.is_empty(), "{:?}", validator.diagnostics.iter().map(|d| d.code.clone()).collect::<Vec<Option<NumberOrString>>>());
+}
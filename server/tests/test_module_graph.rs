@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::features::module_graph::{ModuleGraphFeature, ModuleGraphParams};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_codes(session: &mut SessionInfo, module: &str) -> Vec<String> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join(module).join("__manifest__.py");
+    let file_info = session.sync_odoo.get_file_mgr().borrow().get_file_info(&path.sanitize()).expect("the manifest should be loaded");
+    let diagnostics = file_info.borrow().get_diagnostics();
+    diagnostics.into_iter().filter_map(|diagnostic| match diagnostic.code {
+        Some(NumberOrString::String(code)) => Some(code),
+        _ => None
+    }).collect()
+}
+
+/* module_2 depends on module_1, module_4 depends on a module that is not found and on module_5, that is not installable */
+#[test]
+fn test_module_graph() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let graph = ModuleGraphFeature::get(&mut session, &ModuleGraphParams { modules: vec![S!("module_2"), S!("module_4")] });
+    let names: Vec<&str> = graph.modules.iter().map(|module| module.name.as_str()).collect();
+    assert!(names.contains(&"module_1"), "the dependencies of the modules are returned: {:?}", names);
+    assert!(!names.contains(&"module_3"), "module_3 is not a dependency: {:?}", names);
+    assert!(!names.contains(&"module_not_found"));
+    let module_2 = graph.modules.iter().find(|module| module.name == "module_2").unwrap();
+    assert!(module_2.depends.contains(&S!("module_1")));
+    assert!(module_2.missing.is_empty());
+    assert!(module_2.installable);
+    let module_4 = graph.modules.iter().find(|module| module.name == "module_4").unwrap();
+    assert_eq!(module_4.missing, vec![S!("module_not_found")]);
+    let module_5 = graph.modules.iter().find(|module| module.name == "module_5").unwrap();
+    assert!(!module_5.installable);
+
+    //the missing dependency and the dependency that is not installable are reported on the manifest
+    assert!(!get_codes(&mut session, "module_2").iter().any(|code| code.starts_with("OLS202") || code == "OLS30210"));
+    let codes = get_codes(&mut session, "module_4");
+    assert!(codes.contains(&S!("OLS30210")), "{:?}", codes);
+    assert!(codes.contains(&S!("OLS20205")), "{:?}", codes);
+    assert!(!get_codes(&mut session, "module_5").contains(&S!("OLS20205")));
+
+    //module_2 is imported without being in the depends of module_4
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_4"), S!("models"), S!("graph_imports")];
+    let file = session.sync_odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("graph_imports should be loaded");
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    let not_in_depends: Vec<&String> = validator.diagnostics.iter()
+        .filter(|diagnostic| diagnostic.code == Some(NumberOrString::String(S!("OLS30103"))))
+        .map(|diagnostic| &diagnostic.message).collect();
+    assert_eq!(not_in_depends, vec![&S!("module_2 is not in the dependencies of the module")]);
+}
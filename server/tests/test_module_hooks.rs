@@ -0,0 +1,48 @@
+use odoo_ls_server::core::module_hooks::ModuleHooks;
+use ruff_python_ast::{Stmt, StmtFunctionDef};
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+fn parse_function(code: &str) -> StmtFunctionDef {
+    match parse_valid(code).remove(0) {
+        Stmt::FunctionDef(func) => func,
+        _ => panic!("not a function")
+    }
+}
+
+#[test]
+fn test_hook_parameters() {
+    assert_eq!(ModuleHooks::get_hook_parameters("pre_init_hook", 16), vec!["cr"]);
+    assert_eq!(ModuleHooks::get_hook_parameters("post_init_hook", 16), vec!["cr", "registry"]);
+    assert_eq!(ModuleHooks::get_hook_parameters("uninstall_hook", 16), vec!["cr", "registry"]);
+    for hook in ["pre_init_hook", "post_init_hook", "uninstall_hook"] {
+        assert_eq!(ModuleHooks::get_hook_parameters(hook, 17), vec!["env"]);
+        assert_eq!(ModuleHooks::get_hook_parameters(hook, 18), vec!["env"]);
+    }
+}
+
+#[test]
+fn test_accepts() {
+    assert!(ModuleHooks::accepts(&parse_function("def hook(env): pass").parameters, 1));
+    assert!(!ModuleHooks::accepts(&parse_function("def hook(cr, registry): pass").parameters, 1));
+    assert!(ModuleHooks::accepts(&parse_function("def hook(cr, registry=None): pass").parameters, 1));
+    assert!(ModuleHooks::accepts(&parse_function("def hook(*args): pass").parameters, 2));
+    assert!(!ModuleHooks::accepts(&parse_function("def hook(): pass").parameters, 1));
+    assert!(!ModuleHooks::accepts(&parse_function("def hook(env, *, force): pass").parameters, 1));
+    assert!(ModuleHooks::accepts(&parse_function("def hook(env, *, force=False, **kwargs): pass").parameters, 1));
+    //the methods of the models are called with self only
+    assert!(ModuleHooks::accepts(&parse_function("def _register_hook(self): pass").parameters, 1));
+    assert!(!ModuleHooks::accepts(&parse_function("def _auto_init(self, cr): pass").parameters, 1));
+}
+
+#[test]
+fn test_calls_super() {
+    let func = parse_function("def _auto_init(self):\n    res = super()._auto_init()\n    self._create_index()\n    return res\n");
+    assert!(ModuleHooks::calls_super(&func.body, "_auto_init"));
+    let func = parse_function("def _auto_init(self):\n    if self._table:\n        super(SaleOrder, self)._auto_init()\n");
+    assert!(ModuleHooks::calls_super(&func.body, "_auto_init"));
+    let func = parse_function("def _auto_init(self):\n    super()._register_hook()\n    self._auto_init()\n");
+    assert!(!ModuleHooks::calls_super(&func.body, "_auto_init"));
+}
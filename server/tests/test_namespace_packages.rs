@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::constants::{PackageType, SymType};
+use odoo_ls_server::core::import_resolver::resolve_import_stmt;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer;
+use odoo_ls_server::S;
+use ruff_python_ast::{Alias, Identifier, Stmt};
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+/* These tests check that the directories without __init__.py are resolved as namespace packages (PEP 420), that can
+be split across several root paths */
+
+fn write(root: &PathBuf, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/* Return, for each symbol imported by the code, its type and its paths relative to the root paths. The code is run
+from a file of the first root path. */
+fn get_imported_symbols(name: &str, code: &str) -> Vec<(SymType, Vec<String>)> {
+    let root_a = std::env::temp_dir().join(format!("odoo_ls_tests_namespaces_{}_a_{}", name, std::process::id()));
+    let root_b = std::env::temp_dir().join(format!("odoo_ls_tests_namespaces_{}_b_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root_a);
+    let _ = fs::remove_dir_all(&root_b);
+    write(&root_a, "nspkg/alpha.py", "VALUE = 1\n");
+    write(&root_b, "nspkg/beta.py", "VALUE = 2\n");
+    write(&root_a, "mixed/portion.py", "VALUE = 1\n");
+    write(&root_b, "mixed/__init__.py", "VALUE = 2\n");
+    write(&root_a, "main.py", code);
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    root.borrow_mut().add_path(root_a.sanitize());
+    root.borrow_mut().add_path(root_b.sanitize());
+    let file = Symbol::create_from_path(&mut session, &root_a.join("main.py"), root, false).unwrap();
+    let mut res = vec![];
+    for stmt in parse_valid(code).iter() {
+        let (module, names, level): (Option<&Identifier>, &[Alias], Option<u32>) = match stmt {
+            Stmt::Import(import) => (None, &import.names, None),
+            Stmt::ImportFrom(import_from) => (import_from.module.as_ref(), &import_from.names, Some(import_from.level)),
+            _ => continue
+        };
+        for result in resolve_import_stmt(&mut session, &file, module, names, level, &mut None).iter() {
+            assert!(result.found, "{} should be found", result.name);
+            let symbol = result.symbol.borrow();
+            let paths = symbol.paths().iter().map(|path| {
+                if let Some(relative) = path.strip_prefix(&root_a.sanitize()) {
+                    format!("a{}", relative)
+                } else {
+                    format!("b{}", path.strip_prefix(&root_b.sanitize()).unwrap())
+                }
+            }).collect();
+            res.push((symbol.typ(), paths));
+        }
+    }
+    let _ = fs::remove_dir_all(&root_a);
+    let _ = fs::remove_dir_all(&root_b);
+    res
+}
+
+#[test]
+fn test_namespace_package() {
+    assert_eq!(get_imported_symbols("namespace", "import nspkg\n"), vec![
+        (SymType::NAMESPACE, vec![S!("a/nspkg"), S!("b/nspkg")]),
+    ]);
+}
+
+#[test]
+fn test_namespace_portions() {
+    //each module is found in the portion of its own root path
+    assert_eq!(get_imported_symbols("portions", "from nspkg import alpha, beta\n"), vec![
+        (SymType::FILE, vec![S!("a/nspkg/alpha.py")]),
+        (SymType::FILE, vec![S!("b/nspkg/beta.py")]),
+    ]);
+}
+
+#[test]
+fn test_regular_package_precedence() {
+    //a regular package takes precedence over the namespace portions, even in a later root path
+    assert_eq!(get_imported_symbols("precedence", "import mixed\n"), vec![
+        (SymType::PACKAGE(PackageType::PYTHON_PACKAGE), vec![S!("b/mixed")]),
+    ]);
+}
@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ruff_python_ast::{Expr, Number, Stmt, StmtIf};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps, SymType};
+use odoo_ls_server::core::evaluation::EvaluationValue;
+use odoo_ls_server::core::narrowing::{Narrowing, NarrowingKind, Narrowings};
+use odoo_ls_server::core::python_utils;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_if(source: &str) -> StmtIf {
+    match parse(source).into_iter().next() {
+        Some(Stmt::If(if_stmt)) => if_stmt,
+        _ => panic!("Not an if")
+    }
+}
+
+fn values(narrowing: &Narrowing) -> Vec<String> {
+    narrowing.values.iter().map(|v| match v {
+        Expr::StringLiteral(s) => s.value.to_string(),
+        Expr::NumberLiteral(_) => "<number>".to_string(),
+        _ => "<other>".to_string(),
+    }).collect()
+}
+
+#[test]
+fn test_narrowing_branches() {
+    let if_stmt = parse_if("if state in ('sale', 'done'):\n    pass\nelif state != 'draft':\n    pass\nelse:\n    pass\n");
+    let branches = Narrowings::get_branches(&if_stmt);
+    assert_eq!(branches.len(), 3);
+    assert_eq!(branches[0].narrowings[0].kind, NarrowingKind::INCLUDE);
+    assert_eq!(values(&branches[0].narrowings[0]), vec!["sale", "done"]);
+    assert_eq!(branches[1].narrowings[0].kind, NarrowingKind::EXCLUDE);
+    assert_eq!(values(&branches[1].narrowings[0]), vec!["sale", "done", "draft"]);
+    assert_eq!(branches[2].narrowings[0].kind, NarrowingKind::INCLUDE);
+    assert_eq!(values(&branches[2].narrowings[0]), vec!["draft"]);
+}
+
+#[test]
+fn test_narrowing_chains() {
+    //ordering comparisons don't narrow
+    let if_stmt = parse_if("if 0 < qty <= 10:\n    pass\nelse:\n    pass\n");
+    let branches = Narrowings::get_branches(&if_stmt);
+    assert!(branches.iter().all(|b| b.narrowings.is_empty()));
+
+    //a chain is processed from left to right, and its negation doesn't narrow
+    let if_stmt = parse_if("if 'a' == x != 'b' and not y == 1:\n    pass\nelse:\n    pass\n");
+    let branches = Narrowings::get_branches(&if_stmt);
+    let names: Vec<String> = branches[0].narrowings.iter().map(|n| n.name.id.to_string()).collect();
+    assert_eq!(names, vec!["x", "y"]);
+    assert_eq!(values(&branches[0].narrowings[0]), vec!["a"]);
+    assert_eq!(branches[0].narrowings[1].kind, NarrowingKind::EXCLUDE);
+    assert!(branches[1].narrowings.is_empty());
+}
+
+#[test]
+fn test_narrowing_guards() {
+    let if_stmt = parse_if("if is_posted(move) and not is_draft(self.move_id):\n    pass\nelif check(move.amount + 1):\n    pass\nelse:\n    pass\n");
+    let branches = Narrowings::get_branches(&if_stmt);
+    let guards: Vec<(String, bool)> = branches[0].guards.iter()
+        .map(|g| (Narrowings::get_attribute_chain(g.target).unwrap(), g.positive))
+        .collect();
+    assert_eq!(guards, vec![("move".to_string(), true), ("self.move_id".to_string(), false)]);
+    //a false 'and' doesn't tell which call is false, and an expression can't be narrowed
+    assert!(branches[1].guards.is_empty());
+    assert!(branches[2].guards.is_empty());
+
+    let if_stmt = parse_if("if not is_posted(move):\n    pass\nelse:\n    pass\n");
+    let branches = Narrowings::get_branches(&if_stmt);
+    assert!(!branches[0].guards[0].positive);
+    assert!(branches[1].guards[0].positive);
+}
+
+#[test]
+fn test_always_exits() {
+    assert!(python_utils::always_exits(&parse("x = 1\nraise UserError('error')\n")));
+    assert!(python_utils::always_exits(&parse("if x:\n    return 1\nelse:\n    raise ValueError()\n")));
+    assert!(!python_utils::always_exits(&parse("if x:\n    return 1\nelif y:\n    raise ValueError()\n")));
+    assert!(python_utils::always_exits(&parse("try:\n    return compute()\nexcept ValueError:\n    raise UserError('error')\n")));
+    assert!(!python_utils::always_exits(&parse("try:\n    return compute()\nexcept ValueError:\n    pass\n")));
+    assert!(!python_utils::always_exits(&parse("for x in y:\n    raise ValueError()\n")));
+}
+
+#[test]
+fn test_narrowing_guard_clauses() {
+    let if_stmt = parse_if("if not partner or state is None:\n    raise UserError('error')\n");
+    let guard_clause = Narrowings::get_guard_clause(&if_stmt).expect("the if is a guard clause");
+    assert_eq!(guard_clause.start, if_stmt.range.end());
+    let narrowings: Vec<(String, NarrowingKind, bool)> = guard_clause.narrowings.iter()
+        .map(|n| (n.name.id.to_string(), n.kind, n.truthy))
+        .collect();
+    assert_eq!(narrowings, vec![(S!("partner"), NarrowingKind::EXCLUDE, true), (S!("state"), NarrowingKind::EXCLUDE, false)]);
+    assert!(guard_clause.narrowings[1].values[0].is_none_literal_expr());
+
+    //an elif that exits too gives the negation of both tests, but a branch that goes on doesn't narrow anything after the if
+    let if_stmt = parse_if("if qty == 0:\n    return\nelif qty == 1:\n    raise UserError('error')\n");
+    let guard_clause = Narrowings::get_guard_clause(&if_stmt).unwrap();
+    assert_eq!(guard_clause.narrowings[0].values.len(), 2);
+    assert!(Narrowings::get_guard_clause(&parse_if("if not partner:\n    partner = default\n")).is_none());
+    assert!(Narrowings::get_guard_clause(&parse_if("if not partner:\n    raise UserError('error')\nelse:\n    return\n")).is_none());
+}
+
+#[test]
+fn test_narrowing_asserts() {
+    let Some(Stmt::Assert(assert_stmt)) = parse("assert record and is_posted(move), 'error'\n").into_iter().next() else {
+        panic!("Not an assert");
+    };
+    let branch = Narrowings::get_assert_branch(&assert_stmt);
+    assert_eq!(branch.start, assert_stmt.range.end());
+    assert_eq!(branch.narrowings[0].name.id.as_str(), "record");
+    assert!(branch.narrowings[0].truthy);
+    assert_eq!(branch.guards.len(), 1);
+    assert!(branch.guards[0].positive);
+}
+
+/* Return the literals that a local variable of the function can hold, following the variables it refers to */
+fn get_values(session: &mut SessionInfo, variable: &Rc<RefCell<Symbol>>, depth: u32) -> Vec<String> {
+    let mut res = vec![];
+    let evaluations = variable.borrow().evaluations().cloned().unwrap_or_default();
+    for evaluation in evaluations.iter() {
+        match &evaluation.value {
+            Some(EvaluationValue::CONSTANT(Expr::StringLiteral(s))) => res.push(s.value.to_string()),
+            Some(EvaluationValue::CONSTANT(Expr::NumberLiteral(n))) => match &n.value {
+                Number::Int(i) => res.push(i.to_string()),
+                _ => {}
+            },
+            Some(EvaluationValue::CONSTANT(Expr::NoneLiteral(_))) => res.push(S!("None")),
+            _ => {
+                let Some(symbol) = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None).weak.upgrade() else {
+                    continue;
+                };
+                if depth < 5 && symbol.borrow().typ() == SymType::VARIABLE && !Rc::ptr_eq(&symbol, variable) {
+                    res.extend(get_values(session, &symbol, depth + 1));
+                }
+            }
+        }
+    }
+    res.sort();
+    res.dedup();
+    res
+}
+
+#[test]
+fn test_narrowing_after_guards() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("guard_clauses")];
+    let function = odoo.get_symbol(&(file_tree, vec![S!("GuardClauses"), S!("check_guards")]), u32::MAX).pop();
+    let function = function.expect("check_guards should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    let get_variable = |name: &str| function.borrow().all_symbols().find(|symbol| symbol.borrow().name() == name).expect("variable not found");
+    //`if state is None: raise UserError(...)` removes None from the values of state
+    assert_eq!(get_values(&mut session, &get_variable("checked_state"), 0), vec![S!("draft")]);
+    //`assert qty` removes the falsy values
+    assert_eq!(get_values(&mut session, &get_variable("checked_qty"), 0), vec![S!("5")]);
+    //the declarations of a branch that raises are not reachable after the if
+    assert_eq!(get_values(&mut session, &get_variable("checked_label"), 0), vec![S!("default")]);
+    //the partner is still a record after `if not self.partner_id: raise UserError(...)`
+    let email = get_variable("email");
+    let evaluations = email.borrow().evaluations().cloned().unwrap_or_default();
+    assert!(evaluations.iter().any(|evaluation| evaluation.symbol.get_symbol(&mut session, &mut None, &mut vec![], None).weak.upgrade().is_some()));
+}
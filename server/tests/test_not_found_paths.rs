@@ -0,0 +1,32 @@
+use odoo_ls_server::constants::{BuildSteps, MAX_NOT_FOUND_PATHS};
+use odoo_ls_server::core::symbols::file_symbol::FileSymbol;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+
+fn path(tree: &str) -> Vec<String> {
+    tree.split('.').map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_not_found_paths_are_deduplicated() {
+    let mut file = Symbol::File(FileSymbol::new(String::from("file"), String::from("/tmp/file.py"), false));
+    file.add_not_found_path(BuildSteps::ARCH_EVAL, path("foo.bar.baz"));
+    file.add_not_found_path(BuildSteps::ARCH_EVAL, path("foo.bar.baz"));
+    file.add_not_found_path(BuildSteps::ARCH_EVAL, path("foo.bar.qux"));
+    assert_eq!(file.not_found_paths().len(), 2);
+    //a prefix replaces the paths it covers
+    file.add_not_found_path(BuildSteps::ARCH_EVAL, path("foo"));
+    assert_eq!(file.not_found_paths(), &vec![(BuildSteps::ARCH_EVAL, path("foo"))]);
+    //and covers the next ones, but only for the same step
+    file.add_not_found_path(BuildSteps::ARCH_EVAL, path("foo.other"));
+    file.add_not_found_path(BuildSteps::ARCH, path("foo.other"));
+    assert_eq!(file.not_found_paths().len(), 2);
+}
+
+#[test]
+fn test_not_found_paths_are_capped() {
+    let mut file = Symbol::File(FileSymbol::new(String::from("file"), String::from("/tmp/file.py"), false));
+    for i in 0..MAX_NOT_FOUND_PATHS * 3 {
+        file.add_not_found_path(BuildSteps::ARCH_EVAL, vec![format!("missing_{}", i)]);
+    }
+    assert_eq!(file.not_found_paths().len(), MAX_NOT_FOUND_PATHS);
+}
@@ -0,0 +1,75 @@
+use odoo_ls_server::features::organize_imports::{ImportGroup, OrganizeImportsFeature};
+
+mod setup;
+
+use setup::ast::parse;
+
+fn classify(module: &str) -> ImportGroup {
+    match module.split('.').next().unwrap() {
+        "os" | "re" | "logging" => ImportGroup::STDLIB,
+        "odoo" if module.starts_with("odoo.addons") => ImportGroup::ODOO_ADDONS,
+        "odoo" => ImportGroup::ODOO,
+        _ => ImportGroup::THIRD_PARTY,
+    }
+}
+
+/* Apply the organization to the code, and return the new code */
+fn organize(code: &str, keep_unused: bool) -> Option<String> {
+    let (range, text) = OrganizeImportsFeature::organize(code, &parse(code), keep_unused, &mut classify)?;
+    Some(format!("{}{}{}", &code[..range.start().to_usize()], text, &code[range.end().to_usize()..]))
+}
+
+#[test]
+fn test_organize_imports() {
+    let code = "\
+\"\"\"Docstring\"\"\"
+from odoo import models
+import re
+from odoo.addons.sale.models import sale_order
+import lxml
+from odoo import fields, api
+import os  # noqa
+from . import utils
+from odoo import models
+
+try:
+    import xlrd
+except ImportError:
+    xlrd = None
+
+class A(models.Model):
+    name = fields.Char()
+    x = lxml.etree
+";
+    let expected = "\
+\"\"\"Docstring\"\"\"
+import os  # noqa
+
+import lxml
+
+from odoo import fields, models
+
+from . import utils
+
+try:
+    import xlrd
+except ImportError:
+    xlrd = None
+
+class A(models.Model):
+    name = fields.Char()
+    x = lxml.etree
+";
+    let organized = organize(code, false).unwrap();
+    assert_eq!(organized, expected);
+    //organizing twice doesn't change anything
+    assert!(organize(&organized, false).is_none());
+}
+
+#[test]
+fn test_organize_imports_keep_unused() {
+    let code = "from .models import b, a\nfrom . import wizard\nfrom . import models\n";
+    let organized = organize(code, true).unwrap();
+    assert_eq!(organized, "from . import models, wizard\nfrom .models import a, b\n");
+    assert!(organize(&organized, true).is_none());
+}
@@ -0,0 +1,53 @@
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps, SymType};
+use odoo_ls_server::core::overloads::Overloads;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_types;
+
+/* The definitions of a function decorated by @overload are merged in the first one, and a call is evaluated to the
+values returned by the overloads that accept its arguments */
+#[test]
+fn test_overloads() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("overloads")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("overloads should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let read = file.borrow().get_content_symbol("read", u32::MAX);
+    assert_eq!(read.len(), 1);
+    let read = read[0].clone();
+    assert_eq!(read.borrow().typ(), SymType::FUNCTION);
+    assert!(read.borrow().as_func().is_overloaded());
+    //the implementation is owned by the first overload, but is not a signature
+    assert_eq!(read.borrow().as_func().overloads.len(), 2);
+    assert_eq!(Overloads::get_signatures(&read).len(), 2);
+    //the return evaluation of an overload is its return annotation
+    assert_eq!(get_types(&mut session, &read), vec![(S!("str"), true)]);
+    for (name, expected) in [
+        ("text", vec![(S!("str"), true)]),
+        ("data", vec![(S!("bytes"), true)]),
+        ("unknown", vec![(S!("str"), true), (S!("bytes"), true)]),
+        ("whole", vec![(S!("int"), true)]),
+        ("scaled", vec![(S!("float"), true)]),
+    ] {
+        let variable = file.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+        assert_eq!(get_types(&mut session, &variable), expected, "wrong evaluation of {}", name);
+    }
+    //typing.overload is also recognized by its qualified name
+    let convert = file.borrow().get_content_symbol("convert", u32::MAX);
+    assert_eq!(convert.len(), 1);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(validator.diagnostics.is_empty(), "{:?}", validator.diagnostics.iter().map(|d| d.code.clone()).collect::<Vec<Option<NumberOrString>>>());
+    //the methods are overloaded too, and their first parameter is bound to the instance
+    let reader = file.borrow().get_content_symbol("Reader", u32::MAX).pop().unwrap();
+    assert_eq!(reader.borrow().get_content_symbol("get", u32::MAX).len(), 1);
+    let name = reader.borrow().get_content_symbol("name", u32::MAX).pop().unwrap();
+    assert_eq!(get_types(&mut session, &name), vec![(S!("str"), true)]);
+}
@@ -0,0 +1,110 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use odoo_ls_server::core::evaluation::EvaluationSymbolPtr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer;
+
+const PACKAGES: usize = 10;
+const MODULES: usize = 30;
+
+fn write(root: &PathBuf, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/* A python path of a few hundred files importing each other, with a file that can't be parsed */
+fn write_fixture(root: &PathBuf) {
+    for package in 0..PACKAGES {
+        let modules: Vec<String> = (0..MODULES).map(|module| format!("mod_{}", module)).collect();
+        write(root, &format!("pkg_{}/__init__.py", package), &format!("from . import {}\n", modules.join(", ")));
+        for module in 0..MODULES {
+            let other = (package + 1) % PACKAGES;
+            write(root, &format!("pkg_{}/mod_{}.py", package, module), &format!("\
+import os.path
+from pkg_{other}.mod_{module} import Base
+from .mod_{next} import VALUE as NEXT_VALUE
+
+VALUE = {module}
+NAMES = ['a', 'b', 'c']
+
+class Base:
+    \"\"\"The base of package {package}\"\"\"
+    label = 'base'
+
+    def run(self, value: int = VALUE) -> str:
+        return str(value) + self.label
+
+class Child(Base):
+    item = Base()
+
+def build(count=3, *args, **kwargs):
+    return [Child() for _ in range(count)]
+", next = (module + 1) % MODULES));
+        }
+    }
+    write(root, "pkg_0/broken.py", "def broken(:\n    pass\n");
+    let imports: Vec<String> = (0..PACKAGES).map(|package| format!("import pkg_{}", package)).collect();
+    write(root, "main.py", &(imports.join("\n") + "\nfrom pkg_0 import broken\n"));
+}
+
+/* The symbols under the symbol, with their range and the names of the symbols they evaluate to, sorted */
+fn describe(symbol: &Rc<RefCell<Symbol>>, prefix: &str, res: &mut Vec<String>) {
+    for child in symbol.borrow().all_symbols() {
+        let child_bw = child.borrow();
+        let evaluations: Vec<String> = child_bw.evaluations().map(|evaluations| evaluations.iter().map(|evaluation| match evaluation.symbol.ptr() {
+            EvaluationSymbolPtr::WEAK(w) => w.weak.upgrade().map(|target| target.borrow().name().clone()).unwrap_or_default(),
+            ptr => format!("{:?}", ptr),
+        }).collect()).unwrap_or_default();
+        let name = format!("{}/{}", prefix, child_bw.name());
+        res.push(format!("{} {:?} {:?} {:?}", name, child_bw.typ(), child_bw.range(), evaluations));
+        drop(child_bw);
+        describe(&child, &name, res);
+    }
+}
+
+/* Build the fixture with the given number of workers, and return the description of its symbols and the number of files
+parsed by the workers */
+fn build_fixture(root: &PathBuf, workers: usize) -> (Vec<String>, u64) {
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().parse_pool.set_workers(workers);
+    let root_symbol = session.sync_odoo.symbols.clone().unwrap();
+    root_symbol.borrow_mut().add_path(session.sync_odoo.stdlib_dir.clone());
+    root_symbol.borrow_mut().add_path(root.sanitize());
+    root_symbol.borrow_mut().as_root_mut().sys_path.push(root.sanitize());
+    SyncOdoo::load_builtins(&mut session);
+    let main = Symbol::create_from_path(&mut session, &root.join("main.py"), root_symbol.clone(), false).unwrap();
+    session.sync_odoo.add_to_rebuild_arch(main);
+    SyncOdoo::process_rebuilds(&mut session);
+    let mut res = vec![];
+    for package in 0..PACKAGES {
+        let symbol = session.sync_odoo.get_symbol(&(vec![format!("pkg_{}", package)], vec![]), u32::MAX).pop().expect("the package should be built");
+        describe(&symbol, &format!("pkg_{}", package), &mut res);
+    }
+    res.sort();
+    let hits = session.sync_odoo.get_file_mgr().borrow().parse_pool.hits;
+    (res, hits)
+}
+
+/* The files parsed by the workers give the same symbols as the files parsed by the main thread. The time of the builds
+is compared by the parse_pool bench */
+#[test]
+fn test_parallel_parse() {
+    let root = std::env::temp_dir().join(format!("odoo_ls_tests_parse_pool_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write_fixture(&root);
+    let (sequential, sequential_hits) = build_fixture(&root, 0);
+    let (parallel, parallel_hits) = build_fixture(&root, 4);
+    assert_eq!(sequential_hits, 0);
+    assert!(parallel_hits > 0, "The imported files should be parsed by the workers");
+    assert!(sequential.len() > PACKAGES * MODULES * 5, "{} symbols", sequential.len());
+    assert_eq!(sequential, parallel);
+    let _ = fs::remove_dir_all(&root);
+}
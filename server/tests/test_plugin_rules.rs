@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::plugin_rules::PluginRules;
+
+fn write_rules(name: &str, content: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("odoo_ls_tests_plugin_rules_{}_{}.json", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_plugin_rules_example() {
+    let path = env!("CARGO_MANIFEST_DIR").to_string() + "/plugin_rules_example.json";
+    let (rules, errors) = PluginRules::read_rules(&path);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(rules.len(), 3);
+    let mut plugin_rules = PluginRules::new();
+    plugin_rules.set_rules(rules);
+    assert_eq!(plugin_rules.get_registry("my_framework.registry.register"), Some(&"my_framework.registry.handlers".to_string()));
+    assert!(plugin_rules.is_factory("my_framework.tools.make"));
+    assert!(!plugin_rules.is_factory("my_framework.registry.register"));
+}
+
+#[test]
+fn test_plugin_rules_errors() {
+    //invalid rules are reported, and the valid ones are kept
+    let path = write_rules("invalid", r#"[
+        {"field": "my_framework.fields.Money"},
+        {"field": "my_framework.fields.Money", "factory": "my_framework.tools.make"},
+        {"decorator": "my_framework.registry.register"},
+        {"factory": "make"},
+        {"field": "my_framework.fields.Ref", "comodel_keyword": "comodel name"}
+    ]"#);
+    let (rules, errors) = PluginRules::read_rules(&path);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(errors.len(), 4);
+    assert!(errors[0].contains("rule #2"));
+    assert!(errors[1].contains("'decorator' and 'registry'"));
+    assert!(errors[2].contains("'make' is not a path"));
+
+    let path = write_rules("unknown_key", r#"[{"field": "my_framework.fields.Money", "model": "res.partner"}]"#);
+    let (rules, errors) = PluginRules::read_rules(&path);
+    assert!(rules.is_empty());
+    assert!(errors[0].starts_with("Unable to parse plugin rules file"));
+
+    let (rules, errors) = PluginRules::read_rules("");
+    assert!(rules.is_empty() && errors.is_empty());
+}
@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Return the names of the types returned by a function, and whether they are instances */
+fn get_return_types(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) -> Vec<(String, bool)> {
+    let evaluations = function.borrow().evaluations().cloned().unwrap_or_default();
+    let mut res = vec![];
+    for evaluation in evaluations.iter() {
+        let value = evaluation.symbol.get_symbol(session, &mut None, &mut vec![], None);
+        for value in Symbol::follow_ref(&value, session, &mut None, false, false, None, &mut vec![]).iter() {
+            if let Some(symbol) = value.weak.upgrade() {
+                res.push((symbol.borrow().name().clone(), value.instance.unwrap_or(false)));
+            }
+        }
+    }
+    res
+}
+
+/* The setter and the deleter of a property don't shadow its getter, that gives the value read from the property */
+#[test]
+fn test_property_accessors() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("properties")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("properties should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let class = file.borrow().get_content_symbol("Temperature", u32::MAX).pop().unwrap();
+    let declarations = class.borrow().get_content_symbol("celsius", u32::MAX);
+    assert_eq!(declarations.len(), 1);
+    let getter = declarations[0].clone();
+    {
+        let getter = getter.borrow();
+        let getter = getter.as_func();
+        assert!(getter.is_property);
+        assert_eq!(getter.setter.as_ref().map(|setter| setter.borrow().as_func().args.len()), Some(2));
+        assert_eq!(getter.deleter.as_ref().map(|deleter| deleter.borrow().as_func().args.len()), Some(1));
+    }
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(validator.diagnostics.is_empty(), "{:?}", validator.diagnostics.iter().map(|d| d.code.clone()).collect::<Vec<Option<NumberOrString>>>());
+    //the accessors are validated with the class
+    let setter = getter.borrow().as_func().setter.clone().unwrap();
+    assert_eq!(setter.borrow().build_status(BuildSteps::VALIDATION), BuildStatus::DONE);
+    let read = class.borrow().get_content_symbol("read", u32::MAX).pop().unwrap();
+    assert_eq!(get_return_types(&mut session, &read), vec![(S!("float"), true)]);
+}
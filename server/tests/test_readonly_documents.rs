@@ -0,0 +1,50 @@
+use lsp_types::{Position, Range};
+use odoo_ls_server::features::readonly_documents::{CompiledMember, ReadonlyDocumentFeature, ReadonlyTarget};
+
+fn member(name: &str, members: Vec<CompiledMember>) -> CompiledMember {
+    CompiledMember { name: name.to_string(), members }
+}
+
+#[test]
+fn test_compiled_uri() {
+    let target = ReadonlyTarget::COMPILED(vec![String::from("lxml"), String::from("etree")]);
+    let uri = ReadonlyDocumentFeature::get_uri(&target).unwrap();
+    assert_eq!(uri.as_str(), "odools-readonly:/compiled/lxml/etree.pyi");
+    assert!(ReadonlyDocumentFeature::is_readonly_uri(uri.as_str()));
+    assert_eq!(ReadonlyDocumentFeature::parse_uri(uri.as_str()), Some(target));
+    assert_eq!(ReadonlyDocumentFeature::parse_uri("odools-readonly:/compiled/.pyi"), None);
+    assert_eq!(ReadonlyDocumentFeature::parse_uri("file:///compiled/lxml.pyi"), None);
+    assert!(!ReadonlyDocumentFeature::is_readonly_uri("file:///home/odoo/addons/sale/models/sale.py"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_source_uri() {
+    let target = ReadonlyTarget::SOURCE(String::from("/home/odoo/addons/my module/models/sale.py"));
+    let uri = ReadonlyDocumentFeature::get_uri(&target).unwrap();
+    assert!(uri.as_str().starts_with("odools-readonly:/source/home/odoo/addons/my"));
+    assert_eq!(ReadonlyDocumentFeature::parse_uri(uri.as_str()), Some(target));
+}
+
+#[test]
+fn test_render_compiled() {
+    let members = vec![
+        member("fromstring", vec![]),
+        member("Element", vec![member("tag", vec![]), member("attrib", vec![])]),
+    ];
+    let (content, ranges) = ReadonlyDocumentFeature::render_compiled("lxml.etree", "/usr/lib/etree.so", &members);
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].contains("lxml.etree"));
+    for (path, range) in ranges.iter() {
+        let line = lines[range.start.line as usize];
+        let name = &line[range.start.character as usize..range.end.character as usize];
+        assert_eq!(name, path.last().unwrap());
+    }
+    //members are sorted, and the members of a compiled class are indented under it
+    let paths: Vec<String> = ranges.iter().map(|(path, _)| path.join(".")).collect();
+    assert_eq!(paths, vec!["Element", "Element.attrib", "Element.tag", "fromstring"]);
+    let attrib = &ranges[1].1;
+    assert_eq!(*attrib, Range::new(Position::new(attrib.start.line, 4), Position::new(attrib.start.line, 10)));
+    assert!(lines.contains(&"class Element:"));
+    assert!(lines.contains(&"fromstring: Any"));
+}
@@ -0,0 +1,52 @@
+use ruff_text_size::{TextRange, TextSize};
+use odoo_ls_server::features::ast_utils::AstUtils;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn range_of(code: &str, marker: &str) -> TextRange {
+    let start = code.find(marker).expect("marker not found") as u32;
+    TextRange::new(TextSize::new(start), TextSize::new(start + marker.len() as u32))
+}
+
+fn code_of(code: &str, expr: &ruff_python_ast::Expr) -> String {
+    let range = ruff_text_size::Ranged::range(expr);
+    code[range.start().to_usize()..range.end().to_usize()].to_string()
+}
+
+#[test]
+fn test_lambda_records() {
+    let code = "\
+def f(self):
+    services = self.order_line.filtered(lambda l: l.product_id.type == 'service')
+    names = self.order_line.mapped(lambda l: l.move_ids.filtered(lambda m: m.state).mapped(lambda l: l.name))
+    other = sorted(values, key=lambda l: l.x)
+";
+    let ast = parse(code);
+    let receiver = AstUtils::find_lambda_records(&ast, "l", range_of(code, "l.product_id")).unwrap();
+    assert_eq!(code_of(code, &receiver), "self.order_line");
+    //the innermost lambda that defines the name is used
+    let receiver = AstUtils::find_lambda_records(&ast, "m", range_of(code, "m.state")).unwrap();
+    assert_eq!(code_of(code, &receiver), "l.move_ids");
+    let receiver = AstUtils::find_lambda_records(&ast, "l", range_of(code, "l.name")).unwrap();
+    assert_eq!(code_of(code, &receiver), "l.move_ids.filtered(lambda m: m.state)");
+    //builtins are not recordset methods
+    assert!(AstUtils::find_lambda_records(&ast, "l", range_of(code, "l.x")).is_none());
+}
+
+#[test]
+fn test_function_records() {
+    let code = "\
+def f(self):
+    services = self.order_line.filtered(self._is_service)
+    lines = self.line_ids.sorted(key=self._get_sequence)
+    other = self.line_ids.filtered(other._is_service)
+";
+    let ast = parse(code);
+    let receivers = AstUtils::find_function_records(&ast, "_is_service");
+    assert_eq!(receivers.len(), 1);
+    assert_eq!(code_of(code, &receivers[0]), "self.order_line");
+    let receivers = AstUtils::find_function_records(&ast, "_get_sequence");
+    assert_eq!(code_of(code, &receivers[0]), "self.line_ids");
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use lsp_types::{Location, Position};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::features::references::ReferencesFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn at(locations: &[Location], file: &str, line: u32, character: u32) -> bool {
+    locations.iter().any(|location| location.uri.as_str().ends_with(file) && location.range.start == Position::new(line, character))
+}
+
+/* format_amount is declared in amounts.py, re-exported by amount_exports.py, and called by amount_usage.py that imports
+it from the re-export */
+#[test]
+fn test_references_through_re_export() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let module_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3");
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(module_path.sanitize());
+    SyncOdoo::apply_ignore_rules(&mut session);
+    let models_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models")];
+    let usage = session.sync_odoo.get_symbol(&([models_tree.clone(), vec![S!("amount_usage")]].concat(), vec![]), u32::MAX).pop().expect("amount_usage should be loaded");
+    usage.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(usage.clone()).validate(&mut session);
+
+    let amounts = session.sync_odoo.get_symbol(&([models_tree, vec![S!("amounts")]].concat(), vec![]), u32::MAX).pop().expect("amounts should be loaded");
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &amounts).unwrap();
+    //on the name of the function
+    let references = ReferencesFeature::get_references(&mut session, &amounts, &file_info, 0, 6, true).unwrap();
+    assert!(at(&references, "models/amounts.py", 0, 4), "the declaration should be given: {:?}", references);
+    assert!(at(&references, "models/amount_exports.py", 0, 21), "the re-export should be given: {:?}", references);
+    assert!(at(&references, "models/amount_usage.py", 0, 28), "the import of the re-export should be given: {:?}", references);
+    assert!(at(&references, "models/amount_usage.py", 4, 11), "the call should be given: {:?}", references);
+    assert_eq!(references.len(), 4);
+
+    //the same references are found from the call, without the declaration
+    let usage_info = FileMgr::get_file_info_of_symbol(&mut session, &usage).unwrap();
+    let references = ReferencesFeature::get_references(&mut session, &usage, &usage_info, 4, 14, false).unwrap();
+    assert!(!at(&references, "models/amounts.py", 0, 4));
+    assert!(at(&references, "models/amount_usage.py", 4, 11));
+    assert_eq!(references.len(), 3);
+}
+
+/* f1 is a field of pygls.tests.m_name, declared by module_1. field_name_refs.py names it in the strings given to the
+ORM methods (see FieldNameArgs), that are references of the field for the rename */
+#[test]
+fn test_references_in_field_name_args() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let module_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3");
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(module_path.sanitize());
+    SyncOdoo::apply_ignore_rules(&mut session);
+    let refs = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("field_name_refs")], vec![]), u32::MAX)
+        .pop().expect("field_name_refs should be loaded");
+    refs.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(refs.clone()).validate(&mut session);
+
+    let field = session.sync_odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_1"), S!("models"), S!("models")], vec![S!("model_name"), S!("f1")]), u32::MAX)
+        .pop().expect("f1 should be loaded");
+    let mut positions = vec![];
+    for (file_info, ranges) in ReferencesFeature::get_reference_ranges(&mut session, &field).iter() {
+        let file_info = file_info.borrow();
+        if file_info.uri.ends_with("field_name_refs.py") {
+            positions.extend(ranges.iter().map(|range| file_info.offset_to_position(range.start().to_usize())));
+        }
+    }
+    positions.sort_by_key(|position| (position.line, position.character));
+    assert_eq!(positions, vec![Position::new(7, 20), Position::new(8, 21), Position::new(9, 38), Position::new(9, 64)]);
+}
@@ -0,0 +1,48 @@
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* The server is built once, and shared by the checks of the file */
+#[test]
+fn test_registry() {
+    let mut odoo = setup::setup::setup_server();
+    check_registry_evaluation(&mut odoo);
+    check_registry_usage_validation(&mut odoo);
+}
+
+/* The registry of the environment and of the models are evaluated from the odoo source */
+fn check_registry_evaluation(odoo: &mut SyncOdoo) {
+    let registry_class = odoo.get_symbol(&(vec![S!("odoo"), S!("modules"), S!("registry")], vec![S!("Registry")]), u32::MAX).pop();
+    let registry_class = registry_class.expect("Registry should be loaded");
+    for (file_tree, content_tree) in [(vec![S!("odoo"), S!("api")], vec![S!("Environment"), S!("registry")]), (vec![S!("odoo"), S!("models")], vec![S!("BaseModel"), S!("pool")])] {
+        let symbol = odoo.get_symbol(&(file_tree, content_tree.clone()), u32::MAX).pop();
+        let symbol = symbol.unwrap_or_else(|| panic!("{} should be a member", content_tree.join(".")));
+        let symbol = symbol.borrow();
+        let evaluations = symbol.evaluations().unwrap();
+        assert_eq!(evaluations.len(), 1);
+        let weak = evaluations[0].symbol.as_weak().unwrap();
+        assert!(weak.instance.unwrap_or(false));
+        assert!(std::rc::Rc::ptr_eq(&weak.weak.upgrade().unwrap(), &registry_class), "{} is not a Registry", content_tree.join("."));
+    }
+    //the members only assigned by the methods of the registry are created
+    for member in ["models", "ready", "_init_modules"] {
+        assert!(!registry_class.borrow().get_content_symbol(member, u32::MAX).is_empty(), "{} is not a member of Registry", member);
+    }
+}
+
+/* Iterating the environment and the registry, and their subscripts, give no false positive */
+fn check_registry_usage_validation(odoo: &mut SyncOdoo) {
+    let file = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("registry_usage")], vec![]), u32::MAX).pop();
+    let file = file.expect("registry_usage should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    let messages: Vec<String> = validator.diagnostics.iter().map(|d| d.message.clone()).collect();
+    assert!(messages.is_empty(), "Unexpected diagnostics: {:?}", messages);
+}
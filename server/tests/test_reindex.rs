@@ -0,0 +1,30 @@
+use odoo_ls_server::core::reindex::{ReindexScope, ReindexSummary};
+use serde_json::json;
+
+#[test]
+fn test_reindex_scope_arguments() {
+    assert_eq!(ReindexScope::from_arguments(&vec![]), Ok(ReindexScope::ALL));
+    assert_eq!(ReindexScope::from_arguments(&vec![json!({"scope": "all"})]), Ok(ReindexScope::ALL));
+    assert_eq!(ReindexScope::from_arguments(&vec![json!({"scope": "externals"})]), Ok(ReindexScope::EXTERNALS));
+    assert_eq!(ReindexScope::from_arguments(&vec![json!({"scope": "module", "module": "sale"})]), Ok(ReindexScope::MODULE { module: "sale".to_string() }));
+    assert_eq!(ReindexScope::from_arguments(&vec![json!({"scope": "addonsPath", "path": "/opt/addons"})]), Ok(ReindexScope::ADDONS_PATH { path: "/opt/addons".to_string() }));
+    assert_eq!(ReindexScope::from_arguments(&vec![json!({"scope": "file", "uri": "file:///opt/addons/sale/models.py"})]),
+        Ok(ReindexScope::FILE { uri: "file:///opt/addons/sale/models.py".to_string() }));
+    assert!(ReindexScope::from_arguments(&vec![json!({"scope": "module"})]).is_err());
+    assert!(ReindexScope::from_arguments(&vec![json!({"scope": "workspace"})]).is_err());
+    assert!(ReindexScope::from_arguments(&vec![json!("sale")]).is_err());
+}
+
+#[test]
+fn test_reindex_summary() {
+    let summary = ReindexSummary {
+        scope: ReindexScope::MODULE { module: "sale".to_string() },
+        files_rebuilt: 12,
+        duration: 340,
+    };
+    assert_eq!(serde_json::to_value(&summary).unwrap(), json!({
+        "scope": {"scope": "module", "module": "sale"},
+        "filesRebuilt": 12,
+        "duration": 340,
+    }));
+}
@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_types::{Position, PrepareRenameResponse, WorkspaceEdit};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::rename::RenameFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_models_file(session: &mut SessionInfo, name: &str) -> Rc<RefCell<Symbol>> {
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!(name)];
+    session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().expect("the file should be loaded")
+}
+
+/* Return the start of the edits of the file, sorted */
+fn edited_at(edit: &WorkspaceEdit, file: &str) -> Vec<Position> {
+    let mut res: Vec<Position> = edit.changes.as_ref().unwrap().iter()
+        .filter(|(uri, _)| uri.as_str().ends_with(file))
+        .flat_map(|(_, edits)| edits.iter().map(|edit| edit.range.start))
+        .collect();
+    res.sort();
+    res
+}
+
+#[test]
+fn test_rename() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let module_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3");
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(module_path.sanitize());
+    SyncOdoo::apply_ignore_rules(&mut session);
+    let amounts = get_models_file(&mut session, "amounts");
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &amounts).unwrap();
+
+    //format_amount is renamed in its declaration, in its re-export and where the re-export is used
+    let prepared = RenameFeature::prepare_rename(&mut session, &amounts, &file_info, 0, 6).expect("format_amount can be renamed");
+    let PrepareRenameResponse::Range(range) = prepared else {
+        panic!("the range of the name should be given");
+    };
+    assert_eq!((range.start, range.end), (Position::new(0, 4), Position::new(0, 17)));
+    let edit = RenameFeature::rename(&mut session, &amounts, &file_info, 0, 6, "format_price").expect("format_amount can be renamed");
+    assert_eq!(edited_at(&edit, "models/amounts.py"), vec![Position::new(0, 4)]);
+    assert_eq!(edited_at(&edit, "models/amount_exports.py"), vec![Position::new(0, 21)]);
+    assert_eq!(edited_at(&edit, "models/amount_usage.py"), vec![Position::new(0, 28), Position::new(4, 11)]);
+    assert!(edit.changes.as_ref().unwrap().values().flatten().all(|edit| edit.new_text == "format_price"));
+
+    //the alias of round_amount is kept: only the imported name changes
+    let edit = RenameFeature::rename(&mut session, &amounts, &file_info, 4, 6, "round_price").expect("round_amount can be renamed");
+    assert_eq!(edited_at(&edit, "models/amounts.py"), vec![Position::new(4, 4)]);
+    assert_eq!(edited_at(&edit, "models/amount_alias.py"), vec![Position::new(0, 21)]);
+
+    //the new name is already declared in the file
+    let error = RenameFeature::rename(&mut session, &amounts, &file_info, 0, 6, "round_amount").unwrap_err();
+    assert!(error.contains("already declared"), "{}", error);
+    assert!(RenameFeature::rename(&mut session, &amounts, &file_info, 0, 6, "class").is_err());
+
+    //round is a builtin
+    let error = RenameFeature::prepare_rename(&mut session, &amounts, &file_info, 5, 12).unwrap_err();
+    assert!(error.contains("outside of the workspace"), "{}", error);
+}
@@ -0,0 +1,309 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_builder::PythonArchBuilder;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+
+/* These tests check that the sections of a scope give the right declarations of a variable at each position,
+depending on the control flow (loops, break, else). */
+
+/* Build the architecture of the given code and return, for each line containing the marker '#?', the lines
+(starting at 1) of the declarations of 'x' that are reachable at the start of this line. */
+fn get_declarations(name: &str, code: &str) -> Vec<Vec<usize>> {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_sections_{}_{}.py", name, std::process::id()));
+    fs::write(&path, code).unwrap();
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    let file = Symbol::create_from_path(&mut session, &path, root, false).unwrap();
+    let mut builder = PythonArchBuilder::new(file.clone());
+    builder.load_arch(&mut session);
+    let mut result = vec![];
+    let mut offset = 0;
+    for line in code.split_inclusive('\n') {
+        if line.contains("#?") {
+            let position = offset + (line.len() - line.trim_start().len());
+            result.push(get_lines(&file, code, position as u32));
+        }
+        offset += line.len();
+    }
+    let _ = fs::remove_file(&path);
+    result
+}
+
+fn get_lines(file: &Rc<RefCell<Symbol>>, code: &str, position: u32) -> Vec<usize> {
+    let mut lines: Vec<usize> = file.borrow().get_content_symbol("x", position).iter().map(|sym| {
+        let start = sym.borrow().range().start().to_usize();
+        code[..start].matches('\n').count() + 1
+    }).collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_for_sections() {
+    let res = get_declarations("for", "\
+x = 1
+for i in range(5):
+    y = x #?
+    x = 'a'
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![1], vec![4], vec![1, 4]]);
+}
+
+#[test]
+fn test_for_target() {
+    let res = get_declarations("for_target", "\
+x = 1
+for x in range(5):
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![2], vec![1, 2]]);
+}
+
+#[test]
+fn test_nested_for_sections() {
+    let res = get_declarations("nested_for", "\
+x = 1
+for i in range(5):
+    for j in range(5):
+        y = x #?
+        x = 2
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![1], vec![1, 5], vec![1, 5]]);
+}
+
+#[test]
+fn test_nested_for_at_end_of_body() {
+    /* The inner and the outer loops end at the same position */
+    let res = get_declarations("nested_for_end", "\
+x = 1
+for i in range(5):
+    x = 2
+    for j in range(5):
+        x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![1, 3, 5]]);
+}
+
+#[test]
+fn test_for_else_sections() {
+    let res = get_declarations("for_else", "\
+x = 1
+for i in range(5):
+    x = 2
+else:
+    y = x #?
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![1, 3], vec![6]]);
+}
+
+#[test]
+fn test_for_else_break_sections() {
+    let res = get_declarations("for_else_break", "\
+x = 1
+for i in range(5):
+    x = 2
+    if i:
+        break
+else:
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![3, 7]]);
+}
+
+#[test]
+fn test_for_else_nested_break_sections() {
+    /* A break in a nested loop doesn't exit the outer loop */
+    let res = get_declarations("for_else_nested_break", "\
+x = 1
+for i in range(5):
+    x = 2
+    for j in range(5):
+        break
+else:
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![7]]);
+}
+
+#[test]
+fn test_if_sections() {
+    let res = get_declarations("if", "\
+x = 1
+if a:
+    x = 2
+    y = x #?
+elif b:
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![3], vec![1], vec![1, 3]]);
+}
+
+#[test]
+fn test_if_else_sections() {
+    let res = get_declarations("if_else", "\
+x = 1
+if a:
+    x = 2
+else:
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![3, 5]]);
+}
+
+#[test]
+fn test_if_narrowing_sections() {
+    /* The test narrows x in the body, the narrowed variable is declared at the end of the test */
+    let res = get_declarations("if_narrowing", "\
+x = get_x()
+if x == 2:
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![2], vec![1, 2]]);
+}
+
+#[test]
+fn test_if_static_sections() {
+    /* The branch is selected from the value of a module-level flag, the other branches are not reachable after the if */
+    let res = get_declarations("if_static", "\
+DEBUG = False
+LEVEL = 2 if DEBUG else 3
+if DEBUG:
+    x = 1
+elif LEVEL > 1 and not DEBUG:
+    x = 2
+else:
+    x = 3
+y = x #?
+if DEBUG is not None:
+    x = 4
+y = x #?
+");
+    assert_eq!(res, vec![vec![6], vec![11]]);
+}
+
+#[test]
+fn test_if_undecidable_sections() {
+    /* A flag declared in several branches, or with a value that is not constant, keeps all the branches */
+    let res = get_declarations("if_undecidable", "\
+HAS_PDF = find_in_path('wkhtmltopdf') is not None
+if HAS_PDF:
+    x = 1
+else:
+    x = 2
+y = x #?
+if a:
+    FLAG = True
+else:
+    FLAG = False
+if FLAG:
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![3, 5], vec![3, 5, 12]]);
+}
+
+#[test]
+fn test_try_sections() {
+    /* The variables of the body are possibly bound in the handlers, and both paths are merged after the try */
+    let res = get_declarations("try", "\
+x = 1
+try:
+    x = 2
+except Exception:
+    y = x #?
+    x = 3
+y = x #?
+");
+    assert_eq!(res, vec![vec![1, 3], vec![3, 6]]);
+}
+
+#[test]
+fn test_try_else_finally_sections() {
+    let res = get_declarations("try_else_finally", "\
+x = 1
+try:
+    x = 2
+except ValueError:
+    x = 3
+else:
+    x = 4
+finally:
+    y = x #?
+    x = 5
+y = x #?
+");
+    assert_eq!(res, vec![vec![5, 7], vec![10]]);
+}
+
+#[test]
+fn test_match_sections() {
+    let res = get_declarations("match", "\
+x = 1
+match command:
+    case [x, *rest]:
+        y = x #?
+    case {'key': value}:
+        x = value
+    case _:
+        y = x #?
+y = x #?
+match command:
+    case Point(x=x):
+        pass
+    case Other():
+        x = 'a'
+y = x #?
+");
+    //the last case of the first match is a wildcard: the subject always matches a case
+    assert_eq!(res, vec![vec![3], vec![1], vec![1, 3, 6], vec![1, 3, 6, 11, 14]]);
+}
+
+#[test]
+fn test_while_sections() {
+    let res = get_declarations("while", "\
+x = 1
+while (x := read()):
+    y = x #?
+    x = 'a'
+    y = x #?
+y = x #?
+");
+    assert_eq!(res, vec![vec![2], vec![4], vec![2, 4]]);
+}
+
+#[test]
+fn test_del_sections() {
+    let res = get_declarations("del", "\
+x = 1
+y = x #?
+del x
+y = x #?
+if read():
+    x = 2
+    del x
+y = x #?
+x = 3
+y = x #?
+");
+    //a del in a branch only hides the declarations of this branch
+    assert_eq!(res, vec![vec![1], vec![], vec![], vec![9]]);
+}
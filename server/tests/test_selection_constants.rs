@@ -0,0 +1,26 @@
+use odoo_ls_server::core::selection_field::{ModelSelection, SelectionField};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* module_3 declares its selections with the constants of another file of the module, like the OCA modules */
+#[test]
+fn test_selection_from_constants() {
+    let mut odoo = setup::setup::setup_server();
+    let module = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3")], vec![]), u32::MAX).pop();
+    assert!(module.is_some());
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let model = S!("pygls.tests.states");
+    let mut get_selection = |field: &str| SelectionField::get_model_selection(&mut session, &model, &S!(field), module.clone(), None);
+
+    //comprehension over an imported list, whose elements are constants
+    assert_eq!(get_selection("state"), ModelSelection::KEYS(vec![S!("draft"), S!("confirmed"), S!("done")], false));
+    assert_eq!(get_selection("simple_state"), ModelSelection::KEYS(vec![S!("draft"), S!("done")], false));
+    //iterating on a dict gives its keys
+    assert_eq!(get_selection("priority_key"), ModelSelection::KEYS(vec![S!("0"), S!("1")], false));
+    //method calls and selections given by a method stay unknown
+    assert_eq!(get_selection("priority"), ModelSelection::UNKNOWN);
+    assert_eq!(get_selection("dynamic_state"), ModelSelection::UNKNOWN);
+}
@@ -0,0 +1,47 @@
+use ruff_python_ast::{Expr, Stmt};
+use odoo_ls_server::core::selection_field::{SelectionAddEntry, SelectionField};
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn get_value(code: &str) -> Expr {
+    let module = parse_module(code);
+    match module.body.into_iter().next() {
+        Some(Stmt::Assign(assign)) => *assign.value,
+        _ => panic!("Not an assignment")
+    }
+}
+
+#[test]
+fn test_selection_add_merge() {
+    let base = get_value("state = fields.Selection([('draft', 'Draft'), ('done', 'Done')], required=True)");
+    let base_call = SelectionField::as_selection_call(&base).unwrap();
+    assert!(SelectionField::is_required(base_call));
+    let mut keys = SelectionField::get_literal_keys(SelectionField::get_selection_expr(base_call).unwrap()).unwrap();
+    assert_eq!(keys, vec!["draft", "done"]);
+
+    let extension = get_value("state = fields.Selection(selection_add=[('draft',), ('sent', 'Sent'), ('cancel', 'Cancel')])");
+    let call = SelectionField::as_selection_call(&extension).unwrap();
+    assert!(SelectionField::get_selection_expr(call).is_none());
+    let entries = SelectionField::get_selection_add(call);
+    assert!(matches!(&entries[0], SelectionAddEntry::ANCHOR(key, _) if key == "draft"));
+    assert!(matches!(&entries[1], SelectionAddEntry::ADD(key, _) if key == "sent"));
+    SelectionField::merge(&mut keys, &entries);
+    assert_eq!(keys, vec!["draft", "sent", "cancel", "done"]);
+}
+
+#[test]
+fn test_selection_key_literal_at() {
+    let code = "state = fields.Selection(selection_add=[('sent', 'Sent'), ('dra',)], ondelete={'se': 'cascade'})";
+    let value = get_value(code);
+    let call = SelectionField::as_selection_call(&value).unwrap();
+    let anchor = code.find("'dra'").unwrap() as u32 + 2;
+    assert_eq!(SelectionField::get_key_literal_at(call, anchor).unwrap().value.to_str(), "dra");
+    let ondelete = code.find("'se'").unwrap() as u32 + 2;
+    assert_eq!(SelectionField::get_key_literal_at(call, ondelete).unwrap().value.to_str(), "se");
+    //added keys and labels are not completed
+    let added = code.find("'sent'").unwrap() as u32 + 2;
+    assert!(SelectionField::get_key_literal_at(call, added).is_none());
+    assert!(SelectionField::as_selection_call(&get_value("name = fields.Char()")).is_none());
+}
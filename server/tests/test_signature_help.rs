@@ -0,0 +1,64 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{ParameterLabel, SignatureHelp};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::signature_help::SignatureHelpFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Return the signature help at the end of the first occurrence of marker in the file */
+fn get_signature_help(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str) -> Option<SignatureHelp> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(marker).unwrap() + marker.len();
+    let position = file_info.borrow().offset_to_position(offset);
+    SignatureHelpFeature::get_signature_help(session, file, &file_info, position.line, position.character)
+}
+
+/* Return the labels of the parameters of the active signature, and the label of its active parameter */
+fn get_active(help: &SignatureHelp) -> (String, Option<String>) {
+    let signature = &help.signatures[help.active_signature.unwrap() as usize];
+    let label: Vec<u16> = signature.label.encode_utf16().collect();
+    let active = signature.parameters.as_ref().unwrap().get(signature.active_parameter.unwrap() as usize).map(|parameter| {
+        let ParameterLabel::LabelOffsets([start, end]) = parameter.label else {
+            panic!("the parameters are given by their offsets");
+        };
+        String::from_utf16(&label[start as usize..end as usize]).unwrap()
+    });
+    (signature.label.clone(), active)
+}
+
+/* The signature help gives the parameters of the called function, with the parameter of the argument under the cursor.
+The bound parameter of a method is not displayed, and an overloaded function has one signature per overload */
+#[test]
+fn test_signature_help() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("signatures")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("signatures should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let help = get_signature_help(&mut session, &file, "\ncompute(").unwrap();
+    assert_eq!(get_active(&help), (S!("compute(amount, rate=0.5, *values, rounding=None, **options)"), Some(S!("amount"))));
+    let help = get_signature_help(&mut session, &file, "compute(1, ").unwrap();
+    assert_eq!(get_active(&help).1, Some(S!("rate")));
+    //a keyword argument gives its parameter, wherever it is
+    let help = get_signature_help(&mut session, &file, "compute(1, 2, roun").unwrap();
+    assert_eq!(get_active(&help).1, Some(S!("rounding=None")));
+
+    let help = get_signature_help(&mut session, &file, "Reader(").unwrap();
+    assert_eq!(get_active(&help), (S!("Reader(path, encoding='utf-8')"), Some(S!("path"))));
+    let help = get_signature_help(&mut session, &file, "reader.apply(2, ").unwrap();
+    assert_eq!(get_active(&help), (S!("apply(factor, label='x')"), Some(S!("label='x'"))));
+
+    let help = get_signature_help(&mut session, &file, "reader.read_as(").unwrap();
+    assert_eq!(help.signatures.len(), 2);
+    assert_eq!(help.active_signature, Some(1));
+
+    //outside of the arguments of a call
+    assert!(get_signature_help(&mut session, &file, "reader = Read").is_none());
+}
@@ -0,0 +1,96 @@
+use std::fs;
+
+use crossbeam_channel::Receiver;
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{DiagnosticSeverity, DidCloseTextDocumentParams, DidOpenTextDocumentParams, HoverContents, PublishDiagnosticsParams, TextDocumentIdentifier, TextDocumentItem};
+use odoo_ls_server::core::config::RefreshMode;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::features::hover::HoverFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+const SCRIPT: &str = "import not_a_real_module
+from odoo import models
+
+
+class Helper:
+    def run(self):
+        return 1
+
+
+class Outside(models.Model):
+    _name = \"single.file.model\"
+
+
+helper = Helper()
+";
+
+fn get_published_diagnostics(receiver: &Receiver<Message>) -> Vec<PublishDiagnosticsParams> {
+    let mut res = vec![];
+    while let Ok(msg) = receiver.try_recv() {
+        if let Message::Notification(n) = msg {
+            if n.method == PublishDiagnostics::METHOD {
+                res.push(serde_json::from_value(n.params).unwrap());
+            }
+        }
+    }
+    res
+}
+
+/* A Python file opened outside of the roots is analyzed alone: the features work on it, but its models are not added
+to the registry, only its errors are published, and it is unloaded when it is closed */
+#[test]
+fn test_single_file() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    let refresh_mode = session.sync_odoo.config.refresh_mode.clone();
+    session.sync_odoo.config.refresh_mode = RefreshMode::OnSave;
+
+    //a directory without manifest in its parents, so nothing is suggested to the user
+    let root = std::env::temp_dir().join(format!("odoo_ls_tests_single_file_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let path = root.join("script.py");
+    fs::write(&path, SCRIPT).unwrap();
+    let path_str = path.sanitize();
+    assert!(session.sync_odoo.tree_from_path(&path).is_err());
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem { uri: FileMgr::pathname2uri(&path_str), language_id: S!("python"), version: 1, text: S!(SCRIPT) }
+    });
+    assert!(session.sync_odoo.is_single_file(&path_str));
+    assert_eq!(session.sync_odoo.tree_from_path(&path), Ok((vec![path_str.clone()], vec![])));
+    let file = session.sync_odoo.get_file_symbol(&path).expect("the single file should be loaded");
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &file).unwrap();
+    assert!(file_info.borrow().errors_only);
+
+    //the features work on the file
+    let offset = SCRIPT.find("\nhelper").unwrap() + 1;
+    let position = file_info.borrow().offset_to_position(offset);
+    let hover = HoverFeature::get_hover(&mut session, &file, &file_info, position.line, position.character).expect("no hover on helper");
+    let HoverContents::Markup(content) = hover.contents else {
+        panic!("the hover should be markdown");
+    };
+    assert!(content.value.contains("Helper"), "{}", content.value);
+    //its models are not in the registry
+    assert!(!session.sync_odoo.models.contains_key("single.file.model"));
+    //the unresolved import is a warning, that is not published
+    for published in get_published_diagnostics(&r).iter().filter(|p| p.uri == FileMgr::pathname2uri(&path_str)) {
+        assert!(published.diagnostics.iter().all(|d| d.severity == Some(DiagnosticSeverity::ERROR)), "{:?}", published.diagnostics);
+    }
+
+    Odoo::handle_did_close(&mut session, DidCloseTextDocumentParams {
+        text_document: TextDocumentIdentifier { uri: FileMgr::pathname2uri(&path_str) }
+    });
+    assert!(!session.sync_odoo.is_single_file(&path_str));
+    assert!(session.sync_odoo.tree_from_path(&path).is_err());
+    assert!(session.sync_odoo.get_symbol(&(vec![path_str.clone()], vec![]), u32::MAX).is_empty());
+
+    session.sync_odoo.config.refresh_mode = refresh_mode;
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lsp_types::{CompletionItem, CompletionItemKind, CompletionResponse};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+/* Return the snippets completed at the end of the first occurrence of marker in the file */
+fn get_snippets(session: &mut SessionInfo, file: &Rc<RefCell<Symbol>>, marker: &str) -> Vec<CompletionItem> {
+    let file_info = FileMgr::get_file_info_of_symbol(session, file).unwrap();
+    let source = std::fs::read_to_string(&file.borrow().paths()[0]).unwrap();
+    let offset = source.find(marker).unwrap() + marker.len();
+    let position = file_info.borrow().offset_to_position(offset);
+    match CompletionFeature::autocomplete(session, file, &file_info, position.line, position.character) {
+        Some(CompletionResponse::List(list)) => list.items.into_iter().filter(|item| item.kind == Some(CompletionItemKind::SNIPPET)).collect(),
+        _ => vec![],
+    }
+}
+
+/* The snippets are proposed where they are valid, filled with the models of the dependencies of the module, and
+indented like the file, that uses 2 spaces */
+#[test]
+fn test_snippets() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_6"), S!("models"), S!("snippets")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("snippets should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+
+    let snippets = get_snippets(&mut session, &file, "  many");
+    assert_eq!(snippets.iter().map(|item| item.label.clone()).collect::<Vec<String>>(), vec![S!("many2one")]);
+    let text = snippets[0].insert_text.clone().unwrap();
+    assert!(text.starts_with("${1:field}_id = fields.Many2one(\"${2|"), "{}", text);
+    //module_6 only depends on module_1
+    assert!(text.contains("pygls.tests.m_name"), "{}", text);
+    assert!(!text.contains("module_3.parameters"), "{}", text);
+
+    let snippets = get_snippets(&mut session, &file, "  comp");
+    assert_eq!(snippets.iter().map(|item| item.label.clone()).collect::<Vec<String>>(), vec![S!("compute field")]);
+    let text = snippets[0].insert_text.clone().unwrap();
+    assert!(text.contains("def _compute_${1:field}(self):\n  for record in self:\n    record.${1:field} = ${0:False}"), "{}", text);
+
+    //the models are created at the top level of the files of a module only
+    let snippets = get_snippets(&mut session, &file, "\nnew");
+    assert_eq!(snippets.iter().map(|item| item.label.clone()).collect::<Vec<String>>(), vec![S!("new model")]);
+    assert!(snippets[0].insert_text.as_ref().unwrap().contains("_name = \"${2:module_6.model}\""));
+
+    session.sync_odoo.config.disabled_snippets = vec![S!("many2one")];
+    assert!(get_snippets(&mut session, &file, "  many").is_empty());
+    session.sync_odoo.config.disabled_snippets = vec![];
+}
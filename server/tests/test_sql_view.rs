@@ -0,0 +1,148 @@
+use ruff_python_ast::{Stmt, StmtClassDef};
+use odoo_ls_server::core::field_consistency::FieldDeclaration;
+use odoo_ls_server::core::sql_view::SqlView;
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_class(code: &str) -> StmtClassDef {
+    let Some(Stmt::ClassDef(class)) = parse(code).into_iter().next() else {
+        panic!("Not a class");
+    };
+    class
+}
+
+fn column_names(query: &str) -> Option<Vec<String>> {
+    SqlView::get_columns(query).map(|columns| columns.into_iter().map(|c| c.name).collect())
+}
+
+/* The SELECT list of sale.report */
+const SALE_REPORT_SELECT: &str = "
+    SELECT
+        MIN(l.id) AS id,
+        l.product_id AS product_id,
+        t.uom_id AS product_uom,
+        CASE WHEN l.product_id IS NOT NULL THEN SUM(l.product_uom_qty / u.factor * u2.factor) ELSE 0 END AS product_uom_qty,
+        count(*) AS nbr,
+        s.name AS name,
+        s.date_order AS date,
+        s.state AS state,
+        extract(epoch from avg(date_trunc('day',s.date_order)-date_trunc('day',s.create_date)))/(24*60*60)::decimal(16,2) AS delay,
+        l.discount as discount,
+        s.id AS order_id
+    FROM sale_order_line l
+        JOIN sale_order s ON (l.order_id = s.id)
+";
+
+#[test]
+fn test_sale_report_columns() {
+    assert_eq!(column_names(SALE_REPORT_SELECT).unwrap(), vec!["id", "product_id", "product_uom", "product_uom_qty", "nbr", "name",
+        "date", "state", "delay", "discount", "order_id"]);
+    let columns = SqlView::get_columns(SALE_REPORT_SELECT).unwrap();
+    assert_eq!(&SALE_REPORT_SELECT[columns[8].range.clone()], "delay");
+}
+
+#[test]
+fn test_columns() {
+    //a column without alias is named by the column
+    assert_eq!(column_names("SELECT l.id, l.partner_id, company_id FROM t l").unwrap(), vec!["id", "partner_id", "company_id"]);
+    //unquoted names are case-insensitive
+    assert_eq!(column_names("select ID, Amount AS \"Total\" from t").unwrap(), vec!["id", "Total"]);
+    //no FROM, and a subquery
+    assert_eq!(column_names("SELECT 1 AS id, (SELECT max(x) AS m FROM t) AS maximum").unwrap(), vec!["id", "maximum"]);
+    assert_eq!(column_names("SELECT DISTINCT ON (p.id, p.name) p.id, p.name FROM p").unwrap(), vec!["id", "name"]);
+    //a comment
+    assert_eq!(column_names("SELECT id, -- the record, as shown\n name FROM p").unwrap(), vec!["id", "name"]);
+}
+
+#[test]
+fn test_complicated_queries() {
+    assert_eq!(column_names("WITH x AS (SELECT 1) SELECT id FROM x"), None);
+    assert_eq!(column_names("SELECT * FROM x"), None);
+    assert_eq!(column_names("SELECT p.* FROM p"), None);
+    assert_eq!(column_names("SELECT id, a + b FROM x"), None);
+    assert_eq!(column_names("SELECT id, %s FROM x"), None);
+    assert_eq!(column_names("SELECT id, {fields} FROM x"), None);
+}
+
+#[test]
+fn test_view_select() {
+    let statement = "
+        CREATE or REPLACE view %s as (
+            SELECT
+                row_number() over(ORDER BY leaves.employee_id) as id,
+                leaves.employee_id as employee_id,
+                leaves.number_of_days as number_of_days
+            FROM (SELECT 1) leaves
+        );";
+    let start = SqlView::get_view_select(statement).unwrap();
+    assert!(statement[start..].starts_with("SELECT"));
+    assert_eq!(column_names(&statement[start..]).unwrap(), vec!["id", "employee_id", "number_of_days"]);
+    assert_eq!(SqlView::get_view_select("CREATE VIEW x AS (WITH y AS (SELECT 1) SELECT * FROM y)"), None);
+    assert_eq!(SqlView::get_view_select("SELECT 1"), None);
+}
+
+#[test]
+fn test_view_fields() {
+    let class = parse_class("\
+class LeaveReport(models.Model):
+    _name = 'hr.leave.employee.type.report'
+    _auto = False
+
+    employee_id = fields.Many2one('hr.employee', readonly=True)
+    number_of_days = fields.Float(readonly=True)
+    holiday_status_id = fields.Many2one('hr.leave.type', readonly=True)
+    leave_ids = fields.One2many('hr.leave', 'employee_id')
+    label = fields.Char(compute='_compute_label')
+
+    def init(self):
+        tools.drop_view_if_exists(self._cr, self._table)
+        self._cr.execute('''
+            CREATE or REPLACE view %s as (
+                SELECT
+                    row_number() over(ORDER BY leaves.employee_id) as id,
+                    leaves.employee_id as employee_id,
+                    leaves.number_of_days as number_of_days,
+                    leaves.state as state
+                FROM hr_leave leaves
+            );
+        ''' % (self._table))
+");
+    let query = SqlView::find_query(&class.body).unwrap();
+    let columns = SqlView::get_columns(&query.literal.value.to_str()[query.start..]).unwrap();
+    let fields: Vec<FieldDeclaration> = class.body.iter().filter_map(FieldDeclaration::from_stmt).collect();
+    let missing: Vec<&str> = SqlView::get_fields_without_column(&fields, &columns).iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(missing, vec!["holiday_status_id"]);
+    let extra: Vec<&str> = SqlView::get_columns_without_field(&fields, &columns).iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(extra, vec!["state"]);
+}
+
+#[test]
+fn test_table_query() {
+    let class = parse_class("\
+class Report(models.Model):
+    _name = 'x.report'
+    _auto = False
+
+    @property
+    def _table_query(self):
+        \"\"\"The report\"\"\"
+        return SQL('SELECT id, name FROM x WHERE company_id = %s', self.env.company.id)
+");
+    let query = SqlView::find_query(&class.body).unwrap();
+    assert_eq!(query.start, 0);
+    assert_eq!(column_names(query.literal.value.to_str()).unwrap(), vec!["id", "name"]);
+    //a query built by methods is not read
+    let class = parse_class("\
+class Report(models.Model):
+    _name = 'x.report'
+    _auto = False
+
+    @property
+    def _table_query(self):
+        return '%s %s' % (self._select(), self._from())
+");
+    let query = SqlView::find_query(&class.body).unwrap();
+    assert_eq!(column_names(query.literal.value.to_str()), None);
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::{Odoo, SyncOdoo};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::features::completion::CompletionFeature;
+use odoo_ls_server::features::document_symbols::DocumentSymbolFeature;
+use odoo_ls_server::features::references::ReferencesFeature;
+use odoo_ls_server::features::workspace_symbols::WorkspaceSymbolFeature;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_module_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3")
+}
+
+/* Return the file of module_3/models, validated so that the bodies of its functions are built */
+fn get_validated_file(session: &mut SessionInfo, name: &str) -> Rc<RefCell<Symbol>> {
+    let tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!(name)];
+    let file = session.sync_odoo.get_symbol(&(tree, vec![]), u32::MAX).pop().unwrap();
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(file.clone()).validate(session);
+    file
+}
+
+/* Return the serialized responses of the features giving lists to the client: the outline, the references, the
+workspace symbols and the completion */
+fn get_responses(session: &mut SessionInfo) -> Vec<String> {
+    let outline = get_validated_file(session, "outline");
+    let outline_info = FileMgr::get_file_info_of_symbol(session, &outline).unwrap();
+    let document_symbols = DocumentSymbolFeature::get_document_symbols(&outline, &outline_info);
+    let usage = get_validated_file(session, "amount_usage");
+    let usage_info = FileMgr::get_file_info_of_symbol(session, &usage).unwrap();
+    let amounts = get_validated_file(session, "amounts");
+    let amounts_info = FileMgr::get_file_info_of_symbol(session, &amounts).unwrap();
+    let references = ReferencesFeature::get_references(session, &amounts, &amounts_info, 0, 6, true);
+    let workspace_symbols = WorkspaceSymbolFeature::get_workspace_symbols(session, "amount");
+    let completion = CompletionFeature::autocomplete(session, &usage, &usage_info, 4, 12);
+    vec![
+        serde_json::to_string(&document_symbols).unwrap(),
+        serde_json::to_string(&references).unwrap(),
+        serde_json::to_string(&workspace_symbols).unwrap(),
+        serde_json::to_string(&completion).unwrap(),
+    ]
+}
+
+/* Two builds of unchanged files give the same responses, so that the clients caching them don't redraw them. The
+symbols of the rebuilt files are new, and are stored at other addresses in the maps of their parents and dependents */
+#[test]
+fn test_stable_responses() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(get_module_path().sanitize());
+    SyncOdoo::apply_ignore_rules(&mut session);
+
+    let before = get_responses(&mut session);
+    assert!(before.iter().all(|response| response != "null" && response != "[]"), "{:?}", before);
+    for name in ["outline.py", "amounts.py", "amount_exports.py", "amount_usage.py"] {
+        Odoo::update_file_index(&mut session, get_module_path().join("models").join(name), true, true);
+    }
+    let after = get_responses(&mut session);
+    for (before, after) in before.iter().zip(after.iter()) {
+        assert_eq!(before, after);
+    }
+}
@@ -0,0 +1,51 @@
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::S;
+
+mod setup;
+
+#[test]
+fn test_star_import_all() {
+    let odoo = setup::setup::setup_server();
+    check_star_import_filtered_by_sliced_all(&odoo);
+    check_star_import_filtered_by_incremental_all(&odoo);
+    check_star_import_skips_deleted_names(&odoo);
+}
+
+/* module_3/exports imports with a star the names of a file whose __all__ is built by slicing a constant list */
+fn check_star_import_filtered_by_sliced_all(odoo: &SyncOdoo) {
+    let exports = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("exports")], vec![]), u32::MAX).pop();
+    assert!(exports.is_some());
+    let exports = exports.unwrap();
+    let exports = exports.borrow();
+    for name in ["NAME_1", "NAME_2", "NAME_4"] {
+        assert!(!exports.get_content_symbol(name, u32::MAX).is_empty(), "{} should be imported", name);
+    }
+    for name in ["NAME_3", "ALL_NAMES"] {
+        assert!(exports.get_content_symbol(name, u32::MAX).is_empty(), "{} should be filtered by __all__", name);
+    }
+}
+
+/* module_3/exports imports with a star the names of files whose __all__ is extended by +=, append and extend. An
+__all__ extended by a value that can't be computed doesn't filter the names */
+fn check_star_import_filtered_by_incremental_all(odoo: &SyncOdoo) {
+    let exports = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("exports")], vec![]), u32::MAX).pop();
+    assert!(exports.is_some());
+    let exports = exports.unwrap();
+    let exports = exports.borrow();
+    for name in ["TOOL_1", "TOOL_2", "TOOL_3", "TOOL_4", "HELPER_1", "HELPER_2", "REEXPORT_1", "REEXPORT_2"] {
+        assert!(!exports.get_content_symbol(name, u32::MAX).is_empty(), "{} should be imported", name);
+    }
+    for name in ["TOOL_5", "HELPER_3"] {
+        assert!(exports.get_content_symbol(name, u32::MAX).is_empty(), "{} should be filtered by __all__", name);
+    }
+}
+
+/* module_3/exports imports with a star a file that deletes one of its names: the deleted name is not imported */
+fn check_star_import_skips_deleted_names(odoo: &SyncOdoo) {
+    let exports = odoo.get_symbol(&(vec![S!("odoo"), S!("addons"), S!("module_3"), S!("exports")], vec![]), u32::MAX).pop();
+    assert!(exports.is_some());
+    let exports = exports.unwrap();
+    let exports = exports.borrow();
+    assert!(!exports.get_content_symbol("KEPT_1", u32::MAX).is_empty(), "KEPT_1 should be imported");
+    assert!(exports.get_content_symbol("TEMPORARY_1", u32::MAX).is_empty(), "TEMPORARY_1 is deleted by its file");
+}
@@ -0,0 +1,72 @@
+use ruff_python_ast::{Expr, Stmt};
+use odoo_ls_server::core::static_conditions::{StaticConditions, StaticValue};
+
+mod setup;
+
+use setup::ast::parse_module;
+
+fn get_value(code: &str) -> Option<StaticValue> {
+    let module = parse_module(code);
+    let expr: Expr = match module.body.into_iter().next() {
+        Some(Stmt::Expr(expr)) => *expr.value,
+        _ => panic!("Not an expression")
+    };
+    StaticConditions::get_value(&expr, &mut |expr| match expr.as_name_expr()?.id.as_str() {
+        "DEBUG" => Some(StaticValue::BOOL(false)),
+        "VERSION_INFO" => Some(StaticValue::TUPLE(vec![StaticValue::INT(3), StaticValue::INT(8), StaticValue::INT(10)])),
+        "VERSION" => Some(StaticValue::INT(17)),
+        "MODE" => Some(StaticValue::STR("prod".to_string())),
+        "CONFIG" => Some(StaticValue::NONE),
+        _ => None
+    })
+}
+
+#[test]
+fn test_static_values() {
+    assert_eq!(get_value("not DEBUG"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("DEBUG or MODE"), Some(StaticValue::STR("prod".to_string())));
+    assert_eq!(get_value("DEBUG and unknown"), Some(StaticValue::BOOL(false)));
+    assert_eq!(get_value("VERSION >= 16"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("15 < VERSION <= 16"), Some(StaticValue::BOOL(false)));
+    assert_eq!(get_value("MODE in ('prod', 'staging')"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("CONFIG is None"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("DEBUG == 0"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("'a' if DEBUG else -1"), Some(StaticValue::INT(-1)));
+}
+
+#[test]
+fn test_undecidable_values() {
+    assert!(get_value("unknown").is_none());
+    assert!(get_value("MODE or unknown").is_some());
+    assert!(get_value("unknown or MODE").is_none());
+    assert!(get_value("VERSION is 17").is_none()); //the identity of ints is unknown
+    assert!(get_value("MODE in modes").is_none());
+    assert!(get_value("find_in_path('wkhtmltopdf') is not None").is_none());
+}
+
+#[test]
+fn test_string_values() {
+    assert_eq!(get_value("MODE + '.book'"), Some(StaticValue::STR("prod.book".to_string())));
+    assert_eq!(get_value("VERSION + 1"), Some(StaticValue::INT(18)));
+    assert_eq!(get_value("f'{MODE}.book.v{VERSION}'"), Some(StaticValue::STR("prod.book.v17".to_string())));
+    assert_eq!(get_value("'lib.' f'{MODE}'"), Some(StaticValue::STR("lib.prod".to_string())));
+    assert!(get_value("MODE + 1").is_none());
+    assert!(get_value("f'{MODE!r}'").is_none());
+    assert!(get_value("f'{VERSION:03}'").is_none());
+    assert!(get_value("f'{unknown}.book'").is_none());
+}
+
+#[test]
+fn test_tuple_values() {
+    assert_eq!(get_value("VERSION_INFO >= (3, 8)"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("VERSION_INFO < (3, 8)"), Some(StaticValue::BOOL(false)));
+    assert_eq!(get_value("VERSION_INFO >= (3, 10)"), Some(StaticValue::BOOL(false)));
+    assert_eq!(get_value("(3, 7) < VERSION_INFO < (3, 9)"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("VERSION_INFO[:2] == (3, 8)"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("VERSION_INFO[0] == 3"), Some(StaticValue::BOOL(true)));
+    assert_eq!(get_value("VERSION_INFO[-1]"), Some(StaticValue::INT(10)));
+    assert_eq!(get_value("f'{VERSION_INFO[:2]}'"), Some(StaticValue::STR("(3, 8)".to_string())));
+    //the items of a tuple must be comparable
+    assert_eq!(get_value("VERSION_INFO > (3, 'a')"), None);
+    assert_eq!(get_value("VERSION_INFO[5]"), None);
+}
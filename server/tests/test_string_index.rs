@@ -0,0 +1,66 @@
+use odoo_ls_server::core::string_index::{StringIndex, StringLookup};
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+fn get_files(lookup: StringLookup) -> Vec<String> {
+    match lookup {
+        StringLookup::FOUND(files) => files.into_iter().map(|(path, _)| path).collect(),
+        StringLookup::NOT_INDEXED => panic!("not indexed")
+    }
+}
+
+#[test]
+fn test_get_keys() {
+    assert_eq!(StringIndex::get_keys("res.partner"), vec!["res.partner", "partner"]);
+    assert_eq!(StringIndex::get_keys("partner_id.name"), vec!["partner_id.name", "partner_id", "name"]);
+    assert_eq!(StringIndex::get_keys("name, date_order"), vec!["name, date_order", "name", "date_order"]);
+    assert_eq!(StringIndex::get_keys("id"), Vec::<&str>::new());
+    assert_eq!(StringIndex::get_keys("a.b"), vec!["a.b"]);
+}
+
+#[test]
+fn test_collect_literals() {
+    let ast = parse_valid("class A(models.Model):\n    _name = 'sale.order'\n    x = fields.Char(related='partner_id.name', help=f'{x}')\n");
+    let literals: Vec<String> = StringIndex::collect_literals(&ast).into_iter().map(|(value, _)| value).collect();
+    assert_eq!(literals, vec!["sale.order", "partner_id.name"]);
+}
+
+#[test]
+fn test_update_lookup() {
+    let mut index = StringIndex::new();
+    index.update_file("/a.py", &parse_valid("_name = 'res.partner'\n"));
+    index.update_file("/b.py", &parse_valid("_inherit = ['res.partner', 'mail.thread']\n"));
+    assert_eq!(get_files(index.lookup("res.partner")), vec!["/a.py", "/b.py"]);
+    assert_eq!(get_files(index.lookup("partner")), vec!["/a.py", "/b.py"]);
+    assert_eq!(get_files(index.lookup("mail.thread")), vec!["/b.py"]);
+    assert_eq!(get_files(index.lookup("sale.order")), Vec::<String>::new());
+    assert_eq!(index.lookup("id"), StringLookup::NOT_INDEXED);
+    //the ranges of the literals are kept
+    match index.lookup("mail.thread") {
+        StringLookup::FOUND(files) => assert_eq!(files[0].1.as_ref().map(|ranges| ranges.len()), Some(1)),
+        StringLookup::NOT_INDEXED => panic!("not indexed")
+    }
+    //a new ast replaces the literals of the file
+    index.update_file("/b.py", &parse_valid("_inherit = 'mail.thread'\n"));
+    assert_eq!(get_files(index.lookup("res.partner")), vec!["/a.py"]);
+    index.remove_file("/a.py");
+    assert_eq!(get_files(index.lookup("res.partner")), Vec::<String>::new());
+    assert_eq!(index.len(), 2); //mail.thread and thread
+}
+
+#[test]
+fn test_ranges_cap() {
+    let mut index = StringIndex::new();
+    index.update_file("/a.py", &parse_valid(&"x = 'res.partner'\n".repeat(300)));
+    index.update_file("/b.py", &parse_valid("x = 'res.partner'\n"));
+    match index.lookup("res.partner") {
+        StringLookup::FOUND(files) => {
+            //the ranges of a.py are incomplete, so they are dropped: the file will be searched
+            assert_eq!(files[0], (String::from("/a.py"), None));
+            assert_eq!(files[1].1.as_ref().map(|ranges| ranges.len()), Some(1));
+        },
+        StringLookup::NOT_INDEXED => panic!("not indexed")
+    }
+}
@@ -0,0 +1,167 @@
+use ruff_python_ast::{Expr, Stmt};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use odoo_ls_server::core::python_string::StringSourceMap;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+/* These tests check that the ranges given by StringSourceMap for a part of the value of a string really point to the
+source code of this part, whatever the way the string is written (escape sequences, prefixes, quotes, implicit
+concatenation). Strings are randomly generated and written, then each highlighted range is decoded again by python
+to check that it gives the expected part of the value. */
+
+const CHARS: [char; 17] = ['a', 'b', 'z', '1', '.', '_', ' ', '\'', '"', '\\', '\n', '\t', 'é', '€', '😀', '\x07', '\0'];
+
+/* xorshift generator, to get reproducible random values without any dependency */
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Self { state: seed.wrapping_mul(0x9E3779B97F4A7C15) | 1 }
+    }
+
+    fn next(&mut self, max: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % max as u64) as usize
+    }
+
+    fn choose<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.next(values.len())]
+    }
+}
+
+fn parse_expr(source: &str) -> Expr {
+    let Stmt::Assign(assign) = parse_valid(source).into_iter().next().unwrap() else {
+        panic!("Not an assignment");
+    };
+    *assign.value
+}
+
+/* Write the part of a string with a random prefix, quote and random escape sequences */
+fn write_part(random: &mut Random, value: &str) -> String {
+    let triple = random.next(2) == 0;
+    let quote = if random.next(2) == 0 { "'" } else { "\"" };
+    let quotes = if triple { quote.repeat(3) } else { quote.to_string() };
+    let can_be_raw = !value.contains(['\\', '\'', '"', '\n', '\t', '\x07', '\0']);
+    let prefix = if can_be_raw { random.choose(&["", "u", "r", "R"]) } else { random.choose(&["", "u", "U"]) };
+    let mut res = format!("{}{}", prefix, quotes);
+    for c in value.chars() {
+        if !prefix.eq_ignore_ascii_case("r") && random.next(6) == 0 {
+            res += "\\\n"; //line continuation
+        }
+        let written = match c {
+            '\\' => String::from("\\\\"),
+            '\'' | '"' => {
+                if quote == c.to_string() || random.next(2) == 0 {
+                    format!("\\{}", c)
+                } else {
+                    c.to_string()
+                }
+            },
+            '\n' => {
+                let mut choices = vec!["\\n", "\\x0a", "\\012", "\\u000a", "\\U0000000a"];
+                if triple {
+                    choices.push("\n");
+                }
+                String::from(random.choose(&choices))
+            },
+            '\t' => String::from(random.choose(&["\\t", "\t", "\\x09"])),
+            '\x07' => String::from(random.choose(&["\\a", "\\x07", "\\007"])),
+            '\0' => String::from(random.choose(&["\\000", "\\x00", "\\u0000"])),
+            'é' if !prefix.eq_ignore_ascii_case("r") => String::from(random.choose(&["é", "\\xe9", "\\u00e9", "\\N{LATIN SMALL LETTER E WITH ACUTE}"])),
+            '€' if !prefix.eq_ignore_ascii_case("r") => String::from(random.choose(&["€", "\\u20ac", "\\U000020ac", "\\N{EURO SIGN}"])),
+            '😀' if !prefix.eq_ignore_ascii_case("r") => String::from(random.choose(&["😀", "\\U0001f600"])),
+            _ => c.to_string(),
+        };
+        res += &written;
+    }
+    res + &quotes
+}
+
+/* Generate a random string, and the source code of an expression that gives this string */
+fn generate(random: &mut Random) -> (String, String) {
+    let len = random.next(12);
+    let value: String = (0..len).map(|_| CHARS[random.next(CHARS.len())]).collect();
+    let chars: Vec<char> = value.chars().collect();
+    let mut parts = vec![];
+    let mut current = String::new();
+    for c in chars.iter() {
+        if random.next(4) == 0 {
+            parts.push(current);
+            current = String::new();
+        }
+        current.push(*c);
+    }
+    parts.push(current);
+    let written: Vec<String> = parts.iter().map(|p| write_part(random, p)).collect();
+    let separator = random.choose(&[" ", "\n    "]);
+    (value, format!("x = ({})\n", written.join(separator)))
+}
+
+/* Decode the given range of source code, as it would be in the part of the string that contains it */
+fn decode(source: &str, parts: &Vec<TextRange>, range: &TextRange) -> String {
+    let part = parts.iter().find(|p| p.contains_range(*range)).expect("range should be in a single part");
+    let part_text = &source[part.start().to_usize()..part.end().to_usize()];
+    let prefix_len = part_text.find(['\'', '"']).unwrap();
+    let quote_len = if part_text[prefix_len..].starts_with("'''") || part_text[prefix_len..].starts_with("\"\"\"") { 3 } else { 1 };
+    let opening = &part_text[..prefix_len + quote_len];
+    let closing = &part_text[part_text.len() - quote_len..];
+    let expr = parse_expr(&format!("x = {}{}{}\n", opening, &source[range.start().to_usize()..range.end().to_usize()], closing));
+    expr.as_string_literal_expr().unwrap().value.to_string()
+}
+
+#[test]
+fn test_random_strings_round_trip() {
+    for seed in 0..2000 {
+        let mut random = Random::new(seed);
+        let (value, source) = generate(&mut random);
+        let expr = parse_expr(&source);
+        assert_eq!(expr.as_string_literal_expr().unwrap().value.to_str(), value, "Invalid generated source: {}", source);
+        let parts: Vec<TextRange> = expr.as_string_literal_expr().unwrap().value.iter().map(|p| p.range()).collect();
+        let map = StringSourceMap::from_expr(&source, TextSize::new(0), &expr).unwrap();
+        assert_eq!(map.value(), value, "Wrong value for {}", source);
+        let boundaries: Vec<usize> = value.char_indices().map(|(i, _)| i).chain([value.len()]).collect();
+        for _ in 0..5 {
+            let a = boundaries[random.next(boundaries.len())];
+            let b = boundaries[random.next(boundaries.len())];
+            let (start, end) = if a <= b { (a, b) } else { (b, a) };
+            let decoded: String = map.source_ranges(start..end).iter().map(|r| decode(&source, &parts, r)).collect();
+            assert_eq!(decoded, value[start..end], "Wrong ranges for {}..{} in {}", start, end, source);
+        }
+    }
+}
+
+#[test]
+fn test_dotted_segment_range() {
+    let source = "x = ('partner_id.'\n     'country_\\x69d.code')\n";
+    let expr = parse_expr(source);
+    let map = StringSourceMap::from_expr(source, TextSize::new(0), &expr).unwrap();
+    assert_eq!(map.value(), "partner_id.country_id.code");
+    let start = map.value().find("country_id").unwrap();
+    let range = map.source_range(start..start + "country_id".len()).unwrap();
+    assert_eq!(&source[range.start().to_usize()..range.end().to_usize()], "country_\\x69d");
+    let ranges = map.source_ranges(0..map.value().len());
+    assert_eq!(ranges.len(), 2);
+}
+
+#[test]
+fn test_fstring_and_bytes() {
+    let source = "x = f'{{a}}{value!r}b'\n";
+    let expr = parse_expr(source);
+    let map = StringSourceMap::from_expr(source, TextSize::new(0), &expr).unwrap();
+    assert_eq!(map.value(), "{a}b");
+    let range = map.source_range(0..3).unwrap();
+    assert_eq!(&source[range.start().to_usize()..range.end().to_usize()], "{{a}}");
+
+    let source = "x = rb'\\d' b'\\x41'\n";
+    let expr = parse_expr(source);
+    let map = StringSourceMap::from_expr(source, TextSize::new(0), &expr).unwrap();
+    assert_eq!(map.value(), "\\dA");
+    let range = map.source_range(2..3).unwrap();
+    assert_eq!(&source[range.start().to_usize()..range.end().to_usize()], "\\x41");
+}
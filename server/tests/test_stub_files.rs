@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::PathBuf;
+
+use odoo_ls_server::core::import_resolver::resolve_import_stmt;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer;
+use ruff_python_ast::{Alias, Identifier, Stmt};
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+/* These tests check that the stubs of the packages installed in a root path are preferred to their implementation */
+
+fn write(root: &PathBuf, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/* Return the path of the symbol imported by each import statement of the code, run from a file of the root path */
+fn get_imported_paths(name: &str, code: &str) -> Vec<String> {
+    let root_path = std::env::temp_dir().join(format!("odoo_ls_tests_stubs_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root_path);
+    write(&root_path, "typed/__init__.py", "");
+    write(&root_path, "typed/module.py", "def run(): pass\n");
+    write(&root_path, "typed/module.pyi", "def run() -> int: ...\n");
+    write(&root_path, "shadowed/__init__.py", "VALUE = 1\n");
+    write(&root_path, "shadowed-stubs/__init__.pyi", "VALUE: int\n");
+    write(&root_path, "partial/__init__.py", "VALUE = 1\n");
+    write(&root_path, "partial-stubs/__init__.pyi", "VALUE: int\n");
+    write(&root_path, "partial-stubs/py.typed", "partial\n");
+    write(&root_path, "stub_only.pyi", "VALUE: int\n");
+    write(&root_path, "main.py", code);
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    root.borrow_mut().add_path(root_path.sanitize());
+    let file = Symbol::create_from_path(&mut session, &root_path.join("main.py"), root, false).unwrap();
+    let mut res = vec![];
+    for stmt in parse_valid(code).iter() {
+        let (module, names, level): (Option<&Identifier>, &[Alias], Option<u32>) = match stmt {
+            Stmt::Import(import) => (None, &import.names, None),
+            Stmt::ImportFrom(import_from) => (import_from.module.as_ref(), &import_from.names, Some(import_from.level)),
+            _ => continue
+        };
+        for result in resolve_import_stmt(&mut session, &file, module, names, level, &mut None).iter() {
+            assert!(result.found, "{} should be found", result.name);
+            let path = result.symbol.borrow().paths()[0].clone();
+            res.push(path.strip_prefix(&root_path.sanitize()).unwrap().trim_start_matches('/').to_string());
+        }
+    }
+    let _ = fs::remove_dir_all(&root_path);
+    res
+}
+
+#[test]
+fn test_stub_next_to_implementation() {
+    assert_eq!(get_imported_paths("module", "from typed import module\n"), vec!["typed/module.pyi"]);
+}
+
+#[test]
+fn test_stub_only_module() {
+    assert_eq!(get_imported_paths("stub_only", "import stub_only\n"), vec!["stub_only.pyi"]);
+}
+
+#[test]
+fn test_stub_packages() {
+    //the stubs package shadows the package, unless it is partial
+    assert_eq!(get_imported_paths("packages", "import shadowed\nimport partial\n"), vec!["shadowed-stubs", "partial"]);
+}
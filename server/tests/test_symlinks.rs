@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lsp_server::Message;
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{DidChangeTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams, PublishDiagnosticsParams, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem, VersionedTextDocumentIdentifier};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::config::RefreshMode;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::core::odoo::Odoo;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+use odoo_ls_server::S;
+
+mod setup;
+
+fn get_module_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3")
+}
+
+/* The diagnostics of the import of compute_total by the dependent file */
+fn get_import_diagnostics(session: &mut SessionInfo) -> Vec<String> {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("symlink_dependent")];
+    let file = session.sync_odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("symlink_dependent should be loaded");
+    let file_info = FileMgr::get_file_info_of_symbol(session, &file).unwrap();
+    let diagnostics = file_info.borrow().get_step_diagnostics(BuildSteps::ARCH_EVAL);
+    diagnostics.into_iter().map(|d| d.message).filter(|m| m.contains("compute_total")).collect()
+}
+
+fn get_published_uris(receiver: &crossbeam_channel::Receiver<Message>) -> Vec<String> {
+    let mut res = vec![];
+    while let Ok(msg) = receiver.try_recv() {
+        if let Message::Notification(n) = msg {
+            if n.method == PublishDiagnostics::METHOD {
+                let params: PublishDiagnosticsParams = serde_json::from_value(n.params).unwrap();
+                res.push(params.uri.to_string());
+            }
+        }
+    }
+    res
+}
+
+/* A module opened through a symlink out of the addons paths is the module of the addons path: an edit through the
+symlink rebuilds the files depending on the canonical file, and the diagnostics are published on the opened uri */
+#[cfg(unix)]
+#[test]
+fn test_symlinked_module() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r.clone(), &mut odoo);
+    session.sync_odoo.config.refresh_mode = RefreshMode::OnSave;
+    let root = std::env::temp_dir().join(format!("odoo_ls_tests_symlinks_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    std::os::unix::fs::symlink(get_module_path(), root.join("module_3")).unwrap();
+    //two symlinks pointing to each other
+    std::os::unix::fs::symlink(root.join("loop_b"), root.join("loop_a")).unwrap();
+    std::os::unix::fs::symlink(root.join("loop_a"), root.join("loop_b")).unwrap();
+
+    let canonical_path = get_module_path().join("models").join("symlink_target.py").sanitize();
+    let alias_path = root.join("module_3").join("models").join("symlink_target.py").sanitize();
+    let loop_path = root.join("loop_a").join("symlink_target.py").sanitize();
+    {
+        let file_mgr = session.sync_odoo.get_file_mgr();
+        let mut file_mgr = file_mgr.borrow_mut();
+        assert_eq!(file_mgr.get_canonical_path(&alias_path), canonical_path);
+        assert_eq!(file_mgr.get_canonical_path(&canonical_path), canonical_path);
+        assert_eq!(file_mgr.get_canonical_path(&loop_path), loop_path);
+        file_mgr.add_tree_root(&root.join("loop_a").sanitize());
+        assert_eq!(file_mgr.get_canonical_path(&loop_path), loop_path);
+    }
+    assert!(get_import_diagnostics(&mut session).is_empty());
+
+    let uri = FileMgr::pathname2uri(&alias_path);
+    Odoo::handle_did_open(&mut session, DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: uri.clone(),
+            language_id: S!("python"),
+            version: 1,
+            text: fs::read_to_string(&canonical_path).unwrap(),
+        }
+    });
+    assert!(session.sync_odoo.get_file_mgr().borrow().get_file_info(&alias_path).is_none());
+    assert!(session.sync_odoo.get_file_mgr().borrow().get_file_info(&canonical_path).is_some_and(|f| f.borrow().opened));
+
+    Odoo::handle_did_change(&mut session, DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version: 2 },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: S!("def compute_amount(lines):\n    return sum(lines)\n"),
+        }],
+    });
+    Odoo::handle_did_save(&mut session, DidSaveTextDocumentParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        text: None,
+    });
+    assert!(!get_import_diagnostics(&mut session).is_empty(), "The dependent file should be rebuilt");
+
+    let published = get_published_uris(&r);
+    assert!(published.contains(&uri.to_string()), "{:?}", published);
+    assert!(!published.contains(&FileMgr::pathname2uri(&canonical_path).to_string()), "{:?}", published);
+    let _ = fs::remove_dir_all(&root);
+}
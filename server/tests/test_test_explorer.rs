@@ -0,0 +1,54 @@
+use std::collections::BTreeSet;
+
+use ruff_python_ast::{Stmt, StmtClassDef};
+use odoo_ls_server::features::test_explorer::{TestExplorerFeature, DEFAULT_TEST_TAGS};
+
+mod setup;
+
+use setup::ast::parse;
+
+fn parse_class(code: &str) -> StmtClassDef {
+    let Some(Stmt::ClassDef(class)) = parse(code).into_iter().last() else {
+        panic!("Not a class");
+    };
+    class
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn test_get_tags() {
+    let class = parse_class("
+@tagged('post_install', '-at_install')
+class TestSale(TransactionCase):
+    pass
+");
+    assert_eq!(TestExplorerFeature::get_tags(&class.decorator_list), Some(tags(&["post_install", "-at_install"])));
+    let class = parse_class("
+@common.tagged('-standard', TAG, 'nightly')
+class TestSale(TransactionCase):
+    pass
+");
+    //the tags that are not literal are ignored
+    assert_eq!(TestExplorerFeature::get_tags(&class.decorator_list), Some(tags(&["-standard", "nightly"])));
+    let class = parse_class("
+@other('post_install')
+class TestSale(TransactionCase):
+    pass
+");
+    assert_eq!(TestExplorerFeature::get_tags(&class.decorator_list), None);
+}
+
+#[test]
+fn test_apply_tags() {
+    let defaults: BTreeSet<String> = DEFAULT_TEST_TAGS.iter().map(|tag| tag.to_string()).collect();
+    let applied = TestExplorerFeature::apply_tags(&defaults, &tags(&["post_install", "-at_install"]));
+    assert_eq!(applied.into_iter().collect::<Vec<String>>(), tags(&["post_install", "standard"]));
+    let applied = TestExplorerFeature::apply_tags(&defaults, &tags(&["-standard", "-unknown"]));
+    assert_eq!(applied.into_iter().collect::<Vec<String>>(), tags(&["at_install"]));
+    //a tag added and removed by the same decorator is removed
+    let applied = TestExplorerFeature::apply_tags(&defaults, &tags(&["nightly", "-nightly"]));
+    assert_eq!(applied, defaults);
+}
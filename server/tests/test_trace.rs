@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use odoo_ls_server::trace::{self, CaptureLayer};
+use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn test_trace_capture_and_relativize() {
+    trace::set_workspace_roots(&vec![String::from("/home/user/odoo"), String::from("/home/user/odoo/addons")]);
+    assert_eq!(trace::relativize("Loading /home/user/odoo/addons/sale/models.py"), "Loading ./sale/models.py");
+    assert_eq!(trace::relativize("Root is /home/user/odoo"), "Root is .");
+    assert_eq!(trace::relativize("/tmp/other.py"), "/tmp/other.py");
+
+    let subscriber = tracing_subscriber::registry().with(CaptureLayer);
+    tracing::subscriber::with_default(subscriber, || {
+        info!("not captured");
+        let path = trace::start_capture(Duration::from_millis(200)).unwrap();
+        {
+            let span = tracing::info_span!("rebuild");
+            let _enter = span.enter();
+            info!("Building /home/user/odoo/odoo/models.py");
+        }
+        std::thread::sleep(Duration::from_millis(500));
+        info!("not captured either");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(content.contains("rebuild: Building ./odoo/models.py"));
+        assert!(content.contains("SPAN rebuild: closed after"));
+        assert!(!content.contains("not captured"));
+    });
+}
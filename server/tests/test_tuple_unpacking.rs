@@ -0,0 +1,93 @@
+use lsp_types::NumberOrString;
+use ruff_python_ast::{Expr, Stmt, StmtAssign};
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::tuple_unpacking::TupleUnpacking;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::ast::parse;
+use setup::symbols::{get_declarations, get_evaluated_names};
+
+fn parse_assign(code: &str) -> StmtAssign {
+    match parse(code).into_iter().next() {
+        Some(Stmt::Assign(assign)) => assign,
+        _ => panic!("Not an assignment")
+    }
+}
+
+fn target_elts(assign: &StmtAssign) -> &[Expr] {
+    match &assign.targets[0] {
+        Expr::Tuple(tuple) => &tuple.elts,
+        Expr::List(list) => &list.elts,
+        _ => panic!("Not an unpacking")
+    }
+}
+
+#[test]
+fn test_check_arity() {
+    let assign = parse_assign("qty, price = self._get_qty_price()");
+    assert_eq!(TupleUnpacking::check_arity(target_elts(&assign), 2), None);
+    assert_eq!(TupleUnpacking::check_arity(target_elts(&assign), 3), Some(S!("Too many values to unpack (expected 2)")));
+    let assign = parse_assign("[a, b, c] = values");
+    assert_eq!(TupleUnpacking::check_arity(target_elts(&assign), 2), Some(S!("Not enough values to unpack (expected 3, got 2)")));
+    let assign = parse_assign("first, *others, last = values");
+    assert_eq!(TupleUnpacking::check_arity(target_elts(&assign), 5), None);
+    assert_eq!(TupleUnpacking::check_arity(target_elts(&assign), 1), Some(S!("Not enough values to unpack (expected at least 2, got 1)")));
+}
+
+#[test]
+fn test_get_element_index() {
+    let assign = parse_assign("first, *others, before_last, last = values");
+    let targets = target_elts(&assign);
+    assert_eq!(TupleUnpacking::get_element_index(targets, 0, 5), Some(0));
+    assert_eq!(TupleUnpacking::get_element_index(targets, 2, 5), Some(3));
+    assert_eq!(TupleUnpacking::get_element_index(targets, 3, 5), Some(4));
+    let assign = parse_assign("a, b, c = values");
+    assert_eq!(TupleUnpacking::get_element_index(target_elts(&assign), 2, 2), None);
+}
+
+#[test]
+fn test_get_unpacking_target() {
+    let assign = parse_assign("(a, (b, c)) = d, e = value");
+    let Expr::Tuple(outer) = &assign.targets[0] else {
+        panic!("Not a tuple");
+    };
+    let Expr::Tuple(inner) = &outer.elts[1] else {
+        panic!("Not a tuple");
+    };
+    let target = TupleUnpacking::get_unpacking_target(&assign.targets, &inner.elts[0].as_name_expr().unwrap().range);
+    assert_eq!(target.map(|elts| elts.len()), Some(2));
+    assert!(target.is_some_and(|elts| elts[1].as_name_expr().is_some_and(|name| name.id.as_str() == "c")));
+    let target = TupleUnpacking::get_unpacking_target(&assign.targets, &outer.elts[0].as_name_expr().unwrap().range);
+    assert!(target.is_some_and(|elts| elts[1].is_tuple_expr()));
+    assert_eq!(TupleUnpacking::get_literal_length(&assign.value), None);
+    assert_eq!(TupleUnpacking::get_literal_length(&parse_assign("a, b = 1, 2, 3").value), Some(3));
+    assert_eq!(TupleUnpacking::get_literal_length(&parse_assign("a, b = 1, *rest").value), None);
+}
+
+#[test]
+fn test_unpacked_returns() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("tuple_unpacking")];
+    let function = odoo.get_symbol(&(file_tree, vec![S!("TupleUnpacking"), S!("compute_values")]), u32::MAX).pop();
+    let function = function.expect("compute_values should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    //qty comes from `return 1, "price"` through _get_partner_qty, _get_values and _get_qty_price
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&function, "qty")[0]), vec![S!("int")]);
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&function, "first")[0]), vec![S!("int")]);
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&function, "b")[0]), vec![S!("str")]);
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&function, "c")[0]), Vec::<String>::new());
+    //the length of the value of self.read() is not known
+    assert_eq!(get_evaluated_names(&mut session, &get_declarations(&function, "name")[0]), Vec::<String>::new());
+    let diagnostics = function.borrow().as_func().diagnostics.get(&BuildSteps::ARCH_EVAL).cloned().unwrap_or_default();
+    let messages: Vec<String> = diagnostics.iter()
+        .filter(|d| d.code == Some(NumberOrString::String(S!("OLS10007"))))
+        .map(|d| d.message.clone()).collect();
+    assert_eq!(messages, vec![S!("Not enough values to unpack (expected 3, got 2)")]);
+}
@@ -0,0 +1,44 @@
+use lsp_types::NumberOrString;
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::{get_types, get_variable};
+
+/* Return whether a module-level variable is declared as a type alias, and its type parameters */
+fn get_alias(file: &std::rc::Rc<std::cell::RefCell<Symbol>>, name: &str) -> (bool, Vec<String>) {
+    let variable = file.borrow().get_content_symbol(name, u32::MAX).pop().expect("variable not found");
+    let variable = variable.borrow();
+    (variable.as_variable().is_explicit_type_alias, variable.as_variable().type_params.clone())
+}
+
+/* The type statements and the variables annotated with TypeAlias are evaluated to the aliased types */
+#[test]
+fn test_type_aliases() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("type_aliases")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("type_aliases should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    assert_eq!(get_alias(&file, "Vector"), (true, vec![]));
+    assert_eq!(get_types(&mut session, &get_variable(&file, "Vector")), vec![(S!("list"), false)]);
+    assert_eq!(get_alias(&file, "Pair"), (true, vec![S!("T")]));
+    assert_eq!(get_types(&mut session, &get_variable(&file, "Pair")), vec![(S!("tuple"), false)]);
+    assert_eq!(get_alias(&file, "Number"), (true, vec![]));
+    assert_eq!(get_types(&mut session, &get_variable(&file, "Number")), vec![(S!("int"), false), (S!("float"), false)]);
+    //the other assignments of types are only type aliases by their evaluations
+    assert_eq!(get_alias(&file, "Size"), (false, vec![]));
+    assert_eq!(get_alias(&file, "count"), (false, vec![]));
+    for name in ["Vector", "Pair", "Number", "Size"] {
+        let variable = file.borrow().get_content_symbol(name, u32::MAX).pop().unwrap();
+        assert!(variable.borrow().as_variable().is_type_alias(), "{} should be a type alias", name);
+    }
+    file.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    let mut validator = PythonValidator::new(file.clone());
+    validator.validate(&mut session);
+    assert!(validator.diagnostics.is_empty(), "{:?}", validator.diagnostics.iter().map(|d| d.code.clone()).collect::<Vec<Option<NumberOrString>>>());
+}
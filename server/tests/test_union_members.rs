@@ -0,0 +1,31 @@
+use odoo_ls_server::core::union_members::{MemberPresence, UnionMembers};
+use odoo_ls_server::features::hover::HoverFeature;
+
+#[test]
+fn test_member_presence() {
+    assert_eq!(UnionMembers::get_presence(&[true, true]), MemberPresence::ALL);
+    assert_eq!(UnionMembers::get_presence(&[true, false, false]), MemberPresence::SOME(vec![1, 2]));
+    assert_eq!(UnionMembers::get_presence(&[false, false]), MemberPresence::NONE);
+    //a single type is not a union, but has the same semantics
+    assert_eq!(UnionMembers::get_presence(&[true]), MemberPresence::ALL);
+}
+
+#[test]
+fn test_member_provenance() {
+    let arms = vec![String::from("int"), String::from("str")];
+    assert_eq!(UnionMembers::get_provenance(&arms, &[String::from("str")]), Some(String::from("only when str")));
+    assert_eq!(UnionMembers::get_provenance(&arms, &[String::from("str"), String::from("int")]), None);
+    assert_eq!(UnionMembers::get_provenance(&arms, &[]), None);
+    //the providers that are not arms, like a module, are ignored
+    assert_eq!(UnionMembers::get_provenance(&arms, &[String::from("os"), String::from("int")]), Some(String::from("only when int")));
+    let arms = vec![String::from("sale.order"), String::from("purchase.order"), String::from("account.move")];
+    assert_eq!(UnionMembers::get_provenance(&arms, &[String::from("account.move"), String::from("sale.order")]),
+        Some(String::from("only when sale.order | account.move")));
+}
+
+#[test]
+fn test_union_hover() {
+    assert_eq!(HoverFeature::build_union_provenance("int | str", "str"), Some(String::from("member of `str` only, in `int | str`")));
+    assert_eq!(HoverFeature::build_union_provenance("int | str", "str | int"), Some(String::from("member of all the types of `int | str`")));
+    assert_eq!(HoverFeature::build_union_provenance("int | str", ""), None);
+}
@@ -0,0 +1,53 @@
+use lsp_types::{Diagnostic, NumberOrString};
+use odoo_ls_server::constants::BuildSteps;
+use odoo_ls_server::core::file_mgr::FileMgr;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+use serde_json::Value;
+
+mod setup;
+
+fn find<'a>(diagnostics: &'a [Diagnostic], code: &str) -> &'a Diagnostic {
+    diagnostics.iter().find(|diagnostic| diagnostic.code == Some(NumberOrString::String(S!(code))))
+        .unwrap_or_else(|| panic!("{} should be raised: {:?}", code, diagnostics))
+}
+
+/* Each kind of unresolved import has its code, the closest package found in its related information, and the searched
+paths in its data */
+#[test]
+fn test_unresolved_imports() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("unresolved_imports")];
+    let file = odoo.get_symbol(&(file_tree, vec![]), u32::MAX).pop().expect("unresolved_imports should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let file_info = FileMgr::get_file_info_of_symbol(&mut session, &file).unwrap();
+    let diagnostics = file_info.borrow().get_step_diagnostics(BuildSteps::ARCH_EVAL);
+
+    //the package is not in the folder of models
+    let module_not_found = find(&diagnostics, "OLS20001");
+    assert_eq!(module_not_found.message, "odoo.addons.module_3.models.missing_package.missing_name not found");
+    let data = module_not_found.data.as_ref().unwrap();
+    assert_eq!(data["missing"], "missing_package");
+    assert_eq!(data["closest"], "odoo.addons.module_3.models");
+    let attempted: Vec<&str> = data["attempted_paths"].as_array().unwrap().iter().map(|path| path.as_str().unwrap()).collect();
+    assert_eq!(attempted.len(), 3);
+    assert!(attempted.iter().any(|path| path.ends_with("models/missing_package.py")), "{:?}", attempted);
+    let related = module_not_found.related_information.as_ref().unwrap();
+    assert!(related[0].location.uri.as_str().ends_with("models/__init__.py"));
+
+    //the file is found, but not the name
+    let name_not_found = find(&diagnostics, "OLS20030");
+    assert_eq!(name_not_found.message, "format_amout not found in odoo.addons.module_3.models.amounts. Did you mean format_amount?");
+    let data = name_not_found.data.as_ref().unwrap();
+    assert_eq!(data["suggestion"], "format_amount");
+    assert!(data["attempted_paths"].as_array().unwrap().is_empty());
+    assert!(name_not_found.related_information.as_ref().unwrap()[0].location.uri.as_str().ends_with("models/amounts.py"));
+
+    //amounts.py gives the module, with another case
+    let case_mismatch = find(&diagnostics, "OLS20031");
+    assert_eq!(case_mismatch.message, "odoo.addons.module_3.models.Amounts.round_amount not found: amounts.py differs by its case");
+    let data = case_mismatch.data.as_ref().unwrap();
+    assert_eq!(data["missing"], "Amounts");
+    assert_eq!(data["suggestion"], Value::Null);
+}
@@ -0,0 +1,70 @@
+use odoo_ls_server::core::unused_fields::{FieldUsages, UnusedFieldsOptions};
+use odoo_ls_server::core::xml_data::XmlDataFile;
+use serde_json::json;
+
+mod setup;
+
+use setup::ast::parse_valid;
+
+const CODE: &str = r#"
+from odoo import api, fields, models
+
+class SaleOrder(models.Model):
+    _inherit = "sale.order"
+
+    partner_name = fields.Char(related="partner_id.name")
+    note_count = fields.Integer(compute="_compute_note_count")
+    legacy_code = fields.Char()
+    self_related = fields.Char(related="other_id.self_related")
+
+    @api.depends("line_ids.discount")
+    def _compute_note_count(self):
+        for order in self:
+            order.note_count = len(order.line_ids)
+        self.env["res.partner"].create({"vat_number": False})
+        self.write(dict(delivery_done=True))
+"#;
+
+#[test]
+fn test_python_usages() {
+    let ast = parse_valid(CODE);
+    let mut usages = FieldUsages::default();
+    usages.add_python(&ast);
+    //attributes, strings of depends and related, dict keys and keyword arguments
+    for name in ["note_count", "line_ids", "discount", "partner_id", "name", "vat_number", "delivery_done"] {
+        assert!(usages.is_used(name), "{} should be used", name);
+    }
+    //a declaration is not a use, even when its related mentions the field itself
+    for name in ["partner_name", "legacy_code", "self_related"] {
+        assert!(!usages.is_used(name), "{} should not be used", name);
+    }
+}
+
+#[test]
+fn test_xml_field_names() {
+    let data = XmlDataFile::parse_xml(r#"<odoo>
+        <record id="view_order_form" model="ir.ui.view">
+            <field name="arch" type="xml">
+                <field name="partner_name" invisible="state != 'draft' or not legacy_code"/>
+                <span t-field="record.note_count"/>
+            </field>
+        </record>
+    </odoo>"#);
+    for name in ["arch", "partner_name", "state", "legacy_code", "record", "note_count"] {
+        assert!(data.field_names.contains(&name.to_string()), "{} should be found", name);
+    }
+    assert!(!data.field_names.contains(&"view_order_form".to_string()));
+    let mut usages = FieldUsages::default();
+    usages.add_names(data.field_names.iter());
+    assert!(usages.is_used("legacy_code"));
+}
+
+#[test]
+fn test_unused_fields_options() {
+    assert_eq!(UnusedFieldsOptions::from_arguments(&vec![]).unwrap(), UnusedFieldsOptions::default());
+    let options = UnusedFieldsOptions::from_arguments(&vec![json!({"modules": ["sale_custom"], "exclude": ["legacy_*"], "publishHints": true})]).unwrap();
+    assert_eq!(options.modules, vec!["sale_custom"]);
+    assert_eq!(options.exclude, vec!["legacy_*"]);
+    assert!(options.publish_hints && !options.include_groups && !options.include_abstract);
+    assert!(UnusedFieldsOptions::from_arguments(&vec![json!({"modules": "sale_custom"})]).is_err());
+}
@@ -0,0 +1,52 @@
+use odoo_ls_server::utils::{path_to_uri, sanitize_path, uri_to_path};
+
+/* uri given by the client, canonical path, and uri sent back to the client */
+const WINDOWS_CASES: [(&str, &str, &str); 7] = [
+    ("file:///c%3A/odoo/addons/sale.py", "c:/odoo/addons/sale.py", "file:///c:/odoo/addons/sale.py"),
+    ("file:///C:/odoo/addons/sale.py", "c:/odoo/addons/sale.py", "file:///c:/odoo/addons/sale.py"),
+    ("file:///C%3A/My%20Addons/sale.py", "c:/My Addons/sale.py", "file:///c:/My%20Addons/sale.py"),
+    ("file://server/share/odoo/sale.py", "//server/share/odoo/sale.py", "file://server/share/odoo/sale.py"),
+    ("file://wsl$/Ubuntu/home/odoo/sale.py", "//wsl.localhost/Ubuntu/home/odoo/sale.py", "file://wsl.localhost/Ubuntu/home/odoo/sale.py"),
+    ("file://wsl.localhost/Ubuntu/home/odoo/sale.py", "//wsl.localhost/Ubuntu/home/odoo/sale.py", "file://wsl.localhost/Ubuntu/home/odoo/sale.py"),
+    ("vscode-remote://wsl%2BUbuntu/home/odoo/sale.py", "//wsl.localhost/Ubuntu/home/odoo/sale.py", "file://wsl.localhost/Ubuntu/home/odoo/sale.py"),
+];
+
+const UNIX_CASES: [(&str, &str, &str); 3] = [
+    ("file:///home/odoo/addons/sale.py", "/home/odoo/addons/sale.py", "file:///home/odoo/addons/sale.py"),
+    ("file:///home/odoo/my%20addons/vente%C3%A9.py", "/home/odoo/my addons/venteé.py", "file:///home/odoo/my%20addons/vente%C3%A9.py"),
+    ("vscode-remote://wsl%2BUbuntu/home/odoo/sale.py", "/home/odoo/sale.py", "file:///home/odoo/sale.py"),
+];
+
+#[test]
+fn test_windows_uri_round_trip() {
+    for (uri, path, client_uri) in WINDOWS_CASES.iter() {
+        assert_eq!(uri_to_path(uri, true).as_deref(), Some(*path), "{}", uri);
+        assert_eq!(path_to_uri(path, true), *client_uri);
+        assert_eq!(uri_to_path(client_uri, true).as_deref(), Some(*path));
+    }
+}
+
+#[test]
+fn test_unix_uri_round_trip() {
+    for (uri, path, client_uri) in UNIX_CASES.iter() {
+        assert_eq!(uri_to_path(uri, false).as_deref(), Some(*path), "{}", uri);
+        assert_eq!(path_to_uri(path, false), *client_uri);
+        assert_eq!(uri_to_path(client_uri, false).as_deref(), Some(*path));
+    }
+    //UNC paths can only be opened on Windows
+    assert!(uri_to_path("file://server/share/sale.py", false).is_none());
+    assert!(uri_to_path("untitled:Untitled-1", false).is_none());
+}
+
+#[test]
+fn test_sanitize_windows_paths() {
+    //all the forms of the same file give the same key
+    for path in ["C:\\odoo\\sale.py", "c:/odoo/sale.py", "\\\\?\\C:\\odoo\\sale.py"] {
+        assert_eq!(sanitize_path(path, true), "c:/odoo/sale.py");
+    }
+    for path in ["\\\\wsl$\\Ubuntu\\odoo\\sale.py", "\\\\WSL.LOCALHOST\\Ubuntu\\odoo\\sale.py", "\\\\?\\UNC\\wsl$\\Ubuntu\\odoo\\sale.py"] {
+        assert_eq!(sanitize_path(path, true), "//wsl.localhost/Ubuntu/odoo/sale.py");
+    }
+    assert_eq!(sanitize_path("\\\\?\\UNC\\server\\share\\sale.py", true), "//server/share/sale.py");
+    assert_eq!(sanitize_path("/home/odoo/C:/sale.py", false), "/home/odoo/C:/sale.py");
+}
@@ -0,0 +1,43 @@
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+use odoo_ls_server::core::file_mgr::FileInfo;
+use odoo_ls_server::S;
+use ropey::Rope;
+
+fn apply(rope: &mut Rope, range: Range, text: &str) {
+    FileInfo::apply_change_with_rope(rope, &TextDocumentContentChangeEvent {
+        range: Some(range),
+        range_length: None,
+        text: S!(text),
+    });
+}
+
+/* The columns of the LSP positions are UTF-16 code units: 'é' takes one of them and 2 bytes in UTF-8, the emoji takes
+two of them and 4 bytes */
+#[test]
+fn test_utf16_positions() {
+    let mut rope = Rope::from_str("a = '\u{E9}'\nb = '\u{1F600}' + a\n");
+    assert_eq!(FileInfo::position_to_offset_with_rope(&rope, 1, 9), 9 + 11);
+    assert_eq!(FileInfo::offset_to_position_with_rope(&rope, 9 + 11), Position::new(1, 9));
+    assert_eq!(FileInfo::offset_to_position_with_rope(&rope, 7), Position::new(0, 6));
+    //the positions after the emoji, at the end of a line and at the end of the document
+    assert_eq!(FileInfo::position_to_offset_with_rope(&rope, 1, 7), 9 + 9);
+    assert_eq!(FileInfo::offset_to_position_with_rope(&rope, 9 + 9), Position::new(1, 7));
+    assert_eq!(FileInfo::position_to_offset_with_rope(&rope, 0, 7), 8);
+    assert_eq!(FileInfo::offset_to_position_with_rope(&rope, rope.len_bytes()), Position::new(2, 0));
+    apply(&mut rope, Range::new(Position::new(1, 11), Position::new(1, 12)), "b");
+    assert_eq!(rope.to_string(), "a = '\u{E9}'\nb = '\u{1F600}' + b\n");
+}
+
+/* A change spanning several lines and a batch of changes, each one given in the positions of the text left by the
+previous ones */
+#[test]
+fn test_utf16_changes() {
+    let mut rope = Rope::from_str("x = '\u{1F600}\u{1F600}'\ny = '\u{E9}'\n");
+    apply(&mut rope, Range::new(Position::new(0, 7), Position::new(1, 5)), "");
+    assert_eq!(rope.to_string(), "x = '\u{1F600}\u{E9}'\n");
+    apply(&mut rope, Range::new(Position::new(0, 0), Position::new(0, 0)), "# \u{1F600}\n");
+    apply(&mut rope, Range::new(Position::new(0, 4), Position::new(0, 4)), "!");
+    apply(&mut rope, Range::new(Position::new(2, 0), Position::new(2, 0)), "z = x\n");
+    assert_eq!(rope.to_string(), "# \u{1F600}!\nx = '\u{1F600}\u{E9}'\nz = x\n");
+    assert_eq!(FileInfo::offset_to_position_with_rope(&rope, "# \u{1F600}!\nx = '\u{1F600}\u{E9}".len()), Position::new(1, 8));
+}
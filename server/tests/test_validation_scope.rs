@@ -0,0 +1,36 @@
+use odoo_ls_server::core::ignore::ValidationScope;
+
+fn scope(exclude: &[&str], include: &[&str], exclude_generated: bool) -> ValidationScope {
+    let folders = vec!["/workspace".to_string()];
+    let (scope, errors) = ValidationScope::load(&folders,
+        &exclude.iter().map(|g| g.to_string()).collect(),
+        &include.iter().map(|g| g.to_string()).collect(),
+        exclude_generated);
+    assert!(errors.is_empty());
+    scope
+}
+
+#[test]
+fn test_validation_exclude_include() {
+    let scope = scope(&["lib/", "*_old.py"], &[], false);
+    assert!(scope.is_excluded("/workspace/my_module/lib/vendor.py", None));
+    assert!(scope.is_excluded("/workspace/my_module/models/sale_old.py", None));
+    assert!(!scope.is_excluded("/workspace/my_module/models/sale.py", None));
+
+    //only the included files are validated
+    let scope = scope(&["my_module/tests"], &["my_module"], false);
+    assert!(!scope.is_excluded("/workspace/my_module/models/sale.py", None));
+    assert!(scope.is_excluded("/workspace/my_module/tests/test_sale.py", None));
+    assert!(scope.is_excluded("/workspace/other_module/models/sale.py", None));
+}
+
+#[test]
+fn test_validation_exclude_generated() {
+    let header = "# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.  DO NOT EDIT!\n";
+    assert!(ValidationScope::is_generated("/workspace/my_module/proto/sale_pb2.py", None));
+    assert!(ValidationScope::is_generated("/workspace/my_module/proto/sale.py", Some(header)));
+    assert!(!ValidationScope::is_generated("/workspace/my_module/models/sale.py", Some("# Part of Odoo. See LICENSE file\n")));
+
+    assert!(scope(&[], &[], true).is_excluded("/workspace/my_module/proto/sale.py", Some(header)));
+    assert!(!scope(&[], &[], false).is_excluded("/workspace/my_module/proto/sale.py", Some(header)));
+}
@@ -0,0 +1,110 @@
+use std::fs;
+
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_arch_builder::PythonArchBuilder;
+use odoo_ls_server::core::static_conditions::StaticConditions;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+
+/* These tests check that the conditions on the environment, like in the stubs of typeshed, only keep the declarations
+of the branch selected for the configured Python version. */
+
+const STUB: &str = "\
+import sys
+from typing import TYPE_CHECKING
+import typing as t
+
+if sys.version_info >= (3, 10):
+    protocol = 'typing'
+elif sys.version_info[:2] == (3, 8):
+    protocol = 'typing_38'
+else:
+    protocol = 'typing_extensions'
+
+if sys.version_info.minor < 9:
+    legacy = 1
+
+if sys.platform == 'win32':
+    platform = 'windows'
+elif sys.platform != 'win32':
+    platform = 'posix'
+
+if TYPE_CHECKING:
+    from collections import OrderedDict
+else:
+    OrderedDict = dict
+
+if t.TYPE_CHECKING:
+    checked = True
+
+if sys.maxsize > 2**32:
+    size = 64
+else:
+    size = 32
+";
+
+/* Build the stub with the given configured Python version, and return the lines (starting at 1) of the declarations
+of each name that are reachable at the end of the file, with the names imported for the type checkers only */
+fn get_declarations(name: &str, python_version: Option<(u32, u32, u32)>, names: &[&str]) -> (Vec<Vec<usize>>, Vec<String>) {
+    let path = std::env::temp_dir().join(format!("odoo_ls_tests_versions_{}_{}.pyi", name, std::process::id()));
+    fs::write(&path, STUB).unwrap();
+    let mut odoo = SyncOdoo::new();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    session.sync_odoo.config_report.python_version = python_version;
+    let root = session.sync_odoo.symbols.clone().unwrap();
+    let file = Symbol::create_from_path(&mut session, &path, root, false).unwrap();
+    let mut builder = PythonArchBuilder::new(file.clone());
+    builder.load_arch(&mut session);
+    let mut lines = vec![];
+    let mut type_checking = vec![];
+    for name in names.iter() {
+        let mut declarations: Vec<usize> = file.borrow().get_content_symbol(name, u32::MAX).iter().map(|sym| {
+            let sym = sym.borrow();
+            if sym.as_variable().is_type_checking_import {
+                type_checking.push(name.to_string());
+            }
+            STUB[..sym.range().start().to_usize()].matches('\n').count() + 1
+        }).collect();
+        declarations.sort();
+        lines.push(declarations);
+    }
+    let _ = fs::remove_file(&path);
+    (lines, type_checking)
+}
+
+#[test]
+fn test_new_python_version() {
+    let (lines, _) = get_declarations("new", Some((3, 12, 1)), &["protocol", "legacy"]);
+    assert_eq!(lines, vec![vec![6], vec![]]);
+}
+
+#[test]
+fn test_old_python_version() {
+    let (lines, _) = get_declarations("old", Some((3, 8, 10)), &["protocol", "legacy"]);
+    assert_eq!(lines, vec![vec![8], vec![13]]);
+    let (lines, _) = get_declarations("older", Some((3, 7, 0)), &["protocol", "legacy"]);
+    assert_eq!(lines, vec![vec![10], vec![13]]);
+}
+
+#[test]
+fn test_unknown_python_version() {
+    //the version is unknown until the configuration is checked: all the branches are kept
+    let (lines, _) = get_declarations("unknown", None, &["protocol", "legacy"]);
+    assert_eq!(lines, vec![vec![6, 8, 10], vec![13]]);
+}
+
+#[test]
+fn test_platform() {
+    let (lines, _) = get_declarations("platform", Some((3, 12, 1)), &["platform"]);
+    let expected = if StaticConditions::get_platform() == "win32" { 16 } else { 18 };
+    assert_eq!(lines, vec![vec![expected]]);
+}
+
+#[test]
+fn test_type_checking() {
+    let (lines, type_checking) = get_declarations("type_checking", Some((3, 12, 1)), &["OrderedDict", "checked", "size"]);
+    //the runtime fallback is not reachable, and the imports are flagged as only known by the type checkers
+    assert_eq!(lines, vec![vec![21], vec![26], vec![29, 31]]);
+    assert_eq!(type_checking, vec!["OrderedDict"]);
+}
@@ -0,0 +1,25 @@
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_declared_type_names;
+
+/* The targets of the walruses of the tests of if and while, and of the conditions of the comprehensions, are evaluated
+to their value, and the body of a while is visited */
+#[test]
+fn test_walrus_targets() {
+    let mut odoo = setup::setup::setup_server();
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("walrus")];
+    let function = odoo.get_symbol(&(file_tree, vec![S!("read_values")]), u32::MAX).pop().expect("read_values should be loaded");
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(&mut session);
+    assert_eq!(get_declared_type_names(&mut session, &function, "size"), vec![S!("int")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "remaining"), vec![S!("int")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "label"), vec![S!("str")]);
+    assert_eq!(get_declared_type_names(&mut session, &function, "last"), vec![S!("int")]);
+}
@@ -0,0 +1,60 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use odoo_ls_server::constants::{BuildStatus, BuildSteps};
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::core::python_validator::PythonValidator;
+use odoo_ls_server::core::symbols::symbol::Symbol;
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::S;
+
+mod setup;
+
+use setup::symbols::get_evaluated_names;
+
+fn get_symbol(odoo: &SyncOdoo, content: Vec<String>) -> Rc<RefCell<Symbol>> {
+    let file_tree = vec![S!("odoo"), S!("addons"), S!("module_3"), S!("models"), S!("with_statements")];
+    odoo.get_symbol(&(file_tree, content), u32::MAX).pop().expect("symbol should be loaded")
+}
+
+fn validate(session: &mut SessionInfo, function: &Rc<RefCell<Symbol>>) {
+    function.borrow_mut().set_build_status(BuildSteps::VALIDATION, BuildStatus::PENDING);
+    PythonValidator::new(function.clone()).validate(session);
+}
+
+#[test]
+fn test_with_statements() {
+    let mut odoo = setup::setup::setup_server();
+    check_with_targets(&mut odoo);
+    check_with_enter(&mut odoo);
+}
+
+/* The targets of a with statement are declared in its scope, and its body is visited like the statements around it */
+fn check_with_targets(odoo: &mut SyncOdoo) {
+    let plain = get_symbol(odoo, vec![S!("plain")]);
+    for name in ["first", "second"] {
+        get_symbol(odoo, vec![S!(name)]);
+    }
+    get_symbol(odoo, vec![S!("Job"), S!("run")]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    //Plain has no __enter__: the target is the manager itself
+    assert_eq!(get_evaluated_names(&mut session, &plain), vec![S!("Plain")]);
+}
+
+/* The target is the value returned by __enter__, or by __aenter__ for an async with */
+fn check_with_enter(odoo: &mut SyncOdoo) {
+    let enter = get_symbol(odoo, vec![S!("Connection"), S!("__enter__")]);
+    let aenter = get_symbol(odoo, vec![S!("AsyncConnection"), S!("__aenter__")]);
+    let query = get_symbol(odoo, vec![S!("query")]);
+    let fetch = get_symbol(odoo, vec![S!("fetch")]);
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, odoo);
+    for function in [&enter, &aenter, &query, &fetch] {
+        validate(&mut session, function);
+    }
+    for function in [&query, &fetch] {
+        let cursor = function.borrow().all_symbols().find(|symbol| symbol.borrow().name() == "cursor").expect("cursor should be declared");
+        assert_eq!(get_evaluated_names(&mut session, &cursor), vec![S!("Cursor")]);
+    }
+}
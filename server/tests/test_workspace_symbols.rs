@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use lsp_types::SymbolKind;
+use odoo_ls_server::core::odoo::SyncOdoo;
+use odoo_ls_server::features::workspace_symbols::{MatchQuality, WorkspaceSymbolFeature};
+use odoo_ls_server::threads::SessionInfo;
+use odoo_ls_server::utils::PathSanitizer as _;
+
+mod setup;
+
+/* The symbols of module_3 are searched once it is a workspace folder. The names are matched without case, and the best
+matches are given first */
+#[test]
+fn test_workspace_symbols() {
+    let mut odoo = setup::setup::setup_server();
+    let (s, r) = crossbeam_channel::unbounded();
+    let mut session = SessionInfo::new_from_custom_channel(s, r, &mut odoo);
+    let module_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("addons").join("module_3");
+    session.sync_odoo.get_file_mgr().borrow_mut().add_workspace_folder(module_path.sanitize());
+    SyncOdoo::apply_ignore_rules(&mut session);
+
+    let symbols = WorkspaceSymbolFeature::get_workspace_symbols(&mut session, "voiceRep");
+    let report = symbols.iter().find(|symbol| symbol.name == "InvoiceReport").expect("InvoiceReport should be found");
+    assert_eq!(report.kind, SymbolKind::CLASS);
+    assert_eq!(report.container_name.as_deref(), Some("odoo.addons.module_3.models.workspace_symbols"));
+    assert!(report.location.uri.as_str().ends_with("module_3/models/workspace_symbols.py"));
+    assert_eq!(report.location.range.start.line, 3);
+    assert_eq!(report.location.range.start.character, 6);
+    //the substring of the class name is a better match than the characters spread in the function name
+    let function = symbols.iter().position(|symbol| symbol.name == "print_invoice_report").expect("print_invoice_report should be found");
+    assert!(symbols.iter().position(|symbol| symbol.name == "InvoiceReport").unwrap() < function);
+
+    let symbols = WorkspaceSymbolFeature::get_workspace_symbols(&mut session, "render_inv");
+    let method = symbols.iter().find(|symbol| symbol.name == "render_invoice").expect("the methods should be found");
+    assert_eq!(method.kind, SymbolKind::METHOD);
+    assert_eq!(method.container_name.as_deref(), Some("odoo.addons.module_3.models.workspace_symbols.InvoiceReport"));
+    assert_eq!(method.location.range.start.line, 5);
+
+    let symbols = WorkspaceSymbolFeature::get_workspace_symbols(&mut session, "REPORT_FORMATS");
+    assert!(symbols.iter().any(|symbol| symbol.name == "REPORT_FORMATS" && symbol.kind == SymbolKind::CONSTANT));
+
+    //the symbols of Odoo and of the other modules are not in the workspace
+    let symbols = WorkspaceSymbolFeature::get_workspace_symbols(&mut session, "BaseModel");
+    assert!(symbols.iter().all(|symbol| symbol.location.uri.as_str().contains("module_3")));
+}
+
+#[test]
+fn test_match_quality() {
+    assert_eq!(WorkspaceSymbolFeature::get_match_quality("invoice", "Invoice"), Some(MatchQuality::EXACT));
+    assert_eq!(WorkspaceSymbolFeature::get_match_quality("inv", "InvoiceReport"), Some(MatchQuality::PREFIX));
+    assert_eq!(WorkspaceSymbolFeature::get_match_quality("report", "InvoiceReport"), Some(MatchQuality::SUBSTRING));
+    assert_eq!(WorkspaceSymbolFeature::get_match_quality("ir", "InvoiceReport"), Some(MatchQuality::FUZZY));
+    assert_eq!(WorkspaceSymbolFeature::get_match_quality("ri", "Report"), None);
+}
@@ -0,0 +1,58 @@
+use lsp_types::{Position, Range};
+use odoo_ls_server::core::xml_data::XmlDataFile;
+
+#[test]
+fn test_xml_ids_and_method_references() {
+    let text = r#"<odoo>
+    <template id="report_invoice_document">
+        <t t-call="web.external_layout"/>
+    </template>
+    <record id="ir_cron_send_reminders" model="ir.cron">
+        <field name="name">Send reminders</field>
+        <field name="model_id" ref="model_sale_order"/>
+        <field name="state">code</field>
+        <field name="code">model._send_reminders()</field>
+    </record>
+    <record id="base.view_partner_form" model="ir.ui.view">
+        <field name="code">model.not_a_code_model()</field>
+    </record>
+</odoo>"#;
+    let data = XmlDataFile::parse_xml(text);
+    let ids: Vec<String> = data.ids.iter().map(|id| id.full_id("sale")).collect();
+    assert_eq!(ids, vec!["sale.report_invoice_document", "sale.ir_cron_send_reminders", "base.view_partner_form"]);
+    assert_eq!(data.ids[0].tag, "template");
+    assert_eq!(data.ids[0].range, Range::new(Position::new(1, 18), Position::new(1, 41)));
+    assert_eq!(data.method_references.len(), 1);
+    let reference = &data.method_references[0];
+    assert_eq!(reference.method, "_send_reminders");
+    assert_eq!(reference.range, Range::new(Position::new(8, 33), Position::new(8, 48)));
+    assert!(reference.is_on_model("sale.order"));
+    assert!(!reference.is_on_model("sale.order.line"));
+}
+
+#[test]
+fn test_csv_ids() {
+    let text = "id,name,model_id:id,perm_read\naccess_sale_order,sale.order,model_sale_order,1\n\"access_sale_line\",sale.order.line,model_sale_order_line,1\n";
+    let data = XmlDataFile::parse_csv(text);
+    let ids: Vec<&str> = data.ids.iter().map(|id| id.id.as_str()).collect();
+    assert_eq!(ids, vec!["access_sale_order", "access_sale_line"]);
+    assert_eq!(data.ids[1].range, Range::new(Position::new(2, 1), Position::new(2, 17)));
+}
+
+#[test]
+fn test_id_references() {
+    let text = r#"<odoo>
+    <record id="view_order_form_custom" model="ir.ui.view">
+        <field name="inherit_id" ref="sale.view_order_form"/>
+        <field name="groups_id" eval="[(4, ref('base.group_user'))]"/>
+    </record>
+    <menuitem id="menu_custom" parent="sale.sale_menu_root" action="action_custom" groups="!stock.group_stock_user, account.group_account_user"/>
+    <field name="action_id" ref="%(sale.action_orders)d"/>
+</odoo>"#;
+    let data = XmlDataFile::parse_xml(text);
+    let references: Vec<&str> = data.id_references.iter().map(|(id, _)| id.as_str()).collect();
+    //the ids without a module prefix are in the module, and the evaluated values are not read
+    assert_eq!(references, vec!["sale.view_order_form", "sale.sale_menu_root", "stock.group_stock_user", "account.group_account_user"]);
+    assert_eq!(data.id_references[0].1, Range::new(Position::new(2, 38), Position::new(2, 58)));
+    assert_eq!(data.id_references[3].1, Range::new(Position::new(5, 116), Position::new(5, 142)));
+}